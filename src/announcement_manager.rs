@@ -0,0 +1,436 @@
+//! Spoken track-change announcements through the OS text-to-speech engine.
+//!
+//! The manager watches playlist index changes on the event bus and, when enabled,
+//! speaks a short "Now playing" line for each newly started track. While speech is
+//! running, playback can be ducked via `PlaybackMessage::SetDuckGain`.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::config::AnnouncementConfig;
+use crate::integration_uri::is_remote_track_path;
+use crate::metadata_tags::read_common_track_metadata;
+use crate::protocol::{
+    AnnouncementConfigDelta, ConfigDeltaEntry, ConfigMessage, Message, PlaybackMessage,
+    PlaylistMessage, TrackMetadataSummary,
+};
+
+const SPEECH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Remote track summaries kept for announcements; the oldest are dropped beyond this.
+const REMOTE_METADATA_CACHE_LIMIT: usize = 2048;
+
+/// One OS speech command invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SpeechCommand {
+    program: String,
+    args: Vec<String>,
+}
+
+/// Speaks track-change announcements and coordinates playback ducking.
+pub struct AnnouncementManager {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    config: AnnouncementConfig,
+    last_announced_track_id: Option<String>,
+    remote_metadata_by_track_id: HashMap<String, TrackMetadataSummary>,
+    /// Insertion order of `remote_metadata_by_track_id`, oldest first.
+    remote_metadata_order: VecDeque<String>,
+    speech_generation: Arc<AtomicU64>,
+}
+
+impl AnnouncementManager {
+    /// Creates an announcement manager seeded with startup config.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        initial_config: AnnouncementConfig,
+    ) -> Self {
+        Self {
+            bus_consumer,
+            bus_producer,
+            config: initial_config,
+            last_announced_track_id: None,
+            remote_metadata_by_track_id: HashMap::new(),
+            remote_metadata_order: VecDeque::new(),
+            speech_generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn apply_config_delta(&mut self, delta: AnnouncementConfigDelta) {
+        if let Some(enabled) = delta.enabled {
+            self.config.enabled = enabled;
+            if !enabled {
+                self.cancel_speech();
+            }
+        }
+        if let Some(voice) = delta.voice {
+            self.config.voice = voice;
+        }
+        if let Some(rate_wpm) = delta.rate_wpm {
+            self.config.rate_wpm = rate_wpm;
+        }
+        if let Some(duck_playback) = delta.duck_playback {
+            self.config.duck_playback = duck_playback;
+        }
+        if let Some(duck_gain) = delta.duck_gain {
+            self.config.duck_gain = duck_gain;
+        }
+    }
+
+    fn title_from_path(path: &Path) -> String {
+        path.file_stem()
+            .and_then(|name| name.to_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    }
+
+    /// Builds the spoken line for one track from its title/artist values.
+    fn announcement_text(title: &str, artist: &str) -> Option<String> {
+        let title = title.trim();
+        let artist = artist.trim();
+        if title.is_empty() {
+            return None;
+        }
+        if artist.is_empty() {
+            Some(format!("Now playing {title}"))
+        } else {
+            Some(format!("Now playing {title} by {artist}"))
+        }
+    }
+
+    fn remember_remote_metadata(&mut self, track_id: String, summary: TrackMetadataSummary) {
+        if self
+            .remote_metadata_by_track_id
+            .insert(track_id.clone(), summary)
+            .is_none()
+        {
+            self.remote_metadata_order.push_back(track_id);
+        }
+        while self.remote_metadata_order.len() > REMOTE_METADATA_CACHE_LIMIT {
+            if let Some(oldest) = self.remote_metadata_order.pop_front() {
+                self.remote_metadata_by_track_id.remove(&oldest);
+            }
+        }
+    }
+
+    fn resolve_announcement_text(&self, track_id: &str, path: &Path) -> Option<String> {
+        if let Some(summary) = self.remote_metadata_by_track_id.get(track_id) {
+            return Self::announcement_text(&summary.title, &summary.artist);
+        }
+        if is_remote_track_path(path) {
+            return None;
+        }
+        match read_common_track_metadata(path) {
+            Some(metadata) if !metadata.title.trim().is_empty() => {
+                let artist = if metadata.artist.trim().is_empty() {
+                    metadata.album_artist
+                } else {
+                    metadata.artist
+                };
+                Self::announcement_text(&metadata.title, &artist)
+            }
+            _ => Self::announcement_text(&Self::title_from_path(path), ""),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn speech_commands(text: &str, voice: &str, rate_wpm: u32) -> Vec<SpeechCommand> {
+        let mut args = vec!["-r".to_string(), rate_wpm.to_string()];
+        if !voice.is_empty() {
+            args.push("-v".to_string());
+            args.push(voice.to_string());
+        }
+        // Ends option parsing so text starting with '-' is spoken, not parsed.
+        args.push("--".to_string());
+        args.push(text.to_string());
+        vec![SpeechCommand {
+            program: "say".to_string(),
+            args,
+        }]
+    }
+
+    #[cfg(target_os = "windows")]
+    fn speech_commands(text: &str, voice: &str, rate_wpm: u32) -> Vec<SpeechCommand> {
+        // System.Speech uses a -10..10 rate scale where 0 is roughly 175 wpm.
+        let rate = ((rate_wpm as i64 - 175) / 25).clamp(-10, 10);
+        let escape = |value: &str| value.replace('\'', "''");
+        let mut script = String::from(
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; ",
+        );
+        script.push_str(&format!("$s.Rate = {rate}; "));
+        if !voice.is_empty() {
+            script.push_str(&format!("$s.SelectVoice('{}'); ", escape(voice)));
+        }
+        script.push_str(&format!("$s.Speak('{}')", escape(text)));
+        vec![SpeechCommand {
+            program: "powershell".to_string(),
+            args: vec![
+                "-NoProfile".to_string(),
+                "-NonInteractive".to_string(),
+                "-Command".to_string(),
+                script,
+            ],
+        }]
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn speech_commands(text: &str, voice: &str, rate_wpm: u32) -> Vec<SpeechCommand> {
+        ["espeak-ng", "espeak"]
+            .into_iter()
+            .map(|program| {
+                let mut args = vec!["-s".to_string(), rate_wpm.to_string()];
+                if !voice.is_empty() {
+                    args.push("-v".to_string());
+                    args.push(voice.to_string());
+                }
+                args.push("--".to_string());
+                args.push(text.to_string());
+                SpeechCommand {
+                    program: program.to_string(),
+                    args,
+                }
+            })
+            .collect()
+    }
+
+    fn spawn_speech_process(text: &str, voice: &str, rate_wpm: u32) -> Option<Child> {
+        for command in Self::speech_commands(text, voice, rate_wpm) {
+            match Command::new(&command.program)
+                .args(&command.args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+            {
+                Ok(child) => return Some(child),
+                Err(err) => {
+                    debug!(
+                        "AnnouncementManager: speech command '{}' unavailable: {}",
+                        command.program, err
+                    );
+                }
+            }
+        }
+        warn!("AnnouncementManager: no text-to-speech command available");
+        None
+    }
+
+    fn cancel_speech(&self) {
+        // Bumping the generation makes any in-flight speech watcher kill its process.
+        self.speech_generation.fetch_add(1, Ordering::SeqCst);
+        let _ = self
+            .bus_producer
+            .send(Message::Playback(PlaybackMessage::SetDuckGain(1.0)));
+    }
+
+    fn speak(&mut self, text: String) {
+        let generation = self.speech_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let Some(mut child) =
+            Self::spawn_speech_process(&text, self.config.voice.trim(), self.config.rate_wpm)
+        else {
+            return;
+        };
+        debug!("AnnouncementManager: speaking '{}'", text);
+
+        let duck_gain = self
+            .config
+            .duck_playback
+            .then_some(self.config.duck_gain.clamp(0.0, 1.0));
+        let _ = self
+            .bus_producer
+            .send(Message::Playback(PlaybackMessage::SetDuckGain(
+                duck_gain.unwrap_or(1.0),
+            )));
+
+        let speech_generation = Arc::clone(&self.speech_generation);
+        let bus_producer = self.bus_producer.clone();
+        thread::spawn(move || {
+            loop {
+                if speech_generation.load(Ordering::SeqCst) != generation {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break;
+                }
+                match child.try_wait() {
+                    Ok(Some(_)) => break,
+                    Ok(None) => thread::sleep(SPEECH_POLL_INTERVAL),
+                    Err(err) => {
+                        warn!(
+                            "AnnouncementManager: failed to poll speech process: {}",
+                            err
+                        );
+                        let _ = child.kill();
+                        break;
+                    }
+                }
+            }
+            // Newer announcements own the duck state; only the latest one restores it.
+            if duck_gain.is_some() && speech_generation.load(Ordering::SeqCst) == generation {
+                let _ = bus_producer.send(Message::Playback(PlaybackMessage::SetDuckGain(1.0)));
+            }
+        });
+    }
+
+    fn handle_playing_track_changed(
+        &mut self,
+        playing_track_id: Option<String>,
+        playing_track_path: Option<PathBuf>,
+        is_playing: bool,
+    ) {
+        let (Some(track_id), Some(path)) = (playing_track_id, playing_track_path) else {
+            self.last_announced_track_id = None;
+            return;
+        };
+        if !is_playing || self.last_announced_track_id.as_deref() == Some(track_id.as_str()) {
+            return;
+        }
+        self.last_announced_track_id = Some(track_id.clone());
+        if !self.config.enabled {
+            return;
+        }
+        if let Some(text) = self.resolve_announcement_text(&track_id, &path) {
+            self.speak(text);
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Playlist(PlaylistMessage::PlaylistIndicesChanged {
+                playing_track_id,
+                playing_track_path,
+                is_playing,
+                ..
+            }) => {
+                self.handle_playing_track_changed(playing_track_id, playing_track_path, is_playing);
+            }
+            Message::Playlist(PlaylistMessage::TrackMetadataBatchUpdated { updates }) => {
+                for update in updates {
+                    self.remember_remote_metadata(update.track_id, update.summary);
+                }
+            }
+            Message::Playback(PlaybackMessage::Stop) => {
+                self.last_announced_track_id = None;
+                self.cancel_speech();
+            }
+            Message::Config(ConfigMessage::ConfigChanged(changes)) => {
+                for change in changes {
+                    if let ConfigDeltaEntry::Announcements(delta) = change {
+                        self.apply_config_delta(delta);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts the blocking manager loop.
+    pub fn run(&mut self) {
+        info!("AnnouncementManager: started");
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("AnnouncementManager: bus lagged by {} messages", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        self.cancel_speech();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnnouncementManager, REMOTE_METADATA_CACHE_LIMIT};
+    use crate::config::AnnouncementConfig;
+    use crate::protocol::{AnnouncementConfigDelta, Message, TrackMetadataSummary};
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn test_announcement_text_includes_artist_when_present() {
+        assert_eq!(
+            AnnouncementManager::announcement_text(" Song ", " Band "),
+            Some("Now playing Song by Band".to_string())
+        );
+        assert_eq!(
+            AnnouncementManager::announcement_text("Song", ""),
+            Some("Now playing Song".to_string())
+        );
+        assert_eq!(AnnouncementManager::announcement_text("  ", "Band"), None);
+    }
+
+    #[test]
+    fn test_speech_commands_pass_rate_voice_and_text() {
+        let commands = AnnouncementManager::speech_commands("Now playing Song", "en-us", 200);
+        assert!(!commands.is_empty());
+        for command in commands {
+            let joined = command.args.join(" ");
+            assert!(joined.contains("Now playing Song"));
+            assert!(joined.contains("en-us"));
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_speech_commands_end_options_before_text() {
+        for command in AnnouncementManager::speech_commands("-v bad", "", 200) {
+            let text_index = command.args.len() - 1;
+            assert_eq!(command.args[text_index], "-v bad");
+            assert_eq!(command.args[text_index - 1], "--");
+        }
+    }
+
+    #[test]
+    fn test_remote_metadata_cache_drops_oldest_entries() {
+        let (bus_sender, _) = broadcast::channel::<Message>(8);
+        let mut manager = AnnouncementManager::new(
+            bus_sender.subscribe(),
+            bus_sender.clone(),
+            AnnouncementConfig::default(),
+        );
+        for index in 0..REMOTE_METADATA_CACHE_LIMIT + 5 {
+            manager.remember_remote_metadata(
+                format!("track-{index}"),
+                TrackMetadataSummary::default(),
+            );
+        }
+        assert_eq!(
+            manager.remote_metadata_by_track_id.len(),
+            REMOTE_METADATA_CACHE_LIMIT
+        );
+        assert!(!manager.remote_metadata_by_track_id.contains_key("track-4"));
+        assert!(manager.remote_metadata_by_track_id.contains_key("track-5"));
+    }
+
+    #[test]
+    fn test_config_delta_updates_only_present_fields() {
+        let (bus_sender, _) = broadcast::channel::<Message>(8);
+        let mut manager = AnnouncementManager::new(
+            bus_sender.subscribe(),
+            bus_sender.clone(),
+            AnnouncementConfig::default(),
+        );
+
+        manager.apply_config_delta(AnnouncementConfigDelta {
+            enabled: Some(true),
+            rate_wpm: Some(220),
+            ..AnnouncementConfigDelta::default()
+        });
+
+        assert!(manager.config.enabled);
+        assert_eq!(manager.config.rate_wpm, 220);
+        assert!(manager.config.voice.is_empty());
+        assert!(manager.config.duck_playback);
+    }
+}
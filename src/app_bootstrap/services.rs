@@ -9,19 +9,28 @@ use std::{
 use tokio::sync::broadcast;
 
 use crate::{
+    announcement_manager::AnnouncementManager,
     audio_decoder::AudioDecoder,
     audio_player::AudioPlayer,
+    cache_manager::CacheManager,
     cast_manager::CastManager,
     config,
     db_manager::DbManager,
+    db_read_pool::{DbReadPool, LIBRARY_READ_POOL_SIZE},
     integration_manager::IntegrationManager,
     library_enrichment_manager::LibraryEnrichmentManager,
     library_manager::LibraryManager,
+    lyrics_manager::LyricsManager,
+    maintenance_scheduler::MaintenanceScheduler,
     media_controls_manager::MediaControlsManager,
     metadata_manager::MetadataManager,
     playlist::Playlist,
     playlist_manager::PlaylistManager,
     protocol::{self, IntegrationMessage, Message},
+    session_monitor::SessionMonitor,
+    tag_change_watcher::TagChangeWatcher,
+    tag_lookup_manager::TagLookupManager,
+    task_manager::TaskManager,
     ui_manager::UiManager,
     AppWindow,
 };
@@ -42,6 +51,14 @@ pub struct BackgroundServicesConfig {
     pub initial_library_config: config::LibraryConfig,
     /// Initial buffering config snapshot used to seed runtime services before any config deltas.
     pub initial_buffering_config: config::BufferingConfig,
+    /// Initial announcement config snapshot used to seed `AnnouncementManager`.
+    pub initial_announcement_config: config::AnnouncementConfig,
+    /// Initial power config snapshot used to seed `SessionMonitor`.
+    pub initial_power_config: config::PowerConfig,
+    /// Initial DSP config snapshot used to seed `AudioPlayer`.
+    pub initial_dsp_config: config::DspConfig,
+    /// Developer mock backend settings; `Some` replaces the OpenSubsonic adapter.
+    pub initial_mock_backend_config: Option<config::MockBackendConfig>,
     /// Channel carrying batched playlist import requests.
     pub playlist_bulk_import_rx: Receiver<protocol::PlaylistBulkImportRequest>,
     /// Progress producer forwarded into the library manager.
@@ -50,6 +67,8 @@ pub struct BackgroundServicesConfig {
     pub library_scan_progress_rx: Receiver<protocol::LibraryMessage>,
     /// Optional OpenSubsonic profile seed to restore during startup.
     pub startup_opensubsonic_seed: Option<(protocol::BackendProfileSnapshot, Option<String>, bool)>,
+    /// Optional WebDAV profile seed to restore during startup.
+    pub startup_webdav_seed: Option<(protocol::BackendProfileSnapshot, Option<String>, bool)>,
 }
 
 fn panic_payload_to_string(payload: &(dyn Any + Send)) -> String {
@@ -75,10 +94,15 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         initial_ui_config,
         initial_library_config,
         initial_buffering_config,
+        initial_announcement_config,
+        initial_power_config,
+        initial_dsp_config,
+        initial_mock_backend_config,
         playlist_bulk_import_rx,
         library_scan_progress_tx,
         library_scan_progress_rx,
         startup_opensubsonic_seed,
+        startup_webdav_seed,
     } = config;
 
     let integration_manager_bus_receiver = bus_sender.subscribe();
@@ -87,6 +111,7 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         let mut integration_manager = IntegrationManager::new(
             integration_manager_bus_receiver,
             integration_manager_bus_sender,
+            initial_mock_backend_config,
         );
         integration_manager.run();
     });
@@ -114,10 +139,21 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
     let library_initial_config = initial_library_config.clone();
     thread::spawn(move || {
         let db_manager = DbManager::new().expect("Failed to initialize database");
+        let read_pool = match DbReadPool::new(LIBRARY_READ_POOL_SIZE) {
+            Ok(read_pool) => Some(read_pool),
+            Err(err) => {
+                log::warn!(
+                    "Failed to open library read pool, queries will use the writer connection: {}",
+                    err
+                );
+                None
+            }
+        };
         let mut library_manager = LibraryManager::new(
             library_manager_bus_receiver,
             library_manager_bus_sender,
             db_manager,
+            read_pool,
             library_scan_progress_tx,
             library_initial_config,
         );
@@ -138,6 +174,32 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         enrichment_manager.run();
     });
 
+    let maintenance_scheduler_bus_receiver = bus_sender.subscribe();
+    let maintenance_scheduler_bus_sender = bus_sender.clone();
+    let maintenance_initial_config = initial_library_config.clone();
+    thread::spawn(move || {
+        let db_manager = DbManager::new().expect("Failed to initialize database");
+        let mut maintenance_scheduler = MaintenanceScheduler::new(
+            maintenance_scheduler_bus_receiver,
+            maintenance_scheduler_bus_sender,
+            db_manager,
+            maintenance_initial_config,
+        );
+        maintenance_scheduler.run();
+    });
+
+    let cache_manager_bus_receiver = bus_sender.subscribe();
+    let cache_manager_bus_sender = bus_sender.clone();
+    let cache_manager_initial_config = initial_library_config.clone();
+    thread::spawn(move || {
+        let mut cache_manager = CacheManager::new(
+            cache_manager_bus_receiver,
+            cache_manager_bus_sender,
+            cache_manager_initial_config,
+        );
+        cache_manager.run();
+    });
+
     let metadata_manager_bus_receiver = bus_sender.subscribe();
     let metadata_manager_bus_sender = bus_sender.clone();
     thread::spawn(move || {
@@ -150,6 +212,55 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         metadata_manager.run();
     });
 
+    let tag_change_watcher_bus_receiver = bus_sender.subscribe();
+    let tag_change_watcher_bus_sender = bus_sender.clone();
+    let watch_tag_changes = initial_library_config.watch_tag_changes;
+    let slow_storage_mode = initial_library_config.slow_storage_mode;
+    thread::spawn(move || {
+        let db_manager = DbManager::new().expect("Failed to initialize database");
+        let mut tag_change_watcher = TagChangeWatcher::new(
+            tag_change_watcher_bus_receiver,
+            tag_change_watcher_bus_sender,
+            db_manager,
+            watch_tag_changes,
+            slow_storage_mode,
+        );
+        tag_change_watcher.run();
+    });
+
+    let lyrics_manager_bus_receiver = bus_sender.subscribe();
+    let lyrics_manager_bus_sender = bus_sender.clone();
+    let lyrics_initial_config = initial_library_config.clone();
+    thread::spawn(move || {
+        let db_manager = DbManager::new().expect("Failed to initialize database");
+        let mut lyrics_manager = LyricsManager::new(
+            lyrics_manager_bus_receiver,
+            lyrics_manager_bus_sender,
+            db_manager,
+            lyrics_initial_config,
+        );
+        lyrics_manager.run();
+    });
+
+    let tag_lookup_bus_receiver = bus_sender.subscribe();
+    let tag_lookup_bus_sender = bus_sender.clone();
+    let tag_lookup_initial_config = initial_library_config.clone();
+    thread::spawn(move || {
+        let mut tag_lookup_manager = TagLookupManager::new(
+            tag_lookup_bus_receiver,
+            tag_lookup_bus_sender,
+            tag_lookup_initial_config,
+        );
+        tag_lookup_manager.run();
+    });
+
+    let task_manager_bus_receiver = bus_sender.subscribe();
+    let task_manager_bus_sender = bus_sender.clone();
+    thread::spawn(move || {
+        let mut task_manager = TaskManager::new(task_manager_bus_receiver, task_manager_bus_sender);
+        task_manager.run();
+    });
+
     let media_controls_bus_receiver = bus_sender.subscribe();
     let media_controls_bus_sender = bus_sender.clone();
     thread::spawn(move || {
@@ -158,6 +269,28 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         media_controls_manager.run();
     });
 
+    let announcement_manager_bus_receiver = bus_sender.subscribe();
+    let announcement_manager_bus_sender = bus_sender.clone();
+    thread::spawn(move || {
+        let mut announcement_manager = AnnouncementManager::new(
+            announcement_manager_bus_receiver,
+            announcement_manager_bus_sender,
+            initial_announcement_config,
+        );
+        announcement_manager.run();
+    });
+
+    let session_monitor_bus_receiver = bus_sender.subscribe();
+    let session_monitor_bus_sender = bus_sender.clone();
+    thread::spawn(move || {
+        let mut session_monitor = SessionMonitor::new(
+            session_monitor_bus_receiver,
+            session_monitor_bus_sender,
+            initial_power_config,
+        );
+        session_monitor.run();
+    });
+
     let cast_manager_bus_receiver = bus_sender.subscribe();
     let cast_manager_bus_sender = bus_sender.clone();
     let cast_initial_config = initial_cast_config.clone();
@@ -205,7 +338,10 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
         audio_decoder.run();
     });
 
-    if let Some((snapshot, password, connect_now)) = startup_opensubsonic_seed {
+    for (snapshot, password, connect_now) in startup_opensubsonic_seed
+        .into_iter()
+        .chain(startup_webdav_seed)
+    {
         let _ = bus_sender.send(Message::Integration(
             IntegrationMessage::UpsertBackendProfile {
                 profile: snapshot,
@@ -225,6 +361,7 @@ pub fn spawn_background_services(config: BackgroundServicesConfig) {
             player_bus_sender,
             player_initial_output_config,
             player_initial_buffering_config,
+            initial_dsp_config,
         );
         audio_player.run();
     });
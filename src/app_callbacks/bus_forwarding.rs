@@ -1,13 +1,22 @@
 //! UI callback registration for direct event-bus forwarding.
 
+use std::cell::Cell;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::time::Duration;
 
 use log::{debug, warn};
+use slint::{Model, ModelRc, VecModel};
 use tokio::sync::broadcast;
 
 use crate::{
+    cache_manager,
     protocol::{self, CastMessage, Message, MetadataMessage, PlaybackMessage, PlaylistMessage},
+    seek_markers,
+    ui::{
+        keyboard_shortcuts::{self, ShortcutState},
+        seek_steps,
+    },
     AppWindow,
 };
 
@@ -19,6 +28,13 @@ pub struct BusForwardingCallbacksContext {
     pub ui_handle: slint::Weak<AppWindow>,
 }
 
+fn seek_marker_positions_ms(ui: &AppWindow) -> Vec<u64> {
+    ui.get_seek_markers()
+        .iter()
+        .map(|marker| marker.position_ms.max(0) as u64)
+        .collect()
+}
+
 /// Registers low-level UI callbacks that forward user actions to bus messages.
 pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingCallbacksContext) {
     let BusForwardingCallbacksContext {
@@ -113,6 +129,59 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         ));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_queue_selection(move |action| {
+        let action = match action {
+            0 => protocol::LibraryQueueAction::PlayNow,
+            1 => protocol::LibraryQueueAction::PlayNext,
+            _ => protocol::LibraryQueueAction::AddToQueue,
+        };
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::QueueSelected(
+            action,
+        )));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_toggle_track_source(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::ToggleSelectedTrackSource,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_library_timed_mix_apply(move |minutes_text, destination_index, flow_index| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let target_minutes = match minutes_text.trim().parse::<u32>() {
+            Ok(minutes) if (1..=600).contains(&minutes) => minutes,
+            _ => {
+                ui.set_library_timed_mix_error("Enter a length between 1 and 600 minutes".into());
+                return;
+            }
+        };
+        let destination = if destination_index == 1 {
+            protocol::TimedMixDestination::Playlist
+        } else {
+            protocol::TimedMixDestination::Queue
+        };
+        let flow = match flow_index {
+            1 => protocol::EnergyFlow::BuildUp,
+            2 => protocol::EnergyFlow::WindDown,
+            3 => protocol::EnergyFlow::Peak,
+            _ => protocol::EnergyFlow::AsPicked,
+        };
+        ui.set_show_library_timed_mix_dialog(false);
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::TimedMixSelected {
+                target_minutes,
+                destination,
+                flow,
+            },
+        ));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_library_prepare_add_to_playlists(move || {
         let _ = bus_sender_clone.send(Message::Library(
@@ -158,6 +227,113 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         ));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_organize_files(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::OpenOrganizeFiles,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_organize_files_preview(move |template| {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RequestOrganizePreview {
+                template: template.to_string(),
+            },
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_organize_files_apply(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::ConfirmOrganizeFiles,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_organize_files_undo(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::UndoOrganizeFiles,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_organize_files_close(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::CloseOrganizeFiles,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_fix_album_artists(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::OpenAlbumArtistFixes,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_album_artist_fixes_apply(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::ConfirmAlbumArtistFixes,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_album_artist_fixes_close(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::CloseAlbumArtistFixes,
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_open_track_tags(move || {
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::OpenTrackTags));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_tags_toggle(move |index| {
+        if index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::ToggleTrackTagInDialog(index as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_tags_create(move |name| {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::CreateTrackTagInDialog {
+                name: name.to_string(),
+            },
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_tags_cycle_color(move |index| {
+        if index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::CycleTrackTagColorInDialog(index as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_tags_delete(move |index| {
+        if index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::DeleteTrackTagInDialog(index as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_tags_close(move || {
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::CloseTrackTags));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_open_properties_for_current_selection(move || {
         let _ = bus_sender_clone.send(Message::Metadata(
@@ -170,6 +346,18 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::OpenFileLocation));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_copy_file_path(move || {
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::CopyFilePath));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_open_in_external_tagger(move || {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::OpenInExternalTagger,
+        ));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_properties_field_edited(move |index, value| {
         if index < 0 {
@@ -181,11 +369,36 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         }));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_properties_field_apply_changed(move |index, apply| {
+        if index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Metadata(
+            MetadataMessage::SetPropertiesFieldApply {
+                index: index as usize,
+                apply,
+            },
+        ));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_properties_save(move || {
         let _ = bus_sender_clone.send(Message::Metadata(MetadataMessage::SaveProperties));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_properties_lookup_tags(move || {
+        let _ = bus_sender_clone.send(Message::Metadata(MetadataMessage::LookupPropertiesTags));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_properties_render_spectrogram(move || {
+        let _ = bus_sender_clone.send(Message::Metadata(
+            MetadataMessage::RenderPropertiesSpectrogram,
+        ));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_properties_cancel(move || {
         let _ = bus_sender_clone.send(Message::Metadata(MetadataMessage::CancelProperties));
@@ -196,6 +409,26 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::RequestScan));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_task_pause_toggled(move |task_id, paused| {
+        let _ = bus_sender_clone.send(Message::Task(protocol::TaskMessage::SetPaused {
+            task_id: task_id as u64,
+            paused,
+        }));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_task_cancel_requested(move |task_id| {
+        let _ = bus_sender_clone.send(Message::Task(protocol::TaskMessage::Cancel {
+            task_id: task_id as u64,
+        }));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_tasks_clear_finished(move || {
+        let _ = bus_sender_clone.send(Message::Task(protocol::TaskMessage::ClearFinished));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_clear_library_enrichment_cache(move || {
         let _ = bus_sender_clone.send(Message::Library(
@@ -204,20 +437,22 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
     });
 
     let bus_sender_clone = bus_sender.clone();
-    ui.on_remote_detach_confirm(move |playlist_id| {
-        let _ = bus_sender_clone.send(Message::Playlist(
-            PlaylistMessage::ConfirmDetachRemotePlaylist {
-                playlist_id: playlist_id.to_string(),
-            },
-        ));
+    ui.on_settings_clear_cache(move |cache_index| {
+        let Some(kind) = usize::try_from(cache_index)
+            .ok()
+            .and_then(|index| cache_manager::ALL_CACHES.get(index))
+        else {
+            return;
+        };
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::ClearCache(
+            *kind,
+        )));
     });
 
     let bus_sender_clone = bus_sender.clone();
-    ui.on_remote_detach_cancel(move |playlist_id| {
+    ui.on_settings_reset_transition_history(move || {
         let _ = bus_sender_clone.send(Message::Playlist(
-            PlaylistMessage::CancelDetachRemotePlaylist {
-                playlist_id: playlist_id.to_string(),
-            },
+            protocol::PlaylistMessage::ResetTransitionHistory,
         ));
     });
 
@@ -257,6 +492,93 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         let _ = bus_sender_clone.send(Message::Playback(PlaybackMessage::Seek(percentage)));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_seek_to_snapped(move |percentage, snap_tolerance| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let total_ms = ui.get_total_ms().max(0) as u64;
+        let markers_ms = seek_marker_positions_ms(&ui);
+        let percentage = if total_ms > 0 && !markers_ms.is_empty() {
+            let target_ms = (percentage as f64 * total_ms as f64) as u64;
+            let tolerance_ms = (snap_tolerance.max(0.0) as f64 * total_ms as f64) as u64;
+            seek_markers::snap_to_marker(target_ms, &markers_ms, tolerance_ms) as f32
+                / total_ms as f32
+        } else {
+            percentage
+        };
+        debug!("Seek requested to {}%", percentage * 100.0);
+        let _ = bus_sender_clone.send(Message::Playback(PlaybackMessage::Seek(percentage)));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_seek_to_adjacent_marker(move |forward| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let total_ms = ui.get_total_ms().max(0) as u64;
+        let markers_ms = seek_marker_positions_ms(&ui);
+        if total_ms == 0 || markers_ms.is_empty() {
+            return;
+        }
+        let current_ms =
+            (ui.get_position_percentage().clamp(0.0, 1.0) as f64 * total_ms as f64) as u64;
+        if let Some(target_ms) = seek_markers::adjacent_marker(current_ms, &markers_ms, forward) {
+            let _ = bus_sender_clone.send(Message::Playback(PlaybackMessage::Seek(
+                target_ms as f32 / total_ms as f32,
+            )));
+        }
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    let ui_handle_clone = ui_handle.clone();
+    let held_key_repeats = Rc::new(Cell::new(0u32));
+    ui.on_seek_step(move |forward, large, repeat| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let repeats = if repeat {
+            held_key_repeats.get().saturating_add(1)
+        } else {
+            0
+        };
+        held_key_repeats.set(repeats);
+        let step_secs = if large {
+            ui.get_seek_large_step_secs()
+        } else {
+            ui.get_seek_small_step_secs()
+        };
+        let step_ms = seek_steps::accelerated_step_ms(step_secs.max(1) as u64 * 1000, repeats);
+        let total_ms = ui.get_total_ms().max(0) as u64;
+        let current_ms =
+            (ui.get_position_percentage().clamp(0.0, 1.0) as f64 * total_ms as f64) as u64;
+        if let Some(target_ms) =
+            seek_steps::step_seek_target_ms(current_ms, total_ms, step_ms, forward)
+        {
+            let _ = bus_sender_clone.send(Message::Playback(PlaybackMessage::Seek(
+                target_ms as f32 / total_ms as f32,
+            )));
+        }
+    });
+
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_open_shortcut_help(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let rows = keyboard_shortcuts::shortcut_help_rows(
+            ShortcutState {
+                library_mode: ui.get_collection_mode() == 1,
+                has_seek_markers: ui.get_seek_markers().row_count() > 0,
+            },
+            &keyboard_shortcuts::shortcut_keys_from_ui(&ui),
+        );
+        ui.set_shortcut_help_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        ui.set_show_shortcut_help(true);
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_handle_track_click(move |index, ctrl, shift| {
         debug!(
@@ -334,6 +656,16 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         ));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_jump_to_playing_track(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::JumpToPlayingTrack));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_track_list_scrolled_by_user(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::TrackListScrolledByUser));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_open_playlist_search(move || {
         let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::OpenPlaylistSearch));
@@ -376,6 +708,32 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         let _ = library_search_query_tx.send(query.to_string());
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_set_facet_panel_visible(move |visible| {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::SetFacetPanelVisible(visible),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_toggle_facet(move |kind, value| {
+        let kind = match kind {
+            0 => protocol::LibraryFacetKind::Genre,
+            1 => protocol::LibraryFacetKind::Decade,
+            2 => protocol::LibraryFacetKind::Format,
+            _ => return,
+        };
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::ToggleFacet {
+            kind,
+            value: value.to_string(),
+        }));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_library_clear_facets(move || {
+        let _ = bus_sender_clone.send(Message::Library(protocol::LibraryMessage::ClearFacets));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_library_viewport_changed(move |first_row, row_count| {
         if first_row < 0 {
@@ -427,6 +785,54 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         ));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_add_playlist_sort_column(move |column_index| {
+        if column_index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::AddPlaylistSortColumn(
+            column_index as usize,
+        )));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_open_playlist_column_filter(move |column_index| {
+        if column_index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::OpenColumnFilterDialog(
+            column_index as usize,
+        )));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_apply_playlist_column_filter(move |checked_values, operator_index, operand| {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::ApplyColumnFilterDialog {
+                checked_values: checked_values.iter().collect(),
+                operator_index: operator_index.max(0) as usize,
+                operand: operand.to_string(),
+            },
+        ));
+    });
+
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_set_all_column_filter_values(move |checked| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let value_count = ui.get_column_filter_value_labels().row_count();
+        ui.set_column_filter_value_checked(ModelRc::from(Rc::new(VecModel::from(vec![
+            checked;
+            value_count
+        ]))));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_sort_playlist_permanently(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::RequestSortTracks));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_apply_filter_view_to_playlist(move || {
         let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::RequestApplyFilterView));
@@ -456,6 +862,34 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
         let _ = bus_sender_clone.send(Message::Cast(CastMessage::Disconnect));
     });
 
+    let bus_sender_clone = bus_sender.clone();
+    let ui_handle_clone = ui_handle.clone();
+    ui.on_volume_mute_toggled(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        if !ui.get_cast_connected() {
+            return;
+        }
+        // The receiver reports the new state back, which updates the mute indicator.
+        let _ = bus_sender_clone.send(Message::Cast(CastMessage::SetMuted(!ui.get_volume_muted())));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_import_playlist_url(move |url| {
+        let url = url.trim().to_string();
+        if url.is_empty() {
+            return;
+        }
+        debug!("Playlist URL import requested: {}", url);
+        let _ = bus_sender_clone.send(Message::Integration(
+            protocol::IntegrationMessage::FetchPlaylistUrl {
+                local_playlist_id: None,
+                url,
+            },
+        ));
+    });
+
     let bus_sender_clone = bus_sender.clone();
     ui.on_create_playlist(move || {
         debug!("Create playlist requested");
@@ -482,18 +916,83 @@ pub fn register_bus_forwarding_callbacks(ui: &AppWindow, context: BusForwardingC
     });
 
     let bus_sender_clone = bus_sender.clone();
-    ui.on_delete_playlist(move |index| {
-        debug!("Delete playlist requested: index={}", index);
-        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::DeletePlaylistByIndex(
-            index as usize,
-        )));
+    ui.on_toggle_playlist_folder(move |row| {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::TogglePlaylistFolderByRow(row as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_create_playlist_folder(move |row| {
+        debug!("Create playlist folder requested: row={}", row);
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::CreatePlaylistFolderAtRow((row >= 0).then_some(row as usize)),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_rename_playlist_folder(move |row, name| {
+        debug!(
+            "Rename playlist folder requested: row={}, name={}",
+            row, name
+        );
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::RenamePlaylistFolderByRow(row as usize, name.to_string()),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_delete_playlist_folder(move |row| {
+        debug!("Delete playlist folder requested: row={}", row);
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::DeletePlaylistFolderByRow(row as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_move_playlist_tree_row(move |from, to| {
+        if from < 0 || to < 0 {
+            return;
+        }
+        debug!("Move playlist tree row requested: from={}, to={}", from, to);
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::MovePlaylistTreeRow {
+            from: from as usize,
+            to: to as usize,
+        }));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_toggle_playlist_skip_silence(move |index| {
+        debug!("Toggle playlist skip silence requested: index={}", index);
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::TogglePlaylistSkipSilenceByIndex(index as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_toggle_playlist_normalize_loudness(move |index| {
+        debug!(
+            "Toggle playlist loudness normalization requested: index={}",
+            index
+        );
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::TogglePlaylistNormalizeLoudnessByIndex(index as usize),
+        ));
+    });
+
+    let bus_sender_clone = bus_sender.clone();
+    ui.on_sync_remote_playlist_now(move |index| {
+        debug!("Sync remote playlist now requested: index={}", index);
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SyncRemotePlaylistNowByIndex(index as usize),
+        ));
     });
 
     let bus_sender_clone = bus_sender.clone();
-    ui.on_sync_playlist_to_opensubsonic(move |index| {
-        debug!("Sync playlist to OpenSubsonic requested: index={}", index);
+    ui.on_materialize_remote_playlist(move |index| {
+        debug!("Download remote playlist requested: index={}", index);
         let _ = bus_sender_clone.send(Message::Playlist(
-            PlaylistMessage::SyncPlaylistToOpenSubsonicByIndex(index as usize),
+            PlaylistMessage::MaterializeRemotePlaylistByIndex(index as usize),
         ));
     });
 }
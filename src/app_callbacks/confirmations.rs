@@ -0,0 +1,209 @@
+//! UI callback registration for the shared destructive-action confirmation dialog.
+
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+use slint::Model;
+use tokio::sync::broadcast;
+
+use crate::{
+    app_config_coordinator::apply_config_update,
+    app_context::AppSharedState,
+    config::Config,
+    protocol::{Message, PlaylistMessage},
+    ui::confirmation_policy::{self, ConfirmationAction, ConfirmationRequest},
+    AppWindow,
+};
+
+/// Sends `request.on_confirm` right away when the policy does not ask for `request`,
+/// otherwise stores it as pending and opens the confirmation dialog.
+pub(crate) fn request_confirmation(
+    ui: &AppWindow,
+    config_state: &Arc<Mutex<Config>>,
+    pending_confirmation: &Arc<Mutex<Option<ConfirmationRequest>>>,
+    bus_sender: &broadcast::Sender<Message>,
+    request: ConfirmationRequest,
+) {
+    let needs_prompt = {
+        let config = config_state.lock().expect("config state lock poisoned");
+        confirmation_policy::requires_confirmation(&config.confirmations, &request)
+    };
+    if !needs_prompt {
+        let _ = bus_sender.send(request.on_confirm);
+        return;
+    }
+    ui.set_action_confirm_message(request.message.as_str().into());
+    ui.set_action_confirm_button_text(
+        confirmation_policy::confirm_button_text(request.action).into(),
+    );
+    ui.set_action_confirm_dont_ask(false);
+    // A newer prompt replaces an unanswered one; the older action stays undone.
+    let replaced = pending_confirmation
+        .lock()
+        .expect("pending confirmation lock poisoned")
+        .replace(request);
+    if let Some(on_cancel) = replaced.and_then(|request| request.on_cancel) {
+        let _ = bus_sender.send(on_cancel);
+    }
+    ui.set_show_action_confirm(true);
+}
+
+fn shared_request_confirmation(
+    ui: &AppWindow,
+    shared_state: &AppSharedState,
+    request: ConfirmationRequest,
+) {
+    request_confirmation(
+        ui,
+        &shared_state.config_state,
+        &shared_state.pending_confirmation,
+        &shared_state.bus_sender,
+        request,
+    );
+}
+
+fn playlist_name_at(ui: &AppWindow, index: i32) -> String {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| ui.get_playlists().row_data(index))
+        .map(|item| item.text.to_string())
+        .unwrap_or_default()
+}
+
+/// Registers the confirmation dialog callbacks and the playlist actions that go through it.
+pub(crate) fn register_confirmation_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
+    let shared_state_clone = shared_state.clone();
+    ui.on_action_confirm_accepted(move |dont_ask| {
+        let Some(request) = shared_state_clone
+            .pending_confirmation
+            .lock()
+            .expect("pending confirmation lock poisoned")
+            .take()
+        else {
+            return;
+        };
+        if dont_ask {
+            let next_config = {
+                let state = shared_state_clone
+                    .config_state
+                    .lock()
+                    .expect("config state lock poisoned");
+                let mut next = state.clone();
+                confirmation_policy::set_confirmation_enabled(
+                    &mut next.confirmations,
+                    request.action,
+                    false,
+                );
+                crate::sanitize_config(next)
+            };
+            apply_config_update(&shared_state_clone, next_config, true);
+        }
+        let _ = shared_state_clone.bus_sender.send(request.on_confirm);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_action_confirm_cancelled(move || {
+        let request = shared_state_clone
+            .pending_confirmation
+            .lock()
+            .expect("pending confirmation lock poisoned")
+            .take();
+        if let Some(on_cancel) = request.and_then(|request| request.on_cancel) {
+            let _ = shared_state_clone.bus_sender.send(on_cancel);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_confirmation_enabled(move |action_index, enabled| {
+        let Some(action) = usize::try_from(action_index)
+            .ok()
+            .and_then(|index| confirmation_policy::ALL_ACTIONS.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            confirmation_policy::set_confirmation_enabled(
+                &mut next.confirmations,
+                *action,
+                enabled,
+            );
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let ui_handle = ui.as_weak();
+    ui.on_delete_playlist(move |index| {
+        debug!("Delete playlist requested: index={}", index);
+        let Some(ui) = ui_handle.upgrade() else {
+            return;
+        };
+        let name = playlist_name_at(&ui, index);
+        shared_request_confirmation(
+            &ui,
+            &shared_state_clone,
+            ConfirmationRequest {
+                action: ConfirmationAction::DeletePlaylist,
+                item_count: 1,
+                message: format!("Are you sure you want to delete the playlist '{}'?", name),
+                on_confirm: Message::Playlist(PlaylistMessage::DeletePlaylistByIndex(
+                    index as usize,
+                )),
+                on_cancel: None,
+            },
+        );
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let ui_handle = ui.as_weak();
+    ui.on_sync_playlist_to_opensubsonic(move |index| {
+        debug!("Sync playlist to OpenSubsonic requested: index={}", index);
+        let Some(ui) = ui_handle.upgrade() else {
+            return;
+        };
+        let name = playlist_name_at(&ui, index);
+        shared_request_confirmation(
+            &ui,
+            &shared_state_clone,
+            ConfirmationRequest {
+                action: ConfirmationAction::OverwriteRemotePlaylist,
+                item_count: 1,
+                message: format!(
+                    "Sync '{}' to OpenSubsonic? Later edits will overwrite the server copy.",
+                    name
+                ),
+                on_confirm: Message::Playlist(PlaylistMessage::SyncPlaylistToOpenSubsonicByIndex(
+                    index as usize,
+                )),
+                on_cancel: None,
+            },
+        );
+    });
+}
+
+/// Builds the request for the prompt shown before an edit detaches a synced playlist.
+pub(crate) fn detach_remote_playlist_request(
+    playlist_id: String,
+    playlist_name: &str,
+) -> ConfirmationRequest {
+    ConfirmationRequest {
+        action: ConfirmationAction::DetachRemotePlaylist,
+        item_count: 1,
+        message: format!(
+            "This edit will detach '{}' from OpenSubsonic sync and keep it local-only. Continue?",
+            playlist_name
+        ),
+        on_confirm: Message::Playlist(PlaylistMessage::ConfirmDetachRemotePlaylist {
+            playlist_id: playlist_id.clone(),
+        }),
+        on_cancel: Some(Message::Playlist(
+            PlaylistMessage::CancelDetachRemotePlaylist { playlist_id },
+        )),
+    }
+}
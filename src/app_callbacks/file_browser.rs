@@ -0,0 +1,200 @@
+//! Callback registration for File Browser panels.
+
+use std::{
+    path::PathBuf,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+};
+
+use log::debug;
+use slint::{ComponentHandle, ModelRc, VecModel};
+
+use crate::{
+    app_config_coordinator::apply_config_update,
+    app_context::AppSharedState,
+    metadata_tags, protocol,
+    ui::file_browser::{self, FileBrowserTree},
+    worker_pool, AppWindow, FileBrowserRowData,
+};
+
+/// Folder tree shared by every File Browser panel, plus the generation of the latest
+/// tag preview so a slow read cannot overwrite a newer selection. The tree is only
+/// touched on the UI thread; the mutex lets listing results be posted back to it.
+#[derive(Clone)]
+struct FileBrowserState {
+    tree: Arc<Mutex<FileBrowserTree>>,
+    preview_generation: Arc<AtomicU64>,
+}
+
+impl FileBrowserState {
+    fn tree(&self) -> MutexGuard<'_, FileBrowserTree> {
+        self.tree.lock().expect("file browser tree lock poisoned")
+    }
+}
+
+fn sync_file_browser_ui(ui: &AppWindow, tree: &FileBrowserTree) {
+    let selected = tree.selected();
+    let rows: Vec<FileBrowserRowData> = tree
+        .rows()
+        .iter()
+        .map(|row| FileBrowserRowData {
+            name: row.entry.name.as_str().into(),
+            depth: row.depth as i32,
+            is_dir: row.entry.is_dir,
+            expanded: row.expanded,
+            selected: selected == Some(row.entry.path.as_path()),
+        })
+        .collect();
+    ui.set_file_browser_root_label(tree.root().to_string_lossy().to_string().into());
+    ui.set_file_browser_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+}
+
+/// Lists the directories the tree is waiting on using the decode pool, then hands each
+/// listing back on the UI thread and lists any expanded folders it reveals.
+fn request_listings(state: &FileBrowserState, ui_handle: slint::Weak<AppWindow>) {
+    let (generation, directories) = {
+        let mut tree = state.tree();
+        (tree.generation(), tree.take_pending_listings())
+    };
+    for directory in directories {
+        let state = state.clone();
+        let ui_handle = ui_handle.clone();
+        worker_pool::decode_pool().submit(move || {
+            let entries = file_browser::list_directory(&directory);
+            let _ = slint::invoke_from_event_loop(move || {
+                if !state.tree().set_listing(generation, directory, entries) {
+                    return;
+                }
+                if let Some(ui) = ui_handle.upgrade() {
+                    sync_file_browser_ui(&ui, &state.tree());
+                }
+                request_listings(&state, ui_handle);
+            });
+        });
+    }
+}
+
+/// Reads the tags of `path` on the decode pool and shows them as the panel preview.
+fn request_preview(ui: &AppWindow, state: &FileBrowserState, path: PathBuf, file_name: String) {
+    let generation = state.preview_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    ui.set_file_browser_preview(format!("Reading tags of {file_name}...").into());
+    let preview_generation = state.preview_generation.clone();
+    let ui_handle = ui.as_weak();
+    worker_pool::decode_pool().submit(move || {
+        let metadata = metadata_tags::read_common_track_metadata(&path);
+        let preview = file_browser::preview_text(&file_name, metadata.as_ref());
+        let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+            if preview_generation.load(Ordering::SeqCst) == generation {
+                ui.set_file_browser_preview(preview.into());
+            }
+        });
+    });
+}
+
+/// Registers folder tree navigation, root selection, and add-to-playlist callbacks.
+pub(crate) fn register_file_browser_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
+    let root = {
+        let config = shared_state
+            .config_state
+            .lock()
+            .expect("config state lock poisoned");
+        file_browser::default_root(&config.ui.file_browser_root, &config.library.folders)
+    };
+    let state = FileBrowserState {
+        tree: Arc::new(Mutex::new(FileBrowserTree::new(root))),
+        preview_generation: Arc::new(AtomicU64::new(0)),
+    };
+    sync_file_browser_ui(ui, &state.tree());
+    request_listings(&state, ui.as_weak());
+
+    let state_clone = state.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_file_browser_row_activated(move |index| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let Some(entry) = usize::try_from(index)
+            .ok()
+            .and_then(|index| state_clone.tree().activate(index))
+        else {
+            return;
+        };
+        sync_file_browser_ui(&ui, &state_clone.tree());
+        request_listings(&state_clone, ui.as_weak());
+        if !entry.is_dir {
+            request_preview(&ui, &state_clone, entry.path, entry.name);
+        }
+    });
+
+    let state_clone = state.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_file_browser_refresh(move || {
+        state_clone.tree().refresh();
+        request_listings(&state_clone, ui_handle_clone.clone());
+    });
+
+    let state_clone = state.clone();
+    let shared_state_clone = shared_state.clone();
+    ui.on_file_browser_choose_root(move || {
+        let current_root = state_clone.tree().root().to_path_buf();
+        let Some(root) = rfd::FileDialog::new()
+            .set_directory(current_root)
+            .pick_folder()
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.file_browser_root = root.to_string_lossy().to_string();
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+        state_clone.tree().set_root(root);
+        let ui_handle = shared_state_clone.ui_handles.ui_handle.clone();
+        if let Some(ui) = ui_handle.upgrade() {
+            ui.set_file_browser_preview("".into());
+            sync_file_browser_ui(&ui, &state_clone.tree());
+        }
+        request_listings(&state_clone, ui_handle);
+    });
+
+    let state_clone = state;
+    let shared_state_clone = shared_state.clone();
+    ui.on_file_browser_add_to_playlist(move |index| {
+        let Some(row) = usize::try_from(index)
+            .ok()
+            .and_then(|index| state_clone.tree().row(index).cloned())
+        else {
+            return;
+        };
+        let playlist_bulk_import_tx = shared_state_clone.playlist_bulk_import_tx.clone();
+        let bus_sender = shared_state_clone.bus_sender.clone();
+        // Folders are walked recursively off the UI thread; tags are read by the
+        // playlist import pipeline.
+        thread::spawn(move || {
+            let tracks = crate::collect_audio_files_from_dropped_paths(&[row.entry.path]);
+            if tracks.is_empty() {
+                debug!("File browser: no supported tracks under {}", row.entry.name);
+                return;
+            }
+            let queued = crate::enqueue_playlist_bulk_import(
+                &playlist_bulk_import_tx,
+                &bus_sender,
+                &tracks,
+                protocol::ImportSource::DragAndDrop,
+            );
+            debug!(
+                "Queued {} track(s) from the file browser into playlist",
+                queued
+            );
+        });
+    });
+}
@@ -4,21 +4,311 @@ use std::{
     cell::{Cell, RefCell},
     path::PathBuf,
     rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     thread,
     time::Duration,
 };
 
 use log::debug;
 use slint::winit_030::{winit, EventResult as WinitEventResult, WinitWindowAccessor};
-use slint::ComponentHandle;
+use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
 
 use crate::{
     app_config_coordinator::apply_config_update,
     app_context::AppSharedState,
+    cache_manager,
+    config::FolderImportRuleConfig,
+    import_rules, maintenance_scheduler,
+    playlist_document::PlaylistDocumentFormat,
     protocol::{self, Message},
+    ui::{import_dialog, playlist_columns},
     AppWindow,
 };
 
+/// File name suggested when exporting the playlist at sidebar `index`.
+fn playlist_export_file_stem(ui: Option<&AppWindow>, index: usize) -> String {
+    let playlist_name = ui
+        .and_then(|ui| ui.get_playlists().row_data(index))
+        .map(|item| item.text.to_string())
+        .unwrap_or_default();
+    let file_stem: String = playlist_name
+        .trim()
+        .chars()
+        .map(|ch| {
+            if matches!(ch, '/' | '\\' | ':') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    if file_stem.is_empty() {
+        "Playlist".to_string()
+    } else {
+        file_stem
+    }
+}
+
+/// Roots picked in the import dialog plus the generation of the latest track-count estimate.
+#[derive(Clone, Default)]
+struct ImportDialogState {
+    roots: Rc<RefCell<Vec<PathBuf>>>,
+    estimate_generation: Arc<AtomicU64>,
+}
+
+fn recent_import_locations(shared_state: &AppSharedState) -> Vec<String> {
+    shared_state
+        .config_state
+        .lock()
+        .expect("config state lock poisoned")
+        .ui
+        .recent_import_locations
+        .clone()
+}
+
+fn string_model(values: impl IntoIterator<Item = String>) -> ModelRc<SharedString> {
+    ModelRc::new(VecModel::from(
+        values
+            .into_iter()
+            .map(SharedString::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Pushes the selected roots to the dialog and re-estimates their track count off-thread.
+fn refresh_import_dialog(ui: &AppWindow, state: &ImportDialogState) {
+    let roots = state.roots.borrow().clone();
+    ui.set_import_dialog_roots(string_model(
+        roots.iter().map(|root| root.to_string_lossy().to_string()),
+    ));
+    let generation = state.estimate_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    if roots.is_empty() {
+        ui.set_import_dialog_estimate_text(import_dialog::estimate_text(0, None).into());
+        return;
+    }
+    ui.set_import_dialog_estimate_text(import_dialog::estimate_text(roots.len(), None).into());
+    let estimate_generation = state.estimate_generation.clone();
+    let ui_handle = ui.as_weak();
+    thread::spawn(move || {
+        let track_count = crate::collect_audio_files_from_dropped_paths(&roots).len();
+        let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+            if estimate_generation.load(Ordering::SeqCst) == generation {
+                ui.set_import_dialog_estimate_text(
+                    import_dialog::estimate_text(roots.len(), Some(track_count)).into(),
+                );
+            }
+        });
+    });
+}
+
+fn add_import_dialog_roots(
+    ui: &AppWindow,
+    state: &ImportDialogState,
+    new_roots: impl IntoIterator<Item = PathBuf>,
+) {
+    let changed = import_dialog::add_roots(&mut state.roots.borrow_mut(), new_roots);
+    if changed {
+        refresh_import_dialog(ui, state);
+    }
+}
+
+fn browse_import_files(ui: &AppWindow, state: &ImportDialogState) {
+    let Some(paths) = rfd::FileDialog::new()
+        .add_filter("Audio Files", &crate::SUPPORTED_AUDIO_EXTENSIONS)
+        .pick_files()
+    else {
+        return;
+    };
+    let filtered_paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| {
+            if crate::is_supported_audio_file(path) {
+                true
+            } else {
+                debug!(
+                    "Skipping unsupported file from import dialog: {}",
+                    path.display()
+                );
+                false
+            }
+        })
+        .collect();
+    add_import_dialog_roots(ui, state, filtered_paths);
+}
+
+fn browse_import_folders(ui: &AppWindow, state: &ImportDialogState) {
+    if let Some(folders) = rfd::FileDialog::new().pick_folders() {
+        add_import_dialog_roots(ui, state, folders);
+    }
+}
+
+fn open_import_dialog(ui: &AppWindow, shared_state: &AppSharedState, state: &ImportDialogState) {
+    if !ui.get_show_import_dialog() {
+        state.roots.borrow_mut().clear();
+        refresh_import_dialog(ui, state);
+    }
+    ui.set_import_dialog_recent_locations(string_model(recent_import_locations(shared_state)));
+    ui.set_show_import_dialog(true);
+}
+
+/// Registers the playlist import dialog: file/folder browsing, recent locations, and import.
+fn register_import_dialog_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
+    let dialog_state = ImportDialogState::default();
+
+    let shared_state_clone = shared_state.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_open_file(move || {
+        debug!("Opening import dialog for files");
+        let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() else {
+            return;
+        };
+        open_import_dialog(&ui, &shared_state_clone, &dialog_state_clone);
+        browse_import_files(&ui, &dialog_state_clone);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_open_folder(move || {
+        debug!("Opening import dialog for folders");
+        let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() else {
+            return;
+        };
+        open_import_dialog(&ui, &shared_state_clone, &dialog_state_clone);
+        browse_import_folders(&ui, &dialog_state_clone);
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_browse_files(move || {
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            browse_import_files(&ui, &dialog_state_clone);
+        }
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_browse_folders(move || {
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            browse_import_folders(&ui, &dialog_state_clone);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_add_recent(move |index| {
+        let Some(location) = usize::try_from(index).ok().and_then(|index| {
+            recent_import_locations(&shared_state_clone)
+                .get(index)
+                .cloned()
+        }) else {
+            return;
+        };
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            add_import_dialog_roots(&ui, &dialog_state_clone, [PathBuf::from(location)]);
+        }
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_remove_root(move |index| {
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        {
+            let mut roots = dialog_state_clone.roots.borrow_mut();
+            if index >= roots.len() {
+                return;
+            }
+            roots.remove(index);
+        }
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            refresh_import_dialog(&ui, &dialog_state_clone);
+        }
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_close(move || {
+        dialog_state_clone.roots.borrow_mut().clear();
+        dialog_state_clone
+            .estimate_generation
+            .fetch_add(1, Ordering::SeqCst);
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            ui.set_show_import_dialog(false);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let dialog_state_clone = dialog_state.clone();
+    ui.on_import_dialog_confirm(move || {
+        let roots = std::mem::take(&mut *dialog_state_clone.roots.borrow_mut());
+        dialog_state_clone
+            .estimate_generation
+            .fetch_add(1, Ordering::SeqCst);
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            ui.set_show_import_dialog(false);
+        }
+        if roots.is_empty() {
+            return;
+        }
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.recent_import_locations =
+                import_dialog::remember_recent_locations(&next.ui.recent_import_locations, &roots);
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+
+        let bulk_import_tx = shared_state_clone.playlist_bulk_import_tx.clone();
+        let bus_sender = shared_state_clone.bus_sender.clone();
+        thread::spawn(move || {
+            let batches: Vec<(Vec<PathBuf>, protocol::ImportSource)> = roots
+                .iter()
+                .map(|root| {
+                    if root.is_dir() {
+                        (
+                            crate::collect_audio_files_from_folder(root),
+                            protocol::ImportSource::AddFolderDialog,
+                        )
+                    } else {
+                        (vec![root.clone()], protocol::ImportSource::AddFilesDialog)
+                    }
+                })
+                .filter(|(tracks, _)| !tracks.is_empty())
+                .collect();
+            let total: usize = batches.iter().map(|(tracks, _)| tracks.len()).sum();
+            if total == 0 {
+                debug!("Import dialog found no supported tracks");
+                return;
+            }
+            let _ = bus_sender.send(Message::Playlist(
+                protocol::PlaylistMessage::BulkImportQueued { total },
+            ));
+            let mut queued = 0usize;
+            for (tracks, source) in &batches {
+                queued += crate::enqueue_playlist_bulk_import(
+                    &bulk_import_tx,
+                    &bus_sender,
+                    tracks,
+                    *source,
+                );
+            }
+            debug!(
+                "Queued {} track(s) from {} import location(s)",
+                queued,
+                batches.len()
+            );
+        });
+    });
+}
+
 /// Registers import and library-folder callbacks on the root app component.
 pub(crate) fn register_imports_library_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
     let library_folder_import_context = crate::LibraryFolderImportContext {
@@ -30,7 +320,22 @@ pub(crate) fn register_imports_library_callbacks(ui: &AppWindow, shared_state: &
     let playlist_bulk_import_tx_for_drop = shared_state.playlist_bulk_import_tx.clone();
     let bus_sender_for_drop = shared_state.bus_sender.clone();
     let library_context_for_drop = library_folder_import_context.clone();
-    ui.window().on_winit_window_event(move |_, event| {
+    let config_state_for_scale = shared_state.config_state.clone();
+    // Slint keeps a single winit event hook per window, so monitor scale changes are
+    // routed from here too.
+    ui.window().on_winit_window_event(move |window, event| {
+        if matches!(event, winit::event::WindowEvent::ScaleFactorChanged { .. }) {
+            let override_percent = config_state_for_scale
+                .lock()
+                .expect("config state lock poisoned")
+                .ui
+                .scale_override_percent;
+            return crate::ui::window_scale::handle_monitor_scale_changed(
+                window,
+                event,
+                override_percent,
+            );
+        }
         if let winit::event::WindowEvent::DroppedFile(path) = event {
             debug!("Dropped file: {}", path.display());
             let collection_mode = ui_handle_for_drop
@@ -74,16 +379,11 @@ pub(crate) fn register_imports_library_callbacks(ui: &AppWindow, shared_state: &
                                     debug!("Ignored dropped playlist item(s): no supported tracks");
                                     return;
                                 }
-                                let source = if playlist_paths.iter().any(|path| path.is_dir()) {
-                                    protocol::ImportSource::AddFolderDialog
-                                } else {
-                                    protocol::ImportSource::AddFilesDialog
-                                };
                                 let queued = crate::enqueue_playlist_bulk_import(
                                     &playlist_bulk_import_tx,
                                     &bus_sender,
                                     &tracks,
-                                    source,
+                                    protocol::ImportSource::DragAndDrop,
                                 );
                                 debug!(
                                     "Queued {} track(s) from drag-and-drop into playlist",
@@ -111,77 +411,233 @@ pub(crate) fn register_imports_library_callbacks(ui: &AppWindow, shared_state: &
         WinitEventResult::Propagate
     });
 
-    let playlist_bulk_import_tx_clone = shared_state.playlist_bulk_import_tx.clone();
-    let bus_sender_clone = shared_state.bus_sender.clone();
-    ui.on_open_file(move || {
-        debug!("Opening file dialog");
-        if let Some(paths) = rfd::FileDialog::new()
-            .add_filter("Audio Files", &crate::SUPPORTED_AUDIO_EXTENSIONS)
-            .pick_files()
-        {
-            let filtered_paths: Vec<PathBuf> = paths
-                .into_iter()
-                .filter(|path| {
-                    if crate::is_supported_audio_file(path) {
-                        true
-                    } else {
-                        debug!(
-                            "Skipping unsupported file from import dialog: {}",
-                            path.display()
-                        );
-                        false
-                    }
-                })
-                .collect();
-            if filtered_paths.is_empty() {
-                return;
+    register_import_dialog_callbacks(ui, shared_state);
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_select_library_folder(move |index| {
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            ui.set_settings_library_selected_folder_index(index);
+        }
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_select_import_rule(move |index| {
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            ui.set_settings_library_selected_import_rule_index(index);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_import_rule_open_editor(move |index| {
+        let rule = if index >= 0 {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            match state.library.import_rules.get(index as usize) {
+                Some(rule) => rule.clone(),
+                None => return,
             }
-            let queued = crate::enqueue_playlist_bulk_import(
-                &playlist_bulk_import_tx_clone,
-                &bus_sender_clone,
-                &filtered_paths,
-                protocol::ImportSource::AddFilesDialog,
-            );
-            debug!("Queued {} track(s) from Add files", queued);
+        } else {
+            FolderImportRuleConfig::default()
+        };
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            ui.set_import_rule_edit_index(index);
+            ui.set_import_rule_folder_text(rule.folder.into());
+            ui.set_import_rule_genre_text(rule.genre.into());
+            ui.set_import_rule_tags_text(rule.tags.join(", ").into());
+            ui.set_import_rule_playlist_text(rule.playlist.into());
+            ui.set_import_rule_exclude_from_shuffle(rule.exclude_from_shuffle);
+            ui.set_import_rule_error_text("".into());
+            ui.set_show_import_rule_dialog(true);
         }
     });
 
-    let playlist_bulk_import_tx_clone = shared_state.playlist_bulk_import_tx.clone();
-    let bus_sender_clone = shared_state.bus_sender.clone();
-    ui.on_open_folder(move || {
-        debug!("Opening folder dialog");
-        if let Some(folder_path) = rfd::FileDialog::new().pick_folder() {
-            let bus_sender_for_scan = bus_sender_clone.clone();
-            let bulk_import_tx = playlist_bulk_import_tx_clone.clone();
-            thread::spawn(move || {
-                let tracks = crate::collect_audio_files_from_folder(&folder_path);
-                debug!(
-                    "Found {} track(s) in folder import {}",
-                    tracks.len(),
-                    folder_path.display()
-                );
-                let queued = crate::enqueue_playlist_bulk_import(
-                    &bulk_import_tx,
-                    &bus_sender_for_scan,
-                    &tracks,
-                    protocol::ImportSource::AddFolderDialog,
-                );
-                debug!(
-                    "Queued {} track(s) from Add folder {}",
-                    queued,
-                    folder_path.display()
-                );
-            });
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_import_rule_browse_folder(move || {
+        let Some(folder_path) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        if let Some(ui) = ui_handle_clone.upgrade() {
+            ui.set_import_rule_folder_text(folder_path.to_string_lossy().to_string().into());
+            ui.set_import_rule_error_text("".into());
         }
     });
 
+    let shared_state_clone = shared_state.clone();
+    ui.on_import_rule_save(
+        move |index, folder, genre, tags, playlist, exclude_from_shuffle| {
+            let Some(rule) = import_rules::sanitize_import_rule(&FolderImportRuleConfig {
+                folder: folder.to_string(),
+                genre: genre.to_string(),
+                tags: import_rules::parse_rule_tags(tags.as_str()),
+                playlist: playlist.to_string(),
+                exclude_from_shuffle,
+            }) else {
+                if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+                    ui.set_import_rule_error_text("Choose a folder for this rule.".into());
+                }
+                return;
+            };
+            let next_config = {
+                let state = shared_state_clone
+                    .config_state
+                    .lock()
+                    .expect("config state lock poisoned");
+                let mut next = state.clone();
+                match usize::try_from(index)
+                    .ok()
+                    .filter(|index| *index < next.library.import_rules.len())
+                {
+                    Some(index) => next.library.import_rules[index] = rule,
+                    None => next.library.import_rules.push(rule),
+                }
+                crate::sanitize_config(next)
+            };
+            apply_config_update(&shared_state_clone, next_config, true);
+            if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+                ui.set_show_import_rule_dialog(false);
+            }
+        },
+    );
+
     let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
-    ui.on_settings_select_library_folder(move |index| {
+    ui.on_import_rule_close(move || {
         if let Some(ui) = ui_handle_clone.upgrade() {
-            ui.set_settings_library_selected_folder_index(index);
+            ui.set_show_import_rule_dialog(false);
         }
     });
 
+    let shared_state_clone = shared_state.clone();
+    ui.on_import_rule_remove(move |index| {
+        if index < 0 {
+            return;
+        }
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            if index as usize >= state.library.import_rules.len() {
+                return;
+            }
+            let mut next = state.clone();
+            next.library.import_rules.remove(index as usize);
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_maintenance_interval(move |task_index, choice_index| {
+        let (Some(task), Some(hours)) = (
+            usize::try_from(task_index)
+                .ok()
+                .and_then(|index| maintenance_scheduler::ALL_TASKS.get(index)),
+            usize::try_from(choice_index)
+                .ok()
+                .and_then(|index| maintenance_scheduler::INTERVAL_CHOICES_HOURS.get(index)),
+        ) else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            maintenance_scheduler::set_interval_hours(&mut next.library, *task, *hours);
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_settings_run_maintenance_task(move |task_index| {
+        let Some(task) = usize::try_from(task_index)
+            .ok()
+            .and_then(|index| maintenance_scheduler::ALL_TASKS.get(index))
+        else {
+            return;
+        };
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RunMaintenanceTask(*task),
+        ));
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_pick_cache_location(move |cache_index| {
+        let Some(kind) = usize::try_from(cache_index)
+            .ok()
+            .and_then(|index| cache_manager::ALL_CACHES.get(index))
+        else {
+            return;
+        };
+        let Some(folder_path) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            cache_manager::set_configured_dir(
+                &mut next.library,
+                *kind,
+                folder_path.to_string_lossy().to_string(),
+            );
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_reset_cache_location(move |cache_index| {
+        let Some(kind) = usize::try_from(cache_index)
+            .ok()
+            .and_then(|index| cache_manager::ALL_CACHES.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            cache_manager::set_configured_dir(&mut next.library, *kind, String::new());
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_cache_limit(move |cache_index, choice_index| {
+        let Some(kind) = usize::try_from(cache_index)
+            .ok()
+            .and_then(|index| cache_manager::ALL_CACHES.get(index))
+        else {
+            return;
+        };
+        let Some(value_mb) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| cache_manager::limit_choices_mb(*kind).get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            cache_manager::set_max_size_mb(&mut next.library, *kind, *value_mb);
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
     let library_folder_import_context_clone = library_folder_import_context.clone();
     ui.on_library_add_folder(move || {
         let Some(folder_path) = rfd::FileDialog::new().pick_folder() else {
@@ -276,4 +732,192 @@ pub(crate) fn register_imports_library_callbacks(ui: &AppWindow, shared_state: &
         };
         apply_config_update(&shared_state_clone, next_config, true);
     });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_library_watch_tag_changes(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.library.watch_tag_changes = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_library_slow_storage_mode(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.library.slow_storage_mode = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_export_playlist_xspf(move |index| {
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        let file_stem = playlist_export_file_stem(ui_handle_clone.upgrade().as_ref(), index);
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("XSPF Playlist", &["xspf"])
+            .set_file_name(format!("{file_stem}.xspf"))
+            .save_file()
+        else {
+            return;
+        };
+        let _ = bus_sender_clone.send(Message::Playlist(
+            protocol::PlaylistMessage::ExportPlaylistXspfByIndex { index, path },
+        ));
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let config_state_clone = shared_state.config_state.clone();
+    ui.on_open_playlist_document_export(move |index| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let Ok(playlist_index) = usize::try_from(index) else {
+            return;
+        };
+        // The document is built from the track list, so that playlist has to be open.
+        if ui.get_active_playlist_index() != index {
+            let _ = bus_sender_clone.send(Message::Playlist(
+                protocol::PlaylistMessage::SwitchPlaylistByIndex(playlist_index),
+            ));
+        }
+        let column_names: Vec<SharedString> = {
+            let config = config_state_clone
+                .lock()
+                .expect("config state lock poisoned");
+            playlist_columns::text_columns(&config.ui.playlist_columns)
+                .into_iter()
+                .map(|column| column.name.as_str().into())
+                .collect()
+        };
+        let format_options: Vec<SharedString> = PlaylistDocumentFormat::ALL
+            .iter()
+            .map(|format| format.label().into())
+            .collect();
+        ui.set_playlist_document_format_options(ModelRc::from(Rc::new(VecModel::from(
+            format_options,
+        ))));
+        ui.set_playlist_document_column_checked(ModelRc::from(Rc::new(VecModel::from(vec![
+            true;
+            column_names.len()
+        ]))));
+        ui.set_playlist_document_column_names(ModelRc::from(Rc::new(VecModel::from(column_names))));
+        ui.set_playlist_document_source_index(index);
+        ui.set_playlist_document_error("".into());
+        ui.set_show_playlist_document_dialog(true);
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let config_state_clone = shared_state.config_state.clone();
+    ui.on_playlist_document_export(
+        move |source_index, format_index, column_checked, include_covers| {
+            let Some(ui) = ui_handle_clone.upgrade() else {
+                return;
+            };
+            let (Ok(index), Some(format)) = (
+                usize::try_from(source_index),
+                usize::try_from(format_index)
+                    .ok()
+                    .and_then(|format_index| PlaylistDocumentFormat::ALL.get(format_index)),
+            ) else {
+                return;
+            };
+            let columns: Vec<_> = {
+                let config = config_state_clone
+                    .lock()
+                    .expect("config state lock poisoned");
+                playlist_columns::text_columns(&config.ui.playlist_columns)
+                    .into_iter()
+                    .zip(column_checked.iter())
+                    .filter(|(_, checked)| *checked)
+                    .map(|(column, _)| column)
+                    .collect()
+            };
+            if columns.is_empty() {
+                ui.set_playlist_document_error("Choose at least one column".into());
+                return;
+            }
+            ui.set_playlist_document_error("".into());
+            let file_stem = playlist_export_file_stem(Some(&ui), index);
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter(format.label(), &[format.extension()])
+                .set_file_name(format!("{file_stem}.{}", format.extension()))
+                .save_file()
+            else {
+                return;
+            };
+            ui.set_show_playlist_document_dialog(false);
+            let _ = bus_sender_clone.send(Message::Playlist(
+                protocol::PlaylistMessage::ExportPlaylistDocument {
+                    index,
+                    path,
+                    format: *format,
+                    columns,
+                    include_covers,
+                },
+            ));
+        },
+    );
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_verify_playlist_files(move |index| {
+        let Ok(playlist_index) = usize::try_from(index) else {
+            return;
+        };
+        // Missing files are marked in the track list, so show the playlist being checked.
+        if ui_handle_clone
+            .upgrade()
+            .is_some_and(|ui| ui.get_active_playlist_index() != index)
+        {
+            let _ = bus_sender_clone.send(Message::Playlist(
+                protocol::PlaylistMessage::SwitchPlaylistByIndex(playlist_index),
+            ));
+        }
+        let _ = bus_sender_clone.send(Message::Playlist(
+            protocol::PlaylistMessage::VerifyPlaylistFiles(playlist_index),
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_relocate_missing_files(move || {
+        let Some(new_base) = rfd::FileDialog::new()
+            .set_title("Choose the folder the missing files moved to")
+            .pick_folder()
+        else {
+            return;
+        };
+        let _ = bus_sender_clone.send(Message::Playlist(
+            protocol::PlaylistMessage::RelocateMissingFiles { new_base },
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_import_xspf_playlist(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("XSPF Playlist", &["xspf"])
+            .pick_file()
+        else {
+            return;
+        };
+        let _ = bus_sender_clone.send(Message::Playlist(
+            protocol::PlaylistMessage::ImportXspfPlaylist(path),
+        ));
+    });
 }
@@ -1,9 +1,15 @@
 //! UI callback registration modules grouped by feature area.
 
 pub mod bus_forwarding;
+pub mod confirmations;
+pub mod file_browser;
 pub mod imports_library;
 pub mod layout_editor;
 pub mod playlist_columns;
 pub mod playlist_editing;
+pub mod preset_bundles;
+pub mod profile_share;
 pub mod settings_ui;
+pub mod spectrogram;
 pub mod subsonic_settings;
+pub mod webdav_settings;
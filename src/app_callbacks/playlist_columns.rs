@@ -13,6 +13,7 @@ use crate::{
     config::{Config, PlaylistColumnConfig, UiConfig},
     config_persistence::persist_state_files_with_config_path,
     protocol::{Message, PlaylistMessage},
+    ui::column_format,
     AppWindow,
 };
 
@@ -246,6 +247,77 @@ pub(crate) fn register_playlist_column_callbacks(ui: &AppWindow, shared_state: &
         }
     });
 
+    let shared_state_clone = shared_state.clone();
+    ui.on_open_column_style_dialog(move |visible_index| {
+        let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() else {
+            return;
+        };
+        let (column_name, style) = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let Some(column) = state
+                .ui
+                .playlist_columns
+                .iter()
+                .filter(|column| column.enabled)
+                .nth(visible_index.max(0) as usize)
+            else {
+                return;
+            };
+            let style =
+                crate::layout_column_style(&state.ui.layout, &crate::playlist_column_key(column));
+            (column.name.clone(), style)
+        };
+        let [alignment, elision, number_format, font_weight] =
+            column_format::style_option_indices(style);
+        ui.set_column_style_target_index(visible_index);
+        ui.set_column_style_column_name(column_name.into());
+        ui.set_column_style_alignment_index(alignment);
+        ui.set_column_style_elision_index(elision);
+        ui.set_column_style_number_format_index(number_format);
+        ui.set_column_style_font_weight_index(font_weight);
+        ui.set_show_column_style_dialog(true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_apply_column_style(
+        move |visible_index, alignment, elision, number_format, font_weight| {
+            if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+                ui.set_show_column_style_dialog(false);
+            }
+            let style = column_format::style_from_option_indices([
+                alignment,
+                elision,
+                number_format,
+                font_weight,
+            ]);
+            let next_config = {
+                let mut state = shared_state_clone
+                    .config_state
+                    .lock()
+                    .expect("config state lock poisoned");
+                let Some(column_key) = crate::playlist_column_key_at_visible_index(
+                    &state.ui.playlist_columns,
+                    visible_index.max(0) as usize,
+                ) else {
+                    return;
+                };
+                let mut next = state.clone();
+                crate::upsert_layout_column_style(&mut next.ui.layout, &column_key, style);
+                next = crate::sanitize_config(next);
+                *state = next.clone();
+                next
+            };
+            persist_state_files_with_config_path(
+                &next_config,
+                &shared_state_clone.persistence_paths.config_file,
+            );
+            publish_runtime_from_state(&shared_state_clone, &next_config);
+        },
+    );
+
     let shared_state_clone = shared_state.clone();
     ui.on_toggle_playlist_column(move |column_index| {
         let column_idx = column_index.max(0) as usize;
@@ -291,10 +363,28 @@ pub(crate) fn register_playlist_column_callbacks(ui: &AppWindow, shared_state: &
                 volume: previous_config.ui.volume,
                 playback_order: previous_config.ui.playback_order,
                 repeat_mode: previous_config.ui.repeat_mode,
+                energy_ramp_arc: previous_config.ui.energy_ramp_arc,
+                recent_import_locations: previous_config.ui.recent_import_locations.clone(),
+                hi_res_min_sample_rate_hz: previous_config.ui.hi_res_min_sample_rate_hz,
+                hi_res_min_bit_depth: previous_config.ui.hi_res_min_bit_depth,
+                seek_small_step_secs: previous_config.ui.seek_small_step_secs,
+                seek_large_step_secs: previous_config.ui.seek_large_step_secs,
+                zero_based_row_numbers: previous_config.ui.zero_based_row_numbers,
+                transport_bar_placement: previous_config.ui.transport_bar_placement,
+                scale_override_percent: previous_config.ui.scale_override_percent,
+                file_browser_root: previous_config.ui.file_browser_root.clone(),
+                external_tagger_command: previous_config.ui.external_tagger_command.clone(),
+                library_album_grid: previous_config.ui.library_album_grid,
+                shortcut_keys: previous_config.ui.shortcut_keys.clone(),
             },
             library: previous_config.library.clone(),
             buffering: previous_config.buffering.clone(),
             integrations: previous_config.integrations.clone(),
+            announcements: previous_config.announcements.clone(),
+            power: previous_config.power.clone(),
+            performance: previous_config.performance.clone(),
+            confirmations: previous_config.confirmations.clone(),
+            dsp: previous_config.dsp.clone(),
         });
 
         if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
@@ -367,10 +457,28 @@ pub(crate) fn register_playlist_column_callbacks(ui: &AppWindow, shared_state: &
                 volume: previous_config.ui.volume,
                 playback_order: previous_config.ui.playback_order,
                 repeat_mode: previous_config.ui.repeat_mode,
+                energy_ramp_arc: previous_config.ui.energy_ramp_arc,
+                recent_import_locations: previous_config.ui.recent_import_locations.clone(),
+                hi_res_min_sample_rate_hz: previous_config.ui.hi_res_min_sample_rate_hz,
+                hi_res_min_bit_depth: previous_config.ui.hi_res_min_bit_depth,
+                seek_small_step_secs: previous_config.ui.seek_small_step_secs,
+                seek_large_step_secs: previous_config.ui.seek_large_step_secs,
+                zero_based_row_numbers: previous_config.ui.zero_based_row_numbers,
+                transport_bar_placement: previous_config.ui.transport_bar_placement,
+                scale_override_percent: previous_config.ui.scale_override_percent,
+                file_browser_root: previous_config.ui.file_browser_root.clone(),
+                external_tagger_command: previous_config.ui.external_tagger_command.clone(),
+                library_album_grid: previous_config.ui.library_album_grid,
+                shortcut_keys: previous_config.ui.shortcut_keys.clone(),
             },
             library: previous_config.library.clone(),
             buffering: previous_config.buffering.clone(),
             integrations: previous_config.integrations.clone(),
+            announcements: previous_config.announcements.clone(),
+            power: previous_config.power.clone(),
+            performance: previous_config.performance.clone(),
+            confirmations: previous_config.confirmations.clone(),
+            dsp: previous_config.dsp.clone(),
         });
 
         if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
@@ -445,10 +553,28 @@ pub(crate) fn register_playlist_column_callbacks(ui: &AppWindow, shared_state: &
                 volume: previous_config.ui.volume,
                 playback_order: previous_config.ui.playback_order,
                 repeat_mode: previous_config.ui.repeat_mode,
+                energy_ramp_arc: previous_config.ui.energy_ramp_arc,
+                recent_import_locations: previous_config.ui.recent_import_locations.clone(),
+                hi_res_min_sample_rate_hz: previous_config.ui.hi_res_min_sample_rate_hz,
+                hi_res_min_bit_depth: previous_config.ui.hi_res_min_bit_depth,
+                seek_small_step_secs: previous_config.ui.seek_small_step_secs,
+                seek_large_step_secs: previous_config.ui.seek_large_step_secs,
+                zero_based_row_numbers: previous_config.ui.zero_based_row_numbers,
+                transport_bar_placement: previous_config.ui.transport_bar_placement,
+                scale_override_percent: previous_config.ui.scale_override_percent,
+                file_browser_root: previous_config.ui.file_browser_root.clone(),
+                external_tagger_command: previous_config.ui.external_tagger_command.clone(),
+                library_album_grid: previous_config.ui.library_album_grid,
+                shortcut_keys: previous_config.ui.shortcut_keys.clone(),
             },
             library: previous_config.library.clone(),
             buffering: previous_config.buffering.clone(),
             integrations: previous_config.integrations.clone(),
+            announcements: previous_config.announcements.clone(),
+            power: previous_config.power.clone(),
+            performance: previous_config.performance.clone(),
+            confirmations: previous_config.confirmations.clone(),
+            dsp: previous_config.dsp.clone(),
         });
 
         if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
@@ -517,10 +643,28 @@ pub(crate) fn register_playlist_column_callbacks(ui: &AppWindow, shared_state: &
                 volume: previous_config.ui.volume,
                 playback_order: previous_config.ui.playback_order,
                 repeat_mode: previous_config.ui.repeat_mode,
+                energy_ramp_arc: previous_config.ui.energy_ramp_arc,
+                recent_import_locations: previous_config.ui.recent_import_locations.clone(),
+                hi_res_min_sample_rate_hz: previous_config.ui.hi_res_min_sample_rate_hz,
+                hi_res_min_bit_depth: previous_config.ui.hi_res_min_bit_depth,
+                seek_small_step_secs: previous_config.ui.seek_small_step_secs,
+                seek_large_step_secs: previous_config.ui.seek_large_step_secs,
+                zero_based_row_numbers: previous_config.ui.zero_based_row_numbers,
+                transport_bar_placement: previous_config.ui.transport_bar_placement,
+                scale_override_percent: previous_config.ui.scale_override_percent,
+                file_browser_root: previous_config.ui.file_browser_root.clone(),
+                external_tagger_command: previous_config.ui.external_tagger_command.clone(),
+                library_album_grid: previous_config.ui.library_album_grid,
+                shortcut_keys: previous_config.ui.shortcut_keys.clone(),
             },
             library: previous_config.library.clone(),
             buffering: previous_config.buffering.clone(),
             integrations: previous_config.integrations.clone(),
+            announcements: previous_config.announcements.clone(),
+            power: previous_config.power.clone(),
+            performance: previous_config.performance.clone(),
+            confirmations: previous_config.confirmations.clone(),
+            dsp: previous_config.dsp.clone(),
         });
 
         if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
@@ -1,32 +1,60 @@
 //! Callback registration for playlist track-list editing interactions.
 
+use std::path::PathBuf;
+use std::rc::Rc;
+
 use log::{debug, trace};
-use slint::Model;
+use slint::{Model, ModelRc, VecModel};
 
 use crate::{
+    app_callbacks::confirmations::request_confirmation,
     app_context::AppSharedState,
-    protocol::{self, Message, PlaylistMessage},
+    playlist_restructure::parse_merge_ratio,
+    protocol::{self, Message, PlaylistMergeMode, PlaylistMessage, PlaylistSplitRule},
+    ui::confirmation_policy::{ConfirmationAction, ConfirmationRequest},
     AppWindow,
 };
 
+/// Image types offered when choosing a playlist cover.
+const PLAYLIST_COVER_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "gif", "bmp"];
+
 /// Registers edit/drag/cut/copy/paste callbacks for the playlist and library lists.
 pub(crate) fn register_playlist_editing_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
-    let bus_sender_clone = shared_state.bus_sender.clone();
+    let shared_state_clone = shared_state.clone();
     let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
     ui.on_delete_selected_tracks(move || {
-        if let Some(ui) = ui_handle_clone.upgrade() {
-            if ui.get_collection_mode() == 1 {
-                let _ = bus_sender_clone
-                    .send(Message::Library(protocol::LibraryMessage::DeleteSelected));
-                return;
-            }
-            if ui.get_playlist_filter_active() {
-                crate::flash_read_only_view_indicator(ui_handle_clone.clone());
-                return;
-            }
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        if ui.get_collection_mode() == 1 {
+            let _ = shared_state_clone
+                .bus_sender
+                .send(Message::Library(protocol::LibraryMessage::DeleteSelected));
+            return;
+        }
+        if ui.get_playlist_filter_active() {
+            crate::flash_read_only_view_indicator(ui_handle_clone.clone());
+            return;
         }
-        debug!("Delete selected tracks requested");
-        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::DeleteSelected));
+        let selected_count = ui
+            .get_track_model()
+            .iter()
+            .filter(|row| row.selected)
+            .count();
+        debug!("Delete selected tracks requested: count={}", selected_count);
+        request_confirmation(
+            &ui,
+            &shared_state_clone.config_state,
+            &shared_state_clone.pending_confirmation,
+            &shared_state_clone.bus_sender,
+            ConfirmationRequest {
+                action: ConfirmationAction::RemoveTracks,
+                item_count: selected_count,
+                message: format!("Remove {} tracks from this playlist?", selected_count),
+                on_confirm: Message::Playlist(PlaylistMessage::DeleteSelected),
+                on_cancel: None,
+            },
+        );
     });
 
     let bus_sender_clone = shared_state.bus_sender.clone();
@@ -171,4 +199,306 @@ pub(crate) fn register_playlist_editing_callbacks(ui: &AppWindow, shared_state:
             drag_blocked,
         }));
     });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_up_next_row_activated(move |position| {
+        if position < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::PlayUpcomingTrack(
+            position as usize,
+        )));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_up_next_row_moved(move |from, to| {
+        if from < 0 || to < 0 {
+            return;
+        }
+        debug!("Up next reorder requested: from {} to {}", from, to);
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::MoveUpcomingTrack {
+            from: from as usize,
+            to: to as usize,
+        }));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_save_playback_queue_as_playlist(move |shuffle_order| {
+        debug!(
+            "Save playback queue requested: shuffle_order={}",
+            shuffle_order
+        );
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SavePlaybackQueueAsPlaylist { shuffle_order },
+        ));
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_open_playlist_restructure_dialog(move |index| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let playlist_count = ui.get_playlists().row_count();
+        ui.set_playlist_restructure_merge_checked(ModelRc::from(Rc::new(VecModel::from(vec![
+            false;
+            playlist_count
+        ]))));
+        ui.set_playlist_restructure_source_index(index);
+        ui.set_playlist_restructure_error("".into());
+        ui.set_show_playlist_restructure_dialog(true);
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_playlist_restructure_apply(
+        move |source_index, mode_index, amount_text, merge_checked| {
+            let Some(ui) = ui_handle_clone.upgrade() else {
+                return;
+            };
+            if source_index < 0 {
+                return;
+            }
+            let source_index = source_index as usize;
+            let message = if mode_index < 4 {
+                let rule = match mode_index {
+                    0 => PlaylistSplitRule::Artist,
+                    1 => PlaylistSplitRule::Genre,
+                    2 => PlaylistSplitRule::Decade,
+                    _ => match amount_text.trim().parse::<usize>() {
+                        Ok(count) if count > 0 => PlaylistSplitRule::EveryNTracks(count),
+                        _ => {
+                            ui.set_playlist_restructure_error(
+                                "Enter a positive number of tracks per playlist".into(),
+                            );
+                            return;
+                        }
+                    },
+                };
+                PlaylistMessage::SplitPlaylistByIndex {
+                    index: source_index,
+                    rule,
+                }
+            } else {
+                let mut indices = vec![source_index];
+                indices.extend(
+                    merge_checked
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, checked)| *checked && *index != source_index)
+                        .map(|(index, _)| index),
+                );
+                if indices.len() < 2 {
+                    ui.set_playlist_restructure_error(
+                        "Choose at least one playlist to merge with".into(),
+                    );
+                    return;
+                }
+                let mode = if mode_index == 5 {
+                    match parse_merge_ratio(&amount_text, indices.len()) {
+                        Some(weights) => PlaylistMergeMode::Ratio(weights),
+                        None => {
+                            ui.set_playlist_restructure_error(
+                                format!(
+                                    "Enter {} positive weights separated by ':'",
+                                    indices.len()
+                                )
+                                .into(),
+                            );
+                            return;
+                        }
+                    }
+                } else {
+                    PlaylistMergeMode::RoundRobin
+                };
+                PlaylistMessage::MergePlaylistsByIndex { indices, mode }
+            };
+            debug!("Playlist restructure requested: {:?}", message);
+            ui.set_show_playlist_restructure_dialog(false);
+            let _ = bus_sender_clone.send(Message::Playlist(message));
+        },
+    );
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_open_playlist_details(move |index| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let Ok(row) = usize::try_from(index) else {
+            return;
+        };
+        let description = ui
+            .get_playlist_descriptions()
+            .row_data(row)
+            .unwrap_or_default();
+        let has_cover = ui.get_playlist_has_cover().row_data(row).unwrap_or(false);
+        ui.set_playlist_details_index(index);
+        ui.set_playlist_details_description(description);
+        ui.set_playlist_details_cover(ui.get_playlist_covers().row_data(row).unwrap_or_default());
+        ui.set_playlist_details_has_cover(has_cover);
+        ui.set_playlist_details_cover_source("".into());
+        ui.set_playlist_details_reset_cover(false);
+        ui.set_playlist_details_error("".into());
+        ui.set_show_playlist_details_dialog(true);
+    });
+
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_choose_playlist_details_cover(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Images", &PLAYLIST_COVER_EXTENSIONS)
+            .pick_file()
+        else {
+            return;
+        };
+        match slint::Image::load_from_path(&path) {
+            Ok(image) => {
+                ui.set_playlist_details_cover(image);
+                ui.set_playlist_details_has_cover(true);
+                ui.set_playlist_details_cover_source(path.to_string_lossy().as_ref().into());
+                ui.set_playlist_details_reset_cover(false);
+                ui.set_playlist_details_error("".into());
+            }
+            Err(_) => {
+                ui.set_playlist_details_error("That file could not be read as an image".into());
+            }
+        }
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_save_playlist_details(move |index, description, cover_source, reset_cover| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        ui.set_show_playlist_details_dialog(false);
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        let cover_source = (!cover_source.is_empty()).then(|| PathBuf::from(cover_source.as_str()));
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SavePlaylistDetailsByIndex {
+                index,
+                description: description.to_string(),
+                cover_source,
+                reset_cover,
+            },
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_notes_track_saved(move |note| {
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::SaveNotesPanelTrackNote(note.to_string()),
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_notes_playlist_saved(move |note| {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SaveNotesPanelPlaylistNote(note.to_string()),
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_open_track_transition(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::OpenTrackTransition));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_analyze_selected_loudness(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::AnalyzeSelectedTrackLoudness,
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_transition_save(move |trim_start_text, trim_end_text| {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SaveTrackTransitionDialog {
+                trim_start_text: trim_start_text.to_string(),
+                trim_end_text: trim_end_text.to_string(),
+            },
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_transition_detect(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::DetectTrackTrimsInDialog));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_transition_close(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::CloseTrackTransition));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_open_send_to_playlist(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::OpenSendToPlaylistDialog));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_open_new_playlist_from_selection(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::OpenNewPlaylistFromSelection,
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_send_to_playlist_close(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::CloseSendToPlaylistDialog,
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_send_to_playlist_confirm(move |checked| {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::ConfirmSendToPlaylists {
+            checked: checked.iter().collect(),
+        }));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_send_to_recent_playlist(move |recent_index| {
+        if recent_index < 0 {
+            return;
+        }
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::SendToRecentPlaylist(
+            recent_index as usize,
+        )));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_new_playlist_from_selection_confirm(move |name| {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::ConfirmNewPlaylistFromSelection {
+                name: name.to_string(),
+            },
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_open_track_energy(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::OpenTrackEnergy));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_energy_save(move |level_index| {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::SaveTrackEnergyDialog {
+            level_index,
+        }));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_energy_suggest(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(
+            PlaylistMessage::SuggestTrackEnergyInDialog,
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_track_energy_close(move || {
+        let _ = bus_sender_clone.send(Message::Playlist(PlaylistMessage::CloseTrackEnergy));
+    });
 }
@@ -0,0 +1,208 @@
+//! Callback registration for exporting and importing preset bundles from Settings.
+
+use std::{cell::RefCell, rc::Rc};
+
+use slint::{Model, ModelRc, SharedString, VecModel};
+
+use crate::{
+    app_config_coordinator::apply_config_update,
+    app_context::AppSharedState,
+    ui::{
+        keyboard_shortcuts::ShortcutCommand,
+        preset_bundle::{self, PresetBundle, PresetSection},
+    },
+    AppWindow,
+};
+
+fn set_preset_status(shared_state: &AppSharedState, status: String) {
+    if let Some(ui) = shared_state.ui_handles.ui_handle.upgrade() {
+        ui.set_settings_preset_status(status.into());
+    }
+}
+
+/// Fills and opens the import dialog for `bundle`, with every section picked.
+fn show_import_dialog(
+    ui: &AppWindow,
+    bundle: &PresetBundle,
+    conflicts: &[String],
+    shortcut_conflicts: &[ShortcutCommand],
+) {
+    let section_labels: Vec<SharedString> = bundle
+        .sections
+        .iter()
+        .map(|section| preset_bundle::section_label(*section).into())
+        .collect();
+    let resolution_options: Vec<SharedString> = preset_bundle::CONFLICT_RESOLUTIONS
+        .iter()
+        .map(|resolution| preset_bundle::resolution_label(*resolution).into())
+        .collect();
+    let conflict_text = if conflicts.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "You already have columns named {} with a different format.",
+            conflicts
+                .iter()
+                .map(|name| format!("\"{name}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    ui.set_preset_import_name(bundle.name.as_str().into());
+    ui.set_preset_import_section_checked(ModelRc::from(Rc::new(VecModel::from(vec![
+        true;
+        section_labels.len()
+    ]))));
+    ui.set_preset_import_section_labels(ModelRc::from(Rc::new(VecModel::from(section_labels))));
+    ui.set_preset_import_conflict_text(conflict_text.into());
+    ui.set_preset_import_resolution_options(ModelRc::from(Rc::new(VecModel::from(
+        resolution_options,
+    ))));
+    ui.set_preset_import_resolution_index(0);
+    let shortcut_conflict_text = if shortcut_conflicts.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "You set other shortcut letters than the preset for {}.",
+            shortcut_conflicts
+                .iter()
+                .map(|command| command.label())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    let shortcut_resolution_options: Vec<SharedString> =
+        preset_bundle::SHORTCUT_CONFLICT_RESOLUTIONS
+            .iter()
+            .map(|resolution| preset_bundle::shortcut_resolution_label(*resolution).into())
+            .collect();
+    ui.set_preset_import_shortcut_conflict_text(shortcut_conflict_text.into());
+    ui.set_preset_import_shortcut_resolution_options(ModelRc::from(Rc::new(VecModel::from(
+        shortcut_resolution_options,
+    ))));
+    ui.set_preset_import_shortcut_resolution_index(0);
+    ui.set_show_preset_import_dialog(true);
+}
+
+/// Registers the Settings preset export and import callbacks.
+pub(crate) fn register_preset_bundle_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
+    let pending_bundle: Rc<RefCell<Option<PresetBundle>>> = Rc::new(RefCell::new(None));
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_export_preset(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("roqtune Preset", &[preset_bundle::PRESET_FILE_EXTENSION])
+            .set_file_name(format!(
+                "My preset.{}",
+                preset_bundle::PRESET_FILE_EXTENSION
+            ))
+            .save_file()
+        else {
+            return;
+        };
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let bundle = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            preset_bundle::bundle_from_config(&state, &name)
+        };
+        let status = match preset_bundle::write_bundle_file(&bundle, &path) {
+            Ok(()) => format!("Exported \"{}\"", bundle.name),
+            Err(err) => {
+                log::warn!("Preset export failed for {}: {}", path.display(), err);
+                err
+            }
+        };
+        set_preset_status(&shared_state_clone, status);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    let pending_bundle_clone = Rc::clone(&pending_bundle);
+    ui.on_settings_import_preset(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("roqtune Preset", &[preset_bundle::PRESET_FILE_EXTENSION])
+            .pick_file()
+        else {
+            return;
+        };
+        let bundle = match preset_bundle::read_bundle_file(&path) {
+            Ok(bundle) => bundle,
+            Err(err) => {
+                log::warn!("Preset import failed for {}: {}", path.display(), err);
+                set_preset_status(&shared_state_clone, err);
+                return;
+            }
+        };
+        let (conflicts, shortcut_conflicts) = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            (
+                preset_bundle::column_conflicts(&state.ui.playlist_columns, &bundle),
+                preset_bundle::shortcut_conflicts(&state.ui.shortcut_keys, &bundle),
+            )
+        };
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            show_import_dialog(&ui, &bundle, &conflicts, &shortcut_conflicts);
+            *pending_bundle_clone.borrow_mut() = Some(bundle);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_preset_import_confirmed(move |section_checked, resolution_index, shortcut_index| {
+        let Some(bundle) = pending_bundle.borrow_mut().take() else {
+            return;
+        };
+        let sections: Vec<PresetSection> = bundle
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| section_checked.row_data(*index).unwrap_or(false))
+            .map(|(_, section)| *section)
+            .collect();
+        if sections.is_empty() {
+            return;
+        }
+        let resolution = usize::try_from(resolution_index)
+            .ok()
+            .and_then(|index| preset_bundle::CONFLICT_RESOLUTIONS.get(index))
+            .copied()
+            .unwrap_or_default();
+        let shortcut_resolution = usize::try_from(shortcut_index)
+            .ok()
+            .and_then(|index| preset_bundle::SHORTCUT_CONFLICT_RESOLUTIONS.get(index))
+            .copied()
+            .unwrap_or_default();
+        let previous = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            state.clone()
+        };
+        let next = crate::sanitize_config(preset_bundle::apply_bundle(
+            &previous,
+            &bundle,
+            &sections,
+            resolution,
+            shortcut_resolution,
+        ));
+        // A replaced workspace or theme can be taken back with the layout editor's undo.
+        if sections.contains(&PresetSection::Workspace) || sections.contains(&PresetSection::Theme)
+        {
+            crate::push_layout_undo_snapshot(
+                &shared_state_clone.layout_undo_stack,
+                &shared_state_clone.layout_redo_stack,
+                &previous.ui.layout,
+            );
+        }
+        apply_config_update(&shared_state_clone, next, true);
+        set_preset_status(&shared_state_clone, format!("Imported \"{}\"", bundle.name));
+    });
+}
@@ -0,0 +1,96 @@
+//! Callback registration for sharing remote profiles and opening share links.
+
+use log::{info, warn};
+use slint::{ComponentHandle, Rgba8Pixel, SharedPixelBuffer};
+
+use crate::{
+    profile_share::{self, ProfileShare},
+    protocol::BackendKind,
+    AppWindow,
+};
+
+/// Pixels per QR module in the share dialog.
+const QR_MODULE_SCALE: u32 = 6;
+
+const SETTINGS_TAB_INTEGRATIONS: i32 = 3;
+
+/// Opens Settings -> Integrations with the form of the link's backend filled in. The
+/// password stays empty for the user to enter.
+pub(crate) fn apply_profile_share_to_ui(ui: &AppWindow, share: &ProfileShare) {
+    info!(
+        "Opening profile share link for {} ({:?})",
+        share.endpoint, share.backend_kind
+    );
+    ui.invoke_open_settings();
+    ui.set_settings_dialog_tab_index(SETTINGS_TAB_INTEGRATIONS);
+    let status = "Filled in from a share link. Enter the password and Save.";
+    match share.backend_kind {
+        BackendKind::OpenSubsonic => {
+            ui.set_settings_subsonic_enabled(true);
+            ui.set_settings_subsonic_endpoint(share.endpoint.as_str().into());
+            ui.set_settings_subsonic_username(share.username.as_str().into());
+            ui.set_settings_subsonic_password("".into());
+            ui.set_settings_subsonic_status(status.into());
+        }
+        BackendKind::WebDav => {
+            ui.set_settings_webdav_enabled(true);
+            ui.set_settings_webdav_endpoint(share.endpoint.as_str().into());
+            ui.set_settings_webdav_username(share.username.as_str().into());
+            ui.set_settings_webdav_password("".into());
+            ui.set_settings_webdav_status(status.into());
+        }
+        BackendKind::LocalFs => {}
+    }
+}
+
+/// Registers the Settings -> Integrations share callbacks.
+pub(crate) fn register_profile_share_callbacks(ui: &AppWindow) {
+    let ui_handle = ui.as_weak();
+    ui.on_settings_share_backend_profile(move |webdav| {
+        let Some(ui) = ui_handle.upgrade() else {
+            return;
+        };
+        let (backend_kind, endpoint, username, title) = if webdav {
+            (
+                BackendKind::WebDav,
+                ui.get_settings_webdav_endpoint(),
+                ui.get_settings_webdav_username(),
+                "Share WebDAV Setup",
+            )
+        } else {
+            (
+                BackendKind::OpenSubsonic,
+                ui.get_settings_subsonic_endpoint(),
+                ui.get_settings_subsonic_username(),
+                "Share OpenSubsonic Setup",
+            )
+        };
+        let share = ProfileShare {
+            backend_kind,
+            endpoint: endpoint.to_string(),
+            username: username.to_string(),
+        };
+        let Some(link) = profile_share::encode_profile_share_link(&share) else {
+            let message = "Enter the server URL first";
+            if webdav {
+                ui.set_settings_webdav_status(message.into());
+            } else {
+                ui.set_settings_subsonic_status(message.into());
+            }
+            return;
+        };
+        let qr_image = match profile_share::qr_code_rgba(&link, QR_MODULE_SCALE) {
+            Some((side, pixels)) => slint::Image::from_rgba8(
+                SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(&pixels, side, side),
+            ),
+            None => {
+                warn!("Profile share link is too long for a QR code");
+                slint::Image::default()
+            }
+        };
+        ui.set_profile_share_title(title.into());
+        ui.set_profile_share_link(link.into());
+        ui.set_profile_share_qr(qr_image);
+        ui.set_show_profile_share_dialog(true);
+    });
+}
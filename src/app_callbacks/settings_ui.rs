@@ -1,6 +1,7 @@
 //! Callback registration for settings dialog, tooltip, and window-size UI events.
 
 use std::{
+    cell::RefCell,
     rc::Rc,
     sync::{atomic::Ordering, Arc, Mutex},
     time::Duration,
@@ -10,18 +11,24 @@ use log::debug;
 use slint::{Model, ModelRc, VecModel};
 
 use crate::{
+    app_config_coordinator::apply_config_update,
     app_context::AppSharedState,
     config::{
-        CastConfig, Config, OutputConfig, ResamplerQuality, UiConfig, UiPlaybackOrder, UiRepeatMode,
+        CastConfig, CastDeviceVolumeCap, Config, EqDevicePresetConfig, OutputConfig,
+        OutputDeviceRatePolicyConfig, ResamplerQuality, SampleRatePolicy, UiConfig,
+        UiEnergyRampArc, UiPlaybackOrder, UiRepeatMode, UiTransportBarPlacement,
     },
     config_persistence::persist_state_files_with_config_path,
+    dsp_chain, eq_profile_import, fault_injection, night_mode, output_test,
     protocol::{self, Message, PlaybackMessage, PlaylistMessage},
     runtime_config::{
         audio_settings_changed, config_delta_entries, output_preferences_changed,
         runtime_output_override_snapshot, update_last_runtime_config_snapshot,
         OutputRuntimeSignature, StagedAudioSettings,
     },
-    AppWindow,
+    sample_rate_grouping, silence_skipping,
+    ui::{dsp_compare::DspComparison, seek_steps, window_scale},
+    worker_pool, AppWindow,
 };
 
 fn shared_string_model_to_vec(model: ModelRc<slint::SharedString>) -> Vec<String> {
@@ -91,6 +98,86 @@ fn settings_save_delta_entries(
     config_delta_entries(previous, next)
 }
 
+/// Output device, channels and sample rate as currently staged in the settings dialog,
+/// so the output test can check them before they are applied.
+fn staged_output_test_config(
+    ui: &AppWindow,
+    options: &crate::OutputSettingsOptions,
+    saved: &OutputConfig,
+) -> OutputConfig {
+    let mut output = saved.clone();
+    let device_idx = ui.get_settings_output_device_index().max(0) as usize;
+    output.output_device_auto = device_idx == 0;
+    if device_idx > 0 && device_idx <= options.device_names.len() {
+        output.output_device_name = options.device_names[device_idx - 1].clone();
+    } else if device_idx > options.device_names.len() {
+        let custom_name = ui.get_settings_output_device_custom_value();
+        if !custom_name.trim().is_empty() {
+            output.output_device_name = custom_name.trim().to_string();
+        }
+    }
+    let channel_idx = ui.get_settings_channel_index().max(0) as usize;
+    output.channel_count_auto = channel_idx == 0;
+    if channel_idx > 0 && channel_idx <= options.channel_values.len() {
+        output.channel_count = options.channel_values[channel_idx - 1];
+    } else if let Ok(channels) = ui.get_settings_channel_custom_value().trim().parse::<u16>() {
+        if channel_idx > 0 && channels > 0 {
+            output.channel_count = channels;
+        }
+    }
+    output.sample_rate_auto = ui.get_settings_sample_rate_mode_index() == 0;
+    let sample_rate_idx = ui.get_settings_sample_rate_index().max(0) as usize;
+    if let Some(rate) = options.sample_rate_values.get(sample_rate_idx) {
+        output.sample_rate_khz = *rate;
+    } else if let Ok(rate) = ui
+        .get_settings_sample_rate_custom_value()
+        .trim()
+        .parse::<u32>()
+    {
+        if rate > 0 {
+            output.sample_rate_khz = rate;
+        }
+    }
+    output
+}
+
+/// Saves a change to the Match Content rate policy of the output device selected in
+/// settings. A device without its own policy starts from the one it inherits.
+/// Sends the bypass state the listener should hear and mirrors the A/B row into the UI.
+fn sync_dsp_comparison(
+    ui: &AppWindow,
+    comparison: &DspComparison,
+    bus_sender: &tokio::sync::broadcast::Sender<Message>,
+) {
+    let _ = bus_sender.send(Message::Playback(PlaybackMessage::SetDspBypass(
+        comparison.bypassed(),
+    )));
+    ui.set_settings_dsp_blind_test_active(comparison.blind_test_active());
+    ui.set_settings_dsp_blind_choice(comparison.selected_blind_choice() as i32);
+}
+
+fn update_device_rate_policy(
+    shared: &AppSharedState,
+    update: impl FnOnce(&mut OutputDeviceRatePolicyConfig),
+) {
+    let next_config = {
+        let state = shared
+            .config_state
+            .lock()
+            .expect("config state lock poisoned");
+        let mut next = state.clone();
+        let mut assignment = next.output.active_rate_policy();
+        assignment.output_device_name = next.output.output_device_name.clone();
+        update(&mut assignment);
+        next.output
+            .device_rate_policies
+            .retain(|existing| existing.output_device_name != assignment.output_device_name);
+        next.output.device_rate_policies.push(assignment);
+        crate::sanitize_config(next)
+    };
+    apply_config_update(shared, next_config, true);
+}
+
 /// Registers callbacks that mutate persisted settings and runtime audio/UI state.
 pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
     let bus_sender_clone = shared_state.bus_sender.clone();
@@ -102,6 +189,8 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
             0 => Some(UiPlaybackOrder::Default),
             1 => Some(UiPlaybackOrder::Shuffle),
             2 => Some(UiPlaybackOrder::Random),
+            3 => Some(UiPlaybackOrder::ShuffleFolders),
+            4 => Some(UiPlaybackOrder::EnergyRamp),
             _ => None,
         };
         let Some(next_order) = next_order else {
@@ -124,6 +213,16 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
                     PlaylistMessage::ChangePlaybackOrder(protocol::PlaybackOrder::Random),
                 ));
             }
+            UiPlaybackOrder::ShuffleFolders => {
+                let _ = bus_sender_clone.send(Message::Playlist(
+                    PlaylistMessage::ChangePlaybackOrder(protocol::PlaybackOrder::ShuffleFolders),
+                ));
+            }
+            UiPlaybackOrder::EnergyRamp => {
+                let _ = bus_sender_clone.send(Message::Playlist(
+                    PlaylistMessage::ChangePlaybackOrder(protocol::PlaybackOrder::EnergyRamp),
+                ));
+            }
         }
 
         let should_persist = {
@@ -198,10 +297,70 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
         let _ = bus_sender_clone.send(Message::Playback(PlaybackMessage::SetVolume(clamped)));
     });
 
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let config_state_clone = shared_state.config_state.clone();
+    let runtime_audio_state_clone = shared_state.runtime_handles.runtime_audio_state.clone();
+    let staged_audio_settings_clone = shared_state.runtime_handles.staged_audio_settings.clone();
+    let config_file_clone = shared_state.persistence_paths.config_file.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_cast_max_volume_changed(move |max_volume| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let device_id = ui.get_cast_device_id().trim().to_string();
+        if device_id.is_empty() {
+            return;
+        }
+        let max_volume = max_volume.clamp(0.0, 1.0);
+        debug!(
+            "Cast max volume for {} changed to {}%",
+            device_id,
+            max_volume * 100.0
+        );
+        let (device_volume_caps, snapshot) = {
+            let mut state = config_state_clone
+                .lock()
+                .expect("config state lock poisoned");
+            let caps = &mut state.cast.device_volume_caps;
+            match caps.iter_mut().find(|cap| cap.device_id == device_id) {
+                Some(cap) => cap.max_volume = max_volume,
+                None => caps.push(CastDeviceVolumeCap {
+                    device_id,
+                    max_volume,
+                }),
+            }
+            (state.cast.device_volume_caps.clone(), state.clone())
+        };
+        // Volume caps apply immediately, so keep runtime and staged cast settings in step.
+        runtime_audio_state_clone
+            .lock()
+            .expect("runtime audio state lock poisoned")
+            .cast
+            .device_volume_caps = device_volume_caps.clone();
+        if let Some(staged) = staged_audio_settings_clone
+            .lock()
+            .expect("staged audio settings lock poisoned")
+            .as_mut()
+        {
+            staged.cast.device_volume_caps = device_volume_caps.clone();
+        }
+        ui.set_cast_max_volume(max_volume);
+        persist_state_files_with_config_path(&snapshot, &config_file_clone);
+        let _ = bus_sender_clone.send(Message::Config(protocol::ConfigMessage::ConfigChanged(
+            vec![protocol::ConfigDeltaEntry::Cast(
+                protocol::CastConfigDelta {
+                    device_volume_caps: Some(device_volume_caps),
+                    ..Default::default()
+                },
+            )],
+        )));
+    });
+
     let config_state_clone = shared_state.config_state.clone();
     let output_options_clone = shared_state.runtime_handles.output_options.clone();
     let layout_workspace_size_clone = shared_state.ui_handles.layout_workspace_size.clone();
     let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
     ui.on_open_settings(move || {
         let current_config = {
             let state = config_state_clone
@@ -228,6 +387,18 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
             ui.set_settings_dialog_tab_index(0);
             ui.set_show_settings_dialog(true);
         }
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RequestMaintenanceHistory,
+        ));
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RequestIntegrityReport,
+        ));
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RequestActivityLog,
+        ));
+        let _ = bus_sender_clone.send(Message::Library(
+            protocol::LibraryMessage::RequestCacheUsage,
+        ));
     });
 
     let tooltip_hover_generation = Arc::new(Mutex::new(0u64));
@@ -386,6 +557,9 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
               resampler_quality_index,
               dither_on_bitdepth_reduce,
               downmix_higher_channel_tracks,
+              skip_hidden_pregap,
+              group_sample_rate_families,
+              resample_rate_outliers,
               cast_allow_transcode_fallback,
               color_scheme_id,
               custom_color_values| {
@@ -460,7 +634,9 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
                     .unwrap_or(previous_config.output.bits_per_sample)
             };
             let resampler_quality = match resampler_idx {
-                1 => ResamplerQuality::Highest,
+                0 => ResamplerQuality::Linear,
+                1 => ResamplerQuality::Medium,
+                3 => ResamplerQuality::Highest,
                 _ => ResamplerQuality::High,
             };
             let selected_color_scheme =
@@ -488,12 +664,19 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
                     channel_count_auto,
                     sample_rate_auto,
                     bits_per_sample_auto,
+                    group_sample_rate_families,
+                    resample_rate_outliers,
+                    device_rate_policies: previous_config.output.device_rate_policies.clone(),
                     resampler_quality,
                     dither_on_bitdepth_reduce,
                     downmix_higher_channel_tracks,
+                    skip_hidden_pregap,
+                    skip_silence_spoken_word: previous_config.output.skip_silence_spoken_word,
+                    skip_silence_min_pause_ms: previous_config.output.skip_silence_min_pause_ms,
                 },
                 cast: CastConfig {
                     allow_transcode_fallback: cast_allow_transcode_fallback,
+                    ..previous_config.cast.clone()
                 },
                 ui: UiConfig {
                     show_layout_edit_intro: show_layout_edit_tutorial,
@@ -513,10 +696,28 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
                     volume: previous_config.ui.volume,
                     playback_order: previous_config.ui.playback_order,
                     repeat_mode: previous_config.ui.repeat_mode,
+                    energy_ramp_arc: previous_config.ui.energy_ramp_arc,
+                    recent_import_locations: previous_config.ui.recent_import_locations.clone(),
+                    hi_res_min_sample_rate_hz: previous_config.ui.hi_res_min_sample_rate_hz,
+                    hi_res_min_bit_depth: previous_config.ui.hi_res_min_bit_depth,
+                    seek_small_step_secs: previous_config.ui.seek_small_step_secs,
+                    seek_large_step_secs: previous_config.ui.seek_large_step_secs,
+                    zero_based_row_numbers: previous_config.ui.zero_based_row_numbers,
+                    transport_bar_placement: previous_config.ui.transport_bar_placement,
+                    scale_override_percent: previous_config.ui.scale_override_percent,
+                    file_browser_root: previous_config.ui.file_browser_root.clone(),
+                    external_tagger_command: previous_config.ui.external_tagger_command.clone(),
+                    library_album_grid: previous_config.ui.library_album_grid,
+                    shortcut_keys: previous_config.ui.shortcut_keys.clone(),
                 },
                 library: previous_config.library.clone(),
                 buffering: previous_config.buffering.clone(),
                 integrations: previous_config.integrations.clone(),
+                announcements: previous_config.announcements.clone(),
+                power: previous_config.power.clone(),
+                performance: previous_config.performance.clone(),
+                confirmations: previous_config.confirmations.clone(),
+                dsp: previous_config.dsp.clone(),
             });
 
             let (workspace_width_px, workspace_height_px) =
@@ -684,6 +885,606 @@ pub(crate) fn register_settings_ui_callbacks(ui: &AppWindow, shared_state: &AppS
             })],
         )));
     });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_seek_step(move |large, choice_index| {
+        let Some(secs) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| seek_steps::SEEK_STEP_CHOICES_SECS.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            if large {
+                next.ui.seek_large_step_secs = *secs;
+            } else {
+                next.ui.seek_small_step_secs = *secs;
+            }
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_external_tagger_command(move |command| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.external_tagger_command = command.to_string();
+            crate::sanitize_config(next)
+        };
+        // Keep the field as typed; refreshing the UI would move the cursor mid-edit.
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_scale_override(move |choice_index| {
+        let Some(percent) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| window_scale::SCALE_OVERRIDE_CHOICES_PERCENT.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.scale_override_percent = *percent;
+            crate::sanitize_config(next)
+        };
+        let override_percent = next_config.ui.scale_override_percent;
+        apply_config_update(&shared_state_clone, next_config, true);
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            window_scale::apply_scale_override(ui.window(), override_percent);
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_energy_ramp_arc(move |choice_index| {
+        let arc = match choice_index {
+            0 => UiEnergyRampArc::BuildUp,
+            1 => UiEnergyRampArc::WindDown,
+            2 => UiEnergyRampArc::Peak,
+            _ => return,
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.energy_ramp_arc = arc;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_transport_bar_placement(move |choice_index| {
+        let placement = match choice_index {
+            0 => UiTransportBarPlacement::Off,
+            1 => UiTransportBarPlacement::Top,
+            2 => UiTransportBarPlacement::Bottom,
+            _ => return,
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.transport_bar_placement = placement;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_skip_silence_spoken_word(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.output.skip_silence_spoken_word = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_toggle_follow_playback(move || {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.auto_scroll_to_playing_track = !next.ui.auto_scroll_to_playing_track;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_zero_based_row_numbers(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.zero_based_row_numbers = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_library_set_album_grid(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.ui.library_album_grid = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, false);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_skip_silence_min_pause(move |choice_index| {
+        let Some(min_pause_ms) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| silence_skipping::MIN_PAUSE_CHOICES_MS.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.output.skip_silence_min_pause_ms = *min_pause_ms;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_night_mode_strength(move |choice_index| {
+        let Some(strength_percent) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| night_mode::NIGHT_MODE_STRENGTH_CHOICES_PERCENT.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.dsp.night_mode_strength_percent = *strength_percent;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_preamp(move |choice_index| {
+        let Some(preamp_db) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| dsp_chain::PREAMP_CHOICES_DB.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.dsp.preamp_db = *preamp_db;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_eq_preset(move |choice_index| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            let output_device_name = next.output.output_device_name.clone();
+            next.dsp
+                .eq_device_presets
+                .retain(|assignment| assignment.output_device_name != output_device_name);
+            // Choice 0 is "Off"; presets follow in config order.
+            let preset_name = usize::try_from(choice_index)
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| next.dsp.eq_presets.get(index))
+                .map(|preset| preset.name.clone());
+            if let Some(preset_name) = preset_name {
+                next.dsp.eq_device_presets.push(EqDevicePresetConfig {
+                    output_device_name,
+                    preset_name,
+                });
+            }
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_rate_policy(move |choice_index| {
+        // Choices follow the settings combo box order.
+        let policy = match choice_index {
+            1 => SampleRatePolicy::Pinned,
+            2 => SampleRatePolicy::AlbumBoundaries,
+            _ => SampleRatePolicy::MatchSource,
+        };
+        update_device_rate_policy(&shared_state_clone, |assignment| {
+            assignment.policy = policy;
+        });
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_pinned_rate(move |choice_index| {
+        let Some(rate_hz) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| sample_rate_grouping::PINNED_RATE_CHOICES_HZ.get(index))
+        else {
+            return;
+        };
+        update_device_rate_policy(&shared_state_clone, |assignment| {
+            assignment.pinned_sample_rate_hz = *rate_hz;
+        });
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_import_eq_profile(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("AutoEq / REW parametric EQ", &["txt"])
+            .pick_file()
+        else {
+            return;
+        };
+        let status = match eq_profile_import::import_eq_profile_file(&path) {
+            Ok(preset) => {
+                let status = format!(
+                    "Imported \"{}\" with {} filter(s)",
+                    preset.name,
+                    preset.filters.len()
+                );
+                let next_config = {
+                    let state = shared_state_clone
+                        .config_state
+                        .lock()
+                        .expect("config state lock poisoned");
+                    let mut next = state.clone();
+                    let output_device_name = next.output.output_device_name.clone();
+                    next.dsp
+                        .eq_presets
+                        .retain(|existing| existing.name != preset.name);
+                    next.dsp
+                        .eq_device_presets
+                        .retain(|assignment| assignment.output_device_name != output_device_name);
+                    next.dsp.eq_device_presets.push(EqDevicePresetConfig {
+                        output_device_name,
+                        preset_name: preset.name.clone(),
+                    });
+                    next.dsp.eq_presets.push(preset);
+                    crate::sanitize_config(next)
+                };
+                apply_config_update(&shared_state_clone, next_config, true);
+                status
+            }
+            Err(err) => {
+                log::warn!("EQ profile import failed for {}: {}", path.display(), err);
+                err
+            }
+        };
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            ui.set_settings_eq_status(status.into());
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_remove_eq_preset(move || {
+        let (next_config, removed_name) = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            let Some(preset_name) = next
+                .dsp
+                .eq_preset_name_for_device(&next.output.output_device_name)
+                .map(str::to_string)
+            else {
+                return;
+            };
+            next.dsp
+                .eq_presets
+                .retain(|preset| preset.name != preset_name);
+            (crate::sanitize_config(next), preset_name)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            ui.set_settings_eq_status(format!("Removed \"{removed_name}\"").into());
+        }
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_run_output_test(move |test_index| {
+        let Some(kind) = usize::try_from(test_index)
+            .ok()
+            .and_then(|index| output_test::OUTPUT_TEST_KINDS.get(index).copied())
+        else {
+            return;
+        };
+        let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() else {
+            return;
+        };
+        if ui.get_settings_output_test_running() {
+            return;
+        }
+        let output = {
+            let options = shared_state_clone
+                .runtime_handles
+                .output_options
+                .lock()
+                .expect("output options lock poisoned");
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            staged_output_test_config(&ui, &options, &state.output)
+        };
+        // The test opens its own stream, which exclusive-mode devices only allow once
+        // playback has released the device.
+        let _ = shared_state_clone
+            .bus_sender
+            .send(Message::Playback(PlaybackMessage::Stop));
+        ui.set_settings_output_test_running(true);
+        ui.set_settings_output_test_status(format!("Starting {}...", kind.label()).into());
+        let ui_handle = ui.as_weak();
+        std::thread::spawn(move || {
+            let progress_ui_handle = ui_handle.clone();
+            let result = output_test::run_output_test(&output, kind, |status| {
+                let status = status.to_string();
+                let _ = progress_ui_handle.upgrade_in_event_loop(move |ui| {
+                    ui.set_settings_output_test_status(status.into());
+                });
+            });
+            let status = match result {
+                Ok(summary) => summary,
+                Err(err) => {
+                    log::warn!("Output test failed: {}", err);
+                    err
+                }
+            };
+            let _ = ui_handle.upgrade_in_event_loop(move |ui| {
+                ui.set_settings_output_test_running(false);
+                ui.set_settings_output_test_status(status.into());
+            });
+        });
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_normalize_loudness(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.dsp.normalize_loudness = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_limiter_enabled(move |enabled| {
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.dsp.limiter_enabled = enabled;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_limiter_headroom(move |choice_index| {
+        let Some(headroom_db) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| dsp_chain::LIMITER_HEADROOM_CHOICES_DB.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.dsp.limiter_headroom_db = *headroom_db;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let dsp_comparison = Rc::new(RefCell::new(DspComparison::default()));
+
+    let dsp_comparison_clone = dsp_comparison.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_set_dsp_bypassed(move |bypassed| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let mut comparison = dsp_comparison_clone.borrow_mut();
+        comparison.set_bypassed(bypassed);
+        ui.set_settings_dsp_bypassed(bypassed);
+        sync_dsp_comparison(&ui, &comparison, &bus_sender_clone);
+    });
+
+    let dsp_comparison_clone = dsp_comparison.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_start_dsp_blind_test(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let mut comparison = dsp_comparison_clone.borrow_mut();
+        comparison.start_blind_test(usize::from(rand::random::<bool>()));
+        ui.set_settings_dsp_compare_status("".into());
+        sync_dsp_comparison(&ui, &comparison, &bus_sender_clone);
+    });
+
+    let dsp_comparison_clone = dsp_comparison.clone();
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_select_dsp_blind_choice(move |choice| {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let mut comparison = dsp_comparison_clone.borrow_mut();
+        comparison.select_blind_choice(usize::try_from(choice).unwrap_or(0));
+        sync_dsp_comparison(&ui, &comparison, &bus_sender_clone);
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    ui.on_settings_reveal_dsp_blind_test(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+        let mut comparison = dsp_comparison.borrow_mut();
+        if let Some(result) = comparison.reveal_blind_test() {
+            ui.set_settings_dsp_compare_status(result.into());
+        }
+        sync_dsp_comparison(&ui, &comparison, &bus_sender_clone);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_decode_threads(move |choice_index| {
+        let Some(threads) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| worker_pool::THREAD_COUNT_CHOICES.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.performance.decode_threads = *threads;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_analysis_threads(move |choice_index| {
+        let Some(threads) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| worker_pool::THREAD_COUNT_CHOICES.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.performance.analysis_threads = *threads;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_worker_priority(move |choice_index| {
+        let Some(priority) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| worker_pool::PRIORITY_CHOICES.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.performance.worker_priority = *priority;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_set_rendering_backend(move |choice_index| {
+        let Some(backend) = usize::try_from(choice_index)
+            .ok()
+            .and_then(|index| crate::rendering_backend::RENDERING_BACKEND_CHOICES.get(index))
+        else {
+            return;
+        };
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            next.performance.rendering_backend = *backend;
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config, true);
+    });
+
+    ui.on_settings_set_fault_injection(move |fault_index, enabled| {
+        let Some(fault) = usize::try_from(fault_index)
+            .ok()
+            .and_then(|index| fault_injection::ALL_FAULTS.get(index))
+        else {
+            return;
+        };
+        fault_injection::set_fault_enabled(*fault, enabled);
+        log::warn!(
+            "Fault injection: {} {}",
+            fault.label(),
+            if enabled { "enabled" } else { "disabled" }
+        );
+    });
 }
 
 #[cfg(test)]
@@ -0,0 +1,59 @@
+//! Callback registration for Spectrogram panels.
+
+use std::{cell::RefCell, rc::Rc};
+
+use slint::{ComponentHandle, Rgba8Pixel, SharedPixelBuffer};
+
+use crate::{
+    spectrogram::{self, LiveSpectrogram, SpectrogramImage},
+    AppWindow,
+};
+
+/// Columns of live history; about ten seconds at 48 kHz.
+const LIVE_SPECTROGRAM_WIDTH: u32 = 480;
+const LIVE_SPECTROGRAM_HEIGHT: u32 = 256;
+
+/// Converts rendered pixels into an image Slint can show.
+pub(crate) fn spectrogram_to_image(image: &SpectrogramImage) -> slint::Image {
+    slint::Image::from_rgba8(SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+        &image.rgba,
+        image.width,
+        image.height,
+    ))
+}
+
+/// Registers the timer callback that scrolls visible Spectrogram panels.
+pub(crate) fn register_spectrogram_callbacks(ui: &AppWindow) {
+    let live = Rc::new(RefCell::new(LiveSpectrogram::new(
+        LIVE_SPECTROGRAM_WIDTH,
+        LIVE_SPECTROGRAM_HEIGHT,
+    )));
+    let samples = Rc::new(RefCell::new(Vec::new()));
+    let shown_sample_rate_hz = Rc::new(RefCell::new(0u32));
+    let ui_handle = ui.as_weak();
+    ui.on_spectrogram_tick(move || {
+        let Some(ui) = ui_handle.upgrade() else {
+            return;
+        };
+        let tap = spectrogram::live_tap();
+        let sample_rate_hz = tap.sample_rate_hz();
+        if *shown_sample_rate_hz.borrow() != sample_rate_hz {
+            *shown_sample_rate_hz.borrow_mut() = sample_rate_hz;
+            ui.set_spectrogram_caption(
+                format!(
+                    "Output · {}",
+                    spectrogram::frequency_range_label(sample_rate_hz)
+                )
+                .into(),
+            );
+        }
+
+        let mut samples = samples.borrow_mut();
+        samples.clear();
+        tap.drain_into(&mut samples);
+        let mut live = live.borrow_mut();
+        if live.feed(&samples) {
+            ui.set_spectrogram_image(spectrogram_to_image(live.image()));
+        }
+    });
+}
@@ -0,0 +1,161 @@
+//! Callback registration for WebDAV share settings.
+
+use log::warn;
+
+use crate::{
+    app_config_coordinator::apply_config_update,
+    app_context::AppSharedState,
+    integration_keyring::set_webdav_password,
+    protocol::{self, IntegrationMessage, Message},
+    webdav_controller::{
+        find_webdav_backend, resolve_webdav_password, upsert_webdav_backend_config,
+        webdav_profile_snapshot, WEBDAV_PROFILE_ID,
+    },
+    AppWindow,
+};
+
+/// Registers settings callbacks for saving/testing/syncing the WebDAV share profile.
+pub(crate) fn register_webdav_settings_callbacks(ui: &AppWindow, shared_state: &AppSharedState) {
+    let shared_state_clone = shared_state.clone();
+    ui.on_settings_save_webdav_profile(move |enabled, endpoint, username, password| {
+        let password_trimmed = password.trim().to_string();
+
+        let mut status_message = "WebDAV share saved".to_string();
+        if !password_trimmed.is_empty() {
+            shared_state_clone
+                .opensubsonic_session_passwords
+                .lock()
+                .expect("session password cache lock poisoned")
+                .insert(WEBDAV_PROFILE_ID.to_string(), password_trimmed.clone());
+            if let Err(error) = set_webdav_password(WEBDAV_PROFILE_ID, password_trimmed.as_str()) {
+                warn!(
+                    "Failed to save WebDAV credential for profile '{}': {}",
+                    WEBDAV_PROFILE_ID, error
+                );
+                status_message =
+                    "System keyring unavailable; password cached for this session only".to_string();
+            }
+        }
+
+        let next_config = {
+            let state = shared_state_clone
+                .config_state
+                .lock()
+                .expect("config state lock poisoned");
+            let mut next = state.clone();
+            upsert_webdav_backend_config(&mut next, endpoint.as_str(), username.as_str(), enabled);
+            crate::sanitize_config(next)
+        };
+        apply_config_update(&shared_state_clone, next_config.clone(), true);
+
+        let password_for_upsert = if password_trimmed.is_empty() {
+            resolve_webdav_password(
+                WEBDAV_PROFILE_ID,
+                &shared_state_clone.opensubsonic_session_passwords,
+            )
+        } else {
+            Some(password_trimmed)
+        };
+
+        if let Some(backend) = find_webdav_backend(&next_config) {
+            let snapshot = webdav_profile_snapshot(backend, Some(status_message.clone()));
+            let connect_now = enabled && password_for_upsert.is_some();
+            let _ = shared_state_clone.bus_sender.send(Message::Integration(
+                IntegrationMessage::UpsertBackendProfile {
+                    profile: snapshot,
+                    password: password_for_upsert,
+                    connect_now,
+                },
+            ));
+            if !enabled {
+                let _ = shared_state_clone.bus_sender.send(Message::Integration(
+                    IntegrationMessage::DisconnectBackendProfile {
+                        profile_id: WEBDAV_PROFILE_ID.to_string(),
+                    },
+                ));
+            }
+        }
+
+        if let Some(ui) = shared_state_clone.ui_handles.ui_handle.upgrade() {
+            ui.set_settings_webdav_status(status_message.into());
+            ui.set_settings_webdav_password("".into());
+        }
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let ui_handle_clone = shared_state.ui_handles.ui_handle.clone();
+    let session_passwords_clone = shared_state.opensubsonic_session_passwords.clone();
+    ui.on_settings_test_webdav_connection(move || {
+        let Some(ui) = ui_handle_clone.upgrade() else {
+            return;
+        };
+
+        let endpoint_trimmed = ui
+            .get_settings_webdav_endpoint()
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        let username_trimmed = ui.get_settings_webdav_username().trim().to_string();
+        let password_trimmed = ui.get_settings_webdav_password().trim().to_string();
+        if endpoint_trimmed.is_empty() || username_trimmed.is_empty() {
+            ui.set_settings_webdav_status("Enter share URL and username first".into());
+            return;
+        }
+
+        let password_for_upsert = if password_trimmed.is_empty() {
+            resolve_webdav_password(WEBDAV_PROFILE_ID, &session_passwords_clone)
+        } else {
+            session_passwords_clone
+                .lock()
+                .expect("session password cache lock poisoned")
+                .insert(WEBDAV_PROFILE_ID.to_string(), password_trimmed.clone());
+            Some(password_trimmed)
+        };
+        let profile = protocol::BackendProfileSnapshot {
+            profile_id: WEBDAV_PROFILE_ID.to_string(),
+            backend_kind: protocol::BackendKind::WebDav,
+            display_name: "WebDAV".to_string(),
+            endpoint: endpoint_trimmed,
+            username: username_trimmed,
+            configured: true,
+            connection_state: protocol::BackendConnectionState::Disconnected,
+            status_text: Some("Testing connection...".to_string()),
+        };
+        let _ = bus_sender_clone.send(Message::Integration(
+            IntegrationMessage::UpsertBackendProfile {
+                profile,
+                password: password_for_upsert,
+                connect_now: false,
+            },
+        ));
+        let _ = bus_sender_clone.send(Message::Integration(
+            IntegrationMessage::TestBackendConnection {
+                profile_id: WEBDAV_PROFILE_ID.to_string(),
+            },
+        ));
+        ui.set_settings_webdav_status("Testing connection...".into());
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    ui.on_settings_sync_webdav_now(move || {
+        let _ = bus_sender_clone.send(Message::Integration(
+            IntegrationMessage::SyncBackendProfile {
+                profile_id: WEBDAV_PROFILE_ID.to_string(),
+            },
+        ));
+    });
+
+    let bus_sender_clone = shared_state.bus_sender.clone();
+    let session_passwords_clone = shared_state.opensubsonic_session_passwords.clone();
+    ui.on_settings_disconnect_webdav(move || {
+        session_passwords_clone
+            .lock()
+            .expect("session password cache lock poisoned")
+            .remove(WEBDAV_PROFILE_ID);
+        let _ = bus_sender_clone.send(Message::Integration(
+            IntegrationMessage::DisconnectBackendProfile {
+                profile_id: WEBDAV_PROFILE_ID.to_string(),
+            },
+        ));
+    });
+}
@@ -43,6 +43,7 @@ pub(crate) fn apply_ui_from_state(shared: &AppSharedState) {
 
 /// Publishes runtime-facing config updates derived from persisted state.
 pub(crate) fn publish_runtime_from_state(shared: &AppSharedState, next_config: &Config) {
+    crate::worker_pool::apply_performance_config(&next_config.performance);
     let options_snapshot = output_options_snapshot(shared);
     let runtime_override =
         runtime_output_override_snapshot(&shared.runtime_handles.runtime_output_override);
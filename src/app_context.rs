@@ -15,6 +15,7 @@ use crate::{
     runtime_config::{
         OutputRuntimeSignature, RuntimeAudioState, RuntimeOutputOverride, StagedAudioSettings,
     },
+    ui::confirmation_policy::ConfirmationRequest,
     AppWindow, OutputSettingsOptions,
 };
 
@@ -74,4 +75,6 @@ pub(crate) struct AppSharedState {
     pub(crate) layout_redo_stack: Arc<Mutex<Vec<LayoutConfig>>>,
     /// Producer for bulk playlist import requests.
     pub(crate) playlist_bulk_import_tx: SyncSender<protocol::PlaylistBulkImportRequest>,
+    /// Destructive action waiting for the shared confirmation dialog.
+    pub(crate) pending_confirmation: Arc<Mutex<Option<ConfirmationRequest>>>,
 }
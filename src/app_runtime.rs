@@ -1,14 +1,17 @@
 //! Application runtime bootstrap and top-level orchestration.
 
 use std::{
+    cell::Cell,
     collections::HashMap,
     path::PathBuf,
+    process::Command,
     rc::Rc,
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc, Arc, Mutex,
     },
     thread,
+    time::Instant,
 };
 
 use log::{info, warn};
@@ -24,22 +27,32 @@ use crate::{
     audio_runtime_reactor::{spawn_runtime_event_reactor, RuntimeEventReactorContext},
     config::Config,
     config_persistence::{
-        hydrate_ui_columns_from_layout, load_layout_file, persist_state_files,
-        system_layout_template_text,
+        apply_legacy_dark_mode, hydrate_ui_columns_from_layout, load_layout_file,
+        load_system_layout_template, persist_state_files, system_layout_template_text,
     },
+    db_manager::DbManager,
+    db_recovery::{self, DatabaseHealth, DatabaseRepair},
+    migration_assistant::{self, MigrationPaths},
     opensubsonic_controller::{
         find_opensubsonic_backend, keyring_unavailable_error, opensubsonic_profile_snapshot,
         resolve_opensubsonic_password, OpenSubsonicPasswordResolution, OPENSUBSONIC_PROFILE_ID,
     },
     output_option_selection::bootstrap_output_settings_options,
+    profile_share::ProfileShare,
     protocol::{
-        CastMessage, ConfigMessage, IntegrationMessage, Message, PlaybackMessage, PlaylistMessage,
+        CastMessage, ConfigMessage, IntegrationMessage, LibraryMessage, Message, PlaybackMessage,
+        PlaylistMessage,
     },
+    rendering_backend,
     runtime_config::{
         OutputRuntimeSignature, RuntimeAudioState, RuntimeOutputOverride, StagedAudioSettings,
     },
+    session_journal::{self, SAFE_MODE_FLAG, SESSION_JOURNAL_FILE_NAME},
     setup_app_state_associations, sidebar_width_from_window,
     ui_manager::UiState,
+    webdav_controller::{
+        find_webdav_backend, resolve_webdav_password, webdav_profile_snapshot, WEBDAV_PROFILE_ID,
+    },
     AppWindow,
 };
 
@@ -49,32 +62,55 @@ pub(crate) struct AppRuntime {
     config_state: Arc<Mutex<Config>>,
     config_file: PathBuf,
     layout_file: PathBuf,
+    session_journal_file: PathBuf,
+    restart_in_safe_mode: Rc<Cell<bool>>,
+    pending_database_repair: Rc<Cell<Option<DatabaseRepair>>>,
 }
 
 impl AppRuntime {
     /// Builds the runtime by loading config/layout state and wiring all services/callbacks.
-    pub(crate) fn build() -> Result<Self, Box<dyn std::error::Error>> {
-        let configured_backend = std::env::var("SLINT_BACKEND").unwrap_or_else(|_| {
-            info!("SLINT_BACKEND not set. Defaulting to winit-software");
-            "winit-software".to_string()
-        });
+    ///
+    /// `safe_mode` starts with the system layout, software rendering, and without
+    /// OpenSubsonic or Cast. `database_repair` is run on the database before anything
+    /// opens it. `profile_share` comes from a share link the app was opened with and
+    /// pre-fills the matching profile form in Settings.
+    ///
+    /// Returns `None` when the user quits the migration assistant instead of converting
+    /// data from an older version.
+    pub(crate) fn build(
+        safe_mode: bool,
+        database_repair: Option<DatabaseRepair>,
+        profile_share: Option<ProfileShare>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let config_root = dirs::config_dir().unwrap().join("roqtune");
+        let config_file = config_root.join("config.toml");
+        let layout_file = config_root.join("layout.toml");
+
         #[cfg(target_os = "windows")]
         info!("Windows build: Slint accessibility feature is disabled");
-        let backend_selector =
-            slint::BackendSelector::new().backend_name(configured_backend.clone());
-        backend_selector
-            .select()
-            .map_err(|err| format!("Failed to initialize Slint backend: {}", err))?;
+        let rendering_selection = rendering_backend::select_backend(
+            rendering_backend::configured_rendering_backend(&config_file),
+            safe_mode,
+        )?;
+
+        let database_path = DbManager::database_path();
+        let migration_paths = MigrationPaths {
+            config_file: config_file.clone(),
+            layout_file: layout_file.clone(),
+            database_path: database_path.clone(),
+        };
+        if !migration_assistant::run_assistant(&migration_paths)? {
+            info!("Migration declined; exiting without changing any files");
+            return Ok(None);
+        }
 
         let ui = AppWindow::new()?;
+        crate::startup_timing::mark("window created");
+        ui.set_settings_rendering_diagnostics(rendering_selection.diagnostics_summary().into());
         let ui_state = UiState {
             track_model: Rc::new(VecModel::from(vec![])),
         };
 
-        let config_root = dirs::config_dir().unwrap().join("roqtune");
-        let config_file = config_root.join("config.toml");
-        let layout_file = config_root.join("layout.toml");
-
         if let Err(err) = std::fs::create_dir_all(&config_root) {
             return Err(format!(
                 "Failed to create config directory {}: {}",
@@ -84,6 +120,23 @@ impl AppRuntime {
             .into());
         }
 
+        let session_journal_file = config_root.join(SESSION_JOURNAL_FILE_NAME);
+        let previous_session = session_journal::begin_session(&session_journal_file);
+        if previous_session.crashed {
+            warn!("Previous session did not shut down cleanly");
+        }
+
+        let database_repair_result =
+            database_repair.map(|repair| db_recovery::repair_database(&database_path, repair));
+        let database_health = db_recovery::check_database(&database_path);
+        match &database_health {
+            DatabaseHealth::Healthy => db_recovery::spawn_daily_backup(database_path.clone()),
+            DatabaseHealth::Damaged { problem } => {
+                warn!("Library database failed its integrity check: {}", problem)
+            }
+            DatabaseHealth::Missing => {}
+        }
+
         if !config_file.exists() {
             let default_config = crate::sanitize_config(Config::default());
             info!(
@@ -109,16 +162,19 @@ impl AppRuntime {
         let mut config =
             crate::sanitize_config(toml::from_str::<Config>(&config_content).unwrap_or_default());
         let layout_text = std::fs::read_to_string(&layout_file).ok();
-        let mut layout = load_layout_file(&layout_file);
-        let has_explicit_color_scheme = layout_text
-            .as_deref()
-            .is_some_and(|text| text.contains("color_scheme"));
-        if !has_explicit_color_scheme && config.ui.legacy_dark_mode == Some(false) {
-            layout.color_scheme = crate::theme::ROQTUNE_LIGHT_SCHEME_ID.to_string();
-        }
+        let mut safe_mode_layout_backup = None;
+        let mut layout = if safe_mode {
+            info!("Safe mode: starting with the system layout");
+            safe_mode_layout_backup = session_journal::back_up_layout_for_safe_mode(&layout_file);
+            load_system_layout_template()
+        } else {
+            load_layout_file(&layout_file)
+        };
+        apply_legacy_dark_mode(&config, layout_text.as_deref(), &mut layout);
         config.ui.layout = layout;
         hydrate_ui_columns_from_layout(&mut config);
         let config = crate::sanitize_config(config);
+        crate::startup_timing::mark("config and layout loaded");
         // Use a config-only snapshot so startup never blocks on hardware probe.
         // Full device capability probing is refreshed asynchronously at runtime.
         let initial_output_options = bootstrap_output_settings_options(&config);
@@ -139,6 +195,7 @@ impl AppRuntime {
             runtime_config.library.cover_art_cache_max_size_mb,
             runtime_config.library.artist_image_cache_max_size_mb,
         );
+        crate::cache_manager::configure_cache_dirs(&runtime_config.library);
         let runtime_audio_state = Arc::new(Mutex::new(RuntimeAudioState {
             output: config.output.clone(),
             cast: config.cast.clone(),
@@ -179,11 +236,38 @@ impl AppRuntime {
         )));
 
         let (bus_sender, _) = broadcast::channel(8192);
+        session_journal::spawn_session_journal(
+            session_journal_file.clone(),
+            bus_sender.subscribe(),
+        );
+        crate::metrics::start_exporter_from_env(bus_sender.clone());
+        if crate::metrics::is_enabled() {
+            let mut frame_started: Option<Instant> = None;
+            let notifier_result = ui
+                .window()
+                .set_rendering_notifier(move |state, _| match state {
+                    slint::RenderingState::BeforeRendering => frame_started = Some(Instant::now()),
+                    slint::RenderingState::AfterRendering => {
+                        if let Some(started) = frame_started.take() {
+                            crate::metrics::observe_duration(
+                                crate::metrics::UI_FRAME_RENDER_SECONDS,
+                                &[],
+                                started.elapsed(),
+                            );
+                        }
+                    }
+                    _ => {}
+                });
+            if let Err(err) = notifier_result {
+                warn!("Metrics: UI frame timing unavailable: {err}");
+            }
+        }
         let playback_session_active = Arc::new(AtomicBool::new(false));
         let staged_audio_settings: Arc<Mutex<Option<StagedAudioSettings>>> =
             Arc::new(Mutex::new(None));
         let opensubsonic_session_passwords: Arc<Mutex<HashMap<String, String>>> =
             Arc::new(Mutex::new(HashMap::new()));
+        let pending_confirmation = Arc::new(Mutex::new(None));
         let (playlist_bulk_import_tx, playlist_bulk_import_rx) =
             mpsc::sync_channel::<crate::protocol::PlaylistBulkImportRequest>(64);
         let (library_scan_progress_tx, library_scan_progress_rx) =
@@ -211,6 +295,7 @@ impl AppRuntime {
             layout_undo_stack: Arc::clone(&layout_undo_stack),
             layout_redo_stack: Arc::clone(&layout_redo_stack),
             playlist_bulk_import_tx: playlist_bulk_import_tx.clone(),
+            pending_confirmation: Arc::clone(&pending_confirmation),
         };
 
         {
@@ -261,6 +346,10 @@ impl AppRuntime {
             &ui,
             &shared_state,
         );
+        crate::app_callbacks::webdav_settings::register_webdav_settings_callbacks(
+            &ui,
+            &shared_state,
+        );
         crate::app_callbacks::playlist_editing::register_playlist_editing_callbacks(
             &ui,
             &shared_state,
@@ -271,6 +360,21 @@ impl AppRuntime {
         );
         crate::app_callbacks::layout_editor::register_layout_editor_callbacks(&ui, &shared_state);
         crate::app_callbacks::settings_ui::register_settings_ui_callbacks(&ui, &shared_state);
+        crate::app_callbacks::confirmations::register_confirmation_callbacks(&ui, &shared_state);
+        crate::app_callbacks::preset_bundles::register_preset_bundle_callbacks(&ui, &shared_state);
+        crate::app_callbacks::file_browser::register_file_browser_callbacks(&ui, &shared_state);
+        crate::app_callbacks::spectrogram::register_spectrogram_callbacks(&ui);
+        crate::app_callbacks::profile_share::register_profile_share_callbacks(&ui);
+        if config.ui.scale_override_percent != 0 {
+            // The winit window only exists once the event loop has shown it.
+            let ui_handle = ui.as_weak();
+            let override_percent = config.ui.scale_override_percent;
+            slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+                if let Some(ui) = ui_handle.upgrade() {
+                    crate::ui::window_scale::apply_scale_override(ui.window(), override_percent);
+                }
+            });
+        }
 
         let mut startup_subsonic_session_prompt: Option<(String, String, String)> = None;
         let startup_opensubsonic_seed = find_opensubsonic_backend(&config).map(|backend| {
@@ -318,9 +422,24 @@ impl AppRuntime {
                 }
             };
             let snapshot = opensubsonic_profile_snapshot(backend, status_text);
-            let connect_now = backend.enabled && password.is_some();
+            let connect_now = backend.enabled && password.is_some() && !safe_mode;
+            (snapshot, password, connect_now)
+        });
+        let startup_webdav_seed = find_webdav_backend(&config).map(|backend| {
+            let password =
+                resolve_webdav_password(WEBDAV_PROFILE_ID, &opensubsonic_session_passwords);
+            let status_text = if password.is_some() {
+                "Restored from credential store"
+            } else if backend.enabled {
+                "Missing saved password"
+            } else {
+                "Restored from config"
+            };
+            let snapshot = webdav_profile_snapshot(backend, Some(status_text.to_string()));
+            let connect_now = backend.enabled && password.is_some() && !safe_mode;
             (snapshot, password, connect_now)
         });
+        crate::worker_pool::apply_performance_config(&runtime_config.performance);
         spawn_background_services(BackgroundServicesConfig {
             bus_sender: bus_sender.clone(),
             ui_handle: ui.as_weak().clone(),
@@ -329,12 +448,18 @@ impl AppRuntime {
             initial_ui_config: runtime_config.ui.clone(),
             initial_library_config: runtime_config.library.clone(),
             initial_buffering_config: runtime_config.buffering.clone(),
+            initial_announcement_config: runtime_config.announcements.clone(),
+            initial_power_config: runtime_config.power.clone(),
+            initial_dsp_config: runtime_config.dsp.clone(),
+            initial_mock_backend_config: runtime_config.integrations.mock_backend.clone(),
             playlist_bulk_import_rx,
             library_scan_progress_tx,
             library_scan_progress_rx,
             startup_opensubsonic_seed,
+            startup_webdav_seed,
         });
 
+        crate::startup_timing::mark("background services spawned");
         let _ = bus_sender.send(Message::Integration(IntegrationMessage::RequestSnapshot));
 
         spawn_runtime_event_reactor(RuntimeEventReactorContext {
@@ -350,6 +475,7 @@ impl AppRuntime {
             last_runtime_signature: Arc::clone(&last_runtime_signature),
             last_runtime_config: Arc::clone(&last_runtime_config),
             playback_session_active: Arc::clone(&playback_session_active),
+            pending_confirmation: Arc::clone(&pending_confirmation),
         });
 
         // Playlist columns are global layout state from `layout.toml`; startup must not request
@@ -362,26 +488,103 @@ impl AppRuntime {
         let _ = bus_sender.send(Message::Playback(PlaybackMessage::SetVolume(
             config.ui.volume,
         )));
-        let _ = bus_sender.send(Message::Cast(CastMessage::DiscoverDevices));
+        if !safe_mode {
+            let _ = bus_sender.send(Message::Cast(CastMessage::DiscoverDevices));
+        }
+        if let Some(playback) = previous_session.playback {
+            info!(
+                "Resuming track {} from the crashed session",
+                playback.track_id
+            );
+            let _ = bus_sender.send(Message::Playback(PlaybackMessage::ResumeJournaledTrack {
+                playlist_id: playback.playlist_id,
+                track_id: playback.track_id,
+                elapsed_ms: playback.elapsed_ms,
+            }));
+        }
+
+        match database_repair_result {
+            Some(Ok(report)) => {
+                if report.repair == DatabaseRepair::RebuildFromFiles {
+                    let _ = bus_sender.send(Message::Library(LibraryMessage::RequestScan));
+                }
+                ui.set_database_repair_notice_message(report.message.into());
+                ui.set_show_database_repair_notice(true);
+            }
+            Some(Err(err)) => {
+                ui.set_database_repair_notice_message(
+                    format!("The library database could not be repaired: {err}").into(),
+                );
+                ui.set_show_database_repair_notice(true);
+            }
+            None => {}
+        }
+        let pending_database_repair = Rc::new(Cell::new(None));
+        if let DatabaseHealth::Damaged { problem } = &database_health {
+            let backup_available = !db_recovery::list_backups(&database_path).is_empty();
+            ui.set_database_repair_prompt_details(problem.as_str().into());
+            ui.set_database_repair_backup_available(backup_available);
+            ui.set_show_database_repair_prompt(true);
+        }
+        let pending_database_repair_clone = Rc::clone(&pending_database_repair);
+        ui.on_database_repair_chosen(move |restore_backup| {
+            pending_database_repair_clone.set(Some(if restore_backup {
+                DatabaseRepair::RestoreBackup
+            } else {
+                DatabaseRepair::RebuildFromFiles
+            }));
+            let _ = slint::quit_event_loop();
+        });
+
+        let restart_in_safe_mode = Rc::new(Cell::new(false));
+        if safe_mode {
+            let backup_text = safe_mode_layout_backup.map_or_else(String::new, |path| {
+                format!(" Your layout was saved to {}.", path.display())
+            });
+            ui.set_safe_mode_notice_message(
+                format!(
+                    "roqtune started with the default layout. OpenSubsonic and Cast stay offline \
+                     until the next normal start.{backup_text}"
+                )
+                .into(),
+            );
+            ui.set_show_safe_mode_notice(true);
+        } else if previous_session.crashed {
+            ui.set_show_crash_recovery_prompt(true);
+        }
+        let restart_in_safe_mode_clone = Rc::clone(&restart_in_safe_mode);
+        ui.on_crash_recovery_restart_in_safe_mode(move || {
+            restart_in_safe_mode_clone.set(true);
+            let _ = slint::quit_event_loop();
+        });
 
-        if let Some((username, endpoint, status)) = startup_subsonic_session_prompt {
+        if let Some((username, endpoint, status)) =
+            startup_subsonic_session_prompt.filter(|_| !safe_mode)
+        {
             ui.set_subsonic_session_prompt_username(username.into());
             ui.set_subsonic_session_prompt_endpoint(endpoint.into());
             ui.set_subsonic_session_prompt_password("".into());
             ui.set_subsonic_session_prompt_status(status.into());
             ui.set_show_subsonic_session_password_prompt(true);
         }
+        if let Some(share) = &profile_share {
+            crate::app_callbacks::profile_share::apply_profile_share_to_ui(&ui, share);
+        }
 
-        Ok(Self {
+        Ok(Some(Self {
             ui,
             config_state,
             config_file,
             layout_file,
-        })
+            session_journal_file,
+            restart_in_safe_mode,
+            pending_database_repair,
+        }))
     }
 
     /// Starts the UI event loop after all runtime services are registered.
     pub(crate) fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::startup_timing::mark("entering UI event loop");
         self.ui.run()?;
 
         let final_config = {
@@ -393,6 +596,26 @@ impl AppRuntime {
         };
         persist_state_files(&final_config, &self.config_file, &self.layout_file);
 
+        if let Some(repair) = self.pending_database_repair.get() {
+            info!("Restarting to repair the library database");
+            session_journal::end_session(&self.session_journal_file);
+            let spawn_result = std::env::current_exe()
+                .and_then(|exe| Command::new(exe).arg(repair.to_arg()).spawn());
+            if let Err(err) = spawn_result {
+                warn!("Failed to restart for the database repair: {}", err);
+            }
+        } else if self.restart_in_safe_mode.get() {
+            // The journal stays marked as crashed so the safe-mode run still resumes playback.
+            info!("Restarting in safe mode");
+            let spawn_result = std::env::current_exe()
+                .and_then(|exe| Command::new(exe).arg(SAFE_MODE_FLAG).spawn());
+            if let Err(err) = spawn_result {
+                warn!("Failed to restart in safe mode: {}", err);
+            }
+        } else {
+            session_journal::end_session(&self.session_journal_file);
+        }
+
         info!("Application exiting");
         Ok(())
     }
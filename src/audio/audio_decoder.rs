@@ -4,16 +4,28 @@
 //! decode worker thread that performs file decode, optional seek, resampling,
 //! and packet emission.
 
+use crate::backends::webdav;
 use crate::config::{BufferingConfig, OutputConfig, ResamplerQuality};
-use crate::integration_uri::{parse_opensubsonic_track_uri, OpenSubsonicTrackLocator};
+use crate::fault_injection::{self, Fault};
+use crate::http_range_source::HttpRangeSource;
+use crate::integration_uri::{
+    is_remote_track_path, parse_opensubsonic_track_uri, parse_webdav_track_uri,
+    OpenSubsonicTrackLocator,
+};
+use crate::metadata_tags;
+use crate::metrics;
+use crate::playlist_url::is_web_track_path;
 use crate::protocol::{
     self, AudioMessage, AudioPacket, ConfigMessage, IntegrationMessage, Message, PlaybackMessage,
     PlaylistMessage, TrackIdentifier,
 };
+use crate::sandbox_access;
+use crate::silence_skipping::{self, SilenceSkipper};
 use audio_mixer::{Channel as MixChannel, Mixer};
 use log::{debug, error, warn};
 use rubato::{
-    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+    FastFixedIn, PolynomialDegree, ResampleError, Resampler, SincFixedIn,
+    SincInterpolationParameters, SincInterpolationType, WindowFunction,
 };
 use std::cmp::min;
 use std::collections::{HashMap, VecDeque};
@@ -22,12 +34,12 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use symphonia::core::audio::SampleBuffer;
 use symphonia::core::codecs::{CodecParameters, Decoder, DecoderOptions};
 use symphonia::core::errors::Error;
 use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
-use symphonia::core::io::MediaSourceStream;
+use symphonia::core::io::{MediaSourceStream, ReadOnlySource};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use tokio::sync::broadcast::{Receiver, Sender};
@@ -61,11 +73,11 @@ enum DecodeWorkItem {
     AudioDeviceOpened {
         stream_info: protocol::OutputStreamInfo,
     },
-    UpsertOpenSubsonicPassword {
+    UpsertBackendPassword {
         profile_id: String,
         password: String,
     },
-    RemoveOpenSubsonicPassword {
+    RemoveBackendPassword {
         profile_id: String,
     },
 }
@@ -79,9 +91,50 @@ struct ActiveDecodeTrack {
     decoder: Box<dyn Decoder>,
     source_sample_rate: u32,
     source_channels: u16,
+    /// Packet timestamp where a per-track end trim stops decoding.
+    trim_end_ts: Option<u64>,
+    /// Cuts long pauses when skip-silence playback applies to this track.
+    silence_skipper: Option<SilenceSkipper>,
     input_exhausted: bool,
     consecutive_decode_errors: u32,
     consecutive_packet_read_errors: u32,
+    /// Time spent inside the codec, reported to metrics when the track finishes.
+    decode_time: Duration,
+}
+
+/// Sample-rate converter picked by the configured resampler quality.
+enum QualityResampler {
+    /// Linear interpolation; cheapest, audible aliasing on large ratio changes.
+    Linear(FastFixedIn<f32>),
+    /// Band-limited sinc interpolation.
+    Sinc(SincFixedIn<f32>),
+}
+
+impl QualityResampler {
+    fn input_frames_next(&self) -> usize {
+        match self {
+            Self::Linear(resampler) => resampler.input_frames_next(),
+            Self::Sinc(resampler) => resampler.input_frames_next(),
+        }
+    }
+
+    fn process(&mut self, wave_in: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, ResampleError> {
+        match self {
+            Self::Linear(resampler) => resampler.process(wave_in, None),
+            Self::Sinc(resampler) => resampler.process(wave_in, None),
+        }
+    }
+
+    /// Resamples a short final chunk, or flushes buffered output when `wave_in` is `None`.
+    fn process_partial(
+        &mut self,
+        wave_in: Option<&[Vec<f32>]>,
+    ) -> Result<Vec<Vec<f32>>, ResampleError> {
+        match self {
+            Self::Linear(resampler) => resampler.process_partial(wave_in, None),
+            Self::Sinc(resampler) => resampler.process_partial(wave_in, None),
+        }
+    }
 }
 
 /// Single-threaded decode worker that owns decoder/resampler mutable state.
@@ -92,7 +145,7 @@ struct DecodeWorker {
     work_queue: VecDeque<DecodeWorkItem>,
     pending_tracks: VecDeque<TrackIdentifier>,
     active_track: Option<ActiveDecodeTrack>,
-    resampler: Option<SincFixedIn<f32>>,
+    resampler: Option<QualityResampler>,
     resampler_flushed: bool,
     resample_buffer: VecDeque<f32>,
     downmix_mixers: HashMap<(usize, usize), Mixer<f32>>,
@@ -102,9 +155,13 @@ struct DecodeWorker {
     resampler_quality: ResamplerQuality,
     dither_on_bitdepth_reduce: bool,
     downmix_higher_channel_tracks: bool,
+    skip_hidden_pregap: bool,
+    skip_silence_spoken_word: bool,
+    skip_silence_min_pause_ms: u32,
     decoder_request_chunk_ms: u32,
     decode_generation: u64,
-    opensubsonic_passwords: HashMap<String, String>,
+    /// Cached passwords of OpenSubsonic and WebDAV profiles, keyed by profile id.
+    backend_passwords: HashMap<String, String>,
 }
 
 impl DecodeWorker {
@@ -133,9 +190,12 @@ impl DecodeWorker {
             resampler_quality: ResamplerQuality::High,
             dither_on_bitdepth_reduce: true,
             downmix_higher_channel_tracks: true,
+            skip_hidden_pregap: false,
+            skip_silence_spoken_word: false,
+            skip_silence_min_pause_ms: OutputConfig::default().skip_silence_min_pause_ms,
             decoder_request_chunk_ms: BufferingConfig::default().decoder_request_chunk_ms,
             decode_generation: 0,
-            opensubsonic_passwords: HashMap::new(),
+            backend_passwords: HashMap::new(),
         };
         worker.apply_decode_config(
             Some(&initial_output_config),
@@ -223,6 +283,36 @@ impl DecodeWorker {
         Ok((body, hint_extension))
     }
 
+    /// Opens an http(s) entry from an imported playlist URL as a forward-only stream, so
+    /// live radio streams start without waiting for a (never-ending) download.
+    fn open_web_media_source_stream(
+        track: &TrackIdentifier,
+        hint: &mut Hint,
+    ) -> Result<MediaSourceStream, String> {
+        let url = track.path.to_string_lossy().to_string();
+        let client = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(5))
+            .timeout_read(Duration::from_secs(45))
+            .build();
+        let response = client
+            .get(url.as_str())
+            .call()
+            .map_err(|error| format!("Web stream request failed: {error}"))?;
+        if let Some(extension) = response
+            .header("Content-Type")
+            .and_then(Self::extension_from_content_type)
+        {
+            hint.with_extension(extension);
+        } else if let Some(extension) = track.path.extension().and_then(|ext| ext.to_str()) {
+            let extension = extension.split(['?', '#']).next().unwrap_or(extension);
+            hint.with_extension(extension);
+        }
+        Ok(MediaSourceStream::new(
+            Box::new(ReadOnlySource::new(response.into_reader())),
+            Default::default(),
+        ))
+    }
+
     fn open_media_source_stream(
         &self,
         track: &TrackIdentifier,
@@ -243,7 +333,7 @@ Re-sync the track from the server and try again.",
                     locator.profile_id
                 ));
             }
-            let Some(password) = self.opensubsonic_passwords.get(&locator.profile_id) else {
+            let Some(password) = self.backend_passwords.get(&locator.profile_id) else {
                 return Err(format!(
                     "OpenSubsonic credential not cached for profile '{}'. \
 Check Settings -> OpenSubsonic status and re-save credentials if needed.",
@@ -269,10 +359,45 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             ));
         }
 
+        if let Some(locator) = parse_webdav_track_uri(track.path.as_path()) {
+            if locator.endpoint.trim().is_empty() || locator.username.trim().is_empty() {
+                return Err(format!(
+                    "WebDAV track URI missing share details for profile '{}'. \
+Re-sync the share and try again.",
+                    locator.profile_id
+                ));
+            }
+            let Some(password) = self.backend_passwords.get(&locator.profile_id) else {
+                return Err(format!(
+                    "WebDAV credential not cached for profile '{}'. \
+Check Settings -> Integrations and re-save the WebDAV password if needed.",
+                    locator.profile_id
+                ));
+            };
+            let source = HttpRangeSource::open(
+                webdav::href_url(&locator.endpoint, &locator.href),
+                Some(webdav::basic_authorization(&locator.username, password)),
+            )?;
+            if let Some(extension) = source
+                .content_type()
+                .and_then(Self::extension_from_content_type)
+            {
+                hint.with_extension(extension);
+            }
+            if let Some(extension) = locator.format_hint() {
+                hint.with_extension(extension.as_str());
+            }
+            return Ok(MediaSourceStream::new(Box::new(source), Default::default()));
+        }
+
+        if is_web_track_path(track.path.as_path()) {
+            return Self::open_web_media_source_stream(track, hint);
+        }
+
         if let Some(extension) = track.path.extension().and_then(|ext| ext.to_str()) {
             hint.with_extension(extension);
         }
-        let file = std::fs::File::open(track.path.clone())
+        let file = sandbox_access::open_file(&track.path)
             .map_err(|error| format!("Failed to open file: {error}"))?;
         Ok(MediaSourceStream::new(Box::new(file), Default::default()))
     }
@@ -316,6 +441,11 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
         let next_decoder_request_chunk_ms = buffering
             .map(|value| value.decoder_request_chunk_ms)
             .unwrap_or(self.decoder_request_chunk_ms);
+        if let Some(output) = output {
+            self.skip_hidden_pregap = output.skip_hidden_pregap;
+            self.skip_silence_spoken_word = output.skip_silence_spoken_word;
+            self.skip_silence_min_pause_ms = output.skip_silence_min_pause_ms;
+        }
 
         let audio_processing_changed = self.target_sample_rate != next_target_sample_rate
             || self.target_channels != next_target_channels
@@ -366,6 +496,15 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
         self.resampler_quality = next_resampler_quality;
         self.dither_on_bitdepth_reduce = next_dither_on_bitdepth_reduce;
         self.downmix_higher_channel_tracks = next_downmix_higher_channel_tracks;
+        if let Some(skip_hidden_pregap) = output.skip_hidden_pregap {
+            self.skip_hidden_pregap = skip_hidden_pregap;
+        }
+        if let Some(skip_silence_spoken_word) = output.skip_silence_spoken_word {
+            self.skip_silence_spoken_word = skip_silence_spoken_word;
+        }
+        if let Some(skip_silence_min_pause_ms) = output.skip_silence_min_pause_ms {
+            self.skip_silence_min_pause_ms = skip_silence_min_pause_ms;
+        }
 
         if audio_processing_changed {
             self.resampler = None;
@@ -445,7 +584,10 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                         protocol::ConfigDeltaEntry::Cast(_)
                         | protocol::ConfigDeltaEntry::Ui(_)
                         | protocol::ConfigDeltaEntry::Library(_)
-                        | protocol::ConfigDeltaEntry::Integrations(_) => {}
+                        | protocol::ConfigDeltaEntry::Integrations(_)
+                        | protocol::ConfigDeltaEntry::Announcements(_)
+                        | protocol::ConfigDeltaEntry::Power(_)
+                        | protocol::ConfigDeltaEntry::Dsp(_) => {}
                     }
                 }
             }
@@ -467,14 +609,14 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                 self.resampler_flushed = false;
                 self.resample_buffer.clear();
             }
-            DecodeWorkItem::UpsertOpenSubsonicPassword {
+            DecodeWorkItem::UpsertBackendPassword {
                 profile_id,
                 password,
             } => {
-                self.opensubsonic_passwords.insert(profile_id, password);
+                self.backend_passwords.insert(profile_id, password);
             }
-            DecodeWorkItem::RemoveOpenSubsonicPassword { profile_id } => {
-                self.opensubsonic_passwords.remove(profile_id.as_str());
+            DecodeWorkItem::RemoveBackendPassword { profile_id } => {
+                self.backend_passwords.remove(profile_id.as_str());
             }
         }
     }
@@ -491,8 +633,28 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
         &self,
         source_sample_rate: u32,
         chunk_size: usize,
-    ) -> Result<SincFixedIn<f32>, String> {
+    ) -> Result<QualityResampler, String> {
+        let ratio = self.target_sample_rate as f64 / source_sample_rate as f64;
+        let channels = self.target_channels.max(1) as usize;
         let params = match self.resampler_quality {
+            ResamplerQuality::Linear => {
+                return FastFixedIn::<f32>::new(
+                    ratio,
+                    2.0,
+                    PolynomialDegree::Linear,
+                    chunk_size,
+                    channels,
+                )
+                .map(QualityResampler::Linear)
+                .map_err(|err| format!("Failed to create resampler: {err}"));
+            }
+            ResamplerQuality::Medium => SincInterpolationParameters {
+                sinc_len: 128,
+                f_cutoff: 0.925,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 128,
+                window: WindowFunction::Blackman2,
+            },
             ResamplerQuality::High => SincInterpolationParameters {
                 sinc_len: 256,
                 f_cutoff: 0.95,
@@ -508,14 +670,9 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                 window: WindowFunction::BlackmanHarris2,
             },
         };
-        SincFixedIn::<f32>::new(
-            self.target_sample_rate as f64 / source_sample_rate as f64,
-            2.0,
-            params,
-            chunk_size,
-            self.target_channels.max(1) as usize,
-        )
-        .map_err(|err| format!("Failed to create resampler: {err}"))
+        SincFixedIn::<f32>::new(ratio, 2.0, params, chunk_size, channels)
+            .map(QualityResampler::Sinc)
+            .map_err(|err| format!("Failed to create resampler: {err}"))
     }
 
     fn deinterleave(samples: &[f32], channels: usize) -> Vec<Vec<f32>> {
@@ -605,7 +762,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
 
             if samples.is_empty() {
                 if input_exhausted && !self.resampler_flushed {
-                    match resampler.process_partial::<&[f32]>(None, None) {
+                    match resampler.process_partial(None) {
                         Ok(flush_result) => {
                             self.resampler_flushed = true;
                             return Self::interleave(&flush_result);
@@ -621,7 +778,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
 
             let deinterleaved = Self::deinterleave(&samples, channels);
             let mut waves_out = if deinterleaved[0].len() == input_frames_next {
-                match resampler.process(&deinterleaved, None) {
+                match resampler.process(&deinterleaved) {
                     Ok(waves_out) => waves_out,
                     Err(err) => {
                         warn!("DecodeWorker: resample failed: {}", err);
@@ -629,7 +786,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                     }
                 }
             } else {
-                match resampler.process_partial(Some(&deinterleaved), None) {
+                match resampler.process_partial(Some(&deinterleaved)) {
                     Ok(waves_out) => waves_out,
                     Err(err) => {
                         warn!("DecodeWorker: partial resample failed: {}", err);
@@ -639,7 +796,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             };
 
             if input_exhausted && self.resample_buffer.is_empty() && !self.resampler_flushed {
-                match resampler.process_partial::<&[f32]>(None, None) {
+                match resampler.process_partial(None) {
                     Ok(flush_result) => {
                         for i in 0..channels {
                             waves_out[i].extend(flush_result[i].iter().copied());
@@ -914,8 +1071,22 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                     if packet.track_id() != active.source_track_id {
                         return true;
                     }
+                    if active
+                        .trim_end_ts
+                        .is_some_and(|trim_end_ts| packet.ts() >= trim_end_ts)
+                    {
+                        active.input_exhausted = true;
+                        return false;
+                    }
 
-                    match active.decoder.decode(&packet) {
+                    let decode_started = Instant::now();
+                    let decode_result = if fault_injection::is_fault_enabled(Fault::DecodeErrors) {
+                        Err(Error::DecodeError("injected decode error"))
+                    } else {
+                        active.decoder.decode(&packet)
+                    };
+                    active.decode_time += decode_started.elapsed();
+                    match decode_result {
                         Ok(decoded) => {
                             active.consecutive_decode_errors = 0;
                             let spec = decoded.spec();
@@ -1105,6 +1276,14 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             if exhausted_input {
                 active.input_exhausted = true;
             }
+            if let (Some(skipper), Some((samples, source_channels))) =
+                (active.silence_skipper.as_mut(), decoded_samples.take())
+            {
+                let kept = skipper.process(&samples);
+                if !kept.is_empty() {
+                    decoded_samples = Some((kept, source_channels));
+                }
+            }
         }
 
         if let Some((samples, source_channels)) = decoded_samples {
@@ -1140,6 +1319,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             .active_track
             .take()
             .expect("track exists when finishing");
+        metrics::observe_duration(metrics::TRACK_DECODE_SECONDS, &[], track.decode_time);
         let _ = self
             .bus_sender
             .send(Message::Audio(AudioMessage::AudioPacket(
@@ -1179,7 +1359,9 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
     }
 
     fn emit_track_unavailable_if_remote(&self, track: &TrackIdentifier, reason: &str) {
-        if parse_opensubsonic_track_uri(track.path.as_path()).is_none() {
+        if parse_opensubsonic_track_uri(track.path.as_path()).is_none()
+            && parse_webdav_track_uri(track.path.as_path()).is_none()
+        {
             return;
         }
         let _ = self.bus_sender.send(Message::Playlist(
@@ -1190,7 +1372,62 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
         ));
     }
 
-    fn open_track(&mut self, input_track: TrackIdentifier) -> Option<ActiveDecodeTrack> {
+    /// Converts a per-track end trim into the packet timestamp where decoding stops.
+    /// Trims are ignored when the duration is unknown or the trim covers the whole track.
+    fn trim_end_timestamp(codec_params: &CodecParameters, trim_end_ms: u64) -> Option<u64> {
+        if trim_end_ms == 0 {
+            return None;
+        }
+        let time_base = codec_params.time_base?;
+        let duration = time_base.calc_time(codec_params.n_frames?);
+        let duration_ms = duration.seconds * 1000 + (duration.frac * 1000.0) as u64;
+        let end_ms = duration_ms
+            .checked_sub(trim_end_ms)
+            .filter(|end_ms| *end_ms > 0)?;
+        Some(time_base.calc_timestamp(symphonia::core::units::Time {
+            seconds: end_ms / 1000,
+            frac: (end_ms % 1000) as f64 / 1000.0,
+        }))
+    }
+
+    /// Returns where a track-start decode should begin to skip hidden pregap audio.
+    fn hidden_pregap_skip_ms(&self, track: &TrackIdentifier) -> u64 {
+        if !self.skip_hidden_pregap
+            || track.start_offset_ms > 0
+            || is_remote_track_path(track.path.as_path())
+        {
+            return 0;
+        }
+        metadata_tags::read_embedded_cue_sheet(track.path.as_path())
+            .and_then(|cue_sheet| cue_sheet.hidden_pregap_ms())
+            .unwrap_or(0)
+    }
+
+    /// Returns whether pauses should be cut from a track: the playlist asked for it, or
+    /// the track is tagged as a podcast/audiobook and spoken-word skipping is enabled.
+    fn should_skip_silence(&self, track: &TrackIdentifier) -> bool {
+        if track.skip_silence {
+            return true;
+        }
+        if !self.skip_silence_spoken_word || is_remote_track_path(track.path.as_path()) {
+            return false;
+        }
+        metadata_tags::read_common_track_metadata(track.path.as_path())
+            .is_some_and(|metadata| silence_skipping::is_spoken_word_genre(&metadata.genre))
+    }
+
+    fn open_track(&mut self, mut input_track: TrackIdentifier) -> Option<ActiveDecodeTrack> {
+        let hidden_pregap_ms = self.hidden_pregap_skip_ms(&input_track);
+        if hidden_pregap_ms > 0 {
+            debug!(
+                "DecodeWorker: Skipping {}ms hidden pregap in {}",
+                hidden_pregap_ms,
+                input_track.path.display()
+            );
+        }
+        if input_track.start_offset_ms == 0 {
+            input_track.start_offset_ms = hidden_pregap_ms.max(input_track.trim_start_ms);
+        }
         let mut hint = Hint::new();
         let media_source = match self.open_media_source_stream(&input_track, &mut hint) {
             Ok(source) => source,
@@ -1297,7 +1534,33 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             }
         }
 
-        let technical_metadata = self.build_technical_metadata(&input_track.path, &codec_params);
+        let trim_end_ts = Self::trim_end_timestamp(&codec_params, input_track.trim_end_ms);
+        let mut technical_metadata =
+            self.build_technical_metadata(&input_track.path, &codec_params);
+        if trim_end_ts.is_some() {
+            technical_metadata.duration_ms = technical_metadata
+                .duration_ms
+                .saturating_sub(input_track.trim_end_ms);
+        }
+        let silence_skipper = self.should_skip_silence(&input_track).then(|| {
+            debug!(
+                "DecodeWorker: Skipping pauses over {}ms in {}",
+                self.skip_silence_min_pause_ms,
+                input_track.path.display()
+            );
+            SilenceSkipper::new(
+                source_sample_rate,
+                source_channels as usize,
+                self.skip_silence_min_pause_ms,
+            )
+        });
+        if input_track.normalization_gain_db != 0.0 {
+            debug!(
+                "DecodeWorker: Normalizing {} by {:+.1} dB",
+                input_track.path.display(),
+                input_track.normalization_gain_db
+            );
+        }
         debug!(
             "DecodeWorker: Track ready id={} sr={} channels={} play_immediately={}",
             input_track.id, source_sample_rate, source_channels, input_track.play_immediately
@@ -1311,6 +1574,7 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
                     play_immediately: input_track.play_immediately,
                     technical_metadata: technical_metadata.clone(),
                     start_offset_ms: input_track.start_offset_ms,
+                    normalization_gain_db: input_track.normalization_gain_db,
                 },
             )));
 
@@ -1322,9 +1586,12 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
             decoder,
             source_sample_rate,
             source_channels,
+            trim_end_ts,
+            silence_skipper,
             input_exhausted: false,
             consecutive_decode_errors: 0,
             consecutive_packet_read_errors: 0,
+            decode_time: Duration::ZERO,
         })
     }
 
@@ -1336,6 +1603,11 @@ Check Settings -> OpenSubsonic status and re-save credentials if needed.",
         let sample_rate = codec_params.sample_rate.unwrap_or(44100);
         let format_name = parse_opensubsonic_track_uri(path.as_path())
             .and_then(|locator| locator.format_hint.map(|hint| hint.to_ascii_uppercase()))
+            .or_else(|| {
+                parse_webdav_track_uri(path.as_path())
+                    .and_then(|locator| locator.format_hint())
+                    .map(|hint| hint.to_ascii_uppercase())
+            })
             .or_else(|| {
                 path.extension()
                     .and_then(|ext| ext.to_str())
@@ -1393,7 +1665,7 @@ fn duration_ms_from_codec_params(codec_params: &CodecParameters, sample_rate: u3
 fn duration_ms_from_lofty_properties(path: &PathBuf) -> u64 {
     use lofty::file::AudioFile;
 
-    lofty::read_from_path(path)
+    metadata_tags::read_tagged_file(path)
         .ok()
         .map(|tagged| tagged.properties().duration().as_millis() as u64)
         .filter(|value| *value > 0)
@@ -1412,7 +1684,7 @@ fn implied_bitrate_kbps(audio_data_size_bytes: u64, duration_ms: u64) -> Option<
 }
 
 fn audio_data_size_bytes(path: &PathBuf) -> Option<u64> {
-    let file_size = std::fs::metadata(path).ok()?.len();
+    let file_size = sandbox_access::open_file(path).ok()?.metadata().ok()?.len();
     let metadata_size = get_metadata_size(path);
     let audio_data_size = file_size.saturating_sub(metadata_size);
     (audio_data_size > 0).then_some(audio_data_size)
@@ -1420,7 +1692,7 @@ fn audio_data_size_bytes(path: &PathBuf) -> Option<u64> {
 
 fn get_metadata_size(path: &PathBuf) -> u64 {
     let mut total_size = 0;
-    if let Ok(mut file) = std::fs::File::open(path) {
+    if let Ok(mut file) = sandbox_access::open_file(path) {
         let file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
         if file_size == 0 {
             return 0;
@@ -1668,12 +1940,15 @@ impl AudioDecoder {
                         password,
                         ..
                     }) => {
-                        if profile.backend_kind != protocol::BackendKind::OpenSubsonic {
+                        if !matches!(
+                            profile.backend_kind,
+                            protocol::BackendKind::OpenSubsonic | protocol::BackendKind::WebDav
+                        ) {
                             continue;
                         }
                         if let Some(password) = password {
                             let _ = self.worker_sender.blocking_send(
-                                DecodeWorkItem::UpsertOpenSubsonicPassword {
+                                DecodeWorkItem::UpsertBackendPassword {
                                     profile_id: profile.profile_id,
                                     password,
                                 },
@@ -1683,9 +1958,9 @@ impl AudioDecoder {
                     Message::Integration(IntegrationMessage::RemoveBackendProfile {
                         profile_id,
                     }) => {
-                        let _ = self.worker_sender.blocking_send(
-                            DecodeWorkItem::RemoveOpenSubsonicPassword { profile_id },
-                        );
+                        let _ = self
+                            .worker_sender
+                            .blocking_send(DecodeWorkItem::RemoveBackendPassword { profile_id });
                     }
                     _ => {}
                 },
@@ -1757,12 +2032,14 @@ impl AudioDecoder {
 #[cfg(test)]
 mod tests {
     use super::{AudioDecoder, DecodeWorkItem, DecodeWorker};
-    use crate::config::{BufferingConfig, OutputConfig};
+    use crate::config::{BufferingConfig, OutputConfig, ResamplerQuality};
     use crate::integration_uri::OpenSubsonicTrackLocator;
     use crate::protocol::{self, BufferingConfigDelta, OutputConfigDelta, TrackIdentifier};
     use std::path::PathBuf;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
+    use symphonia::core::codecs::CodecParameters;
+    use symphonia::core::units::TimeBase;
     use tokio::sync::broadcast;
     use tokio::sync::mpsc;
 
@@ -1802,6 +2079,10 @@ mod tests {
             path: PathBuf::from(format!("/tmp/{}.flac", id)),
             play_immediately,
             start_offset_ms: 0,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            skip_silence: false,
+            normalization_gain_db: 0.0,
         }
     }
 
@@ -1813,17 +2094,80 @@ mod tests {
                 path: PathBuf::from("/tmp/a.flac"),
                 play_immediately: false,
                 start_offset_ms: 0,
+                trim_start_ms: 0,
+                trim_end_ms: 0,
+                skip_silence: false,
+                normalization_gain_db: 0.0,
             },
             TrackIdentifier {
                 id: "b".to_string(),
                 path: PathBuf::from("/tmp/b.flac"),
                 play_immediately: true,
                 start_offset_ms: 0,
+                trim_start_ms: 0,
+                trim_end_ms: 0,
+                skip_silence: false,
+                normalization_gain_db: 0.0,
             },
         ];
         assert!(DecodeWorker::should_bootstrap_decode(&tracks));
     }
 
+    #[test]
+    fn test_trim_end_timestamp_converts_trim_and_ignores_unusable_values() {
+        let mut codec_params = CodecParameters::new();
+        codec_params
+            .with_sample_rate(44_100)
+            .with_time_base(TimeBase::new(1, 44_100))
+            .with_n_frames(44_100 * 10);
+
+        assert_eq!(
+            DecodeWorker::trim_end_timestamp(&codec_params, 2_000),
+            Some(44_100 * 8)
+        );
+        assert_eq!(DecodeWorker::trim_end_timestamp(&codec_params, 0), None);
+        assert_eq!(
+            DecodeWorker::trim_end_timestamp(&codec_params, 10_000),
+            None
+        );
+        assert_eq!(
+            DecodeWorker::trim_end_timestamp(&CodecParameters::new(), 2_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_create_resampler_supports_every_quality() {
+        let (bus_sender, _) = broadcast::channel(8);
+        let (_worker_tx, worker_rx) = mpsc::channel(8);
+        let mut worker = DecodeWorker::new(
+            bus_sender,
+            worker_rx,
+            Arc::new(AtomicBool::new(false)),
+            OutputConfig::default(),
+            BufferingConfig::default(),
+        );
+        worker.target_sample_rate = 48_000;
+        worker.target_channels = 2;
+        for quality in [
+            ResamplerQuality::Linear,
+            ResamplerQuality::Medium,
+            ResamplerQuality::High,
+            ResamplerQuality::Highest,
+        ] {
+            worker.resampler_quality = quality;
+            let mut resampler = worker
+                .create_resampler(44_100, 1024)
+                .expect("resampler should build");
+            let frames = resampler.input_frames_next();
+            let waves_out = resampler
+                .process(&[vec![0.0; frames], vec![0.0; frames]])
+                .expect("resampling should succeed");
+            assert_eq!(waves_out.len(), 2, "{quality:?}");
+            assert!(waves_out[0].len() > frames, "{quality:?}");
+        }
+    }
+
     #[test]
     fn test_should_bootstrap_decode_false_when_no_immediate_track() {
         let tracks = vec![TrackIdentifier {
@@ -1831,6 +2175,10 @@ mod tests {
             path: PathBuf::from("/tmp/a.flac"),
             play_immediately: false,
             start_offset_ms: 0,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            skip_silence: false,
+            normalization_gain_db: 0.0,
         }];
         assert!(!DecodeWorker::should_bootstrap_decode(&tracks));
     }
@@ -3,11 +3,14 @@
 //! Consumes decoded packets, manages queue/cursor state, drives the CPAL output
 //! stream, and emits playback progress/track lifecycle notifications.
 
+use crate::dsp_chain::{DspChain, DspControls};
+use crate::fault_injection::{self, Fault};
 use crate::protocol::{
     AudioMessage, AudioPacket, ChannelTransformKind, ConfigMessage, Message, OutputPathInfo,
     OutputSampleFormat, OutputStreamInfo, PlaybackMessage, PlaylistMessage, TrackStarted,
 };
-use crate::{config::BufferingConfig, config::OutputConfig};
+use crate::spectrogram;
+use crate::{config::BufferingConfig, config::DspConfig, config::OutputConfig};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use log::{debug, error, warn};
 use std::{
@@ -21,6 +24,17 @@ use std::{
 };
 use tokio::sync::broadcast::{Receiver, Sender};
 
+/// Playback progress reporting cadence (drives the seek bar and elapsed time).
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+/// Reduced progress cadence while battery saver is active.
+const BATTERY_SAVER_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// Multiplier applied to the refill interval and target buffer while battery saver is active.
+const BATTERY_SAVER_BUFFER_SCALE: usize = 2;
+/// Minimum change in decoded-cache residency before a new occupancy report is sent.
+const DECODED_CACHE_REPORT_STEP_BYTES: usize = 1024 * 1024;
+/// How often a disconnected preferred output device is looked for after a failover.
+const OUTPUT_DEVICE_RETURN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Queue marker used to announce track start inside the audio stream.
 #[derive(Debug, Clone)]
 pub struct TrackHeader {
@@ -28,6 +42,8 @@ pub struct TrackHeader {
     pub id: String,
     /// Start offset applied when this track entered playback.
     pub start_offset_ms: u64,
+    /// Loudness normalization gain the DSP chain applies from this track's first sample.
+    pub normalization_gain_db: f32,
 }
 
 /// Queue item variants consumed by the audio callback.
@@ -67,6 +83,8 @@ pub struct AudioPlayer {
     dither_on_bitdepth_reduce: bool,
     downmix_higher_channel_tracks: bool,
     target_output_device_name: Arc<Mutex<Option<String>>>,
+    /// Configured device that disappeared while playback continues on the system default.
+    failed_over_device_name: Arc<Mutex<Option<String>>>,
     output_stream_info: Arc<Mutex<Option<OutputStreamInfo>>>,
     sample_queue: Arc<Mutex<VecDeque<AudioQueueEntry>>>,
     queue_start_position: Arc<AtomicUsize>,
@@ -78,9 +96,19 @@ pub struct AudioPlayer {
     current_metadata: Arc<Mutex<Option<crate::protocol::TechnicalMetadata>>>,
     decode_bootstrap_pending: Arc<AtomicBool>,
     volume: Arc<AtomicU32>,
+    /// User-selected volume before any temporary ducking is applied.
+    user_volume: f32,
+    /// Temporary gain multiplier used while announcements are spoken.
+    duck_gain: f32,
+    /// DSP settings read by the output callback on every buffer.
+    dsp_controls: Arc<DspControls>,
     buffer_low_watermark_ms: Arc<AtomicUsize>,
     buffer_target_ms: Arc<AtomicUsize>,
     buffer_request_interval_ms: Arc<AtomicUsize>,
+    /// Battery saver: slower progress reporting and larger, less frequent buffer refills.
+    battery_saver_active: Arc<AtomicBool>,
+    /// Memory governor ceiling for decoded samples resident in `sample_queue`.
+    decoded_cache_max_bytes: Arc<AtomicUsize>,
 
     // Setup cache
     cached_track_indices: Arc<Mutex<HashMap<String, TrackIndex>>>,
@@ -138,6 +166,11 @@ impl AudioPlayer {
         }
     }
 
+    fn store_effective_volume(&self) {
+        let effective = (self.user_volume * self.duck_gain).clamp(0.0, 1.0);
+        self.volume.store(effective.to_bits(), Ordering::Relaxed);
+    }
+
     fn output_sample_format_from_cpal(sample_format: cpal::SampleFormat) -> OutputSampleFormat {
         match sample_format {
             cpal::SampleFormat::F32 => OutputSampleFormat::F32,
@@ -360,6 +393,7 @@ impl AudioPlayer {
         bus_sender: Sender<Message>,
         initial_output_config: OutputConfig,
         initial_buffering_config: BufferingConfig,
+        initial_dsp_config: DspConfig,
     ) -> Self {
         let is_playing = Arc::new(AtomicBool::new(false));
         let current_track_position = Arc::new(AtomicUsize::new(0));
@@ -375,6 +409,7 @@ impl AudioPlayer {
             Arc::new(AtomicUsize::new(output_signature.sample_rate_hz as usize));
         let target_channels = Arc::new(AtomicUsize::new(output_signature.channel_count as usize));
         let target_output_device_name = Arc::new(Mutex::new(output_signature.device_name.clone()));
+        let failed_over_device_name = Arc::new(Mutex::new(None));
         let volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
         let buffer_low_watermark_ms = Arc::new(AtomicUsize::new(
             initial_buffering_config.player_low_watermark_ms as usize,
@@ -387,6 +422,10 @@ impl AudioPlayer {
         let buffer_request_interval_ms = Arc::new(AtomicUsize::new(
             initial_buffering_config.player_request_interval_ms.max(20) as usize,
         ));
+        let battery_saver_active = Arc::new(AtomicBool::new(false));
+        let decoded_cache_max_bytes = Arc::new(AtomicUsize::new(Self::megabytes_to_bytes(
+            initial_buffering_config.decoded_cache_max_mb,
+        )));
 
         let mut player = Self {
             bus_receiver,
@@ -409,6 +448,7 @@ impl AudioPlayer {
             dither_on_bitdepth_reduce: output_signature.dither_on_bitdepth_reduce,
             downmix_higher_channel_tracks: initial_output_config.downmix_higher_channel_tracks,
             target_output_device_name,
+            failed_over_device_name: failed_over_device_name.clone(),
             output_stream_info: Arc::new(Mutex::new(None)),
             current_track_id: current_track_id.clone(),
             current_track_position: current_track_position.clone(),
@@ -416,9 +456,14 @@ impl AudioPlayer {
             current_metadata: current_metadata.clone(),
             decode_bootstrap_pending: decode_bootstrap_pending.clone(),
             volume: volume.clone(),
+            user_volume: 1.0,
+            duck_gain: 1.0,
+            dsp_controls: Arc::new(DspControls::new(&initial_dsp_config)),
             buffer_low_watermark_ms: buffer_low_watermark_ms.clone(),
             buffer_target_ms: buffer_target_ms.clone(),
             buffer_request_interval_ms: buffer_request_interval_ms.clone(),
+            battery_saver_active: battery_saver_active.clone(),
+            decoded_cache_max_bytes: decoded_cache_max_bytes.clone(),
             last_output_signature: None,
             playback_session_active: false,
             staged_output_delta: crate::protocol::OutputConfigDelta::default(),
@@ -439,9 +484,15 @@ impl AudioPlayer {
         let current_track_offset_ms_clone = current_track_offset_ms.clone();
         let target_sample_rate_clone = target_sample_rate.clone();
         let target_channels_clone = target_channels.clone();
+        let battery_saver_active_clone = battery_saver_active.clone();
 
         thread::spawn(move || loop {
-            thread::sleep(Duration::from_millis(50));
+            let progress_interval = if battery_saver_active_clone.load(Ordering::Relaxed) {
+                BATTERY_SAVER_PROGRESS_INTERVAL
+            } else {
+                PROGRESS_INTERVAL
+            };
+            thread::sleep(progress_interval);
             if is_playing_clone.load(Ordering::Relaxed) {
                 let metadata = current_metadata_clone.lock().unwrap().clone();
                 let track_id = current_track_id_clone.lock().unwrap().clone();
@@ -478,11 +529,30 @@ impl AudioPlayer {
             }
         });
 
+        // Spawn output device watcher. After a failover it looks for the configured device
+        // and asks the player to move back once it is connected again.
+        let bus_sender_clone = bus_sender.clone();
+        thread::spawn(move || loop {
+            thread::sleep(OUTPUT_DEVICE_RETURN_POLL_INTERVAL);
+            let Some(device_name) = failed_over_device_name.lock().unwrap().clone() else {
+                continue;
+            };
+            if Self::output_device_connected(&device_name) {
+                let _ =
+                    bus_sender_clone.send(Message::Config(ConfigMessage::OutputDeviceReturned {
+                        device_name,
+                    }));
+            }
+        });
+
         // Spawn decode prefetch thread. It requests more decoded audio when
         // buffered samples ahead of playback fall below a configurable threshold.
         let bus_sender_clone = bus_sender.clone();
         let current_track_position_clone = current_track_position.clone();
+        let queue_start_position_clone = queue_start_position.clone();
         let queue_end_position_clone = queue_end_position.clone();
+        let cached_track_indices_clone = cached_track_indices.clone();
+        let decoded_cache_max_bytes_clone = decoded_cache_max_bytes.clone();
         let target_sample_rate_clone = target_sample_rate.clone();
         let target_channels_clone = target_channels.clone();
         let current_metadata_clone = current_metadata.clone();
@@ -490,10 +560,20 @@ impl AudioPlayer {
         let buffer_low_watermark_ms_clone = buffer_low_watermark_ms.clone();
         let buffer_target_ms_clone = buffer_target_ms.clone();
         let buffer_request_interval_ms_clone = buffer_request_interval_ms.clone();
+        let battery_saver_active_clone = battery_saver_active.clone();
+        let mut last_reported_occupancy: Option<(usize, usize, usize)> = None;
         thread::spawn(move || loop {
+            // Battery saver trades memory for fewer wakeups: refill less often, but
+            // further ahead, so the CPU can stay idle between refills.
+            let buffer_scale = if battery_saver_active_clone.load(Ordering::Relaxed) {
+                BATTERY_SAVER_BUFFER_SCALE
+            } else {
+                1
+            };
             let interval_ms = buffer_request_interval_ms_clone
                 .load(Ordering::Relaxed)
-                .max(20) as u64;
+                .max(20)
+                .saturating_mul(buffer_scale) as u64;
             thread::sleep(Duration::from_millis(interval_ms));
 
             let sample_rate = target_sample_rate_clone.load(Ordering::Relaxed);
@@ -501,7 +581,8 @@ impl AudioPlayer {
             let low_watermark_ms = buffer_low_watermark_ms_clone.load(Ordering::Relaxed);
             let target_buffer_ms = buffer_target_ms_clone
                 .load(Ordering::Relaxed)
-                .max(low_watermark_ms.saturating_add(500));
+                .max(low_watermark_ms.saturating_add(500))
+                .saturating_mul(buffer_scale);
 
             let low_watermark_samples =
                 Self::milliseconds_to_samples(low_watermark_ms, sample_rate, channels);
@@ -518,13 +599,48 @@ impl AudioPlayer {
             let queue_end_position = queue_end_position_clone.load(Ordering::Relaxed);
             let buffered_samples = queue_end_position.saturating_sub(current_position);
 
-            let requested_samples = Self::compute_decode_request_samples_for_state(
-                has_active_track,
-                has_bootstrap_pending,
-                buffered_samples,
-                low_watermark_samples,
-                target_buffer_samples,
+            // Every queue position past `queue_start` is one resident f32 sample (track
+            // markers are negligible), so this is the real decoded-cache footprint.
+            let queue_start_position = queue_start_position_clone.load(Ordering::Relaxed);
+            let resident_samples = queue_end_position.saturating_sub(queue_start_position);
+            let max_bytes = decoded_cache_max_bytes_clone.load(Ordering::Relaxed);
+            let max_samples = max_bytes / std::mem::size_of::<f32>();
+
+            let requested_samples = Self::apply_decoded_cache_ceiling(
+                Self::compute_decode_request_samples_for_state(
+                    has_active_track,
+                    has_bootstrap_pending,
+                    buffered_samples,
+                    low_watermark_samples,
+                    target_buffer_samples,
+                ),
+                resident_samples,
+                max_samples,
             );
+
+            let resident_bytes = resident_samples.saturating_mul(std::mem::size_of::<f32>());
+            let cached_tracks = cached_track_indices_clone
+                .lock()
+                .map(|indices| indices.len())
+                .unwrap_or(0);
+            let should_report = match last_reported_occupancy {
+                Some((last_bytes, last_max_bytes, last_tracks)) => {
+                    last_bytes.abs_diff(resident_bytes) >= DECODED_CACHE_REPORT_STEP_BYTES
+                        || last_max_bytes != max_bytes
+                        || last_tracks != cached_tracks
+                }
+                None => true,
+            };
+            if should_report {
+                last_reported_occupancy = Some((resident_bytes, max_bytes, cached_tracks));
+                let _ =
+                    bus_sender_clone.send(Message::Audio(AudioMessage::DecodedCacheOccupancy {
+                        resident_bytes: resident_bytes as u64,
+                        max_bytes: max_bytes as u64,
+                        cached_tracks,
+                    }));
+            }
+
             if requested_samples > 0 {
                 let _ = bus_sender_clone.send(Message::Audio(AudioMessage::RequestDecodeChunk {
                     requested_samples,
@@ -535,6 +651,21 @@ impl AudioPlayer {
         player
     }
 
+    fn megabytes_to_bytes(megabytes: u32) -> usize {
+        (megabytes as usize).saturating_mul(1024 * 1024)
+    }
+
+    /// Clamps a decode request so resident decoded audio never grows past the memory
+    /// ceiling. A full cache refuses further decoding, which also defers next-track
+    /// prefetch until playback drains enough of the queue.
+    fn apply_decoded_cache_ceiling(
+        requested_samples: usize,
+        resident_samples: usize,
+        max_samples: usize,
+    ) -> usize {
+        requested_samples.min(max_samples.saturating_sub(resident_samples))
+    }
+
     fn milliseconds_to_samples(milliseconds: usize, sample_rate: usize, channels: usize) -> usize {
         let sr = sample_rate.max(1) as u128;
         let ch = channels.max(1) as u128;
@@ -582,13 +713,15 @@ impl AudioPlayer {
         bus_sender: &Sender<Message>,
         current_track_position: &Arc<AtomicUsize>,
         volume: &Arc<AtomicU32>,
+        dsp_controls: &DspControls,
+        dsp_chain: &mut DspChain,
         mut convert_sample: F,
         silence_value: T,
     ) where
         T: Copy,
         F: FnMut(f32) -> T,
     {
-        if !is_playing.load(Ordering::Relaxed) {
+        if !is_playing.load(Ordering::Relaxed) || fault_injection::take_injected_underrun() {
             output_buffer.fill(silence_value);
             return;
         }
@@ -601,6 +734,8 @@ impl AudioPlayer {
         }
         let mut output_current_position = 0;
         let gain = f32::from_bits(volume.load(Ordering::Relaxed)).clamp(0.0, 1.0);
+        dsp_chain.refresh(dsp_controls);
+        let mut spectrogram_tap = spectrogram::live_tap().writer();
         let mut queue_cursor = Self::locate_position_in_queue(
             &sample_queue_unlocked,
             queue_start,
@@ -628,7 +763,10 @@ impl AudioPlayer {
                         queue_cursor = Some((entry_index + 1, 0));
                         continue;
                     }
-                    let sample = samples[entry_offset] * gain;
+                    if let Some(tap) = spectrogram_tap.as_mut() {
+                        tap.push(samples[entry_offset]);
+                    }
+                    let sample = dsp_chain.process(samples[entry_offset], gain);
                     output_buffer[output_current_position] = convert_sample(sample);
                     input_current_position = input_current_position.saturating_add(1);
                     output_current_position += 1;
@@ -642,7 +780,9 @@ impl AudioPlayer {
                 AudioQueueEntry::TrackHeader(TrackHeader {
                     id,
                     start_offset_ms,
+                    normalization_gain_db,
                 }) => {
+                    dsp_chain.start_track(dsp_controls, *normalization_gain_db);
                     let _ = bus_sender.send(Message::Playback(PlaybackMessage::TrackStarted(
                         TrackStarted {
                             id: id.clone(),
@@ -734,20 +874,29 @@ impl AudioPlayer {
             )
         } else {
             let host = cpal::default_host();
-            let selected_device = requested_device_name.as_ref().and_then(|device_name| {
-                host.output_devices().ok().and_then(|devices| {
-                    devices
-                        .filter_map(|device| {
-                            let name = device.name().ok()?;
-                            if name == *device_name {
-                                Some(device)
-                            } else {
-                                None
-                            }
-                        })
-                        .next()
+            let selected_device = requested_device_name
+                .as_ref()
+                .filter(|_| {
+                    let injected = fault_injection::is_fault_enabled(Fault::DeviceOpenFailure);
+                    if injected {
+                        warn!("AudioPlayer: injected output device open failure");
+                    }
+                    !injected
                 })
-            });
+                .and_then(|device_name| {
+                    host.output_devices().ok().and_then(|devices| {
+                        devices
+                            .filter_map(|device| {
+                                let name = device.name().ok()?;
+                                if name == *device_name {
+                                    Some(device)
+                                } else {
+                                    None
+                                }
+                            })
+                            .next()
+                    })
+                });
             if requested_device_name.is_some() && selected_device.is_none() {
                 warn!(
                     "AudioPlayer: requested output device not found. Falling back to system default"
@@ -860,6 +1009,133 @@ impl AudioPlayer {
         }
     }
 
+    /// Re-opens the current output device, dropping cached default-device capabilities first
+    /// so a device that changed while the system was asleep is re-enumerated.
+    fn reopen_audio_device(&mut self, reason: &str) {
+        debug!("AudioPlayer: Re-opening audio device ({})", reason);
+        self.cached_requested_device_name = None;
+        self.cached_supported_output_configs.clear();
+        if self.setup_audio_device() {
+            if self.stream.is_some() {
+                self.stream = None;
+                self.create_stream();
+            }
+            self.last_output_signature = Some(self.current_output_signature());
+            if let Some(metadata) = self.current_metadata.lock().unwrap().clone() {
+                self.emit_output_path_for_metadata(&metadata);
+            }
+        } else {
+            warn!(
+                "AudioPlayer: failed to re-open audio device after '{}'",
+                reason
+            );
+        }
+    }
+
+    fn open_output_device_name(&self) -> Option<String> {
+        self.output_stream_info
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|info| info.device_name.clone())
+    }
+
+    fn output_format(&self) -> (usize, usize) {
+        (
+            self.target_sample_rate.load(Ordering::Relaxed),
+            self.target_channels.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Playing track position as a fraction of its duration, measured in the current
+    /// output format.
+    fn playback_position_fraction(&self) -> Option<f32> {
+        if !self.is_playing.load(Ordering::Relaxed) {
+            return None;
+        }
+        let metadata = self.current_metadata.lock().unwrap().clone()?;
+        let (sample_rate, channels) = self.output_format();
+        if metadata.duration_ms == 0 || sample_rate == 0 || channels == 0 {
+            return None;
+        }
+        let track_id = self.current_track_id.lock().unwrap().clone();
+        let start_pos = self
+            .cached_track_indices
+            .lock()
+            .unwrap()
+            .get(&track_id)
+            .map(|index| index.start)?;
+        let elapsed_samples = self
+            .current_track_position
+            .load(Ordering::Relaxed)
+            .saturating_sub(start_pos);
+        let elapsed_ms = self.current_track_offset_ms.load(Ordering::Relaxed) as f64
+            + elapsed_samples as f64 * 1000.0 / (sample_rate as f64 * channels as f64);
+        Some((elapsed_ms / metadata.duration_ms as f64).clamp(0.0, 1.0) as f32)
+    }
+
+    /// Re-opens output on another device and keeps playing from the same position.
+    /// Queued samples already match the output format unless the new device forced a
+    /// different one; in that case the track is re-decoded from where it was.
+    fn switch_output_device(&mut self, reason: &str) {
+        let format_before = self.output_format();
+        let position = self.playback_position_fraction();
+        self.reopen_audio_device(reason);
+        if self.output_format() != format_before {
+            if let Some(position) = position {
+                let _ = self
+                    .bus_sender
+                    .send(Message::Playback(PlaybackMessage::Seek(position)));
+            }
+        }
+    }
+
+    /// Moves playback off an output device that disappeared (for example an unplugged
+    /// USB DAC). When it was the configured device, playback continues on the system
+    /// default until the device returns.
+    fn handle_output_device_lost(&mut self, device_name: &str) {
+        if self.open_output_device_name().as_deref() != Some(device_name) {
+            // Report from a stream that was already replaced.
+            return;
+        }
+        warn!("AudioPlayer: output device '{}' was lost", device_name);
+        self.switch_output_device(&format!("output device '{device_name}' lost"));
+        let Some(fallback_device) = self.open_output_device_name() else {
+            return;
+        };
+        let configured_device = self.target_output_device_name.lock().unwrap().clone();
+        if fallback_device != device_name && configured_device.as_deref() == Some(device_name) {
+            *self.failed_over_device_name.lock().unwrap() = Some(device_name.to_string());
+            let _ = self
+                .bus_sender
+                .send(Message::Config(ConfigMessage::OutputDeviceFailover {
+                    preferred_device: device_name.to_string(),
+                    active_device: fallback_device,
+                    failed_over: true,
+                }));
+        }
+    }
+
+    /// Moves playback back to the configured device after it was reconnected.
+    fn handle_output_device_returned(&mut self, device_name: &str) {
+        if self.failed_over_device_name.lock().unwrap().as_deref() != Some(device_name) {
+            return;
+        }
+        self.switch_output_device(&format!("output device '{device_name}' returned"));
+        if self.open_output_device_name().as_deref() != Some(device_name) {
+            return;
+        }
+        *self.failed_over_device_name.lock().unwrap() = None;
+        debug!("AudioPlayer: moved back to output device '{}'", device_name);
+        let _ = self
+            .bus_sender
+            .send(Message::Config(ConfigMessage::OutputDeviceFailover {
+                preferred_device: device_name.to_string(),
+                active_device: device_name.to_string(),
+                failed_over: false,
+            }));
+    }
+
     fn flush_staged_runtime_output_sample_rate_if_idle(&mut self) {
         if self.is_playing.load(Ordering::Relaxed) {
             return;
@@ -945,6 +1221,7 @@ impl AudioPlayer {
         if let Some(device_name) = latest_output.output_device_name.as_deref() {
             next_output_signature.device_name =
                 Self::canonicalize_requested_device_name(device_name);
+            *self.failed_over_device_name.lock().unwrap() = None;
         }
         if let Some(sample_rate_hz) = latest_output.sample_rate_khz {
             next_output_signature.sample_rate_hz = sample_rate_hz.max(8_000);
@@ -999,6 +1276,45 @@ impl AudioPlayer {
         }
     }
 
+    /// Whether an output device named `device_name` is currently listed by the host.
+    fn output_device_connected(device_name: &str) -> bool {
+        cpal::default_host()
+            .output_devices()
+            .map(|mut devices| {
+                devices.any(|device| device.name().ok().as_deref() == Some(device_name))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Stream error callback that reports a vanished output device once per stream, so
+    /// the player can fail over to another device. `device_connected` looks the device up
+    /// on the host.
+    fn stream_error_callback(
+        bus_sender: Sender<Message>,
+        device_name: String,
+        device_connected: impl Fn(&str) -> bool + Send + 'static,
+    ) -> impl FnMut(cpal::StreamError) + Send + 'static {
+        let mut reported = false;
+        move |err| {
+            error!("Audio stream error: {}", err);
+            if reported {
+                return;
+            }
+            // Unplugged devices surface as backend errors on some hosts (ALSA, PulseAudio),
+            // but so do transient xruns; only a device gone from the host counts as lost.
+            let lost = match err {
+                cpal::StreamError::DeviceNotAvailable => true,
+                cpal::StreamError::BackendSpecific { .. } => !device_connected(&device_name),
+            };
+            if lost {
+                reported = true;
+                let _ = bus_sender.send(Message::Config(ConfigMessage::OutputDeviceLost {
+                    device_name: device_name.clone(),
+                }));
+            }
+        }
+    }
+
     fn create_stream(&mut self) {
         if self.stream.is_some() {
             return;
@@ -1013,6 +1329,7 @@ impl AudioPlayer {
             return;
         };
         let sample_format = self.sample_format.unwrap_or(cpal::SampleFormat::F32);
+        let device_name = device.name().unwrap_or_default();
 
         let sample_queue = self.sample_queue.clone();
         let queue_start_position = self.queue_start_position.clone();
@@ -1024,6 +1341,16 @@ impl AudioPlayer {
         let current_track_position = self.current_track_position.clone();
         let volume = self.volume.clone();
         let dither_on_bitdepth_reduce = self.dither_on_bitdepth_reduce;
+        self.dsp_controls.set_output_device(&device_name);
+        if let Some(preset_name) = self.dsp_controls.active_eq_preset_name() {
+            debug!(
+                "AudioPlayer: EQ preset '{}' active on '{}'",
+                preset_name, device_name
+            );
+        }
+        let dsp_controls = self.dsp_controls.clone();
+        let mut dsp_chain = DspChain::new(config.sample_rate.0, config.channels);
+        spectrogram::live_tap().configure(config.sample_rate.0, config.channels);
 
         let stream_result = match sample_format {
             cpal::SampleFormat::F32 => device.build_output_stream(
@@ -1040,11 +1367,17 @@ impl AudioPlayer {
                         &bus_sender_clone,
                         &current_track_position,
                         &volume,
+                        &dsp_controls,
+                        &mut dsp_chain,
                         |sample| sample.clamp(-1.0, 1.0),
                         0.0,
                     );
                 },
-                |err| error!("Audio stream error: {}", err),
+                Self::stream_error_callback(
+                    self.bus_sender.clone(),
+                    device_name,
+                    Self::output_device_connected,
+                ),
                 None,
             ),
             cpal::SampleFormat::I16 => {
@@ -1063,6 +1396,8 @@ impl AudioPlayer {
                             &bus_sender_clone,
                             &current_track_position,
                             &volume,
+                            &dsp_controls,
+                            &mut dsp_chain,
                             |sample| {
                                 Self::quantize_i16(
                                     sample,
@@ -1073,7 +1408,11 @@ impl AudioPlayer {
                             0,
                         );
                     },
-                    |err| error!("Audio stream error: {}", err),
+                    Self::stream_error_callback(
+                        self.bus_sender.clone(),
+                        device_name,
+                        Self::output_device_connected,
+                    ),
                     None,
                 )
             }
@@ -1093,6 +1432,8 @@ impl AudioPlayer {
                             &bus_sender_clone,
                             &current_track_position,
                             &volume,
+                            &dsp_controls,
+                            &mut dsp_chain,
                             |sample| {
                                 Self::quantize_u16(
                                     sample,
@@ -1103,7 +1444,11 @@ impl AudioPlayer {
                             u16::MAX / 2 + 1,
                         );
                     },
-                    |err| error!("Audio stream error: {}", err),
+                    Self::stream_error_callback(
+                        self.bus_sender.clone(),
+                        device_name,
+                        Self::output_device_connected,
+                    ),
                     None,
                 )
             }
@@ -1178,6 +1523,7 @@ impl AudioPlayer {
                 play_immediately,
                 technical_metadata,
                 start_offset_ms,
+                normalization_gain_db,
             } => {
                 self.decode_bootstrap_pending
                     .store(false, Ordering::Relaxed);
@@ -1186,6 +1532,7 @@ impl AudioPlayer {
                 queue.push_back(AudioQueueEntry::TrackHeader(TrackHeader {
                     id: id.clone(),
                     start_offset_ms,
+                    normalization_gain_db,
                 }));
                 drop(queue);
                 self.queue_end_position.fetch_add(1, Ordering::Relaxed);
@@ -1426,7 +1773,12 @@ impl AudioPlayer {
                                 crate::protocol::ConfigDeltaEntry::Cast(_)
                                 | crate::protocol::ConfigDeltaEntry::Ui(_)
                                 | crate::protocol::ConfigDeltaEntry::Library(_)
-                                | crate::protocol::ConfigDeltaEntry::Integrations(_) => {}
+                                | crate::protocol::ConfigDeltaEntry::Integrations(_)
+                                | crate::protocol::ConfigDeltaEntry::Announcements(_)
+                                | crate::protocol::ConfigDeltaEntry::Power(_) => {}
+                                crate::protocol::ConfigDeltaEntry::Dsp(dsp) => {
+                                    self.dsp_controls.apply_delta(&dsp);
+                                }
                             }
                         }
                         if !latest_buffering.is_empty() {
@@ -1454,6 +1806,14 @@ impl AudioPlayer {
                                 .store(target_buffer_ms, Ordering::Relaxed);
                             self.buffer_request_interval_ms
                                 .store(request_interval_ms, Ordering::Relaxed);
+                            if let Some(decoded_cache_max_mb) =
+                                latest_buffering.decoded_cache_max_mb
+                            {
+                                self.decoded_cache_max_bytes.store(
+                                    Self::megabytes_to_bytes(decoded_cache_max_mb),
+                                    Ordering::Relaxed,
+                                );
+                            }
                         }
                         self.stage_or_apply_output_config_delta(latest_output);
                     }
@@ -1462,10 +1822,32 @@ impl AudioPlayer {
                     }) => {
                         self.stage_or_apply_runtime_output_sample_rate_change(sample_rate_hz);
                     }
+                    Message::Config(ConfigMessage::ReopenAudioDevice { reason }) => {
+                        self.reopen_audio_device(&reason);
+                    }
+                    Message::Config(ConfigMessage::OutputDeviceLost { device_name }) => {
+                        self.handle_output_device_lost(&device_name);
+                    }
+                    Message::Config(ConfigMessage::OutputDeviceReturned { device_name }) => {
+                        self.handle_output_device_returned(&device_name);
+                    }
+                    Message::Config(ConfigMessage::BatterySaverChanged { active }) => {
+                        self.battery_saver_active.store(active, Ordering::Relaxed);
+                        debug!("AudioPlayer: battery saver active={}", active);
+                    }
                     Message::Playback(PlaybackMessage::SetVolume(volume)) => {
-                        let clamped = volume.clamp(0.0, 1.0);
-                        self.volume.store(clamped.to_bits(), Ordering::Relaxed);
-                        debug!("AudioPlayer: Volume set to {:.2}", clamped);
+                        self.user_volume = volume.clamp(0.0, 1.0);
+                        self.store_effective_volume();
+                        debug!("AudioPlayer: Volume set to {:.2}", self.user_volume);
+                    }
+                    Message::Playback(PlaybackMessage::SetDuckGain(gain)) => {
+                        self.duck_gain = gain.clamp(0.0, 1.0);
+                        self.store_effective_volume();
+                        debug!("AudioPlayer: Duck gain set to {:.2}", self.duck_gain);
+                    }
+                    Message::Playback(PlaybackMessage::SetDspBypass(bypassed)) => {
+                        self.dsp_controls.set_bypassed(bypassed);
+                        debug!("AudioPlayer: DSP chain bypassed={}", bypassed);
                     }
                     _ => {}
                 },
@@ -1481,7 +1863,8 @@ impl AudioPlayer {
 #[cfg(test)]
 mod tests {
     use super::{AudioPlayer, AudioQueueEntry, TrackHeader};
-    use crate::config::{BufferingConfig, Config, OutputConfig};
+    use crate::config::{BufferingConfig, Config, DspConfig, OutputConfig};
+    use crate::dsp_chain::{DspChain, DspControls};
     use crate::protocol::{
         AudioPacket, Message, OutputConfigDelta, PlaybackMessage, TechnicalMetadata,
     };
@@ -1517,6 +1900,22 @@ mod tests {
         assert_eq!(request, 30_000);
     }
 
+    #[test]
+    fn test_apply_decoded_cache_ceiling_refuses_when_full() {
+        assert_eq!(
+            AudioPlayer::apply_decoded_cache_ceiling(40_000, 10_000, 100_000),
+            40_000
+        );
+        assert_eq!(
+            AudioPlayer::apply_decoded_cache_ceiling(40_000, 80_000, 100_000),
+            20_000
+        );
+        assert_eq!(
+            AudioPlayer::apply_decoded_cache_ceiling(40_000, 120_000, 100_000),
+            0
+        );
+    }
+
     #[test]
     fn test_compute_decode_request_samples_for_state_without_active_track() {
         let request =
@@ -1573,6 +1972,63 @@ mod tests {
         assert_eq!(signature.device_name, None);
     }
 
+    #[test]
+    fn test_stream_error_callback_reports_lost_device_once() {
+        let (bus_sender, mut bus_receiver) = broadcast::channel(8);
+        let mut on_error =
+            AudioPlayer::stream_error_callback(bus_sender, "USB DAC".to_string(), |_| true);
+        on_error(cpal::StreamError::DeviceNotAvailable);
+        on_error(cpal::StreamError::DeviceNotAvailable);
+
+        assert!(matches!(
+            bus_receiver.try_recv(),
+            Ok(Message::Config(crate::protocol::ConfigMessage::OutputDeviceLost { device_name }))
+                if device_name == "USB DAC"
+        ));
+        assert!(matches!(bus_receiver.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_backend_stream_errors_only_report_a_device_gone_from_the_host() {
+        let xrun = || cpal::StreamError::BackendSpecific {
+            err: cpal::BackendSpecificError {
+                description: "alsa::poll() returned POLLERR".to_string(),
+            },
+        };
+        let (bus_sender, mut bus_receiver) = broadcast::channel(8);
+        let mut on_error =
+            AudioPlayer::stream_error_callback(bus_sender.clone(), "USB DAC".to_string(), |_| true);
+        on_error(xrun());
+        assert!(matches!(bus_receiver.try_recv(), Err(TryRecvError::Empty)));
+
+        let mut on_error =
+            AudioPlayer::stream_error_callback(bus_sender, "USB DAC".to_string(), |name| {
+                name != "USB DAC"
+            });
+        on_error(xrun());
+        assert!(matches!(
+            bus_receiver.try_recv(),
+            Ok(Message::Config(crate::protocol::ConfigMessage::OutputDeviceLost { device_name }))
+                if device_name == "USB DAC"
+        ));
+    }
+
+    #[test]
+    fn test_output_device_loss_from_replaced_stream_is_ignored() {
+        let (bus_sender, bus_receiver) = broadcast::channel(32);
+        let mut player = AudioPlayer::new(
+            bus_receiver,
+            bus_sender,
+            OutputConfig::default(),
+            BufferingConfig::default(),
+            DspConfig::default(),
+        );
+        *player.output_stream_info.lock().unwrap() = None;
+
+        player.handle_output_device_lost("USB DAC");
+        assert_eq!(*player.failed_over_device_name.lock().unwrap(), None);
+    }
+
     #[test]
     fn test_output_config_changes_are_staged_until_session_is_idle() {
         let (bus_sender, bus_receiver) = broadcast::channel(32);
@@ -1581,6 +2037,7 @@ mod tests {
             bus_sender,
             OutputConfig::default(),
             BufferingConfig::default(),
+            DspConfig::default(),
         );
 
         player.set_playback_session_active(true);
@@ -1609,6 +2066,7 @@ mod tests {
             bus_sender,
             OutputConfig::default(),
             BufferingConfig::default(),
+            DspConfig::default(),
         );
 
         let initial_rate = player.target_sample_rate.load(Ordering::Relaxed) as u32;
@@ -1632,12 +2090,14 @@ mod tests {
             AudioQueueEntry::TrackHeader(TrackHeader {
                 id: "t1".to_string(),
                 start_offset_ms: 0,
+                normalization_gain_db: 0.0,
             }),
             AudioQueueEntry::Samples(vec![0.5, 0.25]),
             AudioQueueEntry::TrackFooter("t1".to_string()),
             AudioQueueEntry::TrackHeader(TrackHeader {
                 id: "t2".to_string(),
                 start_offset_ms: 0,
+                normalization_gain_db: 0.0,
             }),
             AudioQueueEntry::Samples(vec![0.9, 0.8]),
         ])));
@@ -1661,6 +2121,8 @@ mod tests {
             &bus_sender,
             &current_track_position,
             &volume,
+            &DspControls::new(&DspConfig::default()),
+            &mut DspChain::new(48_000, 2),
             |sample| sample,
             0.0f32,
         );
@@ -1714,6 +2176,7 @@ mod tests {
             bus_sender,
             OutputConfig::default(),
             BufferingConfig::default(),
+            DspConfig::default(),
         );
         let metadata = TechnicalMetadata {
             format: "FLAC".to_string(),
@@ -1729,6 +2192,7 @@ mod tests {
             play_immediately: true,
             technical_metadata: metadata,
             start_offset_ms: 0,
+            normalization_gain_db: 0.0,
         });
 
         assert_eq!(
@@ -1753,6 +2217,7 @@ mod tests {
             bus_sender,
             OutputConfig::default(),
             BufferingConfig::default(),
+            DspConfig::default(),
         );
         let metadata = TechnicalMetadata {
             format: "MP3".to_string(),
@@ -1768,6 +2233,7 @@ mod tests {
             play_immediately: true,
             technical_metadata: metadata,
             start_offset_ms: 0,
+            normalization_gain_db: 0.0,
         });
         assert_eq!(
             player.pending_immediate_start_track_id.as_deref(),
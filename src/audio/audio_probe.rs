@@ -248,7 +248,7 @@ fn probe_output_device(
 
     for rate in candidate_rates {
         let mut candidates = configs_by_rate.remove(&rate).unwrap_or_default();
-        candidates.sort_by_key(stream_probe_sort_key);
+        candidates.sort_by_key(|config| stream_probe_sort_key(config, 2));
         for config in candidates {
             if probe_stream_config(device, &config) {
                 verified_sample_rates.insert(rate);
@@ -267,8 +267,34 @@ fn probe_output_device(
     }
 }
 
-fn stream_probe_sort_key(config: &cpal::SupportedStreamConfig) -> (u16, u8, u16) {
-    let channel_penalty = config.channels().abs_diff(2);
+/// Stream configs the device offers at `sample_rate_hz`, best match for
+/// `preferred_channels` first, in the order the probe tries them.
+pub fn stream_configs_for_rate(
+    device: &cpal::Device,
+    sample_rate_hz: u32,
+    preferred_channels: u16,
+) -> Vec<cpal::SupportedStreamConfig> {
+    let mut candidates: Vec<cpal::SupportedStreamConfig> = device
+        .supported_output_configs()
+        .map(|configs| {
+            configs
+                .filter(|range| {
+                    sample_rate_hz >= range.min_sample_rate().0
+                        && sample_rate_hz <= range.max_sample_rate().0
+                })
+                .map(|range| range.with_sample_rate(cpal::SampleRate(sample_rate_hz)))
+                .collect()
+        })
+        .unwrap_or_default();
+    candidates.sort_by_key(|config| stream_probe_sort_key(config, preferred_channels));
+    candidates
+}
+
+fn stream_probe_sort_key(
+    config: &cpal::SupportedStreamConfig,
+    preferred_channels: u16,
+) -> (u16, u8, u16) {
+    let channel_penalty = config.channels().abs_diff(preferred_channels);
     let format_penalty = match config.sample_format() {
         cpal::SampleFormat::F32 => 0,
         cpal::SampleFormat::I16 => 1,
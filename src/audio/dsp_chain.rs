@@ -0,0 +1,512 @@
+//! Output DSP chain run by the audio callback.
+//!
+//! Each interleaved sample passes through the loudness normalization gain of its track,
+//! the parametric EQ of the output device, the night-mode compressor, the user volume,
+//! the pre-amp and finally a soft limiter, so EQ, normalization and pre-amp boosts never
+//! clip the output. The decoder sends each track's normalization gain in its header and
+//! the chain switches to it where the track starts. Settings live in [`DspControls`],
+//! shared with the player thread through atomics, and every stream owns a [`DspChain`]
+//! holding the per-stream filter state.
+//!
+//! The chain can be bypassed for A/B comparison. The processed and volume-only signals
+//! are crossfaded over a few milliseconds so switching never clicks, and the filters keep
+//! running while bypassed so switching back starts from settled state.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{DspConfig, EqPresetConfig};
+use crate::night_mode::NightModeCompressor;
+use crate::parametric_eq::ParametricEq;
+use crate::protocol::DspConfigDelta;
+
+/// Pre-amp choices offered by the settings dialog, in dB.
+pub const PREAMP_CHOICES_DB: &[f32] = &[-6.0, -3.0, 0.0, 3.0, 6.0, 9.0, 12.0];
+/// Limiter headroom choices offered by the settings dialog, in dB below full scale.
+pub const LIMITER_HEADROOM_CHOICES_DB: &[f32] = &[0.0, 0.3, 0.5, 1.0, 2.0, 3.0];
+/// Pre-amp range accepted from the config file.
+pub const PREAMP_RANGE_DB: (f32, f32) = (-12.0, 12.0);
+/// Limiter headroom range accepted from the config file.
+pub const LIMITER_HEADROOM_RANGE_DB: (f32, f32) = (0.0, 6.0);
+/// Integrated loudness normalized tracks are brought to (the ReplayGain 2.0 reference).
+pub const NORMALIZATION_TARGET_LUFS: f32 = -18.0;
+/// Largest boost or cut normalization applies, so near-silent or broken measurements
+/// cannot produce extreme gains.
+const NORMALIZATION_MAX_GAIN_DB: f32 = 12.0;
+/// Limiting starts this far below the ceiling and approaches it smoothly.
+const LIMITER_KNEE_DB: f32 = 3.0;
+const LIMITER_RELEASE_MS: f32 = 80.0;
+/// Crossfade length when the chain is bypassed or brought back.
+const BYPASS_CROSSFADE_MS: f32 = 20.0;
+
+/// Settings label for a dB value, e.g. `+3 dB` or `0.5 dB`.
+pub fn db_choice_label(db: f32, signed: bool) -> String {
+    let text = format!("{db:.1}");
+    let text = text.trim_end_matches(".0");
+    if signed && db > 0.0 {
+        format!("+{text} dB")
+    } else {
+        format!("{text} dB")
+    }
+}
+
+/// Index of the offered choice closest to `db`.
+pub fn db_choice_index(choices: &[f32], db: f32) -> usize {
+    choices
+        .iter()
+        .enumerate()
+        .min_by(|(_, left), (_, right)| (*left - db).abs().total_cmp(&(*right - db).abs()))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Gain in dB that brings a track measured at `integrated_lufs` to the normalization target.
+pub fn normalization_gain_db(integrated_lufs: f32) -> f32 {
+    if !integrated_lufs.is_finite() {
+        return 0.0;
+    }
+    (NORMALIZATION_TARGET_LUFS - integrated_lufs)
+        .clamp(-NORMALIZATION_MAX_GAIN_DB, NORMALIZATION_MAX_GAIN_DB)
+}
+
+pub(crate) fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// EQ presets with their device assignments, and the preset picked for the open device.
+#[derive(Debug)]
+struct EqSelection {
+    /// Only the EQ preset and device assignment fields are read.
+    config: DspConfig,
+    output_device_name: String,
+    active: Option<Arc<EqPresetConfig>>,
+}
+
+impl EqSelection {
+    fn reselect(&mut self) {
+        self.active = self
+            .config
+            .eq_preset_for_device(&self.output_device_name)
+            .cloned()
+            .map(Arc::new);
+    }
+}
+
+/// DSP settings shared between the player thread and its output callbacks.
+#[derive(Debug)]
+pub struct DspControls {
+    night_mode_strength_percent: AtomicU32,
+    preamp_db_bits: AtomicU32,
+    limiter_enabled: AtomicBool,
+    limiter_headroom_db_bits: AtomicU32,
+    eq_selection: Mutex<EqSelection>,
+    /// Bumped whenever the active EQ preset changes; streams rebuild their filters then.
+    eq_generation: AtomicU32,
+    /// Normalization gain of the track at the output position, kept for rebuilt streams.
+    normalization_gain_bits: AtomicU32,
+    /// Runtime-only A/B switch; never persisted.
+    bypassed: AtomicBool,
+}
+
+impl DspControls {
+    pub fn new(config: &DspConfig) -> Self {
+        let mut eq_selection = EqSelection {
+            config: config.clone(),
+            output_device_name: String::new(),
+            active: None,
+        };
+        eq_selection.reselect();
+        Self {
+            night_mode_strength_percent: AtomicU32::new(config.night_mode_strength_percent),
+            preamp_db_bits: AtomicU32::new(config.preamp_db.to_bits()),
+            limiter_enabled: AtomicBool::new(config.limiter_enabled),
+            limiter_headroom_db_bits: AtomicU32::new(config.limiter_headroom_db.to_bits()),
+            eq_selection: Mutex::new(eq_selection),
+            eq_generation: AtomicU32::new(1),
+            normalization_gain_bits: AtomicU32::new(1f32.to_bits()),
+            bypassed: AtomicBool::new(false),
+        }
+    }
+
+    /// Bypasses the chain (EQ, normalization, night mode, pre-amp and limiter) or brings
+    /// it back; running streams crossfade on their next buffer.
+    pub fn set_bypassed(&self, bypassed: bool) {
+        self.bypassed.store(bypassed, Ordering::Relaxed);
+    }
+
+    /// Picks the EQ preset assigned to the output device a stream was opened on.
+    pub fn set_output_device(&self, output_device_name: &str) {
+        self.update_eq_selection(|selection| {
+            selection.output_device_name = output_device_name.to_string();
+        });
+    }
+
+    /// Name of the EQ preset running on the open output device, if any.
+    pub fn active_eq_preset_name(&self) -> Option<String> {
+        let selection = self.eq_selection.lock().unwrap();
+        selection.active.as_ref().map(|preset| preset.name.clone())
+    }
+
+    fn update_eq_selection(&self, update: impl FnOnce(&mut EqSelection)) {
+        let mut selection = self.eq_selection.lock().unwrap();
+        let previous = selection.active.clone();
+        update(&mut selection);
+        selection.reselect();
+        if selection.active != previous {
+            self.eq_generation.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// Applies changed settings; running streams pick them up on their next buffer.
+    pub fn apply_delta(&self, delta: &DspConfigDelta) {
+        if let Some(strength) = delta.night_mode_strength_percent {
+            self.night_mode_strength_percent
+                .store(strength, Ordering::Relaxed);
+        }
+        if let Some(preamp_db) = delta.preamp_db {
+            self.preamp_db_bits
+                .store(preamp_db.to_bits(), Ordering::Relaxed);
+        }
+        if let Some(enabled) = delta.limiter_enabled {
+            self.limiter_enabled.store(enabled, Ordering::Relaxed);
+        }
+        if let Some(headroom_db) = delta.limiter_headroom_db {
+            self.limiter_headroom_db_bits
+                .store(headroom_db.to_bits(), Ordering::Relaxed);
+        }
+        if delta.eq_presets.is_some() || delta.eq_device_presets.is_some() {
+            self.update_eq_selection(|selection| {
+                if let Some(presets) = delta.eq_presets.as_ref() {
+                    selection.config.eq_presets = presets.clone();
+                }
+                if let Some(assignments) = delta.eq_device_presets.as_ref() {
+                    selection.config.eq_device_presets = assignments.clone();
+                }
+            });
+        }
+    }
+}
+
+/// Soft-knee peak limiter with instant attack and a smooth release. Peaks above the knee
+/// are bent towards the ceiling and never pass it.
+struct SoftLimiter {
+    enabled: bool,
+    ceiling: f32,
+    knee: f32,
+    gain: f32,
+    release_coeff: f32,
+}
+
+impl SoftLimiter {
+    fn new(sample_rate_hz: u32, channels: u16) -> Self {
+        let samples_per_second = sample_rate_hz.max(1) as f32 * channels.max(1) as f32;
+        let mut limiter = Self {
+            enabled: true,
+            ceiling: 1.0,
+            knee: 1.0,
+            gain: 1.0,
+            release_coeff: (-1.0 / (LIMITER_RELEASE_MS / 1000.0 * samples_per_second)).exp(),
+        };
+        limiter.set_headroom_db(0.0);
+        limiter
+    }
+
+    fn set_headroom_db(&mut self, headroom_db: f32) {
+        let headroom_db =
+            headroom_db.clamp(LIMITER_HEADROOM_RANGE_DB.0, LIMITER_HEADROOM_RANGE_DB.1);
+        self.ceiling = db_to_gain(-headroom_db);
+        self.knee = db_to_gain(-headroom_db - LIMITER_KNEE_DB);
+    }
+
+    /// Output level for a peak of `level`; identity below the knee.
+    fn shaped_level(&self, level: f32) -> f32 {
+        if level <= self.knee {
+            return level;
+        }
+        let span = self.ceiling - self.knee;
+        self.knee + span * ((level - self.knee) / span).tanh()
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled {
+            return sample;
+        }
+        let level = sample.abs();
+        let shaped_level = self.shaped_level(level);
+        let target_gain = if level > self.knee {
+            shaped_level / level
+        } else {
+            1.0
+        };
+        self.gain = if target_gain < self.gain {
+            target_gain
+        } else {
+            target_gain + self.release_coeff * (self.gain - target_gain)
+        };
+        // While the gain releases it can sit above this sample's own target, so the
+        // shaped level stays a hard bound.
+        (sample * self.gain).clamp(-shaped_level, shaped_level)
+    }
+}
+
+/// Per-stream DSP state; call [`DspChain::refresh`] once per output buffer.
+pub struct DspChain {
+    sample_rate_hz: u32,
+    channels: u16,
+    eq: Option<ParametricEq>,
+    eq_generation: u32,
+    normalization_gain: f32,
+    night_mode: NightModeCompressor,
+    preamp_gain: f32,
+    limiter: SoftLimiter,
+    bypassed: bool,
+    /// Share of the processed signal in the output; ramps between 0 and 1 on bypass.
+    processed_mix: f32,
+    processed_mix_step: f32,
+}
+
+impl DspChain {
+    pub fn new(sample_rate_hz: u32, channels: u16) -> Self {
+        let samples_per_second = sample_rate_hz.max(1) as f32 * channels.max(1) as f32;
+        Self {
+            sample_rate_hz,
+            channels,
+            eq: None,
+            eq_generation: 0,
+            normalization_gain: 1.0,
+            night_mode: NightModeCompressor::new(sample_rate_hz, channels),
+            preamp_gain: 1.0,
+            limiter: SoftLimiter::new(sample_rate_hz, channels),
+            bypassed: false,
+            processed_mix: 1.0,
+            processed_mix_step: 1.0 / (BYPASS_CROSSFADE_MS / 1000.0 * samples_per_second),
+        }
+    }
+
+    /// Switches to the normalization gain of a track whose first sample is up next.
+    pub fn start_track(&mut self, controls: &DspControls, normalization_gain_db: f32) {
+        self.normalization_gain = db_to_gain(normalization_gain_db);
+        controls
+            .normalization_gain_bits
+            .store(self.normalization_gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Picks up the latest shared settings.
+    pub fn refresh(&mut self, controls: &DspControls) {
+        let eq_generation = controls.eq_generation.load(Ordering::Acquire);
+        if eq_generation != self.eq_generation {
+            // Never wait on the settings thread here; a busy lock retries next buffer.
+            if let Ok(selection) = controls.eq_selection.try_lock() {
+                self.eq = selection
+                    .active
+                    .as_deref()
+                    .map(|preset| ParametricEq::new(preset, self.sample_rate_hz, self.channels));
+                self.eq_generation = eq_generation;
+            }
+        }
+        if let Some(eq) = self.eq.as_mut() {
+            eq.start_buffer();
+        }
+        self.night_mode
+            .set_strength_percent(controls.night_mode_strength_percent.load(Ordering::Relaxed));
+        self.preamp_gain = db_to_gain(f32::from_bits(
+            controls.preamp_db_bits.load(Ordering::Relaxed),
+        ));
+        self.limiter.enabled = controls.limiter_enabled.load(Ordering::Relaxed);
+        self.limiter.set_headroom_db(f32::from_bits(
+            controls.limiter_headroom_db_bits.load(Ordering::Relaxed),
+        ));
+        self.normalization_gain =
+            f32::from_bits(controls.normalization_gain_bits.load(Ordering::Relaxed));
+        self.bypassed = controls.bypassed.load(Ordering::Relaxed);
+    }
+
+    /// Runs one interleaved sample through the chain with the current volume `gain`.
+    pub fn process(&mut self, sample: f32, gain: f32) -> f32 {
+        let unprocessed = sample * gain;
+        let processed = match self.eq.as_mut() {
+            Some(eq) => eq.process(sample * self.normalization_gain),
+            None => sample * self.normalization_gain,
+        };
+        let processed = self.night_mode.process(processed) * gain * self.preamp_gain;
+        let processed = self.limiter.process(processed);
+
+        let target_mix = if self.bypassed { 0.0 } else { 1.0 };
+        if self.processed_mix < target_mix {
+            self.processed_mix = (self.processed_mix + self.processed_mix_step).min(target_mix);
+        } else if self.processed_mix > target_mix {
+            self.processed_mix = (self.processed_mix - self.processed_mix_step).max(target_mix);
+        }
+        if self.processed_mix >= 1.0 {
+            processed
+        } else {
+            unprocessed + (processed - unprocessed) * self.processed_mix
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain_with(config: &DspConfig) -> DspChain {
+        let mut chain = DspChain::new(48_000, 2);
+        chain.refresh(&DspControls::new(config));
+        chain
+    }
+
+    #[test]
+    fn test_default_chain_passes_normal_levels_through() {
+        let mut chain = chain_with(&DspConfig::default());
+        assert_eq!(chain.process(0.25, 1.0), 0.25);
+        assert_eq!(chain.process(-0.5, 0.5), -0.25);
+    }
+
+    #[test]
+    fn test_limiter_keeps_boosted_peaks_below_ceiling() {
+        let config = DspConfig {
+            preamp_db: 12.0,
+            limiter_headroom_db: 1.0,
+            ..DspConfig::default()
+        };
+        let ceiling = db_to_gain(-1.0);
+        let mut chain = chain_with(&config);
+        for index in 0..48_000 {
+            let sample = if index % 2 == 0 { 0.9 } else { -0.9 };
+            let output = chain.process(sample, 1.0);
+            assert!(output.abs() <= ceiling, "sample {index} reached {output}");
+        }
+    }
+
+    #[test]
+    fn test_disabled_limiter_lets_preamp_through() {
+        let config = DspConfig {
+            preamp_db: 6.0,
+            limiter_enabled: false,
+            ..DspConfig::default()
+        };
+        let mut chain = chain_with(&config);
+        assert!(chain.process(0.9, 1.0) > 1.0);
+    }
+
+    #[test]
+    fn test_bypass_crossfades_to_volume_only_output() {
+        let config = DspConfig {
+            preamp_db: 6.0,
+            limiter_enabled: false,
+            ..DspConfig::default()
+        };
+        let controls = DspControls::new(&config);
+        let mut chain = DspChain::new(48_000, 2);
+        chain.refresh(&controls);
+        let processed = chain.process(0.25, 0.5);
+        assert!(
+            (processed - 0.25).abs() < 1e-3,
+            "pre-amp lifts 0.125 by 6 dB"
+        );
+
+        controls.set_bypassed(true);
+        chain.refresh(&controls);
+        // A little over 20 ms of stereo at 48 kHz.
+        let outputs: Vec<f32> = (0..2_000).map(|_| chain.process(0.25, 0.5)).collect();
+        assert!(outputs
+            .windows(2)
+            .all(|pair| pair[1] <= pair[0] && pair[0] - pair[1] < 1e-3));
+        assert_eq!(*outputs.last().unwrap(), 0.125);
+
+        controls.set_bypassed(false);
+        chain.refresh(&controls);
+        let first = chain.process(0.25, 0.5);
+        assert!(first > 0.125 && first < 0.126);
+    }
+
+    #[test]
+    fn test_track_normalization_gain_is_bypassed_and_survives_refresh() {
+        let config = DspConfig {
+            limiter_enabled: false,
+            ..DspConfig::default()
+        };
+        let controls = DspControls::new(&config);
+        let mut chain = DspChain::new(48_000, 2);
+        chain.refresh(&controls);
+        chain.start_track(&controls, -6.0);
+        assert!((chain.process(0.5, 1.0) - db_to_gain(-6.0) * 0.5).abs() < 1e-6);
+
+        // A stream rebuilt mid-track keeps the gain of the playing track.
+        let mut rebuilt = DspChain::new(44_100, 2);
+        rebuilt.refresh(&controls);
+        assert!((rebuilt.process(0.5, 1.0) - db_to_gain(-6.0) * 0.5).abs() < 1e-6);
+
+        controls.set_bypassed(true);
+        rebuilt.refresh(&controls);
+        rebuilt.processed_mix = 0.0;
+        assert_eq!(rebuilt.process(0.5, 1.0), 0.5);
+    }
+
+    #[test]
+    fn test_db_choice_labels_and_indices() {
+        assert_eq!(db_choice_label(3.0, true), "+3 dB");
+        assert_eq!(db_choice_label(-6.0, true), "-6 dB");
+        assert_eq!(db_choice_label(0.5, false), "0.5 dB");
+        assert_eq!(db_choice_index(PREAMP_CHOICES_DB, 0.0), 2);
+        assert_eq!(db_choice_index(LIMITER_HEADROOM_CHOICES_DB, 0.9), 3);
+    }
+
+    #[test]
+    fn test_eq_preset_follows_output_device_assignment() {
+        use crate::config::{EqDevicePresetConfig, EqFilterConfig, EqFilterKind};
+
+        let preset = |name: &str| EqPresetConfig {
+            name: name.to_string(),
+            target: String::new(),
+            preamp_db: -6.0,
+            filters: vec![EqFilterConfig {
+                kind: EqFilterKind::Peaking,
+                frequency_hz: 1_000.0,
+                gain_db: 3.0,
+                q: 1.0,
+            }],
+        };
+        let assign = |device: &str, preset_name: &str| EqDevicePresetConfig {
+            output_device_name: device.to_string(),
+            preset_name: preset_name.to_string(),
+        };
+        let config = DspConfig {
+            eq_presets: vec![preset("Headphones"), preset("Speakers")],
+            eq_device_presets: vec![
+                assign("default", "Speakers"),
+                assign("USB DAC", "Headphones"),
+            ],
+            ..DspConfig::default()
+        };
+        let controls = DspControls::new(&config);
+        assert_eq!(
+            controls.active_eq_preset_name().as_deref(),
+            Some("Speakers")
+        );
+        controls.set_output_device("USB DAC");
+        assert_eq!(
+            controls.active_eq_preset_name().as_deref(),
+            Some("Headphones")
+        );
+
+        let mut chain = DspChain::new(48_000, 2);
+        chain.refresh(&controls);
+        assert!(chain.eq.is_some());
+        controls.apply_delta(&DspConfigDelta {
+            eq_device_presets: Some(Vec::new()),
+            ..DspConfigDelta::default()
+        });
+        assert_eq!(controls.active_eq_preset_name(), None);
+        chain.refresh(&controls);
+        assert!(chain.eq.is_none());
+    }
+
+    #[test]
+    fn test_normalization_gain_targets_reference_loudness() {
+        assert_eq!(normalization_gain_db(-23.0), 5.0);
+        assert_eq!(normalization_gain_db(-9.0), -9.0);
+        assert_eq!(normalization_gain_db(-70.0), 12.0);
+        assert_eq!(normalization_gain_db(f32::NEG_INFINITY), 0.0);
+    }
+}
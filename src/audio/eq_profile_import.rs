@@ -0,0 +1,203 @@
+//! Import of AutoEq and REW parametric EQ exports as named presets.
+//!
+//! Both tools write one filter per line, for example
+//! `Filter 2: ON PK Fc 172 Hz Gain -3.6 dB Q 0.93`. AutoEq files also carry a
+//! `Preamp: -6.2 dB` line and are named after the headphone (`HD 600 ParametricEQ.txt`).
+//! REW files start with a `Filter Settings file` header and may list disabled or `None`
+//! slots, which are skipped.
+
+use std::path::Path;
+
+use crate::config::{EqFilterConfig, EqFilterKind, EqPresetConfig};
+use crate::parametric_eq::{self, MAX_EQ_FILTERS};
+
+/// Tool that wrote an imported profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqProfileSource {
+    AutoEq,
+    Rew,
+}
+
+impl EqProfileSource {
+    fn label(self) -> &'static str {
+        match self {
+            Self::AutoEq => "AutoEq",
+            Self::Rew => "REW",
+        }
+    }
+}
+
+/// Headphone or speaker name from an export file name, without AutoEq's suffixes.
+pub fn target_from_file_stem(file_stem: &str) -> String {
+    let mut target = file_stem.trim();
+    for suffix in [" ParametricEQ", " FixedBandEQ", " filters", " Filters"] {
+        if let Some(stripped) = target.strip_suffix(suffix) {
+            target = stripped.trim_end();
+            break;
+        }
+    }
+    target.to_string()
+}
+
+fn number_after(tokens: &[&str], key: &str) -> Option<f32> {
+    let position = tokens
+        .iter()
+        .position(|token| token.eq_ignore_ascii_case(key))?;
+    tokens.get(position + 1)?.parse::<f32>().ok()
+}
+
+fn filter_kind(token: &str) -> Option<EqFilterKind> {
+    match token.to_ascii_uppercase().as_str() {
+        "PK" | "PEQ" | "MODAL" => Some(EqFilterKind::Peaking),
+        "LS" | "LSC" | "LSQ" | "LS6" | "LS12" => Some(EqFilterKind::LowShelf),
+        "HS" | "HSC" | "HSQ" | "HS6" | "HS12" => Some(EqFilterKind::HighShelf),
+        "LP" | "LPQ" => Some(EqFilterKind::LowPass),
+        "HP" | "HPQ" => Some(EqFilterKind::HighPass),
+        _ => None,
+    }
+}
+
+/// Parses one `Filter N: ...` line; `Ok(None)` for disabled or empty slots.
+fn parse_filter_line(line: &str) -> Result<Option<EqFilterConfig>, String> {
+    let Some((_, settings)) = line.split_once(':') else {
+        return Err(format!("Unreadable filter line: {line}"));
+    };
+    let tokens: Vec<&str> = settings.split_whitespace().collect();
+    match tokens.first() {
+        Some(state) if state.eq_ignore_ascii_case("ON") => {}
+        _ => return Ok(None),
+    }
+    let Some(kind_token) = tokens.get(1) else {
+        return Ok(None);
+    };
+    if kind_token.eq_ignore_ascii_case("None") {
+        return Ok(None);
+    }
+    let kind = filter_kind(kind_token)
+        .ok_or_else(|| format!("Unsupported filter type \"{kind_token}\" in: {line}"))?;
+    let frequency_hz =
+        number_after(&tokens, "Fc").ok_or_else(|| format!("Missing Fc frequency in: {line}"))?;
+    let gain_db = number_after(&tokens, "Gain").unwrap_or(0.0);
+    let q = match number_after(&tokens, "Q") {
+        Some(q) => q,
+        None if kind == EqFilterKind::Peaking => {
+            return Err(format!("Missing Q for peaking filter in: {line}"));
+        }
+        None => std::f32::consts::FRAC_1_SQRT_2,
+    };
+    let filter = EqFilterConfig {
+        kind,
+        frequency_hz,
+        gain_db,
+        q,
+    };
+    if !parametric_eq::is_valid_eq_filter(&filter) {
+        return Err(format!("Invalid filter settings in: {line}"));
+    }
+    Ok(Some(filter))
+}
+
+/// Parses an exported profile into a preset named after `target` and the source tool.
+pub fn parse_eq_profile(text: &str, target: &str) -> Result<EqPresetConfig, String> {
+    let source = if text.contains("Filter Settings file") || text.contains("Room EQ") {
+        EqProfileSource::Rew
+    } else {
+        EqProfileSource::AutoEq
+    };
+    let mut preamp_db = 0.0;
+    let mut filters = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(preamp) = line.strip_prefix("Preamp:") {
+            let tokens: Vec<&str> = preamp.split_whitespace().collect();
+            preamp_db = tokens
+                .first()
+                .and_then(|token| token.parse::<f32>().ok())
+                .ok_or_else(|| format!("Unreadable preamp line: {line}"))?;
+        } else if line.starts_with("Filter") && !line.starts_with("Filter Settings") {
+            if let Some(filter) = parse_filter_line(line)? {
+                filters.push(filter);
+            }
+        }
+    }
+    if filters.is_empty() {
+        return Err(
+            "No enabled filters found; expected an AutoEq or REW parametric EQ export".to_string(),
+        );
+    }
+    if filters.len() > MAX_EQ_FILTERS {
+        return Err(format!(
+            "Profile has {} filters; at most {MAX_EQ_FILTERS} are supported",
+            filters.len()
+        ));
+    }
+    let target = if target.trim().is_empty() {
+        "Imported EQ".to_string()
+    } else {
+        target.trim().to_string()
+    };
+    Ok(EqPresetConfig {
+        name: format!("{target} ({})", source.label()),
+        target,
+        preamp_db,
+        filters,
+    })
+}
+
+/// Reads and parses an exported profile, naming it after the file.
+pub fn import_eq_profile_file(path: &Path) -> Result<EqPresetConfig, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+    let file_stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    parse_eq_profile(&text, &target_from_file_stem(&file_stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_autoeq_profile() {
+        let text = "Preamp: -6.2 dB\n\
+            Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70\n\
+            Filter 2: ON PK Fc 172 Hz Gain -3.6 dB Q 0.93\n\
+            Filter 3: ON HSC Fc 10000 Hz Gain -2.0 dB Q 0.70\n";
+        let preset = parse_eq_profile(text, &target_from_file_stem("HD 600 ParametricEQ"))
+            .expect("autoeq profile should parse");
+        assert_eq!(preset.name, "HD 600 (AutoEq)");
+        assert_eq!(preset.target, "HD 600");
+        assert_eq!(preset.preamp_db, -6.2);
+        assert_eq!(preset.filters.len(), 3);
+        assert_eq!(preset.filters[0].kind, EqFilterKind::LowShelf);
+        assert_eq!(preset.filters[1].frequency_hz, 172.0);
+        assert_eq!(preset.filters[1].gain_db, -3.6);
+        assert_eq!(preset.filters[2].q, 0.70);
+    }
+
+    #[test]
+    fn test_parse_rew_profile_skips_empty_slots() {
+        let text = "Filter Settings file\n\n\
+            Room EQ V5.20\n\
+            Equaliser: Generic\n\n\
+            Filter  1: ON  PK       Fc   63.5 Hz  Gain  -5.0 dB  Q  4.00\n\
+            Filter  2: ON  LS       Fc   100 Hz   Gain   3.0 dB\n\
+            Filter  3: OFF PK       Fc   400 Hz   Gain   2.0 dB  Q  1.00\n\
+            Filter  4: ON  None\n";
+        let preset = parse_eq_profile(text, "Studio monitors").expect("rew profile should parse");
+        assert_eq!(preset.name, "Studio monitors (REW)");
+        assert_eq!(preset.preamp_db, 0.0);
+        assert_eq!(preset.filters.len(), 2);
+        assert_eq!(preset.filters[0].q, 4.0);
+        assert_eq!(preset.filters[1].kind, EqFilterKind::LowShelf);
+        assert_eq!(preset.filters[1].q, std::f32::consts::FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    fn test_parse_rejects_files_without_filters_or_with_unknown_types() {
+        assert!(parse_eq_profile("just some notes", "x").is_err());
+        assert!(parse_eq_profile("Filter 1: ON XYZ Fc 100 Hz Gain 1 dB Q 1", "x").is_err());
+        assert!(parse_eq_profile("Filter 1: ON PK Fc 100 Hz Gain 1 dB", "x").is_err());
+    }
+}
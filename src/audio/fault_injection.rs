@@ -0,0 +1,109 @@
+//! Developer fault injection for the audio pipeline.
+//!
+//! Faults are process-wide switches toggled from the diagnostics section of the settings
+//! dialog, which only shows them when `ROQTUNE_DEVELOPER_MODE=1` is set. They are never
+//! persisted, so a restart always returns to normal playback.
+//!
+//! - Decode errors: every decoded packet fails, so the decoder fills the gap with silence,
+//!   exhausts its error budget, and gives up on the track.
+//! - Device open failures: the configured output device is treated as missing, so the
+//!   player falls back to the system default device the next time it opens the output.
+//! - Underruns: the output callback periodically renders silence without consuming audio,
+//!   as if the decoded cache had run dry.
+
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+/// Environment variable that reveals the fault injection switches.
+pub const DEVELOPER_MODE_ENV: &str = "ROQTUNE_DEVELOPER_MODE";
+/// Every n-th output callback renders an injected underrun.
+const UNDERRUN_INTERVAL_CALLBACKS: u32 = 40;
+
+/// One injectable audio pipeline fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    DecodeErrors,
+    DeviceOpenFailure,
+    Underruns,
+}
+
+/// Faults in the order the settings dialog lists them.
+pub const ALL_FAULTS: [Fault; 3] = [
+    Fault::DecodeErrors,
+    Fault::DeviceOpenFailure,
+    Fault::Underruns,
+];
+
+static ACTIVE_FAULTS: AtomicU8 = AtomicU8::new(0);
+static OUTPUT_CALLBACKS: AtomicU32 = AtomicU32::new(0);
+
+impl Fault {
+    fn bit(self) -> u8 {
+        match self {
+            Self::DecodeErrors => 1,
+            Self::DeviceOpenFailure => 1 << 1,
+            Self::Underruns => 1 << 2,
+        }
+    }
+
+    /// Settings label for the fault switch.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DecodeErrors => "Inject Decode Errors",
+            Self::DeviceOpenFailure => "Fail Output Device Open",
+            Self::Underruns => "Inject Underruns",
+        }
+    }
+}
+
+/// Returns whether the fault injection switches should be shown.
+pub fn developer_mode_enabled() -> bool {
+    std::env::var(DEVELOPER_MODE_ENV).is_ok_and(|value| value == "1")
+}
+
+/// Turns one fault on or off.
+pub fn set_fault_enabled(fault: Fault, enabled: bool) {
+    if enabled {
+        ACTIVE_FAULTS.fetch_or(fault.bit(), Ordering::Relaxed);
+    } else {
+        ACTIVE_FAULTS.fetch_and(!fault.bit(), Ordering::Relaxed);
+    }
+}
+
+/// Returns whether `fault` is currently injected.
+pub fn is_fault_enabled(fault: Fault) -> bool {
+    ACTIVE_FAULTS.load(Ordering::Relaxed) & fault.bit() != 0
+}
+
+/// Returns whether the current output callback should render an injected underrun.
+pub fn take_injected_underrun() -> bool {
+    if !is_fault_enabled(Fault::Underruns) {
+        return false;
+    }
+    underrun_due(OUTPUT_CALLBACKS.fetch_add(1, Ordering::Relaxed))
+}
+
+fn underrun_due(callback_index: u32) -> bool {
+    callback_index % UNDERRUN_INTERVAL_CALLBACKS == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faults_toggle_independently_and_underruns_are_periodic() {
+        // Only the device fault is toggled: the decoder and player tests share these
+        // process-wide switches and must not see injected decode errors or underruns.
+        set_fault_enabled(Fault::DeviceOpenFailure, true);
+        assert!(is_fault_enabled(Fault::DeviceOpenFailure));
+        assert!(!is_fault_enabled(Fault::Underruns));
+        set_fault_enabled(Fault::DeviceOpenFailure, false);
+        assert!(!is_fault_enabled(Fault::DeviceOpenFailure));
+
+        let injected = (0..UNDERRUN_INTERVAL_CALLBACKS * 2)
+            .filter(|index| underrun_due(*index))
+            .count();
+        assert_eq!(injected, 2);
+        assert!(!take_injected_underrun());
+    }
+}
@@ -0,0 +1,177 @@
+//! Format-quality badges (Hi-Res, Lossless, Lossy) derived from technical metadata.
+//!
+//! Lossless tracks are recognized by their codec/container name. MP4 files only count as
+//! lossless when the probe finds a bit depth, which lofty reports for ALAC but not AAC.
+//! A lossless track is hi-res when its bit depth or sample rate reaches the configured
+//! threshold.
+
+use std::path::Path;
+
+use crate::metadata_tags;
+use crate::protocol::TechnicalMetadata;
+
+/// Format of the built-in "Quality" playlist column.
+pub const QUALITY_COLUMN_FORMAT: &str = "{quality}";
+
+const LOSSLESS_FORMATS: &[&str] = &[
+    "FLAC", "ALAC", "WAV", "WAVE", "AIFF", "AIF", "AIFC", "APE", "WV", "TTA", "DSF", "DFF",
+];
+const MP4_FORMATS: &[&str] = &["M4A", "MP4", "M4B"];
+
+/// User-configurable limits at which a lossless track counts as hi-res.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HiResThresholds {
+    pub min_sample_rate_hz: u32,
+    pub min_bit_depth: u16,
+}
+
+/// Quality class shown as a badge for one track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatQuality {
+    HiRes,
+    Lossless,
+    Lossy { bitrate_kbps: u32 },
+}
+
+impl FormatQuality {
+    /// Badge text, e.g. `Hi-Res` or `Lossy 320k`.
+    pub fn label(self) -> String {
+        match self {
+            Self::HiRes => "Hi-Res".to_string(),
+            Self::Lossless => "Lossless".to_string(),
+            Self::Lossy { bitrate_kbps: 0 } => "Lossy".to_string(),
+            Self::Lossy { bitrate_kbps } => format!("Lossy {bitrate_kbps}k"),
+        }
+    }
+
+    /// Sort key ordering lossy tracks by bitrate below lossless and hi-res tracks.
+    pub fn sort_key(self) -> String {
+        match self {
+            Self::Lossy { bitrate_kbps } => format!("0{bitrate_kbps:06}"),
+            Self::Lossless => "1".to_string(),
+            Self::HiRes => "2".to_string(),
+        }
+    }
+}
+
+/// Classifies a track from its technical metadata.
+pub fn classify_format_quality(
+    meta: &TechnicalMetadata,
+    thresholds: HiResThresholds,
+) -> FormatQuality {
+    let format = meta.format.to_ascii_uppercase();
+    let lossless = LOSSLESS_FORMATS.contains(&format.as_str())
+        || (MP4_FORMATS.contains(&format.as_str()) && meta.bits_per_sample > 0);
+    if !lossless {
+        return FormatQuality::Lossy {
+            bitrate_kbps: meta.bitrate_kbps,
+        };
+    }
+    if meta.bits_per_sample >= thresholds.min_bit_depth
+        || meta.sample_rate_hz >= thresholds.min_sample_rate_hz
+    {
+        FormatQuality::HiRes
+    } else {
+        FormatQuality::Lossless
+    }
+}
+
+/// Whether `path` has the extension of a lossless format. MP4 containers are excluded
+/// because they may hold AAC.
+pub fn is_lossless_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            LOSSLESS_FORMATS.contains(&extension.to_ascii_uppercase().as_str())
+        })
+}
+
+/// Reads format properties from the file header without decoding audio.
+///
+/// `bits_per_sample` is 0 when the codec has no fixed bit depth (lossy codecs).
+pub fn probe_track_format(path: &Path) -> Option<TechnicalMetadata> {
+    use lofty::file::AudioFile;
+
+    let tagged = metadata_tags::read_tagged_file(path).ok()?;
+    let properties = tagged.properties();
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("AUDIO")
+        .to_ascii_uppercase();
+    Some(TechnicalMetadata {
+        format,
+        bitrate_kbps: properties
+            .audio_bitrate()
+            .or(properties.overall_bitrate())
+            .unwrap_or(0),
+        sample_rate_hz: properties.sample_rate().unwrap_or(0),
+        channel_count: u16::from(properties.channels().unwrap_or(0)),
+        duration_ms: properties.duration().as_millis() as u64,
+        bits_per_sample: u16::from(properties.bit_depth().unwrap_or(0)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: HiResThresholds = HiResThresholds {
+        min_sample_rate_hz: 88_200,
+        min_bit_depth: 24,
+    };
+
+    fn meta(format: &str, bitrate_kbps: u32, sample_rate_hz: u32, bits: u16) -> TechnicalMetadata {
+        TechnicalMetadata {
+            format: format.to_string(),
+            bitrate_kbps,
+            sample_rate_hz,
+            channel_count: 2,
+            duration_ms: 1_000,
+            bits_per_sample: bits,
+        }
+    }
+
+    #[test]
+    fn test_classify_format_quality_applies_hi_res_thresholds() {
+        let cd = meta("FLAC", 900, 44_100, 16);
+        let studio = meta("flac", 2_800, 96_000, 24);
+        let high_rate_16_bit = meta("WAV", 2_822, 88_200, 16);
+
+        assert_eq!(
+            classify_format_quality(&cd, THRESHOLDS),
+            FormatQuality::Lossless
+        );
+        assert_eq!(
+            classify_format_quality(&studio, THRESHOLDS),
+            FormatQuality::HiRes
+        );
+        assert_eq!(
+            classify_format_quality(&high_rate_16_bit, THRESHOLDS),
+            FormatQuality::HiRes
+        );
+        assert_eq!(
+            classify_format_quality(
+                &cd,
+                HiResThresholds {
+                    min_sample_rate_hz: 44_100,
+                    min_bit_depth: 24,
+                }
+            ),
+            FormatQuality::HiRes
+        );
+    }
+
+    #[test]
+    fn test_classify_format_quality_separates_lossy_and_alac() {
+        let mp3 = classify_format_quality(&meta("MP3", 320, 44_100, 0), THRESHOLDS);
+        let aac = classify_format_quality(&meta("M4A", 256, 44_100, 0), THRESHOLDS);
+        let alac = classify_format_quality(&meta("M4A", 1_100, 44_100, 16), THRESHOLDS);
+
+        assert_eq!(mp3.label(), "Lossy 320k");
+        assert_eq!(aac.label(), "Lossy 256k");
+        assert_eq!(alac, FormatQuality::Lossless);
+        assert!(mp3.sort_key() > aac.sort_key());
+        assert!(alac.sort_key() > mp3.sort_key());
+    }
+}
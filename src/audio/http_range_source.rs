@@ -0,0 +1,193 @@
+//! Seekable HTTP media source that fetches a remote file in `Range` request chunks.
+//!
+//! Decoding starts after the first chunk arrives instead of after a full download, and
+//! seeks only fetch the chunk around the new position. Servers that ignore `Range` and
+//! answer `200 OK` are handled by keeping the whole body in memory.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use symphonia::core::io::MediaSource;
+
+/// Bytes requested per range request.
+const CHUNK_BYTES: u64 = 512 * 1024;
+
+/// Returns the full resource length from a `Content-Range` value such as
+/// `bytes 0-524287/7340032`.
+pub fn parse_content_range_total(value: &str) -> Option<u64> {
+    let (unit, range) = value.trim().split_once(' ')?;
+    if !unit.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (_, total) = range.trim().rsplit_once('/')?;
+    total.trim().parse().ok()
+}
+
+/// Remote file read through HTTP range requests.
+pub struct HttpRangeSource {
+    http_client: ureq::Agent,
+    url: String,
+    authorization: Option<String>,
+    position: u64,
+    total_len: Option<u64>,
+    buffer: Vec<u8>,
+    buffer_start: u64,
+    /// True once the server answered without range support and `buffer` holds the file.
+    whole_body: bool,
+    content_type: Option<String>,
+}
+
+impl HttpRangeSource {
+    /// Opens `url`, fetching the first chunk to learn the length and content type.
+    pub fn open(url: String, authorization: Option<String>) -> Result<Self, String> {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(5))
+            .timeout_read(Duration::from_secs(45))
+            .build();
+        let mut source = Self {
+            http_client,
+            url,
+            authorization,
+            position: 0,
+            total_len: None,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            whole_body: false,
+            content_type: None,
+        };
+        source
+            .fetch_chunk(0)
+            .map_err(|error| format!("Remote stream request failed: {error}"))?;
+        if source.buffer.is_empty() {
+            return Err("Remote stream response was empty".to_string());
+        }
+        Ok(source)
+    }
+
+    /// `Content-Type` reported by the server, if any.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    fn fetch_chunk(&mut self, start: u64) -> io::Result<()> {
+        let end = start + CHUNK_BYTES - 1;
+        let mut request = self
+            .http_client
+            .get(&self.url)
+            .set("Range", &format!("bytes={start}-{end}"));
+        if let Some(authorization) = self.authorization.as_deref() {
+            request = request.set("Authorization", authorization);
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(416, _)) => {
+                // Requested past the end: nothing left to read.
+                self.buffer.clear();
+                self.buffer_start = start;
+                return Ok(());
+            }
+            Err(error) => return Err(io::Error::other(error.to_string())),
+        };
+        if self.content_type.is_none() {
+            self.content_type = response.header("Content-Type").map(ToOwned::to_owned);
+        }
+        let ranged = response.status() == 206;
+        if ranged {
+            if let Some(total) = response
+                .header("Content-Range")
+                .and_then(parse_content_range_total)
+            {
+                self.total_len = Some(total);
+            }
+        }
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+        if ranged {
+            self.buffer_start = start;
+        } else {
+            self.buffer_start = 0;
+            self.total_len = Some(body.len() as u64);
+            self.whole_body = true;
+        }
+        self.buffer = body;
+        Ok(())
+    }
+
+    fn buffered_range_contains(&self, position: u64) -> bool {
+        position >= self.buffer_start && position < self.buffer_start + self.buffer.len() as u64
+    }
+}
+
+impl Read for HttpRangeSource {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if out.is_empty() || self.total_len.is_some_and(|total| self.position >= total) {
+            return Ok(0);
+        }
+        if !self.buffered_range_contains(self.position) {
+            if self.whole_body {
+                return Ok(0);
+            }
+            self.fetch_chunk(self.position)?;
+            if !self.buffered_range_contains(self.position) {
+                return Ok(0);
+            }
+        }
+        let offset = (self.position - self.buffer_start) as usize;
+        let count = out.len().min(self.buffer.len() - offset);
+        out[..count].copy_from_slice(&self.buffer[offset..offset + count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl Seek for HttpRangeSource {
+    fn seek(&mut self, target: SeekFrom) -> io::Result<u64> {
+        let position = match target {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(delta) => {
+                let Some(total) = self.total_len else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "remote stream length is unknown",
+                    ));
+                };
+                total.checked_add_signed(delta)
+            }
+        };
+        let Some(position) = position else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before start of remote stream",
+            ));
+        };
+        self.position = position;
+        Ok(position)
+    }
+}
+
+impl MediaSource for HttpRangeSource {
+    fn is_seekable(&self) -> bool {
+        self.total_len.is_some()
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        self.total_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_content_range_total;
+
+    #[test]
+    fn test_parse_content_range_total() {
+        assert_eq!(
+            parse_content_range_total("bytes 0-524287/7340032"),
+            Some(7_340_032)
+        );
+        assert_eq!(parse_content_range_total("Bytes 10-20/21"), Some(21));
+        assert_eq!(parse_content_range_total("bytes 0-99/*"), None);
+        assert_eq!(parse_content_range_total("items 0-1/2"), None);
+    }
+}
@@ -0,0 +1,295 @@
+//! Heuristic intro/outro detection for broadcast-style recordings.
+//!
+//! Radio rips often start with a DJ talking before the song and end with talk after it.
+//! Detection decodes the file into short loudness windows, then looks for a silence gap
+//! near either end that separates a noticeably quieter segment (speech) from the louder
+//! song body. Results are suggestions stored as ordinary per-track trims, so they can be
+//! adjusted or cleared by hand afterwards.
+
+use std::path::Path;
+
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, formats::FormatOptions, io::MediaSourceStream,
+    meta::MetadataOptions, probe::Hint,
+};
+
+use crate::protocol::TrackTrims;
+use crate::sandbox_access;
+
+/// Length of one loudness window.
+pub const ANALYSIS_WINDOW_MS: u64 = 100;
+/// Floor applied to window loudness so digital silence stays finite.
+const LOUDNESS_FLOOR_DB: f32 = -90.0;
+/// Absolute ceiling for the silence threshold; quiet masters lower it further.
+const MAX_SILENCE_THRESHOLD_DB: f32 = -40.0;
+/// Windows this far below the median loudness also count as silence.
+const SILENCE_BELOW_MEDIAN_DB: f32 = 25.0;
+const MIN_GAP_MS: u64 = 300;
+/// Shortest talk segment worth trimming.
+const MIN_SEGMENT_MS: u64 = 2_000;
+/// Intros must end within this distance of the start of the file.
+const MAX_INTRO_MS: u64 = 60_000;
+/// Outros must start within this distance of the end of the file.
+const MAX_OUTRO_MS: u64 = 90_000;
+/// Length of song audio compared against the candidate talk segment.
+const COMPARISON_SPAN_MS: u64 = 20_000;
+/// Minimum loudness difference between talk and song.
+const MIN_LOUDNESS_DROP_DB: f32 = 3.0;
+
+/// Decodes a local file and detects talk-over intro/outro trims.
+pub fn analyze_file_trims(path: &Path) -> Result<TrackTrims, String> {
+    let energies_db = window_loudness_db(path)?;
+    Ok(detect_intro_outro_trims(&energies_db, ANALYSIS_WINDOW_MS))
+}
+
+/// Decodes the default track of `path`, passing each packet's interleaved samples
+/// with its channel count and sample rate to `visit`.
+pub(crate) fn for_each_decoded_buffer(
+    path: &Path,
+    mut visit: impl FnMut(&[f32], usize, u32),
+) -> Result<(), String> {
+    let input =
+        sandbox_access::open_file(path).map_err(|err| format!("failed to open source: {err}"))?;
+    let mss = MediaSourceStream::new(Box::new(input), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|value| value.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|err| format!("failed to probe source: {err}"))?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| "no default audio track found".to_string())?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|err| format!("failed to create decoder: {err}"))?;
+
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(_) => continue,
+        };
+        let spec = *decoded.spec();
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        visit(buffer.samples(), spec.channels.count().max(1), spec.rate);
+    }
+    Ok(())
+}
+
+/// Decodes `path` into mono RMS loudness (dBFS) per [`ANALYSIS_WINDOW_MS`] window.
+pub(crate) fn window_loudness_db(path: &Path) -> Result<Vec<f32>, String> {
+    let mut energies_db = Vec::new();
+    let mut window_sum_squares = 0.0f64;
+    let mut window_samples = 0usize;
+    for_each_decoded_buffer(path, |samples, channels, rate| {
+        let window_len = (rate as usize * ANALYSIS_WINDOW_MS as usize / 1000).max(1) * channels;
+        for sample in samples {
+            window_sum_squares += (*sample as f64) * (*sample as f64);
+            window_samples += 1;
+            if window_samples >= window_len {
+                energies_db.push(rms_db(window_sum_squares, window_samples));
+                window_sum_squares = 0.0;
+                window_samples = 0;
+            }
+        }
+    })?;
+    if energies_db.is_empty() {
+        return Err("no decodable audio".to_string());
+    }
+    Ok(energies_db)
+}
+
+fn rms_db(sum_squares: f64, samples: usize) -> f32 {
+    let mean_square = sum_squares / samples.max(1) as f64;
+    if mean_square <= 0.0 {
+        return LOUDNESS_FLOOR_DB;
+    }
+    ((10.0 * mean_square.log10()) as f32).max(LOUDNESS_FLOOR_DB)
+}
+
+pub(crate) fn silence_threshold_db(energies_db: &[f32]) -> f32 {
+    let mut sorted = energies_db.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    MAX_SILENCE_THRESHOLD_DB.min(median - SILENCE_BELOW_MEDIAN_DB)
+}
+
+/// Returns `(start, end)` window ranges of silence runs at least `min_windows` long.
+pub(crate) fn silence_gaps(
+    energies_db: &[f32],
+    threshold_db: f32,
+    min_windows: usize,
+) -> Vec<(usize, usize)> {
+    let mut gaps = Vec::new();
+    let mut run_start = None;
+    for (index, energy) in energies_db.iter().enumerate() {
+        match (*energy < threshold_db, run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                if index - start >= min_windows {
+                    gaps.push((start, index));
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = run_start {
+        if energies_db.len() - start >= min_windows {
+            gaps.push((start, energies_db.len()));
+        }
+    }
+    gaps
+}
+
+/// Mean loudness of the non-silent windows, or `None` when everything is silent.
+fn mean_loud_db(energies_db: &[f32], threshold_db: f32) -> Option<f32> {
+    let loud = energies_db
+        .iter()
+        .copied()
+        .filter(|energy| *energy >= threshold_db)
+        .collect::<Vec<f32>>();
+    (!loud.is_empty()).then(|| loud.iter().sum::<f32>() / loud.len() as f32)
+}
+
+/// Detects intro/outro trims from per-window loudness values.
+///
+/// An intro is the audio before the first silence gap within the opening
+/// [`MAX_INTRO_MS`] when it is at least [`MIN_LOUDNESS_DROP_DB`] quieter than the song
+/// audio that follows; the outro is found the same way from the end of the file.
+pub fn detect_intro_outro_trims(energies_db: &[f32], window_ms: u64) -> TrackTrims {
+    let window_ms = window_ms.max(1);
+    let windows_for = |ms: u64| (ms / window_ms).max(1) as usize;
+    let total = energies_db.len();
+    let min_segment = windows_for(MIN_SEGMENT_MS);
+    let comparison_span = windows_for(COMPARISON_SPAN_MS);
+    if total < min_segment * 2 + comparison_span {
+        return TrackTrims::default();
+    }
+    let threshold_db = silence_threshold_db(energies_db);
+    let gaps = silence_gaps(energies_db, threshold_db, windows_for(MIN_GAP_MS));
+
+    let is_quieter_than = |talk: &[f32], song: &[f32]| match (
+        mean_loud_db(talk, threshold_db),
+        mean_loud_db(song, threshold_db),
+    ) {
+        (Some(talk_db), Some(song_db)) => song_db - talk_db >= MIN_LOUDNESS_DROP_DB,
+        _ => false,
+    };
+
+    let intro_end = gaps
+        .iter()
+        .take_while(|(_, end)| *end <= windows_for(MAX_INTRO_MS))
+        .find(|(start, end)| {
+            *start >= min_segment
+                && total - *end >= comparison_span
+                && is_quieter_than(
+                    &energies_db[..*start],
+                    &energies_db[*end..*end + comparison_span],
+                )
+        })
+        .map(|(_, end)| *end);
+
+    let outro_start = gaps
+        .iter()
+        .rev()
+        .take_while(|(start, _)| total - *start <= windows_for(MAX_OUTRO_MS))
+        .find(|(start, end)| {
+            total - *end >= min_segment
+                && *start >= comparison_span
+                && intro_end.is_none_or(|intro_end| *start >= intro_end + comparison_span)
+                && is_quieter_than(
+                    &energies_db[*end..],
+                    &energies_db[*start - comparison_span..*start],
+                )
+        })
+        .map(|(start, _)| *start);
+
+    TrackTrims {
+        start_ms: intro_end.map_or(0, |end| end as u64 * window_ms),
+        end_ms: outro_start.map_or(0, |start| (total - start) as u64 * window_ms),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(energy_db: f32, seconds: f64) -> Vec<f32> {
+        vec![energy_db; (seconds * 1000.0 / ANALYSIS_WINDOW_MS as f64) as usize]
+    }
+
+    fn concat(segments: &[Vec<f32>]) -> Vec<f32> {
+        segments.concat()
+    }
+
+    #[test]
+    fn test_detect_intro_outro_trims_finds_talk_separated_by_silence() {
+        let energies = concat(&[
+            segment(-30.0, 10.0),
+            segment(-80.0, 0.5),
+            segment(-14.0, 180.0),
+            segment(-80.0, 0.5),
+            segment(-28.0, 8.0),
+        ]);
+        assert_eq!(
+            detect_intro_outro_trims(&energies, ANALYSIS_WINDOW_MS),
+            TrackTrims {
+                start_ms: 10_500,
+                end_ms: 8_500,
+            }
+        );
+    }
+
+    #[test]
+    fn test_detect_intro_outro_trims_ignores_song_gaps_and_loud_openings() {
+        let song_with_break = concat(&[
+            segment(-14.0, 90.0),
+            segment(-80.0, 1.0),
+            segment(-14.0, 90.0),
+        ]);
+        assert_eq!(
+            detect_intro_outro_trims(&song_with_break, ANALYSIS_WINDOW_MS),
+            TrackTrims::default()
+        );
+
+        let loud_cold_open = concat(&[
+            segment(-12.0, 8.0),
+            segment(-80.0, 0.5),
+            segment(-14.0, 180.0),
+        ]);
+        assert_eq!(
+            detect_intro_outro_trims(&loud_cold_open, ANALYSIS_WINDOW_MS),
+            TrackTrims::default()
+        );
+    }
+
+    #[test]
+    fn test_detect_intro_outro_trims_requires_intro_within_limit_and_enough_audio() {
+        let late_gap = concat(&[
+            segment(-30.0, 70.0),
+            segment(-80.0, 0.5),
+            segment(-14.0, 180.0),
+        ]);
+        assert_eq!(
+            detect_intro_outro_trims(&late_gap, ANALYSIS_WINDOW_MS).start_ms,
+            0
+        );
+        assert_eq!(
+            detect_intro_outro_trims(&segment(-14.0, 5.0), ANALYSIS_WINDOW_MS),
+            TrackTrims::default()
+        );
+    }
+}
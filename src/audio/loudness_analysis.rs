@@ -0,0 +1,311 @@
+//! Integrated loudness and true peak analysis (ITU-R BS.1770 / EBU R128).
+//!
+//! Files are decoded once on the analysis pool. Loudness is measured on K-weighted audio
+//! in 400 ms blocks with 75% overlap, gated at -70 LUFS and then 10 LU below the
+//! ungated mean. True peak is estimated by 4x oversampling with a windowed-sinc
+//! interpolator. Results back the "Loudness" and "True Peak" playlist columns, which
+//! flag heavily limited masters.
+
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::intro_outro_detection::for_each_decoded_buffer;
+use crate::protocol::TrackLoudness;
+
+/// Format of the built-in "Loudness" playlist column.
+pub const LOUDNESS_COLUMN_FORMAT: &str = "{loudness}";
+/// Format of the built-in "True Peak" playlist column.
+pub const TRUE_PEAK_COLUMN_FORMAT: &str = "{true_peak}";
+/// Integrated loudness above this is shown as a likely brickwalled master.
+pub const BRICKWALL_LOUDNESS_LUFS: f32 = -9.0;
+/// True peaks above this clip on reconstruction.
+pub const CLIPPING_TRUE_PEAK_DBTP: f32 = 0.0;
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = 10.0;
+/// Gating blocks are four 100 ms sub-blocks long.
+const SUBBLOCKS_PER_BLOCK: usize = 4;
+/// Floor reported for digital silence.
+const PEAK_FLOOR_DBTP: f32 = -90.0;
+const OVERSAMPLING: usize = 4;
+const TAPS_PER_PHASE: usize = 12;
+
+/// Decodes `path` and measures its integrated loudness and true peak.
+pub fn analyze_file_loudness(path: &Path) -> Result<TrackLoudness, String> {
+    let mut meter: Option<LoudnessMeter> = None;
+    for_each_decoded_buffer(path, |samples, channels, rate| {
+        let meter = meter.get_or_insert_with(|| LoudnessMeter::new(channels, rate));
+        meter.reconfigure(channels, rate);
+        meter.push_interleaved(samples);
+    })?;
+    meter
+        .and_then(|meter| meter.finish())
+        .ok_or_else(|| "too short or silent to measure".to_string())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// K-weighting: a high-shelf "head" filter followed by the RLB high-pass.
+fn k_weighting_filters(rate: u32) -> [Biquad; 2] {
+    let rate = rate.max(1) as f64;
+
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+    [shelf, high_pass]
+}
+
+/// Channel weights from BS.1770; the LFE of a 5.1 layout is left out.
+fn channel_weights(channels: usize) -> Vec<f64> {
+    if channels == 6 {
+        vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41]
+    } else {
+        vec![1.0; channels.max(1)]
+    }
+}
+
+/// Polyphase windowed-sinc coefficients, one row per oversampling phase.
+fn interpolation_phases() -> [[f32; TAPS_PER_PHASE]; OVERSAMPLING] {
+    let total = OVERSAMPLING * TAPS_PER_PHASE;
+    let center = (total - 1) as f64 / 2.0;
+    let mut phases = [[0.0f32; TAPS_PER_PHASE]; OVERSAMPLING];
+    for (phase, row) in phases.iter_mut().enumerate() {
+        let mut coefficients = [0.0f64; TAPS_PER_PHASE];
+        for (tap, coefficient) in coefficients.iter_mut().enumerate() {
+            let index = tap * OVERSAMPLING + phase;
+            let x = (index as f64 - center) / OVERSAMPLING as f64;
+            let sinc = if x.abs() < 1e-9 {
+                1.0
+            } else {
+                (PI * x).sin() / (PI * x)
+            };
+            let window = 0.5 - 0.5 * (2.0 * PI * (index + 1) as f64 / (total + 1) as f64).cos();
+            *coefficient = sinc * window;
+        }
+        let sum: f64 = coefficients.iter().sum();
+        for (target, coefficient) in row.iter_mut().zip(coefficients) {
+            *target = (coefficient / sum) as f32;
+        }
+    }
+    phases
+}
+
+fn power_to_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.max(f64::MIN_POSITIVE).log10()
+}
+
+struct LoudnessMeter {
+    channels: usize,
+    rate: u32,
+    weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+    subblock_frames: usize,
+    subblock_sum: f64,
+    subblock_filled: usize,
+    /// Mean weighted power of each completed 100 ms sub-block.
+    subblock_powers: Vec<f64>,
+    phases: [[f32; TAPS_PER_PHASE]; OVERSAMPLING],
+    /// Most recent input samples per channel, newest first.
+    histories: Vec<[f32; TAPS_PER_PHASE]>,
+    peak: f32,
+}
+
+impl LoudnessMeter {
+    fn new(channels: usize, rate: u32) -> Self {
+        let channels = channels.max(1);
+        Self {
+            channels,
+            rate,
+            weights: channel_weights(channels),
+            filters: vec![k_weighting_filters(rate); channels],
+            subblock_frames: (rate as usize / 10).max(1),
+            subblock_sum: 0.0,
+            subblock_filled: 0,
+            subblock_powers: Vec::new(),
+            phases: interpolation_phases(),
+            histories: vec![[0.0; TAPS_PER_PHASE]; channels],
+            peak: 0.0,
+        }
+    }
+
+    /// Resets filter state when a stream changes layout or rate mid-file.
+    fn reconfigure(&mut self, channels: usize, rate: u32) {
+        let channels = channels.max(1);
+        if channels == self.channels && rate == self.rate {
+            return;
+        }
+        let subblock_powers = std::mem::take(&mut self.subblock_powers);
+        let peak = self.peak;
+        *self = Self::new(channels, rate);
+        self.subblock_powers = subblock_powers;
+        self.peak = peak;
+    }
+
+    fn push_interleaved(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(self.channels) {
+            let mut weighted_sum = 0.0;
+            for (channel, sample) in frame.iter().copied().enumerate() {
+                let mut filtered = sample as f64;
+                for filter in &mut self.filters[channel] {
+                    filtered = filter.process(filtered);
+                }
+                weighted_sum += self.weights[channel] * filtered * filtered;
+                self.track_true_peak(channel, sample);
+            }
+            self.subblock_sum += weighted_sum;
+            self.subblock_filled += 1;
+            if self.subblock_filled == self.subblock_frames {
+                self.subblock_powers
+                    .push(self.subblock_sum / self.subblock_frames as f64);
+                self.subblock_sum = 0.0;
+                self.subblock_filled = 0;
+            }
+        }
+    }
+
+    fn track_true_peak(&mut self, channel: usize, sample: f32) {
+        let history = &mut self.histories[channel];
+        history.copy_within(0..TAPS_PER_PHASE - 1, 1);
+        history[0] = sample;
+        self.peak = self.peak.max(sample.abs());
+        for phase in &self.phases {
+            let interpolated: f32 = phase
+                .iter()
+                .zip(history.iter())
+                .map(|(coefficient, sample)| coefficient * sample)
+                .sum();
+            self.peak = self.peak.max(interpolated.abs());
+        }
+    }
+
+    fn finish(self) -> Option<TrackLoudness> {
+        let block_powers: Vec<f64> = self
+            .subblock_powers
+            .windows(SUBBLOCKS_PER_BLOCK)
+            .map(|window| window.iter().sum::<f64>() / SUBBLOCKS_PER_BLOCK as f64)
+            .filter(|power| power_to_lufs(*power) > ABSOLUTE_GATE_LUFS)
+            .collect();
+        if block_powers.is_empty() {
+            return None;
+        }
+        let ungated_mean = block_powers.iter().sum::<f64>() / block_powers.len() as f64;
+        let relative_gate = power_to_lufs(ungated_mean) - RELATIVE_GATE_LU;
+        let gated: Vec<f64> = block_powers
+            .into_iter()
+            .filter(|power| power_to_lufs(*power) > relative_gate)
+            .collect();
+        let integrated = power_to_lufs(gated.iter().sum::<f64>() / gated.len().max(1) as f64);
+        let true_peak_dbtp = if self.peak > 0.0 {
+            (20.0 * self.peak.log10()).max(PEAK_FLOOR_DBTP)
+        } else {
+            PEAK_FLOOR_DBTP
+        };
+        Some(TrackLoudness {
+            integrated_lufs: integrated as f32,
+            true_peak_dbtp,
+        })
+    }
+}
+
+/// Label of the "Loudness" column, e.g. `-14.2 LUFS`.
+pub fn loudness_label(loudness: &TrackLoudness) -> String {
+    format!("{:.1} LUFS", loudness.integrated_lufs)
+}
+
+/// Label of the "True Peak" column, e.g. `-0.3 dBTP`.
+pub fn true_peak_label(loudness: &TrackLoudness) -> String {
+    format!("{:+.1} dBTP", loudness.true_peak_dbtp)
+}
+
+/// Text key that sorts measured values numerically under plain string ordering.
+pub fn numeric_sort_key(value: f32) -> String {
+    format!("{:010.3}", (value as f64 + 1000.0).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency_hz: f64, amplitude: f64, phase: f64, rate: u32, seconds: f64) -> Vec<f32> {
+        let frames = (rate as f64 * seconds) as usize;
+        (0..frames)
+            .map(|frame| {
+                (amplitude * (2.0 * PI * frequency_hz * frame as f64 / rate as f64 + phase).sin())
+                    as f32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integrated_loudness_of_reference_sine() {
+        // A -20 dBFS 997 Hz sine on one channel reads -23 LUFS (BS.1770 calibration).
+        let mut meter = LoudnessMeter::new(1, 48_000);
+        meter.push_interleaved(&sine(997.0, 0.1, 0.0, 48_000, 5.0));
+        let loudness = meter.finish().expect("sine should be measurable");
+
+        assert!(
+            (loudness.integrated_lufs - -23.01).abs() < 0.1,
+            "{}",
+            loudness.integrated_lufs
+        );
+
+        let mut silent = LoudnessMeter::new(2, 44_100);
+        silent.push_interleaved(&vec![0.0; 44_100 * 2]);
+        assert!(silent.finish().is_none());
+    }
+
+    #[test]
+    fn test_true_peak_finds_inter_sample_peaks() {
+        // At a quarter of the sample rate with a 45 degree phase every sample sits at
+        // 0.707 of the real peak, so the sample peak reads 3 dB low.
+        let mut meter = LoudnessMeter::new(1, 48_000);
+        meter.push_interleaved(&sine(12_000.0, 0.5, PI / 4.0, 48_000, 1.0));
+        let loudness = meter.finish().expect("sine should be measurable");
+
+        assert!(
+            (loudness.true_peak_dbtp - -6.02).abs() < 0.5,
+            "{}",
+            loudness.true_peak_dbtp
+        );
+        assert!(numeric_sort_key(-15.0) < numeric_sort_key(-14.2));
+        assert!(numeric_sort_key(-0.5) < numeric_sort_key(0.4));
+        assert_eq!(true_peak_label(&loudness).chars().next(), Some('-'));
+    }
+}
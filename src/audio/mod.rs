@@ -1,6 +1,25 @@
-//! Audio subsystem modules (decode, playback, probing, and option selection).
+//! Audio subsystem modules (decode, playback, probing, analysis, loudness measurement,
+//! format-quality badges, silence skipping, the output DSP chain with its parametric EQ
+//! and night-mode compressor, AutoEq/REW profile import, output device tests, spectrograms,
+//! lossy-transcode detection, sample-rate family grouping, ranged HTTP sources, developer
+//! fault injection, and option selection).
 
 pub(crate) mod audio_decoder;
 pub(crate) mod audio_player;
 pub(crate) mod audio_probe;
+pub(crate) mod dsp_chain;
+pub(crate) mod eq_profile_import;
+pub(crate) mod fault_injection;
+pub(crate) mod format_quality;
+pub(crate) mod http_range_source;
+pub(crate) mod intro_outro_detection;
+pub(crate) mod loudness_analysis;
+pub(crate) mod night_mode;
 pub(crate) mod output_option_selection;
+pub(crate) mod output_test;
+pub(crate) mod parametric_eq;
+pub(crate) mod sample_rate_grouping;
+pub(crate) mod seek_markers;
+pub(crate) mod silence_skipping;
+pub(crate) mod spectrogram;
+pub(crate) mod transcode_detection;
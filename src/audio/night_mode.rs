@@ -0,0 +1,136 @@
+//! Night-mode dynamics compressor for late-night listening.
+//!
+//! A single strength setting lowers the threshold and raises the ratio of a peak
+//! compressor, then applies make-up gain so quiet passages come up while loud peaks
+//! come down. The compressor is the first stage of the output DSP chain and runs on
+//! interleaved samples with one envelope shared by all channels, so the stereo image
+//! does not wander.
+
+/// Strength choices offered by the settings dialog, in percent; `0` disables night mode.
+pub const NIGHT_MODE_STRENGTH_CHOICES_PERCENT: &[u32] = &[0, 25, 50, 75, 100];
+const ATTACK_MS: f32 = 10.0;
+const RELEASE_MS: f32 = 250.0;
+/// Envelope floor, about -120 dBFS, which keeps the dB conversion finite on silence.
+const ENVELOPE_FLOOR: f32 = 1.0e-6;
+
+/// Settings label for a strength choice, e.g. `Off` or `50%`.
+pub fn night_mode_strength_label(percent: u32) -> String {
+    if percent == 0 {
+        "Off".to_string()
+    } else {
+        format!("{percent}%")
+    }
+}
+
+/// Index of the offered choice closest to `percent`.
+pub fn night_mode_strength_choice_index(percent: u32) -> usize {
+    NIGHT_MODE_STRENGTH_CHOICES_PERCENT
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, choice)| choice.abs_diff(percent))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Compressor state owned by one output stream.
+pub struct NightModeCompressor {
+    strength_percent: u32,
+    threshold_db: f32,
+    ratio: f32,
+    makeup_db: f32,
+    enabled: bool,
+    envelope: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+}
+
+impl NightModeCompressor {
+    /// Creates a disabled compressor for an interleaved stream.
+    pub fn new(sample_rate_hz: u32, channels: u16) -> Self {
+        let samples_per_second = sample_rate_hz.max(1) as f32 * channels.max(1) as f32;
+        let coeff = |time_ms: f32| (-1.0 / (time_ms / 1000.0 * samples_per_second)).exp();
+        Self {
+            strength_percent: 0,
+            threshold_db: 0.0,
+            ratio: 1.0,
+            makeup_db: 0.0,
+            enabled: false,
+            envelope: ENVELOPE_FLOOR,
+            attack_coeff: coeff(ATTACK_MS),
+            release_coeff: coeff(RELEASE_MS),
+        }
+    }
+
+    /// Applies a strength setting in percent; `0` turns the compressor off.
+    pub fn set_strength_percent(&mut self, strength_percent: u32) {
+        let strength_percent = strength_percent.min(100);
+        if strength_percent == self.strength_percent {
+            return;
+        }
+        self.strength_percent = strength_percent;
+        let strength = strength_percent as f32 / 100.0;
+        self.enabled = strength > 0.0;
+        self.threshold_db = -12.0 - 18.0 * strength;
+        self.ratio = 1.0 + 5.0 * strength;
+        // Half of the full-scale reduction comes back as make-up gain, which lifts
+        // passages below the threshold without pushing compressed peaks back up.
+        self.makeup_db = -self.threshold_db * (1.0 - 1.0 / self.ratio) * 0.5;
+    }
+
+    /// Compresses one interleaved sample; a no-op while night mode is off.
+    pub fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled {
+            return sample;
+        }
+        let level = sample.abs();
+        let coeff = if level > self.envelope {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.envelope = (level + coeff * (self.envelope - level)).max(ENVELOPE_FLOOR);
+        let envelope_db = 20.0 * self.envelope.log10();
+        let over_db = (envelope_db - self.threshold_db).max(0.0);
+        let reduction_db = over_db * (1.0 - 1.0 / self.ratio);
+        sample * 10f32.powf((self.makeup_db - reduction_db) / 20.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settled_output(strength_percent: u32, amplitude: f32) -> f32 {
+        let mut compressor = NightModeCompressor::new(48_000, 2);
+        compressor.set_strength_percent(strength_percent);
+        let mut output = 0.0;
+        for _ in 0..96_000 {
+            output = compressor.process(amplitude);
+        }
+        output
+    }
+
+    #[test]
+    fn test_night_mode_off_passes_samples_through() {
+        assert_eq!(settled_output(0, 0.9), 0.9);
+        assert_eq!(settled_output(0, 0.01), 0.01);
+    }
+
+    #[test]
+    fn test_night_mode_narrows_dynamic_range() {
+        let loud = settled_output(100, 0.9);
+        let quiet = settled_output(100, 0.01);
+        assert!(loud < 0.9, "loud passages should come down, got {loud}");
+        assert!(quiet > 0.01, "quiet passages should come up, got {quiet}");
+        assert!(loud / quiet < 0.9 / 0.01);
+    }
+
+    #[test]
+    fn test_strength_choices_and_labels() {
+        assert_eq!(night_mode_strength_label(0), "Off");
+        assert_eq!(night_mode_strength_label(75), "75%");
+        assert_eq!(night_mode_strength_choice_index(0), 0);
+        assert_eq!(night_mode_strength_choice_index(60), 2);
+        assert_eq!(night_mode_strength_choice_index(400), 4);
+    }
+}
@@ -5,7 +5,9 @@ use std::collections::BTreeSet;
 use cpal::traits::{DeviceTrait, HostTrait};
 
 use crate::{
-    audio_probe::get_or_probe_output_device, config::Config, runtime_config::RuntimeOutputOverride,
+    audio_probe::get_or_probe_output_device,
+    config::{Config, OutputConfig},
+    runtime_config::RuntimeOutputOverride,
     OutputSettingsOptions,
 };
 
@@ -232,6 +234,35 @@ pub(crate) fn snapshot_output_device_names(host: &cpal::Host) -> Vec<String> {
 }
 
 /// Detects available output settings and computes preferred auto defaults.
+/// Output device selected by `output`, falling back to the system default when the
+/// configured device is missing.
+pub(crate) fn resolve_output_device(
+    host: &cpal::Host,
+    output: &OutputConfig,
+) -> Option<cpal::Device> {
+    if output.output_device_auto {
+        return host.default_output_device();
+    }
+    let requested_name = output.output_device_name.trim().to_string();
+    let matching_device = if requested_name.is_empty() {
+        None
+    } else {
+        host.output_devices().ok().and_then(|devices| {
+            devices
+                .filter_map(|device| {
+                    let name = device.name().ok()?;
+                    if name == requested_name {
+                        Some(device)
+                    } else {
+                        None
+                    }
+                })
+                .next()
+        })
+    };
+    matching_device.or_else(|| host.default_output_device())
+}
+
 pub(crate) fn detect_output_settings_options(config: &Config) -> OutputSettingsOptions {
     const COMMON_SAMPLE_RATES: [u32; 6] = [44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
     const COMMON_CHANNEL_COUNTS: [u16; 4] = [2, 1, 6, 8];
@@ -280,30 +311,7 @@ pub(crate) fn detect_output_settings_options(config: &Config) -> OutputSettingsO
     user_facing_device_names.sort();
     user_facing_device_names.dedup();
 
-    let selected_device = if config.output.output_device_auto {
-        host.default_output_device()
-    } else {
-        let requested_name = config.output.output_device_name.trim().to_string();
-        let matching_device = if requested_name.is_empty() {
-            None
-        } else {
-            host.output_devices().ok().and_then(|devices| {
-                devices
-                    .filter_map(|device| {
-                        let name = device.name().ok()?;
-                        if name == requested_name {
-                            Some(device)
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-            })
-        };
-        matching_device.or_else(|| host.default_output_device())
-    };
-
-    if let Some(device) = selected_device {
+    if let Some(device) = resolve_output_device(&host, &config.output) {
         let probe_result =
             get_or_probe_output_device(&host, &device, config.output.sample_rate_khz.max(8_000));
         for verified_rate in probe_result.verified_sample_rates {
@@ -401,8 +409,8 @@ mod tests {
 
     use crate::{
         config::{
-            BufferingConfig, Config, LibraryConfig, OutputConfig, UiConfig, UiPlaybackOrder,
-            UiRepeatMode,
+            BufferingConfig, Config, LibraryConfig, OutputConfig, UiConfig, UiEnergyRampArc,
+            UiPlaybackOrder, UiRepeatMode, UiTransportBarPlacement,
         },
         runtime_config::RuntimeOutputOverride,
     };
@@ -485,10 +493,28 @@ mod tests {
                 volume: 1.0,
                 playback_order: UiPlaybackOrder::Default,
                 repeat_mode: UiRepeatMode::Off,
+                energy_ramp_arc: UiEnergyRampArc::BuildUp,
+                recent_import_locations: Vec::new(),
+                hi_res_min_sample_rate_hz: 88_200,
+                hi_res_min_bit_depth: 24,
+                seek_small_step_secs: 5,
+                seek_large_step_secs: 30,
+                zero_based_row_numbers: false,
+                transport_bar_placement: UiTransportBarPlacement::Off,
+                scale_override_percent: 0,
+                file_browser_root: String::new(),
+                external_tagger_command: String::new(),
+                library_album_grid: false,
+                shortcut_keys: Vec::new(),
             },
             library: LibraryConfig::default(),
             buffering: BufferingConfig::default(),
             integrations: crate::config::IntegrationsConfig::default(),
+            announcements: crate::config::AnnouncementConfig::default(),
+            power: crate::config::PowerConfig::default(),
+            performance: crate::config::PerformanceConfig::default(),
+            confirmations: crate::config::ConfirmationsConfig::default(),
+            dsp: crate::config::DspConfig::default(),
             cast: crate::config::CastConfig::default(),
         };
         let options = crate::OutputSettingsOptions {
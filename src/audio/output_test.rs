@@ -0,0 +1,406 @@
+//! Output test utility for checking a device and driver setup before playback.
+//!
+//! Tests open their own short-lived streams on the selected device, using the stream
+//! configurations the output probe verifies, and play a steady tone, a tone on each
+//! channel in turn, or a short tone at every verified sample rate. Each stream is
+//! checked for driver errors and for the device pulling the whole signal.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use log::debug;
+
+use crate::{audio_probe, config::OutputConfig, output_option_selection};
+
+/// Peak level of every test signal, about -12 dBFS.
+const TEST_AMPLITUDE: f32 = 0.25;
+const TEST_TONE_HZ: f32 = 440.0;
+const SWEEP_TONE_HZ: f32 = 1_000.0;
+const LFE_TONE_HZ: f32 = 60.0;
+const FADE_MS: u32 = 15;
+/// Time allowed past the signal length for the device to drain its buffer.
+const DRAIN_GRACE_MS: u64 = 1_500;
+
+/// Test signals offered next to the output settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputTestKind {
+    Tone,
+    ChannelIdentification,
+    SampleRateSweep,
+}
+
+/// Tests in the order the settings dialog lists them.
+pub const OUTPUT_TEST_KINDS: [OutputTestKind; 3] = [
+    OutputTestKind::Tone,
+    OutputTestKind::ChannelIdentification,
+    OutputTestKind::SampleRateSweep,
+];
+
+impl OutputTestKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tone => "Test tone",
+            Self::ChannelIdentification => "Channel check",
+            Self::SampleRateSweep => "Sample-rate sweep",
+        }
+    }
+}
+
+/// One stretch of a test, played through its own stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputTestSegment {
+    pub label: String,
+    pub sample_rate_hz: u32,
+    /// Channel the tone plays on; `None` plays it on every channel.
+    pub channel: Option<u16>,
+    pub frequency_hz: f32,
+    pub duration_ms: u32,
+}
+
+/// Speaker name of `channel` (zero-based) in the usual WAVE channel order.
+pub fn channel_name(channel: u16, channels: u16) -> String {
+    const SURROUND_NAMES: [&str; 8] = [
+        "Front left",
+        "Front right",
+        "Center",
+        "LFE",
+        "Rear left",
+        "Rear right",
+        "Side left",
+        "Side right",
+    ];
+    match (channels, channel) {
+        (1, 0) => "Mono".to_string(),
+        (2, 0) => "Left".to_string(),
+        (2, 1) => "Right".to_string(),
+        _ => SURROUND_NAMES
+            .get(channel as usize)
+            .filter(|_| channels <= SURROUND_NAMES.len() as u16)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("Channel {}", channel + 1)),
+    }
+}
+
+/// Segments of `kind` for a device opened with `channels` at `sample_rate_hz`; the sweep
+/// covers `verified_sample_rates` and falls back to `sample_rate_hz` when none are known.
+pub fn output_test_segments(
+    kind: OutputTestKind,
+    channels: u16,
+    sample_rate_hz: u32,
+    verified_sample_rates: &[u32],
+) -> Vec<OutputTestSegment> {
+    let channels = channels.max(1);
+    match kind {
+        OutputTestKind::Tone => vec![OutputTestSegment {
+            label: format!("{TEST_TONE_HZ:.0} Hz tone at {sample_rate_hz} Hz"),
+            sample_rate_hz,
+            channel: None,
+            frequency_hz: TEST_TONE_HZ,
+            duration_ms: 2_000,
+        }],
+        OutputTestKind::ChannelIdentification => (0..channels)
+            .map(|channel| {
+                let name = channel_name(channel, channels);
+                OutputTestSegment {
+                    frequency_hz: if name == "LFE" {
+                        LFE_TONE_HZ
+                    } else {
+                        TEST_TONE_HZ
+                    },
+                    label: name,
+                    sample_rate_hz,
+                    channel: Some(channel),
+                    duration_ms: 1_200,
+                }
+            })
+            .collect(),
+        OutputTestKind::SampleRateSweep => {
+            let rates = if verified_sample_rates.is_empty() {
+                vec![sample_rate_hz]
+            } else {
+                verified_sample_rates.to_vec()
+            };
+            rates
+                .into_iter()
+                .map(|rate| OutputTestSegment {
+                    label: format!("{rate} Hz"),
+                    sample_rate_hz: rate,
+                    channel: None,
+                    frequency_hz: SWEEP_TONE_HZ,
+                    duration_ms: 800,
+                })
+                .collect()
+        }
+    }
+}
+
+/// Interleaved samples of `segment` for a stream with `channels`, faded in and out so
+/// stream starts and stops do not click.
+pub fn render_segment(segment: &OutputTestSegment, channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let sample_rate_hz = segment.sample_rate_hz.max(1);
+    let frames = (u64::from(sample_rate_hz) * u64::from(segment.duration_ms) / 1000) as usize;
+    let fade_frames = ((sample_rate_hz * FADE_MS / 1000) as usize).clamp(1, frames.max(2) / 2);
+    let mut samples = vec![0.0f32; frames * channels];
+    for frame in 0..frames {
+        let fade_position = frame.min(frames - 1 - frame);
+        let envelope = if fade_position < fade_frames {
+            let progress = fade_position as f64 / fade_frames as f64;
+            0.5 - 0.5 * (std::f64::consts::PI * progress).cos()
+        } else {
+            1.0
+        };
+        let cycles = f64::from(segment.frequency_hz) * frame as f64 / f64::from(sample_rate_hz);
+        let value = (f64::from(TEST_AMPLITUDE)
+            * envelope
+            * (2.0 * std::f64::consts::PI * cycles).sin()) as f32;
+        let frame_samples = &mut samples[frame * channels..(frame + 1) * channels];
+        match segment.channel {
+            Some(channel) => {
+                if let Some(sample) = frame_samples.get_mut(channel as usize) {
+                    *sample = value;
+                }
+            }
+            None => frame_samples.fill(value),
+        }
+    }
+    samples
+}
+
+/// Plays `kind` on the device selected by `output`, calling `progress` before each
+/// segment; returns a summary or the first failure.
+pub fn run_output_test(
+    output: &OutputConfig,
+    kind: OutputTestKind,
+    mut progress: impl FnMut(&str),
+) -> Result<String, String> {
+    let host = cpal::default_host();
+    let device = output_option_selection::resolve_output_device(&host, output)
+        .ok_or_else(|| "No output device available".to_string())?;
+    let device_name = device
+        .name()
+        .unwrap_or_else(|_| "Unknown Device".to_string());
+    let preferred_channels = if output.channel_count_auto {
+        2
+    } else {
+        output.channel_count.max(1)
+    };
+    let configured_rate = output.sample_rate_khz.max(8_000);
+    let verified_sample_rates = if kind == OutputTestKind::SampleRateSweep {
+        audio_probe::get_or_probe_output_device(&host, &device, configured_rate)
+            .verified_sample_rates
+    } else {
+        Vec::new()
+    };
+    let tone_rate = if output.sample_rate_auto {
+        device
+            .default_output_config()
+            .map(|config| config.sample_rate().0)
+            .unwrap_or(configured_rate)
+    } else {
+        configured_rate
+    };
+    let opened_channels =
+        audio_probe::stream_configs_for_rate(&device, tone_rate, preferred_channels)
+            .first()
+            .map(|config| config.channels())
+            .unwrap_or(preferred_channels);
+    let segments = output_test_segments(kind, opened_channels, tone_rate, &verified_sample_rates);
+
+    let mut failed_rates = Vec::new();
+    for segment in &segments {
+        progress(&format!("Playing {} on {}", segment.label, device_name));
+        let result = play_segment(&device, segment, preferred_channels);
+        match result {
+            Ok(()) => {}
+            Err(err) if kind == OutputTestKind::SampleRateSweep => {
+                debug!(
+                    "OutputTest: {} failed on '{}': {}",
+                    segment.label, device_name, err
+                );
+                failed_rates.push(segment.label.clone());
+            }
+            Err(err) => {
+                return Err(format!(
+                    "{} failed on {}: {}",
+                    segment.label, device_name, err
+                ))
+            }
+        }
+    }
+    if failed_rates.is_empty() {
+        Ok(format!("{} finished on {}", kind.label(), device_name))
+    } else {
+        Err(format!(
+            "{} on {}: no clean playback at {}",
+            kind.label(),
+            device_name,
+            failed_rates.join(", ")
+        ))
+    }
+}
+
+fn play_segment(
+    device: &cpal::Device,
+    segment: &OutputTestSegment,
+    preferred_channels: u16,
+) -> Result<(), String> {
+    let candidates =
+        audio_probe::stream_configs_for_rate(device, segment.sample_rate_hz, preferred_channels);
+    let config = candidates
+        .into_iter()
+        .find(|config| {
+            matches!(
+                config.sample_format(),
+                cpal::SampleFormat::F32 | cpal::SampleFormat::I16 | cpal::SampleFormat::U16
+            )
+        })
+        .ok_or_else(|| "sample rate not supported by the device".to_string())?;
+    let samples = render_segment(segment, config.channels());
+    match config.sample_format() {
+        cpal::SampleFormat::F32 => play_samples::<f32>(device, &config.config(), samples),
+        cpal::SampleFormat::I16 => play_samples::<i16>(device, &config.config(), samples),
+        _ => play_samples::<u16>(device, &config.config(), samples),
+    }
+}
+
+fn play_samples<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    samples: Vec<f32>,
+) -> Result<(), String>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
+{
+    let frames = samples.len() / config.channels.max(1) as usize;
+    let signal_ms = frames as u64 * 1000 / u64::from(config.sample_rate.0.max(1));
+    let finished = Arc::new(AtomicBool::new(false));
+    let stream_error = Arc::new(AtomicBool::new(false));
+    let finished_for_callback = Arc::clone(&finished);
+    let stream_error_for_callback = Arc::clone(&stream_error);
+    let mut position = 0usize;
+    let stream = device
+        .build_output_stream(
+            config,
+            move |output: &mut [T], _| {
+                for sample in output.iter_mut() {
+                    *sample = match samples.get(position) {
+                        Some(value) => T::from_sample(*value),
+                        None => T::EQUILIBRIUM,
+                    };
+                    position += 1;
+                }
+                if position >= samples.len() {
+                    finished_for_callback.store(true, Ordering::Relaxed);
+                }
+            },
+            move |_| {
+                stream_error_for_callback.store(true, Ordering::Relaxed);
+            },
+            None,
+        )
+        .map_err(|err| format!("could not open stream: {err}"))?;
+    stream
+        .play()
+        .map_err(|err| format!("could not start stream: {err}"))?;
+
+    let deadline = Instant::now() + Duration::from_millis(signal_ms + DRAIN_GRACE_MS);
+    while !finished.load(Ordering::Relaxed) && Instant::now() < deadline {
+        if stream_error.load(Ordering::Relaxed) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    // Let the last buffer reach the speakers before the stream is dropped.
+    std::thread::sleep(Duration::from_millis(120));
+    if stream_error.load(Ordering::Relaxed) {
+        return Err("the driver reported a stream error".to_string());
+    }
+    if !finished.load(Ordering::Relaxed) {
+        return Err("the device stopped pulling audio".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_check_plays_each_channel_alone() {
+        let segments = output_test_segments(OutputTestKind::ChannelIdentification, 6, 48_000, &[]);
+        let labels: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "Front left",
+                "Front right",
+                "Center",
+                "LFE",
+                "Rear left",
+                "Rear right"
+            ]
+        );
+        assert_eq!(segments[3].frequency_hz, LFE_TONE_HZ);
+
+        let samples = render_segment(&segments[1], 6);
+        let peak_of = |channel: usize| {
+            samples
+                .iter()
+                .skip(channel)
+                .step_by(6)
+                .fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+        };
+        assert!((peak_of(1) - TEST_AMPLITUDE).abs() < 0.01);
+        for silent in [0, 2, 3, 4, 5] {
+            assert_eq!(peak_of(silent), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_sweep_covers_verified_rates_with_fallback() {
+        let segments = output_test_segments(
+            OutputTestKind::SampleRateSweep,
+            2,
+            48_000,
+            &[44_100, 96_000],
+        );
+        let rates: Vec<u32> = segments
+            .iter()
+            .map(|segment| segment.sample_rate_hz)
+            .collect();
+        assert_eq!(rates, [44_100, 96_000]);
+
+        let segments = output_test_segments(OutputTestKind::SampleRateSweep, 2, 48_000, &[]);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].sample_rate_hz, 48_000);
+    }
+
+    #[test]
+    fn test_rendered_tone_fades_in_and_out() {
+        let segment = &output_test_segments(OutputTestKind::Tone, 2, 48_000, &[])[0];
+        let samples = render_segment(segment, 2);
+        assert_eq!(samples.len(), 48_000 * 2 * 2);
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[samples.len() - 1].abs() < 1.0e-3);
+        assert!(samples.iter().all(|sample| sample.abs() <= TEST_AMPLITUDE));
+        assert_eq!(samples[2000], samples[2001]);
+    }
+
+    #[test]
+    fn test_channel_names_for_common_layouts() {
+        assert_eq!(channel_name(0, 1), "Mono");
+        assert_eq!(channel_name(1, 2), "Right");
+        assert_eq!(channel_name(7, 8), "Side right");
+        assert_eq!(channel_name(3, 10), "Channel 4");
+    }
+}
@@ -0,0 +1,235 @@
+//! Parametric EQ stage of the output DSP chain.
+//!
+//! Each band is a biquad from the RBJ Audio EQ Cookbook, computed in `f64` so low
+//! shelves near 20 Hz stay stable. Samples arrive interleaved; the EQ keeps one filter
+//! state per channel and tracks the channel from the sample position in the buffer.
+
+use crate::config::{EqFilterConfig, EqFilterKind, EqPresetConfig};
+
+/// Gain range accepted for preset pre-amps and band gains, in dB.
+pub const EQ_GAIN_RANGE_DB: (f32, f32) = (-30.0, 30.0);
+/// Bands kept per preset; extra bands from an import are dropped.
+pub const MAX_EQ_FILTERS: usize = 32;
+
+/// Whether a band can be realized at all; invalid bands are dropped on load.
+pub fn is_valid_eq_filter(filter: &EqFilterConfig) -> bool {
+    filter.frequency_hz.is_finite()
+        && filter.frequency_hz > 0.0
+        && filter.q.is_finite()
+        && filter.q > 0.0
+        && filter.gain_db.is_finite()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BiquadCoefficients {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl BiquadCoefficients {
+    /// Normalized coefficients for `filter`, or `None` when its frequency is outside the
+    /// band the stream can represent.
+    fn for_filter(filter: &EqFilterConfig, sample_rate_hz: u32) -> Option<Self> {
+        let sample_rate_hz = f64::from(sample_rate_hz);
+        let frequency_hz = f64::from(filter.frequency_hz);
+        if !is_valid_eq_filter(filter) || frequency_hz >= sample_rate_hz / 2.0 {
+            return None;
+        }
+        let a = 10f64.powf(f64::from(filter.gain_db) / 40.0);
+        let w0 = 2.0 * std::f64::consts::PI * frequency_hz / sample_rate_hz;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * f64::from(filter.q));
+        let shelf_alpha = 2.0 * a.sqrt() * alpha;
+        let (b0, b1, b2, a0, a1, a2) = match filter.kind {
+            EqFilterKind::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos_w0,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos_w0,
+                1.0 - alpha / a,
+            ),
+            EqFilterKind::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 + shelf_alpha),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 - shelf_alpha),
+                (a + 1.0) + (a - 1.0) * cos_w0 + shelf_alpha,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                (a + 1.0) + (a - 1.0) * cos_w0 - shelf_alpha,
+            ),
+            EqFilterKind::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 + shelf_alpha),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 - shelf_alpha),
+                (a + 1.0) - (a - 1.0) * cos_w0 + shelf_alpha,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                (a + 1.0) - (a - 1.0) * cos_w0 - shelf_alpha,
+            ),
+            EqFilterKind::LowPass => (
+                (1.0 - cos_w0) / 2.0,
+                1.0 - cos_w0,
+                (1.0 - cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            EqFilterKind::HighPass => (
+                (1.0 + cos_w0) / 2.0,
+                -(1.0 + cos_w0),
+                (1.0 + cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+        };
+        Some(Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        })
+    }
+}
+
+/// Direct form I history of one band on one channel.
+#[derive(Debug, Clone, Copy, Default)]
+struct BiquadState {
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+struct EqBand {
+    coefficients: BiquadCoefficients,
+    states: Vec<BiquadState>,
+}
+
+/// EQ state owned by one output stream.
+pub struct ParametricEq {
+    preamp_gain: f64,
+    bands: Vec<EqBand>,
+    channels: usize,
+    next_channel: usize,
+}
+
+impl ParametricEq {
+    /// Builds the EQ for `preset` on an interleaved stream; bands the stream cannot
+    /// represent are skipped.
+    pub fn new(preset: &EqPresetConfig, sample_rate_hz: u32, channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        let bands = preset
+            .filters
+            .iter()
+            .take(MAX_EQ_FILTERS)
+            .filter_map(|filter| BiquadCoefficients::for_filter(filter, sample_rate_hz))
+            .map(|coefficients| EqBand {
+                coefficients,
+                states: vec![BiquadState::default(); channels],
+            })
+            .collect();
+        Self {
+            preamp_gain: 10f64.powf(f64::from(preset.preamp_db) / 20.0),
+            bands,
+            channels,
+            next_channel: 0,
+        }
+    }
+
+    /// Realigns the channel counter; output buffers always start on the first channel.
+    pub fn start_buffer(&mut self) {
+        self.next_channel = 0;
+    }
+
+    /// Filters one interleaved sample.
+    pub fn process(&mut self, sample: f32) -> f32 {
+        let channel = self.next_channel;
+        self.next_channel = (self.next_channel + 1) % self.channels;
+        let mut value = f64::from(sample) * self.preamp_gain;
+        for band in &mut self.bands {
+            let c = band.coefficients;
+            let state = &mut band.states[channel];
+            let output = c.b0 * value + c.b1 * state.x1 + c.b2 * state.x2
+                - c.a1 * state.y1
+                - c.a2 * state.y2;
+            state.x2 = state.x1;
+            state.x1 = value;
+            state.y2 = state.y1;
+            state.y1 = output;
+            value = output;
+        }
+        value as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(preamp_db: f32, filters: Vec<EqFilterConfig>) -> EqPresetConfig {
+        EqPresetConfig {
+            name: "Test".to_string(),
+            target: String::new(),
+            preamp_db,
+            filters,
+        }
+    }
+
+    /// Steady-state peak gain in dB of a mono sine at `frequency_hz`.
+    fn measured_gain_db(eq: &mut ParametricEq, frequency_hz: f32) -> f32 {
+        let mut peak = 0.0f32;
+        for index in 0..48_000 {
+            let cycles = f64::from(frequency_hz) * f64::from(index) / 48_000.0;
+            let output = eq.process(0.1 * (2.0 * std::f64::consts::PI * cycles).sin() as f32);
+            if index >= 24_000 {
+                peak = peak.max(output.abs());
+            }
+        }
+        20.0 * (peak / 0.1).log10()
+    }
+
+    #[test]
+    fn test_peaking_band_boosts_its_center_frequency() {
+        let filters = vec![EqFilterConfig {
+            kind: EqFilterKind::Peaking,
+            frequency_hz: 1_000.0,
+            gain_db: 6.0,
+            q: 1.0,
+        }];
+        let mut eq = ParametricEq::new(&preset(0.0, filters.clone()), 48_000, 1);
+        assert!((measured_gain_db(&mut eq, 1_000.0) - 6.0).abs() < 0.2);
+        let mut eq = ParametricEq::new(&preset(0.0, filters), 48_000, 1);
+        assert!(measured_gain_db(&mut eq, 10_000.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_preamp_and_low_shelf_combine() {
+        let filters = vec![EqFilterConfig {
+            kind: EqFilterKind::LowShelf,
+            frequency_hz: 200.0,
+            gain_db: 6.0,
+            q: std::f32::consts::FRAC_1_SQRT_2,
+        }];
+        let mut eq = ParametricEq::new(&preset(-6.0, filters.clone()), 48_000, 1);
+        assert!(measured_gain_db(&mut eq, 40.0).abs() < 0.5);
+        let mut eq = ParametricEq::new(&preset(-6.0, filters), 48_000, 1);
+        assert!((measured_gain_db(&mut eq, 7_000.0) + 6.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_bands_above_nyquist_are_skipped() {
+        let filters = vec![EqFilterConfig {
+            kind: EqFilterKind::Peaking,
+            frequency_hz: 30_000.0,
+            gain_db: 12.0,
+            q: 1.0,
+        }];
+        let mut eq = ParametricEq::new(&preset(0.0, filters), 48_000, 2);
+        assert!(eq.bands.is_empty());
+        assert_eq!(eq.process(0.5), 0.5);
+    }
+}
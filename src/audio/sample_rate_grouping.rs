@@ -0,0 +1,200 @@
+//! Sample-rate family grouping for "Match Content" output.
+//!
+//! Every output-rate switch reopens the device, which leaves an audible gap between
+//! tracks. Rates fall into two families: multiples of 44.1 kHz and multiples of 48 kHz.
+//! Within one family a lower rate converts to a higher one cleanly, so a queue mixing
+//! 44.1 kHz and 88.2 kHz tracks can play at 88.2 kHz throughout instead of switching on
+//! every track. Optionally, a single track whose rate does not fit the running output
+//! (e.g. one 48 kHz track inside a 44.1 kHz album) is resampled instead of switching
+//! twice around it.
+
+/// Rates offered for pinning an output device, in Hz.
+pub const PINNED_RATE_CHOICES_HZ: &[u32] = &[44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
+
+/// Settings label for a rate choice, e.g. `44.1 kHz`.
+pub fn rate_choice_label(sample_rate_hz: u32) -> String {
+    let text = format!("{:.1}", sample_rate_hz as f64 / 1000.0);
+    format!("{} kHz", text.trim_end_matches(".0"))
+}
+
+/// Index of the choice closest to `sample_rate_hz`.
+pub fn rate_choice_index(sample_rate_hz: u32) -> usize {
+    PINNED_RATE_CHOICES_HZ
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, rate)| rate.abs_diff(sample_rate_hz))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Which rate-switch reductions are enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateGroupingPolicy {
+    /// Keep the output at a higher rate of the same family instead of switching down,
+    /// and open the device at the highest rate of the upcoming same-family run.
+    pub group_families: bool,
+    /// Resample one track that would otherwise switch away and straight back.
+    pub resample_outliers: bool,
+}
+
+impl RateGroupingPolicy {
+    pub fn is_enabled(self) -> bool {
+        self.group_families || self.resample_outliers
+    }
+}
+
+/// Base rate of the family `sample_rate_hz` belongs to: 44100 or 48000, or the rate
+/// itself when it is a multiple of neither.
+pub fn rate_family(sample_rate_hz: u32) -> u32 {
+    if sample_rate_hz % 11_025 == 0 {
+        44_100
+    } else if sample_rate_hz % 8_000 == 0 {
+        48_000
+    } else {
+        sample_rate_hz
+    }
+}
+
+/// Whether output running at `output_rate_hz` can play a track preferring `track_rate_hz`
+/// without switching.
+fn output_covers(policy: RateGroupingPolicy, output_rate_hz: u32, track_rate_hz: u32) -> bool {
+    track_rate_hz == output_rate_hz
+        || (policy.group_families
+            && track_rate_hz < output_rate_hz
+            && rate_family(track_rate_hz) == rate_family(output_rate_hz))
+}
+
+/// Output rate to use for the first of `upcoming` tracks, given in playback order as each
+/// track's preferred output rate (`None` when not known yet).
+///
+/// With the policy disabled this is simply the first track's rate. Unknown rates keep the
+/// current output.
+pub fn plan_output_rate(
+    policy: RateGroupingPolicy,
+    current_rate_hz: Option<u32>,
+    upcoming: &[Option<u32>],
+) -> Option<u32> {
+    let Some(track_rate_hz) = upcoming.first().copied().flatten() else {
+        return current_rate_hz;
+    };
+    if !policy.is_enabled() {
+        return Some(track_rate_hz);
+    }
+    if let Some(current_rate_hz) = current_rate_hz {
+        if output_covers(policy, current_rate_hz, track_rate_hz) {
+            return Some(current_rate_hz);
+        }
+        let next_rate_hz = upcoming.get(1).copied().flatten();
+        if policy.resample_outliers
+            && next_rate_hz.is_some_and(|next| output_covers(policy, current_rate_hz, next))
+        {
+            return Some(current_rate_hz);
+        }
+    }
+    if !policy.group_families {
+        return Some(track_rate_hz);
+    }
+    let family = rate_family(track_rate_hz);
+    upcoming
+        .iter()
+        .map_while(|rate| rate.filter(|rate| rate_family(*rate) == family))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROUPING: RateGroupingPolicy = RateGroupingPolicy {
+        group_families: true,
+        resample_outliers: false,
+    };
+    const OUTLIERS: RateGroupingPolicy = RateGroupingPolicy {
+        group_families: false,
+        resample_outliers: true,
+    };
+
+    #[test]
+    fn test_rate_family_splits_44k_and_48k_multiples() {
+        assert_eq!(rate_family(44_100), 44_100);
+        assert_eq!(rate_family(88_200), 44_100);
+        assert_eq!(rate_family(352_800), 44_100);
+        assert_eq!(rate_family(48_000), 48_000);
+        assert_eq!(rate_family(192_000), 48_000);
+        assert_eq!(rate_family(32_000), 48_000);
+        assert_eq!(rate_family(37_800), 37_800);
+    }
+
+    #[test]
+    fn test_rate_choices_label_and_find_nearest() {
+        assert_eq!(rate_choice_label(44_100), "44.1 kHz");
+        assert_eq!(rate_choice_label(192_000), "192 kHz");
+        assert_eq!(rate_choice_index(48_000), 1);
+        assert_eq!(rate_choice_index(100_000), 3);
+        assert_eq!(rate_choice_index(8_000), 0);
+    }
+
+    #[test]
+    fn test_plan_output_rate_without_policy_follows_each_track() {
+        let policy = RateGroupingPolicy::default();
+        assert_eq!(
+            plan_output_rate(policy, Some(88_200), &[Some(44_100), Some(88_200)]),
+            Some(44_100)
+        );
+        assert_eq!(
+            plan_output_rate(policy, Some(48_000), &[None, Some(44_100)]),
+            Some(48_000)
+        );
+    }
+
+    #[test]
+    fn test_plan_output_rate_groups_same_family_runs() {
+        assert_eq!(
+            plan_output_rate(GROUPING, Some(88_200), &[Some(44_100), Some(48_000)]),
+            Some(88_200)
+        );
+        assert_eq!(
+            plan_output_rate(
+                GROUPING,
+                Some(48_000),
+                &[Some(44_100), Some(88_200), Some(44_100), Some(96_000)]
+            ),
+            Some(88_200)
+        );
+        // Switching up within a family still happens; the output never drops below
+        // the track's own rate.
+        assert_eq!(
+            plan_output_rate(GROUPING, Some(44_100), &[Some(88_200)]),
+            Some(88_200)
+        );
+        // A lone outlier still switches without outlier resampling.
+        assert_eq!(
+            plan_output_rate(GROUPING, Some(44_100), &[Some(48_000), Some(44_100)]),
+            Some(48_000)
+        );
+    }
+
+    #[test]
+    fn test_plan_output_rate_resamples_isolated_outliers() {
+        assert_eq!(
+            plan_output_rate(OUTLIERS, Some(44_100), &[Some(48_000), Some(44_100)]),
+            Some(44_100)
+        );
+        assert_eq!(
+            plan_output_rate(OUTLIERS, Some(44_100), &[Some(48_000), Some(48_000)]),
+            Some(48_000)
+        );
+        assert_eq!(
+            plan_output_rate(OUTLIERS, Some(44_100), &[Some(48_000), None]),
+            Some(48_000)
+        );
+        let both = RateGroupingPolicy {
+            group_families: true,
+            resample_outliers: true,
+        };
+        assert_eq!(
+            plan_output_rate(both, Some(88_200), &[Some(96_000), Some(44_100)]),
+            Some(88_200)
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Chapter and silence markers for the seek bar of long files.
+//!
+//! Long mixes and recorded sets are hard to navigate by dragging alone. Chapters come
+//! from the file's embedded cue sheet, or a `.cue` file with the same stem next to it;
+//! silence markers reuse the loudness windows of intro/outro detection and mark where
+//! audio resumes after a gap. Markers are only computed for files of at least
+//! [`MIN_MARKER_TRACK_MS`].
+
+use std::path::Path;
+
+use crate::cue_sheet::{self, CueSheet};
+use crate::intro_outro_detection::{
+    silence_gaps, silence_threshold_db, window_loudness_db, ANALYSIS_WINDOW_MS,
+};
+use crate::metadata_tags;
+use crate::protocol::{SeekMarker, SeekMarkerKind};
+use crate::sandbox_access;
+
+/// Shortest track that gets seek bar markers.
+pub const MIN_MARKER_TRACK_MS: u64 = 20 * 60 * 1000;
+/// Shortest silence gap that becomes a marker.
+const MIN_SILENCE_MARKER_MS: u64 = 1_000;
+/// Chapters this close to the start of the file add nothing over seeking to zero.
+const MIN_CHAPTER_OFFSET_MS: u64 = 1_000;
+/// Silence markers this close to a chapter are dropped in favor of the chapter.
+const CHAPTER_MERGE_MS: u64 = 5_000;
+/// Markers this close behind the playhead are skipped when jumping forward, so
+/// repeated presses move past a marker that was just jumped to.
+const NEXT_MARKER_SLACK_MS: u64 = 1_000;
+/// Jumping back from within this distance after a marker goes to the one before it.
+const PREVIOUS_MARKER_SLACK_MS: u64 = 3_000;
+
+fn read_sidecar_cue_sheet(path: &Path) -> Option<CueSheet> {
+    let bytes = sandbox_access::read_file(&path.with_extension("cue")).ok()?;
+    let text = String::from_utf8(bytes).ok()?;
+    cue_sheet::parse_cue_sheet(&text)
+}
+
+/// Reads chapter markers and decodes `path` to find silence markers.
+pub fn detect_seek_markers(path: &Path) -> Result<Vec<SeekMarker>, String> {
+    let chapter_starts_ms: Vec<u64> = metadata_tags::read_embedded_cue_sheet(path)
+        .or_else(|| read_sidecar_cue_sheet(path))
+        .map(|sheet| sheet.tracks.iter().map(|track| track.start_ms).collect())
+        .unwrap_or_default();
+    let energies_db = window_loudness_db(path)?;
+    Ok(build_seek_markers(
+        &chapter_starts_ms,
+        &energies_db,
+        ANALYSIS_WINDOW_MS,
+    ))
+}
+
+/// Combines chapter starts and silence gaps into markers sorted by position.
+pub fn build_seek_markers(
+    chapter_starts_ms: &[u64],
+    energies_db: &[f32],
+    window_ms: u64,
+) -> Vec<SeekMarker> {
+    let window_ms = window_ms.max(1);
+    let mut markers: Vec<SeekMarker> = chapter_starts_ms
+        .iter()
+        .filter(|start_ms| **start_ms >= MIN_CHAPTER_OFFSET_MS)
+        .map(|start_ms| SeekMarker {
+            position_ms: *start_ms,
+            kind: SeekMarkerKind::Chapter,
+        })
+        .collect();
+    if !energies_db.is_empty() {
+        let min_windows = (MIN_SILENCE_MARKER_MS / window_ms).max(1) as usize;
+        let gaps = silence_gaps(energies_db, silence_threshold_db(energies_db), min_windows);
+        for (start, end) in gaps {
+            // Leading and trailing silence is not a boundary between two pieces.
+            if start == 0 || end == energies_db.len() {
+                continue;
+            }
+            let position_ms = end as u64 * window_ms;
+            if chapter_starts_ms
+                .iter()
+                .any(|chapter_ms| chapter_ms.abs_diff(position_ms) <= CHAPTER_MERGE_MS)
+            {
+                continue;
+            }
+            markers.push(SeekMarker {
+                position_ms,
+                kind: SeekMarkerKind::Silence,
+            });
+        }
+    }
+    markers.sort_by_key(|marker| marker.position_ms);
+    markers.dedup_by_key(|marker| marker.position_ms);
+    markers
+}
+
+/// Returns the marker closest to `target_ms` within `tolerance_ms`, or `target_ms`.
+pub fn snap_to_marker(target_ms: u64, markers_ms: &[u64], tolerance_ms: u64) -> u64 {
+    markers_ms
+        .iter()
+        .copied()
+        .filter(|marker_ms| marker_ms.abs_diff(target_ms) <= tolerance_ms)
+        .min_by_key(|marker_ms| marker_ms.abs_diff(target_ms))
+        .unwrap_or(target_ms)
+}
+
+/// Returns the marker to jump to from `current_ms`.
+///
+/// Going back from the first marker returns the start of the file; going forward past
+/// the last marker returns `None`.
+pub fn adjacent_marker(current_ms: u64, markers_ms: &[u64], forward: bool) -> Option<u64> {
+    if forward {
+        return markers_ms
+            .iter()
+            .copied()
+            .filter(|marker_ms| *marker_ms > current_ms.saturating_add(NEXT_MARKER_SLACK_MS))
+            .min();
+    }
+    let limit_ms = current_ms.checked_sub(PREVIOUS_MARKER_SLACK_MS)?;
+    Some(
+        markers_ms
+            .iter()
+            .copied()
+            .filter(|marker_ms| *marker_ms < limit_ms)
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(energy_db: f32, seconds: u64) -> Vec<f32> {
+        vec![energy_db; (seconds * 1000 / ANALYSIS_WINDOW_MS) as usize]
+    }
+
+    #[test]
+    fn test_build_seek_markers_merges_chapters_and_inner_silence() {
+        let energies = [
+            segment(-80.0, 2),
+            segment(-14.0, 600),
+            segment(-80.0, 2),
+            segment(-14.0, 600),
+            segment(-80.0, 2),
+            segment(-14.0, 600),
+            segment(-80.0, 3),
+        ]
+        .concat();
+        let markers = build_seek_markers(&[0, 603_000, 1_500_000], &energies, ANALYSIS_WINDOW_MS);
+        assert_eq!(
+            markers,
+            vec![
+                SeekMarker {
+                    position_ms: 603_000,
+                    kind: SeekMarkerKind::Chapter,
+                },
+                SeekMarker {
+                    position_ms: 1_206_000,
+                    kind: SeekMarkerKind::Silence,
+                },
+                SeekMarker {
+                    position_ms: 1_500_000,
+                    kind: SeekMarkerKind::Chapter,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snap_to_marker_picks_nearest_within_tolerance() {
+        let markers = [60_000, 120_000];
+        assert_eq!(snap_to_marker(61_500, &markers, 2_000), 60_000);
+        assert_eq!(snap_to_marker(90_000, &markers, 2_000), 90_000);
+        assert_eq!(snap_to_marker(119_000, &markers, 5_000), 120_000);
+    }
+
+    #[test]
+    fn test_adjacent_marker_steps_past_recent_markers() {
+        let markers = [60_000, 120_000];
+        assert_eq!(adjacent_marker(0, &markers, true), Some(60_000));
+        assert_eq!(adjacent_marker(60_400, &markers, true), Some(120_000));
+        assert_eq!(adjacent_marker(130_000, &markers, true), None);
+        assert_eq!(adjacent_marker(130_000, &markers, false), Some(120_000));
+        assert_eq!(adjacent_marker(121_000, &markers, false), Some(60_000));
+        assert_eq!(adjacent_marker(30_000, &markers, false), Some(0));
+        assert_eq!(adjacent_marker(1_000, &markers, false), None);
+    }
+}
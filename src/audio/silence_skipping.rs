@@ -0,0 +1,221 @@
+//! Real-time silence skipping for spoken-word playback (podcasts, audiobooks).
+//!
+//! Decoded audio passes through a [`SilenceSkipper`] that holds back quiet frames. Pauses
+//! shorter than the configured minimum are released unchanged. Longer pauses are cut down
+//! to a short breath: a little silence is kept on each side of the cut and the two edges
+//! are crossfaded so low-level room noise does not click. The playback position advances
+//! by the audio actually played, so it runs behind file time while silence is cut.
+
+use std::collections::VecDeque;
+
+/// Frames whose loudest sample stays below this level count as silence.
+const SILENCE_THRESHOLD_DBFS: f32 = -45.0;
+/// Pause kept in place of a cut, split evenly around the cut.
+const KEPT_PAUSE_MS: u32 = 200;
+/// Crossfade length across the cut.
+const CROSSFADE_MS: u32 = 10;
+
+/// Minimum pause lengths offered by the settings dialog, in milliseconds.
+pub const MIN_PAUSE_CHOICES_MS: &[u32] = &[250, 500, 750, 1_000, 1_500, 2_000, 3_000, 5_000];
+
+/// Genre keywords that mark a track as podcast or audiobook content.
+const SPOKEN_WORD_GENRE_KEYWORDS: &[&str] = &[
+    "podcast",
+    "audiobook",
+    "audio book",
+    "spoken word",
+    "speech",
+];
+
+/// Settings label for a minimum pause, e.g. `750 ms` or `1.5 s`.
+pub fn min_pause_label(ms: u32) -> String {
+    if ms >= 1_000 {
+        format!("{} s", f64::from(ms) / 1_000.0)
+    } else {
+        format!("{ms} ms")
+    }
+}
+
+/// Index of the offered minimum pause closest to `ms`.
+pub fn min_pause_choice_index(ms: u32) -> usize {
+    MIN_PAUSE_CHOICES_MS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, choice)| choice.abs_diff(ms))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Returns whether a genre tag names podcast or audiobook content.
+pub fn is_spoken_word_genre(genre: &str) -> bool {
+    let genre = genre.to_lowercase();
+    SPOKEN_WORD_GENRE_KEYWORDS
+        .iter()
+        .any(|keyword| genre.contains(keyword))
+}
+
+/// Streaming silence remover for interleaved `f32` audio of one track.
+pub struct SilenceSkipper {
+    channels: usize,
+    threshold: f32,
+    min_pause_frames: usize,
+    edge_frames: usize,
+    fade_frames: usize,
+    /// Quiet frames held back since the last audible frame. Once the pause is long enough
+    /// to cut, only its first `edge_frames` stay here.
+    held: Vec<f32>,
+    /// Rolling last `edge_frames` of a pause that is being cut.
+    tail: VecDeque<f32>,
+    held_frames: usize,
+    cutting: bool,
+}
+
+impl SilenceSkipper {
+    /// Creates a skipper that cuts pauses longer than `min_pause_ms`.
+    pub fn new(sample_rate_hz: u32, channels: usize, min_pause_ms: u32) -> Self {
+        let frames_for = |ms: u32| (u64::from(sample_rate_hz) * u64::from(ms) / 1000) as usize;
+        let min_pause_frames = frames_for(min_pause_ms).max(1);
+        let edge_frames = frames_for(KEPT_PAUSE_MS / 2).min(min_pause_frames / 2);
+        Self {
+            channels: channels.max(1),
+            threshold: 10f32.powf(SILENCE_THRESHOLD_DBFS / 20.0),
+            min_pause_frames,
+            edge_frames,
+            fade_frames: frames_for(CROSSFADE_MS).min(edge_frames),
+            held: Vec::new(),
+            tail: VecDeque::new(),
+            held_frames: 0,
+            cutting: false,
+        }
+    }
+
+    /// Returns the audio to play for `samples`, holding back any trailing pause.
+    ///
+    /// Silence still held when the track ends is dropped.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut output = Vec::with_capacity(samples.len());
+        for frame in samples.chunks_exact(self.channels) {
+            if frame.iter().all(|sample| sample.abs() < self.threshold) {
+                self.hold_silent_frame(frame);
+            } else {
+                self.release_pause(&mut output);
+                output.extend_from_slice(frame);
+            }
+        }
+        output
+    }
+
+    fn hold_silent_frame(&mut self, frame: &[f32]) {
+        self.held_frames += 1;
+        if self.cutting {
+            self.tail.extend(frame.iter().copied());
+            while self.tail.len() > self.edge_frames * self.channels {
+                self.tail.pop_front();
+            }
+            return;
+        }
+        self.held.extend_from_slice(frame);
+        if self.held_frames > self.min_pause_frames {
+            let head_len = self.edge_frames * self.channels;
+            let tail_start = self.held.len() - head_len;
+            self.tail = self.held[tail_start..].iter().copied().collect();
+            self.held.truncate(head_len);
+            self.cutting = true;
+        }
+    }
+
+    fn release_pause(&mut self, output: &mut Vec<f32>) {
+        if self.held_frames == 0 {
+            return;
+        }
+        if self.cutting {
+            let channels = self.channels;
+            let fade_start = (self.edge_frames - self.fade_frames) * channels;
+            output.extend_from_slice(&self.held[..fade_start]);
+            let tail = self.tail.make_contiguous();
+            for frame in 0..self.fade_frames {
+                let fade_in = (frame + 1) as f32 / (self.fade_frames + 1) as f32;
+                for channel in 0..channels {
+                    let index = frame * channels + channel;
+                    output.push(
+                        self.held[fade_start + index] * (1.0 - fade_in) + tail[index] * fade_in,
+                    );
+                }
+            }
+            output.extend_from_slice(&tail[self.fade_frames * channels..]);
+        } else {
+            output.extend_from_slice(&self.held);
+        }
+        self.held.clear();
+        self.tail.clear();
+        self.held_frames = 0;
+        self.cutting = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 1_000;
+
+    fn stereo(frames: usize, level: f32) -> Vec<f32> {
+        vec![level; frames * 2]
+    }
+
+    #[test]
+    fn test_short_pauses_pass_through_unchanged() {
+        let mut skipper = SilenceSkipper::new(RATE, 2, 500);
+        let mut input = stereo(100, 0.5);
+        input.extend(stereo(400, 0.0));
+        input.extend(stereo(100, 0.5));
+
+        let output = skipper.process(&input);
+
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_long_pause_is_cut_to_kept_pause_with_crossfade() {
+        let mut skipper = SilenceSkipper::new(RATE, 2, 500);
+        let mut input = stereo(100, 0.5);
+        input.extend(stereo(2_000, 0.001));
+        input.extend(stereo(100, -0.5));
+
+        let mut output = skipper.process(&input[..1_000]);
+        output.extend(skipper.process(&input[1_000..]));
+
+        let edge = (KEPT_PAUSE_MS / 2) as usize;
+        let fade = CROSSFADE_MS as usize;
+        assert_eq!(output.len(), (100 + 2 * edge - fade + 100) * 2);
+        assert!(output[..200].iter().all(|sample| *sample == 0.5));
+        assert!(output[output.len() - 200..]
+            .iter()
+            .all(|sample| *sample == -0.5));
+    }
+
+    #[test]
+    fn test_trailing_silence_is_held_back() {
+        let mut skipper = SilenceSkipper::new(RATE, 2, 500);
+        let mut input = stereo(10, 0.5);
+        input.extend(stereo(50, 0.0));
+
+        assert_eq!(skipper.process(&input).len(), 20);
+    }
+
+    #[test]
+    fn test_is_spoken_word_genre_matches_podcast_and_audiobook_tags() {
+        assert!(is_spoken_word_genre("Podcast"));
+        assert!(is_spoken_word_genre("Audiobooks"));
+        assert!(is_spoken_word_genre("Books & Spoken Word"));
+        assert!(!is_spoken_word_genre("Post-Rock"));
+    }
+
+    #[test]
+    fn test_min_pause_choices_round_trip_labels_and_indices() {
+        assert_eq!(min_pause_label(750), "750 ms");
+        assert_eq!(min_pause_label(1_500), "1.5 s");
+        assert_eq!(min_pause_label(2_000), "2 s");
+        assert_eq!(MIN_PAUSE_CHOICES_MS[min_pause_choice_index(1_100)], 1_000);
+    }
+}
@@ -0,0 +1,544 @@
+//! Spectrograms for the live Spectrogram panel and the Properties dialog.
+//!
+//! Both views run the same short-time Fourier transform over the mono downmix:
+//! Hann-windowed frames of [`FFT_SIZE`] samples, drawn bottom-up from 0 Hz to Nyquist on
+//! a linear axis so the hard shelf a lossy encoder leaves around 16-20 kHz is easy to
+//! spot. The live panel is fed by the output callback through [`live_tap`], which never
+//! makes the audio thread wait: while nothing drains the tap, new samples are dropped.
+
+use std::{
+    collections::VecDeque,
+    f32::consts::PI,
+    path::Path,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex, MutexGuard, OnceLock,
+    },
+};
+
+/// Samples per analysis frame; a power of two for the radix-2 transform.
+pub const FFT_SIZE: usize = 2048;
+/// Mono frames between live panel columns, about 47 columns per second at 48 kHz.
+pub const LIVE_HOP: usize = 1024;
+/// Quietest level drawn; anything below is black.
+const FLOOR_DB: f32 = -110.0;
+/// Mono frames the live tap holds before the output callback starts dropping samples.
+const LIVE_TAP_CAPACITY: usize = 1 << 15;
+
+/// Radix-2 FFT with a Hann window, producing band levels in dBFS.
+pub struct SpectrumAnalyzer {
+    window: Vec<f32>,
+    twiddles: Vec<(f32, f32)>,
+    real: Vec<f32>,
+    imag: Vec<f32>,
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpectrumAnalyzer {
+    pub fn new() -> Self {
+        let window = (0..FFT_SIZE)
+            .map(|index| 0.5 - 0.5 * (2.0 * PI * index as f32 / FFT_SIZE as f32).cos())
+            .collect();
+        let twiddles = (0..FFT_SIZE / 2)
+            .map(|index| {
+                let angle = -2.0 * PI * index as f32 / FFT_SIZE as f32;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        Self {
+            window,
+            twiddles,
+            real: vec![0.0; FFT_SIZE],
+            imag: vec![0.0; FFT_SIZE],
+        }
+    }
+
+    /// Amplitude of each bin of one [`FFT_SIZE`]-sample frame, from 0 Hz up to just
+    /// below Nyquist, scaled so a full-scale sine reads 1.0.
+    pub fn amplitudes(&mut self, frame: &[f32]) -> Vec<f32> {
+        debug_assert_eq!(frame.len(), FFT_SIZE);
+        for (index, sample) in frame.iter().enumerate() {
+            self.real[index] = sample * self.window[index];
+            self.imag[index] = 0.0;
+        }
+        self.transform();
+
+        // The Hann window halves the coherent gain, so a unit sine peaks at N / 4.
+        let scale = 4.0 / FFT_SIZE as f32;
+        (0..FFT_SIZE / 2)
+            .map(|bin| self.real[bin].hypot(self.imag[bin]) * scale)
+            .collect()
+    }
+
+    /// Levels of one frame folded into `rows` equal-width bands, highest band first.
+    /// Each band keeps its loudest bin; a full-scale sine reads 0 dB.
+    pub fn column(&mut self, frame: &[f32], rows: usize) -> Vec<f32> {
+        let amplitudes = self.amplitudes(frame);
+        let bins = amplitudes.len();
+        (0..rows)
+            .map(|row| {
+                let band = rows - 1 - row;
+                let start = band * bins / rows;
+                let end = ((band + 1) * bins / rows).max(start + 1);
+                let peak = amplitudes[start..end].iter().copied().fold(0.0, f32::max);
+                amplitude_to_db(peak)
+            })
+            .collect()
+    }
+
+    fn transform(&mut self) {
+        let (real, imag) = (&mut self.real, &mut self.imag);
+        let mut swap_index = 0;
+        for index in 1..FFT_SIZE {
+            let mut bit = FFT_SIZE >> 1;
+            while swap_index & bit != 0 {
+                swap_index ^= bit;
+                bit >>= 1;
+            }
+            swap_index |= bit;
+            if index < swap_index {
+                real.swap(index, swap_index);
+                imag.swap(index, swap_index);
+            }
+        }
+
+        let mut span = 2;
+        while span <= FFT_SIZE {
+            let half = span / 2;
+            let stride = FFT_SIZE / span;
+            for start in (0..FFT_SIZE).step_by(span) {
+                for offset in 0..half {
+                    let (twiddle_re, twiddle_im) = self.twiddles[offset * stride];
+                    let even = start + offset;
+                    let odd = even + half;
+                    let odd_re = real[odd] * twiddle_re - imag[odd] * twiddle_im;
+                    let odd_im = real[odd] * twiddle_im + imag[odd] * twiddle_re;
+                    real[odd] = real[even] - odd_re;
+                    imag[odd] = imag[even] - odd_im;
+                    real[even] += odd_re;
+                    imag[even] += odd_im;
+                }
+            }
+            span <<= 1;
+        }
+    }
+}
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return FLOOR_DB;
+    }
+    (20.0 * amplitude.log10()).max(FLOOR_DB)
+}
+
+/// Range covered by the frequency axis, e.g. `0-22.05 kHz`.
+pub fn frequency_range_label(sample_rate_hz: u32) -> String {
+    let nyquist_khz = format!("{:.2}", sample_rate_hz as f64 / 2000.0);
+    let nyquist_khz = nyquist_khz.trim_end_matches('0').trim_end_matches('.');
+    format!("0-{nyquist_khz} kHz")
+}
+
+/// Palette from black through violet, red, and orange to pale yellow at 0 dB.
+fn level_color(level_db: f32) -> [u8; 4] {
+    const STOPS: [(f32, [f32; 3]); 6] = [
+        (0.0, [0.0, 0.0, 0.0]),
+        (0.2, [32.0, 12.0, 74.0]),
+        (0.45, [132.0, 28.0, 110.0]),
+        (0.7, [232.0, 88.0, 36.0]),
+        (0.88, [252.0, 196.0, 64.0]),
+        (1.0, [255.0, 252.0, 220.0]),
+    ];
+    let position = ((level_db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0);
+    let upper = STOPS
+        .iter()
+        .position(|(stop, _)| *stop >= position)
+        .unwrap_or(STOPS.len() - 1)
+        .max(1);
+    let (low_stop, low_color) = STOPS[upper - 1];
+    let (high_stop, high_color) = STOPS[upper];
+    let mix = (position - low_stop) / (high_stop - low_stop);
+    let channel = |index: usize| {
+        (low_color[index] + (high_color[index] - low_color[index]) * mix).round() as u8
+    };
+    [channel(0), channel(1), channel(2), 255]
+}
+
+/// RGBA pixels of a spectrogram, one column per analysis frame or group of frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpectrogramImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl SpectrogramImage {
+    /// Black image of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        let rgba = [0, 0, 0, 255].repeat(width as usize * height as usize);
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// Draws `levels` (top row first) into column `x`.
+    pub fn set_column(&mut self, x: usize, levels: &[f32]) {
+        let width = self.width as usize;
+        for (row, level_db) in levels.iter().take(self.height as usize).enumerate() {
+            let offset = (row * width + x) * 4;
+            self.rgba[offset..offset + 4].copy_from_slice(&level_color(*level_db));
+        }
+    }
+
+    /// Shifts every row one pixel left and draws `levels` into the freed last column.
+    pub fn push_column(&mut self, levels: &[f32]) {
+        let row_bytes = self.width as usize * 4;
+        if row_bytes == 0 {
+            return;
+        }
+        for row_start in (0..self.rgba.len()).step_by(row_bytes) {
+            self.rgba
+                .copy_within(row_start + 4..row_start + row_bytes, row_start);
+        }
+        self.set_column(self.width as usize - 1, levels);
+    }
+}
+
+/// Scrolling view fed from [`live_tap`], one column per [`LIVE_HOP`] frames.
+pub struct LiveSpectrogram {
+    analyzer: SpectrumAnalyzer,
+    pending: Vec<f32>,
+    image: SpectrogramImage,
+}
+
+impl LiveSpectrogram {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            analyzer: SpectrumAnalyzer::new(),
+            pending: Vec::with_capacity(FFT_SIZE + LIVE_TAP_CAPACITY),
+            image: SpectrogramImage::new(width, height),
+        }
+    }
+
+    /// Draws a column for every complete hop in `samples`; returns whether any was drawn.
+    pub fn feed(&mut self, samples: &[f32]) -> bool {
+        self.pending.extend_from_slice(samples);
+        let mut drawn = false;
+        while self.pending.len() >= FFT_SIZE {
+            let levels = self
+                .analyzer
+                .column(&self.pending[..FFT_SIZE], self.image.height as usize);
+            self.image.push_column(&levels);
+            self.pending.drain(..LIVE_HOP);
+            drawn = true;
+        }
+        drawn
+    }
+
+    pub fn image(&self) -> &SpectrogramImage {
+        &self.image
+    }
+}
+
+#[derive(Default)]
+struct TapState {
+    samples: VecDeque<f32>,
+    channels: usize,
+    frame_sum: f32,
+    frame_fill: usize,
+}
+
+/// Bounded hand-off of mono samples from the output callback to the Spectrogram panel.
+pub struct SpectrogramTap {
+    state: Mutex<TapState>,
+    sample_rate_hz: AtomicU32,
+}
+
+/// Tap shared by the audio player and every Spectrogram panel.
+pub fn live_tap() -> &'static SpectrogramTap {
+    static LIVE_TAP: OnceLock<SpectrogramTap> = OnceLock::new();
+    LIVE_TAP.get_or_init(SpectrogramTap::new)
+}
+
+impl SpectrogramTap {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(TapState {
+                samples: VecDeque::with_capacity(LIVE_TAP_CAPACITY * 2),
+                channels: 2,
+                ..TapState::default()
+            }),
+            sample_rate_hz: AtomicU32::new(48_000),
+        }
+    }
+
+    /// Called when an output stream opens; drops samples left from the previous stream.
+    pub fn configure(&self, sample_rate_hz: u32, channels: u16) {
+        let mut state = self.state.lock().expect("spectrogram tap lock poisoned");
+        state.samples.clear();
+        state.channels = usize::from(channels.max(1));
+        state.frame_sum = 0.0;
+        state.frame_fill = 0;
+        self.sample_rate_hz.store(sample_rate_hz, Ordering::Relaxed);
+    }
+
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz.load(Ordering::Relaxed)
+    }
+
+    /// Writer for one output callback, or `None` while a panel is draining the tap or
+    /// nothing has drained it for a while.
+    pub fn writer(&self) -> Option<SpectrogramTapWriter<'_>> {
+        let state = self.state.try_lock().ok()?;
+        (state.samples.len() < LIVE_TAP_CAPACITY).then_some(SpectrogramTapWriter { state })
+    }
+
+    /// Moves every buffered mono sample to the end of `out`.
+    pub fn drain_into(&self, out: &mut Vec<f32>) {
+        let mut state = self.state.lock().expect("spectrogram tap lock poisoned");
+        out.extend(state.samples.drain(..));
+    }
+}
+
+/// Holds the tap for one output callback.
+pub struct SpectrogramTapWriter<'a> {
+    state: MutexGuard<'a, TapState>,
+}
+
+impl SpectrogramTapWriter<'_> {
+    /// Adds one interleaved output sample; each complete frame becomes one mono sample.
+    pub fn push(&mut self, sample: f32) {
+        let state = &mut *self.state;
+        state.frame_sum += sample;
+        state.frame_fill += 1;
+        if state.frame_fill >= state.channels {
+            let mono = state.frame_sum / state.frame_fill as f32;
+            state.samples.push_back(mono);
+            state.frame_sum = 0.0;
+            state.frame_fill = 0;
+        }
+    }
+}
+
+/// Collects full-track columns in bounded memory by merging neighbours once more than
+/// twice the output width have been gathered.
+struct ColumnAccumulator {
+    width: usize,
+    columns: Vec<Vec<f32>>,
+    frames_per_column: usize,
+    current: Option<Vec<f32>>,
+    current_frames: usize,
+}
+
+fn merge_loudest(into: &mut [f32], levels: &[f32]) {
+    for (level, other) in into.iter_mut().zip(levels) {
+        *level = level.max(*other);
+    }
+}
+
+impl ColumnAccumulator {
+    fn new(width: usize) -> Self {
+        Self {
+            width: width.max(1),
+            columns: Vec::new(),
+            frames_per_column: 1,
+            current: None,
+            current_frames: 0,
+        }
+    }
+
+    fn push(&mut self, levels: Vec<f32>) {
+        match self.current.as_mut() {
+            Some(current) => merge_loudest(current, &levels),
+            None => self.current = Some(levels),
+        }
+        self.current_frames += 1;
+        if self.current_frames < self.frames_per_column {
+            return;
+        }
+        if let Some(current) = self.current.take() {
+            self.columns.push(current);
+        }
+        self.current_frames = 0;
+        if self.columns.len() >= self.width * 2 {
+            self.columns = self
+                .columns
+                .chunks_mut(2)
+                .map(|pair| {
+                    let mut merged = std::mem::take(&mut pair[0]);
+                    if let Some(second) = pair.get(1) {
+                        merge_loudest(&mut merged, second);
+                    }
+                    merged
+                })
+                .collect();
+            self.frames_per_column *= 2;
+        }
+    }
+
+    fn into_image(mut self, height: u32) -> Option<SpectrogramImage> {
+        if let Some(current) = self.current.take() {
+            self.columns.push(current);
+        }
+        if self.columns.is_empty() {
+            return None;
+        }
+        let mut image = SpectrogramImage::new(self.width as u32, height);
+        let count = self.columns.len();
+        for x in 0..self.width {
+            let start = x * count / self.width;
+            let end = ((x + 1) * count / self.width).max(start + 1);
+            let mut levels = self.columns[start].clone();
+            for column in &self.columns[start + 1..end] {
+                merge_loudest(&mut levels, column);
+            }
+            image.set_column(x, &levels);
+        }
+        Some(image)
+    }
+}
+
+/// Decodes `path` and renders its whole length as a `width` x `height` spectrogram.
+/// Frames overlap by half; when a column covers several frames it keeps the loudest
+/// level of each band. Also returns the source sample rate.
+pub fn render_file_spectrogram(
+    path: &Path,
+    width: u32,
+    height: u32,
+) -> Result<(SpectrogramImage, u32), String> {
+    let mut analyzer = SpectrumAnalyzer::new();
+    let mut columns = ColumnAccumulator::new(width as usize);
+    let mut pending: Vec<f32> = Vec::with_capacity(FFT_SIZE * 2);
+    let mut sample_rate_hz = 0;
+    crate::intro_outro_detection::for_each_decoded_buffer(path, |samples, channels, rate| {
+        sample_rate_hz = rate;
+        let channels = channels.max(1);
+        pending.extend(
+            samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+        while pending.len() >= FFT_SIZE {
+            columns.push(analyzer.column(&pending[..FFT_SIZE], height as usize));
+            pending.drain(..FFT_SIZE / 2);
+        }
+    })?;
+    if pending.len() > FFT_SIZE / 4 {
+        pending.resize(FFT_SIZE, 0.0);
+        columns.push(analyzer.column(&pending, height as usize));
+    }
+    let image = columns
+        .into_image(height)
+        .ok_or_else(|| "No audio could be decoded".to_string())?;
+    Ok((image, sample_rate_hz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency_hz: f32, sample_rate_hz: f32, amplitude: f32) -> Vec<f32> {
+        (0..FFT_SIZE)
+            .map(|index| {
+                amplitude * (2.0 * PI * frequency_hz * index as f32 / sample_rate_hz).sin()
+            })
+            .collect()
+    }
+
+    fn loudest_row(levels: &[f32]) -> usize {
+        levels
+            .iter()
+            .enumerate()
+            .max_by(|left, right| left.1.total_cmp(right.1))
+            .map(|(row, _)| row)
+            .expect("levels should not be empty")
+    }
+
+    #[test]
+    fn test_sine_lands_in_its_band_at_full_scale() {
+        let mut analyzer = SpectrumAnalyzer::new();
+        // 6 kHz is a quarter of Nyquist at 48 kHz, so band 25 of 100, row 74 from the top.
+        let levels = analyzer.column(&sine(6_000.0, 48_000.0, 1.0), 100);
+        assert_eq!(loudest_row(&levels), 74);
+        assert!(levels[74] > -1.0 && levels[74] < 0.5, "{}", levels[74]);
+        assert!(levels[10] < -90.0);
+
+        let quieter = analyzer.column(&sine(6_000.0, 48_000.0, 0.1), 100);
+        assert!((quieter[74] - (levels[74] - 20.0)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_silence_draws_black() {
+        let mut analyzer = SpectrumAnalyzer::new();
+        let levels = analyzer.column(&vec![0.0; FFT_SIZE], 8);
+        assert!(levels.iter().all(|level| *level == FLOOR_DB));
+        assert_eq!(level_color(FLOOR_DB), [0, 0, 0, 255]);
+        assert_eq!(level_color(0.0), [255, 252, 220, 255]);
+    }
+
+    #[test]
+    fn test_frequency_range_label_trims_trailing_zeros() {
+        assert_eq!(frequency_range_label(44_100), "0-22.05 kHz");
+        assert_eq!(frequency_range_label(48_000), "0-24 kHz");
+        assert_eq!(frequency_range_label(40_000), "0-20 kHz");
+    }
+
+    #[test]
+    fn test_push_column_scrolls_left() {
+        let mut image = SpectrogramImage::new(3, 2);
+        image.push_column(&[0.0, FLOOR_DB]);
+        image.push_column(&[FLOOR_DB, 0.0]);
+        let pixel = |x: usize, y: usize| {
+            let offset = (y * 3 + x) * 4;
+            image.rgba[offset..offset + 4].to_vec()
+        };
+        assert_eq!(pixel(1, 0), level_color(0.0));
+        assert_eq!(pixel(2, 0), level_color(FLOOR_DB));
+        assert_eq!(pixel(2, 1), level_color(0.0));
+        assert_eq!(pixel(0, 0), level_color(FLOOR_DB));
+    }
+
+    #[test]
+    fn test_long_tracks_merge_columns_keeping_loudest_level() {
+        let mut columns = ColumnAccumulator::new(4);
+        for index in 0..100 {
+            let level = if index == 37 { 0.0 } else { FLOOR_DB };
+            columns.push(vec![level]);
+        }
+        assert!(columns.columns.len() < 8);
+        let image = columns.into_image(1).expect("columns should render");
+        assert_eq!(image.width, 4);
+        let lit: Vec<bool> = image.rgba.chunks(4).map(|pixel| pixel[0] > 0).collect();
+        assert_eq!(lit, [false, true, false, false]);
+    }
+
+    #[test]
+    fn test_tap_downmixes_frames_and_stops_when_full() {
+        let tap = SpectrogramTap::new();
+        tap.configure(44_100, 2);
+        {
+            let mut writer = tap.writer().expect("empty tap should accept samples");
+            for sample in [1.0, 0.0, 0.5, 0.5, 0.25] {
+                writer.push(sample);
+            }
+        }
+        let mut drained = Vec::new();
+        tap.drain_into(&mut drained);
+        assert_eq!(drained, [0.5, 0.5]);
+        assert_eq!(tap.sample_rate_hz(), 44_100);
+
+        {
+            let mut writer = tap.writer().expect("drained tap should accept samples");
+            for _ in 0..LIVE_TAP_CAPACITY * 2 {
+                writer.push(0.0);
+            }
+        }
+        assert!(tap.writer().is_none());
+    }
+}
@@ -0,0 +1,320 @@
+//! Detection of lossless files decoded from a lossy source ("fake FLAC").
+//!
+//! MP3, AAC, and Vorbis encoders low-pass their input, typically somewhere between 15 and
+//! 20 kHz depending on bitrate, so a lossless file made from such a source shows a sharp
+//! wall in its spectrum with only the quantization floor above it. Genuine recordings
+//! roll off gradually or keep content up to the converter's anti-alias filter near
+//! Nyquist. The estimate averages the spectrum of the loud analysis frames, finds the
+//! steepest drop between 10 and 20.5 kHz, and weighs it by how consistently individual
+//! frames stop at that frequency.
+
+use std::path::Path;
+
+use crate::spectrogram::{SpectrumAnalyzer, FFT_SIZE};
+
+/// Confidence from which a file is reported as a suspected transcode.
+pub const SUSPICIOUS_CONFIDENCE: f32 = 0.6;
+/// Mono samples per analyzed frame plus the samples skipped after it.
+const ANALYSIS_STRIDE: usize = FFT_SIZE * 4;
+/// Frames quieter than this RMS level (dBFS) carry too little treble to judge.
+const MIN_FRAME_RMS_DB: f32 = -45.0;
+/// Bins above this level (dBFS) count as content when finding where a frame stops.
+const CONTENT_LEVEL_DB: f32 = -90.0;
+/// Loud frames needed for full confidence.
+const MIN_CONFIDENT_FRAMES: usize = 40;
+const SEARCH_LOW_HZ: f32 = 10_000.0;
+const SEARCH_HIGH_HZ: f32 = 20_500.0;
+/// Width of the bands compared on either side of a candidate cutoff.
+const WALL_BAND_HZ: f32 = 400.0;
+/// Gap left on either side of a candidate so the encoder's transition band is skipped.
+const WALL_GAP_HZ: f32 = 100.0;
+/// Drop across the wall below which no cutoff is reported, and from which it is certain.
+const MIN_WALL_DROP_DB: f32 = 12.0;
+const FULL_WALL_DROP_DB: f32 = 36.0;
+/// Content this far above the level just past the wall means the spectrum comes back.
+const MAX_RETURN_ABOVE_WALL_DB: f32 = 10.0;
+
+/// Where a file's spectrum stops and how likely that is to come from a lossy encoder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LossySourceEstimate {
+    /// Frequency of the spectral wall, or `None` when the spectrum has none.
+    pub cutoff_hz: Option<f32>,
+    /// From 0.0 (no sign of a lossy source) to 1.0.
+    pub confidence: f32,
+}
+
+impl LossySourceEstimate {
+    const NONE: Self = Self {
+        cutoff_hz: None,
+        confidence: 0.0,
+    };
+
+    pub fn is_suspicious(&self) -> bool {
+        self.confidence >= SUSPICIOUS_CONFIDENCE
+    }
+
+    /// Report detail, e.g. `spectrum cuts off at 16.0 kHz (92% confidence)`.
+    pub fn describe(&self) -> String {
+        match self.cutoff_hz {
+            Some(cutoff_hz) => format!(
+                "spectrum cuts off at {:.1} kHz ({:.0}% confidence)",
+                cutoff_hz / 1000.0,
+                self.confidence * 100.0
+            ),
+            None => "no lossy cutoff found".to_string(),
+        }
+    }
+}
+
+fn mean(levels: &[f32]) -> f32 {
+    levels.iter().sum::<f32>() / levels.len().max(1) as f32
+}
+
+/// Estimates a lossy source from the average level of each bin (dBFS) and the highest
+/// bin with content in each analyzed frame.
+fn estimate_from_spectrum(
+    average_db: &[f32],
+    sample_rate_hz: u32,
+    frame_top_bins: &[usize],
+) -> LossySourceEstimate {
+    let bin_hz = sample_rate_hz as f32 / FFT_SIZE as f32;
+    let to_bins = |hz: f32| (hz / bin_hz).round() as usize;
+    let gap = to_bins(WALL_GAP_HZ).max(1);
+    let band = to_bins(WALL_BAND_HZ).max(1);
+    let last_bin = (average_db.len() as f32 * 0.98) as usize;
+    let low = to_bins(SEARCH_LOW_HZ);
+    let high = to_bins(SEARCH_HIGH_HZ).min(last_bin.saturating_sub(gap + band));
+    if frame_top_bins.is_empty() || low < gap + band || high <= low {
+        return LossySourceEstimate::NONE;
+    }
+
+    let mut best: Option<(usize, f32, f32)> = None;
+    for candidate in low..=high {
+        let below = mean(&average_db[candidate - gap - band..candidate - gap]);
+        let above = mean(&average_db[candidate + gap..candidate + gap + band]);
+        let returns = average_db[candidate + gap..last_bin]
+            .iter()
+            .any(|level| *level > above + MAX_RETURN_ABOVE_WALL_DB);
+        let drop = below - above;
+        if !returns && best.is_none_or(|(_, best_drop, _)| drop > best_drop) {
+            best = Some((candidate, drop, above));
+        }
+    }
+    let Some((candidate, drop, above)) = best.filter(|(_, drop, _)| *drop >= MIN_WALL_DROP_DB)
+    else {
+        return LossySourceEstimate::NONE;
+    };
+    // Every candidate within the gap of the wall has the same drop; report the edge.
+    let midpoint = above + drop / 2.0;
+    let cutoff_bin = (candidate - gap..candidate + gap + band)
+        .rev()
+        .find(|bin| average_db[*bin] >= midpoint)
+        .unwrap_or(candidate);
+
+    let steepness =
+        ((drop - MIN_WALL_DROP_DB) / (FULL_WALL_DROP_DB - MIN_WALL_DROP_DB)).clamp(0.0, 1.0);
+    let consistency = frame_top_bins
+        .iter()
+        .filter(|top_bin| **top_bin <= cutoff_bin + gap)
+        .count() as f32
+        / frame_top_bins.len() as f32;
+    let cutoff_hz = cutoff_bin as f32 * bin_hz;
+    // Walls near 20 kHz also come from steep mastering or anti-alias filters.
+    let frequency_weight = if cutoff_hz < 17_000.0 {
+        1.0
+    } else if cutoff_hz < 19_500.0 {
+        0.9
+    } else {
+        0.75
+    };
+    let frame_weight =
+        frame_top_bins.len().min(MIN_CONFIDENT_FRAMES) as f32 / MIN_CONFIDENT_FRAMES as f32;
+    LossySourceEstimate {
+        cutoff_hz: Some(cutoff_hz),
+        confidence: steepness * (0.4 + 0.6 * consistency) * frequency_weight * frame_weight,
+    }
+}
+
+/// Spectrum statistics of the loud frames of one file.
+struct CutoffAccumulator {
+    analyzer: SpectrumAnalyzer,
+    sample_rate_hz: u32,
+    power_sums: Vec<f64>,
+    frame_top_bins: Vec<usize>,
+}
+
+impl CutoffAccumulator {
+    fn new(sample_rate_hz: u32) -> Self {
+        Self {
+            analyzer: SpectrumAnalyzer::new(),
+            sample_rate_hz,
+            power_sums: vec![0.0; FFT_SIZE / 2],
+            frame_top_bins: Vec::new(),
+        }
+    }
+
+    fn add_frame(&mut self, frame: &[f32]) {
+        let mean_square =
+            frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len() as f32;
+        if 10.0 * mean_square.max(f32::MIN_POSITIVE).log10() < MIN_FRAME_RMS_DB {
+            return;
+        }
+        let amplitudes = self.analyzer.amplitudes(frame);
+        for (sum, amplitude) in self.power_sums.iter_mut().zip(&amplitudes) {
+            *sum += f64::from(amplitude * amplitude);
+        }
+        let content_amplitude = 10f32.powf(CONTENT_LEVEL_DB / 20.0);
+        let top_bin = amplitudes
+            .iter()
+            .rposition(|amplitude| *amplitude > content_amplitude)
+            .unwrap_or(0);
+        self.frame_top_bins.push(top_bin);
+    }
+
+    fn estimate(&self) -> LossySourceEstimate {
+        let frames = self.frame_top_bins.len();
+        if frames == 0 {
+            return LossySourceEstimate::NONE;
+        }
+        let average_db: Vec<f32> = self
+            .power_sums
+            .iter()
+            .map(|sum| (10.0 * (sum / frames as f64).max(1e-16).log10()) as f32)
+            .collect();
+        estimate_from_spectrum(&average_db, self.sample_rate_hz, &self.frame_top_bins)
+    }
+}
+
+/// Decodes `path` and estimates whether it was made from a lossy source. One frame in
+/// four is analyzed, which is plenty to see a consistent cutoff.
+pub fn analyze_file(path: &Path) -> Result<LossySourceEstimate, String> {
+    let mut accumulator: Option<CutoffAccumulator> = None;
+    let mut frame = Vec::with_capacity(FFT_SIZE);
+    let mut skip_remaining = 0usize;
+    crate::intro_outro_detection::for_each_decoded_buffer(path, |samples, channels, rate| {
+        let accumulator = accumulator.get_or_insert_with(|| CutoffAccumulator::new(rate));
+        let channels = channels.max(1);
+        for mono in samples
+            .chunks_exact(channels)
+            .map(|samples| samples.iter().sum::<f32>() / channels as f32)
+        {
+            if skip_remaining > 0 {
+                skip_remaining -= 1;
+                continue;
+            }
+            frame.push(mono);
+            if frame.len() == FFT_SIZE {
+                accumulator.add_frame(&frame);
+                frame.clear();
+                skip_remaining = ANALYSIS_STRIDE - FFT_SIZE;
+            }
+        }
+    })?;
+    Ok(accumulator
+        .map(|accumulator| accumulator.estimate())
+        .unwrap_or(LossySourceEstimate::NONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE_HZ: u32 = 44_100;
+
+    fn bin_of(hz: f32) -> usize {
+        (hz * FFT_SIZE as f32 / SAMPLE_RATE_HZ as f32) as usize
+    }
+
+    /// Sines every 50 Hz up to `top_hz` with scattered phases, like band-limited noise.
+    fn dense_tone_frame(top_hz: f32) -> Vec<f32> {
+        let mut phase_seed = 12_345u32;
+        let tones: Vec<(f32, f32)> = (1..)
+            .map(|index| index as f32 * 50.0)
+            .take_while(|hz| *hz <= top_hz)
+            .map(|hz| {
+                phase_seed = phase_seed
+                    .wrapping_mul(1_664_525)
+                    .wrapping_add(1_013_904_223);
+                (
+                    hz,
+                    phase_seed as f32 / u32::MAX as f32 * std::f32::consts::TAU,
+                )
+            })
+            .collect();
+        (0..FFT_SIZE)
+            .map(|index| {
+                let time = index as f32 / SAMPLE_RATE_HZ as f32;
+                tones
+                    .iter()
+                    .map(|(hz, phase)| 0.005 * (std::f32::consts::TAU * hz * time + phase).sin())
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sharp_wall_is_a_confident_lossy_source() {
+        let wall = bin_of(16_000.0);
+        let average_db: Vec<f32> = (0..FFT_SIZE / 2)
+            .map(|bin| if bin < wall { -60.0 } else { -125.0 })
+            .collect();
+        let estimate = estimate_from_spectrum(&average_db, SAMPLE_RATE_HZ, &vec![wall; 50]);
+        let cutoff_hz = estimate.cutoff_hz.expect("wall should be found");
+        assert!((cutoff_hz - 16_000.0).abs() < 50.0, "{cutoff_hz}");
+        assert!(estimate.confidence > 0.95, "{}", estimate.confidence);
+        assert!(estimate.is_suspicious());
+        assert_eq!(
+            estimate.describe(),
+            "spectrum cuts off at 16.0 kHz (100% confidence)"
+        );
+
+        let scattered: Vec<usize> = (0..50)
+            .map(|index| {
+                if index % 2 == 0 {
+                    wall
+                } else {
+                    FFT_SIZE / 2 - 1
+                }
+            })
+            .collect();
+        let mixed = estimate_from_spectrum(&average_db, SAMPLE_RATE_HZ, &scattered);
+        assert!(mixed.confidence < estimate.confidence);
+    }
+
+    #[test]
+    fn test_gradual_rolloff_is_not_a_cutoff() {
+        let average_db: Vec<f32> = (0..FFT_SIZE / 2)
+            .map(|bin| -40.0 - bin as f32 * 0.08)
+            .collect();
+        let estimate = estimate_from_spectrum(&average_db, SAMPLE_RATE_HZ, &vec![900; 50]);
+        assert_eq!(estimate, LossySourceEstimate::NONE);
+        assert!(!estimate.is_suspicious());
+    }
+
+    #[test]
+    fn test_accumulator_flags_band_limited_audio_but_not_full_band_audio() {
+        let mut band_limited = CutoffAccumulator::new(SAMPLE_RATE_HZ);
+        let frame = dense_tone_frame(16_000.0);
+        for _ in 0..MIN_CONFIDENT_FRAMES {
+            band_limited.add_frame(&frame);
+        }
+        let estimate = band_limited.estimate();
+        assert!(estimate.is_suspicious(), "{estimate:?}");
+        let cutoff_hz = estimate.cutoff_hz.expect("wall should be found");
+        assert!((cutoff_hz - 16_000.0).abs() < 300.0, "{cutoff_hz}");
+
+        let mut full_band = CutoffAccumulator::new(SAMPLE_RATE_HZ);
+        let frame = dense_tone_frame(21_500.0);
+        for _ in 0..MIN_CONFIDENT_FRAMES {
+            full_band.add_frame(&frame);
+        }
+        assert!(!full_band.estimate().is_suspicious());
+    }
+
+    #[test]
+    fn test_silence_gives_no_estimate() {
+        let mut accumulator = CutoffAccumulator::new(SAMPLE_RATE_HZ);
+        accumulator.add_frame(&[0.0; FFT_SIZE]);
+        assert_eq!(accumulator.estimate(), LossySourceEstimate::NONE);
+    }
+}
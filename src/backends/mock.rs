@@ -0,0 +1,290 @@
+//! Simulated media server adapter for development and tests.
+//!
+//! Enabled by the hidden `[integrations.mock_backend]` table in `config.toml`, where it
+//! replaces the OpenSubsonic adapter for every profile. The fake server keeps a canned
+//! library, favorites, and playlists in memory, waits for the configured latency on every
+//! call, and fails calls on demand so sync, writeback, and server-unavailable flows can be
+//! exercised without a real server. Stream URLs still point at the profile endpoint, so
+//! mock tracks show up in the library but do not play.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::backends::{BackendPlaylist, BackendProfileAuth, BackendTrack, MediaBackendAdapter};
+use crate::config::MockBackendConfig;
+
+const GENRES: &[&str] = &["Rock", "Jazz", "Electronic", "Classical", "Folk", "Hip-Hop"];
+const TRACKS_PER_ALBUM: u32 = 10;
+const ALBUMS_PER_ARTIST: u32 = 4;
+
+struct MockPlaylist {
+    remote_playlist_id: String,
+    name: String,
+    song_ids: Vec<String>,
+}
+
+struct MockServerState {
+    favorites: HashSet<String>,
+    playlists: Vec<MockPlaylist>,
+    next_playlist_number: u32,
+}
+
+/// In-memory fake server implementing [`MediaBackendAdapter`].
+pub struct MockBackendAdapter {
+    config: MockBackendConfig,
+    tracks: Vec<BackendTrack>,
+    state: Mutex<MockServerState>,
+}
+
+fn song_id(index: u32) -> String {
+    format!("mock-song-{index}")
+}
+
+fn canned_track(index: u32) -> BackendTrack {
+    let album = index / TRACKS_PER_ALBUM;
+    let artist = album / ALBUMS_PER_ARTIST;
+    BackendTrack {
+        item_id: song_id(index),
+        title: format!("Mock Track {}", index + 1),
+        artist: format!("Mock Artist {}", artist + 1),
+        album: format!("Mock Album {}", album + 1),
+        genre: GENRES[artist as usize % GENRES.len()].to_string(),
+        year: (1970 + album % 55).to_string(),
+        track_number: (index % TRACKS_PER_ALBUM + 1).to_string(),
+        format_hint: Some(if album % 3 == 0 { "mp3" } else { "flac" }.to_string()),
+        duration_ms: Some(u64::from(150 + index % 120) * 1000),
+    }
+}
+
+impl MockBackendAdapter {
+    /// Creates a fake server with the canned library described by `config`.
+    pub fn new(config: MockBackendConfig) -> Self {
+        let track_count = config.track_count;
+        let tracks = (0..track_count).map(canned_track).collect();
+        let favorites = (0..track_count).step_by(7).map(song_id).collect();
+        let playlists = vec![
+            MockPlaylist {
+                remote_playlist_id: "mock-playlist-1".to_string(),
+                name: "Mock Mix".to_string(),
+                song_ids: (0..track_count.min(25)).map(song_id).collect(),
+            },
+            MockPlaylist {
+                remote_playlist_id: "mock-playlist-2".to_string(),
+                name: "Mock Long Playlist".to_string(),
+                song_ids: (0..track_count).step_by(3).map(song_id).collect(),
+            },
+            MockPlaylist {
+                remote_playlist_id: "mock-playlist-3".to_string(),
+                name: "Mock Empty Playlist".to_string(),
+                song_ids: Vec::new(),
+            },
+        ];
+        Self {
+            config,
+            tracks,
+            state: Mutex::new(MockServerState {
+                favorites,
+                next_playlist_number: playlists.len() as u32 + 1,
+                playlists,
+            }),
+        }
+    }
+
+    /// Simulates one server round trip: waits for the latency, then fails when the server
+    /// is marked unavailable or the call is picked for failure injection.
+    fn round_trip(&self, method: &str) -> Result<(), String> {
+        if self.config.latency_ms > 0 {
+            std::thread::sleep(Duration::from_millis(u64::from(self.config.latency_ms)));
+        }
+        if self.config.unavailable {
+            return Err(format!(
+                "mock server unavailable: {method} failed (connection refused)"
+            ));
+        }
+        if self.config.failure_rate_percent > 0 {
+            let mut roll = [0u8; 1];
+            let _ = getrandom::fill(&mut roll);
+            if u32::from(roll[0]) % 100 < self.config.failure_rate_percent {
+                return Err(format!("mock server injected failure in {method}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn tracks_for_ids(&self, song_ids: &[String]) -> Vec<BackendTrack> {
+        song_ids
+            .iter()
+            .filter_map(|song_id| self.tracks.iter().find(|track| &track.item_id == song_id))
+            .cloned()
+            .collect()
+    }
+}
+
+impl MediaBackendAdapter for MockBackendAdapter {
+    fn test_connection(&self, _profile: &BackendProfileAuth) -> Result<(), String> {
+        self.round_trip("ping")
+    }
+
+    fn fetch_library_tracks(
+        &self,
+        _profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendTrack>, String> {
+        self.round_trip("search3")?;
+        Ok(self.tracks.clone())
+    }
+
+    fn fetch_favorite_tracks(
+        &self,
+        _profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendTrack>, String> {
+        self.round_trip("getStarred2")?;
+        let state = self.state.lock().expect("mock server state lock poisoned");
+        Ok(self
+            .tracks
+            .iter()
+            .filter(|track| state.favorites.contains(&track.item_id))
+            .cloned()
+            .collect())
+    }
+
+    fn fetch_playlists(
+        &self,
+        _profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendPlaylist>, String> {
+        self.round_trip("getPlaylists")?;
+        let state = self.state.lock().expect("mock server state lock poisoned");
+        Ok(state
+            .playlists
+            .iter()
+            .map(|playlist| BackendPlaylist {
+                remote_playlist_id: playlist.remote_playlist_id.clone(),
+                name: playlist.name.clone(),
+                tracks: self.tracks_for_ids(&playlist.song_ids),
+            })
+            .collect())
+    }
+
+    fn set_track_favorite(
+        &self,
+        _profile: &BackendProfileAuth,
+        song_id: &str,
+        favorited: bool,
+    ) -> Result<(), String> {
+        self.round_trip(if favorited { "star" } else { "unstar" })?;
+        let mut state = self.state.lock().expect("mock server state lock poisoned");
+        if favorited {
+            state.favorites.insert(song_id.to_string());
+        } else {
+            state.favorites.remove(song_id);
+        }
+        Ok(())
+    }
+
+    fn create_playlist(
+        &self,
+        _profile: &BackendProfileAuth,
+        name: &str,
+        song_ids: &[String],
+    ) -> Result<String, String> {
+        self.round_trip("createPlaylist")?;
+        let mut state = self.state.lock().expect("mock server state lock poisoned");
+        let remote_playlist_id = format!("mock-playlist-{}", state.next_playlist_number);
+        state.next_playlist_number += 1;
+        state.playlists.push(MockPlaylist {
+            remote_playlist_id: remote_playlist_id.clone(),
+            name: name.to_string(),
+            song_ids: song_ids.to_vec(),
+        });
+        Ok(remote_playlist_id)
+    }
+
+    fn replace_playlist_tracks(
+        &self,
+        _profile: &BackendProfileAuth,
+        remote_playlist_id: &str,
+        song_ids: &[String],
+    ) -> Result<(), String> {
+        self.round_trip("updatePlaylist")?;
+        let mut state = self.state.lock().expect("mock server state lock poisoned");
+        let playlist = state
+            .playlists
+            .iter_mut()
+            .find(|playlist| playlist.remote_playlist_id == remote_playlist_id)
+            .ok_or_else(|| format!("mock server: playlist {remote_playlist_id} not found"))?;
+        playlist.song_ids = song_ids.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> BackendProfileAuth {
+        BackendProfileAuth {
+            profile_id: "mock".to_string(),
+            endpoint: "https://mock.invalid".to_string(),
+            username: "dev".to_string(),
+            password: "dev".to_string(),
+        }
+    }
+
+    fn instant_config() -> MockBackendConfig {
+        MockBackendConfig {
+            latency_ms: 0,
+            ..MockBackendConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_mock_server_serves_canned_library_and_keeps_writebacks() {
+        let adapter = MockBackendAdapter::new(MockBackendConfig {
+            track_count: 40,
+            ..instant_config()
+        });
+        let profile = profile();
+
+        assert_eq!(adapter.fetch_library_tracks(&profile).unwrap().len(), 40);
+        adapter
+            .set_track_favorite(&profile, "mock-song-1", true)
+            .unwrap();
+        let favorites = adapter.fetch_favorite_tracks(&profile).unwrap();
+        assert!(favorites.iter().any(|track| track.item_id == "mock-song-1"));
+
+        let created = adapter
+            .create_playlist(&profile, "New", &["mock-song-2".to_string()])
+            .unwrap();
+        adapter
+            .replace_playlist_tracks(&profile, &created, &["mock-song-3".to_string()])
+            .unwrap();
+        let playlists = adapter.fetch_playlists(&profile).unwrap();
+        let new_playlist = playlists
+            .iter()
+            .find(|playlist| playlist.remote_playlist_id == created)
+            .expect("created playlist is listed");
+        assert_eq!(new_playlist.tracks.len(), 1);
+        assert_eq!(new_playlist.tracks[0].item_id, "mock-song-3");
+        assert!(adapter
+            .replace_playlist_tracks(&profile, "missing", &[])
+            .is_err());
+    }
+
+    #[test]
+    fn test_mock_server_injects_failures() {
+        let unavailable = MockBackendAdapter::new(MockBackendConfig {
+            unavailable: true,
+            ..instant_config()
+        });
+        let always_failing = MockBackendAdapter::new(MockBackendConfig {
+            failure_rate_percent: 100,
+            ..instant_config()
+        });
+
+        assert!(unavailable.test_connection(&profile()).is_err());
+        assert!(always_failing.fetch_playlists(&profile()).is_err());
+        assert!(MockBackendAdapter::new(instant_config())
+            .test_connection(&profile())
+            .is_ok());
+    }
+}
@@ -1,6 +1,8 @@
 //! Backend adapter abstractions and concrete implementations.
 
+pub mod mock;
 pub mod opensubsonic;
+pub mod webdav;
 
 /// Remote track payload returned by backend adapters.
 #[derive(Debug, Clone)]
@@ -13,6 +15,7 @@ pub struct BackendTrack {
     pub year: String,
     pub track_number: String,
     pub format_hint: Option<String>,
+    pub duration_ms: Option<u64>,
 }
 
 /// Remote playlist payload returned by backend adapters.
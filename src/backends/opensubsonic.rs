@@ -70,6 +70,15 @@ impl OpenSubsonicAdapter {
         )
     }
 
+    /// URL serving the original file of `song_id`.
+    pub fn download_url(profile: &BackendProfileAuth, song_id: &str) -> String {
+        Self::api_url(
+            profile,
+            "download",
+            &[("id".to_string(), song_id.to_string())],
+        )
+    }
+
     fn request_json(
         &self,
         profile: &BackendProfileAuth,
@@ -158,6 +167,11 @@ impl OpenSubsonicAdapter {
             .map(str::trim)
             .filter(|value| !value.is_empty())
             .map(|value| value.to_ascii_lowercase());
+        let duration_ms = song
+            .get("duration")
+            .and_then(Value::as_u64)
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| seconds * 1000);
         Some(BackendTrack {
             item_id,
             title,
@@ -167,6 +181,7 @@ impl OpenSubsonicAdapter {
             year,
             track_number,
             format_hint,
+            duration_ms,
         })
     }
 
@@ -0,0 +1,424 @@
+//! WebDAV backend adapter implementation (Nextcloud, ownCloud, and plain WebDAV shares).
+//!
+//! The share is browsed with `PROPFIND` requests, one folder level at a time. Audio files
+//! become library tracks whose album and artist come from the two enclosing folder names,
+//! matching the common `Artist/Album/01 Title.flac` layout. WebDAV has no favorites or
+//! playlists, so those calls return nothing or fail.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::backends::{BackendPlaylist, BackendProfileAuth, BackendTrack, MediaBackendAdapter};
+use crate::base64_encoding::base64_encode;
+use crate::media_file_discovery::is_supported_audio_file;
+
+/// Upper bound on folders visited per sync, guarding against huge or cyclic shares.
+const MAX_DIRECTORIES: usize = 20_000;
+const PROPFIND_BODY: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"utf-8\"?>",
+    "<d:propfind xmlns:d=\"DAV:\"><d:prop>",
+    "<d:resourcetype/><d:getcontenttype/>",
+    "</d:prop></d:propfind>"
+);
+
+/// One resource listed in a `PROPFIND` multistatus response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DavEntry {
+    /// Href as sent by the server, still percent-encoded.
+    pub href: String,
+    pub is_collection: bool,
+    pub content_type: Option<String>,
+}
+
+/// Returns the `Authorization` header value for HTTP basic auth.
+pub fn basic_authorization(username: &str, password: &str) -> String {
+    format!(
+        "Basic {}",
+        base64_encode(format!("{}:{}", username.trim(), password).as_bytes())
+    )
+}
+
+/// Returns `scheme://host[:port]` of a share URL.
+fn endpoint_origin(endpoint: &str) -> &str {
+    let endpoint = endpoint.trim();
+    let host_start = endpoint.find("://").map_or(0, |index| index + 3);
+    match endpoint[host_start..].find('/') {
+        Some(path_start) => &endpoint[..host_start + path_start],
+        None => endpoint.trim_end_matches('/'),
+    }
+}
+
+/// Resolves a server href against the share URL it was listed from.
+pub fn href_url(endpoint: &str, href: &str) -> String {
+    let href = href.trim();
+    if href.starts_with("http://") || href.starts_with("https://") {
+        return href.to_string();
+    }
+    let origin = endpoint_origin(endpoint);
+    if href.starts_with('/') {
+        format!("{origin}{href}")
+    } else {
+        format!("{origin}/{href}")
+    }
+}
+
+fn decoded_path(url_or_href: &str) -> String {
+    let path = if url_or_href.contains("://") {
+        &url_or_href[endpoint_origin(url_or_href).len()..]
+    } else {
+        url_or_href
+    };
+    urlencoding::decode(path)
+        .map(|decoded| decoded.to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn same_resource(left: &str, right: &str) -> bool {
+    decoded_path(left).trim_end_matches('/') == decoded_path(right).trim_end_matches('/')
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Returns the contents of every element named `name`, ignoring namespace prefixes.
+///
+/// Elements with the same name must not nest, which holds for the multistatus elements
+/// read here.
+fn element_contents<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut contents = Vec::new();
+    let mut cursor = 0;
+    let mut content_start = None;
+    while let Some(offset) = xml[cursor..].find('<') {
+        let tag_start = cursor + offset;
+        let Some(tag_length) = xml[tag_start..].find('>') else {
+            break;
+        };
+        let tag_end = tag_start + tag_length;
+        let tag = &xml[tag_start + 1..tag_end];
+        cursor = tag_end + 1;
+        let tag_name = tag
+            .trim_start_matches('/')
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or(tag_name);
+        if !local_name.eq_ignore_ascii_case(name) {
+            continue;
+        }
+        if tag.starts_with('/') {
+            if let Some(start) = content_start.take() {
+                contents.push(&xml[start..tag_start]);
+            }
+        } else if tag.ends_with('/') {
+            contents.push("");
+        } else if content_start.is_none() {
+            content_start = Some(cursor);
+        }
+    }
+    contents
+}
+
+/// Parses a `PROPFIND` multistatus body into its listed resources.
+pub fn parse_multistatus(xml: &str) -> Vec<DavEntry> {
+    element_contents(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = xml_unescape(element_contents(response, "href").first()?.trim());
+            let is_collection = element_contents(response, "resourcetype")
+                .first()
+                .is_some_and(|resource_type| {
+                    !element_contents(resource_type, "collection").is_empty()
+                });
+            let content_type = element_contents(response, "getcontenttype")
+                .first()
+                .map(|value| value.trim().to_ascii_lowercase())
+                .filter(|value| !value.is_empty());
+            Some(DavEntry {
+                href,
+                is_collection,
+                content_type,
+            })
+        })
+        .collect()
+}
+
+/// Splits a leading track number such as `01 - ` off a file stem.
+fn split_track_number(stem: &str) -> (String, String) {
+    let digits: String = stem.chars().take_while(char::is_ascii_digit).collect();
+    if digits.is_empty() || digits.len() > 3 {
+        return (String::new(), stem.to_string());
+    }
+    let title = stem[digits.len()..].trim_start_matches([' ', '-', '.', '_']);
+    if title.is_empty() {
+        return (String::new(), stem.to_string());
+    }
+    let track_number = digits
+        .parse::<u32>()
+        .map(|number| number.to_string())
+        .unwrap_or(digits);
+    (track_number, title.to_string())
+}
+
+/// Builds a track from an audio file, naming album and artist after its folders.
+pub fn track_from_entry(endpoint: &str, entry: &DavEntry) -> BackendTrack {
+    let share_path = decoded_path(endpoint);
+    let file_path = decoded_path(&entry.href);
+    let relative = file_path
+        .strip_prefix(share_path.trim_end_matches('/'))
+        .unwrap_or(&file_path);
+    let segments: Vec<&str> = relative
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let file_name = segments.last().copied().unwrap_or_default();
+    let file = Path::new(file_name);
+    let stem = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    let (track_number, title) = split_track_number(stem);
+    let folder = |depth: usize| {
+        segments
+            .len()
+            .checked_sub(depth + 1)
+            .map(|index| segments[index].to_string())
+    };
+    BackendTrack {
+        item_id: entry.href.clone(),
+        title,
+        artist: folder(2).unwrap_or_else(|| "Unknown Artist".to_string()),
+        album: folder(1).unwrap_or_else(|| "Unknown Album".to_string()),
+        genre: String::new(),
+        year: String::new(),
+        track_number,
+        format_hint: file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase()),
+        duration_ms: None,
+    }
+}
+
+/// WebDAV adapter backed by `ureq`.
+pub struct WebDavAdapter {
+    http_client: ureq::Agent,
+}
+
+impl WebDavAdapter {
+    /// Creates a new WebDAV adapter.
+    pub fn new() -> Self {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(5))
+            .timeout_read(Duration::from_secs(30))
+            .timeout_write(Duration::from_secs(15))
+            .build();
+        Self { http_client }
+    }
+
+    fn propfind(
+        &self,
+        profile: &BackendProfileAuth,
+        url: &str,
+        depth: &str,
+    ) -> Result<Vec<DavEntry>, String> {
+        let response = self
+            .http_client
+            .request("PROPFIND", url)
+            .set("Depth", depth)
+            .set(
+                "Authorization",
+                &basic_authorization(&profile.username, &profile.password),
+            )
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)
+            .map_err(|err| format!("WebDAV request failed ({url}): {err}"))?;
+        if response.status() != 207 {
+            return Err(format!(
+                "WebDAV server answered {} instead of a folder listing for {url}",
+                response.status()
+            ));
+        }
+        let body = response
+            .into_string()
+            .map_err(|err| format!("WebDAV response read failed ({url}): {err}"))?;
+        Ok(parse_multistatus(&body))
+    }
+
+    /// Walks the share and returns its audio files sorted by href.
+    fn list_audio_files(&self, profile: &BackendProfileAuth) -> Result<Vec<DavEntry>, String> {
+        let root_url = format!("{}/", profile.endpoint.trim().trim_end_matches('/'));
+        let mut pending_folders = vec![root_url];
+        let mut visited_folders = HashSet::new();
+        let mut files = Vec::new();
+        while let Some(folder_url) = pending_folders.pop() {
+            if !visited_folders.insert(decoded_path(&folder_url)) {
+                continue;
+            }
+            if visited_folders.len() > MAX_DIRECTORIES {
+                warn!(
+                    "WebDAV: stopped listing {} after {} folders",
+                    profile.endpoint, MAX_DIRECTORIES
+                );
+                break;
+            }
+            for entry in self.propfind(profile, &folder_url, "1")? {
+                let entry_url = href_url(&profile.endpoint, &entry.href);
+                if same_resource(&entry_url, &folder_url) {
+                    continue;
+                }
+                if entry.is_collection {
+                    pending_folders.push(entry_url);
+                } else if is_supported_audio_file(Path::new(&decoded_path(&entry.href)))
+                    || entry
+                        .content_type
+                        .as_deref()
+                        .is_some_and(|content_type| content_type.starts_with("audio/"))
+                {
+                    files.push(entry);
+                }
+            }
+        }
+        files.sort_by(|left, right| left.href.cmp(&right.href));
+        Ok(files)
+    }
+}
+
+impl Default for WebDavAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaBackendAdapter for WebDavAdapter {
+    fn test_connection(&self, profile: &BackendProfileAuth) -> Result<(), String> {
+        let _ = self.propfind(profile, profile.endpoint.trim(), "0")?;
+        Ok(())
+    }
+
+    fn fetch_library_tracks(
+        &self,
+        profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendTrack>, String> {
+        Ok(self
+            .list_audio_files(profile)?
+            .iter()
+            .map(|entry| track_from_entry(&profile.endpoint, entry))
+            .collect())
+    }
+
+    fn fetch_favorite_tracks(
+        &self,
+        _profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendTrack>, String> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_playlists(
+        &self,
+        _profile: &BackendProfileAuth,
+    ) -> Result<Vec<BackendPlaylist>, String> {
+        Ok(Vec::new())
+    }
+
+    fn set_track_favorite(
+        &self,
+        _profile: &BackendProfileAuth,
+        _song_id: &str,
+        _favorited: bool,
+    ) -> Result<(), String> {
+        Err("WebDAV shares do not support favorites".to_string())
+    }
+
+    fn create_playlist(
+        &self,
+        _profile: &BackendProfileAuth,
+        _name: &str,
+        _song_ids: &[String],
+    ) -> Result<String, String> {
+        Err("WebDAV shares do not support playlists".to_string())
+    }
+
+    fn replace_playlist_tracks(
+        &self,
+        _profile: &BackendProfileAuth,
+        _remote_playlist_id: &str,
+        _song_ids: &[String],
+    ) -> Result<(), String> {
+        Err("WebDAV shares do not support playlists".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus_reads_hrefs_and_collections_across_prefixes() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Music/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Music/Rock%20%26%20Roll/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection /></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <D:response xmlns:D="DAV:">
+    <D:href>/remote.php/dav/files/alice/Music/a&amp;b.flac</D:href>
+    <D:propstat><D:prop><D:resourcetype/><D:getcontenttype>audio/flac</D:getcontenttype></D:prop></D:propstat>
+  </D:response>
+</d:multistatus>"#;
+
+        let entries = parse_multistatus(xml);
+
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].is_collection && entries[1].is_collection);
+        assert_eq!(
+            entries[2].href,
+            "/remote.php/dav/files/alice/Music/a&b.flac"
+        );
+        assert!(!entries[2].is_collection);
+        assert_eq!(entries[2].content_type.as_deref(), Some("audio/flac"));
+        assert!(same_resource(
+            "https://cloud.example.com/remote.php/dav/files/alice/Music",
+            &entries[0].href
+        ));
+    }
+
+    #[test]
+    fn test_track_from_entry_names_album_and_artist_after_folders() {
+        let endpoint = "https://cloud.example.com/remote.php/dav/files/alice/Music";
+        let entry = DavEntry {
+            href: "/remote.php/dav/files/alice/Music/The%20Band/First%20Album/03%20-%20Song.flac"
+                .to_string(),
+            is_collection: false,
+            content_type: None,
+        };
+
+        let track = track_from_entry(endpoint, &entry);
+
+        assert_eq!(track.artist, "The Band");
+        assert_eq!(track.album, "First Album");
+        assert_eq!(track.title, "Song");
+        assert_eq!(track.track_number, "3");
+        assert_eq!(track.format_hint.as_deref(), Some("flac"));
+        assert_eq!(
+            href_url(endpoint, &entry.href),
+            "https://cloud.example.com/remote.php/dav/files/alice/Music/The%20Band/First%20Album/03%20-%20Song.flac"
+        );
+        assert_eq!(
+            basic_authorization("Aladdin", "open sesame"),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Locations, size limits, and usage of the on-disk caches.
+//!
+//! Cover art, artist images, audio decoded for cast devices, and offline downloads each
+//! live in their own subfolder of a configurable folder (`[library]` `*_cache_dir`).
+//! Code that reads or writes a cache resolves its folder through [`cache_dir`], so a new
+//! location takes effect as soon as `CacheManager` applies the config change.
+//! `CacheManager` runs on its own worker thread: it publishes
+//! `LibraryMessage::CacheUsageSnapshot`, clears caches on request, and keeps the decoded
+//! audio and offline download caches within their limits. The image caches keep their
+//! existing owners for pruning; clearing artist images is delegated to the enrichment
+//! manager so its database rows are dropped together with the files.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use log::{info, warn};
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::config::LibraryConfig;
+use crate::image_pipeline;
+use crate::protocol::{
+    CacheKind, CacheUsage, ConfigDeltaEntry, ConfigMessage, LibraryConfigDelta, LibraryMessage,
+    Message,
+};
+
+/// Every cache in settings display order.
+pub const ALL_CACHES: [CacheKind; 4] = [
+    CacheKind::CoverArt,
+    CacheKind::ArtistImages,
+    CacheKind::DecodedAudio,
+    CacheKind::OfflineDownloads,
+];
+/// Size limit choices of the image caches, in MB.
+pub const IMAGE_LIMIT_CHOICES_MB: [u32; 6] = [128, 256, 512, 1024, 4096, 16_384];
+/// Size limit choices of the decoded audio and offline caches, in MB; `0` is unlimited.
+pub const FILE_LIMIT_CHOICES_MB: [u32; 6] = [0, 1024, 2048, 8192, 32_768, 131_072];
+/// Smallest non-zero limit of the decoded audio and offline caches.
+const MIN_FILE_CACHE_SIZE_MB: u32 = 256;
+/// Largest limit of the decoded audio and offline caches (1 TB).
+const MAX_FILE_CACHE_SIZE_MB: u32 = 1024 * 1024;
+
+static CACHE_DIR_OVERRIDES: RwLock<BTreeMap<CacheKind, PathBuf>> = RwLock::new(BTreeMap::new());
+
+/// Short display name for one cache.
+pub fn cache_label(kind: CacheKind) -> &'static str {
+    match kind {
+        CacheKind::CoverArt => "Cover art",
+        CacheKind::ArtistImages => "Artist images",
+        CacheKind::DecodedAudio => "Decoded audio",
+        CacheKind::OfflineDownloads => "Offline downloads",
+    }
+}
+
+/// Subfolder of the chosen folder that holds one cache's files.
+fn cache_subdir(kind: CacheKind) -> &'static str {
+    match kind {
+        CacheKind::CoverArt => "covers",
+        CacheKind::ArtistImages => "library_enrichment",
+        CacheKind::DecodedAudio => "cast_transcode",
+        CacheKind::OfflineDownloads => "offline",
+    }
+}
+
+/// Folder a cache uses when no location is configured.
+pub fn default_cache_dir(kind: CacheKind) -> PathBuf {
+    let base = match kind {
+        CacheKind::OfflineDownloads => dirs::data_dir(),
+        _ => dirs::cache_dir(),
+    };
+    base.unwrap_or_else(std::env::temp_dir)
+        .join("roqtune")
+        .join(cache_subdir(kind))
+}
+
+/// Folder a cache uses for the configured location `dir`.
+fn resolve_cache_dir(kind: CacheKind, dir: &str) -> PathBuf {
+    if dir.is_empty() {
+        default_cache_dir(kind)
+    } else {
+        PathBuf::from(dir).join(cache_subdir(kind))
+    }
+}
+
+/// Folder currently used by one cache.
+pub fn cache_dir(kind: CacheKind) -> PathBuf {
+    CACHE_DIR_OVERRIDES
+        .read()
+        .ok()
+        .and_then(|overrides| overrides.get(&kind).cloned())
+        .unwrap_or_else(|| default_cache_dir(kind))
+}
+
+/// Points every cache at the folders configured in `config`.
+pub fn configure_cache_dirs(config: &LibraryConfig) {
+    let Ok(mut overrides) = CACHE_DIR_OVERRIDES.write() else {
+        return;
+    };
+    overrides.clear();
+    for kind in ALL_CACHES {
+        let dir = configured_dir(config, kind);
+        if !dir.is_empty() {
+            overrides.insert(kind, resolve_cache_dir(kind, dir));
+        }
+    }
+}
+
+/// Configured location of one cache; empty means the default folder.
+pub fn configured_dir(config: &LibraryConfig, kind: CacheKind) -> &str {
+    match kind {
+        CacheKind::CoverArt => &config.cover_art_cache_dir,
+        CacheKind::ArtistImages => &config.artist_image_cache_dir,
+        CacheKind::DecodedAudio => &config.decoded_audio_cache_dir,
+        CacheKind::OfflineDownloads => &config.offline_cache_dir,
+    }
+}
+
+/// Stores `dir` as the location of one cache.
+pub fn set_configured_dir(config: &mut LibraryConfig, kind: CacheKind, dir: String) {
+    let slot = match kind {
+        CacheKind::CoverArt => &mut config.cover_art_cache_dir,
+        CacheKind::ArtistImages => &mut config.artist_image_cache_dir,
+        CacheKind::DecodedAudio => &mut config.decoded_audio_cache_dir,
+        CacheKind::OfflineDownloads => &mut config.offline_cache_dir,
+    };
+    *slot = dir;
+}
+
+/// Configured size limit of one cache in MB; `0` means unlimited.
+pub fn max_size_mb(config: &LibraryConfig, kind: CacheKind) -> u32 {
+    match kind {
+        CacheKind::CoverArt => config.cover_art_cache_max_size_mb,
+        CacheKind::ArtistImages => config.artist_image_cache_max_size_mb,
+        CacheKind::DecodedAudio => config.decoded_audio_cache_max_size_mb,
+        CacheKind::OfflineDownloads => config.offline_cache_max_size_mb,
+    }
+}
+
+/// Stores `value_mb` as the size limit of one cache.
+pub fn set_max_size_mb(config: &mut LibraryConfig, kind: CacheKind, value_mb: u32) {
+    let slot = match kind {
+        CacheKind::CoverArt => &mut config.cover_art_cache_max_size_mb,
+        CacheKind::ArtistImages => &mut config.artist_image_cache_max_size_mb,
+        CacheKind::DecodedAudio => &mut config.decoded_audio_cache_max_size_mb,
+        CacheKind::OfflineDownloads => &mut config.offline_cache_max_size_mb,
+    };
+    *slot = value_mb;
+}
+
+/// Clamps a decoded audio or offline cache limit, keeping `0` as unlimited.
+pub fn sanitize_file_cache_max_size_mb(value_mb: u32) -> u32 {
+    if value_mb == 0 {
+        0
+    } else {
+        value_mb.clamp(MIN_FILE_CACHE_SIZE_MB, MAX_FILE_CACHE_SIZE_MB)
+    }
+}
+
+/// Size limit choices offered in settings for one cache.
+pub fn limit_choices_mb(kind: CacheKind) -> &'static [u32] {
+    match kind {
+        CacheKind::CoverArt | CacheKind::ArtistImages => &IMAGE_LIMIT_CHOICES_MB,
+        CacheKind::DecodedAudio | CacheKind::OfflineDownloads => &FILE_LIMIT_CHOICES_MB,
+    }
+}
+
+/// Index into [`limit_choices_mb`] shown for a configured limit; custom values map to the
+/// next larger choice.
+pub fn limit_choice_index(kind: CacheKind, value_mb: u32) -> usize {
+    let choices = limit_choices_mb(kind);
+    if value_mb == 0 {
+        return choices.iter().position(|choice| *choice == 0).unwrap_or(0);
+    }
+    choices
+        .iter()
+        .position(|choice| *choice >= value_mb)
+        .unwrap_or(choices.len() - 1)
+}
+
+/// Formats a size limit such as `512 MB`, `2 GB`, or `No limit`.
+pub fn limit_label(value_mb: u32) -> String {
+    if value_mb == 0 {
+        "No limit".to_string()
+    } else if value_mb >= 1024 && value_mb.is_multiple_of(1024) {
+        format!("{} GB", value_mb / 1024)
+    } else {
+        format!("{value_mb} MB")
+    }
+}
+
+/// Current disk usage of one cache.
+pub fn cache_usage(config: &LibraryConfig, kind: CacheKind) -> CacheUsage {
+    let dir = cache_dir(kind);
+    let (size_bytes, file_count) = image_pipeline::dir_usage(&dir);
+    CacheUsage {
+        kind,
+        dir,
+        size_bytes,
+        file_count,
+        max_size_mb: max_size_mb(config, kind),
+    }
+}
+
+/// Reports cache usage, clears caches on request, and enforces the limits of the decoded
+/// audio and offline caches.
+pub struct CacheManager {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    config: LibraryConfig,
+}
+
+impl CacheManager {
+    /// Creates a cache manager seeded with startup library config.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        initial_config: LibraryConfig,
+    ) -> Self {
+        Self {
+            bus_consumer,
+            bus_producer,
+            config: initial_config,
+        }
+    }
+
+    fn publish_usage(&self) {
+        let usage = ALL_CACHES
+            .into_iter()
+            .map(|kind| cache_usage(&self.config, kind))
+            .collect();
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::CacheUsageSnapshot(usage)));
+    }
+
+    /// Deletes the oldest files of the caches this manager prunes until they fit their
+    /// limits.
+    fn enforce_limits(&self) {
+        for kind in [CacheKind::DecodedAudio, CacheKind::OfflineDownloads] {
+            let limit_mb = max_size_mb(&self.config, kind);
+            if limit_mb == 0 {
+                continue;
+            }
+            let removed = image_pipeline::prune_dir_to_budget(
+                &cache_dir(kind),
+                image_pipeline::mb_to_bytes(limit_mb),
+            );
+            if !removed.is_empty() {
+                info!(
+                    "CacheManager: pruned {} file(s) from the {} cache",
+                    removed.len(),
+                    cache_label(kind)
+                );
+            }
+        }
+    }
+
+    fn clear_cache(&self, kind: CacheKind) {
+        if kind == CacheKind::ArtistImages {
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::ClearEnrichmentCache));
+            return;
+        }
+        let deleted_files = image_pipeline::clear_dir_files(&cache_dir(kind));
+        info!(
+            "CacheManager: cleared {} file(s) from the {} cache",
+            deleted_files,
+            cache_label(kind)
+        );
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::CacheCleared {
+                kind,
+                deleted_files,
+            }));
+        self.publish_usage();
+    }
+
+    fn apply_config_delta(&mut self, delta: LibraryConfigDelta) {
+        let previous_dirs: Vec<PathBuf> = ALL_CACHES.into_iter().map(cache_dir).collect();
+        let mut dirs_changed = false;
+        for (kind, dir) in [
+            (CacheKind::CoverArt, delta.cover_art_cache_dir),
+            (CacheKind::ArtistImages, delta.artist_image_cache_dir),
+            (CacheKind::DecodedAudio, delta.decoded_audio_cache_dir),
+            (CacheKind::OfflineDownloads, delta.offline_cache_dir),
+        ] {
+            if let Some(dir) = dir {
+                set_configured_dir(&mut self.config, kind, dir);
+                dirs_changed = true;
+            }
+        }
+        let mut limits_changed = false;
+        for (kind, value_mb) in [
+            (CacheKind::CoverArt, delta.cover_art_cache_max_size_mb),
+            (
+                CacheKind::ArtistImages,
+                delta.artist_image_cache_max_size_mb,
+            ),
+            (
+                CacheKind::DecodedAudio,
+                delta.decoded_audio_cache_max_size_mb,
+            ),
+            (CacheKind::OfflineDownloads, delta.offline_cache_max_size_mb),
+        ] {
+            if let Some(value_mb) = value_mb {
+                set_max_size_mb(&mut self.config, kind, value_mb);
+                limits_changed = true;
+            }
+        }
+        if !dirs_changed && !limits_changed {
+            return;
+        }
+        if dirs_changed {
+            configure_cache_dirs(&self.config);
+            for (kind, previous_dir) in ALL_CACHES.into_iter().zip(previous_dirs) {
+                if cache_dir(kind) != previous_dir {
+                    self.release_previous_dir(kind, previous_dir);
+                }
+            }
+        }
+        self.enforce_limits();
+        self.publish_usage();
+    }
+
+    /// Empties the folder a cache moved away from. Offline downloads stay in place because
+    /// materialized playlists point at them.
+    fn release_previous_dir(&self, kind: CacheKind, previous_dir: PathBuf) {
+        match kind {
+            CacheKind::OfflineDownloads => {}
+            CacheKind::ArtistImages => {
+                image_pipeline::clear_dir_files(&previous_dir);
+                let _ = self
+                    .bus_producer
+                    .send(Message::Library(LibraryMessage::ClearEnrichmentCache));
+            }
+            CacheKind::CoverArt | CacheKind::DecodedAudio => {
+                let deleted_files = image_pipeline::clear_dir_files(&previous_dir);
+                let _ = self
+                    .bus_producer
+                    .send(Message::Library(LibraryMessage::CacheCleared {
+                        kind,
+                        deleted_files,
+                    }));
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Library(LibraryMessage::RequestCacheUsage) => self.publish_usage(),
+            Message::Library(LibraryMessage::ClearCache(kind)) => self.clear_cache(kind),
+            Message::Library(LibraryMessage::EnrichmentCacheCleared { .. }) => self.publish_usage(),
+            Message::Config(ConfigMessage::ConfigChanged(changes)) => {
+                for change in changes {
+                    if let ConfigDeltaEntry::Library(delta) = change {
+                        self.apply_config_delta(delta);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts the blocking manager loop.
+    pub fn run(&mut self) {
+        info!("CacheManager: started");
+        self.enforce_limits();
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("CacheManager: bus lagged by {} messages", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_dirs_resolve_to_cache_subfolders() {
+        let config = LibraryConfig {
+            decoded_audio_cache_dir: "/mnt/scratch".to_string(),
+            ..LibraryConfig::default()
+        };
+        assert_eq!(
+            resolve_cache_dir(
+                CacheKind::DecodedAudio,
+                configured_dir(&config, CacheKind::DecodedAudio)
+            ),
+            PathBuf::from("/mnt/scratch/cast_transcode")
+        );
+        assert_eq!(
+            resolve_cache_dir(CacheKind::CoverArt, ""),
+            default_cache_dir(CacheKind::CoverArt)
+        );
+        assert!(default_cache_dir(CacheKind::CoverArt).ends_with("roqtune/covers"));
+    }
+
+    #[test]
+    fn test_limit_choices_labels_and_sanitizing() {
+        assert_eq!(limit_choice_index(CacheKind::CoverArt, 512), 2);
+        assert_eq!(limit_choice_index(CacheKind::CoverArt, 600), 3);
+        assert_eq!(limit_choice_index(CacheKind::CoverArt, 100_000), 5);
+        assert_eq!(limit_choice_index(CacheKind::OfflineDownloads, 0), 0);
+        assert_eq!(limit_choice_index(CacheKind::DecodedAudio, 2048), 2);
+        assert_eq!(limit_label(0), "No limit");
+        assert_eq!(limit_label(512), "512 MB");
+        assert_eq!(limit_label(2048), "2 GB");
+        assert_eq!(sanitize_file_cache_max_size_mb(0), 0);
+        assert_eq!(sanitize_file_cache_max_size_mb(10), MIN_FILE_CACHE_SIZE_MB);
+    }
+
+    #[test]
+    fn test_clear_cache_deletes_files_and_reports_usage() {
+        let root =
+            std::env::temp_dir().join(format!("roqtune_cache_manager_test_{}", std::process::id()));
+        let config = LibraryConfig {
+            decoded_audio_cache_dir: root.to_string_lossy().to_string(),
+            ..LibraryConfig::default()
+        };
+        configure_cache_dirs(&config);
+        let dir = cache_dir(CacheKind::DecodedAudio);
+        std::fs::create_dir_all(dir.join("nested")).expect("cache dir should be created");
+        std::fs::write(dir.join("a.wav"), [0u8; 16]).expect("file should be written");
+        std::fs::write(dir.join("nested").join("b.wav"), [0u8; 8]).expect("file should be written");
+
+        let usage = cache_usage(&config, CacheKind::DecodedAudio);
+        assert_eq!(usage.size_bytes, 24);
+        assert_eq!(usage.file_count, 2);
+
+        let (bus_sender, _) = tokio::sync::broadcast::channel::<Message>(8);
+        let mut receiver = bus_sender.subscribe();
+        let manager = CacheManager::new(bus_sender.subscribe(), bus_sender, config);
+        manager.clear_cache(CacheKind::DecodedAudio);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::Library(LibraryMessage::CacheCleared {
+                kind: CacheKind::DecodedAudio,
+                deleted_files: 2
+            }))
+        ));
+        assert_eq!(image_pipeline::dir_usage(&dir), (0, 0));
+
+        configure_cache_dirs(&LibraryConfig::default());
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -21,14 +21,17 @@ use mdns_sd::{ServiceDaemon, ServiceEvent};
 use serde_json::Value;
 use tokio::sync::broadcast::{Receiver, Sender};
 
+use crate::cache_manager;
+use crate::config::CastDeviceVolumeCap;
 use crate::integration_keyring::get_opensubsonic_password;
 use crate::integration_uri::{parse_opensubsonic_track_uri, OpenSubsonicTrackLocator};
 use crate::metadata_tags;
 use crate::protocol::{
-    BackendKind, CastConnectionState, CastDeviceInfo, CastMessage, CastPlaybackPathKind,
-    IntegrationMessage, Message, PlaybackMessage, TechnicalMetadata, TrackMetadataSummary,
-    TrackStarted,
+    BackendKind, CacheKind, CastConnectionState, CastDeviceInfo, CastMessage, CastPlaybackPathKind,
+    CastQueueItem, IntegrationMessage, Message, PlaybackMessage, TechnicalMetadata,
+    TrackMetadataSummary, TrackStarted,
 };
+use crate::sandbox_access;
 
 const CAST_DEFAULT_MEDIA_RECEIVER_APP_ID: &str = "CC1AD845";
 const CAST_NAMESPACE_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
@@ -44,6 +47,17 @@ const CAST_WRITE_TIMEOUT: Duration = Duration::from_millis(1500);
 const STREAM_TOKEN_TTL: Duration = Duration::from_secs(60 * 30);
 const OPENSUBSONIC_API_VERSION: &str = "1.16.1";
 const OPENSUBSONIC_CLIENT_ID: &str = "roqtune";
+/// Number of upcoming tracks mirrored into the receiver queue.
+const CAST_QUEUE_PRELOAD_ITEMS: usize = 3;
+/// Queue edits without a reply after this long are treated as lost.
+const CAST_QUEUE_EDIT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Remembered cast targets kept across sessions, including currently unreachable ones.
+const MAX_KNOWN_CAST_DEVICES: usize = 32;
+/// Receiver levels within this distance are treated as equal when mirroring volume.
+const CAST_VOLUME_EPSILON: f32 = 0.005;
+/// Upper bound on incremental edits per queue target, guarding against receivers that
+/// drop the track id attached to queue items.
+const CAST_QUEUE_MAX_SYNC_STEPS: u32 = 6;
 
 #[derive(Clone)]
 struct StreamResource {
@@ -272,8 +286,8 @@ fn handle_stream_request(
         );
     }
 
-    let mut file =
-        File::open(&resource.path).map_err(|err| format!("failed to open stream file: {err}"))?;
+    let mut file = sandbox_access::open_file(&resource.path)
+        .map_err(|err| format!("failed to open stream file: {err}"))?;
     let file_size = file
         .metadata()
         .map_err(|err| format!("failed to stat stream file: {err}"))?
@@ -466,6 +480,7 @@ fn cast_device_from_resolved_service(service: &mdns_sd::ResolvedService) -> Opti
         host,
         address,
         port,
+        available: true,
     })
 }
 
@@ -520,6 +535,93 @@ fn discover_cast_devices_once() -> Vec<CastDeviceInfo> {
     devices
 }
 
+fn known_cast_devices_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("roqtune")
+        .join("cast_known_devices.json")
+}
+
+fn load_known_cast_devices(path: &Path) -> Vec<CastDeviceInfo> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Vec<CastDeviceInfo>>(&text) {
+        Ok(devices) => devices,
+        Err(err) => {
+            warn!(
+                "CastManager: ignoring unreadable known device cache {}: {}",
+                path.display(),
+                err
+            );
+            Vec::new()
+        }
+    }
+}
+
+fn save_known_cast_devices(path: &Path, devices: &[CastDeviceInfo]) {
+    if let Some(parent) = path.parent() {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            warn!(
+                "CastManager: failed creating known device cache directory {}: {}",
+                parent.display(),
+                err
+            );
+            return;
+        }
+    }
+    let serialized = match serde_json::to_string_pretty(devices) {
+        Ok(serialized) => serialized,
+        Err(err) => {
+            warn!(
+                "CastManager: failed serializing known device cache: {}",
+                err
+            );
+            return;
+        }
+    };
+    if let Err(err) = std::fs::write(path, serialized) {
+        warn!(
+            "CastManager: failed writing known device cache {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+/// Merges one discovery pass into the remembered targets.
+///
+/// Discovered devices replace their remembered entries (addresses can change between
+/// sessions); remembered devices that did not answer stay listed as unavailable.
+fn merge_known_cast_devices(
+    known: &[CastDeviceInfo],
+    discovered: Vec<CastDeviceInfo>,
+) -> Vec<CastDeviceInfo> {
+    let mut merged: Vec<CastDeviceInfo> = discovered
+        .into_iter()
+        .map(|mut device| {
+            device.available = true;
+            device
+        })
+        .collect();
+    for device in known {
+        if merged.iter().any(|existing| existing.id == device.id) {
+            continue;
+        }
+        merged.push(CastDeviceInfo {
+            available: false,
+            ..device.clone()
+        });
+    }
+    merged.sort_by(|a, b| {
+        b.available
+            .cmp(&a.available)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    merged.truncate(MAX_KNOWN_CAST_DEVICES);
+    merged
+}
+
 #[derive(Debug, Clone)]
 struct MediaStatus {
     player_state: String,
@@ -527,6 +629,19 @@ struct MediaStatus {
     duration_s: f64,
     media_session_id: Option<i64>,
     idle_reason: Option<String>,
+    current_item_id: Option<i64>,
+}
+
+/// One incremental edit that moves the receiver queue toward the requested order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CastQueueSyncStep {
+    Unchanged,
+    /// Receiver item ids to remove.
+    Remove(Vec<i64>),
+    /// Positions in the requested list to append to the receiver queue.
+    Insert(Vec<usize>),
+    /// Receiver item ids in their requested order.
+    Reorder(Vec<i64>),
 }
 
 #[derive(Debug, Clone)]
@@ -705,32 +820,8 @@ impl CastSession {
         Err("timed out waiting for media transport id".to_string())
     }
 
-    fn load_media(
-        &mut self,
-        url: &str,
-        content_type: &str,
-        start_offset_ms: u64,
-        metadata: &CastTrackInfo,
-        album_art_url: Option<&str>,
-    ) -> Result<(), String> {
+    fn load_media(&mut self, media: Value, start_offset_ms: u64) -> Result<(), String> {
         let request_id = self.alloc_request_id();
-        let mut media = serde_json::json!({
-            "contentId":url,
-            "streamType":"BUFFERED",
-            "contentType":content_type,
-            "metadata":{
-                "metadataType":3,
-                "title":metadata.title,
-                "artist":metadata.artist,
-                "albumName":metadata.album
-            }
-        });
-        if let Some(duration_ms) = metadata.duration_ms.filter(|value| *value > 0) {
-            media["duration"] = serde_json::json!(duration_ms as f64 / 1000.0);
-        }
-        if let Some(art_url) = album_art_url.filter(|value| !value.trim().is_empty()) {
-            media["metadata"]["images"] = serde_json::json!([{ "url": art_url }]);
-        }
         let mut payload = serde_json::json!({
             "type":"LOAD",
             "requestId":request_id,
@@ -748,6 +839,23 @@ impl CastSession {
         Ok(())
     }
 
+    /// Loads `media` followed by `upcoming_media` as one receiver queue, so the receiver
+    /// can skip ahead on its own.
+    fn queue_load(
+        &mut self,
+        media: Value,
+        upcoming_media: Vec<Value>,
+        start_offset_ms: u64,
+    ) -> Result<(), String> {
+        let request_id = self.alloc_request_id();
+        let payload = queue_load_payload(request_id, media, upcoming_media, start_offset_ms);
+        self.send_json(
+            CAST_NAMESPACE_MEDIA,
+            &self.media_transport_id.clone(),
+            payload,
+        )
+    }
+
     fn play(&mut self, media_session_id: Option<i64>) -> Result<(), String> {
         let request_id = self.alloc_request_id();
         let mut payload = serde_json::json!({"type":"PLAY","requestId":request_id});
@@ -852,6 +960,80 @@ impl CastSession {
         )
     }
 
+    fn send_queue_command(
+        &mut self,
+        command: &str,
+        media_session_id: i64,
+        fields: Value,
+    ) -> Result<(), String> {
+        let request_id = self.alloc_request_id();
+        let mut payload = serde_json::json!({
+            "type":command,
+            "requestId":request_id,
+            "mediaSessionId":media_session_id
+        });
+        if let (Some(payload), Value::Object(fields)) = (payload.as_object_mut(), fields) {
+            payload.extend(fields);
+        }
+        self.send_json(
+            CAST_NAMESPACE_MEDIA,
+            &self.media_transport_id.clone(),
+            payload,
+        )
+    }
+
+    fn queue_insert(
+        &mut self,
+        media_items: Vec<Value>,
+        media_session_id: i64,
+    ) -> Result<(), String> {
+        let items: Vec<Value> = media_items
+            .into_iter()
+            .map(|media| serde_json::json!({"media":media,"autoplay":true}))
+            .collect();
+        self.send_queue_command(
+            "QUEUE_INSERT",
+            media_session_id,
+            serde_json::json!({"items":items}),
+        )
+    }
+
+    fn queue_remove(&mut self, item_ids: &[i64], media_session_id: i64) -> Result<(), String> {
+        self.send_queue_command(
+            "QUEUE_REMOVE",
+            media_session_id,
+            serde_json::json!({"itemIds":item_ids}),
+        )
+    }
+
+    fn queue_reorder(&mut self, item_ids: &[i64], media_session_id: i64) -> Result<(), String> {
+        self.send_queue_command(
+            "QUEUE_REORDER",
+            media_session_id,
+            serde_json::json!({"itemIds":item_ids}),
+        )
+    }
+
+    fn request_queue_item_ids(&mut self, media_session_id: i64) -> Result<(), String> {
+        self.send_queue_command(
+            "QUEUE_GET_ITEM_IDS",
+            media_session_id,
+            serde_json::json!({}),
+        )
+    }
+
+    fn request_queue_items(
+        &mut self,
+        item_ids: &[i64],
+        media_session_id: i64,
+    ) -> Result<(), String> {
+        self.send_queue_command(
+            "QUEUE_GET_ITEMS",
+            media_session_id,
+            serde_json::json!({"itemIds":item_ids}),
+        )
+    }
+
     fn set_volume(&mut self, volume: f32) -> Result<(), String> {
         let request_id = self.alloc_request_id();
         self.send_json(
@@ -865,6 +1047,28 @@ impl CastSession {
         )
     }
 
+    fn set_muted(&mut self, muted: bool) -> Result<(), String> {
+        let request_id = self.alloc_request_id();
+        self.send_json(
+            CAST_NAMESPACE_RECEIVER,
+            &self.receiver_id.clone(),
+            serde_json::json!({
+                "type":"SET_VOLUME",
+                "requestId":request_id,
+                "volume":{"muted":muted}
+            }),
+        )
+    }
+
+    fn request_receiver_status(&mut self) -> Result<(), String> {
+        let request_id = self.alloc_request_id();
+        self.send_json(
+            CAST_NAMESPACE_RECEIVER,
+            &self.receiver_id.clone(),
+            serde_json::json!({"type":"GET_STATUS","requestId":request_id}),
+        )
+    }
+
     fn request_media_status(&mut self) -> Result<(), String> {
         let request_id = self.alloc_request_id();
         self.send_json(
@@ -1049,9 +1253,166 @@ fn parse_media_status(payload: &str) -> Option<MediaStatus> {
             .get("idleReason")
             .and_then(Value::as_str)
             .map(ToString::to_string),
+        current_item_id: status.get("currentItemId").and_then(Value::as_i64),
     })
 }
 
+/// Builds the LOAD/QUEUE_INSERT media payload, tagging it with the playlist track id so
+/// receiver queue items can be mapped back to tracks.
+fn cast_media_info(
+    url: &str,
+    content_type: &str,
+    track_id: &str,
+    metadata: &CastTrackInfo,
+    album_art_url: Option<&str>,
+) -> Value {
+    let mut media = serde_json::json!({
+        "contentId":url,
+        "streamType":"BUFFERED",
+        "contentType":content_type,
+        "customData":{"trackId":track_id},
+        "metadata":{
+            "metadataType":3,
+            "title":metadata.title,
+            "artist":metadata.artist,
+            "albumName":metadata.album
+        }
+    });
+    if let Some(duration_ms) = metadata.duration_ms.filter(|value| *value > 0) {
+        media["duration"] = serde_json::json!(duration_ms as f64 / 1000.0);
+    }
+    if let Some(art_url) = album_art_url.filter(|value| !value.trim().is_empty()) {
+        media["metadata"]["images"] = serde_json::json!([{ "url": art_url }]);
+    }
+    media
+}
+
+/// Builds a `QUEUE_LOAD` request that starts playing `media` with `upcoming_media` queued
+/// after it. Repeat is left to the sender, which queues repeated tracks explicitly.
+fn queue_load_payload(
+    request_id: i64,
+    media: Value,
+    upcoming_media: Vec<Value>,
+    start_offset_ms: u64,
+) -> Value {
+    let items: Vec<Value> = std::iter::once(media)
+        .chain(upcoming_media)
+        .map(|media| serde_json::json!({"media":media,"autoplay":true}))
+        .collect();
+    let mut payload = serde_json::json!({
+        "type":"QUEUE_LOAD",
+        "requestId":request_id,
+        "items":items,
+        "startIndex":0,
+        "repeatMode":"REPEAT_OFF"
+    });
+    if start_offset_ms > 0 {
+        payload["currentTime"] = serde_json::json!(start_offset_ms as f64 / 1000.0);
+    }
+    payload
+}
+
+/// Parses the device volume `(level, muted)` from a `RECEIVER_STATUS` payload.
+fn parse_receiver_volume(payload: &str) -> Option<(f32, bool)> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    if value.get("type").and_then(Value::as_str) != Some("RECEIVER_STATUS") {
+        return None;
+    }
+    let volume = value.get("status")?.get("volume")?;
+    let level = volume.get("level").and_then(Value::as_f64)? as f32;
+    let muted = volume
+        .get("muted")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    Some((level.clamp(0.0, 1.0), muted))
+}
+
+fn parse_queue_item_ids(payload: &str) -> Option<Vec<i64>> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    if value.get("type").and_then(Value::as_str) != Some("QUEUE_ITEM_IDS") {
+        return None;
+    }
+    Some(
+        value
+            .get("itemIds")?
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_i64)
+            .collect(),
+    )
+}
+
+/// Parses `QUEUE_ITEMS` replies into `(item id, track id)` pairs.
+fn parse_queue_items(payload: &str) -> Option<Vec<(i64, Option<String>)>> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    if value.get("type").and_then(Value::as_str) != Some("QUEUE_ITEMS") {
+        return None;
+    }
+    Some(
+        value
+            .get("items")?
+            .as_array()?
+            .iter()
+            .filter_map(|item| {
+                let item_id = item.get("itemId").and_then(Value::as_i64)?;
+                let track_id = item
+                    .get("media")
+                    .and_then(|media| media.get("customData"))
+                    .and_then(|custom| custom.get("trackId"))
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string);
+                Some((item_id, track_id))
+            })
+            .collect(),
+    )
+}
+
+/// Chooses the next edit that brings `queued` (receiver items after the current one, as
+/// `(item id, track id)`) in line with `requested` track ids.
+///
+/// Items already queued are kept and only reordered, so a reorder never reloads media.
+fn plan_cast_queue_sync(queued: &[(i64, String)], requested: &[String]) -> CastQueueSyncStep {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for track_id in requested {
+        *remaining.entry(track_id.as_str()).or_default() += 1;
+    }
+    let mut kept: Vec<(i64, &str)> = Vec::new();
+    let mut removed = Vec::new();
+    for (item_id, track_id) in queued {
+        match remaining.get_mut(track_id.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                kept.push((*item_id, track_id.as_str()));
+            }
+            _ => removed.push(*item_id),
+        }
+    }
+    if !removed.is_empty() {
+        return CastQueueSyncStep::Remove(removed);
+    }
+
+    let mut unclaimed = kept.clone();
+    let mut ordered_item_ids = Vec::with_capacity(requested.len());
+    let mut missing = Vec::new();
+    for (position, track_id) in requested.iter().enumerate() {
+        match unclaimed
+            .iter()
+            .position(|(_, queued_track_id)| *queued_track_id == track_id.as_str())
+        {
+            Some(found) => ordered_item_ids.push(unclaimed.remove(found).0),
+            None => missing.push(position),
+        }
+    }
+    if !missing.is_empty() {
+        return CastQueueSyncStep::Insert(missing);
+    }
+    let current_item_ids: Vec<i64> = kept.iter().map(|(item_id, _)| *item_id).collect();
+    if current_item_ids != ordered_item_ids {
+        return CastQueueSyncStep::Reorder(ordered_item_ids);
+    }
+    CastQueueSyncStep::Unchanged
+}
+
 fn default_track_title_from_path(path: &Path) -> String {
     if let Some(locator) = parse_opensubsonic_track_uri(path) {
         return locator.song_id;
@@ -1146,10 +1507,7 @@ fn find_external_cover_art(track_path: &Path) -> Option<PathBuf> {
 }
 
 fn cast_embedded_art_cache_dir() -> PathBuf {
-    dirs::cache_dir()
-        .unwrap_or_else(std::env::temp_dir)
-        .join("roqtune")
-        .join("cast_covers")
+    cache_manager::cache_dir(CacheKind::CoverArt).join("cast")
 }
 
 fn embedded_art_cache_stem(track_path: &Path) -> String {
@@ -1194,7 +1552,7 @@ fn probe_track_technical_metadata(path: &Path) -> Option<TechnicalMetadata> {
     let mut bits_per_sample = 16u16;
     let mut codec_duration_ms = 0u64;
 
-    let file = File::open(path).ok()?;
+    let file = sandbox_access::open_file(path).ok()?;
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
     let hint = Hint::new();
     let probed = symphonia::default::get_probe()
@@ -1235,7 +1593,7 @@ fn probe_track_technical_metadata(path: &Path) -> Option<TechnicalMetadata> {
         }
     }
 
-    let duration_ms = lofty::read_from_path(path)
+    let duration_ms = metadata_tags::read_tagged_file(path)
         .ok()
         .map(|tagged| tagged.properties().duration().as_millis() as u64)
         .filter(|value| *value > 0)
@@ -1394,7 +1752,8 @@ fn transcode_to_wav_pcm(path: &Path, cache_dir: &Path) -> Result<PathBuf, String
     use symphonia::core::meta::MetadataOptions;
     use symphonia::core::probe::Hint;
 
-    let input = File::open(path).map_err(|err| format!("failed to open source: {err}"))?;
+    let input =
+        sandbox_access::open_file(path).map_err(|err| format!("failed to open source: {err}"))?;
     let mss = MediaSourceStream::new(Box::new(input), Default::default());
     let hint = Hint::new();
     let mut format = symphonia::default::get_probe()
@@ -1505,7 +1864,6 @@ pub struct CastManager {
     connected_device: Option<CastDeviceInfo>,
     session: Option<CastSession>,
     allow_transcode_fallback: bool,
-    transcode_cache_dir: PathBuf,
     current_track_id: Option<String>,
     current_track_source_path: Option<PathBuf>,
     current_track_metadata_summary: Option<TrackMetadataSummary>,
@@ -1515,6 +1873,17 @@ pub struct CastManager {
     stop_requested: bool,
     last_status_poll_at: Instant,
     opensubsonic_passwords: HashMap<String, String>,
+    receiver_current_item_id: Option<i64>,
+    receiver_item_ids: Vec<i64>,
+    receiver_item_tracks: HashMap<i64, Option<String>>,
+    queue_target_track_id: Option<String>,
+    queue_target: Vec<CastQueueItem>,
+    queue_request_sent_at: Option<Instant>,
+    queue_sync_steps: u32,
+    queue_advanced_track_id: Option<String>,
+    device_volume_caps: Vec<CastDeviceVolumeCap>,
+    receiver_volume: Option<(f32, bool, f32)>,
+    known_devices_path: PathBuf,
 }
 
 impl CastManager {
@@ -1525,19 +1894,15 @@ impl CastManager {
         initial_cast_config: crate::config::CastConfig,
     ) -> Self {
         let stream_server = CastStreamServer::new().expect("cast stream server should start");
-        let transcode_cache_dir = dirs::cache_dir()
-            .unwrap_or_else(std::env::temp_dir)
-            .join("roqtune")
-            .join("cast_transcode");
+        let known_devices_path = known_cast_devices_path();
         Self {
             bus_consumer,
             bus_producer,
             stream_server,
-            devices: Vec::new(),
+            devices: load_known_cast_devices(&known_devices_path),
             connected_device: None,
             session: None,
             allow_transcode_fallback: initial_cast_config.allow_transcode_fallback,
-            transcode_cache_dir,
             current_track_id: None,
             current_track_source_path: None,
             current_track_metadata_summary: None,
@@ -1547,6 +1912,17 @@ impl CastManager {
             stop_requested: false,
             last_status_poll_at: Instant::now(),
             opensubsonic_passwords: HashMap::new(),
+            receiver_current_item_id: None,
+            receiver_item_ids: Vec::new(),
+            receiver_item_tracks: HashMap::new(),
+            queue_target_track_id: None,
+            queue_target: Vec::new(),
+            queue_request_sent_at: None,
+            queue_sync_steps: 0,
+            queue_advanced_track_id: None,
+            device_volume_caps: initial_cast_config.device_volume_caps,
+            receiver_volume: None,
+            known_devices_path,
         }
     }
 
@@ -1575,7 +1951,15 @@ impl CastManager {
 
     fn discover_devices(&mut self) {
         self.emit_connection_state(CastConnectionState::Discovering, None, None);
-        self.devices = discover_cast_devices_once();
+        let mut discovered = discover_cast_devices_once();
+        if let Some(connected) = self.connected_device.as_ref() {
+            // Receivers busy with our session do not always answer mDNS in time.
+            if !discovered.iter().any(|device| device.id == connected.id) {
+                discovered.push(connected.clone());
+            }
+        }
+        self.devices = merge_known_cast_devices(&self.devices, discovered);
+        save_known_cast_devices(&self.known_devices_path, &self.devices);
         self.emit_devices();
         let state = if self.session.is_some() {
             CastConnectionState::Connected
@@ -1620,6 +2004,23 @@ impl CastManager {
             );
             return;
         };
+        if self.session.is_some() {
+            if self
+                .connected_device
+                .as_ref()
+                .is_some_and(|connected| connected.id == device.id)
+            {
+                self.emit_connection_state(CastConnectionState::Connected, None, Some(device));
+                return;
+            }
+            // Switching targets: release the old receiver without reporting a disconnect,
+            // so playback is handed straight to the new target instead of back to local.
+            info!(
+                "CastManager: switching cast target to '{}' ({})",
+                device.name, device.address
+            );
+            self.close_session();
+        }
         self.emit_connection_state(CastConnectionState::Connecting, None, Some(device.clone()));
         match CastSession::connect(&device) {
             Ok(session) => {
@@ -1629,7 +2030,17 @@ impl CastManager {
                 );
                 self.session = Some(session);
                 self.connected_device = Some(device.clone());
+                self.receiver_volume = None;
+                if let Some(known) = self.devices.iter_mut().find(|known| known.id == device.id) {
+                    known.available = true;
+                }
+                self.emit_devices();
                 self.emit_connection_state(CastConnectionState::Connected, None, Some(device));
+                if let Some(session) = self.session.as_mut() {
+                    if let Err(err) = session.request_receiver_status() {
+                        warn!("CastManager: receiver status request failed: {}", err);
+                    }
+                }
             }
             Err(err) => {
                 warn!("CastManager: connect failed: {}", err);
@@ -1645,6 +2056,15 @@ impl CastManager {
     }
 
     fn disconnect(&mut self) {
+        self.close_session();
+        self.emit_connection_state(
+            CastConnectionState::Disconnected,
+            Some("Cast session closed.".to_string()),
+            None,
+        );
+    }
+
+    fn close_session(&mut self) {
         if let Some(session) = self.session.as_mut() {
             session.shutdown_receiver_and_close();
         }
@@ -1657,11 +2077,10 @@ impl CastManager {
         self.current_track_duration_ms = None;
         self.stop_requested = false;
         self.connected_device = None;
-        self.emit_connection_state(
-            CastConnectionState::Disconnected,
-            Some("Cast session closed.".to_string()),
-            None,
-        );
+        self.receiver_volume = None;
+        self.reset_receiver_queue();
+        self.queue_target_track_id = None;
+        self.queue_target.clear();
     }
 
     fn load_track_with_mode(
@@ -1671,6 +2090,7 @@ impl CastManager {
         start_offset_ms: u64,
         mode: CastPlaybackPathKind,
         metadata_summary: Option<TrackMetadataSummary>,
+        upcoming: &[CastQueueItem],
     ) -> Result<(), String> {
         let Some(device) = self.connected_device.clone() else {
             return Err("No cast device connected".to_string());
@@ -1715,8 +2135,10 @@ impl CastManager {
                         "Casting: Direct (unmodified source stream)".to_string(),
                     ),
                     CastPlaybackPathKind::TranscodeWavPcm => {
-                        let wav_path =
-                            transcode_to_wav_pcm(&source_path, &self.transcode_cache_dir)?;
+                        let wav_path = transcode_to_wav_pcm(
+                            &source_path,
+                            &cache_manager::cache_dir(CacheKind::DecodedAudio),
+                        )?;
                         (
                             wav_path,
                             "audio/wav".to_string(),
@@ -1743,22 +2165,17 @@ impl CastManager {
             };
 
         if album_art_url.is_none() {
-            if let (Some(art_path), Some(art_content_type)) = (
-                track_info.album_art_path.clone(),
-                track_info.album_art_content_type.clone(),
-            ) {
-                match self
-                    .stream_server
-                    .register_file(art_path, art_content_type, receiver_ip)
-                {
-                    Ok(art_token) => {
-                        if let Some(local_ip) = local_ip {
-                            album_art_url = Some(self.stream_server.media_url(&art_token, local_ip))
-                        }
-                    }
-                    Err(err) => {
-                        debug!("CastManager: failed to register album art stream: {}", err);
-                    }
+            album_art_url = self.register_album_art_url(&track_info, receiver_ip, local_ip);
+        }
+        let mut upcoming_media = Vec::new();
+        for item in upcoming.iter().take(CAST_QUEUE_PRELOAD_ITEMS) {
+            match self.queue_item_media(item) {
+                Ok(media) => upcoming_media.push(media),
+                Err(err) => {
+                    warn!(
+                        "CastManager: skipping queue item {}: {}",
+                        item.track_id, err
+                    );
                 }
             }
         }
@@ -1797,13 +2214,23 @@ impl CastManager {
                 start_offset_ms, track_id
             );
         }
-        session.load_media(
+        let media = cast_media_info(
             &url,
             &content_type,
-            load_start_offset_ms,
+            track_id,
             &track_info,
             album_art_url.as_deref(),
-        )?;
+        );
+        if upcoming_media.is_empty() {
+            session.load_media(media, load_start_offset_ms)?;
+        } else {
+            session.queue_load(media, upcoming_media, load_start_offset_ms)?;
+        }
+        // Either request replaces the receiver queue; items that could not be loaded with
+        // it are inserted once the new media session reports in.
+        self.reset_receiver_queue();
+        self.queue_target_track_id = Some(track_id.to_string());
+        self.queue_target = upcoming.to_vec();
         self.current_track_id = Some(track_id.to_string());
         self.current_path_kind = Some(mode);
         self.current_media_session_id = None;
@@ -1832,21 +2259,409 @@ impl CastManager {
         Ok(())
     }
 
+    fn register_album_art_url(
+        &self,
+        track_info: &CastTrackInfo,
+        receiver_ip: IpAddr,
+        local_ip: Option<IpAddr>,
+    ) -> Option<String> {
+        let (Some(art_path), Some(art_content_type)) = (
+            track_info.album_art_path.clone(),
+            track_info.album_art_content_type.clone(),
+        ) else {
+            return None;
+        };
+        match self
+            .stream_server
+            .register_file(art_path, art_content_type, receiver_ip)
+        {
+            Ok(art_token) => {
+                local_ip.map(|local_ip| self.stream_server.media_url(&art_token, local_ip))
+            }
+            Err(err) => {
+                debug!("CastManager: failed to register album art stream: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Builds the direct-stream media payload for an upcoming receiver queue item.
+    fn queue_item_media(&mut self, item: &CastQueueItem) -> Result<Value, String> {
+        let Some(device) = self.connected_device.clone() else {
+            return Err("No cast device connected".to_string());
+        };
+        let receiver_ip = device
+            .address
+            .parse::<IpAddr>()
+            .map_err(|err| format!("invalid cast receiver IP '{}': {err}", device.address))?;
+        let local_ip = local_ip_for_remote(receiver_ip);
+        let track_info = read_cast_track_info(&item.path, item.metadata_summary.as_ref());
+        let (url, content_type, mut album_art_url) =
+            if let Some(locator) = parse_opensubsonic_track_uri(item.path.as_path()) {
+                let password = self.opensubsonic_password_for_profile(&locator.profile_id)?;
+                (
+                    opensubsonic_download_url(&locator, password.as_str()),
+                    content_type_from_format_hint(locator.format_hint.as_deref())
+                        .unwrap_or_else(|| "audio/mpeg".to_string()),
+                    Some(opensubsonic_cover_art_url(&locator, password.as_str())),
+                )
+            } else {
+                let content_type = extension_to_content_type(&item.path);
+                let token = self.stream_server.register_file(
+                    item.path.clone(),
+                    content_type.clone(),
+                    receiver_ip,
+                )?;
+                let local_ip = local_ip.ok_or_else(|| {
+                    "Unable to determine local sender IP for cast receiver".to_string()
+                })?;
+                (
+                    self.stream_server.media_url(&token, local_ip),
+                    content_type,
+                    None,
+                )
+            };
+        if album_art_url.is_none() {
+            album_art_url = self.register_album_art_url(&track_info, receiver_ip, local_ip);
+        }
+        Ok(cast_media_info(
+            &url,
+            &content_type,
+            &item.track_id,
+            &track_info,
+            album_art_url.as_deref(),
+        ))
+    }
+
+    /// Returns the volume ceiling configured for the connected device.
+    fn volume_cap(&self) -> f32 {
+        let Some(device) = self.connected_device.as_ref() else {
+            return 1.0;
+        };
+        self.device_volume_caps
+            .iter()
+            .find(|cap| cap.device_id == device.id)
+            .map(|cap| cap.max_volume.clamp(0.0, 1.0))
+            .unwrap_or(1.0)
+    }
+
+    fn set_receiver_volume(&mut self, volume: f32, description: &str) {
+        let capped = volume.clamp(0.0, self.volume_cap());
+        if let Some(session) = self.session.as_mut() {
+            if let Err(err) = session.set_volume(capped) {
+                warn!("CastManager: {} failed: {}", description, err);
+            }
+        }
+    }
+
+    /// Mirrors the device volume back to the UI and pulls levels above the cap down,
+    /// including changes made from other apps or the device itself.
+    fn handle_receiver_volume(&mut self, level: f32, muted: bool) {
+        let cap = self.volume_cap();
+        if level > cap + CAST_VOLUME_EPSILON {
+            info!(
+                "CastManager: receiver volume {:.2} exceeds device cap {:.2}, lowering",
+                level, cap
+            );
+            self.set_receiver_volume(cap, "volume cap enforcement");
+        }
+        let level = level.min(cap);
+        if self
+            .receiver_volume
+            .is_some_and(|(previous_level, previous_muted, previous_cap)| {
+                previous_muted == muted
+                    && (previous_level - level).abs() < CAST_VOLUME_EPSILON
+                    && (previous_cap - cap).abs() < f32::EPSILON
+            })
+        {
+            return;
+        }
+        self.receiver_volume = Some((level, muted, cap));
+        let _ = self
+            .bus_producer
+            .send(Message::Cast(CastMessage::VolumeStatusChanged {
+                level,
+                muted,
+                max_volume: cap,
+            }));
+    }
+
+    fn reset_receiver_queue(&mut self) {
+        self.receiver_current_item_id = None;
+        self.receiver_item_ids.clear();
+        self.receiver_item_tracks.clear();
+        self.queue_request_sent_at = None;
+        self.queue_sync_steps = 0;
+        self.queue_advanced_track_id = None;
+    }
+
+    fn set_queue_target(&mut self, current_track_id: String, upcoming: Vec<CastQueueItem>) {
+        self.queue_target_track_id = Some(current_track_id);
+        self.queue_target = upcoming;
+        self.queue_sync_steps = 0;
+        self.sync_receiver_queue();
+    }
+
+    /// Returns the receiver items after the playing one, or `None` while their track ids
+    /// are still unknown.
+    fn queued_receiver_items(&self) -> Option<Vec<(i64, String)>> {
+        let current_item_id = self.receiver_current_item_id?;
+        let current_position = self
+            .receiver_item_ids
+            .iter()
+            .position(|item_id| *item_id == current_item_id)?;
+        self.receiver_item_ids[current_position + 1..]
+            .iter()
+            .map(|item_id| {
+                let track_id = self.receiver_item_tracks.get(item_id)?.clone();
+                Some((*item_id, track_id.unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    fn send_queue_request(
+        &mut self,
+        description: &str,
+        request: impl FnOnce(&mut CastSession) -> Result<(), String>,
+    ) -> bool {
+        let Some(session) = self.session.as_mut() else {
+            return false;
+        };
+        match request(session) {
+            Ok(()) => {
+                self.queue_request_sent_at = Some(Instant::now());
+                true
+            }
+            Err(err) => {
+                warn!("CastManager: {} failed: {}", description, err);
+                false
+            }
+        }
+    }
+
+    /// Applies at most one queue edit toward the requested upcoming tracks, then asks the
+    /// receiver for its updated layout so the next edit works from fresh item ids.
+    fn sync_receiver_queue(&mut self) {
+        if self.queue_request_sent_at.is_some() || self.receiver_current_item_id.is_none() {
+            return;
+        }
+        let Some(media_session_id) = self.current_media_session_id else {
+            return;
+        };
+        if self.queue_target_track_id.is_none()
+            || self.queue_target_track_id != self.current_track_id
+        {
+            return;
+        }
+        if self.queue_sync_steps >= CAST_QUEUE_MAX_SYNC_STEPS {
+            return;
+        }
+        let Some(queued) = self.queued_receiver_items() else {
+            self.send_queue_request("queue item id request", |session| {
+                session.request_queue_item_ids(media_session_id)
+            });
+            return;
+        };
+        let requested: Vec<CastQueueItem> = self
+            .queue_target
+            .iter()
+            .take(CAST_QUEUE_PRELOAD_ITEMS)
+            .cloned()
+            .collect();
+        let requested_ids: Vec<String> =
+            requested.iter().map(|item| item.track_id.clone()).collect();
+        let sent = match plan_cast_queue_sync(&queued, &requested_ids) {
+            CastQueueSyncStep::Unchanged => return,
+            CastQueueSyncStep::Remove(item_ids) => self
+                .send_queue_request("queue remove", |session| {
+                    session.queue_remove(&item_ids, media_session_id)
+                }),
+            CastQueueSyncStep::Insert(positions) => {
+                let mut media_items = Vec::with_capacity(positions.len());
+                for position in positions {
+                    match self.queue_item_media(&requested[position]) {
+                        Ok(media) => media_items.push(media),
+                        Err(err) => {
+                            warn!(
+                                "CastManager: skipping queue item {}: {}",
+                                requested[position].track_id, err
+                            );
+                        }
+                    }
+                }
+                if media_items.is_empty() {
+                    return;
+                }
+                self.send_queue_request("queue insert", |session| {
+                    session.queue_insert(media_items, media_session_id)
+                })
+            }
+            CastQueueSyncStep::Reorder(item_ids) => self
+                .send_queue_request("queue reorder", |session| {
+                    session.queue_reorder(&item_ids, media_session_id)
+                }),
+        };
+        if sent {
+            self.queue_sync_steps += 1;
+            if let Some(session) = self.session.as_mut() {
+                if let Err(err) = session.request_queue_item_ids(media_session_id) {
+                    warn!("CastManager: queue item id request failed: {}", err);
+                }
+            }
+        }
+    }
+
+    fn handle_queue_item_ids(&mut self, item_ids: Vec<i64>) {
+        let unknown: Vec<i64> = item_ids
+            .iter()
+            .copied()
+            .filter(|item_id| !self.receiver_item_tracks.contains_key(item_id))
+            .collect();
+        self.receiver_item_ids = item_ids;
+        self.receiver_item_tracks
+            .retain(|item_id, _| self.receiver_item_ids.contains(item_id));
+        if !unknown.is_empty() {
+            if let Some(media_session_id) = self.current_media_session_id {
+                if self.send_queue_request("queue item request", |session| {
+                    session.request_queue_items(&unknown, media_session_id)
+                }) {
+                    return;
+                }
+            }
+        }
+        self.queue_request_sent_at = None;
+        self.sync_receiver_queue();
+    }
+
+    fn handle_queue_items(&mut self, items: Vec<(i64, Option<String>)>) {
+        for (item_id, track_id) in items {
+            self.receiver_item_tracks.insert(item_id, track_id);
+        }
+        // Items the receiver did not describe are marked unknown so they get replaced.
+        for item_id in &self.receiver_item_ids {
+            self.receiver_item_tracks.entry(*item_id).or_insert(None);
+        }
+        self.queue_request_sent_at = None;
+        self.sync_receiver_queue();
+    }
+
+    /// Follows the receiver onto another queued item without reloading it.
+    ///
+    /// Moving to the next track is reported as `TrackFinished`; any other move, such as
+    /// the receiver's previous button, as `ReceiverJumpedToTrack`. The playlist manager
+    /// answers either with a `LoadTrack` for the same track, which `load_track` then
+    /// acknowledges instead of issuing a new LOAD.
+    fn handle_receiver_item_changed(&mut self, item_id: i64) {
+        let previous_item_id = self.receiver_current_item_id.replace(item_id);
+        if previous_item_id.is_none() {
+            return;
+        }
+        let Some(Some(track_id)) = self.receiver_item_tracks.get(&item_id).cloned() else {
+            return;
+        };
+        let Some(left_track_id) = self.current_track_id.replace(track_id.clone()) else {
+            return;
+        };
+        self.current_path_kind = Some(CastPlaybackPathKind::Direct);
+        self.current_track_duration_ms = None;
+        self.queue_advanced_track_id = Some(track_id.clone());
+        let advanced_to_next = self
+            .queue_target
+            .first()
+            .is_some_and(|item| item.track_id == track_id);
+        if advanced_to_next {
+            debug!(
+                "CastManager: receiver advanced from {} to queued track {}",
+                left_track_id, track_id
+            );
+            let _ = self
+                .bus_producer
+                .send(Message::Playback(PlaybackMessage::TrackFinished(
+                    left_track_id,
+                )));
+        } else {
+            debug!(
+                "CastManager: receiver jumped from {} to track {}",
+                left_track_id, track_id
+            );
+            let _ = self
+                .bus_producer
+                .send(Message::Cast(CastMessage::ReceiverJumpedToTrack {
+                    track_id,
+                }));
+        }
+    }
+
+    fn acknowledge_queue_advance(&mut self, track_id: &str) {
+        let source_path = self.current_track_source_path.clone().unwrap_or_default();
+        let track_info =
+            read_cast_track_info(&source_path, self.current_track_metadata_summary.as_ref());
+        let remote_locator = parse_opensubsonic_track_uri(source_path.as_path());
+        let source_technical_metadata = track_info
+            .source_technical_metadata
+            .clone()
+            .unwrap_or_else(|| {
+                fallback_source_technical_metadata(
+                    &source_path,
+                    track_info.duration_ms,
+                    remote_locator
+                        .as_ref()
+                        .and_then(|locator| locator.format_hint.as_deref()),
+                )
+            });
+        self.current_track_duration_ms = Some(source_technical_metadata.duration_ms)
+            .filter(|value| *value > 0)
+            .or(track_info.duration_ms);
+        let description = if remote_locator.is_some() {
+            "Casting: Direct (OpenSubsonic stream)"
+        } else {
+            "Casting: Direct (unmodified source stream)"
+        };
+        let _ = self.bus_producer.send(Message::Playback(
+            PlaybackMessage::TechnicalMetadataChanged(source_technical_metadata),
+        ));
+        let _ = self
+            .bus_producer
+            .send(Message::Cast(CastMessage::PlaybackPathChanged {
+                kind: CastPlaybackPathKind::Direct,
+                description: description.to_string(),
+                transcode_output_metadata: None,
+            }));
+        let _ = self
+            .bus_producer
+            .send(Message::Playback(PlaybackMessage::TrackStarted(
+                TrackStarted {
+                    id: track_id.to_string(),
+                    start_offset_ms: 0,
+                },
+            )));
+    }
+
     fn load_track(
         &mut self,
         track_id: &str,
         path: PathBuf,
         start_offset_ms: u64,
         metadata_summary: Option<TrackMetadataSummary>,
+        upcoming: Vec<CastQueueItem>,
     ) {
         self.current_track_source_path = Some(path.clone());
         self.current_track_metadata_summary = metadata_summary.clone();
+        if self.queue_advanced_track_id.take().as_deref() == Some(track_id)
+            && start_offset_ms == 0
+            && self.current_track_id.as_deref() == Some(track_id)
+        {
+            // The receiver already moved onto this track from its queue.
+            self.acknowledge_queue_advance(track_id);
+            return;
+        }
         let direct_result = self.load_track_with_mode(
             track_id,
             path.clone(),
             start_offset_ms,
             CastPlaybackPathKind::Direct,
             metadata_summary.clone(),
+            &upcoming,
         );
         if direct_result.is_ok() {
             return;
@@ -1883,6 +2698,7 @@ impl CastManager {
             start_offset_ms,
             CastPlaybackPathKind::TranscodeWavPcm,
             metadata_summary,
+            &upcoming,
         ) {
             Ok(()) => {}
             Err(err) => {
@@ -1913,6 +2729,12 @@ impl CastManager {
         if let Some(media_session_id) = status.media_session_id {
             self.current_media_session_id = Some(media_session_id);
         }
+        if let Some(item_id) = status.current_item_id {
+            if self.receiver_current_item_id != Some(item_id) {
+                self.handle_receiver_item_changed(item_id);
+            }
+            self.sync_receiver_queue();
+        }
         if let Some(track_id) = self.current_track_id.clone() {
             let elapsed_ms = (status.current_time_s.max(0.0) * 1000.0).round() as u64;
             let mut total_ms = (status.duration_s.max(0.0) * 1000.0).round() as u64;
@@ -1960,6 +2782,7 @@ impl CastManager {
                         {
                             let source_path = self.current_track_source_path.clone();
                             let retry_track_id = track_id.clone();
+                            let retry_upcoming = self.queue_target.clone();
                             if let Some(source_path) = source_path {
                                 let retry_offset_ms =
                                     (status.current_time_s.max(0.0) * 1000.0).round() as u64;
@@ -1969,6 +2792,7 @@ impl CastManager {
                                     retry_offset_ms,
                                     CastPlaybackPathKind::TranscodeWavPcm,
                                     self.current_track_metadata_summary.clone(),
+                                    &retry_upcoming,
                                 ) {
                                     Ok(()) => {
                                         let _ = self.bus_producer.send(Message::Cast(
@@ -2027,9 +2851,17 @@ impl CastManager {
             }
         };
         for (namespace, payload) in drained {
-            if namespace == CAST_NAMESPACE_MEDIA {
+            if namespace == CAST_NAMESPACE_RECEIVER {
+                if let Some((level, muted)) = parse_receiver_volume(&payload) {
+                    self.handle_receiver_volume(level, muted);
+                }
+            } else if namespace == CAST_NAMESPACE_MEDIA {
                 if let Some(status) = parse_media_status(&payload) {
                     self.handle_media_status(status);
+                } else if let Some(item_ids) = parse_queue_item_ids(&payload) {
+                    self.handle_queue_item_ids(item_ids);
+                } else if let Some(items) = parse_queue_items(&payload) {
+                    self.handle_queue_items(items);
                 }
             }
         }
@@ -2043,6 +2875,14 @@ impl CastManager {
             return;
         }
         self.last_status_poll_at = Instant::now();
+        if self
+            .queue_request_sent_at
+            .is_some_and(|sent_at| sent_at.elapsed() >= CAST_QUEUE_EDIT_TIMEOUT)
+        {
+            debug!("CastManager: queue request timed out, resyncing receiver queue");
+            self.queue_request_sent_at = None;
+            self.receiver_item_ids.clear();
+        }
         if let Some(session) = self.session.as_mut() {
             if let Err(err) = session.request_media_status() {
                 warn!("CastManager: failed to request media status: {}", err);
@@ -2059,6 +2899,12 @@ impl CastManager {
                         if let Some(allow_transcode_fallback) = cast.allow_transcode_fallback {
                             self.allow_transcode_fallback = allow_transcode_fallback;
                         }
+                        if let Some(device_volume_caps) = cast.device_volume_caps {
+                            self.device_volume_caps = device_volume_caps;
+                            if let Some((level, muted, _)) = self.receiver_volume {
+                                self.handle_receiver_volume(level, muted);
+                            }
+                        }
                     }
                 }
             }
@@ -2070,7 +2916,12 @@ impl CastManager {
                 path,
                 start_offset_ms,
                 metadata_summary,
-            }) => self.load_track(&track_id, path, start_offset_ms, metadata_summary),
+                upcoming,
+            }) => self.load_track(&track_id, path, start_offset_ms, metadata_summary, upcoming),
+            Message::Cast(CastMessage::SyncQueue {
+                current_track_id,
+                upcoming,
+            }) => self.set_queue_target(current_track_id, upcoming),
             Message::Integration(IntegrationMessage::UpsertBackendProfile {
                 profile,
                 password,
@@ -2125,16 +2976,15 @@ impl CastManager {
                 }
             }
             Message::Cast(CastMessage::SetVolume(volume)) => {
-                if let Some(session) = self.session.as_mut() {
-                    if let Err(err) = session.set_volume(volume) {
-                        warn!("CastManager: set volume command failed: {}", err);
-                    }
-                }
+                self.set_receiver_volume(volume, "set volume command");
             }
             Message::Playback(PlaybackMessage::SetVolume(volume)) => {
+                self.set_receiver_volume(volume, "mirrored volume command");
+            }
+            Message::Cast(CastMessage::SetMuted(muted)) => {
                 if let Some(session) = self.session.as_mut() {
-                    if let Err(err) = session.set_volume(volume) {
-                        warn!("CastManager: mirrored volume command failed: {}", err);
+                    if let Err(err) = session.set_muted(muted) {
+                        warn!("CastManager: set muted command failed: {}", err);
                     }
                 }
             }
@@ -2142,7 +2992,9 @@ impl CastManager {
                 CastMessage::DevicesUpdated(_)
                 | CastMessage::ConnectionStateChanged { .. }
                 | CastMessage::PlaybackPathChanged { .. }
-                | CastMessage::PlaybackError { .. },
+                | CastMessage::PlaybackError { .. }
+                | CastMessage::ReceiverJumpedToTrack { .. }
+                | CastMessage::VolumeStatusChanged { .. },
             ) => {}
             _ => {}
         }
@@ -2164,6 +3016,7 @@ impl CastManager {
     /// Starts the blocking cast manager loop.
     pub fn run(&mut self) {
         info!("CastManager: started");
+        self.emit_devices();
         loop {
             if self.process_pending_bus_messages() {
                 break;
@@ -2177,3 +3030,131 @@ impl CastManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(items: &[(i64, &str)]) -> Vec<(i64, String)> {
+        items
+            .iter()
+            .map(|(item_id, track_id)| (*item_id, track_id.to_string()))
+            .collect()
+    }
+
+    fn requested(track_ids: &[&str]) -> Vec<String> {
+        track_ids
+            .iter()
+            .map(|track_id| track_id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_cast_queue_sync_reorders_without_reinserting() {
+        let step = plan_cast_queue_sync(
+            &queued(&[(11, "b"), (12, "c"), (13, "d")]),
+            &requested(&["d", "b", "c"]),
+        );
+        assert_eq!(step, CastQueueSyncStep::Reorder(vec![13, 11, 12]));
+
+        let step = plan_cast_queue_sync(&queued(&[(11, "b"), (12, "c")]), &requested(&["b", "c"]));
+        assert_eq!(step, CastQueueSyncStep::Unchanged);
+    }
+
+    #[test]
+    fn test_plan_cast_queue_sync_removes_before_inserting() {
+        let step = plan_cast_queue_sync(
+            &queued(&[(11, "b"), (12, "stale"), (13, "b")]),
+            &requested(&["b", "e"]),
+        );
+        assert_eq!(step, CastQueueSyncStep::Remove(vec![12, 13]));
+
+        let step = plan_cast_queue_sync(&queued(&[(11, "b")]), &requested(&["e", "b", "f"]));
+        assert_eq!(step, CastQueueSyncStep::Insert(vec![0, 2]));
+
+        // Repeat-one mirrors the playing track as its own successor.
+        let step = plan_cast_queue_sync(&queued(&[]), &requested(&["a"]));
+        assert_eq!(step, CastQueueSyncStep::Insert(vec![0]));
+    }
+
+    #[test]
+    fn test_queue_load_payload_starts_on_current_track() {
+        let payload = queue_load_payload(
+            7,
+            serde_json::json!({"customData":{"trackId":"a"}}),
+            vec![
+                serde_json::json!({"customData":{"trackId":"b"}}),
+                serde_json::json!({"customData":{"trackId":"c"}}),
+            ],
+            1500,
+        );
+        assert_eq!(payload["type"], "QUEUE_LOAD");
+        assert_eq!(payload["startIndex"], 0);
+        assert_eq!(payload["currentTime"], 1.5);
+        let track_ids: Vec<&str> = payload["items"]
+            .as_array()
+            .expect("queue items")
+            .iter()
+            .filter_map(|item| item["media"]["customData"]["trackId"].as_str())
+            .collect();
+        assert_eq!(track_ids, vec!["a", "b", "c"]);
+        assert!(queue_load_payload(8, serde_json::json!({}), Vec::new(), 0)
+            .get("currentTime")
+            .is_none());
+    }
+
+    fn device(id: &str, name: &str, address: &str) -> CastDeviceInfo {
+        CastDeviceInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            model: "Chromecast Audio".to_string(),
+            host: format!("{id}.local"),
+            address: address.to_string(),
+            port: 8009,
+            available: true,
+        }
+    }
+
+    #[test]
+    fn test_merge_known_cast_devices_keeps_missing_devices_as_unavailable() {
+        let known = vec![
+            device("kitchen", "Kitchen", "10.0.0.4"),
+            device("office", "Office", "10.0.0.5"),
+        ];
+        let discovered = vec![
+            device("office", "Office", "10.0.0.9"),
+            device("bedroom", "Bedroom", "10.0.0.6"),
+        ];
+
+        let merged = merge_known_cast_devices(&known, discovered);
+        let summary: Vec<(&str, bool, &str)> = merged
+            .iter()
+            .map(|device| {
+                (
+                    device.id.as_str(),
+                    device.available,
+                    device.address.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("bedroom", true, "10.0.0.6"),
+                ("office", true, "10.0.0.9"),
+                ("kitchen", false, "10.0.0.4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_receiver_volume_reads_level_and_mute() {
+        let payload = r#"{"type":"RECEIVER_STATUS","requestId":0,"status":{"volume":{"controlType":"attenuation","level":0.35,"muted":true,"stepInterval":0.05}}}"#;
+        let (level, muted) = parse_receiver_volume(payload).expect("volume should parse");
+        assert!((level - 0.35).abs() < 1e-6);
+        assert!(muted);
+
+        let media_status = r#"{"type":"MEDIA_STATUS","status":[]}"#;
+        assert!(parse_receiver_volume(media_status).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 //! Persistent application configuration model and defaults.
 
 use crate::layout::LayoutConfig;
+use crate::ui::keyboard_shortcuts::ShortcutCommand;
 
 /// Root configuration persisted to `config.toml`.
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -22,6 +23,21 @@ pub struct Config {
     #[serde(default)]
     /// Remote integration profile configuration.
     pub integrations: IntegrationsConfig,
+    #[serde(default)]
+    /// Spoken track-change announcement preferences.
+    pub announcements: AnnouncementConfig,
+    #[serde(default)]
+    /// System power/session reaction preferences.
+    pub power: PowerConfig,
+    #[serde(default)]
+    /// Background worker pool sizing.
+    pub performance: PerformanceConfig,
+    #[serde(default)]
+    /// Which destructive actions ask for confirmation first.
+    pub confirmations: ConfirmationsConfig,
+    #[serde(default)]
+    /// Playback signal processing applied in the output stage.
+    pub dsp: DspConfig,
 }
 
 /// Output device and format preferences.
@@ -40,12 +56,85 @@ pub struct OutputConfig {
     pub sample_rate_auto: bool,
     #[serde(default = "default_true")]
     pub bits_per_sample_auto: bool,
+    /// In Match Content mode, play runs of 44.1 kHz- or 48 kHz-family tracks at the run's
+    /// highest rate instead of reopening the device for each rate.
+    #[serde(default)]
+    pub group_sample_rate_families: bool,
+    /// In Match Content mode, resample a single track whose rate differs from the tracks
+    /// around it instead of switching the output rate twice.
+    #[serde(default)]
+    pub resample_rate_outliers: bool,
+    /// Match Content rate policy chosen for each output device.
+    #[serde(default)]
+    pub device_rate_policies: Vec<OutputDeviceRatePolicyConfig>,
     #[serde(default)]
     pub resampler_quality: ResamplerQuality,
     #[serde(default = "default_true")]
     pub dither_on_bitdepth_reduce: bool,
     #[serde(default = "default_true")]
     pub downmix_higher_channel_tracks: bool,
+    /// Start playback after hidden pregap audio declared by an embedded cue sheet.
+    #[serde(default)]
+    pub skip_hidden_pregap: bool,
+    /// Cut long pauses from podcasts and audiobooks in every playlist.
+    #[serde(default)]
+    pub skip_silence_spoken_word: bool,
+    /// Shortest pause, in milliseconds, that skip-silence playback cuts.
+    #[serde(default = "default_skip_silence_min_pause_ms")]
+    pub skip_silence_min_pause_ms: u32,
+}
+
+impl OutputConfig {
+    /// Rate policy of the configured output device.
+    pub fn active_rate_policy(&self) -> OutputDeviceRatePolicyConfig {
+        rate_policy_for_device(&self.device_rate_policies, &self.output_device_name)
+    }
+}
+
+/// Rate policy assigned to `output_device_name`, falling back to the policy assigned to
+/// the system default device and then to matching every source.
+pub fn rate_policy_for_device(
+    assignments: &[OutputDeviceRatePolicyConfig],
+    output_device_name: &str,
+) -> OutputDeviceRatePolicyConfig {
+    let find = |name: &str| {
+        assignments
+            .iter()
+            .find(|assignment| assignment.output_device_name == name)
+    };
+    find(output_device_name)
+        .or_else(|| find(DEFAULT_OUTPUT_DEVICE_NAME))
+        .cloned()
+        .unwrap_or_else(|| OutputDeviceRatePolicyConfig {
+            output_device_name: output_device_name.to_string(),
+            policy: SampleRatePolicy::MatchSource,
+            pinned_sample_rate_hz: default_pinned_sample_rate_hz(),
+        })
+}
+
+/// How Match Content output follows track sample rates on one device.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleRatePolicy {
+    /// Switch the output to every track's own rate.
+    #[default]
+    MatchSource,
+    /// Hold the device at one rate and resample everything to it.
+    Pinned,
+    /// Switch only where a new album starts; the rest of the album is resampled to the
+    /// rate of its first track.
+    AlbumBoundaries,
+}
+
+/// Match Content rate policy chosen for one output device.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct OutputDeviceRatePolicyConfig {
+    /// Output device name as shown in settings, or `default` for the system default.
+    pub output_device_name: String,
+    pub policy: SampleRatePolicy,
+    /// Rate held by [`SampleRatePolicy::Pinned`], in Hz.
+    #[serde(default = "default_pinned_sample_rate_hz")]
+    pub pinned_sample_rate_hz: u32,
 }
 
 /// Cast playback preferences persisted between sessions.
@@ -54,12 +143,47 @@ pub struct CastConfig {
     /// Enable sender-side transcoding fallback for receivers that reject direct source streams.
     #[serde(default)]
     pub allow_transcode_fallback: bool,
+    /// Per-device receiver volume ceilings.
+    #[serde(default)]
+    pub device_volume_caps: Vec<CastDeviceVolumeCap>,
+}
+
+impl CastConfig {
+    /// Returns the volume ceiling for one cast device, defaulting to full volume.
+    pub fn max_volume_for_device(&self, device_id: &str) -> f32 {
+        self.device_volume_caps
+            .iter()
+            .find(|cap| cap.device_id == device_id)
+            .map(|cap| cap.max_volume.clamp(0.0, 1.0))
+            .unwrap_or(1.0)
+    }
+}
+
+/// Letter pressed with Ctrl for one remappable main-window command.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ShortcutKeyConfig {
+    pub command: ShortcutCommand,
+    /// Lowercase ASCII letter.
+    pub key: String,
+}
+
+/// Safety ceiling for the receiver volume of one cast device.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct CastDeviceVolumeCap {
+    /// Cast device id as reported by discovery.
+    pub device_id: String,
+    /// Highest receiver volume level from 0.0 to 1.0.
+    pub max_volume: f32,
 }
 
 /// Resampler quality profile used when sample-rate conversion is required.
 #[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ResamplerQuality {
+    /// Linear interpolation; lowest CPU usage, audible aliasing on some material.
+    Linear,
+    /// Short sinc filter for slower machines.
+    Medium,
     /// Good quality with lower CPU usage.
     #[default]
     High,
@@ -103,6 +227,48 @@ pub struct UiConfig {
     pub playback_order: UiPlaybackOrder,
     #[serde(default)]
     pub repeat_mode: UiRepeatMode,
+    /// Arc followed by the energy ramp playback order.
+    #[serde(default)]
+    pub energy_ramp_arc: UiEnergyRampArc,
+    /// Folders recently imported from, most recent first, offered by the import dialog.
+    #[serde(default)]
+    pub recent_import_locations: Vec<String>,
+    /// Lossless tracks at or above this sample rate get the Hi-Res badge.
+    #[serde(default = "default_hi_res_min_sample_rate_hz")]
+    pub hi_res_min_sample_rate_hz: u32,
+    /// Lossless tracks at or above this bit depth get the Hi-Res badge.
+    #[serde(default = "default_hi_res_min_bit_depth")]
+    pub hi_res_min_bit_depth: u16,
+    /// Seconds skipped by Left/Right in the main window.
+    #[serde(default = "default_seek_small_step_secs")]
+    pub seek_small_step_secs: u32,
+    /// Seconds skipped by Shift+Left/Right in the main window.
+    #[serde(default = "default_seek_large_step_secs")]
+    pub seek_large_step_secs: u32,
+    /// Number playlist index and queue position columns from 0 instead of 1.
+    #[serde(default)]
+    pub zero_based_row_numbers: bool,
+    /// Where the compact transport bar sits, outside the layout and above dialogs.
+    #[serde(default)]
+    pub transport_bar_placement: UiTransportBarPlacement,
+    /// Fixed window scale in percent replacing the monitor's scale factor; 0 follows
+    /// the monitor the window is on.
+    #[serde(default)]
+    pub scale_override_percent: u32,
+    /// Folder shown at the top of File Browser panels; empty starts at the first
+    /// library folder, or the home folder without one.
+    #[serde(default)]
+    pub file_browser_root: String,
+    /// Command that opens tracks in an external tag editor, e.g. `kid3` or
+    /// `puddletag`; the track paths are appended as arguments.
+    #[serde(default)]
+    pub external_tagger_command: String,
+    /// Show the Albums library view as a grid of cover art instead of a list.
+    #[serde(default)]
+    pub library_album_grid: bool,
+    /// Ctrl+letter commands bound to another letter than their default.
+    #[serde(default)]
+    pub shortcut_keys: Vec<ShortcutKeyConfig>,
 }
 
 /// Persisted playback-order preference for startup restore.
@@ -113,6 +279,28 @@ pub enum UiPlaybackOrder {
     Default,
     Shuffle,
     Random,
+    ShuffleFolders,
+    EnergyRamp,
+}
+
+/// Persisted arc of the energy ramp playback order.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UiEnergyRampArc {
+    #[default]
+    BuildUp,
+    WindDown,
+    Peak,
+}
+
+/// Placement of the compact transport bar shown on every view.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UiTransportBarPlacement {
+    #[default]
+    Off,
+    Top,
+    Bottom,
 }
 
 /// Persisted repeat preference for startup restore.
@@ -132,6 +320,10 @@ pub struct LibraryConfig {
     pub folders: Vec<String>,
     #[serde(default)]
     pub online_metadata_enabled: bool,
+    /// AcoustID application key for identifying untagged files by fingerprint; empty
+    /// limits tag lookups to MusicBrainz searches by title and artist.
+    #[serde(default)]
+    pub acoustid_api_key: String,
     #[serde(default = "default_true")]
     pub online_metadata_prompt_pending: bool,
     #[serde(default = "default_true")]
@@ -150,6 +342,69 @@ pub struct LibraryConfig {
     pub artist_image_cache_ttl_days: u32,
     #[serde(default = "default_artist_image_cache_max_size_mb")]
     pub artist_image_cache_max_size_mb: u32,
+    /// Folder of the cover-art disk cache; empty uses the default cache folder.
+    #[serde(default)]
+    pub cover_art_cache_dir: String,
+    /// Folder of the artist-image disk cache; empty uses the default cache folder.
+    #[serde(default)]
+    pub artist_image_cache_dir: String,
+    /// Folder of audio decoded for cast devices that cannot play the source format;
+    /// empty uses the default cache folder.
+    #[serde(default)]
+    pub decoded_audio_cache_dir: String,
+    #[serde(default = "default_decoded_audio_cache_max_size_mb")]
+    pub decoded_audio_cache_max_size_mb: u32,
+    /// Folder of remote tracks downloaded for offline playback; empty uses the default
+    /// data folder.
+    #[serde(default)]
+    pub offline_cache_dir: String,
+    /// Size limit of offline downloads in MB; `0` keeps every download.
+    #[serde(default)]
+    pub offline_cache_max_size_mb: u32,
+    /// Rules applied to files first imported from specific library folders.
+    #[serde(default)]
+    pub import_rules: Vec<FolderImportRuleConfig>,
+    /// Hours between scheduled library rescans; `0` disables the task.
+    #[serde(default)]
+    pub maintenance_rescan_interval_hours: u32,
+    /// Hours between scheduled orphaned-row pruning; `0` disables the task.
+    #[serde(default = "default_maintenance_prune_interval_hours")]
+    pub maintenance_prune_interval_hours: u32,
+    /// Hours between scheduled remote profile syncs; `0` disables the task.
+    #[serde(default)]
+    pub maintenance_remote_sync_interval_hours: u32,
+    /// Hours between scheduled cache expiry passes; `0` disables the task.
+    #[serde(default = "default_maintenance_cache_expiry_interval_hours")]
+    pub maintenance_cache_expiry_interval_hours: u32,
+    /// Hours between scheduled file integrity checks; `0` disables the task.
+    #[serde(default)]
+    pub maintenance_integrity_interval_hours: u32,
+    /// Re-read tags of library and playlist files edited by other programs.
+    #[serde(default = "default_true")]
+    pub watch_tag_changes: bool,
+    /// Tunes for libraries on slow network storage: cached covers and formats are
+    /// reused, and background file checks wait for an explicit rescan.
+    #[serde(default)]
+    pub slow_storage_mode: bool,
+}
+
+/// Actions applied to library files imported from one folder (and its subfolders).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct FolderImportRuleConfig {
+    /// Folder the rule applies to.
+    pub folder: String,
+    /// Genre stored for matching files instead of the tagged genre; empty keeps tags.
+    #[serde(default)]
+    pub genre: String,
+    /// User tags assigned to newly imported files.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Playlist (by name) newly imported files are appended to; created when missing.
+    #[serde(default)]
+    pub playlist: String,
+    /// Leave newly imported files out of shuffle order.
+    #[serde(default)]
+    pub exclude_from_shuffle: bool,
 }
 
 /// Declarative playlist column definition.
@@ -186,6 +441,9 @@ pub struct BufferingConfig {
     pub player_request_interval_ms: u32,
     #[serde(default = "default_decoder_request_chunk_ms")]
     pub decoder_request_chunk_ms: u32,
+    /// Ceiling for decoded audio held in memory ahead of playback, in MB.
+    #[serde(default = "default_decoded_cache_max_mb")]
+    pub decoded_cache_max_mb: u32,
 }
 
 /// Integration profile configuration persisted between sessions.
@@ -193,6 +451,46 @@ pub struct BufferingConfig {
 pub struct IntegrationsConfig {
     #[serde(default)]
     pub backends: Vec<BackendProfileConfig>,
+    /// Developer-only fake server replacing OpenSubsonic for every profile. Not shown in
+    /// the settings dialog; enabled by adding an `[integrations.mock_backend]` table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mock_backend: Option<MockBackendConfig>,
+}
+
+/// Behavior of the developer mock backend.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct MockBackendConfig {
+    /// Delay added to every server call, in milliseconds.
+    #[serde(default = "default_mock_backend_latency_ms")]
+    pub latency_ms: u32,
+    /// Share of server calls that fail, in percent.
+    #[serde(default)]
+    pub failure_rate_percent: u32,
+    /// Fail every call as if the server were unreachable.
+    #[serde(default)]
+    pub unavailable: bool,
+    /// Number of tracks in the canned library.
+    #[serde(default = "default_mock_backend_track_count")]
+    pub track_count: u32,
+}
+
+impl Default for MockBackendConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: default_mock_backend_latency_ms(),
+            failure_rate_percent: 0,
+            unavailable: false,
+            track_count: default_mock_backend_track_count(),
+        }
+    }
+}
+
+fn default_mock_backend_latency_ms() -> u32 {
+    150
+}
+
+fn default_mock_backend_track_count() -> u32 {
+    500
 }
 
 /// Persisted backend profile metadata (non-secret fields only).
@@ -211,12 +509,250 @@ pub struct BackendProfileConfig {
     pub enabled: bool,
 }
 
+/// Spoken track-change announcement preferences persisted between sessions.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct AnnouncementConfig {
+    /// Speak "Now playing" announcements through the OS text-to-speech engine on track changes.
+    #[serde(default)]
+    pub enabled: bool,
+    /// OS voice name; empty selects the system default voice.
+    #[serde(default)]
+    pub voice: String,
+    /// Speech rate in words per minute.
+    #[serde(default = "default_announcement_rate_wpm")]
+    pub rate_wpm: u32,
+    /// Lower playback volume while an announcement is being spoken.
+    #[serde(default = "default_true")]
+    pub duck_playback: bool,
+    /// Playback gain applied while ducking (0.0 .. 1.0).
+    #[serde(default = "default_announcement_duck_gain")]
+    pub duck_gain: f32,
+}
+
+/// System power/session reaction preferences persisted between sessions.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PowerConfig {
+    /// Pause playback when the system is about to suspend.
+    #[serde(default = "default_true")]
+    pub pause_on_suspend: bool,
+    /// Pause playback when the desktop session is locked.
+    #[serde(default)]
+    pub pause_on_lock: bool,
+    /// Pause playback when the screensaver activates.
+    #[serde(default)]
+    pub pause_on_screensaver: bool,
+    /// Resume playback on unlock/wake when it was paused by one of the triggers above.
+    #[serde(default)]
+    pub resume_on_unlock: bool,
+    /// Trade background work and UI refresh rate for battery life while unplugged.
+    #[serde(default)]
+    pub battery_saver: bool,
+    /// Pause playback while another application plays audio (calls, browser video).
+    #[serde(default)]
+    pub pause_on_other_audio: bool,
+    /// Resume playback once the other application's audio stops.
+    #[serde(default = "default_true")]
+    pub resume_after_other_audio: bool,
+    /// Only resume when the other audio lasted at most this many minutes; `0` always resumes.
+    #[serde(default = "default_other_audio_resume_window_minutes")]
+    pub other_audio_resume_window_minutes: u32,
+}
+
+fn default_other_audio_resume_window_minutes() -> u32 {
+    30
+}
+
+/// Background worker pool sizing persisted between sessions.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Default)]
+pub struct PerformanceConfig {
+    /// Threads reading tags during library scans; `0` sizes the pool from the CPU count.
+    #[serde(default)]
+    pub decode_threads: u32,
+    /// Threads running whole-file audio analysis; `0` sizes the pool from the CPU count.
+    #[serde(default)]
+    pub analysis_threads: u32,
+    /// Scheduling priority of background workers relative to playback.
+    #[serde(default)]
+    pub worker_priority: WorkerPriority,
+    /// Renderer used for the window; applies on the next start.
+    #[serde(default)]
+    pub rendering_backend: RenderingBackend,
+}
+
+/// Playback signal processing persisted between sessions.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct DspConfig {
+    /// Night-mode compressor strength in percent; `0` disables it.
+    #[serde(default)]
+    pub night_mode_strength_percent: u32,
+    /// Bring measured tracks to a common loudness in every playlist.
+    #[serde(default)]
+    pub normalize_loudness: bool,
+    /// Gain applied after the volume control, in dB.
+    #[serde(default)]
+    pub preamp_db: f32,
+    /// Soft-limit the output so normalization and pre-amp gain never clip.
+    #[serde(default = "default_true")]
+    pub limiter_enabled: bool,
+    /// Distance of the limiter ceiling below full scale, in dB.
+    #[serde(default = "default_limiter_headroom_db")]
+    pub limiter_headroom_db: f32,
+    /// Named parametric EQ presets, usually imported from AutoEq or REW.
+    #[serde(default)]
+    pub eq_presets: Vec<EqPresetConfig>,
+    /// EQ preset chosen for each output device.
+    #[serde(default)]
+    pub eq_device_presets: Vec<EqDevicePresetConfig>,
+}
+
+fn default_limiter_headroom_db() -> f32 {
+    1.0
+}
+
+/// Output device name stored when the output follows the system default device.
+pub const DEFAULT_OUTPUT_DEVICE_NAME: &str = "default";
+
+impl DspConfig {
+    /// Name of the EQ preset assigned to exactly `output_device_name`, if any.
+    pub fn eq_preset_name_for_device(&self, output_device_name: &str) -> Option<&str> {
+        self.eq_device_presets
+            .iter()
+            .find(|assignment| assignment.output_device_name == output_device_name)
+            .map(|assignment| assignment.preset_name.as_str())
+    }
+
+    /// EQ preset used on an opened output device, falling back to the preset assigned to
+    /// the system default device.
+    pub fn eq_preset_for_device(&self, output_device_name: &str) -> Option<&EqPresetConfig> {
+        let preset_name = self
+            .eq_preset_name_for_device(output_device_name)
+            .or_else(|| self.eq_preset_name_for_device(DEFAULT_OUTPUT_DEVICE_NAME))?;
+        self.eq_presets
+            .iter()
+            .find(|preset| preset.name == preset_name)
+    }
+}
+
+/// Named parametric EQ preset correcting one headphone or speaker.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EqPresetConfig {
+    /// Unique preset name shown in settings.
+    pub name: String,
+    /// Headphone or speaker model the preset was measured for.
+    #[serde(default)]
+    pub target: String,
+    /// Gain applied ahead of the filters, in dB; usually negative to leave room for boosts.
+    #[serde(default)]
+    pub preamp_db: f32,
+    #[serde(default)]
+    pub filters: Vec<EqFilterConfig>,
+}
+
+/// One band of a parametric EQ preset.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EqFilterConfig {
+    pub kind: EqFilterKind,
+    /// Center or corner frequency.
+    pub frequency_hz: f32,
+    /// Boost or cut for peaking and shelf bands; ignored by pass filters.
+    #[serde(default)]
+    pub gain_db: f32,
+    #[serde(default = "default_eq_filter_q")]
+    pub q: f32,
+}
+
+fn default_eq_filter_q() -> f32 {
+    std::f32::consts::FRAC_1_SQRT_2
+}
+
+/// Filter shape of one parametric EQ band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqFilterKind {
+    Peaking,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+}
+
+/// EQ preset chosen for one output device.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct EqDevicePresetConfig {
+    /// Output device name as shown in settings, or `default` for the system default.
+    pub output_device_name: String,
+    /// Name of an entry in `eq_presets`.
+    pub preset_name: String,
+}
+
+/// Confirmation prompts persisted between sessions; "Don't ask again" clears a flag.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ConfirmationsConfig {
+    /// Ask before deleting a playlist.
+    #[serde(default = "default_true")]
+    pub delete_playlist: bool,
+    /// Ask before removing more than `remove_tracks_threshold` tracks from a playlist.
+    #[serde(default = "default_true")]
+    pub remove_tracks: bool,
+    /// Removals of up to this many tracks never ask.
+    #[serde(default = "default_remove_tracks_threshold")]
+    pub remove_tracks_threshold: u32,
+    /// Ask before an edit detaches a playlist from OpenSubsonic sync.
+    #[serde(default = "default_true")]
+    pub detach_remote_playlist: bool,
+    /// Ask before syncing a local playlist to OpenSubsonic, whose later edits
+    /// overwrite the server copy.
+    #[serde(default = "default_true")]
+    pub overwrite_remote_playlist: bool,
+}
+
+fn default_remove_tracks_threshold() -> u32 {
+    25
+}
+
+impl Default for ConfirmationsConfig {
+    fn default() -> Self {
+        Self {
+            delete_playlist: true,
+            remove_tracks: true,
+            remove_tracks_threshold: default_remove_tracks_threshold(),
+            detach_remote_playlist: true,
+            overwrite_remote_playlist: true,
+        }
+    }
+}
+
+/// Scheduling priority for background worker pools.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerPriority {
+    /// Workers take jobs back to back.
+    #[default]
+    Normal,
+    /// Workers pause briefly between jobs so playback and the UI stay responsive.
+    Low,
+}
+
+/// Renderer choice for the main window.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderingBackend {
+    /// Slint picks a renderer, falling back to software when it fails to start.
+    Auto,
+    /// OpenGL rendering, falling back to software when it fails to start.
+    Gpu,
+    /// CPU rendering, which works without a GPU driver.
+    #[default]
+    Software,
+}
+
 /// Supported backend profile kinds persisted in config.
 #[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum IntegrationBackendKind {
     #[default]
     OpenSubsonic,
+    WebDav,
 }
 
 impl Default for OutputConfig {
@@ -230,9 +766,15 @@ impl Default for OutputConfig {
             channel_count_auto: true,
             sample_rate_auto: true,
             bits_per_sample_auto: true,
+            group_sample_rate_families: false,
+            resample_rate_outliers: false,
+            device_rate_policies: Vec::new(),
             resampler_quality: ResamplerQuality::High,
             dither_on_bitdepth_reduce: true,
             downmix_higher_channel_tracks: true,
+            skip_hidden_pregap: false,
+            skip_silence_spoken_word: false,
+            skip_silence_min_pause_ms: default_skip_silence_min_pause_ms(),
         }
     }
 }
@@ -255,6 +797,19 @@ impl Default for UiConfig {
             volume: default_volume(),
             playback_order: UiPlaybackOrder::Default,
             repeat_mode: UiRepeatMode::Off,
+            energy_ramp_arc: UiEnergyRampArc::BuildUp,
+            recent_import_locations: Vec::new(),
+            hi_res_min_sample_rate_hz: default_hi_res_min_sample_rate_hz(),
+            hi_res_min_bit_depth: default_hi_res_min_bit_depth(),
+            seek_small_step_secs: default_seek_small_step_secs(),
+            seek_large_step_secs: default_seek_large_step_secs(),
+            zero_based_row_numbers: false,
+            transport_bar_placement: UiTransportBarPlacement::Off,
+            scale_override_percent: 0,
+            file_browser_root: String::new(),
+            external_tagger_command: String::new(),
+            library_album_grid: false,
+            shortcut_keys: Vec::new(),
         }
     }
 }
@@ -266,6 +821,7 @@ impl Default for BufferingConfig {
             player_target_buffer_ms: default_player_target_buffer_ms(),
             player_request_interval_ms: default_player_request_interval_ms(),
             decoder_request_chunk_ms: default_decoder_request_chunk_ms(),
+            decoded_cache_max_mb: default_decoded_cache_max_mb(),
         }
     }
 }
@@ -275,6 +831,7 @@ impl Default for LibraryConfig {
         Self {
             folders: Vec::new(),
             online_metadata_enabled: false,
+            acoustid_api_key: String::new(),
             online_metadata_prompt_pending: true,
             include_playlist_tracks_in_library: true,
             list_image_max_edge_px: default_list_image_max_edge_px(),
@@ -284,6 +841,62 @@ impl Default for LibraryConfig {
             image_memory_cache_ttl_secs: default_image_memory_cache_ttl_secs(),
             artist_image_cache_ttl_days: default_artist_image_cache_ttl_days(),
             artist_image_cache_max_size_mb: default_artist_image_cache_max_size_mb(),
+            cover_art_cache_dir: String::new(),
+            artist_image_cache_dir: String::new(),
+            decoded_audio_cache_dir: String::new(),
+            decoded_audio_cache_max_size_mb: default_decoded_audio_cache_max_size_mb(),
+            offline_cache_dir: String::new(),
+            offline_cache_max_size_mb: 0,
+            import_rules: Vec::new(),
+            maintenance_rescan_interval_hours: 0,
+            maintenance_prune_interval_hours: default_maintenance_prune_interval_hours(),
+            maintenance_remote_sync_interval_hours: 0,
+            maintenance_cache_expiry_interval_hours:
+                default_maintenance_cache_expiry_interval_hours(),
+            maintenance_integrity_interval_hours: 0,
+            watch_tag_changes: true,
+            slow_storage_mode: false,
+        }
+    }
+}
+
+impl Default for AnnouncementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            voice: String::new(),
+            rate_wpm: default_announcement_rate_wpm(),
+            duck_playback: true,
+            duck_gain: default_announcement_duck_gain(),
+        }
+    }
+}
+
+impl Default for DspConfig {
+    fn default() -> Self {
+        Self {
+            night_mode_strength_percent: 0,
+            normalize_loudness: false,
+            preamp_db: 0.0,
+            limiter_enabled: true,
+            limiter_headroom_db: default_limiter_headroom_db(),
+            eq_presets: Vec::new(),
+            eq_device_presets: Vec::new(),
+        }
+    }
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            pause_on_suspend: true,
+            pause_on_lock: false,
+            pause_on_screensaver: false,
+            resume_on_unlock: false,
+            battery_saver: false,
+            pause_on_other_audio: false,
+            resume_after_other_audio: true,
+            other_audio_resume_window_minutes: default_other_audio_resume_window_minutes(),
         }
     }
 }
@@ -308,6 +921,10 @@ fn default_decoder_request_chunk_ms() -> u32 {
     1_500
 }
 
+fn default_decoded_cache_max_mb() -> u32 {
+    256
+}
+
 fn default_window_width() -> u32 {
     900
 }
@@ -316,10 +933,42 @@ fn default_window_height() -> u32 {
     650
 }
 
+fn default_skip_silence_min_pause_ms() -> u32 {
+    1_000
+}
+
+fn default_pinned_sample_rate_hz() -> u32 {
+    48_000
+}
+
+fn default_hi_res_min_sample_rate_hz() -> u32 {
+    88_200
+}
+
+fn default_hi_res_min_bit_depth() -> u16 {
+    24
+}
+
+fn default_seek_small_step_secs() -> u32 {
+    5
+}
+
+fn default_seek_large_step_secs() -> u32 {
+    30
+}
+
 fn default_volume() -> f32 {
     1.0
 }
 
+fn default_announcement_rate_wpm() -> u32 {
+    175
+}
+
+fn default_announcement_duck_gain() -> f32 {
+    0.3
+}
+
 fn default_artist_image_cache_ttl_days() -> u32 {
     30
 }
@@ -348,6 +997,18 @@ fn default_artist_image_cache_max_size_mb() -> u32 {
     256
 }
 
+fn default_decoded_audio_cache_max_size_mb() -> u32 {
+    2048
+}
+
+fn default_maintenance_prune_interval_hours() -> u32 {
+    168
+}
+
+fn default_maintenance_cache_expiry_interval_hours() -> u32 {
+    24
+}
+
 pub fn default_playlist_album_art_column_min_width_px() -> u32 {
     16
 }
@@ -410,6 +1071,30 @@ pub fn default_playlist_columns() -> Vec<PlaylistColumnConfig> {
             enabled: false,
             custom: false,
         },
+        PlaylistColumnConfig {
+            name: "Disc #".to_string(),
+            format: "{disc_number}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Total Tracks".to_string(),
+            format: "{total_tracks}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Composer".to_string(),
+            format: "{composer}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Comment".to_string(),
+            format: "{comment}".to_string(),
+            enabled: false,
+            custom: false,
+        },
         PlaylistColumnConfig {
             name: "Playing".to_string(),
             format: "{playing}".to_string(),
@@ -428,14 +1113,100 @@ pub fn default_playlist_columns() -> Vec<PlaylistColumnConfig> {
             enabled: false,
             custom: false,
         },
+        PlaylistColumnConfig {
+            name: "Tags".to_string(),
+            format: "{tags}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Source".to_string(),
+            format: "{source}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Loudness".to_string(),
+            format: "{loudness}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "True Peak".to_string(),
+            format: "{true_peak}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Energy".to_string(),
+            format: "{energy}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Quality".to_string(),
+            format: "{quality}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Duration".to_string(),
+            format: "{duration}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Bitrate".to_string(),
+            format: "{bitrate}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Size".to_string(),
+            format: "{file_size}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Plays".to_string(),
+            format: "{play_count}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Skips".to_string(),
+            format: "{skip_count}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Last Played".to_string(),
+            format: "{last_played}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "#".to_string(),
+            format: "{playlist_index}".to_string(),
+            enabled: false,
+            custom: false,
+        },
+        PlaylistColumnConfig {
+            name: "Queue #".to_string(),
+            format: "{queue_position}".to_string(),
+            enabled: false,
+            custom: false,
+        },
     ]
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        default_playlist_columns, BufferingConfig, Config, IntegrationBackendKind, LayoutConfig,
-        ResamplerQuality, UiConfig, UiPlaybackOrder, UiRepeatMode,
+        default_playlist_columns, AnnouncementConfig, BufferingConfig, CastConfig,
+        CastDeviceVolumeCap, Config, IntegrationBackendKind, LayoutConfig, OutputConfig,
+        OutputDeviceRatePolicyConfig, PerformanceConfig, PowerConfig, ResamplerQuality,
+        SampleRatePolicy, UiConfig, UiPlaybackOrder, UiRepeatMode, WorkerPriority,
         BUILTIN_TRACK_DETAILS_COLUMN_FORMAT,
     };
 
@@ -451,10 +1222,21 @@ mod tests {
         assert!(config.output.channel_count_auto);
         assert!(config.output.sample_rate_auto);
         assert!(config.output.bits_per_sample_auto);
+        assert!(!config.output.group_sample_rate_families);
+        assert!(!config.output.resample_rate_outliers);
+        assert!(config.output.device_rate_policies.is_empty());
         assert_eq!(config.output.resampler_quality, ResamplerQuality::High);
         assert!(config.output.dither_on_bitdepth_reduce);
         assert!(config.output.downmix_higher_channel_tracks);
+        assert!(!config.output.skip_hidden_pregap);
+        assert!(!config.output.skip_silence_spoken_word);
+        assert_eq!(config.dsp.night_mode_strength_percent, 0);
+        assert_eq!(config.dsp.preamp_db, 0.0);
+        assert!(config.dsp.limiter_enabled);
+        assert_eq!(config.dsp.limiter_headroom_db, 1.0);
+        assert_eq!(config.output.skip_silence_min_pause_ms, 1_000);
         assert!(!config.cast.allow_transcode_fallback);
+        assert!(config.cast.device_volume_caps.is_empty());
 
         assert!(config.ui.show_layout_edit_intro);
         assert!(config.ui.show_tooltips);
@@ -469,8 +1251,24 @@ mod tests {
         assert!((config.ui.volume - 1.0).abs() < f32::EPSILON);
         assert_eq!(config.ui.playback_order, UiPlaybackOrder::Default);
         assert_eq!(config.ui.repeat_mode, UiRepeatMode::Off);
+        assert_eq!(config.ui.energy_ramp_arc, UiEnergyRampArc::BuildUp);
+        assert!(config.ui.recent_import_locations.is_empty());
+        assert_eq!(config.ui.hi_res_min_sample_rate_hz, 88_200);
+        assert_eq!(config.ui.hi_res_min_bit_depth, 24);
+        assert_eq!(config.ui.seek_small_step_secs, 5);
+        assert_eq!(config.ui.seek_large_step_secs, 30);
+        assert!(!config.ui.zero_based_row_numbers);
+        assert_eq!(
+            config.ui.transport_bar_placement,
+            UiTransportBarPlacement::Off
+        );
+        assert_eq!(config.ui.scale_override_percent, 0);
+        assert!(config.ui.file_browser_root.is_empty());
+        assert!(config.ui.external_tagger_command.is_empty());
+        assert!(!config.ui.library_album_grid);
         assert!(config.library.folders.is_empty());
         assert!(!config.library.online_metadata_enabled);
+        assert!(config.library.acoustid_api_key.is_empty());
         assert!(config.library.online_metadata_prompt_pending);
         assert_eq!(config.library.list_image_max_edge_px, 320);
         assert_eq!(config.library.cover_art_cache_max_size_mb, 512);
@@ -479,11 +1277,49 @@ mod tests {
         assert_eq!(config.library.image_memory_cache_ttl_secs, 20);
         assert_eq!(config.library.artist_image_cache_ttl_days, 30);
         assert_eq!(config.library.artist_image_cache_max_size_mb, 256);
+        assert!(config.library.cover_art_cache_dir.is_empty());
+        assert!(config.library.offline_cache_dir.is_empty());
+        assert_eq!(config.library.decoded_audio_cache_max_size_mb, 2048);
+        assert_eq!(config.library.offline_cache_max_size_mb, 0);
+        assert!(config.library.import_rules.is_empty());
+        assert_eq!(config.library.maintenance_rescan_interval_hours, 0);
+        assert_eq!(config.library.maintenance_prune_interval_hours, 168);
+        assert_eq!(config.library.maintenance_remote_sync_interval_hours, 0);
+        assert_eq!(config.library.maintenance_cache_expiry_interval_hours, 24);
+        assert_eq!(config.library.maintenance_integrity_interval_hours, 0);
+        assert!(config.library.watch_tag_changes);
+        assert!(!config.library.slow_storage_mode);
         assert_eq!(config.buffering.player_low_watermark_ms, 12_000);
         assert_eq!(config.buffering.player_target_buffer_ms, 24_000);
         assert_eq!(config.buffering.player_request_interval_ms, 120);
         assert_eq!(config.buffering.decoder_request_chunk_ms, 1_500);
+        assert_eq!(config.buffering.decoded_cache_max_mb, 256);
         assert!(config.integrations.backends.is_empty());
+        assert!(!config.announcements.enabled);
+        assert!(config.announcements.voice.is_empty());
+        assert_eq!(config.announcements.rate_wpm, 175);
+        assert!(config.announcements.duck_playback);
+        assert!((config.announcements.duck_gain - 0.3).abs() < f32::EPSILON);
+        assert!(config.power.pause_on_suspend);
+        assert!(!config.power.pause_on_lock);
+        assert!(!config.power.pause_on_screensaver);
+        assert!(!config.power.resume_on_unlock);
+        assert!(!config.power.battery_saver);
+        assert!(!config.power.pause_on_other_audio);
+        assert!(config.power.resume_after_other_audio);
+        assert_eq!(config.power.other_audio_resume_window_minutes, 30);
+        assert_eq!(config.performance.decode_threads, 0);
+        assert_eq!(config.performance.analysis_threads, 0);
+        assert_eq!(config.performance.worker_priority, WorkerPriority::Normal);
+        assert_eq!(
+            config.performance.rendering_backend,
+            RenderingBackend::Software
+        );
+        assert!(config.confirmations.delete_playlist);
+        assert!(config.confirmations.remove_tracks);
+        assert_eq!(config.confirmations.remove_tracks_threshold, 25);
+        assert!(config.confirmations.detach_remote_playlist);
+        assert!(config.confirmations.overwrite_remote_playlist);
     }
 
     #[test]
@@ -508,9 +1344,16 @@ decoder_request_chunk_ms = 1500
         assert!(parsed.output.sample_rate_auto);
         assert!(parsed.output.bits_per_sample_auto);
         assert_eq!(parsed.output.resampler_quality, ResamplerQuality::High);
+        let linear: Config = toml::from_str("[output]\nresampler_quality = \"linear\"\n")
+            .expect("resampler quality should parse");
+        assert_eq!(linear.output.resampler_quality, ResamplerQuality::Linear);
         assert!(parsed.output.dither_on_bitdepth_reduce);
         assert!(parsed.output.downmix_higher_channel_tracks);
+        assert!(!parsed.output.skip_hidden_pregap);
+        assert!(!parsed.output.skip_silence_spoken_word);
+        assert_eq!(parsed.output.skip_silence_min_pause_ms, 1_000);
         assert!(!parsed.cast.allow_transcode_fallback);
+        assert!(parsed.cast.device_volume_caps.is_empty());
         assert_eq!(parsed.ui.layout, LayoutConfig::default());
         assert!(parsed.ui.show_layout_edit_intro);
         assert!(parsed.ui.show_tooltips);
@@ -534,11 +1377,18 @@ decoder_request_chunk_ms = 1500
         assert_eq!(parsed.library.image_memory_cache_ttl_secs, 20);
         assert_eq!(parsed.library.artist_image_cache_ttl_days, 30);
         assert_eq!(parsed.library.artist_image_cache_max_size_mb, 256);
+        assert!(parsed.library.import_rules.is_empty());
+        assert_eq!(parsed.library.maintenance_prune_interval_hours, 168);
+        assert_eq!(parsed.library.maintenance_cache_expiry_interval_hours, 24);
         assert_eq!(
             parsed.buffering.player_target_buffer_ms,
             BufferingConfig::default().player_target_buffer_ms
         );
         assert!(parsed.integrations.backends.is_empty());
+        assert_eq!(parsed.announcements, AnnouncementConfig::default());
+        assert_eq!(parsed.power, PowerConfig::default());
+        assert_eq!(parsed.performance, PerformanceConfig::default());
+        assert_eq!(parsed.confirmations, ConfirmationsConfig::default());
     }
 
     #[test]
@@ -554,6 +1404,110 @@ decoder_request_chunk_ms = 1500
         assert!(!album_art_column.custom);
     }
 
+    #[test]
+    fn test_default_playlist_columns_include_tags_builtin_disabled() {
+        let columns = default_playlist_columns();
+        let tags_column = columns
+            .iter()
+            .find(|column| column.format == "{tags}")
+            .expect("tags built-in column should exist");
+
+        assert_eq!(tags_column.name, "Tags");
+        assert!(!tags_column.enabled);
+        assert!(!tags_column.custom);
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_source_builtin_disabled() {
+        let columns = default_playlist_columns();
+        let source_column = columns
+            .iter()
+            .find(|column| column.format == "{source}")
+            .expect("source built-in column should exist");
+
+        assert_eq!(source_column.name, "Source");
+        assert!(!source_column.enabled);
+        assert!(!source_column.custom);
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_loudness_builtins_disabled() {
+        let columns = default_playlist_columns();
+        for (format, name) in [("{loudness}", "Loudness"), ("{true_peak}", "True Peak")] {
+            let column = columns
+                .iter()
+                .find(|column| column.format == format)
+                .expect("loudness built-in column should exist");
+
+            assert_eq!(column.name, name);
+            assert!(!column.enabled);
+            assert!(!column.custom);
+        }
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_number_builtins_disabled() {
+        let columns = default_playlist_columns();
+        for (format, name) in [
+            ("{duration}", "Duration"),
+            ("{bitrate}", "Bitrate"),
+            ("{file_size}", "Size"),
+        ] {
+            let column = columns
+                .iter()
+                .find(|column| column.format == format)
+                .expect("number built-in column should exist");
+            assert_eq!(column.name, name);
+            assert!(!column.enabled);
+            assert!(!column.custom);
+        }
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_play_stats_builtins_disabled() {
+        let columns = default_playlist_columns();
+        for (format, name) in [
+            ("{play_count}", "Plays"),
+            ("{skip_count}", "Skips"),
+            ("{last_played}", "Last Played"),
+        ] {
+            let column = columns
+                .iter()
+                .find(|column| column.format == format)
+                .expect("play stats built-in column should exist");
+            assert_eq!(column.name, name);
+            assert!(!column.enabled);
+            assert!(!column.custom);
+        }
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_row_number_builtins_disabled() {
+        let columns = default_playlist_columns();
+        for (format, name) in [("{playlist_index}", "#"), ("{queue_position}", "Queue #")] {
+            let column = columns
+                .iter()
+                .find(|column| column.format == format)
+                .expect("row number built-in column should exist");
+            assert_eq!(column.name, name);
+            assert!(!column.enabled);
+            assert!(!column.custom);
+        }
+    }
+
+    #[test]
+    fn test_default_playlist_columns_include_energy_builtin_disabled() {
+        let columns = default_playlist_columns();
+        let column = columns
+            .iter()
+            .find(|column| column.format == "{energy}")
+            .expect("energy built-in column should exist");
+
+        assert_eq!(column.name, "Energy");
+        assert!(!column.enabled);
+        assert!(!column.custom);
+    }
+
     #[test]
     fn test_default_playlist_columns_include_track_details_builtin_disabled() {
         let columns = default_playlist_columns();
@@ -645,6 +1599,30 @@ decoder_request_chunk_ms = 1500
             parsed.output.downmix_higher_channel_tracks,
             defaults.output.downmix_higher_channel_tracks
         );
+        assert_eq!(
+            parsed.output.skip_hidden_pregap,
+            defaults.output.skip_hidden_pregap
+        );
+        assert_eq!(
+            parsed.output.group_sample_rate_families,
+            defaults.output.group_sample_rate_families
+        );
+        assert_eq!(
+            parsed.output.resample_rate_outliers,
+            defaults.output.resample_rate_outliers
+        );
+        assert_eq!(
+            parsed.output.device_rate_policies,
+            defaults.output.device_rate_policies
+        );
+        assert_eq!(
+            parsed.output.skip_silence_spoken_word,
+            defaults.output.skip_silence_spoken_word
+        );
+        assert_eq!(
+            parsed.output.skip_silence_min_pause_ms,
+            defaults.output.skip_silence_min_pause_ms
+        );
 
         assert_eq!(
             parsed.ui.show_layout_edit_intro,
@@ -671,6 +1649,7 @@ decoder_request_chunk_ms = 1500
         assert!((parsed.ui.volume - defaults.ui.volume).abs() < f32::EPSILON);
         assert_eq!(parsed.ui.playback_order, defaults.ui.playback_order);
         assert_eq!(parsed.ui.repeat_mode, defaults.ui.repeat_mode);
+        assert_eq!(parsed.ui.shortcut_keys, defaults.ui.shortcut_keys);
         assert_eq!(parsed.library.folders, defaults.library.folders);
         assert_eq!(
             parsed.library.online_metadata_enabled,
@@ -725,7 +1704,16 @@ decoder_request_chunk_ms = 1500
             parsed.buffering.decoder_request_chunk_ms,
             defaults.buffering.decoder_request_chunk_ms
         );
+        assert_eq!(
+            parsed.buffering.decoded_cache_max_mb,
+            defaults.buffering.decoded_cache_max_mb
+        );
+        assert_eq!(parsed.cast, defaults.cast);
         assert_eq!(parsed.integrations.backends, defaults.integrations.backends);
+        assert_eq!(parsed.announcements, defaults.announcements);
+        assert_eq!(parsed.power, defaults.power);
+        assert_eq!(parsed.performance, defaults.performance);
+        assert_eq!(parsed.confirmations, defaults.confirmations);
     }
 
     #[test]
@@ -760,4 +1748,81 @@ decoder_request_chunk_ms = 1500
         assert_eq!(sanitized.ui.playlist_album_art_column_min_width_px, 24);
         assert_eq!(sanitized.ui.playlist_album_art_column_max_width_px, 512);
     }
+
+    #[test]
+    fn test_sanitize_config_clamps_and_dedupes_cast_volume_caps() {
+        let input = Config {
+            cast: CastConfig {
+                device_volume_caps: vec![
+                    CastDeviceVolumeCap {
+                        device_id: " living-room ".to_string(),
+                        max_volume: 1.7,
+                    },
+                    CastDeviceVolumeCap {
+                        device_id: "living-room".to_string(),
+                        max_volume: 0.2,
+                    },
+                    CastDeviceVolumeCap {
+                        device_id: "  ".to_string(),
+                        max_volume: 0.5,
+                    },
+                ],
+                ..CastConfig::default()
+            },
+            ..Config::default()
+        };
+
+        let sanitized = crate::sanitize_config(input);
+        assert_eq!(
+            sanitized.cast.device_volume_caps,
+            vec![CastDeviceVolumeCap {
+                device_id: "living-room".to_string(),
+                max_volume: 1.0,
+            }]
+        );
+        assert!((sanitized.cast.max_volume_for_device("living-room") - 1.0).abs() < f32::EPSILON);
+        assert!((sanitized.cast.max_volume_for_device("kitchen") - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_sanitize_config_clamps_rate_policies_and_falls_back_to_default_device() {
+        let pinned = |name: &str, rate_hz: u32| OutputDeviceRatePolicyConfig {
+            output_device_name: name.to_string(),
+            policy: SampleRatePolicy::Pinned,
+            pinned_sample_rate_hz: rate_hz,
+        };
+        let input = Config {
+            output: OutputConfig {
+                output_device_auto: false,
+                output_device_name: "USB DAC".to_string(),
+                device_rate_policies: vec![
+                    pinned(" default ", 1_000_000),
+                    pinned("default", 44_100),
+                    pinned("Speakers", 96_000),
+                ],
+                ..OutputConfig::default()
+            },
+            ..Config::default()
+        };
+
+        let sanitized = crate::sanitize_config(input);
+        assert_eq!(
+            sanitized.output.device_rate_policies,
+            vec![pinned("default", 192_000), pinned("Speakers", 96_000)]
+        );
+        assert_eq!(
+            sanitized.output.active_rate_policy(),
+            pinned("default", 192_000)
+        );
+
+        let speakers = OutputConfig {
+            output_device_name: "Speakers".to_string(),
+            ..sanitized.output.clone()
+        };
+        assert_eq!(speakers.active_rate_policy().pinned_sample_rate_hz, 96_000);
+        assert_eq!(
+            OutputConfig::default().active_rate_policy().policy,
+            SampleRatePolicy::MatchSource
+        );
+    }
 }
@@ -6,8 +6,12 @@ use log::warn;
 use toml_edit::{value, Array, ArrayOfTables, DocumentMut, Item, Table};
 
 use crate::{
-    config::{Config, IntegrationBackendKind, UiPlaybackOrder, UiRepeatMode},
+    config::{
+        Config, IntegrationBackendKind, UiEnergyRampArc, UiPlaybackOrder, UiRepeatMode,
+        UiTransportBarPlacement,
+    },
     layout::LayoutConfig,
+    ui::keyboard_shortcuts::ShortcutCommand,
 };
 
 fn set_table_value_preserving_decor(table: &mut Table, key: &str, item: Item) {
@@ -63,6 +67,11 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
     ensure_section_table(document, "library");
     ensure_section_table(document, "buffering");
     ensure_section_table(document, "integrations");
+    ensure_section_table(document, "announcements");
+    ensure_section_table(document, "power");
+    ensure_section_table(document, "performance");
+    ensure_section_table(document, "confirmations");
+    ensure_section_table(document, "dsp");
 
     {
         let output = document["output"]
@@ -126,10 +135,26 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             config.output.bits_per_sample_auto,
             value,
         );
+        set_table_scalar_if_changed(
+            output,
+            "group_sample_rate_families",
+            previous.output.group_sample_rate_families,
+            config.output.group_sample_rate_families,
+            value,
+        );
+        set_table_scalar_if_changed(
+            output,
+            "resample_rate_outliers",
+            previous.output.resample_rate_outliers,
+            config.output.resample_rate_outliers,
+            value,
+        );
         if !output.contains_key("resampler_quality")
             || previous.output.resampler_quality != config.output.resampler_quality
         {
             let resampler_quality = match config.output.resampler_quality {
+                crate::config::ResamplerQuality::Linear => "linear",
+                crate::config::ResamplerQuality::Medium => "medium",
                 crate::config::ResamplerQuality::High => "high",
                 crate::config::ResamplerQuality::Highest => "highest",
             };
@@ -154,6 +179,61 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
                 value(config.output.downmix_higher_channel_tracks),
             );
         }
+        if !output.contains_key("skip_hidden_pregap")
+            || previous.output.skip_hidden_pregap != config.output.skip_hidden_pregap
+        {
+            set_table_value_preserving_decor(
+                output,
+                "skip_hidden_pregap",
+                value(config.output.skip_hidden_pregap),
+            );
+        }
+        if !output.contains_key("skip_silence_spoken_word")
+            || previous.output.skip_silence_spoken_word != config.output.skip_silence_spoken_word
+        {
+            set_table_value_preserving_decor(
+                output,
+                "skip_silence_spoken_word",
+                value(config.output.skip_silence_spoken_word),
+            );
+        }
+        if !output.contains_key("skip_silence_min_pause_ms")
+            || previous.output.skip_silence_min_pause_ms != config.output.skip_silence_min_pause_ms
+        {
+            set_table_value_preserving_decor(
+                output,
+                "skip_silence_min_pause_ms",
+                value(i64::from(config.output.skip_silence_min_pause_ms)),
+            );
+        }
+        if !output.contains_key("device_rate_policies")
+            || previous.output.device_rate_policies != config.output.device_rate_policies
+        {
+            let mut assignments = ArrayOfTables::new();
+            for assignment in &config.output.device_rate_policies {
+                let policy = match assignment.policy {
+                    crate::config::SampleRatePolicy::MatchSource => "match_source",
+                    crate::config::SampleRatePolicy::Pinned => "pinned",
+                    crate::config::SampleRatePolicy::AlbumBoundaries => "album_boundaries",
+                };
+                let mut row = Table::new();
+                row.insert(
+                    "output_device_name",
+                    value(assignment.output_device_name.clone()),
+                );
+                row.insert("policy", value(policy));
+                row.insert(
+                    "pinned_sample_rate_hz",
+                    value(i64::from(assignment.pinned_sample_rate_hz)),
+                );
+                assignments.push(row);
+            }
+            set_table_value_preserving_decor(
+                output,
+                "device_rate_policies",
+                Item::ArrayOfTables(assignments),
+            );
+        }
     }
 
     {
@@ -167,6 +247,18 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             config.cast.allow_transcode_fallback,
             value,
         );
+        if !cast.contains_key("device_volume_caps")
+            || previous.cast.device_volume_caps != config.cast.device_volume_caps
+        {
+            let mut caps = ArrayOfTables::new();
+            for cap in &config.cast.device_volume_caps {
+                let mut row = Table::new();
+                row.insert("device_id", value(cap.device_id.clone()));
+                row.insert("max_volume", value(f64::from(cap.max_volume)));
+                caps.push(row);
+            }
+            set_table_value_preserving_decor(cast, "device_volume_caps", Item::ArrayOfTables(caps));
+        }
     }
 
     {
@@ -192,6 +284,13 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             config.ui.auto_scroll_to_playing_track,
             value,
         );
+        set_table_scalar_if_changed(
+            ui,
+            "zero_based_row_numbers",
+            previous.ui.zero_based_row_numbers,
+            config.ui.zero_based_row_numbers,
+            value,
+        );
         ui.remove("dark_mode");
         // Layout-owned state is persisted in layout.toml only.
         ui.remove("button_cluster_instances");
@@ -228,6 +327,8 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
                 UiPlaybackOrder::Default => "default",
                 UiPlaybackOrder::Shuffle => "shuffle",
                 UiPlaybackOrder::Random => "random",
+                UiPlaybackOrder::ShuffleFolders => "shuffle_folders",
+                UiPlaybackOrder::EnergyRamp => "energy_ramp",
             };
             set_table_value_preserving_decor(ui, "playback_order", value(playback_order));
         }
@@ -239,6 +340,116 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             };
             set_table_value_preserving_decor(ui, "repeat_mode", value(repeat_mode));
         }
+        if !ui.contains_key("energy_ramp_arc")
+            || previous.ui.energy_ramp_arc != config.ui.energy_ramp_arc
+        {
+            let energy_ramp_arc = match config.ui.energy_ramp_arc {
+                UiEnergyRampArc::BuildUp => "build_up",
+                UiEnergyRampArc::WindDown => "wind_down",
+                UiEnergyRampArc::Peak => "peak",
+            };
+            set_table_value_preserving_decor(ui, "energy_ramp_arc", value(energy_ramp_arc));
+        }
+        if !ui.contains_key("transport_bar_placement")
+            || previous.ui.transport_bar_placement != config.ui.transport_bar_placement
+        {
+            let transport_bar_placement = match config.ui.transport_bar_placement {
+                UiTransportBarPlacement::Off => "off",
+                UiTransportBarPlacement::Top => "top",
+                UiTransportBarPlacement::Bottom => "bottom",
+            };
+            set_table_value_preserving_decor(
+                ui,
+                "transport_bar_placement",
+                value(transport_bar_placement),
+            );
+        }
+        if !ui.contains_key("recent_import_locations")
+            || previous.ui.recent_import_locations != config.ui.recent_import_locations
+        {
+            let mut locations = Array::new();
+            for location in &config.ui.recent_import_locations {
+                locations.push(location.as_str());
+            }
+            set_table_value_preserving_decor(ui, "recent_import_locations", value(locations));
+        }
+        set_table_scalar_if_changed(
+            ui,
+            "hi_res_min_sample_rate_hz",
+            i64::from(previous.ui.hi_res_min_sample_rate_hz),
+            i64::from(config.ui.hi_res_min_sample_rate_hz),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "hi_res_min_bit_depth",
+            i64::from(previous.ui.hi_res_min_bit_depth),
+            i64::from(config.ui.hi_res_min_bit_depth),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "seek_small_step_secs",
+            i64::from(previous.ui.seek_small_step_secs),
+            i64::from(config.ui.seek_small_step_secs),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "seek_large_step_secs",
+            i64::from(previous.ui.seek_large_step_secs),
+            i64::from(config.ui.seek_large_step_secs),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "scale_override_percent",
+            i64::from(previous.ui.scale_override_percent),
+            i64::from(config.ui.scale_override_percent),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "file_browser_root",
+            previous.ui.file_browser_root.as_str(),
+            config.ui.file_browser_root.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "external_tagger_command",
+            previous.ui.external_tagger_command.as_str(),
+            config.ui.external_tagger_command.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            ui,
+            "library_album_grid",
+            previous.ui.library_album_grid,
+            config.ui.library_album_grid,
+            value,
+        );
+        if !ui.contains_key("shortcut_keys") || previous.ui.shortcut_keys != config.ui.shortcut_keys
+        {
+            let mut bindings = ArrayOfTables::new();
+            for binding in &config.ui.shortcut_keys {
+                let command = match binding.command {
+                    ShortcutCommand::LayoutEditor => "layout_editor",
+                    ShortcutCommand::Search => "search",
+                    ShortcutCommand::Undo => "undo",
+                    ShortcutCommand::SelectAll => "select_all",
+                    ShortcutCommand::JumpToPlaying => "jump_to_playing",
+                    ShortcutCommand::Copy => "copy",
+                    ShortcutCommand::Cut => "cut",
+                    ShortcutCommand::Paste => "paste",
+                };
+                let mut row = Table::new();
+                row.insert("command", value(command));
+                row.insert("key", value(binding.key.clone()));
+                bindings.push(row);
+            }
+            set_table_value_preserving_decor(ui, "shortcut_keys", Item::ArrayOfTables(bindings));
+        }
     }
 
     {
@@ -253,6 +464,13 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             config.library.online_metadata_enabled,
             value,
         );
+        set_table_scalar_if_changed(
+            library,
+            "acoustid_api_key",
+            previous.library.acoustid_api_key.as_str(),
+            config.library.acoustid_api_key.as_str(),
+            value,
+        );
         set_table_scalar_if_changed(
             library,
             "online_metadata_prompt_pending",
@@ -316,6 +534,97 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             i64::from(config.library.artist_image_cache_max_size_mb),
             value,
         );
+        set_table_scalar_if_changed(
+            library,
+            "cover_art_cache_dir",
+            previous.library.cover_art_cache_dir.as_str(),
+            config.library.cover_art_cache_dir.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "artist_image_cache_dir",
+            previous.library.artist_image_cache_dir.as_str(),
+            config.library.artist_image_cache_dir.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "decoded_audio_cache_dir",
+            previous.library.decoded_audio_cache_dir.as_str(),
+            config.library.decoded_audio_cache_dir.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "offline_cache_dir",
+            previous.library.offline_cache_dir.as_str(),
+            config.library.offline_cache_dir.as_str(),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "decoded_audio_cache_max_size_mb",
+            i64::from(previous.library.decoded_audio_cache_max_size_mb),
+            i64::from(config.library.decoded_audio_cache_max_size_mb),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "offline_cache_max_size_mb",
+            i64::from(previous.library.offline_cache_max_size_mb),
+            i64::from(config.library.offline_cache_max_size_mb),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "maintenance_rescan_interval_hours",
+            i64::from(previous.library.maintenance_rescan_interval_hours),
+            i64::from(config.library.maintenance_rescan_interval_hours),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "maintenance_prune_interval_hours",
+            i64::from(previous.library.maintenance_prune_interval_hours),
+            i64::from(config.library.maintenance_prune_interval_hours),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "maintenance_remote_sync_interval_hours",
+            i64::from(previous.library.maintenance_remote_sync_interval_hours),
+            i64::from(config.library.maintenance_remote_sync_interval_hours),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "maintenance_cache_expiry_interval_hours",
+            i64::from(previous.library.maintenance_cache_expiry_interval_hours),
+            i64::from(config.library.maintenance_cache_expiry_interval_hours),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "maintenance_integrity_interval_hours",
+            i64::from(previous.library.maintenance_integrity_interval_hours),
+            i64::from(config.library.maintenance_integrity_interval_hours),
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "watch_tag_changes",
+            previous.library.watch_tag_changes,
+            config.library.watch_tag_changes,
+            value,
+        );
+        set_table_scalar_if_changed(
+            library,
+            "slow_storage_mode",
+            previous.library.slow_storage_mode,
+            config.library.slow_storage_mode,
+            value,
+        );
         if !library.contains_key("folders") || previous.library.folders != config.library.folders {
             let mut folders = Array::new();
             for folder in &config.library.folders {
@@ -323,6 +632,25 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             }
             set_table_value_preserving_decor(library, "folders", value(folders));
         }
+        if !library.contains_key("import_rules")
+            || previous.library.import_rules != config.library.import_rules
+        {
+            let mut rules = ArrayOfTables::new();
+            for rule in &config.library.import_rules {
+                let mut row = Table::new();
+                row.insert("folder", value(rule.folder.clone()));
+                row.insert("genre", value(rule.genre.clone()));
+                let mut tags = Array::new();
+                for tag in &rule.tags {
+                    tags.push(tag.as_str());
+                }
+                row.insert("tags", value(tags));
+                row.insert("playlist", value(rule.playlist.clone()));
+                row.insert("exclude_from_shuffle", value(rule.exclude_from_shuffle));
+                rules.push(row);
+            }
+            set_table_value_preserving_decor(library, "import_rules", Item::ArrayOfTables(rules));
+        }
     }
 
     {
@@ -357,6 +685,13 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             i64::from(config.buffering.decoder_request_chunk_ms),
             value,
         );
+        set_table_scalar_if_changed(
+            buffering,
+            "decoded_cache_max_mb",
+            i64::from(previous.buffering.decoded_cache_max_mb),
+            i64::from(config.buffering.decoded_cache_max_mb),
+            value,
+        );
     }
 
     {
@@ -374,6 +709,7 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
                     "backend_kind",
                     value(match backend.backend_kind {
                         IntegrationBackendKind::OpenSubsonic => "open_subsonic",
+                        IntegrationBackendKind::WebDav => "web_dav",
                     }),
                 );
                 row.insert("display_name", value(backend.display_name.clone()));
@@ -389,6 +725,287 @@ fn write_config_to_document(document: &mut DocumentMut, previous: &Config, confi
             );
         }
     }
+
+    {
+        let announcements = document["announcements"]
+            .as_table_mut()
+            .expect("announcements should be a table");
+        set_table_scalar_if_changed(
+            announcements,
+            "enabled",
+            previous.announcements.enabled,
+            config.announcements.enabled,
+            value,
+        );
+        if !announcements.contains_key("voice")
+            || previous.announcements.voice != config.announcements.voice
+        {
+            set_table_value_preserving_decor(
+                announcements,
+                "voice",
+                value(config.announcements.voice.clone()),
+            );
+        }
+        set_table_scalar_if_changed(
+            announcements,
+            "rate_wpm",
+            i64::from(previous.announcements.rate_wpm),
+            i64::from(config.announcements.rate_wpm),
+            value,
+        );
+        set_table_scalar_if_changed(
+            announcements,
+            "duck_playback",
+            previous.announcements.duck_playback,
+            config.announcements.duck_playback,
+            value,
+        );
+        set_table_scalar_if_changed(
+            announcements,
+            "duck_gain",
+            f64::from(previous.announcements.duck_gain),
+            f64::from(config.announcements.duck_gain),
+            value,
+        );
+    }
+
+    {
+        let power = document["power"]
+            .as_table_mut()
+            .expect("power should be a table");
+        set_table_scalar_if_changed(
+            power,
+            "pause_on_suspend",
+            previous.power.pause_on_suspend,
+            config.power.pause_on_suspend,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "pause_on_lock",
+            previous.power.pause_on_lock,
+            config.power.pause_on_lock,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "pause_on_screensaver",
+            previous.power.pause_on_screensaver,
+            config.power.pause_on_screensaver,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "resume_on_unlock",
+            previous.power.resume_on_unlock,
+            config.power.resume_on_unlock,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "battery_saver",
+            previous.power.battery_saver,
+            config.power.battery_saver,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "pause_on_other_audio",
+            previous.power.pause_on_other_audio,
+            config.power.pause_on_other_audio,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "resume_after_other_audio",
+            previous.power.resume_after_other_audio,
+            config.power.resume_after_other_audio,
+            value,
+        );
+        set_table_scalar_if_changed(
+            power,
+            "other_audio_resume_window_minutes",
+            i64::from(previous.power.other_audio_resume_window_minutes),
+            i64::from(config.power.other_audio_resume_window_minutes),
+            value,
+        );
+    }
+
+    {
+        let performance = document["performance"]
+            .as_table_mut()
+            .expect("performance should be a table");
+        set_table_scalar_if_changed(
+            performance,
+            "decode_threads",
+            i64::from(previous.performance.decode_threads),
+            i64::from(config.performance.decode_threads),
+            value,
+        );
+        set_table_scalar_if_changed(
+            performance,
+            "analysis_threads",
+            i64::from(previous.performance.analysis_threads),
+            i64::from(config.performance.analysis_threads),
+            value,
+        );
+        if !performance.contains_key("worker_priority")
+            || previous.performance.worker_priority != config.performance.worker_priority
+        {
+            let worker_priority = match config.performance.worker_priority {
+                crate::config::WorkerPriority::Normal => "normal",
+                crate::config::WorkerPriority::Low => "low",
+            };
+            set_table_value_preserving_decor(
+                performance,
+                "worker_priority",
+                value(worker_priority),
+            );
+        }
+        if !performance.contains_key("rendering_backend")
+            || previous.performance.rendering_backend != config.performance.rendering_backend
+        {
+            let rendering_backend = match config.performance.rendering_backend {
+                crate::config::RenderingBackend::Auto => "auto",
+                crate::config::RenderingBackend::Gpu => "gpu",
+                crate::config::RenderingBackend::Software => "software",
+            };
+            set_table_value_preserving_decor(
+                performance,
+                "rendering_backend",
+                value(rendering_backend),
+            );
+        }
+    }
+
+    {
+        let confirmations = document["confirmations"]
+            .as_table_mut()
+            .expect("confirmations should be a table");
+        set_table_scalar_if_changed(
+            confirmations,
+            "delete_playlist",
+            previous.confirmations.delete_playlist,
+            config.confirmations.delete_playlist,
+            value,
+        );
+        set_table_scalar_if_changed(
+            confirmations,
+            "remove_tracks",
+            previous.confirmations.remove_tracks,
+            config.confirmations.remove_tracks,
+            value,
+        );
+        set_table_scalar_if_changed(
+            confirmations,
+            "remove_tracks_threshold",
+            i64::from(previous.confirmations.remove_tracks_threshold),
+            i64::from(config.confirmations.remove_tracks_threshold),
+            value,
+        );
+        set_table_scalar_if_changed(
+            confirmations,
+            "detach_remote_playlist",
+            previous.confirmations.detach_remote_playlist,
+            config.confirmations.detach_remote_playlist,
+            value,
+        );
+        set_table_scalar_if_changed(
+            confirmations,
+            "overwrite_remote_playlist",
+            previous.confirmations.overwrite_remote_playlist,
+            config.confirmations.overwrite_remote_playlist,
+            value,
+        );
+    }
+
+    {
+        let dsp = document["dsp"]
+            .as_table_mut()
+            .expect("dsp should be a table");
+        set_table_scalar_if_changed(
+            dsp,
+            "night_mode_strength_percent",
+            i64::from(previous.dsp.night_mode_strength_percent),
+            i64::from(config.dsp.night_mode_strength_percent),
+            value,
+        );
+        set_table_scalar_if_changed(
+            dsp,
+            "normalize_loudness",
+            previous.dsp.normalize_loudness,
+            config.dsp.normalize_loudness,
+            value,
+        );
+        set_table_scalar_if_changed(
+            dsp,
+            "preamp_db",
+            f64::from(previous.dsp.preamp_db),
+            f64::from(config.dsp.preamp_db),
+            value,
+        );
+        set_table_scalar_if_changed(
+            dsp,
+            "limiter_enabled",
+            previous.dsp.limiter_enabled,
+            config.dsp.limiter_enabled,
+            value,
+        );
+        set_table_scalar_if_changed(
+            dsp,
+            "limiter_headroom_db",
+            f64::from(previous.dsp.limiter_headroom_db),
+            f64::from(config.dsp.limiter_headroom_db),
+            value,
+        );
+        if !dsp.contains_key("eq_presets") || previous.dsp.eq_presets != config.dsp.eq_presets {
+            let mut presets = ArrayOfTables::new();
+            for preset in &config.dsp.eq_presets {
+                let mut row = Table::new();
+                row.insert("name", value(preset.name.clone()));
+                row.insert("target", value(preset.target.clone()));
+                row.insert("preamp_db", value(f64::from(preset.preamp_db)));
+                let mut filters = ArrayOfTables::new();
+                for filter in &preset.filters {
+                    let kind = match filter.kind {
+                        crate::config::EqFilterKind::Peaking => "peaking",
+                        crate::config::EqFilterKind::LowShelf => "low_shelf",
+                        crate::config::EqFilterKind::HighShelf => "high_shelf",
+                        crate::config::EqFilterKind::LowPass => "low_pass",
+                        crate::config::EqFilterKind::HighPass => "high_pass",
+                    };
+                    let mut filter_row = Table::new();
+                    filter_row.insert("kind", value(kind));
+                    filter_row.insert("frequency_hz", value(f64::from(filter.frequency_hz)));
+                    filter_row.insert("gain_db", value(f64::from(filter.gain_db)));
+                    filter_row.insert("q", value(f64::from(filter.q)));
+                    filters.push(filter_row);
+                }
+                row.insert("filters", Item::ArrayOfTables(filters));
+                presets.push(row);
+            }
+            set_table_value_preserving_decor(dsp, "eq_presets", Item::ArrayOfTables(presets));
+        }
+        if !dsp.contains_key("eq_device_presets")
+            || previous.dsp.eq_device_presets != config.dsp.eq_device_presets
+        {
+            let mut assignments = ArrayOfTables::new();
+            for assignment in &config.dsp.eq_device_presets {
+                let mut row = Table::new();
+                row.insert(
+                    "output_device_name",
+                    value(assignment.output_device_name.clone()),
+                );
+                row.insert("preset_name", value(assignment.preset_name.clone()));
+                assignments.push(row);
+            }
+            set_table_value_preserving_decor(
+                dsp,
+                "eq_device_presets",
+                Item::ArrayOfTables(assignments),
+            );
+        }
+    }
 }
 
 fn merge_table_with_targeted_updates(destination: &mut Table, source: &Table) {
@@ -472,9 +1089,39 @@ pub fn serialize_layout_with_preserved_comments(
         .map_err(|err| format!("failed to parse existing layout as TOML document: {}", err))?;
 
     merge_table_with_targeted_updates(existing_document.as_table_mut(), next_document.as_table());
+    drop_cleared_playlist_column_keys(&mut existing_document, &next_document);
     Ok(existing_document.to_string())
 }
 
+/// Per-column layout keys that are left out when they hold their default.
+const OPTIONAL_PLAYLIST_COLUMN_KEYS: [&str; 2] = ["width_px", "style"];
+
+/// Removes per-column overrides that were reset since `layout.toml` was last written.
+///
+/// The targeted merge only adds and updates keys, so a cleared width or style would
+/// otherwise survive in the file and come back on the next start.
+fn drop_cleared_playlist_column_keys(existing: &mut DocumentMut, next: &DocumentMut) {
+    let Some(next_columns) = next
+        .get("playlist_columns")
+        .and_then(Item::as_array_of_tables)
+    else {
+        return;
+    };
+    let Some(existing_columns) = existing
+        .get_mut("playlist_columns")
+        .and_then(Item::as_array_of_tables_mut)
+    else {
+        return;
+    };
+    for (existing_column, next_column) in existing_columns.iter_mut().zip(next_columns.iter()) {
+        for key in OPTIONAL_PLAYLIST_COLUMN_KEYS {
+            if !next_column.contains_key(key) {
+                existing_column.remove(key);
+            }
+        }
+    }
+}
+
 /// Persists `config.toml`, preferring comment-preserving updates when possible.
 pub fn persist_config_file(config: &Config, path: &Path) {
     let existing_text = std::fs::read_to_string(path).ok();
@@ -586,6 +1233,19 @@ pub fn load_layout_file(path: &Path) -> LayoutConfig {
     }
 }
 
+/// Carries the legacy `ui.dark_mode = false` flag over to the light color scheme when
+/// `layout.toml` does not pick a scheme of its own.
+pub fn apply_legacy_dark_mode(
+    config: &Config,
+    layout_text: Option<&str>,
+    layout: &mut LayoutConfig,
+) {
+    let has_explicit_color_scheme = layout_text.is_some_and(|text| text.contains("color_scheme"));
+    if !has_explicit_color_scheme && config.ui.legacy_dark_mode == Some(false) {
+        layout.color_scheme = crate::theme::ROQTUNE_LIGHT_SCHEME_ID.to_string();
+    }
+}
+
 /// Copies layout-owned playlist column state into legacy UI fields for compatibility.
 pub fn hydrate_ui_columns_from_layout(config: &mut Config) {
     config.ui.playlist_album_art_column_min_width_px =
@@ -799,6 +1459,39 @@ panel = "track_list"
         assert!(serialized.contains("[[button_cluster_instances]]"));
     }
 
+    #[test]
+    fn test_serialize_layout_with_preserved_comments_drops_reset_column_overrides() {
+        let existing = r#"
+version = 1
+
+[[playlist_columns]]
+name = "Title"
+format = "{title}"
+enabled = true
+custom = false
+width_px = 300
+
+[playlist_columns.style]
+alignment = "right"
+
+[root]
+node_type = "leaf"
+id = "l1"
+panel = "track_list"
+"#;
+        let mut layout: LayoutConfig =
+            toml::from_str(existing).expect("existing layout should parse");
+        layout.playlist_column_width_overrides.clear();
+        layout.playlist_column_styles.clear();
+
+        let serialized = serialize_layout_with_preserved_comments(existing, &layout)
+            .expect("comment-preserving layout serialization should succeed");
+        assert!(!serialized.contains("width_px = 300"));
+        assert!(!serialized.contains("alignment"));
+        let reloaded: LayoutConfig = toml::from_str(&serialized).expect("reloads");
+        assert!(reloaded.playlist_column_styles.is_empty());
+    }
+
     #[test]
     fn test_serialize_layout_with_preserved_comments_rejects_invalid_toml() {
         let invalid = "[root\nnode_type = \"leaf\"";
@@ -973,4 +1666,177 @@ backends = []
         assert!(serialized.contains("username = \"alice\""));
         assert!(serialized.contains("enabled = true"));
     }
+
+    #[test]
+    fn test_serialize_config_with_preserved_comments_persists_cast_volume_caps() {
+        let existing = r#"
+[output]
+output_device_name = ""
+output_device_auto = true
+channel_count = 2
+sample_rate_khz = 44100
+bits_per_sample = 24
+channel_count_auto = true
+sample_rate_auto = true
+bits_per_sample_auto = true
+
+[cast]
+# keep this cast comment
+allow_transcode_fallback = false
+device_volume_caps = []
+"#;
+        let mut config = Config::default();
+        config.cast.device_volume_caps = vec![crate::config::CastDeviceVolumeCap {
+            device_id: "living-room".to_string(),
+            max_volume: 0.5,
+        }];
+
+        let serialized = serialize_config_with_preserved_comments(existing, &config)
+            .expect("cast volume caps should serialize");
+        assert!(serialized.contains("# keep this cast comment"));
+        assert!(serialized.contains("[[cast.device_volume_caps]]"));
+        assert!(serialized.contains("device_id = \"living-room\""));
+        assert!(serialized.contains("max_volume = 0.5"));
+
+        let parsed: Config = toml::from_str(&serialized).expect("serialized config should parse");
+        assert_eq!(
+            parsed.cast.device_volume_caps,
+            config.cast.device_volume_caps
+        );
+    }
+
+    #[test]
+    fn test_serialize_config_with_preserved_comments_persists_shortcut_keys() {
+        let existing = include_str!("../config/config.system.toml");
+        let mut config: Config =
+            toml::from_str(existing).expect("system config template should parse");
+        config.ui.shortcut_keys = vec![crate::config::ShortcutKeyConfig {
+            command: ShortcutCommand::JumpToPlaying,
+            key: "g".to_string(),
+        }];
+
+        let serialized = serialize_config_with_preserved_comments(existing, &config)
+            .expect("shortcut keys should serialize");
+        assert!(serialized.contains("[[ui.shortcut_keys]]"));
+        assert!(serialized.contains("command = \"jump_to_playing\""));
+        assert!(serialized.contains("key = \"g\""));
+
+        let parsed: Config = toml::from_str(&serialized).expect("serialized config should parse");
+        assert_eq!(parsed.ui.shortcut_keys, config.ui.shortcut_keys);
+        assert_eq!(parsed.library.folders, config.library.folders);
+    }
+
+    #[test]
+    fn test_serialize_config_with_preserved_comments_persists_eq_presets() {
+        let existing = r#"
+[output]
+output_device_name = ""
+output_device_auto = true
+channel_count = 2
+sample_rate_khz = 44100
+bits_per_sample = 24
+channel_count_auto = true
+sample_rate_auto = true
+bits_per_sample_auto = true
+"#;
+        let mut config = Config::default();
+        config.dsp.eq_presets = vec![crate::config::EqPresetConfig {
+            name: "HD 600 (AutoEq)".to_string(),
+            target: "HD 600".to_string(),
+            preamp_db: -6.5,
+            filters: vec![crate::config::EqFilterConfig {
+                kind: crate::config::EqFilterKind::LowShelf,
+                frequency_hz: 105.0,
+                gain_db: 5.5,
+                q: 0.75,
+            }],
+        }];
+        config.dsp.eq_device_presets = vec![crate::config::EqDevicePresetConfig {
+            output_device_name: "default".to_string(),
+            preset_name: "HD 600 (AutoEq)".to_string(),
+        }];
+
+        let serialized = serialize_config_with_preserved_comments(existing, &config)
+            .expect("eq presets should serialize");
+        assert!(serialized.contains("[[dsp.eq_presets]]"));
+        assert!(serialized.contains("[[dsp.eq_presets.filters]]"));
+        assert!(serialized.contains("kind = \"low_shelf\""));
+        assert!(serialized.contains("[[dsp.eq_device_presets]]"));
+
+        let parsed: Config = toml::from_str(&serialized).expect("serialized config should parse");
+        assert_eq!(parsed.dsp.eq_presets, config.dsp.eq_presets);
+        assert_eq!(parsed.dsp.eq_device_presets, config.dsp.eq_device_presets);
+    }
+
+    #[test]
+    fn test_serialize_config_with_preserved_comments_persists_device_rate_policies() {
+        let existing = r#"
+[output]
+output_device_name = "USB DAC"
+output_device_auto = false
+sample_rate_auto = true
+"#;
+        let mut config = Config::default();
+        config.output.device_rate_policies = vec![
+            crate::config::OutputDeviceRatePolicyConfig {
+                output_device_name: "USB DAC".to_string(),
+                policy: crate::config::SampleRatePolicy::Pinned,
+                pinned_sample_rate_hz: 96_000,
+            },
+            crate::config::OutputDeviceRatePolicyConfig {
+                output_device_name: "default".to_string(),
+                policy: crate::config::SampleRatePolicy::AlbumBoundaries,
+                pinned_sample_rate_hz: 48_000,
+            },
+        ];
+
+        let serialized = serialize_config_with_preserved_comments(existing, &config)
+            .expect("rate policies should serialize");
+        assert!(serialized.contains("[[output.device_rate_policies]]"));
+        assert!(serialized.contains("policy = \"album_boundaries\""));
+
+        let parsed: Config = toml::from_str(&serialized).expect("serialized config should parse");
+        assert_eq!(
+            parsed.output.device_rate_policies,
+            config.output.device_rate_policies
+        );
+    }
+
+    #[test]
+    fn test_serialize_config_with_preserved_comments_persists_library_import_rules() {
+        let existing = r#"
+[output]
+output_device_name = ""
+output_device_auto = true
+channel_count = 2
+sample_rate_khz = 44100
+bits_per_sample = 24
+channel_count_auto = true
+sample_rate_auto = true
+bits_per_sample_auto = true
+
+[library]
+# keep this library comment
+folders = []
+import_rules = []
+"#;
+        let mut config = Config::default();
+        config.library.import_rules = vec![crate::config::FolderImportRuleConfig {
+            folder: "/music/Podcasts".to_string(),
+            genre: "Podcast".to_string(),
+            tags: vec!["spoken".to_string()],
+            playlist: "New Episodes".to_string(),
+            exclude_from_shuffle: true,
+        }];
+
+        let serialized = serialize_config_with_preserved_comments(existing, &config)
+            .expect("import rules should serialize");
+        assert!(serialized.contains("# keep this library comment"));
+        assert!(serialized.contains("[[library.import_rules]]"));
+        assert!(serialized.contains("folder = \"/music/Podcasts\""));
+        assert!(serialized.contains("exclude_from_shuffle = true"));
+
+        let parsed: Config = toml::from_str(&serialized).expect("serialized config should parse");
+        assert_eq!(parsed.library.import_rules, config.library.import_rules);
+    }
 }
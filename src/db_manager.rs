@@ -1,15 +1,22 @@
 //! SQLite-backed persistence for playlists, library index data, and playlist-scoped UI metadata.
 
+use crate::playlist_column_filters::{self, PlaylistColumnFilter};
 use crate::protocol::{
-    FavoriteEntityKind, FavoriteEntityRef, LibraryAlbum, LibraryArtist, LibraryDecade,
+    ActivityCategory, ActivityLogEntry, FavoriteEntityKind, FavoriteEntityRef, ImportSource,
+    IntegrityIssue, IntegrityIssueKind, LibraryAlbum, LibraryArtist, LibraryDecade,
     LibraryEnrichmentAttemptKind, LibraryEnrichmentEntity, LibraryEnrichmentErrorKind,
-    LibraryEnrichmentPayload, LibraryEnrichmentStatus, LibraryGenre, LibraryTrack, PlaylistInfo,
-    RestoredTrack, TrackMetadataSummary,
+    LibraryEnrichmentPayload, LibraryEnrichmentStatus, LibraryFacetCount, LibraryFacetKind,
+    LibraryFacetSelection, LibraryGenre, LibraryTrack, MaintenanceRunRecord, MaintenanceTask,
+    PlaylistFolderInfo, PlaylistInfo, RestoredTrack, TechnicalMetadata, TrackEnergy,
+    TrackEnergySetting, TrackLoudness, TrackMetadataSummary, TrackPlayStats, TrackTrims,
+    TransitionCounts, TransitionOutcome, UserTag,
 };
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OpenFlags, OptionalExtension};
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
+    thread,
+    time::Duration,
 };
 use uuid::Uuid;
 
@@ -26,6 +33,21 @@ pub struct LibraryScanState {
     pub metadata_ready: bool,
 }
 
+/// Stored integrity baseline and latest verification outcome for one library file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileIntegrityRecord {
+    pub path: String,
+    pub file_size_bytes: i64,
+    pub modified_unix_ms: i64,
+    /// Hex content hash of the file bytes when the baseline was taken.
+    pub content_hash: String,
+    pub verified_unix_ms: i64,
+    pub issue_kind: Option<IntegrityIssueKind>,
+    pub issue_detail: String,
+    /// Whether a lossless file was analyzed for a lossy source since its bytes changed.
+    pub spectrum_checked: bool,
+}
+
 /// Phase-A scan upsert payload.
 #[derive(Debug, Clone)]
 pub struct LibraryTrackScanStub {
@@ -77,11 +99,25 @@ pub struct FavoriteSyncQueueEntry {
     pub desired_favorited: bool,
 }
 
+/// Remote playlist URL bound to one local playlist for periodic refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistUrlSource {
+    pub playlist_id: String,
+    pub url: String,
+    pub refreshed_unix_ms: i64,
+}
+
 impl DbManager {
     const DB_FILE_NAME: &'static str = "roqtune.db";
-    const LEGACY_DB_FILE_NAME: &'static str = "playlist.db";
+    pub(crate) const LEGACY_DB_FILE_NAME: &'static str = "playlist.db";
+    const READ_ONLY_BUSY_TIMEOUT: Duration = Duration::from_millis(2000);
+    const READ_SNAPSHOT_BUSY_RETRIES: u32 = 3;
+    const READ_SNAPSHOT_RETRY_BACKOFF: Duration = Duration::from_millis(25);
+    const MAINTENANCE_HISTORY_LIMIT: i64 = 100;
+    const ACTIVITY_LOG_LIMIT: i64 = 2000;
 
-    fn migrate_legacy_db_file(data_dir: &Path) -> Result<(), std::io::Error> {
+    /// Renames `playlist.db` from older versions (and its WAL files) to `roqtune.db`.
+    pub(crate) fn migrate_legacy_db_file(data_dir: &Path) -> Result<(), std::io::Error> {
         let legacy_db_path = data_dir.join(Self::LEGACY_DB_FILE_NAME);
         let db_path = data_dir.join(Self::DB_FILE_NAME);
         if db_path.exists() || !legacy_db_path.exists() {
@@ -137,6 +173,84 @@ impl DbManager {
         }
     }
 
+    fn maintenance_task_to_str(task: MaintenanceTask) -> &'static str {
+        match task {
+            MaintenanceTask::RescanLibrary => "rescan_library",
+            MaintenanceTask::PruneOrphanedRows => "prune_orphaned_rows",
+            MaintenanceTask::RefreshRemoteSync => "refresh_remote_sync",
+            MaintenanceTask::ExpireCaches => "expire_caches",
+            MaintenanceTask::VerifyIntegrity => "verify_integrity",
+        }
+    }
+
+    fn maintenance_task_from_str(value: &str) -> Option<MaintenanceTask> {
+        match value {
+            "rescan_library" => Some(MaintenanceTask::RescanLibrary),
+            "prune_orphaned_rows" => Some(MaintenanceTask::PruneOrphanedRows),
+            "refresh_remote_sync" => Some(MaintenanceTask::RefreshRemoteSync),
+            "expire_caches" => Some(MaintenanceTask::ExpireCaches),
+            "verify_integrity" => Some(MaintenanceTask::VerifyIntegrity),
+            _ => None,
+        }
+    }
+
+    fn activity_category_to_str(category: ActivityCategory) -> &'static str {
+        match category {
+            ActivityCategory::Library => "library",
+            ActivityCategory::Playlist => "playlist",
+            ActivityCategory::Sync => "sync",
+        }
+    }
+
+    fn activity_category_from_str(value: &str) -> Option<ActivityCategory> {
+        match value {
+            "library" => Some(ActivityCategory::Library),
+            "playlist" => Some(ActivityCategory::Playlist),
+            "sync" => Some(ActivityCategory::Sync),
+            _ => None,
+        }
+    }
+
+    fn import_source_to_str(source: ImportSource) -> &'static str {
+        match source {
+            ImportSource::AddFilesDialog => "add_files_dialog",
+            ImportSource::AddFolderDialog => "add_folder_dialog",
+            ImportSource::DragAndDrop => "drag_and_drop",
+            ImportSource::Library => "library",
+        }
+    }
+
+    fn import_source_from_str(value: &str) -> Option<ImportSource> {
+        match value {
+            "add_files_dialog" => Some(ImportSource::AddFilesDialog),
+            "add_folder_dialog" => Some(ImportSource::AddFolderDialog),
+            "drag_and_drop" => Some(ImportSource::DragAndDrop),
+            "library" => Some(ImportSource::Library),
+            _ => None,
+        }
+    }
+
+    fn integrity_issue_kind_to_str(kind: IntegrityIssueKind) -> &'static str {
+        match kind {
+            IntegrityIssueKind::StreamChecksumMismatch => "stream_checksum_mismatch",
+            IntegrityIssueKind::DecodeError => "decode_error",
+            IntegrityIssueKind::ContentChanged => "content_changed",
+            IntegrityIssueKind::Unreadable => "unreadable",
+            IntegrityIssueKind::SuspectedLossySource => "suspected_lossy_source",
+        }
+    }
+
+    fn integrity_issue_kind_from_str(value: &str) -> Option<IntegrityIssueKind> {
+        match value {
+            "stream_checksum_mismatch" => Some(IntegrityIssueKind::StreamChecksumMismatch),
+            "decode_error" => Some(IntegrityIssueKind::DecodeError),
+            "content_changed" => Some(IntegrityIssueKind::ContentChanged),
+            "unreadable" => Some(IntegrityIssueKind::Unreadable),
+            "suspected_lossy_source" => Some(IntegrityIssueKind::SuspectedLossySource),
+            _ => None,
+        }
+    }
+
     fn enrichment_error_kind_to_str(kind: Option<LibraryEnrichmentErrorKind>) -> &'static str {
         match kind {
             Some(LibraryEnrichmentErrorKind::Timeout) => "timeout",
@@ -241,7 +355,20 @@ impl DbManager {
         Self::migrate_legacy_db_file(&data_dir)
             .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
 
-        let db_path = data_dir.join(Self::DB_FILE_NAME);
+        Self::open_at(&data_dir.join(Self::DB_FILE_NAME))
+    }
+
+    /// Path of the on-disk database inside the user data directory.
+    pub fn database_path() -> PathBuf {
+        dirs::data_dir()
+            .expect("Could not find data directory")
+            .join("roqtune")
+            .join(Self::DB_FILE_NAME)
+    }
+
+    /// Opens (creating when missing) the database at `db_path` and brings its schema up
+    /// to date.
+    pub(crate) fn open_at(db_path: &Path) -> Result<Self, rusqlite::Error> {
         let conn = Connection::open(db_path)?;
         Self::configure_connection_pragmas(&conn);
 
@@ -251,6 +378,96 @@ impl DbManager {
         Ok(db_manager)
     }
 
+    /// Opens a read-only connection to the on-disk database.
+    ///
+    /// The schema is not touched, so a writer must have opened the database through
+    /// `new()` first. Read-only connections never take the WAL write lock and can
+    /// run queries in parallel with scan and playlist writes.
+    pub fn new_read_only() -> Result<Self, rusqlite::Error> {
+        Self::open_read_only_at(&Self::database_path())
+    }
+
+    fn open_read_only_at(db_path: &Path) -> Result<Self, rusqlite::Error> {
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX
+                | OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        conn.busy_timeout(Self::READ_ONLY_BUSY_TIMEOUT)?;
+        let _ = conn.pragma_update(None, "temp_store", "MEMORY");
+        let _ = conn.pragma_update(None, "query_only", true);
+        Ok(Self { conn })
+    }
+
+    fn is_busy_error(error: &rusqlite::Error) -> bool {
+        matches!(
+            error,
+            rusqlite::Error::SqliteFailure(failure, _)
+                if matches!(failure.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    }
+
+    /// Runs `read` inside one deferred transaction so every statement it issues sees
+    /// the same WAL snapshot.
+    ///
+    /// Busy or locked failures that outlast the connection busy timeout are retried a
+    /// few times with a short backoff before the error is returned.
+    pub fn with_read_snapshot<T>(
+        &self,
+        mut read: impl FnMut(&Self) -> Result<T, rusqlite::Error>,
+    ) -> Result<T, rusqlite::Error> {
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .conn
+                .execute_batch("BEGIN DEFERRED")
+                .and_then(|_| read(self));
+            let _ = self
+                .conn
+                .execute_batch(if result.is_ok() { "COMMIT" } else { "ROLLBACK" });
+            match result {
+                Err(error)
+                    if Self::is_busy_error(&error)
+                        && attempt < Self::READ_SNAPSHOT_BUSY_RETRIES =>
+                {
+                    attempt += 1;
+                    thread::sleep(Self::READ_SNAPSHOT_RETRY_BACKOFF * attempt);
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Lists every table of an up-to-date database with its columns, so an older file
+    /// can be compared against it before `migrate` changes it.
+    pub(crate) fn current_schema_columns() -> Result<Vec<(String, Vec<String>)>, rusqlite::Error> {
+        let db_manager = Self {
+            conn: Connection::open_in_memory()?,
+        };
+        db_manager.initialize_schema()?;
+        db_manager.migrate()?;
+        let mut table_stmt = db_manager.conn.prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+             ORDER BY name",
+        )?;
+        let tables = table_stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut schema = Vec::with_capacity(tables.len());
+        for table in tables {
+            let mut column_stmt = db_manager
+                .conn
+                .prepare(&format!("PRAGMA table_info({table})"))?;
+            let columns = column_stmt
+                .query_map([], |row| row.get::<_, String>(1))?
+                .collect::<Result<Vec<_>, _>>()?;
+            schema.push((table, columns));
+        }
+        Ok(schema)
+    }
+
     #[cfg(test)]
     /// Creates an in-memory database instance for tests.
     pub fn new_in_memory() -> Result<Self, rusqlite::Error> {
@@ -268,7 +485,23 @@ impl DbManager {
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
                 column_order TEXT,
-                column_width_overrides TEXT
+                column_width_overrides TEXT,
+                note TEXT NOT NULL DEFAULT '',
+                skip_silence INTEGER NOT NULL DEFAULT 0,
+                normalize_loudness INTEGER NOT NULL DEFAULT 0,
+                parent_id TEXT,
+                column_filters TEXT,
+                description TEXT NOT NULL DEFAULT '',
+                cover_path TEXT
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS playlist_folders (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                parent_id TEXT
             )",
             [],
         )?;
@@ -403,6 +636,154 @@ impl DbManager {
             "CREATE INDEX IF NOT EXISTS idx_favorite_sync_queue_profile_updated ON favorite_sync_queue(remote_profile_id, updated_unix_ms)",
             [],
         )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS playlist_url_sources (
+                playlist_id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                refreshed_unix_ms INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_tags (
+                name TEXT NOT NULL PRIMARY KEY COLLATE NOCASE,
+                color TEXT NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_user_tags (
+                track_path TEXT NOT NULL,
+                tag_name TEXT NOT NULL COLLATE NOCASE,
+                PRIMARY KEY(track_path, tag_name)
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_track_user_tags_tag ON track_user_tags(tag_name)",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_notes (
+                track_path TEXT PRIMARY KEY,
+                note TEXT NOT NULL,
+                updated_unix_ms INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_transition_overrides (
+                track_path TEXT PRIMARY KEY,
+                trim_start_ms INTEGER NOT NULL DEFAULT 0,
+                trim_end_ms INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS shuffle_excluded_tracks (
+                track_path TEXT PRIMARY KEY
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_loudness (
+                track_path TEXT PRIMARY KEY,
+                integrated_lufs REAL NOT NULL,
+                true_peak_dbtp REAL NOT NULL,
+                analyzed_unix_ms INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_formats (
+                track_path TEXT PRIMARY KEY,
+                format TEXT NOT NULL,
+                bitrate_kbps INTEGER NOT NULL,
+                sample_rate_hz INTEGER NOT NULL,
+                channel_count INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                bits_per_sample INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_energy (
+                track_path TEXT PRIMARY KEY,
+                energy_level INTEGER NOT NULL,
+                suggested INTEGER NOT NULL DEFAULT 0,
+                updated_unix_ms INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_transition_history (
+                from_path TEXT NOT NULL,
+                to_path TEXT NOT NULL,
+                manual_count INTEGER NOT NULL DEFAULT 0,
+                played_count INTEGER NOT NULL DEFAULT 0,
+                skipped_count INTEGER NOT NULL DEFAULT 0,
+                updated_unix_ms INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (from_path, to_path)
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_play_stats (
+                track_path TEXT PRIMARY KEY,
+                play_count INTEGER NOT NULL DEFAULT 0,
+                skip_count INTEGER NOT NULL DEFAULT 0,
+                last_played_unix_ms INTEGER
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS lyrics_cache (
+                track_path TEXT PRIMARY KEY,
+                lyrics TEXT,
+                fetched_unix_ms INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS maintenance_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task TEXT NOT NULL,
+                started_unix_ms INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                success INTEGER NOT NULL,
+                summary TEXT NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS activity_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recorded_unix_ms INTEGER NOT NULL,
+                category TEXT NOT NULL,
+                message TEXT NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS track_sources (
+                track_id TEXT PRIMARY KEY,
+                source TEXT NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS file_integrity (
+                path TEXT PRIMARY KEY,
+                file_size_bytes INTEGER NOT NULL,
+                modified_unix_ms INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                verified_unix_ms INTEGER NOT NULL,
+                issue_kind TEXT,
+                issue_detail TEXT NOT NULL DEFAULT '',
+                spectrum_checked INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
         Ok(())
     }
 
@@ -441,10 +822,24 @@ impl DbManager {
         let playlist_columns = playlist_stmt.query_map([], |row| row.get::<_, String>(1))?;
         let mut has_column_order = false;
         let mut has_column_width_overrides = false;
+        let mut has_note = false;
+        let mut has_skip_silence = false;
+        let mut has_normalize_loudness = false;
+        let mut has_parent_id = false;
+        let mut has_column_filters = false;
+        let mut has_description = false;
+        let mut has_cover_path = false;
         for col in playlist_columns {
             match col?.as_str() {
                 "column_order" => has_column_order = true,
                 "column_width_overrides" => has_column_width_overrides = true,
+                "note" => has_note = true,
+                "skip_silence" => has_skip_silence = true,
+                "normalize_loudness" => has_normalize_loudness = true,
+                "parent_id" => has_parent_id = true,
+                "column_filters" => has_column_filters = true,
+                "description" => has_description = true,
+                "cover_path" => has_cover_path = true,
                 _ => {}
             }
         }
@@ -458,6 +853,59 @@ impl DbManager {
                 [],
             )?;
         }
+        if !has_note {
+            self.conn.execute(
+                "ALTER TABLE playlists ADD COLUMN note TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+        if !has_skip_silence {
+            self.conn.execute(
+                "ALTER TABLE playlists ADD COLUMN skip_silence INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        if !has_normalize_loudness {
+            self.conn.execute(
+                "ALTER TABLE playlists ADD COLUMN normalize_loudness INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        if !has_parent_id {
+            self.conn
+                .execute("ALTER TABLE playlists ADD COLUMN parent_id TEXT", [])?;
+        }
+        if !has_column_filters {
+            self.conn
+                .execute("ALTER TABLE playlists ADD COLUMN column_filters TEXT", [])?;
+        }
+        if !has_description {
+            self.conn.execute(
+                "ALTER TABLE playlists ADD COLUMN description TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+        if !has_cover_path {
+            self.conn
+                .execute("ALTER TABLE playlists ADD COLUMN cover_path TEXT", [])?;
+        }
+
+        let mut transition_stmt = self
+            .conn
+            .prepare("PRAGMA table_info(track_transition_overrides)")?;
+        let mut has_trim_start_ms = false;
+        for col in transition_stmt.query_map([], |row| row.get::<_, String>(1))? {
+            if col? == "trim_start_ms" {
+                has_trim_start_ms = true;
+            }
+        }
+        if !has_trim_start_ms {
+            self.conn.execute(
+                "ALTER TABLE track_transition_overrides
+                 ADD COLUMN trim_start_ms INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
 
         let mut library_stmt = self.conn.prepare("PRAGMA table_info(library_tracks)")?;
         let library_columns = library_stmt.query_map([], |row| row.get::<_, String>(1))?;
@@ -646,6 +1094,21 @@ impl DbManager {
             )?;
         }
 
+        let mut integrity_stmt = self.conn.prepare("PRAGMA table_info(file_integrity)")?;
+        let integrity_columns = integrity_stmt.query_map([], |row| row.get::<_, String>(1))?;
+        let mut has_spectrum_checked = false;
+        for col in integrity_columns {
+            if col? == "spectrum_checked" {
+                has_spectrum_checked = true;
+            }
+        }
+        if !has_spectrum_checked {
+            self.conn.execute(
+                "ALTER TABLE file_integrity ADD COLUMN spectrum_checked INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
         // Ensure at least one playlist exists
         let count: i64 = self
             .conn
@@ -679,13 +1142,93 @@ impl DbManager {
         Ok(())
     }
 
+    /// Replaces the personal note attached to a playlist.
+    pub fn set_playlist_note(&self, id: &str, note: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlists SET note = ?1 WHERE id = ?2",
+            params![note, id],
+        )?;
+        Ok(())
+    }
+
+    /// Stores the artwork and description of one playlist; `None` for `cover_path` uses
+    /// the generated collage.
+    pub fn set_playlist_details(
+        &self,
+        id: &str,
+        description: &str,
+        cover_path: Option<&Path>,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlists SET description = ?1, cover_path = ?2 WHERE id = ?3",
+            params![
+                description,
+                cover_path.map(|path| path.to_string_lossy().to_string()),
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Stores the per-column filters of one playlist; an empty list clears them.
+    pub fn set_playlist_column_filters(
+        &self,
+        id: &str,
+        filters: &[PlaylistColumnFilter],
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlists SET column_filters = ?1 WHERE id = ?2",
+            params![playlist_column_filters::serialize_filters(filters), id],
+        )?;
+        Ok(())
+    }
+
+    /// Turns skip-silence playback on or off for one playlist.
+    pub fn set_playlist_skip_silence(
+        &self,
+        id: &str,
+        enabled: bool,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlists SET skip_silence = ?1 WHERE id = ?2",
+            params![enabled, id],
+        )?;
+        Ok(())
+    }
+
+    /// Turns loudness normalization on or off for one playlist.
+    pub fn set_playlist_normalize_loudness(
+        &self,
+        id: &str,
+        enabled: bool,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlists SET normalize_loudness = ?1 WHERE id = ?2",
+            params![enabled, id],
+        )?;
+        Ok(())
+    }
+
     /// Returns all playlists currently stored in the database.
     pub fn get_all_playlists(&self) -> Result<Vec<PlaylistInfo>, rusqlite::Error> {
-        let mut stmt = self.conn.prepare("SELECT id, name FROM playlists")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, COALESCE(note, ''), skip_silence, normalize_loudness, parent_id,
+                    column_filters, COALESCE(description, ''), cover_path
+             FROM playlists",
+        )?;
         let playlist_iter = stmt.query_map([], |row| {
             Ok(PlaylistInfo {
                 id: row.get(0)?,
                 name: row.get(1)?,
+                note: row.get(2)?,
+                skip_silence: row.get(3)?,
+                normalize_loudness: row.get(4)?,
+                parent_id: row.get(5)?,
+                column_filters: playlist_column_filters::parse_filters(
+                    row.get::<_, Option<String>>(6)?.as_deref(),
+                ),
+                description: row.get(7)?,
+                cover_path: row.get::<_, Option<String>>(8)?.map(PathBuf::from),
             })
         })?;
 
@@ -696,29 +1239,132 @@ impl DbManager {
         Ok(playlists)
     }
 
-    /// Persists one track row in the given playlist at the provided position.
-    pub fn save_track(
+    /// Returns all sidebar playlist folders, sorted by name.
+    pub fn get_playlist_folders(&self) -> Result<Vec<PlaylistFolderInfo>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, parent_id FROM playlist_folders ORDER BY name COLLATE NOCASE, id",
+        )?;
+        let folders = stmt
+            .query_map([], |row| {
+                Ok(PlaylistFolderInfo {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    parent_id: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(folders)
+    }
+
+    /// Inserts a sidebar folder inside `parent_id`, or at the top level.
+    pub fn create_playlist_folder(
         &self,
         id: &str,
-        playlist_id: &str,
-        path: &str,
-        position: usize,
+        name: &str,
+        parent_id: Option<&str>,
     ) -> Result<(), rusqlite::Error> {
         self.conn.execute(
-            "INSERT INTO tracks (id, playlist_id, path, position) VALUES (?1, ?2, ?3, ?4)",
-            params![id, playlist_id, path, position as i64],
+            "INSERT INTO playlist_folders (id, name, parent_id) VALUES (?1, ?2, ?3)",
+            params![id, name, parent_id],
         )?;
         Ok(())
     }
 
-    /// Persists many track rows for one playlist in one transaction.
-    pub fn save_tracks_batch(
+    /// Renames a sidebar folder.
+    pub fn rename_playlist_folder(&self, id: &str, name: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlist_folders SET name = ?1 WHERE id = ?2",
+            params![name, id],
+        )?;
+        Ok(())
+    }
+
+    /// Moves a sidebar folder inside `parent_id`, or to the top level.
+    pub fn set_playlist_folder_parent(
+        &self,
+        id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE playlist_folders SET parent_id = ?1 WHERE id = ?2",
+            params![parent_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Moves a playlist into the folder `folder_id`, or to the top level.
+    pub fn set_playlist_parent(
         &self,
         playlist_id: &str,
-        tracks: &[(String, PathBuf)],
-        base_position: usize,
+        folder_id: Option<&str>,
     ) -> Result<(), rusqlite::Error> {
-        if tracks.is_empty() {
+        self.conn.execute(
+            "UPDATE playlists SET parent_id = ?1 WHERE id = ?2",
+            params![folder_id, playlist_id],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes a sidebar folder. Its playlists and subfolders move up to its parent, so
+    /// no playlist is lost.
+    pub fn delete_playlist_folder(&self, id: &str) -> Result<(), rusqlite::Error> {
+        let parent_id: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT parent_id FROM playlist_folders WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        let result = self
+            .conn
+            .execute(
+                "UPDATE playlist_folders SET parent_id = ?1 WHERE parent_id = ?2",
+                params![parent_id, id],
+            )
+            .and_then(|_| {
+                self.conn.execute(
+                    "UPDATE playlists SET parent_id = ?1 WHERE parent_id = ?2",
+                    params![parent_id, id],
+                )
+            })
+            .and_then(|_| {
+                self.conn
+                    .execute("DELETE FROM playlist_folders WHERE id = ?1", params![id])
+            });
+        if let Err(err) = result {
+            let _ = self.conn.execute("ROLLBACK", []);
+            return Err(err);
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Persists one track row in the given playlist at the provided position.
+    pub fn save_track(
+        &self,
+        id: &str,
+        playlist_id: &str,
+        path: &str,
+        position: usize,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO tracks (id, playlist_id, path, position) VALUES (?1, ?2, ?3, ?4)",
+            params![id, playlist_id, path, position as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Persists many track rows for one playlist in one transaction.
+    pub fn save_tracks_batch(
+        &self,
+        playlist_id: &str,
+        tracks: &[(String, PathBuf)],
+        base_position: usize,
+    ) -> Result<(), rusqlite::Error> {
+        if tracks.is_empty() {
             return Ok(());
         }
         self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
@@ -749,32 +1395,187 @@ impl DbManager {
         Ok(())
     }
 
+    /// Stores playlist-provided display titles for tracks that have no readable tags.
+    pub fn save_track_display_titles(
+        &self,
+        titles: &[(String, String, String)],
+    ) -> Result<(), rusqlite::Error> {
+        if titles.is_empty() {
+            return Ok(());
+        }
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        let mut stmt = match self
+            .conn
+            .prepare("UPDATE tracks SET title = ?2, artist = ?3 WHERE id = ?1")
+        {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        };
+        for (id, title, artist) in titles {
+            if let Err(err) = stmt.execute(params![id, title, artist]) {
+                drop(stmt);
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        drop(stmt);
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Returns `(path, title, artist)` display titles stored for URL-imported playlists.
+    pub fn get_url_playlist_track_titles(
+        &self,
+    ) -> Result<Vec<(PathBuf, String, String)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.path, t.title, COALESCE(t.artist, '')
+             FROM tracks t
+             JOIN playlist_url_sources s ON s.playlist_id = t.playlist_id
+             WHERE t.title IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Binds a playlist to a remote playlist URL, or updates its last refresh time.
+    pub fn upsert_playlist_url_source(
+        &self,
+        playlist_id: &str,
+        url: &str,
+        refreshed_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO playlist_url_sources (playlist_id, url, refreshed_unix_ms)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(playlist_id) DO UPDATE SET
+                url = excluded.url,
+                refreshed_unix_ms = excluded.refreshed_unix_ms",
+            params![playlist_id, url, refreshed_unix_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Returns all playlists bound to a remote playlist URL.
+    pub fn get_playlist_url_sources(&self) -> Result<Vec<PlaylistUrlSource>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT playlist_id, url, refreshed_unix_ms FROM playlist_url_sources
+             ORDER BY playlist_id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(PlaylistUrlSource {
+                playlist_id: row.get(0)?,
+                url: row.get(1)?,
+                refreshed_unix_ms: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
     /// Deletes one track by id.
     pub fn delete_track(&self, id: &str) -> Result<(), rusqlite::Error> {
         self.conn
             .execute("DELETE FROM tracks WHERE id = ?1", params![id])?;
+        self.conn
+            .execute("DELETE FROM track_sources WHERE track_id = ?1", params![id])?;
         Ok(())
     }
 
     /// Deletes a playlist and all tracks that belong to it.
     pub fn delete_playlist(&self, id: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "DELETE FROM track_sources
+             WHERE track_id IN (SELECT id FROM tracks WHERE playlist_id = ?1)",
+            params![id],
+        )?;
         // Delete tracks first due to foreign key (even if not enforced, it's good practice)
         self.conn
             .execute("DELETE FROM tracks WHERE playlist_id = ?1", params![id])?;
+        self.conn.execute(
+            "DELETE FROM playlist_url_sources WHERE playlist_id = ?1",
+            params![id],
+        )?;
         self.conn
             .execute("DELETE FROM playlists WHERE id = ?1", params![id])?;
         Ok(())
     }
 
+    /// Records how playlist entries were added, keyed by track id.
+    pub fn save_track_sources(
+        &self,
+        sources: &[(String, ImportSource)],
+    ) -> Result<(), rusqlite::Error> {
+        if sources.is_empty() {
+            return Ok(());
+        }
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for (track_id, source) in sources {
+            if let Err(err) = self.conn.execute(
+                "INSERT OR REPLACE INTO track_sources (track_id, source) VALUES (?1, ?2)",
+                params![track_id, Self::import_source_to_str(*source)],
+            ) {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads the recorded sources of one playlist's entries.
+    pub fn get_track_sources_for_playlist(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Vec<(String, ImportSource)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_sources.track_id, track_sources.source
+             FROM track_sources
+             JOIN tracks ON tracks.id = track_sources.track_id
+             WHERE tracks.playlist_id = ?1",
+        )?;
+        let rows = stmt.query_map(params![playlist_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut sources = Vec::new();
+        for row in rows {
+            let (track_id, source) = row?;
+            if let Some(source) = Self::import_source_from_str(&source) {
+                sources.push((track_id, source));
+            }
+        }
+        Ok(sources)
+    }
+
     /// Loads tracks for one playlist ordered by stored position.
     pub fn get_tracks_for_playlist(
         &self,
         playlist_id: &str,
     ) -> Result<Vec<RestoredTrack>, rusqlite::Error> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, path FROM tracks WHERE playlist_id = ?1 ORDER BY position ASC")?;
-        let track_iter = stmt.query_map(params![playlist_id], |row| {
+        self.get_tracks_for_playlist_range(playlist_id, 0, usize::MAX)
+    }
+
+    /// Loads at most `limit` tracks of one playlist, starting `offset` rows into its stored order.
+    pub fn get_tracks_for_playlist_range(
+        &self,
+        playlist_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<RestoredTrack>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path FROM tracks WHERE playlist_id = ?1 ORDER BY position ASC
+             LIMIT ?2 OFFSET ?3",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let track_iter = stmt.query_map(params![playlist_id, limit, offset], |row| {
             Ok(RestoredTrack {
                 id: row.get(0)?,
                 path: PathBuf::from(row.get::<_, String>(1)?),
@@ -1161,62 +1962,978 @@ impl DbManager {
             return Ok(0);
         }
 
-        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
-        let mut playlist_stmt = match self.conn.prepare("DELETE FROM tracks WHERE path = ?1") {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                let _ = self.conn.execute("ROLLBACK", []);
-                return Err(err);
-            }
-        };
-        let mut library_stmt = match self
-            .conn
-            .prepare("DELETE FROM library_tracks WHERE path = ?1")
-        {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                drop(playlist_stmt);
-                let _ = self.conn.execute("ROLLBACK", []);
-                return Err(err);
-            }
-        };
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        let mut playlist_stmt = match self.conn.prepare("DELETE FROM tracks WHERE path = ?1") {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        };
+        let mut library_stmt = match self
+            .conn
+            .prepare("DELETE FROM library_tracks WHERE path = ?1")
+        {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                drop(playlist_stmt);
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        };
+
+        let mut removed_unique_paths = 0usize;
+        let mut seen_paths = HashSet::new();
+        for path in paths {
+            let key = path.to_string_lossy().to_string();
+            if !seen_paths.insert(key.clone()) {
+                continue;
+            }
+
+            let removed_from_playlists = match playlist_stmt.execute(params![key.clone()]) {
+                Ok(changed) => changed,
+                Err(err) => {
+                    drop(library_stmt);
+                    drop(playlist_stmt);
+                    let _ = self.conn.execute("ROLLBACK", []);
+                    return Err(err);
+                }
+            };
+            let removed_from_library = match library_stmt.execute(params![key]) {
+                Ok(changed) => changed,
+                Err(err) => {
+                    drop(library_stmt);
+                    drop(playlist_stmt);
+                    let _ = self.conn.execute("ROLLBACK", []);
+                    return Err(err);
+                }
+            };
+
+            if removed_from_playlists > 0 || removed_from_library > 0 {
+                removed_unique_paths = removed_unique_paths.saturating_add(1);
+            }
+        }
+
+        drop(library_stmt);
+        drop(playlist_stmt);
+        self.conn.execute("COMMIT", [])?;
+        Ok(removed_unique_paths)
+    }
+
+    fn rewrite_one_track_path(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        new_track_id: &str,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE OR REPLACE library_tracks SET path = ?2, track_id = ?3 WHERE path = ?1",
+            params![old_path, new_path, new_track_id],
+        )?;
+        self.conn.execute(
+            "UPDATE tracks SET path = ?2 WHERE path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE favorites
+             SET track_path = ?2,
+                 entity_key = CASE WHEN entity_key = 'file:' || ?1
+                     THEN 'file:' || ?2 ELSE entity_key END
+             WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_user_tags SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_notes SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_transition_overrides SET track_path = ?2
+             WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE shuffle_excluded_tracks SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_loudness SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_formats SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_energy SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_play_stats SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE lyrics_cache SET track_path = ?2 WHERE track_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_transition_history SET from_path = ?2 WHERE from_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE track_transition_history SET to_path = ?2 WHERE to_path = ?1",
+            params![old_path, new_path],
+        )?;
+        self.conn.execute(
+            "UPDATE OR REPLACE file_integrity SET path = ?2 WHERE path = ?1",
+            params![old_path, new_path],
+        )?;
+        Ok(())
+    }
+
+    /// Rewrites `(old_path, new_path, new_library_track_id)` across library, playlist,
+    /// favorite, tag, note, transition-override, shuffle-exclusion, and integrity rows in
+    /// one transaction, after files were moved on disk.
+    pub fn rewrite_track_paths(
+        &self,
+        rewrites: &[(PathBuf, PathBuf, String)],
+    ) -> Result<(), rusqlite::Error> {
+        if rewrites.is_empty() {
+            return Ok(());
+        }
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for (old_path, new_path, new_track_id) in rewrites {
+            if let Err(err) = self.rewrite_one_track_path(
+                &old_path.to_string_lossy(),
+                &new_path.to_string_lossy(),
+                new_track_id,
+            ) {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Creates a user tag, or updates the color of an existing one (names match case-insensitively).
+    pub fn upsert_user_tag(&self, name: &str, color: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO user_tags (name, color) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET color = excluded.color",
+            params![name, color],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes a user tag and all of its track assignments.
+    pub fn delete_user_tag(&self, name: &str) -> Result<usize, rusqlite::Error> {
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        let result = self
+            .conn
+            .execute(
+                "DELETE FROM track_user_tags WHERE tag_name = ?1",
+                params![name],
+            )
+            .and_then(|_| {
+                self.conn
+                    .execute("DELETE FROM user_tags WHERE name = ?1", params![name])
+            });
+        match result {
+            Ok(deleted) => {
+                self.conn.execute("COMMIT", [])?;
+                Ok(deleted)
+            }
+            Err(err) => {
+                let _ = self.conn.execute("ROLLBACK", []);
+                Err(err)
+            }
+        }
+    }
+
+    /// Assigns or clears one tag on every path in one transaction.
+    pub fn set_track_user_tag(
+        &self,
+        paths: &[PathBuf],
+        tag_name: &str,
+        assigned: bool,
+    ) -> Result<(), rusqlite::Error> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let sql = if assigned {
+            "INSERT OR IGNORE INTO track_user_tags (track_path, tag_name) VALUES (?1, ?2)"
+        } else {
+            "DELETE FROM track_user_tags WHERE track_path = ?1 AND tag_name = ?2"
+        };
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for path in paths {
+            if let Err(err) = self
+                .conn
+                .execute(sql, params![path.to_string_lossy(), tag_name])
+            {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads all user tags sorted by name.
+    pub fn get_user_tags(&self) -> Result<Vec<UserTag>, rusqlite::Error> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, color FROM user_tags ORDER BY name COLLATE NOCASE ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(UserTag {
+                name: row.get(0)?,
+                color: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Loads `(track_path, tag_name)` pairs using each tag's stored spelling.
+    pub fn get_track_user_tag_assignments(
+        &self,
+    ) -> Result<Vec<(PathBuf, String)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.track_path, t.name
+             FROM track_user_tags a
+             JOIN user_tags t ON t.name = a.tag_name
+             ORDER BY a.track_path ASC, t.name COLLATE NOCASE ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((PathBuf::from(row.get::<_, String>(0)?), row.get(1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// Stores the personal note for one track path; a blank note deletes the row.
+    pub fn set_track_note(
+        &self,
+        path: &Path,
+        note: &str,
+        updated_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        let path = path.to_string_lossy();
+        if note.trim().is_empty() {
+            self.conn.execute(
+                "DELETE FROM track_notes WHERE track_path = ?1",
+                params![path],
+            )?;
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO track_notes (track_path, note, updated_unix_ms) VALUES (?1, ?2, ?3)
+             ON CONFLICT(track_path) DO UPDATE SET
+                note = excluded.note,
+                updated_unix_ms = excluded.updated_unix_ms",
+            params![path, note, updated_unix_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Loads every stored track note as `(track_path, note)`.
+    pub fn get_track_notes(&self) -> Result<Vec<(PathBuf, String)>, rusqlite::Error> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT track_path, note FROM track_notes ORDER BY track_path ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((PathBuf::from(row.get::<_, String>(0)?), row.get(1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// Sets how much to trim from the start and end of each track; empty trims clear the
+    /// override.
+    pub fn set_track_trims(
+        &self,
+        paths: &[PathBuf],
+        trims: TrackTrims,
+    ) -> Result<(), rusqlite::Error> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let trim_start_ms = trims.start_ms.min(i64::MAX as u64) as i64;
+        let trim_end_ms = trims.end_ms.min(i64::MAX as u64) as i64;
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for path in paths {
+            let path = path.to_string_lossy();
+            let result = if trims.is_empty() {
+                self.conn.execute(
+                    "DELETE FROM track_transition_overrides WHERE track_path = ?1",
+                    params![path],
+                )
+            } else {
+                self.conn.execute(
+                    "INSERT INTO track_transition_overrides (track_path, trim_start_ms, trim_end_ms)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(track_path) DO UPDATE SET
+                        trim_start_ms = excluded.trim_start_ms,
+                        trim_end_ms = excluded.trim_end_ms",
+                    params![path, trim_start_ms, trim_end_ms],
+                )
+            };
+            if let Err(err) = result {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads every per-track trim override as `(track_path, trims)`.
+    pub fn get_track_trim_overrides(&self) -> Result<Vec<(PathBuf, TrackTrims)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_path, trim_start_ms, trim_end_ms FROM track_transition_overrides
+             WHERE trim_start_ms > 0 OR trim_end_ms > 0
+             ORDER BY track_path ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                TrackTrims {
+                    start_ms: row.get::<_, i64>(1)?.max(0) as u64,
+                    end_ms: row.get::<_, i64>(2)?.max(0) as u64,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Stores the loudness measured for `path`, replacing an earlier measurement.
+    pub fn save_track_loudness(
+        &self,
+        path: &Path,
+        loudness: TrackLoudness,
+        analyzed_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO track_loudness
+                (track_path, integrated_lufs, true_peak_dbtp, analyzed_unix_ms)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(track_path) DO UPDATE SET
+                integrated_lufs = excluded.integrated_lufs,
+                true_peak_dbtp = excluded.true_peak_dbtp,
+                analyzed_unix_ms = excluded.analyzed_unix_ms",
+            params![
+                path.to_string_lossy(),
+                f64::from(loudness.integrated_lufs),
+                f64::from(loudness.true_peak_dbtp),
+                analyzed_unix_ms
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Loads every stored loudness measurement as `(track_path, loudness)`.
+    pub fn get_track_loudness(&self) -> Result<Vec<(PathBuf, TrackLoudness)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_path, integrated_lufs, true_peak_dbtp FROM track_loudness
+             ORDER BY track_path ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                TrackLoudness {
+                    integrated_lufs: row.get::<_, f64>(1)? as f32,
+                    true_peak_dbtp: row.get::<_, f64>(2)? as f32,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Stores probed file formats, replacing earlier probes of the same files.
+    pub fn save_track_formats(
+        &self,
+        formats: &[(PathBuf, TechnicalMetadata)],
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for (path, format) in formats {
+            let result = self.conn.execute(
+                "INSERT OR REPLACE INTO track_formats
+                    (track_path, format, bitrate_kbps, sample_rate_hz, channel_count,
+                     duration_ms, bits_per_sample)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    path.to_string_lossy(),
+                    format.format,
+                    format.bitrate_kbps,
+                    format.sample_rate_hz,
+                    format.channel_count,
+                    format.duration_ms as i64,
+                    format.bits_per_sample
+                ],
+            );
+            if let Err(err) = result {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads stored formats of those `paths` that were probed before.
+    pub fn get_track_formats(
+        &self,
+        paths: &[PathBuf],
+    ) -> Result<Vec<(PathBuf, TechnicalMetadata)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT format, bitrate_kbps, sample_rate_hz, channel_count, duration_ms,
+                    bits_per_sample
+             FROM track_formats WHERE track_path = ?1",
+        )?;
+        let mut formats = Vec::new();
+        for path in paths {
+            let format = stmt
+                .query_row(params![path.to_string_lossy()], |row| {
+                    Ok(TechnicalMetadata {
+                        format: row.get(0)?,
+                        bitrate_kbps: row.get(1)?,
+                        sample_rate_hz: row.get(2)?,
+                        channel_count: row.get(3)?,
+                        duration_ms: row.get::<_, i64>(4)?.max(0) as u64,
+                        bits_per_sample: row.get(5)?,
+                    })
+                })
+                .optional()?;
+            if let Some(format) = format {
+                formats.push((path.clone(), format));
+            }
+        }
+        Ok(formats)
+    }
+
+    /// Sets a manual energy level for `paths`, or clears it when `energy` is `None`.
+    pub fn set_track_energy(
+        &self,
+        paths: &[PathBuf],
+        energy: Option<TrackEnergy>,
+        updated_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for path in paths {
+            let path = path.to_string_lossy();
+            let result = match energy {
+                Some(energy) => self.conn.execute(
+                    "INSERT INTO track_energy (track_path, energy_level, suggested, updated_unix_ms)
+                     VALUES (?1, ?2, 0, ?3)
+                     ON CONFLICT(track_path) DO UPDATE SET
+                        energy_level = excluded.energy_level,
+                        suggested = 0,
+                        updated_unix_ms = excluded.updated_unix_ms",
+                    params![path, energy.level(), updated_unix_ms],
+                ),
+                None => self.conn.execute(
+                    "DELETE FROM track_energy WHERE track_path = ?1",
+                    params![path],
+                ),
+            };
+            if let Err(err) = result {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Stores suggested energy levels. Levels set by hand are never overwritten.
+    pub fn save_suggested_track_energy(
+        &self,
+        suggestions: &[(PathBuf, TrackEnergy)],
+        updated_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for (path, energy) in suggestions {
+            if let Err(err) = self.conn.execute(
+                "INSERT INTO track_energy (track_path, energy_level, suggested, updated_unix_ms)
+                 VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(track_path) DO UPDATE SET
+                    energy_level = excluded.energy_level,
+                    updated_unix_ms = excluded.updated_unix_ms
+                 WHERE track_energy.suggested = 1",
+                params![path.to_string_lossy(), energy.level(), updated_unix_ms],
+            ) {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads every stored energy level as `(track_path, setting)`.
+    pub fn get_track_energy(&self) -> Result<Vec<(PathBuf, TrackEnergySetting)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_path, energy_level, suggested FROM track_energy
+             ORDER BY track_path ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)? != 0,
+            ))
+        })?;
+        let mut settings = Vec::new();
+        for row in rows {
+            let (path, level, suggested) = row?;
+            // Rows written by a newer build with more levels are skipped.
+            if let Some(energy) = u8::try_from(level).ok().and_then(TrackEnergy::from_level) {
+                settings.push((path, TrackEnergySetting { energy, suggested }));
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Counts one outcome for the `from_path -> to_path` transition.
+    pub fn record_track_transition(
+        &self,
+        from_path: &Path,
+        to_path: &Path,
+        outcome: TransitionOutcome,
+        updated_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        let column = match outcome {
+            TransitionOutcome::Manual => "manual_count",
+            TransitionOutcome::PlayedThrough => "played_count",
+            TransitionOutcome::Skipped => "skipped_count",
+        };
+        self.conn.execute(
+            &format!(
+                "INSERT INTO track_transition_history (from_path, to_path, {column}, updated_unix_ms)
+                 VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(from_path, to_path) DO UPDATE SET
+                    {column} = {column} + 1,
+                    updated_unix_ms = excluded.updated_unix_ms"
+            ),
+            params![
+                from_path.to_string_lossy(),
+                to_path.to_string_lossy(),
+                updated_unix_ms
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Loads every learned transition as `(from_path, to_path, counts)`.
+    pub fn get_track_transition_history(
+        &self,
+    ) -> Result<Vec<(PathBuf, PathBuf, TransitionCounts)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT from_path, to_path, manual_count, played_count, skipped_count
+             FROM track_transition_history",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                PathBuf::from(row.get::<_, String>(1)?),
+                TransitionCounts {
+                    manual: row.get(2)?,
+                    played_through: row.get(3)?,
+                    skipped: row.get(4)?,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Counts one play, or one skip when `played` is false, and returns the updated stats.
+    /// A play also becomes the track's last-played time.
+    pub fn record_track_play(
+        &self,
+        path: &Path,
+        played: bool,
+        now_unix_ms: i64,
+    ) -> Result<TrackPlayStats, rusqlite::Error> {
+        let (play_increment, skip_increment) = if played { (1, 0) } else { (0, 1) };
+        let last_played_unix_ms = played.then_some(now_unix_ms);
+        self.conn.query_row(
+            "INSERT INTO track_play_stats (track_path, play_count, skip_count, last_played_unix_ms)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(track_path) DO UPDATE SET
+                play_count = play_count + excluded.play_count,
+                skip_count = skip_count + excluded.skip_count,
+                last_played_unix_ms = COALESCE(excluded.last_played_unix_ms, last_played_unix_ms)
+             RETURNING play_count, skip_count, last_played_unix_ms",
+            params![
+                path.to_string_lossy(),
+                play_increment,
+                skip_increment,
+                last_played_unix_ms
+            ],
+            |row| {
+                Ok(TrackPlayStats {
+                    play_count: row.get(0)?,
+                    skip_count: row.get(1)?,
+                    last_played_unix_ms: row.get(2)?,
+                })
+            },
+        )
+    }
+
+    /// Loads every track's play statistics.
+    pub fn get_track_play_stats(&self) -> Result<Vec<(PathBuf, TrackPlayStats)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_path, play_count, skip_count, last_played_unix_ms
+             FROM track_play_stats ORDER BY track_path ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                TrackPlayStats {
+                    play_count: row.get(1)?,
+                    skip_count: row.get(2)?,
+                    last_played_unix_ms: row.get(3)?,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Stores the result of an online lyrics lookup; `None` records that none were found.
+    pub fn save_cached_lyrics(
+        &self,
+        path: &Path,
+        lyrics: Option<&str>,
+        fetched_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO lyrics_cache (track_path, lyrics, fetched_unix_ms)
+             VALUES (?1, ?2, ?3)",
+            params![path.to_string_lossy(), lyrics, fetched_unix_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the cached online lyrics lookup of `path` and when it ran. The lyrics are
+    /// `None` when that lookup found nothing.
+    pub fn get_cached_lyrics(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(Option<String>, i64)>, rusqlite::Error> {
+        self.conn
+            .query_row(
+                "SELECT lyrics, fetched_unix_ms FROM lyrics_cache WHERE track_path = ?1",
+                params![path.to_string_lossy()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+    }
+
+    /// Deletes every learned transition and returns the number of deleted pairs.
+    pub fn clear_track_transition_history(&self) -> Result<usize, rusqlite::Error> {
+        self.conn
+            .execute("DELETE FROM track_transition_history", [])
+    }
+
+    /// Adds or removes tracks from the set left out of shuffle order.
+    pub fn set_tracks_excluded_from_shuffle(
+        &self,
+        paths: &[PathBuf],
+        excluded: bool,
+    ) -> Result<(), rusqlite::Error> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let sql = if excluded {
+            "INSERT OR IGNORE INTO shuffle_excluded_tracks (track_path) VALUES (?1)"
+        } else {
+            "DELETE FROM shuffle_excluded_tracks WHERE track_path = ?1"
+        };
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        for path in paths {
+            if let Err(err) = self.conn.execute(sql, params![path.to_string_lossy()]) {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads every track path excluded from shuffle order.
+    pub fn get_shuffle_excluded_paths(&self) -> Result<Vec<PathBuf>, rusqlite::Error> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT track_path FROM shuffle_excluded_tracks ORDER BY track_path ASC")?;
+        let rows = stmt.query_map([], |row| Ok(PathBuf::from(row.get::<_, String>(0)?)))?;
+        rows.collect()
+    }
+
+    /// Deletes tag, note, transition-override, shuffle-exclusion, loudness, energy,
+    /// play-statistics, cached-lyrics, and learned-transition rows whose local path is
+    /// neither a library track nor a playlist track. Returns deleted row count.
+    pub fn prune_orphaned_track_rows(&self) -> Result<usize, rusqlite::Error> {
+        let mut deleted = 0usize;
+        for table in [
+            "track_user_tags",
+            "track_notes",
+            "track_transition_overrides",
+            "shuffle_excluded_tracks",
+            "track_loudness",
+            "track_formats",
+            "track_energy",
+            "track_play_stats",
+            "lyrics_cache",
+        ] {
+            deleted += self.conn.execute(
+                &format!(
+                    "DELETE FROM {table}
+                     WHERE instr(track_path, '://') = 0
+                       AND track_path NOT IN (SELECT path FROM library_tracks)
+                       AND track_path NOT IN (SELECT path FROM tracks)"
+                ),
+                [],
+            )?;
+        }
+        for column in ["from_path", "to_path"] {
+            deleted += self.conn.execute(
+                &format!(
+                    "DELETE FROM track_transition_history
+                     WHERE instr({column}, '://') = 0
+                       AND {column} NOT IN (SELECT path FROM library_tracks)
+                       AND {column} NOT IN (SELECT path FROM tracks)"
+                ),
+                [],
+            )?;
+        }
+        deleted += self.conn.execute(
+            "DELETE FROM file_integrity WHERE path NOT IN (SELECT path FROM library_tracks)",
+            [],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Loads stored integrity records keyed by file path.
+    pub fn get_file_integrity_records(
+        &self,
+    ) -> Result<HashMap<String, FileIntegrityRecord>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, file_size_bytes, modified_unix_ms, content_hash, verified_unix_ms,
+                    issue_kind, issue_detail, spectrum_checked
+             FROM file_integrity",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(FileIntegrityRecord {
+                path: row.get(0)?,
+                file_size_bytes: row.get(1)?,
+                modified_unix_ms: row.get(2)?,
+                content_hash: row.get(3)?,
+                verified_unix_ms: row.get(4)?,
+                issue_kind: row
+                    .get::<_, Option<String>>(5)?
+                    .as_deref()
+                    .and_then(Self::integrity_issue_kind_from_str),
+                issue_detail: row.get(6)?,
+                spectrum_checked: row.get(7)?,
+            })
+        })?;
+        let mut records = HashMap::new();
+        for row in rows {
+            let record = row?;
+            records.insert(record.path.clone(), record);
+        }
+        Ok(records)
+    }
+
+    /// Inserts or replaces integrity records in one transaction.
+    pub fn upsert_file_integrity_records(
+        &self,
+        records: &[FileIntegrityRecord],
+    ) -> Result<(), rusqlite::Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        self.conn.execute("BEGIN IMMEDIATE TRANSACTION", [])?;
+        let mut stmt = match self.conn.prepare(
+            "INSERT OR REPLACE INTO file_integrity (
+                path, file_size_bytes, modified_unix_ms, content_hash, verified_unix_ms,
+                issue_kind, issue_detail, spectrum_checked
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        ) {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        };
+        for record in records {
+            if let Err(err) = stmt.execute(params![
+                record.path,
+                record.file_size_bytes,
+                record.modified_unix_ms,
+                record.content_hash,
+                record.verified_unix_ms,
+                record.issue_kind.map(Self::integrity_issue_kind_to_str),
+                record.issue_detail,
+                record.spectrum_checked,
+            ]) {
+                drop(stmt);
+                let _ = self.conn.execute("ROLLBACK", []);
+                return Err(err);
+            }
+        }
+        drop(stmt);
+        self.conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Loads files whose latest integrity check found a problem, sorted by path.
+    pub fn get_integrity_issues(&self) -> Result<Vec<IntegrityIssue>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, issue_kind, issue_detail, verified_unix_ms
+             FROM file_integrity
+             WHERE issue_kind IS NOT NULL
+             ORDER BY path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+        let mut issues = Vec::new();
+        for row in rows {
+            let (path, kind, detail, verified_unix_ms) = row?;
+            let Some(kind) = Self::integrity_issue_kind_from_str(&kind) else {
+                continue;
+            };
+            issues.push(IntegrityIssue {
+                path: PathBuf::from(path),
+                kind,
+                detail,
+                verified_unix_ms,
+            });
+        }
+        Ok(issues)
+    }
+
+    /// Appends one maintenance run and trims history to the newest runs.
+    pub fn record_maintenance_run(
+        &self,
+        record: &MaintenanceRunRecord,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO maintenance_runs (task, started_unix_ms, duration_ms, success, summary)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                Self::maintenance_task_to_str(record.task),
+                record.started_unix_ms,
+                i64::try_from(record.duration_ms).unwrap_or(i64::MAX),
+                i64::from(record.success),
+                record.summary,
+            ],
+        )?;
+        self.conn.execute(
+            "DELETE FROM maintenance_runs
+             WHERE id NOT IN (SELECT id FROM maintenance_runs ORDER BY id DESC LIMIT ?1)",
+            params![Self::MAINTENANCE_HISTORY_LIMIT],
+        )?;
+        Ok(())
+    }
+
+    /// Loads up to `limit` maintenance runs, newest first.
+    pub fn get_maintenance_runs(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<MaintenanceRunRecord>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task, started_unix_ms, duration_ms, success, summary
+             FROM maintenance_runs
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![i64::try_from(limit).unwrap_or(i64::MAX)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        let mut runs = Vec::new();
+        for row in rows {
+            let (task, started_unix_ms, duration_ms, success, summary) = row?;
+            let Some(task) = Self::maintenance_task_from_str(&task) else {
+                continue;
+            };
+            runs.push(MaintenanceRunRecord {
+                task,
+                started_unix_ms,
+                duration_ms: u64::try_from(duration_ms).unwrap_or(0),
+                success: success != 0,
+                summary,
+            });
+        }
+        Ok(runs)
+    }
+
+    /// Appends one activity log entry, then drops entries recorded before
+    /// `expire_before_unix_ms` and trims the log to the newest entries.
+    pub fn record_activity(
+        &self,
+        entry: &ActivityLogEntry,
+        expire_before_unix_ms: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO activity_log (recorded_unix_ms, category, message)
+             VALUES (?1, ?2, ?3)",
+            params![
+                entry.recorded_unix_ms,
+                Self::activity_category_to_str(entry.category),
+                entry.message,
+            ],
+        )?;
+        self.conn.execute(
+            "DELETE FROM activity_log
+             WHERE recorded_unix_ms < ?1
+                OR id NOT IN (SELECT id FROM activity_log ORDER BY id DESC LIMIT ?2)",
+            params![expire_before_unix_ms, Self::ACTIVITY_LOG_LIMIT],
+        )?;
+        Ok(())
+    }
 
-        let mut removed_unique_paths = 0usize;
-        let mut seen_paths = HashSet::new();
-        for path in paths {
-            let key = path.to_string_lossy().to_string();
-            if !seen_paths.insert(key.clone()) {
+    /// Loads up to `limit` activity log entries, newest first.
+    pub fn get_activity_log(&self, limit: usize) -> Result<Vec<ActivityLogEntry>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT recorded_unix_ms, category, message
+             FROM activity_log
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![i64::try_from(limit).unwrap_or(i64::MAX)], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (recorded_unix_ms, category, message) = row?;
+            let Some(category) = Self::activity_category_from_str(&category) else {
                 continue;
-            }
-
-            let removed_from_playlists = match playlist_stmt.execute(params![key.clone()]) {
-                Ok(changed) => changed,
-                Err(err) => {
-                    drop(library_stmt);
-                    drop(playlist_stmt);
-                    let _ = self.conn.execute("ROLLBACK", []);
-                    return Err(err);
-                }
-            };
-            let removed_from_library = match library_stmt.execute(params![key]) {
-                Ok(changed) => changed,
-                Err(err) => {
-                    drop(library_stmt);
-                    drop(playlist_stmt);
-                    let _ = self.conn.execute("ROLLBACK", []);
-                    return Err(err);
-                }
             };
-
-            if removed_from_playlists > 0 || removed_from_library > 0 {
-                removed_unique_paths = removed_unique_paths.saturating_add(1);
-            }
+            entries.push(ActivityLogEntry {
+                recorded_unix_ms,
+                category,
+                message,
+            });
         }
-
-        drop(library_stmt);
-        drop(playlist_stmt);
-        self.conn.execute("COMMIT", [])?;
-        Ok(removed_unique_paths)
+        Ok(entries)
     }
 
     /// Loads all tracks in library sorted alphabetically by title.
@@ -1237,6 +2954,7 @@ impl DbManager {
                 genre: row.get(6)?,
                 year: row.get(7)?,
                 track_number: row.get(8)?,
+                duration_ms: None,
             })
         })?;
         let mut tracks = Vec::new();
@@ -1322,7 +3040,8 @@ impl DbManager {
     #[allow(dead_code)]
     pub fn get_library_albums(&self) -> Result<Vec<LibraryAlbum>, rusqlite::Error> {
         let mut stmt = self.conn.prepare(
-            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path
+            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path,
+                    COALESCE(MAX(NULLIF(year, '')), '') AS year
              FROM library_tracks
              GROUP BY album, album_artist
              ORDER BY sort_album ASC, album ASC, album_artist ASC",
@@ -1333,6 +3052,7 @@ impl DbManager {
                 album_artist: row.get(1)?,
                 track_count: row.get::<_, i64>(2)?.max(0) as u32,
                 representative_track_path: row.get::<_, Option<String>>(3)?.map(PathBuf::from),
+                year: row.get(4)?,
             })
         })?;
         let mut albums = Vec::new();
@@ -1343,7 +3063,6 @@ impl DbManager {
     }
 
     /// Loads one albums page and total row count.
-    #[allow(dead_code)]
     pub fn get_library_albums_page(
         &self,
         offset: usize,
@@ -1351,10 +3070,11 @@ impl DbManager {
     ) -> Result<(Vec<LibraryAlbum>, usize), rusqlite::Error> {
         let total = self.get_library_albums_count()?;
         let mut stmt = self.conn.prepare(
-            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path
+            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path,
+                    COALESCE(MAX(NULLIF(year, '')), '') AS year
              FROM library_tracks
              GROUP BY album, album_artist
-             ORDER BY sort_album ASC, album ASC, album_artist ASC
+             ORDER BY sort_album ASC, LOWER(album_artist) ASC, album ASC, album_artist ASC
              LIMIT ?1 OFFSET ?2",
         )?;
         let iter = stmt.query_map(params![limit as i64, offset as i64], |row| {
@@ -1363,6 +3083,7 @@ impl DbManager {
                 album_artist: row.get(1)?,
                 track_count: row.get::<_, i64>(2)?.max(0) as u32,
                 representative_track_path: row.get::<_, Option<String>>(3)?.map(PathBuf::from),
+                year: row.get(4)?,
             })
         })?;
         let mut rows = Vec::new();
@@ -1373,7 +3094,6 @@ impl DbManager {
     }
 
     /// Returns total album aggregate row count.
-    #[allow(dead_code)]
     pub fn get_library_albums_count(&self) -> Result<usize, rusqlite::Error> {
         let count: i64 = self.conn.query_row(
             "SELECT COUNT(*) FROM (SELECT album, album_artist FROM library_tracks GROUP BY album, album_artist)",
@@ -1537,6 +3257,79 @@ impl DbManager {
         Ok(count.max(0) as usize)
     }
 
+    /// Counts library tracks per genre, decade, and format value. Each dimension's counts
+    /// are narrowed by the selections in the other two, so a value's count is the number
+    /// of tracks selecting it would keep.
+    pub fn get_library_facet_counts(
+        &self,
+        selection: &LibraryFacetSelection,
+    ) -> Result<Vec<LibraryFacetCount>, rusqlite::Error> {
+        let dimensions = [
+            (LibraryFacetKind::Genre, "genre_facet", &selection.genres),
+            (LibraryFacetKind::Decade, "decade_facet", &selection.decades),
+            (LibraryFacetKind::Format, "format_facet", &selection.formats),
+        ];
+        let mut counts = Vec::new();
+        for (kind, column, _) in dimensions {
+            let mut conditions = Vec::new();
+            let mut values: Vec<&str> = Vec::new();
+            for (_, other_column, selected) in dimensions {
+                if other_column == column || selected.is_empty() {
+                    continue;
+                }
+                let placeholders = vec!["?"; selected.len()].join(", ");
+                conditions.push(format!("{other_column} IN ({placeholders})"));
+                values.extend(selected.iter().map(String::as_str));
+            }
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {column}, COUNT(*) FROM (
+                    SELECT
+                        CASE
+                            WHEN TRIM(genre) = '' THEN 'Unknown Genre'
+                            ELSE TRIM(genre)
+                        END AS genre_facet,
+                        CASE
+                            WHEN SUBSTR(TRIM(year), 1, 3) GLOB '[0-9][0-9][0-9]'
+                                THEN SUBSTR(TRIM(year), 1, 3) || '0s'
+                            ELSE 'Unknown Decade'
+                        END AS decade_facet,
+                        CASE
+                            WHEN INSTR(path, '.') = 0
+                                OR extension = ''
+                                OR INSTR(extension, '/') > 0
+                                OR INSTR(extension, '\\') > 0
+                                THEN 'Other'
+                            ELSE UPPER(extension)
+                        END AS format_facet
+                    FROM (
+                        SELECT genre, year, path,
+                            REPLACE(path, RTRIM(path, REPLACE(path, '.', '')), '') AS extension
+                        FROM library_tracks
+                    )
+                 )
+                 {where_clause}
+                 GROUP BY {column}
+                 ORDER BY LOWER({column}) ASC"
+            ))?;
+            let iter = stmt.query_map(params_from_iter(values), |row| {
+                Ok(LibraryFacetCount {
+                    kind,
+                    value: row.get(0)?,
+                    track_count: row.get::<_, i64>(1)?.max(0) as u32,
+                })
+            })?;
+            for item in iter {
+                counts.push(item?);
+            }
+        }
+        Ok(counts)
+    }
+
     /// Loads tracks for one album+album-artist pair sorted by track number then title.
     pub fn get_library_album_tracks(
         &self,
@@ -1560,6 +3353,7 @@ impl DbManager {
                 genre: row.get(6)?,
                 year: row.get(7)?,
                 track_number: row.get(8)?,
+                duration_ms: None,
             })
         })?;
         let mut tracks = Vec::new();
@@ -1575,7 +3369,8 @@ impl DbManager {
         artist: &str,
     ) -> Result<(Vec<LibraryAlbum>, Vec<LibraryTrack>), rusqlite::Error> {
         let mut album_stmt = self.conn.prepare(
-            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path
+            "SELECT album, album_artist, COUNT(*) AS track_count, MIN(path) AS representative_track_path,
+                    COALESCE(MAX(NULLIF(year, '')), '') AS year
              FROM library_tracks
              WHERE artist = ?1 OR album_artist = ?1
              GROUP BY album, album_artist
@@ -1587,6 +3382,7 @@ impl DbManager {
                 album_artist: row.get(1)?,
                 track_count: row.get::<_, i64>(2)?.max(0) as u32,
                 representative_track_path: row.get::<_, Option<String>>(3)?.map(PathBuf::from),
+                year: row.get(4)?,
             })
         })?;
         let mut albums = Vec::new();
@@ -1611,6 +3407,7 @@ impl DbManager {
                 genre: row.get(6)?,
                 year: row.get(7)?,
                 track_number: row.get(8)?,
+                duration_ms: None,
             })
         })?;
         let mut tracks = Vec::new();
@@ -1646,6 +3443,7 @@ impl DbManager {
                 genre: row.get(6)?,
                 year: row.get(7)?,
                 track_number: row.get(8)?,
+                duration_ms: None,
             })
         })?;
         let mut tracks = Vec::new();
@@ -1682,6 +3480,7 @@ impl DbManager {
                 genre: row.get(6)?,
                 year: row.get(7)?,
                 track_number: row.get(8)?,
+                duration_ms: None,
             })
         })?;
         let mut tracks = Vec::new();
@@ -1818,16 +3617,15 @@ impl DbManager {
         Ok(())
     }
 
-    /// Removes all expired enrichment cache rows.
+    /// Removes all expired enrichment cache rows and returns number of deleted records.
     pub fn prune_expired_library_enrichment_cache(
         &self,
         now_unix_ms: i64,
-    ) -> Result<(), rusqlite::Error> {
+    ) -> Result<usize, rusqlite::Error> {
         self.conn.execute(
             "DELETE FROM library_enrichment_cache WHERE expires_unix_ms <= ?1",
             params![now_unix_ms],
-        )?;
-        Ok(())
+        )
     }
 
     /// Clears cached image path references matching one on-disk path.
@@ -2178,9 +3976,21 @@ impl DbManager {
 
 #[cfg(test)]
 mod tests {
-    use super::DbManager;
+    use super::{DbManager, FileIntegrityRecord, LibraryTrackScanStub};
+    use crate::playlist_column_filters::{
+        ColumnFilterCondition, ColumnFilterOperator, PlaylistColumnFilter,
+    };
+    use crate::protocol::{
+        ActivityCategory, ActivityLogEntry, ImportSource, IntegrityIssue, IntegrityIssueKind,
+        LibraryFacetCount, LibraryFacetKind, LibraryFacetSelection, MaintenanceRunRecord,
+        MaintenanceTask, PlaylistFolderInfo, TechnicalMetadata, TrackEnergy, TrackEnergySetting,
+        TrackLoudness, TrackPlayStats, TrackTrims, TransitionCounts, TransitionOutcome,
+    };
     use rusqlite::Connection;
-    use std::{fs, path::PathBuf};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
     use uuid::Uuid;
 
     fn unique_temp_test_dir(prefix: &str) -> PathBuf {
@@ -2301,6 +4111,861 @@ mod tests {
         assert_eq!(tracks[0].id, "legacy-track");
     }
 
+    #[test]
+    fn test_get_tracks_for_playlist_range_pages_in_stored_order() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Playlist")
+            .expect("create playlist");
+        for position in 0..5 {
+            db.save_track(
+                &format!("t{position}"),
+                "p1",
+                &format!("/music/{position}.flac"),
+                position,
+            )
+            .expect("save track");
+        }
+
+        let ids = |tracks: Vec<RestoredTrack>| -> Vec<String> {
+            tracks.into_iter().map(|track| track.id).collect()
+        };
+        let window = db
+            .get_tracks_for_playlist_range("p1", 0, 2)
+            .expect("window query");
+        assert_eq!(ids(window), vec!["t0", "t1"]);
+        let rest = db
+            .get_tracks_for_playlist_range("p1", 2, usize::MAX)
+            .expect("remainder query");
+        assert_eq!(ids(rest), vec!["t2", "t3", "t4"]);
+    }
+
+    #[test]
+    fn test_playlist_url_sources_keep_titles_until_playlist_is_deleted() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Radio").expect("create playlist");
+        db.save_tracks_batch(
+            "p1",
+            &[(
+                "t1".to_string(),
+                PathBuf::from("https://radio.example.org/live"),
+            )],
+            0,
+        )
+        .expect("save tracks");
+        db.save_track_display_titles(&[(
+            "t1".to_string(),
+            "Morning Show".to_string(),
+            String::new(),
+        )])
+        .expect("save titles");
+        db.upsert_playlist_url_source("p1", "https://lists.example.org/radio.m3u", 10)
+            .expect("bind url");
+        db.upsert_playlist_url_source("p1", "https://lists.example.org/radio.m3u", 20)
+            .expect("refresh url");
+
+        let sources = db.get_playlist_url_sources().expect("query sources");
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].refreshed_unix_ms, 20);
+        let titles = db.get_url_playlist_track_titles().expect("query titles");
+        assert_eq!(titles.len(), 1);
+        assert_eq!(titles[0].1, "Morning Show");
+
+        db.delete_playlist("p1").expect("delete playlist");
+        assert!(db
+            .get_playlist_url_sources()
+            .expect("query sources")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rewrite_track_paths_updates_playlists_and_favorites() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Mix").expect("create playlist");
+        db.save_tracks_batch(
+            "p1",
+            &[("t1".to_string(), PathBuf::from("/music/in/one.flac"))],
+            0,
+        )
+        .expect("save tracks");
+        db.upsert_favorite(
+            &crate::protocol::FavoriteEntityRef {
+                kind: crate::protocol::FavoriteEntityKind::Track,
+                entity_key: "file:/music/in/one.flac".to_string(),
+                display_primary: "One".to_string(),
+                display_secondary: String::new(),
+                track_path: Some(PathBuf::from("/music/in/one.flac")),
+                remote_profile_id: None,
+                remote_item_id: None,
+            },
+            "local",
+            1,
+        )
+        .expect("save favorite");
+
+        db.rewrite_track_paths(&[(
+            PathBuf::from("/music/in/one.flac"),
+            PathBuf::from("/music/Artist/01 One.flac"),
+            "lib-new".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        let tracks = db.get_tracks_for_playlist("p1").expect("query tracks");
+        assert_eq!(tracks[0].path, PathBuf::from("/music/Artist/01 One.flac"));
+        let favorites = db.get_all_favorites().expect("query favorites");
+        assert_eq!(favorites[0].entity_key, "file:/music/Artist/01 One.flac");
+        assert_eq!(
+            favorites[0].track_path,
+            Some(PathBuf::from("/music/Artist/01 One.flac"))
+        );
+    }
+
+    #[test]
+    fn test_user_tags_follow_renamed_paths_and_delete_with_assignments() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.upsert_user_tag("Workout", "#E5484D")
+            .expect("create tag");
+        db.upsert_user_tag("chill", "#0090FF").expect("create tag");
+        db.upsert_user_tag("WORKOUT", "#46A758")
+            .expect("recolor tag");
+        db.set_track_user_tag(
+            &[
+                PathBuf::from("/music/a.flac"),
+                PathBuf::from("/music/b.flac"),
+            ],
+            "workout",
+            true,
+        )
+        .expect("assign tag");
+        db.set_track_user_tag(&[PathBuf::from("/music/a.flac")], "Chill", true)
+            .expect("assign tag");
+        db.set_track_user_tag(&[PathBuf::from("/music/b.flac")], "Workout", false)
+            .expect("clear tag");
+
+        let tags = db.get_user_tags().expect("query tags");
+        assert_eq!(
+            tags,
+            vec![
+                crate::protocol::UserTag {
+                    name: "chill".to_string(),
+                    color: "#0090FF".to_string(),
+                },
+                crate::protocol::UserTag {
+                    name: "Workout".to_string(),
+                    color: "#46A758".to_string(),
+                },
+            ]
+        );
+
+        db.rewrite_track_paths(&[(
+            PathBuf::from("/music/a.flac"),
+            PathBuf::from("/music/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+        let assignments = db
+            .get_track_user_tag_assignments()
+            .expect("query assignments");
+        assert_eq!(
+            assignments,
+            vec![
+                (PathBuf::from("/music/Artist/a.flac"), "chill".to_string()),
+                (PathBuf::from("/music/Artist/a.flac"), "Workout".to_string()),
+            ]
+        );
+
+        db.delete_user_tag("workout").expect("delete tag");
+        let assignments = db
+            .get_track_user_tag_assignments()
+            .expect("query assignments");
+        assert_eq!(
+            assignments,
+            vec![(PathBuf::from("/music/Artist/a.flac"), "chill".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_deleting_playlist_folder_moves_contents_to_its_parent() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Warmup").expect("create playlist");
+        db.create_playlist_folder("f1", "Running", None)
+            .expect("create folder");
+        db.create_playlist_folder("f2", "Intervals", Some("f1"))
+            .expect("create subfolder");
+        db.create_playlist_folder("f3", "Drills", Some("f2"))
+            .expect("create nested subfolder");
+        db.set_playlist_parent("p1", Some("f2"))
+            .expect("move playlist");
+        db.rename_playlist_folder("f2", "Tempo")
+            .expect("rename folder");
+        assert_eq!(
+            db.get_all_playlists().expect("query playlists")[0].parent_id,
+            Some("f2".to_string())
+        );
+
+        db.delete_playlist_folder("f2").expect("delete folder");
+
+        assert_eq!(
+            db.get_all_playlists().expect("query playlists")[0].parent_id,
+            Some("f1".to_string())
+        );
+        let folders = db.get_playlist_folders().expect("query folders");
+        assert_eq!(
+            folders,
+            vec![
+                PlaylistFolderInfo {
+                    id: "f3".to_string(),
+                    name: "Drills".to_string(),
+                    parent_id: Some("f1".to_string()),
+                },
+                PlaylistFolderInfo {
+                    id: "f1".to_string(),
+                    name: "Running".to_string(),
+                    parent_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_playlist_and_track_notes_round_trip_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Warmup").expect("create playlist");
+        db.set_playlist_note("p1", "Open with the slow ones")
+            .expect("save playlist note");
+        db.set_playlist_skip_silence("p1", true)
+            .expect("save playlist skip silence");
+        db.set_playlist_normalize_loudness("p1", true)
+            .expect("save playlist loudness normalization");
+        let filters = vec![PlaylistColumnFilter {
+            column_key: "{year}".to_string(),
+            values: Vec::new(),
+            condition: Some(ColumnFilterCondition {
+                operator: ColumnFilterOperator::AtLeast,
+                operand: "2010".to_string(),
+            }),
+        }];
+        db.set_playlist_column_filters("p1", &filters)
+            .expect("save playlist column filters");
+        db.set_playlist_details(
+            "p1",
+            "Songs for the long drive",
+            Some(Path::new("/covers/p1.png")),
+        )
+        .expect("save playlist details");
+        let playlists = db.get_all_playlists().expect("query playlists");
+        assert_eq!(playlists[0].column_filters, filters);
+        assert_eq!(playlists[0].description, "Songs for the long drive");
+        assert_eq!(
+            playlists[0].cover_path.as_deref(),
+            Some(Path::new("/covers/p1.png"))
+        );
+        assert_eq!(playlists[0].note, "Open with the slow ones");
+        assert!(playlists[0].skip_silence);
+        assert!(playlists[0].normalize_loudness);
+
+        db.set_track_note(Path::new("/music/a.flac"), "skip intro", 1)
+            .expect("save track note");
+        db.set_track_note(Path::new("/music/b.flac"), "vinyl rip", 1)
+            .expect("save track note");
+        db.set_track_note(Path::new("/music/b.flac"), "   ", 2)
+            .expect("clear track note");
+        db.rewrite_track_paths(&[(
+            PathBuf::from("/music/a.flac"),
+            PathBuf::from("/music/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        let notes = db.get_track_notes().expect("query notes");
+        assert_eq!(
+            notes,
+            vec![(
+                PathBuf::from("/music/Artist/a.flac"),
+                "skip intro".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_track_trim_overrides_round_trip_clear_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let trims = TrackTrims {
+            start_ms: 12_500,
+            end_ms: 4_000,
+        };
+        db.set_track_trims(
+            &[
+                PathBuf::from("/music/a.flac"),
+                PathBuf::from("/music/b.flac"),
+            ],
+            trims,
+        )
+        .expect("save trims");
+        db.set_track_trims(&[PathBuf::from("/music/b.flac")], TrackTrims::default())
+            .expect("clear trim");
+        db.rewrite_track_paths(&[(
+            PathBuf::from("/music/a.flac"),
+            PathBuf::from("/music/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        let overrides = db.get_track_trim_overrides().expect("query trims");
+        assert_eq!(
+            overrides,
+            vec![(PathBuf::from("/music/Artist/a.flac"), trims)]
+        );
+    }
+
+    #[test]
+    fn test_track_transition_history_counts_outcomes_and_resets() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let from = PathBuf::from("/music/a.flac");
+        let to = PathBuf::from("/music/b.flac");
+        for outcome in [
+            TransitionOutcome::Manual,
+            TransitionOutcome::PlayedThrough,
+            TransitionOutcome::PlayedThrough,
+            TransitionOutcome::Skipped,
+        ] {
+            db.record_track_transition(&from, &to, outcome, 1)
+                .expect("record transition");
+        }
+        db.rewrite_track_paths(&[(
+            to,
+            PathBuf::from("/music/Artist/b.flac"),
+            "lib-b".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        assert_eq!(
+            db.get_track_transition_history()
+                .expect("query transitions"),
+            vec![(
+                from,
+                PathBuf::from("/music/Artist/b.flac"),
+                TransitionCounts {
+                    manual: 1,
+                    played_through: 2,
+                    skipped: 1,
+                }
+            )]
+        );
+        assert_eq!(
+            db.clear_track_transition_history()
+                .expect("clear transitions"),
+            1
+        );
+        assert!(db
+            .get_track_transition_history()
+            .expect("query transitions")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_track_play_stats_count_plays_and_skips_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let path = PathBuf::from("/music/a.flac");
+        db.record_track_play(&path, true, 10).expect("record play");
+        db.record_track_play(&path, true, 20).expect("record play");
+        let stats = db.record_track_play(&path, false, 30).expect("record skip");
+        assert_eq!(
+            stats,
+            TrackPlayStats {
+                play_count: 2,
+                skip_count: 1,
+                last_played_unix_ms: Some(20),
+            }
+        );
+        let skipped_only = db
+            .record_track_play(Path::new("/music/b.flac"), false, 40)
+            .expect("record skip");
+        assert_eq!(skipped_only.last_played_unix_ms, None);
+
+        db.rewrite_track_paths(&[(
+            path,
+            PathBuf::from("/music/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+        let rows = db.get_track_play_stats().expect("query play stats");
+        assert_eq!(rows[0], (PathBuf::from("/music/Artist/a.flac"), stats));
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_cached_lyrics_record_misses_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let path = PathBuf::from("/music/a.flac");
+        assert_eq!(db.get_cached_lyrics(&path).expect("query lyrics"), None);
+        db.save_cached_lyrics(&path, None, 10)
+            .expect("save lyrics miss");
+        assert_eq!(
+            db.get_cached_lyrics(&path).expect("query lyrics"),
+            Some((None, 10))
+        );
+        db.save_cached_lyrics(&path, Some("[00:01.00]Hello"), 20)
+            .expect("save lyrics");
+
+        let moved = PathBuf::from("/music/Artist/a.flac");
+        db.rewrite_track_paths(&[(path.clone(), moved.clone(), "lib-a".to_string())])
+            .expect("rewrite paths");
+        assert_eq!(db.get_cached_lyrics(&path).expect("query lyrics"), None);
+        assert_eq!(
+            db.get_cached_lyrics(&moved).expect("query lyrics"),
+            Some((Some("[00:01.00]Hello".to_string()), 20))
+        );
+    }
+
+    #[test]
+    fn test_track_loudness_replaces_measurements_and_follows_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let path = PathBuf::from("/music/a.flac");
+        db.save_track_loudness(
+            &path,
+            TrackLoudness {
+                integrated_lufs: -20.0,
+                true_peak_dbtp: -3.0,
+            },
+            1,
+        )
+        .expect("save loudness");
+        let remeasured = TrackLoudness {
+            integrated_lufs: -8.5,
+            true_peak_dbtp: 0.25,
+        };
+        db.save_track_loudness(&path, remeasured, 2)
+            .expect("replace loudness");
+        db.rewrite_track_paths(&[(
+            path,
+            PathBuf::from("/music/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        assert_eq!(
+            db.get_track_loudness().expect("query loudness"),
+            vec![(PathBuf::from("/music/Artist/a.flac"), remeasured)]
+        );
+    }
+
+    #[test]
+    fn test_track_formats_are_cached_per_path_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let format = |bitrate_kbps| TechnicalMetadata {
+            format: "FLAC".to_string(),
+            bitrate_kbps,
+            sample_rate_hz: 96_000,
+            channel_count: 2,
+            duration_ms: 215_000,
+            bits_per_sample: 24,
+        };
+        let path = PathBuf::from("/nas/a.flac");
+        db.save_track_formats(&[(path.clone(), format(900))])
+            .expect("save format");
+        db.save_track_formats(&[(path.clone(), format(1_400))])
+            .expect("replace format");
+        db.rewrite_track_paths(&[(
+            path.clone(),
+            PathBuf::from("/nas/Artist/a.flac"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        let cached = db
+            .get_track_formats(&[path, PathBuf::from("/nas/Artist/a.flac")])
+            .expect("query formats");
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].0, PathBuf::from("/nas/Artist/a.flac"));
+        assert_eq!(cached[0].1.bitrate_kbps, 1_400);
+        assert_eq!(cached[0].1.bits_per_sample, 24);
+        assert_eq!(cached[0].1.duration_ms, 215_000);
+    }
+
+    #[test]
+    fn test_suggested_track_energy_never_overwrites_manual_levels() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let manual = PathBuf::from("/music/manual.flac");
+        let suggested = PathBuf::from("/music/suggested.flac");
+        db.set_track_energy(&[manual.clone()], Some(TrackEnergy::Calm), 1)
+            .expect("set manual energy");
+        db.save_suggested_track_energy(
+            &[
+                (manual.clone(), TrackEnergy::Intense),
+                (suggested.clone(), TrackEnergy::Upbeat),
+            ],
+            2,
+        )
+        .expect("save suggestions");
+        db.save_suggested_track_energy(&[(suggested.clone(), TrackEnergy::Steady)], 3)
+            .expect("replace suggestion");
+        db.rewrite_track_paths(&[(
+            suggested,
+            PathBuf::from("/music/Artist/suggested.flac"),
+            "lib-s".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        assert_eq!(
+            db.get_track_energy().expect("query energy"),
+            vec![
+                (
+                    PathBuf::from("/music/Artist/suggested.flac"),
+                    TrackEnergySetting {
+                        energy: TrackEnergy::Steady,
+                        suggested: true,
+                    }
+                ),
+                (
+                    manual.clone(),
+                    TrackEnergySetting {
+                        energy: TrackEnergy::Calm,
+                        suggested: false,
+                    }
+                ),
+            ]
+        );
+
+        db.set_track_energy(&[manual], None, 4)
+            .expect("clear manual energy");
+        assert_eq!(db.get_track_energy().expect("query energy").len(), 1);
+    }
+
+    #[test]
+    fn test_shuffle_excluded_tracks_round_trip_and_follow_renamed_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.set_tracks_excluded_from_shuffle(
+            &[PathBuf::from("/music/a.mp3"), PathBuf::from("/music/b.mp3")],
+            true,
+        )
+        .expect("exclude tracks");
+        db.set_tracks_excluded_from_shuffle(&[PathBuf::from("/music/b.mp3")], false)
+            .expect("include track");
+        db.rewrite_track_paths(&[(
+            PathBuf::from("/music/a.mp3"),
+            PathBuf::from("/music/Podcasts/a.mp3"),
+            "lib-a".to_string(),
+        )])
+        .expect("rewrite paths");
+
+        assert_eq!(
+            db.get_shuffle_excluded_paths().expect("query exclusions"),
+            vec![PathBuf::from("/music/Podcasts/a.mp3")]
+        );
+    }
+
+    #[test]
+    fn test_prune_orphaned_track_rows_keeps_playlist_and_remote_paths() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Mix").expect("create playlist");
+        db.save_tracks_batch(
+            "p1",
+            &[("t1".to_string(), PathBuf::from("/music/kept.flac"))],
+            0,
+        )
+        .expect("save tracks");
+        db.set_track_note(Path::new("/music/kept.flac"), "keep", 1)
+            .expect("save kept note");
+        db.set_track_note(Path::new("/music/gone.flac"), "drop", 1)
+            .expect("save orphan note");
+        db.set_tracks_excluded_from_shuffle(
+            &[
+                PathBuf::from("/music/gone.flac"),
+                PathBuf::from("opensubsonic://profile/song-1"),
+            ],
+            true,
+        )
+        .expect("exclude tracks");
+
+        assert_eq!(db.prune_orphaned_track_rows().expect("prune rows"), 2);
+        assert_eq!(
+            db.get_track_notes().expect("query notes"),
+            vec![(PathBuf::from("/music/kept.flac"), "keep".to_string())]
+        );
+        assert_eq!(
+            db.get_shuffle_excluded_paths().expect("query exclusions"),
+            vec![PathBuf::from("opensubsonic://profile/song-1")]
+        );
+    }
+
+    #[test]
+    fn test_library_albums_page_reports_latest_year_and_total() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let stub = |path: &str, album: &str, year: &str| LibraryTrackScanStub {
+            track_id: path.to_string(),
+            path: path.to_string(),
+            title: path.to_string(),
+            artist: "Artist".to_string(),
+            album: album.to_string(),
+            album_artist: "Artist".to_string(),
+            genre: String::new(),
+            year: year.to_string(),
+            track_number: String::new(),
+            sort_title: path.to_string(),
+            sort_artist: "artist".to_string(),
+            sort_album: album.to_ascii_lowercase(),
+            modified_unix_ms: 0,
+            file_size_bytes: 0,
+            metadata_ready: true,
+            last_scanned_unix_ms: 0,
+        };
+        db.upsert_library_track_scan_stub_batch(&[
+            stub("/music/b1.flac", "Beta", "1999"),
+            stub("/music/b2.flac", "Beta", "2001"),
+            stub("/music/a1.flac", "Alpha", ""),
+            stub("/music/c1.flac", "Gamma", "1985"),
+        ])
+        .expect("upsert stubs");
+
+        let (albums, total) = db.get_library_albums_page(0, 2).expect("query page");
+        assert_eq!(total, 3);
+        let summary: Vec<(&str, u32, &str)> = albums
+            .iter()
+            .map(|album| (album.album.as_str(), album.track_count, album.year.as_str()))
+            .collect();
+        assert_eq!(summary, [("Alpha", 1, ""), ("Beta", 2, "2001")]);
+        let (albums, _) = db.get_library_albums_page(2, 2).expect("query last page");
+        assert_eq!(albums.len(), 1);
+        assert_eq!(albums[0].album, "Gamma");
+    }
+
+    #[test]
+    fn test_library_facet_counts_narrow_by_the_other_dimensions() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let stub = |path: &str, genre: &str, year: &str| LibraryTrackScanStub {
+            track_id: path.to_string(),
+            path: path.to_string(),
+            title: path.to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            album_artist: "Artist".to_string(),
+            genre: genre.to_string(),
+            year: year.to_string(),
+            track_number: String::new(),
+            sort_title: path.to_string(),
+            sort_artist: "artist".to_string(),
+            sort_album: "album".to_string(),
+            modified_unix_ms: 0,
+            file_size_bytes: 0,
+            metadata_ready: true,
+            last_scanned_unix_ms: 0,
+        };
+        db.upsert_library_track_scan_stub_batch(&[
+            stub("/music/a.flac", "Jazz", "1975"),
+            stub("/music/b.mp3", "Jazz", "1982"),
+            stub("/music/c.flac", "Rock", "1979"),
+            stub("/music.v2/d", " ", ""),
+        ])
+        .expect("upsert stubs");
+        let summary = |counts: Vec<LibraryFacetCount>| -> Vec<(LibraryFacetKind, String, u32)> {
+            counts
+                .into_iter()
+                .map(|count| (count.kind, count.value, count.track_count))
+                .collect()
+        };
+
+        let all = db
+            .get_library_facet_counts(&LibraryFacetSelection::default())
+            .expect("count facets");
+        assert_eq!(
+            summary(all),
+            [
+                (LibraryFacetKind::Genre, "Jazz".to_string(), 2),
+                (LibraryFacetKind::Genre, "Rock".to_string(), 1),
+                (LibraryFacetKind::Genre, "Unknown Genre".to_string(), 1),
+                (LibraryFacetKind::Decade, "1970s".to_string(), 2),
+                (LibraryFacetKind::Decade, "1980s".to_string(), 1),
+                (LibraryFacetKind::Decade, "Unknown Decade".to_string(), 1),
+                (LibraryFacetKind::Format, "FLAC".to_string(), 2),
+                (LibraryFacetKind::Format, "MP3".to_string(), 1),
+                (LibraryFacetKind::Format, "Other".to_string(), 1),
+            ]
+        );
+
+        let mut selection = LibraryFacetSelection::default();
+        selection.genres.insert("Jazz".to_string());
+        selection.formats.insert("FLAC".to_string());
+        let narrowed = db
+            .get_library_facet_counts(&selection)
+            .expect("count narrowed facets");
+        assert_eq!(
+            summary(narrowed),
+            [
+                (LibraryFacetKind::Genre, "Jazz".to_string(), 1),
+                (LibraryFacetKind::Genre, "Rock".to_string(), 1),
+                (LibraryFacetKind::Decade, "1970s".to_string(), 1),
+                (LibraryFacetKind::Format, "FLAC".to_string(), 1),
+                (LibraryFacetKind::Format, "MP3".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_integrity_records_round_trip_and_list_issues() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        let record = |path: &str, issue_kind: Option<IntegrityIssueKind>| FileIntegrityRecord {
+            path: path.to_string(),
+            file_size_bytes: 1_024,
+            modified_unix_ms: 10,
+            content_hash: "abc123".to_string(),
+            verified_unix_ms: 20,
+            issue_kind,
+            issue_detail: issue_kind
+                .map(|_| "checksum differs".to_string())
+                .unwrap_or_default(),
+            spectrum_checked: issue_kind.is_none(),
+        };
+        db.upsert_file_integrity_records(&[
+            record(
+                "/music/b.flac",
+                Some(IntegrityIssueKind::StreamChecksumMismatch),
+            ),
+            record("/music/a.flac", None),
+        ])
+        .expect("save records");
+
+        let records = db.get_file_integrity_records().expect("query records");
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records.get("/music/a.flac"),
+            Some(&record("/music/a.flac", None))
+        );
+        assert_eq!(
+            db.get_integrity_issues().expect("query issues"),
+            vec![IntegrityIssue {
+                path: PathBuf::from("/music/b.flac"),
+                kind: IntegrityIssueKind::StreamChecksumMismatch,
+                detail: "checksum differs".to_string(),
+                verified_unix_ms: 20,
+            }]
+        );
+
+        db.upsert_file_integrity_records(&[record("/music/b.flac", None)])
+            .expect("clear issue");
+        assert!(db.get_integrity_issues().expect("query issues").is_empty());
+    }
+
+    #[test]
+    fn test_maintenance_runs_are_listed_newest_first() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        for (index, task) in [
+            MaintenanceTask::PruneOrphanedRows,
+            MaintenanceTask::ExpireCaches,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            db.record_maintenance_run(&MaintenanceRunRecord {
+                task,
+                started_unix_ms: 1_000 * index as i64,
+                duration_ms: 5,
+                success: index == 0,
+                summary: format!("run {index}"),
+            })
+            .expect("record run");
+        }
+
+        let runs = db.get_maintenance_runs(10).expect("query runs");
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].task, MaintenanceTask::ExpireCaches);
+        assert!(!runs[0].success);
+        assert_eq!(runs[1].summary, "run 0");
+        assert_eq!(db.get_maintenance_runs(1).expect("query runs").len(), 1);
+    }
+
+    #[test]
+    fn test_activity_log_is_listed_newest_first_and_expires_old_entries() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        for (recorded_unix_ms, category, message) in [
+            (1_000, ActivityCategory::Library, "Imported 230 tracks"),
+            (
+                5_000,
+                ActivityCategory::Playlist,
+                "Deleted playlist \"Road Trip\"",
+            ),
+            (6_000, ActivityCategory::Sync, "Synced profile \"Home\""),
+        ] {
+            db.record_activity(
+                &ActivityLogEntry {
+                    recorded_unix_ms,
+                    category,
+                    message: message.to_string(),
+                },
+                2_000,
+            )
+            .expect("record activity");
+        }
+
+        let entries = db.get_activity_log(10).expect("query activity log");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].category, ActivityCategory::Sync);
+        assert_eq!(entries[1].message, "Deleted playlist \"Road Trip\"");
+        assert_eq!(db.get_activity_log(1).expect("query activity log").len(), 1);
+    }
+
+    #[test]
+    fn test_track_sources_follow_their_playlist_entries() {
+        let db = DbManager::new_in_memory().expect("in-memory db should initialize");
+        db.create_playlist("p1", "Mix").expect("create playlist");
+        db.save_tracks_batch(
+            "p1",
+            &[
+                ("t1".to_string(), PathBuf::from("/music/a.flac")),
+                ("t2".to_string(), PathBuf::from("/music/b.flac")),
+            ],
+            0,
+        )
+        .expect("save tracks");
+        db.save_track_sources(&[
+            ("t1".to_string(), ImportSource::DragAndDrop),
+            ("t2".to_string(), ImportSource::Library),
+        ])
+        .expect("save sources");
+
+        let mut sources = db.get_track_sources_for_playlist("p1").expect("load");
+        sources.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            sources,
+            vec![
+                ("t1".to_string(), ImportSource::DragAndDrop),
+                ("t2".to_string(), ImportSource::Library),
+            ]
+        );
+
+        db.delete_track("t1").expect("delete track");
+        assert_eq!(
+            db.get_track_sources_for_playlist("p1").expect("load"),
+            vec![("t2".to_string(), ImportSource::Library)]
+        );
+        db.delete_playlist("p1").expect("delete playlist");
+        let remaining: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM track_sources", [], |row| row.get(0))
+            .expect("count sources");
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn test_read_only_connection_reads_committed_snapshot_and_rejects_writes() {
+        let dir = unique_temp_test_dir("roqtune_read_only");
+        let db_path = dir.join(DbManager::DB_FILE_NAME);
+        let writer = DbManager::open_at(&db_path).expect("writer db should initialize");
+        writer
+            .create_playlist("a", "Playlist A")
+            .expect("should store playlist");
+
+        let reader = DbManager::open_read_only_at(&db_path).expect("reader should open");
+        let playlists = reader
+            .with_read_snapshot(|db| db.get_all_playlists())
+            .expect("snapshot read should succeed");
+        assert!(playlists.iter().any(|playlist| playlist.id == "a"));
+        assert!(reader.create_playlist("b", "Playlist B").is_err());
+
+        drop(reader);
+        drop(writer);
+        let _ = fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn test_migrate_legacy_db_file_renames_playlist_db_and_sidecars() {
         let temp_dir = unique_temp_test_dir("roqtune_legacy_db_migration");
@@ -0,0 +1,75 @@
+//! Read-only SQLite worker pool for UI-facing library queries.
+//!
+//! Each worker thread owns one read-only `DbManager`, so large search, count, and
+//! scroll queries run alongside scan and playlist writes instead of queueing behind
+//! them on a writer connection.
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use log::warn;
+
+use crate::db_manager::DbManager;
+use crate::metrics;
+
+/// Number of read-only connections used for library queries.
+pub const LIBRARY_READ_POOL_SIZE: usize = 3;
+
+type ReadJob = Box<dyn FnOnce(&DbManager) + Send + 'static>;
+
+/// Fixed-size pool of query threads backed by read-only database connections.
+pub struct DbReadPool {
+    job_tx: mpsc::Sender<ReadJob>,
+}
+
+impl DbReadPool {
+    /// Opens `size` read-only connections and starts one worker thread per connection.
+    pub fn new(size: usize) -> Result<Self, rusqlite::Error> {
+        let mut connections = Vec::with_capacity(size.max(1));
+        for _ in 0..size.max(1) {
+            connections.push(DbManager::new_read_only()?);
+        }
+
+        let (job_tx, job_rx) = mpsc::channel::<ReadJob>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        for db_manager in connections {
+            let job_rx = Arc::clone(&job_rx);
+            thread::spawn(move || Self::run_worker(db_manager, job_rx));
+        }
+        Ok(Self { job_tx })
+    }
+
+    fn run_worker(db_manager: DbManager, job_rx: Arc<Mutex<mpsc::Receiver<ReadJob>>>) {
+        loop {
+            let job = {
+                let Ok(job_rx) = job_rx.lock() else {
+                    break;
+                };
+                job_rx.recv()
+            };
+            match job {
+                Ok(job) => {
+                    let started = Instant::now();
+                    job(&db_manager);
+                    metrics::observe_duration(
+                        metrics::DB_READ_QUERY_SECONDS,
+                        &[],
+                        started.elapsed(),
+                    );
+                }
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Queues one read job on the next idle worker.
+    ///
+    /// Jobs are expected to wrap multi-statement reads in
+    /// `DbManager::with_read_snapshot` so they observe one consistent snapshot.
+    pub fn submit(&self, job: impl FnOnce(&DbManager) + Send + 'static) {
+        if self.job_tx.send(Box::new(job)).is_err() {
+            warn!("DbReadPool: all workers stopped, dropping read job");
+        }
+    }
+}
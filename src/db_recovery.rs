@@ -0,0 +1,467 @@
+//! Startup integrity check, backups, and repair of the SQLite database.
+//!
+//! Before any service opens `roqtune.db`, startup runs `PRAGMA quick_check` on it. A
+//! healthy database is copied into `backups/` next to it at most once a day, keeping the
+//! newest [`KEPT_BACKUPS`] copies. A damaged database is reported to the user instead of
+//! leaving them with an empty library, with two ways out:
+//!
+//! - restore the newest backup, or
+//! - rebuild from the music files: a fresh database is created, the playlists and other
+//!   user data that can still be read are copied out of the damaged file, and the library
+//!   is rescanned from the configured folders.
+//!
+//! Either way the damaged file is kept next to the database as `roqtune.db.damaged-<time>`.
+//! Repairs run in a restarted process (`--repair-database=restore|rebuild`) so that no
+//! connection still holds the damaged file open.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use rusqlite::{Connection, ErrorCode, OpenFlags};
+
+use crate::db_manager::DbManager;
+
+/// Command-line flag prefix asking the next start to repair the database.
+pub const REPAIR_DATABASE_FLAG_PREFIX: &str = "--repair-database=";
+/// Folder next to the database holding its daily backups.
+const BACKUPS_DIR_NAME: &str = "backups";
+const BACKUP_FILE_PREFIX: &str = "roqtune-";
+const BACKUP_FILE_SUFFIX: &str = ".db";
+/// Number of daily backups kept.
+pub const KEPT_BACKUPS: usize = 3;
+/// Minimum age of the newest backup before another one is taken.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Tables copied out of a damaged database on rebuild, parents before children. The
+/// library index itself is rebuilt by rescanning.
+const SALVAGED_TABLES: [&str; 9] = [
+    "playlist_folders",
+    "playlists",
+    "tracks",
+    "playlist_url_sources",
+    "favorites",
+    "user_tags",
+    "track_user_tags",
+    "track_notes",
+    "track_play_stats",
+];
+/// Attempts at moving the damaged file aside while the previous process is still exiting.
+const MOVE_ASIDE_ATTEMPTS: u32 = 20;
+const MOVE_ASIDE_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Repair picked by the user for a damaged database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseRepair {
+    RestoreBackup,
+    RebuildFromFiles,
+}
+
+impl DatabaseRepair {
+    /// Command-line argument that runs this repair on the next start.
+    pub fn to_arg(self) -> String {
+        let name = match self {
+            Self::RestoreBackup => "restore",
+            Self::RebuildFromFiles => "rebuild",
+        };
+        format!("{REPAIR_DATABASE_FLAG_PREFIX}{name}")
+    }
+
+    /// Parses an argument produced by [`DatabaseRepair::to_arg`].
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg.strip_prefix(REPAIR_DATABASE_FLAG_PREFIX)? {
+            "restore" => Some(Self::RestoreBackup),
+            "rebuild" => Some(Self::RebuildFromFiles),
+            _ => None,
+        }
+    }
+}
+
+/// Result of the startup check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseHealth {
+    /// No database yet; the first start creates it.
+    Missing,
+    Healthy,
+    /// The database failed the check; `problem` is SQLite's description.
+    Damaged {
+        problem: String,
+    },
+}
+
+/// Outcome of a successful repair, for the notice shown after the restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairReport {
+    pub repair: DatabaseRepair,
+    pub message: String,
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Runs `PRAGMA quick_check` on the database at `db_path`.
+pub fn check_database(db_path: &Path) -> DatabaseHealth {
+    if !db_path.exists() {
+        return DatabaseHealth::Missing;
+    }
+    let result = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .and_then(|conn| {
+        let mut stmt = conn.prepare("PRAGMA quick_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<Vec<String>, _>>()
+    });
+    match result {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => DatabaseHealth::Healthy,
+        Ok(rows) => DatabaseHealth::Damaged {
+            problem: rows.into_iter().take(3).collect::<Vec<_>>().join("; "),
+        },
+        // Another roqtune instance holding the database is not damage.
+        Err(rusqlite::Error::SqliteFailure(failure, _))
+            if matches!(
+                failure.code,
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked
+            ) =>
+        {
+            warn!("Database check skipped: the database is in use");
+            DatabaseHealth::Healthy
+        }
+        Err(err) => DatabaseHealth::Damaged {
+            problem: err.to_string(),
+        },
+    }
+}
+
+fn backups_dir(db_path: &Path) -> PathBuf {
+    db_path.with_file_name(BACKUPS_DIR_NAME)
+}
+
+/// Backups of the database at `db_path` with the unix time they were taken, newest first.
+pub fn list_backups(db_path: &Path) -> Vec<(u64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(backups_dir(db_path)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<(u64, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let taken = name
+                .to_str()?
+                .strip_prefix(BACKUP_FILE_PREFIX)?
+                .strip_suffix(BACKUP_FILE_SUFFIX)?
+                .parse()
+                .ok()?;
+            Some((taken, entry.path()))
+        })
+        .collect();
+    backups.sort_by(|left, right| right.0.cmp(&left.0));
+    backups
+}
+
+/// Copies a healthy database into the backups folder unless the newest backup is less
+/// than a day old, then drops all but the newest [`KEPT_BACKUPS`].
+pub fn back_up_database_if_due(db_path: &Path, now_secs: u64) -> Result<Option<PathBuf>, String> {
+    let newest = list_backups(db_path).first().map(|(taken, _)| *taken);
+    if newest.is_some_and(|taken| now_secs.saturating_sub(taken) < BACKUP_INTERVAL.as_secs()) {
+        return Ok(None);
+    }
+    let dir = backups_dir(db_path);
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create {}: {}", dir.display(), err))?;
+    let target = dir.join(format!(
+        "{BACKUP_FILE_PREFIX}{now_secs}{BACKUP_FILE_SUFFIX}"
+    ));
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|err| err.to_string())?;
+    conn.execute("VACUUM INTO ?1", [target.to_string_lossy()])
+        .map_err(|err| err.to_string())?;
+    for (_, stale) in list_backups(db_path).into_iter().skip(KEPT_BACKUPS) {
+        if let Err(err) = fs::remove_file(&stale) {
+            warn!("Failed to remove old backup {}: {}", stale.display(), err);
+        }
+    }
+    Ok(Some(target))
+}
+
+fn rename_with_retries(from: &Path, to: &Path) -> io::Result<()> {
+    let mut attempt = 1;
+    loop {
+        match fs::rename(from, to) {
+            Err(_) if attempt < MOVE_ASIDE_ATTEMPTS => {
+                attempt += 1;
+                thread::sleep(MOVE_ASIDE_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Moves the database and its WAL sidecars to `<db>.damaged-<now>` and returns that path.
+fn move_damaged_aside(db_path: &Path, now_secs: u64) -> Result<PathBuf, String> {
+    let damaged = with_suffix(db_path, &format!(".damaged-{now_secs}"));
+    for sidecar in ["", "-wal", "-shm"] {
+        let from = with_suffix(db_path, sidecar);
+        if !from.exists() {
+            continue;
+        }
+        rename_with_retries(&from, &with_suffix(&damaged, sidecar))
+            .map_err(|err| format!("Could not move {} aside: {}", from.display(), err))?;
+    }
+    Ok(damaged)
+}
+
+pub(crate) fn table_columns(conn: &Connection, schema: &str, table: &str) -> Vec<String> {
+    let Ok(mut stmt) = conn.prepare(&format!("PRAGMA {schema}.table_info({table})")) else {
+        return Vec::new();
+    };
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .and_then(|rows| rows.collect())
+        .unwrap_or_default();
+    columns
+}
+
+/// Copies [`SALVAGED_TABLES`] out of `damaged` into the fresh database, one table at a
+/// time so one unreadable table does not lose the others. Returns the rows copied per
+/// table; tables that could not be read are left out.
+fn salvage_tables(
+    conn: &Connection,
+    damaged: &Path,
+) -> Result<Vec<(&'static str, usize)>, rusqlite::Error> {
+    conn.execute("ATTACH DATABASE ?1 AS damaged", [damaged.to_string_lossy()])?;
+    let placeholder_playlists: Vec<String> = conn
+        .prepare("SELECT id FROM main.playlists")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut copied = Vec::new();
+    for table in SALVAGED_TABLES {
+        let damaged_columns = table_columns(conn, "damaged", table);
+        let columns: Vec<String> = table_columns(conn, "main", table)
+            .into_iter()
+            .filter(|column| damaged_columns.contains(column))
+            .map(|column| format!("\"{column}\""))
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+        let columns = columns.join(", ");
+        match conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO main.{table} ({columns}) SELECT {columns} FROM damaged.{table}"
+            ),
+            [],
+        ) {
+            Ok(rows) => copied.push((table, rows)),
+            Err(err) => warn!("Database rebuild: could not recover {}: {}", table, err),
+        }
+    }
+    conn.execute(
+        "DELETE FROM main.tracks WHERE playlist_id NOT IN (SELECT id FROM main.playlists)",
+        [],
+    )?;
+    if copied
+        .iter()
+        .any(|(table, rows)| *table == "playlists" && *rows > 0)
+    {
+        // The fresh database starts with an empty "Default" playlist; drop it once the
+        // user's own playlists are back.
+        for id in placeholder_playlists {
+            conn.execute(
+                "DELETE FROM main.playlists
+                 WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM main.tracks WHERE playlist_id = ?1)",
+                [&id],
+            )?;
+        }
+    }
+    conn.execute("DETACH DATABASE damaged", [])?;
+    Ok(copied)
+}
+
+fn restore_latest_backup(db_path: &Path, now_secs: u64) -> Result<RepairReport, String> {
+    let (taken, backup) = list_backups(db_path)
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No backup of the library database was found.".to_string())?;
+    let damaged = move_damaged_aside(db_path, now_secs)?;
+    fs::copy(&backup, db_path)
+        .map_err(|err| format!("Could not copy {}: {}", backup.display(), err))?;
+    let days = now_secs.saturating_sub(taken) / BACKUP_INTERVAL.as_secs();
+    let age = match days {
+        0 => "earlier today".to_string(),
+        1 => "1 day ago".to_string(),
+        days => format!("{days} days ago"),
+    };
+    Ok(RepairReport {
+        repair: DatabaseRepair::RestoreBackup,
+        message: format!(
+            "The library database was restored from the backup taken {age}. Changes made \
+             since then are lost. The damaged file was kept at {}.",
+            damaged.display()
+        ),
+    })
+}
+
+fn rebuild_from_files(db_path: &Path, now_secs: u64) -> Result<RepairReport, String> {
+    let damaged = move_damaged_aside(db_path, now_secs)?;
+    drop(DbManager::open_at(db_path).map_err(|err| err.to_string())?);
+    let conn = Connection::open(db_path).map_err(|err| err.to_string())?;
+    let copied = salvage_tables(&conn, &damaged).unwrap_or_else(|err| {
+        warn!("Database rebuild: could not read the damaged file: {}", err);
+        Vec::new()
+    });
+    let rows_of = |name: &str| {
+        copied
+            .iter()
+            .find(|(table, _)| *table == name)
+            .map_or(0, |(_, rows)| *rows)
+    };
+    let recovered = match rows_of("playlists") {
+        0 => "No playlists could be recovered".to_string(),
+        playlists => format!(
+            "Recovered {} playlist(s) with {} track(s)",
+            playlists,
+            rows_of("tracks")
+        ),
+    };
+    Ok(RepairReport {
+        repair: DatabaseRepair::RebuildFromFiles,
+        message: format!(
+            "The library database was rebuilt. {recovered}; the library is being rescanned \
+             from your music folders. The damaged file was kept at {}.",
+            damaged.display()
+        ),
+    })
+}
+
+/// Runs `repair` on the database at `db_path`. Must run before anything opens it.
+pub fn repair_database(db_path: &Path, repair: DatabaseRepair) -> Result<RepairReport, String> {
+    let now_secs = unix_now_secs();
+    let result = match repair {
+        DatabaseRepair::RestoreBackup => restore_latest_backup(db_path, now_secs),
+        DatabaseRepair::RebuildFromFiles => rebuild_from_files(db_path, now_secs),
+    };
+    match &result {
+        Ok(report) => info!("Database repair finished: {}", report.message),
+        Err(err) => warn!("Database repair failed: {}", err),
+    }
+    result
+}
+
+/// Takes the daily backup of a healthy database on a background thread.
+pub fn spawn_daily_backup(db_path: PathBuf) {
+    thread::spawn(
+        move || match back_up_database_if_due(&db_path, unix_now_secs()) {
+            Ok(Some(path)) => info!("Backed up the library database to {}", path.display()),
+            Ok(None) => {}
+            Err(err) => warn!("Failed to back up the library database: {}", err),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "roqtune-db-recovery-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir.join("roqtune.db")
+    }
+
+    #[test]
+    fn test_repair_flag_round_trips() {
+        for repair in [
+            DatabaseRepair::RestoreBackup,
+            DatabaseRepair::RebuildFromFiles,
+        ] {
+            assert_eq!(DatabaseRepair::from_arg(&repair.to_arg()), Some(repair));
+        }
+        assert_eq!(DatabaseRepair::from_arg("--safe-mode"), None);
+    }
+
+    #[test]
+    fn test_check_database_reports_missing_healthy_and_damaged_files() {
+        let db_path = temp_db_path("check");
+        assert_eq!(check_database(&db_path), DatabaseHealth::Missing);
+        drop(DbManager::open_at(&db_path).expect("create database"));
+        assert_eq!(check_database(&db_path), DatabaseHealth::Healthy);
+        fs::write(&db_path, b"not a database at all, just some bytes").expect("overwrite");
+        assert!(matches!(
+            check_database(&db_path),
+            DatabaseHealth::Damaged { .. }
+        ));
+    }
+
+    #[test]
+    fn test_daily_backups_are_taken_once_a_day_and_pruned() {
+        let db_path = temp_db_path("backups");
+        drop(DbManager::open_at(&db_path).expect("create database"));
+        let day = BACKUP_INTERVAL.as_secs();
+        let first = back_up_database_if_due(&db_path, day).expect("first backup");
+        assert!(first.is_some());
+        assert_eq!(back_up_database_if_due(&db_path, day + 60), Ok(None));
+        for offset in 2..=5 {
+            back_up_database_if_due(&db_path, day * offset).expect("later backup");
+        }
+        let taken: Vec<u64> = list_backups(&db_path)
+            .into_iter()
+            .map(|(taken, _)| taken)
+            .collect();
+        assert_eq!(taken, [day * 5, day * 4, day * 3]);
+    }
+
+    #[test]
+    fn test_rebuild_keeps_playlists_from_the_damaged_file() {
+        let db_path = temp_db_path("rebuild");
+        {
+            let db = DbManager::open_at(&db_path).expect("create database");
+            db.create_playlist("road-trip", "Road Trip")
+                .expect("create playlist");
+        }
+        let report = repair_database(&db_path, DatabaseRepair::RebuildFromFiles).expect("rebuild");
+        assert!(report.message.contains("Recovered 2 playlist(s)"));
+        let conn = Connection::open(&db_path).expect("open rebuilt database");
+        let mut stmt = conn
+            .prepare("SELECT name FROM playlists ORDER BY name")
+            .expect("prepare playlist query");
+        let names: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .expect("list playlists");
+        assert_eq!(names, ["Default", "Road Trip"]);
+        assert!(db_path
+            .parent()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .is_some_and(|entries| entries.filter_map(Result::ok).any(|entry| entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("roqtune.db.damaged-"))));
+    }
+
+    #[test]
+    fn test_restore_without_backup_leaves_database_in_place() {
+        let db_path = temp_db_path("restore");
+        drop(DbManager::open_at(&db_path).expect("create database"));
+        assert!(repair_database(&db_path, DatabaseRepair::RestoreBackup).is_err());
+        assert!(db_path.exists());
+    }
+}
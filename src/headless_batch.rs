@@ -0,0 +1,604 @@
+//! Headless batch mode: library operations run from the command line without the UI.
+//!
+//! `roqtune --batch <command> [args]` runs one operation against the database and config
+//! used by the desktop app, then exits. Progress goes to stdout as JSON lines, one object
+//! per event with an `event` field; the last line is always `done` or `error`. Logging
+//! stays on stderr so stdout can be piped straight into another tool.
+//!
+//! Commands:
+//! - `scan <folder>`: adds the folder to the library folders and scans the library.
+//! - `replaygain`: measures loudness and true peak of every local library track.
+//! - `export-m3u <dir>`: writes every playlist to `<dir>/<playlist name>.m3u8`.
+//! - `verify-flac`: checks every FLAC file in the library for integrity issues.
+//! - `export-schema <file>`: writes the JSON Schema of the external wire protocol.
+//! - `upgrade-recording <input> <output>`: rewrites a recording of wire messages, one
+//!   envelope per line, in the current protocol version.
+//!
+//! Scans run through the regular [`LibraryManager`] on a private bus, and the other
+//! commands reuse the analysis and integrity helpers of the background services.
+
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    config::Config,
+    config_persistence::persist_config_file,
+    db_manager::DbManager,
+    db_read_pool::{DbReadPool, LIBRARY_READ_POOL_SIZE},
+    dsp_chain, integrity_check,
+    library_manager::LibraryManager,
+    loudness_analysis,
+    protocol::{LibraryMessage, Message},
+    protocol_wire, worker_pool,
+};
+
+/// Command-line flag that switches the binary into batch mode.
+pub const BATCH_FLAG: &str = "--batch";
+/// Files analyzed or verified between two progress events.
+const PROGRESS_CHUNK_SIZE: usize = 32;
+
+const USAGE: &str = "usage: roqtune --batch <scan <folder> | replaygain | export-m3u <dir> \
+     | verify-flac | export-schema <file> | upgrade-recording <input> <output>>";
+
+/// One batch operation requested on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchCommand {
+    Scan { folder: PathBuf },
+    ReplayGain,
+    ExportM3u { output_dir: PathBuf },
+    VerifyFlac,
+    ExportSchema { output: PathBuf },
+    UpgradeRecording { input: PathBuf, output: PathBuf },
+}
+
+/// Parses process arguments (without the program name).
+///
+/// Returns `None` when batch mode was not requested, so the desktop app starts normally.
+pub fn parse_batch_args(args: &[String]) -> Option<Result<BatchCommand, String>> {
+    let flag_index = args.iter().position(|arg| arg == BATCH_FLAG)?;
+    let rest = &args[flag_index + 1..];
+    let command = match rest {
+        [command, folder] if command == "scan" => Ok(BatchCommand::Scan {
+            folder: PathBuf::from(folder),
+        }),
+        [command] if command == "replaygain" => Ok(BatchCommand::ReplayGain),
+        [command, output_dir] if command == "export-m3u" => Ok(BatchCommand::ExportM3u {
+            output_dir: PathBuf::from(output_dir),
+        }),
+        [command] if command == "verify-flac" => Ok(BatchCommand::VerifyFlac),
+        [command, output] if command == "export-schema" => Ok(BatchCommand::ExportSchema {
+            output: PathBuf::from(output),
+        }),
+        [command, input, output] if command == "upgrade-recording" => {
+            Ok(BatchCommand::UpgradeRecording {
+                input: PathBuf::from(input),
+                output: PathBuf::from(output),
+            })
+        }
+        _ => Err(USAGE.to_string()),
+    };
+    Some(command)
+}
+
+/// Runs `command` to completion and returns the process exit code.
+pub fn run(command: BatchCommand) -> i32 {
+    let result = match command {
+        BatchCommand::Scan { folder } => run_scan(&folder),
+        BatchCommand::ReplayGain => run_replaygain(),
+        BatchCommand::ExportM3u { output_dir } => run_export_m3u(&output_dir),
+        BatchCommand::VerifyFlac => run_verify_flac(),
+        BatchCommand::ExportSchema { output } => run_export_schema(&output),
+        BatchCommand::UpgradeRecording { input, output } => run_upgrade_recording(&input, &output),
+    };
+    match result {
+        Ok(summary) => {
+            let mut event = json!({ "event": "done" });
+            if let (Value::Object(event), Value::Object(summary)) = (&mut event, summary) {
+                event.extend(summary);
+            }
+            emit(event);
+            0
+        }
+        Err(message) => {
+            emit(json!({ "event": "error", "message": message }));
+            1
+        }
+    }
+}
+
+fn emit(event: Value) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{event}");
+    let _ = stdout.flush();
+}
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn config_file_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join("roqtune").join("config.toml"))
+        .ok_or_else(|| "Could not resolve the config directory".to_string())
+}
+
+fn load_config(path: &Path) -> Config {
+    let config = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str::<Config>(&text).ok())
+        .unwrap_or_default();
+    crate::sanitize_config(config)
+}
+
+fn open_db() -> Result<DbManager, String> {
+    DbManager::new().map_err(|err| format!("Failed to open the library database: {err}"))
+}
+
+/// Local library paths, skipping remote tracks.
+fn local_library_paths(db_manager: &DbManager) -> Result<Vec<PathBuf>, String> {
+    let mut paths: Vec<PathBuf> = db_manager
+        .get_library_scan_states_by_path()
+        .map_err(|err| format!("Failed to read library tracks: {err}"))?
+        .into_keys()
+        .filter(|path| !path.contains("://"))
+        .map(PathBuf::from)
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn run_scan(folder: &Path) -> Result<Value, String> {
+    let folder = folder
+        .canonicalize()
+        .map_err(|err| format!("Cannot open {}: {err}", folder.display()))?;
+    if !folder.is_dir() {
+        return Err(format!("{} is not a folder", folder.display()));
+    }
+    let folder_text = folder.to_string_lossy().to_string();
+
+    let config_file = config_file_path()?;
+    let mut config = load_config(&config_file);
+    if !config
+        .library
+        .folders
+        .iter()
+        .any(|existing| existing == &folder_text)
+    {
+        config.library.folders.push(folder_text.clone());
+        config = crate::sanitize_config(config);
+        if let Some(config_root) = config_file.parent() {
+            std::fs::create_dir_all(config_root)
+                .map_err(|err| format!("Failed to create {}: {err}", config_root.display()))?;
+        }
+        persist_config_file(&config, &config_file);
+        emit(json!({ "event": "folder_added", "folder": folder_text }));
+    }
+
+    let db_manager = open_db()?;
+    let read_pool = DbReadPool::new(LIBRARY_READ_POOL_SIZE).ok();
+    let (bus_sender, _) = broadcast::channel(8192);
+    let mut bus_receiver = bus_sender.subscribe();
+    let (scan_progress_tx, scan_progress_rx) = mpsc::sync_channel(512);
+    let mut library_manager = LibraryManager::new(
+        bus_sender.subscribe(),
+        bus_sender.clone(),
+        db_manager,
+        read_pool,
+        scan_progress_tx,
+        config.library.clone(),
+    );
+    std::thread::Builder::new()
+        .name("batch-library".to_string())
+        .spawn(move || library_manager.run())
+        .map_err(|err| format!("Failed to start the library scanner: {err}"))?;
+
+    // Scans run synchronously on the manager thread, so the root counts requested
+    // behind the scan arrive once the scan and metadata backfill are finished.
+    let _ = bus_sender.send(Message::Library(LibraryMessage::RequestScan));
+    let _ = bus_sender.send(Message::Library(LibraryMessage::RequestRootCounts));
+
+    let mut scan_error = None;
+    loop {
+        let message = match bus_receiver.blocking_recv() {
+            Ok(message) => message,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return Err("Library scanner stopped".to_string()),
+        };
+        match message {
+            Message::Library(LibraryMessage::DrainScanProgressQueue) => {
+                while let Ok(progress) = scan_progress_rx.try_recv() {
+                    if let LibraryMessage::ScanFailed(error_text) = &progress {
+                        scan_error = Some(error_text.clone());
+                    }
+                    if let Some(event) = scan_progress_event(&progress) {
+                        emit(event);
+                    }
+                }
+            }
+            Message::Library(LibraryMessage::ScanFailed(error_text)) => {
+                emit(json!({ "event": "scan_failed", "message": error_text }));
+                scan_error = Some(error_text);
+            }
+            Message::Library(LibraryMessage::RootCountsResult {
+                tracks,
+                artists,
+                albums,
+                genres,
+                ..
+            }) => {
+                if let Some(error_text) = scan_error {
+                    return Err(error_text);
+                }
+                return Ok(json!({
+                    "tracks": tracks,
+                    "artists": artists,
+                    "albums": albums,
+                    "genres": genres,
+                }));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn scan_progress_event(message: &LibraryMessage) -> Option<Value> {
+    let event = match message {
+        LibraryMessage::ScanStarted => json!({ "event": "scan_started" }),
+        LibraryMessage::ScanProgress {
+            discovered,
+            indexed,
+            metadata_pending,
+        } => json!({
+            "event": "scan_progress",
+            "discovered": discovered,
+            "indexed": indexed,
+            "metadata_pending": metadata_pending,
+        }),
+        LibraryMessage::ScanCompleted { indexed_tracks } => json!({
+            "event": "scan_completed",
+            "indexed_tracks": indexed_tracks,
+        }),
+        LibraryMessage::MetadataBackfillProgress { updated, remaining } => json!({
+            "event": "metadata_progress",
+            "updated": updated,
+            "remaining": remaining,
+        }),
+        LibraryMessage::ScanFailed(error_text) => json!({
+            "event": "scan_failed",
+            "message": error_text,
+        }),
+        LibraryMessage::ScanFolderAccessWarnings(warnings) => json!({
+            "event": "folder_warnings",
+            "warnings": warnings,
+        }),
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// ReplayGain 2.0 track gain for a measured integrated loudness, against the same
+/// reference the player normalizes to.
+fn replaygain_db(integrated_lufs: f32) -> f32 {
+    dsp_chain::NORMALIZATION_TARGET_LUFS - integrated_lufs
+}
+
+fn run_replaygain() -> Result<Value, String> {
+    let db_manager = open_db()?;
+    let paths = local_library_paths(&db_manager)?;
+    let total = paths.len();
+    emit(json!({ "event": "replaygain_started", "total": total }));
+
+    let mut analyzed = 0usize;
+    let mut failed = 0usize;
+    let mut processed = 0usize;
+    for chunk in paths.chunks(PROGRESS_CHUNK_SIZE) {
+        let results = worker_pool::analysis_pool().map(chunk.to_vec(), |path| {
+            let loudness = loudness_analysis::analyze_file_loudness(&path);
+            (path, loudness)
+        });
+        let analyzed_unix_ms = now_unix_ms();
+        for (path, loudness) in results {
+            match loudness {
+                Ok(loudness) => {
+                    db_manager
+                        .save_track_loudness(&path, loudness, analyzed_unix_ms)
+                        .map_err(|err| format!("Failed to save track loudness: {err}"))?;
+                    analyzed += 1;
+                    emit(json!({
+                        "event": "track_analyzed",
+                        "path": path.to_string_lossy(),
+                        "integrated_lufs": loudness.integrated_lufs,
+                        "true_peak_dbtp": loudness.true_peak_dbtp,
+                        "replaygain_db": replaygain_db(loudness.integrated_lufs),
+                    }));
+                }
+                Err(err) => {
+                    failed += 1;
+                    emit(json!({
+                        "event": "track_failed",
+                        "path": path.to_string_lossy(),
+                        "message": err,
+                    }));
+                }
+            }
+        }
+        processed += chunk.len();
+        emit(json!({ "event": "progress", "processed": processed, "total": total }));
+    }
+    Ok(json!({ "analyzed": analyzed, "failed": failed }))
+}
+
+/// File name for an exported playlist, replacing characters that are not allowed in
+/// file names on common platforms.
+fn playlist_file_name(playlist_name: &str) -> String {
+    let stem: String = playlist_name
+        .trim()
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect();
+    let stem = stem.trim_matches('.');
+    if stem.is_empty() {
+        "playlist.m3u8".to_string()
+    } else {
+        format!("{stem}.m3u8")
+    }
+}
+
+/// Extended M3U text listing `paths` in order.
+fn m3u_text(paths: &[PathBuf]) -> String {
+    let mut text = String::from("#EXTM3U\n");
+    for path in paths {
+        text.push_str(&path.to_string_lossy());
+        text.push('\n');
+    }
+    text
+}
+
+fn run_export_m3u(output_dir: &Path) -> Result<Value, String> {
+    std::fs::create_dir_all(output_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", output_dir.display()))?;
+    let db_manager = open_db()?;
+    let playlists = db_manager
+        .get_all_playlists()
+        .map_err(|err| format!("Failed to read playlists: {err}"))?;
+
+    let mut used_names = HashSet::new();
+    let mut exported = 0usize;
+    for playlist in playlists {
+        let paths: Vec<PathBuf> = db_manager
+            .get_tracks_for_playlist(&playlist.id)
+            .map_err(|err| format!("Failed to read playlist {}: {err}", playlist.name))?
+            .into_iter()
+            .map(|track| track.path)
+            .collect();
+        let mut file_name = playlist_file_name(&playlist.name);
+        let mut suffix = 2;
+        while !used_names.insert(file_name.to_lowercase()) {
+            file_name = playlist_file_name(&format!("{} ({suffix})", playlist.name));
+            suffix += 1;
+        }
+        let file_path = output_dir.join(&file_name);
+        std::fs::write(&file_path, m3u_text(&paths))
+            .map_err(|err| format!("Failed to write {}: {err}", file_path.display()))?;
+        exported += 1;
+        emit(json!({
+            "event": "playlist_exported",
+            "playlist": playlist.name,
+            "path": file_path.to_string_lossy(),
+            "tracks": paths.len(),
+        }));
+    }
+    Ok(json!({ "exported": exported }))
+}
+
+fn is_flac_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("flac"))
+}
+
+fn run_verify_flac() -> Result<Value, String> {
+    let db_manager = open_db()?;
+    let mut previous_records = db_manager
+        .get_file_integrity_records()
+        .map_err(|err| format!("Failed to read integrity records: {err}"))?;
+    let checks: Vec<_> = local_library_paths(&db_manager)?
+        .into_iter()
+        .filter(|path| is_flac_path(path))
+        .map(|path| {
+            let previous = previous_records.remove(&*path.to_string_lossy());
+            (path, previous)
+        })
+        .collect();
+    let total = checks.len();
+    emit(json!({ "event": "verify_started", "total": total }));
+
+    let mut issues = 0usize;
+    let mut processed = 0usize;
+    let mut remaining = checks.into_iter().peekable();
+    while remaining.peek().is_some() {
+        let batch: Vec<_> = remaining.by_ref().take(PROGRESS_CHUNK_SIZE).collect();
+        let batch_len = batch.len();
+        let now = now_unix_ms();
+        let records = worker_pool::decode_pool().map(batch, move |(path, previous)| {
+            integrity_check::check_file(&path, previous.as_ref(), now)
+        });
+        db_manager
+            .upsert_file_integrity_records(&records)
+            .map_err(|err| format!("Failed to save integrity records: {err}"))?;
+        for record in &records {
+            if let Some(kind) = record.issue_kind {
+                issues += 1;
+                emit(json!({
+                    "event": "integrity_issue",
+                    "path": record.path,
+                    "kind": integrity_check::issue_kind_label(kind),
+                    "detail": record.issue_detail,
+                }));
+            }
+        }
+        processed += batch_len;
+        emit(json!({ "event": "progress", "processed": processed, "total": total }));
+    }
+    Ok(json!({ "verified": total, "issues": issues }))
+}
+
+fn run_export_schema(output: &Path) -> Result<Value, String> {
+    let schema = serde_json::to_string_pretty(&protocol_wire::message_schema())
+        .map_err(|err| format!("Failed to serialize the schema: {err}"))?;
+    std::fs::write(output, schema)
+        .map_err(|err| format!("Failed to write {}: {err}", output.display()))?;
+    Ok(json!({
+        "path": output.to_string_lossy(),
+        "protocol_version": protocol_wire::PROTOCOL_VERSION,
+    }))
+}
+
+/// Re-encodes each recorded message in the current envelope, passing it through the
+/// bus types so the output holds exactly what the player acts on. Returns the upgraded
+/// text and the 1-based numbers of lines that could not be decoded, with the reason.
+fn upgrade_recording_text(text: &str) -> (String, Vec<(usize, String)>) {
+    let mut upgraded = String::new();
+    let mut skipped = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = protocol_wire::decode(line).and_then(|message| {
+            protocol_wire::WireMessage::from_bus(&message.into_bus())
+                .ok_or_else(|| "message has no wire form".to_string())
+        });
+        match message {
+            Ok(message) => {
+                upgraded.push_str(&protocol_wire::encode(&message));
+                upgraded.push('\n');
+            }
+            Err(error) => skipped.push((index + 1, error)),
+        }
+    }
+    (upgraded, skipped)
+}
+
+fn run_upgrade_recording(input: &Path, output: &Path) -> Result<Value, String> {
+    let text = std::fs::read_to_string(input)
+        .map_err(|err| format!("Failed to read {}: {err}", input.display()))?;
+    let (upgraded, skipped) = upgrade_recording_text(&text);
+    for (line, error) in &skipped {
+        emit(json!({ "event": "skipped_line", "line": line, "error": error }));
+    }
+    std::fs::write(output, &upgraded)
+        .map_err(|err| format!("Failed to write {}: {err}", output.display()))?;
+    Ok(json!({
+        "path": output.to_string_lossy(),
+        "messages": upgraded.lines().count(),
+        "skipped": skipped.len(),
+        "protocol_version": protocol_wire::PROTOCOL_VERSION,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_batch_args_recognizes_commands() {
+        assert_eq!(parse_batch_args(&args(&[])), None);
+        assert_eq!(
+            parse_batch_args(&args(&["--batch", "scan", "/music"])),
+            Some(Ok(BatchCommand::Scan {
+                folder: PathBuf::from("/music"),
+            }))
+        );
+        assert_eq!(
+            parse_batch_args(&args(&["--batch", "export-m3u", "out"])),
+            Some(Ok(BatchCommand::ExportM3u {
+                output_dir: PathBuf::from("out"),
+            }))
+        );
+        assert_eq!(
+            parse_batch_args(&args(&["--batch", "verify-flac"])),
+            Some(Ok(BatchCommand::VerifyFlac))
+        );
+        assert_eq!(
+            parse_batch_args(&args(&["--batch", "export-schema", "schema.json"])),
+            Some(Ok(BatchCommand::ExportSchema {
+                output: PathBuf::from("schema.json"),
+            }))
+        );
+        assert_eq!(
+            parse_batch_args(&args(&[
+                "--batch",
+                "upgrade-recording",
+                "in.jsonl",
+                "out.jsonl"
+            ])),
+            Some(Ok(BatchCommand::UpgradeRecording {
+                input: PathBuf::from("in.jsonl"),
+                output: PathBuf::from("out.jsonl"),
+            }))
+        );
+        assert!(matches!(
+            parse_batch_args(&args(&["--batch", "scan"])),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_playlist_file_name_replaces_reserved_characters() {
+        assert_eq!(playlist_file_name("Rock / Metal"), "Rock _ Metal.m3u8");
+        assert_eq!(playlist_file_name("What?"), "What_.m3u8");
+        assert_eq!(playlist_file_name(" .. "), "playlist.m3u8");
+    }
+
+    #[test]
+    fn test_m3u_text_lists_paths_in_order() {
+        let text = m3u_text(&[PathBuf::from("/a/one.flac"), PathBuf::from("/b/two.mp3")]);
+
+        assert_eq!(text, "#EXTM3U\n/a/one.flac\n/b/two.mp3\n");
+        assert_eq!(replaygain_db(-14.0), -4.0);
+    }
+
+    #[test]
+    fn test_upgrade_recording_text_clamps_messages_and_reports_bad_lines() {
+        let recording = concat!(
+            r#"{"version":1,"message":{"type":"play"}}"#,
+            "\n\n",
+            r#"{"version":1,"message":{"type":"set_volume","volume":1.5}}"#,
+            "\n",
+            r#"{"type":"pause"}"#,
+            "\n",
+        );
+        let (upgraded, skipped) = upgrade_recording_text(recording);
+
+        assert_eq!(
+            upgraded,
+            concat!(
+                r#"{"version":1,"message":{"type":"play"}}"#,
+                "\n",
+                r#"{"version":1,"message":{"type":"set_volume","volume":1.0}}"#,
+                "\n",
+            )
+        );
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, 4);
+    }
+}
@@ -11,11 +11,17 @@ use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
 use zune_core::{colorspace::ColorSpace, options::DecoderOptions};
 use zune_jpeg::JpegDecoder;
 
+use crate::cache_manager;
+use crate::metrics;
+use crate::protocol::CacheKind;
+use crate::sandbox_access;
+
 const PIPELINE_VERSION: &str = "img-v1";
 const DETAIL_PREVIEW_VERSION: &str = "detail-v2";
 const DEFAULT_LIST_IMAGE_MAX_EDGE_PX: u32 = 320;
 const DEFAULT_COVER_DISK_BUDGET_BYTES: u64 = 512u64 * 1024u64 * 1024u64;
 const DEFAULT_ARTIST_DISK_BUDGET_BYTES: u64 = 256u64 * 1024u64 * 1024u64;
+const PLAYLIST_COVER_MAX_EDGE_PX: u32 = 600;
 
 static LIST_IMAGE_MAX_EDGE_PX: AtomicU32 = AtomicU32::new(DEFAULT_LIST_IMAGE_MAX_EDGE_PX);
 static COVER_DISK_BUDGET_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_COVER_DISK_BUDGET_BYTES);
@@ -49,17 +55,12 @@ pub fn mb_to_bytes(value_mb: u32) -> u64 {
     u64::from(value_mb.max(1)) * 1024u64 * 1024u64
 }
 
-fn roqtune_cache_root() -> Option<PathBuf> {
-    dirs::cache_dir().map(|path| path.join("roqtune"))
-}
-
 pub fn kind_cache_root(kind: ManagedImageKind) -> Option<PathBuf> {
-    match kind {
-        ManagedImageKind::CoverArt => roqtune_cache_root().map(|path| path.join("covers")),
-        ManagedImageKind::ArtistImage => {
-            roqtune_cache_root().map(|path| path.join("library_enrichment"))
-        }
-    }
+    let cache_kind = match kind {
+        ManagedImageKind::CoverArt => CacheKind::CoverArt,
+        ManagedImageKind::ArtistImage => CacheKind::ArtistImages,
+    };
+    Some(cache_manager::cache_dir(cache_kind))
 }
 
 pub fn cover_originals_dir() -> Option<PathBuf> {
@@ -210,10 +211,8 @@ fn decode_image_from_memory_with_fallback(bytes: &[u8]) -> Option<DynamicImage>
 }
 
 fn decode_image_from_path_with_fallback(path: &Path) -> Option<DynamicImage> {
-    image::open(path).ok().or_else(|| {
-        let bytes = fs::read(path).ok()?;
-        decode_image_from_memory_with_fallback(&bytes)
-    })
+    let bytes = sandbox_access::read_file(path).ok()?;
+    decode_image_from_memory_with_fallback(&bytes)
 }
 
 fn image_dimensions_with_fallback(path: &Path) -> Option<(u32, u32)> {
@@ -327,8 +326,16 @@ pub fn ensure_list_thumbnail(
     max_edge_px: u32,
 ) -> Option<PathBuf> {
     if let Some(existing) = list_thumbnail_path_if_present(kind, source_path, max_edge_px) {
+        metrics::increment(
+            metrics::CACHE_LOOKUPS_TOTAL,
+            &[("cache", "list_thumbnail"), ("result", "hit")],
+        );
         return Some(existing);
     }
+    metrics::increment(
+        metrics::CACHE_LOOKUPS_TOTAL,
+        &[("cache", "list_thumbnail"), ("result", "miss")],
+    );
     let decoded = decode_image_from_path_with_fallback(source_path)?;
     let (source_width, source_height) = decoded.dimensions();
     let (target_width, target_height) = fit_to_max_edge(source_width, source_height, max_edge_px);
@@ -413,6 +420,96 @@ pub fn ensure_detail_preview_with_threshold(
     Some(target_path)
 }
 
+/// Folder of custom playlist covers. These are user data rather than cache, so they live
+/// in the data directory and are never pruned.
+pub fn playlist_covers_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|path| path.join("roqtune").join("playlist_covers"))
+}
+
+/// Copies `source_path` into the playlist cover folder as a PNG. The name depends on the
+/// source file, so choosing a new image never reuses a path the UI has already loaded.
+pub fn store_playlist_cover(playlist_id: &str, source_path: &Path) -> Option<PathBuf> {
+    let decoded = decode_image_from_path_with_fallback(source_path)?;
+    let (source_width, source_height) = decoded.dimensions();
+    let (target_width, target_height) =
+        fit_to_max_edge(source_width, source_height, PLAYLIST_COVER_MAX_EDGE_PX);
+    let resized = if target_width == source_width && target_height == source_height {
+        decoded
+    } else {
+        decoded.resize(target_width, target_height, FilterType::Lanczos3)
+    };
+    let stem = hash_string(&format!(
+        "{PIPELINE_VERSION}|playlist-cover|{playlist_id}|{}",
+        source_fingerprint(source_path)
+    ));
+    let target_path = playlist_covers_dir()?.join(format!("{stem}.png"));
+    ensure_parent_dir(&target_path)?;
+    let temp_path = target_path.with_extension("png.tmp");
+    save_png_atomic(&resized, &temp_path, &target_path)?;
+    Some(target_path)
+}
+
+/// Deletes a cover written by [`store_playlist_cover`]; other files are left alone.
+pub fn remove_playlist_cover(path: &Path) {
+    let Some(covers_dir) = playlist_covers_dir() else {
+        return;
+    };
+    if path.parent() == Some(covers_dir.as_path()) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Tiles the first four covers into a square 2x2 collage. With fewer covers the first
+/// one fills the square on its own.
+fn compose_collage(covers: &[DynamicImage], edge_px: u32) -> Option<DynamicImage> {
+    match covers {
+        [] => None,
+        [first, second, third, fourth, ..] => {
+            let half = (edge_px / 2).max(1);
+            let mut canvas = image::RgbaImage::new(half * 2, half * 2);
+            for (index, cover) in [first, second, third, fourth].into_iter().enumerate() {
+                let tile = cover
+                    .resize_to_fill(half, half, FilterType::Triangle)
+                    .to_rgba8();
+                let x = i64::from(half) * (index % 2) as i64;
+                let y = i64::from(half) * (index / 2) as i64;
+                image::imageops::replace(&mut canvas, &tile, x, y);
+            }
+            Some(DynamicImage::ImageRgba8(canvas))
+        }
+        [first, ..] => {
+            Some(first.resize_to_fill(edge_px.max(1), edge_px.max(1), FilterType::Triangle))
+        }
+    }
+}
+
+/// Returns the collage of `cover_paths` from the cover cache, building it when the
+/// covers changed since it was last made.
+pub fn ensure_playlist_collage(cover_paths: &[PathBuf], edge_px: u32) -> Option<PathBuf> {
+    let sources = cover_paths
+        .iter()
+        .map(|path| source_fingerprint(path))
+        .collect::<Vec<_>>()
+        .join("|");
+    let stem = hash_string(&format!("{PIPELINE_VERSION}|collage|{sources}|{edge_px}"));
+    let target_path = kind_cache_root(ManagedImageKind::CoverArt)?
+        .join("collage")
+        .join(edge_px.to_string())
+        .join(format!("{stem}.png"));
+    if target_path.exists() && image_is_decodable(&target_path) {
+        return Some(target_path);
+    }
+    let covers: Vec<DynamicImage> = cover_paths
+        .iter()
+        .filter_map(|path| decode_image_from_path_with_fallback(path))
+        .collect();
+    let collage = compose_collage(&covers, edge_px)?;
+    ensure_parent_dir(&target_path)?;
+    let temp_path = target_path.with_extension("png.tmp");
+    save_png_atomic(&collage, &temp_path, &target_path)?;
+    Some(target_path)
+}
+
 pub fn decoded_rgba_bytes(path: &Path) -> Option<u64> {
     let (width, height) = image_dimensions_with_fallback(path)?;
     Some(u64::from(width) * u64::from(height) * 4u64)
@@ -453,7 +550,19 @@ pub fn prune_kind_disk_cache(kind: ManagedImageKind, max_size_bytes: u64) -> Vec
     let Some(root) = kind_cache_root(kind) else {
         return Vec::new();
     };
-    let mut files = list_files_recursive(&root);
+    prune_dir_to_budget(&root, max_size_bytes)
+}
+
+/// Total size in bytes and number of files below `root`.
+pub fn dir_usage(root: &Path) -> (u64, u64) {
+    let files = list_files_recursive(root);
+    let size_bytes = files.iter().map(|(_, size, _)| *size).sum::<u64>();
+    (size_bytes, files.len() as u64)
+}
+
+/// Deletes the least recently modified files below `root` until it fits `max_size_bytes`.
+pub fn prune_dir_to_budget(root: &Path, max_size_bytes: u64) -> Vec<PathBuf> {
+    let mut files = list_files_recursive(root);
     if files.is_empty() {
         return Vec::new();
     }
@@ -481,8 +590,13 @@ pub fn clear_kind_disk_cache(kind: ManagedImageKind) -> usize {
     let Some(root) = kind_cache_root(kind) else {
         return 0;
     };
+    clear_dir_files(&root)
+}
+
+/// Deletes every file below `root` and returns how many were removed.
+pub fn clear_dir_files(root: &Path) -> usize {
     let mut deleted = 0usize;
-    for (path, _, _) in list_files_recursive(&root) {
+    for (path, _, _) in list_files_recursive(root) {
         if fs::remove_file(path).is_ok() {
             deleted = deleted.saturating_add(1);
         }
@@ -493,8 +607,8 @@ pub fn clear_kind_disk_cache(kind: ManagedImageKind) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{
-        decode_image_from_memory_with_fallback, fit_to_max_edge, hash_string, mb_to_bytes,
-        resize_for_detail_display,
+        compose_collage, decode_image_from_memory_with_fallback, fit_to_max_edge, hash_string,
+        mb_to_bytes, resize_for_detail_display,
     };
     use image::{
         codecs::jpeg::JpegEncoder, DynamicImage, GenericImageView, ImageBuffer, ImageFormat, Rgb,
@@ -534,6 +648,25 @@ mod tests {
         assert_eq!(resized.dimensions(), (384, 314));
     }
 
+    #[test]
+    fn test_compose_collage_tiles_four_covers_and_falls_back_to_first() {
+        let solid = |red: u8| {
+            DynamicImage::ImageRgba8(ImageBuffer::from_pixel(30, 20, Rgba([red, 0, 0, 255])))
+        };
+        let covers = [solid(10), solid(20), solid(30), solid(40)];
+        let collage = compose_collage(&covers, 64).expect("collage");
+        assert_eq!(collage.dimensions(), (64, 64));
+        assert_eq!(collage.get_pixel(5, 5)[0], 10);
+        assert_eq!(collage.get_pixel(40, 5)[0], 20);
+        assert_eq!(collage.get_pixel(5, 40)[0], 30);
+        assert_eq!(collage.get_pixel(40, 40)[0], 40);
+
+        let single = compose_collage(&covers[..2], 64).expect("single cover");
+        assert_eq!(single.dimensions(), (64, 64));
+        assert_eq!(single.get_pixel(40, 40)[0], 10);
+        assert!(compose_collage(&[], 64).is_none());
+    }
+
     #[test]
     fn test_decode_image_from_memory_with_fallback_decodes_jpeg_bytes() {
         let rgb = RgbImage::from_pixel(12, 9, Rgb([90, 140, 210]));
@@ -3,9 +3,10 @@
 use keyring::Entry;
 
 const OPENSUBSONIC_SERVICE_NAME: &str = "roqtune.backend.opensubsonic";
+const WEBDAV_SERVICE_NAME: &str = "roqtune.backend.webdav";
 
-fn opensubsonic_entry(profile_id: &str) -> Result<Entry, String> {
-    Entry::new(OPENSUBSONIC_SERVICE_NAME, profile_id)
+fn backend_entry(service_name: &str, profile_id: &str) -> Result<Entry, String> {
+    Entry::new(service_name, profile_id)
         .map_err(|err| format!("failed to create keyring entry for profile '{profile_id}': {err}"))
 }
 
@@ -29,7 +30,7 @@ fn format_keyring_error(operation: &str, profile_id: &str, error: &str) -> Strin
 
 /// Saves the OpenSubsonic password for a profile into the OS keyring.
 pub fn set_opensubsonic_password(profile_id: &str, password: &str) -> Result<(), String> {
-    let entry = opensubsonic_entry(profile_id)?;
+    let entry = backend_entry(OPENSUBSONIC_SERVICE_NAME, profile_id)?;
     entry.set_password(password).map_err(|err| {
         let detail = format!("failed to set keyring password: {err}");
         format_keyring_error("save OpenSubsonic credential", profile_id, detail.as_str())
@@ -38,7 +39,7 @@ pub fn set_opensubsonic_password(profile_id: &str, password: &str) -> Result<(),
 
 /// Loads the OpenSubsonic password for a profile from the OS keyring.
 pub fn get_opensubsonic_password(profile_id: &str) -> Result<Option<String>, String> {
-    let entry = opensubsonic_entry(profile_id)?;
+    let entry = backend_entry(OPENSUBSONIC_SERVICE_NAME, profile_id)?;
     match entry.get_password() {
         Ok(password) => Ok(Some(password)),
         Err(keyring::Error::NoEntry) => Ok(None),
@@ -52,3 +53,29 @@ pub fn get_opensubsonic_password(profile_id: &str) -> Result<Option<String>, Str
         }
     }
 }
+
+/// Saves the WebDAV password for a profile into the OS keyring.
+pub fn set_webdav_password(profile_id: &str, password: &str) -> Result<(), String> {
+    let entry = backend_entry(WEBDAV_SERVICE_NAME, profile_id)?;
+    entry.set_password(password).map_err(|err| {
+        let detail = format!("failed to set keyring password: {err}");
+        format_keyring_error("save WebDAV credential", profile_id, detail.as_str())
+    })
+}
+
+/// Loads the WebDAV password for a profile from the OS keyring.
+pub fn get_webdav_password(profile_id: &str) -> Result<Option<String>, String> {
+    let entry = backend_entry(WEBDAV_SERVICE_NAME, profile_id)?;
+    match entry.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => {
+            let detail = format!("failed to get keyring password: {err}");
+            Err(format_keyring_error(
+                "load WebDAV credential",
+                profile_id,
+                detail.as_str(),
+            ))
+        }
+    }
+}
@@ -4,18 +4,27 @@
 //! and remote sync output (library tracks + playlists).
 
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread;
 
-use log::{debug, warn};
+use log::{debug, info, warn};
 use tokio::sync::broadcast::{Receiver, Sender};
 
+use crate::activity_log;
+use crate::backends::mock::MockBackendAdapter;
 use crate::backends::opensubsonic::OpenSubsonicAdapter;
+use crate::backends::webdav::WebDavAdapter;
 use crate::backends::{BackendProfileAuth, MediaBackendAdapter};
-use crate::integration_uri::encode_opensubsonic_track_uri;
+use crate::config::MockBackendConfig;
+use crate::integration_uri::{encode_opensubsonic_track_uri, encode_webdav_track_uri};
+use crate::playlist_materialize::{download_remote_track, offline_cache_root};
+use crate::playlist_url::fetch_playlist_url;
 use crate::protocol::{
-    BackendConnectionState, BackendKind, BackendProfileSnapshot, BackendSnapshot,
+    ActivityCategory, BackendConnectionState, BackendKind, BackendProfileSnapshot, BackendSnapshot,
     IntegrationMessage, LibraryTrack, Message, RemotePlaylistSnapshot, RemotePlaylistTrackSnapshot,
-    TrackMetadataSummary,
+    TaskKind, TrackMetadataSummary,
 };
+use crate::task_manager::TaskHandle;
 
 /// Coordinates integration profile state and snapshot fan-out over the event bus.
 pub struct IntegrationManager {
@@ -24,19 +33,45 @@ pub struct IntegrationManager {
     profiles: HashMap<String, BackendProfileSnapshot>,
     passwords: HashMap<String, String>,
     snapshot_version: u64,
-    opensubsonic_adapter: OpenSubsonicAdapter,
+    backend_adapter: Box<dyn MediaBackendAdapter>,
+    webdav_adapter: WebDavAdapter,
 }
 
 impl IntegrationManager {
     /// Creates a manager bound to bus channels.
-    pub fn new(bus_consumer: Receiver<Message>, bus_producer: Sender<Message>) -> Self {
+    ///
+    /// `mock_backend` swaps the OpenSubsonic adapter for the developer fake server.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        mock_backend: Option<MockBackendConfig>,
+    ) -> Self {
+        let backend_adapter: Box<dyn MediaBackendAdapter> = match mock_backend {
+            Some(mock_config) => {
+                info!(
+                    "IntegrationManager: using mock backend (latency {} ms, failure rate {}%, unavailable {})",
+                    mock_config.latency_ms, mock_config.failure_rate_percent, mock_config.unavailable
+                );
+                Box::new(MockBackendAdapter::new(mock_config))
+            }
+            None => Box::new(OpenSubsonicAdapter::new()),
+        };
         Self {
             bus_consumer,
             bus_producer,
             profiles: HashMap::new(),
             passwords: HashMap::new(),
             snapshot_version: 0,
-            opensubsonic_adapter: OpenSubsonicAdapter::new(),
+            backend_adapter,
+            webdav_adapter: WebDavAdapter::new(),
+        }
+    }
+
+    /// Returns the adapter serving profiles of `backend_kind`.
+    fn adapter_for(&self, backend_kind: BackendKind) -> &dyn MediaBackendAdapter {
+        match backend_kind {
+            BackendKind::WebDav => &self.webdav_adapter,
+            BackendKind::OpenSubsonic | BackendKind::LocalFs => self.backend_adapter.as_ref(),
         }
     }
 
@@ -53,6 +88,26 @@ impl IntegrationManager {
         ));
     }
 
+    fn profile_display_name(&self, profile_id: &str) -> String {
+        self.profiles
+            .get(profile_id)
+            .map(|profile| profile.display_name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| profile_id.to_string())
+    }
+
+    fn record_sync_failure(&self, profile_id: &str, error: &str) {
+        activity_log::record(
+            &self.bus_producer,
+            ActivityCategory::Sync,
+            format!(
+                "Sync of profile \"{}\" failed: {}",
+                self.profile_display_name(profile_id),
+                error
+            ),
+        );
+    }
+
     fn profile_auth(&self, profile_id: &str) -> Result<BackendProfileAuth, String> {
         let profile = self
             .profiles
@@ -64,7 +119,7 @@ impl IntegrationManager {
             .cloned()
             .ok_or_else(|| {
                 format!(
-                    "missing cached OpenSubsonic credential for profile '{}'. Save credentials in Settings and reconnect.",
+                    "missing cached credential for profile '{}'. Save credentials in Settings and reconnect.",
                     profile_id
                 )
             })?;
@@ -97,7 +152,10 @@ impl IntegrationManager {
         let removed_profile = self.profiles.remove(profile_id);
         self.passwords.remove(profile_id);
         if let Some(profile) = removed_profile {
-            if profile.backend_kind == BackendKind::OpenSubsonic {
+            if matches!(
+                profile.backend_kind,
+                BackendKind::OpenSubsonic | BackendKind::WebDav
+            ) {
                 let _ = self.bus_producer.send(Message::Integration(
                     IntegrationMessage::OpenSubsonicLibraryTracksUpdated {
                         profile_id: profile_id.to_string(),
@@ -154,7 +212,8 @@ impl IntegrationManager {
         profile_id: &str,
         auth: &BackendProfileAuth,
     ) -> Result<(), String> {
-        let tracks = self.opensubsonic_adapter.fetch_library_tracks(auth)?;
+        let tracks = self.backend_adapter.fetch_library_tracks(auth)?;
+        let track_count = tracks.len();
         let library_tracks: Vec<LibraryTrack> = tracks
             .iter()
             .map(|track| LibraryTrack {
@@ -174,6 +233,7 @@ impl IntegrationManager {
                 genre: track.genre.clone(),
                 year: track.year.clone(),
                 track_number: track.track_number.clone(),
+                duration_ms: track.duration_ms,
             })
             .collect();
         let _ = self.bus_producer.send(Message::Integration(
@@ -183,7 +243,7 @@ impl IntegrationManager {
             },
         ));
 
-        let favorite_tracks = self.opensubsonic_adapter.fetch_favorite_tracks(auth)?;
+        let favorite_tracks = self.backend_adapter.fetch_favorite_tracks(auth)?;
         let favorite_library_tracks: Vec<LibraryTrack> = favorite_tracks
             .iter()
             .map(|track| LibraryTrack {
@@ -203,6 +263,7 @@ impl IntegrationManager {
                 genre: track.genre.clone(),
                 year: track.year.clone(),
                 track_number: track.track_number.clone(),
+                duration_ms: track.duration_ms,
             })
             .collect();
         let _ = self.bus_producer.send(Message::Integration(
@@ -212,7 +273,8 @@ impl IntegrationManager {
             },
         ));
 
-        let playlists = self.opensubsonic_adapter.fetch_playlists(auth)?;
+        let playlists = self.backend_adapter.fetch_playlists(auth)?;
+        let playlist_count = playlists.len();
         let remote_playlists: Vec<RemotePlaylistSnapshot> = playlists
             .into_iter()
             .map(|playlist| RemotePlaylistSnapshot {
@@ -240,6 +302,10 @@ impl IntegrationManager {
                             genre: track.genre,
                             year: track.year,
                             track_number: track.track_number,
+                            composer: String::new(),
+                            disc_number: String::new(),
+                            total_tracks: String::new(),
+                            comment: String::new(),
                         },
                     })
                     .collect(),
@@ -251,15 +317,87 @@ impl IntegrationManager {
                 playlists: remote_playlists,
             },
         ));
+        activity_log::record(
+            &self.bus_producer,
+            ActivityCategory::Sync,
+            format!(
+                "Synced profile \"{}\": {} track(s), {} playlist(s)",
+                self.profile_display_name(profile_id),
+                track_count,
+                playlist_count
+            ),
+        );
+        Ok(())
+    }
+
+    fn sync_webdav_profile(
+        &mut self,
+        profile_id: &str,
+        auth: &BackendProfileAuth,
+    ) -> Result<(), String> {
+        let tracks = self.webdav_adapter.fetch_library_tracks(auth)?;
+        let track_count = tracks.len();
+        let library_tracks: Vec<LibraryTrack> = tracks
+            .into_iter()
+            .map(|track| LibraryTrack {
+                id: format!("webdav:{}:{}", profile_id, track.item_id),
+                path: encode_webdav_track_uri(
+                    &auth.profile_id,
+                    &track.item_id,
+                    &auth.endpoint,
+                    &auth.username,
+                )
+                .into(),
+                title: track.title,
+                album_artist: track.artist.clone(),
+                artist: track.artist,
+                album: track.album,
+                genre: track.genre,
+                year: track.year,
+                track_number: track.track_number,
+                duration_ms: track.duration_ms,
+            })
+            .collect();
+        let _ = self.bus_producer.send(Message::Integration(
+            IntegrationMessage::OpenSubsonicLibraryTracksUpdated {
+                profile_id: profile_id.to_string(),
+                tracks: library_tracks,
+            },
+        ));
+        activity_log::record(
+            &self.bus_producer,
+            ActivityCategory::Sync,
+            format!(
+                "Synced profile \"{}\": {} track(s)",
+                self.profile_display_name(profile_id),
+                track_count
+            ),
+        );
         Ok(())
     }
 
+    /// Pulls remote content for a profile using the sync flow of its backend kind.
+    fn sync_remote_profile(
+        &mut self,
+        profile_id: &str,
+        auth: &BackendProfileAuth,
+    ) -> Result<(), String> {
+        let backend_kind = self
+            .profiles
+            .get(profile_id)
+            .map(|profile| profile.backend_kind);
+        match backend_kind {
+            Some(BackendKind::WebDav) => self.sync_webdav_profile(profile_id, auth),
+            _ => self.sync_opensubsonic_profile(profile_id, auth),
+        }
+    }
+
     fn connect_profile(&mut self, profile_id: &str) {
         let Some(profile) = self.profiles.get(profile_id).cloned() else {
             return;
         };
         match profile.backend_kind {
-            BackendKind::OpenSubsonic => {
+            BackendKind::OpenSubsonic | BackendKind::WebDav => {
                 self.set_profile_connection_state(
                     profile_id,
                     BackendConnectionState::Connecting,
@@ -277,7 +415,10 @@ impl IntegrationManager {
                         return;
                     }
                 };
-                if let Err(error) = self.opensubsonic_adapter.test_connection(&auth) {
+                if let Err(error) = self
+                    .adapter_for(profile.backend_kind)
+                    .test_connection(&auth)
+                {
                     self.set_profile_connection_state(
                         profile_id,
                         BackendConnectionState::Error,
@@ -291,7 +432,8 @@ impl IntegrationManager {
                     BackendConnectionState::Connected,
                     Some("Connected".to_string()),
                 );
-                if let Err(error) = self.sync_opensubsonic_profile(profile_id, &auth) {
+                if let Err(error) = self.sync_remote_profile(profile_id, &auth) {
+                    self.record_sync_failure(profile_id, &error);
                     self.set_profile_connection_state(
                         profile_id,
                         BackendConnectionState::Error,
@@ -317,7 +459,11 @@ impl IntegrationManager {
                 return;
             }
         };
-        let result = self.opensubsonic_adapter.test_connection(&auth);
+        let backend_kind = self
+            .profiles
+            .get(profile_id)
+            .map_or(BackendKind::OpenSubsonic, |profile| profile.backend_kind);
+        let result = self.adapter_for(backend_kind).test_connection(&auth);
         match result {
             Ok(()) => {
                 self.set_profile_connection_state(
@@ -347,7 +493,10 @@ impl IntegrationManager {
             BackendConnectionState::Disconnected,
             Some("Disconnected".to_string()),
         );
-        if backend_kind == Some(BackendKind::OpenSubsonic) {
+        if matches!(
+            backend_kind,
+            Some(BackendKind::OpenSubsonic | BackendKind::WebDav)
+        ) {
             let _ = self.bus_producer.send(Message::Integration(
                 IntegrationMessage::OpenSubsonicLibraryTracksUpdated {
                     profile_id: profile_id.to_string(),
@@ -382,7 +531,8 @@ impl IntegrationManager {
                 return;
             }
         };
-        if let Err(error) = self.sync_opensubsonic_profile(profile_id, &auth) {
+        if let Err(error) = self.sync_remote_profile(profile_id, &auth) {
+            self.record_sync_failure(profile_id, &error);
             self.emit_operation_failed(Some(profile_id.to_string()), "sync", error.clone());
             self.set_profile_connection_state(
                 profile_id,
@@ -418,7 +568,7 @@ impl IntegrationManager {
                 return;
             }
         };
-        let result = self.opensubsonic_adapter.replace_playlist_tracks(
+        let result = self.backend_adapter.replace_playlist_tracks(
             &auth,
             remote_playlist_id,
             &track_song_ids,
@@ -477,7 +627,7 @@ impl IntegrationManager {
             }
         };
         match self
-            .opensubsonic_adapter
+            .backend_adapter
             .set_track_favorite(&auth, song_id, favorited)
         {
             Ok(()) => {
@@ -533,7 +683,7 @@ impl IntegrationManager {
             }
         };
         let result = self
-            .opensubsonic_adapter
+            .backend_adapter
             .create_playlist(&auth, name, &track_song_ids);
         match result {
             Ok(remote_playlist_id) => {
@@ -570,6 +720,76 @@ impl IntegrationManager {
         }
     }
 
+    /// Downloads `paths` into the offline track cache on a background thread, reporting
+    /// progress after each track. The first failure or a cancel from the Tasks panel ends
+    /// the job; tracks fetched so far stay cached, so running it again resumes where it
+    /// stopped.
+    fn materialize_remote_playlist(
+        &self,
+        local_playlist_id: String,
+        playlist_name: String,
+        paths: Vec<PathBuf>,
+    ) {
+        let bus_producer = self.bus_producer.clone();
+        let passwords = self.passwords.clone();
+        let task = TaskHandle::start(
+            &self.bus_producer,
+            TaskKind::Download,
+            format!("Downloading '{playlist_name}' for offline playback"),
+            true,
+            true,
+        );
+        thread::spawn(move || {
+            let total = paths.len();
+            let result = offline_cache_root()
+                .ok_or_else(|| "Could not find the app data directory".to_string())
+                .and_then(|cache_root| {
+                    let mut local_paths = Vec::with_capacity(total);
+                    for (index, path) in paths.into_iter().enumerate() {
+                        if !task.wait_while_paused() {
+                            return Err(format!(
+                                "Download cancelled after {} of {} tracks",
+                                index, total
+                            ));
+                        }
+                        let local_path = download_remote_track(&path, &passwords, &cache_root)
+                            .map_err(|error| {
+                                format!("Track {} of {}: {}", index + 1, total, error)
+                            })?;
+                        local_paths.push((path, local_path));
+                        task.progress(
+                            (index + 1) as u64,
+                            Some(total as u64),
+                            format!("{} of {} tracks", index + 1, total),
+                        );
+                        let _ = bus_producer.send(Message::Integration(
+                            IntegrationMessage::RemotePlaylistMaterializeProgress {
+                                local_playlist_id: local_playlist_id.clone(),
+                                playlist_name: playlist_name.clone(),
+                                completed: index + 1,
+                                total,
+                            },
+                        ));
+                    }
+                    Ok(local_paths)
+                });
+            if let Err(error) = result.as_ref() {
+                warn!(
+                    "IntegrationManager: downloading playlist '{}' failed: {}",
+                    playlist_name, error
+                );
+            }
+            task.finish(result.as_ref().map(|_| ()).map_err(String::clone));
+            let _ = bus_producer.send(Message::Integration(
+                IntegrationMessage::RemotePlaylistMaterialized {
+                    local_playlist_id,
+                    playlist_name,
+                    result,
+                },
+            ));
+        });
+    }
+
     /// Starts the blocking event loop.
     pub fn run(&mut self) {
         loop {
@@ -645,6 +865,29 @@ impl IntegrationManager {
                         track_song_ids,
                     );
                 }
+                Ok(Message::Integration(IntegrationMessage::FetchPlaylistUrl {
+                    local_playlist_id,
+                    url,
+                })) => {
+                    let result = fetch_playlist_url(&url);
+                    if let Err(error) = result.as_ref() {
+                        warn!("IntegrationManager: playlist URL fetch failed for {url}: {error}");
+                    }
+                    let _ = self.bus_producer.send(Message::Integration(
+                        IntegrationMessage::PlaylistUrlFetched {
+                            local_playlist_id,
+                            url,
+                            result,
+                        },
+                    ));
+                }
+                Ok(Message::Integration(IntegrationMessage::MaterializeRemotePlaylist {
+                    local_playlist_id,
+                    playlist_name,
+                    paths,
+                })) => {
+                    self.materialize_remote_playlist(local_playlist_id, playlist_name, paths);
+                }
                 Ok(Message::Integration(IntegrationMessage::SetBackendConnectionState {
                     profile_id,
                     state,
@@ -672,6 +915,13 @@ impl IntegrationManager {
                 | Ok(Message::Integration(
                     IntegrationMessage::OpenSubsonicTrackFavoriteUpdateResult { .. },
                 ))
+                | Ok(Message::Integration(IntegrationMessage::PlaylistUrlFetched { .. }))
+                | Ok(Message::Integration(
+                    IntegrationMessage::RemotePlaylistMaterializeProgress { .. },
+                ))
+                | Ok(Message::Integration(IntegrationMessage::RemotePlaylistMaterialized {
+                    ..
+                }))
                 | Ok(_) => {}
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                     warn!(
@@ -709,7 +959,7 @@ mod tests {
     #[test]
     fn test_upsert_profile_emits_snapshot() {
         let (bus_sender, _) = broadcast::channel(16);
-        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone());
+        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone(), None);
         let mut observer = bus_sender.subscribe();
 
         manager.upsert_profile(test_profile("subsonic-home"), None, false);
@@ -730,7 +980,7 @@ mod tests {
     #[test]
     fn test_set_connection_state_updates_snapshot() {
         let (bus_sender, _) = broadcast::channel(16);
-        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone());
+        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone(), None);
         let mut observer = bus_sender.subscribe();
         manager.upsert_profile(test_profile("subsonic-home"), None, false);
         let _ = observer.try_recv();
@@ -762,7 +1012,7 @@ mod tests {
     #[test]
     fn test_remove_profile_emits_snapshot_only_when_profile_exists() {
         let (bus_sender, _) = broadcast::channel(16);
-        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone());
+        let mut manager = IntegrationManager::new(bus_sender.subscribe(), bus_sender.clone(), None);
         let mut observer = bus_sender.subscribe();
         manager.upsert_profile(test_profile("subsonic-home"), None, false);
         let _ = observer.try_recv();
@@ -2,6 +2,8 @@
 
 use std::path::Path;
 
+use crate::playlist_url::is_web_track_path;
+
 /// Decoded OpenSubsonic track locator encoded in a synthetic track path.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OpenSubsonicTrackLocator {
@@ -57,9 +59,81 @@ pub fn encode_opensubsonic_track_uri(
     )
 }
 
-/// Returns true if the provided path encodes a synthetic remote track URI.
+/// Decoded WebDAV track locator encoded in a synthetic track path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebDavTrackLocator {
+    pub profile_id: String,
+    /// Server href of the file, still percent-encoded.
+    pub href: String,
+    pub endpoint: String,
+    pub username: String,
+}
+
+impl WebDavTrackLocator {
+    /// Lowercase file extension of the href, used as the decoder format hint.
+    pub fn format_hint(&self) -> Option<String> {
+        let file_name = self.href.rsplit('/').next()?;
+        let (_, extension) = file_name.rsplit_once('.')?;
+        (!extension.is_empty()).then(|| extension.to_ascii_lowercase())
+    }
+}
+
+const WEBDAV_PREFIX: &str = "rtq://webdav/";
+
+/// Encodes a WebDAV track locator as a synthetic path URI.
+pub fn encode_webdav_track_uri(
+    profile_id: &str,
+    href: &str,
+    endpoint: &str,
+    username: &str,
+) -> String {
+    format!(
+        "{}{}/{}?endpoint={}&username={}",
+        WEBDAV_PREFIX,
+        urlencoding::encode(profile_id),
+        urlencoding::encode(href),
+        urlencoding::encode(&strip_trailing_slash(endpoint)),
+        urlencoding::encode(username.trim())
+    )
+}
+
+/// Parses a synthetic WebDAV track URI from a path.
+pub fn parse_webdav_track_uri(path: &Path) -> Option<WebDavTrackLocator> {
+    let rest = path.to_str()?.strip_prefix(WEBDAV_PREFIX)?;
+    let (path_part, query_part) = rest.split_once('?').unwrap_or((rest, ""));
+    let (raw_profile_id, raw_href) = path_part.split_once('/')?;
+    let profile_id = urlencoding::decode(raw_profile_id).ok()?.to_string();
+    let href = urlencoding::decode(raw_href).ok()?.to_string();
+    if profile_id.trim().is_empty() || href.trim().is_empty() {
+        return None;
+    }
+    let mut endpoint = String::new();
+    let mut username = String::new();
+    for key_value in query_part.split('&') {
+        let (key, raw_value) = key_value.split_once('=').unwrap_or((key_value, ""));
+        let decoded = urlencoding::decode(raw_value)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|_| raw_value.to_string());
+        match key {
+            "endpoint" => endpoint = strip_trailing_slash(&decoded),
+            "username" => username = decoded.trim().to_string(),
+            _ => {}
+        }
+    }
+    Some(WebDavTrackLocator {
+        profile_id,
+        href,
+        endpoint,
+        username,
+    })
+}
+
+/// Returns true if the provided path encodes a synthetic remote track URI or an http(s)
+/// web track imported from a remote playlist URL.
 pub fn is_remote_track_path(path: &Path) -> bool {
-    path.to_str().and_then(strip_opensubsonic_prefix).is_some()
+    path.to_str().is_some_and(|raw| {
+        strip_opensubsonic_prefix(raw).is_some() || raw.starts_with(WEBDAV_PREFIX)
+    }) || is_web_track_path(path)
 }
 
 /// Parses a synthetic OpenSubsonic track URI from a path.
@@ -111,7 +185,8 @@ pub fn parse_opensubsonic_track_uri(path: &Path) -> Option<OpenSubsonicTrackLoca
 #[cfg(test)]
 mod tests {
     use super::{
-        encode_opensubsonic_track_uri, is_remote_track_path, parse_opensubsonic_track_uri,
+        encode_opensubsonic_track_uri, encode_webdav_track_uri, is_remote_track_path,
+        parse_opensubsonic_track_uri, parse_webdav_track_uri,
     };
     use std::path::PathBuf;
 
@@ -148,6 +223,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_webdav_encode_decode_round_trip() {
+        let uri = encode_webdav_track_uri(
+            "webdav-default",
+            "/remote.php/dav/files/alice/Music/A%20B/01%20Song.FLAC",
+            "https://cloud.example.com/remote.php/dav/files/alice/Music/",
+            "alice",
+        );
+        let path = PathBuf::from(uri);
+        assert!(is_remote_track_path(path.as_path()));
+        assert!(parse_opensubsonic_track_uri(path.as_path()).is_none());
+        let decoded = parse_webdav_track_uri(path.as_path()).expect("encoded uri should decode");
+        assert_eq!(decoded.profile_id, "webdav-default");
+        assert_eq!(
+            decoded.href,
+            "/remote.php/dav/files/alice/Music/A%20B/01%20Song.FLAC"
+        );
+        assert_eq!(
+            decoded.endpoint,
+            "https://cloud.example.com/remote.php/dav/files/alice/Music"
+        );
+        assert_eq!(decoded.username, "alice");
+        assert_eq!(decoded.format_hint().as_deref(), Some("flac"));
+    }
+
     #[test]
     fn test_decode_accepts_legacy_uppercase_query_keys() {
         let uri = "rtq://open_subsonic/home-profile/track-001?ENDPOINT=https%3A%2F%2Fmusic.example.com&USERNAME=alice&FORMAT=FLAC";
@@ -4,3 +4,7 @@ pub(crate) mod integration_keyring;
 pub(crate) mod integration_manager;
 pub(crate) mod integration_uri;
 pub(crate) mod opensubsonic_controller;
+pub(crate) mod playlist_materialize;
+pub(crate) mod playlist_url;
+pub(crate) mod profile_share;
+pub(crate) mod webdav_controller;
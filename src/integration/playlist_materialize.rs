@@ -0,0 +1,286 @@
+//! Downloading remote playlist tracks into the offline track cache.
+//!
+//! A remote-bound playlist becomes a plain local playlist once every track has a local
+//! copy. Copies live in the offline downloads cache, one folder per backend profile, and
+//! are named after the server's track id, so materializing a second playlist reuses the
+//! files the first one already fetched. Tags travel inside the downloaded files.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backends::opensubsonic::OpenSubsonicAdapter;
+use crate::backends::{webdav, BackendProfileAuth};
+use crate::cache_manager;
+use crate::integration_uri::{parse_opensubsonic_track_uri, parse_webdav_track_uri};
+use crate::protocol::CacheKind;
+
+/// Extension used when neither the server nor the track names a format.
+const FALLBACK_EXTENSION: &str = "bin";
+
+/// Root folder of downloaded remote tracks.
+pub fn offline_cache_root() -> Option<PathBuf> {
+    Some(cache_manager::cache_dir(CacheKind::OfflineDownloads))
+}
+
+/// Longest readable part kept from a server id in a file stem.
+const MAX_STEM_CHARS: usize = 64;
+
+/// File stem for a server id. Ids that are not already plain letters, digits, `-` and
+/// `_` keep a readable prefix plus a hash, so two ids never share a file.
+pub fn cache_file_stem(remote_id: &str) -> String {
+    let is_plain = |ch: char| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_';
+    if !remote_id.is_empty() && remote_id.len() <= MAX_STEM_CHARS && remote_id.chars().all(is_plain)
+    {
+        return remote_id.to_string();
+    }
+    let readable: String = remote_id
+        .chars()
+        .map(|ch| if is_plain(ch) { ch } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .chars()
+        .take(MAX_STEM_CHARS)
+        .collect();
+    format!("{readable}_{:x}", md5::compute(remote_id))
+}
+
+/// Maps a `Content-Type` header onto a file extension.
+fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "audio/ogg" | "audio/vorbis" | "audio/opus" => Some("ogg"),
+        "audio/aac" | "audio/x-aac" => Some("aac"),
+        "audio/mp4" | "audio/x-m4a" => Some("m4a"),
+        "audio/wav" | "audio/x-wav" => Some("wav"),
+        _ => None,
+    }
+}
+
+/// Where and how to fetch one remote track.
+struct DownloadRequest {
+    profile_id: String,
+    remote_id: String,
+    url: String,
+    authorization: Option<String>,
+    format_hint: Option<String>,
+}
+
+fn download_request(
+    path: &Path,
+    passwords: &HashMap<String, String>,
+) -> Result<DownloadRequest, String> {
+    if let Some(locator) = parse_opensubsonic_track_uri(path) {
+        let password = passwords.get(&locator.profile_id).ok_or_else(|| {
+            format!(
+                "OpenSubsonic credential not cached for profile '{}'",
+                locator.profile_id
+            )
+        })?;
+        let auth = BackendProfileAuth {
+            profile_id: locator.profile_id.clone(),
+            endpoint: locator.endpoint.clone(),
+            username: locator.username.clone(),
+            password: password.clone(),
+        };
+        return Ok(DownloadRequest {
+            url: OpenSubsonicAdapter::download_url(&auth, &locator.song_id),
+            profile_id: locator.profile_id,
+            remote_id: locator.song_id,
+            authorization: None,
+            format_hint: locator.format_hint,
+        });
+    }
+    if let Some(locator) = parse_webdav_track_uri(path) {
+        let password = passwords.get(&locator.profile_id).ok_or_else(|| {
+            format!(
+                "WebDAV credential not cached for profile '{}'",
+                locator.profile_id
+            )
+        })?;
+        return Ok(DownloadRequest {
+            url: webdav::href_url(&locator.endpoint, &locator.href),
+            authorization: Some(webdav::basic_authorization(&locator.username, password)),
+            format_hint: locator.format_hint(),
+            profile_id: locator.profile_id,
+            remote_id: locator.href,
+        });
+    }
+    Err(format!("{} is not a remote library track", path.display()))
+}
+
+/// Returns an already downloaded copy named `stem.<any extension>` in `folder`.
+fn cached_copy(folder: &Path, stem: &str) -> Option<PathBuf> {
+    fs::read_dir(folder)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_stem().and_then(|value| value.to_str()) == Some(stem)
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension != "part")
+        })
+}
+
+/// Returns the copy of a remote track already downloaded into `cache_root`, without
+/// contacting the server.
+pub fn cached_remote_track(path: &Path, cache_root: &Path) -> Option<PathBuf> {
+    let (profile_id, remote_id) = match parse_opensubsonic_track_uri(path) {
+        Some(locator) => (locator.profile_id, locator.song_id),
+        None => {
+            let locator = parse_webdav_track_uri(path)?;
+            (locator.profile_id, locator.href)
+        }
+    };
+    cached_copy(
+        &cache_root.join(cache_file_stem(&profile_id)),
+        &cache_file_stem(&remote_id),
+    )
+}
+
+/// Downloads one remote track into `cache_root`, or returns the copy fetched earlier.
+///
+/// The body is written to a `.part` file first, so an interrupted download is never
+/// mistaken for a finished copy.
+pub fn download_remote_track(
+    path: &Path,
+    passwords: &HashMap<String, String>,
+    cache_root: &Path,
+) -> Result<PathBuf, String> {
+    let request = download_request(path, passwords)?;
+    let folder = cache_root.join(cache_file_stem(&request.profile_id));
+    let stem = cache_file_stem(&request.remote_id);
+    if let Some(existing) = cached_copy(&folder, &stem) {
+        return Ok(existing);
+    }
+    fs::create_dir_all(&folder)
+        .map_err(|error| format!("Failed to create {}: {error}", folder.display()))?;
+
+    let client = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(5))
+        .timeout_read(Duration::from_secs(45))
+        .build();
+    let mut http_request = client.get(&request.url);
+    if let Some(authorization) = request.authorization.as_deref() {
+        http_request = http_request.set("Authorization", authorization);
+    }
+    let response = http_request
+        .call()
+        .map_err(|error| format!("Download request failed: {error}"))?;
+    if response
+        .header("Content-Type")
+        .is_some_and(|content_type| content_type.contains("xml") || content_type.contains("json"))
+    {
+        return Err("Server answered with an error instead of the track".to_string());
+    }
+    let extension = request
+        .format_hint
+        .as_deref()
+        .map(str::trim)
+        .filter(|hint| !hint.is_empty())
+        .map(str::to_ascii_lowercase)
+        .or_else(|| {
+            response
+                .header("Content-Type")
+                .and_then(extension_from_content_type)
+                .map(ToOwned::to_owned)
+        })
+        .unwrap_or_else(|| FALLBACK_EXTENSION.to_string());
+
+    let part_path = folder.join(format!("{stem}.part"));
+    let final_path = folder.join(format!("{stem}.{extension}"));
+    let write_result = fs::File::create(&part_path)
+        .and_then(|mut file| io::copy(&mut response.into_reader(), &mut file));
+    match write_result {
+        Ok(0) => {
+            let _ = fs::remove_file(&part_path);
+            Err("Server sent an empty track".to_string())
+        }
+        Ok(_) => fs::rename(&part_path, &final_path)
+            .map(|_| final_path)
+            .map_err(|error| format!("Failed to store downloaded track: {error}")),
+        Err(error) => {
+            let _ = fs::remove_file(&part_path);
+            Err(format!("Download failed: {error}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_file_stem_keeps_ids_file_system_safe() {
+        assert_eq!(cache_file_stem("al-12_ab"), "al-12_ab");
+        assert!(
+            cache_file_stem("/Music/Artist/01 Song.flac").starts_with("Music_Artist_01_Song_flac_")
+        );
+        assert_ne!(cache_file_stem("a b"), cache_file_stem("a_b"));
+        assert_ne!(cache_file_stem("///"), cache_file_stem("..."));
+    }
+
+    #[test]
+    fn test_download_request_needs_a_remote_track_and_its_credential() {
+        let remote = PathBuf::from(crate::integration_uri::encode_opensubsonic_track_uri(
+            "home",
+            "song 7",
+            "https://music.example.com/",
+            "alice",
+            Some("flac"),
+        ));
+        let missing = download_request(&remote, &HashMap::new()).err();
+        assert!(missing.is_some_and(|error| error.contains("credential not cached")));
+
+        let passwords = HashMap::from([("home".to_string(), "secret".to_string())]);
+        let request = download_request(&remote, &passwords).ok();
+        assert!(request.as_ref().is_some_and(|request| {
+            request.remote_id == "song 7"
+                && request.format_hint.as_deref() == Some("flac")
+                && request
+                    .url
+                    .starts_with("https://music.example.com/rest/download.view?")
+        }));
+
+        let local = download_request(Path::new("/music/local.flac"), &passwords).err();
+        assert!(local.is_some_and(|error| error.contains("not a remote library track")));
+    }
+
+    #[test]
+    fn test_cached_remote_track_finds_finished_downloads_only() {
+        let cache_root =
+            std::env::temp_dir().join(format!("roqtune_offline_cache_{}", std::process::id()));
+        let remote = PathBuf::from(crate::integration_uri::encode_opensubsonic_track_uri(
+            "home",
+            "song-7",
+            "https://music.example.com/",
+            "alice",
+            None,
+        ));
+        let folder = cache_root.join("home");
+        fs::create_dir_all(&folder).expect("create cache folder");
+        fs::write(folder.join("song-7.part"), b"partial").expect("write part file");
+        assert_eq!(cached_remote_track(&remote, &cache_root), None);
+
+        fs::write(folder.join("song-7.flac"), b"done").expect("write cached copy");
+        assert_eq!(
+            cached_remote_track(&remote, &cache_root),
+            Some(folder.join("song-7.flac"))
+        );
+        assert_eq!(
+            cached_remote_track(Path::new("/music/local.flac"), &cache_root),
+            None
+        );
+        let _ = fs::remove_dir_all(&cache_root);
+    }
+}
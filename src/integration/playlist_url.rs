@@ -0,0 +1,325 @@
+//! Remote playlist URL import: fetching and parsing http(s) M3U/PLS lists.
+//!
+//! Entries become web tracks whose path is the entry URL itself. Entries that point at an
+//! audio file (by extension) are labeled as web files; everything else is treated as a
+//! live stream.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::protocol::{RemotePlaylistSnapshot, RemotePlaylistTrackSnapshot, TrackMetadataSummary};
+
+/// How long an imported URL playlist is kept before it is fetched again.
+pub const PLAYLIST_URL_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Largest playlist body accepted from a remote URL.
+const MAX_PLAYLIST_BODY_BYTES: u64 = 4 * 1024 * 1024;
+/// Extensions that identify a finite audio file rather than a live stream.
+const AUDIO_FILE_EXTENSIONS: [&str; 13] = [
+    "mp3", "flac", "ogg", "oga", "opus", "m4a", "mp4", "aac", "wav", "aif", "aiff", "wv", "ape",
+];
+
+/// Returns true for `http://` and `https://` URLs.
+pub fn is_web_url(raw: &str) -> bool {
+    let lower = raw.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Returns true if the track path is an http(s) URL imported from a remote playlist.
+pub fn is_web_track_path(path: &Path) -> bool {
+    path.to_str().is_some_and(is_web_url)
+}
+
+fn url_without_query(url: &str) -> &str {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    &url[..end]
+}
+
+fn url_origin(url: &str) -> Option<&str> {
+    let scheme_end = url.find("://")? + 3;
+    let host_end = url[scheme_end..]
+        .find(['/', '?', '#'])
+        .map(|offset| scheme_end + offset)
+        .unwrap_or(url.len());
+    Some(&url[..host_end])
+}
+
+fn url_last_segment(url: &str) -> Option<String> {
+    let origin_len = url_origin(url)?.len();
+    let path = &url_without_query(url)[origin_len..];
+    let segment = path.rsplit('/').find(|segment| !segment.is_empty())?;
+    let decoded = urlencoding::decode(segment)
+        .map(|value| value.to_string())
+        .unwrap_or_else(|_| segment.to_string());
+    Some(decoded)
+}
+
+fn url_host(url: &str) -> String {
+    url_origin(url)
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, host)| host.to_string())
+        .unwrap_or_default()
+}
+
+/// Returns true if a web track path looks like a live stream rather than an audio file.
+pub fn is_web_stream_path(path: &Path) -> bool {
+    let Some(url) = path.to_str().filter(|url| is_web_url(url)) else {
+        return false;
+    };
+    let extension = url_last_segment(url)
+        .and_then(|segment| {
+            segment
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_ascii_lowercase())
+        })
+        .unwrap_or_default();
+    !AUDIO_FILE_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Returns the display title used for a web entry without playlist-provided metadata.
+pub fn web_track_fallback_title(path: &Path) -> String {
+    let url = path.to_string_lossy();
+    if is_web_stream_path(path) {
+        return format!("Stream: {}", url_host(&url));
+    }
+    url_last_segment(&url)
+        .map(|segment| {
+            segment
+                .rsplit_once('.')
+                .map(|(stem, _)| stem.to_string())
+                .unwrap_or(segment)
+        })
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Derives a playlist name from the URL's last path segment (without extension).
+pub fn playlist_name_from_url(url: &str) -> String {
+    url_last_segment(url)
+        .map(|segment| {
+            segment
+                .rsplit_once('.')
+                .map(|(stem, _)| stem.to_string())
+                .unwrap_or(segment)
+        })
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| url_host(url))
+}
+
+/// Resolves one playlist entry against the playlist URL.
+///
+/// Only http(s) entries are accepted; local paths and other schemes are skipped.
+fn resolve_entry_url(base_url: &str, entry: &str) -> Option<String> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return None;
+    }
+    if is_web_url(entry) {
+        return Some(entry.to_string());
+    }
+    if entry.contains("://") || entry.contains('\\') {
+        return None;
+    }
+    if let Some(rest) = entry.strip_prefix("//") {
+        let scheme = base_url.split_once("://")?.0;
+        return Some(format!("{scheme}://{rest}"));
+    }
+    if entry.starts_with('/') {
+        return Some(format!("{}{}", url_origin(base_url)?, entry));
+    }
+    let base = url_without_query(base_url);
+    let origin_len = url_origin(base)?.len();
+    let directory = match base[origin_len..].rfind('/') {
+        Some(offset) => &base[..origin_len + offset + 1],
+        None => return Some(format!("{base}/{entry}")),
+    };
+    Some(format!("{directory}{entry}"))
+}
+
+fn summary_from_entry(display: Option<&str>, url: &str) -> TrackMetadataSummary {
+    let display = display.map(str::trim).filter(|display| !display.is_empty());
+    let (artist, title) = match display {
+        Some(display) => match display.split_once(" - ") {
+            Some((artist, title)) if !artist.trim().is_empty() && !title.trim().is_empty() => {
+                (artist.trim().to_string(), title.trim().to_string())
+            }
+            _ => (String::new(), display.to_string()),
+        },
+        None => (String::new(), web_track_fallback_title(Path::new(url))),
+    };
+    TrackMetadataSummary {
+        title,
+        artist,
+        album: String::new(),
+        album_artist: String::new(),
+        date: String::new(),
+        genre: String::new(),
+        year: String::new(),
+        track_number: String::new(),
+        composer: String::new(),
+        disc_number: String::new(),
+        total_tracks: String::new(),
+        comment: String::new(),
+    }
+}
+
+fn entry_snapshot(
+    position: usize,
+    url: String,
+    display: Option<&str>,
+) -> RemotePlaylistTrackSnapshot {
+    RemotePlaylistTrackSnapshot {
+        item_id: position.to_string(),
+        summary: summary_from_entry(display, &url),
+        path: url.into(),
+    }
+}
+
+fn parse_m3u(base_url: &str, body: &str) -> Vec<RemotePlaylistTrackSnapshot> {
+    let mut entries = Vec::new();
+    let mut pending_title: Option<String> = None;
+    for line in body.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending_title = info.split_once(',').map(|(_, title)| title.to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some(url) = resolve_entry_url(base_url, line) {
+            entries.push(entry_snapshot(entries.len(), url, pending_title.as_deref()));
+        }
+        pending_title = None;
+    }
+    entries
+}
+
+fn parse_pls(base_url: &str, body: &str) -> Vec<RemotePlaylistTrackSnapshot> {
+    let mut files: BTreeMap<u32, String> = BTreeMap::new();
+    let mut titles: BTreeMap<u32, String> = BTreeMap::new();
+    for line in body.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        if let Some(index) = key.strip_prefix("file").and_then(|n| n.parse().ok()) {
+            files.insert(index, value.trim().to_string());
+        } else if let Some(index) = key.strip_prefix("title").and_then(|n| n.parse().ok()) {
+            titles.insert(index, value.trim().to_string());
+        }
+    }
+    let mut entries = Vec::new();
+    for (index, file) in files {
+        if let Some(url) = resolve_entry_url(base_url, &file) {
+            entries.push(entry_snapshot(
+                entries.len(),
+                url,
+                titles.get(&index).map(String::as_str),
+            ));
+        }
+    }
+    entries
+}
+
+/// Parses an M3U/M3U8 or PLS playlist body into web track entries.
+pub fn parse_playlist_body(base_url: &str, body: &str) -> Vec<RemotePlaylistTrackSnapshot> {
+    let is_pls = body
+        .lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.eq_ignore_ascii_case("[playlist]"));
+    if is_pls {
+        parse_pls(base_url, body)
+    } else {
+        parse_m3u(base_url, body)
+    }
+}
+
+/// Downloads and parses one remote playlist URL.
+pub fn fetch_playlist_url(url: &str) -> Result<RemotePlaylistSnapshot, String> {
+    let url = url.trim();
+    if !is_web_url(url) {
+        return Err("Playlist URL must start with http:// or https://".to_string());
+    }
+    let client = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(5))
+        .timeout_read(Duration::from_secs(20))
+        .build();
+    let response = client
+        .get(url)
+        .call()
+        .map_err(|error| format!("Playlist request failed: {error}"))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_PLAYLIST_BODY_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("Playlist body read failed: {error}"))?;
+    let body = String::from_utf8_lossy(&bytes);
+    let tracks = parse_playlist_body(url, &body);
+    if tracks.is_empty() {
+        return Err("Playlist contains no http(s) entries".to_string());
+    }
+    Ok(RemotePlaylistSnapshot {
+        remote_playlist_id: url.to_string(),
+        name: playlist_name_from_url(url),
+        tracks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_m3u_resolves_relative_entries_and_extinf_titles() {
+        let body = "#EXTM3U\n#EXTINF:215,Artist Name - Song Title\nsongs/one.mp3\n\
+                    /abs/two.flac\nhttps://radio.example.org/live\nC:\\music\\local.mp3\n";
+        let entries = parse_playlist_body("https://host.example.com/lists/mix.m3u?token=1", body);
+        let urls: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.path.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://host.example.com/lists/songs/one.mp3",
+                "https://host.example.com/abs/two.flac",
+                "https://radio.example.org/live",
+            ]
+        );
+        assert_eq!(entries[0].summary.artist, "Artist Name");
+        assert_eq!(entries[0].summary.title, "Song Title");
+        assert_eq!(entries[1].summary.title, "two");
+        assert_eq!(entries[2].summary.title, "Stream: radio.example.org");
+    }
+
+    #[test]
+    fn test_parse_pls_orders_entries_by_index() {
+        let body = "[playlist]\nFile2=http://b.example.com/stream\nTitle2=Second\n\
+                    File1=http://a.example.com/stream\nNumberOfEntries=2\n";
+        let entries = parse_playlist_body("http://lists.example.com/radio.pls", body);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].path.to_string_lossy(),
+            "http://a.example.com/stream"
+        );
+        assert_eq!(entries[1].summary.title, "Second");
+    }
+
+    #[test]
+    fn test_is_web_stream_path_distinguishes_files_from_streams() {
+        assert!(is_web_stream_path(Path::new(
+            "http://radio.example.org:8000/live"
+        )));
+        assert!(!is_web_stream_path(Path::new(
+            "https://host.example.com/a/track.FLAC?sig=abc"
+        )));
+        assert!(!is_web_stream_path(Path::new("/home/user/live")));
+    }
+}
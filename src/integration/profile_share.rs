@@ -0,0 +1,182 @@
+//! Share links for setting up a remote backend profile on another device.
+//!
+//! A link carries the backend kind, server endpoint, and username of a profile, never its
+//! password: `rtq://add-profile?backend=open_subsonic&endpoint=...&username=...`. Starting
+//! roqtune with such a link as its argument pre-fills the matching form in Settings ->
+//! Integrations, where the password is entered on the new device.
+
+use qrcode::{Color, QrCode};
+
+use crate::protocol::BackendKind;
+
+pub(crate) const PROFILE_SHARE_PREFIX: &str = "rtq://add-profile";
+
+/// Modules of light border around the code, as scanners expect.
+const QR_QUIET_ZONE_MODULES: usize = 4;
+
+/// Connection details a share link transfers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProfileShare {
+    pub backend_kind: BackendKind,
+    pub endpoint: String,
+    pub username: String,
+}
+
+fn backend_key(kind: BackendKind) -> Option<&'static str> {
+    match kind {
+        BackendKind::OpenSubsonic => Some("open_subsonic"),
+        BackendKind::WebDav => Some("webdav"),
+        BackendKind::LocalFs => None,
+    }
+}
+
+fn backend_from_key(key: &str) -> Option<BackendKind> {
+    match key.to_ascii_lowercase().as_str() {
+        "open_subsonic" | "opensubsonic" => Some(BackendKind::OpenSubsonic),
+        "webdav" => Some(BackendKind::WebDav),
+        _ => None,
+    }
+}
+
+/// Builds the share link for `share`. Local folders have nothing to share.
+pub(crate) fn encode_profile_share_link(share: &ProfileShare) -> Option<String> {
+    let backend = backend_key(share.backend_kind)?;
+    let endpoint = share.endpoint.trim().trim_end_matches('/');
+    if endpoint.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}?backend={}&endpoint={}&username={}",
+        PROFILE_SHARE_PREFIX,
+        backend,
+        urlencoding::encode(endpoint),
+        urlencoding::encode(share.username.trim())
+    ))
+}
+
+/// Reads a share link. Parameters other than the backend, endpoint, and username are
+/// ignored, so a hand-made link cannot smuggle a password into the form.
+pub(crate) fn parse_profile_share_link(raw: &str) -> Option<ProfileShare> {
+    let raw = raw.trim();
+    let prefix = raw.get(..PROFILE_SHARE_PREFIX.len())?;
+    if !prefix.eq_ignore_ascii_case(PROFILE_SHARE_PREFIX) {
+        return None;
+    }
+    let rest = &raw[PROFILE_SHARE_PREFIX.len()..];
+    let query = rest.strip_prefix('/').unwrap_or(rest).strip_prefix('?')?;
+    let mut backend_kind = None;
+    let mut endpoint = String::new();
+    let mut username = String::new();
+    for key_value in query.split('&') {
+        let (key, raw_value) = key_value.split_once('=').unwrap_or((key_value, ""));
+        let value = urlencoding::decode(raw_value)
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default();
+        match key.to_ascii_lowercase().as_str() {
+            "backend" => backend_kind = backend_from_key(&value),
+            "endpoint" => endpoint = value.trim_end_matches('/').to_string(),
+            "username" => username = value,
+            _ => {}
+        }
+    }
+    let scheme_ok = ["http://", "https://"].iter().any(|scheme| {
+        endpoint
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    });
+    if !scheme_ok {
+        return None;
+    }
+    Some(ProfileShare {
+        backend_kind: backend_kind?,
+        endpoint,
+        username,
+    })
+}
+
+/// Renders `text` as a black-on-white QR code with `scale` pixels per module. Returns
+/// the side length in pixels and the RGBA bytes.
+pub(crate) fn qr_code_rgba(text: &str, scale: u32) -> Option<(u32, Vec<u8>)> {
+    let code = QrCode::new(text.as_bytes()).ok()?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let scale = scale.max(1) as usize;
+    let side_modules = modules + 2 * QR_QUIET_ZONE_MODULES;
+    let side = side_modules * scale;
+    let mut pixels = vec![0xff_u8; side * side * 4];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let module_x = index % modules + QR_QUIET_ZONE_MODULES;
+        let module_y = index / modules + QR_QUIET_ZONE_MODULES;
+        for y in module_y * scale..(module_y + 1) * scale {
+            for x in module_x * scale..(module_x + 1) * scale {
+                let offset = (y * side + x) * 4;
+                pixels[offset..offset + 3].fill(0);
+            }
+        }
+    }
+    Some((u32::try_from(side).ok()?, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share() -> ProfileShare {
+        ProfileShare {
+            backend_kind: BackendKind::OpenSubsonic,
+            endpoint: "https://music.example.com/".to_string(),
+            username: "alice smith".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_share_link_round_trips_without_password() {
+        let link = encode_profile_share_link(&share()).expect("share link");
+        assert_eq!(
+            link,
+            "rtq://add-profile?backend=open_subsonic&endpoint=https%3A%2F%2Fmusic.example.com&username=alice%20smith"
+        );
+        assert!(!link.contains("password"));
+        let parsed = parse_profile_share_link(&link).expect("parsed share");
+        assert_eq!(parsed.endpoint, "https://music.example.com");
+        assert_eq!(parsed.username, "alice smith");
+        assert_eq!(parsed.backend_kind, BackendKind::OpenSubsonic);
+    }
+
+    #[test]
+    fn test_parse_rejects_track_uris_and_bad_endpoints() {
+        assert!(parse_profile_share_link(
+            "rtq://open_subsonic/home/song-1?endpoint=https%3A%2F%2Fmusic.example.com&username=alice"
+        )
+        .is_none());
+        assert!(parse_profile_share_link(
+            "rtq://add-profile?backend=webdav&endpoint=file%3A%2F%2F%2Fetc&username=a"
+        )
+        .is_none());
+        assert!(
+            parse_profile_share_link("rtq://add-profile?backend=ftp&endpoint=https%3A%2F%2Fx")
+                .is_none()
+        );
+        let with_password = parse_profile_share_link(
+            "RTQ://add-profile/?backend=webdav&endpoint=https%3A%2F%2Fcloud.example.com&username=bob&password=secret",
+        )
+        .expect("parsed share");
+        assert_eq!(with_password.backend_kind, BackendKind::WebDav);
+        assert_eq!(with_password.username, "bob");
+    }
+
+    #[test]
+    fn test_qr_code_has_quiet_zone_and_finder_pattern() {
+        let link = encode_profile_share_link(&share()).expect("share link");
+        let (side, pixels) = qr_code_rgba(&link, 2).expect("qr image");
+        assert_eq!(pixels.len(), (side * side * 4) as usize);
+        let dark_at = |x: u32, y: u32| pixels[((y * side + x) * 4) as usize] == 0;
+        assert!(!dark_at(0, 0));
+        let quiet = (QR_QUIET_ZONE_MODULES * 2) as u32;
+        assert!(dark_at(quiet, quiet));
+        assert!(!dark_at(quiet - 1, quiet));
+    }
+}
@@ -0,0 +1,96 @@
+//! WebDAV-specific config, credentials, and profile snapshot helpers.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use log::warn;
+
+use crate::{
+    config::{BackendProfileConfig, Config, IntegrationBackendKind},
+    integration_keyring::get_webdav_password,
+    protocol,
+};
+
+/// Stable profile ID used for the built-in WebDAV integration profile.
+pub const WEBDAV_PROFILE_ID: &str = "webdav-default";
+
+/// Resolves the WebDAV password from the keyring, falling back to the session cache.
+pub fn resolve_webdav_password(
+    profile_id: &str,
+    session_passwords: &Arc<Mutex<HashMap<String, String>>>,
+) -> Option<String> {
+    match get_webdav_password(profile_id) {
+        Ok(Some(password)) => return Some(password),
+        Ok(None) => {}
+        Err(error) => warn!(
+            "Failed to load WebDAV credential for profile '{}': {}",
+            profile_id, error
+        ),
+    }
+    session_passwords
+        .lock()
+        .expect("session password cache lock poisoned")
+        .get(profile_id)
+        .cloned()
+}
+
+/// Returns the configured WebDAV backend profile, if present.
+pub fn find_webdav_backend(config: &Config) -> Option<&BackendProfileConfig> {
+    config
+        .integrations
+        .backends
+        .iter()
+        .find(|backend| backend.profile_id == WEBDAV_PROFILE_ID)
+}
+
+/// Inserts or updates the WebDAV backend entry in config.
+pub fn upsert_webdav_backend_config(
+    config: &mut Config,
+    endpoint: &str,
+    username: &str,
+    enabled: bool,
+) {
+    let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+    let username = username.trim().to_string();
+    if let Some(existing) = config
+        .integrations
+        .backends
+        .iter_mut()
+        .find(|backend| backend.profile_id == WEBDAV_PROFILE_ID)
+    {
+        existing.backend_kind = IntegrationBackendKind::WebDav;
+        existing.display_name = "WebDAV".to_string();
+        existing.endpoint = endpoint;
+        existing.username = username;
+        existing.enabled = enabled;
+        return;
+    }
+    config.integrations.backends.push(BackendProfileConfig {
+        profile_id: WEBDAV_PROFILE_ID.to_string(),
+        backend_kind: IntegrationBackendKind::WebDav,
+        display_name: "WebDAV".to_string(),
+        endpoint,
+        username,
+        enabled,
+    });
+}
+
+/// Converts config-backed WebDAV profile data into a runtime snapshot.
+pub fn webdav_profile_snapshot(
+    config_backend: &BackendProfileConfig,
+    status_text: Option<String>,
+) -> protocol::BackendProfileSnapshot {
+    protocol::BackendProfileSnapshot {
+        profile_id: config_backend.profile_id.clone(),
+        backend_kind: protocol::BackendKind::WebDav,
+        display_name: config_backend.display_name.clone(),
+        endpoint: config_backend.endpoint.clone(),
+        username: config_backend.username.clone(),
+        configured: !config_backend.endpoint.trim().is_empty()
+            && !config_backend.username.trim().is_empty(),
+        connection_state: protocol::BackendConnectionState::Disconnected,
+        status_text,
+    }
+}
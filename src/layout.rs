@@ -37,6 +37,18 @@ pub const PANEL_CODE_SPACER: i32 = 10;
 pub const PANEL_CODE_STATUS_BAR: i32 = 11;
 /// Stable panel kind code for `LayoutPanelKind::ImportButtonCluster`.
 pub const PANEL_CODE_IMPORT_BUTTON_CLUSTER: i32 = 12;
+/// Stable panel kind code for `LayoutPanelKind::UpNext`.
+pub const PANEL_CODE_UP_NEXT: i32 = 13;
+/// Stable panel kind code for `LayoutPanelKind::Notes`.
+pub const PANEL_CODE_NOTES: i32 = 14;
+/// Stable panel kind code for `LayoutPanelKind::Lyrics`.
+pub const PANEL_CODE_LYRICS: i32 = 15;
+/// Stable panel kind code for `LayoutPanelKind::Tasks`.
+pub const PANEL_CODE_TASKS: i32 = 16;
+/// Stable panel kind code for `LayoutPanelKind::FileBrowser`.
+pub const PANEL_CODE_FILE_BROWSER: i32 = 17;
+/// Stable panel kind code for `LayoutPanelKind::Spectrogram`.
+pub const PANEL_CODE_SPECTROGRAM: i32 = 18;
 /// Stable ID for the built-in default color scheme.
 pub const DEFAULT_COLOR_SCHEME_ID: &str = "roqtune_dark";
 
@@ -65,6 +77,12 @@ pub enum LayoutPanelKind {
     AlbumArtViewer,
     Spacer,
     StatusBar,
+    UpNext,
+    Notes,
+    Lyrics,
+    Tasks,
+    FileBrowser,
+    Spectrogram,
     ControlBar,
     AlbumArtPane,
 }
@@ -86,6 +104,12 @@ impl LayoutPanelKind {
             Self::Spacer => PANEL_CODE_SPACER,
             Self::StatusBar => PANEL_CODE_STATUS_BAR,
             Self::ImportButtonCluster => PANEL_CODE_IMPORT_BUTTON_CLUSTER,
+            Self::UpNext => PANEL_CODE_UP_NEXT,
+            Self::Notes => PANEL_CODE_NOTES,
+            Self::Lyrics => PANEL_CODE_LYRICS,
+            Self::Tasks => PANEL_CODE_TASKS,
+            Self::FileBrowser => PANEL_CODE_FILE_BROWSER,
+            Self::Spectrogram => PANEL_CODE_SPECTROGRAM,
             Self::ControlBar => PANEL_CODE_TRANSPORT_BUTTON_CLUSTER,
             Self::AlbumArtPane => PANEL_CODE_ALBUM_ART_VIEWER,
         }
@@ -106,6 +130,12 @@ impl LayoutPanelKind {
             PANEL_CODE_SPACER => Self::Spacer,
             PANEL_CODE_STATUS_BAR => Self::StatusBar,
             PANEL_CODE_IMPORT_BUTTON_CLUSTER => Self::ImportButtonCluster,
+            PANEL_CODE_UP_NEXT => Self::UpNext,
+            PANEL_CODE_NOTES => Self::Notes,
+            PANEL_CODE_LYRICS => Self::Lyrics,
+            PANEL_CODE_TASKS => Self::Tasks,
+            PANEL_CODE_FILE_BROWSER => Self::FileBrowser,
+            PANEL_CODE_SPECTROGRAM => Self::Spectrogram,
             _ => Self::None,
         }
     }
@@ -122,6 +152,12 @@ impl LayoutPanelKind {
             | Self::TrackList
             | Self::MetadataViewer
             | Self::AlbumArtViewer
+            | Self::UpNext
+            | Self::Notes
+            | Self::Lyrics
+            | Self::Tasks
+            | Self::FileBrowser
+            | Self::Spectrogram
             | Self::Spacer => (RELAXED_PANEL_MIN_EDGE_PX, RELAXED_PANEL_MIN_EDGE_PX),
             Self::StatusBar => (RELAXED_PANEL_MIN_EDGE_PX, 20),
             Self::None => (0, 0),
@@ -338,6 +374,78 @@ pub struct PlaylistColumnWidthOverrideConfig {
     pub width_px: u32,
 }
 
+/// Horizontal placement of text in a playlist column.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnTextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where text too long for its column is shortened.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnElision {
+    /// `Long title…`
+    #[default]
+    End,
+    /// `Long…title`, which keeps file extensions and trailing numbers visible.
+    Middle,
+}
+
+/// Number style of built-in duration, bitrate, and file size columns.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnNumberFormat {
+    /// `3:45`, `320 kbps`, `7.4 MB`.
+    #[default]
+    Readable,
+    /// `3:45`, `320k`, `7.4M`.
+    Compact,
+    /// Plain numbers: seconds, kbps, and bytes.
+    Raw,
+}
+
+/// Font weight of a playlist column's cells.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnFontWeight {
+    Light,
+    #[default]
+    Normal,
+    SemiBold,
+    Bold,
+}
+
+/// Text presentation of one playlist column.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+pub struct PlaylistColumnStyleConfig {
+    #[serde(default)]
+    pub alignment: ColumnTextAlignment,
+    #[serde(default)]
+    pub elision: ColumnElision,
+    #[serde(default)]
+    pub number_format: ColumnNumberFormat,
+    #[serde(default)]
+    pub font_weight: ColumnFontWeight,
+}
+
+impl PlaylistColumnStyleConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Persistent per-column text style shared across playlists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistColumnStyleOverrideConfig {
+    /// Stable column key (`{title}` or `custom:Name|Format`).
+    pub column_key: String,
+    pub style: PlaylistColumnStyleConfig,
+}
+
 /// Named theme color components used across the UI.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
 pub struct ThemeColorComponents {
@@ -380,6 +488,8 @@ pub struct LayoutConfig {
     pub playlist_columns: Vec<PlaylistColumnConfig>,
     /// Global playlist column width overrides.
     pub playlist_column_width_overrides: Vec<PlaylistColumnWidthOverrideConfig>,
+    /// Global playlist column text styles; columns without one use the default style.
+    pub playlist_column_styles: Vec<PlaylistColumnStyleOverrideConfig>,
     /// Per-leaf button-cluster settings.
     pub button_cluster_instances: Vec<ButtonClusterInstanceConfig>,
     /// Per-leaf mode settings for collection-aware panels.
@@ -406,6 +516,7 @@ impl Default for LayoutConfig {
             custom_colors: None,
             playlist_columns: default_playlist_columns(),
             playlist_column_width_overrides: Vec::new(),
+            playlist_column_styles: Vec::new(),
             button_cluster_instances: Vec::new(),
             collection_panel_instances: Vec::new(),
             metadata_viewer_panel_instances: Vec::new(),
@@ -422,6 +533,8 @@ struct LayoutPlaylistColumnWire {
     column: PlaylistColumnConfig,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     width_px: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    style: Option<PlaylistColumnStyleConfig>,
 }
 
 fn default_playlist_columns_wire() -> Vec<LayoutPlaylistColumnWire> {
@@ -430,6 +543,7 @@ fn default_playlist_columns_wire() -> Vec<LayoutPlaylistColumnWire> {
         .map(|column| LayoutPlaylistColumnWire {
             column,
             width_px: None,
+            style: None,
         })
         .collect()
 }
@@ -479,9 +593,19 @@ impl<'de> serde::Deserialize<'de> for LayoutConfig {
         let v2 = LayoutConfigWire::deserialize(deserializer)?;
         let mut playlist_columns = Vec::with_capacity(v2.playlist_columns.len());
         let mut playlist_column_width_overrides = Vec::new();
+        let mut playlist_column_styles = Vec::new();
         let mut seen_override_keys = HashSet::new();
+        let mut seen_style_keys = HashSet::new();
         for wire in v2.playlist_columns {
             let key = playlist_column_key(&wire.column);
+            if let Some(style) = wire.style.filter(|style| !style.is_default()) {
+                if seen_style_keys.insert(key.clone()) {
+                    playlist_column_styles.push(PlaylistColumnStyleOverrideConfig {
+                        column_key: key.clone(),
+                        style,
+                    });
+                }
+            }
             if let Some(width_px) = wire.width_px {
                 if seen_override_keys.insert(key.clone()) {
                     playlist_column_width_overrides.push(PlaylistColumnWidthOverrideConfig {
@@ -558,6 +682,7 @@ impl<'de> serde::Deserialize<'de> for LayoutConfig {
             custom_colors: v2.custom_colors,
             playlist_columns,
             playlist_column_width_overrides,
+            playlist_column_styles,
             button_cluster_instances: v2.button_cluster_instances,
             collection_panel_instances: v2.collection_panel_instances,
             metadata_viewer_panel_instances,
@@ -581,6 +706,12 @@ impl serde::Serialize for LayoutConfig {
             }
             override_widths_by_key.insert(key.to_string(), override_item.width_px);
         }
+        let styles_by_key: HashMap<&str, PlaylistColumnStyleConfig> = self
+            .playlist_column_styles
+            .iter()
+            .rev()
+            .map(|entry| (entry.column_key.trim(), entry.style))
+            .collect();
 
         let playlist_columns = self
             .playlist_columns
@@ -589,7 +720,15 @@ impl serde::Serialize for LayoutConfig {
             .map(|column| {
                 let key = playlist_column_key(&column);
                 let width_px = override_widths_by_key.get(&key).copied();
-                LayoutPlaylistColumnWire { column, width_px }
+                let style = styles_by_key
+                    .get(key.as_str())
+                    .copied()
+                    .filter(|style| !style.is_default());
+                LayoutPlaylistColumnWire {
+                    column,
+                    width_px,
+                    style,
+                }
             })
             .collect();
 
@@ -1029,6 +1168,7 @@ pub fn sanitize_layout_config(
         custom_colors: config.custom_colors.clone(),
         playlist_columns: config.playlist_columns.clone(),
         playlist_column_width_overrides: config.playlist_column_width_overrides.clone(),
+        playlist_column_styles: config.playlist_column_styles.clone(),
         button_cluster_instances: config.button_cluster_instances.clone(),
         collection_panel_instances: config.collection_panel_instances.clone(),
         metadata_viewer_panel_instances: config.metadata_viewer_panel_instances.clone(),
@@ -1244,6 +1384,7 @@ pub fn add_root_leaf_if_empty(layout: &LayoutConfig, panel: LayoutPanelKind) ->
             custom_colors: layout.custom_colors.clone(),
             playlist_columns: layout.playlist_columns.clone(),
             playlist_column_width_overrides: layout.playlist_column_width_overrides.clone(),
+            playlist_column_styles: layout.playlist_column_styles.clone(),
             button_cluster_instances: layout.button_cluster_instances.clone(),
             collection_panel_instances: layout.collection_panel_instances.clone(),
             metadata_viewer_panel_instances: layout.metadata_viewer_panel_instances.clone(),
@@ -1477,9 +1618,11 @@ pub fn compute_tree_layout_metrics(
 mod tests {
     use super::{
         compute_tree_layout_metrics, delete_leaf, first_leaf_id, replace_leaf_panel,
-        sanitize_layout_config, set_split_ratio, split_leaf, LayoutConfig, LayoutNode,
-        LayoutPanelKind, LayoutSplitterItem, PlaylistColumnWidthOverrideConfig, SplitAxis,
-        DEFAULT_COLOR_SCHEME_ID, LAYOUT_VERSION, SPLITTER_THICKNESS_PX,
+        sanitize_layout_config, set_split_ratio, split_leaf, ColumnElision, ColumnFontWeight,
+        ColumnTextAlignment, LayoutConfig, LayoutNode, LayoutPanelKind, LayoutSplitterItem,
+        PlaylistColumnStyleConfig, PlaylistColumnStyleOverrideConfig,
+        PlaylistColumnWidthOverrideConfig, SplitAxis, DEFAULT_COLOR_SCHEME_ID, LAYOUT_VERSION,
+        SPLITTER_THICKNESS_PX,
     };
 
     fn splitter_by_id<'a>(splitters: &'a [LayoutSplitterItem], id: &str) -> &'a LayoutSplitterItem {
@@ -1514,6 +1657,7 @@ mod tests {
             custom_colors: None,
             playlist_columns: crate::config::default_playlist_columns(),
             playlist_column_width_overrides: Vec::new(),
+            playlist_column_styles: Vec::new(),
             button_cluster_instances: vec![crate::config::ButtonClusterInstanceConfig {
                 leaf_id: "cluster".to_string(),
                 actions: vec![1, 2, 3, 4],
@@ -1563,6 +1707,7 @@ mod tests {
             custom_colors: None,
             playlist_columns: crate::config::default_playlist_columns(),
             playlist_column_width_overrides: Vec::new(),
+            playlist_column_styles: Vec::new(),
             button_cluster_instances: Vec::new(),
             collection_panel_instances: Vec::new(),
             metadata_viewer_panel_instances: Vec::new(),
@@ -1673,6 +1818,7 @@ mod tests {
             custom_colors: None,
             playlist_columns: crate::config::default_playlist_columns(),
             playlist_column_width_overrides: Vec::new(),
+            playlist_column_styles: Vec::new(),
             button_cluster_instances: Vec::new(),
             collection_panel_instances: Vec::new(),
             metadata_viewer_panel_instances: Vec::new(),
@@ -1748,6 +1894,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_layout_round_trips_column_styles_inline_with_columns() {
+        let mut layout = LayoutConfig::default();
+        let style = PlaylistColumnStyleConfig {
+            alignment: ColumnTextAlignment::Right,
+            elision: ColumnElision::Middle,
+            font_weight: ColumnFontWeight::Bold,
+            ..PlaylistColumnStyleConfig::default()
+        };
+        layout
+            .playlist_column_styles
+            .push(PlaylistColumnStyleOverrideConfig {
+                column_key: "{title}".to_string(),
+                style,
+            });
+        layout
+            .playlist_column_styles
+            .push(PlaylistColumnStyleOverrideConfig {
+                column_key: "{artist}".to_string(),
+                style: PlaylistColumnStyleConfig::default(),
+            });
+
+        let serialized = toml::to_string(&layout).expect("layout should serialize");
+        assert!(serialized.contains("alignment = \"right\""));
+        assert_eq!(serialized.matches("elision").count(), 1);
+
+        let parsed: LayoutConfig = toml::from_str(&serialized).expect("layout should parse");
+        assert_eq!(
+            parsed.playlist_column_styles,
+            vec![PlaylistColumnStyleOverrideConfig {
+                column_key: "{title}".to_string(),
+                style,
+            }]
+        );
+    }
+
     #[test]
     fn test_layout_deserializes_legacy_top_level_width_overrides() {
         let mut legacy_layout = include_str!("../config/layout.system.toml").to_string();
@@ -0,0 +1,86 @@
+//! Human-readable activity log of significant library, playlist, and sync actions.
+//!
+//! Managers call [`record`] after a change succeeds; the entry travels over the bus as
+//! `LibraryMessage::RecordActivity` and is persisted by the maintenance scheduler, which
+//! also answers `LibraryMessage::RequestActivityLog` for the settings view. The stored
+//! log is capped by entry count and entries older than [`RETENTION_DAYS`] are dropped
+//! whenever a new one is written.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast::Sender;
+
+use crate::maintenance_scheduler;
+use crate::protocol::{ActivityCategory, ActivityLogEntry, LibraryMessage, Message};
+
+/// Entries older than this are pruned.
+pub const RETENTION_DAYS: i64 = 180;
+/// Number of entries included in snapshots for the settings view.
+pub const SNAPSHOT_LIMIT: usize = 200;
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Publishes one activity log entry stamped with the current time.
+pub fn record(bus: &Sender<Message>, category: ActivityCategory, message: impl Into<String>) {
+    let _ = bus.send(Message::Library(LibraryMessage::RecordActivity(
+        ActivityLogEntry {
+            recorded_unix_ms: now_unix_ms(),
+            category,
+            message: message.into(),
+        },
+    )));
+}
+
+/// Oldest timestamp kept when pruning at `now_unix_ms`.
+pub fn retention_cutoff_unix_ms(now_unix_ms: i64) -> i64 {
+    now_unix_ms.saturating_sub(RETENTION_DAYS * MS_PER_DAY)
+}
+
+/// Short display name for one category.
+pub fn category_label(category: ActivityCategory) -> &'static str {
+    match category {
+        ActivityCategory::Library => "Library",
+        ActivityCategory::Playlist => "Playlist",
+        ActivityCategory::Sync => "Sync",
+    }
+}
+
+/// One settings list row for an entry, e.g. `3 h ago · Playlist · Deleted playlist "Mix"`.
+pub fn format_entry(now_unix_ms: i64, entry: &ActivityLogEntry) -> String {
+    format!(
+        "{} \u{00b7} {} \u{00b7} {}",
+        maintenance_scheduler::format_elapsed(now_unix_ms, entry.recorded_unix_ms),
+        category_label(entry.category),
+        entry.message
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_publishes_entry_and_format_includes_category() {
+        let (bus, mut receiver) = tokio::sync::broadcast::channel(4);
+        record(&bus, ActivityCategory::Playlist, "Deleted playlist \"Mix\"");
+        let Ok(Message::Library(LibraryMessage::RecordActivity(entry))) = receiver.try_recv()
+        else {
+            panic!("expected a recorded activity");
+        };
+        assert_eq!(entry.category, ActivityCategory::Playlist);
+        assert_eq!(
+            format_entry(entry.recorded_unix_ms + 3 * 60 * 60 * 1000, &entry),
+            "3 h ago \u{00b7} Playlist \u{00b7} Deleted playlist \"Mix\""
+        );
+        assert_eq!(
+            retention_cutoff_unix_ms(entry.recorded_unix_ms),
+            entry.recorded_unix_ms - RETENTION_DAYS * MS_PER_DAY
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Album-artist suggestions for compilations that lack an album-artist tag.
+//!
+//! Tracks are grouped into albums by album title and containing folder, so unrelated
+//! albums that share a title stay apart. An album is flagged when none of its tracks
+//! has an album artist and its tracks name more than one artist. The suggestion is the
+//! dominant artist when one clearly leads, otherwise "Various Artists".
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::protocol::{AlbumArtistSuggestion, LibraryTrack};
+
+/// Album artist suggested for albums without a dominant artist.
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+/// Minimum number of distinct track artists before an album is flagged.
+const MIN_DISTINCT_ARTISTS: usize = 2;
+/// Share of tracks one artist needs to be suggested instead of "Various Artists".
+const DOMINANT_ARTIST_SHARE: f64 = 0.6;
+
+#[derive(Default)]
+struct AlbumGroup<'a> {
+    album: &'a str,
+    tracks: Vec<&'a LibraryTrack>,
+}
+
+fn suggest_for_group(group: &AlbumGroup<'_>) -> Option<AlbumArtistSuggestion> {
+    if group
+        .tracks
+        .iter()
+        .any(|track| !track.album_artist.trim().is_empty())
+    {
+        return None;
+    }
+    // Artist spelling of the first track wins for case-insensitive duplicates.
+    let mut artist_counts: HashMap<String, (&str, usize)> = HashMap::new();
+    for track in &group.tracks {
+        let artist = track.artist.trim();
+        if artist.is_empty() {
+            continue;
+        }
+        artist_counts
+            .entry(artist.to_lowercase())
+            .or_insert((artist, 0))
+            .1 += 1;
+    }
+    if artist_counts.len() < MIN_DISTINCT_ARTISTS {
+        return None;
+    }
+
+    let (dominant_artist, dominant_count) = artist_counts
+        .values()
+        .max_by(|left, right| left.1.cmp(&right.1).then_with(|| right.0.cmp(left.0)))
+        .copied()?;
+    let suggested_album_artist =
+        if dominant_count as f64 / group.tracks.len() as f64 >= DOMINANT_ARTIST_SHARE {
+            dominant_artist.to_string()
+        } else {
+            VARIOUS_ARTISTS.to_string()
+        };
+    Some(AlbumArtistSuggestion {
+        album: group.album.to_string(),
+        artist_count: artist_counts.len(),
+        suggested_album_artist,
+        paths: group
+            .tracks
+            .iter()
+            .map(|track| track.path.clone())
+            .collect(),
+    })
+}
+
+/// Flags albums in `tracks` that need an album artist, ordered by album title.
+pub fn suggest_album_artists(tracks: &[LibraryTrack]) -> Vec<AlbumArtistSuggestion> {
+    let mut groups: BTreeMap<(String, PathBuf), AlbumGroup<'_>> = BTreeMap::new();
+    for track in tracks {
+        let album = track.album.trim();
+        if album.is_empty() {
+            continue;
+        }
+        let folder = track.path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let group = groups.entry((album.to_lowercase(), folder)).or_default();
+        group.album = album;
+        group.tracks.push(track);
+    }
+    groups.values().filter_map(suggest_for_group).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, artist: &str, album: &str, album_artist: &str) -> LibraryTrack {
+        LibraryTrack {
+            id: path.to_string(),
+            path: PathBuf::from(path),
+            title: String::new(),
+            artist: artist.to_string(),
+            album: album.to_string(),
+            album_artist: album_artist.to_string(),
+            genre: String::new(),
+            year: String::new(),
+            track_number: String::new(),
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_suggest_album_artists_flags_compilations_without_album_artist() {
+        let suggestions = suggest_album_artists(&[
+            track("/m/hits/1.flac", "A", "Hits", ""),
+            track("/m/hits/2.flac", "B", "Hits", ""),
+            track("/m/hits/3.flac", "C", "Hits", ""),
+            track("/m/solo/1.flac", "D", "Solo", ""),
+            track("/m/solo/2.flac", "d", "Solo", ""),
+            track("/m/tagged/1.flac", "E", "Tagged", "Various Artists"),
+            track("/m/tagged/2.flac", "F", "Tagged", ""),
+        ]);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].album, "Hits");
+        assert_eq!(suggestions[0].artist_count, 3);
+        assert_eq!(suggestions[0].suggested_album_artist, VARIOUS_ARTISTS);
+        assert_eq!(suggestions[0].paths.len(), 3);
+    }
+
+    #[test]
+    fn test_suggest_album_artists_prefers_dominant_artist() {
+        let suggestions = suggest_album_artists(&[
+            track("/m/live/1.flac", "Band", "Live", ""),
+            track("/m/live/2.flac", "Band", "Live", ""),
+            track("/m/live/3.flac", "Band", "Live", ""),
+            track("/m/live/4.flac", "Band feat. Guest", "Live", ""),
+            track("/m/other/1.flac", "X", "Live", ""),
+        ]);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].suggested_album_artist, "Band");
+        assert_eq!(suggestions[0].paths.len(), 4);
+    }
+}
@@ -0,0 +1,413 @@
+//! Tag-template file organization: planning and applying on-disk moves/renames.
+//!
+//! Targets are rendered from a `/`-separated template such as
+//! `%albumartist%/%year% - %album%/%track% %title%` relative to the library folder that
+//! contains each file. Planning never touches the filesystem beyond existence checks;
+//! applying a plan rolls back already-completed moves when any move fails.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::metadata_tags::CommonTrackMetadata;
+use crate::protocol::OrganizeFileMove;
+
+/// Template offered when the organize dialog opens.
+pub const DEFAULT_ORGANIZE_TEMPLATE: &str = "%albumartist%/%year% - %album%/%track% %title%";
+/// Upper bound for collision suffixes such as ` (2)`.
+const MAX_COLLISION_SUFFIX: usize = 999;
+
+/// Result of planning an organize run over a set of files.
+#[derive(Debug, Default)]
+pub struct OrganizePlan {
+    pub moves: Vec<OrganizeFileMove>,
+    pub unchanged: usize,
+    pub skipped: Vec<String>,
+}
+
+fn sanitize_component(raw: &str) -> String {
+    let replaced: String = raw
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect();
+    let trimmed = replaced.trim_matches(|ch: char| ch.is_whitespace() || ch == '-' || ch == '.');
+    if trimmed.is_empty() {
+        "Unknown".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn padded_track_number(raw: &str) -> String {
+    let number = raw.split('/').next().unwrap_or_default().trim();
+    match number.parse::<u32>() {
+        Ok(value) => format!("{value:02}"),
+        Err(_) => number.to_string(),
+    }
+}
+
+fn token_value(token: &str, metadata: &CommonTrackMetadata, source: &Path) -> Option<String> {
+    let non_empty = |value: &str| {
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+    let value = match token {
+        "albumartist" => non_empty(&metadata.album_artist)
+            .or_else(|| non_empty(&metadata.artist))
+            .unwrap_or_else(|| "Unknown Artist".to_string()),
+        "artist" => non_empty(&metadata.artist).unwrap_or_else(|| "Unknown Artist".to_string()),
+        "album" => non_empty(&metadata.album).unwrap_or_else(|| "Unknown Album".to_string()),
+        "year" => non_empty(&metadata.year)
+            .or_else(|| {
+                non_empty(&metadata.date).map(|date| date.chars().take(4).collect::<String>())
+            })
+            .unwrap_or_default(),
+        "track" => padded_track_number(&metadata.track_number),
+        "title" => non_empty(&metadata.title).unwrap_or_else(|| {
+            source
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }),
+        "genre" => non_empty(&metadata.genre).unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn render_component(
+    component: &str,
+    metadata: &CommonTrackMetadata,
+    source: &Path,
+) -> Result<String, String> {
+    let mut rendered = String::new();
+    let mut rest = component;
+    while let Some(start) = rest.find('%') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            return Err(format!(
+                "Unterminated token in template segment '{component}'"
+            ));
+        };
+        let token = after[..end].to_ascii_lowercase();
+        let value = token_value(&token, metadata, source)
+            .ok_or_else(|| format!("Unknown template token '%{token}%'"))?;
+        rendered.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(sanitize_component(&rendered))
+}
+
+/// Checks that a template is non-empty and only uses supported tokens.
+pub fn validate_template(template: &str) -> Result<(), String> {
+    render_relative_path(
+        template,
+        &CommonTrackMetadata::default(),
+        Path::new("x.flac"),
+    )
+    .map(|_| ())
+}
+
+/// Renders the library-relative target path (including extension) for one file.
+pub fn render_relative_path(
+    template: &str,
+    metadata: &CommonTrackMetadata,
+    source: &Path,
+) -> Result<PathBuf, String> {
+    let components: Vec<&str> = template
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|component| !component.is_empty())
+        .collect();
+    if components.is_empty() {
+        return Err("Template is empty".to_string());
+    }
+    let mut relative = PathBuf::new();
+    for component in &components {
+        relative.push(render_component(component, metadata, source)?);
+    }
+    if let Some(extension) = source.extension() {
+        let file_name = format!(
+            "{}.{}",
+            relative
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            extension.to_string_lossy()
+        );
+        relative.set_file_name(file_name);
+    }
+    Ok(relative)
+}
+
+fn library_root_for<'a>(path: &Path, library_folders: &'a [PathBuf]) -> Option<&'a Path> {
+    library_folders
+        .iter()
+        .filter(|folder| path.starts_with(folder))
+        .max_by_key(|folder| folder.components().count())
+        .map(PathBuf::as_path)
+}
+
+fn with_collision_suffix(target: &Path, attempt: usize) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match target.extension() {
+        Some(extension) => format!("{stem} ({attempt}).{}", extension.to_string_lossy()),
+        None => format!("{stem} ({attempt})"),
+    };
+    target.with_file_name(file_name)
+}
+
+/// Plans moves for `sources` using `read_metadata` for tag lookups.
+///
+/// Files outside every library folder, or whose tags cannot be read, are reported as
+/// skipped. Targets that already exist on disk or collide within the plan get a
+/// numbered suffix.
+pub fn plan_moves(
+    template: &str,
+    sources: &[PathBuf],
+    library_folders: &[PathBuf],
+    read_metadata: impl Fn(&Path) -> Option<CommonTrackMetadata>,
+) -> Result<OrganizePlan, String> {
+    validate_template(template)?;
+    let mut plan = OrganizePlan::default();
+    let mut claimed_targets: HashSet<PathBuf> = HashSet::new();
+    for source in sources {
+        let Some(root) = library_root_for(source, library_folders) else {
+            plan.skipped
+                .push(format!("{}: not inside a library folder", source.display()));
+            continue;
+        };
+        let Some(metadata) = read_metadata(source) else {
+            plan.skipped
+                .push(format!("{}: tags could not be read", source.display()));
+            continue;
+        };
+        let target = root.join(render_relative_path(template, &metadata, source)?);
+        if &target == source {
+            plan.unchanged += 1;
+            claimed_targets.insert(target);
+            continue;
+        }
+        let is_taken = |candidate: &PathBuf| {
+            candidate != source && (claimed_targets.contains(candidate) || candidate.exists())
+        };
+        let mut candidate = target.clone();
+        let mut attempt = 2;
+        while is_taken(&candidate) {
+            if attempt > MAX_COLLISION_SUFFIX {
+                plan.skipped
+                    .push(format!("{}: too many name collisions", source.display()));
+                break;
+            }
+            candidate = with_collision_suffix(&target, attempt);
+            attempt += 1;
+        }
+        if is_taken(&candidate) {
+            continue;
+        }
+        if &candidate == source {
+            plan.unchanged += 1;
+            claimed_targets.insert(candidate);
+            continue;
+        }
+        claimed_targets.insert(candidate.clone());
+        plan.moves.push(OrganizeFileMove {
+            from: source.clone(),
+            to: candidate,
+        });
+    }
+    Ok(plan)
+}
+
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            // Cross-device moves cannot be renamed; fall back to copy + remove.
+            fs::copy(from, to)?;
+            if let Err(error) = fs::remove_file(from) {
+                let _ = fs::remove_file(to);
+                return Err(error);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Removes `dir` and its now-empty ancestors, stopping at `root`.
+pub fn remove_empty_dirs_up_to(dir: &Path, root: &Path) {
+    let mut current = Some(dir);
+    while let Some(directory) = current {
+        if directory == root || !directory.starts_with(root) {
+            break;
+        }
+        if fs::remove_dir(directory).is_err() {
+            break;
+        }
+        current = directory.parent();
+    }
+}
+
+/// Returns the moves that undo `moves`, in reverse order.
+pub fn reverse_moves(moves: &[OrganizeFileMove]) -> Vec<OrganizeFileMove> {
+    moves
+        .iter()
+        .rev()
+        .map(|file_move| OrganizeFileMove {
+            from: file_move.to.clone(),
+            to: file_move.from.clone(),
+        })
+        .collect()
+}
+
+/// Applies `moves` in order, undoing completed moves if any move fails.
+///
+/// Source directories left empty by the moves are removed up to their library folder.
+pub fn apply_file_moves(
+    moves: &[OrganizeFileMove],
+    library_folders: &[PathBuf],
+) -> Result<(), String> {
+    let mut completed: Vec<&OrganizeFileMove> = Vec::with_capacity(moves.len());
+    for file_move in moves {
+        if let Err(error) = move_file(&file_move.from, &file_move.to) {
+            for done in completed.iter().rev() {
+                let _ = move_file(&done.to, &done.from);
+            }
+            return Err(format!(
+                "Failed to move {} to {}: {}",
+                file_move.from.display(),
+                file_move.to.display(),
+                error
+            ));
+        }
+        completed.push(file_move);
+    }
+    for file_move in moves {
+        if let (Some(parent), Some(root)) = (
+            file_move.from.parent(),
+            library_root_for(&file_move.from, library_folders),
+        ) {
+            remove_empty_dirs_up_to(parent, root);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(artist: &str, album: &str, title: &str, track: &str) -> CommonTrackMetadata {
+        CommonTrackMetadata {
+            title: title.to_string(),
+            artist: artist.to_string(),
+            album: album.to_string(),
+            date: "1999-04-01".to_string(),
+            track_number: track.to_string(),
+            ..CommonTrackMetadata::default()
+        }
+    }
+
+    fn temp_library(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("roqtune_organize_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("temp library should be created");
+        root
+    }
+
+    #[test]
+    fn test_render_relative_path_sanitizes_and_falls_back() {
+        let rendered = render_relative_path(
+            DEFAULT_ORGANIZE_TEMPLATE,
+            &metadata("AC/DC", "", "What? Now", "3/12"),
+            Path::new("/music/in/file.flac"),
+        )
+        .expect("template should render");
+        assert_eq!(
+            rendered,
+            PathBuf::from("AC_DC/1999 - Unknown Album/03 What_ Now.flac")
+        );
+        assert!(render_relative_path(
+            "%bogus%",
+            &CommonTrackMetadata::default(),
+            Path::new("a.mp3")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_plan_moves_suffixes_collisions_and_skips_outside_files() {
+        let root = temp_library("plan");
+        let first = root.join("a.flac");
+        let second = root.join("b.flac");
+        let outside = PathBuf::from("/elsewhere/c.flac");
+        let plan = plan_moves(
+            "%artist%/%title%",
+            &[first.clone(), second.clone(), outside],
+            std::slice::from_ref(&root),
+            |_| Some(metadata("Artist", "Album", "Song", "1")),
+        )
+        .expect("plan should succeed");
+        assert_eq!(plan.moves.len(), 2);
+        assert_eq!(plan.moves[0].to, root.join("Artist/Song.flac"));
+        assert_eq!(plan.moves[1].to, root.join("Artist/Song (2).flac"));
+        assert_eq!(plan.skipped.len(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_apply_file_moves_moves_and_rolls_back_on_failure() {
+        let root = temp_library("apply");
+        let source_dir = root.join("incoming");
+        fs::create_dir_all(&source_dir).expect("source dir should be created");
+        let first = source_dir.join("one.mp3");
+        let second = source_dir.join("two.mp3");
+        fs::write(&first, b"one").expect("first file should be written");
+        fs::write(&second, b"two").expect("second file should be written");
+        fs::create_dir_all(root.join("Blocked")).expect("blocked dir should be created");
+        fs::write(root.join("Blocked/two.mp3"), b"taken").expect("blocker should be written");
+
+        let failing = vec![
+            OrganizeFileMove {
+                from: first.clone(),
+                to: root.join("Artist/one.mp3"),
+            },
+            OrganizeFileMove {
+                from: second.clone(),
+                to: root.join("Blocked/two.mp3"),
+            },
+        ];
+        assert!(apply_file_moves(&failing, std::slice::from_ref(&root)).is_err());
+        assert!(first.exists());
+        assert!(!root.join("Artist/one.mp3").exists());
+
+        let moves = vec![OrganizeFileMove {
+            from: first.clone(),
+            to: root.join("Artist/one.mp3"),
+        }];
+        apply_file_moves(&moves, std::slice::from_ref(&root)).expect("move should succeed");
+        assert!(!first.exists());
+        assert!(root.join("Artist/one.mp3").exists());
+        assert!(source_dir.exists(), "non-empty source dir is kept");
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,238 @@
+//! Per-folder import rules applied while scanning library folders.
+//!
+//! A rule matches files inside its folder (including subfolders). The genre of the
+//! most specific matching rule replaces the tagged genre whenever the file's metadata
+//! is indexed; tags, playlists, and shuffle exclusion are applied once, when a path is
+//! first imported, so later manual edits are not overwritten by rescans.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::FolderImportRuleConfig;
+use crate::user_tags;
+
+/// Combined actions of every rule matching one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportRuleActions {
+    pub genre: Option<String>,
+    pub tags: Vec<String>,
+    pub playlists: Vec<String>,
+    pub exclude_from_shuffle: bool,
+}
+
+/// Resolves the actions for `path`.
+///
+/// Tags and playlists from all matching rules are combined; the genre comes from the
+/// matching rule with the deepest folder that sets one.
+pub fn resolve_import_rules(rules: &[FolderImportRuleConfig], path: &Path) -> ImportRuleActions {
+    let mut matching: Vec<&FolderImportRuleConfig> = rules
+        .iter()
+        .filter(|rule| !rule.folder.is_empty() && path.starts_with(&rule.folder))
+        .collect();
+    matching.sort_by_key(|rule| Path::new(&rule.folder).components().count());
+
+    let mut actions = ImportRuleActions::default();
+    for rule in matching {
+        if !rule.genre.is_empty() {
+            actions.genre = Some(rule.genre.clone());
+        }
+        for tag in &rule.tags {
+            let key = user_tags::tag_key(tag);
+            if !actions
+                .tags
+                .iter()
+                .any(|existing| user_tags::tag_key(existing) == key)
+            {
+                actions.tags.push(tag.clone());
+            }
+        }
+        if !rule.playlist.is_empty()
+            && !actions
+                .playlists
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&rule.playlist))
+        {
+            actions.playlists.push(rule.playlist.clone());
+        }
+        actions.exclude_from_shuffle |= rule.exclude_from_shuffle;
+    }
+    actions
+}
+
+/// Import-only actions collected during one scan, grouped for batch application.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingImportActions {
+    pub paths_by_tag: BTreeMap<String, Vec<PathBuf>>,
+    pub paths_by_playlist: BTreeMap<String, Vec<PathBuf>>,
+    pub shuffle_excluded_paths: Vec<PathBuf>,
+}
+
+impl PendingImportActions {
+    /// Records the import-only actions for one newly imported path.
+    pub fn record(&mut self, path: &Path, actions: &ImportRuleActions) {
+        for tag in &actions.tags {
+            self.paths_by_tag
+                .entry(tag.clone())
+                .or_default()
+                .push(path.to_path_buf());
+        }
+        for playlist in &actions.playlists {
+            self.paths_by_playlist
+                .entry(playlist.clone())
+                .or_default()
+                .push(path.to_path_buf());
+        }
+        if actions.exclude_from_shuffle {
+            self.shuffle_excluded_paths.push(path.to_path_buf());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths_by_tag.is_empty()
+            && self.paths_by_playlist.is_empty()
+            && self.shuffle_excluded_paths.is_empty()
+    }
+}
+
+/// Parses a comma-separated tag list as typed in the rule editor.
+pub fn parse_rule_tags(raw: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw.split(',').filter_map(user_tags::normalize_tag_name) {
+        if !tags
+            .iter()
+            .any(|existing| user_tags::tag_key(existing) == user_tags::tag_key(&tag))
+        {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Normalizes one rule; returns `None` when it has no folder.
+pub fn sanitize_import_rule(rule: &FolderImportRuleConfig) -> Option<FolderImportRuleConfig> {
+    let folder = rule.folder.trim();
+    if folder.is_empty() {
+        return None;
+    }
+    Some(FolderImportRuleConfig {
+        folder: folder.to_string(),
+        genre: rule.genre.trim().to_string(),
+        tags: parse_rule_tags(&rule.tags.join(",")),
+        playlist: rule
+            .playlist
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        exclude_from_shuffle: rule.exclude_from_shuffle,
+    })
+}
+
+/// One-line description of a rule for the settings list.
+pub fn rule_summary(rule: &FolderImportRuleConfig) -> String {
+    let mut actions = Vec::new();
+    if !rule.genre.is_empty() {
+        actions.push(format!("genre \"{}\"", rule.genre));
+    }
+    if !rule.tags.is_empty() {
+        actions.push(format!("tags {}", rule.tags.join(", ")));
+    }
+    if !rule.playlist.is_empty() {
+        actions.push(format!("add to \"{}\"", rule.playlist));
+    }
+    if rule.exclude_from_shuffle {
+        actions.push("exclude from shuffle".to_string());
+    }
+    if actions.is_empty() {
+        actions.push("no actions".to_string());
+    }
+    format!("{} \u{2192} {}", rule.folder, actions.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(folder: &str) -> FolderImportRuleConfig {
+        FolderImportRuleConfig {
+            folder: folder.to_string(),
+            ..FolderImportRuleConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_import_rules_combines_matches_and_prefers_deepest_genre() {
+        let rules = vec![
+            FolderImportRuleConfig {
+                genre: "Spoken".to_string(),
+                tags: vec!["talk".to_string()],
+                ..rule("/music/Podcasts")
+            },
+            FolderImportRuleConfig {
+                genre: "Podcast".to_string(),
+                tags: vec!["Talk".to_string(), "news".to_string()],
+                playlist: "News".to_string(),
+                exclude_from_shuffle: true,
+                ..rule("/music/Podcasts/Daily")
+            },
+            FolderImportRuleConfig {
+                genre: "Rock".to_string(),
+                ..rule("/music/Rock")
+            },
+        ];
+
+        let actions =
+            resolve_import_rules(&rules, Path::new("/music/Podcasts/Daily/2024/episode.mp3"));
+        assert_eq!(
+            actions,
+            ImportRuleActions {
+                genre: Some("Podcast".to_string()),
+                tags: vec!["talk".to_string(), "news".to_string()],
+                playlists: vec!["News".to_string()],
+                exclude_from_shuffle: true,
+            }
+        );
+        assert_eq!(
+            resolve_import_rules(&rules, Path::new("/music/PodcastsOld/a.mp3")),
+            ImportRuleActions::default()
+        );
+    }
+
+    #[test]
+    fn test_sanitize_import_rule_trims_fields_and_dedupes_tags() {
+        let sanitized = sanitize_import_rule(&FolderImportRuleConfig {
+            folder: " /music/Podcasts ".to_string(),
+            genre: " Podcast ".to_string(),
+            tags: vec![" late  night ".to_string(), "Late Night".to_string()],
+            playlist: "  New   Episodes ".to_string(),
+            exclude_from_shuffle: true,
+        })
+        .expect("rule with a folder should be kept");
+        assert_eq!(sanitized.folder, "/music/Podcasts");
+        assert_eq!(sanitized.genre, "Podcast");
+        assert_eq!(sanitized.tags, vec!["late night".to_string()]);
+        assert_eq!(sanitized.playlist, "New Episodes");
+        assert_eq!(sanitize_import_rule(&rule("  ")), None);
+        assert_eq!(
+            parse_rule_tags("workout, chill,, Workout"),
+            vec!["workout".to_string(), "chill".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_rule_summary_lists_configured_actions() {
+        let summary = rule_summary(&FolderImportRuleConfig {
+            genre: "Podcast".to_string(),
+            playlist: "News".to_string(),
+            exclude_from_shuffle: true,
+            ..rule("/music/Podcasts")
+        });
+        assert_eq!(
+            summary,
+            "/music/Podcasts \u{2192} genre \"Podcast\"; add to \"News\"; exclude from shuffle"
+        );
+        assert_eq!(
+            rule_summary(&rule("/music/Inbox")),
+            "/music/Inbox \u{2192} no actions"
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Integrity verification for local library files.
+//!
+//! Every check hashes the file bytes; FLAC files are also decoded and compared with the
+//! MD5 stored in their STREAMINFO block. The first hash becomes the file's baseline. A
+//! later check that finds different bytes while size and modification time are
+//! unchanged reports the file as silently changed (bit rot); files that were rewritten
+//! normally just get a new baseline. FLAC files whose bytes still match a clean baseline
+//! skip the decode, so repeat runs cost one read per file.
+//!
+//! Lossless files are also analyzed once per content change for a spectrum cutoff that
+//! betrays a lossy source (see [`crate::transcode_detection`]); suspicious files are
+//! reported with the estimated cutoff and confidence.
+
+use std::fs;
+use std::io::{self, ErrorKind, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use symphonia::core::{
+    codecs::DecoderOptions, errors::Error, formats::FormatOptions, io::MediaSourceStream,
+    meta::MetadataOptions, probe::Hint,
+};
+
+use crate::db_manager::FileIntegrityRecord;
+use crate::format_quality;
+use crate::protocol::IntegrityIssueKind;
+use crate::sandbox_access;
+use crate::transcode_detection;
+
+/// Bytes hashed per chunk of the content hash.
+const HASH_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Outcome of decoding a FLAC stream with checksum verification enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamCheck {
+    Verified,
+    /// The encoder did not store a stream MD5.
+    NoChecksum,
+    Mismatch,
+    DecodeErrors(usize),
+}
+
+/// Hashes a file as the MD5 of its per-megabyte chunk MD5s, as lowercase hex.
+pub fn content_hash(path: &Path) -> io::Result<String> {
+    let mut file = sandbox_access::open_file(path)?;
+    let mut chunk = Vec::with_capacity(HASH_CHUNK_BYTES);
+    let mut chunk_digests = Vec::new();
+    loop {
+        chunk.clear();
+        (&mut file)
+            .take(HASH_CHUNK_BYTES as u64)
+            .read_to_end(&mut chunk)?;
+        if chunk.is_empty() {
+            break;
+        }
+        chunk_digests.extend_from_slice(&md5::compute(&chunk).0);
+        if chunk.len() < HASH_CHUNK_BYTES {
+            break;
+        }
+    }
+    Ok(format!("{:x}", md5::compute(&chunk_digests)))
+}
+
+/// Decodes a FLAC file and checks the decoded audio against its stream MD5.
+pub fn verify_flac_stream(path: &Path) -> Result<StreamCheck, String> {
+    let input =
+        sandbox_access::open_file(path).map_err(|err| format!("failed to open source: {err}"))?;
+    let mss = MediaSourceStream::new(Box::new(input), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("flac");
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|err| format!("failed to probe source: {err}"))?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| "no default audio track found".to_string())?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions { verify: true })
+        .map_err(|err| format!("failed to create decoder: {err}"))?;
+
+    let mut decode_errors = 0usize;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(err)) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(_) => {
+                decode_errors += 1;
+                break;
+            }
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        if decoder.decode(&packet).is_err() {
+            decode_errors += 1;
+        }
+    }
+    if decode_errors > 0 {
+        return Ok(StreamCheck::DecodeErrors(decode_errors));
+    }
+    Ok(match decoder.finalize().verify_ok {
+        Some(true) => StreamCheck::Verified,
+        Some(false) => StreamCheck::Mismatch,
+        None => StreamCheck::NoChecksum,
+    })
+}
+
+fn is_flac(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("flac"))
+}
+
+/// Checks one file against its previous record and returns the record to store.
+pub fn check_file(
+    path: &Path,
+    previous: Option<&FileIntegrityRecord>,
+    now_unix_ms: i64,
+) -> FileIntegrityRecord {
+    let mut record = FileIntegrityRecord {
+        path: path.to_string_lossy().to_string(),
+        file_size_bytes: 0,
+        modified_unix_ms: 0,
+        content_hash: String::new(),
+        verified_unix_ms: now_unix_ms,
+        issue_kind: None,
+        issue_detail: String::new(),
+        spectrum_checked: false,
+    };
+    let unreadable = |mut record: FileIntegrityRecord, err: io::Error| {
+        if let Some(previous) = previous {
+            record.content_hash = previous.content_hash.clone();
+        }
+        record.issue_kind = Some(IntegrityIssueKind::Unreadable);
+        record.issue_detail = err.to_string();
+        record
+    };
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) => return unreadable(record, err),
+    };
+    record.file_size_bytes = i64::try_from(metadata.len()).unwrap_or(i64::MAX);
+    record.modified_unix_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0);
+    record.content_hash = match content_hash(path) {
+        Ok(hash) => hash,
+        Err(err) => return unreadable(record, err),
+    };
+
+    if let Some(previous) = previous.filter(|previous| {
+        previous.file_size_bytes == record.file_size_bytes
+            && previous.modified_unix_ms == record.modified_unix_ms
+            && !previous.content_hash.is_empty()
+    }) {
+        if previous.content_hash != record.content_hash {
+            // Keep the original baseline so the issue persists until the file is replaced.
+            record.content_hash = previous.content_hash.clone();
+            record.issue_kind = Some(IntegrityIssueKind::ContentChanged);
+            record.issue_detail =
+                "contents changed without a size or modification time change".to_string();
+            return record;
+        }
+        match previous.issue_kind {
+            None if previous.spectrum_checked || !format_quality::is_lossless_path(path) => {
+                record.spectrum_checked = previous.spectrum_checked;
+                return record;
+            }
+            // The verdict depends only on the bytes, which are unchanged.
+            Some(IntegrityIssueKind::SuspectedLossySource) => {
+                record.issue_kind = previous.issue_kind;
+                record.issue_detail = previous.issue_detail.clone();
+                record.spectrum_checked = true;
+                return record;
+            }
+            _ => {}
+        }
+    }
+
+    if is_flac(path) {
+        match verify_flac_stream(path) {
+            Ok(StreamCheck::Verified) | Ok(StreamCheck::NoChecksum) => {}
+            Ok(StreamCheck::Mismatch) => {
+                record.issue_kind = Some(IntegrityIssueKind::StreamChecksumMismatch);
+                record.issue_detail =
+                    "decoded audio does not match the FLAC stream MD5".to_string();
+            }
+            Ok(StreamCheck::DecodeErrors(count)) => {
+                record.issue_kind = Some(IntegrityIssueKind::DecodeError);
+                record.issue_detail = format!("{count} frame(s) failed to decode");
+            }
+            Err(err) => {
+                record.issue_kind = Some(IntegrityIssueKind::DecodeError);
+                record.issue_detail = err;
+            }
+        }
+    }
+
+    if record.issue_kind.is_none() && format_quality::is_lossless_path(path) {
+        // Formats the decoder cannot read are not retried until their bytes change.
+        record.spectrum_checked = true;
+        if let Ok(estimate) = transcode_detection::analyze_file(path) {
+            if estimate.is_suspicious() {
+                record.issue_kind = Some(IntegrityIssueKind::SuspectedLossySource);
+                record.issue_detail = estimate.describe();
+            }
+        }
+    }
+    record
+}
+
+/// Short display label for an issue kind.
+pub fn issue_kind_label(kind: IntegrityIssueKind) -> &'static str {
+    match kind {
+        IntegrityIssueKind::StreamChecksumMismatch => "Checksum mismatch",
+        IntegrityIssueKind::DecodeError => "Decode error",
+        IntegrityIssueKind::ContentChanged => "Silently changed",
+        IntegrityIssueKind::Unreadable => "Unreadable",
+        IntegrityIssueKind::SuspectedLossySource => "Possible lossy source",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("roqtune_integrity_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join(name);
+        fs::write(&path, contents).expect("write temp file");
+        path
+    }
+
+    #[test]
+    fn test_content_hash_is_stable_and_detects_changes() {
+        let path = temp_file("a.mp3", b"first contents");
+        let first = content_hash(&path).expect("hash file");
+        assert_eq!(first, content_hash(&path).expect("hash file again"));
+        assert_eq!(first.len(), 32);
+        fs::write(&path, b"other contents").expect("rewrite file");
+        assert_ne!(first, content_hash(&path).expect("hash changed file"));
+    }
+
+    #[test]
+    fn test_check_file_reports_silent_change_but_accepts_rewrites() {
+        let path = temp_file("a.mp3", b"original audio");
+        let baseline = check_file(&path, None, 1);
+        assert_eq!(baseline.issue_kind, None);
+
+        let tampered = FileIntegrityRecord {
+            content_hash: "0".repeat(32),
+            ..baseline.clone()
+        };
+        let silent = check_file(&path, Some(&tampered), 2);
+        assert_eq!(silent.issue_kind, Some(IntegrityIssueKind::ContentChanged));
+        assert_eq!(silent.content_hash, tampered.content_hash);
+
+        let rewritten = FileIntegrityRecord {
+            modified_unix_ms: baseline.modified_unix_ms - 1_000,
+            ..tampered
+        };
+        let accepted = check_file(&path, Some(&rewritten), 3);
+        assert_eq!(accepted.issue_kind, None);
+        assert_eq!(accepted.content_hash, baseline.content_hash);
+    }
+
+    #[test]
+    fn test_check_file_flags_missing_and_undecodable_files() {
+        let missing = check_file(Path::new("/nonexistent/roqtune/a.flac"), None, 1);
+        assert_eq!(missing.issue_kind, Some(IntegrityIssueKind::Unreadable));
+
+        let garbage = temp_file("broken.flac", b"not a flac stream");
+        let broken = check_file(&garbage, None, 1);
+        assert_eq!(broken.issue_kind, Some(IntegrityIssueKind::DecodeError));
+    }
+
+    #[test]
+    fn test_check_file_analyzes_lossless_files_once_per_content() {
+        let path = temp_file("a.wav", b"not really a wave file");
+        let baseline = check_file(&path, None, 1);
+        assert_eq!(baseline.issue_kind, None);
+        assert!(baseline.spectrum_checked);
+
+        let flagged = FileIntegrityRecord {
+            issue_kind: Some(IntegrityIssueKind::SuspectedLossySource),
+            issue_detail: "spectrum cuts off at 16.0 kHz (92% confidence)".to_string(),
+            ..baseline.clone()
+        };
+        let kept = check_file(&path, Some(&flagged), 2);
+        assert_eq!(kept.issue_kind, flagged.issue_kind);
+        assert_eq!(kept.issue_detail, flagged.issue_detail);
+
+        let lossy = temp_file("a.mp3", b"lossy audio");
+        assert!(!check_file(&lossy, None, 1).spectrum_checked);
+    }
+}
@@ -26,6 +26,7 @@ use crate::protocol::{
     LibraryEnrichmentPayload, LibraryEnrichmentPriority, LibraryEnrichmentStatus, LibraryMessage,
     Message,
 };
+use crate::sandbox_access;
 
 const WIKIPEDIA_ACTION_API_URL: &str = "https://en.wikipedia.org/w/api.php";
 const WIKIPEDIA_REST_BASE_URL: &str = "https://en.wikipedia.org/w/rest.php/v1";
@@ -39,6 +40,7 @@ const MAX_CANDIDATES: usize = 12;
 const MAX_SUMMARY_FETCHES: usize = 10;
 const MAX_BLURB_CHARS: usize = 360;
 const MAX_PENDING_PREFETCH_REQUESTS: usize = 64;
+const BATTERY_SAVER_MAX_PENDING_PREFETCH_REQUESTS: usize = 16;
 const DETAIL_FETCH_BUDGET: Duration = Duration::from_secs(6);
 const VISIBLE_PREFETCH_FETCH_BUDGET: Duration = Duration::from_millis(2500);
 const BACKGROUND_WARM_FETCH_BUDGET: Duration = Duration::from_millis(1200);
@@ -159,6 +161,8 @@ pub struct LibraryEnrichmentManager {
     in_flight_attempts: HashMap<LibraryEnrichmentEntity, LibraryEnrichmentAttemptKind>,
     deferred_not_before: HashMap<LibraryEnrichmentEntity, Instant>,
     last_background_dispatch_at: Option<Instant>,
+    /// Battery saver pauses background warming and shrinks the visible prefetch window.
+    battery_saver_active: bool,
     audiodb_limiter:
         RateLimiter<NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock>,
     http_client: ureq::Agent,
@@ -194,6 +198,7 @@ impl LibraryEnrichmentManager {
             in_flight_attempts: HashMap::new(),
             deferred_not_before: HashMap::new(),
             last_background_dispatch_at: None,
+            battery_saver_active: false,
             audiodb_limiter: RateLimiter::direct(
                 Quota::with_period(Duration::from_secs(2))
                     .expect("valid limiter period")
@@ -3167,7 +3172,7 @@ impl LibraryEnrichmentManager {
     }
 
     fn file_looks_like_supported_image(path: &Path) -> bool {
-        let mut file = match fs::File::open(path) {
+        let mut file = match sandbox_access::open_file(path) {
             Ok(file) => file,
             Err(_) => return false,
         };
@@ -3383,10 +3388,11 @@ impl LibraryEnrichmentManager {
         let queue_too_large = match attempt_kind {
             LibraryEnrichmentAttemptKind::Detail => false,
             LibraryEnrichmentAttemptKind::VisiblePrefetch => {
-                self.visible_artist_queue.len() >= MAX_PENDING_PREFETCH_REQUESTS
+                self.visible_artist_queue.len() >= self.max_pending_prefetch_requests()
             }
             LibraryEnrichmentAttemptKind::BackgroundWarm => {
-                self.background_artist_queue.len() >= MAX_PENDING_PREFETCH_REQUESTS
+                self.battery_saver_active
+                    || self.background_artist_queue.len() >= MAX_PENDING_PREFETCH_REQUESTS
             }
         };
         if queue_too_large {
@@ -3405,6 +3411,38 @@ impl LibraryEnrichmentManager {
         }
     }
 
+    fn max_pending_prefetch_requests(&self) -> usize {
+        if self.battery_saver_active {
+            BATTERY_SAVER_MAX_PENDING_PREFETCH_REQUESTS
+        } else {
+            MAX_PENDING_PREFETCH_REQUESTS
+        }
+    }
+
+    fn apply_battery_saver(&mut self, active: bool) {
+        if self.battery_saver_active == active {
+            return;
+        }
+        self.battery_saver_active = active;
+        if !active {
+            return;
+        }
+        // Drop queued background warming entirely and trim the visible prefetch window;
+        // the UI re-sends both queues as the user scrolls.
+        self.replace_background_queue(Vec::new());
+        let limit = self.max_pending_prefetch_requests();
+        while self.visible_artist_queue.len() > limit {
+            if let Some(entity) = self.visible_artist_queue.pop_back() {
+                if self.queued_attempts.get(&entity)
+                    == Some(&LibraryEnrichmentAttemptKind::VisiblePrefetch)
+                {
+                    self.queued_attempts.remove(&entity);
+                    self.deferred_not_before.remove(&entity);
+                }
+            }
+        }
+    }
+
     fn replace_prefetch_queue(&mut self, entities: Vec<LibraryEnrichmentEntity>) {
         let mut ordered = Vec::new();
         let mut desired_set = HashSet::new();
@@ -3434,7 +3472,7 @@ impl LibraryEnrichmentManager {
         self.visible_artist_queue.clear();
 
         for entity in ordered {
-            if self.visible_artist_queue.len() >= MAX_PENDING_PREFETCH_REQUESTS {
+            if self.visible_artist_queue.len() >= self.max_pending_prefetch_requests() {
                 break;
             }
             if matches!(
@@ -3486,6 +3524,9 @@ impl LibraryEnrichmentManager {
                 || in_flight_entities.contains(entity)
         });
         self.background_artist_queue.clear();
+        if self.battery_saver_active {
+            return;
+        }
 
         for entity in ordered {
             if self.background_artist_queue.len() >= MAX_PENDING_PREFETCH_REQUESTS {
@@ -3597,6 +3638,9 @@ impl LibraryEnrichmentManager {
                 }
                 self.apply_library_config_delta(&library_update);
             }
+            Message::Config(crate::protocol::ConfigMessage::BatterySaverChanged { active }) => {
+                self.apply_battery_saver(active);
+            }
             Message::Library(LibraryMessage::RequestEnrichment { entity, priority }) => {
                 let attempt_kind = match priority {
                     LibraryEnrichmentPriority::Interactive => LibraryEnrichmentAttemptKind::Detail,
@@ -0,0 +1,152 @@
+//! Genre, decade, and format facets of the library filter sidebar.
+//!
+//! Selections combine across dimensions with AND and within one dimension with OR:
+//! picking Jazz, Rock, and 1970s keeps 1970s tracks tagged either genre. Values are
+//! normalized the way the Genres and Decades views and `DbManager::get_library_facet_counts`
+//! normalize them, so a track filtered here lands in the bucket its count was shown under.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::protocol::{LibraryFacetKind, LibraryFacetSelection, LibraryTrack};
+
+/// Genre facet value of tracks without a genre tag.
+pub const UNKNOWN_GENRE: &str = "Unknown Genre";
+/// Decade facet value of tracks without a usable year tag.
+pub const UNKNOWN_DECADE: &str = "Unknown Decade";
+/// Format facet value of files without an extension.
+pub const OTHER_FORMAT: &str = "Other";
+
+/// Genre facet value of a raw genre tag.
+pub fn genre_facet(genre: &str) -> String {
+    let trimmed = genre.trim();
+    if trimmed.is_empty() {
+        UNKNOWN_GENRE.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Decade facet value of a raw year tag, e.g. `1970s` for `1974-05-01`.
+pub fn decade_facet(year: &str) -> String {
+    let trimmed = year.trim();
+    match trimmed.get(..3) {
+        Some(prefix) if prefix.chars().all(|ch| ch.is_ascii_digit()) => format!("{prefix}0s"),
+        _ => UNKNOWN_DECADE.to_string(),
+    }
+}
+
+/// Format facet value of a file: its upper-cased extension, e.g. `FLAC`.
+pub fn format_facet(path: &Path) -> String {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| !extension.is_empty())
+        .map(|extension| extension.to_ascii_uppercase())
+        .unwrap_or_else(|| OTHER_FORMAT.to_string())
+}
+
+/// Values picked in the `kind` dimension.
+pub fn selected_values(
+    selection: &LibraryFacetSelection,
+    kind: LibraryFacetKind,
+) -> &BTreeSet<String> {
+    match kind {
+        LibraryFacetKind::Genre => &selection.genres,
+        LibraryFacetKind::Decade => &selection.decades,
+        LibraryFacetKind::Format => &selection.formats,
+    }
+}
+
+/// Returns `true` when no facet value is picked.
+pub fn selection_is_empty(selection: &LibraryFacetSelection) -> bool {
+    selection.genres.is_empty() && selection.decades.is_empty() && selection.formats.is_empty()
+}
+
+/// Returns `true` when `value` is picked in the `kind` dimension.
+pub fn is_selected(selection: &LibraryFacetSelection, kind: LibraryFacetKind, value: &str) -> bool {
+    selected_values(selection, kind).contains(value)
+}
+
+/// Picks `value` in the `kind` dimension, or unpicks it when it already was.
+pub fn toggle(selection: &mut LibraryFacetSelection, kind: LibraryFacetKind, value: &str) {
+    let values = match kind {
+        LibraryFacetKind::Genre => &mut selection.genres,
+        LibraryFacetKind::Decade => &mut selection.decades,
+        LibraryFacetKind::Format => &mut selection.formats,
+    };
+    if !values.remove(value) {
+        values.insert(value.to_string());
+    }
+}
+
+/// Returns `true` when `track` passes every dimension of `selection`.
+pub fn track_matches(selection: &LibraryFacetSelection, track: &LibraryTrack) -> bool {
+    let passes =
+        |values: &BTreeSet<String>, value: String| values.is_empty() || values.contains(&value);
+    passes(&selection.genres, genre_facet(&track.genre))
+        && passes(&selection.decades, decade_facet(&track.year))
+        && passes(&selection.formats, format_facet(&track.path))
+}
+
+/// Sidebar heading of a facet dimension.
+pub fn kind_title(kind: LibraryFacetKind) -> &'static str {
+    match kind {
+        LibraryFacetKind::Genre => "Genre",
+        LibraryFacetKind::Decade => "Decade",
+        LibraryFacetKind::Format => "Format",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn track(path: &str, genre: &str, year: &str) -> LibraryTrack {
+        LibraryTrack {
+            id: path.to_string(),
+            path: PathBuf::from(path),
+            title: String::new(),
+            artist: String::new(),
+            album: String::new(),
+            album_artist: String::new(),
+            genre: genre.to_string(),
+            year: year.to_string(),
+            track_number: String::new(),
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_facet_values_are_normalized() {
+        assert_eq!(genre_facet("  Jazz "), "Jazz");
+        assert_eq!(genre_facet(" "), UNKNOWN_GENRE);
+        assert_eq!(decade_facet("1974-05-01"), "1970s");
+        assert_eq!(decade_facet("'74"), UNKNOWN_DECADE);
+        assert_eq!(format_facet(Path::new("/music/a.Flac")), "FLAC");
+        assert_eq!(format_facet(Path::new("/music.v2/track")), OTHER_FORMAT);
+    }
+
+    #[test]
+    fn test_track_matches_ands_dimensions_and_ors_values() {
+        let mut selection = LibraryFacetSelection::default();
+        let jazz = track("/music/a.flac", "Jazz", "1975");
+        let rock = track("/music/b.mp3", "Rock", "1979");
+        assert!(selection_is_empty(&selection));
+        assert!(track_matches(&selection, &jazz));
+
+        toggle(&mut selection, LibraryFacetKind::Genre, "Jazz");
+        toggle(&mut selection, LibraryFacetKind::Genre, "Rock");
+        toggle(&mut selection, LibraryFacetKind::Decade, "1970s");
+        assert!(track_matches(&selection, &jazz));
+        assert!(track_matches(&selection, &rock));
+
+        toggle(&mut selection, LibraryFacetKind::Format, "FLAC");
+        assert!(track_matches(&selection, &jazz));
+        assert!(!track_matches(&selection, &rock));
+
+        toggle(&mut selection, LibraryFacetKind::Genre, "Jazz");
+        assert!(!is_selected(&selection, LibraryFacetKind::Genre, "Jazz"));
+        assert!(!track_matches(&selection, &jazz));
+    }
+}
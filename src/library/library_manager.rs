@@ -3,23 +3,40 @@
 //! This manager maintains a lightweight metadata index for Library mode,
 //! handles manual scans, and serves pre-sorted query results over the bus.
 
-use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use log::{debug, info, warn};
 use tokio::sync::broadcast::{Receiver, Sender};
 
+use crate::activity_log;
+use crate::album_artist_inference;
+use crate::config::FolderImportRuleConfig;
 use crate::db_manager::{
     DbManager, FavoriteSyncQueueEntry, LibraryScanState, LibraryTrackMetadataUpdate,
     LibraryTrackScanStub,
 };
+use crate::db_read_pool::DbReadPool;
+use crate::file_organizer;
+use crate::format_quality;
+use crate::import_rules::{self, PendingImportActions};
 use crate::integration_uri::parse_opensubsonic_track_uri;
 use crate::metadata_tags;
-use crate::protocol::{self, IntegrationMessage, LibraryMessage, Message};
+use crate::protocol::{
+    self, IntegrationMessage, LibraryMessage, Message, MetadataMessage, TaskKind,
+};
+use crate::sandbox_access::{self, FolderAccess};
+use crate::source_matching;
+use crate::task_manager::TaskHandle;
+use crate::timed_mix;
+use crate::track_energy;
+use crate::track_relocation;
+use crate::user_tags;
+use crate::worker_pool;
 
 const SUPPORTED_AUDIO_EXTENSIONS: [&str; 7] = ["mp3", "wav", "ogg", "flac", "aac", "m4a", "mp4"];
 const LIBRARY_SCAN_UPSERT_BATCH_SIZE: usize = 256;
@@ -38,25 +55,45 @@ struct LibraryTrackMetadata {
     track_number: String,
 }
 
+type PlaylistTrackMetadataCache = Arc<Mutex<HashMap<PathBuf, protocol::LibraryTrack>>>;
+type LocalDurationCache = Arc<Mutex<HashMap<PathBuf, Option<u64>>>>;
+
+/// Manager-side state needed to build effective library rows off the manager thread.
+#[derive(Clone)]
+struct LibraryQueryContext {
+    remote_tracks: Vec<protocol::LibraryTrack>,
+    include_playlist_tracks_in_library: bool,
+    playlist_track_metadata_cache: PlaylistTrackMetadataCache,
+    local_duration_cache: LocalDurationCache,
+}
+
 /// Coordinates library index scans and query responses.
 pub struct LibraryManager {
     bus_consumer: Receiver<Message>,
     bus_producer: Sender<Message>,
     db_manager: DbManager,
+    read_pool: Option<DbReadPool>,
     library_folders: Vec<String>,
+    import_rules: Vec<FolderImportRuleConfig>,
     scan_progress_tx: SyncSender<LibraryMessage>,
     playback_active: bool,
     remote_tracks_by_profile: HashMap<String, Vec<protocol::LibraryTrack>>,
     include_playlist_tracks_in_library: bool,
-    playlist_track_metadata_cache: RefCell<HashMap<PathBuf, protocol::LibraryTrack>>,
+    playlist_track_metadata_cache: PlaylistTrackMetadataCache,
+    local_duration_cache: LocalDurationCache,
+    last_organize_moves: Vec<protocol::OrganizeFileMove>,
 }
 
 impl LibraryManager {
     /// Creates a library manager bound to bus channels and storage backend.
+    ///
+    /// When `read_pool` is present, page, root-count, and global-search queries run on
+    /// its read-only connections instead of the manager's writer connection.
     pub fn new(
         bus_consumer: Receiver<Message>,
         bus_producer: Sender<Message>,
         db_manager: DbManager,
+        read_pool: Option<DbReadPool>,
         scan_progress_tx: SyncSender<LibraryMessage>,
         initial_library_config: crate::config::LibraryConfig,
     ) -> Self {
@@ -64,13 +101,17 @@ impl LibraryManager {
             bus_consumer,
             bus_producer,
             db_manager,
+            read_pool,
             library_folders: initial_library_config.folders,
+            import_rules: initial_library_config.import_rules,
             scan_progress_tx,
             playback_active: false,
             remote_tracks_by_profile: HashMap::new(),
+            last_organize_moves: Vec::new(),
             include_playlist_tracks_in_library: initial_library_config
                 .include_playlist_tracks_in_library,
-            playlist_track_metadata_cache: RefCell::new(HashMap::new()),
+            playlist_track_metadata_cache: Arc::new(Mutex::new(HashMap::new())),
+            local_duration_cache: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -82,6 +123,31 @@ impl LibraryManager {
         merged
     }
 
+    fn query_context(&self) -> LibraryQueryContext {
+        LibraryQueryContext {
+            remote_tracks: self.all_remote_tracks(),
+            include_playlist_tracks_in_library: self.include_playlist_tracks_in_library,
+            playlist_track_metadata_cache: Arc::clone(&self.playlist_track_metadata_cache),
+            local_duration_cache: Arc::clone(&self.local_duration_cache),
+        }
+    }
+
+    /// Runs one read query on the read pool, or inline on the writer connection when
+    /// no pool is available.
+    fn dispatch_read_query(
+        &self,
+        query: impl FnOnce(&DbManager, &LibraryQueryContext, &Sender<Message>) + Send + 'static,
+    ) {
+        let context = self.query_context();
+        match self.read_pool.as_ref() {
+            Some(read_pool) => {
+                let bus_producer = self.bus_producer.clone();
+                read_pool.submit(move |db_manager| query(db_manager, &context, &bus_producer));
+            }
+            None => query(&self.db_manager, &context, &self.bus_producer),
+        }
+    }
+
     fn sort_tracks_by_title_artist_album(tracks: &mut [protocol::LibraryTrack]) {
         tracks.sort_by(|left, right| {
             left.title
@@ -127,13 +193,11 @@ impl LibraryManager {
         digits.parse::<i32>().unwrap_or(0)
     }
 
-    fn playlist_track_from_path(&self, path: &Path) -> protocol::LibraryTrack {
-        if let Some(cached) = self
-            .playlist_track_metadata_cache
-            .borrow()
-            .get(path)
-            .cloned()
-        {
+    fn playlist_track_from_path(
+        cache: &Mutex<HashMap<PathBuf, protocol::LibraryTrack>>,
+        path: &Path,
+    ) -> protocol::LibraryTrack {
+        if let Some(cached) = cache.lock().ok().and_then(|cache| cache.get(path).cloned()) {
             return cached;
         }
 
@@ -148,36 +212,81 @@ impl LibraryManager {
             genre: metadata.genre,
             year: metadata.year,
             track_number: metadata.track_number,
+            duration_ms: None,
         };
-        self.playlist_track_metadata_cache
-            .borrow_mut()
-            .insert(path.to_path_buf(), track.clone());
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(path.to_path_buf(), track.clone());
+        }
         track
     }
 
+    /// Length of a local file for remote-copy matching: the cached format probe when
+    /// the UI has stored one, otherwise a fresh probe, remembered until its tags change.
+    fn local_duration_ms(
+        db_manager: &DbManager,
+        cache: &LocalDurationCache,
+        path: &Path,
+    ) -> Option<u64> {
+        if let Some(cached) = cache.lock().ok().and_then(|cache| cache.get(path).copied()) {
+            return cached;
+        }
+        let duration_ms = db_manager
+            .get_track_formats(&[path.to_path_buf()])
+            .ok()
+            .and_then(|formats| formats.into_iter().next())
+            .map(|(_, format)| format.duration_ms)
+            .or_else(|| format_quality::probe_track_format(path).map(|format| format.duration_ms))
+            .filter(|duration_ms| *duration_ms > 0);
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(path.to_path_buf(), duration_ms);
+        }
+        duration_ms
+    }
+
     fn effective_library_tracks(&self) -> Result<Vec<protocol::LibraryTrack>, String> {
-        let mut tracks = self
-            .db_manager
-            .get_library_tracks()
+        Self::effective_library_tracks_from(&self.db_manager, &self.query_context())
+    }
+
+    fn effective_library_tracks_from(
+        db_manager: &DbManager,
+        context: &LibraryQueryContext,
+    ) -> Result<Vec<protocol::LibraryTrack>, String> {
+        // Library rows and playlist paths are read from one snapshot so a concurrent
+        // scan or playlist write cannot produce a mixed view.
+        let (mut tracks, playlist_paths) = db_manager
+            .with_read_snapshot(|db| {
+                let tracks = db.get_library_tracks()?;
+                let playlist_paths = if context.include_playlist_tracks_in_library {
+                    db.get_distinct_playlist_track_paths()?
+                } else {
+                    Vec::new()
+                };
+                Ok((tracks, playlist_paths))
+            })
             .map_err(|err| format!("Failed to load tracks: {}", err))?;
         let mut seen_paths: HashSet<PathBuf> =
             tracks.iter().map(|track| track.path.clone()).collect();
-        for track in self.all_remote_tracks() {
+        // Remote copies of local files share the local file's row.
+        seen_paths.extend(
+            source_matching::match_remote_copies(&tracks, &context.remote_tracks, |path| {
+                Self::local_duration_ms(db_manager, &context.local_duration_cache, path)
+            })
+            .into_values()
+            .flatten(),
+        );
+        for track in &context.remote_tracks {
             if seen_paths.insert(track.path.clone()) {
-                tracks.push(track);
+                tracks.push(track.clone());
             }
         }
-        if self.include_playlist_tracks_in_library {
-            let playlist_paths = self
-                .db_manager
-                .get_distinct_playlist_track_paths()
-                .map_err(|err| format!("Failed to load playlist tracks: {}", err))?;
-            for path in playlist_paths {
-                if !seen_paths.insert(path.clone()) {
-                    continue;
-                }
-                tracks.push(self.playlist_track_from_path(path.as_path()));
+        for path in playlist_paths {
+            if !seen_paths.insert(path.clone()) {
+                continue;
             }
+            tracks.push(Self::playlist_track_from_path(
+                &context.playlist_track_metadata_cache,
+                path.as_path(),
+            ));
         }
         Self::sort_tracks_by_title_artist_album(&mut tracks);
         Ok(tracks)
@@ -216,25 +325,30 @@ impl LibraryManager {
     fn effective_albums_from_tracks(
         tracks: &[protocol::LibraryTrack],
     ) -> Vec<protocol::LibraryAlbum> {
-        let mut by_album: HashMap<(String, String), (u32, Option<PathBuf>)> = HashMap::new();
+        let mut by_album: HashMap<(String, String), (u32, Option<PathBuf>, String)> =
+            HashMap::new();
         for track in tracks {
             let key = (track.album.clone(), track.album_artist.clone());
-            let entry = by_album.entry(key).or_insert((0, None));
+            let entry = by_album.entry(key).or_insert((0, None, String::new()));
             entry.0 = entry.0.saturating_add(1);
             match entry.1.as_ref() {
                 Some(existing) if existing <= &track.path => {}
                 _ => entry.1 = Some(track.path.clone()),
             }
+            if track.year > entry.2 {
+                entry.2 = track.year.clone();
+            }
         }
         let mut albums: Vec<protocol::LibraryAlbum> = by_album
             .into_iter()
             .map(
-                |((album, album_artist), (track_count, representative_track_path))| {
+                |((album, album_artist), (track_count, representative_track_path, year))| {
                     protocol::LibraryAlbum {
                         album,
                         album_artist,
                         track_count,
                         representative_track_path,
+                        year,
                     }
                 },
             )
@@ -434,7 +548,7 @@ impl LibraryManager {
         let mut tracks = Vec::new();
 
         while let Some(directory) = pending_directories.pop() {
-            let entries = match std::fs::read_dir(&directory) {
+            let entries = match sandbox_access::read_dir(&directory) {
                 Ok(entries) => entries,
                 Err(err) => {
                     debug!(
@@ -579,6 +693,7 @@ impl LibraryManager {
                 year,
                 genre,
                 track_number,
+                ..
             } = parsed;
 
             if !title.is_empty() {
@@ -719,83 +834,136 @@ impl LibraryManager {
         }
     }
 
+    /// Reports a failed scan to the UI and returns the error for the scan task.
+    fn fail_scan(&self, error_text: String) -> Result<(), String> {
+        self.push_scan_progress_update(LibraryMessage::ScanFailed(error_text.clone()), false);
+        Err(error_text)
+    }
+
     fn scan_library(&mut self) {
+        let task = TaskHandle::start(
+            &self.bus_producer,
+            TaskKind::LibraryScan,
+            "Scanning library folders",
+            true,
+            true,
+        );
+        let result = self.run_library_scan(&task);
+        task.finish(result);
+    }
+
+    fn run_library_scan(&mut self, task: &TaskHandle) -> Result<(), String> {
         self.push_scan_progress_update(LibraryMessage::ScanStarted, false);
 
         let scan_started_unix_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|duration| duration.as_millis() as i64)
             .unwrap_or(0);
-        let existing_scan_states: HashMap<String, LibraryScanState> = match self
-            .db_manager
-            .get_library_scan_states_by_path()
-        {
-            Ok(states) => states,
-            Err(err) => {
-                self.push_scan_progress_update(
-                    LibraryMessage::ScanFailed(format!("Failed to load scan baseline: {}", err)),
-                    false,
-                );
-                return;
-            }
-        };
+        let existing_scan_states: HashMap<String, LibraryScanState> =
+            match self.db_manager.get_library_scan_states_by_path() {
+                Ok(states) => states,
+                Err(err) => {
+                    return self.fail_scan(format!("Failed to load scan baseline: {}", err));
+                }
+            };
 
         let mut all_files = Vec::new();
+        let mut unreadable_folders = Vec::new();
+        let mut access_warnings = Vec::new();
         for folder in &self.library_folders {
             if folder.trim().is_empty() {
                 continue;
             }
             let folder_path = PathBuf::from(folder);
-            if !folder_path.exists() {
+            let access = sandbox_access::check_folder_access(&folder_path);
+            if access != FolderAccess::Available {
                 warn!(
-                    "Library scan: folder does not exist: {}",
-                    folder_path.display()
+                    "Library scan: skipping folder {} ({:?})",
+                    folder_path.display(),
+                    access
                 );
+                access_warnings.extend(sandbox_access::access_message(&folder_path, access));
+                if access == FolderAccess::Denied {
+                    unreadable_folders.push(folder_path);
+                }
                 continue;
             }
             let files = Self::collect_audio_files_from_folder(&folder_path);
             all_files.extend(files);
         }
         all_files.sort_unstable();
+        if !access_warnings.is_empty() {
+            self.push_scan_progress_update(
+                LibraryMessage::ScanFolderAccessWarnings(access_warnings),
+                false,
+            );
+        }
 
-        let mut scanned_paths: HashSet<String> = HashSet::new();
+        // Tracks under folders we can no longer read are kept rather than pruned; the
+        // files are most likely still there behind a revoked sandbox grant.
+        let mut scanned_paths: HashSet<String> = existing_scan_states
+            .keys()
+            .filter(|path| {
+                unreadable_folders
+                    .iter()
+                    .any(|folder| Path::new(path.as_str()).starts_with(folder))
+            })
+            .cloned()
+            .collect();
         let mut scan_stubs_batch: Vec<LibraryTrackScanStub> =
             Vec::with_capacity(LIBRARY_SCAN_UPSERT_BATCH_SIZE);
-        let mut metadata_backfill_targets: Vec<(PathBuf, String, i64, i64)> = Vec::new();
+        let mut metadata_backfill_targets: Vec<(PathBuf, String, i64, i64, Option<String>)> =
+            Vec::new();
+        let mut pending_import_actions = PendingImportActions::default();
         let mut discovered = 0usize;
         let mut indexed = 0usize;
         let mut metadata_pending = 0usize;
+        let mut imported = 0usize;
+        let total_files = all_files.len() as u64;
 
         for file_path in all_files {
+            if !task.wait_while_paused() {
+                return self.fail_scan("Library scan cancelled".to_string());
+            }
             let path_string = file_path.to_string_lossy().to_string();
             let (modified_unix_ms, file_size_bytes) = Self::file_scan_state(&file_path);
             let track_id = Self::stable_library_track_id(&file_path);
             scanned_paths.insert(path_string.clone());
             discovered = discovered.saturating_add(1);
 
-            let needs_metadata = existing_scan_states
-                .get(&path_string)
+            let existing_state = existing_scan_states.get(&path_string);
+            let needs_metadata = existing_state
                 .map(|state| {
                     state.modified_unix_ms != modified_unix_ms
                         || state.file_size_bytes != file_size_bytes
                         || !state.metadata_ready
                 })
                 .unwrap_or(true);
+            let rule_actions = import_rules::resolve_import_rules(&self.import_rules, &file_path);
+            if existing_state.is_none() {
+                pending_import_actions.record(&file_path, &rule_actions);
+                imported = imported.saturating_add(1);
+            }
 
             if needs_metadata {
-                scan_stubs_batch.push(Self::fallback_scan_stub(
+                let mut scan_stub = Self::fallback_scan_stub(
                     &file_path,
                     path_string.clone(),
                     track_id,
                     modified_unix_ms,
                     file_size_bytes,
                     scan_started_unix_ms,
-                ));
+                );
+                if let Some(genre) = &rule_actions.genre {
+                    scan_stub.genre = genre.clone();
+                }
+                scan_stubs_batch.push(scan_stub);
                 metadata_backfill_targets.push((
                     file_path,
                     path_string,
                     modified_unix_ms,
                     file_size_bytes,
+                    rule_actions.genre,
                 ));
                 metadata_pending = metadata_pending.saturating_add(1);
                 if scan_stubs_batch.len() >= LIBRARY_SCAN_UPSERT_BATCH_SIZE {
@@ -803,15 +971,11 @@ impl LibraryManager {
                         .db_manager
                         .upsert_library_track_scan_stub_batch(&scan_stubs_batch)
                     {
-                        self.push_scan_progress_update(
-                            LibraryMessage::ScanFailed(format!(
-                                "Failed to upsert scan batch ({} rows): {}",
-                                scan_stubs_batch.len(),
-                                err
-                            )),
-                            false,
-                        );
-                        return;
+                        return self.fail_scan(format!(
+                            "Failed to upsert scan batch ({} rows): {}",
+                            scan_stubs_batch.len(),
+                            err
+                        ));
                     }
                     indexed = indexed.saturating_add(scan_stubs_batch.len());
                     scan_stubs_batch.clear();
@@ -829,6 +993,11 @@ impl LibraryManager {
                     },
                     true,
                 );
+                task.progress(
+                    discovered as u64,
+                    Some(total_files),
+                    format!("{indexed} indexed, {metadata_pending} need tags"),
+                );
             }
             self.maybe_cooperate_for_playback(discovered);
         }
@@ -838,15 +1007,11 @@ impl LibraryManager {
                 .db_manager
                 .upsert_library_track_scan_stub_batch(&scan_stubs_batch)
             {
-                self.push_scan_progress_update(
-                    LibraryMessage::ScanFailed(format!(
-                        "Failed to upsert final scan batch ({} rows): {}",
-                        scan_stubs_batch.len(),
-                        err
-                    )),
-                    false,
-                );
-                return;
+                return self.fail_scan(format!(
+                    "Failed to upsert final scan batch ({} rows): {}",
+                    scan_stubs_batch.len(),
+                    err
+                ));
             }
         }
 
@@ -854,14 +1019,24 @@ impl LibraryManager {
             .db_manager
             .delete_library_paths_not_in_set(&scanned_paths)
         {
-            self.push_scan_progress_update(
-                LibraryMessage::ScanFailed(format!(
-                    "Failed to prune removed library files: {}",
-                    err
-                )),
-                false,
+            return self.fail_scan(format!("Failed to prune removed library files: {}", err));
+        }
+
+        self.apply_pending_import_actions(pending_import_actions);
+
+        let removed = existing_scan_states
+            .keys()
+            .filter(|path| !scanned_paths.contains(*path))
+            .count();
+        if imported > 0 || removed > 0 {
+            activity_log::record(
+                &self.bus_producer,
+                protocol::ActivityCategory::Library,
+                format!(
+                    "Library scan imported {} new track(s) and removed {} missing track(s)",
+                    imported, removed
+                ),
             );
-            return;
         }
 
         self.push_scan_progress_update(
@@ -871,37 +1046,61 @@ impl LibraryManager {
             false,
         );
 
-        let mut metadata_batch: Vec<LibraryTrackMetadataUpdate> =
-            Vec::with_capacity(LIBRARY_SCAN_METADATA_BATCH_SIZE);
+        // Tag reads fan out over the shared decode pool one batch at a time, so rows
+        // are still written and reported in scan order.
         let mut metadata_updated = 0usize;
         let total_pending = metadata_backfill_targets.len();
-        for (target_index, (file_path, path_string, modified_unix_ms, file_size_bytes)) in
-            metadata_backfill_targets.into_iter().enumerate()
-        {
-            metadata_batch.push(Self::metadata_update_from_file(
-                &file_path,
-                path_string,
-                modified_unix_ms,
-                file_size_bytes,
-                scan_started_unix_ms,
-            ));
-            if metadata_batch.len() >= LIBRARY_SCAN_METADATA_BATCH_SIZE {
-                if let Err(err) = self
-                    .db_manager
-                    .update_library_track_metadata_batch(&metadata_batch)
-                {
-                    self.push_scan_progress_update(
-                        LibraryMessage::ScanFailed(format!(
-                            "Failed metadata backfill batch ({} rows): {}",
-                            metadata_batch.len(),
-                            err
-                        )),
-                        false,
+        let mut remaining_targets = metadata_backfill_targets.into_iter().peekable();
+        while remaining_targets.peek().is_some() {
+            if !task.wait_while_paused() {
+                return self.fail_scan(format!(
+                    "Library scan cancelled; tags of {} track(s) are read on the next scan",
+                    total_pending.saturating_sub(metadata_updated)
+                ));
+            }
+            task.progress(
+                metadata_updated as u64,
+                Some(total_pending as u64),
+                "Reading tags",
+            );
+            let batch_targets: Vec<_> = remaining_targets
+                .by_ref()
+                .take(LIBRARY_SCAN_METADATA_BATCH_SIZE)
+                .collect();
+            let metadata_batch: Vec<LibraryTrackMetadataUpdate> = worker_pool::decode_pool().map(
+                batch_targets,
+                move |(
+                    file_path,
+                    path_string,
+                    modified_unix_ms,
+                    file_size_bytes,
+                    genre_override,
+                )| {
+                    let mut metadata_update = Self::metadata_update_from_file(
+                        &file_path,
+                        path_string,
+                        modified_unix_ms,
+                        file_size_bytes,
+                        scan_started_unix_ms,
                     );
-                    return;
-                }
-                metadata_updated = metadata_updated.saturating_add(metadata_batch.len());
-                metadata_batch.clear();
+                    if let Some(genre) = genre_override {
+                        metadata_update.genre = genre;
+                    }
+                    metadata_update
+                },
+            );
+            if let Err(err) = self
+                .db_manager
+                .update_library_track_metadata_batch(&metadata_batch)
+            {
+                return self.fail_scan(format!(
+                    "Failed metadata backfill batch ({} rows): {}",
+                    metadata_batch.len(),
+                    err
+                ));
+            }
+            metadata_updated = metadata_updated.saturating_add(metadata_batch.len());
+            if remaining_targets.peek().is_some() {
                 self.push_scan_progress_update(
                     LibraryMessage::MetadataBackfillProgress {
                         updated: metadata_updated,
@@ -910,25 +1109,9 @@ impl LibraryManager {
                     true,
                 );
             }
-            self.maybe_cooperate_for_playback(target_index.saturating_add(1));
-        }
-
-        if !metadata_batch.is_empty() {
-            if let Err(err) = self
-                .db_manager
-                .update_library_track_metadata_batch(&metadata_batch)
-            {
-                self.push_scan_progress_update(
-                    LibraryMessage::ScanFailed(format!(
-                        "Failed final metadata backfill batch ({} rows): {}",
-                        metadata_batch.len(),
-                        err
-                    )),
-                    false,
-                );
-                return;
+            if self.playback_active {
+                std::thread::sleep(LIBRARY_SCAN_PLAYBACK_COOPERATE_SLEEP);
             }
-            metadata_updated = metadata_updated.saturating_add(metadata_batch.len());
         }
         if total_pending > 0 {
             self.push_scan_progress_update(
@@ -945,6 +1128,72 @@ impl LibraryManager {
             scanned_paths.len(),
             total_pending
         );
+        Ok(())
+    }
+
+    /// Applies import-rule tags, playlist additions, and shuffle exclusions to the paths
+    /// first imported by the current scan.
+    fn apply_pending_import_actions(&self, pending: PendingImportActions) {
+        if pending.is_empty() {
+            return;
+        }
+        if !pending.paths_by_tag.is_empty() {
+            let mut known_tags = self.db_manager.get_user_tags().unwrap_or_default();
+            for (tag, paths) in &pending.paths_by_tag {
+                let key = user_tags::tag_key(tag);
+                let stored_name = match known_tags
+                    .iter()
+                    .find(|known| user_tags::tag_key(&known.name) == key)
+                {
+                    Some(known) => known.name.clone(),
+                    None => {
+                        let color = user_tags::next_palette_color(&known_tags);
+                        if let Err(err) = self.db_manager.upsert_user_tag(tag, &color) {
+                            warn!("Failed to create import-rule tag '{}': {}", tag, err);
+                            continue;
+                        }
+                        known_tags.push(protocol::UserTag {
+                            name: tag.clone(),
+                            color,
+                        });
+                        tag.clone()
+                    }
+                };
+                if let Err(err) = self
+                    .db_manager
+                    .set_track_user_tag(paths, &stored_name, true)
+                {
+                    warn!(
+                        "Failed to apply import-rule tag '{}' to {} track(s): {}",
+                        stored_name,
+                        paths.len(),
+                        err
+                    );
+                }
+            }
+            self.publish_user_tags_snapshot();
+        }
+        for (playlist, paths) in pending.paths_by_playlist {
+            info!(
+                "Import rules: adding {} track(s) to playlist '{}'",
+                paths.len(),
+                playlist
+            );
+            let _ = self.bus_producer.send(Message::Playlist(
+                protocol::PlaylistMessage::AddTracksToPlaylistByName {
+                    name: playlist,
+                    paths,
+                },
+            ));
+        }
+        if !pending.shuffle_excluded_paths.is_empty() {
+            let _ = self.bus_producer.send(Message::Playlist(
+                protocol::PlaylistMessage::SetTracksExcludedFromShuffle {
+                    paths: pending.shuffle_excluded_paths,
+                    excluded: true,
+                },
+            ));
+        }
     }
 
     fn publish_tracks(&self) {
@@ -1007,48 +1256,48 @@ impl LibraryManager {
     }
 
     fn publish_global_search_data(&self) {
-        let tracks = match self.effective_library_tracks() {
-            Ok(tracks) => tracks,
-            Err(error_text) => {
-                self.send_scan_failed(error_text);
-                return;
-            }
-        };
-        let artists = Self::effective_artists_from_tracks(&tracks);
-        let albums = Self::effective_albums_from_tracks(&tracks);
+        self.dispatch_read_query(|db_manager, context, bus_producer| {
+            let tracks = match Self::effective_library_tracks_from(db_manager, context) {
+                Ok(tracks) => tracks,
+                Err(error_text) => {
+                    let _ =
+                        bus_producer.send(Message::Library(LibraryMessage::ScanFailed(error_text)));
+                    return;
+                }
+            };
+            let artists = Self::effective_artists_from_tracks(&tracks);
+            let albums = Self::effective_albums_from_tracks(&tracks);
 
-        let _ = self
-            .bus_producer
-            .send(Message::Library(LibraryMessage::GlobalSearchDataResult {
+            let _ = bus_producer.send(Message::Library(LibraryMessage::GlobalSearchDataResult {
                 tracks,
                 artists,
                 albums,
             }));
+        });
     }
 
     fn publish_root_counts(&self) {
-        let tracks = match self.effective_library_tracks() {
-            Ok(tracks) => tracks,
-            Err(err) => {
-                warn!("Failed to load effective library tracks: {}", err);
-                return;
-            }
-        };
-        let artists = Self::effective_artists_from_tracks(&tracks);
-        let albums = Self::effective_albums_from_tracks(&tracks);
-        let genres = Self::effective_genres_from_tracks(&tracks);
-        let decades = Self::effective_decades_from_tracks(&tracks);
-        let favorites = match self.db_manager.get_favorites_count() {
-            Ok(count) => count,
-            Err(err) => {
-                warn!("Failed to load favorites count: {}", err);
-                return;
-            }
-        };
+        self.dispatch_read_query(|db_manager, context, bus_producer| {
+            let tracks = match Self::effective_library_tracks_from(db_manager, context) {
+                Ok(tracks) => tracks,
+                Err(err) => {
+                    warn!("Failed to load effective library tracks: {}", err);
+                    return;
+                }
+            };
+            let artists = Self::effective_artists_from_tracks(&tracks);
+            let albums = Self::effective_albums_from_tracks(&tracks);
+            let genres = Self::effective_genres_from_tracks(&tracks);
+            let decades = Self::effective_decades_from_tracks(&tracks);
+            let favorites = match db_manager.get_favorites_count() {
+                Ok(count) => count,
+                Err(err) => {
+                    warn!("Failed to load favorites count: {}", err);
+                    return;
+                }
+            };
 
-        let _ = self
-            .bus_producer
-            .send(Message::Library(LibraryMessage::RootCountsResult {
+            let _ = bus_producer.send(Message::Library(LibraryMessage::RootCountsResult {
                 tracks: tracks.len(),
                 artists: artists.len(),
                 albums: albums.len(),
@@ -1056,6 +1305,39 @@ impl LibraryManager {
                 decades: decades.len(),
                 favorites,
             }));
+        });
+    }
+
+    fn publish_facet_counts(&self, selection: protocol::LibraryFacetSelection) {
+        self.dispatch_read_query(move |db_manager, _context, bus_producer| {
+            match db_manager.get_library_facet_counts(&selection) {
+                Ok(counts) => {
+                    let _ = bus_producer
+                        .send(Message::Library(LibraryMessage::FacetCountsResult(counts)));
+                }
+                Err(err) => warn!("Failed to load library facet counts: {}", err),
+            }
+        });
+    }
+
+    fn publish_remote_copies(&self) {
+        self.dispatch_read_query(|db_manager, context, bus_producer| {
+            let local_tracks = match db_manager.get_library_tracks() {
+                Ok(tracks) => tracks,
+                Err(err) => {
+                    warn!("Failed to load library tracks for remote copies: {}", err);
+                    return;
+                }
+            };
+            let copies = source_matching::match_remote_copies(
+                &local_tracks,
+                &context.remote_tracks,
+                |path| Self::local_duration_ms(db_manager, &context.local_duration_cache, path),
+            );
+            let _ = bus_producer.send(Message::Library(LibraryMessage::RemoteCopiesSnapshot(
+                copies.into_iter().collect(),
+            )));
+        });
     }
 
     fn publish_favorites_snapshot(&self) {
@@ -1073,6 +1355,82 @@ impl LibraryManager {
         }
     }
 
+    fn publish_user_tags_snapshot(&self) {
+        let snapshot = self.db_manager.get_user_tags().and_then(|tags| {
+            self.db_manager
+                .get_track_user_tag_assignments()
+                .map(|assignments| (tags, assignments))
+        });
+        match snapshot {
+            Ok((tags, assignments)) => {
+                let _ =
+                    self.bus_producer
+                        .send(Message::Library(LibraryMessage::UserTagsSnapshot {
+                            tags,
+                            assignments,
+                        }));
+            }
+            Err(err) => warn!("Failed to load user tags snapshot: {}", err),
+        }
+    }
+
+    fn upsert_user_tag(&self, name: &str, color: &str) {
+        let Some(name) = user_tags::normalize_tag_name(name) else {
+            return;
+        };
+        let color = user_tags::normalize_tag_color(color);
+        if let Err(err) = self.db_manager.upsert_user_tag(&name, &color) {
+            warn!("Failed to save user tag '{}': {}", name, err);
+            return;
+        }
+        self.publish_user_tags_snapshot();
+    }
+
+    fn delete_user_tag(&self, name: &str) {
+        if let Err(err) = self.db_manager.delete_user_tag(name) {
+            warn!("Failed to delete user tag '{}': {}", name, err);
+            return;
+        }
+        self.publish_user_tags_snapshot();
+    }
+
+    fn set_track_user_tag(&self, paths: &[PathBuf], tag: &str, assigned: bool) {
+        if let Err(err) = self.db_manager.set_track_user_tag(paths, tag, assigned) {
+            warn!(
+                "Failed to update tag '{}' on {} track(s): {}",
+                tag,
+                paths.len(),
+                err
+            );
+            return;
+        }
+        self.publish_user_tags_snapshot();
+    }
+
+    fn publish_track_notes_snapshot(&self) {
+        match self.db_manager.get_track_notes() {
+            Ok(notes) => {
+                let _ =
+                    self.bus_producer
+                        .send(Message::Library(LibraryMessage::TrackNotesSnapshot {
+                            notes,
+                        }));
+            }
+            Err(err) => warn!("Failed to load track notes snapshot: {}", err),
+        }
+    }
+
+    fn set_track_note(&self, path: &Path, note: &str) {
+        if let Err(err) = self
+            .db_manager
+            .set_track_note(path, note, Self::unix_now_ms())
+        {
+            warn!("Failed to save note for {}: {}", path.display(), err);
+            return;
+        }
+        self.publish_track_notes_snapshot();
+    }
+
     fn publish_favorites_root_page(&self, request_id: u64, offset: usize, limit: usize) {
         let track_count = self
             .db_manager
@@ -1147,6 +1505,7 @@ impl LibraryManager {
                                 genre: String::new(),
                                 year: String::new(),
                                 track_number: String::new(),
+                                duration_ms: None,
                             })
                         }
                         protocol::FavoriteEntityKind::Artist => {
@@ -1170,7 +1529,7 @@ impl LibraryManager {
                             })
                         }
                         protocol::FavoriteEntityKind::Album => {
-                            let (track_count, representative_track_path) =
+                            let (track_count, representative_track_path, year) =
                                 match self.db_manager.get_library_album_tracks(
                                     &favorite.display_primary,
                                     &favorite.display_secondary,
@@ -1178,14 +1537,19 @@ impl LibraryManager {
                                     Ok(tracks) => {
                                         let representative_track_path =
                                             tracks.first().map(|track| track.path.clone());
-                                        (tracks.len() as u32, representative_track_path)
+                                        let year = tracks
+                                            .iter()
+                                            .map(|track| track.year.clone())
+                                            .max()
+                                            .unwrap_or_default();
+                                        (tracks.len() as u32, representative_track_path, year)
                                     }
                                     Err(err) => {
                                         warn!(
                                         "Failed to load album favorite detail for '{} / {}': {}",
                                         favorite.display_primary, favorite.display_secondary, err
                                     );
-                                        (0, None)
+                                        (0, None, String::new())
                                     }
                                 };
                             protocol::LibraryEntryPayload::Album(protocol::LibraryAlbum {
@@ -1193,6 +1557,7 @@ impl LibraryManager {
                                 album_artist: favorite.display_secondary.clone(),
                                 track_count,
                                 representative_track_path,
+                                year,
                             })
                         }
                     })
@@ -1445,64 +1810,9 @@ impl LibraryManager {
         _query: String,
     ) {
         let limit = limit.max(1);
-        let result: Result<(usize, Vec<protocol::LibraryEntryPayload>), String> = match view {
-            protocol::LibraryViewQuery::Tracks => self.effective_library_tracks().map(|rows| {
-                let total = rows.len();
-                let entries = rows
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .map(protocol::LibraryEntryPayload::Track)
-                    .collect();
-                (total, entries)
-            }),
-            protocol::LibraryViewQuery::Artists => self.effective_library_tracks().map(|tracks| {
-                let rows = Self::effective_artists_from_tracks(&tracks);
-                let total = rows.len();
-                let entries = rows
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .map(protocol::LibraryEntryPayload::Artist)
-                    .collect();
-                (total, entries)
-            }),
-            protocol::LibraryViewQuery::Albums => self.effective_library_tracks().map(|tracks| {
-                let rows = Self::effective_albums_from_tracks(&tracks);
-                let total = rows.len();
-                let entries = rows
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .map(protocol::LibraryEntryPayload::Album)
-                    .collect();
-                (total, entries)
-            }),
-            protocol::LibraryViewQuery::Genres => self.effective_library_tracks().map(|tracks| {
-                let rows = Self::effective_genres_from_tracks(&tracks);
-                let total = rows.len();
-                let entries = rows
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .map(protocol::LibraryEntryPayload::Genre)
-                    .collect();
-                (total, entries)
-            }),
-            protocol::LibraryViewQuery::Decades => self.effective_library_tracks().map(|tracks| {
-                let rows = Self::effective_decades_from_tracks(&tracks);
-                let total = rows.len();
-                let entries = rows
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .map(protocol::LibraryEntryPayload::Decade)
-                    .collect();
-                (total, entries)
-            }),
+        match view {
             protocol::LibraryViewQuery::FavoritesRoot => {
                 self.publish_favorites_root_page(request_id, offset, limit);
-                return;
             }
             protocol::LibraryViewQuery::FavoriteTracks => {
                 self.publish_favorite_entities_page(
@@ -1511,7 +1821,6 @@ impl LibraryManager {
                     offset,
                     limit,
                 );
-                return;
             }
             protocol::LibraryViewQuery::FavoriteArtists => {
                 self.publish_favorite_entities_page(
@@ -1520,7 +1829,6 @@ impl LibraryManager {
                     offset,
                     limit,
                 );
-                return;
             }
             protocol::LibraryViewQuery::FavoriteAlbums => {
                 self.publish_favorite_entities_page(
@@ -1529,90 +1837,202 @@ impl LibraryManager {
                     offset,
                     limit,
                 );
-                return;
             }
-            protocol::LibraryViewQuery::GlobalSearch => self
-                .effective_library_tracks()
-                .map(|tracks| {
-                    let artists = Self::effective_artists_from_tracks(&tracks);
-                    let albums = Self::effective_albums_from_tracks(&tracks);
-                    let mut entries: Vec<protocol::LibraryEntryPayload> =
-                        Vec::with_capacity(tracks.len() + artists.len() + albums.len());
-                    entries.extend(tracks.into_iter().map(protocol::LibraryEntryPayload::Track));
-                    entries.extend(
-                        artists
+            view => self.dispatch_read_query(move |db_manager, context, bus_producer| {
+                let message =
+                    match Self::library_page_rows(db_manager, context, view, offset, limit) {
+                        Ok((total, entries)) => LibraryMessage::LibraryPageResult {
+                            request_id,
+                            total,
+                            entries,
+                        },
+                        Err(error_text) => LibraryMessage::ScanFailed(error_text),
+                    };
+                let _ = bus_producer.send(Message::Library(message));
+            }),
+        }
+    }
+
+    fn library_page_rows(
+        db_manager: &DbManager,
+        context: &LibraryQueryContext,
+        view: protocol::LibraryViewQuery,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(usize, Vec<protocol::LibraryEntryPayload>), String> {
+        match view {
+            protocol::LibraryViewQuery::Tracks => {
+                Self::effective_library_tracks_from(db_manager, context).map(|rows| {
+                    let total = rows.len();
+                    let entries = rows
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .map(protocol::LibraryEntryPayload::Track)
+                        .collect();
+                    (total, entries)
+                })
+            }
+            protocol::LibraryViewQuery::Artists => {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
+                    let rows = Self::effective_artists_from_tracks(&tracks);
+                    let total = rows.len();
+                    let entries = rows
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .map(protocol::LibraryEntryPayload::Artist)
+                        .collect();
+                    (total, entries)
+                })
+            }
+            // Without remote or playlist-only tracks to merge in, album pages come straight
+            // from the index so large libraries never load every track for the album grid.
+            protocol::LibraryViewQuery::Albums
+                if context.remote_tracks.is_empty()
+                    && !context.include_playlist_tracks_in_library =>
+            {
+                db_manager
+                    .with_read_snapshot(|db| db.get_library_albums_page(offset, limit))
+                    .map(|(rows, total)| {
+                        let entries = rows
                             .into_iter()
-                            .map(protocol::LibraryEntryPayload::Artist),
-                    );
-                    entries.extend(albums.into_iter().map(protocol::LibraryEntryPayload::Album));
-                    entries.sort_by(|left, right| {
-                        let left_key = match left {
-                            protocol::LibraryEntryPayload::Track(track) => {
-                                track.title.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Artist(artist) => {
-                                artist.artist.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Album(album) => {
-                                album.album.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Genre(genre) => {
-                                genre.genre.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Decade(decade) => {
-                                decade.decade.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::FavoriteCategory(category) => {
-                                category.title.to_ascii_lowercase()
-                            }
-                        };
-                        let right_key = match right {
-                            protocol::LibraryEntryPayload::Track(track) => {
-                                track.title.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Artist(artist) => {
-                                artist.artist.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Album(album) => {
-                                album.album.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Genre(genre) => {
-                                genre.genre.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::Decade(decade) => {
-                                decade.decade.to_ascii_lowercase()
-                            }
-                            protocol::LibraryEntryPayload::FavoriteCategory(category) => {
-                                category.title.to_ascii_lowercase()
-                            }
-                        };
-                        let left_kind_rank = match left {
-                            protocol::LibraryEntryPayload::Artist(_) => 0,
-                            protocol::LibraryEntryPayload::Album(_) => 1,
-                            protocol::LibraryEntryPayload::Track(_) => 2,
-                            protocol::LibraryEntryPayload::Genre(_) => 3,
-                            protocol::LibraryEntryPayload::Decade(_) => 4,
-                            protocol::LibraryEntryPayload::FavoriteCategory(_) => 5,
-                        };
-                        let right_kind_rank = match right {
-                            protocol::LibraryEntryPayload::Artist(_) => 0,
-                            protocol::LibraryEntryPayload::Album(_) => 1,
-                            protocol::LibraryEntryPayload::Track(_) => 2,
-                            protocol::LibraryEntryPayload::Genre(_) => 3,
-                            protocol::LibraryEntryPayload::Decade(_) => 4,
-                            protocol::LibraryEntryPayload::FavoriteCategory(_) => 5,
-                        };
-                        left_key
-                            .cmp(&right_key)
-                            .then_with(|| left_kind_rank.cmp(&right_kind_rank))
-                    });
-                    let total = entries.len();
-                    let rows = entries.into_iter().skip(offset).take(limit).collect();
-                    (total, rows)
+                            .map(protocol::LibraryEntryPayload::Album)
+                            .collect();
+                        (total, entries)
+                    })
+                    .map_err(|err| format!("Failed to load albums: {}", err))
+            }
+            protocol::LibraryViewQuery::Albums => {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
+                    let rows = Self::effective_albums_from_tracks(&tracks);
+                    let total = rows.len();
+                    let entries = rows
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .map(protocol::LibraryEntryPayload::Album)
+                        .collect();
+                    (total, entries)
                 })
-                .map_err(|err| format!("Failed to load global search page: {}", err)),
+            }
+            protocol::LibraryViewQuery::Genres => {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
+                    let rows = Self::effective_genres_from_tracks(&tracks);
+                    let total = rows.len();
+                    let entries = rows
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .map(protocol::LibraryEntryPayload::Genre)
+                        .collect();
+                    (total, entries)
+                })
+            }
+            protocol::LibraryViewQuery::Decades => {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
+                    let rows = Self::effective_decades_from_tracks(&tracks);
+                    let total = rows.len();
+                    let entries = rows
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .map(protocol::LibraryEntryPayload::Decade)
+                        .collect();
+                    (total, entries)
+                })
+            }
+            protocol::LibraryViewQuery::FavoritesRoot
+            | protocol::LibraryViewQuery::FavoriteTracks
+            | protocol::LibraryViewQuery::FavoriteArtists
+            | protocol::LibraryViewQuery::FavoriteAlbums => {
+                unreachable!("favorites pages are served on the manager thread")
+            }
+            protocol::LibraryViewQuery::GlobalSearch => {
+                Self::effective_library_tracks_from(db_manager, context)
+                    .map(|tracks| {
+                        let artists = Self::effective_artists_from_tracks(&tracks);
+                        let albums = Self::effective_albums_from_tracks(&tracks);
+                        let mut entries: Vec<protocol::LibraryEntryPayload> =
+                            Vec::with_capacity(tracks.len() + artists.len() + albums.len());
+                        entries
+                            .extend(tracks.into_iter().map(protocol::LibraryEntryPayload::Track));
+                        entries.extend(
+                            artists
+                                .into_iter()
+                                .map(protocol::LibraryEntryPayload::Artist),
+                        );
+                        entries
+                            .extend(albums.into_iter().map(protocol::LibraryEntryPayload::Album));
+                        entries.sort_by(|left, right| {
+                            let left_key = match left {
+                                protocol::LibraryEntryPayload::Track(track) => {
+                                    track.title.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Artist(artist) => {
+                                    artist.artist.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Album(album) => {
+                                    album.album.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Genre(genre) => {
+                                    genre.genre.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Decade(decade) => {
+                                    decade.decade.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::FavoriteCategory(category) => {
+                                    category.title.to_ascii_lowercase()
+                                }
+                            };
+                            let right_key = match right {
+                                protocol::LibraryEntryPayload::Track(track) => {
+                                    track.title.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Artist(artist) => {
+                                    artist.artist.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Album(album) => {
+                                    album.album.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Genre(genre) => {
+                                    genre.genre.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::Decade(decade) => {
+                                    decade.decade.to_ascii_lowercase()
+                                }
+                                protocol::LibraryEntryPayload::FavoriteCategory(category) => {
+                                    category.title.to_ascii_lowercase()
+                                }
+                            };
+                            let left_kind_rank = match left {
+                                protocol::LibraryEntryPayload::Artist(_) => 0,
+                                protocol::LibraryEntryPayload::Album(_) => 1,
+                                protocol::LibraryEntryPayload::Track(_) => 2,
+                                protocol::LibraryEntryPayload::Genre(_) => 3,
+                                protocol::LibraryEntryPayload::Decade(_) => 4,
+                                protocol::LibraryEntryPayload::FavoriteCategory(_) => 5,
+                            };
+                            let right_kind_rank = match right {
+                                protocol::LibraryEntryPayload::Artist(_) => 0,
+                                protocol::LibraryEntryPayload::Album(_) => 1,
+                                protocol::LibraryEntryPayload::Track(_) => 2,
+                                protocol::LibraryEntryPayload::Genre(_) => 3,
+                                protocol::LibraryEntryPayload::Decade(_) => 4,
+                                protocol::LibraryEntryPayload::FavoriteCategory(_) => 5,
+                            };
+                            left_key
+                                .cmp(&right_key)
+                                .then_with(|| left_kind_rank.cmp(&right_kind_rank))
+                        });
+                        let total = entries.len();
+                        let rows = entries.into_iter().skip(offset).take(limit).collect();
+                        (total, rows)
+                    })
+                    .map_err(|err| format!("Failed to load global search page: {}", err))
+            }
             protocol::LibraryViewQuery::ArtistDetail { artist } => {
-                self.effective_library_tracks().map(|tracks| {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
                     let rows = Self::tracks_for_artist_detail(&tracks, &artist);
                     let total = rows.len();
                     let entries = rows
@@ -1627,7 +2047,7 @@ impl LibraryManager {
             protocol::LibraryViewQuery::AlbumDetail {
                 album,
                 album_artist,
-            } => self.effective_library_tracks().map(|tracks| {
+            } => Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
                 let rows = Self::tracks_for_album_detail(&tracks, &album, &album_artist);
                 let total = rows.len();
                 let entries = rows
@@ -1639,7 +2059,7 @@ impl LibraryManager {
                 (total, entries)
             }),
             protocol::LibraryViewQuery::GenreDetail { genre } => {
-                self.effective_library_tracks().map(|tracks| {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
                     let rows = Self::tracks_for_genre_detail(&tracks, &genre);
                     let total = rows.len();
                     let entries = rows
@@ -1652,7 +2072,7 @@ impl LibraryManager {
                 })
             }
             protocol::LibraryViewQuery::DecadeDetail { decade } => {
-                self.effective_library_tracks().map(|tracks| {
+                Self::effective_library_tracks_from(db_manager, context).map(|tracks| {
                     let rows = Self::tracks_for_decade_detail(&tracks, &decade);
                     let total = rows.len();
                     let entries = rows
@@ -1664,79 +2084,215 @@ impl LibraryManager {
                     (total, entries)
                 })
             }
-        };
-
-        match result {
-            Ok((total, entries)) => {
-                let _ =
-                    self.bus_producer
-                        .send(Message::Library(LibraryMessage::LibraryPageResult {
-                            request_id,
-                            total,
-                            entries,
-                        }));
-            }
-            Err(error_text) => self.send_scan_failed(error_text),
         }
     }
 
-    fn resolve_selection_paths(
+    /// Resolves library selections to queueable tracks, in selection order without
+    /// duplicate paths.
+    fn resolve_selection_tracks(
         &self,
         selections: Vec<protocol::LibrarySelectionSpec>,
-    ) -> Result<Vec<PathBuf>, String> {
+    ) -> Result<Vec<protocol::RestoredTrack>, String> {
         let effective_tracks = self.effective_library_tracks()?;
-        let mut resolved_paths = Vec::new();
-        let mut seen_paths = HashSet::new();
-
+        let mut candidates = Vec::new();
         for selection in selections {
             match selection {
                 protocol::LibrarySelectionSpec::Track { path } => {
-                    let dedupe_key = path.to_string_lossy().to_string();
-                    if seen_paths.insert(dedupe_key) {
-                        resolved_paths.push(path);
-                    }
-                }
-                protocol::LibrarySelectionSpec::Artist { artist } => {
-                    for track in Self::tracks_for_artist_detail(&effective_tracks, &artist) {
-                        let dedupe_key = track.path.to_string_lossy().to_string();
-                        if seen_paths.insert(dedupe_key) {
-                            resolved_paths.push(track.path);
-                        }
-                    }
+                    let id = effective_tracks
+                        .iter()
+                        .find(|track| track.path == path)
+                        .map_or_else(
+                            || path.to_string_lossy().to_string(),
+                            |track| track.id.clone(),
+                        );
+                    candidates.push(protocol::RestoredTrack { id, path });
                 }
+                protocol::LibrarySelectionSpec::Artist { artist } => candidates.extend(
+                    Self::tracks_for_artist_detail(&effective_tracks, &artist)
+                        .into_iter()
+                        .map(Self::restored_track_from_library_track),
+                ),
                 protocol::LibrarySelectionSpec::Album {
                     album,
                     album_artist,
-                } => {
-                    for track in
-                        Self::tracks_for_album_detail(&effective_tracks, &album, &album_artist)
-                    {
-                        let dedupe_key = track.path.to_string_lossy().to_string();
-                        if seen_paths.insert(dedupe_key) {
-                            resolved_paths.push(track.path);
-                        }
-                    }
-                }
-                protocol::LibrarySelectionSpec::Genre { genre } => {
-                    for track in Self::tracks_for_genre_detail(&effective_tracks, &genre) {
-                        let dedupe_key = track.path.to_string_lossy().to_string();
-                        if seen_paths.insert(dedupe_key) {
-                            resolved_paths.push(track.path);
-                        }
-                    }
-                }
-                protocol::LibrarySelectionSpec::Decade { decade } => {
-                    for track in Self::tracks_for_decade_detail(&effective_tracks, &decade) {
-                        let dedupe_key = track.path.to_string_lossy().to_string();
-                        if seen_paths.insert(dedupe_key) {
-                            resolved_paths.push(track.path);
-                        }
-                    }
-                }
+                } => candidates.extend(
+                    Self::tracks_for_album_detail(&effective_tracks, &album, &album_artist)
+                        .into_iter()
+                        .map(Self::restored_track_from_library_track),
+                ),
+                protocol::LibrarySelectionSpec::Genre { genre } => candidates.extend(
+                    Self::tracks_for_genre_detail(&effective_tracks, &genre)
+                        .into_iter()
+                        .map(Self::restored_track_from_library_track),
+                ),
+                protocol::LibrarySelectionSpec::Decade { decade } => candidates.extend(
+                    Self::tracks_for_decade_detail(&effective_tracks, &decade)
+                        .into_iter()
+                        .map(Self::restored_track_from_library_track),
+                ),
             }
         }
 
-        Ok(resolved_paths)
+        let mut seen_paths = HashSet::new();
+        candidates.retain(|track| seen_paths.insert(track.path.to_string_lossy().to_string()));
+        Ok(candidates)
+    }
+
+    fn restored_track_from_library_track(track: protocol::LibraryTrack) -> protocol::RestoredTrack {
+        protocol::RestoredTrack {
+            id: track.id,
+            path: track.path,
+        }
+    }
+
+    fn resolve_selection_paths(
+        &self,
+        selections: Vec<protocol::LibrarySelectionSpec>,
+    ) -> Result<Vec<PathBuf>, String> {
+        Ok(self
+            .resolve_selection_tracks(selections)?
+            .into_iter()
+            .map(|track| track.path)
+            .collect())
+    }
+
+    /// Sends the tracks behind a library selection to the playback queue.
+    fn queue_selection(
+        &self,
+        selections: Vec<protocol::LibrarySelectionSpec>,
+        action: protocol::LibraryQueueAction,
+    ) {
+        let tracks = match self.resolve_selection_tracks(selections) {
+            Ok(tracks) if tracks.is_empty() => {
+                let _ =
+                    self.bus_producer
+                        .send(Message::Library(LibraryMessage::QueueSelectionFailed(
+                            "No tracks matched the selected library items".to_string(),
+                        )));
+                return;
+            }
+            Ok(tracks) => tracks,
+            Err(err) => {
+                let _ = self
+                    .bus_producer
+                    .send(Message::Library(LibraryMessage::QueueSelectionFailed(err)));
+                return;
+            }
+        };
+
+        let track_count = tracks.len();
+        let playback_message = match action {
+            protocol::LibraryQueueAction::PlayNow => {
+                protocol::PlaybackMessage::StartQueue(protocol::PlaybackQueueRequest {
+                    source: protocol::PlaybackQueueSource::Library,
+                    tracks,
+                    start_index: 0,
+                })
+            }
+            protocol::LibraryQueueAction::PlayNext => protocol::PlaybackMessage::EnqueueTracks {
+                tracks,
+                play_next: true,
+            },
+            protocol::LibraryQueueAction::AddToQueue => protocol::PlaybackMessage::EnqueueTracks {
+                tracks,
+                play_next: false,
+            },
+        };
+        let _ = self.bus_producer.send(Message::Playback(playback_message));
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::SelectionQueued {
+                action,
+                track_count,
+            }));
+    }
+
+    /// Picks tracks of a library selection adding up to about `target_minutes`, then
+    /// plays them or saves them as a new playlist.
+    ///
+    /// Durations are probed from file headers; tracks without one, such as remote
+    /// tracks, are left out. `flow` reorders the picked tracks by stored energy level.
+    fn generate_timed_mix(
+        &self,
+        selections: Vec<protocol::LibrarySelectionSpec>,
+        target_minutes: u32,
+        destination: protocol::TimedMixDestination,
+        flow: protocol::EnergyFlow,
+    ) {
+        let fail = |error_text: String| {
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::TimedMixFailed(error_text)));
+        };
+        let tracks = match self.resolve_selection_tracks(selections) {
+            Ok(tracks) => tracks,
+            Err(err) => return fail(err),
+        };
+        let paths: Vec<PathBuf> = tracks.iter().map(|track| track.path.clone()).collect();
+        let durations_ms: Vec<u64> = worker_pool::decode_pool().map(paths, |path| {
+            format_quality::probe_track_format(&path).map_or(0, |format| format.duration_ms)
+        });
+        let mut seed = [0u8; 32];
+        let _ = getrandom::fill(&mut seed);
+        let target_ms = u64::from(target_minutes) * 60_000;
+        let mix = timed_mix::plan_timed_mix(&durations_ms, target_ms, seed);
+        if mix.track_indices.is_empty() {
+            return fail(format!(
+                "No tracks with a known length fit into {} minute(s)",
+                target_minutes
+            ));
+        }
+        let track_count = mix.track_indices.len();
+        let mut mix_tracks: Vec<protocol::RestoredTrack> = mix
+            .track_indices
+            .iter()
+            .map(|&index| tracks[index].clone())
+            .collect();
+        if flow != protocol::EnergyFlow::AsPicked {
+            let energy_by_path: HashMap<PathBuf, protocol::TrackEnergy> =
+                match self.db_manager.get_track_energy() {
+                    Ok(settings) => settings
+                        .into_iter()
+                        .map(|(path, setting)| (path, setting.energy))
+                        .collect(),
+                    Err(err) => {
+                        warn!("Failed to load track energy for timed mix: {}", err);
+                        HashMap::new()
+                    }
+                };
+            let energies: Vec<Option<protocol::TrackEnergy>> = mix_tracks
+                .iter()
+                .map(|track| energy_by_path.get(&track.path).copied())
+                .collect();
+            let order = track_energy::order_by_energy_flow(&energies, flow);
+            mix_tracks = order
+                .into_iter()
+                .map(|index| mix_tracks[index].clone())
+                .collect();
+        }
+        let message = match destination {
+            protocol::TimedMixDestination::Queue => Message::Playback(
+                protocol::PlaybackMessage::StartQueue(protocol::PlaybackQueueRequest {
+                    source: protocol::PlaybackQueueSource::Library,
+                    tracks: mix_tracks,
+                    start_index: 0,
+                }),
+            ),
+            protocol::TimedMixDestination::Playlist => {
+                Message::Playlist(protocol::PlaylistMessage::CreatePlaylistWithTracks {
+                    name: format!("{} Minute Mix", target_minutes),
+                    paths: mix_tracks.into_iter().map(|track| track.path).collect(),
+                })
+            }
+        };
+        let _ = self.bus_producer.send(message);
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::TimedMixGenerated {
+                destination,
+                track_count,
+                total_ms: mix.total_ms,
+            }));
     }
 
     fn add_selection_to_playlists(
@@ -1931,6 +2487,18 @@ impl LibraryManager {
 
         match removal_result {
             Ok(removed_tracks) => {
+                activity_log::record(
+                    &self.bus_producer,
+                    protocol::ActivityCategory::Library,
+                    if remove_from_playlists {
+                        format!(
+                            "Removed {} track(s) from the library and playlists",
+                            removed_tracks
+                        )
+                    } else {
+                        format!("Removed {} track(s) from the library", removed_tracks)
+                    },
+                );
                 if remove_from_playlists {
                     let _ = self.bus_producer.send(Message::Playlist(
                         protocol::PlaylistMessage::PruneActivePlaylistPaths {
@@ -1953,8 +2521,212 @@ impl LibraryManager {
         }
     }
 
+    fn library_folder_paths(&self) -> Vec<PathBuf> {
+        self.library_folders.iter().map(PathBuf::from).collect()
+    }
+
+    fn plan_organize_files(
+        &self,
+        selections: Vec<protocol::LibrarySelectionSpec>,
+        template: String,
+    ) {
+        let paths = match self.resolve_selection_paths(selections) {
+            Ok(paths) => paths,
+            Err(err) => {
+                let _ = self
+                    .bus_producer
+                    .send(Message::Library(LibraryMessage::OrganizeFilesFailed(err)));
+                return;
+            }
+        };
+        let mut seen_paths = HashSet::new();
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| seen_paths.insert(path.clone()))
+            .collect();
+        match file_organizer::plan_moves(
+            &template,
+            &paths,
+            &self.library_folder_paths(),
+            metadata_tags::read_common_track_metadata,
+        ) {
+            Ok(plan) => {
+                let _ = self.bus_producer.send(Message::Library(
+                    LibraryMessage::OrganizeFilesPlanned {
+                        moves: plan.moves,
+                        unchanged: plan.unchanged,
+                        skipped: plan.skipped,
+                    },
+                ));
+            }
+            Err(err) => {
+                let _ = self
+                    .bus_producer
+                    .send(Message::Library(LibraryMessage::OrganizeFilesFailed(err)));
+            }
+        }
+    }
+
+    /// Suggests album artists for the albums touched by `selections`. Whole albums are
+    /// considered so a partial selection cannot hide tracks that already carry a tag.
+    fn plan_album_artist_fixes(&self, selections: Vec<protocol::LibrarySelectionSpec>) {
+        let planned = self.resolve_selection_paths(selections).and_then(|paths| {
+            let selected: HashSet<PathBuf> = paths.into_iter().collect();
+            let tracks = self.effective_library_tracks()?;
+            Ok(album_artist_inference::suggest_album_artists(&tracks)
+                .into_iter()
+                .filter(|suggestion| suggestion.paths.iter().any(|path| selected.contains(path)))
+                .collect())
+        });
+        let message = match planned {
+            Ok(suggestions) => LibraryMessage::AlbumArtistFixesPlanned(suggestions),
+            Err(err) => LibraryMessage::AlbumArtistFixesFailed(err),
+        };
+        let _ = self.bus_producer.send(Message::Library(message));
+    }
+
+    /// Moves files on disk, then rewrites their stored paths. A failed database update
+    /// moves the files back so disk and index never disagree.
+    fn apply_organize_moves(&mut self, moves: Vec<protocol::OrganizeFileMove>, undone: bool) {
+        if moves.is_empty() {
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::OrganizeFilesFailed(
+                    "No files to move".to_string(),
+                )));
+            return;
+        }
+        let library_folders = self.library_folder_paths();
+        if let Err(err) = file_organizer::apply_file_moves(&moves, &library_folders) {
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::OrganizeFilesFailed(err)));
+            return;
+        }
+        let rewrites: Vec<(PathBuf, PathBuf, String)> = moves
+            .iter()
+            .map(|file_move| {
+                (
+                    file_move.from.clone(),
+                    file_move.to.clone(),
+                    Self::stable_library_track_id(&file_move.to),
+                )
+            })
+            .collect();
+        if let Err(err) = self.db_manager.rewrite_track_paths(&rewrites) {
+            let reversed = file_organizer::reverse_moves(&moves);
+            if let Err(rollback_err) = file_organizer::apply_file_moves(&reversed, &library_folders)
+            {
+                warn!(
+                    "LibraryManager: failed to restore files after organize error: {}",
+                    rollback_err
+                );
+            }
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::OrganizeFilesFailed(
+                    format!("Failed to update library paths: {}", err),
+                )));
+            return;
+        }
+        info!(
+            "LibraryManager: organized {} file(s){}",
+            moves.len(),
+            if undone { " (undo)" } else { "" }
+        );
+        self.last_organize_moves = if undone { Vec::new() } else { moves.clone() };
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::OrganizeFilesApplied {
+                moves,
+                undone,
+            }));
+        self.publish_root_counts();
+        self.publish_user_tags_snapshot();
+        self.publish_track_notes_snapshot();
+    }
+
+    /// Rebinds files missing from their stored paths to copies found under `new_base`.
+    /// Only stored paths change; nothing is moved on disk.
+    fn relocate_track_paths(&mut self, missing: Vec<PathBuf>, new_base: PathBuf) {
+        let plan = track_relocation::plan_relocation(&missing, &new_base, Path::is_file);
+        if plan.moves.is_empty() {
+            let _ =
+                self.bus_producer
+                    .send(Message::Library(LibraryMessage::TrackRelocationFailed(
+                        format!(
+                            "None of the missing files were found under {}",
+                            new_base.display()
+                        ),
+                    )));
+            return;
+        }
+        let rewrites: Vec<(PathBuf, PathBuf, String)> = plan
+            .moves
+            .iter()
+            .map(|file_move| {
+                (
+                    file_move.from.clone(),
+                    file_move.to.clone(),
+                    Self::stable_library_track_id(&file_move.to),
+                )
+            })
+            .collect();
+        if let Err(err) = self.db_manager.rewrite_track_paths(&rewrites) {
+            let _ =
+                self.bus_producer
+                    .send(Message::Library(LibraryMessage::TrackRelocationFailed(
+                        format!("Failed to update track paths: {}", err),
+                    )));
+            return;
+        }
+        info!(
+            "LibraryManager: relocated {} missing file(s) under {}, {} unresolved",
+            plan.moves.len(),
+            new_base.display(),
+            plan.unresolved.len()
+        );
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::TrackPathsRelocated {
+                moves: plan.moves,
+                unresolved: plan.unresolved,
+            }));
+        self.publish_root_counts();
+        self.publish_user_tags_snapshot();
+        self.publish_track_notes_snapshot();
+    }
+
+    fn undo_organize_files(&mut self) {
+        if self.last_organize_moves.is_empty() {
+            let _ = self
+                .bus_producer
+                .send(Message::Library(LibraryMessage::OrganizeFilesFailed(
+                    "Nothing to undo".to_string(),
+                )));
+            return;
+        }
+        let reversed = file_organizer::reverse_moves(&self.last_organize_moves);
+        self.apply_organize_moves(reversed, true);
+    }
+
     /// Starts the blocking event loop for library scans and query requests.
+    /// Reports library folders whose saved portal grant was revoked since the last run.
+    fn report_revoked_portal_grants(&self) {
+        let access_warnings: Vec<String> = sandbox_access::revoked_portal_grants()
+            .iter()
+            .filter_map(|folder| sandbox_access::access_message(folder, FolderAccess::Denied))
+            .collect();
+        if !access_warnings.is_empty() {
+            self.push_scan_progress_update(
+                LibraryMessage::ScanFolderAccessWarnings(access_warnings),
+                false,
+            );
+        }
+    }
+
     pub fn run(&mut self) {
+        self.report_revoked_portal_grants();
         loop {
             match self.bus_consumer.blocking_recv() {
                 Ok(message) => match message {
@@ -1964,6 +2736,12 @@ impl LibraryManager {
                             if let protocol::ConfigDeltaEntry::Library(library) = change {
                                 if let Some(folders) = library.folders {
                                     self.library_folders = folders;
+                                    sandbox_access::save_portal_grants(
+                                        &self.library_folder_paths(),
+                                    );
+                                }
+                                if let Some(import_rules) = library.import_rules {
+                                    self.import_rules = import_rules;
                                 }
                                 if let Some(include_playlist_tracks_in_library) =
                                     library.include_playlist_tracks_in_library
@@ -2014,10 +2792,36 @@ impl LibraryManager {
                     }
                     Message::Library(LibraryMessage::RequestRootCounts) => {
                         self.publish_root_counts();
+                        self.publish_remote_copies();
+                    }
+                    Message::Library(LibraryMessage::RequestFacetCounts(selection)) => {
+                        self.publish_facet_counts(selection);
                     }
                     Message::Library(LibraryMessage::RequestFavoritesSnapshot) => {
                         self.publish_favorites_snapshot();
                     }
+                    Message::Library(LibraryMessage::RequestUserTagsSnapshot) => {
+                        self.publish_user_tags_snapshot();
+                    }
+                    Message::Library(LibraryMessage::RequestTrackNotesSnapshot) => {
+                        self.publish_track_notes_snapshot();
+                    }
+                    Message::Library(LibraryMessage::SetTrackNote { path, note }) => {
+                        self.set_track_note(&path, &note);
+                    }
+                    Message::Library(LibraryMessage::UpsertUserTag { name, color }) => {
+                        self.upsert_user_tag(&name, &color);
+                    }
+                    Message::Library(LibraryMessage::DeleteUserTag { name }) => {
+                        self.delete_user_tag(&name);
+                    }
+                    Message::Library(LibraryMessage::SetTrackUserTag {
+                        paths,
+                        tag,
+                        assigned,
+                    }) => {
+                        self.set_track_user_tag(&paths, &tag, assigned);
+                    }
                     Message::Library(LibraryMessage::RequestTracks) => {
                         self.publish_tracks();
                     }
@@ -2060,6 +2864,7 @@ impl LibraryManager {
                             self.remote_tracks_by_profile.insert(profile_id, tracks);
                         }
                         self.publish_root_counts();
+                        self.publish_remote_copies();
                         self.publish_tracks();
                         self.publish_global_search_data();
                     }
@@ -2133,6 +2938,17 @@ impl LibraryManager {
                     }) => {
                         self.paste_selection_to_active_playlist(selections);
                     }
+                    Message::Library(LibraryMessage::QueueSelection { selections, action }) => {
+                        self.queue_selection(selections, action);
+                    }
+                    Message::Library(LibraryMessage::GenerateTimedMix {
+                        selections,
+                        target_minutes,
+                        destination,
+                        flow,
+                    }) => {
+                        self.generate_timed_mix(selections, target_minutes, destination, flow);
+                    }
                     Message::Library(LibraryMessage::EvaluateRemoveSelection {
                         request_id,
                         selections,
@@ -2145,11 +2961,42 @@ impl LibraryManager {
                     }) => {
                         self.remove_selection_from_library(selections, remove_from_playlists);
                     }
+                    Message::Library(LibraryMessage::PlanOrganizeFiles {
+                        selections,
+                        template,
+                    }) => {
+                        self.plan_organize_files(selections, template);
+                    }
+                    Message::Library(LibraryMessage::ApplyOrganizeFiles { moves }) => {
+                        self.apply_organize_moves(moves, false);
+                    }
+                    Message::Library(LibraryMessage::UndoOrganizeFiles) => {
+                        self.undo_organize_files();
+                    }
+                    Message::Library(LibraryMessage::RelocateTrackPaths { missing, new_base }) => {
+                        self.relocate_track_paths(missing, new_base);
+                    }
+                    Message::Library(LibraryMessage::PlanAlbumArtistFixes { selections }) => {
+                        self.plan_album_artist_fixes(selections);
+                    }
                     Message::Library(LibraryMessage::ToggleFavorite { entity, desired }) => {
                         if let Err(error) = self.apply_toggle_favorite(entity, desired) {
                             warn!("Failed to apply favorite toggle: {}", error);
                         }
                     }
+                    Message::Metadata(MetadataMessage::ChangedTagsRefreshed(refreshed)) => {
+                        // Playlist-only tracks are re-read on the next query.
+                        if let Ok(mut cache) = self.playlist_track_metadata_cache.lock() {
+                            for (path, _) in &refreshed {
+                                cache.remove(path);
+                            }
+                        }
+                        if let Ok(mut cache) = self.local_duration_cache.lock() {
+                            for (path, _) in &refreshed {
+                                cache.remove(path);
+                            }
+                        }
+                    }
                     _ => {}
                 },
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
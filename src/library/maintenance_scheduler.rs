@@ -0,0 +1,634 @@
+//! Scheduled background library maintenance.
+//!
+//! `MaintenanceScheduler` runs on its own worker thread and triggers periodic tasks at
+//! the intervals configured in `[library]`: rescanning library folders, pruning per-track
+//! rows for files that left the library, re-syncing connected remote profiles, expiring
+//! caches, and verifying file integrity. Rescans and syncs are delegated to their owning
+//! managers over the bus; the other tasks run here, with integrity checks fanned out over
+//! the shared decode pool. Every run is recorded in the database and published as
+//! `LibraryMessage::MaintenanceHistorySnapshot`. The scheduler also persists activity
+//! log entries and serves `LibraryMessage::ActivityLogSnapshot`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use tokio::sync::broadcast::error::TryRecvError;
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::activity_log;
+use crate::cache_manager;
+use crate::config::LibraryConfig;
+use crate::db_manager::DbManager;
+use crate::image_pipeline::{self, ManagedImageKind};
+use crate::integrity_check;
+use crate::protocol::{
+    ActivityLogEntry, BackendConnectionState, CacheKind, ConfigDeltaEntry, ConfigMessage,
+    IntegrationMessage, LibraryConfigDelta, LibraryMessage, MaintenanceRunRecord, MaintenanceTask,
+    Message,
+};
+use crate::worker_pool;
+
+/// Upper bound for configured task intervals (one year).
+pub const MAX_INTERVAL_HOURS: u32 = 24 * 365;
+/// Interval choices offered in settings, in hours; `0` disables a task.
+pub const INTERVAL_CHOICES_HOURS: [u32; 5] = [0, 6, 24, 168, 720];
+/// Every task in settings display order.
+pub const ALL_TASKS: [MaintenanceTask; 5] = [
+    MaintenanceTask::RescanLibrary,
+    MaintenanceTask::PruneOrphanedRows,
+    MaintenanceTask::RefreshRemoteSync,
+    MaintenanceTask::ExpireCaches,
+    MaintenanceTask::VerifyIntegrity,
+];
+/// Number of runs included in history snapshots.
+const HISTORY_SNAPSHOT_LIMIT: usize = 30;
+/// Delay after startup before tasks that never ran become due.
+const FIRST_RUN_DELAY_MS: i64 = 10 * 60 * 1000;
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const IDLE_LOOP_SLEEP: Duration = Duration::from_millis(500);
+const MS_PER_HOUR: i64 = 60 * 60 * 1000;
+
+/// Short display name for one task.
+pub fn task_label(task: MaintenanceTask) -> &'static str {
+    match task {
+        MaintenanceTask::RescanLibrary => "Rescan library folders",
+        MaintenanceTask::PruneOrphanedRows => "Prune orphaned track data",
+        MaintenanceTask::RefreshRemoteSync => "Refresh remote syncs",
+        MaintenanceTask::ExpireCaches => "Expire caches",
+        MaintenanceTask::VerifyIntegrity => "Verify file integrity",
+    }
+}
+
+/// Configured interval for one task, in hours.
+pub fn interval_hours(config: &LibraryConfig, task: MaintenanceTask) -> u32 {
+    match task {
+        MaintenanceTask::RescanLibrary => config.maintenance_rescan_interval_hours,
+        MaintenanceTask::PruneOrphanedRows => config.maintenance_prune_interval_hours,
+        MaintenanceTask::RefreshRemoteSync => config.maintenance_remote_sync_interval_hours,
+        MaintenanceTask::ExpireCaches => config.maintenance_cache_expiry_interval_hours,
+        MaintenanceTask::VerifyIntegrity => config.maintenance_integrity_interval_hours,
+    }
+}
+
+/// Stores `hours` as the interval for one task.
+pub fn set_interval_hours(config: &mut LibraryConfig, task: MaintenanceTask, hours: u32) {
+    let slot = match task {
+        MaintenanceTask::RescanLibrary => &mut config.maintenance_rescan_interval_hours,
+        MaintenanceTask::PruneOrphanedRows => &mut config.maintenance_prune_interval_hours,
+        MaintenanceTask::RefreshRemoteSync => &mut config.maintenance_remote_sync_interval_hours,
+        MaintenanceTask::ExpireCaches => &mut config.maintenance_cache_expiry_interval_hours,
+        MaintenanceTask::VerifyIntegrity => &mut config.maintenance_integrity_interval_hours,
+    };
+    *slot = hours;
+}
+
+/// Whether a task reads the library files themselves rather than only the database.
+fn reads_library_files(task: MaintenanceTask) -> bool {
+    matches!(
+        task,
+        MaintenanceTask::RescanLibrary | MaintenanceTask::VerifyIntegrity
+    )
+}
+
+/// Index into [`INTERVAL_CHOICES_HOURS`] shown for a configured interval; custom values
+/// map to the next longer choice.
+pub fn interval_choice_index(hours: u32) -> usize {
+    INTERVAL_CHOICES_HOURS
+        .iter()
+        .position(|choice| *choice >= hours)
+        .unwrap_or(INTERVAL_CHOICES_HOURS.len() - 1)
+}
+
+/// Formats the time since `then_unix_ms` as a short relative label.
+pub fn format_elapsed(now_unix_ms: i64, then_unix_ms: i64) -> String {
+    let elapsed_minutes = now_unix_ms.saturating_sub(then_unix_ms).max(0) / 60_000;
+    if elapsed_minutes < 1 {
+        "just now".to_string()
+    } else if elapsed_minutes < 60 {
+        format!("{elapsed_minutes} min ago")
+    } else if elapsed_minutes < 48 * 60 {
+        format!("{} h ago", elapsed_minutes / 60)
+    } else {
+        format!("{} d ago", elapsed_minutes / (24 * 60))
+    }
+}
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Runs periodic maintenance tasks and records their history.
+pub struct MaintenanceScheduler {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    db_manager: DbManager,
+    config: LibraryConfig,
+    started_unix_ms: i64,
+    last_run_unix_ms: HashMap<MaintenanceTask, i64>,
+    connected_profile_ids: Vec<String>,
+    battery_saver_active: bool,
+}
+
+impl MaintenanceScheduler {
+    /// Creates a scheduler seeded with startup library config and stored run history.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        db_manager: DbManager,
+        initial_config: LibraryConfig,
+    ) -> Self {
+        let mut last_run_unix_ms = HashMap::new();
+        match db_manager.get_maintenance_runs(usize::MAX) {
+            Ok(runs) => {
+                for run in runs {
+                    last_run_unix_ms
+                        .entry(run.task)
+                        .or_insert(run.started_unix_ms);
+                }
+            }
+            Err(err) => warn!("MaintenanceScheduler: failed to load run history: {}", err),
+        }
+        Self {
+            bus_consumer,
+            bus_producer,
+            db_manager,
+            config: initial_config,
+            started_unix_ms: now_unix_ms(),
+            last_run_unix_ms,
+            connected_profile_ids: Vec::new(),
+            battery_saver_active: false,
+        }
+    }
+
+    fn apply_config_delta(&mut self, delta: LibraryConfigDelta) {
+        if let Some(folders) = delta.folders {
+            self.config.folders = folders;
+        }
+        if let Some(max_size_mb) = delta.cover_art_cache_max_size_mb {
+            self.config.cover_art_cache_max_size_mb = max_size_mb;
+        }
+        if let Some(max_size_mb) = delta.artist_image_cache_max_size_mb {
+            self.config.artist_image_cache_max_size_mb = max_size_mb;
+        }
+        if let Some(max_size_mb) = delta.decoded_audio_cache_max_size_mb {
+            self.config.decoded_audio_cache_max_size_mb = max_size_mb;
+        }
+        if let Some(max_size_mb) = delta.offline_cache_max_size_mb {
+            self.config.offline_cache_max_size_mb = max_size_mb;
+        }
+        if let Some(hours) = delta.maintenance_rescan_interval_hours {
+            self.config.maintenance_rescan_interval_hours = hours;
+        }
+        if let Some(hours) = delta.maintenance_prune_interval_hours {
+            self.config.maintenance_prune_interval_hours = hours;
+        }
+        if let Some(hours) = delta.maintenance_remote_sync_interval_hours {
+            self.config.maintenance_remote_sync_interval_hours = hours;
+        }
+        if let Some(hours) = delta.maintenance_cache_expiry_interval_hours {
+            self.config.maintenance_cache_expiry_interval_hours = hours;
+        }
+        if let Some(hours) = delta.maintenance_integrity_interval_hours {
+            self.config.maintenance_integrity_interval_hours = hours;
+        }
+        if let Some(slow_storage_mode) = delta.slow_storage_mode {
+            self.config.slow_storage_mode = slow_storage_mode;
+        }
+    }
+
+    /// Tasks whose interval elapsed since their last run. Tasks that never ran become due
+    /// [`FIRST_RUN_DELAY_MS`] after startup. Nothing is due while battery saver is active,
+    /// and in slow storage mode tasks that read library files only run on request.
+    fn due_tasks(&self, now_unix_ms: i64) -> Vec<MaintenanceTask> {
+        if self.battery_saver_active {
+            return Vec::new();
+        }
+        ALL_TASKS
+            .into_iter()
+            .filter(|task| !(self.config.slow_storage_mode && reads_library_files(*task)))
+            .filter(|task| {
+                let hours = interval_hours(&self.config, *task);
+                if hours == 0 {
+                    return false;
+                }
+                match self.last_run_unix_ms.get(task) {
+                    Some(last_run) => now_unix_ms - last_run >= i64::from(hours) * MS_PER_HOUR,
+                    None => now_unix_ms - self.started_unix_ms >= FIRST_RUN_DELAY_MS,
+                }
+            })
+            .collect()
+    }
+
+    fn rescan_library(&self) -> Result<String, String> {
+        if self.config.folders.is_empty() {
+            return Ok("No library folders configured".to_string());
+        }
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::RequestScan));
+        Ok(format!(
+            "Requested rescan of {} folder(s)",
+            self.config.folders.len()
+        ))
+    }
+
+    fn refresh_remote_sync(&self) -> Result<String, String> {
+        if self.connected_profile_ids.is_empty() {
+            return Ok("No connected remote profiles".to_string());
+        }
+        for profile_id in &self.connected_profile_ids {
+            let _ = self.bus_producer.send(Message::Integration(
+                IntegrationMessage::SyncBackendProfile {
+                    profile_id: profile_id.clone(),
+                },
+            ));
+        }
+        Ok(format!(
+            "Requested sync of {} profile(s)",
+            self.connected_profile_ids.len()
+        ))
+    }
+
+    fn expire_caches(&self) -> Result<String, String> {
+        let expired_rows = self
+            .db_manager
+            .prune_expired_library_enrichment_cache(now_unix_ms())
+            .map_err(|err| err.to_string())?;
+        let removed_covers = image_pipeline::prune_kind_disk_cache(
+            ManagedImageKind::CoverArt,
+            image_pipeline::mb_to_bytes(self.config.cover_art_cache_max_size_mb),
+        );
+        let removed_artist_images = image_pipeline::prune_kind_disk_cache(
+            ManagedImageKind::ArtistImage,
+            image_pipeline::mb_to_bytes(self.config.artist_image_cache_max_size_mb),
+        );
+        let mut removed_files = 0usize;
+        for kind in [CacheKind::DecodedAudio, CacheKind::OfflineDownloads] {
+            let max_size_mb = cache_manager::max_size_mb(&self.config, kind);
+            if max_size_mb > 0 {
+                removed_files += image_pipeline::prune_dir_to_budget(
+                    &cache_manager::cache_dir(kind),
+                    image_pipeline::mb_to_bytes(max_size_mb),
+                )
+                .len();
+            }
+        }
+        for path in &removed_artist_images {
+            if let Err(err) = self
+                .db_manager
+                .clear_library_enrichment_image_path(path.to_string_lossy().as_ref())
+            {
+                warn!(
+                    "MaintenanceScheduler: failed to clear image reference {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+        Ok(format!(
+            "Removed {} expired metadata row(s), {} cached image(s), and {} cached file(s)",
+            expired_rows,
+            removed_covers.len() + removed_artist_images.len(),
+            removed_files
+        ))
+    }
+
+    /// Hashes every local library file (verifying FLAC stream checksums and checking
+    /// lossless files for a lossy source) on the decode pool, then stores the results and
+    /// publishes the integrity report.
+    fn verify_integrity(&self) -> Result<String, String> {
+        let library_paths = self
+            .db_manager
+            .get_library_scan_states_by_path()
+            .map_err(|err| err.to_string())?;
+        let mut previous_records = self
+            .db_manager
+            .get_file_integrity_records()
+            .map_err(|err| err.to_string())?;
+        let checks: Vec<_> = library_paths
+            .into_keys()
+            .filter(|path| !path.contains("://"))
+            .map(|path| {
+                let previous = previous_records.remove(&path);
+                (PathBuf::from(path), previous)
+            })
+            .collect();
+        let checked = checks.len();
+        let now = now_unix_ms();
+        let records = worker_pool::decode_pool().map(checks, move |(path, previous)| {
+            integrity_check::check_file(&path, previous.as_ref(), now)
+        });
+        self.db_manager
+            .upsert_file_integrity_records(&records)
+            .map_err(|err| err.to_string())?;
+        let mut issues = 0usize;
+        for record in records.iter().filter(|record| record.issue_kind.is_some()) {
+            issues += 1;
+            warn!(
+                "MaintenanceScheduler: integrity check failed for {}: {}",
+                record.path, record.issue_detail
+            );
+        }
+        self.publish_integrity_report();
+        Ok(format!(
+            "Verified {checked} file(s); {issues} with integrity issues"
+        ))
+    }
+
+    fn run_task(&mut self, task: MaintenanceTask) {
+        let started_unix_ms = now_unix_ms();
+        let started_at = Instant::now();
+        let result = match task {
+            MaintenanceTask::RescanLibrary => self.rescan_library(),
+            MaintenanceTask::PruneOrphanedRows => self
+                .db_manager
+                .prune_orphaned_track_rows()
+                .map(|deleted| format!("Removed {deleted} orphaned row(s)"))
+                .map_err(|err| err.to_string()),
+            MaintenanceTask::RefreshRemoteSync => self.refresh_remote_sync(),
+            MaintenanceTask::ExpireCaches => self.expire_caches(),
+            MaintenanceTask::VerifyIntegrity => self.verify_integrity(),
+        };
+        let record = MaintenanceRunRecord {
+            task,
+            started_unix_ms,
+            duration_ms: started_at.elapsed().as_millis() as u64,
+            success: result.is_ok(),
+            summary: result.unwrap_or_else(|err| err),
+        };
+        info!(
+            "MaintenanceScheduler: {} finished in {} ms (success={}): {}",
+            task_label(task),
+            record.duration_ms,
+            record.success,
+            record.summary
+        );
+        if let Err(err) = self.db_manager.record_maintenance_run(&record) {
+            warn!("MaintenanceScheduler: failed to record run: {}", err);
+        }
+        self.last_run_unix_ms.insert(task, started_unix_ms);
+        self.publish_history();
+    }
+
+    fn publish_history(&self) {
+        let runs = match self.db_manager.get_maintenance_runs(HISTORY_SNAPSHOT_LIMIT) {
+            Ok(runs) => runs,
+            Err(err) => {
+                warn!("MaintenanceScheduler: failed to load run history: {}", err);
+                return;
+            }
+        };
+        let _ = self.bus_producer.send(Message::Library(
+            LibraryMessage::MaintenanceHistorySnapshot(runs),
+        ));
+    }
+
+    fn record_activity(&self, entry: ActivityLogEntry) {
+        let cutoff = activity_log::retention_cutoff_unix_ms(now_unix_ms());
+        if let Err(err) = self.db_manager.record_activity(&entry, cutoff) {
+            warn!("MaintenanceScheduler: failed to record activity: {}", err);
+        }
+    }
+
+    fn publish_activity_log(&self) {
+        let entries = match self
+            .db_manager
+            .get_activity_log(activity_log::SNAPSHOT_LIMIT)
+        {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("MaintenanceScheduler: failed to load activity log: {}", err);
+                return;
+            }
+        };
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::ActivityLogSnapshot(
+                entries,
+            )));
+    }
+
+    fn publish_integrity_report(&self) {
+        let issues = match self.db_manager.get_integrity_issues() {
+            Ok(issues) => issues,
+            Err(err) => {
+                warn!(
+                    "MaintenanceScheduler: failed to load integrity issues: {}",
+                    err
+                );
+                return;
+            }
+        };
+        let _ = self
+            .bus_producer
+            .send(Message::Library(LibraryMessage::IntegrityReportSnapshot(
+                issues,
+            )));
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Library(LibraryMessage::RunMaintenanceTask(task)) => self.run_task(task),
+            Message::Library(LibraryMessage::RequestMaintenanceHistory) => self.publish_history(),
+            Message::Library(LibraryMessage::RequestIntegrityReport) => {
+                self.publish_integrity_report()
+            }
+            Message::Library(LibraryMessage::RecordActivity(entry)) => self.record_activity(entry),
+            Message::Library(LibraryMessage::RequestActivityLog) => self.publish_activity_log(),
+            Message::Config(ConfigMessage::ConfigChanged(changes)) => {
+                for change in changes {
+                    if let ConfigDeltaEntry::Library(delta) = change {
+                        self.apply_config_delta(delta);
+                    }
+                }
+            }
+            Message::Config(ConfigMessage::BatterySaverChanged { active }) => {
+                self.battery_saver_active = active;
+            }
+            Message::Integration(IntegrationMessage::BackendSnapshotUpdated(snapshot)) => {
+                self.connected_profile_ids = snapshot
+                    .profiles
+                    .into_iter()
+                    .filter(|profile| profile.connection_state == BackendConnectionState::Connected)
+                    .map(|profile| profile.profile_id)
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    fn process_pending_bus_messages(&mut self) -> bool {
+        loop {
+            match self.bus_consumer.try_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!("MaintenanceScheduler: bus lagged by {} messages", skipped);
+                }
+                Err(TryRecvError::Closed) => return true,
+            }
+        }
+    }
+
+    /// Starts the blocking scheduler loop.
+    pub fn run(&mut self) {
+        info!("MaintenanceScheduler: started");
+        self.publish_history();
+        let mut last_schedule_check: Option<Instant> = None;
+        loop {
+            if self.process_pending_bus_messages() {
+                break;
+            }
+            if last_schedule_check
+                .is_none_or(|checked| checked.elapsed() >= SCHEDULE_CHECK_INTERVAL)
+            {
+                last_schedule_check = Some(Instant::now());
+                for task in self.due_tasks(now_unix_ms()) {
+                    self.run_task(task);
+                }
+            }
+            thread::sleep(IDLE_LOOP_SLEEP);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast;
+
+    fn test_scheduler(config: LibraryConfig) -> (MaintenanceScheduler, Receiver<Message>) {
+        let (bus_sender, _) = broadcast::channel::<Message>(32);
+        let receiver = bus_sender.subscribe();
+        let scheduler = MaintenanceScheduler::new(
+            bus_sender.subscribe(),
+            bus_sender,
+            DbManager::new_in_memory().expect("in-memory db should initialize"),
+            config,
+        );
+        (scheduler, receiver)
+    }
+
+    #[test]
+    fn test_due_tasks_follow_intervals_first_run_delay_and_battery_saver() {
+        let (mut scheduler, _receiver) = test_scheduler(LibraryConfig {
+            maintenance_rescan_interval_hours: 0,
+            maintenance_prune_interval_hours: 24,
+            maintenance_remote_sync_interval_hours: 6,
+            maintenance_cache_expiry_interval_hours: 24,
+            ..LibraryConfig::default()
+        });
+        let start = scheduler.started_unix_ms;
+        scheduler
+            .last_run_unix_ms
+            .insert(MaintenanceTask::PruneOrphanedRows, start);
+
+        assert!(scheduler.due_tasks(start).is_empty());
+        assert_eq!(
+            scheduler.due_tasks(start + FIRST_RUN_DELAY_MS),
+            vec![
+                MaintenanceTask::RefreshRemoteSync,
+                MaintenanceTask::ExpireCaches
+            ]
+        );
+        assert!(scheduler
+            .due_tasks(start + 24 * MS_PER_HOUR)
+            .contains(&MaintenanceTask::PruneOrphanedRows));
+
+        scheduler.handle_message(Message::Config(ConfigMessage::BatterySaverChanged {
+            active: true,
+        }));
+        assert!(scheduler.due_tasks(start + 24 * MS_PER_HOUR).is_empty());
+    }
+
+    #[test]
+    fn test_slow_storage_mode_leaves_file_reading_tasks_to_run_now() {
+        let (mut scheduler, _receiver) = test_scheduler(LibraryConfig {
+            maintenance_rescan_interval_hours: 6,
+            maintenance_prune_interval_hours: 6,
+            maintenance_integrity_interval_hours: 6,
+            ..LibraryConfig::default()
+        });
+        let due_at = scheduler.started_unix_ms + FIRST_RUN_DELAY_MS;
+        assert!(scheduler
+            .due_tasks(due_at)
+            .contains(&MaintenanceTask::RescanLibrary));
+
+        scheduler.handle_message(Message::Config(ConfigMessage::ConfigChanged(vec![
+            ConfigDeltaEntry::Library(LibraryConfigDelta {
+                slow_storage_mode: Some(true),
+                ..LibraryConfigDelta::default()
+            }),
+        ])));
+        assert_eq!(
+            scheduler.due_tasks(due_at),
+            vec![
+                MaintenanceTask::PruneOrphanedRows,
+                MaintenanceTask::ExpireCaches
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_now_records_history_and_publishes_snapshot() {
+        let (mut scheduler, mut receiver) = test_scheduler(LibraryConfig::default());
+        scheduler.handle_message(Message::Library(LibraryMessage::RunMaintenanceTask(
+            MaintenanceTask::RescanLibrary,
+        )));
+
+        let Ok(Message::Library(LibraryMessage::MaintenanceHistorySnapshot(runs))) =
+            receiver.try_recv()
+        else {
+            panic!("expected a maintenance history snapshot");
+        };
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].task, MaintenanceTask::RescanLibrary);
+        assert!(runs[0].success);
+        assert_eq!(runs[0].summary, "No library folders configured");
+        assert!(scheduler
+            .last_run_unix_ms
+            .contains_key(&MaintenanceTask::RescanLibrary));
+    }
+
+    #[test]
+    fn test_verify_integrity_publishes_report_before_history() {
+        let (mut scheduler, mut receiver) = test_scheduler(LibraryConfig::default());
+        scheduler.handle_message(Message::Library(LibraryMessage::RunMaintenanceTask(
+            MaintenanceTask::VerifyIntegrity,
+        )));
+
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::Library(LibraryMessage::IntegrityReportSnapshot(issues)))
+                if issues.is_empty()
+        ));
+        let Ok(Message::Library(LibraryMessage::MaintenanceHistorySnapshot(runs))) =
+            receiver.try_recv()
+        else {
+            panic!("expected a maintenance history snapshot");
+        };
+        assert_eq!(runs[0].task, MaintenanceTask::VerifyIntegrity);
+        assert_eq!(
+            runs[0].summary,
+            "Verified 0 file(s); 0 with integrity issues"
+        );
+    }
+
+    #[test]
+    fn test_interval_choice_index_and_elapsed_labels() {
+        assert_eq!(interval_choice_index(0), 0);
+        assert_eq!(interval_choice_index(24), 2);
+        assert_eq!(interval_choice_index(30), 3);
+        assert_eq!(interval_choice_index(MAX_INTERVAL_HOURS), 4);
+        assert_eq!(format_elapsed(120_000, 100_000), "just now");
+        assert_eq!(format_elapsed(5 * 60_000, 0), "5 min ago");
+        assert_eq!(format_elapsed(3 * MS_PER_HOUR, 0), "3 h ago");
+        assert_eq!(format_elapsed(72 * MS_PER_HOUR, 0), "3 d ago");
+    }
+}
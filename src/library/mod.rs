@@ -1,4 +1,20 @@
-//! Library subsystem modules (scanner/index and metadata enrichment).
+//! Library subsystem modules (scanner/index, import rules, metadata enrichment, file organization,
+//! album-artist inference, scheduled maintenance, integrity verification, the activity log, user
+//! tags, time-boxed mixes, track energy levels, play statistics, matching of remote copies to
+//! local files, album/EP/single grouping on artist pages, and genre/decade/format facets).
 
+pub(crate) mod activity_log;
+pub(crate) mod album_artist_inference;
+pub(crate) mod file_organizer;
+pub(crate) mod import_rules;
+pub(crate) mod integrity_check;
 pub(crate) mod library_enrichment_manager;
+pub(crate) mod library_facets;
 pub(crate) mod library_manager;
+pub(crate) mod maintenance_scheduler;
+pub(crate) mod play_stats;
+pub(crate) mod release_kind;
+pub(crate) mod source_matching;
+pub(crate) mod timed_mix;
+pub(crate) mod track_energy;
+pub(crate) mod user_tags;
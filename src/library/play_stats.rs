@@ -0,0 +1,271 @@
+//! Per-track play statistics: play and skip counts, last-played labels, and search
+//! filters over them.
+//!
+//! A track counts as played when it ends on its own or is left after its early-skip
+//! window; leaving it sooner counts as a skip. Filters such as `plays:>10` or
+//! `played:>30d` narrow playlist and library searches to tracks with matching stats.
+
+use crate::{maintenance_scheduler, protocol::TrackPlayStats};
+
+/// Built-in playlist column formats showing play statistics.
+pub const PLAY_COUNT_COLUMN_FORMAT: &str = "{play_count}";
+pub const SKIP_COUNT_COLUMN_FORMAT: &str = "{skip_count}";
+pub const LAST_PLAYED_COLUMN_FORMAT: &str = "{last_played}";
+
+const PLAYS_FILTER_PREFIX: &str = "plays:";
+const SKIPS_FILTER_PREFIX: &str = "skips:";
+const PLAYED_FILTER_PREFIX: &str = "played:";
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Comparison of a count filter such as `plays:>=3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountComparison {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl CountComparison {
+    fn matches(self, value: u32, bound: u32) -> bool {
+        match self {
+            CountComparison::Equal => value == bound,
+            CountComparison::Less => value < bound,
+            CountComparison::LessOrEqual => value <= bound,
+            CountComparison::Greater => value > bound,
+            CountComparison::GreaterOrEqual => value >= bound,
+        }
+    }
+}
+
+/// One play statistics condition from a search query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayStatsCondition {
+    Plays(CountComparison, u32),
+    Skips(CountComparison, u32),
+    /// `played:never`.
+    NeverPlayed,
+    /// `played:<Nd`: played within the last N days.
+    PlayedWithinDays(u32),
+    /// `played:>Nd`: not played in the last N days, including never.
+    NotPlayedWithinDays(u32),
+}
+
+impl PlayStatsCondition {
+    /// Whether a track with `stats` (or none recorded) passes this condition at `now_unix_ms`.
+    pub fn matches(&self, stats: Option<&TrackPlayStats>, now_unix_ms: i64) -> bool {
+        let stats = stats.copied().unwrap_or_default();
+        let played_within = |days: u32| {
+            stats.last_played_unix_ms.is_some_and(|last_played| {
+                now_unix_ms.saturating_sub(last_played) < i64::from(days) * MS_PER_DAY
+            })
+        };
+        match *self {
+            PlayStatsCondition::Plays(comparison, bound) => {
+                comparison.matches(stats.play_count, bound)
+            }
+            PlayStatsCondition::Skips(comparison, bound) => {
+                comparison.matches(stats.skip_count, bound)
+            }
+            PlayStatsCondition::NeverPlayed => stats.last_played_unix_ms.is_none(),
+            PlayStatsCondition::PlayedWithinDays(days) => played_within(days),
+            PlayStatsCondition::NotPlayedWithinDays(days) => !played_within(days),
+        }
+    }
+}
+
+/// Play statistics conditions requested by a search query, plus the rest of the query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayStatsFilterQuery {
+    /// Conditions a track must all pass. Empty means no filter.
+    pub conditions: Vec<PlayStatsCondition>,
+    pub text: String,
+}
+
+impl PlayStatsFilterQuery {
+    pub fn matches(&self, stats: Option<&TrackPlayStats>, now_unix_ms: i64) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(stats, now_unix_ms))
+    }
+}
+
+fn parse_count_condition(value: &str) -> Option<(CountComparison, u32)> {
+    let (comparison, number) = [
+        (">=", CountComparison::GreaterOrEqual),
+        ("<=", CountComparison::LessOrEqual),
+        (">", CountComparison::Greater),
+        ("<", CountComparison::Less),
+        ("=", CountComparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(operator, comparison)| {
+        value
+            .strip_prefix(operator)
+            .map(|number| (comparison, number))
+    })
+    .unwrap_or((CountComparison::Equal, value));
+    number.parse().ok().map(|number| (comparison, number))
+}
+
+fn parse_played_condition(value: &str) -> Option<PlayStatsCondition> {
+    if value.eq_ignore_ascii_case("never") {
+        return Some(PlayStatsCondition::NeverPlayed);
+    }
+    let days = |number: &str| {
+        number
+            .strip_suffix(['d', 'D'])
+            .unwrap_or(number)
+            .parse::<u32>()
+            .ok()
+    };
+    if let Some(number) = value.strip_prefix('<') {
+        return days(number).map(PlayStatsCondition::PlayedWithinDays);
+    }
+    value
+        .strip_prefix('>')
+        .and_then(days)
+        .map(PlayStatsCondition::NotPlayedWithinDays)
+}
+
+fn strip_prefix_ignore_case<'a>(token: &'a str, prefix: &str) -> Option<&'a str> {
+    token
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &token[prefix.len()..])
+}
+
+/// Extracts `plays:`, `skips:`, and `played:` tokens from a search query.
+///
+/// Tokens that do not parse are dropped rather than matched as text.
+pub fn parse_play_stats_filter_query(query: &str) -> PlayStatsFilterQuery {
+    let mut conditions = Vec::new();
+    let mut text_parts = Vec::new();
+    for token in query.split_whitespace() {
+        let condition = if let Some(value) = strip_prefix_ignore_case(token, PLAYS_FILTER_PREFIX) {
+            Some(
+                parse_count_condition(value)
+                    .map(|(comparison, bound)| PlayStatsCondition::Plays(comparison, bound)),
+            )
+        } else if let Some(value) = strip_prefix_ignore_case(token, SKIPS_FILTER_PREFIX) {
+            Some(
+                parse_count_condition(value)
+                    .map(|(comparison, bound)| PlayStatsCondition::Skips(comparison, bound)),
+            )
+        } else {
+            strip_prefix_ignore_case(token, PLAYED_FILTER_PREFIX).map(parse_played_condition)
+        };
+        match condition {
+            Some(Some(condition)) => {
+                if !conditions.contains(&condition) {
+                    conditions.push(condition);
+                }
+            }
+            Some(None) => {}
+            None => text_parts.push(token),
+        }
+    }
+    PlayStatsFilterQuery {
+        conditions,
+        text: text_parts.join(" "),
+    }
+}
+
+/// Label of the "Last Played" column; empty for tracks never played.
+fn last_played_label(stats: Option<&TrackPlayStats>, now_unix_ms: i64) -> String {
+    stats
+        .and_then(|stats| stats.last_played_unix_ms)
+        .map(|last_played| maintenance_scheduler::format_elapsed(now_unix_ms, last_played))
+        .unwrap_or_default()
+}
+
+/// Cell text of the play statistics column with built-in `format`.
+pub fn column_text(format: &str, stats: Option<&TrackPlayStats>, now_unix_ms: i64) -> String {
+    match format {
+        PLAY_COUNT_COLUMN_FORMAT => stats.map_or(0, |stats| stats.play_count).to_string(),
+        SKIP_COUNT_COLUMN_FORMAT => stats.map_or(0, |stats| stats.skip_count).to_string(),
+        _ => last_played_label(stats, now_unix_ms),
+    }
+}
+
+/// Value the play statistics column with built-in `format` sorts by; tracks never
+/// played sort first.
+pub fn sort_value(format: &str, stats: Option<&TrackPlayStats>) -> u64 {
+    let stats = stats.copied().unwrap_or_default();
+    match format {
+        PLAY_COUNT_COLUMN_FORMAT => u64::from(stats.play_count),
+        SKIP_COUNT_COLUMN_FORMAT => u64::from(stats.skip_count),
+        _ => stats
+            .last_played_unix_ms
+            .map_or(0, |last_played| last_played.max(0) as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(play_count: u32, skip_count: u32, last_played_unix_ms: Option<i64>) -> TrackPlayStats {
+        TrackPlayStats {
+            play_count,
+            skip_count,
+            last_played_unix_ms,
+        }
+    }
+
+    #[test]
+    fn test_parse_play_stats_filter_query_extracts_conditions() {
+        let query = parse_play_stats_filter_query("live PLAYS:>=3 skips:0 played:>30d played:bad");
+        assert_eq!(query.text, "live");
+        assert_eq!(
+            query.conditions,
+            vec![
+                PlayStatsCondition::Plays(CountComparison::GreaterOrEqual, 3),
+                PlayStatsCondition::Skips(CountComparison::Equal, 0),
+                PlayStatsCondition::NotPlayedWithinDays(30),
+            ]
+        );
+        assert_eq!(
+            parse_play_stats_filter_query("played:never played:<7").conditions,
+            vec![
+                PlayStatsCondition::NeverPlayed,
+                PlayStatsCondition::PlayedWithinDays(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_play_stats_conditions_treat_missing_stats_as_never_played() {
+        let now = 100 * MS_PER_DAY;
+        let recent = stats(4, 1, Some(now - 2 * MS_PER_DAY));
+        let stale = stats(1, 3, Some(now - 40 * MS_PER_DAY));
+        let stale_filter = parse_play_stats_filter_query("played:>30d");
+        assert!(!stale_filter.matches(Some(&recent), now));
+        assert!(stale_filter.matches(Some(&stale), now));
+        assert!(stale_filter.matches(None, now));
+
+        let favorites = parse_play_stats_filter_query("plays:>3 skips:<2");
+        assert!(favorites.matches(Some(&recent), now));
+        assert!(!favorites.matches(Some(&stale), now));
+        assert!(!favorites.matches(None, now));
+        assert!(parse_play_stats_filter_query("played:never").matches(None, now));
+    }
+
+    #[test]
+    fn test_column_text_and_sort_value_cover_unplayed_tracks() {
+        let played = stats(2, 1, Some(0));
+        assert_eq!(column_text(PLAY_COUNT_COLUMN_FORMAT, None, 0), "0");
+        assert_eq!(column_text(SKIP_COUNT_COLUMN_FORMAT, Some(&played), 0), "1");
+        assert_eq!(column_text(LAST_PLAYED_COLUMN_FORMAT, None, 0), "");
+        assert_eq!(
+            column_text(LAST_PLAYED_COLUMN_FORMAT, Some(&played), 3 * MS_PER_DAY),
+            "3 d ago"
+        );
+        assert_eq!(sort_value(PLAY_COUNT_COLUMN_FORMAT, Some(&played)), 2);
+        assert!(
+            sort_value(LAST_PLAYED_COLUMN_FORMAT, None)
+                < sort_value(LAST_PLAYED_COLUMN_FORMAT, Some(&stats(1, 0, Some(5))))
+        );
+    }
+}
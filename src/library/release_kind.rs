@@ -0,0 +1,112 @@
+//! Grouping an artist's releases into albums, EPs, and singles.
+//!
+//! Library tags rarely carry a release type, so the kind is read from the naming
+//! conventions stores use (`Title - Single`, `Title (EP)`, `Title [EP]`) and otherwise
+//! guessed from the number of tracks: up to three is a single, up to six an EP. The count
+//! is the number of tracks in the library, so an album the user only owns a few songs of
+//! can be listed as a single.
+
+use crate::protocol::LibraryAlbum;
+
+/// Most tracks a release without a naming hint can have and still count as a single.
+const SINGLE_MAX_TRACKS: u32 = 3;
+/// Most tracks a release without a naming hint can have and still count as an EP.
+const EP_MAX_TRACKS: u32 = 6;
+
+/// Kind of release an album row is listed under on an artist's page, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReleaseKind {
+    Album,
+    Ep,
+    Single,
+}
+
+impl ReleaseKind {
+    /// Section title shown above the first release of this kind.
+    pub fn section_title(self) -> &'static str {
+        match self {
+            Self::Album => "Albums",
+            Self::Ep => "EPs",
+            Self::Single => "Singles",
+        }
+    }
+}
+
+/// Release kind named by a trailing ` - Single`, `(EP)`, `[Single]`, ... in `title`.
+fn kind_from_title(title: &str) -> Option<ReleaseKind> {
+    let lower = title.trim().to_ascii_lowercase();
+    let suffix = lower
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('(').map(|(_, marker)| marker))
+        .or_else(|| {
+            lower
+                .strip_suffix(']')
+                .and_then(|rest| rest.rsplit_once('[').map(|(_, marker)| marker))
+        })
+        .or_else(|| lower.rsplit_once(" - ").map(|(_, marker)| marker))?;
+    match suffix.trim() {
+        "single" => Some(ReleaseKind::Single),
+        "ep" => Some(ReleaseKind::Ep),
+        _ => None,
+    }
+}
+
+/// Release kind of `album`, from its title when it names one, else its track count.
+pub fn release_kind(album: &LibraryAlbum) -> ReleaseKind {
+    if let Some(kind) = kind_from_title(&album.album) {
+        return kind;
+    }
+    match album.track_count {
+        0 => ReleaseKind::Album,
+        count if count <= SINGLE_MAX_TRACKS => ReleaseKind::Single,
+        count if count <= EP_MAX_TRACKS => ReleaseKind::Ep,
+        _ => ReleaseKind::Album,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(title: &str, track_count: u32) -> LibraryAlbum {
+        LibraryAlbum {
+            album: title.to_string(),
+            album_artist: "Artist".to_string(),
+            track_count,
+            representative_track_path: None,
+            year: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_release_kind_prefers_title_markers() {
+        assert_eq!(
+            release_kind(&album("Summer - Single", 5)),
+            ReleaseKind::Single
+        );
+        assert_eq!(
+            release_kind(&album("Night Drive (EP)", 12)),
+            ReleaseKind::Ep
+        );
+        assert_eq!(
+            release_kind(&album("Remixes [single]", 9)),
+            ReleaseKind::Single
+        );
+        assert_eq!(
+            release_kind(&album("Live - Deluxe", 2)),
+            ReleaseKind::Single
+        );
+        assert_eq!(
+            release_kind(&album("Step (Remastered)", 10)),
+            ReleaseKind::Album
+        );
+    }
+
+    #[test]
+    fn test_release_kind_falls_back_to_track_count() {
+        assert_eq!(release_kind(&album("One", 1)), ReleaseKind::Single);
+        assert_eq!(release_kind(&album("Four", 4)), ReleaseKind::Ep);
+        assert_eq!(release_kind(&album("Seven", 7)), ReleaseKind::Album);
+        assert!(ReleaseKind::Album < ReleaseKind::Ep && ReleaseKind::Ep < ReleaseKind::Single);
+    }
+}
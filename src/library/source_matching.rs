@@ -0,0 +1,230 @@
+//! Matches remote backend copies of songs that also exist as local files.
+//!
+//! Copies match on artist and title, plus album when both sides name one. Case,
+//! punctuation, and a leading "The" are ignored, so "The Beatles - Let It Be!" matches
+//! "beatles - let it be". Tracks missing either tag never match, and both lengths must
+//! be known and agree within [`DURATION_TOLERANCE_MS`], so two untagged files that
+//! happen to share a file name stay apart.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{integration_uri::is_remote_track_path, protocol::LibraryTrack};
+
+/// Largest length difference between two copies of the same song.
+pub const DURATION_TOLERANCE_MS: u64 = 3_000;
+
+/// What the scanner stores for a missing artist or title tag.
+const PLACEHOLDER_COMPONENTS: [&str; 2] = ["unknownartist", "unknowntitle"];
+
+/// Lowercases `value` and keeps only letters and digits, dropping a leading "the".
+pub fn normalize_match_component(value: &str) -> String {
+    let words: Vec<String> = value
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|ch| ch.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    let skip = usize::from(words.len() > 1 && words[0] == "the");
+    words[skip..].concat()
+}
+
+fn artist_title_key(track: &LibraryTrack) -> Option<(String, String)> {
+    let known = |value: &str| {
+        let normalized = normalize_match_component(value);
+        (!normalized.is_empty() && !PLACEHOLDER_COMPONENTS.contains(&normalized.as_str()))
+            .then_some(normalized)
+    };
+    Some((known(&track.artist)?, known(&track.title)?))
+}
+
+/// Maps each local file to the remote copies of the same song, in `remote` order.
+///
+/// Remote lengths come from the rows themselves; `local_duration_ms` supplies the local
+/// ones and is only asked about files whose tags already match. When several local
+/// files match a remote track, the first one in path order gets it.
+pub fn match_remote_copies(
+    local: &[LibraryTrack],
+    remote: &[LibraryTrack],
+    local_duration_ms: impl Fn(&Path) -> Option<u64>,
+) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut local_by_key: HashMap<(String, String), Vec<(&PathBuf, String)>> = HashMap::new();
+    for track in local
+        .iter()
+        .filter(|track| !is_remote_track_path(&track.path))
+    {
+        if let Some(key) = artist_title_key(track) {
+            local_by_key
+                .entry(key)
+                .or_default()
+                .push((&track.path, normalize_match_component(&track.album)));
+        }
+    }
+    for candidates in local_by_key.values_mut() {
+        candidates.sort_by(|left, right| left.0.cmp(right.0));
+    }
+
+    let mut local_durations: HashMap<&PathBuf, Option<u64>> = HashMap::new();
+    let mut copies: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for track in remote {
+        let Some(remote_duration) = track.duration_ms else {
+            continue;
+        };
+        let Some(candidates) = artist_title_key(track).and_then(|key| local_by_key.get(&key))
+        else {
+            continue;
+        };
+        let album = normalize_match_component(&track.album);
+        let matched = candidates.iter().find(|(local_path, local_album)| {
+            let albums_agree = album.is_empty() || local_album.is_empty() || *local_album == album;
+            albums_agree
+                && local_durations
+                    .entry(*local_path)
+                    .or_insert_with(|| local_duration_ms(local_path))
+                    .is_some_and(|local_duration| {
+                        local_duration.abs_diff(remote_duration) <= DURATION_TOLERANCE_MS
+                    })
+        });
+        if let Some((local_path, _)) = matched {
+            copies
+                .entry((*local_path).clone())
+                .or_default()
+                .push(track.path.clone());
+        }
+    }
+    copies
+}
+
+/// Picks the file to play for a library track.
+///
+/// The local file wins whenever it exists, unless the user chose the remote source for
+/// it in `prefer_remote`; then, or when the file is gone, its first remote copy plays.
+pub fn preferred_playback_path(
+    path: &Path,
+    copies: &HashMap<PathBuf, Vec<PathBuf>>,
+    prefer_remote: &HashSet<PathBuf>,
+    local_exists: impl Fn(&Path) -> bool,
+) -> PathBuf {
+    match copies.get(path).and_then(|copies| copies.first()) {
+        Some(remote) if prefer_remote.contains(path) || !local_exists(path) => remote.clone(),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, artist: &str, title: &str, album: &str) -> LibraryTrack {
+        LibraryTrack {
+            id: path.to_string(),
+            path: PathBuf::from(path),
+            title: title.to_string(),
+            artist: artist.to_string(),
+            album: album.to_string(),
+            album_artist: artist.to_string(),
+            genre: String::new(),
+            year: String::new(),
+            track_number: String::new(),
+            duration_ms: Some(200_000),
+        }
+    }
+
+    #[test]
+    fn test_normalize_match_component_ignores_case_punctuation_and_leading_the() {
+        assert_eq!(normalize_match_component("The Beatles"), "beatles");
+        assert_eq!(normalize_match_component("Let It Be!"), "letitbe");
+        assert_eq!(normalize_match_component("The The"), "the");
+        assert_eq!(normalize_match_component("  "), "");
+    }
+
+    #[test]
+    fn test_match_remote_copies_requires_compatible_albums() {
+        let local = vec![
+            track("/music/b.flac", "The Beatles", "Let It Be", "Let It Be"),
+            track("/music/a.flac", "Beatles", "Let it be!", ""),
+            track("/music/c.flac", "Nina Simone", "Sinnerman", "Pastel Blues"),
+        ];
+        let remote = vec![
+            track("rtq://a/1", "beatles", "let it be", "Let It Be"),
+            track("rtq://a/2", "Nina Simone", "Sinnerman", "Live"),
+            track("rtq://a/3", "Nina Simone", "Sinnerman", ""),
+            track("rtq://a/4", "Nina Simone", "", ""),
+        ];
+        let copies = match_remote_copies(&local, &remote, |_| Some(200_000));
+        assert_eq!(copies.len(), 2);
+        assert_eq!(
+            copies[&PathBuf::from("/music/a.flac")],
+            vec![PathBuf::from("rtq://a/1")]
+        );
+        assert_eq!(
+            copies[&PathBuf::from("/music/c.flac")],
+            vec![PathBuf::from("rtq://a/3")]
+        );
+    }
+
+    #[test]
+    fn test_match_remote_copies_skips_untagged_tracks_sharing_a_title() {
+        let local = vec![
+            track(
+                "/music/intro.flac",
+                "Unknown Artist",
+                "Intro",
+                "Unknown Album",
+            ),
+            track("/music/outro.flac", "", "Outro", ""),
+        ];
+        let remote = vec![
+            track("rtq://a/1", "Unknown Artist", "Intro", "Unknown Album"),
+            track("rtq://a/2", "", "Outro", ""),
+        ];
+        assert!(match_remote_copies(&local, &remote, |_| Some(200_000)).is_empty());
+    }
+
+    #[test]
+    fn test_match_remote_copies_requires_lengths_within_tolerance() {
+        let local = vec![
+            track("/music/live.flac", "Nina Simone", "Sinnerman", ""),
+            track("/music/studio.flac", "Nina Simone", "Sinnerman", ""),
+        ];
+        let mut remote = vec![track("rtq://a/1", "Nina Simone", "Sinnerman", "")];
+        let lengths = |path: &Path| match path.to_str() {
+            Some("/music/live.flac") => Some(620_000),
+            Some("/music/studio.flac") => Some(201_500),
+            _ => None,
+        };
+        assert_eq!(
+            match_remote_copies(&local, &remote, lengths)[&PathBuf::from("/music/studio.flac")],
+            vec![PathBuf::from("rtq://a/1")]
+        );
+        assert!(match_remote_copies(&local, &remote, |_| None).is_empty());
+        remote[0].duration_ms = None;
+        assert!(match_remote_copies(&local, &remote, lengths).is_empty());
+    }
+
+    #[test]
+    fn test_preferred_playback_path_prefers_existing_local_files() {
+        let local = PathBuf::from("/music/a.flac");
+        let copies = HashMap::from([(local.clone(), vec![PathBuf::from("rtq://a/1")])]);
+        let mut prefer_remote = HashSet::new();
+        let pick = |prefer_remote: &HashSet<PathBuf>, exists: bool| {
+            preferred_playback_path(&local, &copies, prefer_remote, |_| exists)
+        };
+        assert_eq!(pick(&prefer_remote, true), local);
+        assert_eq!(pick(&prefer_remote, false), PathBuf::from("rtq://a/1"));
+        prefer_remote.insert(local.clone());
+        assert_eq!(pick(&prefer_remote, true), PathBuf::from("rtq://a/1"));
+        assert_eq!(
+            preferred_playback_path(Path::new("/music/b.flac"), &copies, &prefer_remote, |_| {
+                false
+            }),
+            PathBuf::from("/music/b.flac")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Track selection for time-boxed mixes ("make me 45 minutes of this").
+//!
+//! Candidates are shuffled so repeated requests give different mixes, then a subset-sum
+//! search over whole seconds picks the combination whose total lands closest to the
+//! target. Mixes may run slightly long, because one extra song usually fits a commute or
+//! workout better than stopping minutes early.
+
+use rand::{rngs::StdRng, RngExt, SeedableRng};
+
+/// Longest a mix may run past its target.
+pub const OVERSHOOT_ALLOWANCE_MS: u64 = 90_000;
+/// Candidates considered after shuffling, bounding the search cost on huge selections.
+const MAX_CANDIDATES: usize = 400;
+
+/// Tracks chosen for one mix.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimedMix {
+    /// Positions in the candidate list, in play order.
+    pub track_indices: Vec<usize>,
+    pub total_ms: u64,
+}
+
+fn whole_seconds(duration_ms: u64) -> usize {
+    (duration_ms.saturating_add(500) / 1_000).max(1) as usize
+}
+
+/// Picks tracks from `durations_ms` whose total is as close to `target_ms` as possible.
+///
+/// Tracks with an unknown (zero) duration are skipped. Ties between a short and a long
+/// total go to the short one.
+pub fn plan_timed_mix(durations_ms: &[u64], target_ms: u64, seed: [u8; 32]) -> TimedMix {
+    let limit_ms = target_ms.saturating_add(OVERSHOOT_ALLOWANCE_MS);
+    let mut candidates: Vec<usize> = (0..durations_ms.len())
+        .filter(|&index| durations_ms[index] > 0 && durations_ms[index] <= limit_ms)
+        .collect();
+    if target_ms == 0 || candidates.is_empty() {
+        return TimedMix::default();
+    }
+    let mut rng = StdRng::from_seed(seed);
+    for i in (1..candidates.len()).rev() {
+        let j = rng.random_range(0..=i);
+        candidates.swap(i, j);
+    }
+    candidates.truncate(MAX_CANDIDATES);
+
+    let target_seconds = whole_seconds(target_ms);
+    let limit_seconds = whole_seconds(limit_ms);
+    // `reached_by[s]` is the first candidate completing a total of `s` seconds; walking
+    // back through it always lands on totals reached by earlier candidates only.
+    let mut reached_by: Vec<Option<usize>> = vec![None; limit_seconds + 1];
+    let mut reached = vec![false; limit_seconds + 1];
+    reached[0] = true;
+    for (position, &index) in candidates.iter().enumerate() {
+        let seconds = whole_seconds(durations_ms[index]);
+        for total in (seconds..=limit_seconds).rev() {
+            if !reached[total] && reached[total - seconds] {
+                reached[total] = true;
+                reached_by[total] = Some(position);
+            }
+        }
+    }
+
+    let Some(best_total) = (1..=limit_seconds)
+        .filter(|&total| reached[total])
+        .min_by_key(|&total| (total.abs_diff(target_seconds), total))
+    else {
+        return TimedMix::default();
+    };
+    let mut positions = Vec::new();
+    let mut total = best_total;
+    while let Some(position) = reached_by[total] {
+        positions.push(position);
+        total -= whole_seconds(durations_ms[candidates[position]]);
+    }
+    positions.sort_unstable();
+    let track_indices: Vec<usize> = positions
+        .into_iter()
+        .map(|position| candidates[position])
+        .collect();
+    let total_ms = track_indices.iter().map(|&index| durations_ms[index]).sum();
+    TimedMix {
+        track_indices,
+        total_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_timed_mix_hits_reachable_target_exactly() {
+        let durations = [180_000, 240_000, 0, 200_000, 1_000_000, 300_000];
+        for seed in 0..8u8 {
+            let mix = plan_timed_mix(&durations, 620_000, [seed; 32]);
+            let mut chosen = mix.track_indices.clone();
+            chosen.sort_unstable();
+            assert_eq!(chosen, vec![0, 1, 3]);
+            assert_eq!(mix.total_ms, 620_000);
+        }
+    }
+
+    #[test]
+    fn test_plan_timed_mix_allows_small_overshoot_only() {
+        let mix = plan_timed_mix(&[180_000], 170_000, [1; 32]);
+        assert_eq!(mix.track_indices, vec![0]);
+
+        assert_eq!(
+            plan_timed_mix(&[400_000], 170_000, [1; 32]),
+            TimedMix::default()
+        );
+        assert_eq!(plan_timed_mix(&[], 170_000, [1; 32]), TimedMix::default());
+
+        let mix = plan_timed_mix(&[60_000, 60_000, 60_000], 600_000, [2; 32]);
+        assert_eq!(mix.track_indices.len(), 3);
+        assert_eq!(mix.total_ms, 180_000);
+    }
+}
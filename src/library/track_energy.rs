@@ -0,0 +1,196 @@
+//! Mood/energy levels: suggestions from BPM and loudness, search filtering, and
+//! session ordering.
+//!
+//! Suggestions are a rough guess from two signals most libraries already have: the BPM
+//! tag and the measured integrated loudness. Tempo weighs more than loudness, because
+//! modern masters of calm songs are often just as loud as dance tracks.
+
+use crate::protocol::{EnergyFlow, TrackEnergy, TrackLoudness};
+
+/// Built-in playlist column format showing a track's energy level.
+pub const ENERGY_COLUMN_FORMAT: &str = "{energy}";
+/// Search token prefix keeping only tracks with a given energy level.
+const ENERGY_FILTER_PREFIX: &str = "energy:";
+/// Tempo range mapped onto the energy scale; slower or faster tags are clamped.
+const CALM_BPM: f32 = 60.0;
+const INTENSE_BPM: f32 = 180.0;
+/// Integrated loudness range mapped onto the energy scale.
+const CALM_LUFS: f32 = -20.0;
+const INTENSE_LUFS: f32 = -6.0;
+const BPM_WEIGHT: f32 = 0.6;
+
+/// Parses an energy name (`calm`, `upbeat`, ...) or a level from 1 to 5.
+pub fn parse_energy(value: &str) -> Option<TrackEnergy> {
+    let value = value.trim();
+    if let Ok(level) = value.parse::<u8>() {
+        return TrackEnergy::from_level(level);
+    }
+    TrackEnergy::ALL
+        .into_iter()
+        .find(|energy| energy.label().eq_ignore_ascii_case(value))
+}
+
+fn normalized(value: f32, low: f32, high: f32) -> f32 {
+    ((value - low) / (high - low)).clamp(0.0, 1.0)
+}
+
+/// Suggests an energy level from a BPM tag and/or a loudness measurement.
+///
+/// Returns `None` when neither signal is available.
+pub fn suggest_energy(bpm: Option<f32>, loudness: Option<&TrackLoudness>) -> Option<TrackEnergy> {
+    let bpm_score = bpm
+        .filter(|bpm| bpm.is_finite() && *bpm > 0.0)
+        .map(|bpm| normalized(bpm, CALM_BPM, INTENSE_BPM));
+    let loudness_score = loudness
+        .map(|loudness| loudness.integrated_lufs)
+        .filter(|lufs| lufs.is_finite())
+        .map(|lufs| normalized(lufs, CALM_LUFS, INTENSE_LUFS));
+    let score = match (bpm_score, loudness_score) {
+        (Some(bpm), Some(loudness)) => bpm * BPM_WEIGHT + loudness * (1.0 - BPM_WEIGHT),
+        (Some(score), None) | (None, Some(score)) => score,
+        (None, None) => return None,
+    };
+    TrackEnergy::from_level(1 + (score * 4.0).round() as u8)
+}
+
+/// Energy levels requested by `energy:<level>` tokens, plus the rest of the query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnergyFilterQuery {
+    /// Accepted levels; several tokens match any of them. Empty means no filter.
+    pub energies: Vec<TrackEnergy>,
+    pub text: String,
+}
+
+/// Extracts `energy:<name|1-5>` tokens from a search query.
+///
+/// Tokens naming an unknown level are dropped rather than matched as text.
+pub fn parse_energy_filter_query(query: &str) -> EnergyFilterQuery {
+    let mut energies = Vec::new();
+    let mut text_parts = Vec::new();
+    for token in query.split_whitespace() {
+        let value = token
+            .get(..ENERGY_FILTER_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(ENERGY_FILTER_PREFIX))
+            .map(|_| &token[ENERGY_FILTER_PREFIX.len()..]);
+        match value {
+            Some(value) => {
+                if let Some(energy) = parse_energy(value) {
+                    if !energies.contains(&energy) {
+                        energies.push(energy);
+                    }
+                }
+            }
+            None => text_parts.push(token),
+        }
+    }
+    EnergyFilterQuery {
+        energies,
+        text: text_parts.join(" "),
+    }
+}
+
+/// Returns the play order for tracks with these energy levels.
+///
+/// Tracks without a level count as [`TrackEnergy::Steady`]. The sort is stable, so
+/// tracks of the same level keep their picked order. A peak deals the ascending order
+/// alternately to the rising and the falling half, so both slopes stay gradual.
+pub fn order_by_energy_flow(energies: &[Option<TrackEnergy>], flow: EnergyFlow) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..energies.len()).collect();
+    let level = |index: usize| energies[index].unwrap_or(TrackEnergy::Steady);
+    match flow {
+        EnergyFlow::AsPicked => {}
+        EnergyFlow::BuildUp => order.sort_by_key(|&index| level(index)),
+        EnergyFlow::WindDown => order.sort_by_key(|&index| std::cmp::Reverse(level(index))),
+        EnergyFlow::Peak => {
+            order.sort_by_key(|&index| level(index));
+            let (rising, falling): (Vec<_>, Vec<_>) = order
+                .into_iter()
+                .enumerate()
+                .partition(|(position, _)| position % 2 == 0);
+            order = rising
+                .into_iter()
+                .chain(falling.into_iter().rev())
+                .map(|(_, index)| index)
+                .collect();
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loudness(integrated_lufs: f32) -> TrackLoudness {
+        TrackLoudness {
+            integrated_lufs,
+            true_peak_dbtp: -1.0,
+        }
+    }
+
+    #[test]
+    fn test_suggest_energy_maps_tempo_and_loudness_onto_levels() {
+        assert_eq!(suggest_energy(None, None), None);
+        assert_eq!(suggest_energy(Some(62.0), None), Some(TrackEnergy::Calm));
+        assert_eq!(suggest_energy(Some(120.0), None), Some(TrackEnergy::Steady));
+        assert_eq!(
+            suggest_energy(Some(200.0), None),
+            Some(TrackEnergy::Intense)
+        );
+        assert_eq!(
+            suggest_energy(None, Some(&loudness(-7.0))),
+            Some(TrackEnergy::Intense)
+        );
+        assert_eq!(
+            suggest_energy(Some(70.0), Some(&loudness(-8.0))),
+            Some(TrackEnergy::Steady)
+        );
+        assert_eq!(
+            suggest_energy(Some(0.0), Some(&loudness(-19.0))),
+            Some(TrackEnergy::Calm)
+        );
+    }
+
+    #[test]
+    fn test_parse_energy_filter_query_accepts_names_and_levels() {
+        let query = parse_energy_filter_query("energy:Calm  daft energy:5 energy:calm energy:loud");
+        assert_eq!(
+            query.energies,
+            vec![TrackEnergy::Calm, TrackEnergy::Intense]
+        );
+        assert_eq!(query.text, "daft");
+        assert_eq!(
+            parse_energy_filter_query("punk"),
+            EnergyFilterQuery {
+                energies: Vec::new(),
+                text: "punk".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_order_by_energy_flow_is_stable_and_treats_unknown_as_steady() {
+        let energies = [
+            Some(TrackEnergy::Intense),
+            None,
+            Some(TrackEnergy::Calm),
+            Some(TrackEnergy::Steady),
+        ];
+        assert_eq!(
+            order_by_energy_flow(&energies, EnergyFlow::AsPicked),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            order_by_energy_flow(&energies, EnergyFlow::BuildUp),
+            vec![2, 1, 3, 0]
+        );
+        assert_eq!(
+            order_by_energy_flow(&energies, EnergyFlow::WindDown),
+            vec![0, 1, 3, 2]
+        );
+        assert_eq!(
+            order_by_energy_flow(&energies, EnergyFlow::Peak),
+            vec![2, 3, 0, 1]
+        );
+    }
+}
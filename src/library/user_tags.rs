@@ -0,0 +1,166 @@
+//! User-defined track tags with color labels.
+//!
+//! Tags are free-form labels such as `workout` or `chill` stored per track path in
+//! `DbManager`. Names compare case-insensitively; the stored spelling of the first
+//! definition is kept for display. Search queries can require tags with `tag:<name>`
+//! tokens (quote names that contain spaces: `tag:"late night"`).
+
+use crate::protocol::UserTag;
+
+/// Built-in column format that renders a track's tags as colored chips.
+pub const TAGS_COLUMN_FORMAT: &str = "{tags}";
+/// Glyph drawn in the tag color in front of each tag name.
+pub const TAG_CHIP_SYMBOL: &str = "\u{25CF}";
+/// Color palette offered when creating a tag, in picker order.
+pub const TAG_COLOR_PALETTE: [&str; 8] = [
+    "#E5484D", "#F76B15", "#FFC53D", "#46A758", "#12A594", "#0090FF", "#8E4EC6", "#8B8D98",
+];
+const MAX_TAG_NAME_CHARS: usize = 48;
+const TAG_FILTER_PREFIX: &str = "tag:";
+
+/// Trims and collapses whitespace in a tag name; returns `None` for empty names.
+pub fn normalize_tag_name(raw: &str) -> Option<String> {
+    let collapsed = raw.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    Some(collapsed.chars().take(MAX_TAG_NAME_CHARS).collect())
+}
+
+/// Case-insensitive identity used to compare tag names.
+pub fn tag_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Returns `#RRGGBB` for a valid hex color, falling back to the last palette entry.
+pub fn normalize_tag_color(raw: &str) -> String {
+    let trimmed = raw.trim();
+    let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    if hex.len() == 6 && hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        format!("#{}", hex.to_ascii_uppercase())
+    } else {
+        TAG_COLOR_PALETTE[TAG_COLOR_PALETTE.len() - 1].to_string()
+    }
+}
+
+/// Parses a normalized `#RRGGBB` color into its components.
+pub fn tag_color_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// Picks the palette color for a new tag so consecutive tags get distinct colors.
+pub fn next_palette_color(existing: &[UserTag]) -> String {
+    TAG_COLOR_PALETTE[existing.len() % TAG_COLOR_PALETTE.len()].to_string()
+}
+
+/// Search query split into required tag keys and the remaining free text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilterQuery {
+    pub required_tag_keys: Vec<String>,
+    pub text: String,
+}
+
+/// Extracts `tag:<name>` tokens from a search query.
+pub fn parse_tag_filter_query(query: &str) -> TagFilterQuery {
+    let mut required_tag_keys = Vec::new();
+    let mut text_parts = Vec::new();
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let is_tag_token = rest
+            .get(..TAG_FILTER_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(TAG_FILTER_PREFIX));
+        if is_tag_token {
+            let after_prefix = &rest[TAG_FILTER_PREFIX.len()..];
+            let (name, remainder) = if let Some(quoted) = after_prefix.strip_prefix('"') {
+                match quoted.find('"') {
+                    Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                    None => (quoted, ""),
+                }
+            } else {
+                let end = after_prefix
+                    .find(char::is_whitespace)
+                    .unwrap_or(after_prefix.len());
+                (&after_prefix[..end], &after_prefix[end..])
+            };
+            if let Some(name) = normalize_tag_name(name) {
+                required_tag_keys.push(tag_key(&name));
+            }
+            rest = remainder.trim_start();
+            continue;
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        text_parts.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    TagFilterQuery {
+        required_tag_keys,
+        text: text_parts.join(" "),
+    }
+}
+
+/// Returns `true` when `track_tags` contains every required tag key.
+pub fn track_has_required_tags(track_tags: &[String], required_tag_keys: &[String]) -> bool {
+    required_tag_keys.iter().all(|required| {
+        track_tags
+            .iter()
+            .any(|track_tag| tag_key(track_tag) == *required)
+    })
+}
+
+/// Returns `true` when any tag name contains the lowercase free-text query.
+pub fn track_tags_match_text(track_tags: &[String], normalized_query: &str) -> bool {
+    !normalized_query.is_empty()
+        && track_tags
+            .iter()
+            .any(|track_tag| track_tag.to_lowercase().contains(normalized_query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag_name_collapses_whitespace_and_rejects_empty() {
+        assert_eq!(
+            normalize_tag_name("  late   night "),
+            Some("late night".to_string())
+        );
+        assert_eq!(normalize_tag_name("   "), None);
+    }
+
+    #[test]
+    fn test_normalize_tag_color_accepts_hex_and_falls_back() {
+        assert_eq!(normalize_tag_color("#46a758"), "#46A758");
+        assert_eq!(normalize_tag_color("46A758"), "#46A758");
+        assert_eq!(normalize_tag_color("green"), "#8B8D98");
+        assert_eq!(tag_color_rgb("#46A758"), Some((0x46, 0xA7, 0x58)));
+    }
+
+    #[test]
+    fn test_parse_tag_filter_query_extracts_plain_and_quoted_tags() {
+        let parsed = parse_tag_filter_query("tag:Workout daft TAG:\"late night\" punk");
+        assert_eq!(
+            parsed.required_tag_keys,
+            vec!["workout".to_string(), "late night".to_string()]
+        );
+        assert_eq!(parsed.text, "daft punk");
+    }
+
+    #[test]
+    fn test_track_has_required_tags_is_case_insensitive_and_requires_all() {
+        let tags = vec!["Workout".to_string(), "Chill".to_string()];
+        assert!(track_has_required_tags(&tags, &["workout".to_string()]));
+        assert!(!track_has_required_tags(
+            &tags,
+            &["workout".to_string(), "party".to_string()]
+        ));
+        assert!(track_has_required_tags(&tags, &[]));
+        assert!(track_tags_match_text(&tags, "chi"));
+        assert!(!track_tags_match_text(&tags, ""));
+    }
+}
@@ -5,6 +5,7 @@
 
 //! roqtune binary entrypoint and top-level orchestration glue.
 
+mod announcement_manager;
 mod app_bootstrap;
 mod app_callbacks;
 mod app_config_coordinator;
@@ -12,10 +13,15 @@ mod app_context;
 mod app_runtime;
 mod audio;
 mod backends;
+mod base64_encoding;
+mod cache_manager;
 mod cast;
 mod config;
 mod config_persistence;
 mod db_manager;
+mod db_read_pool;
+mod db_recovery;
+mod headless_batch;
 mod image_pipeline;
 mod integration;
 mod layout;
@@ -23,27 +29,69 @@ mod library;
 mod media_controls_manager;
 mod media_file_discovery;
 mod metadata;
+mod metrics;
+mod migration_assistant;
 #[path = "playlist/playlist.rs"]
 mod playlist;
+#[path = "playlist/playlist_column_filters.rs"]
+mod playlist_column_filters;
+#[path = "playlist/playlist_document.rs"]
+mod playlist_document;
+#[path = "playlist/playlist_folders.rs"]
+mod playlist_folders;
 #[path = "playlist/playlist_manager.rs"]
 mod playlist_manager;
+#[path = "playlist/playlist_restructure.rs"]
+mod playlist_restructure;
+#[path = "playlist/playlist_sort.rs"]
+mod playlist_sort;
 mod protocol;
 mod protocol_utils;
+mod protocol_wire;
+#[path = "playlist/remote_sync_status.rs"]
+mod remote_sync_status;
+mod rendering_backend;
 mod runtime;
 mod runtime_config;
+mod sandbox_access;
+mod session_journal;
+mod session_monitor;
+mod startup_timing;
 mod text_template;
 mod theme;
+#[path = "playlist/track_relocation.rs"]
+mod track_relocation;
+#[path = "playlist/track_source.rs"]
+mod track_source;
+#[path = "playlist/transition_history.rs"]
+mod transition_history;
 mod ui;
 mod ui_manager;
+mod worker_pool;
+#[path = "playlist/xspf.rs"]
+mod xspf;
 
-pub(crate) use audio::{audio_decoder, audio_player, audio_probe, output_option_selection};
+pub(crate) use audio::{
+    audio_decoder, audio_player, audio_probe, dsp_chain, eq_profile_import, fault_injection,
+    format_quality, http_range_source, intro_outro_detection, loudness_analysis, night_mode,
+    output_option_selection, output_test, parametric_eq, sample_rate_grouping, seek_markers,
+    silence_skipping, spectrogram, transcode_detection,
+};
 pub(crate) use cast::cast_manager;
 pub(crate) use integration::{
     integration_keyring, integration_manager, integration_uri, opensubsonic_controller,
+    playlist_materialize, playlist_url, profile_share, webdav_controller,
+};
+pub(crate) use library::{
+    activity_log, album_artist_inference, file_organizer, import_rules, integrity_check,
+    library_enrichment_manager, library_facets, library_manager, maintenance_scheduler, play_stats,
+    release_kind, source_matching, timed_mix, track_energy, user_tags,
 };
-pub(crate) use library::{library_enrichment_manager, library_manager};
-pub(crate) use metadata::{metadata_manager, metadata_tags};
-pub(crate) use runtime::audio_runtime_reactor;
+pub(crate) use metadata::{
+    batch_edit, cue_sheet, lyrics, lyrics_manager, metadata_manager, metadata_tags,
+    tag_change_watcher, tag_lookup_manager,
+};
+pub(crate) use runtime::{audio_runtime_reactor, task_manager};
 
 use std::{
     collections::HashSet,
@@ -60,8 +108,11 @@ use std::{
 use app_config_coordinator::apply_config_update;
 use app_context::AppSharedState;
 use config::{
-    BackendProfileConfig, BufferingConfig, Config, IntegrationsConfig, LibraryConfig, OutputConfig,
-    ResamplerQuality, UiConfig, UiPlaybackOrder, UiRepeatMode,
+    AnnouncementConfig, BackendProfileConfig, BufferingConfig, CastConfig, CastDeviceVolumeCap,
+    Config, ConfirmationsConfig, DspConfig, EqDevicePresetConfig, EqFilterConfig, EqPresetConfig,
+    IntegrationsConfig, LibraryConfig, MockBackendConfig, OutputConfig,
+    OutputDeviceRatePolicyConfig, PerformanceConfig, ResamplerQuality, SampleRatePolicy, UiConfig,
+    UiEnergyRampArc, UiPlaybackOrder, UiRepeatMode, UiTransportBarPlacement,
 };
 use layout::{add_root_leaf_if_empty, sanitize_layout_config};
 use log::warn;
@@ -115,6 +166,9 @@ fn setup_app_state_associations(ui: &AppWindow, ui_state: &UiState) {
     ui.set_settings_library_folders(ModelRc::from(Rc::new(VecModel::from(Vec::<
         slint::SharedString,
     >::new()))));
+    ui.set_settings_library_import_rules(ModelRc::from(Rc::new(VecModel::from(Vec::<
+        slint::SharedString,
+    >::new()))));
 }
 
 /// Briefly toggles the playlist read-only visual indicator for blocked edits.
@@ -249,7 +303,11 @@ pub(crate) fn resolve_effective_runtime_config(
         .expect("runtime audio state lock poisoned")
         .clone();
     let mut effective_config = persisted_config.clone();
-    effective_config.output = runtime_audio.output;
+    effective_config.output = OutputConfig {
+        // Rate policies only steer switching between tracks, so they apply straight away.
+        device_rate_policies: persisted_config.output.device_rate_policies.clone(),
+        ..runtime_audio.output
+    };
     effective_config.cast = runtime_audio.cast;
     resolve_runtime_config(&effective_config, output_options, runtime_output_override)
 }
@@ -347,10 +405,58 @@ fn add_library_folders_to_config_and_scan(
     added_count
 }
 
+/// Clamps a dB setting into `(min_db, max_db)`; non-finite values become 0 dB.
+fn sanitize_db(db: f32, (min_db, max_db): (f32, f32)) -> f32 {
+    let db = if db.is_finite() { db } else { 0.0 };
+    db.clamp(min_db, max_db)
+}
+
+/// Drops unusable EQ bands and duplicate preset names, clamps gains, and forgets device
+/// assignments whose preset no longer exists.
+fn sanitize_eq_presets(
+    presets: &[EqPresetConfig],
+    assignments: &[EqDevicePresetConfig],
+) -> (Vec<EqPresetConfig>, Vec<EqDevicePresetConfig>) {
+    let mut seen_names = HashSet::new();
+    let presets: Vec<EqPresetConfig> = presets
+        .iter()
+        .filter(|preset| !preset.name.trim().is_empty() && seen_names.insert(preset.name.clone()))
+        .map(|preset| EqPresetConfig {
+            name: preset.name.clone(),
+            target: preset.target.clone(),
+            preamp_db: sanitize_db(preset.preamp_db, parametric_eq::EQ_GAIN_RANGE_DB),
+            filters: preset
+                .filters
+                .iter()
+                .filter(|filter| parametric_eq::is_valid_eq_filter(filter))
+                .take(parametric_eq::MAX_EQ_FILTERS)
+                .map(|filter| EqFilterConfig {
+                    gain_db: sanitize_db(filter.gain_db, parametric_eq::EQ_GAIN_RANGE_DB),
+                    ..*filter
+                })
+                .collect(),
+        })
+        .collect();
+    let mut seen_devices = HashSet::new();
+    let assignments = assignments
+        .iter()
+        .filter(|assignment| {
+            presets
+                .iter()
+                .any(|preset| preset.name == assignment.preset_name)
+                && seen_devices.insert(assignment.output_device_name.clone())
+        })
+        .cloned()
+        .collect();
+    (presets, assignments)
+}
+
 /// Sanitizes loaded config values and normalizes derived fields into safe runtime ranges.
 pub(crate) fn sanitize_config(config: Config) -> Config {
     let sanitized_playlist_columns = sanitize_playlist_columns(&config.ui.playlist_columns);
     let migrated_status_bar_leaf_ids = legacy_status_bar_leaf_ids(&config.ui.layout.root);
+    let (eq_presets, eq_device_presets) =
+        sanitize_eq_presets(&config.dsp.eq_presets, &config.dsp.eq_device_presets);
     let output_device_name = if config.output.output_device_auto {
         "default".to_string()
     } else {
@@ -413,6 +519,10 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
     sanitized_layout.playlist_album_art_column_max_width_px = clamped_album_art_column_max_width_px;
     sanitized_layout.playlist_columns = sanitized_playlist_columns.clone();
     sanitized_layout.playlist_column_width_overrides = sanitized_column_width_overrides;
+    sanitized_layout.playlist_column_styles = sanitize_layout_column_styles(
+        &sanitized_layout.playlist_column_styles,
+        &sanitized_playlist_columns,
+    );
     sanitized_layout.button_cluster_instances = sanitized_button_cluster_instances;
     sanitized_layout.collection_panel_instances = sanitized_collection_panel_instances;
     sanitized_layout.metadata_viewer_panel_instances = sanitized_metadata_viewer_panel_instances;
@@ -425,6 +535,7 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
         .clamp(1_000, 120_000);
     let clamped_interval = config.buffering.player_request_interval_ms.max(20);
     let clamped_decoder_chunk = config.buffering.decoder_request_chunk_ms.max(100);
+    let clamped_decoded_cache_max_mb = config.buffering.decoded_cache_max_mb.clamp(16, 4_096);
     let mut sanitized_library_folders = Vec::new();
     let mut seen_folders = HashSet::new();
     for folder in config.library.folders {
@@ -456,6 +567,52 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
         .library
         .artist_image_cache_max_size_mb
         .clamp(16, 16_384);
+    let clamped_decoded_audio_cache_max_size_mb = cache_manager::sanitize_file_cache_max_size_mb(
+        config.library.decoded_audio_cache_max_size_mb,
+    );
+    let clamped_offline_cache_max_size_mb =
+        cache_manager::sanitize_file_cache_max_size_mb(config.library.offline_cache_max_size_mb);
+    let mut sanitized_import_rules = Vec::new();
+    let mut seen_import_rule_folders = HashSet::new();
+    for rule in config
+        .library
+        .import_rules
+        .iter()
+        .filter_map(import_rules::sanitize_import_rule)
+    {
+        if seen_import_rule_folders.insert(rule.folder.to_ascii_lowercase()) {
+            sanitized_import_rules.push(rule);
+        }
+    }
+    let mut sanitized_cast_volume_caps = Vec::new();
+    let mut seen_cast_device_ids = HashSet::new();
+    for cap in &config.cast.device_volume_caps {
+        let trimmed_device_id = cap.device_id.trim();
+        if trimmed_device_id.is_empty() || !seen_cast_device_ids.insert(trimmed_device_id) {
+            continue;
+        }
+        sanitized_cast_volume_caps.push(CastDeviceVolumeCap {
+            device_id: trimmed_device_id.to_string(),
+            max_volume: if cap.max_volume.is_finite() {
+                cap.max_volume.clamp(0.0, 1.0)
+            } else {
+                1.0
+            },
+        });
+    }
+    let mut sanitized_rate_policies = Vec::new();
+    let mut seen_rate_policy_devices = HashSet::new();
+    for assignment in &config.output.device_rate_policies {
+        let trimmed_device_name = assignment.output_device_name.trim();
+        if trimmed_device_name.is_empty() || !seen_rate_policy_devices.insert(trimmed_device_name) {
+            continue;
+        }
+        sanitized_rate_policies.push(OutputDeviceRatePolicyConfig {
+            output_device_name: trimmed_device_name.to_string(),
+            policy: assignment.policy,
+            pinned_sample_rate_hz: assignment.pinned_sample_rate_hz.clamp(8_000, 192_000),
+        });
+    }
     let mut sanitized_backends = Vec::new();
     let mut seen_backend_ids = HashSet::new();
     for backend in config.integrations.backends {
@@ -486,11 +643,20 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
             channel_count_auto: config.output.channel_count_auto,
             sample_rate_auto: config.output.sample_rate_auto,
             bits_per_sample_auto: config.output.bits_per_sample_auto,
+            group_sample_rate_families: config.output.group_sample_rate_families,
+            resample_rate_outliers: config.output.resample_rate_outliers,
+            device_rate_policies: sanitized_rate_policies,
             resampler_quality: config.output.resampler_quality,
             dither_on_bitdepth_reduce: config.output.dither_on_bitdepth_reduce,
             downmix_higher_channel_tracks: config.output.downmix_higher_channel_tracks,
+            skip_hidden_pregap: config.output.skip_hidden_pregap,
+            skip_silence_spoken_word: config.output.skip_silence_spoken_word,
+            skip_silence_min_pause_ms: config.output.skip_silence_min_pause_ms.clamp(250, 5_000),
+        },
+        cast: CastConfig {
+            allow_transcode_fallback: config.cast.allow_transcode_fallback,
+            device_volume_caps: sanitized_cast_volume_caps,
         },
-        cast: config.cast.clone(),
         ui: UiConfig {
             show_layout_edit_intro: config.ui.show_layout_edit_intro,
             show_tooltips: config.ui.show_tooltips,
@@ -505,10 +671,28 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
             volume: clamped_volume,
             playback_order: config.ui.playback_order,
             repeat_mode: config.ui.repeat_mode,
+            energy_ramp_arc: config.ui.energy_ramp_arc,
+            recent_import_locations: ui::import_dialog::sanitize_recent_locations(
+                &config.ui.recent_import_locations,
+            ),
+            hi_res_min_sample_rate_hz: config.ui.hi_res_min_sample_rate_hz.clamp(8_000, 768_000),
+            hi_res_min_bit_depth: config.ui.hi_res_min_bit_depth.clamp(8, 32),
+            seek_small_step_secs: config.ui.seek_small_step_secs.clamp(1, 600),
+            seek_large_step_secs: config.ui.seek_large_step_secs.clamp(1, 600),
+            zero_based_row_numbers: config.ui.zero_based_row_numbers,
+            transport_bar_placement: config.ui.transport_bar_placement,
+            scale_override_percent: ui::window_scale::sanitize_scale_override_percent(
+                config.ui.scale_override_percent,
+            ),
+            file_browser_root: config.ui.file_browser_root.trim().to_string(),
+            external_tagger_command: config.ui.external_tagger_command.trim().to_string(),
+            library_album_grid: config.ui.library_album_grid,
+            shortcut_keys: ui::keyboard_shortcuts::sanitize_shortcut_keys(&config.ui.shortcut_keys),
         },
         library: LibraryConfig {
             folders: sanitized_library_folders,
             online_metadata_enabled: config.library.online_metadata_enabled,
+            acoustid_api_key: config.library.acoustid_api_key.trim().to_string(),
             online_metadata_prompt_pending: config.library.online_metadata_prompt_pending,
             include_playlist_tracks_in_library: config.library.include_playlist_tracks_in_library,
             list_image_max_edge_px: clamped_list_image_max_edge_px,
@@ -518,15 +702,93 @@ pub(crate) fn sanitize_config(config: Config) -> Config {
             image_memory_cache_ttl_secs: clamped_image_memory_cache_ttl_secs,
             artist_image_cache_ttl_days: clamped_artist_image_cache_ttl_days,
             artist_image_cache_max_size_mb: clamped_artist_image_cache_max_size_mb,
+            cover_art_cache_dir: config.library.cover_art_cache_dir.trim().to_string(),
+            artist_image_cache_dir: config.library.artist_image_cache_dir.trim().to_string(),
+            decoded_audio_cache_dir: config.library.decoded_audio_cache_dir.trim().to_string(),
+            decoded_audio_cache_max_size_mb: clamped_decoded_audio_cache_max_size_mb,
+            offline_cache_dir: config.library.offline_cache_dir.trim().to_string(),
+            offline_cache_max_size_mb: clamped_offline_cache_max_size_mb,
+            import_rules: sanitized_import_rules,
+            maintenance_rescan_interval_hours: config
+                .library
+                .maintenance_rescan_interval_hours
+                .min(maintenance_scheduler::MAX_INTERVAL_HOURS),
+            maintenance_prune_interval_hours: config
+                .library
+                .maintenance_prune_interval_hours
+                .min(maintenance_scheduler::MAX_INTERVAL_HOURS),
+            maintenance_remote_sync_interval_hours: config
+                .library
+                .maintenance_remote_sync_interval_hours
+                .min(maintenance_scheduler::MAX_INTERVAL_HOURS),
+            maintenance_cache_expiry_interval_hours: config
+                .library
+                .maintenance_cache_expiry_interval_hours
+                .min(maintenance_scheduler::MAX_INTERVAL_HOURS),
+            maintenance_integrity_interval_hours: config
+                .library
+                .maintenance_integrity_interval_hours
+                .min(maintenance_scheduler::MAX_INTERVAL_HOURS),
+            watch_tag_changes: config.library.watch_tag_changes,
+            slow_storage_mode: config.library.slow_storage_mode,
         },
         buffering: BufferingConfig {
             player_low_watermark_ms: clamped_low_watermark,
             player_target_buffer_ms: clamped_target,
             player_request_interval_ms: clamped_interval,
             decoder_request_chunk_ms: clamped_decoder_chunk,
+            decoded_cache_max_mb: clamped_decoded_cache_max_mb,
         },
         integrations: IntegrationsConfig {
             backends: sanitized_backends,
+            mock_backend: config
+                .integrations
+                .mock_backend
+                .map(|mock| MockBackendConfig {
+                    latency_ms: mock.latency_ms.min(10_000),
+                    failure_rate_percent: mock.failure_rate_percent.min(100),
+                    unavailable: mock.unavailable,
+                    track_count: mock.track_count.clamp(1, 100_000),
+                }),
+        },
+        announcements: AnnouncementConfig {
+            enabled: config.announcements.enabled,
+            voice: config.announcements.voice.trim().to_string(),
+            rate_wpm: config.announcements.rate_wpm.clamp(80, 450),
+            duck_playback: config.announcements.duck_playback,
+            duck_gain: config.announcements.duck_gain.clamp(0.0, 1.0),
+        },
+        power: config.power.clone(),
+        performance: PerformanceConfig {
+            decode_threads: config
+                .performance
+                .decode_threads
+                .min(worker_pool::MAX_WORKER_THREADS),
+            analysis_threads: config
+                .performance
+                .analysis_threads
+                .min(worker_pool::MAX_WORKER_THREADS),
+            worker_priority: config.performance.worker_priority,
+            rendering_backend: config.performance.rendering_backend,
+        },
+        confirmations: ConfirmationsConfig {
+            remove_tracks_threshold: config
+                .confirmations
+                .remove_tracks_threshold
+                .clamp(1, ui::confirmation_policy::MAX_REMOVE_TRACKS_THRESHOLD),
+            ..config.confirmations.clone()
+        },
+        dsp: DspConfig {
+            night_mode_strength_percent: config.dsp.night_mode_strength_percent.min(100),
+            normalize_loudness: config.dsp.normalize_loudness,
+            preamp_db: sanitize_db(config.dsp.preamp_db, dsp_chain::PREAMP_RANGE_DB),
+            limiter_enabled: config.dsp.limiter_enabled,
+            limiter_headroom_db: sanitize_db(
+                config.dsp.limiter_headroom_db,
+                dsp_chain::LIMITER_HEADROOM_RANGE_DB,
+            ),
+            eq_presets,
+            eq_device_presets,
         },
     }
 }
@@ -566,21 +828,34 @@ pub(crate) fn apply_config_to_ui(
     workspace_height_px: u32,
 ) {
     const SAMPLE_RATE_MODE_OPTIONS: [&str; 2] = ["Match Content (Recommended)", "Manual"];
-    const RESAMPLER_QUALITY_OPTIONS: [&str; 2] = ["High", "Highest"];
+    const RESAMPLER_QUALITY_OPTIONS: [&str; 4] = ["Linear", "Medium", "High", "Highest"];
 
     ui.set_volume_level(config.ui.volume);
     let playback_order_index = match config.ui.playback_order {
         UiPlaybackOrder::Default => 0,
         UiPlaybackOrder::Shuffle => 1,
         UiPlaybackOrder::Random => 2,
+        UiPlaybackOrder::ShuffleFolders => 3,
+        UiPlaybackOrder::EnergyRamp => 4,
     };
     let repeat_mode_index = match config.ui.repeat_mode {
         UiRepeatMode::Off => 0,
         UiRepeatMode::Playlist => 1,
         UiRepeatMode::Track => 2,
     };
+    let energy_ramp_arc_index = match config.ui.energy_ramp_arc {
+        UiEnergyRampArc::BuildUp => 0,
+        UiEnergyRampArc::WindDown => 1,
+        UiEnergyRampArc::Peak => 2,
+    };
     ui.set_playback_order_index(playback_order_index);
     ui.set_repeat_mode(repeat_mode_index);
+    ui.set_settings_energy_ramp_arc_index(energy_ramp_arc_index);
+    ui.set_settings_transport_bar_placement_index(match config.ui.transport_bar_placement {
+        UiTransportBarPlacement::Off => 0,
+        UiTransportBarPlacement::Top => 1,
+        UiTransportBarPlacement::Bottom => 2,
+    });
     ui.set_sidebar_width_px(sidebar_width_from_window(config.ui.window_width));
     ui.set_layout_panel_options(ModelRc::from(Rc::new(VecModel::from(
         layout_panel_options(),
@@ -683,8 +958,10 @@ pub(crate) fn apply_config_to_ui(
     );
     let sample_rate_mode_index = if config.output.sample_rate_auto { 0 } else { 1 };
     let resampler_quality_index = match config.output.resampler_quality {
-        ResamplerQuality::High => 0,
-        ResamplerQuality::Highest => 1,
+        ResamplerQuality::Linear => 0,
+        ResamplerQuality::Medium => 1,
+        ResamplerQuality::High => 2,
+        ResamplerQuality::Highest => 3,
     };
 
     ui.set_settings_output_device_index(device_index as i32);
@@ -696,6 +973,127 @@ pub(crate) fn apply_config_to_ui(
     ui.set_settings_show_layout_edit_tutorial(config.ui.show_layout_edit_intro);
     ui.set_settings_show_tooltips(config.ui.show_tooltips);
     ui.set_settings_auto_scroll_to_playing_track(config.ui.auto_scroll_to_playing_track);
+    ui.set_seek_small_step_secs(config.ui.seek_small_step_secs as i32);
+    ui.set_seek_large_step_secs(config.ui.seek_large_step_secs as i32);
+    let seek_step_options: Vec<slint::SharedString> = ui::seek_steps::SEEK_STEP_CHOICES_SECS
+        .iter()
+        .map(|secs| ui::seek_steps::seek_step_label(*secs).into())
+        .collect();
+    ui.set_settings_seek_step_options(ModelRc::from(Rc::new(VecModel::from(seek_step_options))));
+    ui.set_settings_seek_small_step_index(ui::seek_steps::seek_step_choice_index(
+        config.ui.seek_small_step_secs,
+    ) as i32);
+    ui.set_settings_seek_large_step_index(ui::seek_steps::seek_step_choice_index(
+        config.ui.seek_large_step_secs,
+    ) as i32);
+    ui.set_settings_external_tagger_command(config.ui.external_tagger_command.as_str().into());
+    ui.set_library_album_grid(config.ui.library_album_grid);
+    ui::keyboard_shortcuts::apply_shortcut_keys_to_ui(ui, &config.ui.shortcut_keys);
+    ui.set_settings_skip_silence_spoken_word(config.output.skip_silence_spoken_word);
+    ui.set_settings_zero_based_row_numbers(config.ui.zero_based_row_numbers);
+    let scale_override_options: Vec<slint::SharedString> =
+        ui::window_scale::SCALE_OVERRIDE_CHOICES_PERCENT
+            .iter()
+            .map(|percent| ui::window_scale::scale_override_label(*percent).into())
+            .collect();
+    ui.set_settings_scale_override_options(ModelRc::from(Rc::new(VecModel::from(
+        scale_override_options,
+    ))));
+    ui.set_settings_scale_override_index(ui::window_scale::scale_override_choice_index(
+        config.ui.scale_override_percent,
+    ) as i32);
+    let skip_silence_pause_options: Vec<slint::SharedString> =
+        silence_skipping::MIN_PAUSE_CHOICES_MS
+            .iter()
+            .map(|ms| silence_skipping::min_pause_label(*ms).into())
+            .collect();
+    ui.set_settings_skip_silence_pause_options(ModelRc::from(Rc::new(VecModel::from(
+        skip_silence_pause_options,
+    ))));
+    ui.set_settings_skip_silence_pause_index(silence_skipping::min_pause_choice_index(
+        config.output.skip_silence_min_pause_ms,
+    ) as i32);
+    let night_mode_strength_options: Vec<slint::SharedString> =
+        night_mode::NIGHT_MODE_STRENGTH_CHOICES_PERCENT
+            .iter()
+            .map(|percent| night_mode::night_mode_strength_label(*percent).into())
+            .collect();
+    ui.set_settings_night_mode_strength_options(ModelRc::from(Rc::new(VecModel::from(
+        night_mode_strength_options,
+    ))));
+    ui.set_settings_night_mode_strength_index(night_mode::night_mode_strength_choice_index(
+        config.dsp.night_mode_strength_percent,
+    ) as i32);
+    ui.set_settings_normalize_loudness(config.dsp.normalize_loudness);
+    let mut eq_preset_options: Vec<slint::SharedString> = vec!["Off".into()];
+    eq_preset_options.extend(
+        config
+            .dsp
+            .eq_presets
+            .iter()
+            .map(|preset| preset.name.as_str().into()),
+    );
+    ui.set_settings_eq_preset_options(ModelRc::from(Rc::new(VecModel::from(eq_preset_options))));
+    let assigned_eq_preset = config
+        .dsp
+        .eq_preset_name_for_device(&config.output.output_device_name);
+    let eq_preset_index = config
+        .dsp
+        .eq_presets
+        .iter()
+        .position(|preset| Some(preset.name.as_str()) == assigned_eq_preset)
+        .map_or(0, |index| index + 1);
+    ui.set_settings_eq_preset_index(eq_preset_index as i32);
+    ui.set_settings_eq_device_name(if config.output.output_device_auto {
+        "System default".into()
+    } else {
+        config.output.output_device_name.as_str().into()
+    });
+    let rate_policy = config.output.active_rate_policy();
+    ui.set_settings_rate_policy_index(match rate_policy.policy {
+        SampleRatePolicy::MatchSource => 0,
+        SampleRatePolicy::Pinned => 1,
+        SampleRatePolicy::AlbumBoundaries => 2,
+    });
+    let pinned_rate_options: Vec<slint::SharedString> =
+        sample_rate_grouping::PINNED_RATE_CHOICES_HZ
+            .iter()
+            .map(|rate_hz| sample_rate_grouping::rate_choice_label(*rate_hz).into())
+            .collect();
+    ui.set_settings_pinned_rate_options(ModelRc::from(Rc::new(VecModel::from(
+        pinned_rate_options,
+    ))));
+    ui.set_settings_pinned_rate_index(sample_rate_grouping::rate_choice_index(
+        rate_policy.pinned_sample_rate_hz,
+    ) as i32);
+    let output_test_options: Vec<slint::SharedString> = output_test::OUTPUT_TEST_KINDS
+        .iter()
+        .map(|kind| kind.label().into())
+        .collect();
+    ui.set_settings_output_test_options(ModelRc::from(Rc::new(VecModel::from(
+        output_test_options,
+    ))));
+    let preamp_options: Vec<slint::SharedString> = dsp_chain::PREAMP_CHOICES_DB
+        .iter()
+        .map(|db| dsp_chain::db_choice_label(*db, true).into())
+        .collect();
+    ui.set_settings_preamp_options(ModelRc::from(Rc::new(VecModel::from(preamp_options))));
+    ui.set_settings_preamp_index(dsp_chain::db_choice_index(
+        dsp_chain::PREAMP_CHOICES_DB,
+        config.dsp.preamp_db,
+    ) as i32);
+    ui.set_settings_limiter_enabled(config.dsp.limiter_enabled);
+    let limiter_headroom_options: Vec<slint::SharedString> = dsp_chain::LIMITER_HEADROOM_CHOICES_DB
+        .iter()
+        .map(|db| dsp_chain::db_choice_label(*db, false).into())
+        .collect();
+    ui.set_settings_limiter_headroom_options(ModelRc::from(Rc::new(VecModel::from(
+        limiter_headroom_options,
+    ))));
+    ui.set_settings_limiter_headroom_index(dsp_chain::db_choice_index(
+        dsp_chain::LIMITER_HEADROOM_CHOICES_DB,
+        config.dsp.limiter_headroom_db,
+    ) as i32);
     let resolved_theme = resolve_theme(&config.ui.layout);
     let parse_theme_color = |value: &str| {
         parse_slint_color(value).unwrap_or_else(|| slint::Color::from_rgb_u8(0, 0, 0))
@@ -759,6 +1157,9 @@ pub(crate) fn apply_config_to_ui(
     ui.set_settings_custom_color_picker_b(239.0);
     ui.set_settings_dither_on_bitdepth_reduce(config.output.dither_on_bitdepth_reduce);
     ui.set_settings_downmix_higher_channel_tracks(config.output.downmix_higher_channel_tracks);
+    ui.set_settings_skip_hidden_pregap(config.output.skip_hidden_pregap);
+    ui.set_settings_group_sample_rate_families(config.output.group_sample_rate_families);
+    ui.set_settings_resample_rate_outliers(config.output.resample_rate_outliers);
     ui.set_settings_cast_allow_transcode_fallback(config.cast.allow_transcode_fallback);
     ui.set_settings_verified_sample_rates_summary(
         output_options.verified_sample_rates_summary.clone().into(),
@@ -787,10 +1188,118 @@ pub(crate) fn apply_config_to_ui(
             ui.set_settings_library_selected_folder_index(0);
         }
     }
+    let import_rule_summaries: Vec<slint::SharedString> = config
+        .library
+        .import_rules
+        .iter()
+        .map(|rule| import_rules::rule_summary(rule).into())
+        .collect();
+    ui.set_settings_library_import_rules(ModelRc::from(Rc::new(VecModel::from(
+        import_rule_summaries,
+    ))));
+    let maintenance_interval_indices: Vec<i32> = maintenance_scheduler::ALL_TASKS
+        .iter()
+        .map(|task| {
+            maintenance_scheduler::interval_choice_index(maintenance_scheduler::interval_hours(
+                &config.library,
+                *task,
+            )) as i32
+        })
+        .collect();
+    ui.set_settings_maintenance_interval_indices(ModelRc::from(Rc::new(VecModel::from(
+        maintenance_interval_indices,
+    ))));
+    let confirmation_labels: Vec<slint::SharedString> = ui::confirmation_policy::ALL_ACTIONS
+        .iter()
+        .map(|action| {
+            ui::confirmation_policy::settings_label(&config.confirmations, *action).into()
+        })
+        .collect();
+    ui.set_settings_confirmation_labels(ModelRc::from(Rc::new(VecModel::from(
+        confirmation_labels,
+    ))));
+    let confirmation_enabled: Vec<bool> = ui::confirmation_policy::ALL_ACTIONS
+        .iter()
+        .map(|action| {
+            ui::confirmation_policy::is_confirmation_enabled(&config.confirmations, *action)
+        })
+        .collect();
+    ui.set_settings_confirmation_enabled(ModelRc::from(Rc::new(VecModel::from(
+        confirmation_enabled,
+    ))));
+    let decode_thread_options: Vec<slint::SharedString> = worker_pool::thread_choice_labels(
+        worker_pool::resolved_decode_threads(&PerformanceConfig::default()),
+    )
+    .into_iter()
+    .map(Into::into)
+    .collect();
+    ui.set_settings_decode_thread_options(ModelRc::from(Rc::new(VecModel::from(
+        decode_thread_options,
+    ))));
+    ui.set_settings_decode_thread_index(worker_pool::thread_choice_index(
+        config.performance.decode_threads,
+    ) as i32);
+    let analysis_thread_options: Vec<slint::SharedString> = worker_pool::thread_choice_labels(
+        worker_pool::resolved_analysis_threads(&PerformanceConfig::default()),
+    )
+    .into_iter()
+    .map(Into::into)
+    .collect();
+    ui.set_settings_analysis_thread_options(ModelRc::from(Rc::new(VecModel::from(
+        analysis_thread_options,
+    ))));
+    ui.set_settings_analysis_thread_index(worker_pool::thread_choice_index(
+        config.performance.analysis_threads,
+    ) as i32);
+    let worker_priority_options: Vec<slint::SharedString> = worker_pool::PRIORITY_CHOICES
+        .iter()
+        .map(|priority| worker_pool::priority_label(*priority).into())
+        .collect();
+    ui.set_settings_worker_priority_options(ModelRc::from(Rc::new(VecModel::from(
+        worker_priority_options,
+    ))));
+    ui.set_settings_worker_priority_index(
+        worker_pool::PRIORITY_CHOICES
+            .iter()
+            .position(|priority| *priority == config.performance.worker_priority)
+            .unwrap_or(0) as i32,
+    );
+    let rendering_backend_options: Vec<slint::SharedString> =
+        rendering_backend::RENDERING_BACKEND_CHOICES
+            .iter()
+            .map(|backend| rendering_backend::rendering_backend_label(*backend).into())
+            .collect();
+    ui.set_settings_rendering_backend_options(ModelRc::from(Rc::new(VecModel::from(
+        rendering_backend_options,
+    ))));
+    ui.set_settings_rendering_backend_index(
+        rendering_backend::RENDERING_BACKEND_CHOICES
+            .iter()
+            .position(|backend| *backend == config.performance.rendering_backend)
+            .unwrap_or(0) as i32,
+    );
+    ui.set_settings_developer_mode(fault_injection::developer_mode_enabled());
+    let fault_labels: Vec<slint::SharedString> = fault_injection::ALL_FAULTS
+        .iter()
+        .map(|fault| fault.label().into())
+        .collect();
+    ui.set_settings_fault_injection_labels(ModelRc::from(Rc::new(VecModel::from(fault_labels))));
+    let fault_states: Vec<bool> = fault_injection::ALL_FAULTS
+        .iter()
+        .map(|fault| fault_injection::is_fault_enabled(*fault))
+        .collect();
+    ui.set_settings_fault_injection_enabled(ModelRc::from(Rc::new(VecModel::from(fault_states))));
+    if ui.get_settings_library_selected_import_rule_index() as i64
+        >= config.library.import_rules.len() as i64
+    {
+        ui.set_settings_library_selected_import_rule_index(-1);
+    }
     ui.set_settings_library_online_metadata_enabled(config.library.online_metadata_enabled);
     ui.set_settings_library_include_playlist_tracks_in_library(
         config.library.include_playlist_tracks_in_library,
     );
+    ui.set_settings_library_watch_tag_changes(config.library.watch_tag_changes);
+    ui.set_settings_library_slow_storage_mode(config.library.slow_storage_mode);
     if let Some(backend) = find_opensubsonic_backend(config) {
         ui.set_settings_subsonic_enabled(backend.enabled);
         ui.set_settings_subsonic_endpoint(backend.endpoint.clone().into());
@@ -811,6 +1320,26 @@ pub(crate) fn apply_config_to_ui(
         ui.set_settings_subsonic_password("".into());
         ui.set_settings_subsonic_status("Not configured".into());
     }
+    if let Some(backend) = webdav_controller::find_webdav_backend(config) {
+        ui.set_settings_webdav_enabled(backend.enabled);
+        ui.set_settings_webdav_endpoint(backend.endpoint.clone().into());
+        ui.set_settings_webdav_username(backend.username.clone().into());
+        ui.set_settings_webdav_password("".into());
+        let status = if backend.endpoint.trim().is_empty() || backend.username.trim().is_empty() {
+            "Not configured".to_string()
+        } else if backend.enabled {
+            "Configured (ready to connect)".to_string()
+        } else {
+            "Configured (disabled)".to_string()
+        };
+        ui.set_settings_webdav_status(status.into());
+    } else {
+        ui.set_settings_webdav_enabled(false);
+        ui.set_settings_webdav_endpoint("".into());
+        ui.set_settings_webdav_username("".into());
+        ui.set_settings_webdav_password("".into());
+        ui.set_settings_webdav_status("Not configured".into());
+    }
     apply_playlist_columns_to_ui(ui, config);
     apply_layout_to_ui(ui, config, workspace_width_px, workspace_height_px);
 }
@@ -837,9 +1366,33 @@ fn install_panic_hook() {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    startup_timing::begin();
     initialize_logging();
     install_panic_hook();
-    app_runtime::AppRuntime::build()?.run()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = headless_batch::parse_batch_args(&args) {
+        let exit_code = match command {
+            Ok(command) => headless_batch::run(command),
+            Err(usage) => {
+                eprintln!("{usage}");
+                2
+            }
+        };
+        std::process::exit(exit_code);
+    }
+    let safe_mode = args
+        .iter()
+        .any(|arg| arg == session_journal::SAFE_MODE_FLAG);
+    let database_repair = args
+        .iter()
+        .find_map(|arg| db_recovery::DatabaseRepair::from_arg(arg));
+    let profile_share = args
+        .iter()
+        .find_map(|arg| profile_share::parse_profile_share_link(arg));
+    match app_runtime::AppRuntime::build(safe_mode, database_repair, profile_share)? {
+        Some(runtime) => runtime.run(),
+        None => Ok(()),
+    }
 }
 
 #[cfg(test)]
@@ -7,6 +7,8 @@ use std::{
 
 use log::debug;
 
+use crate::sandbox_access;
+
 /// File extensions treated as importable audio tracks.
 pub const SUPPORTED_AUDIO_EXTENSIONS: [&str; 7] =
     ["mp3", "wav", "ogg", "flac", "aac", "m4a", "mp4"];
@@ -29,7 +31,7 @@ pub fn collect_audio_files_from_folder(folder_path: &Path) -> Vec<PathBuf> {
     let mut tracks = Vec::new();
 
     while let Some(directory) = pending_directories.pop() {
-        let entries = match std::fs::read_dir(&directory) {
+        let entries = match sandbox_access::read_dir(&directory) {
             Ok(entries) => entries,
             Err(err) => {
                 debug!("Failed to read directory {}: {}", directory.display(), err);
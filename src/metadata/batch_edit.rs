@@ -0,0 +1,106 @@
+//! Aggregation and merging for editing the tags of several tracks at once.
+//!
+//! Multi-selection editing covers the common field set only; file-specific extra tags
+//! stay untouched. Fields whose values differ between the tracks are shown empty with
+//! a "<multiple values>" hint, and only fields the user marks to apply are written.
+
+use crate::protocol::MetadataEditorField;
+
+/// Placeholder shown for a field whose values differ between the selected tracks.
+pub const MULTIPLE_VALUES_PLACEHOLDER: &str = "<multiple values>";
+
+/// Aggregates the common fields of several tracks.
+///
+/// Returns the fields in the first track's order, each holding the shared value or an
+/// empty value, plus a parallel flag that is `true` where the tracks disagree.
+pub fn aggregate_common_fields(
+    per_track_fields: &[Vec<MetadataEditorField>],
+) -> (Vec<MetadataEditorField>, Vec<bool>) {
+    let Some(first_track) = per_track_fields.first() else {
+        return (Vec::new(), Vec::new());
+    };
+    let value_of = |fields: &[MetadataEditorField], id: &str| -> String {
+        fields
+            .iter()
+            .find(|field| field.id == id)
+            .map(|field| field.value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut aggregated = Vec::new();
+    let mut mixed = Vec::new();
+    for field in first_track.iter().filter(|field| field.common) {
+        let shared_value = value_of(first_track, &field.id);
+        let differs = per_track_fields[1..]
+            .iter()
+            .any(|fields| value_of(fields, &field.id) != shared_value);
+        aggregated.push(MetadataEditorField {
+            value: if differs { String::new() } else { shared_value },
+            ..field.clone()
+        });
+        mixed.push(differs);
+    }
+    (aggregated, mixed)
+}
+
+/// Applies `edits` on top of one track's current fields, leaving every other field as-is.
+pub fn merge_field_edits(
+    mut fields: Vec<MetadataEditorField>,
+    edits: &[MetadataEditorField],
+) -> Vec<MetadataEditorField> {
+    for edit in edits {
+        match fields.iter_mut().find(|field| field.id == edit.id) {
+            Some(field) => field.value = edit.value.clone(),
+            None => fields.push(edit.clone()),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(id: &str, value: &str, common: bool) -> MetadataEditorField {
+        MetadataEditorField {
+            id: id.to_string(),
+            field_name: id.to_string(),
+            value: value.to_string(),
+            common,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_common_fields_marks_differing_values() {
+        let (fields, mixed) = aggregate_common_fields(&[
+            vec![
+                field("common:title", "One", true),
+                field("common:album_artist", "Various", true),
+                field("key:MOOD", "calm", false),
+            ],
+            vec![
+                field("common:title", "Two", true),
+                field("common:album_artist", "Various ", true),
+            ],
+        ]);
+
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].value, "");
+        assert_eq!(fields[1].value, "Various");
+        assert_eq!(mixed, vec![true, false]);
+    }
+
+    #[test]
+    fn test_merge_field_edits_keeps_unedited_fields() {
+        let merged = merge_field_edits(
+            vec![
+                field("common:title", "One", true),
+                field("common:album_artist", "", true),
+            ],
+            &[field("common:album_artist", "Various Artists", true)],
+        );
+
+        assert_eq!(merged[0].value, "One");
+        assert_eq!(merged[1].value, "Various Artists");
+    }
+}
@@ -0,0 +1,173 @@
+//! Embedded cue-sheet parsing and hidden-pregap (HTOA) detection.
+//!
+//! CD rips frequently carry the disc's cue sheet in a `CUESHEET` tag. When the first
+//! track's `INDEX 01` starts after zero, the audio before it is a hidden track in the
+//! pregap ("hidden track one audio"), which playback can optionally skip.
+
+/// Cue sheet timestamps count CD frames, 75 per second.
+const CUE_FRAMES_PER_SECOND: u64 = 75;
+/// Pregaps shorter than this are treated as ordinary lead-in rather than hidden audio.
+const MIN_HIDDEN_PREGAP_MS: u64 = 1_000;
+
+/// One `TRACK` entry of a cue sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: String,
+    pub performer: String,
+    /// Start of the track's pregap (`INDEX 00`), when present.
+    pub pregap_start_ms: Option<u64>,
+    /// Start of the track proper (`INDEX 01`).
+    pub start_ms: u64,
+}
+
+/// Parsed cue sheet with tracks in file order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CueSheet {
+    pub title: String,
+    pub performer: String,
+    pub tracks: Vec<CueTrack>,
+}
+
+impl CueSheet {
+    /// Returns the length of hidden audio before track one, if any.
+    pub fn hidden_pregap_ms(&self) -> Option<u64> {
+        let first_track = self.tracks.first()?;
+        (first_track.start_ms >= MIN_HIDDEN_PREGAP_MS).then_some(first_track.start_ms)
+    }
+}
+
+/// Parses an `mm:ss:ff` cue timestamp into milliseconds.
+pub fn parse_cue_timestamp_ms(raw: &str) -> Option<u64> {
+    let mut parts = raw.trim().split(':');
+    let minutes = parts.next()?.parse::<u64>().ok()?;
+    let seconds = parts.next()?.parse::<u64>().ok()?;
+    let frames = parts.next()?.parse::<u64>().ok()?;
+    if parts.next().is_some() || seconds >= 60 || frames >= CUE_FRAMES_PER_SECOND {
+        return None;
+    }
+    Some((minutes * 60 + seconds) * 1000 + frames * 1000 / CUE_FRAMES_PER_SECOND)
+}
+
+fn unquote(raw: &str) -> String {
+    let trimmed = raw.trim();
+    trimmed
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(trimmed)
+        .to_string()
+}
+
+/// Parses cue sheet text; returns `None` when it defines no usable tracks.
+pub fn parse_cue_sheet(text: &str) -> Option<CueSheet> {
+    let mut sheet = CueSheet::default();
+    let mut current_track: Option<CueTrack> = None;
+    let mut current_track_has_start = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command.to_ascii_uppercase().as_str() {
+            "TRACK" => {
+                if let Some(track) = current_track.take() {
+                    if current_track_has_start {
+                        sheet.tracks.push(track);
+                    }
+                }
+                let number = rest
+                    .split_whitespace()
+                    .next()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .unwrap_or(sheet.tracks.len() as u32 + 1);
+                current_track = Some(CueTrack {
+                    number,
+                    ..CueTrack::default()
+                });
+                current_track_has_start = false;
+            }
+            "TITLE" => match current_track.as_mut() {
+                Some(track) => track.title = unquote(rest),
+                None => sheet.title = unquote(rest),
+            },
+            "PERFORMER" => match current_track.as_mut() {
+                Some(track) => track.performer = unquote(rest),
+                None => sheet.performer = unquote(rest),
+            },
+            "INDEX" => {
+                let Some(track) = current_track.as_mut() else {
+                    continue;
+                };
+                let mut fields = rest.split_whitespace();
+                let index = fields.next().and_then(|value| value.parse::<u32>().ok());
+                let timestamp_ms = fields.next().and_then(parse_cue_timestamp_ms);
+                match (index, timestamp_ms) {
+                    (Some(0), Some(timestamp_ms)) => track.pregap_start_ms = Some(timestamp_ms),
+                    (Some(1), Some(timestamp_ms)) => {
+                        track.start_ms = timestamp_ms;
+                        current_track_has_start = true;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(track) = current_track {
+        if current_track_has_start {
+            sheet.tracks.push(track);
+        }
+    }
+
+    (!sheet.tracks.is_empty()).then_some(sheet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTOA_CUE: &str = r#"PERFORMER "Some Band"
+TITLE "Some Album"
+FILE "album.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Opener"
+    INDEX 00 00:00:00
+    INDEX 01 01:32:45
+  TRACK 02 AUDIO
+    TITLE "Second"
+    PERFORMER "Guest"
+    INDEX 00 04:10:00
+    INDEX 01 04:12:00
+"#;
+
+    #[test]
+    fn test_parse_cue_timestamp_ms_converts_frames() {
+        assert_eq!(parse_cue_timestamp_ms("01:32:45"), Some(92_600));
+        assert_eq!(parse_cue_timestamp_ms("00:00:74"), Some(986));
+        assert_eq!(parse_cue_timestamp_ms("00:00:75"), None);
+        assert_eq!(parse_cue_timestamp_ms("1:2"), None);
+    }
+
+    #[test]
+    fn test_parse_cue_sheet_reads_tracks_and_indexes() {
+        let sheet = parse_cue_sheet(HTOA_CUE).expect("cue sheet should parse");
+        assert_eq!(sheet.title, "Some Album");
+        assert_eq!(sheet.performer, "Some Band");
+        assert_eq!(sheet.tracks.len(), 2);
+        assert_eq!(sheet.tracks[0].title, "Opener");
+        assert_eq!(sheet.tracks[0].pregap_start_ms, Some(0));
+        assert_eq!(sheet.tracks[1].number, 2);
+        assert_eq!(sheet.tracks[1].performer, "Guest");
+        assert_eq!(sheet.tracks[1].start_ms, 252_000);
+    }
+
+    #[test]
+    fn test_hidden_pregap_ms_requires_delayed_first_index() {
+        let sheet = parse_cue_sheet(HTOA_CUE).expect("cue sheet should parse");
+        assert_eq!(sheet.hidden_pregap_ms(), Some(92_600));
+
+        let plain =
+            parse_cue_sheet("TRACK 01 AUDIO\nINDEX 01 00:00:32\n").expect("cue sheet should parse");
+        assert_eq!(plain.hidden_pregap_ms(), None);
+        assert_eq!(parse_cue_sheet("REM no tracks"), None);
+    }
+}
@@ -0,0 +1,459 @@
+//! Lyrics parsing: LRC text, ID3v2 `SYLT` frames, and plain lyrics.
+//!
+//! Local lyrics are resolved in order of precision: a sidecar `.lrc` file with the
+//! track's stem, a synced `SYLT` frame, then the `USLT`/`LYRICS` tag. Lyrics tags often
+//! hold LRC text themselves, so every text source is tried as LRC before falling back
+//! to plain lines.
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::metadata_tags;
+use crate::protocol::{LyricLine, LyricsSource, TrackLyrics};
+use crate::sandbox_access;
+
+const ID3V2_HEADER_LEN: usize = 10;
+const SYLT_FRAME_ID: &[u8; 4] = b"SYLT";
+/// `SYLT` timestamp format code for absolute milliseconds.
+const SYLT_TIMESTAMPS_MS: u8 = 2;
+
+/// Parses an LRC `mm:ss.xx` timestamp into milliseconds.
+fn parse_lrc_timestamp_ms(raw: &str) -> Option<u64> {
+    let (minutes, seconds) = raw.trim().split_once(':')?;
+    let minutes = minutes.parse::<u64>().ok()?;
+    let (whole_seconds, fraction) = seconds.split_once(['.', ':']).unwrap_or((seconds, ""));
+    let whole_seconds = whole_seconds.parse::<u64>().ok()?;
+    if whole_seconds >= 60 || !fraction.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let fraction_ms = fraction
+        .chars()
+        .chain("000".chars())
+        .take(3)
+        .collect::<String>()
+        .parse::<u64>()
+        .ok()?;
+    Some((minutes * 60 + whole_seconds) * 1000 + fraction_ms)
+}
+
+/// Drops enhanced-LRC word timings such as `<00:12.34>` from a line.
+fn strip_word_timings(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        stripped.push_str(&rest[..open]);
+        let tag = &rest[open + 1..open + close];
+        if parse_lrc_timestamp_ms(tag).is_none() {
+            stripped.push_str(&rest[open..=open + close]);
+        }
+        rest = &rest[open + close + 1..];
+    }
+    stripped.push_str(rest);
+    stripped.trim().to_string()
+}
+
+/// Parses synced LRC text into lines sorted by start time.
+///
+/// A line may carry several timestamps (`[00:12.00][01:40.00]Chorus`). The `[offset:]`
+/// tag shifts every line earlier by its value in milliseconds. Returns `None` when no
+/// line is timestamped.
+pub fn parse_lrc(text: &str) -> Option<Vec<LyricLine>> {
+    let mut offset_ms = 0i64;
+    let mut timed: Vec<(i64, String)> = Vec::new();
+    for raw_line in text.lines() {
+        let mut rest = raw_line.trim();
+        let mut starts_ms = Vec::new();
+        while let Some(tag_body) = rest.strip_prefix('[') {
+            let Some(close) = tag_body.find(']') else {
+                break;
+            };
+            let tag = &tag_body[..close];
+            if let Some(start_ms) = parse_lrc_timestamp_ms(tag) {
+                starts_ms.push(start_ms as i64);
+            } else if let Some(value) = tag
+                .split_once(':')
+                .filter(|(key, _)| key.trim().eq_ignore_ascii_case("offset"))
+                .map(|(_, value)| value.trim())
+            {
+                offset_ms = value.parse().unwrap_or(offset_ms);
+            }
+            rest = &tag_body[close + 1..];
+        }
+        let line_text = strip_word_timings(rest);
+        timed.extend(
+            starts_ms
+                .into_iter()
+                .map(|start| (start, line_text.clone())),
+        );
+    }
+    if timed.is_empty() {
+        return None;
+    }
+    timed.sort_by_key(|(start_ms, _)| *start_ms);
+    Some(
+        timed
+            .into_iter()
+            .map(|(start_ms, text)| LyricLine {
+                start_ms: start_ms.saturating_sub(offset_ms).max(0) as u64,
+                text,
+            })
+            .collect(),
+    )
+}
+
+/// Splits plain lyrics into lines, keeping blank lines between verses.
+fn plain_lines(text: &str) -> Vec<LyricLine> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return Vec::new();
+    };
+    lines[first..=last]
+        .iter()
+        .map(|line| LyricLine {
+            start_ms: 0,
+            text: (*line).to_string(),
+        })
+        .collect()
+}
+
+/// Builds lyrics from LRC or plain text; `None` when the text has no lyrics.
+pub fn lyrics_from_text(text: &str, source: LyricsSource) -> Option<TrackLyrics> {
+    if let Some(lines) = parse_lrc(text) {
+        return Some(TrackLyrics {
+            source,
+            synced: true,
+            lines,
+        });
+    }
+    let lines = plain_lines(text);
+    (!lines.is_empty()).then_some(TrackLyrics {
+        source,
+        synced: false,
+        lines,
+    })
+}
+
+/// Index of the line being sung at `elapsed_ms`, or `None` before the first line.
+pub fn active_line_index(lines: &[LyricLine], elapsed_ms: u64) -> Option<usize> {
+    lines
+        .partition_point(|line| line.start_ms <= elapsed_ms)
+        .checked_sub(1)
+}
+
+fn decode_id3_text(bytes: &[u8], encoding: u8) -> String {
+    match encoding {
+        0 => bytes.iter().map(|&byte| char::from(byte)).collect(),
+        1 | 2 => {
+            let (big_endian, body) = match bytes {
+                [0xFE, 0xFF, body @ ..] => (true, body),
+                [0xFF, 0xFE, body @ ..] => (false, body),
+                _ => (encoding == 2, bytes),
+            };
+            let units: Vec<u16> = body
+                .chunks_exact(2)
+                .map(|pair| {
+                    if big_endian {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Splits an ID3 string terminated by one zero byte, or two for UTF-16 encodings.
+fn split_id3_terminated(bytes: &[u8], encoding: u8) -> (&[u8], &[u8]) {
+    let end = if matches!(encoding, 1 | 2) {
+        (0..bytes.len().saturating_sub(1))
+            .step_by(2)
+            .find(|&index| bytes[index] == 0 && bytes[index + 1] == 0)
+            .map(|index| (index, index + 2))
+    } else {
+        bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .map(|index| (index, index + 1))
+    };
+    match end {
+        Some((text_end, rest_start)) => (&bytes[..text_end], &bytes[rest_start..]),
+        None => (bytes, &[]),
+    }
+}
+
+/// Parses the body of an ID3v2 `SYLT` frame with millisecond timestamps.
+///
+/// Entries that start with a line break begin a new line; when any entry does, the
+/// others are syllables appended to the line before them.
+pub fn parse_sylt_frame(data: &[u8]) -> Option<Vec<LyricLine>> {
+    let [encoding, _, _, _, timestamp_format, _content_type, rest @ ..] = data else {
+        return None;
+    };
+    if *timestamp_format != SYLT_TIMESTAMPS_MS {
+        return None;
+    }
+    let (_descriptor, mut rest) = split_id3_terminated(rest, *encoding);
+    let mut entries: Vec<(u64, String)> = Vec::new();
+    while !rest.is_empty() {
+        let (text, after_text) = split_id3_terminated(rest, *encoding);
+        let Some((timestamp, after_timestamp)) = after_text.split_first_chunk::<4>() else {
+            break;
+        };
+        entries.push((
+            u64::from(u32::from_be_bytes(*timestamp)),
+            decode_id3_text(text, *encoding),
+        ));
+        rest = after_timestamp;
+    }
+    let syllables = entries
+        .iter()
+        .any(|(_, text)| text.starts_with(['\n', '\r']));
+    let mut lines: Vec<LyricLine> = Vec::new();
+    for (start_ms, text) in entries {
+        let starts_line = !syllables || text.starts_with(['\n', '\r']);
+        match lines.last_mut() {
+            Some(line) if !starts_line => line.text.push_str(&text),
+            _ => lines.push(LyricLine { start_ms, text }),
+        }
+    }
+    for line in &mut lines {
+        line.text = line.text.trim().to_string();
+    }
+    lines.retain(|line| !line.text.is_empty());
+    lines.sort_by_key(|line| line.start_ms);
+    (!lines.is_empty()).then_some(lines)
+}
+
+fn synchsafe_u32(bytes: [u8; 4]) -> usize {
+    bytes.iter().fold(0usize, |value, byte| {
+        (value << 7) | usize::from(byte & 0x7F)
+    })
+}
+
+/// Reverses ID3v2 unsynchronisation, which inserts a zero byte after every `0xFF`.
+fn remove_unsynchronisation(bytes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut previous_ff = false;
+    for &byte in bytes {
+        if !(previous_ff && byte == 0) {
+            output.push(byte);
+        }
+        previous_ff = byte == 0xFF;
+    }
+    output
+}
+
+/// Returns the body of the first `frame_id` frame of an ID3v2.3/2.4 tag.
+///
+/// `header` is the 10-byte tag header and `tag` the bytes that follow it.
+fn find_id3v2_frame(header: &[u8], tag: &[u8], frame_id: &[u8; 4]) -> Option<Vec<u8>> {
+    if header.len() < ID3V2_HEADER_LEN || &header[..3] != b"ID3" {
+        return None;
+    }
+    let (major, flags) = (header[3], header[5]);
+    if !matches!(major, 3 | 4) {
+        return None;
+    }
+    let tag_unsynchronised = flags & 0x80 != 0;
+    let tag = if tag_unsynchronised && major == 3 {
+        remove_unsynchronisation(tag)
+    } else {
+        tag.to_vec()
+    };
+    let mut position = 0;
+    if flags & 0x40 != 0 {
+        let size_bytes: [u8; 4] = tag.get(..4)?.try_into().ok()?;
+        position = if major == 4 {
+            synchsafe_u32(size_bytes)
+        } else {
+            4 + u32::from_be_bytes(size_bytes) as usize
+        };
+    }
+    while position + ID3V2_HEADER_LEN <= tag.len() {
+        let frame_header = &tag[position..position + ID3V2_HEADER_LEN];
+        if frame_header[0] == 0 {
+            break;
+        }
+        let size_bytes: [u8; 4] = frame_header[4..8].try_into().ok()?;
+        let size = if major == 4 {
+            synchsafe_u32(size_bytes)
+        } else {
+            u32::from_be_bytes(size_bytes) as usize
+        };
+        let body_start = position + ID3V2_HEADER_LEN;
+        let body = tag.get(body_start..body_start + size)?;
+        if &frame_header[..4] == frame_id {
+            let format_flags = if major == 4 { frame_header[9] } else { 0 };
+            // Compressed and encrypted frames are not worth supporting for lyrics.
+            if format_flags & 0x0C != 0 || (major == 3 && frame_header[9] & 0xC0 != 0) {
+                return None;
+            }
+            let body = if format_flags & 0x01 != 0 {
+                body.get(4..)?
+            } else {
+                body
+            };
+            return Some(
+                if major == 4 && (tag_unsynchronised || format_flags & 0x02 != 0) {
+                    remove_unsynchronisation(body)
+                } else {
+                    body.to_vec()
+                },
+            );
+        }
+        position = body_start + size;
+    }
+    None
+}
+
+/// Reads synced lyrics from the `SYLT` frame of a leading ID3v2 tag.
+fn read_embedded_synced_lyrics(path: &Path) -> Option<Vec<LyricLine>> {
+    let mut file = sandbox_access::open_file(path).ok()?;
+    let mut header = [0u8; ID3V2_HEADER_LEN];
+    file.read_exact(&mut header).ok()?;
+    if &header[..3] != b"ID3" {
+        return None;
+    }
+    let mut tag = vec![0u8; synchsafe_u32([header[6], header[7], header[8], header[9]])];
+    file.read_exact(&mut tag).ok()?;
+    parse_sylt_frame(&find_id3v2_frame(&header, &tag, SYLT_FRAME_ID)?)
+}
+
+/// Resolves lyrics stored with a local track: sidecar `.lrc`, `SYLT`, then the lyrics tag.
+pub fn read_local_lyrics(path: &Path) -> Option<TrackLyrics> {
+    if let Some(lyrics) = sandbox_access::read_file(&path.with_extension("lrc"))
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|text| lyrics_from_text(&text, LyricsSource::SidecarFile))
+    {
+        return Some(lyrics);
+    }
+    if let Some(lines) = read_embedded_synced_lyrics(path) {
+        return Some(TrackLyrics {
+            source: LyricsSource::EmbeddedTag,
+            synced: true,
+            lines,
+        });
+    }
+    metadata_tags::read_embedded_lyrics(path)
+        .and_then(|text| lyrics_from_text(&text, LyricsSource::EmbeddedTag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(start_ms: u64, text: &str) -> LyricLine {
+        LyricLine {
+            start_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_lrc_handles_repeated_timestamps_offset_and_word_timings() {
+        let text = "[ti:Song]\n[offset:500]\n[00:12.34]First <00:12.80>line\n\
+                    [00:20.00][01:05.5]Chorus\n[00:15]Second\nnot timed";
+        assert_eq!(
+            parse_lrc(text),
+            Some(vec![
+                line(11_840, "First line"),
+                line(14_500, "Second"),
+                line(19_500, "Chorus"),
+                line(65_000, "Chorus"),
+            ])
+        );
+        assert_eq!(parse_lrc("Just words\n[ar:Someone]"), None);
+    }
+
+    #[test]
+    fn test_lyrics_from_text_falls_back_to_plain_lines() {
+        let lyrics = lyrics_from_text("\n\nVerse one\n\nVerse two\n\n", LyricsSource::EmbeddedTag)
+            .expect("plain lyrics should parse");
+        assert!(!lyrics.synced);
+        assert_eq!(
+            lyrics.lines,
+            vec![line(0, "Verse one"), line(0, ""), line(0, "Verse two")]
+        );
+        assert_eq!(lyrics_from_text(" \n ", LyricsSource::Lrclib), None);
+    }
+
+    #[test]
+    fn test_active_line_index_follows_elapsed_time() {
+        let lines = vec![line(1_000, "a"), line(5_000, "b"), line(9_000, "c")];
+        assert_eq!(active_line_index(&lines, 500), None);
+        assert_eq!(active_line_index(&lines, 1_000), Some(0));
+        assert_eq!(active_line_index(&lines, 8_999), Some(1));
+        assert_eq!(active_line_index(&lines, 60_000), Some(2));
+    }
+
+    fn sylt_body(entries: &[(&str, u32)]) -> Vec<u8> {
+        let mut body = vec![3, b'e', b'n', b'g', SYLT_TIMESTAMPS_MS, 1, 0];
+        for (text, start_ms) in entries {
+            body.extend_from_slice(text.as_bytes());
+            body.push(0);
+            body.extend_from_slice(&start_ms.to_be_bytes());
+        }
+        body
+    }
+
+    #[test]
+    fn test_parse_sylt_frame_joins_syllables_into_lines() {
+        let body = sylt_body(&[
+            ("Hel", 1_000),
+            ("lo", 1_400),
+            ("\nworld", 3_000),
+            ("\nagain", 5_000),
+        ]);
+        assert_eq!(
+            parse_sylt_frame(&body),
+            Some(vec![
+                line(1_000, "Hello"),
+                line(3_000, "world"),
+                line(5_000, "again")
+            ])
+        );
+        let mut frames_timed = body.clone();
+        frames_timed[4] = 1;
+        assert_eq!(parse_sylt_frame(&frames_timed), None);
+    }
+
+    #[test]
+    fn test_find_id3v2_frame_reads_v24_frames_after_others() {
+        let sylt = sylt_body(&[("One", 0), ("Two", 2_000)]);
+        let mut tag = Vec::new();
+        tag.extend_from_slice(b"TIT2");
+        tag.extend_from_slice(&[0, 0, 0, 3, 0, 0, 3, b'H', b'i']);
+        tag.extend_from_slice(SYLT_FRAME_ID);
+        let size = sylt.len() as u8;
+        tag.extend_from_slice(&[0, 0, 0, size, 0, 0]);
+        tag.extend_from_slice(&sylt);
+        tag.extend_from_slice(&[0; 16]);
+        let header = [b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, tag.len() as u8];
+        assert_eq!(
+            find_id3v2_frame(&header, &tag, SYLT_FRAME_ID),
+            Some(sylt.clone())
+        );
+        assert_eq!(find_id3v2_frame(&header, &tag, b"USLT"), None);
+        assert_eq!(
+            parse_sylt_frame(&sylt),
+            Some(vec![line(0, "One"), line(2_000, "Two")])
+        );
+    }
+
+    #[test]
+    fn test_remove_unsynchronisation_drops_stuffed_zero_bytes() {
+        assert_eq!(
+            remove_unsynchronisation(&[0xFF, 0x00, 0xE0, 0x00, 0xFF, 0x00, 0x00]),
+            vec![0xFF, 0xE0, 0x00, 0xFF, 0x00]
+        );
+    }
+}
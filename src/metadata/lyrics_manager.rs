@@ -0,0 +1,279 @@
+//! Lyrics lookup runtime component.
+//!
+//! This manager answers lyrics requests for the playing track from local sources first.
+//! When online metadata is enabled and no synced lyrics exist locally, it also asks
+//! LRCLIB; those answers, misses included, are cached in the database.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{debug, warn};
+use serde_json::Value;
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::db_manager::DbManager;
+use crate::integration_uri::is_remote_track_path;
+use crate::lyrics;
+use crate::protocol::{
+    ConfigDeltaEntry, ConfigMessage, LyricsMessage, LyricsSource, Message, TrackLyrics,
+};
+
+const LRCLIB_API_URL: &str = "https://lrclib.net/api";
+const LRCLIB_USER_AGENT: &str =
+    "roqtune/0.1.0 (https://github.com/roqtune/roqtune; contact: lyrics lookup)";
+/// How long a track without online lyrics is left alone before LRCLIB is asked again.
+const LYRICS_MISS_RETRY_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Resolves lyrics for the playing track from sidecar files, tags, and LRCLIB.
+pub struct LyricsManager {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    db_manager: DbManager,
+    online_lyrics_enabled: bool,
+    http_client: ureq::Agent,
+}
+
+/// Lyrics text of one LRCLIB record, preferring synced lyrics. Instrumental tracks
+/// have none.
+fn lrclib_record_text(record: &Value) -> Option<String> {
+    if record
+        .get("instrumental")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    ["syncedLyrics", "plainLyrics"].into_iter().find_map(|key| {
+        record
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(ToOwned::to_owned)
+    })
+}
+
+/// Lyrics text of an LRCLIB `/get` record or the first usable `/search` result.
+fn lrclib_response_text(response: &Value) -> Option<String> {
+    match response.as_array() {
+        Some(records) => records.iter().find_map(lrclib_record_text),
+        None => lrclib_record_text(response),
+    }
+}
+
+/// LRCLIB lookup URL. With a known duration the exact `/get` endpoint is used, which
+/// matches within a couple of seconds; otherwise a `/search` by title and artist.
+fn lrclib_url(artist: &str, title: &str, album: &str, duration_ms: Option<u64>) -> String {
+    let artist = urlencoding::encode(artist.trim());
+    let title = urlencoding::encode(title.trim());
+    match duration_ms {
+        Some(duration_ms) => format!(
+            "{LRCLIB_API_URL}/get?artist_name={artist}&track_name={title}&album_name={}&duration={}",
+            urlencoding::encode(album.trim()),
+            (duration_ms + 500) / 1000
+        ),
+        None => format!("{LRCLIB_API_URL}/search?track_name={title}&artist_name={artist}"),
+    }
+}
+
+impl LyricsManager {
+    /// Creates a lyrics manager bound to bus channels and storage backend.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        db_manager: DbManager,
+        initial_library_config: crate::config::LibraryConfig,
+    ) -> Self {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(5))
+            .timeout_read(Duration::from_secs(10))
+            .build();
+        Self {
+            bus_consumer,
+            bus_producer,
+            db_manager,
+            online_lyrics_enabled: initial_library_config.online_metadata_enabled,
+            http_client,
+        }
+    }
+
+    fn now_unix_ms() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0)
+    }
+
+    /// Asks LRCLIB for lyrics text. `Ok(None)` means LRCLIB has none for the track.
+    fn fetch_lrclib_text(
+        &self,
+        artist: &str,
+        title: &str,
+        album: &str,
+        duration_ms: Option<u64>,
+    ) -> Result<Option<String>, String> {
+        let url = lrclib_url(artist, title, album, duration_ms);
+        let response = match self
+            .http_client
+            .get(&url)
+            .set("User-Agent", LRCLIB_USER_AGENT)
+            .set("Accept", "application/json")
+            .call()
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(error) => return Err(format!("Request failed: {error}")),
+        };
+        let body: Value = response
+            .into_json()
+            .map_err(|error| format!("Invalid JSON response: {error}"))?;
+        Ok(lrclib_response_text(&body))
+    }
+
+    /// Online lyrics of `path`, from the cache when fresh enough and LRCLIB otherwise.
+    /// Failed requests are not cached, so the next play tries again.
+    fn online_lyrics(
+        &self,
+        path: &Path,
+        artist: &str,
+        title: &str,
+        album: &str,
+        duration_ms: Option<u64>,
+    ) -> Option<TrackLyrics> {
+        let now_unix_ms = Self::now_unix_ms();
+        let cached = self
+            .db_manager
+            .get_cached_lyrics(path)
+            .unwrap_or_else(|error| {
+                warn!("LyricsManager: failed to read lyrics cache: {error}");
+                None
+            });
+        let text = match cached {
+            Some((Some(text), _)) => Some(text),
+            Some((None, fetched_unix_ms))
+                if now_unix_ms.saturating_sub(fetched_unix_ms) < LYRICS_MISS_RETRY_MS =>
+            {
+                None
+            }
+            _ => match self.fetch_lrclib_text(artist, title, album, duration_ms) {
+                Ok(text) => {
+                    if let Err(error) =
+                        self.db_manager
+                            .save_cached_lyrics(path, text.as_deref(), now_unix_ms)
+                    {
+                        warn!("LyricsManager: failed to cache lyrics: {error}");
+                    }
+                    text
+                }
+                Err(error) => {
+                    warn!(
+                        "LyricsManager: LRCLIB lookup failed for {}: {error}",
+                        path.display()
+                    );
+                    None
+                }
+            },
+        };
+        text.and_then(|text| lyrics::lyrics_from_text(&text, LyricsSource::Lrclib))
+    }
+
+    fn resolve_lyrics(
+        &self,
+        path: &Path,
+        artist: &str,
+        title: &str,
+        album: &str,
+        duration_ms: Option<u64>,
+    ) -> Option<TrackLyrics> {
+        let local = if is_remote_track_path(path) {
+            None
+        } else {
+            lyrics::read_local_lyrics(path)
+        };
+        if local.as_ref().is_some_and(|lyrics| lyrics.synced)
+            || !self.online_lyrics_enabled
+            || title.trim().is_empty()
+            || artist.trim().is_empty()
+        {
+            return local;
+        }
+        match self.online_lyrics(path, artist, title, album, duration_ms) {
+            Some(online) if online.synced || local.is_none() => Some(online),
+            _ => local,
+        }
+    }
+
+    /// Starts the blocking event loop for lyrics requests.
+    pub fn run(&mut self) {
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(Message::Lyrics(LyricsMessage::RequestLyrics {
+                    path,
+                    artist,
+                    title,
+                    album,
+                    duration_ms,
+                })) => {
+                    debug!("LyricsManager: resolving lyrics for {}", path.display());
+                    let lyrics = self.resolve_lyrics(&path, &artist, &title, &album, duration_ms);
+                    let _ = self
+                        .bus_producer
+                        .send(Message::Lyrics(LyricsMessage::LyricsLoaded {
+                            path,
+                            lyrics,
+                        }));
+                }
+                Ok(Message::Config(ConfigMessage::ConfigChanged(changes))) => {
+                    for change in changes {
+                        if let ConfigDeltaEntry::Library(library) = change {
+                            if let Some(enabled) = library.online_metadata_enabled {
+                                self.online_lyrics_enabled = enabled;
+                            }
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("LyricsManager lagged on control bus, skipped {skipped} message(s)");
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lrclib_response_text_prefers_synced_lyrics() {
+        let record = serde_json::json!({
+            "instrumental": false,
+            "plainLyrics": "Hello",
+            "syncedLyrics": "[00:01.00] Hello",
+        });
+        assert_eq!(
+            lrclib_response_text(&record).as_deref(),
+            Some("[00:01.00] Hello")
+        );
+        let search = serde_json::json!([
+            { "instrumental": true, "plainLyrics": null, "syncedLyrics": null },
+            { "instrumental": false, "plainLyrics": "Hello", "syncedLyrics": "  " },
+        ]);
+        assert_eq!(lrclib_response_text(&search).as_deref(), Some("Hello"));
+        assert_eq!(lrclib_response_text(&serde_json::json!([])), None);
+    }
+
+    #[test]
+    fn test_lrclib_url_uses_exact_lookup_when_duration_is_known() {
+        assert_eq!(
+            lrclib_url("A & B", "Song", "Album", Some(215_600)),
+            "https://lrclib.net/api/get?artist_name=A%20%26%20B&track_name=Song&album_name=Album&duration=216"
+        );
+        assert_eq!(
+            lrclib_url("Artist", "Song", "", None),
+            "https://lrclib.net/api/search?track_name=Song&artist_name=Artist"
+        );
+    }
+}
@@ -2,9 +2,10 @@
 //!
 //! This manager serves track Properties payloads and persists edited metadata
 //! values back to audio files, then synchronizes library index rows when present.
+//! It also re-reads tags of files that other programs edited.
 
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use log::{debug, warn};
 use tokio::sync::broadcast::{Receiver, Sender};
@@ -12,9 +13,9 @@ use tokio::sync::broadcast::{Receiver, Sender};
 use lofty::config::WriteOptions;
 use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::prelude::Accessor;
-use lofty::read_from_path;
 use lofty::tag::{ItemKey, Tag};
 
+use crate::batch_edit;
 use crate::db_manager::DbManager;
 use crate::metadata_tags;
 use crate::protocol::{Message, MetadataEditorField, MetadataMessage, TrackMetadataSummary};
@@ -39,7 +40,7 @@ const COMMON_FIELD_SPECS: [(&str, &str); 17] = [
     ("common:copyright", "Copyright"),
 ];
 
-/// Coordinates metadata properties loading/saving for one-file workflows.
+/// Coordinates metadata properties loading/saving for single- and multi-track edits.
 pub struct MetadataManager {
     bus_consumer: Receiver<Message>,
     bus_producer: Sender<Message>,
@@ -214,6 +215,10 @@ impl MetadataManager {
             "common:year" => metadata.year.clone(),
             "common:date" => metadata.date.clone(),
             "common:genre" => metadata.genre.clone(),
+            "common:composer" => metadata.composer.clone(),
+            "common:disc_number" => metadata.disc_number.clone(),
+            "common:track_total" => metadata.total_tracks.clone(),
+            "common:comment" => metadata.comment.clone(),
             _ => String::new(),
         }
     }
@@ -231,7 +236,7 @@ impl MetadataManager {
     }
 
     fn read_properties_payload(path: &Path) -> Result<(String, Vec<MetadataEditorField>), String> {
-        let tagged_file = match read_from_path(path) {
+        let tagged_file = match metadata_tags::read_tagged_file(path) {
             Ok(tagged_file) => Some(tagged_file),
             Err(error) => {
                 warn!(
@@ -388,6 +393,10 @@ impl MetadataManager {
             genre: Self::get_common_value(tag, "common:genre"),
             year,
             track_number: Self::get_common_value(tag, "common:track_number"),
+            composer: Self::get_common_value(tag, "common:composer"),
+            disc_number: Self::get_common_value(tag, "common:disc_number"),
+            total_tracks: Self::get_common_value(tag, "common:track_total"),
+            comment: Self::get_common_value(tag, "common:comment"),
         }
     }
 
@@ -396,8 +405,8 @@ impl MetadataManager {
         path: &Path,
         fields: &[MetadataEditorField],
     ) -> Result<(TrackMetadataSummary, Option<String>), String> {
-        let mut tagged_file =
-            read_from_path(path).map_err(|error| format!("Failed to read tags: {error}"))?;
+        let mut tagged_file = metadata_tags::read_tagged_file(path)
+            .map_err(|error| format!("Failed to read tags: {error}"))?;
         let tag_type = tagged_file.primary_tag_type();
         if tagged_file.tag(tag_type).is_none() {
             tagged_file.insert_tag(Tag::new(tag_type));
@@ -441,8 +450,8 @@ impl MetadataManager {
             .save_to_path(path, WriteOptions::default())
             .map_err(|error| format!("Failed to write tags: {error}"))?;
 
-        let refreshed =
-            read_from_path(path).map_err(|error| format!("Failed to refresh tags: {error}"))?;
+        let refreshed = metadata_tags::read_tagged_file(path)
+            .map_err(|error| format!("Failed to refresh tags: {error}"))?;
         let refreshed_tag = refreshed.primary_tag().or_else(|| refreshed.first_tag());
         let summary = Self::build_summary(path, refreshed_tag);
 
@@ -467,6 +476,86 @@ impl MetadataManager {
         Ok((summary, db_sync_warning))
     }
 
+    fn load_batch_properties(
+        paths: &[PathBuf],
+    ) -> Result<(Vec<MetadataEditorField>, Vec<bool>), String> {
+        let mut per_track_fields = Vec::with_capacity(paths.len());
+        for path in paths {
+            let (_, fields) = Self::read_properties_payload(path)
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            per_track_fields.push(fields);
+        }
+        Ok(batch_edit::aggregate_common_fields(&per_track_fields))
+    }
+
+    /// Writes `edits` to each track, keeping each track's other fields. Tracks that fail
+    /// are reported in the returned error text; the others are still saved.
+    fn save_batch_properties(
+        &self,
+        paths: &[PathBuf],
+        edits: &[MetadataEditorField],
+    ) -> (
+        Vec<(PathBuf, TrackMetadataSummary)>,
+        Option<String>,
+        Option<String>,
+    ) {
+        let mut saved = Vec::with_capacity(paths.len());
+        let mut db_sync_warning = None;
+        let mut failures = Vec::new();
+        for path in paths {
+            let result = Self::read_properties_payload(path).and_then(|(_, fields)| {
+                let merged = batch_edit::merge_field_edits(fields, edits);
+                self.save_track_properties(path, &merged)
+            });
+            match result {
+                Ok((summary, warning)) => {
+                    db_sync_warning = db_sync_warning.or(warning);
+                    saved.push((path.clone(), summary));
+                }
+                Err(error) => failures.push(format!("{}: {}", path.display(), error)),
+            }
+        }
+        let error = failures
+            .first()
+            .map(|first| format!("Saved {} of {} tracks. {}", saved.len(), paths.len(), first));
+        (saved, db_sync_warning, error)
+    }
+
+    /// Re-reads tags of files edited by other programs and syncs their library index rows.
+    /// Files that can no longer be read are skipped.
+    fn refresh_changed_tags(&self, paths: &[PathBuf]) -> Vec<(PathBuf, TrackMetadataSummary)> {
+        let mut refreshed = Vec::with_capacity(paths.len());
+        for path in paths {
+            let tagged_file = match metadata_tags::read_tagged_file(path) {
+                Ok(tagged_file) => tagged_file,
+                Err(error) => {
+                    warn!(
+                        "MetadataManager: failed to re-read tags of {}: {}",
+                        path.display(),
+                        error
+                    );
+                    continue;
+                }
+            };
+            let tag = tagged_file
+                .primary_tag()
+                .or_else(|| tagged_file.first_tag());
+            let summary = Self::build_summary(path, tag);
+            if let Err(error) = self
+                .db_manager
+                .update_library_track_metadata_by_path(path.to_string_lossy().as_ref(), &summary)
+            {
+                warn!(
+                    "MetadataManager: failed to sync re-read tags of {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+            refreshed.push((path.clone(), summary));
+        }
+        refreshed
+    }
+
     /// Starts the blocking event loop for metadata properties operations.
     pub fn run(&mut self) {
         loop {
@@ -534,6 +623,61 @@ impl MetadataManager {
                         }
                     }
                 }
+                Ok(Message::Metadata(MetadataMessage::RequestTrackPropertiesBatch {
+                    request_id,
+                    paths,
+                })) => {
+                    debug!(
+                        "MetadataManager: loading properties request_id={} tracks={}",
+                        request_id,
+                        paths.len()
+                    );
+                    let message = match Self::load_batch_properties(&paths) {
+                        Ok((fields, mixed)) => MetadataMessage::TrackPropertiesBatchLoaded {
+                            request_id,
+                            fields,
+                            mixed,
+                        },
+                        Err(error) => {
+                            MetadataMessage::TrackPropertiesBatchLoadFailed { request_id, error }
+                        }
+                    };
+                    let _ = self.bus_producer.send(Message::Metadata(message));
+                }
+                Ok(Message::Metadata(MetadataMessage::SaveTrackPropertiesBatch {
+                    request_id,
+                    paths,
+                    edits,
+                })) => {
+                    debug!(
+                        "MetadataManager: saving {} field(s) to {} track(s) request_id={}",
+                        edits.len(),
+                        paths.len(),
+                        request_id
+                    );
+                    let (saved, db_sync_warning, error) =
+                        self.save_batch_properties(&paths, &edits);
+                    let _ = self.bus_producer.send(Message::Metadata(
+                        MetadataMessage::TrackPropertiesBatchSaved {
+                            request_id,
+                            saved,
+                            db_sync_warning,
+                            error,
+                        },
+                    ));
+                }
+                Ok(Message::Metadata(MetadataMessage::RefreshChangedTags(paths))) => {
+                    debug!(
+                        "MetadataManager: re-reading tags of {} externally changed file(s)",
+                        paths.len()
+                    );
+                    let refreshed = self.refresh_changed_tags(&paths);
+                    if !refreshed.is_empty() {
+                        let _ = self.bus_producer.send(Message::Metadata(
+                            MetadataMessage::ChangedTagsRefreshed(refreshed),
+                        ));
+                    }
+                }
                 Ok(_) => {}
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                     log::warn!(
@@ -5,8 +5,9 @@ use std::io::BufReader;
 use std::path::Path;
 
 use lofty::config::{ParseOptions, ParsingMode};
-use lofty::file::TaggedFile;
+use lofty::error::LoftyError;
 use lofty::file::TaggedFileExt;
+use lofty::file::{FileType, TaggedFile};
 use lofty::prelude::Accessor;
 use lofty::probe::Probe;
 use lofty::tag::{ItemKey, Tag};
@@ -18,6 +19,9 @@ use symphonia::core::meta::{
 };
 use symphonia::core::probe::Hint;
 
+use crate::cue_sheet::{self, CueSheet};
+use crate::sandbox_access;
+
 /// Normalized common metadata values extracted from file tags.
 #[derive(Debug, Clone, Default)]
 pub struct CommonTrackMetadata {
@@ -29,6 +33,10 @@ pub struct CommonTrackMetadata {
     pub year: String,
     pub genre: String,
     pub track_number: String,
+    pub composer: String,
+    pub disc_number: String,
+    pub total_tracks: String,
+    pub comment: String,
 }
 
 fn first_non_empty_value<F>(primary_tag: Option<&Tag>, tags: &[Tag], mut extractor: F) -> String
@@ -83,11 +91,25 @@ fn metadata_parse_options(
         .max_junk_bytes(max_junk_bytes)
 }
 
+/// Opens `path` through the sandbox layer as a probe typed by its extension.
+pub(crate) fn open_probe(path: &Path) -> std::io::Result<Probe<BufReader<File>>> {
+    let reader = BufReader::new(sandbox_access::open_file(path)?);
+    Ok(match FileType::from_path(path) {
+        Some(file_type) => Probe::with_file_type(reader, file_type),
+        None => Probe::new(reader),
+    })
+}
+
+/// Reads all tags and properties of `path`, like `lofty::read_from_path`.
+pub(crate) fn read_tagged_file(path: &Path) -> Result<TaggedFile, LoftyError> {
+    open_probe(path)?.read()
+}
+
 fn read_tagged_file_for_metadata(path: &Path, read_cover_art: bool) -> Option<TaggedFile> {
     let primary_options = metadata_parse_options(read_cover_art, ParsingMode::BestAttempt, 1024);
     let relaxed_options = metadata_parse_options(read_cover_art, ParsingMode::Relaxed, 64 * 1024);
 
-    match Probe::open(path) {
+    match open_probe(path) {
         Ok(probe) => match probe.options(primary_options).read() {
             Ok(tagged_file) => return Some(tagged_file),
             Err(primary_error) => {
@@ -107,7 +129,7 @@ fn read_tagged_file_for_metadata(path: &Path, read_cover_art: bool) -> Option<Ta
         }
     }
 
-    let file = match File::open(path) {
+    let file = match sandbox_access::open_file(path) {
         Ok(file) => file,
         Err(error) => {
             debug!(
@@ -197,8 +219,24 @@ fn read_common_track_metadata_with_lofty(path: &Path) -> Option<CommonTrackMetad
             .map(str::to_string)
             .or_else(|| tag.track().map(|value| value.to_string()))
     });
+    let composer = first_non_empty_value(primary_tag, tags, |tag| {
+        tag.get_string(ItemKey::Composer).map(str::to_string)
+    });
+    let disc_number = first_non_empty_value(primary_tag, tags, |tag| {
+        tag.get_string(ItemKey::DiscNumber)
+            .map(str::to_string)
+            .or_else(|| tag.disk().map(|value| value.to_string()))
+    });
+    let total_tracks = first_non_empty_value(primary_tag, tags, |tag| {
+        tag.get_string(ItemKey::TrackTotal)
+            .map(str::to_string)
+            .or_else(|| tag.track_total().map(|value| value.to_string()))
+    });
+    let comment = first_non_empty_value(primary_tag, tags, |tag| {
+        tag.comment().map(|value| value.into_owned())
+    });
 
-    Some(CommonTrackMetadata {
+    let mut metadata = CommonTrackMetadata {
         title,
         artist,
         album,
@@ -207,7 +245,13 @@ fn read_common_track_metadata_with_lofty(path: &Path) -> Option<CommonTrackMetad
         year,
         genre,
         track_number,
-    })
+        composer,
+        disc_number,
+        total_tracks,
+        comment,
+    };
+    split_position_totals(&mut metadata);
+    Some(metadata)
 }
 
 fn read_embedded_cover_art_with_lofty(path: &Path) -> Option<Vec<u8>> {
@@ -231,7 +275,7 @@ fn read_embedded_cover_art_with_lofty(path: &Path) -> Option<Vec<u8>> {
 }
 
 fn open_symphonia_probe(path: &Path) -> Option<symphonia::core::probe::ProbeResult> {
-    let file = File::open(path).ok()?;
+    let file = sandbox_access::open_file(path).ok()?;
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
     let mut hint = Hint::new();
     if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
@@ -287,6 +331,14 @@ fn apply_symphonia_tag(
         Some(StandardTagKey::TrackNumber) | Some(StandardTagKey::Part) => {
             updated |= set_if_empty(&mut metadata.track_number, &value)
         }
+        Some(StandardTagKey::Composer) => updated |= set_if_empty(&mut metadata.composer, &value),
+        Some(StandardTagKey::DiscNumber) => {
+            updated |= set_if_empty(&mut metadata.disc_number, &value)
+        }
+        Some(StandardTagKey::TrackTotal) => {
+            updated |= set_if_empty(&mut metadata.total_tracks, &value)
+        }
+        Some(StandardTagKey::Comment) => updated |= set_if_empty(&mut metadata.comment, &value),
         _ => {}
     }
 
@@ -306,6 +358,10 @@ fn apply_symphonia_tag(
         "TYER" | "YEAR" => set_if_empty(&mut metadata.year, &value),
         "TCON" | "GENRE" => set_if_empty(&mut metadata.genre, &value),
         "TRCK" | "TRACK" | "TRACKNUMBER" => set_if_empty(&mut metadata.track_number, &value),
+        "TCOM" | "COMPOSER" => set_if_empty(&mut metadata.composer, &value),
+        "TPOS" | "DISC" | "DISCNUMBER" => set_if_empty(&mut metadata.disc_number, &value),
+        "TRACKTOTAL" | "TOTALTRACKS" => set_if_empty(&mut metadata.total_tracks, &value),
+        "COMM" | "COMMENT" | "DESCRIPTION" => set_if_empty(&mut metadata.comment, &value),
         _ => false,
     }
 }
@@ -330,6 +386,24 @@ fn has_any_common_metadata(metadata: &CommonTrackMetadata) -> bool {
         || !metadata.year.is_empty()
         || !metadata.genre.is_empty()
         || !metadata.track_number.is_empty()
+        || !metadata.composer.is_empty()
+        || !metadata.disc_number.is_empty()
+        || !metadata.total_tracks.is_empty()
+        || !metadata.comment.is_empty()
+}
+
+/// Splits `3/12`-style track and disc positions, moving the track count into
+/// `total_tracks` when no separate total tag was found.
+fn split_position_totals(metadata: &mut CommonTrackMetadata) {
+    if let Some((number, total)) = metadata.track_number.split_once('/') {
+        if metadata.total_tracks.is_empty() {
+            metadata.total_tracks = total.trim().to_string();
+        }
+        metadata.track_number = number.trim().to_string();
+    }
+    if let Some((number, _)) = metadata.disc_number.split_once('/') {
+        metadata.disc_number = number.trim().to_string();
+    }
 }
 
 fn read_common_track_metadata_with_symphonia(path: &Path) -> Option<CommonTrackMetadata> {
@@ -352,6 +426,7 @@ fn read_common_track_metadata_with_symphonia(path: &Path) -> Option<CommonTrackM
     if metadata.year.is_empty() {
         metadata.year = derive_year_from_date(&metadata.date);
     }
+    split_position_totals(&mut metadata);
 
     if has_any_common_metadata(&metadata) {
         Some(metadata)
@@ -397,6 +472,30 @@ fn read_embedded_cover_art_with_symphonia(path: &Path) -> Option<Vec<u8>> {
     None
 }
 
+fn cue_sheet_text(revision: &MetadataRevision) -> Option<String> {
+    revision
+        .tags()
+        .iter()
+        .find(|tag| tag.key.trim().eq_ignore_ascii_case("CUESHEET"))
+        .map(|tag| symphonia_value_to_string(&tag.value))
+        .filter(|text| !text.is_empty())
+}
+
+fn read_embedded_cue_sheet_text(path: &Path) -> Option<String> {
+    let mut probed = open_symphonia_probe(path)?;
+
+    if let Some(probe_meta) = probed.metadata.get() {
+        if let Some(text) = probe_meta.current().and_then(cue_sheet_text) {
+            return Some(text);
+        }
+    }
+
+    while !probed.format.metadata().is_latest() {
+        let _ = probed.format.metadata().pop();
+    }
+    probed.format.metadata().current().and_then(cue_sheet_text)
+}
+
 /// Reads normalized common metadata values from a media file.
 pub fn read_common_track_metadata(path: &Path) -> Option<CommonTrackMetadata> {
     if let Some(lofty_metadata) = read_common_track_metadata_with_lofty(path) {
@@ -434,10 +533,48 @@ pub fn read_embedded_cover_art(path: &Path) -> Option<Vec<u8>> {
     symphonia_cover
 }
 
+/// Reads the tempo from the file's BPM tag, if present and positive.
+pub fn read_bpm(path: &Path) -> Option<f32> {
+    let tagged_file = read_tagged_file_for_metadata(path, false)?;
+    let value = first_non_empty_value(tagged_file.primary_tag(), tagged_file.tags(), |tag| {
+        tag.get_string(ItemKey::Bpm)
+            .or_else(|| tag.get_string(ItemKey::IntegerBpm))
+            .map(str::to_string)
+    });
+    value
+        .replace(',', ".")
+        .parse::<f32>()
+        .ok()
+        .filter(|bpm| bpm.is_finite() && *bpm > 0.0)
+}
+
+/// Reads lyrics from the file's `USLT`/`LYRICS` tag, if present.
+pub fn read_embedded_lyrics(path: &Path) -> Option<String> {
+    let tagged_file = read_tagged_file_for_metadata(path, false)?;
+    let value = first_non_empty_value(tagged_file.primary_tag(), tagged_file.tags(), |tag| {
+        tag.get_string(ItemKey::Lyrics).map(str::to_string)
+    });
+    (!value.is_empty()).then_some(value)
+}
+
+/// Reads and parses a cue sheet embedded in the file's `CUESHEET` tag, if present.
+pub fn read_embedded_cue_sheet(path: &Path) -> Option<CueSheet> {
+    let text = read_embedded_cue_sheet_text(path)?;
+    let parsed = cue_sheet::parse_cue_sheet(&text);
+    if parsed.is_none() {
+        debug!(
+            "Embedded cue sheet in {} defines no playable tracks",
+            path.display()
+        );
+    }
+    parsed
+}
+
 #[cfg(test)]
 mod tests {
     use super::derive_year_from_date;
     use super::read_common_track_metadata;
+    use super::{split_position_totals, CommonTrackMetadata};
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -485,6 +622,28 @@ mod tests {
         assert_eq!(derive_year_from_date("released 2003-04-01"), "2003");
     }
 
+    #[test]
+    fn test_split_position_totals_keeps_explicit_track_total() {
+        let mut metadata = CommonTrackMetadata {
+            track_number: "3/12".to_string(),
+            disc_number: "1 / 2".to_string(),
+            ..CommonTrackMetadata::default()
+        };
+        split_position_totals(&mut metadata);
+        assert_eq!(metadata.track_number, "3");
+        assert_eq!(metadata.total_tracks, "12");
+        assert_eq!(metadata.disc_number, "1");
+
+        let mut tagged_total = CommonTrackMetadata {
+            track_number: "4/10".to_string(),
+            total_tracks: "11".to_string(),
+            ..CommonTrackMetadata::default()
+        };
+        split_position_totals(&mut tagged_total);
+        assert_eq!(tagged_total.track_number, "4");
+        assert_eq!(tagged_total.total_tracks, "11");
+    }
+
     #[test]
     fn test_read_common_track_metadata_with_large_junk_gap() {
         let path = unique_temp_mp3_path("large_junk_gap");
@@ -1,4 +1,11 @@
-//! Metadata subsystem modules (tag parsing and metadata orchestration).
+//! Metadata subsystem modules (tag parsing, embedded cue sheets, lyrics, online tag
+//! lookup, detection of tags edited by other programs and metadata orchestration).
 
+pub(crate) mod batch_edit;
+pub(crate) mod cue_sheet;
+pub(crate) mod lyrics;
+pub(crate) mod lyrics_manager;
 pub(crate) mod metadata_manager;
 pub(crate) mod metadata_tags;
+pub(crate) mod tag_change_watcher;
+pub(crate) mod tag_lookup_manager;
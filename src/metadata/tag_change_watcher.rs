@@ -0,0 +1,292 @@
+//! Detection of tags edited by other programs.
+//!
+//! `TagChangeWatcher` runs on its own worker thread and periodically compares the
+//! modification times of library and playlist files against the last pass. A file whose
+//! time changed is reported once it has stayed unchanged for [`SETTLE_DELAY`], so a tag
+//! editor rewriting a batch of files produces one refresh instead of one per write.
+//! Changed files are sent to `MetadataManager` in batches as
+//! `MetadataMessage::RefreshChangedTags`. Files roqtune saved itself are skipped. Slow
+//! storage mode stops the passes, since each one touches every watched file.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use log::{info, warn};
+use tokio::sync::broadcast::error::TryRecvError;
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::db_manager::DbManager;
+use crate::integration_uri::is_remote_track_path;
+use crate::protocol::{ConfigDeltaEntry, ConfigMessage, Message, MetadataMessage};
+use crate::sandbox_access;
+
+/// Time between modification-time passes over all watched files.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a changed file must stay untouched before its tags are re-read.
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+/// Most files re-read per refresh request.
+const REFRESH_BATCH_SIZE: usize = 200;
+const IDLE_LOOP_SLEEP: Duration = Duration::from_millis(500);
+
+/// Modification times seen for watched files, with changes waiting to settle.
+#[derive(Debug, Default)]
+pub struct TagChangeTracker {
+    known: HashMap<PathBuf, i64>,
+    pending: HashMap<PathBuf, (i64, Instant)>,
+}
+
+impl TagChangeTracker {
+    /// Records one pass of `(path, modified_unix_ms)` pairs and returns the files whose
+    /// change has settled. Files seen for the first time only set a baseline; files
+    /// missing from `current` are forgotten.
+    pub fn observe(&mut self, current: HashMap<PathBuf, i64>, now: Instant) -> Vec<PathBuf> {
+        self.pending.retain(|path, _| current.contains_key(path));
+        let mut settled = Vec::new();
+        for (path, modified) in &current {
+            let Some(known) = self.known.get(path) else {
+                continue;
+            };
+            if known == modified {
+                self.pending.remove(path);
+                continue;
+            }
+            match self.pending.get(path) {
+                Some((pending, since))
+                    if pending == modified && now.duration_since(*since) >= SETTLE_DELAY =>
+                {
+                    self.pending.remove(path);
+                    settled.push(path.clone());
+                }
+                Some((pending, _)) if pending == modified => {}
+                _ => {
+                    self.pending.insert(path.clone(), (*modified, now));
+                }
+            }
+        }
+        self.known = current
+            .into_iter()
+            .map(|(path, modified)| {
+                let baseline = match self.pending.get(&path) {
+                    Some(_) => self.known.get(&path).copied().unwrap_or(modified),
+                    None => modified,
+                };
+                (path, baseline)
+            })
+            .collect();
+        settled.sort();
+        settled
+    }
+
+    /// Takes the next pass's modification time of `path` as its baseline, so a change
+    /// made by roqtune itself is not reported.
+    pub fn forget(&mut self, path: &Path) {
+        self.known.remove(path);
+        self.pending.remove(path);
+    }
+}
+
+fn modified_unix_ms(path: &Path) -> Option<i64> {
+    let modified = sandbox_access::modified_time(path).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64)
+}
+
+/// Polls watched files and requests tag refreshes for the ones edited elsewhere.
+pub struct TagChangeWatcher {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    db_manager: DbManager,
+    enabled: bool,
+    slow_storage_mode: bool,
+    battery_saver_active: bool,
+    tracker: TagChangeTracker,
+}
+
+impl TagChangeWatcher {
+    /// Creates a watcher; `enabled` and `slow_storage_mode` mirror
+    /// `library.watch_tag_changes` and `library.slow_storage_mode`.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        db_manager: DbManager,
+        enabled: bool,
+        slow_storage_mode: bool,
+    ) -> Self {
+        Self {
+            bus_consumer,
+            bus_producer,
+            db_manager,
+            enabled,
+            slow_storage_mode,
+            battery_saver_active: false,
+            tracker: TagChangeTracker::default(),
+        }
+    }
+
+    fn watched_paths(&self) -> Result<Vec<PathBuf>, rusqlite::Error> {
+        self.db_manager.with_read_snapshot(|db| {
+            let mut paths: Vec<PathBuf> = db
+                .get_library_tracks()?
+                .into_iter()
+                .map(|track| track.path)
+                .collect();
+            paths.extend(db.get_distinct_playlist_track_paths()?);
+            Ok(paths)
+        })
+    }
+
+    fn poll(&mut self) {
+        let paths = match self.watched_paths() {
+            Ok(paths) => paths,
+            Err(err) => {
+                warn!("TagChangeWatcher: failed to load watched paths: {}", err);
+                return;
+            }
+        };
+        let current: HashMap<PathBuf, i64> = paths
+            .into_iter()
+            .filter(|path| !is_remote_track_path(path))
+            .filter_map(|path| modified_unix_ms(&path).map(|modified| (path, modified)))
+            .collect();
+        let changed = self.tracker.observe(current, Instant::now());
+        if changed.is_empty() {
+            return;
+        }
+        info!(
+            "TagChangeWatcher: {} file(s) changed outside roqtune",
+            changed.len()
+        );
+        for batch in changed.chunks(REFRESH_BATCH_SIZE) {
+            let _ = self
+                .bus_producer
+                .send(Message::Metadata(MetadataMessage::RefreshChangedTags(
+                    batch.to_vec(),
+                )));
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Metadata(MetadataMessage::TrackPropertiesSaved { path, .. }) => {
+                self.tracker.forget(&path);
+            }
+            Message::Metadata(MetadataMessage::TrackPropertiesBatchSaved { saved, .. }) => {
+                for (path, _) in &saved {
+                    self.tracker.forget(path);
+                }
+            }
+            Message::Config(ConfigMessage::ConfigChanged(changes)) => {
+                for change in changes {
+                    if let ConfigDeltaEntry::Library(delta) = change {
+                        if let Some(enabled) = delta.watch_tag_changes {
+                            self.enabled = enabled;
+                        }
+                        if let Some(slow_storage_mode) = delta.slow_storage_mode {
+                            self.slow_storage_mode = slow_storage_mode;
+                        }
+                    }
+                }
+            }
+            Message::Config(ConfigMessage::BatterySaverChanged { active }) => {
+                self.battery_saver_active = active;
+            }
+            _ => {}
+        }
+    }
+
+    fn process_pending_bus_messages(&mut self) -> bool {
+        loop {
+            match self.bus_consumer.try_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!("TagChangeWatcher: bus lagged by {} messages", skipped);
+                }
+                Err(TryRecvError::Closed) => return true,
+            }
+        }
+    }
+
+    /// Starts the blocking polling loop.
+    pub fn run(&mut self) {
+        info!("TagChangeWatcher: started");
+        let mut last_poll: Option<Instant> = None;
+        loop {
+            if self.process_pending_bus_messages() {
+                break;
+            }
+            let due = last_poll.is_none_or(|polled| polled.elapsed() >= POLL_INTERVAL);
+            let watching = self.enabled && !self.slow_storage_mode;
+            if due && watching && !self.battery_saver_active {
+                last_poll = Some(Instant::now());
+                self.poll();
+            } else if due && !watching {
+                // Start from a fresh baseline when re-enabled so edits made while off
+                // are not all reported at once.
+                self.tracker = TagChangeTracker::default();
+            }
+            thread::sleep(IDLE_LOOP_SLEEP);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pass(entries: &[(&str, i64)]) -> HashMap<PathBuf, i64> {
+        entries
+            .iter()
+            .map(|(path, modified)| (PathBuf::from(path), *modified))
+            .collect()
+    }
+
+    #[test]
+    fn test_tracker_reports_changes_once_they_settle() {
+        let mut tracker = TagChangeTracker::default();
+        let start = Instant::now();
+        assert!(tracker
+            .observe(pass(&[("/a.flac", 1), ("/b.flac", 1)]), start)
+            .is_empty());
+        assert!(tracker
+            .observe(pass(&[("/a.flac", 2), ("/b.flac", 1)]), start)
+            .is_empty());
+        // Rewritten again before settling: the wait starts over.
+        let later = start + SETTLE_DELAY;
+        assert!(tracker
+            .observe(pass(&[("/a.flac", 3), ("/b.flac", 1)]), later)
+            .is_empty());
+        assert_eq!(
+            tracker.observe(
+                pass(&[("/a.flac", 3), ("/b.flac", 1)]),
+                later + SETTLE_DELAY
+            ),
+            [PathBuf::from("/a.flac")]
+        );
+        assert!(tracker
+            .observe(
+                pass(&[("/a.flac", 3), ("/b.flac", 1)]),
+                later + SETTLE_DELAY * 2
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tracker_skips_new_and_forgotten_files() {
+        let mut tracker = TagChangeTracker::default();
+        let start = Instant::now();
+        tracker.observe(pass(&[("/a.flac", 1)]), start);
+        tracker.forget(Path::new("/a.flac"));
+        let later = start + SETTLE_DELAY;
+        assert!(tracker
+            .observe(pass(&[("/a.flac", 5), ("/new.flac", 9)]), later)
+            .is_empty());
+        assert!(tracker
+            .observe(
+                pass(&[("/a.flac", 5), ("/new.flac", 9)]),
+                later + SETTLE_DELAY
+            )
+            .is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+//! Online tag lookup runtime component.
+//!
+//! This manager identifies a track for the Properties dialog and proposes tag values.
+//! Files are fingerprinted with Chromaprint's `fpcalc` and matched through AcoustID when
+//! an AcoustID API key is configured; otherwise, or when fingerprinting finds nothing,
+//! MusicBrainz is searched by title and artist. Proposals are only written to files once
+//! the user saves the dialog. Requests honor the AcoustID and MusicBrainz rate limits.
+
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use governor::state::NotKeyed;
+use governor::{Quota, RateLimiter};
+use log::{debug, warn};
+use serde_json::Value;
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::integration_uri::is_remote_track_path;
+use crate::protocol::{
+    ConfigDeltaEntry, ConfigMessage, Message, MetadataMessage, TagLookupProposal,
+};
+
+const ACOUSTID_LOOKUP_URL: &str = "https://api.acoustid.org/v2/lookup";
+const MUSICBRAINZ_API_URL: &str = "https://musicbrainz.org/ws/2";
+const MUSICBRAINZ_USER_AGENT: &str =
+    "roqtune/0.1.0 (https://github.com/roqtune/roqtune; contact: tag lookup)";
+/// Chromaprint command-line fingerprinter, looked up on `PATH`.
+const FPCALC_PROGRAM: &str = "fpcalc";
+/// AcoustID matches below this score are too uncertain to propose.
+const MIN_ACOUSTID_SCORE: f64 = 0.5;
+/// MusicBrainz search results below this score (0-100) are too uncertain to propose.
+const MIN_MUSICBRAINZ_SEARCH_SCORE: u64 = 80;
+
+type DirectRateLimiter =
+    RateLimiter<NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock>;
+
+/// Resolves tag proposals for single tracks from AcoustID and MusicBrainz.
+pub struct TagLookupManager {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    online_lookup_enabled: bool,
+    acoustid_api_key: String,
+    http_client: ureq::Agent,
+    /// AcoustID allows three requests per second.
+    acoustid_limiter: DirectRateLimiter,
+    /// MusicBrainz allows one request per second.
+    musicbrainz_limiter: DirectRateLimiter,
+}
+
+fn rate_limiter(period: Duration) -> DirectRateLimiter {
+    RateLimiter::direct(
+        Quota::with_period(period)
+            .expect("valid limiter period")
+            .allow_burst(NonZeroU32::new(1).expect("non-zero limiter burst")),
+    )
+}
+
+/// Blocks until `limiter` admits another request.
+fn wait_for_slot(limiter: &DirectRateLimiter) {
+    while limiter.check().is_err() {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Duration in whole seconds and fingerprint from `fpcalc -json` output.
+fn parse_fpcalc_output(output: &str) -> Option<(u64, String)> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let duration_secs = parsed.get("duration").and_then(Value::as_f64)?;
+    let fingerprint = parsed
+        .get("fingerprint")
+        .and_then(Value::as_str)
+        .filter(|fingerprint| !fingerprint.is_empty())?;
+    Some((duration_secs.round() as u64, fingerprint.to_string()))
+}
+
+/// MusicBrainz recording ids of an AcoustID lookup response with their match scores,
+/// best first. Results without linked recordings are skipped.
+fn acoustid_recording_matches(response: &Value) -> Vec<(String, f64)> {
+    let mut matches: Vec<(String, f64)> = Vec::new();
+    for result in response
+        .get("results")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let score = result.get("score").and_then(Value::as_f64).unwrap_or(0.0);
+        for recording_id in result
+            .get("recordings")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|recording| recording.get("id").and_then(Value::as_str))
+        {
+            if !matches.iter().any(|(id, _)| id == recording_id) {
+                matches.push((recording_id.to_string(), score));
+            }
+        }
+    }
+    matches.sort_by(|left, right| right.1.total_cmp(&left.1));
+    matches
+}
+
+/// Display text of a MusicBrainz `artist-credit` list, joining names with their join
+/// phrases (`A feat. B`).
+fn artist_credit_text(entity: &Value) -> String {
+    entity
+        .get("artist-credit")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|credit| {
+            format!(
+                "{}{}",
+                credit.get("name").and_then(Value::as_str).unwrap_or(""),
+                credit
+                    .get("joinphrase")
+                    .and_then(Value::as_str)
+                    .unwrap_or("")
+            )
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn push_tag_value(values: &mut Vec<(String, String)>, id: &str, value: String) {
+    let value = value.trim().to_string();
+    if !value.is_empty() {
+        values.push((id.to_string(), value));
+    }
+}
+
+/// Tag values of a MusicBrainz recording, taking album details from its first release.
+fn recording_tag_values(recording: &Value) -> Vec<(String, String)> {
+    let mut values = Vec::new();
+    push_tag_value(
+        &mut values,
+        "common:title",
+        recording
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    );
+    push_tag_value(&mut values, "common:artist", artist_credit_text(recording));
+    let Some(release) = recording
+        .get("releases")
+        .and_then(Value::as_array)
+        .and_then(|releases| releases.first())
+    else {
+        return values;
+    };
+    push_tag_value(
+        &mut values,
+        "common:album",
+        release
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    );
+    push_tag_value(
+        &mut values,
+        "common:album_artist",
+        artist_credit_text(release),
+    );
+    let date = release
+        .get("date")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    push_tag_value(&mut values, "common:year", date.chars().take(4).collect());
+    push_tag_value(&mut values, "common:date", date.to_string());
+    if let Some(medium) = release
+        .get("media")
+        .and_then(Value::as_array)
+        .and_then(|media| media.first())
+    {
+        let track_number = medium
+            .get("track")
+            .or_else(|| medium.get("tracks"))
+            .and_then(Value::as_array)
+            .and_then(|tracks| tracks.first())
+            .and_then(|track| track.get("number"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        push_tag_value(&mut values, "common:track_number", track_number.to_string());
+        if let Some(track_count) = medium.get("track-count").and_then(Value::as_u64) {
+            push_tag_value(&mut values, "common:track_total", track_count.to_string());
+        }
+        if let Some(position) = medium.get("position").and_then(Value::as_u64) {
+            push_tag_value(&mut values, "common:disc_number", position.to_string());
+        }
+    }
+    values
+}
+
+/// Best recording of a MusicBrainz search response with its score (0-100); ties keep
+/// the earlier result.
+fn best_search_recording(response: &Value) -> Option<(&Value, u64)> {
+    response
+        .get("recordings")
+        .and_then(Value::as_array)?
+        .iter()
+        .map(|recording| {
+            let score = recording.get("score").and_then(Value::as_u64).unwrap_or(0);
+            (recording, score)
+        })
+        .rev()
+        .max_by_key(|(_, score)| *score)
+}
+
+/// Escapes Lucene query syntax in a MusicBrainz search term.
+fn lucene_escape(term: &str) -> String {
+    term.chars()
+        .flat_map(|ch| {
+            let escape = "+-&|!(){}[]^\"~*?:\\/".contains(ch);
+            escape
+                .then_some('\\')
+                .into_iter()
+                .chain(std::iter::once(ch))
+        })
+        .collect()
+}
+
+fn musicbrainz_search_url(title: &str, artist: &str) -> String {
+    let mut query = format!("recording:\"{}\"", lucene_escape(title.trim()));
+    if !artist.trim().is_empty() {
+        query.push_str(&format!(" AND artist:\"{}\"", lucene_escape(artist.trim())));
+    }
+    format!(
+        "{MUSICBRAINZ_API_URL}/recording?query={}&limit=5&fmt=json",
+        urlencoding::encode(&query)
+    )
+}
+
+fn musicbrainz_recording_url(recording_id: &str) -> String {
+    format!(
+        "{MUSICBRAINZ_API_URL}/recording/{}?inc=artists+releases+media&fmt=json",
+        urlencoding::encode(recording_id)
+    )
+}
+
+/// Title to search for: the tagged title, or the file name without its extension and
+/// leading track number for untagged files.
+fn search_title(title: &str, path: &Path) -> String {
+    if !title.trim().is_empty() {
+        return title.trim().to_string();
+    }
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    stem.trim_start_matches(|ch: char| ch.is_ascii_digit() || " .-_".contains(ch))
+        .replace('_', " ")
+        .trim()
+        .to_string()
+}
+
+impl TagLookupManager {
+    /// Creates a tag lookup manager bound to bus channels.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        initial_library_config: crate::config::LibraryConfig,
+    ) -> Self {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(5))
+            .timeout_read(Duration::from_secs(15))
+            .build();
+        Self {
+            bus_consumer,
+            bus_producer,
+            online_lookup_enabled: initial_library_config.online_metadata_enabled,
+            acoustid_api_key: initial_library_config.acoustid_api_key.trim().to_string(),
+            http_client,
+            acoustid_limiter: rate_limiter(Duration::from_millis(334)),
+            musicbrainz_limiter: rate_limiter(Duration::from_secs(1)),
+        }
+    }
+
+    fn get_json(&self, url: &str) -> Result<Value, String> {
+        let response = self
+            .http_client
+            .get(url)
+            .set("User-Agent", MUSICBRAINZ_USER_AGENT)
+            .set("Accept", "application/json")
+            .call()
+            .map_err(|error| format!("Request failed: {error}"))?;
+        response
+            .into_json()
+            .map_err(|error| format!("Invalid JSON response: {error}"))
+    }
+
+    /// Chromaprint fingerprint of a local file, via `fpcalc`.
+    fn fingerprint(path: &Path) -> Result<(u64, String), String> {
+        let output = Command::new(FPCALC_PROGRAM)
+            .arg("-json")
+            .arg(path)
+            .output()
+            .map_err(|error| format!("Could not run {FPCALC_PROGRAM} (Chromaprint): {error}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{FPCALC_PROGRAM} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        parse_fpcalc_output(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| format!("{FPCALC_PROGRAM} returned no fingerprint"))
+    }
+
+    /// Proposal from the AcoustID fingerprint match, or `Ok(None)` without a good match.
+    fn lookup_by_fingerprint(&self, path: &Path) -> Result<Option<TagLookupProposal>, String> {
+        let (duration_secs, fingerprint) = Self::fingerprint(path)?;
+        wait_for_slot(&self.acoustid_limiter);
+        let response = self
+            .http_client
+            .post(ACOUSTID_LOOKUP_URL)
+            .set("User-Agent", MUSICBRAINZ_USER_AGENT)
+            .send_form(&[
+                ("client", self.acoustid_api_key.as_str()),
+                ("meta", "recordingids"),
+                ("duration", &duration_secs.to_string()),
+                ("fingerprint", &fingerprint),
+            ])
+            .map_err(|error| format!("AcoustID request failed: {error}"))?
+            .into_json::<Value>()
+            .map_err(|error| format!("Invalid AcoustID response: {error}"))?;
+        if response.get("status").and_then(Value::as_str) != Some("ok") {
+            let message = response
+                .pointer("/error/message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            return Err(format!("AcoustID lookup failed: {message}"));
+        }
+        let Some((recording_id, score)) = acoustid_recording_matches(&response)
+            .into_iter()
+            .find(|(_, score)| *score >= MIN_ACOUSTID_SCORE)
+        else {
+            return Ok(None);
+        };
+        wait_for_slot(&self.musicbrainz_limiter);
+        let recording = self.get_json(&musicbrainz_recording_url(&recording_id))?;
+        Ok(Some(TagLookupProposal {
+            source: format!(
+                "AcoustID fingerprint, {}% match",
+                (score * 100.0).round() as u32
+            ),
+            values: recording_tag_values(&recording),
+        }))
+    }
+
+    /// Proposal from a MusicBrainz search, or `Ok(None)` without a good match.
+    fn lookup_by_search(
+        &self,
+        title: &str,
+        artist: &str,
+    ) -> Result<Option<TagLookupProposal>, String> {
+        wait_for_slot(&self.musicbrainz_limiter);
+        let response = self.get_json(&musicbrainz_search_url(title, artist))?;
+        Ok(best_search_recording(&response)
+            .filter(|(_, score)| *score >= MIN_MUSICBRAINZ_SEARCH_SCORE)
+            .map(|(recording, score)| TagLookupProposal {
+                source: format!("MusicBrainz search, {score}% match"),
+                values: recording_tag_values(recording),
+            }))
+    }
+
+    fn lookup(&self, path: &Path, title: &str, artist: &str) -> Result<TagLookupProposal, String> {
+        if !self.online_lookup_enabled {
+            return Err(
+                "Enable Settings -> Library -> Fetch metadata from internet to look up tags online."
+                    .to_string(),
+            );
+        }
+        let mut fingerprint_error = None;
+        if !self.acoustid_api_key.is_empty() && !is_remote_track_path(path) {
+            match self.lookup_by_fingerprint(path) {
+                Ok(Some(proposal)) => return Ok(proposal),
+                Ok(None) => debug!("TagLookupManager: no AcoustID match for {}", path.display()),
+                Err(error) => {
+                    warn!(
+                        "TagLookupManager: fingerprint lookup failed for {}: {error}",
+                        path.display()
+                    );
+                    fingerprint_error = Some(error);
+                }
+            }
+        }
+        let title = search_title(title, path);
+        if title.is_empty() {
+            return Err(fingerprint_error.unwrap_or_else(|| {
+                "Nothing to search for: add a title, or set library.acoustid_api_key to identify untagged files by fingerprint."
+                    .to_string()
+            }));
+        }
+        match self.lookup_by_search(&title, artist)? {
+            Some(proposal) => Ok(proposal),
+            None => Err(fingerprint_error
+                .map(|error| format!("No MusicBrainz match found ({error})."))
+                .unwrap_or_else(|| "No MusicBrainz match found.".to_string())),
+        }
+    }
+
+    /// Starts the blocking event loop for tag lookup requests.
+    pub fn run(&mut self) {
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(Message::Metadata(MetadataMessage::LookupTrackTags {
+                    request_id,
+                    path,
+                    title,
+                    artist,
+                })) => {
+                    debug!("TagLookupManager: looking up tags for {}", path.display());
+                    let result = self.lookup(&path, &title, &artist);
+                    let _ = self.bus_producer.send(Message::Metadata(
+                        MetadataMessage::TrackTagLookupCompleted {
+                            request_id,
+                            path,
+                            result,
+                        },
+                    ));
+                }
+                Ok(Message::Config(ConfigMessage::ConfigChanged(changes))) => {
+                    for change in changes {
+                        if let ConfigDeltaEntry::Library(library) = change {
+                            if let Some(enabled) = library.online_metadata_enabled {
+                                self.online_lookup_enabled = enabled;
+                            }
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("TagLookupManager lagged on control bus, skipped {skipped} message(s)");
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fpcalc_output_and_acoustid_matches() {
+        assert_eq!(
+            parse_fpcalc_output(r#"{"duration": 215.62, "fingerprint": "AQAAT0"}"#),
+            Some((216, "AQAAT0".to_string()))
+        );
+        assert_eq!(parse_fpcalc_output(r#"{"duration": 1.0}"#), None);
+        let response = serde_json::json!({
+            "status": "ok",
+            "results": [
+                { "id": "a", "score": 0.62, "recordings": [{ "id": "rec-2" }] },
+                { "id": "b", "score": 0.97, "recordings": [{ "id": "rec-1" }, { "id": "rec-2" }] },
+                { "id": "c", "score": 0.99 },
+            ]
+        });
+        assert_eq!(
+            acoustid_recording_matches(&response),
+            vec![("rec-1".to_string(), 0.97), ("rec-2".to_string(), 0.62)]
+        );
+    }
+
+    #[test]
+    fn test_recording_tag_values_use_first_release() {
+        let recording = serde_json::json!({
+            "id": "rec-1",
+            "score": 100,
+            "title": "Song",
+            "artist-credit": [
+                { "name": "Alice", "joinphrase": " feat. " },
+                { "name": "Bob", "joinphrase": "" }
+            ],
+            "releases": [{
+                "title": "Album",
+                "date": "2004-05-17",
+                "artist-credit": [{ "name": "Alice" }],
+                "media": [{ "position": 2, "track-count": 11, "track": [{ "number": "7" }] }]
+            }]
+        });
+        assert_eq!(
+            recording_tag_values(&recording),
+            [
+                ("common:title", "Song"),
+                ("common:artist", "Alice feat. Bob"),
+                ("common:album", "Album"),
+                ("common:album_artist", "Alice"),
+                ("common:year", "2004"),
+                ("common:date", "2004-05-17"),
+                ("common:track_number", "7"),
+                ("common:track_total", "11"),
+                ("common:disc_number", "2"),
+            ]
+            .map(|(id, value)| (id.to_string(), value.to_string()))
+        );
+        let search = serde_json::json!({ "recordings": [
+            { "title": "Other", "score": 64 },
+            recording,
+        ]});
+        assert_eq!(
+            best_search_recording(&search).map(|(_, score)| score),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn test_search_terms_fall_back_to_file_name() {
+        assert_eq!(
+            search_title("", Path::new("/music/03 - Some_Song.flac")),
+            "Some Song"
+        );
+        assert_eq!(
+            search_title(" Tagged ", Path::new("/music/x.flac")),
+            "Tagged"
+        );
+        assert_eq!(
+            musicbrainz_search_url("AC/DC: Live", ""),
+            "https://musicbrainz.org/ws/2/recording?query=recording%3A%22AC%5C%2FDC%5C%3A%20Live%22&limit=5&fmt=json"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Metrics facade with an optional local exporter for performance investigations.
+//!
+//! Instrumented code records counters, gauges, and latency histograms through the
+//! functions below. Recording does nothing until the exporter is enabled with the
+//! `ROQTUNE_METRICS` environment variable:
+//!
+//! - `http` or `http:<port>` serves the Prometheus text format on
+//!   `http://127.0.0.1:<port>/metrics` (default port 9464).
+//! - `dump` or `dump:<path>` rewrites the same text to a file every few seconds
+//!   (default `<cache_dir>/roqtune/metrics.prom`).
+//!
+//! Series recorded today: per-track decode time, list-thumbnail cache lookups, library
+//! read-query latency, event-bus queue depth, and UI frame render time.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use log::{info, warn};
+use tokio::sync::broadcast::Sender;
+
+use crate::protocol::Message;
+
+/// Environment variable selecting the exporter.
+pub const METRICS_ENV: &str = "ROQTUNE_METRICS";
+const DEFAULT_HTTP_PORT: u16 = 9464;
+const DUMP_INTERVAL: Duration = Duration::from_secs(10);
+const BUS_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Histogram bucket upper bounds, in seconds.
+const BUCKETS_SECONDS: [f64; 14] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0, 30.0,
+];
+
+/// Decode time of one finished track, in seconds.
+pub const TRACK_DECODE_SECONDS: &str = "roqtune_track_decode_seconds";
+/// Cache lookups, labeled by `cache` and `result` (`hit` or `miss`).
+pub const CACHE_LOOKUPS_TOTAL: &str = "roqtune_cache_lookups_total";
+/// Run time of one library read-pool query job, in seconds.
+pub const DB_READ_QUERY_SECONDS: &str = "roqtune_db_read_query_seconds";
+/// Messages queued on the event bus and not yet seen by every subscriber.
+pub const BUS_QUEUE_DEPTH: &str = "roqtune_bus_queue_depth";
+/// Time spent rendering one UI frame, in seconds.
+pub const UI_FRAME_RENDER_SECONDS: &str = "roqtune_ui_frame_render_seconds";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+/// Exporter requested through [`METRICS_ENV`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExporterTarget {
+    Http { port: u16 },
+    Dump { path: PathBuf },
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    bucket_counts: [u64; BUCKETS_SECONDS.len()],
+    count: u64,
+    sum: f64,
+}
+
+type SeriesKey = (&'static str, String);
+
+#[derive(Debug, Default)]
+struct Registry {
+    counters: BTreeMap<SeriesKey, u64>,
+    gauges: BTreeMap<SeriesKey, f64>,
+    histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+fn label_text(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", value.replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Returns whether metrics are being recorded.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Adds one to a counter.
+pub fn increment(name: &'static str, labels: &[(&str, &str)]) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut registry) = registry().lock() {
+        *registry
+            .counters
+            .entry((name, label_text(labels)))
+            .or_default() += 1;
+    }
+}
+
+/// Sets a gauge to its latest value.
+pub fn set_gauge(name: &'static str, labels: &[(&str, &str)], value: f64) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut registry) = registry().lock() {
+        registry.gauges.insert((name, label_text(labels)), value);
+    }
+}
+
+/// Records one latency sample.
+pub fn observe_duration(name: &'static str, labels: &[(&str, &str)], duration: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    let seconds = duration.as_secs_f64();
+    if let Ok(mut registry) = registry().lock() {
+        let histogram = registry
+            .histograms
+            .entry((name, label_text(labels)))
+            .or_default();
+        if let Some(bucket) = BUCKETS_SECONDS.iter().position(|bound| seconds <= *bound) {
+            histogram.bucket_counts[bucket] += 1;
+        }
+        histogram.count += 1;
+        histogram.sum += seconds;
+    }
+}
+
+fn series_name(name: &str, labels: &str, extra_label: Option<String>) -> String {
+    let labels = match (labels.is_empty(), extra_label) {
+        (true, None) => return name.to_string(),
+        (true, Some(extra)) => extra,
+        (false, None) => labels.to_string(),
+        (false, Some(extra)) => format!("{labels},{extra}"),
+    };
+    format!("{name}{{{labels}}}")
+}
+
+fn write_type_line(
+    text: &mut String,
+    last_family: &mut Option<&'static str>,
+    name: &'static str,
+    kind: &str,
+) {
+    if *last_family != Some(name) {
+        let _ = writeln!(text, "# TYPE {name} {kind}");
+        *last_family = Some(name);
+    }
+}
+
+/// Renders every recorded series in the Prometheus text exposition format.
+pub fn render_prometheus() -> String {
+    let Ok(registry) = registry().lock() else {
+        return String::new();
+    };
+    let mut text = String::new();
+    let mut last_family = None;
+    for ((name, labels), value) in &registry.counters {
+        write_type_line(&mut text, &mut last_family, name, "counter");
+        let _ = writeln!(text, "{} {value}", series_name(name, labels, None));
+    }
+    for ((name, labels), value) in &registry.gauges {
+        write_type_line(&mut text, &mut last_family, name, "gauge");
+        let _ = writeln!(text, "{} {value}", series_name(name, labels, None));
+    }
+    for ((name, labels), histogram) in &registry.histograms {
+        write_type_line(&mut text, &mut last_family, name, "histogram");
+        let bucket_name = format!("{name}_bucket");
+        let mut cumulative = 0;
+        for (bound, count) in BUCKETS_SECONDS.iter().zip(histogram.bucket_counts) {
+            cumulative += count;
+            let le = format!("le=\"{bound}\"");
+            let _ = writeln!(
+                text,
+                "{} {cumulative}",
+                series_name(&bucket_name, labels, Some(le))
+            );
+        }
+        let inf = "le=\"+Inf\"".to_string();
+        let _ = writeln!(
+            text,
+            "{} {}",
+            series_name(&bucket_name, labels, Some(inf)),
+            histogram.count
+        );
+        let _ = writeln!(
+            text,
+            "{} {}",
+            series_name(&format!("{name}_sum"), labels, None),
+            histogram.sum
+        );
+        let _ = writeln!(
+            text,
+            "{} {}",
+            series_name(&format!("{name}_count"), labels, None),
+            histogram.count
+        );
+    }
+    text
+}
+
+fn parse_exporter_target(value: &str) -> Option<ExporterTarget> {
+    let (kind, argument) = match value.trim().split_once(':') {
+        Some((kind, argument)) => (kind, Some(argument.trim())),
+        None => (value.trim(), None),
+    };
+    match kind {
+        "http" => {
+            let port = match argument {
+                Some(port) => port.parse().ok()?,
+                None => DEFAULT_HTTP_PORT,
+            };
+            Some(ExporterTarget::Http { port })
+        }
+        "dump" => {
+            let path = match argument.filter(|path| !path.is_empty()) {
+                Some(path) => PathBuf::from(path),
+                None => dirs::cache_dir()?.join("roqtune").join("metrics.prom"),
+            };
+            Some(ExporterTarget::Dump { path })
+        }
+        _ => None,
+    }
+}
+
+/// Enables recording and starts the exporter when [`METRICS_ENV`] asks for one.
+///
+/// Also samples the event-bus queue depth once per second.
+pub fn start_exporter_from_env(bus_sender: Sender<Message>) {
+    let Ok(value) = std::env::var(METRICS_ENV) else {
+        return;
+    };
+    let Some(target) = parse_exporter_target(&value) else {
+        warn!("Metrics: ignoring unsupported {METRICS_ENV} value '{value}'");
+        return;
+    };
+    ENABLED.store(true, Ordering::Relaxed);
+    let _ = thread::Builder::new()
+        .name("metrics-bus-sampler".to_string())
+        .spawn(move || loop {
+            set_gauge(BUS_QUEUE_DEPTH, &[], bus_sender.len() as f64);
+            thread::sleep(BUS_SAMPLE_INTERVAL);
+        });
+    match target {
+        ExporterTarget::Http { port } => match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => {
+                info!("Metrics: serving Prometheus metrics on http://127.0.0.1:{port}/metrics");
+                let _ = thread::Builder::new()
+                    .name("metrics-http".to_string())
+                    .spawn(move || {
+                        for stream in listener.incoming().flatten() {
+                            serve_metrics_request(stream);
+                        }
+                    });
+            }
+            Err(err) => warn!("Metrics: failed to bind 127.0.0.1:{port}: {err}"),
+        },
+        ExporterTarget::Dump { path } => {
+            info!("Metrics: dumping metrics to {}", path.display());
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = thread::Builder::new()
+                .name("metrics-dump".to_string())
+                .spawn(move || loop {
+                    thread::sleep(DUMP_INTERVAL);
+                    if let Err(err) = std::fs::write(&path, render_prometheus()) {
+                        warn!("Metrics: failed to write {}: {err}", path.display());
+                    }
+                });
+        }
+    }
+}
+
+fn serve_metrics_request(mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request);
+    let body = render_prometheus();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exporter_target_accepts_http_and_dump() {
+        assert_eq!(
+            parse_exporter_target("http"),
+            Some(ExporterTarget::Http {
+                port: DEFAULT_HTTP_PORT
+            })
+        );
+        assert_eq!(
+            parse_exporter_target("http:9100"),
+            Some(ExporterTarget::Http { port: 9100 })
+        );
+        assert_eq!(
+            parse_exporter_target("dump:/tmp/roqtune.prom"),
+            Some(ExporterTarget::Dump {
+                path: PathBuf::from("/tmp/roqtune.prom")
+            })
+        );
+        assert_eq!(parse_exporter_target("statsd"), None);
+        assert_eq!(parse_exporter_target("http:port"), None);
+    }
+
+    #[test]
+    fn test_render_prometheus_writes_counters_and_cumulative_buckets() {
+        ENABLED.store(true, Ordering::Relaxed);
+        increment(
+            "roqtune_test_lookups_total",
+            &[("cache", "test"), ("result", "hit")],
+        );
+        observe_duration("roqtune_test_seconds", &[], Duration::from_millis(3));
+        observe_duration("roqtune_test_seconds", &[], Duration::from_millis(40));
+
+        let text = render_prometheus();
+
+        assert!(text.contains("# TYPE roqtune_test_lookups_total counter"));
+        assert!(text.contains("roqtune_test_lookups_total{cache=\"test\",result=\"hit\"} 1"));
+        assert!(text.contains("roqtune_test_seconds_bucket{le=\"0.005\"} 1"));
+        assert!(text.contains("roqtune_test_seconds_bucket{le=\"0.05\"} 2"));
+        assert!(text.contains("roqtune_test_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(text.contains("roqtune_test_seconds_count 2"));
+    }
+}
@@ -0,0 +1,733 @@
+//! Guided migration of data written by an older roqtune version.
+//!
+//! Older databases, `config.toml` and `layout.toml` files used to be converted silently
+//! while loading. Startup now first plans the conversion with [`plan_migration`]; when
+//! anything needs converting, [`run_assistant`] shows the plan (including entries that
+//! cannot be carried over) and only after the user agrees backs the files up into
+//! `migration-backup-<time>/` next to the config and converts them with
+//! [`run_migration`]. The loaders keep their compatibility handling, so declining leaves
+//! every file exactly as it was.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use rusqlite::{Connection, OpenFlags};
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel};
+
+use crate::{
+    config::{default_playlist_columns, Config},
+    config_persistence::{
+        apply_legacy_dark_mode, hydrate_ui_columns_from_layout, load_layout_file,
+    },
+    db_manager::DbManager,
+    db_recovery,
+    ui::playlist_columns::normalize_column_format,
+    MigrationAssistantWindow,
+};
+
+const BACKUP_DIR_PREFIX: &str = "migration-backup-";
+
+/// Panel kinds that loading renames or converts into another panel.
+const LEGACY_PANEL_KINDS: [&str; 3] = ["metadata_viewer", "album_art_viewer", "status_bar"];
+
+/// Which part of the user's data a migration entry belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationArea {
+    Database,
+    Profiles,
+    Settings,
+    Layout,
+}
+
+impl MigrationArea {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Database => "Playlists and library",
+            Self::Profiles => "Profiles",
+            Self::Settings => "Settings",
+            Self::Layout => "Layout",
+        }
+    }
+}
+
+/// One entry of a migration plan or report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationItem {
+    pub area: MigrationArea,
+    pub description: String,
+}
+
+impl MigrationItem {
+    fn new(area: MigrationArea, description: impl Into<String>) -> Self {
+        Self {
+            area,
+            description: description.into(),
+        }
+    }
+
+    fn display_text(&self) -> String {
+        format!("{}: {}", self.area.label(), self.description)
+    }
+}
+
+/// Files the assistant inspects and converts.
+#[derive(Clone, Debug)]
+pub struct MigrationPaths {
+    pub config_file: PathBuf,
+    pub layout_file: PathBuf,
+    pub database_path: PathBuf,
+}
+
+impl MigrationPaths {
+    fn legacy_database_path(&self) -> PathBuf {
+        self.database_path
+            .with_file_name(DbManager::LEGACY_DB_FILE_NAME)
+    }
+
+    /// The database file that exists on disk: `roqtune.db`, or `playlist.db` from
+    /// versions that used that name.
+    fn existing_database_path(&self) -> Option<PathBuf> {
+        [self.database_path.clone(), self.legacy_database_path()]
+            .into_iter()
+            .find(|path| path.exists())
+    }
+}
+
+/// What startup would convert, and what it would have to drop.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationPlan {
+    pub items: Vec<MigrationItem>,
+    pub unmigratable: Vec<MigrationItem>,
+}
+
+impl MigrationPlan {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.unmigratable.is_empty()
+    }
+
+    fn touches(&self, areas: &[MigrationArea]) -> bool {
+        self.items
+            .iter()
+            .chain(&self.unmigratable)
+            .any(|item| areas.contains(&item.area))
+    }
+}
+
+/// Outcome of [`run_migration`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub backup_dir: PathBuf,
+    pub converted: Vec<MigrationItem>,
+    pub not_migrated: Vec<MigrationItem>,
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("1 {singular}")
+    } else {
+        format!("{count} {plural}")
+    }
+}
+
+fn plan_database(paths: &MigrationPaths, plan: &mut MigrationPlan) {
+    let Some(db_path) = paths.existing_database_path() else {
+        return;
+    };
+    if db_path != paths.database_path {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Database,
+            format!(
+                "Rename the database {} to {}",
+                DbManager::LEGACY_DB_FILE_NAME,
+                paths
+                    .database_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            ),
+        ));
+    }
+    let reference = match DbManager::current_schema_columns() {
+        Ok(reference) => reference,
+        Err(err) => {
+            warn!("Could not build the reference database schema: {}", err);
+            return;
+        }
+    };
+    // A damaged file is left to the integrity check that runs after the assistant.
+    let Ok(conn) = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) else {
+        return;
+    };
+    for (table, columns) in reference {
+        let existing: HashSet<String> = db_recovery::table_columns(&conn, "main", &table)
+            .into_iter()
+            .collect();
+        // Missing tables are simply created; only tables with older columns convert.
+        if existing.is_empty() {
+            continue;
+        }
+        let missing: Vec<String> = columns
+            .into_iter()
+            .filter(|column| !existing.contains(column))
+            .collect();
+        if !missing.is_empty() {
+            plan.items.push(MigrationItem::new(
+                MigrationArea::Database,
+                format!(
+                    "Add {} to the {} table ({})",
+                    plural(missing.len(), "new field", "new fields"),
+                    table,
+                    missing.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn plan_config(config_file: &Path, plan: &mut MigrationPlan) {
+    let Ok(text) = fs::read_to_string(config_file) else {
+        return;
+    };
+    let value = match toml::from_str::<toml::Value>(&text) {
+        Ok(value) => value,
+        Err(err) => {
+            plan.unmigratable.push(MigrationItem::new(
+                MigrationArea::Settings,
+                format!("config.toml cannot be read and will be replaced by defaults ({err})"),
+            ));
+            return;
+        }
+    };
+    if let Err(err) = toml::from_str::<Config>(&text) {
+        plan.unmigratable.push(MigrationItem::new(
+            MigrationArea::Settings,
+            format!("config.toml has values this version does not understand and will be replaced by defaults ({err})"),
+        ));
+        return;
+    }
+    if let Some(dark_mode) = value
+        .get("ui")
+        .and_then(|ui| ui.get("dark_mode"))
+        .and_then(toml::Value::as_bool)
+    {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Settings,
+            format!(
+                "Replace the dark mode switch with the {} color scheme",
+                if dark_mode { "dark" } else { "light" }
+            ),
+        ));
+    }
+    if value
+        .get("output")
+        .and_then(|output| output.get("resampler_quality"))
+        .and_then(toml::Value::as_str)
+        == Some("very_high")
+    {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Settings,
+            "Rename the resampler quality \"very high\" to \"highest\"",
+        ));
+    }
+    let untyped_backends = value
+        .get("integrations")
+        .and_then(|integrations| integrations.get("backends"))
+        .and_then(toml::Value::as_array)
+        .map_or(0, |backends| {
+            backends
+                .iter()
+                .filter(|backend| backend.get("backend_kind").is_none())
+                .count()
+        });
+    if untyped_backends > 0 {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Profiles,
+            format!(
+                "Mark {} as OpenSubsonic",
+                plural(untyped_backends, "server profile", "server profiles")
+            ),
+        ));
+    }
+}
+
+/// Collects `(leaf id, panel)` for every leaf under `node`, whatever version wrote it.
+fn collect_leaf_panels<'a>(node: &'a toml::Value, out: &mut Vec<(&'a str, &'a str)>) {
+    let Some(table) = node.as_table() else {
+        return;
+    };
+    if let Some(panel) = table.get("panel").and_then(toml::Value::as_str) {
+        let id = table.get("id").and_then(toml::Value::as_str).unwrap_or("");
+        out.push((id, panel));
+    }
+    for child in table.values() {
+        collect_leaf_panels(child, out);
+    }
+}
+
+fn plan_layout(layout_file: &Path, plan: &mut MigrationPlan) {
+    let Ok(text) = fs::read_to_string(layout_file) else {
+        return;
+    };
+    let value = match toml::from_str::<toml::Value>(&text) {
+        Ok(value) => value,
+        Err(err) => {
+            plan.unmigratable.push(MigrationItem::new(
+                MigrationArea::Layout,
+                format!(
+                    "layout.toml cannot be read and will be replaced by the default layout ({err})"
+                ),
+            ));
+            return;
+        }
+    };
+    if let Err(err) = toml::from_str::<crate::layout::LayoutConfig>(&text) {
+        plan.unmigratable.push(MigrationItem::new(
+            MigrationArea::Layout,
+            format!("layout.toml has values this version does not understand and will be replaced by the default layout ({err})"),
+        ));
+        return;
+    }
+
+    let mut leaves = Vec::new();
+    if let Some(root) = value.get("root") {
+        collect_leaf_panels(root, &mut leaves);
+    }
+    let legacy_panels = leaves
+        .iter()
+        .filter(|(_, panel)| LEGACY_PANEL_KINDS.contains(panel))
+        .count();
+    if legacy_panels > 0 {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Layout,
+            format!(
+                "Convert {} to text and image panels",
+                plural(
+                    legacy_panels,
+                    "viewer or status bar panel",
+                    "viewer and status bar panels"
+                )
+            ),
+        ));
+    }
+
+    if value.get("playlist_column_width_overrides").is_some() {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Layout,
+            "Move saved column widths onto their playlist columns",
+        ));
+    }
+    if value.get("metadata_viewer_panel_instances").is_some()
+        || value.get("album_art_viewer_panel_instances").is_some()
+    {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Layout,
+            "Rename viewer panel settings to text and image panel settings",
+        ));
+    }
+    if let Some(instances) = value
+        .get("viewer_panel_instances")
+        .and_then(toml::Value::as_array)
+    {
+        plan.items.push(MigrationItem::new(
+            MigrationArea::Layout,
+            "Split combined viewer panel settings into text and image panel settings",
+        ));
+        for leaf_id in instances
+            .iter()
+            .filter_map(|instance| instance.get("leaf_id").and_then(toml::Value::as_str))
+        {
+            let converts = leaves.iter().any(|(id, panel)| {
+                *id == leaf_id
+                    && matches!(
+                        *panel,
+                        "text_panel" | "image_panel" | "metadata_viewer" | "album_art_viewer"
+                    )
+            });
+            if !converts {
+                plan.unmigratable.push(MigrationItem::new(
+                    MigrationArea::Layout,
+                    format!(
+                        "Viewer panel settings for \"{leaf_id}\" match no text or image panel and will be dropped"
+                    ),
+                ));
+            }
+        }
+    }
+
+    let known_formats: HashSet<String> = default_playlist_columns()
+        .iter()
+        .map(|column| normalize_column_format(&column.format))
+        .collect();
+    for column in value
+        .get("playlist_columns")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let is_custom = column
+            .get("custom")
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(false);
+        let format = column
+            .get("format")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("");
+        if is_custom || format.trim().is_empty() {
+            continue;
+        }
+        if !known_formats.contains(&normalize_column_format(format)) {
+            let name = column
+                .get("name")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(format);
+            plan.unmigratable.push(MigrationItem::new(
+                MigrationArea::Layout,
+                format!("The column \"{name}\" ({format}) no longer exists and will be removed"),
+            ));
+        }
+    }
+}
+
+/// Lists what loading `paths` would convert or drop. An empty plan means the files
+/// are already in the current format (or do not exist yet).
+pub fn plan_migration(paths: &MigrationPaths) -> MigrationPlan {
+    let mut plan = MigrationPlan::default();
+    plan_database(paths, &mut plan);
+    plan_config(&paths.config_file, &mut plan);
+    plan_layout(&paths.layout_file, &mut plan);
+    plan
+}
+
+fn back_up_files(paths: &MigrationPaths, backup_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|err| format!("Could not create {}: {}", backup_dir.display(), err))?;
+    for file in [&paths.config_file, &paths.layout_file] {
+        if !file.exists() {
+            continue;
+        }
+        let target = backup_dir.join(file.file_name().unwrap_or_default());
+        fs::copy(file, &target)
+            .map_err(|err| format!("Could not back up {}: {}", file.display(), err))?;
+    }
+    if let Some(db_path) = paths.existing_database_path() {
+        let target = backup_dir.join(db_path.file_name().unwrap_or_default());
+        let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|err| format!("Could not back up {}: {}", db_path.display(), err))?;
+        conn.execute("VACUUM INTO ?1", [target.to_string_lossy()])
+            .map_err(|err| format!("Could not back up {}: {}", db_path.display(), err))?;
+    }
+    Ok(())
+}
+
+fn write_toml<T: serde::Serialize>(value: &T, path: &Path) -> Result<(), String> {
+    let text = toml::to_string(value).map_err(|err| err.to_string())?;
+    fs::write(path, text).map_err(|err| err.to_string())
+}
+
+/// Converts the database, config and layout, returning an entry for each part that
+/// failed.
+///
+/// The config and layout are loaded and sanitized with the same code startup uses and
+/// then written out whole, since the comment-preserving writers keep keys they do not
+/// know about. Comments from the old files remain in the backup.
+fn convert_files(paths: &MigrationPaths, plan: &MigrationPlan) -> Vec<MigrationItem> {
+    let mut failures = Vec::new();
+    if plan.touches(&[MigrationArea::Database]) {
+        let converted = paths
+            .database_path
+            .parent()
+            .map_or(Ok(()), DbManager::migrate_legacy_db_file)
+            .map_err(|err| format!("The database could not be renamed ({err})"))
+            .and_then(|()| {
+                DbManager::open_at(&paths.database_path)
+                    .map(drop)
+                    .map_err(|err| format!("The database could not be updated ({err})"))
+            });
+        if let Err(description) = converted {
+            failures.push(MigrationItem::new(MigrationArea::Database, description));
+        }
+    }
+    if plan.touches(&[
+        MigrationArea::Settings,
+        MigrationArea::Profiles,
+        MigrationArea::Layout,
+    ]) {
+        // Unreadable files are replaced by defaults, as the plan announced; the
+        // originals are in the backup.
+        let mut config = fs::read_to_string(&paths.config_file)
+            .ok()
+            .and_then(|text| toml::from_str::<Config>(&text).ok())
+            .unwrap_or_default();
+        let layout_text = fs::read_to_string(&paths.layout_file).ok();
+        let mut layout = load_layout_file(&paths.layout_file);
+        apply_legacy_dark_mode(&config, layout_text.as_deref(), &mut layout);
+        config.ui.layout = layout;
+        hydrate_ui_columns_from_layout(&mut config);
+        let config = crate::sanitize_config(config);
+        if let Err(err) = write_toml(&config, &paths.config_file) {
+            failures.push(MigrationItem::new(
+                MigrationArea::Settings,
+                format!("config.toml could not be written ({err})"),
+            ));
+        }
+        if let Err(err) = write_toml(&config.ui.layout, &paths.layout_file) {
+            failures.push(MigrationItem::new(
+                MigrationArea::Layout,
+                format!("layout.toml could not be written ({err})"),
+            ));
+        }
+    }
+    failures
+}
+
+/// Backs up every file in `paths` into a fresh `migration-backup-<time>/` folder, then
+/// converts them according to `plan`.
+///
+/// Returns an error without touching anything when the backup cannot be taken.
+pub fn run_migration(
+    paths: &MigrationPaths,
+    plan: &MigrationPlan,
+    now_secs: u64,
+) -> Result<MigrationReport, String> {
+    let backup_root = paths
+        .config_file
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let backup_dir = backup_root.join(format!("{BACKUP_DIR_PREFIX}{now_secs}"));
+    back_up_files(paths, &backup_dir)?;
+    info!("Backed up data for migration to {}", backup_dir.display());
+
+    let failures = convert_files(paths, plan);
+    let failed_areas: Vec<MigrationArea> = failures
+        .iter()
+        .flat_map(|failure| match failure.area {
+            // Profiles live in config.toml, so they fail along with the settings.
+            MigrationArea::Settings => vec![MigrationArea::Settings, MigrationArea::Profiles],
+            area => vec![area],
+        })
+        .collect();
+    for failure in &failures {
+        warn!("Migration failed: {}", failure.description);
+    }
+    let mut converted = plan.items.clone();
+    converted.retain(|item| !failed_areas.contains(&item.area));
+    let mut not_migrated = plan.unmigratable.clone();
+    not_migrated.extend(failures);
+    Ok(MigrationReport {
+        backup_dir,
+        converted,
+        not_migrated,
+    })
+}
+
+fn string_model(items: &[MigrationItem]) -> ModelRc<SharedString> {
+    let texts: Vec<SharedString> = items
+        .iter()
+        .map(|item| item.display_text().into())
+        .collect();
+    ModelRc::from(Rc::new(VecModel::from(texts)))
+}
+
+/// Shows the migration assistant when `paths` hold data from an older version.
+///
+/// Returns `false` when the user quit instead of migrating, in which case nothing was
+/// changed and roqtune should exit.
+pub fn run_assistant(paths: &MigrationPaths) -> Result<bool, Box<dyn std::error::Error>> {
+    let plan = plan_migration(paths);
+    if plan.is_empty() {
+        return Ok(true);
+    }
+    info!(
+        "Data from an older version found: {} to convert, {} not migratable",
+        plan.items.len(),
+        plan.unmigratable.len()
+    );
+
+    let window = MigrationAssistantWindow::new()?;
+    window.set_planned_items(string_model(&plan.items));
+    window.set_unmigratable_items(string_model(&plan.unmigratable));
+
+    let window_weak = window.as_weak();
+    window.window().on_close_requested(move || {
+        // Closing mid-migration would leave half-converted files behind.
+        match window_weak.upgrade() {
+            Some(window) if window.get_busy() => slint::CloseRequestResponse::KeepWindowShown,
+            _ => slint::CloseRequestResponse::HideWindow,
+        }
+    });
+
+    let window_weak = window.as_weak();
+    let paths_clone = paths.clone();
+    window.on_migrate(move || {
+        let Some(window) = window_weak.upgrade() else {
+            return;
+        };
+        window.set_busy(true);
+        window.set_error_text("".into());
+        let window_weak = window.as_weak();
+        let paths = paths_clone.clone();
+        let plan = plan.clone();
+        std::thread::spawn(move || {
+            let result = run_migration(&paths, &plan, unix_now_secs());
+            let _ = window_weak.upgrade_in_event_loop(move |window| {
+                window.set_busy(false);
+                match result {
+                    Ok(report) => {
+                        let mut report_text = format!(
+                            "Your previous files were backed up to {}.",
+                            report.backup_dir.display()
+                        );
+                        if !report.not_migrated.is_empty() {
+                            report_text.push_str(
+                                " Entries that were not migrated are still in the backup.",
+                            );
+                        }
+                        window.set_report_text(report_text.into());
+                        window.set_converted_items(string_model(&report.converted));
+                        window.set_failed_items(string_model(&report.not_migrated));
+                        window.set_finished(true);
+                    }
+                    Err(err) => {
+                        warn!("Migration backup failed: {}", err);
+                        window.set_error_text(
+                            format!("Nothing was converted because the backup failed: {err}")
+                                .into(),
+                        );
+                    }
+                }
+            });
+        });
+    });
+    window.on_quit(|| {
+        let _ = slint::quit_event_loop();
+    });
+    window.on_continue_startup(|| {
+        let _ = slint::quit_event_loop();
+    });
+
+    window.run()?;
+    Ok(window.get_finished())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config_persistence::system_layout_template_text;
+
+    fn temp_paths(name: &str) -> MigrationPaths {
+        let dir =
+            std::env::temp_dir().join(format!("roqtune-migration-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("data")).expect("create temp dir");
+        MigrationPaths {
+            config_file: dir.join("config.toml"),
+            layout_file: dir.join("layout.toml"),
+            database_path: dir.join("data").join("roqtune.db"),
+        }
+    }
+
+    #[test]
+    fn test_current_files_need_no_migration() {
+        let paths = temp_paths("current");
+        let config = crate::sanitize_config(Config::default());
+        fs::write(&paths.config_file, toml::to_string(&config).unwrap()).expect("write config");
+        fs::write(&paths.layout_file, system_layout_template_text()).expect("write layout");
+        drop(DbManager::open_at(&paths.database_path).expect("create database"));
+        assert_eq!(plan_migration(&paths), MigrationPlan::default());
+    }
+
+    #[test]
+    fn test_plan_lists_legacy_settings_profiles_and_layout_entries() {
+        let paths = temp_paths("plan");
+        fs::write(
+            &paths.config_file,
+            "[ui]\ndark_mode = false\n\n[output]\nresampler_quality = \"very_high\"\n\n\
+             [[integrations.backends]]\nprofile_id = \"home\"\n",
+        )
+        .expect("write config");
+        fs::write(
+            &paths.layout_file,
+            "[[playlist_columns]]\nname = \"Mood\"\nformat = \"{mood}\"\n\n\
+             [[playlist_column_width_overrides]]\ncolumn_key = \"{title}\"\nwidth_px = 200\n\n\
+             [[viewer_panel_instances]]\nleaf_id = \"gone\"\n\n\
+             [root]\nnode_type = \"leaf\"\nid = \"l1\"\npanel = \"status_bar\"\n",
+        )
+        .expect("write layout");
+
+        let plan = plan_migration(&paths);
+        let areas: Vec<MigrationArea> = plan.items.iter().map(|item| item.area).collect();
+        assert_eq!(
+            areas,
+            vec![
+                MigrationArea::Settings,
+                MigrationArea::Settings,
+                MigrationArea::Profiles,
+                MigrationArea::Layout,
+                MigrationArea::Layout,
+                MigrationArea::Layout,
+            ]
+        );
+        assert_eq!(plan.unmigratable.len(), 2);
+        assert!(plan.unmigratable[0].description.contains("\"gone\""));
+        assert!(plan.unmigratable[1].description.contains("\"Mood\""));
+    }
+
+    #[test]
+    fn test_migration_backs_up_then_converts_legacy_database_and_config() {
+        let paths = temp_paths("run");
+        let legacy_db = paths.legacy_database_path();
+        {
+            let conn = Connection::open(&legacy_db).expect("create legacy database");
+            conn.execute_batch(
+                "CREATE TABLE tracks (
+                     id TEXT PRIMARY KEY, path TEXT NOT NULL, position INTEGER NOT NULL,
+                     title TEXT, artist TEXT, album TEXT, date TEXT, genre TEXT
+                 );
+                 INSERT INTO tracks (id, path, position) VALUES ('t1', '/music/a.flac', 0);",
+            )
+            .expect("create legacy schema");
+        }
+        fs::write(&paths.config_file, "[ui]\ndark_mode = false\n").expect("write config");
+
+        let plan = plan_migration(&paths);
+        assert!(plan
+            .items
+            .iter()
+            .any(|item| item.description.starts_with("Rename the database")));
+        assert!(plan
+            .items
+            .iter()
+            .any(|item| item.description.contains("the tracks table")));
+
+        let report = run_migration(&paths, &plan, 42).expect("migration runs");
+        assert!(report.not_migrated.is_empty());
+        assert_eq!(report.converted, plan.items);
+        assert!(report.backup_dir.join("playlist.db").exists());
+        assert_eq!(
+            fs::read_to_string(report.backup_dir.join("config.toml")).unwrap(),
+            "[ui]\ndark_mode = false\n"
+        );
+        assert!(!legacy_db.exists());
+        assert!(paths.database_path.exists());
+        assert!(!fs::read_to_string(&paths.config_file)
+            .unwrap()
+            .contains("dark_mode"));
+        assert!(fs::read_to_string(&paths.layout_file)
+            .unwrap()
+            .contains(crate::theme::ROQTUNE_LIGHT_SCHEME_ID));
+        assert_eq!(plan_migration(&paths), MigrationPlan::default());
+    }
+}
@@ -1,9 +1,15 @@
 //! In-memory playlist model used by both editing and playback flows.
 
-use crate::protocol::{PlaybackOrder, RepeatMode};
+use crate::playlist_sort;
+use crate::protocol::{EnergyFlow, PlaybackOrder, RepeatMode, TrackEnergy};
+use crate::track_energy;
+use crate::transition_history::{self, TransitionScores};
 use log::debug;
 use rand::{rngs::StdRng, RngExt, SeedableRng};
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 /// One playlist entry containing source path and stable id.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -26,6 +32,18 @@ pub struct Playlist {
     repeat_mode: RepeatMode,
     shuffled_indices: Vec<usize>,
     shuffle_rank_by_track_index: Vec<usize>,
+    /// Paths left out of shuffle order unless playback starts on them.
+    shuffle_excluded_paths: HashSet<PathBuf>,
+    /// Library folders whose top-level subfolders shuffle-by-folder order plays as units.
+    library_roots: Vec<PathBuf>,
+    /// Shuffle-by-folder unit of each track, parallel to `tracks`.
+    shuffle_folders: Vec<Option<PathBuf>>,
+    /// Energy levels by path, used to order tracks in energy ramp order.
+    energy_by_path: HashMap<PathBuf, TrackEnergy>,
+    /// Arc followed by energy ramp order.
+    energy_ramp_arc: EnergyFlow,
+    /// Learned "plays well after" scores, biasing energy ramp order within a level.
+    transition_scores: TransitionScores,
     // Use StdRng instead of ThreadRng for thread safety
     rng_seed: [u8; 32],
 }
@@ -47,15 +65,23 @@ impl Playlist {
             repeat_mode: RepeatMode::Off,
             shuffled_indices: Vec::new(),
             shuffle_rank_by_track_index: Vec::new(),
+            shuffle_excluded_paths: HashSet::new(),
+            library_roots: Vec::new(),
+            shuffle_folders: Vec::new(),
+            energy_by_path: HashMap::new(),
+            energy_ramp_arc: EnergyFlow::BuildUp,
+            transition_scores: TransitionScores::new(),
             rng_seed: seed,
         }
     }
 
     /// Appends a track to the playlist and updates shuffle order if needed.
     pub fn add_track(&mut self, track: Track) {
+        self.shuffle_folders
+            .push(Self::folder_unit(&self.library_roots, &track.path));
         self.tracks.push(track);
         // Update shuffled indices when adding a new track
-        if self.playback_order == PlaybackOrder::Shuffle {
+        if self.uses_shuffle_order() {
             self.insert_new_track_into_shuffle_order(
                 self.playing_track_index
                     .or(self.selected_indices.first().copied()),
@@ -151,6 +177,22 @@ impl Playlist {
         self.tracks.len()
     }
 
+    /// Replaces track paths found in `remapped`, keeping ids and order. Returns true if
+    /// any track changed.
+    pub fn remap_track_paths(&mut self, remapped: &HashMap<PathBuf, PathBuf>) -> bool {
+        let mut changed = false;
+        for track in &mut self.tracks {
+            if let Some(new_path) = remapped.get(&track.path) {
+                track.path = new_path.clone();
+                changed = true;
+            }
+        }
+        if changed {
+            self.rebuild_shuffle_folders();
+        }
+        changed
+    }
+
     /// Deletes one track and updates selection/playing/shuffle indices accordingly.
     pub fn delete_track(&mut self, index: usize) {
         if index >= self.tracks.len() {
@@ -158,6 +200,7 @@ impl Playlist {
         }
 
         self.tracks.remove(index);
+        self.shuffle_folders.remove(index);
 
         // Update playing track index
         if let Some(playing_idx) = self.playing_track_index {
@@ -248,6 +291,7 @@ impl Playlist {
             }
         }
         self.tracks = new_tracks;
+        self.rebuild_shuffle_folders();
 
         self.selected_indices = old_selected_ids
             .iter()
@@ -269,7 +313,7 @@ impl Playlist {
             self.is_playing = false;
         }
 
-        if self.playback_order == PlaybackOrder::Shuffle {
+        if self.uses_shuffle_order() {
             self.generate_shuffle_order(
                 self.playing_track_index
                     .or(self.selected_indices.first().copied()),
@@ -280,6 +324,18 @@ impl Playlist {
         }
     }
 
+    /// Reorders every track into `order`, a list of current track indices.
+    ///
+    /// Returns `false` and leaves the playlist untouched unless `order` names each track
+    /// exactly once.
+    pub fn sort_tracks(&mut self, order: Vec<usize>) -> bool {
+        if !playlist_sort::is_full_reorder(&order, self.tracks.len()) {
+            return false;
+        }
+        self.apply_filter_view_snapshot(order);
+        true
+    }
+
     /// Moves a set of tracks to the target gap index.
     pub fn move_tracks(&mut self, mut indices: Vec<usize>, to_gap: usize) {
         let len = self.tracks.len();
@@ -311,15 +367,16 @@ impl Playlist {
 
         let mut moved = Vec::with_capacity(block_len);
         for &i in indices.iter().rev() {
-            moved.push(self.tracks.remove(i));
+            moved.push((self.tracks.remove(i), self.shuffle_folders.remove(i)));
         }
         moved.reverse();
 
         let removed_before = indices.iter().filter(|&&i| i < to_gap).count();
         let insert_at = to_gap.saturating_sub(removed_before);
 
-        for (offset, t) in moved.into_iter().enumerate() {
+        for (offset, (t, folder)) in moved.into_iter().enumerate() {
             self.tracks.insert(insert_at + offset, t);
+            self.shuffle_folders.insert(insert_at + offset, folder);
         }
 
         self.selected_indices = (insert_at..insert_at + block_len).collect();
@@ -398,7 +455,7 @@ impl Playlist {
                     None
                 }
             }
-            PlaybackOrder::Shuffle => {
+            PlaybackOrder::Shuffle | PlaybackOrder::ShuffleFolders | PlaybackOrder::EnergyRamp => {
                 if self.shuffled_indices.is_empty() {
                     self.generate_shuffle_order(Some(current_index));
                 }
@@ -462,7 +519,7 @@ impl Playlist {
                     None
                 }
             }
-            PlaybackOrder::Shuffle => {
+            PlaybackOrder::Shuffle | PlaybackOrder::ShuffleFolders | PlaybackOrder::EnergyRamp => {
                 if self.shuffled_indices.is_empty() {
                     self.generate_shuffle_order(Some(current_index));
                 }
@@ -504,13 +561,168 @@ impl Playlist {
         }
     }
 
+    /// Resolves up to `limit` upcoming track indices after `current_index`.
+    ///
+    /// The sequence follows the same order/repeat policy as `get_next_track_index`
+    /// and stops before coming back around to `current_index`. Random order has no
+    /// stable upcoming sequence and yields an empty list.
+    pub fn upcoming_track_indices(&mut self, current_index: usize, limit: usize) -> Vec<usize> {
+        if limit == 0
+            || current_index >= self.tracks.len()
+            || self.playback_order == PlaybackOrder::Random
+        {
+            return Vec::new();
+        }
+        if self.repeat_mode == RepeatMode::Track {
+            return vec![current_index];
+        }
+
+        let mut upcoming = Vec::new();
+        let mut index = current_index;
+        while upcoming.len() < limit {
+            let Some(next_index) = self.get_next_track_index(index) else {
+                break;
+            };
+            if next_index == current_index || next_index >= self.tracks.len() {
+                break;
+            }
+            upcoming.push(next_index);
+            index = next_index;
+        }
+        upcoming
+    }
+
+    /// Moves one upcoming entry so it plays at position `to` instead of `from`.
+    ///
+    /// Positions count from the track right after `current_index` (0 is next). Only
+    /// entries before a repeat-playlist wrap can be moved. Default order reorders
+    /// the tracks themselves; shuffle order only reorders the shuffle sequence.
+    /// Returns `false` when nothing was moved.
+    pub fn move_upcoming_track(&mut self, current_index: usize, from: usize, to: usize) -> bool {
+        if from == to || current_index >= self.tracks.len() {
+            return false;
+        }
+        match self.playback_order {
+            PlaybackOrder::Default => {
+                let first = current_index + 1;
+                let remaining = self.tracks.len() - first;
+                if from >= remaining || to >= remaining {
+                    return false;
+                }
+                let to_gap = if to > from {
+                    first + to + 1
+                } else {
+                    first + to
+                };
+                self.move_tracks(vec![first + from], to_gap);
+                true
+            }
+            PlaybackOrder::Shuffle | PlaybackOrder::ShuffleFolders | PlaybackOrder::EnergyRamp => {
+                if self.shuffle_rank_by_track_index.len() != self.tracks.len() {
+                    self.rebuild_shuffle_rank_index();
+                }
+                let Some(rank) = self
+                    .shuffle_rank_by_track_index
+                    .get(current_index)
+                    .copied()
+                    .filter(|rank| *rank != usize::MAX)
+                else {
+                    return false;
+                };
+                let first = rank + 1;
+                let remaining = self.shuffled_indices.len().saturating_sub(first);
+                if from >= remaining || to >= remaining {
+                    return false;
+                }
+                let moved = self.shuffled_indices.remove(first + from);
+                self.shuffled_indices.insert(first + to, moved);
+                self.rebuild_shuffle_rank_index();
+                true
+            }
+            PlaybackOrder::Random => false,
+        }
+    }
+
+    /// Adds `tracks` so they play right after the playing track, in the given order.
+    ///
+    /// Default and random order insert them after the playing track; shuffle order
+    /// appends them and moves them to the front of the upcoming shuffle sequence.
+    /// Without a playing track they are appended.
+    pub fn insert_tracks_to_play_next(&mut self, tracks: Vec<Track>) {
+        let first_new_index = self.tracks.len();
+        for track in tracks {
+            self.add_track(track);
+        }
+        let new_indices: Vec<usize> = (first_new_index..self.tracks.len()).collect();
+        let Some(current_index) = self
+            .playing_track_index
+            .filter(|index| *index < first_new_index)
+        else {
+            return;
+        };
+        match self.playback_order {
+            PlaybackOrder::Default | PlaybackOrder::Random => {
+                let insert_at = current_index + 1;
+                let selected_indices: Vec<usize> = self
+                    .selected_indices
+                    .iter()
+                    .map(|index| Self::compute_new_index(*index, &new_indices, insert_at))
+                    .collect();
+                self.move_tracks(new_indices, insert_at);
+                self.selected_indices = selected_indices;
+            }
+            PlaybackOrder::Shuffle | PlaybackOrder::ShuffleFolders | PlaybackOrder::EnergyRamp => {
+                for (position, new_index) in new_indices.into_iter().enumerate() {
+                    let upcoming = self.upcoming_track_indices(current_index, usize::MAX);
+                    if let Some(from) = upcoming.iter().position(|index| *index == new_index) {
+                        self.move_upcoming_track(current_index, from, position);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns every track index in the order a saved copy of the queue should list them.
+    ///
+    /// With `resolve_shuffle` set and shuffle order active, tracks follow the shuffle
+    /// sequence, with shuffle-excluded tracks appended in playlist order. Otherwise the
+    /// playlist order is kept.
+    pub fn queue_track_indices(&mut self, resolve_shuffle: bool) -> Vec<usize> {
+        if !resolve_shuffle || !self.uses_shuffle_order() {
+            return (0..self.tracks.len()).collect();
+        }
+        if self.shuffled_indices.is_empty() {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+        let mut indices: Vec<usize> = self
+            .shuffled_indices
+            .iter()
+            .copied()
+            .filter(|&index| index < self.tracks.len())
+            .collect();
+        let in_shuffle: HashSet<usize> = indices.iter().copied().collect();
+        indices.extend((0..self.tracks.len()).filter(|index| !in_shuffle.contains(index)));
+        indices
+    }
+
     /// Forces reshuffle generation when shuffle mode is active.
     pub fn force_re_randomize_shuffle(&mut self) {
-        if self.playback_order == PlaybackOrder::Shuffle {
+        if self.uses_shuffle_order() {
             self.generate_shuffle_order(Some(self.get_selected_track_index()));
         }
     }
 
+    /// Whether the current order plays through `shuffled_indices`.
+    fn uses_shuffle_order(&self) -> bool {
+        matches!(
+            self.playback_order,
+            PlaybackOrder::Shuffle | PlaybackOrder::ShuffleFolders | PlaybackOrder::EnergyRamp
+        )
+    }
+
     fn bump_rng_seed(&mut self) {
         for byte in &mut self.rng_seed {
             *byte = byte.wrapping_add(1);
@@ -538,6 +750,45 @@ impl Playlist {
             self.generate_shuffle_order(first_track_index);
             return;
         }
+        if self.is_shuffle_excluded(new_index) {
+            self.rebuild_shuffle_rank_index();
+            return;
+        }
+        if self.playback_order == PlaybackOrder::ShuffleFolders {
+            // Keep the folder together: join its last queued track, or start a new
+            // folder unit after every existing one.
+            let folder = self.shuffle_folder(new_index);
+            let insert_at = self
+                .shuffled_indices
+                .iter()
+                .rposition(|&index| self.shuffle_folder(index) == folder)
+                .map_or(self.shuffled_indices.len(), |position| position + 1);
+            self.shuffled_indices.insert(insert_at, new_index);
+            self.rebuild_shuffle_rank_index();
+            return;
+        }
+        if self.playback_order == PlaybackOrder::EnergyRamp {
+            // Join the end of the new track's level on a one-way slope; a peak has no
+            // single spot for a level, so the track waits at the end.
+            let level = |index: usize| self.track_energy(index).unwrap_or(TrackEnergy::Steady);
+            let new_level = level(new_index);
+            let queued = &self.shuffled_indices;
+            let insert_at = match self.energy_ramp_arc {
+                EnergyFlow::BuildUp => queued
+                    .iter()
+                    .rposition(|&index| level(index) <= new_level)
+                    .map_or(1, |position| position + 1),
+                EnergyFlow::WindDown => queued
+                    .iter()
+                    .rposition(|&index| level(index) >= new_level)
+                    .map_or(1, |position| position + 1),
+                EnergyFlow::AsPicked | EnergyFlow::Peak => queued.len(),
+            }
+            .min(queued.len());
+            self.shuffled_indices.insert(insert_at, new_index);
+            self.rebuild_shuffle_rank_index();
+            return;
+        }
 
         let mut rng = self.next_rng();
         let min_insert = match first_track_index {
@@ -566,31 +817,231 @@ impl Playlist {
             return;
         }
 
-        let mut indices: Vec<usize> = (0..track_count).collect();
+        if self.playback_order == PlaybackOrder::ShuffleFolders {
+            self.generate_folder_shuffle_order(first_track_index);
+            return;
+        }
+
+        let mut indices: Vec<usize> = (0..track_count)
+            .filter(|&index| !self.is_shuffle_excluded(index))
+            .collect();
         let mut rng = self.next_rng();
 
-        for i in (1..track_count).rev() {
+        for i in (1..indices.len()).rev() {
             let j = rng.random_range(0..=i);
             indices.swap(i, j);
         }
 
-        if let Some(first_idx) = first_track_index {
+        let first_idx = first_track_index.filter(|&index| index < track_count);
+        if let Some(first_idx) = first_idx {
             if let Some(pos) = indices.iter().position(|&i| i == first_idx) {
                 indices.remove(pos);
-                indices.insert(0, first_idx);
             }
         }
+        if self.playback_order == PlaybackOrder::EnergyRamp {
+            // Shuffled first, so tracks of the same level play in random order.
+            let energies: Vec<Option<TrackEnergy>> = indices
+                .iter()
+                .map(|&index| self.track_energy(index))
+                .collect();
+            indices = track_energy::order_by_energy_flow(&energies, self.energy_ramp_arc)
+                .into_iter()
+                .map(|position| indices[position])
+                .collect();
+        }
+        if let Some(first_idx) = first_idx {
+            indices.insert(0, first_idx);
+        }
+        if self.playback_order == PlaybackOrder::EnergyRamp && !self.transition_scores.is_empty() {
+            let level = |index: usize| self.track_energy(index).unwrap_or(TrackEnergy::Steady);
+            transition_history::bias_adjacency(
+                &mut indices,
+                |a, b| level(a) == level(b),
+                |from, to| self.transition_score(from, to),
+            );
+        }
 
         debug!("Playlist: New shuffle sequence: {:?}", indices);
         self.shuffled_indices = indices;
         self.rebuild_shuffle_rank_index();
     }
 
-    /// Sets playback order and initializes shuffle sequence when entering shuffle mode.
+    /// Shuffles whole folders, keeping each folder's tracks in playlist order.
+    ///
+    /// The folder of `first_track_index` plays first, starting at that track; its
+    /// earlier tracks follow the rest of the folder so the folder still plays fully.
+    fn generate_folder_shuffle_order(&mut self, first_track_index: Option<usize>) {
+        let track_count = self.tracks.len();
+        let mut folders: Vec<Vec<usize>> = Vec::new();
+        {
+            let mut folder_position: HashMap<Option<&Path>, usize> = HashMap::new();
+            for index in (0..track_count).filter(|&index| !self.is_shuffle_excluded(index)) {
+                let folder = self.shuffle_folder(index);
+                let position = *folder_position.entry(folder).or_insert_with(|| {
+                    folders.push(Vec::new());
+                    folders.len() - 1
+                });
+                folders[position].push(index);
+            }
+        }
+        let mut rng = self.next_rng();
+
+        for i in (1..folders.len()).rev() {
+            let j = rng.random_range(0..=i);
+            folders.swap(i, j);
+        }
+
+        if let Some(first_idx) = first_track_index.filter(|&index| index < track_count) {
+            let first_folder = self.shuffle_folder(first_idx);
+            let mut first_unit = match folders
+                .iter()
+                .position(|folder| self.shuffle_folder(folder[0]) == first_folder)
+            {
+                Some(position) => folders.remove(position),
+                None => Vec::new(),
+            };
+            first_unit.retain(|&index| index != first_idx);
+            let split = first_unit.partition_point(|&index| index < first_idx);
+            first_unit.rotate_left(split);
+            first_unit.insert(0, first_idx);
+            folders.insert(0, first_unit);
+        }
+
+        let indices: Vec<usize> = folders.into_iter().flatten().collect();
+        debug!("Playlist: New folder shuffle sequence: {:?}", indices);
+        self.shuffled_indices = indices;
+        self.rebuild_shuffle_rank_index();
+    }
+
+    /// Folder that shuffle-by-folder order keeps together for the track at `index`.
+    fn shuffle_folder(&self, index: usize) -> Option<&Path> {
+        self.shuffle_folders[index].as_deref()
+    }
+
+    fn rebuild_shuffle_folders(&mut self) {
+        self.shuffle_folders = self
+            .tracks
+            .iter()
+            .map(|track| Self::folder_unit(&self.library_roots, &track.path))
+            .collect();
+    }
+
+    /// The first folder below the deepest library root containing `path`, so disc
+    /// subfolders stay with their album. Tracks outside the library group by their
+    /// parent folder.
+    fn folder_unit(library_roots: &[PathBuf], path: &Path) -> Option<PathBuf> {
+        library_roots
+            .iter()
+            .filter_map(|root| Some((root, path.strip_prefix(root).ok()?)))
+            .max_by_key(|(root, _)| root.components().count())
+            .and_then(|(root, relative)| {
+                let mut components = relative.components();
+                let top_level = components.next()?;
+                // A track directly in the root has no top-level folder of its own.
+                components.next().map(|_| root.join(top_level))
+            })
+            .or_else(|| path.parent().map(Path::to_path_buf))
+    }
+
+    fn is_shuffle_excluded(&self, index: usize) -> bool {
+        !self.shuffle_excluded_paths.is_empty()
+            && self
+                .tracks
+                .get(index)
+                .is_some_and(|track| self.shuffle_excluded_paths.contains(&track.path))
+    }
+
+    fn track_energy(&self, index: usize) -> Option<TrackEnergy> {
+        self.tracks
+            .get(index)
+            .and_then(|track| self.energy_by_path.get(&track.path))
+            .copied()
+    }
+
+    fn transition_score(&self, from: usize, to: usize) -> i32 {
+        self.transition_scores
+            .get(&self.tracks[from].path)
+            .and_then(|scores| scores.get(&self.tracks[to].path))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Replaces the learned transition scores and reorders when energy ramp order is active.
+    pub fn set_transition_scores(&mut self, transition_scores: TransitionScores) {
+        if self.transition_scores == transition_scores {
+            return;
+        }
+        self.transition_scores = transition_scores;
+        if self.playback_order == PlaybackOrder::EnergyRamp {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+    }
+
+    /// Replaces the known energy levels and reorders when energy ramp order is active.
+    pub fn set_track_energy_levels(&mut self, energy_by_path: HashMap<PathBuf, TrackEnergy>) {
+        if self.energy_by_path == energy_by_path {
+            return;
+        }
+        self.energy_by_path = energy_by_path;
+        if self.playback_order == PlaybackOrder::EnergyRamp {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+    }
+
+    /// Sets the arc followed by energy ramp order, reordering when it is active.
+    pub fn set_energy_ramp_arc(&mut self, arc: EnergyFlow) {
+        if self.energy_ramp_arc == arc {
+            return;
+        }
+        self.energy_ramp_arc = arc;
+        if self.playback_order == PlaybackOrder::EnergyRamp {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+    }
+
+    /// Replaces the paths left out of shuffle order and reshuffles when they changed.
+    pub fn set_shuffle_excluded_paths(&mut self, paths: HashSet<PathBuf>) {
+        if self.shuffle_excluded_paths == paths {
+            return;
+        }
+        self.shuffle_excluded_paths = paths;
+        if self.uses_shuffle_order() {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+    }
+
+    /// Replaces the library folders and reshuffles when shuffle-by-folder order is active.
+    pub fn set_library_roots(&mut self, roots: Vec<PathBuf>) {
+        if self.library_roots == roots {
+            return;
+        }
+        self.library_roots = roots;
+        self.rebuild_shuffle_folders();
+        if self.playback_order == PlaybackOrder::ShuffleFolders {
+            self.generate_shuffle_order(
+                self.playing_track_index
+                    .or(self.selected_indices.first().copied()),
+            );
+        }
+    }
+
+    /// Sets playback order and initializes shuffle sequence when entering a shuffle mode.
     pub fn set_playback_order(&mut self, order: PlaybackOrder) {
         if self.playback_order != order {
             self.playback_order = order;
-            if order == PlaybackOrder::Shuffle {
+            if self.uses_shuffle_order() {
                 self.generate_shuffle_order(
                     self.playing_track_index
                         .or(self.selected_indices.first().copied()),
@@ -1552,6 +2003,22 @@ mod tests {
         assert_eq!(playlist.get_selected_indices(), vec![0, 1]);
     }
 
+    #[test]
+    fn test_sort_tracks_reorders_only_complete_orders() {
+        let mut playlist = Playlist::new();
+        playlist.add_track(make_track("A"));
+        playlist.add_track(make_track("B"));
+        playlist.add_track(make_track("C"));
+        playlist.set_playing_track_index(Some(0));
+
+        assert!(!playlist.sort_tracks(vec![2, 1]));
+        assert_order(&playlist, vec!["A", "B", "C"]);
+
+        assert!(playlist.sort_tracks(vec![2, 0, 1]));
+        assert_order(&playlist, vec!["C", "A", "B"]);
+        assert_eq!(playlist.get_playing_track_index(), Some(1));
+    }
+
     #[test]
     fn test_apply_filter_view_snapshot_stops_when_playing_track_removed() {
         let mut playlist = Playlist::new();
@@ -1638,6 +2105,209 @@ mod tests {
         assert_eq!(next_3, None);
     }
 
+    #[test]
+    fn test_shuffle_order_skips_excluded_tracks_unless_started_on_them() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_shuffle_excluded_paths(HashSet::from([
+            PathBuf::from("/music/B"),
+            PathBuf::from("/music/D"),
+        ]));
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::Shuffle);
+
+        assert_eq!(playlist.upcoming_track_indices(0, 10), vec![2]);
+
+        playlist.add_track(make_track("E"));
+        let mut upcoming = playlist.upcoming_track_indices(0, 10);
+        upcoming.sort_unstable();
+        assert_eq!(upcoming, vec![2, 4]);
+
+        // Playback started on an excluded track still continues through the shuffle.
+        assert!(playlist.get_next_track_index(1).is_some());
+        assert!(!playlist.upcoming_track_indices(1, 10).contains(&3));
+    }
+
+    #[test]
+    fn test_shuffle_folders_keeps_disc_folders_with_their_album() {
+        let mut playlist = Playlist::new();
+        playlist.set_library_roots(vec![PathBuf::from("/music")]);
+        for (folder, name) in [
+            ("Album/CD1", "1"),
+            ("Single", "1"),
+            ("Album/CD2", "1"),
+            ("Album/CD1", "2"),
+            ("Single", "2"),
+            ("Album/CD2", "2"),
+        ] {
+            playlist.add_track(Track {
+                path: PathBuf::from(format!("/music/{}/{}", folder, name)),
+                id: format!("{}/{}", folder, name),
+            });
+        }
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::ShuffleFolders);
+
+        let sequence: Vec<&str> = std::iter::once(0)
+            .chain(playlist.upcoming_track_indices(0, 10))
+            .map(|index| playlist.get_track(index).id.as_str())
+            .collect();
+        assert_eq!(
+            sequence,
+            [
+                "Album/CD1/1",
+                "Album/CD2/1",
+                "Album/CD1/2",
+                "Album/CD2/2",
+                "Single/1",
+                "Single/2",
+            ]
+        );
+
+        // A new disc track joins the end of its album's run.
+        playlist.add_track(Track {
+            path: PathBuf::from("/music/Album/CD3/1"),
+            id: "Album/CD3/1".to_string(),
+        });
+        let queue: Vec<&str> = playlist
+            .queue_track_indices(true)
+            .iter()
+            .map(|&index| playlist.get_track(index).id.as_str())
+            .collect();
+        assert_eq!(queue[4], "Album/CD3/1");
+
+        // Without library folders, each disc folder is its own unit.
+        playlist.set_library_roots(Vec::new());
+        let sequence: Vec<&str> = std::iter::once(0)
+            .chain(playlist.upcoming_track_indices(0, 10))
+            .map(|index| playlist.get_track(index).id.as_str())
+            .collect();
+        assert_eq!(&sequence[..2], &["Album/CD1/1", "Album/CD1/2"]);
+    }
+
+    #[test]
+    fn test_shuffle_folders_plays_each_folder_fully_before_the_next() {
+        let mut playlist = Playlist::new();
+        for (folder, name) in [
+            ("a", "1"),
+            ("b", "1"),
+            ("a", "2"),
+            ("c", "1"),
+            ("b", "2"),
+            ("a", "3"),
+        ] {
+            playlist.add_track(Track {
+                path: PathBuf::from(format!("/music/{}/{}", folder, name)),
+                id: format!("{}{}", folder, name),
+            });
+        }
+        playlist.set_selected_indices(vec![2]);
+        playlist.set_playback_order(PlaybackOrder::ShuffleFolders);
+
+        let sequence: Vec<usize> = std::iter::once(2)
+            .chain(playlist.upcoming_track_indices(2, 10))
+            .collect();
+        assert_eq!(sequence.len(), 6);
+        // Folder "a" starts at the selected track and finishes before another folder.
+        assert_eq!(&sequence[..3], &[2, 5, 0]);
+        let rest: Vec<&str> = sequence[3..]
+            .iter()
+            .map(|&index| playlist.get_track(index).id.as_str())
+            .collect();
+        assert!(rest == ["b1", "b2", "c1"] || rest == ["c1", "b1", "b2"]);
+
+        playlist.add_track(Track {
+            path: PathBuf::from("/music/b/3"),
+            id: "b3".to_string(),
+        });
+        let b_positions: Vec<usize> = playlist
+            .queue_track_indices(true)
+            .iter()
+            .enumerate()
+            .filter(|(_, &index)| playlist.get_track(index).id.starts_with('b'))
+            .map(|(position, _)| position)
+            .collect();
+        assert_eq!(b_positions.len(), 3);
+        assert_eq!(b_positions[2] - b_positions[0], 2);
+    }
+
+    #[test]
+    fn test_shuffle_folders_follow_moved_and_deleted_tracks() {
+        let mut playlist = Playlist::new();
+        for id in ["a/1", "b/1", "a/2", "c/1"] {
+            playlist.add_track(Track {
+                path: PathBuf::from(format!("/music/{}", id)),
+                id: id.to_string(),
+            });
+        }
+        playlist.move_tracks(vec![1], 4);
+        playlist.delete_track(2);
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::ShuffleFolders);
+
+        let sequence: Vec<&str> = std::iter::once(0)
+            .chain(playlist.upcoming_track_indices(0, 10))
+            .map(|index| playlist.get_track(index).id.as_str())
+            .collect();
+        assert_eq!(sequence, ["a/1", "a/2", "b/1"]);
+    }
+
+    #[test]
+    fn test_energy_ramp_follows_the_arc_after_the_first_track() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_track_energy_levels(HashMap::from([
+            (PathBuf::from("/music/A"), TrackEnergy::Steady),
+            (PathBuf::from("/music/B"), TrackEnergy::Intense),
+            (PathBuf::from("/music/C"), TrackEnergy::Calm),
+            (PathBuf::from("/music/D"), TrackEnergy::Upbeat),
+        ]));
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::EnergyRamp);
+
+        // E has no level and ramps as Steady, level with A.
+        let upcoming = playlist.upcoming_track_indices(0, 10);
+        assert_eq!(upcoming.first(), Some(&2));
+        assert_eq!(&upcoming[2..], &[3, 1]);
+
+        playlist.set_energy_ramp_arc(EnergyFlow::WindDown);
+        let upcoming = playlist.upcoming_track_indices(0, 10);
+        assert_eq!(&upcoming[..2], &[1, 3]);
+        assert_eq!(upcoming.last(), Some(&2));
+
+        // A new unrated track joins the Steady tracks, ahead of the calm finish.
+        playlist.add_track(make_track("F"));
+        let upcoming = playlist.upcoming_track_indices(0, 10);
+        assert_eq!(upcoming.len(), 5);
+        assert_eq!(&upcoming[3..], &[5, 2]);
+    }
+
+    #[test]
+    fn test_energy_ramp_prefers_learned_successors_of_the_same_level() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_transition_scores(TransitionScores::from([
+            (
+                PathBuf::from("/music/A"),
+                HashMap::from([(PathBuf::from("/music/D"), 3)]),
+            ),
+            (
+                PathBuf::from("/music/D"),
+                HashMap::from([(PathBuf::from("/music/B"), 1)]),
+            ),
+        ]));
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::EnergyRamp);
+
+        assert_eq!(playlist.upcoming_track_indices(0, 10), vec![3, 1, 2]);
+    }
+
     #[test]
     fn test_random_order_picks_different_track_when_multiple_tracks_exist() {
         let mut playlist = Playlist::new();
@@ -1647,4 +2317,95 @@ mod tests {
 
         assert_eq!(playlist.get_next_track_index(0), Some(1));
     }
+
+    #[test]
+    fn test_upcoming_track_indices_follow_default_order_and_wrap_before_current() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+
+        assert_eq!(playlist.upcoming_track_indices(1, 10), vec![2, 3]);
+        assert_eq!(playlist.upcoming_track_indices(0, 2), vec![1, 2]);
+
+        playlist.set_repeat_mode(RepeatMode::Playlist);
+        assert_eq!(playlist.upcoming_track_indices(2, 10), vec![3, 0, 1]);
+    }
+
+    #[test]
+    fn test_upcoming_track_indices_match_shuffle_sequence() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::Shuffle);
+
+        let upcoming = playlist.upcoming_track_indices(0, 10);
+        let next_1 = playlist.get_next_track_index(0).unwrap();
+        let next_2 = playlist.get_next_track_index(next_1).unwrap();
+        let next_3 = playlist.get_next_track_index(next_2).unwrap();
+        assert_eq!(upcoming, vec![next_1, next_2, next_3]);
+    }
+
+    #[test]
+    fn test_queue_track_indices_follow_shuffle_sequence_only_when_requested() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+        assert_eq!(playlist.queue_track_indices(true), vec![0, 1, 2, 3]);
+
+        playlist.set_shuffle_excluded_paths(HashSet::from([PathBuf::from("/music/C")]));
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::Shuffle);
+        assert_eq!(playlist.queue_track_indices(false), vec![0, 1, 2, 3]);
+
+        let mut expected = vec![0];
+        expected.extend(playlist.upcoming_track_indices(0, 10));
+        expected.push(2);
+        assert_eq!(playlist.queue_track_indices(true), expected);
+    }
+
+    #[test]
+    fn test_move_upcoming_track_reorders_default_and_shuffle_sequences() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C", "D"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_playing_track_index(Some(0));
+
+        assert!(playlist.move_upcoming_track(0, 2, 0));
+        assert_order(&playlist, vec!["A", "D", "B", "C"]);
+        assert_eq!(playlist.get_playing_track_index(), Some(0));
+        assert!(!playlist.move_upcoming_track(0, 3, 0));
+
+        playlist.set_selected_indices(vec![0]);
+        playlist.set_playback_order(PlaybackOrder::Shuffle);
+        let before = playlist.upcoming_track_indices(0, 10);
+        assert!(playlist.move_upcoming_track(0, 0, 2));
+        assert_eq!(
+            playlist.upcoming_track_indices(0, 10),
+            vec![before[1], before[2], before[0]]
+        );
+    }
+
+    #[test]
+    fn test_insert_tracks_to_play_next_follows_playing_track() {
+        let mut playlist = Playlist::new();
+        for id in ["A", "B", "C"] {
+            playlist.add_track(make_track(id));
+        }
+        playlist.set_playing_track_index(Some(1));
+        playlist.set_selected_indices(vec![2]);
+
+        playlist.insert_tracks_to_play_next(vec![make_track("X"), make_track("Y")]);
+        assert_order(&playlist, vec!["A", "B", "X", "Y", "C"]);
+        assert_eq!(playlist.get_playing_track_index(), Some(1));
+        assert_selected(&playlist, vec![4]);
+
+        playlist.set_playback_order(PlaybackOrder::Shuffle);
+        playlist.insert_tracks_to_play_next(vec![make_track("Z")]);
+        assert_eq!(playlist.upcoming_track_indices(1, 1), vec![5]);
+    }
 }
@@ -0,0 +1,256 @@
+//! Per-column playlist filters.
+//!
+//! Each playlist keeps at most one filter per column, edited from the column header's
+//! "Filter..." entry: a set of cell values to keep (Artist is "Abba" or "Blur"), a
+//! comparison (Year ≥ 2010), or both. Filters narrow the playlist view together with
+//! the search query and are stored with the playlist, so they come back when the
+//! playlist is opened again.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnFilterOperator {
+    Contains,
+    Equals,
+    NotEquals,
+    AtLeast,
+    AtMost,
+}
+
+/// Operators in the order the filter dialog lists them.
+pub const FILTER_OPERATORS: [ColumnFilterOperator; 5] = [
+    ColumnFilterOperator::Contains,
+    ColumnFilterOperator::Equals,
+    ColumnFilterOperator::NotEquals,
+    ColumnFilterOperator::AtLeast,
+    ColumnFilterOperator::AtMost,
+];
+
+pub fn operator_label(operator: ColumnFilterOperator) -> &'static str {
+    match operator {
+        ColumnFilterOperator::Contains => "contains",
+        ColumnFilterOperator::Equals => "is",
+        ColumnFilterOperator::NotEquals => "is not",
+        ColumnFilterOperator::AtLeast => "≥",
+        ColumnFilterOperator::AtMost => "≤",
+    }
+}
+
+/// A comparison against a typed operand.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ColumnFilterCondition {
+    pub operator: ColumnFilterOperator,
+    pub operand: String,
+}
+
+/// Reads `cell` as a number: a plain number (`2010`, `-7.5 LUFS`) or a duration
+/// (`3:45`, `1:02:03`) in seconds.
+fn numeric_value(cell: &str) -> Option<f64> {
+    let trimmed = cell.trim();
+    if trimmed.contains(':') {
+        return trimmed.split(':').try_fold(0.0, |total, part| {
+            (!part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit()))
+                .then(|| total * 60.0 + part.parse::<f64>().unwrap_or_default())
+        });
+    }
+    let end = trimmed
+        .char_indices()
+        .find(|&(index, ch)| !(ch.is_ascii_digit() || ch == '.' || (index == 0 && ch == '-')))
+        .map_or(trimmed.len(), |(index, _)| index);
+    trimmed[..end].parse().ok()
+}
+
+/// Compares numerically when both sides read as numbers, otherwise as text ignoring
+/// case.
+fn compare_cell(cell: &str, operand: &str) -> Ordering {
+    match (numeric_value(cell), numeric_value(operand)) {
+        (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs).unwrap_or(Ordering::Equal),
+        _ => cell
+            .trim()
+            .to_lowercase()
+            .cmp(&operand.trim().to_lowercase()),
+    }
+}
+
+impl ColumnFilterCondition {
+    pub fn matches(&self, cell: &str) -> bool {
+        let operand = self.operand.trim();
+        match self.operator {
+            ColumnFilterOperator::Contains => cell.to_lowercase().contains(&operand.to_lowercase()),
+            ColumnFilterOperator::Equals => compare_cell(cell, operand).is_eq(),
+            ColumnFilterOperator::NotEquals => compare_cell(cell, operand).is_ne(),
+            // Blank cells have no value to compare, so ranges leave them out.
+            ColumnFilterOperator::AtLeast => {
+                !cell.trim().is_empty() && compare_cell(cell, operand).is_ge()
+            }
+            ColumnFilterOperator::AtMost => {
+                !cell.trim().is_empty() && compare_cell(cell, operand).is_le()
+            }
+        }
+    }
+}
+
+/// The filter of one playlist column, keyed like the column's width and style.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct PlaylistColumnFilter {
+    pub column_key: String,
+    /// Cell texts to keep; empty keeps every value.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<ColumnFilterCondition>,
+}
+
+impl PlaylistColumnFilter {
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.condition.is_none()
+    }
+
+    pub fn matches(&self, cell: &str) -> bool {
+        (self.values.is_empty() || self.values.iter().any(|value| value == cell))
+            && self
+                .condition
+                .as_ref()
+                .is_none_or(|condition| condition.matches(cell))
+    }
+
+    /// Short description for the filter summary, e.g. `Year ≥ 2010`.
+    pub fn summary(&self, column_name: &str) -> String {
+        let mut parts = Vec::new();
+        match self.values.as_slice() {
+            [] => {}
+            [value] => parts.push(format!("is \"{value}\"")),
+            values => parts.push(format!("is one of {} values", values.len())),
+        }
+        if let Some(condition) = &self.condition {
+            parts.push(format!(
+                "{} {}",
+                operator_label(condition.operator),
+                condition.operand.trim()
+            ));
+        }
+        format!("{} {}", column_name, parts.join(" and "))
+    }
+}
+
+/// Replaces the filter for `filter.column_key`, dropping it when it is empty.
+pub fn set_filter(filters: &mut Vec<PlaylistColumnFilter>, filter: PlaylistColumnFilter) {
+    let position = filters
+        .iter()
+        .position(|existing| existing.column_key == filter.column_key);
+    match (position, filter.is_empty()) {
+        (Some(position), true) => {
+            filters.remove(position);
+        }
+        (Some(position), false) => filters[position] = filter,
+        (None, true) => {}
+        (None, false) => filters.push(filter),
+    }
+}
+
+/// Counts how often each cell text occurs, for the dialog's value checklist. Values
+/// are listed in natural order, numbers by value.
+pub fn distinct_values<'a>(cells: impl IntoIterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for cell in cells {
+        *counts.entry(cell).or_default() += 1;
+    }
+    let mut values: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    values.sort_by(|(lhs, _), (rhs, _)| compare_cell(lhs, rhs).then_with(|| lhs.cmp(rhs)));
+    values
+}
+
+/// Stored form of a playlist's filters in the database.
+pub fn serialize_filters(filters: &[PlaylistColumnFilter]) -> Option<String> {
+    if filters.is_empty() {
+        return None;
+    }
+    serde_json::to_string(filters).ok()
+}
+
+/// Reads filters stored by [`serialize_filters`]; unreadable text yields no filters.
+pub fn parse_filters(text: Option<&str>) -> Vec<PlaylistColumnFilter> {
+    text.and_then(|text| serde_json::from_str(text).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(
+        values: &[&str],
+        condition: Option<(ColumnFilterOperator, &str)>,
+    ) -> PlaylistColumnFilter {
+        PlaylistColumnFilter {
+            column_key: "{artist}".to_string(),
+            values: values.iter().map(|value| value.to_string()).collect(),
+            condition: condition.map(|(operator, operand)| ColumnFilterCondition {
+                operator,
+                operand: operand.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_value_and_condition_filters_match_cells() {
+        let artists = filter(&["Abba", "Blur"], None);
+        assert!(artists.matches("Blur"));
+        assert!(!artists.matches("Oasis"));
+
+        let since_2010 = filter(&[], Some((ColumnFilterOperator::AtLeast, "2010")));
+        assert!(since_2010.matches("2010"));
+        assert!(since_2010.matches("2015-06-01"));
+        assert!(!since_2010.matches("1999"));
+        assert!(!since_2010.matches(""));
+
+        let short = filter(&[], Some((ColumnFilterOperator::AtMost, "3:00")));
+        assert!(short.matches("2:59"));
+        assert!(!short.matches("10:01"));
+
+        let both = filter(&["Blur"], Some((ColumnFilterOperator::Contains, "LU")));
+        assert!(both.matches("Blur"));
+        assert!(!both.matches("Abba"));
+    }
+
+    #[test]
+    fn test_set_filter_replaces_and_drops_empty_filters() {
+        let mut filters = Vec::new();
+        set_filter(&mut filters, filter(&["Abba"], None));
+        set_filter(&mut filters, filter(&["Blur"], None));
+        assert_eq!(filters, vec![filter(&["Blur"], None)]);
+        set_filter(&mut filters, filter(&[], None));
+        assert!(filters.is_empty());
+    }
+
+    #[test]
+    fn test_distinct_values_count_and_order_numbers_by_value() {
+        assert_eq!(
+            distinct_values(["10", "9", "10", ""]),
+            vec![
+                ("".to_string(), 1),
+                ("9".to_string(), 1),
+                ("10".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filters_round_trip_through_storage_text() {
+        let filters = vec![filter(
+            &["Abba"],
+            Some((ColumnFilterOperator::NotEquals, "x")),
+        )];
+        assert_eq!(
+            parse_filters(serialize_filters(&filters).as_deref()),
+            filters
+        );
+        assert_eq!(serialize_filters(&[]), None);
+        assert!(parse_filters(Some("not json")).is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+//! Formatted playlist documents for sharing set lists or archiving.
+//!
+//! The document holds the playlist as the track list shows it: the chosen columns with
+//! their rendered text, rows in view order. It is written as a standalone HTML page
+//! (cover art embedded, laid out for printing or saving as PDF from a browser), as a
+//! Markdown table, or as aligned plain text.
+
+use std::path::PathBuf;
+
+use crate::base64_encoding::base64_encode;
+
+/// Output format of an exported playlist document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistDocumentFormat {
+    Html,
+    Markdown,
+    PlainText,
+}
+
+impl PlaylistDocumentFormat {
+    /// Formats in the order the export dialog lists them.
+    pub const ALL: [Self; 3] = [Self::Html, Self::Markdown, Self::PlainText];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Markdown => "Markdown",
+            Self::PlainText => "Plain text",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "md",
+            Self::PlainText => "txt",
+        }
+    }
+}
+
+/// Cover image shown in the document header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentCover {
+    pub path: PathBuf,
+    pub bytes: Vec<u8>,
+}
+
+/// A playlist ready to be written as a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaylistDocument {
+    pub title: String,
+    pub note: String,
+    pub columns: Vec<String>,
+    /// Cell text per track, one entry per column.
+    pub rows: Vec<Vec<String>>,
+    pub covers: Vec<DocumentCover>,
+}
+
+/// Writes `document` in `format`.
+pub fn render_document(document: &PlaylistDocument, format: PlaylistDocumentFormat) -> String {
+    match format {
+        PlaylistDocumentFormat::Html => render_html(document),
+        PlaylistDocumentFormat::Markdown => render_markdown(document),
+        PlaylistDocumentFormat::PlainText => render_plain_text(document),
+    }
+}
+
+fn track_count_text(document: &PlaylistDocument) -> String {
+    match document.rows.len() {
+        1 => "1 track".to_string(),
+        count => format!("{count} tracks"),
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn image_mime_type(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        "image/webp"
+    } else if bytes.starts_with(b"GIF8") {
+        "image/gif"
+    } else {
+        "image/jpeg"
+    }
+}
+
+fn render_html(document: &PlaylistDocument) -> String {
+    let title = html_escape(&document.title);
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{title}</title>\n"));
+    html.push_str(
+        "<style>\n\
+         body { font-family: sans-serif; margin: 2em; color: #222; }\n\
+         header { display: flex; gap: 1.5em; align-items: flex-end; margin-bottom: 1.5em; }\n\
+         .covers { display: flex; gap: 0.5em; }\n\
+         .covers img { width: 140px; height: 140px; object-fit: cover; border-radius: 4px; }\n\
+         h1 { margin: 0 0 0.3em; }\n\
+         .meta { color: #666; margin: 0.2em 0; white-space: pre-wrap; }\n\
+         table { border-collapse: collapse; width: 100%; font-size: 0.9em; }\n\
+         th, td { text-align: left; padding: 0.35em 0.6em; border-bottom: 1px solid #ddd; }\n\
+         th { border-bottom: 2px solid #999; }\n\
+         @media print { body { margin: 0; } tr { page-break-inside: avoid; } }\n\
+         </style>\n</head>\n<body>\n<header>\n",
+    );
+    if !document.covers.is_empty() {
+        html.push_str("<div class=\"covers\">\n");
+        for cover in &document.covers {
+            html.push_str(&format!(
+                "<img alt=\"\" src=\"data:{};base64,{}\">\n",
+                image_mime_type(&cover.bytes),
+                base64_encode(&cover.bytes)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str(&format!("<div>\n<h1>{title}</h1>\n"));
+    if !document.note.trim().is_empty() {
+        html.push_str(&format!(
+            "<p class=\"meta\">{}</p>\n",
+            html_escape(document.note.trim())
+        ));
+    }
+    html.push_str(&format!(
+        "<p class=\"meta\">{}</p>\n</div>\n</header>\n",
+        track_count_text(document)
+    ));
+    html.push_str("<table>\n<thead>\n<tr>");
+    for column in &document.columns {
+        html.push_str(&format!("<th>{}</th>", html_escape(column)));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+    for row in &document.rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", html_escape(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+fn render_markdown(document: &PlaylistDocument) -> String {
+    let mut markdown = format!("# {}\n\n", document.title.trim());
+    for cover in &document.covers {
+        markdown.push_str(&format!("![Cover](<{}>)\n", cover.path.to_string_lossy()));
+    }
+    if !document.covers.is_empty() {
+        markdown.push('\n');
+    }
+    if !document.note.trim().is_empty() {
+        markdown.push_str(&format!("{}\n\n", document.note.trim()));
+    }
+    markdown.push_str(&format!("_{}_\n\n", track_count_text(document)));
+    if document.columns.is_empty() {
+        return markdown;
+    }
+    let header: Vec<String> = document
+        .columns
+        .iter()
+        .map(|column| markdown_cell(column))
+        .collect();
+    markdown.push_str(&format!("| {} |\n", header.join(" | ")));
+    markdown.push_str(&format!("|{}\n", " --- |".repeat(document.columns.len())));
+    for row in &document.rows {
+        let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+        markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    markdown
+}
+
+fn render_plain_text(document: &PlaylistDocument) -> String {
+    let title = document.title.trim();
+    let mut text = format!("{title}\n{}\n", "=".repeat(title.chars().count().max(1)));
+    if !document.note.trim().is_empty() {
+        text.push_str(&format!("{}\n", document.note.trim()));
+    }
+    text.push_str(&format!("{}\n\n", track_count_text(document)));
+    let mut widths: Vec<usize> = document
+        .columns
+        .iter()
+        .map(|column| column.chars().count())
+        .collect();
+    for row in &document.rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    text.push_str(&format_line(&document.columns));
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    text.push_str(&format_line(&rule));
+    for row in &document.rows {
+        text.push_str(&format_line(row));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_document() -> PlaylistDocument {
+        PlaylistDocument {
+            title: "Friday <Set>".to_string(),
+            note: "Opening slot".to_string(),
+            columns: vec!["#".to_string(), "Title".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "Intro | Reprise".to_string()],
+                vec!["2".to_string(), "Long Title & More".to_string()],
+            ],
+            covers: vec![DocumentCover {
+                path: PathBuf::from("/music/cover.png"),
+                bytes: b"\x89PNG....".to_vec(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_html_escapes_text_and_embeds_covers() {
+        let html = render_document(&sample_document(), PlaylistDocumentFormat::Html);
+        assert!(html.contains("<h1>Friday &lt;Set&gt;</h1>"));
+        assert!(html.contains("<td>Long Title &amp; More</td>"));
+        assert!(html.contains("<th>#</th><th>Title</th>"));
+        assert!(html.contains("src=\"data:image/png;base64,iVBORy4uLi4=\""));
+        assert!(html.contains("2 tracks"));
+    }
+
+    #[test]
+    fn test_render_markdown_builds_table_with_escaped_pipes() {
+        let markdown = render_document(&sample_document(), PlaylistDocumentFormat::Markdown);
+        assert!(markdown.starts_with("# Friday <Set>\n\n![Cover](</music/cover.png>)\n"));
+        assert!(markdown.contains("| # | Title |\n| --- | --- |\n"));
+        assert!(markdown.contains("| 1 | Intro \\| Reprise |\n"));
+    }
+
+    #[test]
+    fn test_render_plain_text_aligns_columns() {
+        let text = render_document(&sample_document(), PlaylistDocumentFormat::PlainText);
+        assert!(text.starts_with("Friday <Set>\n============\nOpening slot\n2 tracks\n\n"));
+        assert!(text.contains("#  Title\n-  -----------------\n1  Intro | Reprise\n"));
+    }
+}
@@ -0,0 +1,332 @@
+//! Sidebar tree of playlists organized in nested folders.
+//!
+//! Folders and playlists each store the id of the folder containing them. The sidebar
+//! shows the tree flattened into rows, folders before playlists at every level; a
+//! collapsed folder hides everything below it. Parents that no longer exist, or that
+//! would make a folder contain itself, are treated as the top level so a damaged tree
+//! still shows every playlist.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::protocol::{PlaylistFolderInfo, PlaylistInfo};
+
+/// What one sidebar row shows, as an index into the folder or playlist list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistTreeItem {
+    Folder(usize),
+    Playlist(usize),
+}
+
+/// One visible row of the flattened sidebar tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaylistTreeRow {
+    pub item: PlaylistTreeItem,
+    pub depth: usize,
+    /// Folder the row is shown in, as an index into the folder list.
+    pub parent_folder: Option<usize>,
+    /// Whether a folder row shows its contents; always `false` for playlists.
+    pub expanded: bool,
+}
+
+/// A drag-and-drop move resolved to ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistTreeMove {
+    Playlist {
+        playlist_id: String,
+        folder_id: Option<String>,
+    },
+    Folder {
+        folder_id: String,
+        parent_id: Option<String>,
+    },
+}
+
+/// Whether making `parent_id` the parent of `folder_id` would put the folder inside itself.
+pub fn would_create_cycle(
+    folders: &[PlaylistFolderInfo],
+    folder_id: &str,
+    parent_id: Option<&str>,
+) -> bool {
+    let parents: HashMap<&str, Option<&str>> = folders
+        .iter()
+        .map(|folder| (folder.id.as_str(), folder.parent_id.as_deref()))
+        .collect();
+    let mut visited = HashSet::new();
+    let mut current = parent_id;
+    while let Some(id) = current {
+        if id == folder_id {
+            return true;
+        }
+        if !visited.insert(id) {
+            return false;
+        }
+        current = parents.get(id).copied().flatten();
+    }
+    false
+}
+
+/// Parent folder index of every folder as shown in the tree.
+fn shown_folder_parents(folders: &[PlaylistFolderInfo]) -> Vec<Option<usize>> {
+    let index_by_id: HashMap<&str, usize> = folders
+        .iter()
+        .enumerate()
+        .map(|(index, folder)| (folder.id.as_str(), index))
+        .collect();
+    folders
+        .iter()
+        .map(|folder| {
+            let parent_id = folder.parent_id.as_deref()?;
+            let parent_index = *index_by_id.get(parent_id)?;
+            (!would_create_cycle(folders, &folder.id, Some(parent_id))).then_some(parent_index)
+        })
+        .collect()
+}
+
+/// Flattens the tree into visible rows. `collapsed` holds ids of folders whose contents
+/// are hidden.
+pub fn build_tree_rows(
+    playlists: &[PlaylistInfo],
+    folders: &[PlaylistFolderInfo],
+    collapsed: &HashSet<String>,
+) -> Vec<PlaylistTreeRow> {
+    let folder_parents = shown_folder_parents(folders);
+    let index_by_id: HashMap<&str, usize> = folders
+        .iter()
+        .enumerate()
+        .map(|(index, folder)| (folder.id.as_str(), index))
+        .collect();
+    let mut children: HashMap<Option<usize>, Vec<PlaylistTreeItem>> = HashMap::new();
+    for (index, parent) in folder_parents.iter().enumerate() {
+        children
+            .entry(*parent)
+            .or_default()
+            .push(PlaylistTreeItem::Folder(index));
+    }
+    for (index, playlist) in playlists.iter().enumerate() {
+        let parent = playlist
+            .parent_id
+            .as_deref()
+            .and_then(|id| index_by_id.get(id).copied());
+        children
+            .entry(parent)
+            .or_default()
+            .push(PlaylistTreeItem::Playlist(index));
+    }
+
+    let mut rows = Vec::new();
+    append_rows(&children, folders, collapsed, None, 0, &mut rows);
+    rows
+}
+
+fn append_rows(
+    children: &HashMap<Option<usize>, Vec<PlaylistTreeItem>>,
+    folders: &[PlaylistFolderInfo],
+    collapsed: &HashSet<String>,
+    parent_folder: Option<usize>,
+    depth: usize,
+    rows: &mut Vec<PlaylistTreeRow>,
+) {
+    let Some(items) = children.get(&parent_folder) else {
+        return;
+    };
+    for item in items {
+        match *item {
+            PlaylistTreeItem::Folder(index) => {
+                let expanded = !collapsed.contains(&folders[index].id);
+                rows.push(PlaylistTreeRow {
+                    item: *item,
+                    depth,
+                    parent_folder,
+                    expanded,
+                });
+                if expanded {
+                    append_rows(children, folders, collapsed, Some(index), depth + 1, rows);
+                }
+            }
+            PlaylistTreeItem::Playlist(_) => rows.push(PlaylistTreeRow {
+                item: *item,
+                depth,
+                parent_folder,
+                expanded: false,
+            }),
+        }
+    }
+}
+
+/// Resolves dropping the row at `from` onto the row at `to`: onto a folder moves it into
+/// that folder, onto a playlist moves it next to that playlist, and past the last row
+/// moves it to the top level. Returns `None` when nothing would change or a folder would
+/// end up inside itself.
+pub fn resolve_drop(
+    rows: &[PlaylistTreeRow],
+    playlists: &[PlaylistInfo],
+    folders: &[PlaylistFolderInfo],
+    from: usize,
+    to: usize,
+) -> Option<PlaylistTreeMove> {
+    let source = rows.get(from)?;
+    if from == to {
+        return None;
+    }
+    let target_folder = match rows.get(to) {
+        None => None,
+        Some(PlaylistTreeRow {
+            item: PlaylistTreeItem::Folder(index),
+            ..
+        }) => Some(*index),
+        Some(row) => row.parent_folder,
+    };
+    if target_folder == source.parent_folder {
+        return None;
+    }
+    let target_id = target_folder.map(|index| folders[index].id.clone());
+    match source.item {
+        PlaylistTreeItem::Playlist(index) => Some(PlaylistTreeMove::Playlist {
+            playlist_id: playlists.get(index)?.id.clone(),
+            folder_id: target_id,
+        }),
+        PlaylistTreeItem::Folder(index) => {
+            let folder_id = folders.get(index)?.id.clone();
+            if would_create_cycle(folders, &folder_id, target_id.as_deref()) {
+                return None;
+            }
+            Some(PlaylistTreeMove::Folder {
+                folder_id,
+                parent_id: target_id,
+            })
+        }
+    }
+}
+
+/// Name for a new folder that does not clash with its siblings, e.g. `New Folder 2`.
+pub fn unique_folder_name(folders: &[PlaylistFolderInfo], parent_id: Option<&str>) -> String {
+    let sibling_names: HashSet<String> = folders
+        .iter()
+        .filter(|folder| folder.parent_id.as_deref() == parent_id)
+        .map(|folder| folder.name.to_lowercase())
+        .collect();
+    let mut name = "New Folder".to_string();
+    let mut suffix = 2;
+    while sibling_names.contains(&name.to_lowercase()) {
+        name = format!("New Folder {suffix}");
+        suffix += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(id: &str, parent_id: Option<&str>) -> PlaylistInfo {
+        PlaylistInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            note: String::new(),
+            skip_silence: false,
+            normalize_loudness: false,
+            parent_id: parent_id.map(str::to_string),
+            column_filters: Vec::new(),
+            description: String::new(),
+            cover_path: None,
+        }
+    }
+
+    fn folder(id: &str, parent_id: Option<&str>) -> PlaylistFolderInfo {
+        PlaylistFolderInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+        }
+    }
+
+    fn describe(rows: &[PlaylistTreeRow]) -> Vec<(String, usize)> {
+        rows.iter()
+            .map(|row| {
+                let label = match row.item {
+                    PlaylistTreeItem::Folder(index) => format!("folder{index}"),
+                    PlaylistTreeItem::Playlist(index) => format!("playlist{index}"),
+                };
+                (label, row.depth)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_tree_rows_nests_folders_before_playlists() {
+        let playlists = vec![
+            playlist("p0", None),
+            playlist("p1", Some("inner")),
+            playlist("p2", Some("outer")),
+            playlist("p3", Some("missing")),
+        ];
+        let folders = vec![folder("inner", Some("outer")), folder("outer", None)];
+        let rows = build_tree_rows(&playlists, &folders, &HashSet::new());
+        assert_eq!(
+            describe(&rows),
+            [
+                ("folder1".to_string(), 0),
+                ("folder0".to_string(), 1),
+                ("playlist1".to_string(), 2),
+                ("playlist2".to_string(), 1),
+                ("playlist0".to_string(), 0),
+                ("playlist3".to_string(), 0),
+            ]
+        );
+
+        let collapsed = HashSet::from(["outer".to_string()]);
+        let rows = build_tree_rows(&playlists, &folders, &collapsed);
+        assert_eq!(rows.len(), 3);
+        assert!(!rows[0].expanded);
+    }
+
+    #[test]
+    fn test_build_tree_rows_shows_folder_cycles_at_top_level() {
+        let folders = vec![folder("a", Some("b")), folder("b", Some("a"))];
+        let rows = build_tree_rows(&[], &folders, &HashSet::new());
+        assert_eq!(
+            describe(&rows),
+            [("folder0".to_string(), 0), ("folder1".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_drop_moves_into_folders_and_rejects_cycles() {
+        let playlists = vec![playlist("p0", None), playlist("p1", Some("inner"))];
+        let folders = vec![folder("inner", Some("outer")), folder("outer", None)];
+        let rows = build_tree_rows(&playlists, &folders, &HashSet::new());
+        // Rows: outer, inner, p1, p0.
+        assert_eq!(
+            resolve_drop(&rows, &playlists, &folders, 3, 1),
+            Some(PlaylistTreeMove::Playlist {
+                playlist_id: "p0".to_string(),
+                folder_id: Some("inner".to_string()),
+            })
+        );
+        assert_eq!(
+            resolve_drop(&rows, &playlists, &folders, 2, 4),
+            Some(PlaylistTreeMove::Playlist {
+                playlist_id: "p1".to_string(),
+                folder_id: None,
+            })
+        );
+        assert_eq!(
+            resolve_drop(&rows, &playlists, &folders, 1, 3),
+            Some(PlaylistTreeMove::Folder {
+                folder_id: "inner".to_string(),
+                parent_id: None,
+            })
+        );
+        assert_eq!(resolve_drop(&rows, &playlists, &folders, 0, 1), None);
+        assert_eq!(resolve_drop(&rows, &playlists, &folders, 2, 1), None);
+    }
+
+    #[test]
+    fn test_unique_folder_name_skips_sibling_names() {
+        let mut folders = vec![folder("a", None), folder("b", None)];
+        folders[0].name = "New Folder".to_string();
+        folders[1].name = "new folder 2".to_string();
+        assert_eq!(unique_folder_name(&folders, None), "New Folder 3");
+        assert_eq!(unique_folder_name(&folders, Some("a")), "New Folder");
+    }
+}
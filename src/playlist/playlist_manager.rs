@@ -4,22 +4,48 @@
 //! mutations, and coordinates decode/playback queueing behavior via the event bus.
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Receiver as StdReceiver;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use log::{debug, error, info, trace, warn};
 use tokio::sync::broadcast::{Receiver, Sender};
 use uuid::Uuid;
 
 use crate::{
-    config::{OutputConfig, UiConfig, UiPlaybackOrder, UiRepeatMode},
+    activity_log,
+    config::{
+        self, OutputConfig, OutputDeviceRatePolicyConfig, SampleRatePolicy, UiConfig,
+        UiEnergyRampArc, UiPlaybackOrder, UiRepeatMode,
+    },
     db_manager::DbManager,
-    integration_uri::{is_remote_track_path, parse_opensubsonic_track_uri},
+    dsp_chain, format_quality, image_pipeline,
+    integration_uri::{is_remote_track_path, parse_opensubsonic_track_uri, parse_webdav_track_uri},
+    intro_outro_detection, loudness_analysis, metadata_tags,
     playlist::{Playlist, Track},
-    protocol::{self, TrackIdentifier},
+    playlist_folders,
+    playlist_restructure::{self, SplitTrackInfo},
+    playlist_url::PLAYLIST_URL_REFRESH_INTERVAL,
+    protocol::{self, TrackIdentifier, TrackTrims},
+    remote_sync_status::RemoteSyncTracker,
+    sample_rate_grouping::{self, RateGroupingPolicy},
+    sandbox_access, seek_markers, source_matching,
+    task_manager::TaskHandle,
+    track_energy,
+    transition_history::{self, TransitionScores},
+    worker_pool, xspf,
 };
 
 const TRACK_LIST_HISTORY_LIMIT: usize = 128;
+/// Rows of the active playlist restored before the first UI snapshot; the rest follow it.
+const STARTUP_RESTORE_WINDOW: usize = 256;
+const UPCOMING_TRACKS_LIMIT: usize = 12;
+/// Upcoming tracks considered when grouping output rates by family.
+const RATE_GROUPING_LOOKAHEAD: usize = 8;
+/// How often URL-imported playlists are checked for a due refresh.
+const URL_PLAYLIST_REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 struct PlaylistTrackListSnapshot {
@@ -40,6 +66,8 @@ pub struct PlaylistManager {
     playback_playlist: Playlist,
     playback_queue_source: Option<protocol::PlaybackQueueSource>,
     playback_route: protocol::PlaybackRoute,
+    /// Cast target currently receiving playback, used to tell device switches from refreshes.
+    cast_device_id: Option<String>,
     playback_order: protocol::PlaybackOrder,
     repeat_mode: protocol::RepeatMode,
     bus_consumer: Receiver<protocol::Message>,
@@ -49,28 +77,122 @@ pub struct PlaylistManager {
     cached_track_ids: HashMap<String, u64>, // id -> start_offset_ms
     fully_cached_track_ids: HashSet<String>,
     requested_track_offsets: HashMap<String, u64>, // id -> requested start_offset_ms
+    hidden_pregap_offsets: HashMap<String, u64>,   // id -> offset after hidden pregap
     pending_start_track_id: Option<String>,
     pending_order_change: Option<protocol::PlaybackOrder>,
     track_sample_rate_cache: HashMap<PathBuf, Option<u32>>,
+    /// Per-track start/end trims from transition overrides, keyed by path.
+    track_trim_overrides: HashMap<PathBuf, TrackTrims>,
+    /// Paths left out of shuffle order, e.g. by folder import rules.
+    shuffle_excluded_paths: HashSet<PathBuf>,
+    /// Library folders, whose top-level subfolders shuffle-by-folder order keeps together.
+    library_roots: Vec<PathBuf>,
+    /// Stored energy levels and arc for energy ramp order; both are handed to the
+    /// playback queue when it starts, so changes never reorder tracks already cached.
+    track_energy_levels: HashMap<PathBuf, protocol::TrackEnergy>,
+    energy_ramp_arc: protocol::EnergyFlow,
+    /// Learned "plays well after" scores, also handed to the playback queue on start.
+    transition_scores: TransitionScores,
+    /// Track played before the current one, whose transition is learned when it ends.
+    transition_predecessor: Option<PathBuf>,
+    /// Remote copies of local library files, and the files set to play from them.
+    remote_copies_by_path: HashMap<PathBuf, Vec<PathBuf>>,
+    remote_source_paths: HashSet<PathBuf>,
+    /// Playlists whose tracks are decoded with pauses cut.
+    skip_silence_playlist_ids: HashSet<String>,
+    /// Playlists whose tracks are brought to a common loudness.
+    normalize_loudness_playlist_ids: HashSet<String>,
+    /// Global loudness normalization setting, applied to every playback queue.
+    normalize_loudness: bool,
+    /// Stored integrated loudness per track path, used for normalization gain.
+    track_loudness_lufs: HashMap<PathBuf, f32>,
     pending_rate_switch: Option<u32>,
     pending_rate_switch_play_immediately: bool,
     current_output_rate_hz: Option<u32>,
     verified_output_rates: Vec<u32>,
     sample_rate_auto_enabled: bool,
+    rate_grouping_policy: RateGroupingPolicy,
+    /// Output device the Match Content rate policy is looked up for.
+    output_device_name: String,
+    device_rate_policies: Vec<OutputDeviceRatePolicyConfig>,
+    /// Match Content rate policy of the current output device.
+    rate_policy: OutputDeviceRatePolicyConfig,
+    /// Local paths whose sample rate was requested from the file header; each is read once.
+    sample_rate_probed_paths: HashSet<PathBuf>,
     max_num_cached_tracks: usize,
     current_track_duration_ms: u64,
     current_elapsed_ms: u64,
     last_seek_ms: u64,
     started_track_id: Option<String>,
+    /// Track the current seek bar markers were published for.
+    seek_markers_track_id: Option<String>,
+    /// Detected seek bar markers, keyed by path, so replays skip the decode.
+    seek_marker_cache: HashMap<PathBuf, Vec<protocol::SeekMarker>>,
     track_list_undo_stack: Vec<PlaylistTrackListSnapshot>,
     track_list_redo_stack: Vec<PlaylistTrackListSnapshot>,
+    /// Playlists created by the last split or merge; the next undo deletes them.
+    restructure_undo_playlist_ids: Vec<String>,
     playback_preferences_restored_from_config: bool,
     pending_mixed_detach: Option<PendingMixedDetach>,
     suppress_remote_writeback: bool,
     last_remote_writeback_signature: HashMap<String, String>,
+    remote_sync: RemoteSyncTracker,
     remote_track_metadata_by_path: HashMap<PathBuf, protocol::TrackMetadataSummary>,
     backend_connection_states: HashMap<String, protocol::BackendConnectionState>,
     unavailable_track_ids: HashSet<String>,
+    url_playlist_refreshes_in_flight: HashSet<String>,
+    /// Remote-bound playlists whose tracks are being downloaded to become local.
+    materializing_playlist_ids: HashSet<String>,
+    upcoming_tracks: Vec<protocol::UpcomingTrack>,
+}
+
+/// Energy ramp arc selected in settings, as the order the playlist applies.
+fn energy_flow_for_ramp_arc(arc: UiEnergyRampArc) -> protocol::EnergyFlow {
+    match arc {
+        UiEnergyRampArc::BuildUp => protocol::EnergyFlow::BuildUp,
+        UiEnergyRampArc::WindDown => protocol::EnergyFlow::WindDown,
+        UiEnergyRampArc::Peak => protocol::EnergyFlow::Peak,
+    }
+}
+
+/// Runs `analyze` for each path on the shared analysis pool as one Tasks panel entry,
+/// which can be paused or cancelled between files. Returns how many files `analyze`
+/// reported a result for.
+fn run_analysis_task(
+    bus_producer: &Sender<protocol::Message>,
+    label: &str,
+    paths: Vec<PathBuf>,
+    analyze: impl Fn(PathBuf) -> bool + Send + Sync + 'static,
+) -> usize {
+    let total = paths.len();
+    let task = TaskHandle::start(
+        bus_producer,
+        protocol::TaskKind::Analysis,
+        label,
+        true,
+        true,
+    );
+    let files_done = Arc::new(AtomicUsize::new(0));
+    let job_task = task.clone();
+    let analyzed = worker_pool::analysis_pool()
+        .map(paths, move |path| {
+            if !job_task.wait_while_paused() {
+                return false;
+            }
+            let analyzed = analyze(path);
+            let done = files_done.fetch_add(1, Ordering::Relaxed) + 1;
+            job_task.progress(
+                done as u64,
+                Some(total as u64),
+                format!("{done} of {total} files"),
+            );
+            analyzed
+        })
+        .into_iter()
+        .filter(|analyzed| *analyzed)
+        .count();
+    task.finish(Ok(()));
+    analyzed
 }
 
 impl PlaylistManager {
@@ -90,6 +212,7 @@ impl PlaylistManager {
             playback_playlist: playlist,
             playback_queue_source: None,
             playback_route: protocol::PlaybackRoute::Local,
+            cast_device_id: None,
             playback_order: protocol::PlaybackOrder::Default,
             repeat_mode: protocol::RepeatMode::Off,
             bus_consumer,
@@ -99,28 +222,57 @@ impl PlaylistManager {
             cached_track_ids: HashMap::new(),
             fully_cached_track_ids: HashSet::new(),
             requested_track_offsets: HashMap::new(),
+            hidden_pregap_offsets: HashMap::new(),
             pending_start_track_id: None,
             pending_order_change: None,
             track_sample_rate_cache: HashMap::new(),
+            track_trim_overrides: HashMap::new(),
+            shuffle_excluded_paths: HashSet::new(),
+            library_roots: Vec::new(),
+            track_energy_levels: HashMap::new(),
+            energy_ramp_arc: protocol::EnergyFlow::BuildUp,
+            transition_scores: TransitionScores::new(),
+            transition_predecessor: None,
+            remote_copies_by_path: HashMap::new(),
+            remote_source_paths: HashSet::new(),
+            skip_silence_playlist_ids: HashSet::new(),
+            normalize_loudness_playlist_ids: HashSet::new(),
+            normalize_loudness: false,
+            track_loudness_lufs: HashMap::new(),
             pending_rate_switch: None,
             pending_rate_switch_play_immediately: false,
             current_output_rate_hz: None,
             verified_output_rates: Vec::new(),
             sample_rate_auto_enabled: initial_output_config.sample_rate_auto,
+            rate_grouping_policy: RateGroupingPolicy {
+                group_families: initial_output_config.group_sample_rate_families,
+                resample_outliers: initial_output_config.resample_rate_outliers,
+            },
+            output_device_name: initial_output_config.output_device_name.clone(),
+            device_rate_policies: initial_output_config.device_rate_policies.clone(),
+            rate_policy: initial_output_config.active_rate_policy(),
+            sample_rate_probed_paths: HashSet::new(),
             max_num_cached_tracks: 2,
             current_track_duration_ms: 0,
             current_elapsed_ms: 0,
             last_seek_ms: u64::MAX,
             started_track_id: None,
+            seek_markers_track_id: None,
+            seek_marker_cache: HashMap::new(),
             track_list_undo_stack: Vec::new(),
             track_list_redo_stack: Vec::new(),
+            restructure_undo_playlist_ids: Vec::new(),
             playback_preferences_restored_from_config: true,
             pending_mixed_detach: None,
             suppress_remote_writeback: false,
             last_remote_writeback_signature: HashMap::new(),
+            remote_sync: RemoteSyncTracker::default(),
             remote_track_metadata_by_path: HashMap::new(),
             backend_connection_states: HashMap::new(),
             unavailable_track_ids: HashSet::new(),
+            url_playlist_refreshes_in_flight: HashSet::new(),
+            materializing_playlist_ids: HashSet::new(),
+            upcoming_tracks: Vec::new(),
         };
         manager.restore_playback_preferences_from_ui_config(&initial_ui_config);
         manager
@@ -135,6 +287,28 @@ impl PlaylistManager {
         }
     }
 
+    /// Whether tracks decoded for the current playback queue should have pauses cut.
+    fn playback_skips_silence(&self) -> bool {
+        self.playback_playlist_id()
+            .is_some_and(|playlist_id| self.skip_silence_playlist_ids.contains(&playlist_id))
+    }
+
+    /// Loudness normalization gain for `path` in the current playback queue; `0.0` when
+    /// normalization is off or the track has not been measured.
+    fn playback_normalization_gain_db(&self, path: &Path) -> f32 {
+        let enabled = self.normalize_loudness
+            || self.playback_playlist_id().is_some_and(|playlist_id| {
+                self.normalize_loudness_playlist_ids.contains(&playlist_id)
+            });
+        if !enabled {
+            return 0.0;
+        }
+        self.track_loudness_lufs
+            .get(path)
+            .map(|integrated_lufs| dsp_chain::normalization_gain_db(*integrated_lufs))
+            .unwrap_or(0.0)
+    }
+
     fn remote_binding_from_playlist_id(playlist_id: &str) -> Option<(String, String)> {
         let prefix = "remote:opensubsonic:";
         let suffix = playlist_id.strip_prefix(prefix)?;
@@ -233,6 +407,61 @@ impl PlaylistManager {
         ));
     }
 
+    fn emit_remote_sync_statuses(&self) {
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::RemotePlaylistSyncStatusChanged(self.remote_sync.statuses()),
+        ));
+    }
+
+    /// Song ids stored for a remote-bound playlist, or `None` when it holds other tracks.
+    fn stored_remote_song_ids(&self, playlist_id: &str) -> Option<Vec<String>> {
+        let (profile_id, _) = Self::remote_binding_from_playlist_id(playlist_id)?;
+        let tracks = self.db_manager.get_tracks_for_playlist(playlist_id).ok()?;
+        tracks
+            .iter()
+            .map(|track| {
+                parse_opensubsonic_track_uri(track.path.as_path())
+                    .filter(|locator| locator.profile_id == profile_id)
+                    .map(|locator| locator.song_id)
+            })
+            .collect()
+    }
+
+    fn sync_remote_playlist_now(&mut self, playlist_id: &str) {
+        let Some((profile_id, remote_playlist_id)) =
+            Self::remote_binding_from_playlist_id(playlist_id)
+        else {
+            return;
+        };
+        if self.remote_sync.has_local_edits_pending(playlist_id)
+            && !self.remote_sync.has_writeback_in_flight(playlist_id)
+        {
+            let Some(song_ids) = self.stored_remote_song_ids(playlist_id) else {
+                self.emit_remote_writeback_state(
+                    playlist_id.to_string(),
+                    false,
+                    Some("Playlist holds tracks that are not on the server".to_string()),
+                );
+                return;
+            };
+            self.last_remote_writeback_signature
+                .insert(playlist_id.to_string(), song_ids.join(","));
+            self.remote_sync.record_writeback_sent(playlist_id);
+            let _ = self.bus_producer.send(protocol::Message::Integration(
+                protocol::IntegrationMessage::PushOpenSubsonicPlaylistUpdate {
+                    profile_id: profile_id.clone(),
+                    remote_playlist_id,
+                    local_playlist_id: playlist_id.to_string(),
+                    track_song_ids: song_ids,
+                },
+            ));
+            self.emit_remote_sync_statuses();
+        }
+        let _ = self.bus_producer.send(protocol::Message::Integration(
+            protocol::IntegrationMessage::SyncBackendProfile { profile_id },
+        ));
+    }
+
     fn emit_track_unavailable_metadata(&self, track_id: &str) {
         let summary = protocol::TrackMetadataSummary {
             title: "Remote track unavailable".to_string(),
@@ -243,6 +472,10 @@ impl PlaylistManager {
             genre: String::new(),
             year: String::new(),
             track_number: String::new(),
+            composer: String::new(),
+            disc_number: String::new(),
+            total_tracks: String::new(),
+            comment: String::new(),
         };
         let _ = self.bus_producer.send(protocol::Message::Playlist(
             protocol::PlaylistMessage::TrackMetadataBatchUpdated {
@@ -301,6 +534,72 @@ impl PlaylistManager {
         }
     }
 
+    /// Clears the unavailable state of remote tracks whose profile is connected again.
+    ///
+    /// Restores each track's cached remote metadata (or the generic remote placeholder when
+    /// the library has not been re-synced yet) so the "Remote track unavailable" override
+    /// disappears without a playlist switch.
+    fn recover_unavailable_remote_tracks(&mut self) {
+        if self.unavailable_track_ids.is_empty() {
+            return;
+        }
+        let mut recovered: Vec<(String, PathBuf)> = Vec::new();
+        for playlist in [&self.editing_playlist, &self.playback_playlist] {
+            for index in 0..playlist.num_tracks() {
+                let track = playlist.get_track(index);
+                if !self.unavailable_track_ids.contains(&track.id)
+                    || recovered.iter().any(|(id, _)| id == &track.id)
+                {
+                    continue;
+                }
+                let Some(locator) = parse_opensubsonic_track_uri(track.path.as_path()) else {
+                    continue;
+                };
+                if matches!(
+                    self.backend_connection_states.get(&locator.profile_id),
+                    Some(protocol::BackendConnectionState::Connected)
+                ) {
+                    recovered.push((track.id.clone(), track.path.clone()));
+                }
+            }
+        }
+        if recovered.is_empty() {
+            return;
+        }
+        info!(
+            "PlaylistManager: Recovered {} remote track(s) after profile reconnect",
+            recovered.len()
+        );
+        let updates = recovered
+            .into_iter()
+            .map(|(track_id, path)| {
+                self.unavailable_track_ids.remove(&track_id);
+                let summary = self
+                    .remote_track_metadata_by_path
+                    .get(&path)
+                    .cloned()
+                    .unwrap_or_else(|| protocol::TrackMetadataSummary {
+                        title: "Remote track".to_string(),
+                        artist: String::new(),
+                        album: String::new(),
+                        album_artist: String::new(),
+                        date: String::new(),
+                        genre: String::new(),
+                        year: String::new(),
+                        track_number: String::new(),
+                        composer: String::new(),
+                        disc_number: String::new(),
+                        total_tracks: String::new(),
+                        comment: String::new(),
+                    });
+                protocol::TrackMetadataPatch { track_id, summary }
+            })
+            .collect();
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TrackMetadataBatchUpdated { updates },
+        ));
+    }
+
     fn find_playable_index_from(&mut self, start_index: usize, forward: bool) -> Option<usize> {
         if self.playback_playlist.num_tracks() == 0
             || start_index >= self.playback_playlist.num_tracks()
@@ -456,6 +755,8 @@ impl PlaylistManager {
         self.editing_playlist
             .set_playback_order(self.playback_order);
         self.editing_playlist.set_repeat_mode(self.repeat_mode);
+        self.editing_playlist
+            .set_library_roots(self.library_roots.clone());
         if let Ok(tracks) = self
             .db_manager
             .get_tracks_for_playlist(&self.active_playlist_id)
@@ -516,6 +817,106 @@ impl PlaylistManager {
         self.broadcast_selection_changed();
     }
 
+    /// Follows files moved by the library organizer so loaded playlists and undo
+    /// history keep pointing at the new locations.
+    fn remap_organized_track_paths(&mut self, moves: Vec<protocol::OrganizeFileMove>) {
+        let remapped: HashMap<PathBuf, PathBuf> = moves
+            .into_iter()
+            .map(|file_move| (file_move.from, file_move.to))
+            .collect();
+        if remapped.is_empty() {
+            return;
+        }
+        self.playback_playlist.remap_track_paths(&remapped);
+        for snapshot in self
+            .track_list_undo_stack
+            .iter_mut()
+            .chain(self.track_list_redo_stack.iter_mut())
+        {
+            for track in &mut snapshot.tracks {
+                if let Some(new_path) = remapped.get(&track.path) {
+                    track.path = new_path.clone();
+                }
+            }
+        }
+        if !self.editing_playlist.remap_track_paths(&remapped) {
+            return;
+        }
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistRestored(self.snapshot_editing_playlist_tracks()),
+        ));
+        self.broadcast_playlist_changed();
+        self.broadcast_selection_changed();
+    }
+
+    /// Checks the local files of the playlist at sidebar `index` off the manager thread.
+    fn verify_playlist_files(&self, index: usize) {
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        let Some(playlist_id) = playlists.get(index).map(|playlist| playlist.id.clone()) else {
+            return;
+        };
+        let tracks = match self.db_manager.get_tracks_for_playlist(&playlist_id) {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                error!(
+                    "Failed to load tracks of {} for verification: {}",
+                    playlist_id, err
+                );
+                return;
+            }
+        };
+        let bus_producer = self.bus_producer.clone();
+        std::thread::spawn(move || {
+            let local_tracks: Vec<protocol::RestoredTrack> = tracks
+                .into_iter()
+                .filter(|track| !is_remote_track_path(&track.path))
+                .collect();
+            let checked_ids = local_tracks.iter().map(|track| track.id.clone()).collect();
+            let missing = local_tracks
+                .into_iter()
+                .filter(|track| !track.path.is_file())
+                .collect();
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::PlaylistFilesVerified {
+                    playlist_id,
+                    checked_ids,
+                    missing,
+                },
+            ));
+        });
+    }
+
+    /// Marks checked tracks whose file is missing as unavailable, so playback skips them,
+    /// and clears the mark from tracks whose file is back.
+    fn apply_verified_playlist_files(
+        &mut self,
+        checked_ids: Vec<String>,
+        missing: &[protocol::RestoredTrack],
+    ) {
+        let missing_ids: HashSet<&str> = missing.iter().map(|track| track.id.as_str()).collect();
+        for track_id in checked_ids {
+            if missing_ids.contains(track_id.as_str()) {
+                self.unavailable_track_ids.insert(track_id);
+            } else {
+                self.unavailable_track_ids.remove(&track_id);
+            }
+        }
+    }
+
+    /// Points tracks at relocated files and makes them playable again.
+    fn apply_relocated_track_paths(&mut self, moves: Vec<protocol::OrganizeFileMove>) {
+        let relocated: HashSet<&PathBuf> = moves.iter().map(|file_move| &file_move.from).collect();
+        for playlist in [&self.editing_playlist, &self.playback_playlist] {
+            for index in 0..playlist.num_tracks() {
+                let track = playlist.get_track(index);
+                if relocated.contains(&track.path) {
+                    self.unavailable_track_ids.remove(&track.id);
+                }
+            }
+        }
+        self.remap_organized_track_paths(moves);
+    }
+
     fn remove_remote_metadata_for_profile(&mut self, profile_id: &str) {
         self.remote_track_metadata_by_path.retain(|path, _| {
             parse_opensubsonic_track_uri(path.as_path())
@@ -569,11 +970,14 @@ impl PlaylistManager {
                 let _ = self.db_manager.save_tracks_batch(&detached_id, &pending, 0);
             }
             let _ = self.db_manager.delete_playlist(&self.active_playlist_id);
+            self.remote_sync.forget(&self.active_playlist_id);
             self.active_playlist_id = detached_id;
             self.editing_playlist = Playlist::new();
             self.editing_playlist
                 .set_playback_order(self.playback_order);
             self.editing_playlist.set_repeat_mode(self.repeat_mode);
+            self.editing_playlist
+                .set_library_roots(self.library_roots.clone());
             if let Ok(restored) = self
                 .db_manager
                 .get_tracks_for_playlist(&self.active_playlist_id)
@@ -591,6 +995,129 @@ impl PlaylistManager {
         }
     }
 
+    /// Starts downloading the tracks of a remote-bound playlist into the offline cache.
+    fn request_materialize_remote_playlist(&mut self, playlist_id: &str) {
+        if Self::remote_binding_from_playlist_id(playlist_id).is_none()
+            || self.materializing_playlist_ids.contains(playlist_id)
+        {
+            return;
+        }
+        let tracks = match self.db_manager.get_tracks_for_playlist(playlist_id) {
+            Ok(tracks) => tracks,
+            Err(error) => {
+                error!(
+                    "Failed to read playlist {} for download: {}",
+                    playlist_id, error
+                );
+                return;
+            }
+        };
+        let mut seen_paths = HashSet::new();
+        let paths: Vec<PathBuf> = tracks
+            .into_iter()
+            .map(|track| track.path)
+            .filter(|path| {
+                (parse_opensubsonic_track_uri(path).is_some()
+                    || parse_webdav_track_uri(path).is_some())
+                    && seen_paths.insert(path.clone())
+            })
+            .collect();
+        let playlist_name = self
+            .playlist_name_by_id(playlist_id)
+            .unwrap_or_else(|| "Remote Playlist".to_string());
+        self.materializing_playlist_ids
+            .insert(playlist_id.to_string());
+        let _ = self.bus_producer.send(protocol::Message::Integration(
+            protocol::IntegrationMessage::MaterializeRemotePlaylist {
+                local_playlist_id: playlist_id.to_string(),
+                playlist_name,
+                paths,
+            },
+        ));
+    }
+
+    /// Replaces a remote-bound playlist with a local one holding the downloaded copies,
+    /// in the same order. Returns the id of the new playlist.
+    fn materialize_remote_playlist(
+        &mut self,
+        playlist_id: &str,
+        local_paths: Vec<(PathBuf, PathBuf)>,
+    ) -> Result<String, String> {
+        let local_paths: HashMap<PathBuf, PathBuf> = local_paths.into_iter().collect();
+        let playlist_name = self
+            .playlist_name_by_id(playlist_id)
+            .ok_or_else(|| "the playlist no longer exists".to_string())?;
+        let tracks = self
+            .db_manager
+            .get_tracks_for_playlist(playlist_id)
+            .map_err(|error| format!("failed to read playlist tracks: {error}"))?;
+        let materialized_id = Uuid::new_v4().to_string();
+        self.db_manager
+            .create_playlist(&materialized_id, &playlist_name)
+            .map_err(|error| format!("failed to create local playlist: {error}"))?;
+        let rows: Vec<(String, PathBuf)> = tracks
+            .into_iter()
+            .map(|track| {
+                let path = local_paths.get(&track.path).cloned().unwrap_or(track.path);
+                (Uuid::new_v4().to_string(), path)
+            })
+            .collect();
+        if !rows.is_empty() {
+            self.db_manager
+                .save_tracks_batch(&materialized_id, &rows, 0)
+                .map_err(|error| format!("failed to store local playlist tracks: {error}"))?;
+        }
+        self.db_manager
+            .delete_playlist(playlist_id)
+            .map_err(|error| format!("failed to remove remote-bound playlist: {error}"))?;
+        self.remote_sync.forget(playlist_id);
+        self.last_remote_writeback_signature.remove(playlist_id);
+        if self.skip_silence_playlist_ids.remove(playlist_id)
+            && self
+                .db_manager
+                .set_playlist_skip_silence(&materialized_id, true)
+                .is_ok()
+        {
+            self.skip_silence_playlist_ids
+                .insert(materialized_id.clone());
+        }
+        if self.normalize_loudness_playlist_ids.remove(playlist_id)
+            && self
+                .db_manager
+                .set_playlist_normalize_loudness(&materialized_id, true)
+                .is_ok()
+        {
+            self.normalize_loudness_playlist_ids
+                .insert(materialized_id.clone());
+        }
+        if matches!(
+            self.playback_queue_source.as_ref(),
+            Some(protocol::PlaybackQueueSource::Playlist { playlist_id: queued_id })
+                if queued_id == playlist_id
+        ) {
+            self.playback_queue_source = None;
+        }
+        if self.active_playlist_id == playlist_id {
+            self.active_playlist_id = materialized_id.clone();
+            self.reload_editing_playlist_from_active();
+        }
+        activity_log::record(
+            &self.bus_producer,
+            protocol::ActivityCategory::Playlist,
+            format!(
+                "Downloaded playlist \"{}\" as a local playlist with {} track(s)",
+                playlist_name,
+                rows.len()
+            ),
+        );
+        self.emit_remote_sync_statuses();
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        self.broadcast_playlist_state_snapshot(playlists);
+        self.broadcast_playlist_changed();
+        self.broadcast_selection_changed();
+        Ok(materialized_id)
+    }
+
     fn promote_local_playlist_to_remote_binding(
         &mut self,
         local_playlist_id: &str,
@@ -672,17 +1199,27 @@ impl PlaylistManager {
             return;
         }
 
+        let from_library = matches!(request.source, protocol::PlaybackQueueSource::Library);
         let mut playback_playlist = Playlist::new();
+        playback_playlist.set_shuffle_excluded_paths(self.shuffle_excluded_paths.clone());
+        playback_playlist.set_track_energy_levels(self.track_energy_levels.clone());
+        playback_playlist.set_energy_ramp_arc(self.energy_ramp_arc);
+        playback_playlist.set_transition_scores(self.transition_scores.clone());
         playback_playlist.set_playback_order(self.playback_order);
         playback_playlist.set_repeat_mode(self.repeat_mode);
+        playback_playlist.set_library_roots(self.library_roots.clone());
         for track in request.tracks {
-            playback_playlist.add_track(Track {
-                path: track.path,
-                id: track.id,
-            });
+            let path = if from_library {
+                self.library_playback_path(track.path)
+            } else {
+                track.path
+            };
+            playback_playlist.add_track(Track { path, id: track.id });
         }
 
         let clamped_start = request.start_index.min(playback_playlist.num_tracks() - 1);
+        let start_path = playback_playlist.get_track(clamped_start).path.clone();
+        self.begin_manual_transition(start_path);
         playback_playlist.set_selected_indices(vec![clamped_start]);
         playback_playlist.force_re_randomize_shuffle();
         self.playback_playlist = playback_playlist;
@@ -713,6 +1250,7 @@ impl PlaylistManager {
         if self.playback_route == protocol::PlaybackRoute::Cast {
             let track = self.playback_playlist.get_track(index).clone();
             let metadata_summary = self.remote_track_metadata_by_path.get(&track.path).cloned();
+            let upcoming = self.cast_queue_items(index);
             let _ =
                 self.bus_producer
                     .send(protocol::Message::Cast(protocol::CastMessage::LoadTrack {
@@ -720,6 +1258,7 @@ impl PlaylistManager {
                         path: track.path,
                         start_offset_ms: 0,
                         metadata_summary,
+                        upcoming,
                     }));
             self.pending_start_track_id = Some(track_id);
             self.broadcast_playlist_changed();
@@ -772,6 +1311,7 @@ impl PlaylistManager {
                 path: track.path,
                 start_offset_ms: self.current_elapsed_ms,
                 metadata_summary,
+                upcoming: self.cast_queue_items(index),
             }));
         self.pending_start_track_id = Some(track.id);
         self.broadcast_playlist_changed();
@@ -792,6 +1332,8 @@ impl PlaylistManager {
         }
         let track = self.playback_playlist.get_track(index).clone();
         let resume_offset_ms = self.current_elapsed_ms;
+        let trims = self.track_trims(&track);
+        let normalization_gain_db = self.playback_normalization_gain_db(&track.path);
 
         self.clear_cached_tracks();
         let _ = self.bus_producer.send(protocol::Message::Audio(
@@ -800,6 +1342,10 @@ impl PlaylistManager {
                 path: track.path,
                 play_immediately: true,
                 start_offset_ms: resume_offset_ms,
+                trim_start_ms: trims.start_ms,
+                trim_end_ms: trims.end_ms,
+                skip_silence: self.playback_skips_silence(),
+                normalization_gain_db,
             }]),
         ));
         self.requested_track_offsets
@@ -808,7 +1354,181 @@ impl PlaylistManager {
         true
     }
 
+    /// Restarts the playing track at `target_ms`, on the cast receiver or local decoder.
+    fn seek_playing_track_to_ms(&mut self, target_ms: u64) {
+        if self.playback_route == protocol::PlaybackRoute::Cast {
+            let _ = self
+                .bus_producer
+                .send(protocol::Message::Cast(protocol::CastMessage::SeekMs(
+                    target_ms,
+                )));
+            self.current_elapsed_ms = target_ms;
+            return;
+        }
+
+        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index() {
+            if playing_idx < self.playback_playlist.num_tracks() {
+                let track = self.playback_playlist.get_track(playing_idx);
+                let track_id = track.id.clone();
+                let track_path = track.path.clone();
+                let trims = self.track_trims(track);
+                let normalization_gain_db = self.playback_normalization_gain_db(&track_path);
+
+                // Remove from cached list since it's no longer cached at offset 0
+                self.cached_track_ids.remove(&track_id);
+                self.fully_cached_track_ids.remove(&track_id);
+                self.pending_start_track_id = None;
+                self.started_track_id = None;
+                self.requested_track_offsets.clear();
+
+                // 1. Stop current decoding
+                self.stop_decoding();
+
+                // 2. Clear player cache
+                let _ = self.bus_producer.send(protocol::Message::Playback(
+                    protocol::PlaybackMessage::ClearPlayerCache,
+                ));
+
+                // 3. Restart decoding at offset
+                let _ = self.bus_producer.send(protocol::Message::Audio(
+                    protocol::AudioMessage::DecodeTracks(vec![TrackIdentifier {
+                        id: track_id.clone(),
+                        path: track_path,
+                        play_immediately: true,
+                        start_offset_ms: target_ms,
+                        trim_start_ms: trims.start_ms,
+                        trim_end_ms: trims.end_ms,
+                        skip_silence: self.playback_skips_silence(),
+                        normalization_gain_db,
+                    }]),
+                ));
+                self.requested_track_offsets.insert(track_id, target_ms);
+            }
+        }
+    }
+
+    /// Adds tracks to the running playback queue, or starts a library queue with them
+    /// when nothing is playing.
+    /// Swaps a library file for its remote copy when the user chose it or the file is gone.
+    fn library_playback_path(&self, path: PathBuf) -> PathBuf {
+        if !self.remote_copies_by_path.contains_key(&path) {
+            return path;
+        }
+        source_matching::preferred_playback_path(
+            &path,
+            &self.remote_copies_by_path,
+            &self.remote_source_paths,
+            |path| path.exists(),
+        )
+    }
+
+    fn set_prefer_remote_copy(&mut self, path: PathBuf, prefer_remote: bool) {
+        if prefer_remote {
+            self.remote_source_paths.insert(path);
+        } else {
+            self.remote_source_paths.remove(&path);
+        }
+    }
+
+    fn enqueue_tracks(&mut self, tracks: Vec<protocol::RestoredTrack>, play_next: bool) {
+        if tracks.is_empty() {
+            return;
+        }
+        if self.playback_playlist.get_playing_track_index().is_none() {
+            self.start_playback_queue(protocol::PlaybackQueueRequest {
+                source: protocol::PlaybackQueueSource::Library,
+                tracks,
+                start_index: 0,
+            });
+            return;
+        }
+
+        // Track ids key the decode cache, so a track queued twice gets a fresh id.
+        let mut queued_ids: HashSet<String> = (0..self.playback_playlist.num_tracks())
+            .map(|index| self.playback_playlist.get_track_id(index))
+            .collect();
+        let tracks: Vec<Track> = tracks
+            .into_iter()
+            .map(|track| {
+                let id = if queued_ids.contains(&track.id) {
+                    Uuid::new_v4().to_string()
+                } else {
+                    track.id
+                };
+                queued_ids.insert(id.clone());
+                Track {
+                    path: self.library_playback_path(track.path),
+                    id,
+                }
+            })
+            .collect();
+        debug!(
+            "PlaylistManager: queueing {} track(s), play_next={}",
+            tracks.len(),
+            play_next
+        );
+        if play_next {
+            self.playback_playlist.insert_tracks_to_play_next(tracks);
+        } else {
+            for track in tracks {
+                self.playback_playlist.add_track(track);
+            }
+        }
+        self.cache_tracks(false);
+        self.broadcast_playlist_changed();
+    }
+
+    /// Resumes the track that was playing when the previous session crashed.
+    ///
+    /// The playlist is read back from the database, so the journaled track resumes even
+    /// when another playlist is active in the editor.
+    fn resume_journaled_track(&mut self, playlist_id: String, track_id: String, elapsed_ms: u64) {
+        let tracks = match self.db_manager.get_tracks_for_playlist(&playlist_id) {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                warn!(
+                    "PlaylistManager: failed to load journaled playlist {}: {}",
+                    playlist_id, err
+                );
+                return;
+            }
+        };
+        let Some(start_index) = tracks.iter().position(|track| track.id == track_id) else {
+            debug!(
+                "PlaylistManager: journaled track {} is no longer in playlist {}",
+                track_id, playlist_id
+            );
+            return;
+        };
+        info!(
+            "PlaylistManager: resuming journaled track {} at {}ms",
+            track_id, elapsed_ms
+        );
+        self.start_playback_queue(protocol::PlaybackQueueRequest {
+            source: protocol::PlaybackQueueSource::Playlist { playlist_id },
+            tracks,
+            start_index,
+        });
+        if elapsed_ms > 0 {
+            self.seek_playing_track_to_ms(elapsed_ms);
+        }
+    }
+
     fn update_runtime_policy_from_output_delta(&mut self, output: &protocol::OutputConfigDelta) {
+        if let Some(group_families) = output.group_sample_rate_families {
+            self.rate_grouping_policy.group_families = group_families;
+        }
+        if let Some(resample_outliers) = output.resample_rate_outliers {
+            self.rate_grouping_policy.resample_outliers = resample_outliers;
+        }
+        if let Some(output_device_name) = output.output_device_name.as_ref() {
+            self.output_device_name = output_device_name.clone();
+        }
+        if let Some(device_rate_policies) = output.device_rate_policies.as_ref() {
+            self.device_rate_policies = device_rate_policies.clone();
+        }
+        self.rate_policy =
+            config::rate_policy_for_device(&self.device_rate_policies, &self.output_device_name);
         let Some(sample_rate_auto) = output.sample_rate_auto else {
             return;
         };
@@ -824,6 +1544,8 @@ impl PlaylistManager {
             UiPlaybackOrder::Default => protocol::PlaybackOrder::Default,
             UiPlaybackOrder::Shuffle => protocol::PlaybackOrder::Shuffle,
             UiPlaybackOrder::Random => protocol::PlaybackOrder::Random,
+            UiPlaybackOrder::ShuffleFolders => protocol::PlaybackOrder::ShuffleFolders,
+            UiPlaybackOrder::EnergyRamp => protocol::PlaybackOrder::EnergyRamp,
         };
         let next_repeat_mode = match ui.repeat_mode {
             UiRepeatMode::Off => protocol::RepeatMode::Off,
@@ -833,6 +1555,7 @@ impl PlaylistManager {
 
         let changed =
             self.playback_order != next_playback_order || self.repeat_mode != next_repeat_mode;
+        self.energy_ramp_arc = energy_flow_for_ramp_arc(ui.energy_ramp_arc);
         self.playback_order = next_playback_order;
         self.repeat_mode = next_repeat_mode;
         self.editing_playlist
@@ -849,10 +1572,14 @@ impl PlaylistManager {
             protocol::PlaybackOrder::Default => UiPlaybackOrder::Default,
             protocol::PlaybackOrder::Shuffle => UiPlaybackOrder::Shuffle,
             protocol::PlaybackOrder::Random => UiPlaybackOrder::Random,
+            protocol::PlaybackOrder::ShuffleFolders => UiPlaybackOrder::ShuffleFolders,
+            protocol::PlaybackOrder::EnergyRamp => UiPlaybackOrder::EnergyRamp,
         }) {
             UiPlaybackOrder::Default => protocol::PlaybackOrder::Default,
             UiPlaybackOrder::Shuffle => protocol::PlaybackOrder::Shuffle,
             UiPlaybackOrder::Random => protocol::PlaybackOrder::Random,
+            UiPlaybackOrder::ShuffleFolders => protocol::PlaybackOrder::ShuffleFolders,
+            UiPlaybackOrder::EnergyRamp => protocol::PlaybackOrder::EnergyRamp,
         };
         let next_repeat_mode = match ui.repeat_mode.unwrap_or(match self.repeat_mode {
             protocol::RepeatMode::Off => UiRepeatMode::Off,
@@ -883,615 +1610,1812 @@ impl PlaylistManager {
         self.verified_output_rates = rates;
     }
 
-    fn track_sample_rate_hz_cached(&mut self, track: &Track) -> Option<u32> {
-        if let Some(cached) = self.track_sample_rate_cache.get(&track.path) {
-            return *cached;
-        }
-        // Startup playback must remain non-blocking. Do not synchronously probe files in the
-        // hot path; rely on technical metadata updates to backfill this cache.
-        if !is_remote_track_path(track.path.as_path()) {
-            return None;
+    fn restore_transition_history(&mut self) {
+        match self.db_manager.get_track_transition_history() {
+            Ok(rows) => {
+                self.transition_scores = transition_history::scores_from_counts(rows);
+            }
+            Err(err) => {
+                error!("Failed to load learned transitions: {}", err);
+            }
         }
-        self.track_sample_rate_cache
-            .insert(track.path.clone(), None);
-        None
     }
 
-    fn desired_output_rate_for_track(&mut self, track: &Track) -> Option<u32> {
-        if !self.sample_rate_auto_enabled {
-            return self.current_output_rate_hz;
+    fn learn_transition(
+        &mut self,
+        from_path: PathBuf,
+        to_path: PathBuf,
+        outcome: protocol::TransitionOutcome,
+    ) {
+        if from_path == to_path {
+            return;
         }
-        if self.verified_output_rates.is_empty() {
-            // Startup can race with asynchronous output capability probing. When rates are still
-            // unknown, prefer the track's native rate so first-playback stays content-matched.
-            return self
-                .track_sample_rate_hz_cached(track)
-                .or(self.current_output_rate_hz);
+        let updated_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        if let Err(err) =
+            self.db_manager
+                .record_track_transition(&from_path, &to_path, outcome, updated_unix_ms)
+        {
+            warn!("Failed to record track transition: {}", err);
+            return;
         }
-
-        let source_rate = match self.track_sample_rate_hz_cached(track) {
-            Some(source_rate) => source_rate,
-            None => {
-                if is_remote_track_path(track.path.as_path()) {
-                    return self.current_output_rate_hz;
-                }
-                self.current_output_rate_hz
-                    .or_else(|| self.verified_output_rates.last().copied())?
-            }
-        };
-        if self.verified_output_rates.contains(&source_rate) {
-            return Some(source_rate);
+        let scores = self.transition_scores.entry(from_path).or_default();
+        let score = scores.entry(to_path.clone()).or_default();
+        *score += transition_history::outcome_weight(outcome);
+        if *score == 0 {
+            scores.remove(&to_path);
         }
-        self.verified_output_rates
-            .iter()
-            .copied()
-            .find(|rate| *rate > source_rate)
-            .or_else(|| self.verified_output_rates.last().copied())
     }
 
-    fn request_runtime_output_rate_switch(
-        &mut self,
-        sample_rate_hz: u32,
-        play_immediately: bool,
-    ) -> bool {
-        if !self.can_apply_runtime_rate_switch_now() {
-            debug!(
-                "PlaylistManager: Deferring runtime output-rate switch to {} Hz until playback session is idle",
-                sample_rate_hz
-            );
-            return false;
-        }
-        if self.current_output_rate_hz == Some(sample_rate_hz) {
-            debug!(
-                "PlaylistManager: Runtime output-rate switch to {} Hz skipped (already active)",
-                sample_rate_hz
+    /// Counts the started track as played or skipped, learns how it went after its
+    /// predecessor, and returns its path, to become the predecessor of whatever plays next.
+    fn conclude_playing_track(&mut self, natural_end: bool) -> Option<PathBuf> {
+        self.started_track_id.as_ref()?;
+        let index = self
+            .playback_playlist
+            .get_playing_track_index()
+            .filter(|index| *index < self.playback_playlist.num_tracks())?;
+        let path = self.playback_playlist.get_track(index).path.clone();
+        let skipped = !natural_end
+            && transition_history::is_early_skip(
+                self.current_elapsed_ms,
+                self.current_track_duration_ms,
             );
-            return false;
+        self.record_track_play(&path, !skipped);
+        if let Some(previous) = self.transition_predecessor.take() {
+            let outcome = if skipped {
+                protocol::TransitionOutcome::Skipped
+            } else {
+                protocol::TransitionOutcome::PlayedThrough
+            };
+            self.learn_transition(previous, path.clone(), outcome);
         }
-        if self.pending_rate_switch == Some(sample_rate_hz) {
-            self.pending_rate_switch_play_immediately |= play_immediately;
-            debug!(
-                "PlaylistManager: Runtime output-rate switch to {} Hz already pending",
-                sample_rate_hz
-            );
-            return true;
+        Some(path)
+    }
+
+    /// Learns that the user picked `next_path` to follow the playing track.
+    fn begin_manual_transition(&mut self, next_path: PathBuf) {
+        self.transition_predecessor = self.conclude_playing_track(false);
+        if let Some(previous) = self.transition_predecessor.clone() {
+            self.learn_transition(previous, next_path, protocol::TransitionOutcome::Manual);
         }
+    }
 
-        match self.bus_producer.send(protocol::Message::Config(
-            protocol::ConfigMessage::SetRuntimeOutputRate {
-                sample_rate_hz,
-                reason: "playlist_rate_segment".to_string(),
-            },
-        )) {
-            Ok(_) => {
-                self.pending_rate_switch = Some(sample_rate_hz);
-                self.pending_rate_switch_play_immediately = play_immediately;
-                debug!(
-                    "PlaylistManager: Requested runtime output-rate switch to {} Hz",
-                    sample_rate_hz
-                );
-                true
+    fn reset_transition_history(&mut self) {
+        let cleared = match self.db_manager.clear_track_transition_history() {
+            Ok(cleared) => cleared,
+            Err(err) => {
+                error!("Failed to reset learned transitions: {}", err);
+                return;
+            }
+        };
+        self.transition_scores.clear();
+        self.transition_predecessor = None;
+        self.playback_playlist
+            .set_transition_scores(TransitionScores::new());
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TransitionHistoryReset { cleared },
+        ));
+    }
+
+    fn set_library_roots(&mut self, folders: Vec<String>) {
+        self.library_roots = folders
+            .iter()
+            .map(|folder| folder.trim())
+            .filter(|folder| !folder.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        self.editing_playlist
+            .set_library_roots(self.library_roots.clone());
+        self.playback_playlist
+            .set_library_roots(self.library_roots.clone());
+    }
+
+    fn restore_shuffle_excluded_paths(&mut self) {
+        match self.db_manager.get_shuffle_excluded_paths() {
+            Ok(paths) => {
+                self.shuffle_excluded_paths = paths.into_iter().collect();
             }
             Err(err) => {
-                warn!(
-                    "PlaylistManager: Failed to request runtime output-rate switch to {} Hz: {}",
-                    sample_rate_hz, err
-                );
-                false
+                error!("Failed to load shuffle exclusions: {}", err);
             }
         }
     }
 
-    fn maybe_start_pending_after_rate_switch(&mut self) {
-        if self.pending_rate_switch.is_some() {
+    fn set_tracks_excluded_from_shuffle(&mut self, paths: Vec<PathBuf>, excluded: bool) {
+        if let Err(err) = self
+            .db_manager
+            .set_tracks_excluded_from_shuffle(&paths, excluded)
+        {
+            error!("Failed to save shuffle exclusions: {}", err);
             return;
         }
-        let play_immediately = std::mem::take(&mut self.pending_rate_switch_play_immediately);
-        self.cache_tracks(play_immediately);
+        for path in paths {
+            if excluded {
+                self.shuffle_excluded_paths.insert(path);
+            } else {
+                self.shuffle_excluded_paths.remove(&path);
+            }
+        }
+        self.playback_playlist
+            .set_shuffle_excluded_paths(self.shuffle_excluded_paths.clone());
     }
 
-    fn playback_session_active(&self) -> bool {
-        self.playback_playlist.is_playing()
-            || self.playback_playlist.get_playing_track_index().is_some()
+    /// Appends tracks to the playlist named `name` (case-insensitive), creating it first
+    /// when no playlist has that name.
+    fn add_tracks_to_playlist_by_name(&mut self, name: &str, paths: Vec<PathBuf>) {
+        let name = name.trim();
+        if name.is_empty() || paths.is_empty() {
+            return;
+        }
+        let existing_id = self
+            .db_manager
+            .get_all_playlists()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|playlist| playlist.name.eq_ignore_ascii_case(name))
+            .map(|playlist| playlist.id);
+        let playlist_id = match existing_id {
+            Some(id) => id,
+            None => {
+                let id = Uuid::new_v4().to_string();
+                if let Err(err) = self.db_manager.create_playlist(&id, name) {
+                    error!("Failed to create playlist '{}': {}", name, err);
+                    return;
+                }
+                activity_log::record(
+                    &self.bus_producer,
+                    protocol::ActivityCategory::Playlist,
+                    format!("Created playlist \"{}\"", name),
+                );
+                let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+                self.broadcast_playlists_restored(playlists);
+                id
+            }
+        };
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::AddTracksToPlaylists {
+                playlist_ids: vec![playlist_id],
+                paths,
+            },
+        ));
     }
 
-    fn can_apply_runtime_rate_switch_now(&self) -> bool {
-        self.playback_route == protocol::PlaybackRoute::Local
-            && !(self.playback_session_active() && self.started_track_id.is_some())
+    fn restore_track_trim_overrides(&mut self) {
+        match self.db_manager.get_track_trim_overrides() {
+            Ok(overrides) => {
+                self.track_trim_overrides = overrides.into_iter().collect();
+            }
+            Err(err) => {
+                error!("Failed to load track transition overrides: {}", err);
+            }
+        }
+        self.broadcast_track_trim_overrides();
     }
 
-    fn handle_technical_metadata_changed(&mut self, meta: protocol::TechnicalMetadata) {
-        debug!(
-            "PlaylistManager: Received technical metadata changed for track: {:?}",
-            meta
-        );
-        self.current_track_duration_ms = meta.duration_ms;
-        if let Some(playing_index) = self.playback_playlist.get_playing_track_index() {
-            if playing_index < self.playback_playlist.num_tracks() {
-                let playing_track = self.playback_playlist.get_track(playing_index);
-                if !is_remote_track_path(playing_track.path.as_path()) {
-                    self.track_sample_rate_cache
-                        .insert(playing_track.path.clone(), Some(meta.sample_rate_hz));
-                }
-            }
+    fn broadcast_track_trim_overrides(&self) {
+        let mut overrides: Vec<(PathBuf, TrackTrims)> = self
+            .track_trim_overrides
+            .iter()
+            .map(|(path, trims)| (path.clone(), *trims))
+            .collect();
+        overrides.sort();
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TrackTrimOverridesSnapshot(overrides),
+        ));
+    }
+
+    fn set_track_trims(&mut self, paths: Vec<PathBuf>, trims: TrackTrims) {
+        if let Err(err) = self.db_manager.set_track_trims(&paths, trims) {
+            error!("Failed to save track transition overrides: {}", err);
+            return;
         }
-        // Verified-rate probing can still be in flight at startup. Allow a pre-probe switch
-        // attempt from decoder technical metadata so first playback can still be content-matched.
-        let source_rate_supported = self.verified_output_rates.is_empty()
-            || self.verified_output_rates.contains(&meta.sample_rate_hz);
-        let should_switch = self.playback_route == protocol::PlaybackRoute::Local
-            && self.sample_rate_auto_enabled
-            && self.pending_rate_switch.is_none()
-            && self.current_output_rate_hz != Some(meta.sample_rate_hz)
-            && source_rate_supported
-            && self.can_apply_runtime_rate_switch_now();
-        if should_switch {
-            debug!(
-                "PlaylistManager: Source {} Hz differs from output {:?}; requesting runtime switch before continuing playback",
-                meta.sample_rate_hz, self.current_output_rate_hz
-            );
-            self.clear_cached_tracks();
-            if !self.request_runtime_output_rate_switch(meta.sample_rate_hz, true) {
-                self.cache_tracks(true);
+        for path in paths {
+            if trims.is_empty() {
+                self.track_trim_overrides.remove(&path);
+            } else {
+                self.track_trim_overrides.insert(path, trims);
             }
-        } else {
-            debug!(
-                "PlaylistManager: Keeping output {:?} for source {} Hz (route={:?}, auto={}, pending={:?}, source_supported={})",
-                self.current_output_rate_hz,
-                meta.sample_rate_hz,
-                self.playback_route,
-                self.sample_rate_auto_enabled,
-                self.pending_rate_switch,
-                source_rate_supported
-            );
         }
+        self.broadcast_track_trim_overrides();
     }
 
-    fn snapshot_editing_playlist_tracks(&self) -> Vec<protocol::RestoredTrack> {
-        (0..self.editing_playlist.num_tracks())
-            .map(|index| {
-                let track = self.editing_playlist.get_track(index);
-                protocol::RestoredTrack {
-                    id: track.id.clone(),
-                    path: track.path.clone(),
-                }
-            })
-            .collect()
+    /// Stores a playlist's skip-silence mode and, when that playlist is playing, restarts
+    /// decoding at the current position so the change is heard right away.
+    fn set_playlist_skip_silence(&mut self, playlist_id: String, enabled: bool) {
+        if let Err(err) = self
+            .db_manager
+            .set_playlist_skip_silence(&playlist_id, enabled)
+        {
+            error!("Failed to save playlist skip-silence mode: {}", err);
+            return;
+        }
+        if enabled {
+            self.skip_silence_playlist_ids.insert(playlist_id.clone());
+        } else {
+            self.skip_silence_playlist_ids.remove(&playlist_id);
+        }
+        let playing_from_playlist =
+            self.playback_playlist_id().as_deref() == Some(playlist_id.as_str());
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistSkipSilenceChanged {
+                playlist_id,
+                enabled,
+            },
+        ));
+        if playing_from_playlist {
+            self.redecode_playing_track();
+        }
     }
 
-    fn broadcast_playlist_state_snapshot(&mut self, playlists: Vec<protocol::PlaylistInfo>) {
-        let restored_tracks = self.snapshot_editing_playlist_tracks();
+    /// Saves the artwork and description dialog. A chosen image is copied into the playlist
+    /// cover folder and replaces the previous custom cover file.
+    fn save_playlist_details(
+        &mut self,
+        index: usize,
+        description: String,
+        cover_source: Option<PathBuf>,
+        reset_cover: bool,
+    ) {
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        let Some(playlist) = playlists.get(index) else {
+            return;
+        };
+        let playlist_id = playlist.id.clone();
+        let description = description.trim().to_string();
+        let mut cover_path = playlist.cover_path.clone();
+        if let Some(source_path) = cover_source {
+            match image_pipeline::store_playlist_cover(&playlist_id, &source_path) {
+                Some(stored_path) => cover_path = Some(stored_path),
+                None => warn!(
+                    "PlaylistManager: could not read playlist cover image {}",
+                    source_path.display()
+                ),
+            }
+        } else if reset_cover {
+            cover_path = None;
+        }
+        if let Err(err) =
+            self.db_manager
+                .set_playlist_details(&playlist_id, &description, cover_path.as_deref())
+        {
+            error!("Failed to save playlist details: {}", err);
+            return;
+        }
+        if let Some(previous_path) = playlist.cover_path.as_ref() {
+            if cover_path.as_ref() != Some(previous_path) {
+                image_pipeline::remove_playlist_cover(previous_path);
+            }
+        }
         let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistRestored(restored_tracks),
+            protocol::PlaylistMessage::PlaylistDetailsChanged {
+                playlist_id,
+                description,
+                cover_path,
+            },
         ));
-        self.emit_metadata_updates_for_tracks(&self.snapshot_editing_playlist_tracks());
-        self.reconcile_editing_playlist_remote_availability();
-        self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+    }
+
+    /// Stores a playlist's loudness normalization mode and, when that playlist is playing,
+    /// restarts decoding at the current position so the new gain is heard right away.
+    fn set_playlist_normalize_loudness(&mut self, playlist_id: String, enabled: bool) {
+        if let Err(err) = self
+            .db_manager
+            .set_playlist_normalize_loudness(&playlist_id, enabled)
+        {
+            error!(
+                "Failed to save playlist loudness normalization mode: {}",
+                err
+            );
+            return;
+        }
+        if enabled {
+            self.normalize_loudness_playlist_ids
+                .insert(playlist_id.clone());
+        } else {
+            self.normalize_loudness_playlist_ids.remove(&playlist_id);
+        }
+        let playing_from_playlist =
+            self.playback_playlist_id().as_deref() == Some(playlist_id.as_str());
         let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistsRestored(playlists),
+            protocol::PlaylistMessage::PlaylistNormalizeLoudnessChanged {
+                playlist_id,
+                enabled,
+            },
         ));
-        if !self.active_playlist_id.is_empty() {
-            let _ = self.bus_producer.send(protocol::Message::Playlist(
-                protocol::PlaylistMessage::ActivePlaylistChanged(self.active_playlist_id.clone()),
+        if playing_from_playlist && !self.normalize_loudness {
+            self.redecode_playing_track();
+        }
+    }
+
+    /// Seeks the locally playing track to where it is, so decode options that changed
+    /// mid-track (skip silence, normalization gain) apply without waiting for the next one.
+    fn redecode_playing_track(&self) {
+        if self.playback_playlist.is_playing()
+            && self.playback_route == protocol::PlaybackRoute::Local
+            && self.current_track_duration_ms > 0
+        {
+            let _ = self.bus_producer.send(protocol::Message::Playback(
+                protocol::PlaybackMessage::Seek(
+                    self.current_elapsed_ms as f32 / self.current_track_duration_ms as f32,
+                ),
             ));
-            // Column layout is global (`layout.toml`), so switching active playlists must not
-            // trigger playlist-scoped column-order reloads.
         }
     }
 
-    fn sync_remote_playlists(
-        &mut self,
-        profile_id: &str,
-        playlists: Vec<protocol::RemotePlaylistSnapshot>,
-    ) {
-        self.suppress_remote_writeback = true;
-        let existing_before_sync = self.db_manager.get_all_playlists().unwrap_or_default();
-        let mut remote_playlist_ids = HashSet::new();
-        for remote_playlist in playlists {
-            let remote_playlist_id = remote_playlist.remote_playlist_id.clone();
-            let local_playlist_id =
-                format!("remote:opensubsonic:{}:{}", profile_id, remote_playlist_id);
-            remote_playlist_ids.insert(local_playlist_id.clone());
-            if !existing_before_sync
-                .iter()
-                .any(|playlist| playlist.id == local_playlist_id)
-            {
-                let _ = self
-                    .db_manager
-                    .create_playlist(&local_playlist_id, &remote_playlist.name);
-            } else {
-                let _ = self
-                    .db_manager
-                    .rename_playlist(&local_playlist_id, &remote_playlist.name);
-            }
+    fn track_trims(&self, track: &Track) -> TrackTrims {
+        self.track_trim_overrides
+            .get(&track.path)
+            .copied()
+            .unwrap_or_default()
+    }
 
-            if let Ok(existing_tracks) = self.db_manager.get_tracks_for_playlist(&local_playlist_id)
-            {
-                for track in existing_tracks {
-                    let _ = self.db_manager.delete_track(&track.id);
-                }
-            }
+    /// Analyzes local files for talk-over intros/outros off the manager thread.
+    ///
+    /// Each detection is stored through `SetTrackTrims`, so results go through the same
+    /// path as manual edits; files without a detectable intro/outro keep their trims.
+    fn spawn_track_trim_detection(&self, mut paths: Vec<PathBuf>) {
+        paths.retain(|path| !is_remote_track_path(path));
+        let bus_producer = self.bus_producer.clone();
+        // Files are analyzed in parallel on the shared analysis pool; this thread only
+        // waits for the batch so it can report the final count.
+        std::thread::spawn(move || {
+            let total = paths.len();
+            let job_bus_producer = bus_producer.clone();
+            let detected = run_analysis_task(
+                &bus_producer,
+                "Detecting intros and outros",
+                paths,
+                move |path| match intro_outro_detection::analyze_file_trims(&path) {
+                    Ok(trims) if !trims.is_empty() => {
+                        let _ = job_bus_producer.send(protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SetTrackTrims {
+                                paths: vec![path],
+                                trims,
+                            },
+                        ));
+                        true
+                    }
+                    Ok(_) => false,
+                    Err(err) => {
+                        warn!(
+                            "Intro/outro detection failed for {}: {}",
+                            path.display(),
+                            err
+                        );
+                        false
+                    }
+                },
+            );
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::TrackTrimDetectionFinished { detected, total },
+            ));
+        });
+    }
 
-            let mut pending_db_rows: Vec<(String, PathBuf)> =
-                Vec::with_capacity(remote_playlist.tracks.len());
-            let mut metadata_updates = Vec::with_capacity(remote_playlist.tracks.len());
-            for (position, remote_track) in remote_playlist.tracks.into_iter().enumerate() {
-                let local_track_id = format!(
-                    "remote-track:opensubsonic:{}:{}:{}:{}",
-                    profile_id, remote_playlist_id, remote_track.item_id, position
-                );
-                self.remote_track_metadata_by_path
-                    .insert(remote_track.path.clone(), remote_track.summary.clone());
-                metadata_updates.push(protocol::TrackMetadataPatch {
-                    track_id: local_track_id.clone(),
-                    summary: remote_track.summary,
-                });
-                pending_db_rows.push((local_track_id, remote_track.path));
-            }
-            let _ = self
-                .db_manager
-                .save_tracks_batch(&local_playlist_id, &pending_db_rows, 0);
-            if !metadata_updates.is_empty() {
+    fn restore_track_play_stats(&self) {
+        match self.db_manager.get_track_play_stats() {
+            Ok(stats) if !stats.is_empty() => {
                 let _ = self.bus_producer.send(protocol::Message::Playlist(
-                    protocol::PlaylistMessage::TrackMetadataBatchUpdated {
-                        updates: metadata_updates,
-                    },
+                    protocol::PlaylistMessage::TrackPlayStatsChanged(stats),
                 ));
             }
-        }
-        for stale_playlist in existing_before_sync.iter().filter(|playlist| {
-            playlist
-                .id
-                .strip_prefix("remote:opensubsonic:")
-                .and_then(|suffix| suffix.split_once(':'))
-                .map(|(existing_profile_id, _)| existing_profile_id == profile_id)
-                .unwrap_or(false)
-                && !remote_playlist_ids.contains(&playlist.id)
-        }) {
-            let _ = self.db_manager.delete_playlist(&stale_playlist.id);
-            if matches!(
-                self.playback_queue_source.as_ref(),
-                Some(protocol::PlaybackQueueSource::Playlist { playlist_id })
-                    if playlist_id == &stale_playlist.id
-            ) {
-                self.playback_queue_source = None;
+            Ok(_) => {}
+            Err(err) => {
+                error!("Failed to load track play stats: {}", err);
             }
         }
-        if let Ok(mut playlists) = self.db_manager.get_all_playlists() {
-            if playlists.is_empty() {
-                let default_id = Uuid::new_v4().to_string();
-                if self
-                    .db_manager
-                    .create_playlist(&default_id, "Default")
-                    .is_ok()
-                {
-                    playlists = self.db_manager.get_all_playlists().unwrap_or_default();
-                }
-            }
-            if playlists
-                .iter()
-                .all(|playlist| playlist.id != self.active_playlist_id)
-            {
-                self.active_playlist_id = playlists
-                    .first()
-                    .map(|playlist| playlist.id.clone())
-                    .unwrap_or_default();
+    }
+
+    fn record_track_play(&self, path: &Path, played: bool) {
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        match self.db_manager.record_track_play(path, played, now_unix_ms) {
+            Ok(stats) => {
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackPlayStatsChanged(vec![(
+                        path.to_path_buf(),
+                        stats,
+                    )]),
+                ));
             }
-            if !self.active_playlist_id.is_empty() {
-                self.reload_editing_playlist_from_active();
+            Err(err) => {
+                warn!("Failed to record track play: {}", err);
             }
-            self.broadcast_playlist_state_snapshot(playlists);
-            self.broadcast_playlist_changed();
-            self.broadcast_selection_changed();
         }
-        self.suppress_remote_writeback = false;
-    }
-
-    fn normalized_playlist_name(name: &str) -> String {
-        name.trim().to_string()
     }
 
-    fn generate_unique_playlist_name(existing_names: &[String], requested_name: &str) -> String {
-        let requested = Self::normalized_playlist_name(requested_name);
-        if !requested.is_empty() && !existing_names.iter().any(|name| name == &requested) {
-            return requested;
-        }
-
-        let mut suffix = 1usize;
-        loop {
-            let candidate = format!("Playlist {suffix}");
-            if !existing_names.iter().any(|name| name == &candidate) {
-                return candidate;
+    fn restore_track_loudness(&mut self) {
+        match self.db_manager.get_track_loudness() {
+            Ok(measurements) if !measurements.is_empty() => {
+                self.track_loudness_lufs = measurements
+                    .iter()
+                    .map(|(path, loudness)| (path.clone(), loudness.integrated_lufs))
+                    .collect();
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackLoudnessChanged(measurements),
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => {
+                error!("Failed to load track loudness: {}", err);
             }
-            suffix = suffix.saturating_add(1);
         }
     }
 
-    /// Intentional paste behavior:
-    /// insert after the last selected item; if nothing is selected, append.
-    /// Keep this stable so paste is predictable across playlist workflows.
-    fn paste_insert_gap(selected_indices: &[usize], playlist_len: usize) -> usize {
-        selected_indices
-            .iter()
-            .copied()
-            .filter(|&index| index < playlist_len)
-            .max()
-            .map(|index| index.saturating_add(1))
-            .unwrap_or(playlist_len)
-    }
-
-    fn capture_track_list_snapshot(&self) -> PlaylistTrackListSnapshot {
-        PlaylistTrackListSnapshot {
-            tracks: (0..self.editing_playlist.num_tracks())
-                .map(|index| self.editing_playlist.get_track(index).clone())
-                .collect(),
-            selected_indices: self.editing_playlist.get_selected_indices(),
+    fn store_track_loudness(&mut self, path: PathBuf, loudness: protocol::TrackLoudness) {
+        let analyzed_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        if let Err(err) = self
+            .db_manager
+            .save_track_loudness(&path, loudness, analyzed_unix_ms)
+        {
+            error!("Failed to save track loudness: {}", err);
+            return;
         }
+        self.track_loudness_lufs
+            .insert(path.clone(), loudness.integrated_lufs);
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TrackLoudnessChanged(vec![(path, loudness)]),
+        ));
     }
 
-    fn track_list_changed(
-        previous: &PlaylistTrackListSnapshot,
-        current: &PlaylistTrackListSnapshot,
-    ) -> bool {
-        previous.tracks != current.tracks
+    /// Measures loudness and true peak of local files on the analysis pool; each
+    /// result is stored through `StoreTrackLoudness`.
+    fn spawn_track_loudness_analysis(&self, mut paths: Vec<PathBuf>) {
+        paths.retain(|path| !is_remote_track_path(path));
+        let bus_producer = self.bus_producer.clone();
+        std::thread::spawn(move || {
+            let total = paths.len();
+            let job_bus_producer = bus_producer.clone();
+            let analyzed =
+                run_analysis_task(&bus_producer, "Measuring loudness", paths, move |path| {
+                    match loudness_analysis::analyze_file_loudness(&path) {
+                        Ok(loudness) => {
+                            let _ = job_bus_producer.send(protocol::Message::Playlist(
+                                protocol::PlaylistMessage::StoreTrackLoudness { path, loudness },
+                            ));
+                            true
+                        }
+                        Err(err) => {
+                            warn!("Loudness analysis failed for {}: {}", path.display(), err);
+                            false
+                        }
+                    }
+                });
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::TrackLoudnessAnalysisFinished { analyzed, total },
+            ));
+        });
     }
 
-    fn push_track_list_history_snapshot(
-        stack: &mut Vec<PlaylistTrackListSnapshot>,
-        snapshot: PlaylistTrackListSnapshot,
-    ) {
-        stack.push(snapshot);
-        if stack.len() > TRACK_LIST_HISTORY_LIMIT {
-            let overflow = stack.len() - TRACK_LIST_HISTORY_LIMIT;
-            stack.drain(0..overflow);
+    fn publish_track_energy(&mut self) {
+        match self.db_manager.get_track_energy() {
+            Ok(settings) => {
+                self.track_energy_levels = settings
+                    .iter()
+                    .map(|(path, setting)| (path.clone(), setting.energy))
+                    .collect();
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackEnergySnapshot(settings),
+                ));
+            }
+            Err(err) => {
+                error!("Failed to load track energy: {}", err);
+            }
         }
     }
 
-    fn push_track_list_undo_snapshot(&mut self, snapshot: PlaylistTrackListSnapshot) {
-        Self::push_track_list_history_snapshot(&mut self.track_list_undo_stack, snapshot);
-        self.track_list_redo_stack.clear();
-    }
-
-    fn clear_track_list_history(&mut self) {
-        self.track_list_undo_stack.clear();
-        self.track_list_redo_stack.clear();
-    }
-
-    fn append_tracks_to_playlist(
-        &mut self,
-        playlist_id: &str,
-        paths: &[PathBuf],
-    ) -> Vec<protocol::RestoredTrack> {
-        let existing_len = self
-            .db_manager
-            .get_tracks_for_playlist(playlist_id)
-            .map(|tracks| tracks.len())
-            .unwrap_or(0);
-
-        let pending: Vec<(String, PathBuf)> = paths
-            .iter()
-            .map(|path| (Uuid::new_v4().to_string(), path.clone()))
-            .collect();
+    fn set_track_energy(&mut self, paths: Vec<PathBuf>, energy: Option<protocol::TrackEnergy>) {
+        if paths.is_empty() {
+            return;
+        }
+        let updated_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
         if let Err(err) = self
             .db_manager
-            .save_tracks_batch(playlist_id, &pending, existing_len)
+            .set_track_energy(&paths, energy, updated_unix_ms)
         {
-            error!(
-                "Failed to append {} track(s) to playlist {} in batch: {}",
-                pending.len(),
-                playlist_id,
-                err
-            );
-            return Vec::new();
+            error!("Failed to save track energy: {}", err);
+            return;
         }
-        let inserted: Vec<protocol::RestoredTrack> = pending
-            .into_iter()
-            .map(|(id, path)| protocol::RestoredTrack { id, path })
-            .collect();
-        inserted
+        self.publish_track_energy();
     }
 
-    fn emit_metadata_updates_for_tracks(&self, tracks: &[protocol::RestoredTrack]) {
-        let updates: Vec<protocol::TrackMetadataPatch> = tracks
-            .iter()
-            .filter_map(|track| {
-                self.remote_track_metadata_by_path
-                    .get(&track.path)
-                    .cloned()
-                    .map(|summary| protocol::TrackMetadataPatch {
-                        track_id: track.id.clone(),
-                        summary,
-                    })
-            })
-            .collect();
-        if updates.is_empty() {
+    fn store_suggested_track_energy(&mut self, suggestions: Vec<(PathBuf, protocol::TrackEnergy)>) {
+        if suggestions.is_empty() {
             return;
         }
-        let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::TrackMetadataBatchUpdated { updates },
-        ));
+        let updated_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        if let Err(err) = self
+            .db_manager
+            .save_suggested_track_energy(&suggestions, updated_unix_ms)
+        {
+            error!("Failed to save suggested track energy: {}", err);
+            return;
+        }
+        self.publish_track_energy();
     }
 
-    fn restored_tracks_from_snapshot(
-        snapshot: &PlaylistTrackListSnapshot,
-    ) -> Vec<protocol::RestoredTrack> {
-        snapshot
-            .tracks
-            .iter()
-            .map(|track| protocol::RestoredTrack {
-                id: track.id.clone(),
-                path: track.path.clone(),
-            })
-            .collect()
+    /// Suggests energy levels for local files from their BPM tag and loudness.
+    ///
+    /// Files without a stored loudness measurement are measured first, and that
+    /// measurement is stored too. Suggestions go out in one `StoreSuggestedTrackEnergy`
+    /// batch, which leaves levels set by hand alone.
+    fn spawn_track_energy_suggestion(&self, mut paths: Vec<PathBuf>) {
+        paths.retain(|path| !is_remote_track_path(path));
+        let known_loudness: HashMap<PathBuf, protocol::TrackLoudness> =
+            match self.db_manager.get_track_loudness() {
+                Ok(measurements) => measurements.into_iter().collect(),
+                Err(err) => {
+                    error!("Failed to load track loudness: {}", err);
+                    HashMap::new()
+                }
+            };
+        let bus_producer = self.bus_producer.clone();
+        std::thread::spawn(move || {
+            let total = paths.len();
+            let job_bus_producer = bus_producer.clone();
+            let suggestions: Vec<(PathBuf, protocol::TrackEnergy)> = worker_pool::analysis_pool()
+                .map(paths, move |path| {
+                    let loudness = match known_loudness.get(&path) {
+                        Some(loudness) => Some(*loudness),
+                        None => match loudness_analysis::analyze_file_loudness(&path) {
+                            Ok(loudness) => {
+                                let _ = job_bus_producer.send(protocol::Message::Playlist(
+                                    protocol::PlaylistMessage::StoreTrackLoudness {
+                                        path: path.clone(),
+                                        loudness,
+                                    },
+                                ));
+                                Some(loudness)
+                            }
+                            Err(err) => {
+                                warn!("Loudness analysis failed for {}: {}", path.display(), err);
+                                None
+                            }
+                        },
+                    };
+                    let bpm = metadata_tags::read_bpm(&path);
+                    track_energy::suggest_energy(bpm, loudness.as_ref())
+                        .map(|energy| (path, energy))
+                })
+                .into_iter()
+                .flatten()
+                .collect();
+            let suggested = suggestions.len();
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::StoreSuggestedTrackEnergy { suggestions },
+            ));
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::TrackEnergySuggestionFinished { suggested, total },
+            ));
+        });
     }
 
-    fn build_playlist_from_snapshot(&self, snapshot: &PlaylistTrackListSnapshot) -> Playlist {
-        let mut playlist = Playlist::new();
-        playlist.set_playback_order(self.playback_order);
-        playlist.set_repeat_mode(self.repeat_mode);
-        for track in &snapshot.tracks {
-            playlist.add_track(track.clone());
+    /// Publishes seek bar markers once per started track, detecting them on the
+    /// analysis pool for long local files that were not analyzed before.
+    fn publish_seek_markers_for_playing_track(&mut self) {
+        let Some(playing_idx) = self.playback_playlist.get_playing_track_index() else {
+            return;
+        };
+        if playing_idx >= self.playback_playlist.num_tracks() {
+            return;
         }
-        playlist.set_selected_indices(snapshot.selected_indices.clone());
-        playlist
+        let track = self.playback_playlist.get_track(playing_idx);
+        if self.started_track_id.as_deref() != Some(track.id.as_str())
+            || self.seek_markers_track_id.as_deref() == Some(track.id.as_str())
+        {
+            return;
+        }
+        let track_id = track.id.clone();
+        let path = track.path.clone();
+        self.seek_markers_track_id = Some(track_id.clone());
+
+        let cached = self.seek_marker_cache.get(&path).cloned();
+        let needs_detection = cached.is_none()
+            && !is_remote_track_path(path.as_path())
+            && self.current_track_duration_ms >= seek_markers::MIN_MARKER_TRACK_MS;
+        let _ = self.bus_producer.send(protocol::Message::Playback(
+            protocol::PlaybackMessage::SeekMarkersChanged {
+                track_id: track_id.clone(),
+                markers: cached.unwrap_or_default(),
+            },
+        ));
+        if !needs_detection {
+            return;
+        }
+        let bus_producer = self.bus_producer.clone();
+        worker_pool::analysis_pool().submit(move || {
+            match seek_markers::detect_seek_markers(&path) {
+                Ok(markers) => {
+                    debug!(
+                        "PlaylistManager: detected {} seek marker(s) for {}",
+                        markers.len(),
+                        path.display()
+                    );
+                    let _ = bus_producer.send(protocol::Message::Playback(
+                        protocol::PlaybackMessage::SeekMarkersChanged { track_id, markers },
+                    ));
+                }
+                Err(err) => warn!(
+                    "Seek marker detection failed for {}: {}",
+                    path.display(),
+                    err
+                ),
+            }
+        });
     }
 
-    fn synchronize_active_playlist_tracks_in_db(&self, snapshot: &PlaylistTrackListSnapshot) {
-        if self.active_playlist_id.is_empty() {
-            return;
+    fn track_sample_rate_hz_cached(&mut self, track: &Track) -> Option<u32> {
+        if let Some(cached) = self.track_sample_rate_cache.get(&track.path) {
+            return *cached;
+        }
+        // Startup playback must remain non-blocking. Do not synchronously probe files in the
+        // hot path; rely on technical metadata updates to backfill this cache.
+        if !is_remote_track_path(track.path.as_path()) {
+            return None;
         }
+        self.track_sample_rate_cache
+            .insert(track.path.clone(), None);
+        None
+    }
 
-        let persisted = match self
-            .db_manager
-            .get_tracks_for_playlist(&self.active_playlist_id)
-        {
-            Ok(tracks) => tracks,
-            Err(err) => {
-                error!(
-                    "Failed to load tracks for snapshot persistence (playlist {}): {}",
-                    self.active_playlist_id, err
-                );
-                return;
+    fn desired_output_rate_for_track(&mut self, track: &Track) -> Option<u32> {
+        if !self.sample_rate_auto_enabled {
+            return self.current_output_rate_hz;
+        }
+        if self.rate_policy.policy == SampleRatePolicy::Pinned {
+            return Some(
+                self.verified_output_rate_for_source(self.rate_policy.pinned_sample_rate_hz),
+            );
+        }
+        if self.verified_output_rates.is_empty() {
+            // Startup can race with asynchronous output capability probing. When rates are still
+            // unknown, prefer the track's native rate so first-playback stays content-matched.
+            return self
+                .track_sample_rate_hz_cached(track)
+                .or(self.current_output_rate_hz);
+        }
+
+        let source_rate = match self.track_sample_rate_hz_cached(track) {
+            Some(source_rate) => source_rate,
+            None => {
+                if is_remote_track_path(track.path.as_path()) {
+                    return self.current_output_rate_hz;
+                }
+                self.current_output_rate_hz
+                    .or_else(|| self.verified_output_rates.last().copied())?
             }
         };
+        Some(self.verified_output_rate_for_source(source_rate))
+    }
 
-        let persisted_ids: HashSet<&str> =
-            persisted.iter().map(|track| track.id.as_str()).collect();
-        let snapshot_ids: HashSet<&str> = snapshot
-            .tracks
+    /// Verified output rate used for `source_rate`: the same rate, else the next higher
+    /// one, else the highest.
+    fn verified_output_rate_for_source(&self, source_rate: u32) -> u32 {
+        if self.verified_output_rates.is_empty()
+            || self.verified_output_rates.contains(&source_rate)
+        {
+            return source_rate;
+        }
+        self.verified_output_rates
             .iter()
-            .map(|track| track.id.as_str())
-            .collect();
+            .copied()
+            .find(|rate| *rate > source_rate)
+            .or_else(|| self.verified_output_rates.last().copied())
+            .unwrap_or(source_rate)
+    }
 
-        for removed in persisted
-            .iter()
-            .filter(|track| !snapshot_ids.contains(track.id.as_str()))
-        {
-            if let Err(err) = self.db_manager.delete_track(&removed.id) {
-                error!(
-                    "Failed to delete removed snapshot track {} from database: {}",
-                    removed.id, err
-                );
-            }
+    /// First index of the album the track at `index` belongs to. Tracks carry no album
+    /// tag here, so an album is a run of consecutive tracks from one folder.
+    fn album_start_index(&self, index: usize) -> usize {
+        let folder = self.playback_playlist.get_track(index).path.parent();
+        let mut start = index;
+        while start > 0 && self.playback_playlist.get_track(start - 1).path.parent() == folder {
+            start -= 1;
         }
+        start
+    }
 
-        for (position, track) in snapshot.tracks.iter().enumerate() {
-            if persisted_ids.contains(track.id.as_str()) {
-                continue;
-            }
-            if let Err(err) = self.db_manager.save_track(
-                &track.id,
-                &self.active_playlist_id,
-                track.path.to_str().unwrap_or(""),
-                position,
-            ) {
-                error!(
-                    "Failed to insert snapshot track {} at position {}: {}",
-                    track.id, position, err
-                );
+    /// Output rate for the track at `index` while the output runs at `output_rate_hz`.
+    /// With rate grouping enabled the following tracks are taken into account, so a run
+    /// of one rate family or an isolated outlier does not reopen the device per track.
+    /// The album-boundaries policy plays the whole album at the rate of its first track.
+    fn planned_output_rate_for_index(
+        &mut self,
+        index: usize,
+        output_rate_hz: Option<u32>,
+    ) -> Option<u32> {
+        let track = self.playback_playlist.get_track(index).clone();
+        let desired_rate = self
+            .desired_output_rate_for_track(&track)
+            .or(self.current_output_rate_hz);
+        if !self.sample_rate_auto_enabled {
+            return desired_rate;
+        }
+        match self.rate_policy.policy {
+            SampleRatePolicy::MatchSource => {}
+            SampleRatePolicy::Pinned => return desired_rate,
+            SampleRatePolicy::AlbumBoundaries => {
+                let album_start = self.album_start_index(index);
+                let album_track = self.playback_playlist.get_track(album_start).clone();
+                return self
+                    .track_sample_rate_hz_cached(&album_track)
+                    .map(|rate| self.verified_output_rate_for_source(rate))
+                    .or(desired_rate);
             }
         }
-
-        let ordered_ids: Vec<String> = snapshot
-            .tracks
-            .iter()
-            .map(|track| track.id.clone())
-            .collect();
-        if let Err(err) = self.db_manager.update_positions(ordered_ids) {
-            error!(
-                "Failed to update track positions for snapshot persistence: {}",
-                err
-            );
+        if !self.rate_grouping_policy.is_enabled() {
+            return desired_rate;
+        }
+        let mut upcoming = vec![desired_rate];
+        // Random order picks the next track when it is reached; looking ahead would
+        // consume its choices.
+        if self.playback_order != protocol::PlaybackOrder::Random {
+            let mut current_index = index;
+            while upcoming.len() < RATE_GROUPING_LOOKAHEAD {
+                let Some(next_index) = self.playback_playlist.get_next_track_index(current_index)
+                else {
+                    break;
+                };
+                if next_index == index
+                    || next_index == current_index
+                    || next_index >= self.playback_playlist.num_tracks()
+                {
+                    break;
+                }
+                current_index = next_index;
+                let track = self.playback_playlist.get_track(next_index).clone();
+                let rate = self
+                    .track_sample_rate_hz_cached(&track)
+                    .map(|rate| self.verified_output_rate_for_source(rate));
+                upcoming.push(rate);
+            }
         }
+        sample_rate_grouping::plan_output_rate(self.rate_grouping_policy, output_rate_hz, &upcoming)
     }
 
-    fn apply_track_list_snapshot(&mut self, snapshot: PlaylistTrackListSnapshot) {
-        self.editing_playlist = self.build_playlist_from_snapshot(&snapshot);
-
-        self.synchronize_active_playlist_tracks_in_db(&snapshot);
-
-        let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistRestored(Self::restored_tracks_from_snapshot(
-                &snapshot,
-            )),
-        ));
-
-        self.broadcast_playlist_changed();
-        self.broadcast_selection_changed();
+    /// Path of the local track at `index` when its sample rate is unknown and has not been
+    /// requested yet.
+    fn unprobed_sample_rate_path(&mut self, index: usize) -> Option<PathBuf> {
+        let path = self.playback_playlist.get_track(index).path.clone();
+        (!is_remote_track_path(path.as_path())
+            && !self.track_sample_rate_cache.contains_key(&path)
+            && self.sample_rate_probed_paths.insert(path.clone()))
+        .then_some(path)
     }
 
-    fn import_tracks_batch(&mut self, paths: Vec<PathBuf>, source: protocol::ImportSource) {
-        if paths.is_empty() {
-            return;
-        }
-        debug!(
-            "PlaylistManager: importing {} track(s) from {:?}",
-            paths.len(),
-            source
-        );
-        let previous_track_list = self.capture_track_list_snapshot();
-        let insert_at = self.editing_playlist.num_tracks();
-        let pending: Vec<(String, PathBuf)> = paths
-            .into_iter()
-            .map(|path| (Uuid::new_v4().to_string(), path))
-            .collect();
-        if let Err(err) =
-            self.db_manager
-                .save_tracks_batch(&self.active_playlist_id, &pending, insert_at)
-        {
-            error!(
-                "Failed to persist {} batched imported track(s): {}",
-                pending.len(),
-                err
-            );
+    /// Reads the sample rate of upcoming local tracks from their file headers in the
+    /// background, so rate grouping can plan ahead of playback. The album-boundaries
+    /// policy reads the first track of the album instead. Results come back as
+    /// [`protocol::PlaylistMessage::TrackFormatsProbed`].
+    fn probe_upcoming_sample_rates(&mut self, first_index: usize) {
+        if !self.sample_rate_auto_enabled || first_index >= self.playback_playlist.num_tracks() {
             return;
         }
-        let mut inserted_tracks = Vec::with_capacity(pending.len());
-        for (id, path) in pending {
-            let track = Track {
-                path: path.clone(),
-                id: id.clone(),
-            };
-            self.editing_playlist.add_track(track);
-            inserted_tracks.push(protocol::RestoredTrack { id, path });
+        let mut paths = Vec::new();
+        match self.rate_policy.policy {
+            SampleRatePolicy::Pinned => return,
+            SampleRatePolicy::AlbumBoundaries => {
+                let album_start = self.album_start_index(first_index);
+                paths.extend(self.unprobed_sample_rate_path(album_start));
+            }
+            SampleRatePolicy::MatchSource => {
+                if !self.rate_grouping_policy.is_enabled()
+                    || self.playback_order == protocol::PlaybackOrder::Random
+                {
+                    return;
+                }
+                let mut current_index = first_index;
+                for _ in 0..RATE_GROUPING_LOOKAHEAD {
+                    if current_index >= self.playback_playlist.num_tracks() {
+                        break;
+                    }
+                    paths.extend(self.unprobed_sample_rate_path(current_index));
+                    match self.playback_playlist.get_next_track_index(current_index) {
+                        Some(next_index)
+                            if next_index != current_index && next_index != first_index =>
+                        {
+                            current_index = next_index;
+                        }
+                        _ => break,
+                    }
+                }
+            }
         }
-        let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::TracksInsertedBatch {
-                tracks: inserted_tracks,
-                insert_at,
-            },
-        ));
-        if Self::track_list_changed(&previous_track_list, &self.capture_track_list_snapshot()) {
-            self.push_track_list_undo_snapshot(previous_track_list);
+        if paths.is_empty() {
+            return;
         }
-        self.broadcast_playlist_changed();
-        self.broadcast_selection_changed();
+        let bus_producer = self.bus_producer.clone();
+        worker_pool::decode_pool().submit(move || {
+            let formats: Vec<(PathBuf, protocol::TechnicalMetadata)> = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let format = format_quality::probe_track_format(&path)?;
+                    Some((path, format))
+                })
+                .collect();
+            if formats.is_empty() {
+                return;
+            }
+            let _ = bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::TrackFormatsProbed(formats),
+            ));
+        });
     }
 
-    fn drain_bulk_import_queue(&mut self) {
-        while let Ok(request) = self.bulk_import_rx.try_recv() {
-            self.import_tracks_batch(request.paths, request.source);
+    fn handle_track_formats_probed(&mut self, formats: &[(PathBuf, protocol::TechnicalMetadata)]) {
+        for (path, format) in formats {
+            if format.sample_rate_hz > 0 && !is_remote_track_path(path.as_path()) {
+                self.track_sample_rate_cache
+                    .entry(path.clone())
+                    .or_insert(Some(format.sample_rate_hz));
+            }
         }
     }
 
-    /// Starts the blocking event loop for playlist messages and playback coordination.
-    pub fn run(&mut self) {
-        // Restore playlists from database
-        let mut playlists = match self.db_manager.get_all_playlists() {
-            Ok(p) => p,
-            Err(e) => {
-                error!("Failed to get playlists from database: {}", e);
-                Vec::new()
-            }
-        };
-        if playlists.is_empty() {
-            let default_id = Uuid::new_v4().to_string();
-            if let Err(err) = self.db_manager.create_playlist(&default_id, "Default") {
-                error!("Failed to create default playlist at startup: {}", err);
-            } else {
-                playlists = self.db_manager.get_all_playlists().unwrap_or_default();
-            }
+    fn request_runtime_output_rate_switch(
+        &mut self,
+        sample_rate_hz: u32,
+        play_immediately: bool,
+    ) -> bool {
+        if !self.can_apply_runtime_rate_switch_now() {
+            debug!(
+                "PlaylistManager: Deferring runtime output-rate switch to {} Hz until playback session is idle",
+                sample_rate_hz
+            );
+            return false;
         }
+        if self.current_output_rate_hz == Some(sample_rate_hz) {
+            debug!(
+                "PlaylistManager: Runtime output-rate switch to {} Hz skipped (already active)",
+                sample_rate_hz
+            );
+            return false;
+        }
+        if self.pending_rate_switch == Some(sample_rate_hz) {
+            self.pending_rate_switch_play_immediately |= play_immediately;
+            debug!(
+                "PlaylistManager: Runtime output-rate switch to {} Hz already pending",
+                sample_rate_hz
+            );
+            return true;
+        }
+
+        match self.bus_producer.send(protocol::Message::Config(
+            protocol::ConfigMessage::SetRuntimeOutputRate {
+                sample_rate_hz,
+                reason: "playlist_rate_segment".to_string(),
+            },
+        )) {
+            Ok(_) => {
+                self.pending_rate_switch = Some(sample_rate_hz);
+                self.pending_rate_switch_play_immediately = play_immediately;
+                debug!(
+                    "PlaylistManager: Requested runtime output-rate switch to {} Hz",
+                    sample_rate_hz
+                );
+                true
+            }
+            Err(err) => {
+                warn!(
+                    "PlaylistManager: Failed to request runtime output-rate switch to {} Hz: {}",
+                    sample_rate_hz, err
+                );
+                false
+            }
+        }
+    }
+
+    fn maybe_start_pending_after_rate_switch(&mut self) {
+        if self.pending_rate_switch.is_some() {
+            return;
+        }
+        let play_immediately = std::mem::take(&mut self.pending_rate_switch_play_immediately);
+        self.cache_tracks(play_immediately);
+    }
+
+    fn playback_session_active(&self) -> bool {
+        self.playback_playlist.is_playing()
+            || self.playback_playlist.get_playing_track_index().is_some()
+    }
+
+    fn can_apply_runtime_rate_switch_now(&self) -> bool {
+        self.playback_route == protocol::PlaybackRoute::Local
+            && !(self.playback_session_active() && self.started_track_id.is_some())
+    }
+
+    fn handle_technical_metadata_changed(&mut self, meta: protocol::TechnicalMetadata) {
+        debug!(
+            "PlaylistManager: Received technical metadata changed for track: {:?}",
+            meta
+        );
+        self.current_track_duration_ms = meta.duration_ms;
+        let mut grouped_rate = None;
+        if let Some(playing_index) = self.playback_playlist.get_playing_track_index() {
+            if playing_index < self.playback_playlist.num_tracks() {
+                let playing_track = self.playback_playlist.get_track(playing_index);
+                if !is_remote_track_path(playing_track.path.as_path()) {
+                    self.track_sample_rate_cache
+                        .insert(playing_track.path.clone(), Some(meta.sample_rate_hz));
+                }
+                if self.sample_rate_auto_enabled
+                    && (self.rate_grouping_policy.is_enabled()
+                        || self.rate_policy.policy != SampleRatePolicy::MatchSource)
+                {
+                    grouped_rate = self
+                        .planned_output_rate_for_index(playing_index, self.current_output_rate_hz);
+                }
+            }
+        }
+        let target_rate = grouped_rate.unwrap_or(meta.sample_rate_hz);
+        // Verified-rate probing can still be in flight at startup. Allow a pre-probe switch
+        // attempt from decoder technical metadata so first playback can still be content-matched.
+        let source_rate_supported = grouped_rate.is_some()
+            || self.verified_output_rates.is_empty()
+            || self.verified_output_rates.contains(&meta.sample_rate_hz);
+        let should_switch = self.playback_route == protocol::PlaybackRoute::Local
+            && self.sample_rate_auto_enabled
+            && self.pending_rate_switch.is_none()
+            && self.current_output_rate_hz != Some(target_rate)
+            && source_rate_supported
+            && self.can_apply_runtime_rate_switch_now();
+        if should_switch {
+            debug!(
+                "PlaylistManager: Source {} Hz needs output {} Hz instead of {:?}; requesting runtime switch before continuing playback",
+                meta.sample_rate_hz, target_rate, self.current_output_rate_hz
+            );
+            self.clear_cached_tracks();
+            if !self.request_runtime_output_rate_switch(target_rate, true) {
+                self.cache_tracks(true);
+            }
+        } else {
+            debug!(
+                "PlaylistManager: Keeping output {:?} for source {} Hz (route={:?}, auto={}, pending={:?}, source_supported={})",
+                self.current_output_rate_hz,
+                meta.sample_rate_hz,
+                self.playback_route,
+                self.sample_rate_auto_enabled,
+                self.pending_rate_switch,
+                source_rate_supported
+            );
+        }
+        self.announce_output_rate_decision(
+            meta.sample_rate_hz,
+            should_switch.then_some(target_rate),
+        );
+    }
+
+    /// Why the playing track's output runs at `output_rate_hz` instead of its own rate.
+    fn output_rate_reason(
+        &self,
+        index: usize,
+        source_rate_hz: u32,
+        output_rate_hz: u32,
+    ) -> protocol::OutputRateReason {
+        if !self.sample_rate_auto_enabled {
+            return protocol::OutputRateReason::FixedRate;
+        }
+        if self.rate_policy.policy == SampleRatePolicy::Pinned {
+            return protocol::OutputRateReason::PinnedForDevice;
+        }
+        if output_rate_hz == source_rate_hz {
+            return protocol::OutputRateReason::MatchesSource;
+        }
+        if self.rate_policy.policy == SampleRatePolicy::AlbumBoundaries
+            && self.album_start_index(index) != index
+        {
+            return protocol::OutputRateReason::AlbumRate;
+        }
+        if !self.verified_output_rates.is_empty()
+            && !self.verified_output_rates.contains(&source_rate_hz)
+            && output_rate_hz == self.verified_output_rate_for_source(source_rate_hz)
+        {
+            return protocol::OutputRateReason::NearestSupported;
+        }
+        if self.rate_policy.policy == SampleRatePolicy::MatchSource {
+            if self.rate_grouping_policy.group_families
+                && output_rate_hz > source_rate_hz
+                && sample_rate_grouping::rate_family(output_rate_hz)
+                    == sample_rate_grouping::rate_family(source_rate_hz)
+            {
+                return protocol::OutputRateReason::SameFamily;
+            }
+            if self.rate_grouping_policy.resample_outliers && self.pending_rate_switch.is_none() {
+                return protocol::OutputRateReason::IsolatedTrack;
+            }
+        }
+        protocol::OutputRateReason::SwitchPending
+    }
+
+    /// Tells the now-playing view which rate local output uses for the playing track and
+    /// why. `switching_to_hz` is set when a switch was just requested.
+    fn announce_output_rate_decision(&self, source_rate_hz: u32, switching_to_hz: Option<u32>) {
+        if self.playback_route != protocol::PlaybackRoute::Local {
+            return;
+        }
+        let Some(playing_index) = self.playback_playlist.get_playing_track_index() else {
+            return;
+        };
+        let Some(output_rate_hz) = switching_to_hz.or(self.current_output_rate_hz) else {
+            return;
+        };
+        if playing_index >= self.playback_playlist.num_tracks() {
+            return;
+        }
+        let reason = self.output_rate_reason(playing_index, source_rate_hz, output_rate_hz);
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::OutputRateDecided(protocol::OutputRateDecision {
+                output_rate_hz,
+                reason,
+            }),
+        ));
+    }
+
+    fn snapshot_editing_playlist_tracks(&self) -> Vec<protocol::RestoredTrack> {
+        (0..self.editing_playlist.num_tracks())
+            .map(|index| {
+                let track = self.editing_playlist.get_track(index);
+                protocol::RestoredTrack {
+                    id: track.id.clone(),
+                    path: track.path.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn broadcast_playlist_state_snapshot(&mut self, playlists: Vec<protocol::PlaylistInfo>) {
+        let restored_tracks = self.snapshot_editing_playlist_tracks();
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistRestored(restored_tracks),
+        ));
+        self.broadcast_active_playlist_track_sources();
+        self.emit_metadata_updates_for_tracks(&self.snapshot_editing_playlist_tracks());
+        self.reconcile_editing_playlist_remote_availability();
+        self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+        self.broadcast_playlists_restored(playlists);
+        if !self.active_playlist_id.is_empty() {
+            let _ = self.bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::ActivePlaylistChanged(self.active_playlist_id.clone()),
+            ));
+            // Column layout is global (`layout.toml`), so switching active playlists must not
+            // trigger playlist-scoped column-order reloads.
+        }
+    }
+
+    /// Appends the active playlist rows after the startup window and sends them to the UI as
+    /// one continuation batch, so the first rows render before the whole playlist is read.
+    fn restore_remaining_active_playlist_tracks(&mut self, offset: usize) {
+        let remaining = match self.db_manager.get_tracks_for_playlist_range(
+            &self.active_playlist_id,
+            offset,
+            usize::MAX,
+        ) {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                error!("Failed to restore remaining playlist tracks: {}", err);
+                return;
+            }
+        };
+        if remaining.is_empty() {
+            return;
+        }
+        for track in &remaining {
+            self.editing_playlist.add_track(Track {
+                path: track.path.clone(),
+                id: track.id.clone(),
+            });
+        }
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistRestoreContinued(remaining.clone()),
+        ));
+        self.broadcast_active_playlist_track_sources();
+        self.emit_metadata_updates_for_tracks(&remaining);
+        self.reconcile_editing_playlist_remote_availability();
+    }
+
+    /// Sends the sidebar snapshot: all playlists together with the folders organizing them.
+    fn broadcast_playlists_restored(&self, playlists: Vec<protocol::PlaylistInfo>) {
+        let folders = self.db_manager.get_playlist_folders().unwrap_or_default();
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistsRestored { playlists, folders },
+        ));
+    }
+
+    /// Rebroadcasts the sidebar snapshot after a folder change.
+    fn broadcast_playlist_tree(&self) {
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        self.broadcast_playlists_restored(playlists);
+    }
+
+    fn sync_remote_playlists(
+        &mut self,
+        profile_id: &str,
+        playlists: Vec<protocol::RemotePlaylistSnapshot>,
+    ) {
+        self.suppress_remote_writeback = true;
+        let existing_before_sync = self.db_manager.get_all_playlists().unwrap_or_default();
+        let mut remote_playlist_ids = HashSet::new();
+        for remote_playlist in playlists {
+            let remote_playlist_id = remote_playlist.remote_playlist_id.clone();
+            let local_playlist_id =
+                format!("remote:opensubsonic:{}:{}", profile_id, remote_playlist_id);
+            remote_playlist_ids.insert(local_playlist_id.clone());
+            if !existing_before_sync
+                .iter()
+                .any(|playlist| playlist.id == local_playlist_id)
+            {
+                let _ = self
+                    .db_manager
+                    .create_playlist(&local_playlist_id, &remote_playlist.name);
+            } else {
+                let _ = self
+                    .db_manager
+                    .rename_playlist(&local_playlist_id, &remote_playlist.name);
+            }
+
+            if self.remote_sync.has_local_edits_pending(&local_playlist_id) {
+                // Keep unwritten local edits; the next pull after their writeback applies.
+                let remote_song_ids: Vec<String> = remote_playlist
+                    .tracks
+                    .iter()
+                    .map(|track| track.item_id.clone())
+                    .collect();
+                if self.stored_remote_song_ids(&local_playlist_id) != Some(remote_song_ids) {
+                    self.remote_sync.record_pull_deferred(&local_playlist_id);
+                }
+                continue;
+            }
+            self.remote_sync
+                .record_pull_applied(&local_playlist_id, Self::now_unix_ms());
+
+            if let Ok(existing_tracks) = self.db_manager.get_tracks_for_playlist(&local_playlist_id)
+            {
+                for track in existing_tracks {
+                    let _ = self.db_manager.delete_track(&track.id);
+                }
+            }
+
+            let mut pending_db_rows: Vec<(String, PathBuf)> =
+                Vec::with_capacity(remote_playlist.tracks.len());
+            let mut metadata_updates = Vec::with_capacity(remote_playlist.tracks.len());
+            for (position, remote_track) in remote_playlist.tracks.into_iter().enumerate() {
+                let local_track_id = format!(
+                    "remote-track:opensubsonic:{}:{}:{}:{}",
+                    profile_id, remote_playlist_id, remote_track.item_id, position
+                );
+                self.remote_track_metadata_by_path
+                    .insert(remote_track.path.clone(), remote_track.summary.clone());
+                metadata_updates.push(protocol::TrackMetadataPatch {
+                    track_id: local_track_id.clone(),
+                    summary: remote_track.summary,
+                });
+                pending_db_rows.push((local_track_id, remote_track.path));
+            }
+            let _ = self
+                .db_manager
+                .save_tracks_batch(&local_playlist_id, &pending_db_rows, 0);
+            if !metadata_updates.is_empty() {
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackMetadataBatchUpdated {
+                        updates: metadata_updates,
+                    },
+                ));
+            }
+        }
+        for stale_playlist in existing_before_sync.iter().filter(|playlist| {
+            playlist
+                .id
+                .strip_prefix("remote:opensubsonic:")
+                .and_then(|suffix| suffix.split_once(':'))
+                .map(|(existing_profile_id, _)| existing_profile_id == profile_id)
+                .unwrap_or(false)
+                && !remote_playlist_ids.contains(&playlist.id)
+        }) {
+            let _ = self.db_manager.delete_playlist(&stale_playlist.id);
+            self.remote_sync.forget(&stale_playlist.id);
+            if matches!(
+                self.playback_queue_source.as_ref(),
+                Some(protocol::PlaybackQueueSource::Playlist { playlist_id })
+                    if playlist_id == &stale_playlist.id
+            ) {
+                self.playback_queue_source = None;
+            }
+        }
+        if let Ok(mut playlists) = self.db_manager.get_all_playlists() {
+            if playlists.is_empty() {
+                let default_id = Uuid::new_v4().to_string();
+                if self
+                    .db_manager
+                    .create_playlist(&default_id, "Default")
+                    .is_ok()
+                {
+                    playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                }
+            }
+            if playlists
+                .iter()
+                .all(|playlist| playlist.id != self.active_playlist_id)
+            {
+                self.active_playlist_id = playlists
+                    .first()
+                    .map(|playlist| playlist.id.clone())
+                    .unwrap_or_default();
+            }
+            if !self.active_playlist_id.is_empty() {
+                self.reload_editing_playlist_from_active();
+            }
+            self.broadcast_playlist_state_snapshot(playlists);
+            self.broadcast_playlist_changed();
+            self.broadcast_selection_changed();
+        }
+        self.suppress_remote_writeback = false;
+        self.emit_remote_sync_statuses();
+    }
+
+    fn now_unix_ms() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or(0)
+    }
+
+    fn spawn_url_playlist_refresh_ticker(bus_producer: Sender<protocol::Message>) {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(URL_PLAYLIST_REFRESH_CHECK_INTERVAL);
+            if bus_producer
+                .send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::RefreshUrlPlaylists,
+                ))
+                .is_err()
+            {
+                break;
+            }
+        });
+    }
+
+    fn restore_url_playlist_titles(&mut self) {
+        let titles = match self.db_manager.get_url_playlist_track_titles() {
+            Ok(titles) => titles,
+            Err(err) => {
+                warn!(
+                    "PlaylistManager: Failed to load URL playlist titles: {}",
+                    err
+                );
+                return;
+            }
+        };
+        for (path, title, artist) in titles {
+            self.remote_track_metadata_by_path.insert(
+                path,
+                protocol::TrackMetadataSummary {
+                    title,
+                    artist,
+                    album: String::new(),
+                    album_artist: String::new(),
+                    date: String::new(),
+                    genre: String::new(),
+                    year: String::new(),
+                    track_number: String::new(),
+                    composer: String::new(),
+                    disc_number: String::new(),
+                    total_tracks: String::new(),
+                    comment: String::new(),
+                },
+            );
+        }
+    }
+
+    fn request_due_url_playlist_refreshes(&mut self) {
+        let sources = match self.db_manager.get_playlist_url_sources() {
+            Ok(sources) => sources,
+            Err(err) => {
+                warn!(
+                    "PlaylistManager: Failed to load playlist URL sources: {}",
+                    err
+                );
+                return;
+            }
+        };
+        let now_ms = Self::now_unix_ms();
+        let interval_ms = PLAYLIST_URL_REFRESH_INTERVAL.as_millis() as i64;
+        for source in sources {
+            if now_ms.saturating_sub(source.refreshed_unix_ms) < interval_ms
+                || !self
+                    .url_playlist_refreshes_in_flight
+                    .insert(source.playlist_id.clone())
+            {
+                continue;
+            }
+            debug!(
+                "PlaylistManager: Refreshing URL playlist {} from {}",
+                source.playlist_id, source.url
+            );
+            let _ = self.bus_producer.send(protocol::Message::Integration(
+                protocol::IntegrationMessage::FetchPlaylistUrl {
+                    local_playlist_id: Some(source.playlist_id),
+                    url: source.url,
+                },
+            ));
+        }
+    }
+
+    /// Creates (for a new import) or replaces the tracks of a URL-bound playlist.
+    ///
+    /// Refreshes that return the same entries only bump the refresh time, so an unchanged
+    /// server-side list never disturbs the playlist view.
+    fn apply_playlist_url_fetch(
+        &mut self,
+        local_playlist_id: Option<String>,
+        url: String,
+        snapshot: protocol::RemotePlaylistSnapshot,
+    ) {
+        let existing_playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        let playlist_id = match local_playlist_id {
+            Some(playlist_id) => {
+                if !existing_playlists
+                    .iter()
+                    .any(|playlist| playlist.id == playlist_id)
+                {
+                    return;
+                }
+                let existing_paths: Vec<PathBuf> = self
+                    .db_manager
+                    .get_tracks_for_playlist(&playlist_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|track| track.path)
+                    .collect();
+                let unchanged = existing_paths.len() == snapshot.tracks.len()
+                    && existing_paths
+                        .iter()
+                        .zip(snapshot.tracks.iter())
+                        .all(|(existing, fetched)| existing == &fetched.path);
+                if unchanged {
+                    let _ = self.db_manager.upsert_playlist_url_source(
+                        &playlist_id,
+                        &url,
+                        Self::now_unix_ms(),
+                    );
+                    return;
+                }
+                playlist_id
+            }
+            None => {
+                let existing_names: Vec<String> = existing_playlists
+                    .iter()
+                    .map(|playlist| playlist.name.clone())
+                    .collect();
+                let name = Self::generate_unique_playlist_name(&existing_names, &snapshot.name);
+                let playlist_id = Uuid::new_v4().to_string();
+                if let Err(err) = self.db_manager.create_playlist(&playlist_id, &name) {
+                    error!("Failed to create URL playlist in database: {}", err);
+                    return;
+                }
+                info!(
+                    "PlaylistManager: Imported URL playlist {} ({}) with {} entries",
+                    name,
+                    playlist_id,
+                    snapshot.tracks.len()
+                );
+                playlist_id
+            }
+        };
+
+        if let Ok(existing_tracks) = self.db_manager.get_tracks_for_playlist(&playlist_id) {
+            for track in existing_tracks {
+                let _ = self.db_manager.delete_track(&track.id);
+            }
+        }
+        let mut rows: Vec<(String, PathBuf)> = Vec::with_capacity(snapshot.tracks.len());
+        let mut titles: Vec<(String, String, String)> = Vec::with_capacity(snapshot.tracks.len());
+        for entry in snapshot.tracks {
+            let track_id = Uuid::new_v4().to_string();
+            titles.push((
+                track_id.clone(),
+                entry.summary.title.clone(),
+                entry.summary.artist.clone(),
+            ));
+            self.remote_track_metadata_by_path
+                .insert(entry.path.clone(), entry.summary);
+            rows.push((track_id, entry.path));
+        }
+        if let Err(err) = self.db_manager.save_tracks_batch(&playlist_id, &rows, 0) {
+            error!("Failed to save URL playlist tracks: {}", err);
+        }
+        let _ = self.db_manager.save_track_display_titles(&titles);
+        let _ = self
+            .db_manager
+            .upsert_playlist_url_source(&playlist_id, &url, Self::now_unix_ms());
+
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        if playlist_id == self.active_playlist_id {
+            self.reload_editing_playlist_from_active();
+            self.broadcast_playlist_state_snapshot(playlists);
+            self.broadcast_playlist_changed();
+            self.broadcast_selection_changed();
+        } else {
+            self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+            self.broadcast_playlists_restored(playlists);
+        }
+    }
+
+    fn normalized_playlist_name(name: &str) -> String {
+        name.trim().to_string()
+    }
+
+    fn generate_unique_playlist_name(existing_names: &[String], requested_name: &str) -> String {
+        let requested = Self::normalized_playlist_name(requested_name);
+        if !requested.is_empty() && !existing_names.iter().any(|name| name == &requested) {
+            return requested;
+        }
+
+        let mut suffix = 1usize;
+        loop {
+            let candidate = format!("Playlist {suffix}");
+            if !existing_names.iter().any(|name| name == &candidate) {
+                return candidate;
+            }
+            suffix = suffix.saturating_add(1);
+        }
+    }
+
+    /// Intentional paste behavior:
+    /// insert after the last selected item; if nothing is selected, append.
+    /// Keep this stable so paste is predictable across playlist workflows.
+    fn paste_insert_gap(selected_indices: &[usize], playlist_len: usize) -> usize {
+        selected_indices
+            .iter()
+            .copied()
+            .filter(|&index| index < playlist_len)
+            .max()
+            .map(|index| index.saturating_add(1))
+            .unwrap_or(playlist_len)
+    }
+
+    fn capture_track_list_snapshot(&self) -> PlaylistTrackListSnapshot {
+        PlaylistTrackListSnapshot {
+            tracks: (0..self.editing_playlist.num_tracks())
+                .map(|index| self.editing_playlist.get_track(index).clone())
+                .collect(),
+            selected_indices: self.editing_playlist.get_selected_indices(),
+        }
+    }
+
+    fn track_list_changed(
+        previous: &PlaylistTrackListSnapshot,
+        current: &PlaylistTrackListSnapshot,
+    ) -> bool {
+        previous.tracks != current.tracks
+    }
+
+    fn push_track_list_history_snapshot(
+        stack: &mut Vec<PlaylistTrackListSnapshot>,
+        snapshot: PlaylistTrackListSnapshot,
+    ) {
+        stack.push(snapshot);
+        if stack.len() > TRACK_LIST_HISTORY_LIMIT {
+            let overflow = stack.len() - TRACK_LIST_HISTORY_LIMIT;
+            stack.drain(0..overflow);
+        }
+    }
+
+    fn push_track_list_undo_snapshot(&mut self, snapshot: PlaylistTrackListSnapshot) {
+        Self::push_track_list_history_snapshot(&mut self.track_list_undo_stack, snapshot);
+        self.track_list_redo_stack.clear();
+        self.restructure_undo_playlist_ids.clear();
+    }
+
+    fn clear_track_list_history(&mut self) {
+        self.track_list_undo_stack.clear();
+        self.track_list_redo_stack.clear();
+    }
+
+    fn append_tracks_to_playlist(
+        &mut self,
+        playlist_id: &str,
+        paths: &[PathBuf],
+    ) -> Vec<protocol::RestoredTrack> {
+        let existing_len = self
+            .db_manager
+            .get_tracks_for_playlist(playlist_id)
+            .map(|tracks| tracks.len())
+            .unwrap_or(0);
+
+        let pending: Vec<(String, PathBuf)> = paths
+            .iter()
+            .map(|path| (Uuid::new_v4().to_string(), path.clone()))
+            .collect();
+        if let Err(err) = self
+            .db_manager
+            .save_tracks_batch(playlist_id, &pending, existing_len)
+        {
+            error!(
+                "Failed to append {} track(s) to playlist {} in batch: {}",
+                pending.len(),
+                playlist_id,
+                err
+            );
+            return Vec::new();
+        }
+        self.record_track_sources(
+            pending.iter().map(|(id, _)| id),
+            protocol::ImportSource::Library,
+        );
+        let inserted: Vec<protocol::RestoredTrack> = pending
+            .into_iter()
+            .map(|(id, path)| protocol::RestoredTrack { id, path })
+            .collect();
+        inserted
+    }
+
+    /// Persists how the entries in `track_ids` were added and announces it.
+    fn record_track_sources<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a String>,
+        source: protocol::ImportSource,
+    ) {
+        let sources: Vec<(String, protocol::ImportSource)> = track_ids
+            .into_iter()
+            .map(|track_id| (track_id.clone(), source))
+            .collect();
+        if let Err(err) = self.db_manager.save_track_sources(&sources) {
+            error!("Failed to record track sources: {}", err);
+            return;
+        }
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TrackSourcesChanged(sources),
+        ));
+    }
+
+    fn broadcast_active_playlist_track_sources(&self) {
+        match self
+            .db_manager
+            .get_track_sources_for_playlist(&self.active_playlist_id)
+        {
+            Ok(sources) => {
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackSourcesChanged(sources),
+                ));
+            }
+            Err(err) => error!("Failed to load track sources: {}", err),
+        }
+    }
+
+    fn emit_metadata_updates_for_tracks(&self, tracks: &[protocol::RestoredTrack]) {
+        let updates: Vec<protocol::TrackMetadataPatch> = tracks
+            .iter()
+            .filter_map(|track| {
+                self.remote_track_metadata_by_path
+                    .get(&track.path)
+                    .cloned()
+                    .map(|summary| protocol::TrackMetadataPatch {
+                        track_id: track.id.clone(),
+                        summary,
+                    })
+            })
+            .collect();
+        if updates.is_empty() {
+            return;
+        }
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TrackMetadataBatchUpdated { updates },
+        ));
+    }
+
+    fn restored_tracks_from_snapshot(
+        snapshot: &PlaylistTrackListSnapshot,
+    ) -> Vec<protocol::RestoredTrack> {
+        snapshot
+            .tracks
+            .iter()
+            .map(|track| protocol::RestoredTrack {
+                id: track.id.clone(),
+                path: track.path.clone(),
+            })
+            .collect()
+    }
+
+    fn build_playlist_from_snapshot(&self, snapshot: &PlaylistTrackListSnapshot) -> Playlist {
+        let mut playlist = Playlist::new();
+        playlist.set_playback_order(self.playback_order);
+        playlist.set_repeat_mode(self.repeat_mode);
+        playlist.set_library_roots(self.library_roots.clone());
+        for track in &snapshot.tracks {
+            playlist.add_track(track.clone());
+        }
+        playlist.set_selected_indices(snapshot.selected_indices.clone());
+        playlist
+    }
+
+    fn synchronize_active_playlist_tracks_in_db(&self, snapshot: &PlaylistTrackListSnapshot) {
+        if self.active_playlist_id.is_empty() {
+            return;
+        }
+
+        let persisted = match self
+            .db_manager
+            .get_tracks_for_playlist(&self.active_playlist_id)
+        {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                error!(
+                    "Failed to load tracks for snapshot persistence (playlist {}): {}",
+                    self.active_playlist_id, err
+                );
+                return;
+            }
+        };
+
+        let persisted_ids: HashSet<&str> =
+            persisted.iter().map(|track| track.id.as_str()).collect();
+        let snapshot_ids: HashSet<&str> = snapshot
+            .tracks
+            .iter()
+            .map(|track| track.id.as_str())
+            .collect();
+
+        for removed in persisted
+            .iter()
+            .filter(|track| !snapshot_ids.contains(track.id.as_str()))
+        {
+            if let Err(err) = self.db_manager.delete_track(&removed.id) {
+                error!(
+                    "Failed to delete removed snapshot track {} from database: {}",
+                    removed.id, err
+                );
+            }
+        }
+
+        for (position, track) in snapshot.tracks.iter().enumerate() {
+            if persisted_ids.contains(track.id.as_str()) {
+                continue;
+            }
+            if let Err(err) = self.db_manager.save_track(
+                &track.id,
+                &self.active_playlist_id,
+                track.path.to_str().unwrap_or(""),
+                position,
+            ) {
+                error!(
+                    "Failed to insert snapshot track {} at position {}: {}",
+                    track.id, position, err
+                );
+            }
+        }
+
+        let ordered_ids: Vec<String> = snapshot
+            .tracks
+            .iter()
+            .map(|track| track.id.clone())
+            .collect();
+        if let Err(err) = self.db_manager.update_positions(ordered_ids) {
+            error!(
+                "Failed to update track positions for snapshot persistence: {}",
+                err
+            );
+        }
+    }
+
+    fn apply_track_list_snapshot(&mut self, snapshot: PlaylistTrackListSnapshot) {
+        self.editing_playlist = self.build_playlist_from_snapshot(&snapshot);
+
+        self.synchronize_active_playlist_tracks_in_db(&snapshot);
+
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistRestored(Self::restored_tracks_from_snapshot(
+                &snapshot,
+            )),
+        ));
+
+        self.broadcast_playlist_changed();
+        self.broadcast_selection_changed();
+    }
+
+    fn import_tracks_batch(&mut self, paths: Vec<PathBuf>, source: protocol::ImportSource) {
+        if paths.is_empty() {
+            return;
+        }
+        debug!(
+            "PlaylistManager: importing {} track(s) from {:?}",
+            paths.len(),
+            source
+        );
+        let previous_track_list = self.capture_track_list_snapshot();
+        let insert_at = self.editing_playlist.num_tracks();
+        let pending: Vec<(String, PathBuf)> = paths
+            .into_iter()
+            .map(|path| (Uuid::new_v4().to_string(), path))
+            .collect();
+        if let Err(err) =
+            self.db_manager
+                .save_tracks_batch(&self.active_playlist_id, &pending, insert_at)
+        {
+            error!(
+                "Failed to persist {} batched imported track(s): {}",
+                pending.len(),
+                err
+            );
+            return;
+        }
+        self.record_track_sources(pending.iter().map(|(id, _)| id), source);
+        let mut inserted_tracks = Vec::with_capacity(pending.len());
+        for (id, path) in pending {
+            let track = Track {
+                path: path.clone(),
+                id: id.clone(),
+            };
+            self.editing_playlist.add_track(track);
+            inserted_tracks.push(protocol::RestoredTrack { id, path });
+        }
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::TracksInsertedBatch {
+                tracks: inserted_tracks,
+                insert_at,
+            },
+        ));
+        if Self::track_list_changed(&previous_track_list, &self.capture_track_list_snapshot()) {
+            self.push_track_list_undo_snapshot(previous_track_list);
+        }
+        self.broadcast_playlist_changed();
+        self.broadcast_selection_changed();
+    }
+
+    fn drain_bulk_import_queue(&mut self) {
+        while let Ok(request) = self.bulk_import_rx.try_recv() {
+            self.import_tracks_batch(request.paths, request.source);
+        }
+    }
+
+    /// Starts the blocking event loop for playlist messages and playback coordination.
+    pub fn run(&mut self) {
+        self.restore_url_playlist_titles();
+        self.restore_track_trim_overrides();
+        self.restore_track_loudness();
+        self.restore_track_play_stats();
+        self.publish_track_energy();
+        self.restore_transition_history();
+        self.restore_shuffle_excluded_paths();
+        Self::spawn_url_playlist_refresh_ticker(self.bus_producer.clone());
+        // Restore playlists from database
+        let mut playlists = match self.db_manager.get_all_playlists() {
+            Ok(p) => p,
+            Err(e) => {
+                error!("Failed to get playlists from database: {}", e);
+                Vec::new()
+            }
+        };
+        if playlists.is_empty() {
+            let default_id = Uuid::new_v4().to_string();
+            if let Err(err) = self.db_manager.create_playlist(&default_id, "Default") {
+                error!("Failed to create default playlist at startup: {}", err);
+            } else {
+                playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+            }
+        }
+        self.skip_silence_playlist_ids = playlists
+            .iter()
+            .filter(|playlist| playlist.skip_silence)
+            .map(|playlist| playlist.id.clone())
+            .collect();
+        self.normalize_loudness_playlist_ids = playlists
+            .iter()
+            .filter(|playlist| playlist.normalize_loudness)
+            .map(|playlist| playlist.id.clone())
+            .collect();
 
         if !playlists.is_empty() {
             self.active_playlist_id = playlists[0].id.clone();
@@ -1500,29 +3424,42 @@ impl PlaylistManager {
                 playlists[0].name, playlists[0].id
             );
 
-            // Restore tracks for active playlist
+            // Restore the visible window of the active playlist first
             self.editing_playlist = Playlist::new();
             self.editing_playlist
-                .set_playback_order(self.playback_order);
-            self.editing_playlist.set_repeat_mode(self.repeat_mode);
-            match self
-                .db_manager
-                .get_tracks_for_playlist(&self.active_playlist_id)
-            {
+                .set_playback_order(self.playback_order);
+            self.editing_playlist.set_repeat_mode(self.repeat_mode);
+            self.editing_playlist
+                .set_library_roots(self.library_roots.clone());
+            let window_len = match self.db_manager.get_tracks_for_playlist_range(
+                &self.active_playlist_id,
+                0,
+                STARTUP_RESTORE_WINDOW,
+            ) {
                 Ok(tracks) => {
-                    info!("Restoring {} tracks from database", tracks.len());
                     for track in tracks.iter() {
                         self.editing_playlist.add_track(Track {
                             path: track.path.clone(),
                             id: track.id.clone(),
                         });
                     }
+                    tracks.len()
                 }
                 Err(e) => {
                     error!("Failed to restore tracks from database: {}", e);
+                    0
                 }
-            }
+            };
             self.broadcast_playlist_state_snapshot(playlists);
+            crate::startup_timing::mark("active playlist window restored");
+            if window_len == STARTUP_RESTORE_WINDOW {
+                self.restore_remaining_active_playlist_tracks(window_len);
+            }
+            info!(
+                "Restored {} tracks from database",
+                self.editing_playlist.num_tracks()
+            );
+            crate::startup_timing::mark("active playlist fully restored");
         }
 
         loop {
@@ -1574,6 +3511,36 @@ impl PlaylistManager {
                     ) => {
                         self.drain_bulk_import_queue();
                     }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::RefreshUrlPlaylists) => {
+                        self.request_due_url_playlist_refreshes();
+                    }
+                    protocol::Message::Integration(
+                        protocol::IntegrationMessage::PlaylistUrlFetched {
+                            local_playlist_id,
+                            url,
+                            result,
+                        },
+                    ) => {
+                        if let Some(playlist_id) = local_playlist_id.as_ref() {
+                            self.url_playlist_refreshes_in_flight.remove(playlist_id);
+                        }
+                        match result {
+                            Ok(snapshot) => {
+                                self.apply_playlist_url_fetch(local_playlist_id, url, snapshot)
+                            }
+                            Err(error) => {
+                                // Keep the last good entries; retry on the next due refresh.
+                                if let Some(playlist_id) = local_playlist_id {
+                                    let _ = self.db_manager.upsert_playlist_url_source(
+                                        &playlist_id,
+                                        &url,
+                                        Self::now_unix_ms(),
+                                    );
+                                }
+                                debug!("PlaylistManager: URL playlist fetch failed: {}", error);
+                            }
+                        }
+                    }
                     protocol::Message::Playlist(protocol::PlaylistMessage::LoadTracksBatch {
                         paths,
                         source,
@@ -1670,16 +3637,31 @@ impl PlaylistManager {
                                     self.update_runtime_policy_from_output_delta(&output);
                                 }
                                 protocol::ConfigDeltaEntry::Ui(ui) => {
+                                    if let Some(arc) = ui.energy_ramp_arc {
+                                        self.energy_ramp_arc = energy_flow_for_ramp_arc(arc);
+                                    }
                                     if !self.playback_preferences_restored_from_config {
                                         playback_changed =
                                             self.restore_playback_preferences_from_ui_delta(&ui);
                                         self.playback_preferences_restored_from_config = true;
                                     }
                                 }
+                                protocol::ConfigDeltaEntry::Library(library) => {
+                                    if let Some(folders) = library.folders {
+                                        self.set_library_roots(folders);
+                                    }
+                                }
                                 protocol::ConfigDeltaEntry::Cast(_)
-                                | protocol::ConfigDeltaEntry::Library(_)
                                 | protocol::ConfigDeltaEntry::Buffering(_)
-                                | protocol::ConfigDeltaEntry::Integrations(_) => {}
+                                | protocol::ConfigDeltaEntry::Integrations(_)
+                                | protocol::ConfigDeltaEntry::Announcements(_)
+                                | protocol::ConfigDeltaEntry::Power(_) => {}
+                                protocol::ConfigDeltaEntry::Dsp(dsp) => {
+                                    if let Some(enabled) = dsp.normalize_loudness {
+                                        self.normalize_loudness = enabled;
+                                        self.redecode_playing_track();
+                                    }
+                                }
                             }
                         }
                         if playback_changed {
@@ -1763,13 +3745,24 @@ impl PlaylistManager {
                     ) => {}
                     protocol::Message::Cast(protocol::CastMessage::ConnectionStateChanged {
                         state,
+                        device,
                         ..
                     }) => match state {
                         protocol::CastConnectionState::Connected => {
+                            let device_id = device.map(|device| device.id);
+                            // Re-announcements for the same target (e.g. after a device refresh)
+                            // must not restart the remote track; a new target takes over playback.
+                            let target_changed = self.playback_route
+                                != protocol::PlaybackRoute::Cast
+                                || self.cast_device_id != device_id;
                             self.playback_route = protocol::PlaybackRoute::Cast;
-                            self.handoff_to_cast_if_playing();
+                            self.cast_device_id = device_id;
+                            if target_changed {
+                                self.handoff_to_cast_if_playing();
+                            }
                         }
                         protocol::CastConnectionState::Disconnected => {
+                            self.cast_device_id = None;
                             if self.playback_route == protocol::PlaybackRoute::Cast {
                                 let was_playing = self.playback_playlist.is_playing();
                                 self.playback_route = protocol::PlaybackRoute::Local;
@@ -1787,6 +3780,11 @@ impl PlaylistManager {
                         protocol::CastConnectionState::Discovering
                         | protocol::CastConnectionState::Connecting => {}
                     },
+                    protocol::Message::Cast(protocol::CastMessage::ReceiverJumpedToTrack {
+                        track_id,
+                    }) => {
+                        self.follow_cast_receiver_jump(&track_id);
+                    }
                     protocol::Message::Playback(protocol::PlaybackMessage::Play) => {
                         debug!("PlaylistManager: Received play resume command");
                         let has_paused_track = !self.playback_playlist.is_playing()
@@ -1814,10 +3812,28 @@ impl PlaylistManager {
                     protocol::Message::Playback(protocol::PlaybackMessage::StartQueue(request)) => {
                         self.start_playback_queue(request);
                     }
+                    protocol::Message::Playback(
+                        protocol::PlaybackMessage::ResumeJournaledTrack {
+                            playlist_id,
+                            track_id,
+                            elapsed_ms,
+                        },
+                    ) => {
+                        self.resume_journaled_track(playlist_id, track_id, elapsed_ms);
+                    }
+                    protocol::Message::Playback(protocol::PlaybackMessage::EnqueueTracks {
+                        tracks,
+                        play_next,
+                    }) => {
+                        self.enqueue_tracks(tracks, play_next);
+                    }
                     protocol::Message::Playback(protocol::PlaybackMessage::Stop) => {
                         debug!("PlaylistManager: Received stop command");
+                        self.transition_predecessor = None;
+                        let _ = self.conclude_playing_track(false);
                         self.pending_start_track_id = None;
                         self.started_track_id = None;
+                        self.seek_markers_track_id = None;
                         self.playback_playlist.set_playing(false);
                         self.playback_playlist.set_playing_track_index(None);
                         self.playback_queue_source = None;
@@ -1858,6 +3874,7 @@ impl PlaylistManager {
                             if let Some(next_index) =
                                 self.playback_playlist.get_next_track_index(current_index)
                             {
+                                self.transition_predecessor = self.conclude_playing_track(false);
                                 self.play_playback_track(next_index, true);
                             }
                         }
@@ -1873,6 +3890,7 @@ impl PlaylistManager {
                                 .playback_playlist
                                 .get_previous_track_index(current_index)
                             {
+                                self.transition_predecessor = None;
                                 self.play_playback_track(prev_index, false);
                             }
                         }
@@ -1896,6 +3914,7 @@ impl PlaylistManager {
                             );
                             continue;
                         }
+                        self.transition_predecessor = self.conclude_playing_track(true);
                         self.pending_start_track_id = None;
                         self.started_track_id = None;
                         if let Some(playing_idx) = self.playback_playlist.get_playing_track_index()
@@ -2074,6 +4093,31 @@ impl PlaylistManager {
                     ) => {
                         self.prune_active_playlist_paths(paths);
                     }
+                    protocol::Message::Library(
+                        protocol::LibraryMessage::OrganizeFilesApplied { moves, .. },
+                    ) => {
+                        self.remap_organized_track_paths(moves);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::VerifyPlaylistFiles(index),
+                    ) => {
+                        self.verify_playlist_files(index);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::PlaylistFilesVerified {
+                            checked_ids,
+                            missing,
+                            ..
+                        },
+                    ) => {
+                        self.apply_verified_playlist_files(checked_ids, &missing);
+                    }
+                    protocol::Message::Library(protocol::LibraryMessage::TrackPathsRelocated {
+                        moves,
+                        ..
+                    }) => {
+                        self.apply_relocated_track_paths(moves);
+                    }
                     protocol::Message::Playlist(protocol::PlaylistMessage::SelectTrackMulti {
                         index,
                         ctrl,
@@ -2243,6 +4287,10 @@ impl PlaylistManager {
                         }
                     }
                     protocol::Message::Playlist(protocol::PlaylistMessage::UndoTrackListEdit) => {
+                        if !self.restructure_undo_playlist_ids.is_empty() {
+                            self.undo_playlist_restructure();
+                            continue;
+                        }
                         let Some(previous_snapshot) = self.track_list_undo_stack.pop() else {
                             continue;
                         };
@@ -2264,6 +4312,27 @@ impl PlaylistManager {
                         );
                         self.apply_track_list_snapshot(next_snapshot);
                     }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::SortTracks(order)) => {
+                        let previous_track_list = self.capture_track_list_snapshot();
+                        if !self.editing_playlist.sort_tracks(order) {
+                            debug!("PlaylistManager: ignoring sort order for a changed playlist");
+                            continue;
+                        }
+                        let new_ids: Vec<String> = (0..self.editing_playlist.num_tracks())
+                            .map(|index| self.editing_playlist.get_track_id(index))
+                            .collect();
+                        if let Err(err) = self.db_manager.update_positions(new_ids) {
+                            error!("Failed to update positions in database: {}", err);
+                        }
+                        if Self::track_list_changed(
+                            &previous_track_list,
+                            &self.capture_track_list_snapshot(),
+                        ) {
+                            self.push_track_list_undo_snapshot(previous_track_list);
+                        }
+                        self.broadcast_playlist_changed();
+                        self.broadcast_selection_changed();
+                    }
                     protocol::Message::Playlist(
                         protocol::PlaylistMessage::ApplyFilterViewSnapshot(source_indices),
                     ) => {
@@ -2326,11 +4395,14 @@ impl PlaylistManager {
                         if let Err(e) = self.db_manager.create_playlist(&id, &resolved_name) {
                             error!("Failed to create playlist in database: {}", e);
                         } else {
+                            activity_log::record(
+                                &self.bus_producer,
+                                protocol::ActivityCategory::Playlist,
+                                format!("Created playlist \"{}\"", resolved_name),
+                            );
                             let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
                             self.emit_opensubsonic_sync_eligible_playlists(&playlists);
-                            let _ = self.bus_producer.send(protocol::Message::Playlist(
-                                protocol::PlaylistMessage::PlaylistsRestored(playlists),
-                            ));
+                            self.broadcast_playlists_restored(playlists);
                         }
                     }
                     protocol::Message::Playlist(
@@ -2348,10 +4420,13 @@ impl PlaylistManager {
                         id,
                         name,
                     }) => {
-                        let existing_playlist_names = self
-                            .db_manager
-                            .get_all_playlists()
-                            .unwrap_or_default()
+                        let all_playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        let previous_name = all_playlists
+                            .iter()
+                            .find(|playlist| playlist.id == id)
+                            .map(|playlist| playlist.name.clone())
+                            .unwrap_or_default();
+                        let existing_playlist_names = all_playlists
                             .into_iter()
                             .filter(|playlist| playlist.id != id)
                             .map(|playlist| playlist.name)
@@ -2365,13 +4440,156 @@ impl PlaylistManager {
                         if let Err(e) = self.db_manager.rename_playlist(&id, &resolved_name) {
                             error!("Failed to rename playlist in database: {}", e);
                         } else {
+                            if previous_name != resolved_name {
+                                activity_log::record(
+                                    &self.bus_producer,
+                                    protocol::ActivityCategory::Playlist,
+                                    format!(
+                                        "Renamed playlist \"{}\" to \"{}\"",
+                                        previous_name, resolved_name
+                                    ),
+                                );
+                            }
                             let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
                             self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+                            self.broadcast_playlists_restored(playlists);
+                        }
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::SetPlaylistNote {
+                        playlist_id,
+                        note,
+                    }) => {
+                        let note = note.trim_end().to_string();
+                        if let Err(e) = self.db_manager.set_playlist_note(&playlist_id, &note) {
+                            error!("Failed to save playlist note in database: {}", e);
+                        } else {
+                            let _ = self.bus_producer.send(protocol::Message::Playlist(
+                                protocol::PlaylistMessage::PlaylistNoteChanged {
+                                    playlist_id,
+                                    note,
+                                },
+                            ));
+                        }
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SetPlaylistColumnFilters {
+                            playlist_id,
+                            filters,
+                        },
+                    ) => {
+                        if let Err(e) = self
+                            .db_manager
+                            .set_playlist_column_filters(&playlist_id, &filters)
+                        {
+                            error!("Failed to save playlist column filters in database: {}", e);
+                        } else {
                             let _ = self.bus_producer.send(protocol::Message::Playlist(
-                                protocol::PlaylistMessage::PlaylistsRestored(playlists),
+                                protocol::PlaylistMessage::PlaylistColumnFiltersChanged {
+                                    playlist_id,
+                                    filters,
+                                },
                             ));
                         }
                     }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SavePlaylistDetailsByIndex {
+                            index,
+                            description,
+                            cover_source,
+                            reset_cover,
+                        },
+                    ) => {
+                        self.save_playlist_details(index, description, cover_source, reset_cover);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SetPlaylistSkipSilence {
+                            playlist_id,
+                            enabled,
+                        },
+                    ) => {
+                        self.set_playlist_skip_silence(playlist_id, enabled);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SetPlaylistNormalizeLoudness {
+                            playlist_id,
+                            enabled,
+                        },
+                    ) => {
+                        self.set_playlist_normalize_loudness(playlist_id, enabled);
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::SetTrackTrims {
+                        paths,
+                        trims,
+                    }) => {
+                        self.set_track_trims(paths, trims);
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::DetectTrackTrims {
+                        paths,
+                    }) => {
+                        self.spawn_track_trim_detection(paths);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::AnalyzeTrackLoudness { paths },
+                    ) => {
+                        self.spawn_track_loudness_analysis(paths);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::StoreTrackLoudness { path, loudness },
+                    ) => {
+                        self.store_track_loudness(path, loudness);
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::SetTrackEnergy {
+                        paths,
+                        energy,
+                    }) => {
+                        self.set_track_energy(paths, energy);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SuggestTrackEnergy { paths },
+                    ) => {
+                        self.spawn_track_energy_suggestion(paths);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::StoreSuggestedTrackEnergy { suggestions },
+                    ) => {
+                        self.store_suggested_track_energy(suggestions);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::ResetTransitionHistory,
+                    ) => {
+                        self.reset_transition_history();
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::AddTracksToPlaylistByName { name, paths },
+                    ) => {
+                        self.add_tracks_to_playlist_by_name(&name, paths);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::CreatePlaylistWithTracks { name, paths },
+                    ) => {
+                        let _ = self.create_playlist_with_tracks(&name, paths);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SetTracksExcludedFromShuffle { paths, excluded },
+                    ) => {
+                        self.set_tracks_excluded_from_shuffle(paths, excluded);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SetPreferRemoteCopy {
+                            path,
+                            prefer_remote,
+                        },
+                    ) => {
+                        self.set_prefer_remote_copy(path, prefer_remote);
+                    }
+                    protocol::Message::Library(protocol::LibraryMessage::RemoteCopiesSnapshot(
+                        copies,
+                    )) => {
+                        self.remote_copies_by_path = copies.into_iter().collect();
+                        let remote_copies_by_path = &self.remote_copies_by_path;
+                        self.remote_source_paths
+                            .retain(|path| remote_copies_by_path.contains_key(path));
+                    }
                     protocol::Message::Playlist(
                         protocol::PlaylistMessage::DeletePlaylistByIndex(index),
                     ) => {
@@ -2395,6 +4613,83 @@ impl PlaylistManager {
                             ));
                         }
                     }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SplitPlaylistByIndex { index, rule },
+                    ) => {
+                        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        if let Some(playlist) = playlists.get(index) {
+                            let playlist_id = playlist.id.clone();
+                            self.split_playlist(&playlist_id, rule);
+                        }
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::MergePlaylistsByIndex { indices, mode },
+                    ) => {
+                        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        let playlist_ids: Vec<String> = indices
+                            .iter()
+                            .filter_map(|index| playlists.get(*index))
+                            .map(|playlist| playlist.id.clone())
+                            .collect();
+                        self.merge_playlists(&playlist_ids, &mode);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SyncRemotePlaylistNowByIndex(index),
+                    ) => {
+                        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        if let Some(playlist) = playlists.get(index) {
+                            let playlist_id = playlist.id.clone();
+                            self.sync_remote_playlist_now(&playlist_id);
+                        }
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::MaterializeRemotePlaylistByIndex(index),
+                    ) => {
+                        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        if let Some(playlist) = playlists.get(index) {
+                            let playlist_id = playlist.id.clone();
+                            self.request_materialize_remote_playlist(&playlist_id);
+                        }
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::ExportPlaylistXspfByIndex { index, path },
+                    ) => {
+                        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+                        if let Some(playlist) = playlists.get(index) {
+                            let playlist_id = playlist.id.clone();
+                            let message = self
+                                .export_playlist_xspf(&playlist_id, &path)
+                                .unwrap_or_else(|error| format!("XSPF export failed: {error}"));
+                            self.emit_xspf_transfer_finished(message);
+                        }
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::ImportXspfPlaylist(
+                        path,
+                    )) => {
+                        let message = self
+                            .import_xspf_playlist(&path)
+                            .unwrap_or_else(|error| format!("XSPF import failed: {error}"));
+                        self.emit_xspf_transfer_finished(message);
+                    }
+                    protocol::Message::Integration(
+                        protocol::IntegrationMessage::RemotePlaylistMaterialized {
+                            local_playlist_id,
+                            result,
+                            ..
+                        },
+                    ) => {
+                        self.materializing_playlist_ids.remove(&local_playlist_id);
+                        if let Ok(local_paths) = result {
+                            if let Err(error) =
+                                self.materialize_remote_playlist(&local_playlist_id, local_paths)
+                            {
+                                error!(
+                                    "Failed to make playlist {} local: {}",
+                                    local_playlist_id, error
+                                );
+                            }
+                        }
+                    }
                     protocol::Message::Playlist(
                         protocol::PlaylistMessage::SyncPlaylistToOpenSubsonic { id },
                     ) => {
@@ -2404,10 +4699,34 @@ impl PlaylistManager {
                         id,
                     }) => {
                         debug!("PlaylistManager: Deleting playlist {}", id);
+                        let (deleted_name, deleted_cover_path) = self
+                            .db_manager
+                            .get_all_playlists()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .find(|playlist| playlist.id == id)
+                            .map(|playlist| (playlist.name, playlist.cover_path))
+                            .unwrap_or_default();
+                        let deleted_track_count = self
+                            .db_manager
+                            .get_tracks_for_playlist(&id)
+                            .map(|tracks| tracks.len())
+                            .unwrap_or(0);
 
                         if let Err(e) = self.db_manager.delete_playlist(&id) {
                             error!("Failed to delete playlist from database: {}", e);
                         } else {
+                            if let Some(cover_path) = deleted_cover_path {
+                                image_pipeline::remove_playlist_cover(&cover_path);
+                            }
+                            activity_log::record(
+                                &self.bus_producer,
+                                protocol::ActivityCategory::Playlist,
+                                format!(
+                                    "Deleted playlist \"{}\" ({} track(s))",
+                                    deleted_name, deleted_track_count
+                                ),
+                            );
                             if matches!(
                                 self.playback_queue_source.as_ref(),
                                 Some(protocol::PlaybackQueueSource::Playlist { playlist_id })
@@ -2442,12 +4761,85 @@ impl PlaylistManager {
                                     ),
                                 ),
                             ));
-                            let _ = self.bus_producer.send(protocol::Message::Playlist(
-                                protocol::PlaylistMessage::PlaylistsRestored(playlists),
-                            ));
+                            self.broadcast_playlists_restored(playlists);
                             self.broadcast_playlist_changed();
                         }
                     }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::CreatePlaylistFolder { name, parent_id },
+                    ) => {
+                        let name = Self::normalized_playlist_name(&name);
+                        if name.is_empty() {
+                            continue;
+                        }
+                        let id = Uuid::new_v4().to_string();
+                        if let Err(err) =
+                            self.db_manager
+                                .create_playlist_folder(&id, &name, parent_id.as_deref())
+                        {
+                            error!("Failed to create playlist folder: {}", err);
+                            continue;
+                        }
+                        self.broadcast_playlist_tree();
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::RenamePlaylistFolder { id, name },
+                    ) => {
+                        let name = Self::normalized_playlist_name(&name);
+                        if name.is_empty() {
+                            continue;
+                        }
+                        if let Err(err) = self.db_manager.rename_playlist_folder(&id, &name) {
+                            error!("Failed to rename playlist folder {}: {}", id, err);
+                            continue;
+                        }
+                        self.broadcast_playlist_tree();
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::DeletePlaylistFolder { id },
+                    ) => {
+                        if let Err(err) = self.db_manager.delete_playlist_folder(&id) {
+                            error!("Failed to delete playlist folder {}: {}", id, err);
+                            continue;
+                        }
+                        self.broadcast_playlist_tree();
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::MovePlaylistToFolder {
+                            playlist_id,
+                            folder_id,
+                        },
+                    ) => {
+                        if let Err(err) = self
+                            .db_manager
+                            .set_playlist_parent(&playlist_id, folder_id.as_deref())
+                        {
+                            error!("Failed to move playlist {} to folder: {}", playlist_id, err);
+                            continue;
+                        }
+                        self.broadcast_playlist_tree();
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::MovePlaylistFolder { id, parent_id },
+                    ) => {
+                        let folders = self.db_manager.get_playlist_folders().unwrap_or_default();
+                        if playlist_folders::would_create_cycle(&folders, &id, parent_id.as_deref())
+                        {
+                            debug!(
+                                "PlaylistManager: ignoring move of folder {} into itself",
+                                id
+                            );
+                            continue;
+                        }
+                        if let Err(err) = self
+                            .db_manager
+                            .set_playlist_folder_parent(&id, parent_id.as_deref())
+                        {
+                            error!("Failed to move playlist folder {}: {}", id, err);
+                            continue;
+                        }
+                        self.broadcast_playlist_tree();
+                    }
                     protocol::Message::Playlist(
                         protocol::PlaylistMessage::SwitchPlaylistByIndex(index),
                     ) => {
@@ -2492,6 +4884,8 @@ impl PlaylistManager {
                                 self.editing_playlist
                                     .set_playback_order(self.playback_order);
                                 self.editing_playlist.set_repeat_mode(self.repeat_mode);
+                                self.editing_playlist
+                                    .set_library_roots(self.library_roots.clone());
                                 for track in tracks.iter() {
                                     self.editing_playlist.add_track(Track {
                                         path: track.path.clone(),
@@ -2501,6 +4895,7 @@ impl PlaylistManager {
                                 let _ = self.bus_producer.send(protocol::Message::Playlist(
                                     protocol::PlaylistMessage::PlaylistRestored(tracks),
                                 ));
+                                self.broadcast_active_playlist_track_sources();
                                 self.emit_metadata_updates_for_tracks(
                                     &self.snapshot_editing_playlist_tracks(),
                                 );
@@ -2519,11 +4914,19 @@ impl PlaylistManager {
                     protocol::Message::Integration(
                         protocol::IntegrationMessage::BackendSnapshotUpdated(snapshot),
                     ) => {
-                        self.backend_connection_states.clear();
+                        let previous_states = std::mem::take(&mut self.backend_connection_states);
+                        let mut reconnected = false;
                         for profile in snapshot.profiles {
+                            reconnected |= profile.connection_state
+                                == protocol::BackendConnectionState::Connected
+                                && previous_states.get(&profile.profile_id)
+                                    != Some(&protocol::BackendConnectionState::Connected);
                             self.backend_connection_states
                                 .insert(profile.profile_id, profile.connection_state);
                         }
+                        if reconnected {
+                            self.recover_unavailable_remote_tracks();
+                        }
                         self.reconcile_editing_playlist_remote_availability();
                     }
                     protocol::Message::Playlist(
@@ -2553,6 +4956,10 @@ impl PlaylistManager {
                                     genre: track.genre,
                                     year: track.year,
                                     track_number: track.track_number,
+                                    composer: String::new(),
+                                    disc_number: String::new(),
+                                    total_tracks: String::new(),
+                                    comment: String::new(),
                                 },
                             );
                         }
@@ -2575,6 +4982,12 @@ impl PlaylistManager {
                             error,
                         },
                     ) => {
+                        self.remote_sync.record_writeback_result(
+                            &local_playlist_id,
+                            success,
+                            Self::now_unix_ms(),
+                        );
+                        self.emit_remote_sync_statuses();
                         let _ = self.bus_producer.send(protocol::Message::Playlist(
                             protocol::PlaylistMessage::RemotePlaylistWritebackState {
                                 playlist_id: local_playlist_id,
@@ -2613,6 +5026,11 @@ impl PlaylistManager {
                             &remote_playlist_id,
                         ) {
                             Ok(remote_bound_playlist_id) => {
+                                self.remote_sync.record_pull_applied(
+                                    &remote_bound_playlist_id,
+                                    Self::now_unix_ms(),
+                                );
+                                self.emit_remote_sync_statuses();
                                 let playlists =
                                     self.db_manager.get_all_playlists().unwrap_or_default();
                                 self.broadcast_playlist_state_snapshot(playlists);
@@ -2647,8 +5065,18 @@ impl PlaylistManager {
                             "PlaylistManager: Received TrackCached: {} at offset {}",
                             id, offset
                         );
-                        self.requested_track_offsets.remove(&id);
-                        self.cached_track_ids.insert(id.clone(), offset);
+                        // A track-start decode may begin after a hidden pregap; keep it keyed
+                        // at offset 0 so it still counts as cached at the track start.
+                        if self.requested_track_offsets.remove(&id) == Some(0) && offset > 0 {
+                            self.hidden_pregap_offsets.insert(id.clone(), offset);
+                        }
+                        let cached_offset =
+                            if self.hidden_pregap_offsets.get(&id).copied() == Some(offset) {
+                                0
+                            } else {
+                                offset
+                            };
+                        self.cached_track_ids.insert(id.clone(), cached_offset);
                         self.fully_cached_track_ids.insert(id.clone());
 
                         // Check if this was the track we were waiting for to change playback order
@@ -2672,6 +5100,11 @@ impl PlaylistManager {
                         self.cached_track_ids.remove(&id);
                         self.fully_cached_track_ids.remove(&id);
                     }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::TrackFormatsProbed(
+                        formats,
+                    )) => {
+                        self.handle_track_formats_probed(&formats);
+                    }
                     protocol::Message::Playlist(
                         protocol::PlaylistMessage::ChangePlaybackOrder(order),
                     ) => {
@@ -2706,6 +5139,7 @@ impl PlaylistManager {
                             self.clear_cached_tracks();
                             self.cache_tracks(false);
                         }
+                        self.broadcast_upcoming_tracks();
                     }
                     protocol::Message::Playlist(protocol::PlaylistMessage::ToggleRepeat) => {
                         let repeat_mode = self.playback_playlist.toggle_repeat();
@@ -2715,6 +5149,23 @@ impl PlaylistManager {
                         let _ = self.bus_producer.send(protocol::Message::Playlist(
                             protocol::PlaylistMessage::RepeatModeChanged(repeat_mode),
                         ));
+                        self.broadcast_upcoming_tracks();
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::PlayUpcomingTrack(
+                        position,
+                    )) => {
+                        self.play_upcoming_track(position);
+                    }
+                    protocol::Message::Playlist(protocol::PlaylistMessage::MoveUpcomingTrack {
+                        from,
+                        to,
+                    }) => {
+                        self.move_upcoming_track(from, to);
+                    }
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::SavePlaybackQueueAsPlaylist { shuffle_order },
+                    ) => {
+                        self.save_playback_queue_as_playlist(shuffle_order);
                     }
                     protocol::Message::Playback(
                         protocol::PlaybackMessage::TechnicalMetadataChanged(meta),
@@ -2734,6 +5185,24 @@ impl PlaylistManager {
                         self.current_elapsed_ms = elapsed_ms;
                         if total_ms > 0 {
                             self.current_track_duration_ms = total_ms.max(elapsed_ms);
+                            self.publish_seek_markers_for_playing_track();
+                        }
+                    }
+                    protocol::Message::Playback(
+                        protocol::PlaybackMessage::SeekMarkersChanged { track_id, markers },
+                    ) => {
+                        if markers.is_empty() {
+                            continue;
+                        }
+                        let playing_path = self
+                            .playback_playlist
+                            .get_playing_track_index()
+                            .filter(|idx| *idx < self.playback_playlist.num_tracks())
+                            .map(|idx| self.playback_playlist.get_track(idx))
+                            .filter(|track| track.id == track_id)
+                            .map(|track| track.path.clone());
+                        if let Some(path) = playing_path {
+                            self.seek_marker_cache.insert(path, markers);
                         }
                     }
                     protocol::Message::Playback(protocol::PlaybackMessage::Seek(percentage)) => {
@@ -2752,290 +5221,904 @@ impl PlaylistManager {
                             self.current_track_duration_ms
                         );
 
+                        self.seek_playing_track_to_ms(target_ms);
+                    }
+                    protocol::Message::Playback(protocol::PlaybackMessage::SetVolume(volume)) => {
                         if self.playback_route == protocol::PlaybackRoute::Cast {
                             let _ = self.bus_producer.send(protocol::Message::Cast(
-                                protocol::CastMessage::SeekMs(target_ms),
+                                protocol::CastMessage::SetVolume(volume),
                             ));
-                            self.current_elapsed_ms = target_ms;
-                            continue;
                         }
+                    }
+                    _ => trace!("PlaylistManager: ignoring unsupported message"),
+                },
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!(
+                        "PlaylistManager lagged on control bus, skipped {} message(s)",
+                        skipped
+                    );
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                    error!("PlaylistManager: bus closed");
+                    break;
+                }
+            }
+        }
+    }
 
-                        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index()
-                        {
-                            if playing_idx < self.playback_playlist.num_tracks() {
-                                let track = self.playback_playlist.get_track(playing_idx);
-                                let track_id = track.id.clone();
-                                let track_path = track.path.clone();
-
-                                // Remove from cached list since it's no longer cached at offset 0
-                                self.cached_track_ids.remove(&track_id);
-                                self.fully_cached_track_ids.remove(&track_id);
-                                self.pending_start_track_id = None;
-                                self.started_track_id = None;
-                                self.requested_track_offsets.clear();
+    fn apply_playback_order_change(&mut self, _order: protocol::PlaybackOrder) {
+        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index() {
+            let current_id = self.playback_playlist.get_track_id(playing_idx);
 
-                                // 1. Stop current decoding
-                                self.stop_decoding();
+            // 1. Stop current decoding
+            self.stop_decoding();
 
-                                // 2. Clear player cache
-                                let _ = self.bus_producer.send(protocol::Message::Playback(
-                                    protocol::PlaybackMessage::ClearPlayerCache,
-                                ));
+            // 2. Clear our knowledge of cached tracks except current
+            let current_offset = self.cached_track_ids.get(&current_id).cloned().unwrap_or(0);
+            self.cached_track_ids.clear();
+            self.fully_cached_track_ids.clear();
+            self.cached_track_ids
+                .insert(current_id.clone(), current_offset);
+            self.fully_cached_track_ids.insert(current_id);
 
-                                // 3. Restart decoding at offset
-                                let _ = self.bus_producer.send(protocol::Message::Audio(
-                                    protocol::AudioMessage::DecodeTracks(vec![TrackIdentifier {
-                                        id: track_id.clone(),
-                                        path: track_path,
-                                        play_immediately: true,
-                                        start_offset_ms: target_ms,
-                                    }]),
-                                ));
-                                self.requested_track_offsets.insert(track_id, target_ms);
-                            }
+            // 3. Tell player to clear its buffer after the current track
+            let _ = self.bus_producer.send(protocol::Message::Playback(
+                protocol::PlaybackMessage::ClearNextTracks,
+            ));
+
+            // 4. Start caching according to new order
+            self.cache_tracks(false);
+        }
+    }
+
+    fn cache_tracks(&mut self, play_immediately: bool) {
+        if self.playback_playlist.num_tracks() == 0 {
+            return;
+        }
+        if self.pending_rate_switch.is_some() {
+            self.pending_rate_switch_play_immediately |= play_immediately;
+            return;
+        }
+
+        let first_index = self
+            .playback_playlist
+            .get_playing_track_index()
+            .unwrap_or(0);
+        self.probe_upcoming_sample_rates(first_index);
+        let desired_first_rate =
+            self.planned_output_rate_for_index(first_index, self.current_output_rate_hz);
+        if let Some(desired_first_rate) = desired_first_rate {
+            if self.current_output_rate_hz != Some(desired_first_rate)
+                && self.request_runtime_output_rate_switch(desired_first_rate, play_immediately)
+            {
+                return;
+            }
+        }
+
+        let mut current_index = first_index;
+        let mut track_paths = Vec::new();
+        let mut staged_track_ids = HashSet::new();
+        let mut segment_rate: Option<u32> = desired_first_rate;
+
+        for _ in 0..self.max_num_cached_tracks {
+            if current_index < self.playback_playlist.num_tracks() {
+                let track_id = self.playback_playlist.get_track_id(current_index);
+                let is_cached_at_track_start =
+                    self.cached_track_ids.get(&track_id).copied() == Some(0);
+                let is_requested_at_track_start =
+                    self.requested_track_offsets.get(&track_id).copied() == Some(0)
+                        || staged_track_ids.contains(&track_id);
+                if is_cached_at_track_start || is_requested_at_track_start {
+                    if let Some(next_index) =
+                        self.playback_playlist.get_next_track_index(current_index)
+                    {
+                        if next_index == current_index {
+                            break;
                         }
+                        current_index = next_index;
+                        if next_index >= self.playback_playlist.num_tracks() {
+                            break;
+                        }
+                    } else {
+                        break;
                     }
-                    protocol::Message::Playback(protocol::PlaybackMessage::SetVolume(volume)) => {
-                        if self.playback_route == protocol::PlaybackRoute::Cast {
-                            let _ = self.bus_producer.send(protocol::Message::Cast(
-                                protocol::CastMessage::SetVolume(volume),
-                            ));
+                    continue;
+                }
+
+                let desired_rate = self.planned_output_rate_for_index(
+                    current_index,
+                    segment_rate.or(self.current_output_rate_hz),
+                );
+                if let Some(required_segment_rate) = segment_rate {
+                    if desired_rate != Some(required_segment_rate) {
+                        break;
+                    }
+                } else {
+                    segment_rate = desired_rate;
+                }
+
+                let track = self.playback_playlist.get_track(current_index).clone();
+                let trims = self.track_trims(&track);
+                track_paths.push(TrackIdentifier {
+                    id: track_id.clone(),
+                    path: track.path.clone(),
+                    play_immediately: play_immediately && current_index == first_index,
+                    start_offset_ms: 0,
+                    trim_start_ms: trims.start_ms,
+                    trim_end_ms: trims.end_ms,
+                    skip_silence: self.playback_skips_silence(),
+                    normalization_gain_db: self.playback_normalization_gain_db(&track.path),
+                });
+                staged_track_ids.insert(track_id);
+            }
+            if let Some(next_index) = self.playback_playlist.get_next_track_index(current_index) {
+                if next_index == current_index {
+                    break;
+                }
+                current_index = next_index;
+                if next_index >= self.playback_playlist.num_tracks() {
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+        debug!(
+            "PlaylistManager: Updated cached tracks: {:?}",
+            self.cached_track_ids
+        );
+        if !track_paths.is_empty() {
+            for track in &track_paths {
+                self.requested_track_offsets
+                    .insert(track.id.clone(), track.start_offset_ms);
+            }
+            let _ = self.bus_producer.send(protocol::Message::Audio(
+                protocol::AudioMessage::DecodeTracks(track_paths),
+            ));
+        }
+    }
+
+    fn clear_cached_tracks(&mut self) {
+        self.pending_start_track_id = None;
+        self.started_track_id = None;
+        self.pending_rate_switch = None;
+        self.pending_rate_switch_play_immediately = false;
+        self.cached_track_ids.clear();
+        self.fully_cached_track_ids.clear();
+        self.requested_track_offsets.clear();
+        let _ = self.bus_producer.send(protocol::Message::Audio(
+            protocol::AudioMessage::StopDecoding,
+        ));
+        let _ = self.bus_producer.send(protocol::Message::Playback(
+            protocol::PlaybackMessage::ClearPlayerCache,
+        ));
+    }
+
+    fn stop_decoding(&mut self) {
+        self.pending_start_track_id = None;
+        self.pending_rate_switch = None;
+        self.pending_rate_switch_play_immediately = false;
+        self.requested_track_offsets.clear();
+        let _ = self.bus_producer.send(protocol::Message::Audio(
+            protocol::AudioMessage::StopDecoding,
+        ));
+    }
+
+    /// Broadcast the current playlist/playback state to all listeners.
+    ///
+    /// **Important**: `playing_index` is the position inside `playback_playlist`,
+    /// which is a queue snapshot built in **view order** (filtered/sorted).
+    /// It is NOT a source index into the editing playlist.  Consumers must use
+    /// `playing_track_id` to resolve the actual source position.
+    fn broadcast_playlist_changed(&mut self) {
+        let mut playing_track_path = None;
+        let mut playing_track_id = None;
+
+        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index() {
+            if playing_idx < self.playback_playlist.num_tracks() {
+                let track = self.playback_playlist.get_track(playing_idx);
+                playing_track_path = Some(track.path.clone());
+                playing_track_id = Some(track.id.clone());
+            }
+        }
+
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistIndicesChanged {
+                playing_playlist_id: self.playback_playlist_id(),
+                // NOTE: This index is in playback-queue space, not editing-playlist
+                // source space.  The UI must resolve via `playing_track_id`.
+                playing_index: self.playback_playlist.get_playing_track_index(),
+                playing_track_id,
+                playing_track_path,
+                playing_track_metadata: None,
+                selected_indices: self.editing_playlist.get_selected_indices(),
+                is_playing: self.playback_playlist.is_playing(),
+                playback_order: self.playback_order,
+                repeat_mode: self.repeat_mode,
+            },
+        ));
+        self.broadcast_upcoming_tracks();
+
+        if self.suppress_remote_writeback {
+            return;
+        }
+        let Some((profile_id, remote_playlist_id)) =
+            Self::remote_binding_from_playlist_id(&self.active_playlist_id)
+        else {
+            return;
+        };
+        let Some(song_ids) = self.remote_song_ids_if_pure_playlist(&self.active_playlist_id) else {
+            return;
+        };
+        let signature = song_ids.join(",");
+        if self
+            .last_remote_writeback_signature
+            .get(&self.active_playlist_id)
+            .is_some_and(|previous| previous == &signature)
+        {
+            return;
+        }
+        self.last_remote_writeback_signature
+            .insert(self.active_playlist_id.clone(), signature);
+        self.remote_sync
+            .record_writeback_sent(&self.active_playlist_id);
+        let _ = self.bus_producer.send(protocol::Message::Integration(
+            protocol::IntegrationMessage::PushOpenSubsonicPlaylistUpdate {
+                profile_id,
+                remote_playlist_id,
+                local_playlist_id: self.active_playlist_id.clone(),
+                track_song_ids: song_ids,
+            },
+        ));
+        self.emit_remote_sync_statuses();
+    }
+
+    /// Broadcast the resolved upcoming sequence when it differs from the last one sent.
+    fn broadcast_upcoming_tracks(&mut self) {
+        let upcoming_tracks: Vec<protocol::UpcomingTrack> =
+            match self.playback_playlist.get_playing_track_index() {
+                Some(playing_idx) if playing_idx < self.playback_playlist.num_tracks() => self
+                    .playback_playlist
+                    .upcoming_track_indices(playing_idx, UPCOMING_TRACKS_LIMIT)
+                    .into_iter()
+                    .map(|queue_index| {
+                        let track = self.playback_playlist.get_track(queue_index);
+                        protocol::UpcomingTrack {
+                            queue_index,
+                            id: track.id.clone(),
+                            path: track.path.clone(),
                         }
-                    }
-                    _ => trace!("PlaylistManager: ignoring unsupported message"),
-                },
-                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
-                    log::warn!(
-                        "PlaylistManager lagged on control bus, skipped {} message(s)",
-                        skipped
-                    );
-                }
-                Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                    error!("PlaylistManager: bus closed");
-                    break;
-                }
-            }
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+        if upcoming_tracks == self.upcoming_tracks {
+            return;
         }
+        self.upcoming_tracks = upcoming_tracks.clone();
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::UpcomingTracksChanged(upcoming_tracks),
+        ));
+        self.sync_cast_queue();
     }
 
-    fn apply_playback_order_change(&mut self, _order: protocol::PlaybackOrder) {
-        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index() {
-            let current_id = self.playback_playlist.get_track_id(playing_idx);
+    /// Mirrors the upcoming tracks into the cast receiver queue.
+    ///
+    /// The cast manager diffs this against the receiver, so order, repeat, and queue edits
+    /// reach the remote session without reloading the track that is playing.
+    fn sync_cast_queue(&self) {
+        if self.playback_route != protocol::PlaybackRoute::Cast
+            || !self.playback_playlist.is_playing()
+        {
+            return;
+        }
+        let Some(playing_idx) = self.playback_playlist.get_playing_track_index() else {
+            return;
+        };
+        if playing_idx >= self.playback_playlist.num_tracks() {
+            return;
+        }
+        let _ = self
+            .bus_producer
+            .send(protocol::Message::Cast(protocol::CastMessage::SyncQueue {
+                current_track_id: self.playback_playlist.get_track_id(playing_idx),
+                upcoming: self.cast_queue_items(playing_idx),
+            }));
+    }
 
-            // 1. Stop current decoding
-            self.stop_decoding();
+    /// The tracks that play after `playing_idx`, as cast receiver queue items.
+    fn cast_queue_items(&self, playing_idx: usize) -> Vec<protocol::CastQueueItem> {
+        self.playback_playlist
+            .upcoming_track_indices(playing_idx, UPCOMING_TRACKS_LIMIT)
+            .into_iter()
+            .map(|queue_index| {
+                let track = self.playback_playlist.get_track(queue_index);
+                protocol::CastQueueItem {
+                    track_id: track.id.clone(),
+                    path: track.path.clone(),
+                    metadata_summary: self.remote_track_metadata_by_path.get(&track.path).cloned(),
+                }
+            })
+            .collect()
+    }
 
-            // 2. Clear our knowledge of cached tracks except current
-            let current_offset = self.cached_track_ids.get(&current_id).cloned().unwrap_or(0);
-            self.cached_track_ids.clear();
-            self.fully_cached_track_ids.clear();
-            self.cached_track_ids
-                .insert(current_id.clone(), current_offset);
-            self.fully_cached_track_ids.insert(current_id);
+    /// Follows a skip made on the cast receiver itself, e.g. its previous button.
+    fn follow_cast_receiver_jump(&mut self, track_id: &str) {
+        if self.playback_route != protocol::PlaybackRoute::Cast {
+            return;
+        }
+        let Some(index) = (0..self.playback_playlist.num_tracks())
+            .find(|index| self.playback_playlist.get_track_id(*index) == track_id)
+        else {
+            debug!(
+                "PlaylistManager: Cast receiver jumped to unknown track {}",
+                track_id
+            );
+            return;
+        };
+        let path = self.playback_playlist.get_track(index).path.clone();
+        self.begin_manual_transition(path);
+        self.play_playback_track(index, true);
+    }
 
-            // 3. Tell player to clear its buffer after the current track
-            let _ = self.bus_producer.send(protocol::Message::Playback(
-                protocol::PlaybackMessage::ClearNextTracks,
-            ));
+    fn play_upcoming_track(&mut self, position: usize) {
+        let Some(upcoming) = self.upcoming_tracks.get(position).cloned() else {
+            return;
+        };
+        // The list may be stale if the queue changed after it was broadcast.
+        if upcoming.queue_index >= self.playback_playlist.num_tracks()
+            || self.playback_playlist.get_track_id(upcoming.queue_index) != upcoming.id
+        {
+            debug!(
+                "PlaylistManager: Ignoring stale upcoming track jump to {}",
+                upcoming.id
+            );
+            return;
+        }
+        self.begin_manual_transition(upcoming.path.clone());
+        self.play_playback_track(upcoming.queue_index, true);
+    }
 
-            // 4. Start caching according to new order
-            self.cache_tracks(false);
+    fn move_upcoming_track(&mut self, from: usize, to: usize) {
+        let Some(playing_idx) = self.playback_playlist.get_playing_track_index() else {
+            return;
+        };
+        if !self
+            .playback_playlist
+            .move_upcoming_track(playing_idx, from, to)
+        {
+            return;
+        }
+        if self.playback_route == protocol::PlaybackRoute::Local {
+            // Re-queue the tracks after the current one, same as an order change.
+            let current_id = self.playback_playlist.get_track_id(playing_idx);
+            if self.fully_cached_track_ids.contains(&current_id) {
+                self.apply_playback_order_change(self.playback_order);
+            } else {
+                self.pending_order_change = Some(self.playback_order);
+            }
         }
+        self.broadcast_playlist_changed();
     }
 
-    fn cache_tracks(&mut self, play_immediately: bool) {
+    /// Copies the playback queue into a new local playlist named after its source.
+    fn save_playback_queue_as_playlist(&mut self, shuffle_order: bool) {
         if self.playback_playlist.num_tracks() == 0 {
             return;
         }
-        if self.pending_rate_switch.is_some() {
-            self.pending_rate_switch_play_immediately |= play_immediately;
-            return;
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        let base_name = match self.playback_queue_source.as_ref() {
+            Some(protocol::PlaybackQueueSource::Playlist { playlist_id }) => playlists
+                .iter()
+                .find(|playlist| &playlist.id == playlist_id)
+                .map(|playlist| format!("{} (Queue)", playlist.name))
+                .unwrap_or_else(|| "Saved Queue".to_string()),
+            Some(protocol::PlaybackQueueSource::Library) => "Library Queue".to_string(),
+            None => "Saved Queue".to_string(),
+        };
+        let mut name = base_name.clone();
+        let mut suffix = 2usize;
+        while playlists.iter().any(|playlist| playlist.name == name) {
+            name = format!("{base_name} {suffix}");
+            suffix = suffix.saturating_add(1);
         }
 
-        let first_index = self
+        let rows: Vec<(String, PathBuf)> = self
             .playback_playlist
-            .get_playing_track_index()
-            .unwrap_or(0);
-        let first_track = self.playback_playlist.get_track(first_index).clone();
-        let desired_first_rate = self
-            .desired_output_rate_for_track(&first_track)
-            .or(self.current_output_rate_hz);
-        if let Some(desired_first_rate) = desired_first_rate {
-            if self.current_output_rate_hz != Some(desired_first_rate)
-                && self.request_runtime_output_rate_switch(desired_first_rate, play_immediately)
-            {
-                return;
-            }
+            .queue_track_indices(shuffle_order)
+            .into_iter()
+            .map(|index| {
+                (
+                    Uuid::new_v4().to_string(),
+                    self.playback_playlist.get_track(index).path.clone(),
+                )
+            })
+            .collect();
+        let playlist_id = Uuid::new_v4().to_string();
+        if let Err(err) = self.db_manager.create_playlist(&playlist_id, &name) {
+            error!("Failed to create playlist '{}': {}", name, err);
+            return;
+        }
+        if let Err(err) = self.db_manager.save_tracks_batch(&playlist_id, &rows, 0) {
+            error!(
+                "Failed to save queue tracks to playlist '{}': {}",
+                name, err
+            );
         }
+        info!(
+            "PlaylistManager: Saved playback queue as {} ({}) with {} tracks",
+            name,
+            playlist_id,
+            rows.len()
+        );
+        activity_log::record(
+            &self.bus_producer,
+            protocol::ActivityCategory::Playlist,
+            format!(
+                "Saved the playback queue as \"{}\" ({} track(s))",
+                name,
+                rows.len()
+            ),
+        );
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+        self.broadcast_playlists_restored(playlists);
+    }
 
-        let mut current_index = first_index;
-        let mut track_paths = Vec::new();
-        let mut staged_track_ids = HashSet::new();
-        let mut segment_rate: Option<u32> = desired_first_rate;
+    fn emit_xspf_transfer_finished(&self, message: String) {
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::XspfTransferFinished(message),
+        ));
+    }
 
-        for _ in 0..self.max_num_cached_tracks {
-            if current_index < self.playback_playlist.num_tracks() {
-                let track_id = self.playback_playlist.get_track_id(current_index);
-                let is_cached_at_track_start =
-                    self.cached_track_ids.get(&track_id).copied() == Some(0);
-                let is_requested_at_track_start =
-                    self.requested_track_offsets.get(&track_id).copied() == Some(0)
-                        || staged_track_ids.contains(&track_id);
-                if is_cached_at_track_start || is_requested_at_track_start {
-                    if let Some(next_index) =
-                        self.playback_playlist.get_next_track_index(current_index)
-                    {
-                        if next_index == current_index {
-                            break;
-                        }
-                        current_index = next_index;
-                        if next_index >= self.playback_playlist.num_tracks() {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                    continue;
-                }
+    fn emit_playlist_restructure_finished(&self, message: String) {
+        let _ = self.bus_producer.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::PlaylistRestructureFinished(message),
+        ));
+    }
 
-                let track = self.playback_playlist.get_track(current_index).clone();
-                let desired_rate = self
-                    .desired_output_rate_for_track(&track)
-                    .or(self.current_output_rate_hz);
-                if let Some(required_segment_rate) = segment_rate {
-                    if desired_rate != Some(required_segment_rate) {
-                        break;
-                    }
-                } else {
-                    segment_rate = desired_rate;
+    /// Looks up artist, genre, and year for split rules: library rows first, then synced
+    /// remote metadata, then the file's own tags.
+    fn split_track_infos(&self, paths: &[PathBuf]) -> Vec<SplitTrackInfo> {
+        let library_tracks: HashMap<PathBuf, protocol::LibraryTrack> = self
+            .db_manager
+            .get_library_tracks()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|track| (track.path.clone(), track))
+            .collect();
+        paths
+            .iter()
+            .map(|path| {
+                if let Some(track) = library_tracks.get(path) {
+                    return SplitTrackInfo {
+                        artist: track.artist.clone(),
+                        genre: track.genre.clone(),
+                        year: track.year.clone(),
+                    };
                 }
-
-                track_paths.push(TrackIdentifier {
-                    id: track_id.clone(),
-                    path: track.path.clone(),
-                    play_immediately: play_immediately && current_index == first_index,
-                    start_offset_ms: 0,
-                });
-                staged_track_ids.insert(track_id);
-            }
-            if let Some(next_index) = self.playback_playlist.get_next_track_index(current_index) {
-                if next_index == current_index {
-                    break;
+                if let Some(summary) = self.remote_track_metadata_by_path.get(path) {
+                    return SplitTrackInfo {
+                        artist: summary.artist.clone(),
+                        genre: summary.genre.clone(),
+                        year: summary.year.clone(),
+                    };
                 }
-                current_index = next_index;
-                if next_index >= self.playback_playlist.num_tracks() {
-                    break;
+                if is_remote_track_path(path) {
+                    return SplitTrackInfo::default();
+                }
+                metadata_tags::read_common_track_metadata(path)
+                    .map(|metadata| SplitTrackInfo {
+                        artist: metadata.artist,
+                        genre: metadata.genre,
+                        year: metadata.year,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Creates one playlist per `(name, paths)` entry, renaming on name clashes, and
+    /// records them for undo.
+    /// Returns `base_name`, numbered as `base_name 2`, `base_name 3`... while taken.
+    fn unique_playlist_name(base_name: &str, existing_names: &[String]) -> String {
+        let mut name = base_name.to_string();
+        let mut suffix = 2usize;
+        while existing_names.iter().any(|existing| existing == &name) {
+            name = format!("{base_name} {suffix}");
+            suffix = suffix.saturating_add(1);
+        }
+        name
+    }
+
+    /// Creates a new playlist holding `paths`, numbering the name when it is taken.
+    /// Returns the new playlist's id and final name.
+    fn create_playlist_with_tracks(
+        &mut self,
+        name: &str,
+        paths: Vec<PathBuf>,
+    ) -> Option<(String, String)> {
+        let name = name.trim();
+        if name.is_empty() || paths.is_empty() {
+            return None;
+        }
+        let existing_names: Vec<String> = self
+            .db_manager
+            .get_all_playlists()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|playlist| playlist.name)
+            .collect();
+        let name = Self::unique_playlist_name(name, &existing_names);
+        let playlist_id = Uuid::new_v4().to_string();
+        if let Err(err) = self.db_manager.create_playlist(&playlist_id, &name) {
+            error!("Failed to create playlist '{}': {}", name, err);
+            return None;
+        }
+        let rows: Vec<(String, PathBuf)> = paths
+            .into_iter()
+            .map(|path| (Uuid::new_v4().to_string(), path))
+            .collect();
+        if let Err(err) = self.db_manager.save_tracks_batch(&playlist_id, &rows, 0) {
+            error!("Failed to save tracks to playlist '{}': {}", name, err);
+        }
+        activity_log::record(
+            &self.bus_producer,
+            protocol::ActivityCategory::Playlist,
+            format!("Created playlist \"{}\" with {} track(s)", name, rows.len()),
+        );
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+        self.broadcast_playlists_restored(playlists);
+        Some((playlist_id, name))
+    }
+
+    /// Writes a playlist to `path` as XSPF, with tags, durations, and track notes.
+    /// Returns the user-facing outcome.
+    fn export_playlist_xspf(&self, playlist_id: &str, path: &Path) -> Result<String, String> {
+        let playlist = self
+            .db_manager
+            .get_all_playlists()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|playlist| playlist.id == playlist_id)
+            .ok_or_else(|| "the playlist no longer exists".to_string())?;
+        let tracks = self
+            .db_manager
+            .get_tracks_for_playlist(playlist_id)
+            .map_err(|error| format!("failed to read playlist tracks: {error}"))?;
+        let library_tracks: HashMap<PathBuf, protocol::LibraryTrack> = self
+            .db_manager
+            .get_library_tracks()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|track| (track.path.clone(), track))
+            .collect();
+        let notes: HashMap<PathBuf, String> = self
+            .db_manager
+            .get_track_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        let xspf_tracks: Vec<xspf::XspfTrack> = tracks
+            .into_iter()
+            .map(|track| {
+                let track_path = track.path;
+                let remote = is_remote_track_path(&track_path);
+                let summary = library_tracks
+                    .get(&track_path)
+                    .map(|track| {
+                        (
+                            track.title.clone(),
+                            track.artist.clone(),
+                            track.album.clone(),
+                            track.track_number.clone(),
+                        )
+                    })
+                    .or_else(|| {
+                        self.remote_track_metadata_by_path
+                            .get(&track_path)
+                            .map(|summary| {
+                                (
+                                    summary.title.clone(),
+                                    summary.artist.clone(),
+                                    summary.album.clone(),
+                                    summary.track_number.clone(),
+                                )
+                            })
+                    })
+                    .or_else(|| {
+                        (!remote)
+                            .then(|| metadata_tags::read_common_track_metadata(&track_path))
+                            .flatten()
+                            .map(|metadata| {
+                                (
+                                    metadata.title,
+                                    metadata.artist,
+                                    metadata.album,
+                                    metadata.track_number,
+                                )
+                            })
+                    })
+                    .unwrap_or_default();
+                let (title, creator, album, track_number) = summary;
+                let duration_ms = (!remote)
+                    .then(|| format_quality::probe_track_format(&track_path))
+                    .flatten()
+                    .map(|format| format.duration_ms);
+                xspf::XspfTrack {
+                    location: Some(xspf::location_for_path(&track_path)),
+                    identifiers: if remote {
+                        vec![track_path.to_string_lossy().to_string()]
+                    } else {
+                        Vec::new()
+                    },
+                    title,
+                    creator,
+                    album,
+                    track_num: track_number
+                        .split('/')
+                        .next()
+                        .and_then(|number| number.trim().parse().ok()),
+                    duration_ms,
+                    annotation: notes.get(&track_path).cloned().unwrap_or_default(),
                 }
+            })
+            .collect();
+        let track_count = xspf_tracks.len();
+        let document = xspf::write_xspf(&xspf::XspfPlaylist {
+            title: playlist.name.clone(),
+            annotation: playlist.note,
+            tracks: xspf_tracks,
+        });
+        std::fs::write(path, document)
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+        Ok(format!(
+            "Exported \"{}\" as XSPF ({} track(s))",
+            playlist.name, track_count
+        ))
+    }
+
+    /// Creates a playlist from an XSPF file, carrying over its annotation as the playlist
+    /// note and track annotations as notes of tracks that have none yet.
+    fn import_xspf_playlist(&mut self, path: &Path) -> Result<String, String> {
+        let bytes = sandbox_access::read_file(path)
+            .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+        let text = String::from_utf8(bytes)
+            .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+        let parsed = xspf::parse_xspf(&text)?;
+        let resolved: Vec<(PathBuf, String)> = parsed
+            .tracks
+            .iter()
+            .filter_map(|track| {
+                xspf::resolve_track_path(track, |path| path.exists())
+                    .map(|path| (path, track.annotation.trim().to_string()))
+            })
+            .collect();
+        if resolved.is_empty() {
+            return Err("the file lists no tracks".to_string());
+        }
+        let missing_count = resolved
+            .iter()
+            .filter(|(path, _)| !is_remote_track_path(path) && !path.exists())
+            .count();
+        let base_name = Some(parsed.title.trim())
+            .filter(|title| !title.is_empty())
+            .map(ToOwned::to_owned)
+            .or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "Imported Playlist".to_string());
+        let track_count = resolved.len();
+        let paths = resolved.iter().map(|(path, _)| path.clone()).collect();
+        let (playlist_id, name) = self
+            .create_playlist_with_tracks(&base_name, paths)
+            .ok_or_else(|| "failed to create the playlist".to_string())?;
+
+        let note = parsed.annotation.trim_end().to_string();
+        if !note.is_empty() {
+            if let Err(error) = self.db_manager.set_playlist_note(&playlist_id, &note) {
+                error!("Failed to save imported playlist note: {}", error);
             } else {
-                break;
+                let _ = self.bus_producer.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::PlaylistNoteChanged { playlist_id, note },
+                ));
             }
         }
-        debug!(
-            "PlaylistManager: Updated cached tracks: {:?}",
-            self.cached_track_ids
-        );
-        if !track_paths.is_empty() {
-            for track in &track_paths {
-                self.requested_track_offsets
-                    .insert(track.id.clone(), track.start_offset_ms);
+        let mut noted_paths: HashSet<PathBuf> = self
+            .db_manager
+            .get_track_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        for (path, annotation) in resolved {
+            if !annotation.is_empty() && noted_paths.insert(path.clone()) {
+                let _ = self.bus_producer.send(protocol::Message::Library(
+                    protocol::LibraryMessage::SetTrackNote {
+                        path,
+                        note: annotation,
+                    },
+                ));
             }
-            let _ = self.bus_producer.send(protocol::Message::Audio(
-                protocol::AudioMessage::DecodeTracks(track_paths),
-            ));
+        }
+        if missing_count == 0 {
+            Ok(format!("Imported \"{name}\" ({track_count} track(s))"))
+        } else {
+            Ok(format!(
+                "Imported \"{name}\" ({track_count} track(s), {missing_count} file(s) not found)"
+            ))
         }
     }
 
-    fn clear_cached_tracks(&mut self) {
-        self.pending_start_track_id = None;
-        self.started_track_id = None;
-        self.pending_rate_switch = None;
-        self.pending_rate_switch_play_immediately = false;
-        self.cached_track_ids.clear();
-        self.fully_cached_track_ids.clear();
-        self.requested_track_offsets.clear();
-        let _ = self.bus_producer.send(protocol::Message::Audio(
-            protocol::AudioMessage::StopDecoding,
-        ));
-        let _ = self.bus_producer.send(protocol::Message::Playback(
-            protocol::PlaybackMessage::ClearPlayerCache,
-        ));
-    }
-
-    fn stop_decoding(&mut self) {
-        self.pending_start_track_id = None;
-        self.pending_rate_switch = None;
-        self.pending_rate_switch_play_immediately = false;
-        self.requested_track_offsets.clear();
-        let _ = self.bus_producer.send(protocol::Message::Audio(
-            protocol::AudioMessage::StopDecoding,
-        ));
+    fn create_restructured_playlists(&mut self, planned: Vec<(String, Vec<PathBuf>)>) {
+        let mut existing_names: Vec<String> = self
+            .db_manager
+            .get_all_playlists()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|playlist| playlist.name)
+            .collect();
+        let mut created_ids = Vec::with_capacity(planned.len());
+        for (base_name, paths) in planned {
+            let name = Self::unique_playlist_name(&base_name, &existing_names);
+            let playlist_id = Uuid::new_v4().to_string();
+            if let Err(err) = self.db_manager.create_playlist(&playlist_id, &name) {
+                error!("Failed to create playlist '{}': {}", name, err);
+                continue;
+            }
+            let rows: Vec<(String, PathBuf)> = paths
+                .into_iter()
+                .map(|path| (Uuid::new_v4().to_string(), path))
+                .collect();
+            if let Err(err) = self.db_manager.save_tracks_batch(&playlist_id, &rows, 0) {
+                error!("Failed to save tracks to playlist '{}': {}", name, err);
+            }
+            existing_names.push(name);
+            created_ids.push(playlist_id);
+        }
+        self.restructure_undo_playlist_ids = created_ids;
+        let playlists = self.db_manager.get_all_playlists().unwrap_or_default();
+        self.emit_opensubsonic_sync_eligible_playlists(&playlists);
+        self.broadcast_playlists_restored(playlists);
     }
 
-    /// Broadcast the current playlist/playback state to all listeners.
-    ///
-    /// **Important**: `playing_index` is the position inside `playback_playlist`,
-    /// which is a queue snapshot built in **view order** (filtered/sorted).
-    /// It is NOT a source index into the editing playlist.  Consumers must use
-    /// `playing_track_id` to resolve the actual source position.
-    fn broadcast_playlist_changed(&mut self) {
-        let mut playing_track_path = None;
-        let mut playing_track_id = None;
-
-        if let Some(playing_idx) = self.playback_playlist.get_playing_track_index() {
-            if playing_idx < self.playback_playlist.num_tracks() {
-                let track = self.playback_playlist.get_track(playing_idx);
-                playing_track_path = Some(track.path.clone());
-                playing_track_id = Some(track.id.clone());
+    fn split_playlist(&mut self, playlist_id: &str, rule: protocol::PlaylistSplitRule) {
+        let Some(source_name) = self.playlist_name_by_id(playlist_id) else {
+            return;
+        };
+        let paths: Vec<PathBuf> = self
+            .db_manager
+            .get_tracks_for_playlist(playlist_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|track| track.path)
+            .collect();
+        let infos = match rule {
+            protocol::PlaylistSplitRule::EveryNTracks(_) => {
+                vec![SplitTrackInfo::default(); paths.len()]
             }
+            _ => self.split_track_infos(&paths),
+        };
+        let groups = playlist_restructure::plan_split(&infos, rule);
+        if groups.len() < 2 {
+            self.emit_playlist_restructure_finished(format!(
+                "Nothing to split: all tracks of \"{}\" fall into one group",
+                source_name
+            ));
+            return;
         }
-
-        let _ = self.bus_producer.send(protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistIndicesChanged {
-                playing_playlist_id: self.playback_playlist_id(),
-                // NOTE: This index is in playback-queue space, not editing-playlist
-                // source space.  The UI must resolve via `playing_track_id`.
-                playing_index: self.playback_playlist.get_playing_track_index(),
-                playing_track_id,
-                playing_track_path,
-                playing_track_metadata: None,
-                selected_indices: self.editing_playlist.get_selected_indices(),
-                is_playing: self.playback_playlist.is_playing(),
-                playback_order: self.playback_order,
-                repeat_mode: self.repeat_mode,
-            },
+        let group_count = groups.len();
+        let planned = groups
+            .into_iter()
+            .map(|group| {
+                (
+                    format!("{} - {}", source_name, group.label),
+                    group
+                        .track_indices
+                        .into_iter()
+                        .map(|index| paths[index].clone())
+                        .collect(),
+                )
+            })
+            .collect();
+        self.create_restructured_playlists(planned);
+        info!(
+            "PlaylistManager: split playlist {} into {} playlists ({:?})",
+            playlist_id, group_count, rule
+        );
+        activity_log::record(
+            &self.bus_producer,
+            protocol::ActivityCategory::Playlist,
+            format!(
+                "Split playlist \"{}\" into {} playlists",
+                source_name, group_count
+            ),
+        );
+        self.emit_playlist_restructure_finished(format!(
+            "Split \"{}\" into {} playlists. Undo removes them.",
+            source_name, group_count
         ));
+    }
 
-        if self.suppress_remote_writeback {
+    fn merge_playlists(&mut self, playlist_ids: &[String], mode: &protocol::PlaylistMergeMode) {
+        if playlist_ids.len() < 2 {
+            self.emit_playlist_restructure_finished(
+                "Choose at least two playlists to merge".to_string(),
+            );
             return;
         }
-        let Some((profile_id, remote_playlist_id)) =
-            Self::remote_binding_from_playlist_id(&self.active_playlist_id)
-        else {
-            return;
-        };
-        let Some(song_ids) = self.remote_song_ids_if_pure_playlist(&self.active_playlist_id) else {
-            return;
-        };
-        let signature = song_ids.join(",");
-        if self
-            .last_remote_writeback_signature
-            .get(&self.active_playlist_id)
-            .is_some_and(|previous| previous == &signature)
-        {
-            return;
+        let mut names = Vec::with_capacity(playlist_ids.len());
+        let mut sources: Vec<Vec<PathBuf>> = Vec::with_capacity(playlist_ids.len());
+        for playlist_id in playlist_ids {
+            names.push(self.playlist_name_by_id(playlist_id).unwrap_or_default());
+            sources.push(
+                self.db_manager
+                    .get_tracks_for_playlist(playlist_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|track| track.path)
+                    .collect(),
+            );
         }
-        self.last_remote_writeback_signature
-            .insert(self.active_playlist_id.clone(), signature);
-        let _ = self.bus_producer.send(protocol::Message::Integration(
-            protocol::IntegrationMessage::PushOpenSubsonicPlaylistUpdate {
-                profile_id,
-                remote_playlist_id,
-                local_playlist_id: self.active_playlist_id.clone(),
-                track_song_ids: song_ids,
-            },
+        let lengths: Vec<usize> = sources.iter().map(Vec::len).collect();
+        let paths: Vec<PathBuf> = playlist_restructure::plan_merge(&lengths, mode)
+            .into_iter()
+            .map(|(playlist, track)| sources[playlist][track].clone())
+            .collect();
+        let track_count = paths.len();
+        let name = format!("{} (Merged)", names.join(" + "));
+        self.create_restructured_playlists(vec![(name.clone(), paths)]);
+        info!(
+            "PlaylistManager: merged {} playlists into '{}' ({:?})",
+            playlist_ids.len(),
+            name,
+            mode
+        );
+        activity_log::record(
+            &self.bus_producer,
+            protocol::ActivityCategory::Playlist,
+            format!(
+                "Merged {} playlists into \"{}\" ({} track(s))",
+                playlist_ids.len(),
+                name,
+                track_count
+            ),
+        );
+        self.emit_playlist_restructure_finished(format!(
+            "Merged {} playlists into \"{}\". Undo removes it.",
+            playlist_ids.len(),
+            name
         ));
     }
 
+    /// Deletes the playlists created by the last split or merge.
+    fn undo_playlist_restructure(&mut self) {
+        let created_ids = std::mem::take(&mut self.restructure_undo_playlist_ids);
+        let existing: HashSet<String> = self
+            .db_manager
+            .get_all_playlists()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|playlist| playlist.id)
+            .collect();
+        let mut removed = 0usize;
+        for id in created_ids.into_iter().filter(|id| existing.contains(id)) {
+            let _ = self.bus_producer.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::DeletePlaylist { id },
+            ));
+            removed += 1;
+        }
+        if removed > 0 {
+            self.emit_playlist_restructure_finished(format!(
+                "Removed {} playlist(s) created by the last split or merge",
+                removed
+            ));
+        }
+    }
+
     fn broadcast_selection_changed(&self) {
         let selected_indices = self.editing_playlist.get_selected_indices();
         let _ = self.bus_producer.send(protocol::Message::Playlist(
@@ -3412,9 +6495,10 @@ mod tests {
         let mut saw_tracks = false;
         while start.elapsed() < timeout && !(saw_playlists && saw_active && saw_tracks) {
             match harness.receiver.try_recv() {
-                Ok(protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored(
+                Ok(protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
                     playlists,
-                ))) => {
+                    ..
+                })) => {
                     saw_playlists = !playlists.is_empty();
                 }
                 Ok(protocol::Message::Playlist(
@@ -3479,6 +6563,43 @@ mod tests {
         assert_eq!(redone_order, vec![id2, id0, id1]);
     }
 
+    #[test]
+    fn test_sort_tracks_reorders_playlist_as_one_undoable_edit() {
+        let mut harness = PlaylistManagerHarness::new();
+        let (id0, _) = harness.add_track("pm_sort_tracks_0");
+        let (id1, _) = harness.add_track("pm_sort_tracks_1");
+        let (id2, _) = harness.add_track("pm_sort_tracks_2");
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SortTracks(vec![2, 0, 1]),
+        ));
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(
+                    protocol::PlaylistMessage::PlaylistIndicesChanged { .. }
+                )
+            )
+        });
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::UndoTrackListEdit,
+        ));
+        let undone_order =
+            wait_for_playlist_restored_track_ids(&mut harness.receiver, Duration::from_secs(1));
+        assert_eq!(undone_order, vec![id0.clone(), id1.clone(), id2.clone()]);
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::RedoTrackListEdit,
+        ));
+        let redone_order =
+            wait_for_playlist_restored_track_ids(&mut harness.receiver, Duration::from_secs(1));
+        assert_eq!(redone_order, vec![id2, id0, id1]);
+    }
+
     #[test]
     fn test_switch_playlist_clears_track_list_undo_history() {
         let mut harness = PlaylistManagerHarness::new();
@@ -3505,25 +6626,30 @@ mod tests {
             },
         ));
 
-        let playlists =
-            wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+        let playlists = wait_for_message(
+            &mut harness.receiver,
+            Duration::from_secs(1),
+            |message| {
                 matches!(
                     message,
-                    protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored(list))
+                    protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored { playlists: list, .. })
                         if list.iter().any(|playlist| playlist.name == "Undo Scope Target")
                 )
-            });
-        let target_playlist_id = if let protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistsRestored(list),
-        ) = playlists
-        {
-            list.into_iter()
-                .find(|playlist| playlist.name == "Undo Scope Target")
-                .map(|playlist| playlist.id)
-                .expect("created playlist should be present in restoration list")
-        } else {
-            panic!("expected PlaylistsRestored message");
-        };
+            },
+        );
+        let target_playlist_id =
+            if let protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+                playlists: list,
+                ..
+            }) = playlists
+            {
+                list.into_iter()
+                    .find(|playlist| playlist.name == "Undo Scope Target")
+                    .map(|playlist| playlist.id)
+                    .expect("created playlist should be present in restoration list")
+            } else {
+                panic!("expected PlaylistsRestored message");
+            };
 
         harness.send(protocol::Message::Playlist(
             protocol::PlaylistMessage::SwitchPlaylist {
@@ -3615,6 +6741,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_and_delete_playlist_record_activity() {
+        let mut harness = PlaylistManagerHarness::new();
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::CreatePlaylist {
+                name: "Road Trip".to_string(),
+            },
+        ));
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Library(protocol::LibraryMessage::RecordActivity(entry))
+                    if entry.category == protocol::ActivityCategory::Playlist
+                        && entry.message == "Created playlist \"Road Trip\""
+            )
+        });
+        let playlists_message =
+            wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+                matches!(
+                    message,
+                    protocol::Message::Playlist(
+                        protocol::PlaylistMessage::PlaylistsRestored { .. }
+                    )
+                )
+            });
+        let protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+            playlists: list,
+            ..
+        }) = playlists_message
+        else {
+            panic!("expected PlaylistsRestored message");
+        };
+        let playlist_id = list
+            .into_iter()
+            .find(|playlist| playlist.name == "Road Trip")
+            .map(|playlist| playlist.id)
+            .expect("created playlist should be present");
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::DeletePlaylist { id: playlist_id },
+        ));
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Library(protocol::LibraryMessage::RecordActivity(entry))
+                    if entry.message == "Deleted playlist \"Road Trip\" (0 track(s))"
+            )
+        });
+    }
+
+    #[test]
+    fn test_playlist_folder_changes_rebroadcast_the_sidebar_tree() {
+        let mut harness = PlaylistManagerHarness::new();
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::CreatePlaylistFolder {
+                name: " Workouts ".to_string(),
+                parent_id: None,
+            },
+        ));
+        let message = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+                    folders,
+                    ..
+                }) if folders.iter().any(|folder| folder.name == "Workouts")
+            )
+        });
+        let protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+            folders,
+            ..
+        }) = message
+        else {
+            panic!("expected PlaylistsRestored message");
+        };
+        let folder_id = folders[0].id.clone();
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::MovePlaylistToFolder {
+                playlist_id: harness.active_playlist_id.clone(),
+                folder_id: Some(folder_id.clone()),
+            },
+        ));
+        let active_playlist_id = harness.active_playlist_id.clone();
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+                    playlists,
+                    ..
+                }) if playlists.iter().any(|playlist| playlist.id == active_playlist_id
+                    && playlist.parent_id.as_deref() == Some(folder_id.as_str()))
+            )
+        });
+
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::MovePlaylistFolder {
+                id: folder_id.clone(),
+                parent_id: Some(folder_id.clone()),
+            },
+        ));
+        harness.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::DeletePlaylistFolder {
+                id: folder_id.clone(),
+            },
+        ));
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+                    playlists,
+                    folders,
+                }) if folders.is_empty()
+                    && playlists.iter().all(|playlist| playlist.parent_id.is_none())
+            )
+        });
+    }
+
     #[test]
     fn test_add_tracks_to_playlists_to_non_active_playlist_persists_without_ui_insert() {
         let mut harness = PlaylistManagerHarness::new();
@@ -3625,25 +6873,30 @@ mod tests {
                 name: "Add To Target".to_string(),
             },
         ));
-        let playlists_message =
-            wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+        let playlists_message = wait_for_message(
+            &mut harness.receiver,
+            Duration::from_secs(1),
+            |message| {
                 matches!(
                     message,
-                    protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored(list))
+                    protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored { playlists: list, .. })
                         if list.iter().any(|playlist| playlist.name == "Add To Target")
                 )
-            });
-        let target_playlist_id = if let protocol::Message::Playlist(
-            protocol::PlaylistMessage::PlaylistsRestored(list),
-        ) = playlists_message
-        {
-            list.into_iter()
-                .find(|playlist| playlist.name == "Add To Target")
-                .map(|playlist| playlist.id)
-                .expect("created playlist should be present")
-        } else {
-            panic!("expected PlaylistsRestored message");
-        };
+            },
+        );
+        let target_playlist_id =
+            if let protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistsRestored {
+                playlists: list,
+                ..
+            }) = playlists_message
+            {
+                list.into_iter()
+                    .find(|playlist| playlist.name == "Add To Target")
+                    .map(|playlist| playlist.id)
+                    .expect("created playlist should be present")
+            } else {
+                panic!("expected PlaylistsRestored message");
+            };
         harness.drain_messages();
 
         let target_path = PathBuf::from("/tmp/pm_add_to_other_0.mp3");
@@ -4714,11 +7967,17 @@ mod tests {
                     channel_count_auto: Some(config.output.channel_count_auto),
                     sample_rate_auto: Some(config.output.sample_rate_auto),
                     bits_per_sample_auto: Some(config.output.bits_per_sample_auto),
+                    group_sample_rate_families: Some(config.output.group_sample_rate_families),
+                    resample_rate_outliers: Some(config.output.resample_rate_outliers),
+                    device_rate_policies: Some(config.output.device_rate_policies.clone()),
                     resampler_quality: Some(config.output.resampler_quality),
                     dither_on_bitdepth_reduce: Some(config.output.dither_on_bitdepth_reduce),
                     downmix_higher_channel_tracks: Some(
                         config.output.downmix_higher_channel_tracks,
                     ),
+                    skip_hidden_pregap: Some(config.output.skip_hidden_pregap),
+                    skip_silence_spoken_word: Some(config.output.skip_silence_spoken_word),
+                    skip_silence_min_pause_ms: Some(config.output.skip_silence_min_pause_ms),
                 },
             )]),
         ));
@@ -4820,93 +8079,501 @@ mod tests {
         let _ = wait_for_message(&mut harness.receiver, timeout, |message| {
             matches!(
                 message,
-                protocol::Message::Playback(protocol::PlaybackMessage::ClearPlayerCache)
+                protocol::Message::Playback(protocol::PlaybackMessage::ClearPlayerCache)
+            )
+        });
+
+        let _ = wait_for_message(&mut harness.receiver, timeout, |message| match message {
+            protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
+                tracks.iter().any(|track| {
+                    track.id == id0 && track.play_immediately && track.start_offset_ms == 50_000
+                })
+            }
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_resume_journaled_track_starts_decode_at_journaled_offset() {
+        let timeout = Duration::from_secs(3);
+        let mut harness = PlaylistManagerHarness::new();
+        let _ = harness.add_track("pm_journal_resume_0");
+        let (id1, _) = harness.add_track("pm_journal_resume_1");
+        harness.drain_messages();
+
+        harness.send(protocol::Message::Playback(
+            protocol::PlaybackMessage::ResumeJournaledTrack {
+                playlist_id: harness.active_playlist_id.clone(),
+                track_id: id1.clone(),
+                elapsed_ms: 42_000,
+            },
+        ));
+
+        let _ = wait_for_message(&mut harness.receiver, timeout, |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(protocol::PlaylistMessage::PlaylistIndicesChanged {
+                    is_playing: true,
+                    playing_index: Some(1),
+                    ..
+                })
+            )
+        });
+        let _ = wait_for_message(&mut harness.receiver, timeout, |message| match message {
+            protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
+                tracks.iter().any(|track| {
+                    track.id == id1 && track.play_immediately && track.start_offset_ms == 42_000
+                })
+            }
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_track_evicted_invalidates_cached_entry() {
+        let mut harness = PlaylistManagerHarness::new();
+        let (id0, _) = harness.add_track("pm_track_evicted_0");
+        harness.drain_messages();
+
+        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
+
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playback(protocol::PlaybackMessage::ClearPlayerCache)
+            )
+        });
+        let _ =
+            wait_for_message(
+                &mut harness.receiver,
+                Duration::from_secs(1),
+                |message| match message {
+                    protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
+                        tracks
+                            .first()
+                            .map(|track| track.id == id0 && track.play_immediately)
+                            .unwrap_or(false)
+                    }
+                    _ => false,
+                },
+            );
+
+        harness.drain_messages();
+        harness.send(protocol::Message::Audio(
+            protocol::AudioMessage::TrackCached(id0.clone(), 0),
+        ));
+        harness.send(protocol::Message::Audio(
+            protocol::AudioMessage::TrackEvicted(id0.clone()),
+        ));
+        harness.drain_messages();
+
+        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
+
+        let _ =
+            wait_for_message(
+                &mut harness.receiver,
+                Duration::from_secs(1),
+                |message| match message {
+                    protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
+                        tracks
+                            .first()
+                            .map(|track| track.id == id0 && track.play_immediately)
+                            .unwrap_or(false)
+                    }
+                    _ => false,
+                },
+            );
+    }
+
+    #[test]
+    fn test_track_start_cache_after_hidden_pregap_skip_counts_as_cached_at_start() {
+        let mut harness = PlaylistManagerHarness::new();
+        let (id0, _) = harness.add_track("pm_hidden_pregap_0");
+        harness.drain_messages();
+
+        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks))
+                    if tracks.iter().any(|track| track.id == id0 && track.start_offset_ms == 0)
+            )
+        });
+
+        // Decoder and player both report the offset the decode actually began at.
+        for _ in 0..2 {
+            harness.send(protocol::Message::Audio(
+                protocol::AudioMessage::TrackCached(id0.clone(), 92_600),
+            ));
+        }
+        harness.drain_messages();
+
+        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
+        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playback(protocol::PlaybackMessage::PlayTrackById(id))
+                    if id == &id0
+            )
+        });
+    }
+
+    fn make_direct_manager() -> (PlaylistManager, Receiver<protocol::Message>) {
+        let (bus_sender, _) = broadcast::channel(256);
+        let receiver = bus_sender.subscribe();
+        let manager_receiver = bus_sender.subscribe();
+        let (_bulk_import_tx, bulk_import_rx) =
+            std::sync::mpsc::sync_channel::<protocol::PlaylistBulkImportRequest>(64);
+        let db_manager = DbManager::new_in_memory().expect("failed to create in-memory db");
+        let manager = PlaylistManager::new(
+            Playlist::new(),
+            manager_receiver,
+            bus_sender,
+            db_manager,
+            bulk_import_rx,
+            OutputConfig::default(),
+            UiConfig::default(),
+        );
+        (manager, receiver)
+    }
+
+    fn drain_upcoming_track_ids(receiver: &mut Receiver<protocol::Message>) -> Option<Vec<String>> {
+        let mut latest = None;
+        while let Ok(message) = receiver.try_recv() {
+            if let protocol::Message::Playlist(protocol::PlaylistMessage::UpcomingTracksChanged(
+                tracks,
+            )) = message
+            {
+                latest = Some(tracks.into_iter().map(|track| track.id).collect());
+            }
+        }
+        latest
+    }
+
+    #[test]
+    fn test_upcoming_tracks_follow_queue_and_support_reorder_and_jump() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        manager.start_playback_queue(protocol::PlaybackQueueRequest {
+            source: protocol::PlaybackQueueSource::Library,
+            tracks: ["a", "b", "c", "d"]
+                .iter()
+                .map(|id| protocol::RestoredTrack {
+                    id: id.to_string(),
+                    path: PathBuf::from(format!("/tmp/upcoming_{id}.flac")),
+                })
+                .collect(),
+            start_index: 0,
+        });
+        assert_eq!(
+            drain_upcoming_track_ids(&mut receiver),
+            Some(vec!["b".to_string(), "c".to_string(), "d".to_string()])
+        );
+
+        manager.move_upcoming_track(2, 0);
+        assert_eq!(
+            drain_upcoming_track_ids(&mut receiver),
+            Some(vec!["d".to_string(), "b".to_string(), "c".to_string()])
+        );
+
+        manager.play_upcoming_track(1);
+        assert_eq!(
+            manager
+                .playback_playlist
+                .get_playing_track_index()
+                .map(|index| manager.playback_playlist.get_track_id(index)),
+            Some("b".to_string())
+        );
+        assert_eq!(
+            drain_upcoming_track_ids(&mut receiver),
+            Some(vec!["c".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_enqueue_tracks_plays_next_or_appends_to_running_queue() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        let restored = |id: &str| protocol::RestoredTrack {
+            id: id.to_string(),
+            path: PathBuf::from(format!("/tmp/enqueue_{id}.flac")),
+        };
+        manager.start_playback_queue(protocol::PlaybackQueueRequest {
+            source: protocol::PlaybackQueueSource::Library,
+            tracks: vec![restored("a"), restored("b")],
+            start_index: 0,
+        });
+        let _ = drain_upcoming_track_ids(&mut receiver);
+
+        manager.enqueue_tracks(vec![restored("x")], true);
+        assert_eq!(
+            drain_upcoming_track_ids(&mut receiver),
+            Some(vec!["x".to_string(), "b".to_string()])
+        );
+
+        manager.enqueue_tracks(vec![restored("y"), restored("a")], false);
+        let upcoming = drain_upcoming_track_ids(&mut receiver).expect("upcoming tracks sent");
+        assert_eq!(upcoming.len(), 4);
+        assert_eq!(&upcoming[..3], ["x", "b", "y"]);
+        assert_ne!(upcoming[3], "a", "re-queued track gets a fresh id");
+    }
+
+    #[test]
+    fn test_save_playback_queue_as_playlist_copies_queue_under_unique_name() {
+        let (mut manager, _receiver) = make_direct_manager();
+        manager.start_playback_queue(protocol::PlaybackQueueRequest {
+            source: protocol::PlaybackQueueSource::Library,
+            tracks: ["a", "b", "c"]
+                .iter()
+                .map(|id| protocol::RestoredTrack {
+                    id: id.to_string(),
+                    path: PathBuf::from(format!("/tmp/queue_{id}.flac")),
+                })
+                .collect(),
+            start_index: 0,
+        });
+
+        manager.save_playback_queue_as_playlist(false);
+        manager.save_playback_queue_as_playlist(false);
+
+        let playlists = manager.db_manager.get_all_playlists().expect("playlists");
+        let saved = playlists
+            .iter()
+            .find(|playlist| playlist.name == "Library Queue")
+            .expect("saved queue playlist");
+        assert!(playlists
+            .iter()
+            .any(|playlist| playlist.name == "Library Queue 2"));
+        let paths: Vec<PathBuf> = manager
+            .db_manager
+            .get_tracks_for_playlist(&saved.id)
+            .expect("saved tracks")
+            .into_iter()
+            .map(|track| track.path)
+            .collect();
+        assert_eq!(
+            paths,
+            ["a", "b", "c"]
+                .iter()
+                .map(|id| PathBuf::from(format!("/tmp/queue_{id}.flac")))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_split_and_merge_create_playlists_that_undo_deletes() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        let add_playlist = |manager: &PlaylistManager, id: &str, name: &str, count: usize| {
+            manager
+                .db_manager
+                .create_playlist(id, name)
+                .expect("playlist");
+            let rows: Vec<(String, PathBuf)> = (0..count)
+                .map(|index| {
+                    (
+                        format!("{id}_{index}"),
+                        PathBuf::from(format!("/tmp/restructure_{id}_{index}.flac")),
+                    )
+                })
+                .collect();
+            manager
+                .db_manager
+                .save_tracks_batch(id, &rows, 0)
+                .expect("tracks");
+        };
+        add_playlist(&manager, "mix", "Mix", 5);
+        add_playlist(&manager, "extra", "Extra", 1);
+        let playlist_paths = |manager: &PlaylistManager, name: &str| -> Vec<PathBuf> {
+            let playlists = manager.db_manager.get_all_playlists().expect("playlists");
+            let playlist = playlists
+                .iter()
+                .find(|playlist| playlist.name == name)
+                .expect("playlist exists");
+            manager
+                .db_manager
+                .get_tracks_for_playlist(&playlist.id)
+                .expect("tracks")
+                .into_iter()
+                .map(|track| track.path)
+                .collect()
+        };
+
+        manager.split_playlist("mix", protocol::PlaylistSplitRule::EveryNTracks(2));
+        assert_eq!(playlist_paths(&manager, "Mix - Part 3").len(), 1);
+        assert_eq!(manager.restructure_undo_playlist_ids.len(), 3);
+
+        manager.merge_playlists(
+            &["extra".to_string(), "mix".to_string()],
+            &protocol::PlaylistMergeMode::RoundRobin,
+        );
+        let merged = playlist_paths(&manager, "Extra + Mix (Merged)");
+        assert_eq!(merged.len(), 6);
+        assert_eq!(merged[0], PathBuf::from("/tmp/restructure_extra_0.flac"));
+        assert_eq!(merged[1], PathBuf::from("/tmp/restructure_mix_0.flac"));
+        assert_eq!(merged[2], PathBuf::from("/tmp/restructure_mix_1.flac"));
+
+        while receiver.try_recv().is_ok() {}
+        manager.undo_playlist_restructure();
+        let mut deleted = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            if let protocol::Message::Playlist(protocol::PlaylistMessage::DeletePlaylist { id }) =
+                message
+            {
+                deleted.push(id);
+            }
+        }
+        assert_eq!(deleted.len(), 1, "undo removes only the last merge");
+        assert!(manager.restructure_undo_playlist_ids.is_empty());
+    }
+
+    #[test]
+    fn test_xspf_export_then_import_keeps_order_and_notes() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        let remote = PathBuf::from(
+            "rtq://open_subsonic/home/song-1?endpoint=https%3A%2F%2Fmusic.example.com&username=alice",
+        );
+        let local = PathBuf::from("/tmp/roqtune_xspf_missing track.flac");
+        manager
+            .db_manager
+            .create_playlist("trip", "Road Trip")
+            .expect("playlist");
+        manager
+            .db_manager
+            .save_tracks_batch(
+                "trip",
+                &[
+                    ("t0".to_string(), local.clone()),
+                    ("t1".to_string(), remote.clone()),
+                ],
+                0,
+            )
+            .expect("tracks");
+        manager
+            .db_manager
+            .set_playlist_note("trip", "Windows down")
+            .expect("playlist note");
+        manager
+            .db_manager
+            .set_track_note(&remote, "Skip the intro", 1)
+            .expect("track note");
+
+        let file = std::env::temp_dir().join(format!(
+            "roqtune-xspf-roundtrip-{}.xspf",
+            std::process::id()
+        ));
+        manager
+            .export_playlist_xspf("trip", &file)
+            .expect("exported");
+        manager
+            .db_manager
+            .set_track_note(&remote, "", 2)
+            .expect("clear track note");
+        while receiver.try_recv().is_ok() {}
+        let message = manager.import_xspf_playlist(&file).expect("imported");
+        let _ = std::fs::remove_file(&file);
+        assert!(message.contains("Road Trip 2") && message.contains("1 file(s) not found"));
+
+        let imported = manager
+            .db_manager
+            .get_all_playlists()
+            .expect("playlists")
+            .into_iter()
+            .find(|playlist| playlist.name == "Road Trip 2")
+            .expect("imported playlist");
+        assert_eq!(imported.note, "Windows down");
+        let paths: Vec<PathBuf> = manager
+            .db_manager
+            .get_tracks_for_playlist(&imported.id)
+            .expect("tracks")
+            .into_iter()
+            .map(|track| track.path)
+            .collect();
+        assert_eq!(paths, vec![local, remote.clone()]);
+        let note_request = wait_for_message(&mut receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Library(protocol::LibraryMessage::SetTrackNote { .. })
             )
         });
-
-        let _ = wait_for_message(&mut harness.receiver, timeout, |message| match message {
-            protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
-                tracks.iter().any(|track| {
-                    track.id == id0 && track.play_immediately && track.start_offset_ms == 50_000
-                })
-            }
-            _ => false,
-        });
+        assert!(matches!(
+            note_request,
+            protocol::Message::Library(protocol::LibraryMessage::SetTrackNote { path, note })
+                if path == remote && note == "Skip the intro"
+        ));
     }
 
     #[test]
-    fn test_track_evicted_invalidates_cached_entry() {
-        let mut harness = PlaylistManagerHarness::new();
-        let (id0, _) = harness.add_track("pm_track_evicted_0");
-        harness.drain_messages();
-
-        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
+    fn test_materialize_remote_playlist_swaps_downloaded_copies_in_order() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        let remote_id = "remote:opensubsonic:home:pl-1";
+        let remote = |song: &str| {
+            PathBuf::from(format!(
+                "rtq://open_subsonic/home/{song}?endpoint=https%3A%2F%2Fmusic.example.com&username=alice"
+            ))
+        };
+        manager
+            .db_manager
+            .create_playlist(remote_id, "Road Trip")
+            .expect("playlist");
+        let rows = vec![
+            ("t0".to_string(), remote("song-2")),
+            ("t1".to_string(), PathBuf::from("/music/local.flac")),
+            ("t2".to_string(), remote("song-1")),
+            ("t3".to_string(), remote("song-2")),
+        ];
+        manager
+            .db_manager
+            .save_tracks_batch(remote_id, &rows, 0)
+            .expect("tracks");
+        manager.active_playlist_id = remote_id.to_string();
 
-        let _ = wait_for_message(&mut harness.receiver, Duration::from_secs(1), |message| {
+        manager.request_materialize_remote_playlist(remote_id);
+        let request = wait_for_message(&mut receiver, Duration::from_secs(1), |message| {
             matches!(
                 message,
-                protocol::Message::Playback(protocol::PlaybackMessage::ClearPlayerCache)
+                protocol::Message::Integration(
+                    protocol::IntegrationMessage::MaterializeRemotePlaylist { .. }
+                )
             )
         });
-        let _ =
-            wait_for_message(
-                &mut harness.receiver,
-                Duration::from_secs(1),
-                |message| match message {
-                    protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
-                        tracks
-                            .first()
-                            .map(|track| track.id == id0 && track.play_immediately)
-                            .unwrap_or(false)
-                    }
-                    _ => false,
-                },
-            );
-
-        harness.drain_messages();
-        harness.send(protocol::Message::Audio(
-            protocol::AudioMessage::TrackCached(id0.clone(), 0),
-        ));
-        harness.send(protocol::Message::Audio(
-            protocol::AudioMessage::TrackEvicted(id0.clone()),
-        ));
-        harness.drain_messages();
-
-        harness.start_playlist_queue_from_ids(std::slice::from_ref(&id0), 0);
-
-        let _ =
-            wait_for_message(
-                &mut harness.receiver,
-                Duration::from_secs(1),
-                |message| match message {
-                    protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) => {
-                        tracks
-                            .first()
-                            .map(|track| track.id == id0 && track.play_immediately)
-                            .unwrap_or(false)
-                    }
-                    _ => false,
-                },
-            );
-    }
-
-    fn make_direct_manager() -> (PlaylistManager, Receiver<protocol::Message>) {
-        let (bus_sender, _) = broadcast::channel(256);
-        let receiver = bus_sender.subscribe();
-        let manager_receiver = bus_sender.subscribe();
-        let (_bulk_import_tx, bulk_import_rx) =
-            std::sync::mpsc::sync_channel::<protocol::PlaylistBulkImportRequest>(64);
-        let db_manager = DbManager::new_in_memory().expect("failed to create in-memory db");
-        let manager = PlaylistManager::new(
-            Playlist::new(),
-            manager_receiver,
-            bus_sender,
-            db_manager,
-            bulk_import_rx,
-            OutputConfig::default(),
-            UiConfig::default(),
+        let protocol::Message::Integration(
+            protocol::IntegrationMessage::MaterializeRemotePlaylist { paths, .. },
+        ) = request
+        else {
+            panic!("expected MaterializeRemotePlaylist");
+        };
+        assert_eq!(paths, vec![remote("song-2"), remote("song-1")]);
+
+        let materialized_id = manager
+            .materialize_remote_playlist(
+                remote_id,
+                vec![
+                    (remote("song-2"), PathBuf::from("/offline/home/song-2.flac")),
+                    (remote("song-1"), PathBuf::from("/offline/home/song-1.mp3")),
+                ],
+            )
+            .expect("materialized");
+        let paths: Vec<PathBuf> = manager
+            .db_manager
+            .get_tracks_for_playlist(&materialized_id)
+            .expect("tracks")
+            .into_iter()
+            .map(|track| track.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/offline/home/song-2.flac"),
+                PathBuf::from("/music/local.flac"),
+                PathBuf::from("/offline/home/song-1.mp3"),
+                PathBuf::from("/offline/home/song-2.flac"),
+            ]
+        );
+        let playlists = manager.db_manager.get_all_playlists().expect("playlists");
+        assert!(playlists.iter().all(|playlist| playlist.id != remote_id));
+        assert_eq!(manager.active_playlist_id, materialized_id);
+        assert_eq!(
+            manager.playlist_name_by_id(&materialized_id).as_deref(),
+            Some("Road Trip")
         );
-        (manager, receiver)
     }
 
     #[test]
@@ -5006,6 +8673,209 @@ mod tests {
         assert_eq!(tracks[0].id, "t0");
     }
 
+    #[test]
+    fn test_cache_tracks_groups_same_family_rates_into_one_segment() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        manager.sample_rate_auto_enabled = true;
+        manager.rate_grouping_policy.group_families = true;
+        manager.verified_output_rates = vec![44_100, 48_000, 88_200];
+        manager.current_output_rate_hz = Some(88_200);
+        manager.playback_playlist = Playlist::new();
+        for (id, rate) in [("t0", 44_100), ("t1", 88_200)] {
+            let path = PathBuf::from(format!("/tmp/{id}_family.flac"));
+            manager
+                .track_sample_rate_cache
+                .insert(path.clone(), Some(rate));
+            manager.playback_playlist.add_track(Track {
+                id: id.to_string(),
+                path,
+            });
+        }
+        manager.playback_playlist.set_playing_track_index(Some(0));
+        manager.playback_playlist.set_playing(true);
+
+        manager.cache_tracks(false);
+
+        let message = wait_for_message(&mut receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Audio(_)
+                    | protocol::Message::Config(
+                        protocol::ConfigMessage::SetRuntimeOutputRate { .. }
+                    )
+            )
+        });
+        let protocol::Message::Audio(protocol::AudioMessage::DecodeTracks(tracks)) = message else {
+            panic!("expected DecodeTracks without an output-rate switch");
+        };
+        let ids: Vec<&str> = tracks.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["t0", "t1"]);
+        assert_eq!(manager.pending_rate_switch, None);
+    }
+
+    #[test]
+    fn test_technical_metadata_keeps_output_rate_for_isolated_outlier() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        manager.sample_rate_auto_enabled = true;
+        manager.rate_grouping_policy.resample_outliers = true;
+        manager.verified_output_rates = vec![44_100, 48_000];
+        manager.current_output_rate_hz = Some(44_100);
+        manager.playback_playlist = Playlist::new();
+        manager.playback_playlist.add_track(Track {
+            id: "outlier".to_string(),
+            path: PathBuf::from("/tmp/outlier_48.flac"),
+        });
+        manager.playback_playlist.add_track(Track {
+            id: "album".to_string(),
+            path: PathBuf::from("/tmp/album_44.flac"),
+        });
+        manager
+            .track_sample_rate_cache
+            .insert(PathBuf::from("/tmp/album_44.flac"), Some(44_100));
+        manager.playback_playlist.set_playing_track_index(Some(0));
+        manager.playback_playlist.set_playing(true);
+
+        manager.handle_technical_metadata_changed(protocol::TechnicalMetadata {
+            format: "FLAC".to_string(),
+            bitrate_kbps: 1_000,
+            sample_rate_hz: 48_000,
+            channel_count: 2,
+            duration_ms: 200_000,
+            bits_per_sample: 24,
+        });
+
+        assert_no_message(&mut receiver, Duration::from_millis(250), |message| {
+            matches!(
+                message,
+                protocol::Message::Config(protocol::ConfigMessage::SetRuntimeOutputRate { .. })
+            )
+        });
+        assert_eq!(manager.pending_rate_switch, None);
+
+        manager.rate_grouping_policy.resample_outliers = false;
+        manager.handle_technical_metadata_changed(protocol::TechnicalMetadata {
+            format: "FLAC".to_string(),
+            bitrate_kbps: 1_000,
+            sample_rate_hz: 48_000,
+            channel_count: 2,
+            duration_ms: 200_000,
+            bits_per_sample: 24,
+        });
+        assert_eq!(manager.pending_rate_switch, Some(48_000));
+    }
+
+    fn rate_test_metadata(sample_rate_hz: u32) -> protocol::TechnicalMetadata {
+        protocol::TechnicalMetadata {
+            format: "FLAC".to_string(),
+            bitrate_kbps: 1_000,
+            sample_rate_hz,
+            channel_count: 2,
+            duration_ms: 200_000,
+            bits_per_sample: 24,
+        }
+    }
+
+    fn wait_for_output_rate_decision(
+        receiver: &mut Receiver<protocol::Message>,
+    ) -> protocol::OutputRateDecision {
+        let message = wait_for_message(receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(protocol::PlaylistMessage::OutputRateDecided(_))
+            )
+        });
+        let protocol::Message::Playlist(protocol::PlaylistMessage::OutputRateDecided(decision)) =
+            message
+        else {
+            panic!("expected OutputRateDecided");
+        };
+        decision
+    }
+
+    #[test]
+    fn test_pinned_rate_policy_switches_to_pinned_rate_for_every_track() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        manager.sample_rate_auto_enabled = true;
+        manager.verified_output_rates = vec![44_100, 48_000, 96_000];
+        manager.current_output_rate_hz = Some(44_100);
+        manager.rate_policy = OutputDeviceRatePolicyConfig {
+            output_device_name: "default".to_string(),
+            policy: SampleRatePolicy::Pinned,
+            pinned_sample_rate_hz: 96_000,
+        };
+        manager.playback_playlist = Playlist::new();
+        manager.playback_playlist.add_track(Track {
+            id: "t0".to_string(),
+            path: PathBuf::from("/tmp/pinned/t0.flac"),
+        });
+        manager.playback_playlist.set_playing_track_index(Some(0));
+        manager.playback_playlist.set_playing(true);
+
+        manager.handle_technical_metadata_changed(rate_test_metadata(44_100));
+
+        assert_eq!(manager.pending_rate_switch, Some(96_000));
+        assert_eq!(
+            wait_for_output_rate_decision(&mut receiver),
+            protocol::OutputRateDecision {
+                output_rate_hz: 96_000,
+                reason: protocol::OutputRateReason::PinnedForDevice,
+            }
+        );
+    }
+
+    #[test]
+    fn test_album_boundary_policy_keeps_album_rate_until_the_next_album() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        manager.sample_rate_auto_enabled = true;
+        manager.verified_output_rates = vec![44_100, 48_000, 96_000];
+        manager.current_output_rate_hz = Some(44_100);
+        manager.update_runtime_policy_from_output_delta(&protocol::OutputConfigDelta {
+            output_device_name: Some("USB DAC".to_string()),
+            device_rate_policies: Some(vec![OutputDeviceRatePolicyConfig {
+                output_device_name: "default".to_string(),
+                policy: SampleRatePolicy::AlbumBoundaries,
+                pinned_sample_rate_hz: 48_000,
+            }]),
+            ..protocol::OutputConfigDelta::default()
+        });
+        assert_eq!(
+            manager.rate_policy.policy,
+            SampleRatePolicy::AlbumBoundaries
+        );
+        manager.playback_playlist = Playlist::new();
+        for (id, path, rate) in [
+            ("a1", "/tmp/album_a/01.flac", 44_100),
+            ("a2", "/tmp/album_a/02.flac", 96_000),
+            ("b1", "/tmp/album_b/01.flac", 48_000),
+        ] {
+            let path = PathBuf::from(path);
+            manager
+                .track_sample_rate_cache
+                .insert(path.clone(), Some(rate));
+            manager.playback_playlist.add_track(Track {
+                id: id.to_string(),
+                path,
+            });
+        }
+        manager.playback_playlist.set_playing_track_index(Some(1));
+        manager.playback_playlist.set_playing(true);
+
+        manager.handle_technical_metadata_changed(rate_test_metadata(96_000));
+
+        assert_eq!(manager.pending_rate_switch, None);
+        assert_eq!(
+            wait_for_output_rate_decision(&mut receiver),
+            protocol::OutputRateDecision {
+                output_rate_hz: 44_100,
+                reason: protocol::OutputRateReason::AlbumRate,
+            }
+        );
+        assert_eq!(
+            manager.planned_output_rate_for_index(2, Some(44_100)),
+            Some(48_000)
+        );
+    }
+
     #[test]
     fn test_cache_tracks_repeat_track_does_not_enqueue_duplicate_ids() {
         let (mut manager, mut receiver) = make_direct_manager();
@@ -5275,4 +9145,96 @@ mod tests {
         assert_eq!(id, "remote_legacy");
         assert!(manager.unavailable_track_ids.contains("remote_legacy"));
     }
+
+    #[test]
+    fn test_recover_unavailable_remote_tracks_restores_metadata_after_reconnect() {
+        let (mut manager, mut receiver) = make_direct_manager();
+        let remote_path = PathBuf::from(
+            "rtq://open_subsonic/test-profile/song-001?ENDPOINT=https%3A%2F%2Fmusic.example.com&USERNAME=alice&FORMAT=flac",
+        );
+        manager.editing_playlist = Playlist::new();
+        manager.editing_playlist.add_track(Track {
+            id: "remote_track".to_string(),
+            path: remote_path.clone(),
+        });
+        manager
+            .unavailable_track_ids
+            .insert("remote_track".to_string());
+        manager.remote_track_metadata_by_path.insert(
+            remote_path,
+            protocol::TrackMetadataSummary {
+                title: "Song One".to_string(),
+                artist: "Artist".to_string(),
+                album: String::new(),
+                album_artist: String::new(),
+                date: String::new(),
+                genre: String::new(),
+                year: String::new(),
+                track_number: String::new(),
+                composer: String::new(),
+                disc_number: String::new(),
+                total_tracks: String::new(),
+                comment: String::new(),
+            },
+        );
+        manager.backend_connection_states.insert(
+            "test-profile".to_string(),
+            protocol::BackendConnectionState::Connected,
+        );
+
+        manager.recover_unavailable_remote_tracks();
+
+        let metadata_message = wait_for_message(&mut receiver, Duration::from_secs(1), |message| {
+            matches!(
+                message,
+                protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackMetadataBatchUpdated { .. }
+                )
+            )
+        });
+        let protocol::Message::Playlist(protocol::PlaylistMessage::TrackMetadataBatchUpdated {
+            updates,
+        }) = metadata_message
+        else {
+            panic!("expected TrackMetadataBatchUpdated");
+        };
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].track_id, "remote_track");
+        assert_eq!(updates[0].summary.title, "Song One");
+        assert!(manager.unavailable_track_ids.is_empty());
+    }
+
+    #[test]
+    fn test_missing_files_stay_unavailable_until_relocated() {
+        let (mut manager, _receiver) = make_direct_manager();
+        let old_path = PathBuf::from("/old/music/Album/01.flac");
+        let new_path = PathBuf::from("/mnt/nas/music/Album/01.flac");
+        manager.editing_playlist = Playlist::new();
+        for (id, path) in [("missing", &old_path), ("present", &new_path)] {
+            manager.editing_playlist.add_track(Track {
+                id: id.to_string(),
+                path: path.clone(),
+            });
+        }
+        manager.unavailable_track_ids.insert("present".to_string());
+
+        manager.apply_verified_playlist_files(
+            vec!["missing".to_string(), "present".to_string()],
+            &[protocol::RestoredTrack {
+                id: "missing".to_string(),
+                path: old_path.clone(),
+            }],
+        );
+        assert_eq!(
+            manager.unavailable_track_ids,
+            HashSet::from(["missing".to_string()])
+        );
+
+        manager.apply_relocated_track_paths(vec![protocol::OrganizeFileMove {
+            from: old_path,
+            to: new_path.clone(),
+        }]);
+        assert!(manager.unavailable_track_ids.is_empty());
+        assert_eq!(manager.editing_playlist.get_track(0).path, new_path);
+    }
 }
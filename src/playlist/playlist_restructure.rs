@@ -0,0 +1,186 @@
+//! Track plans for splitting one playlist into several and merging several into one.
+//!
+//! These functions only decide which tracks go where and in what order. PlaylistManager
+//! creates the resulting playlists and leaves the source playlists untouched, so undoing a
+//! split or merge only has to delete what it created.
+
+use std::collections::HashMap;
+
+use crate::protocol::{PlaylistMergeMode, PlaylistSplitRule};
+
+/// Grouping metadata of one track in a playlist being split.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitTrackInfo {
+    pub artist: String,
+    pub genre: String,
+    pub year: String,
+}
+
+/// One playlist to create from a split.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitGroup {
+    /// Appended to the source playlist name.
+    pub label: String,
+    /// Positions in the source playlist, in playlist order.
+    pub track_indices: Vec<usize>,
+}
+
+/// Returns the decade of a year tag, matching the library's Decades view.
+pub fn decade_label(year: &str) -> String {
+    let prefix: String = year.trim().chars().take(3).collect();
+    if prefix.len() == 3 && prefix.chars().all(|ch| ch.is_ascii_digit()) {
+        format!("{prefix}0s")
+    } else {
+        "Unknown Decade".to_string()
+    }
+}
+
+fn group_label(track: &SplitTrackInfo, rule: PlaylistSplitRule) -> String {
+    let (value, unknown) = match rule {
+        PlaylistSplitRule::Artist => (track.artist.trim(), "Unknown Artist"),
+        PlaylistSplitRule::Genre => (track.genre.trim(), "Unknown Genre"),
+        PlaylistSplitRule::Decade => return decade_label(&track.year),
+        PlaylistSplitRule::EveryNTracks(_) => return String::new(),
+    };
+    if value.is_empty() {
+        unknown.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Groups playlist tracks by `rule`, in order of each group's first track.
+///
+/// Artist and genre values that differ only in case share a group named after the
+/// first spelling seen.
+pub fn plan_split(tracks: &[SplitTrackInfo], rule: PlaylistSplitRule) -> Vec<SplitGroup> {
+    if let PlaylistSplitRule::EveryNTracks(chunk_size) = rule {
+        let indices: Vec<usize> = (0..tracks.len()).collect();
+        return indices
+            .chunks(chunk_size.max(1))
+            .enumerate()
+            .map(|(part, chunk)| SplitGroup {
+                label: format!("Part {}", part + 1),
+                track_indices: chunk.to_vec(),
+            })
+            .collect();
+    }
+    let mut groups: Vec<SplitGroup> = Vec::new();
+    let mut group_by_key: HashMap<String, usize> = HashMap::new();
+    for (index, track) in tracks.iter().enumerate() {
+        let label = group_label(track, rule);
+        let group_index = *group_by_key.entry(label.to_lowercase()).or_insert_with(|| {
+            groups.push(SplitGroup {
+                label,
+                track_indices: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[group_index].track_indices.push(index);
+    }
+    groups
+}
+
+/// Interleaves playlists of the given lengths into `(playlist, track)` positions.
+///
+/// Each turn takes one track from every playlist in round-robin mode, or as many as the
+/// playlist's weight in ratio mode. Playlists that run out drop out of later turns.
+pub fn plan_merge(lengths: &[usize], mode: &PlaylistMergeMode) -> Vec<(usize, usize)> {
+    let weights: Vec<usize> = match mode {
+        PlaylistMergeMode::RoundRobin => vec![1; lengths.len()],
+        PlaylistMergeMode::Ratio(weights) => (0..lengths.len())
+            .map(|index| weights.get(index).copied().unwrap_or(1).max(1))
+            .collect(),
+    };
+    let total: usize = lengths.iter().sum();
+    let mut taken = vec![0usize; lengths.len()];
+    let mut order = Vec::with_capacity(total);
+    while order.len() < total {
+        for (playlist, (&length, &weight)) in lengths.iter().zip(&weights).enumerate() {
+            let end = (taken[playlist] + weight).min(length);
+            order.extend((taken[playlist]..end).map(|track| (playlist, track)));
+            taken[playlist] = end;
+        }
+    }
+    order
+}
+
+/// Parses a merge ratio such as `2:1` with one positive weight per merged playlist.
+pub fn parse_merge_ratio(text: &str, playlist_count: usize) -> Option<Vec<usize>> {
+    let weights: Vec<usize> = text
+        .split([':', ','])
+        .map(|part| {
+            part.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|weight| *weight > 0)
+        })
+        .collect::<Option<_>>()?;
+    (weights.len() == playlist_count).then_some(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(artist: &str, genre: &str, year: &str) -> SplitTrackInfo {
+        SplitTrackInfo {
+            artist: artist.to_string(),
+            genre: genre.to_string(),
+            year: year.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_split_groups_by_rule_in_first_seen_order() {
+        let tracks = vec![
+            info("Beta", "Jazz", "1994"),
+            info("Alpha", "", "2001-05-01"),
+            info("beta", "Rock", ""),
+            info("", "jazz", "1999"),
+        ];
+
+        let by_artist = plan_split(&tracks, PlaylistSplitRule::Artist);
+        assert_eq!(
+            by_artist
+                .iter()
+                .map(|group| (group.label.as_str(), group.track_indices.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Beta", vec![0, 2]),
+                ("Alpha", vec![1]),
+                ("Unknown Artist", vec![3]),
+            ]
+        );
+        let by_decade = plan_split(&tracks, PlaylistSplitRule::Decade);
+        assert_eq!(
+            by_decade
+                .iter()
+                .map(|group| group.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1990s", "2000s", "Unknown Decade"]
+        );
+        assert_eq!(plan_split(&tracks, PlaylistSplitRule::Genre).len(), 3);
+
+        let parts = plan_split(&tracks, PlaylistSplitRule::EveryNTracks(3));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].label, "Part 2");
+        assert_eq!(parts[1].track_indices, vec![3]);
+    }
+
+    #[test]
+    fn test_plan_merge_interleaves_round_robin_and_by_ratio() {
+        assert_eq!(
+            plan_merge(&[3, 1], &PlaylistMergeMode::RoundRobin),
+            vec![(0, 0), (1, 0), (0, 1), (0, 2)]
+        );
+        assert_eq!(
+            plan_merge(&[4, 3], &PlaylistMergeMode::Ratio(vec![2, 1])),
+            vec![(0, 0), (0, 1), (1, 0), (0, 2), (0, 3), (1, 1), (1, 2)]
+        );
+        assert!(plan_merge(&[0, 0], &PlaylistMergeMode::RoundRobin).is_empty());
+        assert_eq!(parse_merge_ratio(" 3 : 1 ", 2), Some(vec![3, 1]));
+        assert_eq!(parse_merge_ratio("2:0", 2), None);
+        assert_eq!(parse_merge_ratio("2:1", 3), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Multi-key playlist sorting.
+//!
+//! Clicking a column header sorts the playlist view by that column alone; Shift-clicking
+//! adds the column as a further key, so e.g. album artist, then year, then disc, then
+//! track number sort together. A sorted view is read-only until it is discarded or
+//! written into the playlist with `PlaylistMessage::SortTracks`, which reorders the
+//! tracks as one undoable edit.
+
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistSortDirection {
+    Ascending,
+    Descending,
+}
+
+/// One sort key: a playlist column and its direction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaylistSortKey {
+    pub column_key: String,
+    pub direction: PlaylistSortDirection,
+}
+
+impl PlaylistSortKey {
+    fn ascending(column_key: &str) -> Self {
+        Self {
+            column_key: column_key.to_string(),
+            direction: PlaylistSortDirection::Ascending,
+        }
+    }
+}
+
+/// Sorts by `column_key` alone. Clicking the only sort column again flips it to
+/// descending, then turns sorting off.
+pub fn cycle_primary_key(keys: &mut Vec<PlaylistSortKey>, column_key: &str) {
+    let sole_direction = match keys.as_slice() {
+        [key] if key.column_key == column_key => Some(key.direction),
+        _ => None,
+    };
+    keys.clear();
+    match sole_direction {
+        None => keys.push(PlaylistSortKey::ascending(column_key)),
+        Some(PlaylistSortDirection::Ascending) => keys.push(PlaylistSortKey {
+            column_key: column_key.to_string(),
+            direction: PlaylistSortDirection::Descending,
+        }),
+        Some(PlaylistSortDirection::Descending) => {}
+    }
+}
+
+/// Adds `column_key` as the last sort key. A column that already is a key flips to
+/// descending, then drops out.
+pub fn cycle_additional_key(keys: &mut Vec<PlaylistSortKey>, column_key: &str) {
+    let Some(position) = keys.iter().position(|key| key.column_key == column_key) else {
+        keys.push(PlaylistSortKey::ascending(column_key));
+        return;
+    };
+    match keys[position].direction {
+        PlaylistSortDirection::Ascending => {
+            keys[position].direction = PlaylistSortDirection::Descending;
+        }
+        PlaylistSortDirection::Descending => {
+            keys.remove(position);
+        }
+    }
+}
+
+/// Compares two rows by their per-key sort values, keeping playlist order for ties.
+pub fn compare_rows(
+    keys: &[PlaylistSortKey],
+    (lhs_values, lhs_index): (&[String], usize),
+    (rhs_values, rhs_index): (&[String], usize),
+) -> Ordering {
+    keys.iter()
+        .zip(lhs_values.iter().zip(rhs_values))
+        .map(|(key, (lhs, rhs))| match key.direction {
+            PlaylistSortDirection::Ascending => lhs.cmp(rhs),
+            PlaylistSortDirection::Descending => rhs.cmp(lhs),
+        })
+        .find(|order| order.is_ne())
+        .unwrap_or_else(|| lhs_index.cmp(&rhs_index))
+}
+
+/// Returns `true` when `order` lists every track index below `track_count` exactly once.
+pub fn is_full_reorder(order: &[usize], track_count: usize) -> bool {
+    if order.len() != track_count {
+        return false;
+    }
+    let mut seen = vec![false; track_count];
+    order
+        .iter()
+        .all(|&index| index < track_count && !std::mem::replace(&mut seen[index], true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(column_key: &str, direction: PlaylistSortDirection) -> PlaylistSortKey {
+        PlaylistSortKey {
+            column_key: column_key.to_string(),
+            direction,
+        }
+    }
+
+    #[test]
+    fn test_primary_and_additional_keys_cycle_directions() {
+        use PlaylistSortDirection::{Ascending, Descending};
+        let mut keys = Vec::new();
+        cycle_primary_key(&mut keys, "album_artist");
+        cycle_additional_key(&mut keys, "year");
+        cycle_additional_key(&mut keys, "disc");
+        assert_eq!(
+            keys,
+            vec![
+                key("album_artist", Ascending),
+                key("year", Ascending),
+                key("disc", Ascending)
+            ]
+        );
+
+        cycle_additional_key(&mut keys, "year");
+        assert_eq!(keys[1], key("year", Descending));
+        cycle_additional_key(&mut keys, "year");
+        assert_eq!(
+            keys,
+            vec![key("album_artist", Ascending), key("disc", Ascending)]
+        );
+
+        cycle_primary_key(&mut keys, "disc");
+        assert_eq!(keys, vec![key("disc", Ascending)]);
+        cycle_primary_key(&mut keys, "disc");
+        assert_eq!(keys, vec![key("disc", Descending)]);
+        cycle_primary_key(&mut keys, "disc");
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn test_compare_rows_applies_keys_in_order_then_playlist_order() {
+        use PlaylistSortDirection::{Ascending, Descending};
+        let keys = vec![key("artist", Ascending), key("year", Descending)];
+        let a = vec!["abba".to_string(), "1976".to_string()];
+        let b = vec!["abba".to_string(), "1979".to_string()];
+        let c = vec!["blur".to_string(), "1994".to_string()];
+        assert_eq!(compare_rows(&keys, (&a, 0), (&b, 1)), Ordering::Greater);
+        assert_eq!(compare_rows(&keys, (&b, 1), (&c, 2)), Ordering::Less);
+        assert_eq!(compare_rows(&keys, (&a, 3), (&a, 1)), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_is_full_reorder_requires_each_index_once() {
+        assert!(is_full_reorder(&[2, 0, 1], 3));
+        assert!(!is_full_reorder(&[2, 0], 3));
+        assert!(!is_full_reorder(&[2, 0, 0], 3));
+        assert!(!is_full_reorder(&[3, 0, 1], 3));
+    }
+}
@@ -0,0 +1,168 @@
+//! Revision tracking behind the sync indicator of remote-bound playlists.
+//!
+//! Every local edit written back to the server bumps the playlist's local revision; a
+//! successful writeback result marks that revision as written. While written revisions
+//! lag behind, the playlist has local edits pending and pulled server snapshots are held
+//! back instead of overwriting those edits, which leaves remote changes pending until the
+//! next pull after the writeback lands.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::protocol::RemotePlaylistSyncStatus;
+
+#[derive(Debug, Default, Clone)]
+struct PlaylistRevisions {
+    local_revision: u64,
+    written_revision: u64,
+    /// Revisions sent to the server, oldest first, still waiting for a result.
+    in_flight: VecDeque<u64>,
+    remote_changes_pending: bool,
+    last_synced_unix_ms: Option<i64>,
+}
+
+impl PlaylistRevisions {
+    fn local_edits_pending(&self) -> bool {
+        self.local_revision > self.written_revision
+    }
+}
+
+/// Sync bookkeeping for all remote-bound playlists, keyed by local playlist id.
+#[derive(Debug, Default)]
+pub struct RemoteSyncTracker {
+    playlists: HashMap<String, PlaylistRevisions>,
+}
+
+impl RemoteSyncTracker {
+    /// Records a local edit that is being written back to the server.
+    pub fn record_writeback_sent(&mut self, playlist_id: &str) {
+        let revisions = self.playlists.entry(playlist_id.to_string()).or_default();
+        revisions.local_revision += 1;
+        let revision = revisions.local_revision;
+        revisions.in_flight.push_back(revision);
+    }
+
+    /// Applies the result of the oldest writeback still in flight.
+    pub fn record_writeback_result(&mut self, playlist_id: &str, success: bool, now_unix_ms: i64) {
+        let Some(revisions) = self.playlists.get_mut(playlist_id) else {
+            return;
+        };
+        let Some(revision) = revisions.in_flight.pop_front() else {
+            return;
+        };
+        if !success {
+            return;
+        }
+        revisions.written_revision = revisions.written_revision.max(revision);
+        revisions.last_synced_unix_ms = Some(now_unix_ms);
+        if !revisions.local_edits_pending() {
+            // The server now holds the local copy, so held-back remote changes are gone.
+            revisions.remote_changes_pending = false;
+        }
+    }
+
+    /// Returns whether local edits have not been confirmed by the server yet.
+    pub fn has_local_edits_pending(&self, playlist_id: &str) -> bool {
+        self.playlists
+            .get(playlist_id)
+            .is_some_and(PlaylistRevisions::local_edits_pending)
+    }
+
+    /// Returns whether a local edit is still waiting for its writeback result.
+    pub fn has_writeback_in_flight(&self, playlist_id: &str) -> bool {
+        self.playlists
+            .get(playlist_id)
+            .is_some_and(|revisions| !revisions.in_flight.is_empty())
+    }
+
+    /// Records a server snapshot that was applied to the local playlist.
+    pub fn record_pull_applied(&mut self, playlist_id: &str, now_unix_ms: i64) {
+        let revisions = self.playlists.entry(playlist_id.to_string()).or_default();
+        revisions.remote_changes_pending = false;
+        revisions.last_synced_unix_ms = Some(now_unix_ms);
+    }
+
+    /// Records a server snapshot held back because local edits are pending.
+    pub fn record_pull_deferred(&mut self, playlist_id: &str) {
+        self.playlists
+            .entry(playlist_id.to_string())
+            .or_default()
+            .remote_changes_pending = true;
+    }
+
+    /// Drops the bookkeeping of a playlist that is no longer remote-bound.
+    pub fn forget(&mut self, playlist_id: &str) {
+        self.playlists.remove(playlist_id);
+    }
+
+    /// Current status of every tracked playlist, sorted by playlist id.
+    pub fn statuses(&self) -> Vec<RemotePlaylistSyncStatus> {
+        let mut statuses: Vec<RemotePlaylistSyncStatus> = self
+            .playlists
+            .iter()
+            .map(|(playlist_id, revisions)| RemotePlaylistSyncStatus {
+                playlist_id: playlist_id.clone(),
+                last_synced_unix_ms: revisions.last_synced_unix_ms,
+                local_edits_pending: revisions.local_edits_pending(),
+                remote_changes_pending: revisions.remote_changes_pending,
+            })
+            .collect();
+        statuses.sort_by(|left, right| left.playlist_id.cmp(&right.playlist_id));
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(tracker: &RemoteSyncTracker, playlist_id: &str) -> RemotePlaylistSyncStatus {
+        tracker
+            .statuses()
+            .into_iter()
+            .find(|status| status.playlist_id == playlist_id)
+            .expect("playlist is tracked")
+    }
+
+    #[test]
+    fn test_local_edits_stay_pending_until_every_writeback_succeeds() {
+        let mut tracker = RemoteSyncTracker::default();
+        tracker.record_pull_applied("p", 1_000);
+        tracker.record_writeback_sent("p");
+        tracker.record_writeback_sent("p");
+
+        tracker.record_writeback_result("p", true, 2_000);
+        assert!(tracker.has_local_edits_pending("p"));
+        assert!(tracker.has_writeback_in_flight("p"));
+
+        tracker.record_writeback_result("p", false, 3_000);
+        let failed = status(&tracker, "p");
+        assert!(failed.local_edits_pending);
+        assert_eq!(failed.last_synced_unix_ms, Some(2_000));
+        assert!(!tracker.has_writeback_in_flight("p"));
+
+        tracker.record_writeback_sent("p");
+        tracker.record_writeback_result("p", true, 4_000);
+        assert!(!tracker.has_local_edits_pending("p"));
+        assert_eq!(status(&tracker, "p").last_synced_unix_ms, Some(4_000));
+    }
+
+    #[test]
+    fn test_deferred_pull_marks_remote_changes_until_synced() {
+        let mut tracker = RemoteSyncTracker::default();
+        tracker.record_writeback_sent("p");
+        tracker.record_pull_deferred("p");
+        let pending = status(&tracker, "p");
+        assert!(pending.local_edits_pending);
+        assert!(pending.remote_changes_pending);
+
+        tracker.record_writeback_result("p", true, 5_000);
+        assert!(!status(&tracker, "p").remote_changes_pending);
+
+        tracker.record_pull_deferred("p");
+        tracker.record_pull_applied("p", 6_000);
+        assert!(!status(&tracker, "p").remote_changes_pending);
+
+        tracker.forget("p");
+        assert!(tracker.statuses().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Finding moved music files under a new base folder.
+//!
+//! When a music folder moves (a new drive letter, a NAS mounted elsewhere, a renamed
+//! parent folder), playlist paths still point at the old location. Each missing file is
+//! looked up under the folder the user picks by trying the tail of its old path, longest
+//! first: `/old/music/Artist/Album/01.flac` relocated to `/mnt/nas` tries
+//! `/mnt/nas/old/music/Artist/Album/01.flac`, then `/mnt/nas/music/Artist/Album/01.flac`,
+//! and so on down to `/mnt/nas/01.flac`. Keeping as much of the old tree as possible
+//! avoids binding tracks to same-named files from other albums.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::protocol::OrganizeFileMove;
+
+/// Outcome of looking up missing files under a new base folder.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelocationPlan {
+    pub moves: Vec<OrganizeFileMove>,
+    /// Missing files with no match under the new base folder.
+    pub unresolved: Vec<PathBuf>,
+}
+
+/// Deepest folder containing every path, used to tell the user where the files were.
+pub fn common_folder(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut parents = paths.iter().filter_map(|path| path.parent());
+    let mut common = parents.next()?.to_path_buf();
+    for parent in parents {
+        while !parent.starts_with(&common) {
+            if !common.pop() {
+                return None;
+            }
+        }
+    }
+    (!common.as_os_str().is_empty()).then_some(common)
+}
+
+/// New location of `missing` under `new_base`, preferring the longest matching tail.
+fn relocated_path(
+    missing: &Path,
+    new_base: &Path,
+    exists: &impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    let components: Vec<&std::ffi::OsStr> = missing
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    (0..components.len())
+        .map(|start| {
+            components[start..]
+                .iter()
+                .fold(new_base.to_path_buf(), |path, name| path.join(name))
+        })
+        .find(|candidate| candidate != missing && exists(candidate))
+}
+
+/// Looks up every missing file under `new_base`; `exists` reports whether a candidate
+/// file is present.
+pub fn plan_relocation(
+    missing: &[PathBuf],
+    new_base: &Path,
+    exists: impl Fn(&Path) -> bool,
+) -> RelocationPlan {
+    let mut plan = RelocationPlan::default();
+    for path in missing {
+        match relocated_path(path, new_base, &exists) {
+            Some(to) => plan.moves.push(OrganizeFileMove {
+                from: path.clone(),
+                to,
+            }),
+            None => plan.unresolved.push(path.clone()),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_common_folder_finds_shared_parent() {
+        let paths = vec![
+            PathBuf::from("/old/music/A/Album/01.flac"),
+            PathBuf::from("/old/music/B/02.flac"),
+        ];
+        assert_eq!(common_folder(&paths), Some(PathBuf::from("/old/music")));
+        assert_eq!(
+            common_folder(&paths[..1]),
+            Some(PathBuf::from("/old/music/A/Album"))
+        );
+        assert_eq!(common_folder(&[]), None);
+    }
+
+    #[test]
+    fn test_plan_relocation_prefers_longest_matching_tail() {
+        let present: HashSet<PathBuf> = [
+            "/mnt/nas/music/Artist/Album/01.flac",
+            "/mnt/nas/Artist/Album/01.flac",
+            "/mnt/nas/02.flac",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        let missing = vec![
+            PathBuf::from("/old/music/Artist/Album/01.flac"),
+            PathBuf::from("/old/music/Other/02.flac"),
+            PathBuf::from("/old/music/Other/03.flac"),
+        ];
+        let plan = plan_relocation(&missing, Path::new("/mnt/nas"), |path| {
+            present.contains(path)
+        });
+        assert_eq!(
+            plan.moves,
+            [
+                OrganizeFileMove {
+                    from: missing[0].clone(),
+                    to: PathBuf::from("/mnt/nas/music/Artist/Album/01.flac"),
+                },
+                OrganizeFileMove {
+                    from: missing[1].clone(),
+                    to: PathBuf::from("/mnt/nas/02.flac"),
+                },
+            ]
+        );
+        assert_eq!(plan.unresolved, [missing[2].clone()]);
+    }
+}
@@ -0,0 +1,88 @@
+//! Provenance labels for playlist entries.
+//!
+//! Local entries carry the [`ImportSource`] recorded when they were added. Remote entries
+//! need no record: their OpenSubsonic profile, or web origin, is part of the track path.
+//! The `{source}` playlist column and playlist search both use [`source_label`].
+
+use std::path::Path;
+
+use crate::integration_uri::parse_opensubsonic_track_uri;
+use crate::playlist_url::{is_web_stream_path, is_web_track_path};
+use crate::protocol::ImportSource;
+
+/// Format of the built-in "Source" playlist column.
+pub const SOURCE_COLUMN_FORMAT: &str = "{source}";
+
+fn import_source_label(source: ImportSource) -> &'static str {
+    match source {
+        ImportSource::AddFilesDialog => "Added files",
+        ImportSource::AddFolderDialog => "Added folder",
+        ImportSource::DragAndDrop => "Dropped files",
+        ImportSource::Library => "Library",
+    }
+}
+
+fn endpoint_host(endpoint: &str) -> &str {
+    let without_scheme = endpoint
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(endpoint);
+    without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or(without_scheme)
+}
+
+/// Describes where the entry at `path` came from; empty when nothing is known.
+pub fn source_label(path: &Path, recorded: Option<ImportSource>) -> String {
+    if let Some(locator) = parse_opensubsonic_track_uri(path) {
+        let host = endpoint_host(&locator.endpoint);
+        return match (locator.username.is_empty(), host.is_empty()) {
+            (false, false) => format!("OpenSubsonic ({}@{})", locator.username, host),
+            (true, false) => format!("OpenSubsonic ({host})"),
+            _ => format!("OpenSubsonic ({})", locator.profile_id),
+        };
+    }
+    if is_web_stream_path(path) {
+        return "Web stream".to_string();
+    }
+    if is_web_track_path(path) {
+        return "Web playlist".to_string();
+    }
+    recorded
+        .map(import_source_label)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_label_prefers_remote_origin_over_recorded_source() {
+        assert_eq!(
+            source_label(Path::new("/music/a.flac"), Some(ImportSource::DragAndDrop)),
+            "Dropped files"
+        );
+        assert_eq!(source_label(Path::new("/music/a.flac"), None), "");
+        assert_eq!(
+            source_label(
+                Path::new("https://example.com/live"),
+                Some(ImportSource::Library)
+            ),
+            "Web stream"
+        );
+        let remote = crate::integration_uri::encode_opensubsonic_track_uri(
+            "home",
+            "song-1",
+            "https://music.example.com:4533/",
+            "alex",
+            None,
+        );
+        assert_eq!(
+            source_label(Path::new(&remote), Some(ImportSource::Library)),
+            "OpenSubsonic (alex@music.example.com:4533)"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Learned "plays well after" scores from the user's own track-to-track transitions.
+//!
+//! Every time a track follows another, the pair gains or loses weight: picking the next
+//! track by hand and letting it play through count in favor, skipping it early counts
+//! against. Energy ramp order then prefers well-scored successors among tracks of the
+//! same energy level. Everything stays in the local database.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::protocol::{TransitionCounts, TransitionOutcome};
+
+/// Leaving a track within this time counts as a skip, even for long tracks.
+const SKIP_WINDOW_MS: u64 = 60_000;
+
+/// Learned scores keyed by previous track path, then next track path.
+pub type TransitionScores = HashMap<PathBuf, HashMap<PathBuf, i32>>;
+
+/// Score change for one outcome.
+pub fn outcome_weight(outcome: TransitionOutcome) -> i32 {
+    match outcome {
+        TransitionOutcome::Manual => 2,
+        TransitionOutcome::PlayedThrough => 1,
+        TransitionOutcome::Skipped => -2,
+    }
+}
+
+/// Total score of a pair from its stored counts.
+pub fn transition_score(counts: &TransitionCounts) -> i32 {
+    let weighted = |count: u32, outcome| count as i32 * outcome_weight(outcome);
+    weighted(counts.manual, TransitionOutcome::Manual)
+        + weighted(counts.played_through, TransitionOutcome::PlayedThrough)
+        + weighted(counts.skipped, TransitionOutcome::Skipped)
+}
+
+/// Builds the score lookup from stored pairs, leaving out pairs that net to zero.
+pub fn scores_from_counts(rows: Vec<(PathBuf, PathBuf, TransitionCounts)>) -> TransitionScores {
+    let mut scores = TransitionScores::new();
+    for (from_path, to_path, counts) in rows {
+        let score = transition_score(&counts);
+        if score != 0 {
+            scores.entry(from_path).or_default().insert(to_path, score);
+        }
+    }
+    scores
+}
+
+/// Whether leaving a track after `elapsed_ms` counts as skipping it.
+///
+/// A track is skipped when left before its halfway point, capped at one minute.
+pub fn is_early_skip(elapsed_ms: u64, duration_ms: u64) -> bool {
+    let window_ms = if duration_ms == 0 {
+        SKIP_WINDOW_MS
+    } else {
+        (duration_ms / 2).min(SKIP_WINDOW_MS)
+    };
+    elapsed_ms < window_ms
+}
+
+/// Reorders runs of interchangeable tracks so each one is followed by its best-scored
+/// successor.
+///
+/// `order[0]` stays put. From then on, the track at each position is swapped for the
+/// best positively scored successor of the previous track among the rest of its run,
+/// where a run is a stretch of consecutive tracks that are all `same_group` as its first.
+/// Without a positive score the existing order is kept.
+pub fn bias_adjacency(
+    order: &mut Vec<usize>,
+    same_group: impl Fn(usize, usize) -> bool,
+    score: impl Fn(usize, usize) -> i32,
+) {
+    for position in 1..order.len() {
+        let previous = order[position - 1];
+        let run_end = (position + 1..order.len())
+            .find(|&candidate| !same_group(order[position], order[candidate]))
+            .unwrap_or(order.len());
+        let best = (position..run_end)
+            .map(|candidate| (candidate, score(previous, order[candidate])))
+            .filter(|(_, score)| *score > 0)
+            .fold(None, |best: Option<(usize, i32)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            });
+        if let Some((best_position, _)) = best.filter(|(best, _)| *best != position) {
+            let track = order.remove(best_position);
+            order.insert(position, track);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_early_skip_uses_half_the_track_capped_at_a_minute() {
+        assert!(is_early_skip(50_000, 120_000));
+        assert!(!is_early_skip(61_000, 120_000));
+        assert!(is_early_skip(59_000, 600_000));
+        assert!(!is_early_skip(60_000, 600_000));
+        assert!(is_early_skip(10_000, 0));
+    }
+
+    #[test]
+    fn test_bias_adjacency_prefers_learned_successors_within_a_run() {
+        // Tracks 1..=3 share a group; 4 is on its own.
+        let group = |index: usize| if index == 4 { 1 } else { 0 };
+        let score = |from: usize, to: usize| match (from, to) {
+            (0, 3) => 3,
+            (3, 2) => 1,
+            (3, 4) => 5,
+            (2, 1) => -2,
+            _ => 0,
+        };
+        let mut order = vec![0, 1, 2, 3, 4];
+        bias_adjacency(&mut order, |a, b| group(a) == group(b), score);
+        // 4 is in a different run, so it never jumps ahead of 2 and 1.
+        assert_eq!(order, vec![0, 3, 2, 1, 4]);
+
+        let mut unscored = vec![0, 2, 1];
+        bias_adjacency(&mut unscored, |_, _| true, |_, _| 0);
+        assert_eq!(unscored, vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_scores_from_counts_drops_pairs_that_net_to_zero() {
+        let counts = |manual, played_through, skipped| TransitionCounts {
+            manual,
+            played_through,
+            skipped,
+        };
+        let scores = scores_from_counts(vec![
+            (PathBuf::from("/a"), PathBuf::from("/b"), counts(1, 2, 0)),
+            (PathBuf::from("/a"), PathBuf::from("/c"), counts(0, 2, 1)),
+            (PathBuf::from("/b"), PathBuf::from("/c"), counts(0, 0, 1)),
+        ]);
+        assert_eq!(scores[&PathBuf::from("/a")].len(), 1);
+        assert_eq!(scores[&PathBuf::from("/a")][&PathBuf::from("/b")], 4);
+        assert_eq!(scores[&PathBuf::from("/b")][&PathBuf::from("/c")], -2);
+    }
+}
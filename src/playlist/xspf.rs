@@ -0,0 +1,354 @@
+//! XSPF ("spiff") playlist files: writing and reading the XML Shareable Playlist Format.
+//!
+//! XSPF carries more than a plain path list: titles, creators, albums, durations, notes
+//! as annotations, and identifiers. Local files are written as `file://` locations;
+//! remote tracks keep their `rtq://` URI as both location and identifier, so another
+//! roqtune install with the same server profile resolves them again.
+
+use std::path::{Path, PathBuf};
+
+use crate::integration_uri::is_remote_track_path;
+
+const XSPF_NAMESPACE: &str = "http://xspf.org/ns/0/";
+
+/// One `<track>` of an XSPF playlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XspfTrack {
+    pub location: Option<String>,
+    pub identifiers: Vec<String>,
+    pub title: String,
+    pub creator: String,
+    pub album: String,
+    pub track_num: Option<u32>,
+    pub duration_ms: Option<u64>,
+    pub annotation: String,
+}
+
+/// A parsed or to-be-written XSPF playlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XspfPlaylist {
+    pub title: String,
+    pub annotation: String,
+    pub tracks: Vec<XspfTrack>,
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn xml_unescape(text: &str) -> String {
+    let text = text.trim();
+    if let Some(cdata) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|inner| inner.strip_suffix("]]>"))
+    {
+        return cdata.to_string();
+    }
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find(';').map(|offset| start + offset) else {
+            break;
+        };
+        let entity = &rest[start + 1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => unescaped.push(ch),
+            None => unescaped.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Contents of each `<name>...</name>` element directly found in `xml`, in order.
+/// Nested elements of the same name are not expected in XSPF.
+fn element_contents<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{name}");
+    let close = format!("</{name}>");
+    let mut contents = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after_name = &rest[start + open.len()..];
+        // `<title>` must not match `<titleExtra>`.
+        if !after_name.starts_with(['>', ' ', '\t', '\r', '\n', '/']) {
+            rest = after_name;
+            continue;
+        }
+        let Some(tag_end) = after_name.find('>') else {
+            break;
+        };
+        if after_name[..tag_end].ends_with('/') {
+            contents.push("");
+            rest = &after_name[tag_end + 1..];
+            continue;
+        }
+        let body = &after_name[tag_end + 1..];
+        let Some(body_end) = body.find(&close) else {
+            break;
+        };
+        contents.push(&body[..body_end]);
+        rest = &body[body_end + close.len()..];
+    }
+    contents
+}
+
+fn first_text(xml: &str, name: &str) -> String {
+    element_contents(xml, name)
+        .first()
+        .map(|text| xml_unescape(text))
+        .unwrap_or_default()
+}
+
+/// XSPF location for a track path: a `file://` URI for local files, the path as-is
+/// for remote and web tracks.
+pub fn location_for_path(path: &Path) -> String {
+    if is_remote_track_path(path) {
+        return path.to_string_lossy().to_string();
+    }
+    let normalized = path.to_string_lossy().replace('\\', "/");
+    let encoded: Vec<String> = normalized
+        .split('/')
+        .map(|segment| {
+            if segment.len() == 2 && segment.ends_with(':') {
+                // Keep Windows drive letters readable (`C:`).
+                segment.to_string()
+            } else {
+                urlencoding::encode(segment).to_string()
+            }
+        })
+        .collect();
+    let joined = encoded.join("/");
+    if joined.starts_with('/') {
+        format!("file://{joined}")
+    } else {
+        format!("file:///{joined}")
+    }
+}
+
+/// Track path for an XSPF location; `file://` URIs become local paths.
+pub fn path_from_location(location: &str) -> Option<PathBuf> {
+    let location = location.trim();
+    if location.is_empty() {
+        return None;
+    }
+    let Some(encoded) = location.strip_prefix("file://") else {
+        return Some(PathBuf::from(location));
+    };
+    let decoded = urlencoding::decode(encoded).ok()?.to_string();
+    // `file:///C:/Music` names `C:/Music`.
+    let bytes = decoded.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return Some(PathBuf::from(&decoded[1..]));
+    }
+    Some(PathBuf::from(decoded))
+}
+
+/// Picks the path to import for a track.
+///
+/// An existing local file wins, then a remote identifier, then whatever the location
+/// names, so a missing local file still shows up (as unavailable) in the playlist.
+pub fn resolve_track_path(
+    track: &XspfTrack,
+    local_exists: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    let location_path = track.location.as_deref().and_then(path_from_location);
+    if let Some(path) = location_path
+        .as_ref()
+        .filter(|path| is_remote_track_path(path) || local_exists(path))
+    {
+        return Some(path.clone());
+    }
+    track
+        .identifiers
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| is_remote_track_path(path))
+        .or(location_path)
+}
+
+/// Serializes a playlist as an XSPF document.
+pub fn write_xspf(playlist: &XspfPlaylist) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<playlist version=\"1\" xmlns=\"{XSPF_NAMESPACE}\">\n"
+    ));
+    let push_element = |xml: &mut String, indent: &str, name: &str, value: &str| {
+        if !value.trim().is_empty() {
+            xml.push_str(&format!("{indent}<{name}>{}</{name}>\n", xml_escape(value)));
+        }
+    };
+    push_element(&mut xml, "  ", "title", &playlist.title);
+    push_element(&mut xml, "  ", "annotation", &playlist.annotation);
+    push_element(&mut xml, "  ", "creator", "roqtune");
+    xml.push_str("  <trackList>\n");
+    for track in &playlist.tracks {
+        xml.push_str("    <track>\n");
+        if let Some(location) = track.location.as_deref() {
+            push_element(&mut xml, "      ", "location", location);
+        }
+        for identifier in &track.identifiers {
+            push_element(&mut xml, "      ", "identifier", identifier);
+        }
+        push_element(&mut xml, "      ", "title", &track.title);
+        push_element(&mut xml, "      ", "creator", &track.creator);
+        push_element(&mut xml, "      ", "album", &track.album);
+        if let Some(track_num) = track.track_num {
+            push_element(&mut xml, "      ", "trackNum", &track_num.to_string());
+        }
+        if let Some(duration_ms) = track.duration_ms.filter(|duration| *duration > 0) {
+            push_element(&mut xml, "      ", "duration", &duration_ms.to_string());
+        }
+        push_element(&mut xml, "      ", "annotation", &track.annotation);
+        xml.push_str("    </track>\n");
+    }
+    xml.push_str("  </trackList>\n</playlist>\n");
+    xml
+}
+
+/// Parses an XSPF document.
+pub fn parse_xspf(text: &str) -> Result<XspfPlaylist, String> {
+    let Some(playlist_xml) = element_contents(text, "playlist").into_iter().next() else {
+        return Err("Not an XSPF playlist: no <playlist> element".to_string());
+    };
+    let track_list = element_contents(playlist_xml, "trackList")
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    // Playlist-level title and annotation appear before the track list.
+    let header = playlist_xml
+        .find("<trackList")
+        .map_or(playlist_xml, |start| &playlist_xml[..start]);
+    let tracks = element_contents(track_list, "track")
+        .into_iter()
+        .map(|track_xml| XspfTrack {
+            location: element_contents(track_xml, "location")
+                .first()
+                .map(|location| xml_unescape(location))
+                .filter(|location| !location.is_empty()),
+            identifiers: element_contents(track_xml, "identifier")
+                .into_iter()
+                .map(xml_unescape)
+                .filter(|identifier| !identifier.is_empty())
+                .collect(),
+            title: first_text(track_xml, "title"),
+            creator: first_text(track_xml, "creator"),
+            album: first_text(track_xml, "album"),
+            track_num: first_text(track_xml, "trackNum").parse().ok(),
+            duration_ms: first_text(track_xml, "duration").parse().ok(),
+            annotation: first_text(track_xml, "annotation"),
+        })
+        .collect();
+    Ok(XspfPlaylist {
+        title: first_text(header, "title"),
+        annotation: first_text(header, "annotation"),
+        tracks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_then_parse_xspf_round_trips_metadata() {
+        let playlist = XspfPlaylist {
+            title: "Rock & Roll <Live>".to_string(),
+            annotation: "For the \"road\" trip".to_string(),
+            tracks: vec![
+                XspfTrack {
+                    location: Some(location_for_path(Path::new("/music/A B/01 #1.flac"))),
+                    identifiers: Vec::new(),
+                    title: "Intro".to_string(),
+                    creator: "Band".to_string(),
+                    album: "Live".to_string(),
+                    track_num: Some(1),
+                    duration_ms: Some(61_500),
+                    annotation: "Great opener".to_string(),
+                },
+                XspfTrack {
+                    location: Some("rtq://open_subsonic/home/s1?endpoint=x".to_string()),
+                    identifiers: vec!["rtq://open_subsonic/home/s1?endpoint=x".to_string()],
+                    ..XspfTrack::default()
+                },
+            ],
+        };
+        let xml = write_xspf(&playlist);
+        assert!(xml.contains("<location>file:///music/A%20B/01%20%231.flac</location>"));
+        assert!(xml.contains("<title>Rock &amp; Roll &lt;Live&gt;</title>"));
+        assert_eq!(parse_xspf(&xml), Ok(playlist));
+    }
+
+    #[test]
+    fn test_parse_xspf_reads_foreign_files_and_rejects_other_xml() {
+        let xml = r#"<?xml version="1.0"?>
+<playlist version="1" xmlns="http://xspf.org/ns/0/">
+  <title><![CDATA[Mixed & Matched]]></title>
+  <trackList>
+    <track><location>file:///C:/Music/a.mp3</location><title>A &#233;t&#xE9;</title></track>
+    <track><title>No location</title><image/></track>
+  </trackList>
+</playlist>"#;
+        let playlist = parse_xspf(xml).expect("parses");
+        assert_eq!(playlist.title, "Mixed & Matched");
+        assert_eq!(playlist.tracks.len(), 2);
+        assert_eq!(playlist.tracks[0].title, "A été");
+        assert_eq!(
+            path_from_location(playlist.tracks[0].location.as_deref().unwrap_or_default()),
+            Some(PathBuf::from("C:/Music/a.mp3"))
+        );
+        assert_eq!(playlist.tracks[1].location, None);
+        assert!(parse_xspf("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_resolve_track_path_prefers_existing_files_then_remote_identifiers() {
+        let remote = "rtq://open_subsonic/home/s1?endpoint=x";
+        let track = XspfTrack {
+            location: Some("file:///music/a.flac".to_string()),
+            identifiers: vec!["urn:isrc:X".to_string(), remote.to_string()],
+            ..XspfTrack::default()
+        };
+        assert_eq!(
+            resolve_track_path(&track, |_| true),
+            Some(PathBuf::from("/music/a.flac"))
+        );
+        assert_eq!(
+            resolve_track_path(&track, |_| false),
+            Some(PathBuf::from(remote))
+        );
+        let unknown = XspfTrack {
+            location: Some("file:///music/gone.flac".to_string()),
+            ..XspfTrack::default()
+        };
+        assert_eq!(
+            resolve_track_path(&unknown, |_| false),
+            Some(PathBuf::from("/music/gone.flac"))
+        );
+        assert_eq!(resolve_track_path(&XspfTrack::default(), |_| true), None);
+    }
+}
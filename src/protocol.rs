@@ -3,12 +3,18 @@
 //! This module defines all message payloads exchanged between playlist logic,
 //! decoding, playback, UI, and runtime configuration handlers.
 
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
 use crate::config::{
-    BackendProfileConfig, PlaylistColumnConfig, ResamplerQuality, UiPlaybackOrder, UiRepeatMode,
+    BackendProfileConfig, CastDeviceVolumeCap, EqDevicePresetConfig, EqPresetConfig,
+    FolderImportRuleConfig, OutputDeviceRatePolicyConfig, PlaylistColumnConfig, ResamplerQuality,
+    UiEnergyRampArc, UiPlaybackOrder, UiRepeatMode,
 };
 use crate::layout::LayoutConfig;
+use crate::playlist_column_filters::PlaylistColumnFilter;
+use crate::playlist_document::PlaylistDocumentFormat;
+use crate::spectrogram::SpectrogramImage;
 
 /// Repeat behavior applied when navigating beyond the current track.
 #[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -29,6 +35,8 @@ pub enum Message {
     Config(ConfigMessage),
     Cast(CastMessage),
     Integration(IntegrationMessage),
+    Lyrics(LyricsMessage),
+    Task(TaskMessage),
 }
 
 /// Track traversal strategy for next/previous operations.
@@ -37,6 +45,10 @@ pub enum PlaybackOrder {
     Default,
     Shuffle,
     Random,
+    /// Plays folders in random order, each folder's tracks in playlist order.
+    ShuffleFolders,
+    /// Auto-DJ order: upcoming tracks follow an energy arc, shuffled within each level.
+    EnergyRamp,
 }
 
 /// Image category used for async list-thumbnail preparation updates.
@@ -102,6 +114,12 @@ pub enum PlaylistMessage {
     #[allow(dead_code)]
     LoadTrack(PathBuf),
     DrainBulkImportQueue,
+    /// Announces `total` tracks about to be queued by the import dialog, for progress.
+    BulkImportQueued {
+        total: usize,
+    },
+    /// Periodic tick asking `PlaylistManager` to refresh due URL-imported playlists.
+    RefreshUrlPlaylists,
     #[allow(dead_code)]
     LoadTracksBatch {
         paths: Vec<PathBuf>,
@@ -167,12 +185,60 @@ pub enum PlaylistMessage {
     SetPlaylistSearchQuery(String),
     ClearPlaylistFilterView,
     CyclePlaylistSortByColumn(usize),
+    /// Adds a visible column as a further sort key of the playlist view.
+    AddPlaylistSortColumn(usize),
+    /// Asks the UI to write the sorted view order into the playlist.
+    RequestSortTracks,
+    /// Reorders every track of the editing playlist into the listed current indices as
+    /// one undoable edit.
+    SortTracks(Vec<usize>),
+    /// Opens the filter dialog for a visible column of the playlist view.
+    OpenColumnFilterDialog(usize),
+    /// Applies the filter dialog: the checked values, an operator index (0 for no
+    /// condition, otherwise one past `FILTER_OPERATORS`) and its operand.
+    ApplyColumnFilterDialog {
+        checked_values: Vec<bool>,
+        operator_index: usize,
+        operand: String,
+    },
+    /// Stores the per-column filters of a playlist; an empty list clears them.
+    SetPlaylistColumnFilters {
+        playlist_id: String,
+        filters: Vec<PlaylistColumnFilter>,
+    },
+    PlaylistColumnFiltersChanged {
+        playlist_id: String,
+        filters: Vec<PlaylistColumnFilter>,
+    },
+    /// Saves the artwork and description dialog for the sidebar playlist at `index`.
+    /// `cover_source` is an image file to use as the cover and `reset_cover` returns to
+    /// the generated collage; with neither, the current cover is kept.
+    SavePlaylistDetailsByIndex {
+        index: usize,
+        description: String,
+        cover_source: Option<PathBuf>,
+        reset_cover: bool,
+    },
+    PlaylistDetailsChanged {
+        playlist_id: String,
+        description: String,
+        cover_path: Option<PathBuf>,
+    },
+    /// 2x2 album-cover collage generated for a playlist; `None` when it has no art.
+    PlaylistCollageReady {
+        playlist_id: String,
+        collage_path: Option<PathBuf>,
+    },
     RequestApplyFilterView,
     ApplyFilterViewSnapshot(Vec<usize>),
     PlaylistViewportChanged {
         first_row: usize,
         row_count: usize,
     },
+    /// The user scrolled the playlist or library track list; pauses follow playback.
+    TrackListScrolledByUser,
+    /// Scroll the playing track into view, switching to its playlist when needed.
+    JumpToPlayingTrack,
     PlaylistViewportWidthChanged(u32),
     DeselectAll,
     SelectAll,
@@ -196,6 +262,8 @@ pub enum PlaylistMessage {
         to: usize,
     },
     PlaylistRestored(Vec<RestoredTrack>),
+    /// Active playlist rows loaded after the startup window, appended to `PlaylistRestored`.
+    PlaylistRestoreContinued(Vec<RestoredTrack>),
     TrackAdded {
         id: String,
         path: PathBuf,
@@ -208,6 +276,151 @@ pub enum PlaylistMessage {
         name: String,
     },
     RenamePlaylistByIndex(usize, String),
+    SetPlaylistNote {
+        playlist_id: String,
+        note: String,
+    },
+    PlaylistNoteChanged {
+        playlist_id: String,
+        note: String,
+    },
+    /// Save the playlist note edited in the notes panel for the playlist it shows.
+    SaveNotesPanelPlaylistNote(String),
+    /// Toggle skip-silence playback for the playlist at a sidebar index.
+    TogglePlaylistSkipSilenceByIndex(usize),
+    SetPlaylistSkipSilence {
+        playlist_id: String,
+        enabled: bool,
+    },
+    PlaylistSkipSilenceChanged {
+        playlist_id: String,
+        enabled: bool,
+    },
+    /// Toggle loudness normalization for the playlist at a sidebar index.
+    TogglePlaylistNormalizeLoudnessByIndex(usize),
+    SetPlaylistNormalizeLoudness {
+        playlist_id: String,
+        enabled: bool,
+    },
+    PlaylistNormalizeLoudnessChanged {
+        playlist_id: String,
+        enabled: bool,
+    },
+    /// Open the transition dialog for the selected playlist tracks.
+    OpenTrackTransition,
+    CloseTrackTransition,
+    /// Save the start/end trims (in seconds, as typed) from the transition dialog.
+    SaveTrackTransitionDialog {
+        trim_start_text: String,
+        trim_end_text: String,
+    },
+    /// Detect intro/outro trims for the tracks the transition dialog applies to.
+    DetectTrackTrimsInDialog,
+    SetTrackTrims {
+        paths: Vec<PathBuf>,
+        trims: TrackTrims,
+    },
+    /// Analyze files for talk-over intros/outros and store the detected trims.
+    DetectTrackTrims {
+        paths: Vec<PathBuf>,
+    },
+    TrackTrimDetectionFinished {
+        detected: usize,
+        total: usize,
+    },
+    /// Full set of per-track trims.
+    TrackTrimOverridesSnapshot(Vec<(PathBuf, TrackTrims)>),
+    /// Measure loudness and true peak of the selected playlist tracks.
+    AnalyzeSelectedTrackLoudness,
+    AnalyzeTrackLoudness {
+        paths: Vec<PathBuf>,
+    },
+    /// One finished measurement, stored by the playlist manager.
+    StoreTrackLoudness {
+        path: PathBuf,
+        loudness: TrackLoudness,
+    },
+    TrackLoudnessAnalysisFinished {
+        analyzed: usize,
+        total: usize,
+    },
+    /// Stored measurements that were loaded or changed.
+    TrackLoudnessChanged(Vec<(PathBuf, TrackLoudness)>),
+    /// Open the playlist picker that sends the selected playlist tracks elsewhere.
+    OpenSendToPlaylistDialog,
+    /// Ask for a name for a new playlist holding the selected playlist tracks.
+    OpenNewPlaylistFromSelection,
+    CloseSendToPlaylistDialog,
+    /// Send the picked tracks to the playlists checked in the picker.
+    ConfirmSendToPlaylists {
+        checked: Vec<bool>,
+    },
+    /// Send the picked tracks to the picker's recent target at this index.
+    SendToRecentPlaylist(usize),
+    /// Create a playlist with the picked tracks under this name.
+    ConfirmNewPlaylistFromSelection {
+        name: String,
+    },
+    /// Open the energy dialog for the selected playlist tracks.
+    OpenTrackEnergy,
+    CloseTrackEnergy,
+    /// Save the level picked in the energy dialog; index 0 clears it.
+    SaveTrackEnergyDialog {
+        level_index: i32,
+    },
+    /// Suggest energy levels for the tracks the energy dialog applies to.
+    SuggestTrackEnergyInDialog,
+    /// Set a manual energy level, or clear it with `None`.
+    SetTrackEnergy {
+        paths: Vec<PathBuf>,
+        energy: Option<TrackEnergy>,
+    },
+    /// Derive energy levels from BPM tags and loudness, measuring loudness when missing.
+    SuggestTrackEnergy {
+        paths: Vec<PathBuf>,
+    },
+    /// Finished suggestions, stored by the playlist manager without touching manual levels.
+    StoreSuggestedTrackEnergy {
+        suggestions: Vec<(PathBuf, TrackEnergy)>,
+    },
+    TrackEnergySuggestionFinished {
+        suggested: usize,
+        total: usize,
+    },
+    /// Full set of per-track energy levels.
+    TrackEnergySnapshot(Vec<(PathBuf, TrackEnergySetting)>),
+    /// Stored play statistics that were loaded or changed.
+    TrackPlayStatsChanged(Vec<(PathBuf, TrackPlayStats)>),
+    /// Forget every learned track-to-track transition.
+    ResetTransitionHistory,
+    /// Learned transitions were forgotten; `cleared` track pairs were removed.
+    TransitionHistoryReset {
+        cleared: usize,
+    },
+    /// Header-probed format properties of playlist tracks, for quality badges and for
+    /// planning output sample rates ahead of playback.
+    TrackFormatsProbed(Vec<(PathBuf, TechnicalMetadata)>),
+    /// Output rate chosen for the playing track under the Match Content policy.
+    OutputRateDecided(OutputRateDecision),
+    /// Append tracks to the playlist with this name, creating it when missing.
+    AddTracksToPlaylistByName {
+        name: String,
+        paths: Vec<PathBuf>,
+    },
+    /// Create a new playlist holding these tracks, numbering the name when it is taken.
+    CreatePlaylistWithTracks {
+        name: String,
+        paths: Vec<PathBuf>,
+    },
+    SetTracksExcludedFromShuffle {
+        paths: Vec<PathBuf>,
+        excluded: bool,
+    },
+    /// Play the remote copy of a local library file instead of the file itself.
+    SetPreferRemoteCopy {
+        path: PathBuf,
+        prefer_remote: bool,
+    },
     DeletePlaylist {
         id: String,
     },
@@ -221,7 +434,42 @@ pub enum PlaylistMessage {
     },
     SwitchPlaylistByIndex(usize),
     RequestPlaylistState,
-    PlaylistsRestored(Vec<PlaylistInfo>),
+    /// All playlists with the sidebar folders they are organized in.
+    PlaylistsRestored {
+        playlists: Vec<PlaylistInfo>,
+        folders: Vec<PlaylistFolderInfo>,
+    },
+    /// Sidebar tree row commands; rows are resolved to ids by the UI manager.
+    TogglePlaylistFolderByRow(usize),
+    CreatePlaylistFolderAtRow(Option<usize>),
+    RenamePlaylistFolderByRow(usize, String),
+    DeletePlaylistFolderByRow(usize),
+    /// Drag-and-drop in the sidebar: moves the row at `from` into the folder at `to`, or
+    /// next to the playlist at `to`. `to` past the last row moves it to the top level.
+    MovePlaylistTreeRow {
+        from: usize,
+        to: usize,
+    },
+    CreatePlaylistFolder {
+        name: String,
+        parent_id: Option<String>,
+    },
+    RenamePlaylistFolder {
+        id: String,
+        name: String,
+    },
+    /// Deletes a folder; its playlists and subfolders move up to its parent.
+    DeletePlaylistFolder {
+        id: String,
+    },
+    MovePlaylistToFolder {
+        playlist_id: String,
+        folder_id: Option<String>,
+    },
+    MovePlaylistFolder {
+        id: String,
+        parent_id: Option<String>,
+    },
     OpenSubsonicSyncEligiblePlaylists(Vec<String>),
     ActivePlaylistChanged(String),
     SetActivePlaylistColumnWidthOverride {
@@ -256,6 +504,26 @@ pub enum PlaylistMessage {
     ChangePlaybackOrder(PlaybackOrder),
     ToggleRepeat,
     RepeatModeChanged(RepeatMode),
+    /// Next tracks the playback queue will play, resolved through the current
+    /// playback order (shuffle included) and repeat mode.
+    UpcomingTracksChanged(Vec<UpcomingTrack>),
+    /// Jump playback to one entry of the last broadcast upcoming list.
+    /// `position` is an index into that list, not a queue index.
+    PlayUpcomingTrack(usize),
+    /// Reorder the upcoming sequence by moving the entry at upcoming-list
+    /// position `from` so it ends up at position `to`.
+    MoveUpcomingTrack {
+        from: usize,
+        to: usize,
+    },
+    /// Recorded import sources of playlist entries, keyed by track id. Receivers merge
+    /// these into what they already know.
+    TrackSourcesChanged(Vec<(String, ImportSource)>),
+    /// Copy the playback queue into a new local playlist. `shuffle_order` lists the
+    /// tracks in their resolved shuffle sequence instead of playlist order.
+    SavePlaybackQueueAsPlaylist {
+        shuffle_order: bool,
+    },
     RemoteDetachConfirmationRequested {
         playlist_id: String,
         playlist_name: String,
@@ -271,6 +539,191 @@ pub enum PlaylistMessage {
         success: bool,
         error: Option<String>,
     },
+    /// Split the playlist at this sidebar index into new playlists, one per group.
+    SplitPlaylistByIndex {
+        index: usize,
+        rule: PlaylistSplitRule,
+    },
+    /// Interleave the playlists at these sidebar indices into one new playlist.
+    MergePlaylistsByIndex {
+        indices: Vec<usize>,
+        mode: PlaylistMergeMode,
+    },
+    /// Outcome of a split or merge, shown to the user.
+    PlaylistRestructureFinished(String),
+    /// Sync state of every remote-bound playlist; replaces the previous list.
+    RemotePlaylistSyncStatusChanged(Vec<RemotePlaylistSyncStatus>),
+    /// Write back pending local edits of one remote-bound playlist, then pull its
+    /// profile's playlists from the server.
+    SyncRemotePlaylistNowByIndex(usize),
+    /// Download every track of the remote-bound playlist at this sidebar index, then
+    /// turn it into a local playlist of the downloaded files.
+    MaterializeRemotePlaylistByIndex(usize),
+    /// Write the playlist at this sidebar index to `path` as an XSPF file.
+    ExportPlaylistXspfByIndex {
+        index: usize,
+        path: PathBuf,
+    },
+    /// Create a new playlist from the XSPF file at this path.
+    ImportXspfPlaylist(PathBuf),
+    /// Outcome of an XSPF import or export, shown to the user.
+    XspfTransferFinished(String),
+    /// Write the playlist open at this sidebar index to `path` as a formatted document
+    /// with the given columns, rendered as the track list shows them.
+    ExportPlaylistDocument {
+        index: usize,
+        path: PathBuf,
+        format: PlaylistDocumentFormat,
+        columns: Vec<PlaylistColumnConfig>,
+        include_covers: bool,
+    },
+    /// Check that every local file of the playlist at this sidebar index still exists.
+    VerifyPlaylistFiles(usize),
+    /// Result of a file check; `checked_ids` are the local tracks that were looked at.
+    PlaylistFilesVerified {
+        playlist_id: String,
+        checked_ids: Vec<String>,
+        missing: Vec<RestoredTrack>,
+    },
+    /// Look up the files missing from the last check under a new base folder.
+    RelocateMissingFiles {
+        new_base: PathBuf,
+    },
+}
+
+/// Rule assigning each track of a split playlist to one new playlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistSplitRule {
+    Artist,
+    Genre,
+    Decade,
+    EveryNTracks(usize),
+}
+
+/// How tracks of merged playlists are interleaved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistMergeMode {
+    /// One track from each playlist per turn.
+    RoundRobin,
+    /// Per-playlist track counts per turn, in the order the playlists are merged.
+    Ratio(Vec<usize>),
+}
+
+/// Sync state of one remote-bound playlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemotePlaylistSyncStatus {
+    pub playlist_id: String,
+    /// Last successful pull or writeback during this session.
+    pub last_synced_unix_ms: Option<i64>,
+    /// Local edits not yet written back to the server.
+    pub local_edits_pending: bool,
+    /// Server changes held back because local edits were pending when they arrived.
+    pub remote_changes_pending: bool,
+}
+
+/// One file move planned or applied by the organize-files tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganizeFileMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Album flagged as a likely compilation missing its album-artist tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlbumArtistSuggestion {
+    pub album: String,
+    /// Distinct track artists found on the album.
+    pub artist_count: usize,
+    pub suggested_album_artist: String,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Periodic background task run by the maintenance scheduler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaintenanceTask {
+    RescanLibrary,
+    PruneOrphanedRows,
+    RefreshRemoteSync,
+    ExpireCaches,
+    VerifyIntegrity,
+}
+
+/// Problem found while verifying one library file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityIssueKind {
+    /// Decoded FLAC audio does not match the MD5 stored in its STREAMINFO block.
+    StreamChecksumMismatch,
+    /// The audio stream contains frames that fail to decode.
+    DecodeError,
+    /// File bytes changed although its size and modification time did not.
+    ContentChanged,
+    /// The file could not be opened or read.
+    Unreadable,
+    /// A lossless file whose spectrum cuts off like a lossy encode; the detail carries
+    /// the cutoff and a confidence score.
+    SuspectedLossySource,
+}
+
+/// One library file that failed its latest integrity check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityIssue {
+    pub path: PathBuf,
+    pub kind: IntegrityIssueKind,
+    pub detail: String,
+    pub verified_unix_ms: i64,
+}
+
+/// One finished maintenance task run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaintenanceRunRecord {
+    pub task: MaintenanceTask,
+    pub started_unix_ms: i64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub summary: String,
+}
+
+/// On-disk cache owned by the cache manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CacheKind {
+    CoverArt,
+    ArtistImages,
+    DecodedAudio,
+    OfflineDownloads,
+}
+
+/// Disk usage of one cache folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub kind: CacheKind,
+    pub dir: PathBuf,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    /// Configured size limit in MB; `0` means unlimited.
+    pub max_size_mb: u32,
+}
+
+/// Area of the app an activity log entry belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityCategory {
+    Library,
+    Playlist,
+    Sync,
+}
+
+/// One human-readable entry of the activity log, such as "Deleted playlist \"Road Trip\"".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityLogEntry {
+    pub recorded_unix_ms: i64,
+    pub category: ActivityCategory,
+    pub message: String,
+}
+
+/// One user-defined track tag and its `#RRGGBB` label color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserTag {
+    pub name: String,
+    pub color: String,
 }
 
 /// Library-domain commands and notifications.
@@ -308,10 +761,45 @@ pub enum LibraryMessage {
     PasteSelectionToActivePlaylist {
         selections: Vec<LibrarySelectionSpec>,
     },
+    /// Play, play next, or queue the current library selection.
+    QueueSelected(LibraryQueueAction),
+    /// Switch the selected library track between its local file and remote copy.
+    ToggleSelectedTrackSource,
+    /// Resolve library selections to tracks and hand them to the playback queue.
+    QueueSelection {
+        selections: Vec<LibrarySelectionSpec>,
+        action: LibraryQueueAction,
+    },
+    SelectionQueued {
+        action: LibraryQueueAction,
+        track_count: usize,
+    },
+    QueueSelectionFailed(String),
+    /// Build a mix of about `target_minutes` from the current library selection.
+    TimedMixSelected {
+        target_minutes: u32,
+        destination: TimedMixDestination,
+        flow: EnergyFlow,
+    },
+    /// Resolve library selections and pick tracks adding up to about `target_minutes`.
+    GenerateTimedMix {
+        selections: Vec<LibrarySelectionSpec>,
+        target_minutes: u32,
+        destination: TimedMixDestination,
+        flow: EnergyFlow,
+    },
+    TimedMixGenerated {
+        destination: TimedMixDestination,
+        track_count: usize,
+        total_ms: u64,
+    },
+    TimedMixFailed(String),
     CopySelected,
     CutSelected,
     DeleteSelected,
     OpenFileLocation,
+    CopyFilePath,
+    OpenInExternalTagger,
     ConfirmRemoveSelection,
     CancelRemoveSelection,
     EvaluateRemoveSelection {
@@ -322,8 +810,80 @@ pub enum LibraryMessage {
         selections: Vec<LibrarySelectionSpec>,
         remove_from_playlists: bool,
     },
+    /// Open the organize-files dialog for the current library selection.
+    OpenOrganizeFiles,
+    CloseOrganizeFiles,
+    /// Plan moves for the pending organize selection using `template`.
+    RequestOrganizePreview {
+        template: String,
+    },
+    PlanOrganizeFiles {
+        selections: Vec<LibrarySelectionSpec>,
+        template: String,
+    },
+    /// Apply the most recently previewed organize plan.
+    ConfirmOrganizeFiles,
+    ApplyOrganizeFiles {
+        moves: Vec<OrganizeFileMove>,
+    },
+    /// Move the files touched by the last applied organize run back.
+    UndoOrganizeFiles,
+    /// Find `missing` files under `new_base` and rewrite their stored paths.
+    RelocateTrackPaths {
+        missing: Vec<PathBuf>,
+        new_base: PathBuf,
+    },
+    /// Open the album-artist fixes dialog for the current library selection.
+    OpenAlbumArtistFixes,
+    CloseAlbumArtistFixes,
+    PlanAlbumArtistFixes {
+        selections: Vec<LibrarySelectionSpec>,
+    },
+    /// Write the previewed album-artist suggestions through the tag writer.
+    ConfirmAlbumArtistFixes,
+    /// Open the tags dialog for the selected playlist or library tracks.
+    OpenTrackTags,
+    CloseTrackTags,
+    /// Assign or clear the tag at dialog row `index` on every dialog target.
+    ToggleTrackTagInDialog(usize),
+    /// Create a tag named `name` and assign it to the dialog targets.
+    CreateTrackTagInDialog {
+        name: String,
+    },
+    /// Move the tag at dialog row `index` to the next palette color.
+    CycleTrackTagColorInDialog(usize),
+    DeleteTrackTagInDialog(usize),
+    RequestUserTagsSnapshot,
+    /// Save the track note edited in the notes panel for the track it shows.
+    SaveNotesPanelTrackNote(String),
+    RequestTrackNotesSnapshot,
+    /// Store `note` for `path`; an empty note removes it.
+    SetTrackNote {
+        path: PathBuf,
+        note: String,
+    },
+    UpsertUserTag {
+        name: String,
+        color: String,
+    },
+    DeleteUserTag {
+        name: String,
+    },
+    SetTrackUserTag {
+        paths: Vec<PathBuf>,
+        tag: String,
+        assigned: bool,
+    },
     RequestScan,
     RequestRootCounts,
+    /// Asks for facet counts under the given sidebar selection.
+    RequestFacetCounts(LibraryFacetSelection),
+    ToggleFacet {
+        kind: LibraryFacetKind,
+        value: String,
+    },
+    ClearFacets,
+    SetFacetPanelVisible(bool),
     RequestFavoritesSnapshot,
     #[allow(dead_code)]
     RequestTracks,
@@ -403,6 +963,8 @@ pub enum LibraryMessage {
         remaining: usize,
     },
     ScanFailed(String),
+    /// Library folders skipped by a scan because they could not be read, as user-facing text.
+    ScanFolderAccessWarnings(Vec<String>),
     RootCountsResult {
         tracks: usize,
         artists: usize,
@@ -411,7 +973,11 @@ pub enum LibraryMessage {
         decades: usize,
         favorites: usize,
     },
+    FacetCountsResult(Vec<LibraryFacetCount>),
     TracksResult(Vec<LibraryTrack>),
+    /// Local files that also have remote backend copies, with those copies. The copies
+    /// are folded into the local file's library row.
+    RemoteCopiesSnapshot(Vec<(PathBuf, Vec<PathBuf>)>),
     ArtistsResult(Vec<LibraryArtist>),
     AlbumsResult(Vec<LibraryAlbum>),
     GenresResult(Vec<LibraryGenre>),
@@ -451,6 +1017,14 @@ pub enum LibraryMessage {
         entity: FavoriteEntityRef,
         favorited: bool,
     },
+    /// Every defined tag plus `(track_path, tag_name)` assignments.
+    UserTagsSnapshot {
+        tags: Vec<UserTag>,
+        assignments: Vec<(PathBuf, String)>,
+    },
+    TrackNotesSnapshot {
+        notes: Vec<(PathBuf, String)>,
+    },
     EnrichmentResult(LibraryEnrichmentPayload),
     EnrichmentCacheCleared {
         cleared_rows: usize,
@@ -469,6 +1043,47 @@ pub enum LibraryMessage {
         requires_playlist_removal: bool,
     },
     RemoveSelectionFailed(String),
+    OrganizeFilesPlanned {
+        moves: Vec<OrganizeFileMove>,
+        unchanged: usize,
+        skipped: Vec<String>,
+    },
+    /// Files were moved and library/favorite paths rewritten; `undone` marks an undo run.
+    OrganizeFilesApplied {
+        moves: Vec<OrganizeFileMove>,
+        undone: bool,
+    },
+    OrganizeFilesFailed(String),
+    /// Stored paths were rewritten to the files found by a relocation.
+    TrackPathsRelocated {
+        moves: Vec<OrganizeFileMove>,
+        unresolved: Vec<PathBuf>,
+    },
+    TrackRelocationFailed(String),
+    AlbumArtistFixesPlanned(Vec<AlbumArtistSuggestion>),
+    AlbumArtistFixesFailed(String),
+    /// Runs one maintenance task immediately, independent of its schedule.
+    RunMaintenanceTask(MaintenanceTask),
+    RequestMaintenanceHistory,
+    /// Most recent maintenance runs, newest first.
+    MaintenanceHistorySnapshot(Vec<MaintenanceRunRecord>),
+    RequestIntegrityReport,
+    /// Files that failed their latest integrity check, sorted by path.
+    IntegrityReportSnapshot(Vec<IntegrityIssue>),
+    /// Appends one entry to the persisted activity log.
+    RecordActivity(ActivityLogEntry),
+    RequestActivityLog,
+    /// Most recent activity log entries, newest first.
+    ActivityLogSnapshot(Vec<ActivityLogEntry>),
+    RequestCacheUsage,
+    /// Usage of every cache in settings display order.
+    CacheUsageSnapshot(Vec<CacheUsage>),
+    /// Deletes every file of one cache.
+    ClearCache(CacheKind),
+    CacheCleared {
+        kind: CacheKind,
+        deleted_files: usize,
+    },
     ToastTimeout {
         generation: u64,
     },
@@ -538,6 +1153,11 @@ pub enum MetadataMessage {
         index: usize,
         value: String,
     },
+    /// Marks whether a field of a multi-track edit is written on save.
+    SetPropertiesFieldApply {
+        index: usize,
+        apply: bool,
+    },
     SaveProperties,
     CancelProperties,
     RequestTrackProperties {
@@ -571,6 +1191,229 @@ pub enum MetadataMessage {
         path: PathBuf,
         error: String,
     },
+    RequestTrackPropertiesBatch {
+        request_id: u64,
+        paths: Vec<PathBuf>,
+    },
+    /// Aggregated common fields of several tracks; `mixed[i]` is set where they differ.
+    TrackPropertiesBatchLoaded {
+        request_id: u64,
+        fields: Vec<MetadataEditorField>,
+        mixed: Vec<bool>,
+    },
+    TrackPropertiesBatchLoadFailed {
+        request_id: u64,
+        error: String,
+    },
+    /// Writes only `edits` to every track in `paths`.
+    SaveTrackPropertiesBatch {
+        request_id: u64,
+        paths: Vec<PathBuf>,
+        edits: Vec<MetadataEditorField>,
+    },
+    /// Result of a multi-track save; `error` describes tracks that failed, if any.
+    TrackPropertiesBatchSaved {
+        request_id: u64,
+        saved: Vec<(PathBuf, TrackMetadataSummary)>,
+        db_sync_warning: Option<String>,
+        error: Option<String>,
+    },
+    /// Looks up the single track open in Properties online and proposes tag values.
+    LookupPropertiesTags,
+    /// Identifies `path` by fingerprint or by its current `title`/`artist`.
+    LookupTrackTags {
+        request_id: u64,
+        path: PathBuf,
+        title: String,
+        artist: String,
+    },
+    TrackTagLookupCompleted {
+        request_id: u64,
+        path: PathBuf,
+        result: Result<TagLookupProposal, String>,
+    },
+    /// Renders a full-track spectrogram of the single track open in Properties.
+    RenderPropertiesSpectrogram,
+    /// Rendered spectrogram and source sample rate, or why decoding failed.
+    PropertiesSpectrogramRendered {
+        request_id: u64,
+        path: PathBuf,
+        result: Result<(SpectrogramImage, u32), String>,
+    },
+    /// Re-reads tags of files another program edited.
+    RefreshChangedTags(Vec<PathBuf>),
+    /// Tags re-read after outside edits; library index rows are already updated.
+    ChangedTagsRefreshed(Vec<(PathBuf, TrackMetadataSummary)>),
+}
+
+/// Tag values proposed by an online lookup, applied to the Properties editor unsaved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagLookupProposal {
+    /// Where the match came from, e.g. `AcoustID fingerprint, 94% match`.
+    pub source: String,
+    /// `(field id, value)` pairs such as `("common:title", "Song")`.
+    pub values: Vec<(String, String)>,
+}
+
+/// Lyrics lookup commands and results.
+#[derive(Debug, Clone)]
+pub enum LyricsMessage {
+    /// Look up lyrics for the track that started playing. Tags are only used for the
+    /// online lookup; `duration_ms` narrows it down when known.
+    RequestLyrics {
+        path: PathBuf,
+        artist: String,
+        title: String,
+        album: String,
+        duration_ms: Option<u64>,
+    },
+    /// Lyrics found for `path`, or `None` when no source has any.
+    LyricsLoaded {
+        path: PathBuf,
+        lyrics: Option<TrackLyrics>,
+    },
+}
+
+/// Where a track's lyrics came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsSource {
+    /// `.lrc` file next to the track.
+    SidecarFile,
+    /// `SYLT` or `USLT`/`LYRICS` tag of the file.
+    EmbeddedTag,
+    /// lrclib.net lookup, cached in the library database.
+    Lrclib,
+}
+
+/// One lyrics line; `start_ms` is 0 for every line of unsynced lyrics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricLine {
+    pub start_ms: u64,
+    pub text: String,
+}
+
+/// Lyrics of one track.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackLyrics {
+    pub source: LyricsSource,
+    /// Whether lines carry timestamps to follow playback with.
+    pub synced: bool,
+    pub lines: Vec<LyricLine>,
+}
+
+/// Background task registrations, panel controls, and the resulting task list.
+#[derive(Debug, Clone)]
+pub enum TaskMessage {
+    /// A subsystem registered a long-running job.
+    Started {
+        task_id: u64,
+        kind: TaskKind,
+        label: String,
+        can_pause: bool,
+        can_cancel: bool,
+    },
+    /// Work done so far; `total` is `None` while the amount of work is unknown.
+    Progress {
+        task_id: u64,
+        completed: u64,
+        total: Option<u64>,
+        detail: String,
+    },
+    Finished {
+        task_id: u64,
+        outcome: TaskOutcome,
+    },
+    /// Tasks panel asked a job to stop.
+    Cancel {
+        task_id: u64,
+    },
+    /// Tasks panel paused or resumed a job.
+    SetPaused {
+        task_id: u64,
+        paused: bool,
+    },
+    /// Drops finished tasks from the list.
+    ClearFinished,
+    /// Current task list, running tasks first.
+    TasksChanged(Vec<TaskSnapshot>),
+}
+
+/// Subsystem a background task belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Tracks added to a playlist from files or folders.
+    Import,
+    LibraryScan,
+    /// Loudness or intro/outro analysis of tracks.
+    Analysis,
+    /// Remote tracks downloaded into the offline cache.
+    Download,
+}
+
+/// How a background task ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskOutcome {
+    Succeeded,
+    Cancelled,
+    Failed(String),
+}
+
+/// Lifecycle state of a background task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskState {
+    Running,
+    Paused,
+    /// Cancel was requested and the job has not stopped yet.
+    Cancelling,
+    Finished(TaskOutcome),
+}
+
+/// One row of the Tasks panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSnapshot {
+    pub task_id: u64,
+    pub kind: TaskKind,
+    pub label: String,
+    pub state: TaskState,
+    pub completed: u64,
+    pub total: Option<u64>,
+    pub detail: String,
+    pub can_pause: bool,
+    pub can_cancel: bool,
+}
+
+/// How a library selection joins the playback queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryQueueAction {
+    /// Replace the playback queue and start playing.
+    PlayNow,
+    /// Insert after the playing track.
+    PlayNext,
+    /// Append to the end of the playback queue.
+    AddToQueue,
+}
+
+/// Where a generated timed mix goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedMixDestination {
+    /// Replace the playback queue and start playing.
+    Queue,
+    /// Save as a new playlist named after the target length.
+    Playlist,
+}
+
+/// How a generated session orders tracks by energy level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnergyFlow {
+    /// Keep the picked order.
+    #[default]
+    AsPicked,
+    /// Start calm and end intense.
+    BuildUp,
+    /// Start intense and end calm.
+    WindDown,
+    /// Build up to the most intense tracks in the middle, then wind down.
+    Peak,
 }
 
 /// Selection item used to resolve library items to concrete track paths.
@@ -583,11 +1426,15 @@ pub enum LibrarySelectionSpec {
     Decade { decade: String },
 }
 
-/// Source hint for track ingest operations.
+/// Source hint for track ingest operations, also recorded per playlist entry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImportSource {
     AddFilesDialog,
     AddFolderDialog,
+    /// Files or folders dropped onto the playlist.
+    DragAndDrop,
+    /// Tracks sent from the library view or added by library import rules.
+    Library,
 }
 
 /// Metadata patch keyed by stable track id.
@@ -606,6 +1453,17 @@ pub struct RestoredTrack {
     pub path: PathBuf,
 }
 
+/// One resolved entry of the upcoming playback sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingTrack {
+    /// Index within the playback queue (not an editing-playlist source index).
+    pub queue_index: usize,
+    /// Stable track id.
+    pub id: String,
+    /// File path on disk.
+    pub path: PathBuf,
+}
+
 /// Playback queue source used for UI synchronization and routing semantics.
 #[derive(Debug, Clone)]
 pub enum PlaybackQueueSource {
@@ -642,6 +1500,35 @@ pub struct PlaylistInfo {
     pub id: String,
     /// User-visible name.
     pub name: String,
+    /// Personal note attached to the playlist (empty when unset).
+    pub note: String,
+    /// Whether pauses are cut from tracks played from this playlist.
+    #[serde(default)]
+    pub skip_silence: bool,
+    /// Whether tracks played from this playlist are brought to a common loudness.
+    #[serde(default)]
+    pub normalize_loudness: bool,
+    /// Sidebar folder containing this playlist; `None` at the top level.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Per-column filters narrowing the playlist view.
+    #[serde(default)]
+    pub column_filters: Vec<PlaylistColumnFilter>,
+    /// Text shown with the playlist in the sidebar tooltip and playlist header.
+    #[serde(default)]
+    pub description: String,
+    /// Custom cover stored by the image pipeline; `None` uses the album-cover collage.
+    #[serde(default)]
+    pub cover_path: Option<PathBuf>,
+}
+
+/// Sidebar folder grouping playlists and other folders.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct PlaylistFolderInfo {
+    pub id: String,
+    pub name: String,
+    /// Folder containing this one; `None` at the top level.
+    pub parent_id: Option<String>,
 }
 
 /// One indexed track entry in the music library.
@@ -656,6 +1543,9 @@ pub struct LibraryTrack {
     pub genre: String,
     pub year: String,
     pub track_number: String,
+    /// Length reported by a remote backend; local rows leave it unset.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
 }
 
 /// Favorites entity kind supported by local persistence and integrations.
@@ -713,6 +1603,9 @@ pub struct LibraryAlbum {
     pub album_artist: String,
     pub track_count: u32,
     pub representative_track_path: Option<PathBuf>,
+    /// Latest release year tagged on the album's tracks; empty when untagged.
+    #[serde(default)]
+    pub year: String,
 }
 
 /// One artist aggregate entry in the indexed music library.
@@ -737,6 +1630,31 @@ pub struct LibraryDecade {
     pub track_count: u32,
 }
 
+/// Dimension of the library filter sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LibraryFacetKind {
+    Genre,
+    Decade,
+    Format,
+}
+
+/// Facet values picked in the library filter sidebar. Empty sets leave a dimension
+/// unfiltered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LibraryFacetSelection {
+    pub genres: BTreeSet<String>,
+    pub decades: BTreeSet<String>,
+    pub formats: BTreeSet<String>,
+}
+
+/// Number of tracks one facet value keeps under the other dimensions' selections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryFacetCount {
+    pub kind: LibraryFacetKind,
+    pub value: String,
+    pub track_count: u32,
+}
+
 /// Generic paged-entry payload for library pagination requests.
 #[derive(Debug, Clone)]
 pub enum LibraryEntryPayload {
@@ -795,6 +1713,34 @@ pub struct OutputPathInfo {
     pub dithered: bool,
 }
 
+/// Why local output runs at the rate chosen for the playing track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputRateReason {
+    /// Match Content is off; the rate comes from settings.
+    FixedRate,
+    /// The output device is pinned to one rate.
+    PinnedForDevice,
+    /// The output runs at the track's own rate.
+    MatchesSource,
+    /// The device does not support the track's rate.
+    NearestSupported,
+    /// The track continues an album that started at this rate.
+    AlbumRate,
+    /// A higher rate of the same family already covers the track.
+    SameFamily,
+    /// A single track is resampled instead of switching away and straight back.
+    IsolatedTrack,
+    /// The output has not switched to the track's rate yet.
+    SwitchPending,
+}
+
+/// Output rate decision for the playing track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputRateDecision {
+    pub output_rate_hz: u32,
+    pub reason: OutputRateReason,
+}
+
 /// Channel-transform strategy used when source/output channel counts differ.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChannelTransformKind {
@@ -810,6 +1756,8 @@ pub enum AudioPacket {
         play_immediately: bool,
         technical_metadata: TechnicalMetadata,
         start_offset_ms: u64,
+        /// Loudness normalization gain the output DSP chain applies to this track, in dB.
+        normalization_gain_db: f32,
     },
     Samples {
         samples: Vec<f32>,
@@ -830,17 +1778,155 @@ pub struct TrackIdentifier {
     pub play_immediately: bool,
     /// Decode start position in milliseconds.
     pub start_offset_ms: u64,
+    /// Milliseconds skipped at the start of the track when decoding from the beginning.
+    pub trim_start_ms: u64,
+    /// Milliseconds dropped from the end of the track by a per-track transition override.
+    pub trim_end_ms: u64,
+    /// Cut long pauses while decoding (playlist skip-silence mode).
+    pub skip_silence: bool,
+    /// Loudness normalization gain for the output DSP chain, in dB; `0.0` leaves it as is.
+    pub normalization_gain_db: f32,
+}
+
+/// Per-track transition override trimming audio from the start and end of a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TrackTrims {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl TrackTrims {
+    /// Returns `true` when neither end of the track is trimmed.
+    pub fn is_empty(&self) -> bool {
+        self.start_ms == 0 && self.end_ms == 0
+    }
+}
+
+/// Measured loudness of one file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackLoudness {
+    /// Gated integrated loudness (EBU R128).
+    pub integrated_lufs: f32,
+    /// Estimated inter-sample peak level.
+    pub true_peak_dbtp: f32,
+}
+
+/// Mood/energy level of one track, from calm to intense.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrackEnergy {
+    Calm,
+    Relaxed,
+    Steady,
+    Upbeat,
+    Intense,
+}
+
+impl TrackEnergy {
+    pub const ALL: [TrackEnergy; 5] = [
+        TrackEnergy::Calm,
+        TrackEnergy::Relaxed,
+        TrackEnergy::Steady,
+        TrackEnergy::Upbeat,
+        TrackEnergy::Intense,
+    ];
+
+    /// Returns the level from 1 (calm) to 5 (intense).
+    pub fn level(self) -> u8 {
+        match self {
+            TrackEnergy::Calm => 1,
+            TrackEnergy::Relaxed => 2,
+            TrackEnergy::Steady => 3,
+            TrackEnergy::Upbeat => 4,
+            TrackEnergy::Intense => 5,
+        }
+    }
+
+    /// Returns the energy for a 1-5 level.
+    pub fn from_level(level: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(level).checked_sub(1)?).copied()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TrackEnergy::Calm => "Calm",
+            TrackEnergy::Relaxed => "Relaxed",
+            TrackEnergy::Steady => "Steady",
+            TrackEnergy::Upbeat => "Upbeat",
+            TrackEnergy::Intense => "Intense",
+        }
+    }
+}
+
+/// Stored energy level of one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackEnergySetting {
+    pub energy: TrackEnergy,
+    /// True when the level was suggested from BPM/loudness rather than set by hand.
+    pub suggested: bool,
+}
+
+/// How a track went after the track played before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionOutcome {
+    /// The user picked this track to follow the previous one.
+    Manual,
+    /// The track played through, or most of the way.
+    PlayedThrough,
+    /// The track was skipped early.
+    Skipped,
+}
+
+/// Play statistics of one track.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrackPlayStats {
+    pub play_count: u32,
+    pub skip_count: u32,
+    /// When the track was last played; `None` until it is.
+    pub last_played_unix_ms: Option<i64>,
+}
+
+/// Stored outcome counts for one `previous track -> next track` pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransitionCounts {
+    pub manual: u32,
+    pub played_through: u32,
+    pub skipped: u32,
+}
+
+/// Source of a seek bar marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekMarkerKind {
+    /// Track start from an embedded or sidecar cue sheet.
+    Chapter,
+    /// End of a detected silence gap.
+    Silence,
+}
+
+/// Position on the seek bar worth jumping to in a long file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeekMarker {
+    pub position_ms: u64,
+    pub kind: SeekMarkerKind,
 }
 
 /// Audio-domain commands and notifications.
 #[derive(Debug, Clone)]
 pub enum AudioMessage {
     DecodeTracks(Vec<TrackIdentifier>),
-    RequestDecodeChunk { requested_samples: usize },
+    RequestDecodeChunk {
+        requested_samples: usize,
+    },
     StopDecoding,
     TrackCached(String, u64), // id, start_offset_ms
     TrackEvicted(String),
     AudioPacket(AudioPacket),
+    /// Decoded-audio memory governor report: bytes resident ahead of/at the playhead,
+    /// the configured ceiling, and how many tracks currently have queued audio.
+    DecodedCacheOccupancy {
+        resident_bytes: u64,
+        max_bytes: u64,
+        cached_tracks: usize,
+    },
 }
 
 /// Playback-domain commands and notifications.
@@ -850,6 +1936,18 @@ pub enum PlaybackMessage {
     Play, // resume the active playback queue
     PlayActiveCollection,
     StartQueue(PlaybackQueueRequest),
+    /// Resumes the track journaled by a session that did not shut down cleanly.
+    ResumeJournaledTrack {
+        playlist_id: String,
+        track_id: String,
+        elapsed_ms: u64,
+    },
+    /// Adds tracks to the running playback queue, after the playing track when
+    /// `play_next` is set, or starts a new queue when nothing is playing.
+    EnqueueTracks {
+        tracks: Vec<RestoredTrack>,
+        play_next: bool,
+    },
     PlayTrackById(String), // play a specific track by identifier
     Stop,
     Pause,
@@ -861,6 +1959,10 @@ pub enum PlaybackMessage {
     ClearNextTracks,
     Seek(f32),
     SetVolume(f32),
+    /// Temporary gain multiplier applied on top of the user volume (1.0 = no ducking).
+    SetDuckGain(f32),
+    /// Bypasses the output DSP chain for A/B comparison (`true`) or brings it back.
+    SetDspBypass(bool),
     TechnicalMetadataChanged(TechnicalMetadata),
     OutputPathChanged(OutputPathInfo),
     PlaybackProgress {
@@ -878,10 +1980,15 @@ pub enum PlaybackMessage {
         variant: UiImageVariant,
     },
     MetadataDisplayChanged(Option<DetailedMetadata>),
+    /// Seek bar markers for the playing track (empty for short files or no markers).
+    SeekMarkersChanged {
+        track_id: String,
+        markers: Vec<SeekMarker>,
+    },
 }
 
-/// One discoverable Google Cast target.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// One discoverable or remembered Google Cast target.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct CastDeviceInfo {
     /// Stable cast target id (UUID string from mDNS `id=` txt record when available).
     pub id: String,
@@ -895,6 +2002,16 @@ pub struct CastDeviceInfo {
     pub address: String,
     /// Cast control port (typically 8009).
     pub port: u16,
+    /// Whether the target answered the most recent discovery pass.
+    #[serde(skip)]
+    pub available: bool,
+}
+
+impl CastDeviceInfo {
+    /// Returns `true` for multi-room speaker groups.
+    pub fn is_group(&self) -> bool {
+        self.model.eq_ignore_ascii_case("Google Cast Group")
+    }
 }
 
 /// High-level cast connection state.
@@ -913,6 +2030,14 @@ pub enum CastPlaybackPathKind {
     TranscodeWavPcm,
 }
 
+/// One upcoming track mirrored into the cast receiver queue.
+#[derive(Debug, Clone)]
+pub struct CastQueueItem {
+    pub track_id: String,
+    pub path: PathBuf,
+    pub metadata_summary: Option<TrackMetadataSummary>,
+}
+
 /// Cast subsystem commands and notifications.
 #[derive(Debug, Clone)]
 pub enum CastMessage {
@@ -932,12 +2057,34 @@ pub enum CastMessage {
         path: PathBuf,
         start_offset_ms: u64,
         metadata_summary: Option<TrackMetadataSummary>,
+        /// Tracks to queue on the receiver after this one, loaded in the same request.
+        upcoming: Vec<CastQueueItem>,
+    },
+    /// Replaces the tracks queued on the receiver after the current one.
+    ///
+    /// Applied incrementally with the cast queue API so the playing item is not reloaded.
+    SyncQueue {
+        current_track_id: String,
+        upcoming: Vec<CastQueueItem>,
+    },
+    /// The receiver moved to a queued track other than the next one, e.g. through its
+    /// own previous button or another sender.
+    ReceiverJumpedToTrack {
+        track_id: String,
     },
     Play,
     Pause,
     Stop,
     SeekMs(u64),
     SetVolume(f32),
+    SetMuted(bool),
+    /// Receiver volume as reported by the device, including changes made by other senders.
+    VolumeStatusChanged {
+        level: f32,
+        muted: bool,
+        /// Volume ceiling configured for the connected device.
+        max_volume: f32,
+    },
     PlaybackPathChanged {
         kind: CastPlaybackPathKind,
         description: String,
@@ -982,7 +2129,7 @@ pub struct MetadataEditorField {
 }
 
 /// Metadata summary used to refresh playlist/library views after save.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TrackMetadataSummary {
     pub title: String,
     pub artist: String,
@@ -992,6 +2139,10 @@ pub struct TrackMetadataSummary {
     pub genre: String,
     pub year: String,
     pub track_number: String,
+    pub composer: String,
+    pub disc_number: String,
+    pub total_tracks: String,
+    pub comment: String,
 }
 
 /// Runtime configuration updates and hardware notifications.
@@ -1005,6 +2156,9 @@ pub enum ConfigDeltaEntry {
     Library(LibraryConfigDelta),
     Buffering(BufferingConfigDelta),
     Integrations(IntegrationsConfigDelta),
+    Announcements(AnnouncementConfigDelta),
+    Power(PowerConfigDelta),
+    Dsp(DspConfigDelta),
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -1017,14 +2171,21 @@ pub struct OutputConfigDelta {
     pub channel_count_auto: Option<bool>,
     pub sample_rate_auto: Option<bool>,
     pub bits_per_sample_auto: Option<bool>,
+    pub group_sample_rate_families: Option<bool>,
+    pub resample_rate_outliers: Option<bool>,
+    pub device_rate_policies: Option<Vec<OutputDeviceRatePolicyConfig>>,
     pub resampler_quality: Option<ResamplerQuality>,
     pub dither_on_bitdepth_reduce: Option<bool>,
     pub downmix_higher_channel_tracks: Option<bool>,
+    pub skip_hidden_pregap: Option<bool>,
+    pub skip_silence_spoken_word: Option<bool>,
+    pub skip_silence_min_pause_ms: Option<u32>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct CastConfigDelta {
     pub allow_transcode_fallback: Option<bool>,
+    pub device_volume_caps: Option<Vec<CastDeviceVolumeCap>>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -1041,6 +2202,11 @@ pub struct UiConfigDelta {
     pub volume: Option<f32>,
     pub playback_order: Option<UiPlaybackOrder>,
     pub repeat_mode: Option<UiRepeatMode>,
+    pub energy_ramp_arc: Option<UiEnergyRampArc>,
+    pub hi_res_min_sample_rate_hz: Option<u32>,
+    pub hi_res_min_bit_depth: Option<u16>,
+    pub zero_based_row_numbers: Option<bool>,
+    pub external_tagger_command: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -1056,6 +2222,20 @@ pub struct LibraryConfigDelta {
     pub image_memory_cache_ttl_secs: Option<u32>,
     pub artist_image_cache_ttl_days: Option<u32>,
     pub artist_image_cache_max_size_mb: Option<u32>,
+    pub cover_art_cache_dir: Option<String>,
+    pub artist_image_cache_dir: Option<String>,
+    pub decoded_audio_cache_dir: Option<String>,
+    pub decoded_audio_cache_max_size_mb: Option<u32>,
+    pub offline_cache_dir: Option<String>,
+    pub offline_cache_max_size_mb: Option<u32>,
+    pub import_rules: Option<Vec<FolderImportRuleConfig>>,
+    pub maintenance_rescan_interval_hours: Option<u32>,
+    pub maintenance_prune_interval_hours: Option<u32>,
+    pub maintenance_remote_sync_interval_hours: Option<u32>,
+    pub maintenance_cache_expiry_interval_hours: Option<u32>,
+    pub maintenance_integrity_interval_hours: Option<u32>,
+    pub watch_tag_changes: Option<bool>,
+    pub slow_storage_mode: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -1064,6 +2244,7 @@ pub struct BufferingConfigDelta {
     pub player_target_buffer_ms: Option<u32>,
     pub player_request_interval_ms: Option<u32>,
     pub decoder_request_chunk_ms: Option<u32>,
+    pub decoded_cache_max_mb: Option<u32>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -1071,16 +2252,100 @@ pub struct IntegrationsConfigDelta {
     pub backends: Option<Vec<BackendProfileConfig>>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnnouncementConfigDelta {
+    pub enabled: Option<bool>,
+    pub voice: Option<String>,
+    pub rate_wpm: Option<u32>,
+    pub duck_playback: Option<bool>,
+    pub duck_gain: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerConfigDelta {
+    pub pause_on_suspend: Option<bool>,
+    pub pause_on_lock: Option<bool>,
+    pub pause_on_screensaver: Option<bool>,
+    pub resume_on_unlock: Option<bool>,
+    pub battery_saver: Option<bool>,
+    pub pause_on_other_audio: Option<bool>,
+    pub resume_after_other_audio: Option<bool>,
+    pub other_audio_resume_window_minutes: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DspConfigDelta {
+    pub night_mode_strength_percent: Option<u32>,
+    pub normalize_loudness: Option<bool>,
+    pub preamp_db: Option<f32>,
+    pub limiter_enabled: Option<bool>,
+    pub limiter_headroom_db: Option<f32>,
+    pub eq_presets: Option<Vec<EqPresetConfig>>,
+    pub eq_device_presets: Option<Vec<EqDevicePresetConfig>>,
+}
+
 /// Runtime configuration updates and hardware notifications.
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
 pub enum ConfigMessage {
     ConfigChanged(Vec<ConfigDeltaEntry>),
-    RuntimeOutputSampleRateChanged { sample_rate_hz: u32 },
-    AudioDeviceOpened { stream_info: OutputStreamInfo },
-    SetRuntimeOutputRate { sample_rate_hz: u32, reason: String },
+    RuntimeOutputSampleRateChanged {
+        sample_rate_hz: u32,
+    },
+    AudioDeviceOpened {
+        stream_info: OutputStreamInfo,
+    },
+    SetRuntimeOutputRate {
+        sample_rate_hz: u32,
+        reason: String,
+    },
     ClearRuntimeOutputRateOverride,
-    OutputDeviceCapabilitiesChanged { verified_sample_rates: Vec<u32> },
+    OutputDeviceCapabilitiesChanged {
+        verified_sample_rates: Vec<u32>,
+    },
+    /// Re-open the current output device (for example after the system wakes from suspend).
+    ReopenAudioDevice {
+        reason: String,
+    },
+    /// The output stream lost its device, for example because a USB DAC was unplugged.
+    OutputDeviceLost {
+        device_name: String,
+    },
+    /// The configured output device that playback failed over from is connected again.
+    OutputDeviceReturned {
+        device_name: String,
+    },
+    /// Playback moved to `active_device` because `preferred_device` disappeared
+    /// (`failed_over`), or moved back to `preferred_device` after it returned.
+    OutputDeviceFailover {
+        preferred_device: String,
+        active_device: String,
+        failed_over: bool,
+    },
+    SystemSessionChanged(SystemSessionEvent),
+    /// Raw AC/battery transition reported by the power source watcher.
+    PowerSourceChanged {
+        on_battery: bool,
+    },
+    /// Battery saver became active/inactive (`battery_saver` enabled and running on battery).
+    BatterySaverChanged {
+        active: bool,
+    },
+}
+
+/// OS session/power transitions observed by the session monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemSessionEvent {
+    Suspending,
+    Resumed,
+    Locked,
+    Unlocked,
+    ScreensaverActivated,
+    ScreensaverDeactivated,
+    /// Another application started playing audio.
+    OtherAudioStarted,
+    /// No other application is playing audio anymore.
+    OtherAudioStopped,
 }
 
 /// Registered backend kind used by integration profiles and track sources.
@@ -1089,6 +2354,7 @@ pub enum ConfigMessage {
 pub enum BackendKind {
     LocalFs,
     OpenSubsonic,
+    WebDav,
 }
 
 /// High-level runtime connectivity state for one backend profile.
@@ -1154,6 +2420,7 @@ pub enum IntegrationMessage {
         status_text: Option<String>,
     },
     BackendSnapshotUpdated(BackendSnapshot),
+    /// Full remote library of one profile. WebDAV profiles report their tracks here too.
     OpenSubsonicLibraryTracksUpdated {
         profile_id: String,
         tracks: Vec<LibraryTrack>,
@@ -1208,6 +2475,35 @@ pub enum IntegrationMessage {
         action: String,
         error: String,
     },
+    /// Downloads an http(s) M3U/PLS playlist. `local_playlist_id` is `None` for a new import
+    /// and names the bound playlist for refreshes.
+    FetchPlaylistUrl {
+        local_playlist_id: Option<String>,
+        url: String,
+    },
+    PlaylistUrlFetched {
+        local_playlist_id: Option<String>,
+        url: String,
+        result: Result<RemotePlaylistSnapshot, String>,
+    },
+    /// Downloads remote tracks of a playlist into the offline track cache, in order.
+    MaterializeRemotePlaylist {
+        local_playlist_id: String,
+        playlist_name: String,
+        paths: Vec<PathBuf>,
+    },
+    RemotePlaylistMaterializeProgress {
+        local_playlist_id: String,
+        playlist_name: String,
+        completed: usize,
+        total: usize,
+    },
+    /// Outcome of a download job: each remote path paired with its local copy.
+    RemotePlaylistMaterialized {
+        local_playlist_id: String,
+        playlist_name: String,
+        result: Result<Vec<(PathBuf, PathBuf)>, String>,
+    },
 }
 
 /// Remote playlist snapshot emitted by integration sync events.
@@ -1,8 +1,9 @@
 //! Helper implementations for protocol patch types.
 
 use crate::protocol::{
-    BufferingConfigDelta, CastConfigDelta, IntegrationsConfigDelta, LibraryConfigDelta,
-    OutputConfigDelta, UiConfigDelta,
+    AnnouncementConfigDelta, BufferingConfigDelta, CastConfigDelta, DspConfigDelta,
+    IntegrationsConfigDelta, LibraryConfigDelta, OutputConfigDelta, PowerConfigDelta,
+    UiConfigDelta,
 };
 
 impl OutputConfigDelta {
@@ -15,9 +16,15 @@ impl OutputConfigDelta {
             && self.channel_count_auto.is_none()
             && self.sample_rate_auto.is_none()
             && self.bits_per_sample_auto.is_none()
+            && self.group_sample_rate_families.is_none()
+            && self.resample_rate_outliers.is_none()
+            && self.device_rate_policies.is_none()
             && self.resampler_quality.is_none()
             && self.dither_on_bitdepth_reduce.is_none()
             && self.downmix_higher_channel_tracks.is_none()
+            && self.skip_hidden_pregap.is_none()
+            && self.skip_silence_spoken_word.is_none()
+            && self.skip_silence_min_pause_ms.is_none()
     }
 
     pub fn merge_from(&mut self, newer: Self) {
@@ -45,6 +52,15 @@ impl OutputConfigDelta {
         if newer.bits_per_sample_auto.is_some() {
             self.bits_per_sample_auto = newer.bits_per_sample_auto;
         }
+        if newer.group_sample_rate_families.is_some() {
+            self.group_sample_rate_families = newer.group_sample_rate_families;
+        }
+        if newer.resample_rate_outliers.is_some() {
+            self.resample_rate_outliers = newer.resample_rate_outliers;
+        }
+        if newer.device_rate_policies.is_some() {
+            self.device_rate_policies = newer.device_rate_policies;
+        }
         if newer.resampler_quality.is_some() {
             self.resampler_quality = newer.resampler_quality;
         }
@@ -54,12 +70,21 @@ impl OutputConfigDelta {
         if newer.downmix_higher_channel_tracks.is_some() {
             self.downmix_higher_channel_tracks = newer.downmix_higher_channel_tracks;
         }
+        if newer.skip_hidden_pregap.is_some() {
+            self.skip_hidden_pregap = newer.skip_hidden_pregap;
+        }
+        if newer.skip_silence_spoken_word.is_some() {
+            self.skip_silence_spoken_word = newer.skip_silence_spoken_word;
+        }
+        if newer.skip_silence_min_pause_ms.is_some() {
+            self.skip_silence_min_pause_ms = newer.skip_silence_min_pause_ms;
+        }
     }
 }
 
 impl CastConfigDelta {
     pub fn is_empty(&self) -> bool {
-        self.allow_transcode_fallback.is_none()
+        self.allow_transcode_fallback.is_none() && self.device_volume_caps.is_none()
     }
 }
 
@@ -77,6 +102,11 @@ impl UiConfigDelta {
             && self.volume.is_none()
             && self.playback_order.is_none()
             && self.repeat_mode.is_none()
+            && self.energy_ramp_arc.is_none()
+            && self.hi_res_min_sample_rate_hz.is_none()
+            && self.hi_res_min_bit_depth.is_none()
+            && self.zero_based_row_numbers.is_none()
+            && self.external_tagger_command.is_none()
     }
 
     pub fn merge_from(&mut self, newer: Self) {
@@ -118,6 +148,21 @@ impl UiConfigDelta {
         if newer.repeat_mode.is_some() {
             self.repeat_mode = newer.repeat_mode;
         }
+        if newer.energy_ramp_arc.is_some() {
+            self.energy_ramp_arc = newer.energy_ramp_arc;
+        }
+        if newer.hi_res_min_sample_rate_hz.is_some() {
+            self.hi_res_min_sample_rate_hz = newer.hi_res_min_sample_rate_hz;
+        }
+        if newer.hi_res_min_bit_depth.is_some() {
+            self.hi_res_min_bit_depth = newer.hi_res_min_bit_depth;
+        }
+        if newer.zero_based_row_numbers.is_some() {
+            self.zero_based_row_numbers = newer.zero_based_row_numbers;
+        }
+        if newer.external_tagger_command.is_some() {
+            self.external_tagger_command = newer.external_tagger_command;
+        }
     }
 }
 
@@ -134,6 +179,20 @@ impl LibraryConfigDelta {
             && self.image_memory_cache_ttl_secs.is_none()
             && self.artist_image_cache_ttl_days.is_none()
             && self.artist_image_cache_max_size_mb.is_none()
+            && self.cover_art_cache_dir.is_none()
+            && self.artist_image_cache_dir.is_none()
+            && self.decoded_audio_cache_dir.is_none()
+            && self.decoded_audio_cache_max_size_mb.is_none()
+            && self.offline_cache_dir.is_none()
+            && self.offline_cache_max_size_mb.is_none()
+            && self.import_rules.is_none()
+            && self.maintenance_rescan_interval_hours.is_none()
+            && self.maintenance_prune_interval_hours.is_none()
+            && self.maintenance_remote_sync_interval_hours.is_none()
+            && self.maintenance_cache_expiry_interval_hours.is_none()
+            && self.maintenance_integrity_interval_hours.is_none()
+            && self.watch_tag_changes.is_none()
+            && self.slow_storage_mode.is_none()
     }
 
     pub fn merge_from(&mut self, newer: Self) {
@@ -171,6 +230,50 @@ impl LibraryConfigDelta {
         if newer.artist_image_cache_max_size_mb.is_some() {
             self.artist_image_cache_max_size_mb = newer.artist_image_cache_max_size_mb;
         }
+        if newer.cover_art_cache_dir.is_some() {
+            self.cover_art_cache_dir = newer.cover_art_cache_dir;
+        }
+        if newer.artist_image_cache_dir.is_some() {
+            self.artist_image_cache_dir = newer.artist_image_cache_dir;
+        }
+        if newer.decoded_audio_cache_dir.is_some() {
+            self.decoded_audio_cache_dir = newer.decoded_audio_cache_dir;
+        }
+        if newer.decoded_audio_cache_max_size_mb.is_some() {
+            self.decoded_audio_cache_max_size_mb = newer.decoded_audio_cache_max_size_mb;
+        }
+        if newer.offline_cache_dir.is_some() {
+            self.offline_cache_dir = newer.offline_cache_dir;
+        }
+        if newer.offline_cache_max_size_mb.is_some() {
+            self.offline_cache_max_size_mb = newer.offline_cache_max_size_mb;
+        }
+        if newer.import_rules.is_some() {
+            self.import_rules = newer.import_rules;
+        }
+        if newer.maintenance_rescan_interval_hours.is_some() {
+            self.maintenance_rescan_interval_hours = newer.maintenance_rescan_interval_hours;
+        }
+        if newer.maintenance_prune_interval_hours.is_some() {
+            self.maintenance_prune_interval_hours = newer.maintenance_prune_interval_hours;
+        }
+        if newer.maintenance_remote_sync_interval_hours.is_some() {
+            self.maintenance_remote_sync_interval_hours =
+                newer.maintenance_remote_sync_interval_hours;
+        }
+        if newer.maintenance_cache_expiry_interval_hours.is_some() {
+            self.maintenance_cache_expiry_interval_hours =
+                newer.maintenance_cache_expiry_interval_hours;
+        }
+        if newer.maintenance_integrity_interval_hours.is_some() {
+            self.maintenance_integrity_interval_hours = newer.maintenance_integrity_interval_hours;
+        }
+        if newer.watch_tag_changes.is_some() {
+            self.watch_tag_changes = newer.watch_tag_changes;
+        }
+        if newer.slow_storage_mode.is_some() {
+            self.slow_storage_mode = newer.slow_storage_mode;
+        }
     }
 }
 
@@ -180,6 +283,7 @@ impl BufferingConfigDelta {
             && self.player_target_buffer_ms.is_none()
             && self.player_request_interval_ms.is_none()
             && self.decoder_request_chunk_ms.is_none()
+            && self.decoded_cache_max_mb.is_none()
     }
 
     pub fn merge_from(&mut self, newer: Self) {
@@ -195,6 +299,9 @@ impl BufferingConfigDelta {
         if newer.decoder_request_chunk_ms.is_some() {
             self.decoder_request_chunk_ms = newer.decoder_request_chunk_ms;
         }
+        if newer.decoded_cache_max_mb.is_some() {
+            self.decoded_cache_max_mb = newer.decoded_cache_max_mb;
+        }
     }
 }
 
@@ -203,3 +310,83 @@ impl IntegrationsConfigDelta {
         self.backends.is_none()
     }
 }
+
+impl AnnouncementConfigDelta {
+    pub fn is_empty(&self) -> bool {
+        self.enabled.is_none()
+            && self.voice.is_none()
+            && self.rate_wpm.is_none()
+            && self.duck_playback.is_none()
+            && self.duck_gain.is_none()
+    }
+
+    pub fn merge_from(&mut self, newer: Self) {
+        if newer.enabled.is_some() {
+            self.enabled = newer.enabled;
+        }
+        if newer.voice.is_some() {
+            self.voice = newer.voice;
+        }
+        if newer.rate_wpm.is_some() {
+            self.rate_wpm = newer.rate_wpm;
+        }
+        if newer.duck_playback.is_some() {
+            self.duck_playback = newer.duck_playback;
+        }
+        if newer.duck_gain.is_some() {
+            self.duck_gain = newer.duck_gain;
+        }
+    }
+}
+
+impl PowerConfigDelta {
+    pub fn is_empty(&self) -> bool {
+        self.pause_on_suspend.is_none()
+            && self.pause_on_lock.is_none()
+            && self.pause_on_screensaver.is_none()
+            && self.resume_on_unlock.is_none()
+            && self.battery_saver.is_none()
+            && self.pause_on_other_audio.is_none()
+            && self.resume_after_other_audio.is_none()
+            && self.other_audio_resume_window_minutes.is_none()
+    }
+
+    pub fn merge_from(&mut self, newer: Self) {
+        if newer.pause_on_suspend.is_some() {
+            self.pause_on_suspend = newer.pause_on_suspend;
+        }
+        if newer.pause_on_lock.is_some() {
+            self.pause_on_lock = newer.pause_on_lock;
+        }
+        if newer.pause_on_screensaver.is_some() {
+            self.pause_on_screensaver = newer.pause_on_screensaver;
+        }
+        if newer.resume_on_unlock.is_some() {
+            self.resume_on_unlock = newer.resume_on_unlock;
+        }
+        if newer.battery_saver.is_some() {
+            self.battery_saver = newer.battery_saver;
+        }
+        if newer.pause_on_other_audio.is_some() {
+            self.pause_on_other_audio = newer.pause_on_other_audio;
+        }
+        if newer.resume_after_other_audio.is_some() {
+            self.resume_after_other_audio = newer.resume_after_other_audio;
+        }
+        if newer.other_audio_resume_window_minutes.is_some() {
+            self.other_audio_resume_window_minutes = newer.other_audio_resume_window_minutes;
+        }
+    }
+}
+
+impl DspConfigDelta {
+    pub fn is_empty(&self) -> bool {
+        self.night_mode_strength_percent.is_none()
+            && self.normalize_loudness.is_none()
+            && self.preamp_db.is_none()
+            && self.limiter_enabled.is_none()
+            && self.limiter_headroom_db.is_none()
+            && self.eq_presets.is_none()
+            && self.eq_device_presets.is_none()
+    }
+}
@@ -0,0 +1,361 @@
+//! Versioned external wire format for bus messages.
+//!
+//! External integrations (web remotes, scripting hooks, message recordings) exchange a
+//! stable subset of [`Message`] as JSON instead of the internal bus types, which change
+//! freely between releases. Every payload is wrapped in a [`WireEnvelope`] carrying
+//! [`PROTOCOL_VERSION`]:
+//!
+//! ```json
+//! {"version": 1, "message": {"type": "seek", "position": 0.5}}
+//! ```
+//!
+//! A release that changes the format bumps [`PROTOCOL_VERSION`], and [`decode`] keeps
+//! reading the previous version through a shim. `roqtune --batch upgrade-recording`
+//! rewrites recorded messages in the current version; [`message_schema`] describes the
+//! format as JSON Schema and is exported with `roqtune --batch export-schema <file>`.
+
+use serde_json::{json, Map, Value};
+
+use crate::protocol::{self, Message, PlaybackMessage};
+
+/// Current wire protocol version.
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Oldest version [`decode`] still accepts.
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// Versioned wrapper around one wire message.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct WireEnvelope {
+    pub version: u32,
+    pub message: WireMessage,
+}
+
+/// Bus messages exposed to external integrations.
+///
+/// Variants and fields are only ever added; renaming or removing one requires a new
+/// [`PROTOCOL_VERSION`] and a shim in [`decode`].
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WireMessage {
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Previous,
+    /// Seeks to a fraction (0.0-1.0) of the playing track.
+    Seek {
+        position: f32,
+    },
+    /// Sets the output volume (0.0-1.0).
+    SetVolume {
+        volume: f32,
+    },
+    PlayTrack {
+        track_id: String,
+    },
+    TrackStarted {
+        track_id: String,
+        start_offset_ms: u64,
+    },
+    TrackFinished {
+        track_id: String,
+    },
+    PlaybackProgress {
+        elapsed_ms: u64,
+        total_ms: u64,
+    },
+    TechnicalMetadata {
+        format: String,
+        bitrate_kbps: u32,
+        sample_rate_hz: u32,
+        channel_count: u16,
+        duration_ms: u64,
+        bits_per_sample: u16,
+    },
+}
+
+impl WireMessage {
+    /// Wire form of a bus message, or `None` when the message is internal.
+    pub fn from_bus(message: &Message) -> Option<Self> {
+        let Message::Playback(playback) = message else {
+            return None;
+        };
+        let wire = match playback {
+            PlaybackMessage::Play => Self::Play,
+            PlaybackMessage::Pause => Self::Pause,
+            PlaybackMessage::Stop => Self::Stop,
+            PlaybackMessage::Next => Self::Next,
+            PlaybackMessage::Previous => Self::Previous,
+            PlaybackMessage::Seek(position) => Self::Seek {
+                position: *position,
+            },
+            PlaybackMessage::SetVolume(volume) => Self::SetVolume { volume: *volume },
+            PlaybackMessage::PlayTrackById(track_id) => Self::PlayTrack {
+                track_id: track_id.clone(),
+            },
+            PlaybackMessage::TrackStarted(started) => Self::TrackStarted {
+                track_id: started.id.clone(),
+                start_offset_ms: started.start_offset_ms,
+            },
+            PlaybackMessage::TrackFinished(track_id) => Self::TrackFinished {
+                track_id: track_id.clone(),
+            },
+            PlaybackMessage::PlaybackProgress {
+                elapsed_ms,
+                total_ms,
+            } => Self::PlaybackProgress {
+                elapsed_ms: *elapsed_ms,
+                total_ms: *total_ms,
+            },
+            PlaybackMessage::TechnicalMetadataChanged(meta) => Self::TechnicalMetadata {
+                format: meta.format.clone(),
+                bitrate_kbps: meta.bitrate_kbps,
+                sample_rate_hz: meta.sample_rate_hz,
+                channel_count: meta.channel_count,
+                duration_ms: meta.duration_ms,
+                bits_per_sample: meta.bits_per_sample,
+            },
+            _ => return None,
+        };
+        Some(wire)
+    }
+
+    /// Bus message to publish for a wire message received from an integration.
+    pub fn into_bus(self) -> Message {
+        let playback = match self {
+            Self::Play => PlaybackMessage::Play,
+            Self::Pause => PlaybackMessage::Pause,
+            Self::Stop => PlaybackMessage::Stop,
+            Self::Next => PlaybackMessage::Next,
+            Self::Previous => PlaybackMessage::Previous,
+            Self::Seek { position } => PlaybackMessage::Seek(position.clamp(0.0, 1.0)),
+            Self::SetVolume { volume } => PlaybackMessage::SetVolume(volume.clamp(0.0, 1.0)),
+            Self::PlayTrack { track_id } => PlaybackMessage::PlayTrackById(track_id),
+            Self::TrackStarted {
+                track_id,
+                start_offset_ms,
+            } => PlaybackMessage::TrackStarted(protocol::TrackStarted {
+                id: track_id,
+                start_offset_ms,
+            }),
+            Self::TrackFinished { track_id } => PlaybackMessage::TrackFinished(track_id),
+            Self::PlaybackProgress {
+                elapsed_ms,
+                total_ms,
+            } => PlaybackMessage::PlaybackProgress {
+                elapsed_ms,
+                total_ms,
+            },
+            Self::TechnicalMetadata {
+                format,
+                bitrate_kbps,
+                sample_rate_hz,
+                channel_count,
+                duration_ms,
+                bits_per_sample,
+            } => PlaybackMessage::TechnicalMetadataChanged(protocol::TechnicalMetadata {
+                format,
+                bitrate_kbps,
+                sample_rate_hz,
+                channel_count,
+                duration_ms,
+                bits_per_sample,
+            }),
+        };
+        Message::Playback(playback)
+    }
+
+    /// One example of every variant; the schema is derived from their serde output.
+    ///
+    /// Each variant names the example after it, starting from `Play`, so adding a
+    /// variant does not compile until it is linked into the list.
+    fn schema_examples() -> Vec<Self> {
+        let mut examples = Vec::new();
+        let mut next = Some(Self::Play);
+        while let Some(example) = next {
+            next = match &example {
+                Self::Play => Some(Self::Pause),
+                Self::Pause => Some(Self::Stop),
+                Self::Stop => Some(Self::Next),
+                Self::Next => Some(Self::Previous),
+                Self::Previous => Some(Self::Seek { position: 0.5 }),
+                Self::Seek { .. } => Some(Self::SetVolume { volume: 0.5 }),
+                Self::SetVolume { .. } => Some(Self::PlayTrack {
+                    track_id: String::new(),
+                }),
+                Self::PlayTrack { .. } => Some(Self::TrackStarted {
+                    track_id: String::new(),
+                    start_offset_ms: 0,
+                }),
+                Self::TrackStarted { .. } => Some(Self::TrackFinished {
+                    track_id: String::new(),
+                }),
+                Self::TrackFinished { .. } => Some(Self::PlaybackProgress {
+                    elapsed_ms: 0,
+                    total_ms: 0,
+                }),
+                Self::PlaybackProgress { .. } => Some(Self::TechnicalMetadata {
+                    format: String::new(),
+                    bitrate_kbps: 0,
+                    sample_rate_hz: 0,
+                    channel_count: 0,
+                    duration_ms: 0,
+                    bits_per_sample: 0,
+                }),
+                Self::TechnicalMetadata { .. } => None,
+            };
+            examples.push(example);
+        }
+        examples
+    }
+}
+
+/// Serializes `message` in the current envelope.
+pub fn encode(message: &WireMessage) -> String {
+    let envelope = WireEnvelope {
+        version: PROTOCOL_VERSION,
+        message: message.clone(),
+    };
+    serde_json::to_string(&envelope).unwrap_or_default()
+}
+
+/// Parses an enveloped message of a supported version.
+pub fn decode(text: &str) -> Result<WireMessage, String> {
+    let envelope: WireEnvelope = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    if !(MIN_SUPPORTED_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&envelope.version) {
+        return Err(format!(
+            "unsupported protocol version {} (supported {}-{})",
+            envelope.version, MIN_SUPPORTED_PROTOCOL_VERSION, PROTOCOL_VERSION
+        ));
+    }
+    Ok(envelope.message)
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// JSON Schema (draft 2020-12) of [`WireEnvelope`] for the current version.
+pub fn message_schema() -> Value {
+    let variants: Vec<Value> = WireMessage::schema_examples()
+        .iter()
+        .filter_map(|example| serde_json::to_value(example).ok())
+        .filter_map(|value| match value {
+            Value::Object(fields) => Some(fields),
+            _ => None,
+        })
+        .map(|fields| {
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for (name, value) in fields {
+                let property = if name == "type" {
+                    json!({ "const": value })
+                } else {
+                    json!({ "type": json_type_name(&value) })
+                };
+                properties.insert(name.clone(), property);
+                required.push(Value::String(name));
+            }
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "roqtune wire message",
+        "type": "object",
+        "properties": {
+            "version": {
+                "type": "integer",
+                "minimum": MIN_SUPPORTED_PROTOCOL_VERSION,
+                "maximum": PROTOCOL_VERSION,
+            },
+            "message": { "oneOf": variants },
+        },
+        "required": ["version", "message"],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trips_current_envelope() {
+        let message = WireMessage::Seek { position: 0.25 };
+        let text = encode(&message);
+
+        assert_eq!(
+            text,
+            r#"{"version":1,"message":{"type":"seek","position":0.25}}"#
+        );
+        assert_eq!(decode(&text), Ok(message));
+    }
+
+    #[test]
+    fn test_decode_rejects_bare_messages_and_unsupported_versions() {
+        assert!(decode(r#"{"type":"play_track","track_id":"t1"}"#).is_err());
+        assert!(decode(r#"{"version":0,"message":{"type":"play"}}"#).is_err());
+        assert!(decode(r#"{"version":2,"message":{"type":"play"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_bus_conversion_round_trips_every_wire_message() {
+        for example in WireMessage::schema_examples() {
+            let bus = example.clone().into_bus();
+            assert_eq!(WireMessage::from_bus(&bus), Some(example));
+        }
+        assert_eq!(
+            WireMessage::from_bus(&Message::Playback(PlaybackMessage::ClearPlayerCache)),
+            None
+        );
+    }
+
+    /// Variant tags as serde knows them, read from its unknown-variant error.
+    fn serde_variant_tags() -> Vec<String> {
+        let error = serde_json::from_value::<WireMessage>(json!({ "type": "" }))
+            .expect_err("empty tag is not a variant")
+            .to_string();
+        let (_, expected) = error.split_once("expected one of ").expect("variant list");
+        expected
+            .split(", ")
+            .map(|tag| tag.trim_matches('`').to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_message_schema_lists_every_variant_with_field_types() {
+        let schema = message_schema();
+        let variants = schema["properties"]["message"]["oneOf"]
+            .as_array()
+            .expect("variants");
+
+        let tags: Vec<&str> = variants
+            .iter()
+            .filter_map(|variant| variant["properties"]["type"]["const"].as_str())
+            .collect();
+        assert_eq!(tags, serde_variant_tags());
+        let seek = variants
+            .iter()
+            .find(|variant| variant["properties"]["type"]["const"] == "seek")
+            .expect("seek variant");
+        assert_eq!(seek["properties"]["position"]["type"], "number");
+        let progress = variants
+            .iter()
+            .find(|variant| variant["properties"]["type"]["const"] == "playback_progress")
+            .expect("progress variant");
+        assert_eq!(progress["properties"]["elapsed_ms"]["type"], "integer");
+    }
+}
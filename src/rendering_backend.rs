@@ -0,0 +1,182 @@
+//! Slint rendering backend selection with software fallback.
+//!
+//! The renderer is picked once at startup from `performance.rendering_backend`, or from
+//! `SLINT_BACKEND` when set. GPU renderers fall back to the software renderer when they
+//! fail to initialize, which is common with older Linux GL drivers, and safe mode always
+//! renders in software. The outcome is kept for the settings diagnostics readout.
+
+use std::path::Path;
+
+use log::{info, warn};
+
+use crate::config::{Config, RenderingBackend};
+
+/// Slint backend that renders on the CPU and works without a GPU driver.
+pub const SOFTWARE_BACKEND_NAME: &str = "winit-software";
+/// Slint backend that renders through OpenGL.
+const GPU_BACKEND_NAME: &str = "winit-femtovg";
+/// Winit backend with Slint choosing the renderer.
+const AUTO_BACKEND_NAME: &str = "winit";
+
+/// Choices offered by the settings dialog, in display order.
+pub const RENDERING_BACKEND_CHOICES: [RenderingBackend; 3] = [
+    RenderingBackend::Auto,
+    RenderingBackend::Gpu,
+    RenderingBackend::Software,
+];
+
+/// Settings label for one rendering choice.
+pub fn rendering_backend_label(backend: RenderingBackend) -> &'static str {
+    match backend {
+        RenderingBackend::Auto => "Auto",
+        RenderingBackend::Gpu => "GPU (OpenGL)",
+        RenderingBackend::Software => "Software",
+    }
+}
+
+/// Backend the running window ended up with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderingSelection {
+    /// What was asked for, either a config choice or `SLINT_BACKEND`.
+    pub requested: String,
+    /// Slint backend name that initialized.
+    pub backend_name: String,
+    /// Why an earlier candidate was skipped, when the selection fell back.
+    pub fallback_reason: Option<String>,
+}
+
+impl RenderingSelection {
+    /// One-line summary for the diagnostics readout.
+    pub fn diagnostics_summary(&self) -> String {
+        match &self.fallback_reason {
+            Some(reason) => format!(
+                "Rendering: {} (requested {}; fell back: {reason})",
+                self.backend_name, self.requested
+            ),
+            None => format!(
+                "Rendering: {} (requested {})",
+                self.backend_name, self.requested
+            ),
+        }
+    }
+}
+
+/// Rendering backend stored in the config file, read before the rest of startup so the
+/// backend is chosen before any window exists. Unreadable files use the default.
+pub fn configured_rendering_backend(config_file: &Path) -> RenderingBackend {
+    std::fs::read_to_string(config_file)
+        .ok()
+        .and_then(|text| toml::from_str::<Config>(&text).ok())
+        .map(|config| config.performance.rendering_backend)
+        .unwrap_or_default()
+}
+
+/// Slint backend names to try in order, with a label for the request.
+pub fn backend_candidates(
+    env_backend: Option<&str>,
+    configured: RenderingBackend,
+    safe_mode: bool,
+) -> (String, Vec<&str>) {
+    if let Some(env_backend) = env_backend.filter(|name| !name.trim().is_empty()) {
+        return (
+            format!("SLINT_BACKEND={env_backend}"),
+            vec![env_backend.trim(), SOFTWARE_BACKEND_NAME],
+        );
+    }
+    if safe_mode {
+        return (
+            "software (safe mode)".to_string(),
+            vec![SOFTWARE_BACKEND_NAME],
+        );
+    }
+    let requested = rendering_backend_label(configured).to_string();
+    let candidates = match configured {
+        RenderingBackend::Auto => vec![AUTO_BACKEND_NAME, SOFTWARE_BACKEND_NAME],
+        RenderingBackend::Gpu => vec![GPU_BACKEND_NAME, SOFTWARE_BACKEND_NAME],
+        RenderingBackend::Software => vec![SOFTWARE_BACKEND_NAME],
+    };
+    (requested, candidates)
+}
+
+/// Initializes the first Slint backend that works, trying each candidate in order.
+pub fn select_backend(
+    configured: RenderingBackend,
+    safe_mode: bool,
+) -> Result<RenderingSelection, String> {
+    let env_backend = std::env::var("SLINT_BACKEND").ok();
+    let (requested, mut candidates) =
+        backend_candidates(env_backend.as_deref(), configured, safe_mode);
+    candidates.dedup();
+    let mut fallback_reason = None;
+    for backend_name in candidates {
+        match slint::BackendSelector::new()
+            .backend_name(backend_name.to_string())
+            .select()
+        {
+            Ok(()) => {
+                info!("Rendering with Slint backend {backend_name} (requested {requested})");
+                return Ok(RenderingSelection {
+                    requested,
+                    backend_name: backend_name.to_string(),
+                    fallback_reason,
+                });
+            }
+            Err(err) => {
+                warn!("Slint backend {backend_name} failed to initialize: {err}");
+                fallback_reason = Some(format!("{backend_name}: {err}"));
+            }
+        }
+    }
+    Err(format!(
+        "Failed to initialize Slint backend: {}",
+        fallback_reason.unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_candidates_fall_back_to_software() {
+        assert_eq!(
+            backend_candidates(None, RenderingBackend::Gpu, false),
+            (
+                "GPU (OpenGL)".to_string(),
+                vec![GPU_BACKEND_NAME, SOFTWARE_BACKEND_NAME]
+            )
+        );
+        assert_eq!(
+            backend_candidates(None, RenderingBackend::Software, false).1,
+            vec![SOFTWARE_BACKEND_NAME]
+        );
+        assert_eq!(
+            backend_candidates(None, RenderingBackend::Gpu, true).1,
+            vec![SOFTWARE_BACKEND_NAME]
+        );
+        assert_eq!(
+            backend_candidates(Some("qt"), RenderingBackend::Auto, true),
+            (
+                "SLINT_BACKEND=qt".to_string(),
+                vec!["qt", SOFTWARE_BACKEND_NAME]
+            )
+        );
+        assert_eq!(
+            backend_candidates(Some(" "), RenderingBackend::Auto, false).1,
+            vec![AUTO_BACKEND_NAME, SOFTWARE_BACKEND_NAME]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_summary_reports_fallback() {
+        let selection = RenderingSelection {
+            requested: "GPU (OpenGL)".to_string(),
+            backend_name: SOFTWARE_BACKEND_NAME.to_string(),
+            fallback_reason: Some("winit-femtovg: no GL context".to_string()),
+        };
+        assert_eq!(
+            selection.diagnostics_summary(),
+            "Rendering: winit-software (requested GPU (OpenGL); fell back: winit-femtovg: no GL context)"
+        );
+    }
+}
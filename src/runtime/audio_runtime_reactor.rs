@@ -20,6 +20,8 @@ use crate::{
         runtime_output_override_snapshot, update_last_runtime_config_snapshot,
         OutputRuntimeSignature, RuntimeAudioState, RuntimeOutputOverride, StagedAudioSettings,
     },
+    ui::confirmation_policy::ConfirmationRequest,
+    webdav_controller::WEBDAV_PROFILE_ID,
     AppWindow, OutputSettingsOptions,
 };
 
@@ -49,6 +51,28 @@ pub struct RuntimeEventReactorContext {
     pub last_runtime_config: Arc<Mutex<Config>>,
     /// Playback activity flag used for low-disruption apply paths.
     pub playback_session_active: Arc<AtomicBool>,
+    /// Destructive action waiting for the shared confirmation dialog.
+    pub pending_confirmation: Arc<Mutex<Option<ConfirmationRequest>>>,
+}
+
+/// Returns the settings status line of one integration profile in a backend snapshot.
+fn backend_profile_status(snapshot: &protocol::BackendSnapshot, profile_id: &str) -> String {
+    snapshot
+        .profiles
+        .iter()
+        .find(|profile| profile.profile_id == profile_id)
+        .map(|profile| {
+            profile
+                .status_text
+                .clone()
+                .unwrap_or_else(|| match profile.connection_state {
+                    protocol::BackendConnectionState::Connected => "Connected".to_string(),
+                    protocol::BackendConnectionState::Connecting => "Connecting...".to_string(),
+                    protocol::BackendConnectionState::Disconnected => "Disconnected".to_string(),
+                    protocol::BackendConnectionState::Error => "Error".to_string(),
+                })
+        })
+        .unwrap_or_else(|| "Not configured".to_string())
 }
 
 /// Spawns the runtime event reactor thread and starts processing bus messages.
@@ -66,6 +90,7 @@ pub fn spawn_runtime_event_reactor(context: RuntimeEventReactorContext) {
         last_runtime_signature,
         last_runtime_config,
         playback_session_active,
+        pending_confirmation,
     } = context;
 
     let mut device_event_receiver = bus_sender.subscribe();
@@ -278,15 +303,22 @@ pub fn spawn_runtime_event_reactor(context: RuntimeEventReactorContext) {
                 playlist_name,
             })) => {
                 let ui_weak = ui_handle.clone();
-                let message = format!(
-                    "This edit will detach '{}' from OpenSubsonic sync and keep it local-only. Continue?",
-                    playlist_name
+                let config_state = Arc::clone(&config_state);
+                let pending_confirmation = Arc::clone(&pending_confirmation);
+                let bus_sender = bus_sender_clone.clone();
+                let request = crate::app_callbacks::confirmations::detach_remote_playlist_request(
+                    playlist_id,
+                    &playlist_name,
                 );
                 let _ = slint::invoke_from_event_loop(move || {
                     if let Some(ui) = ui_weak.upgrade() {
-                        ui.set_remote_detach_target_playlist_id(playlist_id.into());
-                        ui.set_remote_detach_confirm_message(message.into());
-                        ui.set_show_remote_detach_confirm(true);
+                        crate::app_callbacks::confirmations::request_confirmation(
+                            &ui,
+                            &config_state,
+                            &pending_confirmation,
+                            &bus_sender,
+                            request,
+                        );
                     }
                 });
             }
@@ -312,30 +344,12 @@ pub fn spawn_runtime_event_reactor(context: RuntimeEventReactorContext) {
             }
             Ok(Message::Integration(IntegrationMessage::BackendSnapshotUpdated(snapshot))) => {
                 let ui_weak = ui_handle.clone();
-                let status = snapshot
-                    .profiles
-                    .iter()
-                    .find(|profile| profile.profile_id == OPENSUBSONIC_PROFILE_ID)
-                    .map(|profile| {
-                        profile.status_text.clone().unwrap_or_else(|| {
-                            match profile.connection_state {
-                                protocol::BackendConnectionState::Connected => {
-                                    "Connected".to_string()
-                                }
-                                protocol::BackendConnectionState::Connecting => {
-                                    "Connecting...".to_string()
-                                }
-                                protocol::BackendConnectionState::Disconnected => {
-                                    "Disconnected".to_string()
-                                }
-                                protocol::BackendConnectionState::Error => "Error".to_string(),
-                            }
-                        })
-                    })
-                    .unwrap_or_else(|| "Not configured".to_string());
+                let status = backend_profile_status(&snapshot, OPENSUBSONIC_PROFILE_ID);
+                let webdav_status = backend_profile_status(&snapshot, WEBDAV_PROFILE_ID);
                 let _ = slint::invoke_from_event_loop(move || {
                     if let Some(ui) = ui_weak.upgrade() {
                         ui.set_settings_subsonic_status(status.into());
+                        ui.set_settings_webdav_status(webdav_status.into());
                     }
                 });
             }
@@ -344,16 +358,26 @@ pub fn spawn_runtime_event_reactor(context: RuntimeEventReactorContext) {
                 action,
                 error,
             })) => {
-                if profile_id.as_deref() != Some(OPENSUBSONIC_PROFILE_ID) {
-                    continue;
-                }
                 let ui_weak = ui_handle.clone();
-                let status = format!("OpenSubsonic {action} failed: {error}");
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(ui) = ui_weak.upgrade() {
-                        ui.set_settings_subsonic_status(status.into());
+                match profile_id.as_deref() {
+                    Some(OPENSUBSONIC_PROFILE_ID) => {
+                        let status = format!("OpenSubsonic {action} failed: {error}");
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = ui_weak.upgrade() {
+                                ui.set_settings_subsonic_status(status.into());
+                            }
+                        });
                     }
-                });
+                    Some(WEBDAV_PROFILE_ID) => {
+                        let status = format!("WebDAV {action} failed: {error}");
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = ui_weak.upgrade() {
+                                ui.set_settings_webdav_status(status.into());
+                            }
+                        });
+                    }
+                    _ => {}
+                }
             }
             Ok(_) => {}
             Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
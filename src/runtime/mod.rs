@@ -1,3 +1,4 @@
 //! Runtime coordination modules.
 
 pub(crate) mod audio_runtime_reactor;
+pub(crate) mod task_manager;
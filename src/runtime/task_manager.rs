@@ -0,0 +1,393 @@
+//! Background task registry for long-running jobs.
+//!
+//! Subsystems register a job with [`TaskHandle::start`], report progress through the
+//! handle, and check it between units of work for pause and cancel requests. The
+//! [`TaskManager`] keeps the list shown in Tasks panels, applies the panel's pause and
+//! cancel controls, and keeps recently finished tasks, failures included, until cleared.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use log::{debug, warn};
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::protocol::{Message, TaskKind, TaskMessage, TaskOutcome, TaskSnapshot, TaskState};
+
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Finished tasks kept for the panel; the oldest are dropped first.
+const MAX_FINISHED_TASKS: usize = 20;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Pause and cancel flags shared between a running job and the task manager.
+#[derive(Debug, Default)]
+struct TaskControl {
+    cancel_requested: AtomicBool,
+    paused: AtomicBool,
+}
+
+/// Controls of running tasks, keyed by task id.
+fn task_controls() -> &'static Mutex<HashMap<u64, Arc<TaskControl>>> {
+    static TASK_CONTROLS: OnceLock<Mutex<HashMap<u64, Arc<TaskControl>>>> = OnceLock::new();
+    TASK_CONTROLS.get_or_init(Default::default)
+}
+
+fn task_control(task_id: u64) -> Option<Arc<TaskControl>> {
+    task_controls()
+        .lock()
+        .ok()
+        .and_then(|controls| controls.get(&task_id).cloned())
+}
+
+/// Panel label for a task kind.
+pub fn task_kind_label(kind: TaskKind) -> &'static str {
+    match kind {
+        TaskKind::Import => "Import",
+        TaskKind::LibraryScan => "Library scan",
+        TaskKind::Analysis => "Analysis",
+        TaskKind::Download => "Download",
+    }
+}
+
+/// A registered long-running job. Clones share the same task; exactly one of them
+/// should call [`TaskHandle::finish`].
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    task_id: u64,
+    bus_producer: Sender<Message>,
+    control: Arc<TaskControl>,
+}
+
+impl TaskHandle {
+    /// Registers a job with the task manager. `can_pause` and `can_cancel` tell the
+    /// panel which controls the job checks for.
+    pub fn start(
+        bus_producer: &Sender<Message>,
+        kind: TaskKind,
+        label: impl Into<String>,
+        can_pause: bool,
+        can_cancel: bool,
+    ) -> Self {
+        let task_id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        let control = Arc::new(TaskControl::default());
+        if let Ok(mut controls) = task_controls().lock() {
+            controls.insert(task_id, Arc::clone(&control));
+        }
+        let _ = bus_producer.send(Message::Task(TaskMessage::Started {
+            task_id,
+            kind,
+            label: label.into(),
+            can_pause,
+            can_cancel,
+        }));
+        Self {
+            task_id,
+            bus_producer: bus_producer.clone(),
+            control,
+        }
+    }
+
+    /// Reports work done so far; `total` is `None` while the amount of work is unknown.
+    pub fn progress(&self, completed: u64, total: Option<u64>, detail: impl Into<String>) {
+        let _ = self.bus_producer.send(Message::Task(TaskMessage::Progress {
+            task_id: self.task_id,
+            completed,
+            total,
+            detail: detail.into(),
+        }));
+    }
+
+    /// Whether cancellation was requested from the panel.
+    pub fn is_cancelled(&self) -> bool {
+        self.control.cancel_requested.load(Ordering::Relaxed)
+    }
+
+    /// Blocks while the task is paused. Returns `false` when the task was cancelled,
+    /// so callers can stop before the next unit of work.
+    pub fn wait_while_paused(&self) -> bool {
+        while self.control.paused.load(Ordering::Relaxed) && !self.is_cancelled() {
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+        !self.is_cancelled()
+    }
+
+    /// Unregisters the task with its outcome. A cancelled task reports `Cancelled`
+    /// whatever `result` says, since jobs usually stop with an error when cancelled.
+    pub fn finish(self, result: Result<(), String>) {
+        if let Ok(mut controls) = task_controls().lock() {
+            controls.remove(&self.task_id);
+        }
+        let outcome = match result {
+            _ if self.is_cancelled() => TaskOutcome::Cancelled,
+            Ok(()) => TaskOutcome::Succeeded,
+            Err(error) => TaskOutcome::Failed(error),
+        };
+        let _ = self.bus_producer.send(Message::Task(TaskMessage::Finished {
+            task_id: self.task_id,
+            outcome,
+        }));
+    }
+}
+
+/// Task list as shown in the panel: running tasks oldest first, then finished tasks
+/// newest first.
+#[derive(Debug, Default)]
+struct TaskTable {
+    tasks: Vec<TaskSnapshot>,
+}
+
+impl TaskTable {
+    fn task_mut(&mut self, task_id: u64) -> Option<&mut TaskSnapshot> {
+        self.tasks.iter_mut().find(|task| task.task_id == task_id)
+    }
+
+    fn snapshot(&self) -> Vec<TaskSnapshot> {
+        let (mut active, mut finished): (Vec<_>, Vec<_>) = self
+            .tasks
+            .iter()
+            .cloned()
+            .partition(|task| !matches!(task.state, TaskState::Finished(_)));
+        active.sort_by_key(|task| task.task_id);
+        finished.sort_by_key(|task| std::cmp::Reverse(task.task_id));
+        active.extend(finished);
+        active
+    }
+
+    fn trim_finished(&mut self) {
+        let mut finished_ids: Vec<u64> = self
+            .tasks
+            .iter()
+            .filter(|task| matches!(task.state, TaskState::Finished(_)))
+            .map(|task| task.task_id)
+            .collect();
+        if finished_ids.len() <= MAX_FINISHED_TASKS {
+            return;
+        }
+        finished_ids.sort_unstable();
+        let dropped = &finished_ids[..finished_ids.len() - MAX_FINISHED_TASKS];
+        self.tasks.retain(|task| !dropped.contains(&task.task_id));
+    }
+
+    /// Applies one task message; returns whether the list changed.
+    fn apply(&mut self, message: TaskMessage) -> bool {
+        match message {
+            TaskMessage::Started {
+                task_id,
+                kind,
+                label,
+                can_pause,
+                can_cancel,
+            } => {
+                self.tasks.push(TaskSnapshot {
+                    task_id,
+                    kind,
+                    label,
+                    state: TaskState::Running,
+                    completed: 0,
+                    total: None,
+                    detail: String::new(),
+                    can_pause,
+                    can_cancel,
+                });
+                true
+            }
+            TaskMessage::Progress {
+                task_id,
+                completed,
+                total,
+                detail,
+            } => {
+                let Some(task) = self.task_mut(task_id) else {
+                    return false;
+                };
+                task.completed = completed;
+                task.total = total;
+                task.detail = detail;
+                true
+            }
+            TaskMessage::Finished { task_id, outcome } => {
+                let Some(task) = self.task_mut(task_id) else {
+                    return false;
+                };
+                if outcome == TaskOutcome::Succeeded {
+                    if let Some(total) = task.total {
+                        task.completed = total;
+                    }
+                }
+                task.state = TaskState::Finished(outcome);
+                self.trim_finished();
+                true
+            }
+            TaskMessage::Cancel { task_id } => {
+                let Some(task) = self.task_mut(task_id).filter(|task| {
+                    task.can_cancel && matches!(task.state, TaskState::Running | TaskState::Paused)
+                }) else {
+                    return false;
+                };
+                task.state = TaskState::Cancelling;
+                if let Some(control) = task_control(task_id) {
+                    control.cancel_requested.store(true, Ordering::Relaxed);
+                }
+                true
+            }
+            TaskMessage::SetPaused { task_id, paused } => {
+                let Some(task) = self.task_mut(task_id).filter(|task| task.can_pause) else {
+                    return false;
+                };
+                let next_state = match (&task.state, paused) {
+                    (TaskState::Running, true) => TaskState::Paused,
+                    (TaskState::Paused, false) => TaskState::Running,
+                    _ => return false,
+                };
+                task.state = next_state;
+                if let Some(control) = task_control(task_id) {
+                    control.paused.store(paused, Ordering::Relaxed);
+                }
+                true
+            }
+            TaskMessage::ClearFinished => {
+                let before = self.tasks.len();
+                self.tasks
+                    .retain(|task| !matches!(task.state, TaskState::Finished(_)));
+                self.tasks.len() != before
+            }
+            TaskMessage::TasksChanged(_) => false,
+        }
+    }
+}
+
+/// Tracks background tasks registered by other subsystems for the Tasks panel.
+pub struct TaskManager {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    table: TaskTable,
+}
+
+impl TaskManager {
+    /// Creates a task manager bound to bus channels.
+    pub fn new(bus_consumer: Receiver<Message>, bus_producer: Sender<Message>) -> Self {
+        Self {
+            bus_consumer,
+            bus_producer,
+            table: TaskTable::default(),
+        }
+    }
+
+    /// Starts the blocking event loop for task updates and panel controls.
+    pub fn run(&mut self) {
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(Message::Task(message)) => {
+                    if let TaskMessage::Finished { task_id, outcome } = &message {
+                        debug!("TaskManager: task {task_id} finished: {outcome:?}");
+                    }
+                    if self.table.apply(message) {
+                        let _ = self
+                            .bus_producer
+                            .send(Message::Task(TaskMessage::TasksChanged(
+                                self.table.snapshot(),
+                            )));
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("TaskManager lagged on control bus, skipped {skipped} message(s)");
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started(task_id: u64) -> TaskMessage {
+        TaskMessage::Started {
+            task_id,
+            kind: TaskKind::Download,
+            label: format!("Task {task_id}"),
+            can_pause: true,
+            can_cancel: true,
+        }
+    }
+
+    #[test]
+    fn test_task_table_lists_running_tasks_before_finished_ones() {
+        let mut table = TaskTable::default();
+        for task_id in 1..=3 {
+            assert!(table.apply(started(task_id)));
+        }
+        assert!(table.apply(TaskMessage::Progress {
+            task_id: 1,
+            completed: 4,
+            total: Some(10),
+            detail: "Track 4".to_string(),
+        }));
+        assert!(table.apply(TaskMessage::Finished {
+            task_id: 1,
+            outcome: TaskOutcome::Succeeded,
+        }));
+        assert!(table.apply(TaskMessage::Finished {
+            task_id: 2,
+            outcome: TaskOutcome::Failed("Disk full".to_string()),
+        }));
+        let snapshot = table.snapshot();
+        assert_eq!(
+            snapshot.iter().map(|task| task.task_id).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(snapshot[2].completed, 10);
+        assert_eq!(
+            snapshot[1].state,
+            TaskState::Finished(TaskOutcome::Failed("Disk full".to_string()))
+        );
+        assert!(table.apply(TaskMessage::ClearFinished));
+        assert_eq!(table.snapshot().len(), 1);
+    }
+
+    #[test]
+    fn test_panel_controls_reach_the_running_job() {
+        let (bus_producer, _bus_consumer) = tokio::sync::broadcast::channel(16);
+        let task = TaskHandle::start(&bus_producer, TaskKind::Analysis, "Loudness", true, true);
+        let mut table = TaskTable::default();
+        table.apply(started(task.task_id));
+
+        assert!(table.apply(TaskMessage::SetPaused {
+            task_id: task.task_id,
+            paused: true,
+        }));
+        assert!(task.control.paused.load(Ordering::Relaxed));
+        assert!(!table.apply(TaskMessage::SetPaused {
+            task_id: task.task_id,
+            paused: true,
+        }));
+
+        assert!(table.apply(TaskMessage::Cancel {
+            task_id: task.task_id,
+        }));
+        assert!(task.is_cancelled());
+        assert!(!task.wait_while_paused());
+        let task_id = task.task_id;
+        task.finish(Err("Stopped".to_string()));
+        assert!(task_control(task_id).is_none());
+    }
+
+    #[test]
+    fn test_finished_tasks_are_capped() {
+        let mut table = TaskTable::default();
+        for task_id in 1..=(MAX_FINISHED_TASKS as u64 + 5) {
+            table.apply(started(task_id));
+            table.apply(TaskMessage::Finished {
+                task_id,
+                outcome: TaskOutcome::Succeeded,
+            });
+        }
+        let snapshot = table.snapshot();
+        assert_eq!(snapshot.len(), MAX_FINISHED_TASKS);
+        assert_eq!(snapshot.last().map(|task| task.task_id), Some(6));
+    }
+}
@@ -8,8 +8,9 @@ use tokio::sync::broadcast;
 use crate::{
     config::{CastConfig, Config, OutputConfig},
     protocol::{
-        BufferingConfigDelta, CastConfigDelta, ConfigDeltaEntry, ConfigMessage,
-        IntegrationsConfigDelta, LibraryConfigDelta, Message, OutputConfigDelta, UiConfigDelta,
+        AnnouncementConfigDelta, BufferingConfigDelta, CastConfigDelta, ConfigDeltaEntry,
+        ConfigMessage, DspConfigDelta, IntegrationsConfigDelta, LibraryConfigDelta, Message,
+        OutputConfigDelta, PowerConfigDelta, UiConfigDelta,
     },
 };
 
@@ -79,9 +80,15 @@ pub fn output_preferences_changed(previous: &OutputConfig, next: &OutputConfig)
         || previous.channel_count_auto != next.channel_count_auto
         || previous.sample_rate_auto != next.sample_rate_auto
         || previous.bits_per_sample_auto != next.bits_per_sample_auto
+        || previous.group_sample_rate_families != next.group_sample_rate_families
+        || previous.resample_rate_outliers != next.resample_rate_outliers
+        || previous.device_rate_policies != next.device_rate_policies
         || previous.resampler_quality != next.resampler_quality
         || previous.dither_on_bitdepth_reduce != next.dither_on_bitdepth_reduce
         || previous.downmix_higher_channel_tracks != next.downmix_higher_channel_tracks
+        || previous.skip_hidden_pregap != next.skip_hidden_pregap
+        || previous.skip_silence_spoken_word != next.skip_silence_spoken_word
+        || previous.skip_silence_min_pause_ms != next.skip_silence_min_pause_ms
 }
 
 /// Returns `true` when runtime-relevant audio settings changed.
@@ -118,6 +125,15 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if previous.output.bits_per_sample_auto != next.output.bits_per_sample_auto {
         output.bits_per_sample_auto = Some(next.output.bits_per_sample_auto);
     }
+    if previous.output.group_sample_rate_families != next.output.group_sample_rate_families {
+        output.group_sample_rate_families = Some(next.output.group_sample_rate_families);
+    }
+    if previous.output.resample_rate_outliers != next.output.resample_rate_outliers {
+        output.resample_rate_outliers = Some(next.output.resample_rate_outliers);
+    }
+    if previous.output.device_rate_policies != next.output.device_rate_policies {
+        output.device_rate_policies = Some(next.output.device_rate_policies.clone());
+    }
     if previous.output.resampler_quality != next.output.resampler_quality {
         output.resampler_quality = Some(next.output.resampler_quality);
     }
@@ -127,6 +143,15 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if previous.output.downmix_higher_channel_tracks != next.output.downmix_higher_channel_tracks {
         output.downmix_higher_channel_tracks = Some(next.output.downmix_higher_channel_tracks);
     }
+    if previous.output.skip_hidden_pregap != next.output.skip_hidden_pregap {
+        output.skip_hidden_pregap = Some(next.output.skip_hidden_pregap);
+    }
+    if previous.output.skip_silence_spoken_word != next.output.skip_silence_spoken_word {
+        output.skip_silence_spoken_word = Some(next.output.skip_silence_spoken_word);
+    }
+    if previous.output.skip_silence_min_pause_ms != next.output.skip_silence_min_pause_ms {
+        output.skip_silence_min_pause_ms = Some(next.output.skip_silence_min_pause_ms);
+    }
     if !output.is_empty() {
         deltas.push(ConfigDeltaEntry::Output(output));
     }
@@ -135,6 +160,9 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if previous.cast.allow_transcode_fallback != next.cast.allow_transcode_fallback {
         cast.allow_transcode_fallback = Some(next.cast.allow_transcode_fallback);
     }
+    if previous.cast.device_volume_caps != next.cast.device_volume_caps {
+        cast.device_volume_caps = Some(next.cast.device_volume_caps.clone());
+    }
     if !cast.is_empty() {
         deltas.push(ConfigDeltaEntry::Cast(cast));
     }
@@ -182,6 +210,21 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if previous.ui.repeat_mode != next.ui.repeat_mode {
         ui.repeat_mode = Some(next.ui.repeat_mode);
     }
+    if previous.ui.energy_ramp_arc != next.ui.energy_ramp_arc {
+        ui.energy_ramp_arc = Some(next.ui.energy_ramp_arc);
+    }
+    if previous.ui.hi_res_min_sample_rate_hz != next.ui.hi_res_min_sample_rate_hz {
+        ui.hi_res_min_sample_rate_hz = Some(next.ui.hi_res_min_sample_rate_hz);
+    }
+    if previous.ui.hi_res_min_bit_depth != next.ui.hi_res_min_bit_depth {
+        ui.hi_res_min_bit_depth = Some(next.ui.hi_res_min_bit_depth);
+    }
+    if previous.ui.zero_based_row_numbers != next.ui.zero_based_row_numbers {
+        ui.zero_based_row_numbers = Some(next.ui.zero_based_row_numbers);
+    }
+    if previous.ui.external_tagger_command != next.ui.external_tagger_command {
+        ui.external_tagger_command = Some(next.ui.external_tagger_command.clone());
+    }
     if !ui.is_empty() {
         deltas.push(ConfigDeltaEntry::Ui(ui));
     }
@@ -233,6 +276,66 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     {
         library.artist_image_cache_max_size_mb = Some(next.library.artist_image_cache_max_size_mb);
     }
+    if previous.library.cover_art_cache_dir != next.library.cover_art_cache_dir {
+        library.cover_art_cache_dir = Some(next.library.cover_art_cache_dir.clone());
+    }
+    if previous.library.artist_image_cache_dir != next.library.artist_image_cache_dir {
+        library.artist_image_cache_dir = Some(next.library.artist_image_cache_dir.clone());
+    }
+    if previous.library.decoded_audio_cache_dir != next.library.decoded_audio_cache_dir {
+        library.decoded_audio_cache_dir = Some(next.library.decoded_audio_cache_dir.clone());
+    }
+    if previous.library.decoded_audio_cache_max_size_mb
+        != next.library.decoded_audio_cache_max_size_mb
+    {
+        library.decoded_audio_cache_max_size_mb =
+            Some(next.library.decoded_audio_cache_max_size_mb);
+    }
+    if previous.library.offline_cache_dir != next.library.offline_cache_dir {
+        library.offline_cache_dir = Some(next.library.offline_cache_dir.clone());
+    }
+    if previous.library.offline_cache_max_size_mb != next.library.offline_cache_max_size_mb {
+        library.offline_cache_max_size_mb = Some(next.library.offline_cache_max_size_mb);
+    }
+    if previous.library.import_rules != next.library.import_rules {
+        library.import_rules = Some(next.library.import_rules.clone());
+    }
+    if previous.library.maintenance_rescan_interval_hours
+        != next.library.maintenance_rescan_interval_hours
+    {
+        library.maintenance_rescan_interval_hours =
+            Some(next.library.maintenance_rescan_interval_hours);
+    }
+    if previous.library.maintenance_prune_interval_hours
+        != next.library.maintenance_prune_interval_hours
+    {
+        library.maintenance_prune_interval_hours =
+            Some(next.library.maintenance_prune_interval_hours);
+    }
+    if previous.library.maintenance_remote_sync_interval_hours
+        != next.library.maintenance_remote_sync_interval_hours
+    {
+        library.maintenance_remote_sync_interval_hours =
+            Some(next.library.maintenance_remote_sync_interval_hours);
+    }
+    if previous.library.maintenance_cache_expiry_interval_hours
+        != next.library.maintenance_cache_expiry_interval_hours
+    {
+        library.maintenance_cache_expiry_interval_hours =
+            Some(next.library.maintenance_cache_expiry_interval_hours);
+    }
+    if previous.library.maintenance_integrity_interval_hours
+        != next.library.maintenance_integrity_interval_hours
+    {
+        library.maintenance_integrity_interval_hours =
+            Some(next.library.maintenance_integrity_interval_hours);
+    }
+    if previous.library.watch_tag_changes != next.library.watch_tag_changes {
+        library.watch_tag_changes = Some(next.library.watch_tag_changes);
+    }
+    if previous.library.slow_storage_mode != next.library.slow_storage_mode {
+        library.slow_storage_mode = Some(next.library.slow_storage_mode);
+    }
     if !library.is_empty() {
         deltas.push(ConfigDeltaEntry::Library(library));
     }
@@ -250,6 +353,9 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if previous.buffering.decoder_request_chunk_ms != next.buffering.decoder_request_chunk_ms {
         buffering.decoder_request_chunk_ms = Some(next.buffering.decoder_request_chunk_ms);
     }
+    if previous.buffering.decoded_cache_max_mb != next.buffering.decoded_cache_max_mb {
+        buffering.decoded_cache_max_mb = Some(next.buffering.decoded_cache_max_mb);
+    }
     if !buffering.is_empty() {
         deltas.push(ConfigDeltaEntry::Buffering(buffering));
     }
@@ -261,6 +367,84 @@ pub fn config_delta_entries(previous: &Config, next: &Config) -> Vec<ConfigDelta
     if !integrations.is_empty() {
         deltas.push(ConfigDeltaEntry::Integrations(integrations));
     }
+
+    let mut announcements = AnnouncementConfigDelta::default();
+    if previous.announcements.enabled != next.announcements.enabled {
+        announcements.enabled = Some(next.announcements.enabled);
+    }
+    if previous.announcements.voice != next.announcements.voice {
+        announcements.voice = Some(next.announcements.voice.clone());
+    }
+    if previous.announcements.rate_wpm != next.announcements.rate_wpm {
+        announcements.rate_wpm = Some(next.announcements.rate_wpm);
+    }
+    if previous.announcements.duck_playback != next.announcements.duck_playback {
+        announcements.duck_playback = Some(next.announcements.duck_playback);
+    }
+    if previous.announcements.duck_gain != next.announcements.duck_gain {
+        announcements.duck_gain = Some(next.announcements.duck_gain);
+    }
+    if !announcements.is_empty() {
+        deltas.push(ConfigDeltaEntry::Announcements(announcements));
+    }
+
+    let mut power = PowerConfigDelta::default();
+    if previous.power.pause_on_suspend != next.power.pause_on_suspend {
+        power.pause_on_suspend = Some(next.power.pause_on_suspend);
+    }
+    if previous.power.pause_on_lock != next.power.pause_on_lock {
+        power.pause_on_lock = Some(next.power.pause_on_lock);
+    }
+    if previous.power.pause_on_screensaver != next.power.pause_on_screensaver {
+        power.pause_on_screensaver = Some(next.power.pause_on_screensaver);
+    }
+    if previous.power.resume_on_unlock != next.power.resume_on_unlock {
+        power.resume_on_unlock = Some(next.power.resume_on_unlock);
+    }
+    if previous.power.battery_saver != next.power.battery_saver {
+        power.battery_saver = Some(next.power.battery_saver);
+    }
+    if previous.power.pause_on_other_audio != next.power.pause_on_other_audio {
+        power.pause_on_other_audio = Some(next.power.pause_on_other_audio);
+    }
+    if previous.power.resume_after_other_audio != next.power.resume_after_other_audio {
+        power.resume_after_other_audio = Some(next.power.resume_after_other_audio);
+    }
+    if previous.power.other_audio_resume_window_minutes
+        != next.power.other_audio_resume_window_minutes
+    {
+        power.other_audio_resume_window_minutes =
+            Some(next.power.other_audio_resume_window_minutes);
+    }
+    if !power.is_empty() {
+        deltas.push(ConfigDeltaEntry::Power(power));
+    }
+
+    let mut dsp = DspConfigDelta::default();
+    if previous.dsp.night_mode_strength_percent != next.dsp.night_mode_strength_percent {
+        dsp.night_mode_strength_percent = Some(next.dsp.night_mode_strength_percent);
+    }
+    if previous.dsp.normalize_loudness != next.dsp.normalize_loudness {
+        dsp.normalize_loudness = Some(next.dsp.normalize_loudness);
+    }
+    if previous.dsp.preamp_db != next.dsp.preamp_db {
+        dsp.preamp_db = Some(next.dsp.preamp_db);
+    }
+    if previous.dsp.limiter_enabled != next.dsp.limiter_enabled {
+        dsp.limiter_enabled = Some(next.dsp.limiter_enabled);
+    }
+    if previous.dsp.limiter_headroom_db != next.dsp.limiter_headroom_db {
+        dsp.limiter_headroom_db = Some(next.dsp.limiter_headroom_db);
+    }
+    if previous.dsp.eq_presets != next.dsp.eq_presets {
+        dsp.eq_presets = Some(next.dsp.eq_presets.clone());
+    }
+    if previous.dsp.eq_device_presets != next.dsp.eq_device_presets {
+        dsp.eq_device_presets = Some(next.dsp.eq_device_presets.clone());
+    }
+    if !dsp.is_empty() {
+        deltas.push(ConfigDeltaEntry::Dsp(dsp));
+    }
     deltas
 }
 
@@ -308,6 +492,10 @@ pub fn config_diff_is_runtime_sample_rate_only(previous: &Config, next: &Config)
         || previous.library != next.library
         || previous.buffering != next.buffering
         || previous.integrations != next.integrations
+        || previous.announcements != next.announcements
+        || previous.power != next.power
+        || previous.performance != next.performance
+        || previous.dsp != next.dsp
     {
         return false;
     }
@@ -380,4 +568,20 @@ mod tests {
             "expected library delta with image_memory_cache_ttl_secs"
         );
     }
+
+    #[test]
+    fn test_config_delta_entries_include_night_mode_strength() {
+        let previous = Config::default();
+        let mut next = previous.clone();
+        next.dsp.night_mode_strength_percent = 50;
+
+        let deltas = config_delta_entries(&previous, &next);
+        assert!(
+            deltas.iter().any(|delta| matches!(
+                delta,
+                ConfigDeltaEntry::Dsp(dsp) if dsp.night_mode_strength_percent == Some(50)
+            )),
+            "expected dsp delta with night_mode_strength_percent"
+        );
+    }
 }
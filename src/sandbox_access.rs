@@ -0,0 +1,298 @@
+//! Sandbox-aware access to user-chosen files and folders.
+//!
+//! Under Flatpak or Snap, folders picked through the desktop file chooser come from
+//! the XDG document portal (`/run/user/<uid>/doc/<id>/...`) or are reachable only
+//! because of a granted plug. Those grants outlive the session, so picked paths are
+//! stored as-is in config, but the user can revoke them at any time.
+//!
+//! Decoding, tag and artwork reads, and the library and tag-change watchers open files
+//! through [`open_file`], [`read_file`], [`read_dir`] and [`modified_time`], which log a
+//! revoked grant once per folder instead of letting it look like a deleted file. Scans
+//! check access with [`check_folder_access`] before walking a folder. The portal
+//! folders among the library folders are saved in `portal_grants.json`, so grants
+//! revoked while roqtune was closed are reported at the next start.
+
+use std::collections::HashSet;
+use std::fs::{self, File, ReadDir};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use log::warn;
+
+const PORTAL_GRANTS_FILE_NAME: &str = "portal_grants.json";
+
+/// Packaging sandbox the process runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxKind {
+    Unsandboxed,
+    Flatpak,
+    Snap,
+}
+
+/// Result of probing a user-chosen folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FolderAccess {
+    Available,
+    /// The folder is gone; its files really were removed or moved.
+    Missing,
+    /// The folder may still exist but the sandbox or filesystem refuses access.
+    Denied,
+}
+
+fn detect_sandbox_kind(
+    flatpak_id: Option<String>,
+    flatpak_info_exists: bool,
+    snap: Option<String>,
+) -> SandboxKind {
+    if flatpak_id.is_some_and(|id| !id.is_empty()) || flatpak_info_exists {
+        SandboxKind::Flatpak
+    } else if snap.is_some_and(|snap| !snap.is_empty()) {
+        SandboxKind::Snap
+    } else {
+        SandboxKind::Unsandboxed
+    }
+}
+
+/// Sandbox of the current process, detected once.
+pub fn sandbox_kind() -> SandboxKind {
+    static KIND: OnceLock<SandboxKind> = OnceLock::new();
+    *KIND.get_or_init(|| {
+        detect_sandbox_kind(
+            std::env::var("FLATPAK_ID").ok(),
+            Path::new("/.flatpak-info").exists(),
+            std::env::var("SNAP").ok(),
+        )
+    })
+}
+
+/// Whether `path` was exported to the app by the XDG document portal.
+pub fn is_portal_document_path(path: &Path) -> bool {
+    if path.starts_with("/run/flatpak/doc") {
+        return true;
+    }
+    let prefix: Vec<Component> = path.components().take(5).collect();
+    matches!(
+        prefix.as_slice(),
+        [
+            Component::RootDir,
+            Component::Normal(run),
+            Component::Normal(user),
+            Component::Normal(_),
+            Component::Normal(doc),
+        ] if *run == "run" && *user == "user" && *doc == "doc"
+    )
+}
+
+fn classify_access_error(path: &Path, kind: ErrorKind) -> FolderAccess {
+    match kind {
+        ErrorKind::PermissionDenied => FolderAccess::Denied,
+        // A revoked portal grant removes the document entry instead of failing with EACCES.
+        ErrorKind::NotFound if is_portal_document_path(path) => FolderAccess::Denied,
+        ErrorKind::NotFound => FolderAccess::Missing,
+        _ => FolderAccess::Denied,
+    }
+}
+
+/// Probes whether `folder` can be listed.
+pub fn check_folder_access(folder: &Path) -> FolderAccess {
+    match std::fs::read_dir(folder) {
+        Ok(_) => FolderAccess::Available,
+        Err(err) => classify_access_error(folder, err.kind()),
+    }
+}
+
+/// Logs a denied read once per folder. Missing files are left to the caller.
+fn report_access_error(path: &Path, err: &io::Error) {
+    if classify_access_error(path, err.kind()) != FolderAccess::Denied {
+        return;
+    }
+    static REPORTED_FOLDERS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    let folder = path.parent().unwrap_or(path);
+    let newly_reported = REPORTED_FOLDERS
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .expect("sandbox access report lock poisoned")
+        .insert(folder.to_path_buf());
+    if newly_reported {
+        if let Some(message) = access_message(folder, FolderAccess::Denied) {
+            warn!("{} ({})", message, err);
+        }
+    }
+}
+
+/// Opens `path` for reading.
+pub fn open_file(path: &Path) -> io::Result<File> {
+    File::open(path).inspect_err(|err| report_access_error(path, err))
+}
+
+/// Reads the whole of `path`.
+pub fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    open_file(path)?
+        .read_to_end(&mut bytes)
+        .inspect_err(|err| report_access_error(path, err))?;
+    Ok(bytes)
+}
+
+/// Lists the entries of `folder`.
+pub fn read_dir(folder: &Path) -> io::Result<ReadDir> {
+    fs::read_dir(folder).inspect_err(|err| report_access_error(folder, err))
+}
+
+/// Modification time of `path`, for watchers polling for changes.
+pub fn modified_time(path: &Path) -> io::Result<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .inspect_err(|err| report_access_error(path, err))
+}
+
+fn portal_grants_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|path| path.join("roqtune").join(PORTAL_GRANTS_FILE_NAME))
+}
+
+fn portal_grants_from_folders(folders: &[PathBuf]) -> Vec<PathBuf> {
+    let mut grants: Vec<PathBuf> = folders
+        .iter()
+        .filter(|folder| is_portal_document_path(folder))
+        .cloned()
+        .collect();
+    grants.sort();
+    grants.dedup();
+    grants
+}
+
+/// Saves the portal document folders among `folders`, replacing the saved list.
+pub fn save_portal_grants(folders: &[PathBuf]) {
+    let Some(path) = portal_grants_path() else {
+        return;
+    };
+    let grants = portal_grants_from_folders(folders);
+    if grants.is_empty() && !path.exists() {
+        return;
+    }
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| {
+            let text = serde_json::to_string_pretty(&grants).map_err(io::Error::other)?;
+            fs::write(&path, text)
+        });
+    if let Err(err) = result {
+        warn!(
+            "Failed to save portal grants to {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+/// Portal document folders saved by [`save_portal_grants`].
+pub fn load_portal_grants() -> Vec<PathBuf> {
+    portal_grants_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Saved portal folders the app can no longer read.
+pub fn revoked_portal_grants() -> Vec<PathBuf> {
+    load_portal_grants()
+        .into_iter()
+        .filter(|folder| check_folder_access(folder) == FolderAccess::Denied)
+        .collect()
+}
+
+/// User-facing explanation for a folder that could not be read, if any.
+pub fn access_message(folder: &Path, access: FolderAccess) -> Option<String> {
+    let folder = folder.display();
+    match (access, sandbox_kind()) {
+        (FolderAccess::Available, _) => None,
+        (FolderAccess::Missing, _) => Some(format!("Folder not found: {}", folder)),
+        (FolderAccess::Denied, SandboxKind::Flatpak) => Some(format!(
+            "Access to {} was revoked. Add the folder again to grant access.",
+            folder
+        )),
+        (FolderAccess::Denied, SandboxKind::Snap) => Some(format!(
+            "Cannot read {}. Check the snap's removable-media/home permissions.",
+            folder
+        )),
+        (FolderAccess::Denied, SandboxKind::Unsandboxed) => {
+            Some(format!("Permission denied: {}", folder))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revoked_portal_documents_are_denied_not_missing() {
+        assert!(is_portal_document_path(Path::new(
+            "/run/user/1000/doc/3f2a1b/Music"
+        )));
+        assert!(!is_portal_document_path(Path::new("/run/user/1000/music")));
+        assert_eq!(
+            classify_access_error(
+                Path::new("/run/user/1000/doc/3f2a1b/Music"),
+                ErrorKind::NotFound
+            ),
+            FolderAccess::Denied
+        );
+        assert_eq!(
+            classify_access_error(Path::new("/home/alex/Music"), ErrorKind::NotFound),
+            FolderAccess::Missing
+        );
+        assert_eq!(
+            classify_access_error(Path::new("/home/alex/Music"), ErrorKind::PermissionDenied),
+            FolderAccess::Denied
+        );
+    }
+
+    #[test]
+    fn test_portal_grants_keep_only_portal_folders() {
+        let folders = vec![
+            PathBuf::from("/run/user/1000/doc/3f2a1b/Music"),
+            PathBuf::from("/home/alex/Music"),
+            PathBuf::from("/run/user/1000/doc/3f2a1b/Music"),
+            PathBuf::from("/run/flatpak/doc/9c1e/Podcasts"),
+        ];
+        assert_eq!(
+            portal_grants_from_folders(&folders),
+            vec![
+                PathBuf::from("/run/flatpak/doc/9c1e/Podcasts"),
+                PathBuf::from("/run/user/1000/doc/3f2a1b/Music"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_file_reports_missing_files_as_not_found() {
+        let path = std::env::temp_dir().join("roqtune-sandbox-access-missing.flac");
+        let err = open_file(&path).expect_err("missing file");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(read_dir(&path).is_err());
+    }
+
+    #[test]
+    fn test_detect_sandbox_kind_prefers_flatpak_markers() {
+        assert_eq!(
+            detect_sandbox_kind(Some("io.github.roqtune".to_string()), false, None),
+            SandboxKind::Flatpak
+        );
+        assert_eq!(
+            detect_sandbox_kind(None, true, Some("/snap/roqtune/1".to_string())),
+            SandboxKind::Flatpak
+        );
+        assert_eq!(
+            detect_sandbox_kind(None, false, Some("/snap/roqtune/1".to_string())),
+            SandboxKind::Snap
+        );
+        assert_eq!(
+            detect_sandbox_kind(Some(String::new()), false, None),
+            SandboxKind::Unsandboxed
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Unclean-shutdown detection and playback journaling for crash recovery.
+//!
+//! Each run marks `session_journal.json` in the config directory as running at startup
+//! and clears it on a normal exit. While the app runs, the journal follows the playing
+//! playlist, track, and position. A journal still marked as running at the next start
+//! means the previous session crashed: the app offers a safe-mode restart and resumes
+//! the journaled track where it stopped.
+//!
+//! Safe mode (`roqtune --safe-mode`) starts with the system layout, after copying the
+//! user's `layout.toml` aside, and keeps OpenSubsonic and Cast offline for the session.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use log::warn;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::protocol::{Message, PlaybackMessage, PlaylistMessage};
+
+/// Command-line flag that starts the app in safe mode.
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+/// Journal file name inside the config directory.
+pub const SESSION_JOURNAL_FILE_NAME: &str = "session_journal.json";
+/// Copy of `layout.toml` taken when safe mode replaces the layout.
+pub const SAFE_MODE_LAYOUT_BACKUP_FILE_NAME: &str = "layout.before-safe-mode.toml";
+/// Minimum position change before the journal is rewritten for progress alone.
+const POSITION_WRITE_INTERVAL_MS: u64 = 5_000;
+
+/// Set once the session is marked clean, so late bus traffic cannot mark it running again.
+static SESSION_ENDED: AtomicBool = AtomicBool::new(false);
+
+/// Playing track recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct JournaledPlayback {
+    pub playlist_id: String,
+    pub track_id: String,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+struct SessionJournal {
+    #[serde(default)]
+    running: bool,
+    #[serde(default)]
+    playback: Option<JournaledPlayback>,
+}
+
+/// What the journal says about the previous session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreviousSession {
+    /// The previous session never reached a normal exit.
+    pub crashed: bool,
+    /// Track playing when the previous session crashed.
+    pub playback: Option<JournaledPlayback>,
+}
+
+fn read_journal(path: &Path) -> SessionJournal {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_journal(path: &Path, journal: &SessionJournal) {
+    let Ok(text) = serde_json::to_string(journal) else {
+        return;
+    };
+    let temp_path = path.with_extension("json.tmp");
+    if let Err(err) = fs::write(&temp_path, text).and_then(|_| fs::rename(&temp_path, path)) {
+        warn!(
+            "Failed to write session journal {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+/// Reads the previous session's outcome and marks the current session as running.
+///
+/// The crashed session's playback stays journaled until this session overwrites it, so
+/// a safe-mode restart can still resume it.
+pub fn begin_session(path: &Path) -> PreviousSession {
+    let previous = read_journal(path);
+    let crashed = previous.running;
+    let playback = previous.playback.filter(|_| crashed);
+    write_journal(
+        path,
+        &SessionJournal {
+            running: true,
+            playback: playback.clone(),
+        },
+    );
+    PreviousSession { crashed, playback }
+}
+
+/// Marks the session as cleanly shut down.
+pub fn end_session(path: &Path) {
+    SESSION_ENDED.store(true, Ordering::Relaxed);
+    write_journal(path, &SessionJournal::default());
+}
+
+/// Copies `layout.toml` aside before safe mode replaces it with the system layout.
+pub fn back_up_layout_for_safe_mode(layout_file: &Path) -> Option<PathBuf> {
+    let backup_path = layout_file.with_file_name(SAFE_MODE_LAYOUT_BACKUP_FILE_NAME);
+    match fs::copy(layout_file, &backup_path) {
+        Ok(_) => Some(backup_path),
+        Err(err) => {
+            warn!(
+                "Failed to back up layout {} for safe mode: {}",
+                layout_file.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Follows playback bus messages and decides when the journal needs rewriting.
+#[derive(Debug, Default)]
+struct PlaybackJournal {
+    playback: Option<JournaledPlayback>,
+    written_elapsed_ms: u64,
+}
+
+impl PlaybackJournal {
+    /// Applies one bus message; returns `true` when the journal should be rewritten.
+    fn observe(&mut self, message: &Message) -> bool {
+        match message {
+            Message::Playlist(PlaylistMessage::PlaylistIndicesChanged {
+                playing_playlist_id,
+                playing_track_id,
+                ..
+            }) => {
+                let next = match (playing_playlist_id, playing_track_id) {
+                    (Some(playlist_id), Some(track_id)) => {
+                        let elapsed_ms = self
+                            .playback
+                            .as_ref()
+                            .filter(|current| &current.track_id == track_id)
+                            .map_or(0, |current| current.elapsed_ms);
+                        Some(JournaledPlayback {
+                            playlist_id: playlist_id.clone(),
+                            track_id: track_id.clone(),
+                            elapsed_ms,
+                        })
+                    }
+                    _ => None,
+                };
+                self.replace(next)
+            }
+            Message::Playback(PlaybackMessage::Stop) => self.replace(None),
+            Message::Playback(PlaybackMessage::PlaybackProgress { elapsed_ms, .. }) => {
+                let Some(playback) = self.playback.as_mut() else {
+                    return false;
+                };
+                playback.elapsed_ms = *elapsed_ms;
+                if elapsed_ms.abs_diff(self.written_elapsed_ms) < POSITION_WRITE_INTERVAL_MS {
+                    return false;
+                }
+                self.written_elapsed_ms = *elapsed_ms;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn replace(&mut self, next: Option<JournaledPlayback>) -> bool {
+        if self.playback == next {
+            return false;
+        }
+        self.written_elapsed_ms = next.as_ref().map_or(0, |playback| playback.elapsed_ms);
+        self.playback = next;
+        true
+    }
+}
+
+/// Keeps the running session's journal up to date with the playing track.
+pub fn spawn_session_journal(path: PathBuf, mut receiver: Receiver<Message>) {
+    thread::spawn(move || {
+        let mut journal = PlaybackJournal::default();
+        loop {
+            match receiver.blocking_recv() {
+                Ok(message) => {
+                    if journal.observe(&message) && !SESSION_ENDED.load(Ordering::Relaxed) {
+                        write_journal(
+                            &path,
+                            &SessionJournal {
+                                running: true,
+                                playback: journal.playback.clone(),
+                            },
+                        );
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Session journal lagged on control bus, skipped {} message(s)",
+                        skipped
+                    );
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_journal_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "roqtune-session-journal-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::create_dir_all(&dir);
+        dir.join(SESSION_JOURNAL_FILE_NAME)
+    }
+
+    fn indices_changed(playlist_id: &str, track_id: &str) -> Message {
+        Message::Playlist(PlaylistMessage::PlaylistIndicesChanged {
+            playing_playlist_id: Some(playlist_id.to_string()),
+            playing_index: Some(0),
+            playing_track_id: Some(track_id.to_string()),
+            playing_track_path: None,
+            playing_track_metadata: None,
+            selected_indices: Vec::new(),
+            is_playing: true,
+            playback_order: crate::protocol::PlaybackOrder::Default,
+            repeat_mode: crate::protocol::RepeatMode::Off,
+        })
+    }
+
+    #[test]
+    fn test_begin_session_reports_crash_only_when_previous_run_did_not_end() {
+        let path = temp_journal_path("crash");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(begin_session(&path), PreviousSession::default());
+        let playback = JournaledPlayback {
+            playlist_id: "p1".to_string(),
+            track_id: "t1".to_string(),
+            elapsed_ms: 42_000,
+        };
+        write_journal(
+            &path,
+            &SessionJournal {
+                running: true,
+                playback: Some(playback.clone()),
+            },
+        );
+
+        let previous = begin_session(&path);
+        assert!(previous.crashed);
+        assert_eq!(previous.playback, Some(playback));
+
+        end_session(&path);
+        assert_eq!(begin_session(&path), PreviousSession::default());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_playback_journal_throttles_progress_writes() {
+        let mut journal = PlaybackJournal::default();
+        let progress = |elapsed_ms| {
+            Message::Playback(PlaybackMessage::PlaybackProgress {
+                elapsed_ms,
+                total_ms: 300_000,
+            })
+        };
+
+        assert!(journal.observe(&indices_changed("p1", "t1")));
+        assert!(!journal.observe(&indices_changed("p1", "t1")));
+        assert!(!journal.observe(&progress(1_000)));
+        assert!(journal.observe(&progress(6_000)));
+        assert_eq!(
+            journal
+                .playback
+                .as_ref()
+                .map(|playback| playback.elapsed_ms),
+            Some(6_000)
+        );
+        assert!(journal.observe(&indices_changed("p1", "t2")));
+        assert_eq!(
+            journal
+                .playback
+                .as_ref()
+                .map(|playback| playback.elapsed_ms),
+            Some(0)
+        );
+        assert!(journal.observe(&Message::Playback(PlaybackMessage::Stop)));
+        assert_eq!(journal.playback, None);
+    }
+}
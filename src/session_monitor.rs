@@ -0,0 +1,842 @@
+//! System suspend/lock/screensaver, other-application audio, and power source monitoring.
+//!
+//! Watcher threads translate OS signals into `ConfigMessage::SystemSessionChanged`
+//! and `ConfigMessage::PowerSourceChanged` bus events. The `SessionMonitor` loop then
+//! pauses/resumes playback according to `PowerConfig`, asks the audio player to
+//! re-open its device after a wake, and publishes battery saver transitions.
+//!
+//! Other-application audio is detected on Linux by polling the PulseAudio/PipeWire
+//! sink inputs through `pactl`; streams owned by this process and corked (paused)
+//! streams are ignored. The poll only runs while `pause_on_other_audio` is enabled.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use log::{debug, info, warn};
+use tokio::sync::broadcast::{Receiver, Sender};
+
+use crate::config::PowerConfig;
+use crate::protocol::{
+    ConfigDeltaEntry, ConfigMessage, Message, PlaybackMessage, PlaylistMessage, PowerConfigDelta,
+    SystemSessionEvent,
+};
+
+/// Poll interval for the wall-clock gap detector.
+const SLEEP_GAP_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Extra wall-clock time beyond the poll interval treated as an unannounced suspend.
+const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(8);
+/// Poll interval for AC/battery detection.
+const POWER_SOURCE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Poll interval for other-application audio detection.
+const OTHER_AUDIO_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Consecutive quiet polls before other audio counts as stopped, so short gaps (e.g.
+/// between two browser videos) do not resume playback.
+const OTHER_AUDIO_STOP_POLLS: u32 = 3;
+
+/// Why playback was paused by the monitor, used to decide whether a later wake/unlock
+/// should resume it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionPauseReason {
+    Suspend,
+    Lock,
+    Screensaver,
+    OtherAudio,
+}
+
+/// Debounces raw "other audio active" polls into start/stop events.
+#[derive(Debug, Default)]
+struct OtherAudioDebounce {
+    active: bool,
+    quiet_polls: u32,
+}
+
+impl OtherAudioDebounce {
+    fn observe(&mut self, other_audio_playing: bool) -> Option<SystemSessionEvent> {
+        if other_audio_playing {
+            self.quiet_polls = 0;
+            if !self.active {
+                self.active = true;
+                return Some(SystemSessionEvent::OtherAudioStarted);
+            }
+            return None;
+        }
+        if !self.active {
+            return None;
+        }
+        self.quiet_polls += 1;
+        if self.quiet_polls < OTHER_AUDIO_STOP_POLLS {
+            return None;
+        }
+        self.active = false;
+        self.quiet_polls = 0;
+        Some(SystemSessionEvent::OtherAudioStopped)
+    }
+}
+
+/// Reacts to OS session transitions according to power preferences.
+pub struct SessionMonitor {
+    bus_consumer: Receiver<Message>,
+    bus_producer: Sender<Message>,
+    config: PowerConfig,
+    is_playing: bool,
+    paused_by: Option<SessionPauseReason>,
+    suspend_announced: bool,
+    on_battery: bool,
+    battery_saver_active: bool,
+    other_audio_paused_at: Option<Instant>,
+    other_audio_watch_enabled: Arc<AtomicBool>,
+}
+
+impl SessionMonitor {
+    /// Creates a session monitor seeded with startup power config.
+    pub fn new(
+        bus_consumer: Receiver<Message>,
+        bus_producer: Sender<Message>,
+        initial_config: PowerConfig,
+    ) -> Self {
+        let other_audio_watch_enabled =
+            Arc::new(AtomicBool::new(initial_config.pause_on_other_audio));
+        Self {
+            bus_consumer,
+            bus_producer,
+            config: initial_config,
+            is_playing: false,
+            paused_by: None,
+            suspend_announced: false,
+            on_battery: false,
+            battery_saver_active: false,
+            other_audio_paused_at: None,
+            other_audio_watch_enabled,
+        }
+    }
+
+    fn apply_config_delta(&mut self, delta: PowerConfigDelta) {
+        if let Some(pause_on_suspend) = delta.pause_on_suspend {
+            self.config.pause_on_suspend = pause_on_suspend;
+        }
+        if let Some(pause_on_lock) = delta.pause_on_lock {
+            self.config.pause_on_lock = pause_on_lock;
+        }
+        if let Some(pause_on_screensaver) = delta.pause_on_screensaver {
+            self.config.pause_on_screensaver = pause_on_screensaver;
+        }
+        if let Some(resume_on_unlock) = delta.resume_on_unlock {
+            self.config.resume_on_unlock = resume_on_unlock;
+        }
+        if let Some(battery_saver) = delta.battery_saver {
+            self.config.battery_saver = battery_saver;
+            self.sync_battery_saver_state();
+        }
+        if let Some(pause_on_other_audio) = delta.pause_on_other_audio {
+            self.config.pause_on_other_audio = pause_on_other_audio;
+            self.other_audio_watch_enabled
+                .store(pause_on_other_audio, Ordering::Relaxed);
+        }
+        if let Some(resume_after_other_audio) = delta.resume_after_other_audio {
+            self.config.resume_after_other_audio = resume_after_other_audio;
+        }
+        if let Some(window_minutes) = delta.other_audio_resume_window_minutes {
+            self.config.other_audio_resume_window_minutes = window_minutes;
+        }
+    }
+
+    fn sync_battery_saver_state(&mut self) {
+        let active = self.config.battery_saver && self.on_battery;
+        if active == self.battery_saver_active {
+            return;
+        }
+        self.battery_saver_active = active;
+        info!(
+            "SessionMonitor: battery saver {}",
+            if active { "enabled" } else { "disabled" }
+        );
+        let _ = self
+            .bus_producer
+            .send(Message::Config(ConfigMessage::BatterySaverChanged {
+                active,
+            }));
+    }
+
+    /// Derives the power source from Linux `/sys/class/power_supply` entries given as
+    /// `(type, online, status)` triples. Returns `None` when no supply is reported.
+    fn on_battery_from_linux_supplies(supplies: &[(String, String, String)]) -> Option<bool> {
+        if supplies.is_empty() {
+            return None;
+        }
+        let mains_online = supplies
+            .iter()
+            .any(|(kind, online, _)| kind == "Mains" && online == "1");
+        if mains_online {
+            return Some(false);
+        }
+        let discharging = supplies
+            .iter()
+            .any(|(kind, _, status)| kind == "Battery" && status == "Discharging");
+        Some(discharging)
+    }
+
+    /// Derives the power source from the first line of `pmset -g batt`.
+    fn on_battery_from_pmset_output(output: &str) -> Option<bool> {
+        let first_line = output.lines().next()?;
+        if first_line.contains("'Battery Power'") {
+            Some(true)
+        } else if first_line.contains("'AC Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn read_linux_power_supplies() -> Vec<(String, String, String)> {
+        let read_attr = |dir: &Path, name: &str| {
+            fs::read_to_string(dir.join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| {
+                let dir = entry.path();
+                (
+                    read_attr(&dir, "type"),
+                    read_attr(&dir, "online"),
+                    read_attr(&dir, "status"),
+                )
+            })
+            .collect()
+    }
+
+    fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn detect_on_battery() -> Option<bool> {
+        if cfg!(target_os = "linux") {
+            Self::on_battery_from_linux_supplies(&Self::read_linux_power_supplies())
+        } else if cfg!(target_os = "macos") {
+            Self::on_battery_from_pmset_output(&Self::command_stdout("pmset", &["-g", "batt"])?)
+        } else if cfg!(target_os = "windows") {
+            // Win32_Battery.BatteryStatus 1 means "discharging"; no output means no battery.
+            let status = Self::command_stdout(
+                "powershell",
+                &[
+                    "-NoProfile",
+                    "-Command",
+                    "(Get-CimInstance -ClassName Win32_Battery).BatteryStatus",
+                ],
+            )?;
+            let status = status.lines().next()?.trim().to_string();
+            (!status.is_empty()).then_some(status == "1")
+        } else {
+            None
+        }
+    }
+
+    /// Parses one `gdbus monitor` output line into a session event.
+    fn parse_gdbus_monitor_line(line: &str) -> Option<SystemSessionEvent> {
+        let line = line.trim();
+        if line.contains(".PrepareForSleep") {
+            if line.contains("(true,)") {
+                return Some(SystemSessionEvent::Suspending);
+            }
+            if line.contains("(false,)") {
+                return Some(SystemSessionEvent::Resumed);
+            }
+            return None;
+        }
+        if line.contains("org.freedesktop.login1.Session.Lock ")
+            || line.ends_with(".Session.Lock ()")
+        {
+            return Some(SystemSessionEvent::Locked);
+        }
+        if line.contains("org.freedesktop.login1.Session.Unlock ")
+            || line.ends_with(".Session.Unlock ()")
+        {
+            return Some(SystemSessionEvent::Unlocked);
+        }
+        if line.contains(".ScreenSaver.ActiveChanged") {
+            if line.contains("(true,)") {
+                return Some(SystemSessionEvent::ScreensaverActivated);
+            }
+            if line.contains("(false,)") {
+                return Some(SystemSessionEvent::ScreensaverDeactivated);
+            }
+        }
+        None
+    }
+
+    /// Returns `true` when `pactl list sink-inputs` output contains an uncorked stream
+    /// that does not belong to process `own_pid`.
+    fn other_audio_playing_from_pactl_output(output: &str, own_pid: u32) -> bool {
+        let own_pid = own_pid.to_string();
+        let mut blocks = Vec::new();
+        for line in output.lines() {
+            if line.starts_with("Sink Input #") {
+                blocks.push(Vec::new());
+            } else if let Some(block) = blocks.last_mut() {
+                block.push(line.trim());
+            }
+        }
+        blocks.iter().any(|block| {
+            let corked = block.iter().any(|line| *line == "Corked: yes");
+            let own_stream = block.iter().any(|line| {
+                line.strip_prefix("application.process.id = ")
+                    .is_some_and(|pid| pid.trim_matches('"') == own_pid)
+            });
+            !corked && !own_stream
+        })
+    }
+
+    /// Returns `true` when wall-clock time advanced far beyond the monotonic interval,
+    /// which indicates the machine was asleep in between.
+    fn is_sleep_gap(monotonic_elapsed: Duration, wall_elapsed: Duration) -> bool {
+        wall_elapsed > monotonic_elapsed.saturating_add(SLEEP_GAP_THRESHOLD)
+    }
+
+    fn spawn_gdbus_watcher(bus_producer: Sender<Message>, bus_flag: &'static str, dest: &str) {
+        let child = Command::new("gdbus")
+            .args(["monitor", bus_flag, "--dest", dest])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                debug!(
+                    "SessionMonitor: gdbus watcher for {} unavailable: {}",
+                    dest, err
+                );
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        let dest = dest.to_string();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(event) = Self::parse_gdbus_monitor_line(&line) {
+                    let _ = bus_producer
+                        .send(Message::Config(ConfigMessage::SystemSessionChanged(event)));
+                }
+            }
+            let _ = child.wait();
+            debug!("SessionMonitor: gdbus watcher for {} exited", dest);
+        });
+    }
+
+    fn spawn_sleep_gap_watcher(bus_producer: Sender<Message>) {
+        thread::spawn(move || {
+            let mut last_monotonic = Instant::now();
+            let mut last_wall = SystemTime::now();
+            loop {
+                thread::sleep(SLEEP_GAP_POLL_INTERVAL);
+                let now_monotonic = Instant::now();
+                let now_wall = SystemTime::now();
+                let monotonic_elapsed = now_monotonic.duration_since(last_monotonic);
+                let wall_elapsed = now_wall.duration_since(last_wall).unwrap_or_default();
+                if Self::is_sleep_gap(monotonic_elapsed, wall_elapsed) {
+                    let _ = bus_producer.send(Message::Config(
+                        ConfigMessage::SystemSessionChanged(SystemSessionEvent::Resumed),
+                    ));
+                }
+                last_monotonic = now_monotonic;
+                last_wall = now_wall;
+            }
+        });
+    }
+
+    fn spawn_power_source_watcher(bus_producer: Sender<Message>) {
+        thread::spawn(move || {
+            let mut last_on_battery = None;
+            loop {
+                if let Some(on_battery) = Self::detect_on_battery() {
+                    if last_on_battery != Some(on_battery) {
+                        last_on_battery = Some(on_battery);
+                        let _ =
+                            bus_producer.send(Message::Config(ConfigMessage::PowerSourceChanged {
+                                on_battery,
+                            }));
+                    }
+                }
+                thread::sleep(POWER_SOURCE_POLL_INTERVAL);
+            }
+        });
+    }
+
+    fn spawn_other_audio_watcher(bus_producer: Sender<Message>, enabled: Arc<AtomicBool>) {
+        thread::spawn(move || {
+            let own_pid = std::process::id();
+            let mut debounce = OtherAudioDebounce::default();
+            loop {
+                thread::sleep(OTHER_AUDIO_POLL_INTERVAL);
+                let playing = enabled.load(Ordering::Relaxed)
+                    && Self::command_stdout("pactl", &["list", "sink-inputs"]).is_some_and(
+                        |output| Self::other_audio_playing_from_pactl_output(&output, own_pid),
+                    );
+                if let Some(event) = debounce.observe(playing) {
+                    let _ = bus_producer
+                        .send(Message::Config(ConfigMessage::SystemSessionChanged(event)));
+                }
+            }
+        });
+    }
+
+    fn spawn_watchers(&self) {
+        if cfg!(target_os = "linux") {
+            Self::spawn_gdbus_watcher(
+                self.bus_producer.clone(),
+                "--system",
+                "org.freedesktop.login1",
+            );
+            Self::spawn_gdbus_watcher(
+                self.bus_producer.clone(),
+                "--session",
+                "org.freedesktop.ScreenSaver",
+            );
+            Self::spawn_other_audio_watcher(
+                self.bus_producer.clone(),
+                self.other_audio_watch_enabled.clone(),
+            );
+        }
+        // Portable fallback: detects wakes even when no suspend signal was delivered.
+        Self::spawn_sleep_gap_watcher(self.bus_producer.clone());
+        Self::spawn_power_source_watcher(self.bus_producer.clone());
+    }
+
+    fn pause_for(&mut self, reason: SessionPauseReason) {
+        if !self.is_playing {
+            return;
+        }
+        info!("SessionMonitor: pausing playback ({:?})", reason);
+        self.paused_by = Some(reason);
+        let _ = self
+            .bus_producer
+            .send(Message::Playback(PlaybackMessage::Pause));
+    }
+
+    fn resume_if_paused_by(&mut self, reasons: &[SessionPauseReason]) {
+        let Some(reason) = self.paused_by else {
+            return;
+        };
+        if !reasons.contains(&reason) {
+            return;
+        }
+        self.paused_by = None;
+        if !self.config.resume_on_unlock {
+            return;
+        }
+        info!("SessionMonitor: resuming playback after {:?}", reason);
+        let _ = self
+            .bus_producer
+            .send(Message::Playback(PlaybackMessage::Play));
+    }
+
+    /// Returns `true` when playback paused for other audio `elapsed` ago should resume.
+    fn should_resume_after_other_audio(&self, elapsed: Duration) -> bool {
+        let window_minutes = self.config.other_audio_resume_window_minutes;
+        self.config.resume_after_other_audio
+            && (window_minutes == 0
+                || elapsed <= Duration::from_secs(u64::from(window_minutes) * 60))
+    }
+
+    fn handle_session_event(&mut self, event: SystemSessionEvent) {
+        debug!("SessionMonitor: session event {:?}", event);
+        match event {
+            SystemSessionEvent::Suspending => {
+                self.suspend_announced = true;
+                if self.config.pause_on_suspend {
+                    self.pause_for(SessionPauseReason::Suspend);
+                }
+            }
+            SystemSessionEvent::Resumed => {
+                if !self.suspend_announced && self.config.pause_on_suspend {
+                    // Wake detected without a prior suspend signal (gap detector).
+                    self.pause_for(SessionPauseReason::Suspend);
+                }
+                self.suspend_announced = false;
+                let _ = self
+                    .bus_producer
+                    .send(Message::Config(ConfigMessage::ReopenAudioDevice {
+                        reason: "system resumed from suspend".to_string(),
+                    }));
+                // A locked session after wake is resumed by the matching unlock instead.
+                if self.paused_by == Some(SessionPauseReason::Suspend) && !self.config.pause_on_lock
+                {
+                    self.resume_if_paused_by(&[SessionPauseReason::Suspend]);
+                }
+            }
+            SystemSessionEvent::Locked => {
+                if self.config.pause_on_lock {
+                    self.pause_for(SessionPauseReason::Lock);
+                }
+            }
+            SystemSessionEvent::ScreensaverActivated => {
+                if self.config.pause_on_screensaver {
+                    self.pause_for(SessionPauseReason::Screensaver);
+                }
+            }
+            SystemSessionEvent::Unlocked | SystemSessionEvent::ScreensaverDeactivated => {
+                self.resume_if_paused_by(&[
+                    SessionPauseReason::Suspend,
+                    SessionPauseReason::Lock,
+                    SessionPauseReason::Screensaver,
+                ]);
+            }
+            SystemSessionEvent::OtherAudioStarted => {
+                if self.config.pause_on_other_audio && self.paused_by.is_none() {
+                    self.pause_for(SessionPauseReason::OtherAudio);
+                    if self.paused_by == Some(SessionPauseReason::OtherAudio) {
+                        self.other_audio_paused_at = Some(Instant::now());
+                    }
+                }
+            }
+            SystemSessionEvent::OtherAudioStopped => {
+                if self.paused_by != Some(SessionPauseReason::OtherAudio) {
+                    return;
+                }
+                self.paused_by = None;
+                let elapsed = self
+                    .other_audio_paused_at
+                    .take()
+                    .map(|paused_at| paused_at.elapsed())
+                    .unwrap_or_default();
+                if self.should_resume_after_other_audio(elapsed) {
+                    info!("SessionMonitor: resuming playback after other audio stopped");
+                    let _ = self
+                        .bus_producer
+                        .send(Message::Playback(PlaybackMessage::Play));
+                }
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Config(ConfigMessage::SystemSessionChanged(event)) => {
+                self.handle_session_event(event);
+            }
+            Message::Config(ConfigMessage::PowerSourceChanged { on_battery }) => {
+                debug!("SessionMonitor: on_battery={}", on_battery);
+                self.on_battery = on_battery;
+                self.sync_battery_saver_state();
+            }
+            Message::Config(ConfigMessage::ConfigChanged(changes)) => {
+                for change in changes {
+                    if let ConfigDeltaEntry::Power(delta) = change {
+                        self.apply_config_delta(delta);
+                    }
+                }
+            }
+            Message::Playlist(PlaylistMessage::PlaylistIndicesChanged { is_playing, .. }) => {
+                self.is_playing = is_playing;
+                if is_playing {
+                    // Any playback started by the user supersedes a pending auto-resume.
+                    self.paused_by = None;
+                }
+            }
+            Message::Playback(PlaybackMessage::Stop) => {
+                self.is_playing = false;
+                self.paused_by = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts OS watchers and the blocking monitor loop.
+    pub fn run(&mut self) {
+        info!("SessionMonitor: started");
+        self.spawn_watchers();
+        loop {
+            match self.bus_consumer.blocking_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("SessionMonitor: bus lagged by {} messages", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OtherAudioDebounce, SessionMonitor, OTHER_AUDIO_STOP_POLLS};
+    use crate::config::PowerConfig;
+    use crate::protocol::{
+        ConfigMessage, Message, PlaybackMessage, PlaylistMessage, SystemSessionEvent,
+    };
+    use std::time::Duration;
+    use tokio::sync::broadcast::{self, error::TryRecvError, Receiver};
+
+    fn playing_indices_changed(is_playing: bool) -> Message {
+        Message::Playlist(PlaylistMessage::PlaylistIndicesChanged {
+            playing_playlist_id: None,
+            playing_index: Some(0),
+            playing_track_id: Some("t1".to_string()),
+            playing_track_path: None,
+            playing_track_metadata: None,
+            selected_indices: Vec::new(),
+            is_playing,
+            playback_order: crate::protocol::PlaybackOrder::Default,
+            repeat_mode: crate::protocol::RepeatMode::Off,
+        })
+    }
+
+    fn drain(receiver: &mut Receiver<Message>) -> Vec<Message> {
+        let mut messages = Vec::new();
+        loop {
+            match receiver.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+        messages
+    }
+
+    #[test]
+    fn test_parse_gdbus_monitor_line_maps_known_signals() {
+        assert_eq!(
+            SessionMonitor::parse_gdbus_monitor_line(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)"
+            ),
+            Some(SystemSessionEvent::Suspending)
+        );
+        assert_eq!(
+            SessionMonitor::parse_gdbus_monitor_line(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)"
+            ),
+            Some(SystemSessionEvent::Resumed)
+        );
+        assert_eq!(
+            SessionMonitor::parse_gdbus_monitor_line(
+                "/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Lock ()"
+            ),
+            Some(SystemSessionEvent::Locked)
+        );
+        assert_eq!(
+            SessionMonitor::parse_gdbus_monitor_line(
+                "/org/freedesktop/ScreenSaver: org.freedesktop.ScreenSaver.ActiveChanged (false,)"
+            ),
+            Some(SystemSessionEvent::ScreensaverDeactivated)
+        );
+        assert_eq!(
+            SessionMonitor::parse_gdbus_monitor_line("The name org.freedesktop.login1 is owned"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_sleep_gap_requires_wall_clock_jump() {
+        assert!(!SessionMonitor::is_sleep_gap(
+            Duration::from_secs(2),
+            Duration::from_secs(3)
+        ));
+        assert!(SessionMonitor::is_sleep_gap(
+            Duration::from_secs(2),
+            Duration::from_secs(600)
+        ));
+    }
+
+    #[test]
+    fn test_power_source_parsing_prefers_mains_and_pmset_header() {
+        let supply = |kind: &str, online: &str, status: &str| {
+            (kind.to_string(), online.to_string(), status.to_string())
+        };
+        assert_eq!(
+            SessionMonitor::on_battery_from_linux_supplies(&[
+                supply("Mains", "0", ""),
+                supply("Battery", "", "Discharging"),
+            ]),
+            Some(true)
+        );
+        assert_eq!(
+            SessionMonitor::on_battery_from_linux_supplies(&[
+                supply("Mains", "1", ""),
+                supply("Battery", "", "Discharging"),
+            ]),
+            Some(false)
+        );
+        assert_eq!(SessionMonitor::on_battery_from_linux_supplies(&[]), None);
+        assert_eq!(
+            SessionMonitor::on_battery_from_pmset_output(
+                "Now drawing from 'Battery Power'\n -InternalBattery-0 80%; discharging"
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            SessionMonitor::on_battery_from_pmset_output("Now drawing from 'AC Power'"),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_battery_saver_follows_power_source_and_config() {
+        let (bus_sender, _) = broadcast::channel::<Message>(32);
+        let mut receiver = bus_sender.subscribe();
+        let mut monitor = SessionMonitor::new(
+            bus_sender.subscribe(),
+            bus_sender.clone(),
+            PowerConfig {
+                battery_saver: true,
+                ..PowerConfig::default()
+            },
+        );
+
+        monitor.handle_message(Message::Config(ConfigMessage::PowerSourceChanged {
+            on_battery: true,
+        }));
+        monitor.handle_message(Message::Config(ConfigMessage::PowerSourceChanged {
+            on_battery: true,
+        }));
+        monitor.handle_message(Message::Config(ConfigMessage::PowerSourceChanged {
+            on_battery: false,
+        }));
+
+        let messages = drain(&mut receiver);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                Message::Config(ConfigMessage::BatterySaverChanged { active: true }),
+                Message::Config(ConfigMessage::BatterySaverChanged { active: false })
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_lock_pauses_and_unlock_resumes_only_when_enabled() {
+        let (bus_sender, _) = broadcast::channel::<Message>(32);
+        let mut receiver = bus_sender.subscribe();
+        let mut monitor = SessionMonitor::new(
+            bus_sender.subscribe(),
+            bus_sender.clone(),
+            PowerConfig {
+                pause_on_lock: true,
+                resume_on_unlock: true,
+                ..PowerConfig::default()
+            },
+        );
+
+        monitor.handle_message(playing_indices_changed(true));
+        monitor.handle_message(Message::Config(ConfigMessage::SystemSessionChanged(
+            SystemSessionEvent::Locked,
+        )));
+        monitor.handle_message(playing_indices_changed(false));
+        monitor.handle_message(Message::Config(ConfigMessage::SystemSessionChanged(
+            SystemSessionEvent::Unlocked,
+        )));
+
+        let messages = drain(&mut receiver);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                Message::Playback(PlaybackMessage::Pause),
+                Message::Playback(PlaybackMessage::Play)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_other_audio_detection_ignores_own_and_corked_streams() {
+        let output = "Sink Input #41\n\tCorked: no\n\tProperties:\n\t\tapplication.name = \"roqtune\"\n\t\tapplication.process.id = \"100\"\nSink Input #42\n\tCorked: yes\n\tProperties:\n\t\tapplication.name = \"Firefox\"\n\t\tapplication.process.id = \"200\"\n";
+        assert!(!SessionMonitor::other_audio_playing_from_pactl_output(
+            output, 100
+        ));
+        let with_call = format!(
+            "{output}Sink Input #43\n\tCorked: no\n\tProperties:\n\t\tapplication.process.id = \"300\"\n"
+        );
+        assert!(SessionMonitor::other_audio_playing_from_pactl_output(
+            &with_call, 100
+        ));
+        assert!(!SessionMonitor::other_audio_playing_from_pactl_output(
+            "", 100
+        ));
+    }
+
+    #[test]
+    fn test_other_audio_debounce_waits_for_sustained_quiet() {
+        let mut debounce = OtherAudioDebounce::default();
+        assert_eq!(debounce.observe(false), None);
+        assert_eq!(
+            debounce.observe(true),
+            Some(SystemSessionEvent::OtherAudioStarted)
+        );
+        assert_eq!(debounce.observe(true), None);
+        for _ in 1..OTHER_AUDIO_STOP_POLLS {
+            assert_eq!(debounce.observe(false), None);
+        }
+        assert_eq!(debounce.observe(true), None);
+        for _ in 1..OTHER_AUDIO_STOP_POLLS {
+            assert_eq!(debounce.observe(false), None);
+        }
+        assert_eq!(
+            debounce.observe(false),
+            Some(SystemSessionEvent::OtherAudioStopped)
+        );
+    }
+
+    #[test]
+    fn test_other_audio_pauses_and_resumes_within_window() {
+        let (bus_sender, _) = broadcast::channel::<Message>(32);
+        let mut receiver = bus_sender.subscribe();
+        let mut monitor = SessionMonitor::new(
+            bus_sender.subscribe(),
+            bus_sender.clone(),
+            PowerConfig {
+                pause_on_other_audio: true,
+                other_audio_resume_window_minutes: 5,
+                ..PowerConfig::default()
+            },
+        );
+
+        monitor.handle_message(playing_indices_changed(true));
+        monitor.handle_message(Message::Config(ConfigMessage::SystemSessionChanged(
+            SystemSessionEvent::OtherAudioStarted,
+        )));
+        monitor.handle_message(playing_indices_changed(false));
+        monitor.handle_message(Message::Config(ConfigMessage::SystemSessionChanged(
+            SystemSessionEvent::Unlocked,
+        )));
+        monitor.handle_message(Message::Config(ConfigMessage::SystemSessionChanged(
+            SystemSessionEvent::OtherAudioStopped,
+        )));
+
+        let messages = drain(&mut receiver);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                Message::Playback(PlaybackMessage::Pause),
+                Message::Playback(PlaybackMessage::Play)
+            ]
+        ));
+        assert!(monitor.should_resume_after_other_audio(Duration::from_secs(5 * 60)));
+        assert!(!monitor.should_resume_after_other_audio(Duration::from_secs(5 * 60 + 1)));
+        monitor.config.other_audio_resume_window_minutes = 0;
+        assert!(monitor.should_resume_after_other_audio(Duration::from_secs(24 * 3600)));
+        monitor.config.resume_after_other_audio = false;
+        assert!(!monitor.should_resume_after_other_audio(Duration::ZERO));
+    }
+}
@@ -0,0 +1,75 @@
+//! Startup phase timing markers.
+//!
+//! Phases are measured from the first `begin()` call in `main`, written to the
+//! application log, and summarized in the diagnostics section of settings so
+//! startup regressions can be compared across builds.
+
+use std::{
+    sync::{Mutex, OnceLock},
+    time::Instant,
+};
+
+use log::info;
+
+static STARTUP_STARTED_AT: OnceLock<Instant> = OnceLock::new();
+static RECORDED_PHASES: Mutex<Vec<(&'static str, u128)>> = Mutex::new(Vec::new());
+
+/// Records the process start reference point. Later calls are ignored.
+pub fn begin() {
+    let _ = STARTUP_STARTED_AT.set(Instant::now());
+}
+
+/// Logs and records elapsed time since `begin()` for one startup phase.
+///
+/// Each phase is recorded at most once, so markers can live on paths that also run
+/// after startup (for example playlist restore on every playlist switch).
+pub fn mark(phase: &'static str) {
+    let Some(started_at) = STARTUP_STARTED_AT.get() else {
+        return;
+    };
+    let Ok(mut recorded) = RECORDED_PHASES.lock() else {
+        return;
+    };
+    if recorded
+        .iter()
+        .any(|(recorded_phase, _)| *recorded_phase == phase)
+    {
+        return;
+    }
+    let elapsed_ms = started_at.elapsed().as_millis();
+    recorded.push((phase, elapsed_ms));
+    info!("Startup timing: {} at {} ms", phase, elapsed_ms);
+}
+
+/// Returns the phases recorded so far as display text for the diagnostics panel.
+pub fn summary() -> String {
+    RECORDED_PHASES
+        .lock()
+        .map(|recorded| format_summary(&recorded))
+        .unwrap_or_default()
+}
+
+fn format_summary(phases: &[(&'static str, u128)]) -> String {
+    if phases.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = phases
+        .iter()
+        .map(|(phase, elapsed_ms)| format!("  {phase}: {elapsed_ms} ms"))
+        .collect();
+    format!("Startup timings (since launch)\n{}", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_summary_lists_phases_in_recorded_order() {
+        assert_eq!(format_summary(&[]), "");
+        assert_eq!(
+            format_summary(&[("window created", 80), ("playlist view populated", 145)]),
+            "Startup timings (since launch)\n  window created: 80 ms\n  playlist view populated: 145 ms"
+        );
+    }
+}
@@ -14,7 +14,7 @@ pub(crate) const DEFAULT_ARTIST_BIO_PANEL_TEMPLATE: &str =
     "[size=title][b][color=text_primary][if=title]{title}[else]Artist Bio[/if][/color][/b][/size][if=artist]\\n[size=body][color=text_secondary]{artist}[/color][/size][/if][if=genre]\\n[size=caption][color=text_muted]{genre}[/color][/size][/if]";
 pub(crate) const DEFAULT_METADATA_PANEL_TEMPLATE: &str = DEFAULT_TRACK_PANEL_TEMPLATE;
 pub(crate) const DEFAULT_STATUS_PANEL_TEMPLATE: &str =
-    "[valign=center][halign=left][size=12][color=text_secondary][if=path]Now Playing: [if=artist]{artist} - [/if][if=title]{title}[else]Unknown[/if][if=selection_summary] | {selection_summary}[/if][else]{selection_summary}[/if][/color][/size][/halign][halign=right][size=11][color=text_muted][if=format]Source: [if=source_provider]{source_provider} | [/if]{format}[if=bit_depth] ({bit_depth} bit[/if][if=sample_rate_hz], {sample_rate_hz}[/if][if=channels], {channels}ch[/if][if=bitrate_kbps], {bitrate_kbps}kbps[/if][if=bit_depth])[/if][else][if=cast_state]Source: Unknown[/if][/if][if=cast_state] | {cast_state}[/if][if=playback_mode] | [if=output_format]{playback_mode}: {output_format}[if=output_bit_depth] ({output_bit_depth} bit[/if][if=output_sample_rate_hz], {output_sample_rate_hz}[/if][if=output_channels], {output_channels}ch[/if][if=output_bitrate_kbps], {output_bitrate_kbps}kbps[/if][if=output_bit_depth])[/if][else]{playback_mode}[/if][/if][if=resampled] | Resample: {resample_from_hz} -> {resample_to_hz}[/if][if=channel_transform][if=resampled] / [/if][if=resampled][else] | [/if]{channel_transform}: {channel_from_channels}ch -> {channel_to_channels}ch[/if][if=dithered][if=resampled;channel_transform] / [/if][if=resampled;channel_transform][else] | [/if]Dither[/if][/color][/size][/halign][/valign]";
+    "[valign=center][halign=left][size=12][color=text_secondary][if=path]Now Playing: [if=artist]{artist} - [/if][if=title]{title}[else]Unknown[/if][if=selection_summary] | {selection_summary}[/if][else]{selection_summary}[/if][/color][/size][/halign][halign=right][size=11][color=text_muted][if=quality][color=accent]{quality}[/color] | [/if][if=format]Source: [if=source_provider]{source_provider} | [/if]{format}[if=bit_depth] ({bit_depth} bit[/if][if=sample_rate_hz], {sample_rate_hz}[/if][if=channels], {channels}ch[/if][if=bitrate_kbps], {bitrate_kbps}kbps[/if][if=bit_depth])[/if][else][if=cast_state]Source: Unknown[/if][/if][if=cast_state] | {cast_state}[/if][if=playback_mode] | [if=output_format]{playback_mode}: {output_format}[if=output_bit_depth] ({output_bit_depth} bit[/if][if=output_sample_rate_hz], {output_sample_rate_hz}[/if][if=output_channels], {output_channels}ch[/if][if=output_bitrate_kbps], {output_bitrate_kbps}kbps[/if][if=output_bit_depth])[/if][else]{playback_mode}[/if][/if][if=resampled] | Resample: {resample_from_hz} -> {resample_to_hz}[/if][if=channel_transform][if=resampled] / [/if][if=resampled][else] | [/if]{channel_transform}: {channel_from_channels}ch -> {channel_to_channels}ch[/if][if=dithered][if=resampled;channel_transform] / [/if][if=resampled;channel_transform][else] | [/if]Dither[/if][/color][/size][/halign][/valign]";
 pub(crate) const PLAYING_SYMBOL_PLAYING: &str = "▶️";
 pub(crate) const PLAYING_SYMBOL_PAUSED: &str = "⏸️";
 pub(crate) const FAVORITE_SYMBOL_ON: &str = "❤️";
@@ -250,6 +250,10 @@ pub(crate) struct TemplateContext<'a> {
     pub year: &'a str,
     pub genre: &'a str,
     pub track_number: &'a str,
+    pub composer: &'a str,
+    pub disc_number: &'a str,
+    pub total_tracks: &'a str,
+    pub comment: &'a str,
     pub file_name: Option<&'a str>,
     pub path: Option<&'a str>,
     pub playing: Option<&'a str>,
@@ -276,6 +280,7 @@ pub(crate) struct TemplateContext<'a> {
     pub technical_channel_from_channels: &'a str,
     pub technical_channel_to_channels: &'a str,
     pub technical_dithered: &'a str,
+    pub technical_quality: &'a str,
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -302,6 +307,7 @@ pub(crate) struct StatusTemplateFields<'a> {
     pub technical_channel_from_channels: &'a str,
     pub technical_channel_to_channels: &'a str,
     pub technical_dithered: &'a str,
+    pub technical_quality: &'a str,
 }
 
 impl<'a> TemplateContext<'a> {
@@ -330,6 +336,10 @@ impl<'a> TemplateContext<'a> {
             year,
             genre,
             track_number,
+            composer: "",
+            disc_number: "",
+            total_tracks: "",
+            comment: "",
             file_name,
             path: path_text,
             playing: None,
@@ -356,9 +366,24 @@ impl<'a> TemplateContext<'a> {
             technical_channel_from_channels: "",
             technical_channel_to_channels: "",
             technical_dithered: "",
+            technical_quality: "",
         }
     }
 
+    pub(crate) fn with_track_details(
+        mut self,
+        composer: &'a str,
+        disc_number: &'a str,
+        total_tracks: &'a str,
+        comment: &'a str,
+    ) -> Self {
+        self.composer = composer;
+        self.disc_number = disc_number;
+        self.total_tracks = total_tracks;
+        self.comment = comment;
+        self
+    }
+
     pub(crate) fn with_indicator_symbols(
         mut self,
         playing: Option<&'a str>,
@@ -392,6 +417,7 @@ impl<'a> TemplateContext<'a> {
         self.technical_channel_from_channels = fields.technical_channel_from_channels;
         self.technical_channel_to_channels = fields.technical_channel_to_channels;
         self.technical_dithered = fields.technical_dithered;
+        self.technical_quality = fields.technical_quality;
         self
     }
 
@@ -414,6 +440,12 @@ impl<'a> TemplateContext<'a> {
             }
             "genre" => Some(self.genre.to_string()),
             "track" | "track_number" | "tracknumber" => Some(self.track_number.to_string()),
+            "composer" => Some(self.composer.to_string()),
+            "disc" | "disc_number" | "discnumber" => Some(self.disc_number.to_string()),
+            "total_tracks" | "totaltracks" | "track_total" | "tracktotal" => {
+                Some(self.total_tracks.to_string())
+            }
+            "comment" => Some(self.comment.to_string()),
             "playing" => Some(self.playing.unwrap_or_default().to_string()),
             "favorite" => Some(self.favorite.unwrap_or_default().to_string()),
             "file_name" | "filename" | "file" => {
@@ -460,7 +492,10 @@ impl<'a> TemplateContext<'a> {
                 Some(self.technical_channel_to_channels.to_string())
             }
             "dithered" => Some(self.technical_dithered.to_string()),
-            "album_art" | "disc" | "disc_number" | "duration" => Some(String::new()),
+            "quality" => Some(self.technical_quality.to_string()),
+            "album_art" | "disc" | "disc_number" | "duration" | "tags" | "source" | "loudness"
+            | "true_peak" | "energy" | "bitrate" | "file_size" | "play_count" | "skip_count"
+            | "last_played" | "playlist_index" | "queue_position" => Some(String::new()),
             _ => None,
         }
     }
@@ -1178,6 +1213,10 @@ mod tests {
             year: "2026",
             genre: "Rock",
             track_number: "7",
+            composer: "Composer",
+            disc_number: "2",
+            total_tracks: "12",
+            comment: "Remastered",
             file_name: Some("track.flac"),
             path: Some("/music/track.flac"),
             playing: None,
@@ -1204,6 +1243,7 @@ mod tests {
             technical_channel_from_channels: "",
             technical_channel_to_channels: "",
             technical_dithered: "",
+            technical_quality: "",
         }
     }
 
@@ -1299,6 +1339,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_track_detail_placeholders_render_from_context() {
+        let rendered = render_template(
+            "{composer}|{disc}.{track_number} of {total_tracks}|{comment}",
+            &context("Song"),
+        );
+        assert_eq!(rendered.plain_text, "Composer|2.7 of 12|Remastered");
+    }
+
     #[test]
     fn test_if_condition_renders_true_branch() {
         let rendered = render_template("[if=title]{title}[else]No track[/if]", &context("Song"));
@@ -0,0 +1,287 @@
+//! Cell text for playlist column styles: number formats, row numbering, and middle
+//! elision.
+//!
+//! Slint only elides at the end of a text, so middle elision shortens the value before it
+//! reaches the UI, using the same average glyph width as column auto-sizing.
+
+use std::collections::HashMap;
+
+use crate::layout::{
+    ColumnElision, ColumnFontWeight, ColumnNumberFormat, ColumnTextAlignment,
+    PlaylistColumnStyleConfig,
+};
+
+/// Built-in playlist column formats rendered from probed track formats and file sizes.
+pub(crate) const DURATION_COLUMN_FORMAT: &str = "{duration}";
+pub(crate) const BITRATE_COLUMN_FORMAT: &str = "{bitrate}";
+pub(crate) const FILE_SIZE_COLUMN_FORMAT: &str = "{file_size}";
+/// Built-in playlist column formats numbering rows by playlist and queue position.
+pub(crate) const PLAYLIST_INDEX_COLUMN_FORMAT: &str = "{playlist_index}";
+pub(crate) const QUEUE_POSITION_COLUMN_FORMAT: &str = "{queue_position}";
+/// Built-in playlist column formats showing tag numbers, which sort by value.
+pub(crate) const TAG_NUMBER_COLUMN_FORMATS: [&str; 3] =
+    ["{track_number}", "{disc_number}", "{total_tracks}"];
+
+/// Average width of one 13px cell glyph, as used for column auto-sizing.
+const CELL_CHAR_WIDTH_PX: u32 = 7;
+const ELLIPSIS: char = '…';
+
+const ALIGNMENTS: [ColumnTextAlignment; 3] = [
+    ColumnTextAlignment::Left,
+    ColumnTextAlignment::Center,
+    ColumnTextAlignment::Right,
+];
+const ELISIONS: [ColumnElision; 2] = [ColumnElision::End, ColumnElision::Middle];
+const NUMBER_FORMATS: [ColumnNumberFormat; 3] = [
+    ColumnNumberFormat::Readable,
+    ColumnNumberFormat::Compact,
+    ColumnNumberFormat::Raw,
+];
+const FONT_WEIGHTS: [ColumnFontWeight; 4] = [
+    ColumnFontWeight::Light,
+    ColumnFontWeight::Normal,
+    ColumnFontWeight::SemiBold,
+    ColumnFontWeight::Bold,
+];
+
+fn option_index<T: PartialEq>(options: &[T], value: &T) -> i32 {
+    options
+        .iter()
+        .position(|option| option == value)
+        .unwrap_or_default() as i32
+}
+
+fn option_at<T: Copy + Default>(options: &[T], index: i32) -> T {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| options.get(index).copied())
+        .unwrap_or_default()
+}
+
+/// Combo box indices of a style in the column style dialog: alignment, elision,
+/// number format, and font weight.
+pub(crate) fn style_option_indices(style: PlaylistColumnStyleConfig) -> [i32; 4] {
+    [
+        option_index(&ALIGNMENTS, &style.alignment),
+        option_index(&ELISIONS, &style.elision),
+        option_index(&NUMBER_FORMATS, &style.number_format),
+        option_index(&FONT_WEIGHTS, &style.font_weight),
+    ]
+}
+
+/// Style picked in the column style dialog; out-of-range indices fall back to defaults.
+pub(crate) fn style_from_option_indices(indices: [i32; 4]) -> PlaylistColumnStyleConfig {
+    PlaylistColumnStyleConfig {
+        alignment: option_at(&ALIGNMENTS, indices[0]),
+        elision: option_at(&ELISIONS, indices[1]),
+        number_format: option_at(&NUMBER_FORMATS, indices[2]),
+        font_weight: option_at(&FONT_WEIGHTS, indices[3]),
+    }
+}
+
+/// CSS-style font weight of a column's cells.
+pub(crate) fn font_weight_value(weight: ColumnFontWeight) -> i32 {
+    match weight {
+        ColumnFontWeight::Light => 300,
+        ColumnFontWeight::Normal => 400,
+        ColumnFontWeight::SemiBold => 600,
+        ColumnFontWeight::Bold => 700,
+    }
+}
+
+/// Formats a track length.
+pub(crate) fn format_duration(duration_ms: u64, format: ColumnNumberFormat) -> String {
+    let total_secs = duration_ms / 1000;
+    if format == ColumnNumberFormat::Raw {
+        return total_secs.to_string();
+    }
+    let (hours, minutes, seconds) = (total_secs / 3600, (total_secs / 60) % 60, total_secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Formats an average bitrate given in kbps.
+pub(crate) fn format_bitrate(bitrate_kbps: u64, format: ColumnNumberFormat) -> String {
+    match format {
+        ColumnNumberFormat::Readable => format!("{bitrate_kbps} kbps"),
+        ColumnNumberFormat::Compact => format!("{bitrate_kbps}k"),
+        ColumnNumberFormat::Raw => bitrate_kbps.to_string(),
+    }
+}
+
+/// Formats a file size with decimal units.
+pub(crate) fn format_file_size(bytes: u64, format: ColumnNumberFormat) -> String {
+    const UNITS: [(&str, &str); 4] = [("KB", "K"), ("MB", "M"), ("GB", "G"), ("TB", "T")];
+    if format == ColumnNumberFormat::Raw {
+        return bytes.to_string();
+    }
+    let compact = format == ColumnNumberFormat::Compact;
+    if bytes < 1000 {
+        return if compact {
+            format!("{bytes}B")
+        } else {
+            format!("{bytes} B")
+        };
+    }
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = *next_unit;
+    }
+    let number = if value < 10.0 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.0}")
+    };
+    if compact {
+        format!("{number}{}", unit.1)
+    } else {
+        format!("{number} {}", unit.0)
+    }
+}
+
+/// Number shown for the row at 0-based `position`.
+pub(crate) fn row_number(position: usize, zero_based: bool) -> u64 {
+    position as u64 + u64::from(!zero_based)
+}
+
+/// Queue positions by track id: the playing track comes first, then the upcoming
+/// tracks in play order. A track upcoming twice keeps its earlier position.
+pub(crate) fn queue_positions<'a>(
+    playing_id: Option<&'a str>,
+    upcoming_ids: impl IntoIterator<Item = &'a str>,
+) -> HashMap<&'a str, usize> {
+    let mut positions = HashMap::new();
+    for (position, id) in playing_id.into_iter().chain(upcoming_ids).enumerate() {
+        positions.entry(id).or_insert(position);
+    }
+    positions
+}
+
+/// Sort key ordering numeric cells by value rather than by their text.
+pub(crate) fn numeric_sort_key(value: u64) -> String {
+    format!("{value:020}")
+}
+
+/// Sort key of a tag number cell such as `7` or `7/12`, by its leading digits; cells
+/// without a number sort first.
+pub(crate) fn tag_number_sort_key(text: &str) -> String {
+    let digits: String = text
+        .trim()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits
+        .parse::<u64>()
+        .map(numeric_sort_key)
+        .unwrap_or_default()
+}
+
+/// Number of characters that fit a column `width_px` wide.
+pub(crate) fn chars_for_width(width_px: u32) -> usize {
+    (width_px / CELL_CHAR_WIDTH_PX).max(1) as usize
+}
+
+/// Shortens `text` to `max_chars` by replacing its middle with an ellipsis.
+pub(crate) fn elide_middle(text: &str, max_chars: usize) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return None;
+    }
+    let kept = max_chars.saturating_sub(1);
+    let tail_len = kept / 2;
+    let head_len = kept - tail_len;
+    let mut elided: String = chars[..head_len].iter().collect();
+    elided.push(ELLIPSIS);
+    elided.extend(&chars[chars.len() - tail_len..]);
+    Some(elided)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_formats_for_duration_bitrate_and_size() {
+        assert_eq!(
+            format_duration(225_400, ColumnNumberFormat::Readable),
+            "3:45"
+        );
+        assert_eq!(
+            format_duration(3_723_000, ColumnNumberFormat::Compact),
+            "1:02:03"
+        );
+        assert_eq!(format_duration(225_400, ColumnNumberFormat::Raw), "225");
+        assert_eq!(
+            format_bitrate(320, ColumnNumberFormat::Readable),
+            "320 kbps"
+        );
+        assert_eq!(format_bitrate(320, ColumnNumberFormat::Compact), "320k");
+        assert_eq!(
+            format_file_size(7_412_345, ColumnNumberFormat::Readable),
+            "7.4 MB"
+        );
+        assert_eq!(
+            format_file_size(48_000_000, ColumnNumberFormat::Compact),
+            "48M"
+        );
+        assert_eq!(format_file_size(512, ColumnNumberFormat::Readable), "512 B");
+        assert_eq!(
+            format_file_size(7_412_345, ColumnNumberFormat::Raw),
+            "7412345"
+        );
+    }
+
+    #[test]
+    fn test_style_option_indices_round_trip() {
+        let style = PlaylistColumnStyleConfig {
+            alignment: ColumnTextAlignment::Right,
+            elision: ColumnElision::Middle,
+            number_format: ColumnNumberFormat::Raw,
+            font_weight: ColumnFontWeight::SemiBold,
+        };
+        assert_eq!(style_option_indices(style), [2, 1, 2, 2]);
+        assert_eq!(style_from_option_indices([2, 1, 2, 2]), style);
+        assert_eq!(
+            style_from_option_indices([-1, 9, 0, 1]),
+            PlaylistColumnStyleConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_queue_positions_start_at_the_playing_track() {
+        let positions = queue_positions(Some("b"), ["c", "a", "c"]);
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions["b"], 0);
+        assert_eq!(positions["c"], 1);
+        assert_eq!(positions["a"], 2);
+        assert_eq!(row_number(positions["a"], false), 3);
+        assert_eq!(row_number(positions["a"], true), 2);
+        assert!(queue_positions(None, []).is_empty());
+    }
+
+    #[test]
+    fn test_tag_number_sort_key_orders_by_value() {
+        assert!(tag_number_sort_key("9") < tag_number_sort_key("10"));
+        assert_eq!(tag_number_sort_key(" 7/12"), tag_number_sort_key("7"));
+        assert_eq!(tag_number_sort_key("A1"), "");
+        assert!(tag_number_sort_key("") < tag_number_sort_key("0"));
+    }
+
+    #[test]
+    fn test_elide_middle_keeps_both_ends() {
+        assert_eq!(elide_middle("short.flac", 20), None);
+        assert_eq!(
+            elide_middle("01 A Very Long Title.flac", 11).as_deref(),
+            Some("01 A ….flac")
+        );
+        assert_eq!(elide_middle("abc", 1).as_deref(), Some("…"));
+    }
+}
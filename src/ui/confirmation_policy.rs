@@ -0,0 +1,163 @@
+//! Central policy for confirmation prompts before destructive actions.
+//!
+//! Each [`ConfirmationAction`] maps to one flag in `[confirmations]`. Callers describe
+//! the action as a [`ConfirmationRequest`] carrying the bus message that performs it;
+//! the shared dialog either asks first or, when the flag is off ("Don't ask again"),
+//! the message is sent right away.
+
+use crate::config::ConfirmationsConfig;
+use crate::protocol::Message;
+
+/// Upper bound for the configured track-removal threshold.
+pub const MAX_REMOVE_TRACKS_THRESHOLD: u32 = 100_000;
+
+/// Destructive actions that can ask for confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationAction {
+    DeletePlaylist,
+    /// Removing more tracks from a playlist than the configured threshold.
+    RemoveTracks,
+    /// An edit that turns a synced OpenSubsonic playlist into a local-only one.
+    DetachRemotePlaylist,
+    /// Syncing a local playlist to OpenSubsonic, after which edits overwrite the server copy.
+    OverwriteRemotePlaylist,
+}
+
+/// Every action in settings display order.
+pub const ALL_ACTIONS: [ConfirmationAction; 4] = [
+    ConfirmationAction::DeletePlaylist,
+    ConfirmationAction::RemoveTracks,
+    ConfirmationAction::DetachRemotePlaylist,
+    ConfirmationAction::OverwriteRemotePlaylist,
+];
+
+/// One action waiting for the user's answer.
+#[derive(Debug, Clone)]
+pub struct ConfirmationRequest {
+    pub action: ConfirmationAction,
+    /// Number of items affected; compared against thresholds.
+    pub item_count: usize,
+    pub message: String,
+    /// Sent when the user confirms, or immediately when the action does not ask.
+    pub on_confirm: Message,
+    /// Sent when the user cancels.
+    pub on_cancel: Option<Message>,
+}
+
+/// Whether the prompt for `action` is enabled.
+pub fn is_confirmation_enabled(config: &ConfirmationsConfig, action: ConfirmationAction) -> bool {
+    match action {
+        ConfirmationAction::DeletePlaylist => config.delete_playlist,
+        ConfirmationAction::RemoveTracks => config.remove_tracks,
+        ConfirmationAction::DetachRemotePlaylist => config.detach_remote_playlist,
+        ConfirmationAction::OverwriteRemotePlaylist => config.overwrite_remote_playlist,
+    }
+}
+
+/// Turns the prompt for `action` on or off.
+pub fn set_confirmation_enabled(
+    config: &mut ConfirmationsConfig,
+    action: ConfirmationAction,
+    enabled: bool,
+) {
+    let slot = match action {
+        ConfirmationAction::DeletePlaylist => &mut config.delete_playlist,
+        ConfirmationAction::RemoveTracks => &mut config.remove_tracks,
+        ConfirmationAction::DetachRemotePlaylist => &mut config.detach_remote_playlist,
+        ConfirmationAction::OverwriteRemotePlaylist => &mut config.overwrite_remote_playlist,
+    };
+    *slot = enabled;
+}
+
+/// Whether `request` has to be confirmed before its message is sent.
+pub fn requires_confirmation(config: &ConfirmationsConfig, request: &ConfirmationRequest) -> bool {
+    if !is_confirmation_enabled(config, request.action) {
+        return false;
+    }
+    match request.action {
+        ConfirmationAction::RemoveTracks => {
+            request.item_count > config.remove_tracks_threshold as usize
+        }
+        ConfirmationAction::DeletePlaylist
+        | ConfirmationAction::DetachRemotePlaylist
+        | ConfirmationAction::OverwriteRemotePlaylist => true,
+    }
+}
+
+/// Label of the dialog's confirm button.
+pub fn confirm_button_text(action: ConfirmationAction) -> &'static str {
+    match action {
+        ConfirmationAction::DeletePlaylist => "Delete",
+        ConfirmationAction::RemoveTracks => "Remove",
+        ConfirmationAction::DetachRemotePlaylist => "Detach",
+        ConfirmationAction::OverwriteRemotePlaylist => "Sync",
+    }
+}
+
+/// Settings row describing when `action` asks.
+pub fn settings_label(config: &ConfirmationsConfig, action: ConfirmationAction) -> String {
+    match action {
+        ConfirmationAction::DeletePlaylist => "Deleting a playlist".to_string(),
+        ConfirmationAction::RemoveTracks => format!(
+            "Removing more than {} tracks from a playlist",
+            config.remove_tracks_threshold
+        ),
+        ConfirmationAction::DetachRemotePlaylist => {
+            "Detaching a playlist from OpenSubsonic sync".to_string()
+        }
+        ConfirmationAction::OverwriteRemotePlaylist => {
+            "Syncing a playlist to OpenSubsonic".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::PlaylistMessage;
+
+    fn request(action: ConfirmationAction, item_count: usize) -> ConfirmationRequest {
+        ConfirmationRequest {
+            action,
+            item_count,
+            message: String::new(),
+            on_confirm: Message::Playlist(PlaylistMessage::DeleteSelected),
+            on_cancel: None,
+        }
+    }
+
+    #[test]
+    fn test_requires_confirmation_respects_flags_and_track_threshold() {
+        let mut config = ConfirmationsConfig::default();
+        assert!(requires_confirmation(
+            &config,
+            &request(ConfirmationAction::DeletePlaylist, 1)
+        ));
+        assert!(!requires_confirmation(
+            &config,
+            &request(ConfirmationAction::RemoveTracks, 25)
+        ));
+        assert!(requires_confirmation(
+            &config,
+            &request(ConfirmationAction::RemoveTracks, 26)
+        ));
+
+        set_confirmation_enabled(&mut config, ConfirmationAction::RemoveTracks, false);
+        assert!(!requires_confirmation(
+            &config,
+            &request(ConfirmationAction::RemoveTracks, 500)
+        ));
+        assert!(!config.remove_tracks);
+    }
+
+    #[test]
+    fn test_every_action_toggles_its_own_flag() {
+        for action in ALL_ACTIONS {
+            let mut config = ConfirmationsConfig::default();
+            set_confirmation_enabled(&mut config, action, false);
+            for other in ALL_ACTIONS {
+                assert_eq!(is_confirmation_enabled(&config, other), other != action);
+            }
+        }
+    }
+}
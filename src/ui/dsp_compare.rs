@@ -0,0 +1,124 @@
+//! A/B comparison of the output DSP chain from the settings dialog.
+//!
+//! The plain switch plays the processed signal (A) or bypasses the chain (B). The blind
+//! test hides which of two choices, "1" or "2", runs the chain: the listener switches
+//! between them, leaves the one they prefer selected and reveals the answer. Neither
+//! state is persisted; the chain always starts processed.
+
+/// Labels of the two blind-test choices.
+const BLIND_CHOICE_LABELS: [&str; 2] = ["1", "2"];
+
+#[derive(Debug)]
+struct BlindTest {
+    /// Choice that runs the chain; the other one bypasses it.
+    processed_choice: usize,
+    selected_choice: usize,
+}
+
+/// State behind the settings dialog's A/B row.
+#[derive(Debug, Default)]
+pub struct DspComparison {
+    bypassed: bool,
+    blind_test: Option<BlindTest>,
+}
+
+impl DspComparison {
+    /// Whether the chain is bypassed for what the listener hears right now.
+    pub fn bypassed(&self) -> bool {
+        match &self.blind_test {
+            Some(test) => test.selected_choice != test.processed_choice,
+            None => self.bypassed,
+        }
+    }
+
+    /// Sets the plain A/B switch; a running blind test keeps control until revealed.
+    pub fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+
+    pub fn blind_test_active(&self) -> bool {
+        self.blind_test.is_some()
+    }
+
+    /// Choice the listener is hearing in a running blind test.
+    pub fn selected_blind_choice(&self) -> usize {
+        self.blind_test
+            .as_ref()
+            .map_or(0, |test| test.selected_choice)
+    }
+
+    /// Starts a blind test with the chain on `processed_choice`, playing choice "1" first.
+    pub fn start_blind_test(&mut self, processed_choice: usize) {
+        self.blind_test = Some(BlindTest {
+            processed_choice: processed_choice % BLIND_CHOICE_LABELS.len(),
+            selected_choice: 0,
+        });
+    }
+
+    pub fn select_blind_choice(&mut self, choice: usize) {
+        if let Some(test) = self.blind_test.as_mut() {
+            test.selected_choice = choice % BLIND_CHOICE_LABELS.len();
+        }
+    }
+
+    /// Ends the blind test, taking the selected choice as the preferred one, and
+    /// describes the result. The plain switch takes over again.
+    pub fn reveal_blind_test(&mut self) -> Option<String> {
+        let test = self.blind_test.take()?;
+        let processed = BLIND_CHOICE_LABELS[test.processed_choice];
+        let preferred = BLIND_CHOICE_LABELS[test.selected_choice];
+        let verdict = if test.selected_choice == test.processed_choice {
+            "the processed signal"
+        } else {
+            "the bypassed signal"
+        };
+        Some(format!(
+            "{processed} was processed. You preferred {preferred}, {verdict}."
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blind_test_hides_bypass_behind_choices() {
+        let mut comparison = DspComparison::default();
+        comparison.set_bypassed(true);
+        comparison.start_blind_test(1);
+        assert!(comparison.blind_test_active());
+        assert_eq!(comparison.selected_blind_choice(), 0);
+        assert!(comparison.bypassed());
+        comparison.select_blind_choice(1);
+        assert!(!comparison.bypassed());
+
+        // The plain switch waits until the test is revealed.
+        comparison.set_bypassed(false);
+        assert!(!comparison.bypassed());
+        comparison.select_blind_choice(0);
+        assert!(comparison.bypassed());
+    }
+
+    #[test]
+    fn test_reveal_reports_preference_and_returns_to_plain_switch() {
+        let mut comparison = DspComparison::default();
+        assert_eq!(comparison.reveal_blind_test(), None);
+
+        comparison.start_blind_test(0);
+        comparison.select_blind_choice(1);
+        assert_eq!(
+            comparison.reveal_blind_test().as_deref(),
+            Some("1 was processed. You preferred 2, the bypassed signal.")
+        );
+        assert!(!comparison.blind_test_active());
+        assert!(!comparison.bypassed());
+
+        comparison.start_blind_test(3);
+        comparison.select_blind_choice(1);
+        assert_eq!(
+            comparison.reveal_blind_test().as_deref(),
+            Some("2 was processed. You preferred 2, the processed signal.")
+        );
+    }
+}
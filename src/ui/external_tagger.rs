@@ -0,0 +1,109 @@
+//! Opening tracks in the external tag editor configured in settings.
+//!
+//! The command is split like a shell would for plain words and quotes, so both a bare
+//! program name (`kid3`) and a launcher with arguments (`flatpak run org.kde.kid3`)
+//! work. Track paths are appended after the configured arguments. Inside the Flatpak
+//! sandbox the editor is started on the host through `flatpak-spawn --host`.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Splits `command` into words; single or double quotes keep spaces inside one word.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for ch in command.chars() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => current.push(ch),
+            None if ch == '"' || ch == '\'' => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            None if ch.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+/// Program and arguments that open `paths` with `command`, or `None` when no command is
+/// configured.
+pub fn tagger_invocation(
+    command: &str,
+    paths: &[PathBuf],
+    in_flatpak: bool,
+) -> Option<(OsString, Vec<OsString>)> {
+    let mut words = split_command(command).into_iter().map(OsString::from);
+    let program = words.next()?;
+    let mut args: Vec<OsString> = words.collect();
+    args.extend(paths.iter().map(|path| path.clone().into_os_string()));
+    if in_flatpak {
+        args.insert(0, program);
+        args.insert(0, OsString::from("--host"));
+        return Some((OsString::from("flatpak-spawn"), args));
+    }
+    Some((program, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command_keeps_quoted_words_together() {
+        assert_eq!(
+            split_command("  flatpak run  org.kde.kid3 "),
+            ["flatpak", "run", "org.kde.kid3"]
+        );
+        assert_eq!(
+            split_command("\"/Applications/Mp3tag Pro/mp3tag\" --batch ''"),
+            ["/Applications/Mp3tag Pro/mp3tag", "--batch", ""]
+        );
+        assert!(split_command("   ").is_empty());
+    }
+
+    #[test]
+    fn test_tagger_invocation_appends_paths_and_escapes_the_sandbox() {
+        let paths = vec![
+            PathBuf::from("/music/a.flac"),
+            PathBuf::from("/music/b c.mp3"),
+        ];
+        assert_eq!(
+            tagger_invocation("puddletag", &paths, false),
+            Some((
+                OsString::from("puddletag"),
+                vec![
+                    OsString::from("/music/a.flac"),
+                    OsString::from("/music/b c.mp3")
+                ]
+            ))
+        );
+        assert_eq!(
+            tagger_invocation("kid3 -x", &paths[..1], true),
+            Some((
+                OsString::from("flatpak-spawn"),
+                vec![
+                    OsString::from("--host"),
+                    OsString::from("kid3"),
+                    OsString::from("-x"),
+                    OsString::from("/music/a.flac")
+                ]
+            ))
+        );
+        assert_eq!(tagger_invocation("", &paths, false), None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Folder tree model behind File Browser panels.
+//!
+//! Directories are listed one level at a time as they are expanded, so showing the
+//! panel never walks a whole music collection. The tree itself does no I/O: it names
+//! the directories it is waiting on, a worker thread lists them with
+//! [`list_directory`], and the results are handed back on the UI thread. Each level
+//! lists folders first, then supported audio files; tags of the selected file are read
+//! later on a worker thread and summarized by [`preview_text`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{metadata_tags::CommonTrackMetadata, sandbox_access};
+
+/// One folder or audio file inside a listed directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileBrowserEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// One visible row of the flattened tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileBrowserRow {
+    pub entry: FileBrowserEntry,
+    pub depth: usize,
+    pub expanded: bool,
+}
+
+/// Root used when none is configured: the first library folder, else the home folder.
+pub fn default_root(configured_root: &str, library_folders: &[String]) -> PathBuf {
+    let configured_root = configured_root.trim();
+    if !configured_root.is_empty() {
+        return PathBuf::from(configured_root);
+    }
+    library_folders
+        .iter()
+        .map(|folder| folder.trim())
+        .find(|folder| !folder.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("/"))
+}
+
+/// Folders and supported audio files directly inside `path`, folders first, each group
+/// sorted case-insensitively. Hidden entries and unreadable directories are skipped.
+pub fn list_directory(path: &Path) -> Vec<FileBrowserEntry> {
+    let Ok(read_dir) = sandbox_access::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<FileBrowserEntry> = read_dir
+        .flatten()
+        .filter_map(|dir_entry| {
+            let name = dir_entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            let path = dir_entry.path();
+            // Follow symlinks so linked music folders browse like real ones.
+            let is_dir = path.is_dir();
+            if !is_dir && !(path.is_file() && crate::is_supported_audio_file(&path)) {
+                return None;
+            }
+            Some(FileBrowserEntry { path, name, is_dir })
+        })
+        .collect();
+    entries.sort_by(|left, right| {
+        right
+            .is_dir
+            .cmp(&left.is_dir)
+            .then_with(|| left.name.to_lowercase().cmp(&right.name.to_lowercase()))
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    entries
+}
+
+/// Expandable folder tree under one root.
+#[derive(Debug, Default)]
+pub struct FileBrowserTree {
+    root: PathBuf,
+    expanded: HashSet<PathBuf>,
+    listings: HashMap<PathBuf, Vec<FileBrowserEntry>>,
+    /// Listed directories shown as they were until their refreshed listing arrives.
+    stale: HashSet<PathBuf>,
+    /// Directories handed out by [`Self::take_pending_listings`] and not yet listed.
+    requested: HashSet<PathBuf>,
+    /// Bumped on refresh and root changes so listings read before them are dropped.
+    generation: u64,
+    rows: Vec<FileBrowserRow>,
+    selected: Option<PathBuf>,
+}
+
+impl FileBrowserTree {
+    /// Tree showing the contents of `root`, with every folder collapsed. It has no rows
+    /// until the root listing is handed in.
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            ..Self::default()
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Switches to a new root, collapsing everything.
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = root;
+        self.expanded.clear();
+        self.listings.clear();
+        self.stale.clear();
+        self.selected = None;
+        self.restart_listings();
+        self.rebuild_rows();
+    }
+
+    /// Marks every listed directory for re-reading. Current rows stay until the new
+    /// listings arrive; expanded folders that are gone by then collapse.
+    pub fn refresh(&mut self) {
+        self.stale = self.listings.keys().cloned().collect();
+        self.restart_listings();
+    }
+
+    fn restart_listings(&mut self) {
+        self.requested.clear();
+        self.generation += 1;
+    }
+
+    /// Tag for listings read from now on; pass it back to [`Self::set_listing`].
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Visible directories, the root and expanded folders, that still need listing.
+    /// Each is returned once until it is listed or the tree is refreshed.
+    pub fn take_pending_listings(&mut self) -> Vec<PathBuf> {
+        let mut pending = Vec::new();
+        let mut queue = vec![self.root.clone()];
+        while let Some(directory) = queue.pop() {
+            match self.listings.get(&directory) {
+                Some(entries) => queue.extend(
+                    entries
+                        .iter()
+                        .filter(|entry| entry.is_dir && self.expanded.contains(&entry.path))
+                        .map(|entry| entry.path.clone()),
+                ),
+                None if self.requested.contains(&directory) => {}
+                None => pending.push(directory.clone()),
+            }
+            if self.stale.contains(&directory) && !self.requested.contains(&directory) {
+                pending.push(directory);
+            }
+        }
+        self.requested.extend(pending.iter().cloned());
+        pending
+    }
+
+    /// Stores the entries of `directory` read under `generation`. Returns false, and
+    /// changes nothing, when the tree was refreshed or re-rooted since.
+    pub fn set_listing(
+        &mut self,
+        generation: u64,
+        directory: PathBuf,
+        entries: Vec<FileBrowserEntry>,
+    ) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        self.requested.remove(&directory);
+        self.stale.remove(&directory);
+        self.expanded.retain(|path| {
+            path.parent() != Some(directory.as_path())
+                || entries
+                    .iter()
+                    .any(|entry| entry.is_dir && entry.path == *path)
+        });
+        self.listings.insert(directory, entries);
+        self.rebuild_rows();
+        true
+    }
+
+    pub fn rows(&self) -> &[FileBrowserRow] {
+        &self.rows
+    }
+
+    pub fn row(&self, index: usize) -> Option<&FileBrowserRow> {
+        self.rows.get(index)
+    }
+
+    pub fn selected(&self) -> Option<&Path> {
+        self.selected.as_deref()
+    }
+
+    /// Selects the row at `index`, expanding or collapsing it when it is a folder.
+    /// Returns the selected entry. A newly expanded folder shows its contents once its
+    /// listing is handed in.
+    pub fn activate(&mut self, index: usize) -> Option<FileBrowserEntry> {
+        let entry = self.rows.get(index)?.entry.clone();
+        self.selected = Some(entry.path.clone());
+        if entry.is_dir {
+            if !self.expanded.remove(&entry.path) {
+                self.expanded.insert(entry.path.clone());
+            }
+            self.rebuild_rows();
+        }
+        Some(entry)
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        self.append_rows(&self.root, 0, &mut rows);
+        self.rows = rows;
+    }
+
+    fn append_rows(&self, directory: &Path, depth: usize, rows: &mut Vec<FileBrowserRow>) {
+        let Some(entries) = self.listings.get(directory) else {
+            return;
+        };
+        for entry in entries {
+            let expanded = entry.is_dir && self.expanded.contains(&entry.path);
+            rows.push(FileBrowserRow {
+                entry: entry.clone(),
+                depth,
+                expanded,
+            });
+            if expanded {
+                self.append_rows(&entry.path, depth + 1, rows);
+            }
+        }
+    }
+}
+
+/// One-line tag summary shown for the selected file, e.g. `Title — Artist · Album (1999)`.
+pub fn preview_text(file_name: &str, metadata: Option<&CommonTrackMetadata>) -> String {
+    let Some(metadata) = metadata else {
+        return format!("{file_name}: no readable tags");
+    };
+    let title = if metadata.title.is_empty() {
+        file_name
+    } else {
+        metadata.title.as_str()
+    };
+    let mut text = title.to_string();
+    if !metadata.artist.is_empty() {
+        text.push_str(" — ");
+        text.push_str(&metadata.artist);
+    }
+    if !metadata.album.is_empty() {
+        text.push_str(" · ");
+        text.push_str(&metadata.album);
+    }
+    if !metadata.year.is_empty() {
+        text.push_str(&format!(" ({})", metadata.year));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_temp_dir(test_name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock should be after UNIX_EPOCH")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "roqtune_file_browser_{}_{}_{}",
+            test_name,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).expect("temp dir should be creatable");
+        dir
+    }
+
+    #[test]
+    fn test_list_directory_shows_folders_then_audio_files() {
+        let dir = unique_temp_dir("listing");
+        std::fs::create_dir(dir.join("b album")).expect("folder");
+        std::fs::create_dir(dir.join("A album")).expect("folder");
+        std::fs::create_dir(dir.join(".hidden")).expect("folder");
+        std::fs::write(dir.join("track.flac"), b"").expect("file");
+        std::fs::write(dir.join("cover.jpg"), b"").expect("file");
+        let names: Vec<String> = list_directory(&dir)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["A album", "b album", "track.flac"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Lists what the tree is waiting on, as the UI's worker does.
+    fn load_pending_listings(tree: &mut FileBrowserTree) {
+        loop {
+            let pending = tree.take_pending_listings();
+            if pending.is_empty() {
+                return;
+            }
+            for directory in pending {
+                let entries = list_directory(&directory);
+                assert!(tree.set_listing(tree.generation(), directory, entries));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_expands_folders_one_level_at_a_time() {
+        let dir = unique_temp_dir("tree");
+        std::fs::create_dir_all(dir.join("Artist").join("Album")).expect("folders");
+        std::fs::write(dir.join("Artist").join("Album").join("01.mp3"), b"").expect("file");
+        let mut tree = FileBrowserTree::new(dir.clone());
+        assert!(tree.rows().is_empty());
+        assert_eq!(tree.take_pending_listings(), [dir.clone()]);
+        assert!(tree.take_pending_listings().is_empty());
+        let entries = list_directory(&dir);
+        assert!(tree.set_listing(tree.generation(), dir.clone(), entries));
+        assert_eq!(tree.rows().len(), 1);
+
+        tree.activate(0);
+        assert_eq!(tree.rows().len(), 1);
+        load_pending_listings(&mut tree);
+        let rows: Vec<(&str, usize)> = tree
+            .rows()
+            .iter()
+            .map(|row| (row.entry.name.as_str(), row.depth))
+            .collect();
+        assert_eq!(rows, [("Artist", 0), ("Album", 1)]);
+        assert!(tree.rows()[0].expanded);
+
+        tree.activate(1);
+        load_pending_listings(&mut tree);
+        assert_eq!(tree.rows().len(), 3);
+        assert_eq!(tree.rows()[2].depth, 2);
+
+        tree.activate(0);
+        assert_eq!(tree.rows().len(), 1);
+        assert_eq!(tree.selected(), Some(dir.join("Artist").as_path()));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_refresh_keeps_rows_until_relisted_and_drops_older_listings() {
+        let dir = unique_temp_dir("refresh");
+        std::fs::create_dir_all(dir.join("Artist").join("Album")).expect("folders");
+        let mut tree = FileBrowserTree::new(dir.clone());
+        load_pending_listings(&mut tree);
+        tree.activate(0);
+        load_pending_listings(&mut tree);
+        assert_eq!(tree.rows().len(), 2);
+
+        let old_generation = tree.generation();
+        std::fs::remove_dir_all(dir.join("Artist")).expect("remove folder");
+        tree.refresh();
+        assert_eq!(tree.rows().len(), 2);
+        assert!(!tree.set_listing(old_generation, dir.clone(), Vec::new()));
+
+        let mut pending = tree.take_pending_listings();
+        pending.sort();
+        assert_eq!(pending, [dir.clone(), dir.join("Artist")]);
+        let entries = list_directory(&dir);
+        assert!(tree.set_listing(tree.generation(), dir.clone(), entries));
+        assert!(tree.rows().is_empty());
+        // The vanished folder collapsed, so it is not asked for again.
+        assert!(tree.take_pending_listings().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_default_root_prefers_configured_then_library_folder() {
+        assert_eq!(
+            default_root(" /music/inbox ", &["/music".to_string()]),
+            PathBuf::from("/music/inbox")
+        );
+        assert_eq!(
+            default_root("", &[" ".to_string(), "/music".to_string()]),
+            PathBuf::from("/music")
+        );
+    }
+
+    #[test]
+    fn test_preview_text_summarizes_tags() {
+        let metadata = CommonTrackMetadata {
+            title: "Song".to_string(),
+            artist: "Band".to_string(),
+            album: "Record".to_string(),
+            year: "1999".to_string(),
+            ..CommonTrackMetadata::default()
+        };
+        assert_eq!(
+            preview_text("01.flac", Some(&metadata)),
+            "Song — Band · Record (1999)"
+        );
+        assert_eq!(preview_text("01.flac", None), "01.flac: no readable tags");
+    }
+}
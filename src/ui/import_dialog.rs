@@ -0,0 +1,126 @@
+//! State helpers for the playlist import dialog.
+//!
+//! The dialog collects several files and folders ("roots") before importing, offers
+//! recently used locations from `ui.recent_import_locations`, and shows an estimated
+//! track count for the current roots before the import is confirmed.
+
+use std::path::{Path, PathBuf};
+
+/// Maximum number of recent import locations kept in config.
+pub const MAX_RECENT_IMPORT_LOCATIONS: usize = 8;
+
+/// Trims, de-duplicates, and caps persisted recent locations, keeping their order.
+pub fn sanitize_recent_locations(locations: &[String]) -> Vec<String> {
+    let mut sanitized: Vec<String> = Vec::with_capacity(locations.len());
+    for location in locations {
+        let location = location.trim();
+        if location.is_empty() || sanitized.iter().any(|existing| existing == location) {
+            continue;
+        }
+        sanitized.push(location.to_string());
+        if sanitized.len() == MAX_RECENT_IMPORT_LOCATIONS {
+            break;
+        }
+    }
+    sanitized
+}
+
+/// Location remembered for `root`: folders as-is, files by their parent folder.
+fn recent_location_for_root(root: &Path) -> Option<String> {
+    let folder = if root.is_file() { root.parent()? } else { root };
+    let location = folder.to_string_lossy().trim().to_string();
+    (!location.is_empty()).then_some(location)
+}
+
+/// Moves the locations of `roots` to the front of `recent`, most recent first.
+pub fn remember_recent_locations(recent: &[String], roots: &[PathBuf]) -> Vec<String> {
+    let mut locations: Vec<String> = roots
+        .iter()
+        .filter_map(|root| recent_location_for_root(root.as_path()))
+        .collect();
+    locations.extend(recent.iter().cloned());
+    sanitize_recent_locations(&locations)
+}
+
+/// Appends `new_roots` that are not already selected; returns whether anything changed.
+pub fn add_roots(roots: &mut Vec<PathBuf>, new_roots: impl IntoIterator<Item = PathBuf>) -> bool {
+    let previous_len = roots.len();
+    for root in new_roots {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots.len() != previous_len
+}
+
+/// Summary line under the root list; `track_count` is `None` while counting.
+pub fn estimate_text(root_count: usize, track_count: Option<usize>) -> String {
+    if root_count == 0 {
+        return "Add files or folders to import.".to_string();
+    }
+    let locations = if root_count == 1 {
+        "1 location".to_string()
+    } else {
+        format!("{root_count} locations")
+    };
+    match track_count {
+        None => format!("Counting tracks in {locations}..."),
+        Some(1) => format!("About 1 track in {locations}"),
+        Some(count) => format!("About {count} tracks in {locations}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remember_recent_locations_moves_roots_to_front_without_duplicates() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "roqtune_import_dialog_recent_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&temp_dir).expect("temp dir should be created");
+        let file_path = temp_dir.join("song.flac");
+        std::fs::write(&file_path, b"").expect("temp file should be written");
+        let temp_location = temp_dir.to_string_lossy().to_string();
+
+        let recent: Vec<String> = (0..MAX_RECENT_IMPORT_LOCATIONS)
+            .map(|index| format!("/music/{index}"))
+            .chain(std::iter::once(temp_location.clone()))
+            .collect();
+        let updated = remember_recent_locations(
+            &recent,
+            &[PathBuf::from("/music/3"), file_path, temp_dir.clone()],
+        );
+
+        assert_eq!(updated.len(), MAX_RECENT_IMPORT_LOCATIONS);
+        assert_eq!(updated[0], "/music/3");
+        assert_eq!(updated[1], temp_location);
+        assert_eq!(updated[2], "/music/0");
+        assert_eq!(
+            updated
+                .iter()
+                .filter(|location| **location == "/music/3")
+                .count(),
+            1
+        );
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_add_roots_skips_already_selected_paths() {
+        let mut roots = vec![PathBuf::from("/music/a")];
+        assert!(add_roots(
+            &mut roots,
+            [PathBuf::from("/music/a"), PathBuf::from("/music/b")]
+        ));
+        assert!(!add_roots(&mut roots, [PathBuf::from("/music/b")]));
+        assert_eq!(
+            roots,
+            vec![PathBuf::from("/music/a"), PathBuf::from("/music/b")]
+        );
+        assert_eq!(estimate_text(2, None), "Counting tracks in 2 locations...");
+        assert_eq!(estimate_text(1, Some(12)), "About 12 tracks in 1 location");
+    }
+}
@@ -0,0 +1,971 @@
+//! Registry of main-window keyboard shortcuts and the `?` help overlay rows built from it.
+//!
+//! Every binding records the exact condition the Slint key handler tests, and the tests
+//! below check the registry and the key handler against each other in both directions,
+//! so the help overlay cannot list a shortcut that does not exist or miss one that does.
+//!
+//! The Ctrl+letter commands are remappable: `ui.shortcut_keys` in `config.toml` (or an
+//! imported preset bundle) binds a [`ShortcutCommand`] to another letter, the key handler
+//! compares against the `shortcut_key_*` window properties, and the help overlay shows
+//! the letters in use.
+
+use slint::SharedString;
+
+use crate::{config::ShortcutKeyConfig, AppWindow, ShortcutHelpRowData};
+
+/// Where a shortcut applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutContext {
+    General,
+    Playlist,
+    Library,
+    Playback,
+    NowPlaying,
+}
+
+/// Main-window command bound to Ctrl plus a remappable letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutCommand {
+    LayoutEditor,
+    Search,
+    Undo,
+    SelectAll,
+    JumpToPlaying,
+    Copy,
+    Cut,
+    Paste,
+}
+
+/// Every remappable command in binding table order.
+pub const REMAPPABLE_COMMANDS: [ShortcutCommand; 8] = [
+    ShortcutCommand::LayoutEditor,
+    ShortcutCommand::Search,
+    ShortcutCommand::Undo,
+    ShortcutCommand::SelectAll,
+    ShortcutCommand::JumpToPlaying,
+    ShortcutCommand::Copy,
+    ShortcutCommand::Cut,
+    ShortcutCommand::Paste,
+];
+
+impl ShortcutCommand {
+    /// Letter bound to the command when the table does not remap it.
+    pub fn default_key(self) -> char {
+        match self {
+            ShortcutCommand::LayoutEditor => 'l',
+            ShortcutCommand::Search => 'f',
+            ShortcutCommand::Undo => 'z',
+            ShortcutCommand::SelectAll => 'a',
+            ShortcutCommand::JumpToPlaying => 'j',
+            ShortcutCommand::Copy => 'c',
+            ShortcutCommand::Cut => 'x',
+            ShortcutCommand::Paste => 'v',
+        }
+    }
+
+    /// Name of the command in the preset import dialog.
+    pub fn label(self) -> &'static str {
+        match self {
+            ShortcutCommand::LayoutEditor => "Layout editor",
+            ShortcutCommand::Search => "Search",
+            ShortcutCommand::Undo => "Undo / redo",
+            ShortcutCommand::SelectAll => "Select all",
+            ShortcutCommand::JumpToPlaying => "Jump to playing track",
+            ShortcutCommand::Copy => "Copy",
+            ShortcutCommand::Cut => "Cut",
+            ShortcutCommand::Paste => "Paste",
+        }
+    }
+
+    /// Stand-in for the command's letter in [`ShortcutBinding::keys`].
+    fn placeholder(self) -> &'static str {
+        match self {
+            ShortcutCommand::LayoutEditor => "{layout_editor}",
+            ShortcutCommand::Search => "{search}",
+            ShortcutCommand::Undo => "{undo}",
+            ShortcutCommand::SelectAll => "{select_all}",
+            ShortcutCommand::JumpToPlaying => "{jump_to_playing}",
+            ShortcutCommand::Copy => "{copy}",
+            ShortcutCommand::Cut => "{cut}",
+            ShortcutCommand::Paste => "{paste}",
+        }
+    }
+}
+
+/// Letter bound to `command` by the binding table `keys`.
+pub fn shortcut_key(keys: &[ShortcutKeyConfig], command: ShortcutCommand) -> char {
+    keys.iter()
+        .find(|entry| entry.command == command)
+        .and_then(|entry| entry.key.chars().next())
+        .unwrap_or_else(|| command.default_key())
+}
+
+/// Cleans a binding table. Keys must be single ASCII letters and are lowercased; a
+/// command or letter already claimed by an earlier entry is skipped. A command left
+/// without a letter gets its default one, or the first free letter when another command
+/// took it. Only bindings that differ from the defaults are kept.
+pub fn sanitize_shortcut_keys(keys: &[ShortcutKeyConfig]) -> Vec<ShortcutKeyConfig> {
+    let mut assigned: Vec<(ShortcutCommand, char)> = Vec::new();
+    for entry in keys {
+        let mut chars = entry.key.trim().chars();
+        let (Some(letter), None) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        if !letter.is_ascii_alphabetic() {
+            continue;
+        }
+        let letter = letter.to_ascii_lowercase();
+        if assigned
+            .iter()
+            .any(|(command, taken)| *command == entry.command || *taken == letter)
+        {
+            continue;
+        }
+        assigned.push((entry.command, letter));
+    }
+    let is_free = |assigned: &[(ShortcutCommand, char)], letter: char| {
+        !assigned.iter().any(|(_, taken)| *taken == letter)
+    };
+    let unassigned: Vec<ShortcutCommand> = REMAPPABLE_COMMANDS
+        .into_iter()
+        .filter(|command| !assigned.iter().any(|(taken, _)| taken == command))
+        .collect();
+    let mut displaced = Vec::new();
+    for command in unassigned {
+        if is_free(&assigned, command.default_key()) {
+            assigned.push((command, command.default_key()));
+        } else {
+            displaced.push(command);
+        }
+    }
+    for command in displaced {
+        let letter = ('a'..='z')
+            .find(|letter| is_free(&assigned, *letter))
+            .unwrap_or_else(|| command.default_key());
+        assigned.push((command, letter));
+    }
+    REMAPPABLE_COMMANDS
+        .into_iter()
+        .filter_map(|command| {
+            let (_, letter) = assigned.iter().find(|(taken, _)| *taken == command)?;
+            (*letter != command.default_key()).then(|| ShortcutKeyConfig {
+                command,
+                key: letter.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Sets the `shortcut_key_*` properties the key handler compares key presses with.
+pub fn apply_shortcut_keys_to_ui(ui: &AppWindow, keys: &[ShortcutKeyConfig]) {
+    for command in REMAPPABLE_COMMANDS {
+        let key = SharedString::from(shortcut_key(keys, command).to_string());
+        match command {
+            ShortcutCommand::LayoutEditor => ui.set_shortcut_key_layout_editor(key),
+            ShortcutCommand::Search => ui.set_shortcut_key_search(key),
+            ShortcutCommand::Undo => ui.set_shortcut_key_undo(key),
+            ShortcutCommand::SelectAll => ui.set_shortcut_key_select_all(key),
+            ShortcutCommand::JumpToPlaying => ui.set_shortcut_key_jump_to_playing(key),
+            ShortcutCommand::Copy => ui.set_shortcut_key_copy(key),
+            ShortcutCommand::Cut => ui.set_shortcut_key_cut(key),
+            ShortcutCommand::Paste => ui.set_shortcut_key_paste(key),
+        }
+    }
+}
+
+/// Binding table the key handler currently uses, read back from the window.
+pub fn shortcut_keys_from_ui(ui: &AppWindow) -> Vec<ShortcutKeyConfig> {
+    let keys: Vec<ShortcutKeyConfig> = REMAPPABLE_COMMANDS
+        .into_iter()
+        .map(|command| {
+            let key = match command {
+                ShortcutCommand::LayoutEditor => ui.get_shortcut_key_layout_editor(),
+                ShortcutCommand::Search => ui.get_shortcut_key_search(),
+                ShortcutCommand::Undo => ui.get_shortcut_key_undo(),
+                ShortcutCommand::SelectAll => ui.get_shortcut_key_select_all(),
+                ShortcutCommand::JumpToPlaying => ui.get_shortcut_key_jump_to_playing(),
+                ShortcutCommand::Copy => ui.get_shortcut_key_copy(),
+                ShortcutCommand::Cut => ui.get_shortcut_key_cut(),
+                ShortcutCommand::Paste => ui.get_shortcut_key_paste(),
+            };
+            ShortcutKeyConfig {
+                command,
+                key: key.to_string(),
+            }
+        })
+        .collect();
+    sanitize_shortcut_keys(&keys)
+}
+
+/// One keyboard shortcut handled by the main window key handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortcutBinding {
+    pub context: ShortcutContext,
+    /// Keys as shown in the help overlay; `{command}` placeholders stand for the
+    /// remappable letter of a [`ShortcutCommand`].
+    pub keys: &'static str,
+    pub action: &'static str,
+    /// Leading condition of the matching `if` in the `key-handler` of `roqtune.slint`.
+    pub condition: &'static str,
+}
+
+/// UI state deciding which shortcut contexts are currently active.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShortcutState {
+    pub library_mode: bool,
+    pub has_seek_markers: bool,
+}
+
+const fn binding(
+    context: ShortcutContext,
+    keys: &'static str,
+    action: &'static str,
+    condition: &'static str,
+) -> ShortcutBinding {
+    ShortcutBinding {
+        context,
+        keys,
+        action,
+        condition,
+    }
+}
+
+/// All main-window shortcuts in display order.
+pub const SHORTCUTS: &[ShortcutBinding] = &[
+    binding(
+        ShortcutContext::General,
+        "?",
+        "Show or hide this shortcut list",
+        "event.text == \"?\"",
+    ),
+    binding(
+        ShortcutContext::General,
+        "F6 / Ctrl+{layout_editor}",
+        "Toggle the layout editor",
+        "event.text == Key.F6 || (event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_layout_editor)",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Ctrl+{undo} / Ctrl+Shift+{undo}",
+        "Undo / redo the last edit",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_undo",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Ctrl+{select_all}",
+        "Select all tracks",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_select_all",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Ctrl+{jump_to_playing}",
+        "Jump to the playing track, switching playlists if needed",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_jump_to_playing",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Up / Down",
+        "Move the selection (Shift extends it)",
+        "event.text == Key.UpArrow",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Up / Down",
+        "Move the selection (Shift extends it)",
+        "event.text == Key.DownArrow",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Home / End",
+        "Jump to the first / last track",
+        "event.text == Key.Home",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Home / End",
+        "Jump to the first / last track",
+        "event.text == Key.End",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Page Up / Page Down",
+        "Move the selection by one page",
+        "event.text == Key.PageUp",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Page Up / Page Down",
+        "Move the selection by one page",
+        "event.text == Key.PageDown",
+    ),
+    binding(
+        ShortcutContext::General,
+        "Esc",
+        "Close the open menu, dialog, search, or filter view",
+        "event.text == Key.Escape",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "Ctrl+{search}",
+        "Search the playlist",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_search",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "Ctrl+{copy} / Ctrl+{cut} / Ctrl+{paste}",
+        "Copy / cut / paste tracks",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_copy",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "Ctrl+{copy} / Ctrl+{cut} / Ctrl+{paste}",
+        "Copy / cut / paste tracks",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_cut",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "Ctrl+{copy} / Ctrl+{cut} / Ctrl+{paste}",
+        "Copy / cut / paste tracks",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_paste",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "Delete",
+        "Remove selected tracks (or the playlist when the sidebar has focus)",
+        "event.text == Key.Delete",
+    ),
+    binding(
+        ShortcutContext::Playlist,
+        "F2",
+        "Rename the active playlist",
+        "event.text == Key.F2",
+    ),
+    binding(
+        ShortcutContext::Library,
+        "Ctrl+{search}",
+        "Search the library",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_search",
+    ),
+    binding(
+        ShortcutContext::Library,
+        "Ctrl+{copy}",
+        "Copy selected tracks",
+        "event.modifiers.control && event.text.to-lowercase() == root.shortcut_key_copy",
+    ),
+    binding(
+        ShortcutContext::Library,
+        "Delete",
+        "Remove selected tracks from the library",
+        "event.text == Key.Delete",
+    ),
+    binding(
+        ShortcutContext::Playback,
+        "Right / Left",
+        "Seek forward / back by the small step (hold to speed up)",
+        "event.text == Key.RightArrow",
+    ),
+    binding(
+        ShortcutContext::Playback,
+        "Right / Left",
+        "Seek forward / back by the small step (hold to speed up)",
+        "event.text == Key.LeftArrow",
+    ),
+    binding(
+        ShortcutContext::Playback,
+        "Shift+Right / Shift+Left",
+        "Seek forward / back by the large step",
+        "event.text == Key.RightArrow",
+    ),
+    binding(
+        ShortcutContext::Playback,
+        "Shift+Right / Shift+Left",
+        "Seek forward / back by the large step",
+        "event.text == Key.LeftArrow",
+    ),
+    binding(
+        ShortcutContext::NowPlaying,
+        "Ctrl+Right / Ctrl+Left",
+        "Jump to the next / previous seek bar marker",
+        "event.text == Key.RightArrow && event.modifiers.control",
+    ),
+    binding(
+        ShortcutContext::NowPlaying,
+        "Ctrl+Right / Ctrl+Left",
+        "Jump to the next / previous seek bar marker",
+        "event.text == Key.LeftArrow && event.modifiers.control",
+    ),
+];
+
+fn context_title(context: ShortcutContext) -> &'static str {
+    match context {
+        ShortcutContext::General => "General",
+        ShortcutContext::Playlist => "Playlist",
+        ShortcutContext::Library => "Library",
+        ShortcutContext::Playback => "Playback",
+        ShortcutContext::NowPlaying => "Now Playing",
+    }
+}
+
+fn is_context_active(context: ShortcutContext, state: ShortcutState) -> bool {
+    match context {
+        ShortcutContext::General => true,
+        ShortcutContext::Playlist => !state.library_mode,
+        ShortcutContext::Library => state.library_mode,
+        ShortcutContext::Playback => true,
+        ShortcutContext::NowPlaying => state.has_seek_markers,
+    }
+}
+
+/// Fills the `{command}` placeholders of `keys` with the letters bound in `shortcut_keys`.
+fn keys_label(keys: &str, shortcut_keys: &[ShortcutKeyConfig]) -> String {
+    let mut label = keys.to_string();
+    for command in REMAPPABLE_COMMANDS {
+        let letter = shortcut_key(shortcut_keys, command).to_ascii_uppercase();
+        label = label.replace(command.placeholder(), &letter.to_string());
+    }
+    label
+}
+
+/// Returns `(context title, keys, action)` rows for the active shortcuts, grouped by
+/// context in registry order, with the letters bound in `shortcut_keys`. Bindings
+/// sharing keys and action are listed once.
+pub fn active_shortcut_rows(
+    state: ShortcutState,
+    shortcut_keys: &[ShortcutKeyConfig],
+) -> Vec<(&'static str, String, &'static str)> {
+    let mut rows: Vec<(&'static str, String, &'static str)> = Vec::new();
+    for context in [
+        ShortcutContext::General,
+        ShortcutContext::Playlist,
+        ShortcutContext::Library,
+        ShortcutContext::Playback,
+        ShortcutContext::NowPlaying,
+    ] {
+        if !is_context_active(context, state) {
+            continue;
+        }
+        let title = context_title(context);
+        for shortcut in SHORTCUTS
+            .iter()
+            .filter(|shortcut| shortcut.context == context)
+        {
+            let row = (
+                title,
+                keys_label(shortcut.keys, shortcut_keys),
+                shortcut.action,
+            );
+            if !rows.contains(&row) {
+                rows.push(row);
+            }
+        }
+    }
+    rows
+}
+
+/// Builds the help overlay rows, with a header row before each context group.
+pub fn shortcut_help_rows(
+    state: ShortcutState,
+    shortcut_keys: &[ShortcutKeyConfig],
+) -> Vec<ShortcutHelpRowData> {
+    let mut rows = Vec::new();
+    let mut current_title = "";
+    for (title, keys, action) in active_shortcut_rows(state, shortcut_keys) {
+        if title != current_title {
+            current_title = title;
+            rows.push(ShortcutHelpRowData {
+                header: true,
+                keys: title.into(),
+                action: "".into(),
+            });
+        }
+        rows.push(ShortcutHelpRowData {
+            header: false,
+            keys: keys.into(),
+            action: action.into(),
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Conditions of the `if` statements in the main window key handler, in handler
+    /// order, with conditions spanning several lines joined into one.
+    fn key_handler_conditions() -> Vec<String> {
+        let slint_ui = include_str!("../roqtune.slint");
+        let start = slint_ui
+            .find("key-handler := FocusScope {")
+            .expect("main window key handler");
+        let end = start
+            + slint_ui[start..]
+                .find("key-released(event)")
+                .expect("key handler release callback");
+        let mut conditions = Vec::new();
+        let mut pending: Option<String> = None;
+        for line in slint_ui[start..end].lines().map(str::trim) {
+            let part = match pending.take() {
+                Some(mut condition) => {
+                    condition.push(' ');
+                    condition.push_str(line);
+                    condition
+                }
+                None => match line.strip_prefix("if (") {
+                    Some(condition) => condition.to_string(),
+                    None => continue,
+                },
+            };
+            match part.strip_suffix(") {") {
+                Some(condition) => conditions.push(condition.to_string()),
+                None => pending = Some(part),
+            }
+        }
+        conditions
+            .into_iter()
+            .filter(|condition| condition.contains("event.text"))
+            .collect()
+    }
+
+    /// Whether handler `condition` is the registered condition itself, or the registered
+    /// condition followed only by a guard on window state (`&& root.show_...`).
+    fn handles_binding(condition: &str, shortcut: &ShortcutBinding) -> bool {
+        match condition.strip_prefix(shortcut.condition) {
+            Some("") => true,
+            Some(guard) => guard.starts_with(" && ") && !guard.contains("event."),
+            None => false,
+        }
+    }
+
+    /// A key press as the handler sees it: the `event.text` token (`Key.F2`, `"f"`) and
+    /// the held modifiers.
+    struct KeyPress {
+        text: &'static str,
+        control: bool,
+        shift: bool,
+    }
+
+    fn press(text: &'static str, control: bool, shift: bool) -> KeyPress {
+        KeyPress {
+            text,
+            control,
+            shift,
+        }
+    }
+
+    #[derive(PartialEq)]
+    enum Value {
+        Text(String),
+        Flag(bool),
+    }
+
+    /// Evaluates a key handler condition for `key`, with the `root.shortcut_key_*`
+    /// letters taken from `shortcut_keys` and every other `root.` property unset.
+    struct ConditionEval<'a> {
+        tokens: Vec<String>,
+        position: usize,
+        key: &'a KeyPress,
+        shortcut_keys: &'a [ShortcutKeyConfig],
+    }
+
+    impl ConditionEval<'_> {
+        fn tokenize(condition: &str) -> Vec<String> {
+            let mut tokens = Vec::new();
+            let mut chars = condition.chars().peekable();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    ' ' => {}
+                    '(' | ')' | '!' => tokens.push(ch.to_string()),
+                    '|' | '&' | '=' => {
+                        chars.next();
+                        tokens.push(format!("{ch}{ch}"));
+                    }
+                    '"' => {
+                        let mut literal = String::from('"');
+                        for next in chars.by_ref() {
+                            literal.push(next);
+                            if next == '"' {
+                                break;
+                            }
+                        }
+                        tokens.push(literal);
+                    }
+                    _ => {
+                        let mut word = ch.to_string();
+                        while let Some(&next) = chars.peek() {
+                            if next.is_alphanumeric() || next == '.' || next == '_' || next == '-' {
+                                word.push(next);
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        tokens.push(word);
+                    }
+                }
+            }
+            tokens
+        }
+
+        fn matches(condition: &str, key: &KeyPress, shortcut_keys: &[ShortcutKeyConfig]) -> bool {
+            let mut eval = ConditionEval {
+                tokens: Self::tokenize(condition),
+                position: 0,
+                key,
+                shortcut_keys,
+            };
+            let value = eval.or_expr();
+            assert_eq!(eval.position, eval.tokens.len(), "unparsed `{condition}`");
+            value
+        }
+
+        fn next_is(&self, token: &str) -> bool {
+            self.tokens.get(self.position).map(String::as_str) == Some(token)
+        }
+
+        fn or_expr(&mut self) -> bool {
+            let mut value = self.and_expr();
+            while self.next_is("||") {
+                self.position += 1;
+                value |= self.and_expr();
+            }
+            value
+        }
+
+        fn and_expr(&mut self) -> bool {
+            let mut value = self.unary();
+            while self.next_is("&&") {
+                self.position += 1;
+                value &= self.unary();
+            }
+            value
+        }
+
+        fn unary(&mut self) -> bool {
+            if self.next_is("!") {
+                self.position += 1;
+                return !self.unary();
+            }
+            if self.next_is("(") {
+                self.position += 1;
+                let value = self.or_expr();
+                assert!(self.next_is(")"));
+                self.position += 1;
+                return value;
+            }
+            let lhs = self.operand();
+            if self.next_is("==") {
+                self.position += 1;
+                let rhs = self.operand();
+                return lhs == rhs;
+            }
+            lhs == Value::Flag(true)
+        }
+
+        fn operand(&mut self) -> Value {
+            let token = self.tokens[self.position].clone();
+            self.position += 1;
+            match token.as_str() {
+                "event.text" => Value::Text(self.key.text.to_string()),
+                "event.text.to-lowercase" => {
+                    assert!(self.next_is("("));
+                    self.position += 1;
+                    assert!(self.next_is(")"));
+                    self.position += 1;
+                    Value::Text(self.key.text.to_lowercase())
+                }
+                "event.modifiers.control" => Value::Flag(self.key.control),
+                "event.modifiers.shift" => Value::Flag(self.key.shift),
+                _ if token.starts_with("root.shortcut_key_") => {
+                    let placeholder = format!("{{{}}}", &token["root.shortcut_key_".len()..]);
+                    let command = REMAPPABLE_COMMANDS
+                        .into_iter()
+                        .find(|command| command.placeholder() == placeholder)
+                        .unwrap_or_else(|| panic!("unknown shortcut key property `{token}`"));
+                    Value::Text(format!("\"{}\"", shortcut_key(self.shortcut_keys, command)))
+                }
+                _ if token.starts_with("root.") => Value::Flag(false),
+                _ => Value::Text(token),
+            }
+        }
+    }
+
+    /// Action of the registered shortcut the key handler runs for `key` with the
+    /// binding table `shortcut_keys`, if any.
+    fn resolve_remapped_action(
+        key: &KeyPress,
+        state: ShortcutState,
+        shortcut_keys: &[ShortcutKeyConfig],
+    ) -> Option<&'static str> {
+        let condition = key_handler_conditions()
+            .into_iter()
+            .find(|condition| ConditionEval::matches(condition, key, shortcut_keys))?;
+        let candidates: Vec<&ShortcutBinding> = SHORTCUTS
+            .iter()
+            .filter(|shortcut| handles_binding(&condition, shortcut))
+            .filter(|shortcut| is_context_active(shortcut.context, state))
+            .collect();
+        candidates
+            .iter()
+            .find(|shortcut| shortcut.keys.starts_with("Shift+") == key.shift)
+            .or(candidates.first())
+            .map(|shortcut| shortcut.action)
+    }
+
+    /// Action of the registered shortcut the key handler runs for `key` with the default
+    /// letters, if any.
+    fn resolve_action(key: &KeyPress, state: ShortcutState) -> Option<&'static str> {
+        resolve_remapped_action(key, state, &[])
+    }
+
+    #[test]
+    fn test_registry_and_key_handler_cover_each_other() {
+        let conditions = key_handler_conditions();
+        for shortcut in SHORTCUTS {
+            assert!(
+                conditions
+                    .iter()
+                    .any(|condition| handles_binding(condition, shortcut)),
+                "registered shortcut {:?} is not handled by the key handler",
+                shortcut.keys
+            );
+        }
+        for condition in &conditions {
+            assert!(
+                SHORTCUTS
+                    .iter()
+                    .any(|shortcut| handles_binding(condition, shortcut)),
+                "key handler condition `{condition}` is missing from the shortcut registry"
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_presses_resolve_to_registered_actions() {
+        let playlist = ShortcutState {
+            library_mode: false,
+            has_seek_markers: true,
+        };
+        let library = ShortcutState {
+            library_mode: true,
+            has_seek_markers: true,
+        };
+        let cases = [
+            (
+                press("\"?\"", false, false),
+                playlist,
+                "Show or hide this shortcut list",
+            ),
+            (
+                press("Key.F6", false, false),
+                playlist,
+                "Toggle the layout editor",
+            ),
+            (
+                press("\"L\"", true, false),
+                playlist,
+                "Toggle the layout editor",
+            ),
+            (
+                press("\"z\"", true, false),
+                playlist,
+                "Undo / redo the last edit",
+            ),
+            (
+                press("\"Z\"", true, true),
+                playlist,
+                "Undo / redo the last edit",
+            ),
+            (press("\"f\"", true, false), playlist, "Search the playlist"),
+            (press("\"f\"", true, false), library, "Search the library"),
+            (
+                press("\"x\"", true, false),
+                playlist,
+                "Copy / cut / paste tracks",
+            ),
+            (press("\"c\"", true, false), library, "Copy selected tracks"),
+            (
+                press("\"j\"", true, false),
+                library,
+                "Jump to the playing track, switching playlists if needed",
+            ),
+            (
+                press("Key.Delete", false, false),
+                playlist,
+                "Remove selected tracks (or the playlist when the sidebar has focus)",
+            ),
+            (
+                press("Key.Delete", false, false),
+                library,
+                "Remove selected tracks from the library",
+            ),
+            (
+                press("Key.RightArrow", false, false),
+                playlist,
+                "Seek forward / back by the small step (hold to speed up)",
+            ),
+            (
+                press("Key.LeftArrow", false, true),
+                playlist,
+                "Seek forward / back by the large step",
+            ),
+            (
+                press("Key.RightArrow", true, false),
+                playlist,
+                "Jump to the next / previous seek bar marker",
+            ),
+            (
+                press("Key.PageDown", false, true),
+                library,
+                "Move the selection by one page",
+            ),
+            (
+                press("Key.F2", false, false),
+                playlist,
+                "Rename the active playlist",
+            ),
+        ];
+        for (key, state, action) in cases {
+            assert_eq!(
+                resolve_action(&key, state),
+                Some(action),
+                "{} control={} shift={}",
+                key.text,
+                key.control,
+                key.shift
+            );
+        }
+    }
+
+    #[test]
+    fn test_unbound_key_presses_resolve_to_nothing() {
+        let state = ShortcutState::default();
+        // A letter typed without Ctrl belongs to whatever text field has focus.
+        assert_eq!(resolve_action(&press("\"f\"", false, false), state), None);
+        assert_eq!(resolve_action(&press("\"q\"", true, false), state), None);
+        assert_eq!(resolve_action(&press("Key.F5", false, false), state), None);
+        // Esc only acts while something is open to close.
+        assert_eq!(
+            resolve_action(&press("Key.Escape", false, false), state),
+            None
+        );
+    }
+
+    #[test]
+    fn test_active_shortcut_rows_follow_collection_mode_and_markers() {
+        let playlist_rows = active_shortcut_rows(ShortcutState::default(), &[]);
+        assert_eq!(
+            playlist_rows.first(),
+            Some(&(
+                "General",
+                "?".to_string(),
+                "Show or hide this shortcut list"
+            ))
+        );
+        assert!(playlist_rows.contains(&(
+            "Playlist",
+            "F2".to_string(),
+            "Rename the active playlist"
+        )));
+        assert!(!playlist_rows
+            .iter()
+            .any(|(title, _, _)| *title == "Library"));
+        assert!(!playlist_rows
+            .iter()
+            .any(|(title, _, _)| *title == "Now Playing"));
+        assert_eq!(
+            playlist_rows
+                .iter()
+                .filter(|(_, keys, _)| *keys == "Up / Down")
+                .count(),
+            1
+        );
+
+        let library_rows = active_shortcut_rows(
+            ShortcutState {
+                library_mode: true,
+                has_seek_markers: true,
+            },
+            &[],
+        );
+        assert!(library_rows.contains(&("Library", "Ctrl+F".to_string(), "Search the library")));
+        assert!(library_rows.contains(&(
+            "Playback",
+            "Shift+Right / Shift+Left".to_string(),
+            "Seek forward / back by the large step"
+        )));
+        assert!(!library_rows
+            .iter()
+            .any(|(title, _, _)| *title == "Playlist"));
+        assert!(library_rows
+            .iter()
+            .any(|(title, _, _)| *title == "Now Playing"));
+    }
+
+    fn remap(command: ShortcutCommand, key: &str) -> ShortcutKeyConfig {
+        ShortcutKeyConfig {
+            command,
+            key: key.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_remapped_letters_drive_key_presses_and_help_rows() {
+        let state = ShortcutState::default();
+        let shortcut_keys = sanitize_shortcut_keys(&[
+            remap(ShortcutCommand::Search, "K"),
+            remap(ShortcutCommand::Paste, "b"),
+        ]);
+        assert_eq!(
+            resolve_remapped_action(&press("\"K\"", true, false), state, &shortcut_keys),
+            Some("Search the playlist")
+        );
+        assert_eq!(
+            resolve_remapped_action(&press("\"f\"", true, false), state, &shortcut_keys),
+            None
+        );
+        assert_eq!(
+            resolve_remapped_action(&press("\"b\"", true, false), state, &shortcut_keys),
+            Some("Copy / cut / paste tracks")
+        );
+
+        let rows = active_shortcut_rows(state, &shortcut_keys);
+        assert!(rows.contains(&("Playlist", "Ctrl+K".to_string(), "Search the playlist")));
+        assert!(rows.contains(&(
+            "Playlist",
+            "Ctrl+C / Ctrl+X / Ctrl+B".to_string(),
+            "Copy / cut / paste tracks"
+        )));
+        assert!(rows.contains(&(
+            "General",
+            "Ctrl+Z / Ctrl+Shift+Z".to_string(),
+            "Undo / redo the last edit"
+        )));
+    }
+
+    #[test]
+    fn test_sanitize_shortcut_keys_keeps_one_letter_per_command() {
+        assert_eq!(
+            sanitize_shortcut_keys(&[
+                remap(ShortcutCommand::Copy, "c"),
+                remap(ShortcutCommand::Undo, "Ctrl+U"),
+                remap(ShortcutCommand::SelectAll, "1"),
+            ]),
+            Vec::new(),
+            "default and invalid letters fall back to the defaults"
+        );
+        assert_eq!(
+            sanitize_shortcut_keys(&[
+                remap(ShortcutCommand::Search, "c"),
+                remap(ShortcutCommand::Search, "k"),
+                remap(ShortcutCommand::Paste, "C"),
+            ]),
+            vec![
+                remap(ShortcutCommand::Search, "c"),
+                remap(ShortcutCommand::Copy, "b"),
+            ],
+            "a taken letter is skipped and the displaced command gets the first free one"
+        );
+    }
+}
@@ -177,7 +177,7 @@ fn sanitize_button_actions(actions: &[i32]) -> Vec<i32> {
     actions
         .iter()
         .copied()
-        .filter(|action| (1..=12).contains(action))
+        .filter(|action| (1..=14).contains(action))
         .collect()
 }
 
@@ -1077,10 +1077,28 @@ pub(crate) fn with_updated_layout(previous: &Config, layout: LayoutConfig) -> Co
             volume: previous.ui.volume,
             playback_order: previous.ui.playback_order,
             repeat_mode: previous.ui.repeat_mode,
+            energy_ramp_arc: previous.ui.energy_ramp_arc,
+            recent_import_locations: previous.ui.recent_import_locations.clone(),
+            hi_res_min_sample_rate_hz: previous.ui.hi_res_min_sample_rate_hz,
+            hi_res_min_bit_depth: previous.ui.hi_res_min_bit_depth,
+            seek_small_step_secs: previous.ui.seek_small_step_secs,
+            seek_large_step_secs: previous.ui.seek_large_step_secs,
+            zero_based_row_numbers: previous.ui.zero_based_row_numbers,
+            transport_bar_placement: previous.ui.transport_bar_placement,
+            scale_override_percent: previous.ui.scale_override_percent,
+            file_browser_root: previous.ui.file_browser_root.clone(),
+            external_tagger_command: previous.ui.external_tagger_command.clone(),
+            library_album_grid: previous.ui.library_album_grid,
+            shortcut_keys: previous.ui.shortcut_keys.clone(),
         },
         library: previous.library.clone(),
         buffering: previous.buffering.clone(),
         integrations: previous.integrations.clone(),
+        announcements: previous.announcements.clone(),
+        power: previous.power.clone(),
+        performance: previous.performance.clone(),
+        confirmations: previous.confirmations.clone(),
+        dsp: previous.dsp.clone(),
     })
 }
 
@@ -1,6 +1,16 @@
 //! UI-focused helper modules shared by callback/runtime wiring.
 
+pub(crate) mod column_format;
+pub(crate) mod confirmation_policy;
+pub(crate) mod dsp_compare;
+pub(crate) mod external_tagger;
+pub(crate) mod file_browser;
+pub(crate) mod import_dialog;
+pub(crate) mod keyboard_shortcuts;
 pub(crate) mod layout_editor_state;
 pub(crate) mod playlist_columns;
+pub(crate) mod preset_bundle;
+pub(crate) mod seek_steps;
 #[cfg(test)]
 mod slint_contract_tests;
+pub(crate) mod window_scale;
@@ -9,7 +9,11 @@ use slint::{Model, ModelRc, VecModel};
 
 use crate::{
     config::{self, PlaylistColumnConfig, UiConfig},
-    layout::{LayoutConfig, PlaylistColumnWidthOverrideConfig},
+    layout::{
+        LayoutConfig, PlaylistColumnStyleConfig, PlaylistColumnStyleOverrideConfig,
+        PlaylistColumnWidthOverrideConfig,
+    },
+    ui::column_format,
     AppWindow,
 };
 
@@ -36,6 +40,21 @@ pub(crate) fn is_playing_builtin_column(column: &PlaylistColumnConfig) -> bool {
     !column.custom && normalize_column_format(&column.format) == "{playing}"
 }
 
+/// Enabled columns that show text, in display order; the album-art, favorite and playing
+/// indicator columns are left out.
+pub(crate) fn text_columns(columns: &[PlaylistColumnConfig]) -> Vec<PlaylistColumnConfig> {
+    columns
+        .iter()
+        .filter(|column| {
+            column.enabled
+                && !is_album_art_builtin_column(column)
+                && !is_favorite_builtin_column(column)
+                && !is_playing_builtin_column(column)
+        })
+        .cloned()
+        .collect()
+}
+
 /// Maps a column to its UI kind code used by Slint models.
 pub(crate) fn playlist_column_kind(column: &PlaylistColumnConfig) -> i32 {
     if is_album_art_builtin_column(column) {
@@ -189,6 +208,9 @@ pub(crate) fn playlist_column_width_bounds_with_album_art(
     if normalized_format == "{track}"
         || normalized_format == "{track_number}"
         || normalized_format == "{tracknumber}"
+        || normalized_format == "{total_tracks}"
+        || normalized_format == "{playlist_index}"
+        || normalized_format == "{queue_position}"
         || normalized_name == "track #"
         || normalized_name == "track"
     {
@@ -229,6 +251,7 @@ pub(crate) fn playlist_column_width_bounds_with_album_art(
     if normalized_format == "{artist}"
         || normalized_format == "{album_artist}"
         || normalized_format == "{albumartist}"
+        || normalized_format == "{composer}"
         || normalized_name == "artist"
         || normalized_name == "album artist"
     {
@@ -345,9 +368,22 @@ pub(crate) fn apply_playlist_columns_to_ui(ui: &AppWindow, config: &crate::confi
         .map(|column| column.custom)
         .collect();
     let visible_kinds = visible_playlist_column_kinds(&config.ui.playlist_columns);
+    let visible_font_weights: Vec<i32> = config
+        .ui
+        .playlist_columns
+        .iter()
+        .filter(|column| column.enabled)
+        .map(|column| {
+            let style = layout_column_style(&config.ui.layout, &playlist_column_key(column));
+            column_format::font_weight_value(style.font_weight)
+        })
+        .collect();
 
     ui.set_playlist_visible_column_headers(ModelRc::from(Rc::new(VecModel::from(visible_headers))));
     ui.set_playlist_visible_column_kinds(ModelRc::from(Rc::new(VecModel::from(visible_kinds))));
+    ui.set_playlist_visible_column_font_weights(ModelRc::from(Rc::new(VecModel::from(
+        visible_font_weights,
+    ))));
     ui.set_playlist_column_menu_labels(ModelRc::from(Rc::new(VecModel::from(menu_labels))));
     ui.set_playlist_column_menu_checked(ModelRc::from(Rc::new(VecModel::from(menu_checked))));
     ui.set_playlist_column_menu_is_custom(ModelRc::from(Rc::new(VecModel::from(menu_is_custom))));
@@ -418,6 +454,60 @@ pub(crate) fn clear_layout_column_width_override(layout: &mut LayoutConfig, colu
         .retain(|entry| entry.column_key != column_key);
 }
 
+/// Drops styles of unknown columns, repeated keys, and styles equal to the default.
+pub(crate) fn sanitize_layout_column_styles(
+    styles: &[PlaylistColumnStyleOverrideConfig],
+    columns: &[PlaylistColumnConfig],
+) -> Vec<PlaylistColumnStyleOverrideConfig> {
+    let known_keys: HashSet<String> = columns.iter().map(playlist_column_key).collect();
+    let mut seen_keys = HashSet::new();
+    styles
+        .iter()
+        .filter_map(|entry| {
+            let key = entry.column_key.trim();
+            (!entry.style.is_default()
+                && known_keys.contains(key)
+                && seen_keys.insert(key.to_string()))
+            .then(|| PlaylistColumnStyleOverrideConfig {
+                column_key: key.to_string(),
+                style: entry.style,
+            })
+        })
+        .collect()
+}
+
+/// Returns the stored style for a column key, or the default style.
+pub(crate) fn layout_column_style(
+    layout: &LayoutConfig,
+    column_key: &str,
+) -> PlaylistColumnStyleConfig {
+    layout
+        .playlist_column_styles
+        .iter()
+        .find(|entry| entry.column_key == column_key)
+        .map(|entry| entry.style)
+        .unwrap_or_default()
+}
+
+/// Inserts or updates the style of a column; the default style removes the entry.
+pub(crate) fn upsert_layout_column_style(
+    layout: &mut LayoutConfig,
+    column_key: &str,
+    style: PlaylistColumnStyleConfig,
+) {
+    layout
+        .playlist_column_styles
+        .retain(|entry| entry.column_key != column_key);
+    if !style.is_default() {
+        layout
+            .playlist_column_styles
+            .push(PlaylistColumnStyleOverrideConfig {
+                column_key: column_key.to_string(),
+                style,
+            });
+    }
+}
+
 /// Reorders visible columns while preserving hidden-column relative placement.
 pub(crate) fn reorder_visible_playlist_columns(
     columns: &[PlaylistColumnConfig],
@@ -547,6 +637,10 @@ mod tests {
     use slint::{Model, ModelRc, VecModel};
 
     use crate::config::PlaylistColumnConfig;
+    use crate::layout::{
+        ColumnFontWeight, LayoutConfig, PlaylistColumnStyleConfig,
+        PlaylistColumnStyleOverrideConfig,
+    };
 
     use super::{
         clamp_width_for_visible_column, default_album_art_column_width_bounds,
@@ -555,7 +649,8 @@ mod tests {
         playlist_column_width_bounds_with_album_art, playlist_column_widths_from_model,
         reorder_visible_playlist_columns, resolve_playlist_header_column_from_x,
         resolve_playlist_header_divider_from_x, resolve_playlist_header_gap_from_x,
-        sanitize_playlist_columns, visible_playlist_column_kinds, ColumnWidthBounds,
+        sanitize_layout_column_styles, sanitize_playlist_columns, upsert_layout_column_style,
+        visible_playlist_column_kinds, ColumnWidthBounds,
     };
 
     #[test]
@@ -871,6 +966,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_column_styles_drop_defaults_unknown_columns_and_duplicates() {
+        let bold = PlaylistColumnStyleConfig {
+            font_weight: ColumnFontWeight::Bold,
+            ..PlaylistColumnStyleConfig::default()
+        };
+        let mut layout = LayoutConfig::default();
+        upsert_layout_column_style(&mut layout, "{title}", bold);
+        upsert_layout_column_style(&mut layout, "{artist}", bold);
+        upsert_layout_column_style(
+            &mut layout,
+            "{artist}",
+            PlaylistColumnStyleConfig::default(),
+        );
+        assert_eq!(layout.playlist_column_styles.len(), 1);
+
+        let entry = |key: &str| PlaylistColumnStyleOverrideConfig {
+            column_key: key.to_string(),
+            style: bold,
+        };
+        let sanitized = sanitize_layout_column_styles(
+            &[
+                entry("{title}"),
+                entry("{title}"),
+                entry("custom:Gone|{path}"),
+                PlaylistColumnStyleOverrideConfig {
+                    column_key: "{album}".to_string(),
+                    style: PlaylistColumnStyleConfig::default(),
+                },
+            ],
+            &layout.playlist_columns,
+        );
+        assert_eq!(sanitized, vec![entry("{title}")]);
+    }
+
     #[test]
     fn test_sanitize_playlist_columns_restores_builtins_and_preserves_custom() {
         let custom = PlaylistColumnConfig {
@@ -0,0 +1,622 @@
+//! Shareable preset bundles of playlist columns, the workspace layout, the color scheme,
+//! and keyboard shortcuts.
+//!
+//! A bundle is one TOML file holding a `layout.toml`-shaped `[layout]` table, the
+//! remapped Ctrl+letter shortcuts, and the list of sections it carries, so "foobar-like"
+//! or "iTunes-like" setups can be passed around. Importing applies only the picked
+//! sections. Custom columns are merged rather than replaced: the user's own custom
+//! columns stay available (hidden when the preset does not show them), and a preset
+//! column named like an existing one with another format is resolved with
+//! [`ColumnConflictResolution`]. Shortcuts the user remapped to another letter than the
+//! preset's are resolved with [`ShortcutConflictResolution`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::{
+    config::{Config, PlaylistColumnConfig, ShortcutKeyConfig},
+    layout::{
+        self, LayoutConfig, PlaylistColumnStyleOverrideConfig, PlaylistColumnWidthOverrideConfig,
+    },
+    ui::{
+        keyboard_shortcuts::{self, ShortcutCommand},
+        playlist_columns::playlist_column_key,
+    },
+};
+
+/// Value of the `format` key identifying a preset bundle file.
+const PRESET_FILE_FORMAT: &str = "roqtune-preset";
+/// Newest bundle version this build reads and the one it writes.
+const PRESET_FILE_VERSION: u32 = 1;
+/// Extension offered by the export and import file dialogs.
+pub const PRESET_FILE_EXTENSION: &str = "toml";
+
+/// Part of the setup a bundle can carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetSection {
+    /// Playlist column list, widths, and styles.
+    Columns,
+    /// Panel split tree and per-panel settings.
+    Workspace,
+    /// Color scheme and custom colors.
+    Theme,
+    /// Letters of the remappable Ctrl+letter shortcuts.
+    Shortcuts,
+}
+
+/// Every section in import dialog order.
+pub const ALL_SECTIONS: [PresetSection; 4] = [
+    PresetSection::Columns,
+    PresetSection::Workspace,
+    PresetSection::Theme,
+    PresetSection::Shortcuts,
+];
+
+/// What to do with a preset custom column named like an existing one with another format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnConflictResolution {
+    /// Keep both, renaming the preset column after the preset.
+    #[default]
+    KeepBoth,
+    /// Replace the existing column with the preset one.
+    UsePreset,
+    /// Keep the existing column in the preset column's place.
+    KeepMine,
+}
+
+/// Every resolution in import dialog order.
+pub const CONFLICT_RESOLUTIONS: [ColumnConflictResolution; 3] = [
+    ColumnConflictResolution::KeepBoth,
+    ColumnConflictResolution::UsePreset,
+    ColumnConflictResolution::KeepMine,
+];
+
+/// What to do with a shortcut the user bound to another letter than the preset does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShortcutConflictResolution {
+    /// Use the preset's letter.
+    #[default]
+    UsePreset,
+    /// Keep the user's letter; a preset shortcut on the same letter moves elsewhere.
+    KeepMine,
+}
+
+/// Every shortcut resolution in import dialog order.
+pub const SHORTCUT_CONFLICT_RESOLUTIONS: [ShortcutConflictResolution; 2] = [
+    ShortcutConflictResolution::UsePreset,
+    ShortcutConflictResolution::KeepMine,
+];
+
+/// A parsed preset bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetBundle {
+    pub name: String,
+    pub sections: Vec<PresetSection>,
+    pub layout: LayoutConfig,
+    /// Remapped shortcuts in `ui.shortcut_keys` form; empty keeps every default letter.
+    pub shortcut_keys: Vec<ShortcutKeyConfig>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct PresetBundleFile {
+    format: String,
+    version: u32,
+    name: String,
+    sections: Vec<PresetSection>,
+    #[serde(default)]
+    shortcut_keys: Vec<ShortcutKeyConfig>,
+    layout: LayoutConfig,
+}
+
+/// Import dialog label of a section.
+pub fn section_label(section: PresetSection) -> &'static str {
+    match section {
+        PresetSection::Columns => "Playlist columns",
+        PresetSection::Workspace => "Workspace layout",
+        PresetSection::Theme => "Color scheme",
+        PresetSection::Shortcuts => "Keyboard shortcuts",
+    }
+}
+
+/// Import dialog label of a conflict resolution.
+pub fn resolution_label(resolution: ColumnConflictResolution) -> &'static str {
+    match resolution {
+        ColumnConflictResolution::KeepBoth => "Keep both",
+        ColumnConflictResolution::UsePreset => "Use the preset's",
+        ColumnConflictResolution::KeepMine => "Keep mine",
+    }
+}
+
+/// Import dialog label of a shortcut conflict resolution.
+pub fn shortcut_resolution_label(resolution: ShortcutConflictResolution) -> &'static str {
+    match resolution {
+        ShortcutConflictResolution::UsePreset => "Use the preset's shortcuts",
+        ShortcutConflictResolution::KeepMine => "Keep my shortcuts",
+    }
+}
+
+/// Builds a bundle of every section from the current configuration.
+pub fn bundle_from_config(config: &Config, name: &str) -> PresetBundle {
+    let mut layout = config.ui.layout.clone();
+    layout.playlist_columns = config.ui.playlist_columns.clone();
+    layout.selected_leaf_id = None;
+    PresetBundle {
+        name: name.trim().to_string(),
+        sections: ALL_SECTIONS.to_vec(),
+        layout,
+        shortcut_keys: config.ui.shortcut_keys.clone(),
+    }
+}
+
+/// Serializes `bundle` to bundle file text.
+pub fn serialize_bundle(bundle: &PresetBundle) -> Result<String, String> {
+    toml::to_string(&PresetBundleFile {
+        format: PRESET_FILE_FORMAT.to_string(),
+        version: PRESET_FILE_VERSION,
+        name: bundle.name.clone(),
+        sections: bundle.sections.clone(),
+        shortcut_keys: bundle.shortcut_keys.clone(),
+        layout: bundle.layout.clone(),
+    })
+    .map_err(|err| format!("Could not write the preset: {err}"))
+}
+
+/// Parses bundle file text, rejecting other files and newer bundle versions.
+pub fn parse_bundle(text: &str) -> Result<PresetBundle, String> {
+    let file: PresetBundleFile =
+        toml::from_str(text).map_err(|_| "This file is not a roqtune preset".to_string())?;
+    if file.format != PRESET_FILE_FORMAT {
+        return Err("This file is not a roqtune preset".to_string());
+    }
+    if file.version > PRESET_FILE_VERSION {
+        return Err("This preset was made by a newer version of roqtune".to_string());
+    }
+    let mut sections = Vec::new();
+    for section in file.sections {
+        if !sections.contains(&section) {
+            sections.push(section);
+        }
+    }
+    if sections.is_empty() {
+        return Err("This preset is empty".to_string());
+    }
+    let name = file.name.trim();
+    Ok(PresetBundle {
+        name: if name.is_empty() {
+            "Imported preset".to_string()
+        } else {
+            name.to_string()
+        },
+        sections,
+        layout: file.layout,
+        shortcut_keys: keyboard_shortcuts::sanitize_shortcut_keys(&file.shortcut_keys),
+    })
+}
+
+/// Writes `bundle` to `path`.
+pub fn write_bundle_file(bundle: &PresetBundle, path: &Path) -> Result<(), String> {
+    let text = serialize_bundle(bundle)?;
+    std::fs::write(path, text).map_err(|err| format!("Could not write {}: {err}", path.display()))
+}
+
+/// Reads and parses the bundle at `path`.
+pub fn read_bundle_file(path: &Path) -> Result<PresetBundle, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    parse_bundle(&text)
+}
+
+/// Names of preset custom columns that clash with an existing custom column of the same
+/// name but another format.
+pub fn column_conflicts(current: &[PlaylistColumnConfig], bundle: &PresetBundle) -> Vec<String> {
+    if !bundle.sections.contains(&PresetSection::Columns) {
+        return Vec::new();
+    }
+    let current_formats: HashMap<&str, &str> = current
+        .iter()
+        .filter(|column| column.custom)
+        .map(|column| (column.name.trim(), column.format.trim()))
+        .collect();
+    let mut conflicts = Vec::new();
+    for column in bundle.layout.playlist_columns.iter().filter(|c| c.custom) {
+        let name = column.name.trim();
+        if current_formats
+            .get(name)
+            .is_some_and(|format| *format != column.format.trim())
+            && !conflicts.iter().any(|conflict| conflict == name)
+        {
+            conflicts.push(name.to_string());
+        }
+    }
+    conflicts
+}
+
+/// Remappable commands the user bound to a letter other than both the default and the
+/// preset's.
+pub fn shortcut_conflicts(
+    current: &[ShortcutKeyConfig],
+    bundle: &PresetBundle,
+) -> Vec<ShortcutCommand> {
+    if !bundle.sections.contains(&PresetSection::Shortcuts) {
+        return Vec::new();
+    }
+    keyboard_shortcuts::REMAPPABLE_COMMANDS
+        .into_iter()
+        .filter(|command| {
+            let mine = keyboard_shortcuts::shortcut_key(current, *command);
+            mine != command.default_key()
+                && mine != keyboard_shortcuts::shortcut_key(&bundle.shortcut_keys, *command)
+        })
+        .collect()
+}
+
+/// Merges the preset column list into the current one. The result follows the preset's
+/// order and visibility; current custom columns the preset lacks are appended hidden.
+/// Returns the merged columns and the renames applied to preset column keys.
+fn merge_columns(
+    current: &[PlaylistColumnConfig],
+    imported: &[PlaylistColumnConfig],
+    resolution: ColumnConflictResolution,
+    preset_name: &str,
+) -> (Vec<PlaylistColumnConfig>, HashMap<String, String>) {
+    let current_by_name: HashMap<&str, &PlaylistColumnConfig> = current
+        .iter()
+        .filter(|column| column.custom)
+        .map(|column| (column.name.trim(), column))
+        .collect();
+    let mut merged: Vec<PlaylistColumnConfig> = Vec::new();
+    let mut renamed_keys = HashMap::new();
+    for column in imported {
+        let existing = current_by_name
+            .get(column.name.trim())
+            .filter(|existing| column.custom && existing.format.trim() != column.format.trim());
+        let merged_column = match (existing, resolution) {
+            (None, _) | (Some(_), ColumnConflictResolution::UsePreset) => column.clone(),
+            (Some(existing), ColumnConflictResolution::KeepMine) => PlaylistColumnConfig {
+                enabled: column.enabled,
+                ..(*existing).clone()
+            },
+            (Some(_), ColumnConflictResolution::KeepBoth) => {
+                let renamed = PlaylistColumnConfig {
+                    name: format!("{} ({})", column.name.trim(), preset_name),
+                    ..column.clone()
+                };
+                renamed_keys.insert(playlist_column_key(column), playlist_column_key(&renamed));
+                renamed
+            }
+        };
+        merged.push(merged_column);
+    }
+
+    let merged_keys: HashSet<String> = merged.iter().map(playlist_column_key).collect();
+    let replaced_names: HashSet<&str> = if resolution == ColumnConflictResolution::UsePreset {
+        imported
+            .iter()
+            .filter(|column| column.custom)
+            .map(|column| column.name.trim())
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    for column in current.iter().filter(|column| column.custom) {
+        if merged_keys.contains(&playlist_column_key(column))
+            || replaced_names.contains(column.name.trim())
+        {
+            continue;
+        }
+        merged.push(PlaylistColumnConfig {
+            enabled: false,
+            ..column.clone()
+        });
+    }
+    (merged, renamed_keys)
+}
+
+/// Returns `config` with the picked `sections` of `bundle` applied. The result still has
+/// to go through `sanitize_config`.
+pub fn apply_bundle(
+    config: &Config,
+    bundle: &PresetBundle,
+    sections: &[PresetSection],
+    resolution: ColumnConflictResolution,
+    shortcut_resolution: ShortcutConflictResolution,
+) -> Config {
+    let mut next = config.clone();
+    let picked =
+        |section: PresetSection| sections.contains(&section) && bundle.sections.contains(&section);
+    let imported = &bundle.layout;
+
+    if picked(PresetSection::Columns) {
+        let (columns, renamed_keys) = merge_columns(
+            &config.ui.playlist_columns,
+            &imported.playlist_columns,
+            resolution,
+            &bundle.name,
+        );
+        let rename = |key: &str| {
+            renamed_keys
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| key.to_string())
+        };
+        let mut width_overrides: Vec<PlaylistColumnWidthOverrideConfig> = imported
+            .playlist_column_width_overrides
+            .iter()
+            .map(|entry| PlaylistColumnWidthOverrideConfig {
+                column_key: rename(&entry.column_key),
+                width_px: entry.width_px,
+            })
+            .collect();
+        let mut styles: Vec<PlaylistColumnStyleOverrideConfig> = imported
+            .playlist_column_styles
+            .iter()
+            .map(|entry| PlaylistColumnStyleOverrideConfig {
+                column_key: rename(&entry.column_key),
+                style: entry.style,
+            })
+            .collect();
+        for entry in &config.ui.layout.playlist_column_width_overrides {
+            if !width_overrides
+                .iter()
+                .any(|imported| imported.column_key == entry.column_key)
+            {
+                width_overrides.push(entry.clone());
+            }
+        }
+        for entry in &config.ui.layout.playlist_column_styles {
+            if !styles
+                .iter()
+                .any(|imported| imported.column_key == entry.column_key)
+            {
+                styles.push(entry.clone());
+            }
+        }
+        next.ui.playlist_columns = columns.clone();
+        next.ui.layout.playlist_columns = columns;
+        next.ui.layout.playlist_column_width_overrides = width_overrides;
+        next.ui.layout.playlist_column_styles = styles;
+        next.ui.playlist_album_art_column_min_width_px =
+            imported.playlist_album_art_column_min_width_px;
+        next.ui.playlist_album_art_column_max_width_px =
+            imported.playlist_album_art_column_max_width_px;
+    }
+
+    if picked(PresetSection::Workspace) {
+        next.ui.layout.root = imported.root.clone();
+        next.ui.layout.button_cluster_instances = imported.button_cluster_instances.clone();
+        next.ui.layout.collection_panel_instances = imported.collection_panel_instances.clone();
+        next.ui.layout.metadata_viewer_panel_instances =
+            imported.metadata_viewer_panel_instances.clone();
+        next.ui.layout.album_art_viewer_panel_instances =
+            imported.album_art_viewer_panel_instances.clone();
+        next.ui.layout.selected_leaf_id = layout::first_leaf_id(&imported.root);
+    }
+
+    if picked(PresetSection::Theme) {
+        next.ui.layout.color_scheme = imported.color_scheme.clone();
+        next.ui.layout.custom_colors = imported.custom_colors.clone();
+    }
+
+    if picked(PresetSection::Shortcuts) {
+        // Kept letters come first so they win when a preset letter clashes with them.
+        let mut shortcut_keys: Vec<ShortcutKeyConfig> = match shortcut_resolution {
+            ShortcutConflictResolution::UsePreset => Vec::new(),
+            ShortcutConflictResolution::KeepMine => {
+                shortcut_conflicts(&config.ui.shortcut_keys, bundle)
+                    .into_iter()
+                    .map(|command| ShortcutKeyConfig {
+                        command,
+                        key: keyboard_shortcuts::shortcut_key(&config.ui.shortcut_keys, command)
+                            .to_string(),
+                    })
+                    .collect()
+            }
+        };
+        shortcut_keys.extend(bundle.shortcut_keys.iter().cloned());
+        next.ui.shortcut_keys = keyboard_shortcuts::sanitize_shortcut_keys(&shortcut_keys);
+    }
+
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str, format: &str) -> PlaylistColumnConfig {
+        PlaylistColumnConfig {
+            name: name.to_string(),
+            format: format.to_string(),
+            enabled: true,
+            custom: true,
+        }
+    }
+
+    fn preset_with_columns(columns: Vec<PlaylistColumnConfig>) -> PresetBundle {
+        let mut layout = LayoutConfig::default();
+        layout.playlist_columns = columns;
+        PresetBundle {
+            name: "foobar-like".to_string(),
+            sections: vec![PresetSection::Columns],
+            layout,
+            shortcut_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trips_and_rejects_foreign_files() {
+        let mut config = Config::default();
+        config.ui.playlist_columns.push(custom("Mood", "{mood}"));
+        config.ui.layout.color_scheme = "nord".to_string();
+        let bundle = bundle_from_config(&config, " iTunes-like ");
+
+        let parsed = parse_bundle(&serialize_bundle(&bundle).expect("serialize")).expect("parse");
+        assert_eq!(parsed.name, "iTunes-like");
+        assert_eq!(parsed.sections, ALL_SECTIONS.to_vec());
+        assert_eq!(parsed.layout.color_scheme, "nord");
+        assert!(parsed
+            .layout
+            .playlist_columns
+            .contains(&custom("Mood", "{mood}")));
+        assert_eq!(parsed.layout.root, config.ui.layout.root);
+
+        assert!(parse_bundle("color_scheme = \"nord\"").is_err());
+        let newer = serialize_bundle(&bundle)
+            .expect("serialize")
+            .replace("version = 1", "version = 99");
+        assert!(parse_bundle(&newer).is_err());
+    }
+
+    #[test]
+    fn test_column_conflicts_resolve_by_choice_and_keep_user_columns() {
+        let mut config = Config::default();
+        config.ui.playlist_columns = vec![
+            custom("Mood", "{mood}"),
+            custom("Rating", "{rating}"),
+            custom("Work", "{work}"),
+        ];
+        let bundle = preset_with_columns(vec![
+            custom("Rating", "{stars}"),
+            custom("Grouping", "{grouping}"),
+        ]);
+        assert_eq!(
+            column_conflicts(&config.ui.playlist_columns, &bundle),
+            vec!["Rating".to_string()]
+        );
+
+        let names = |resolution| {
+            apply_bundle(
+                &config,
+                &bundle,
+                &ALL_SECTIONS,
+                resolution,
+                ShortcutConflictResolution::default(),
+            )
+            .ui
+            .playlist_columns
+            .iter()
+            .map(|column| (column.name.clone(), column.format.clone(), column.enabled))
+            .collect::<Vec<_>>()
+        };
+        let column =
+            |name: &str, format: &str, enabled| (name.to_string(), format.to_string(), enabled);
+
+        assert_eq!(
+            names(ColumnConflictResolution::KeepBoth),
+            vec![
+                column("Rating (foobar-like)", "{stars}", true),
+                column("Grouping", "{grouping}", true),
+                column("Mood", "{mood}", false),
+                column("Rating", "{rating}", false),
+                column("Work", "{work}", false),
+            ]
+        );
+        assert_eq!(
+            names(ColumnConflictResolution::UsePreset),
+            vec![
+                column("Rating", "{stars}", true),
+                column("Grouping", "{grouping}", true),
+                column("Mood", "{mood}", false),
+                column("Work", "{work}", false),
+            ]
+        );
+        assert_eq!(
+            names(ColumnConflictResolution::KeepMine),
+            vec![
+                column("Rating", "{rating}", true),
+                column("Grouping", "{grouping}", true),
+                column("Mood", "{mood}", false),
+                column("Work", "{work}", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_bundle_only_touches_picked_sections() {
+        let config = Config::default();
+        let mut bundle = bundle_from_config(&config, "dark");
+        bundle.layout.color_scheme = "nord".to_string();
+        bundle.layout.root = layout::LayoutNode::Empty;
+
+        let themed = apply_bundle(
+            &config,
+            &bundle,
+            &[PresetSection::Theme],
+            ColumnConflictResolution::default(),
+            ShortcutConflictResolution::default(),
+        );
+        assert_eq!(themed.ui.layout.color_scheme, "nord");
+        assert_eq!(themed.ui.layout.root, config.ui.layout.root);
+
+        bundle.sections = vec![PresetSection::Columns];
+        let columns_only = apply_bundle(
+            &config,
+            &bundle,
+            &ALL_SECTIONS,
+            ColumnConflictResolution::default(),
+            ShortcutConflictResolution::default(),
+        );
+        assert_eq!(
+            columns_only.ui.layout.color_scheme,
+            config.ui.layout.color_scheme
+        );
+    }
+
+    #[test]
+    fn test_shortcut_section_round_trips_and_resolves_remapped_letters() {
+        let shortcut = |command, key: &str| ShortcutKeyConfig {
+            command,
+            key: key.to_string(),
+        };
+        let mut exporter = Config::default();
+        exporter.ui.shortcut_keys = vec![
+            shortcut(ShortcutCommand::Search, "k"),
+            shortcut(ShortcutCommand::SelectAll, "e"),
+        ];
+        let bundle = parse_bundle(
+            &serialize_bundle(&bundle_from_config(&exporter, "iTunes-like")).expect("serialize"),
+        )
+        .expect("parse");
+        assert_eq!(bundle.shortcut_keys, exporter.ui.shortcut_keys);
+
+        let mut config = Config::default();
+        config.ui.shortcut_keys = vec![
+            shortcut(ShortcutCommand::Search, "s"),
+            shortcut(ShortcutCommand::Paste, "b"),
+            shortcut(ShortcutCommand::JumpToPlaying, "e"),
+        ];
+        assert_eq!(
+            shortcut_conflicts(&config.ui.shortcut_keys, &bundle),
+            vec![
+                ShortcutCommand::Search,
+                ShortcutCommand::JumpToPlaying,
+                ShortcutCommand::Paste,
+            ]
+        );
+        let keys = |resolution| {
+            apply_bundle(
+                &config,
+                &bundle,
+                &[PresetSection::Shortcuts],
+                ColumnConflictResolution::default(),
+                resolution,
+            )
+            .ui
+            .shortcut_keys
+        };
+        assert_eq!(
+            keys(ShortcutConflictResolution::UsePreset),
+            exporter.ui.shortcut_keys
+        );
+        assert_eq!(
+            keys(ShortcutConflictResolution::KeepMine),
+            vec![
+                shortcut(ShortcutCommand::Search, "s"),
+                shortcut(ShortcutCommand::JumpToPlaying, "e"),
+                shortcut(ShortcutCommand::Paste, "b"),
+            ],
+            "the kept Ctrl+E leaves select all on its default letter"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Keyboard seek steps: configurable small/large step sizes and hold-to-accelerate.
+//!
+//! Left/Right seek by the small step and Shift+Left/Right by the large step. While a key
+//! is held, auto-repeat presses grow the step so long tracks can be scrubbed quickly.
+//! Forward seeks stop short of the track end so a held key never skips to the next track.
+
+/// Step sizes offered by the settings dialog, in seconds.
+pub const SEEK_STEP_CHOICES_SECS: &[u32] = &[1, 2, 5, 10, 15, 30, 60, 120, 300];
+/// Distance kept from the end of the track by forward seeks.
+const END_GUARD_MS: u64 = 1_000;
+/// Auto-repeat presses before the step doubles, and before it quadruples.
+const DOUBLE_AFTER_REPEATS: u32 = 4;
+const QUADRUPLE_AFTER_REPEATS: u32 = 12;
+
+/// Settings label for a step size, e.g. `5 s` or `2 min`.
+pub fn seek_step_label(secs: u32) -> String {
+    if secs >= 60 && secs % 60 == 0 {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{secs} s")
+    }
+}
+
+/// Index of the offered choice closest to `secs`.
+pub fn seek_step_choice_index(secs: u32) -> usize {
+    SEEK_STEP_CHOICES_SECS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, choice)| choice.abs_diff(secs))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Step length after `repeat_count` consecutive auto-repeat presses of a held key.
+pub fn accelerated_step_ms(step_ms: u64, repeat_count: u32) -> u64 {
+    if repeat_count >= QUADRUPLE_AFTER_REPEATS {
+        step_ms.saturating_mul(4)
+    } else if repeat_count >= DOUBLE_AFTER_REPEATS {
+        step_ms.saturating_mul(2)
+    } else {
+        step_ms
+    }
+}
+
+/// Seek target for one step, or `None` when the position would not change.
+///
+/// Backward steps clamp at the track start; forward steps clamp one second before the
+/// end and never move the position backwards.
+pub fn step_seek_target_ms(
+    current_ms: u64,
+    total_ms: u64,
+    step_ms: u64,
+    forward: bool,
+) -> Option<u64> {
+    if total_ms == 0 {
+        return None;
+    }
+    let current_ms = current_ms.min(total_ms);
+    let target_ms = if forward {
+        current_ms
+            .saturating_add(step_ms)
+            .min(total_ms.saturating_sub(END_GUARD_MS))
+            .max(current_ms)
+    } else {
+        current_ms.saturating_sub(step_ms)
+    };
+    (target_ms != current_ms).then_some(target_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_seek_target_clamps_near_track_boundaries() {
+        assert_eq!(
+            step_seek_target_ms(10_000, 60_000, 5_000, true),
+            Some(15_000)
+        );
+        assert_eq!(
+            step_seek_target_ms(57_000, 60_000, 5_000, true),
+            Some(59_000)
+        );
+        assert_eq!(step_seek_target_ms(59_500, 60_000, 5_000, true), None);
+        assert_eq!(step_seek_target_ms(3_000, 60_000, 5_000, false), Some(0));
+        assert_eq!(step_seek_target_ms(0, 60_000, 5_000, false), None);
+        assert_eq!(step_seek_target_ms(1_000, 0, 5_000, true), None);
+    }
+
+    #[test]
+    fn test_accelerated_step_grows_while_key_is_held() {
+        assert_eq!(accelerated_step_ms(5_000, 0), 5_000);
+        assert_eq!(accelerated_step_ms(5_000, DOUBLE_AFTER_REPEATS), 10_000);
+        assert_eq!(accelerated_step_ms(5_000, QUADRUPLE_AFTER_REPEATS), 20_000);
+    }
+
+    #[test]
+    fn test_seek_step_choices_round_trip_labels_and_indices() {
+        assert_eq!(seek_step_label(5), "5 s");
+        assert_eq!(seek_step_label(120), "2 min");
+        assert_eq!(SEEK_STEP_CHOICES_SECS[seek_step_choice_index(30)], 30);
+        assert_eq!(SEEK_STEP_CHOICES_SECS[seek_step_choice_index(29)], 30);
+    }
+}
@@ -21,6 +21,839 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_import_menu_opens_playlist_url_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("text: \"Import playlist URL...\"")
+                && slint_ui.contains("root.show_playlist_url_dialog = true;"),
+            "Import menu should open the playlist URL dialog"
+        );
+        assert!(
+            slint_ui.contains("callback import_playlist_url(string);")
+                && slint_ui.contains("root.import_playlist_url(root.playlist_url_input);"),
+            "Playlist URL dialog should submit through the import callback"
+        );
+    }
+
+    #[test]
+    fn test_library_context_menu_opens_organize_files_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("text: \"Organize files...\"")
+                && slint_ui.contains("root.library_organize_files();"),
+            "Library context menu should expose the organize files action"
+        );
+        assert!(
+            slint_ui.contains("root.organize_files_preview(root.organize_template);")
+                && slint_ui.contains("enabled: root.organize_apply_enabled;")
+                && slint_ui.contains("enabled: root.organize_undo_enabled;"),
+            "Organize dialog should preview before applying and offer undo"
+        );
+    }
+
+    #[test]
+    fn test_library_context_menu_toggles_remote_copy_source() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let library_slint = include_str!("components/library.slint");
+        assert!(
+            slint_ui.contains("callback library_toggle_track_source();")
+                && slint_ui.contains("enabled: root.library_source_toggle_text != \"\";")
+                && slint_ui.contains("root.library_toggle_track_source();"),
+            "Library context menu should switch tracks between local and remote copies"
+        );
+        assert!(
+            library_slint.contains("root.data.source_badge == \"linked\" ? 48px : 26px"),
+            "Library rows with a remote copy should show the linked source badge"
+        );
+    }
+
+    #[test]
+    fn test_track_context_menus_hand_files_to_other_apps() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert_eq!(
+            slint_ui.matches("root.copy_file_path();").count(),
+            2,
+            "Playlist and library context menus should both copy file paths"
+        );
+        assert_eq!(
+            slint_ui.matches("root.open_in_external_tagger();").count(),
+            2,
+            "Playlist and library context menus should both open the external tagger"
+        );
+        assert!(
+            slint_ui.contains("changed clipboard_copy_token =>")
+                && slint_ui.contains("clipboard-proxy.copy();"),
+            "Copied paths should reach the system clipboard through the hidden text input"
+        );
+        assert!(
+            slint_ui.contains("root.settings_set_external_tagger_command(text);"),
+            "Settings should configure the external tagger command"
+        );
+    }
+
+    #[test]
+    fn test_library_albums_toggle_cover_grid() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.library_set_album_grid(root.library_album_grid);"),
+            "Albums view should toggle and persist the cover grid"
+        );
+        assert!(
+            slint_ui.contains("library-album-grid := ListView {")
+                && slint_ui.contains("first-grid-row * self.columns,"),
+            "The album grid should page rows through the library viewport"
+        );
+        assert!(
+            slint_ui.contains("root.library_item_activated(album-tile.index);")
+                && slint_ui.contains("root.library_queue_selection(2);"),
+            "Album tiles should open their tracks and offer play and enqueue"
+        );
+        let library_ui = include_str!("components/library.slint");
+        assert!(
+            library_ui.contains("export component AlbumGridTile")
+                && library_ui.contains("text: root.data.caption;"),
+            "Album tiles should show cover art with artist and year"
+        );
+    }
+
+    #[test]
+    fn test_damaged_database_prompt_offers_restore_and_rebuild() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("if root.show_database_repair_prompt : Rectangle {")
+                && slint_ui.contains("callback database_repair_chosen(bool);"),
+            "A damaged database should prompt for a repair at startup"
+        );
+        assert!(
+            slint_ui.contains("root.database_repair_chosen(true);")
+                && slint_ui.contains("root.database_repair_chosen(false);")
+                && slint_ui.contains("if root.database_repair_backup_available : Button {"),
+            "The prompt should offer a rebuild and, when a backup exists, a restore"
+        );
+        assert!(
+            slint_ui.contains("message: root.database_repair_notice_message;"),
+            "The repair outcome should be reported after the restart"
+        );
+    }
+
+    #[test]
+    fn test_library_tracks_view_has_facet_filter_sidebar() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains(
+                "root.library_set_facet_panel_visible(!root.library_facet_panel_visible);"
+            ),
+            "The Tracks view header should toggle the filter sidebar"
+        );
+        assert!(
+            slint_ui.contains("for facet in root.library_facet_rows : Rectangle {")
+                && slint_ui.contains("root.library_toggle_facet(facet.kind, facet.value);"),
+            "The filter sidebar should list facet values that toggle on click"
+        );
+        assert!(
+            slint_ui.contains("clicked => { root.library_clear_facets(); }"),
+            "The filter sidebar should clear all picked facets"
+        );
+    }
+
+    #[test]
+    fn test_artist_page_shows_release_sections_and_album_play_buttons() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("text: item.section_title;")
+                && slint_ui.contains("y: parent.section-height;"),
+            "Artist pages should head each release kind with its own section"
+        );
+        let library_ui = include_str!("components/library.slint");
+        assert!(
+            library_ui.contains("if root.data.show_play_button : Rectangle {")
+                && library_ui.contains("clicked => { root.play(); }"),
+            "Album rows on artist pages should have a play button"
+        );
+        assert!(
+            slint_ui.contains("root.library_select_list_item(row, false, false, false);")
+                && slint_ui.contains("root.library_queue_selection(0);"),
+            "The album play button should play that release"
+        );
+    }
+
+    #[test]
+    fn test_track_context_menus_open_tags_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert_eq!(
+            slint_ui.matches("root.open_track_tags();").count(),
+            2,
+            "Playlist and library context menus should both expose the tags action"
+        );
+        assert!(
+            slint_ui.contains("height: root.playlist-context-menu-height;")
+                && slint_ui.contains("root.height - root.playlist-context-menu-height"),
+            "Playlist context menu height and clamp should account for the tags item"
+        );
+        assert!(
+            slint_ui.contains("root.track_tags_create(root.track_tags_new_name);")
+                && slint_ui.contains("root.track_tags_cycle_color(index);")
+                && slint_ui.contains("root.track_tags_delete(index);"),
+            "Tags dialog should create, recolor, and delete tags"
+        );
+    }
+
+    #[test]
+    fn test_playlist_context_menu_opens_transition_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert_eq!(
+            slint_ui.matches("root.open_track_transition();").count(),
+            1,
+            "Playlist context menu should expose the transition action"
+        );
+        assert!(
+            slint_ui.contains(
+                "property <length> playlist-context-menu-height: root.context-menu-height + (context-menu-item-height + 2px) * 8;"
+            ),
+            "Playlist context menu height should account for the tags, transition, energy, loudness, send-to, copy path, and tagger items"
+        );
+        assert_eq!(
+            slint_ui
+                .matches(
+                    "root.track_transition_save(root.track_transition_trim_start_text, root.track_transition_trim_end_text);"
+                )
+                .count(),
+            3,
+            "Transition dialog should save from both fields and the Save button"
+        );
+        assert!(
+            slint_ui.contains("root.track_transition_detect();"),
+            "Transition dialog should offer intro/outro detection"
+        );
+    }
+
+    #[test]
+    fn test_playlist_context_menu_opens_energy_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("text: \"Energy...\"")
+                && slint_ui.contains("root.open_track_energy();"),
+            "Playlist context menu should expose the energy action"
+        );
+        assert!(
+            slint_ui.contains("root.track_energy_save(root.track_energy_level_index);")
+                && slint_ui.contains("root.track_energy_suggest();"),
+            "Energy dialog should save the picked level and offer suggestions"
+        );
+        assert!(
+            slint_ui.contains("callback library_timed_mix_apply(string, int, int);")
+                && slint_ui.contains("current-index <=> root.library_timed_mix_flow_index;"),
+            "Timed mix dialog should pass the energy flow choice"
+        );
+    }
+
+    #[test]
+    fn test_library_settings_expose_import_rule_editor() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui
+                .contains("for rule[index] in root.settings_library_import_rules : Rectangle {")
+                && slint_ui.contains("clicked => { root.import_rule_open_editor(-1); }"),
+            "Library settings should list import rules and allow adding one"
+        );
+        assert!(
+            slint_ui.contains(
+                "root.import_rule_remove(root.settings_library_selected_import_rule_index);"
+            ),
+            "Library settings should allow removing the selected import rule"
+        );
+        assert!(
+            slint_ui.contains("checked <=> root.import_rule_exclude_from_shuffle;")
+                && slint_ui.contains("root.import_rule_browse_folder();"),
+            "Import rule dialog should edit the folder and shuffle exclusion"
+        );
+    }
+
+    #[test]
+    fn test_integrations_settings_expose_webdav_share() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("if root.settings_dialog_tab_index == 3 : ScrollView {"),
+            "Integrations settings should scroll now that they hold two backends"
+        );
+        assert!(
+            slint_ui
+                .contains("callback settings_save_webdav_profile(bool, string, string, string);")
+                && slint_ui.contains("callback settings_test_webdav_connection();")
+                && slint_ui.contains("callback settings_sync_webdav_now();")
+                && slint_ui.contains("callback settings_disconnect_webdav();"),
+            "WebDAV settings should expose save, test, sync, and disconnect callbacks"
+        );
+        assert!(
+            slint_ui.contains("text <=> root.settings_webdav_password;")
+                && slint_ui.contains("text: root.settings_webdav_status;"),
+            "WebDAV settings should bind the password field and status line"
+        );
+    }
+
+    #[test]
+    fn test_library_settings_expose_scheduled_maintenance() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("if root.settings_dialog_tab_index == 2 : ScrollView {"),
+            "Library settings should scroll now that they include maintenance controls"
+        );
+        assert!(
+            slint_ui.contains(
+                "root.settings_set_maintenance_interval(task-index, self.current-index);"
+            ) && slint_ui
+                .contains("clicked => { root.settings_run_maintenance_task(task-index); }"),
+            "Each maintenance task should expose an interval picker and a Run Now action"
+        );
+        assert!(
+            slint_ui.contains("for run in root.settings_maintenance_history : Rectangle {"),
+            "Library settings should list recent maintenance runs"
+        );
+        assert!(
+            slint_ui.contains("\"Verify file integrity\"")
+                && slint_ui.contains("for issue in root.settings_integrity_issues : Rectangle {"),
+            "Library settings should schedule integrity checks and list failing files"
+        );
+        assert!(
+            slint_ui.contains("for activity in root.settings_activity_log : Rectangle {"),
+            "Library settings should list the activity log"
+        );
+    }
+
+    #[test]
+    fn test_library_settings_list_caches_with_location_limit_and_clear_controls() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let types_slint = include_str!("types.slint");
+        assert!(
+            types_slint.contains("export struct CacheRowData {")
+                && slint_ui.contains(
+                    "for cache-row[cache-index] in root.settings_cache_rows : HorizontalLayout {"
+                ),
+            "Library settings should list one row per cache"
+        );
+        assert!(
+            slint_ui.contains("clicked => { root.settings_pick_cache_location(cache-index); }")
+                && slint_ui
+                    .contains("clicked => { root.settings_reset_cache_location(cache-index); }")
+                && slint_ui
+                    .contains("root.settings_set_cache_limit(cache-index, self.current-index);")
+                && slint_ui.contains("clicked => { root.settings_clear_cache(cache-index); }"),
+            "Each cache row should change its folder, pick a size limit, and clear its files"
+        );
+    }
+
+    #[test]
+    fn test_energy_ramp_playback_order_is_cycled_and_its_arc_is_configurable() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let controls_slint = include_str!("components/controls.slint");
+        assert!(
+            slint_ui.contains("let next-order = mod(root.playback_order_index + 1, 5);")
+                && controls_slint.contains("root.playback-order-index == 4 ? \"Energy ramp\""),
+            "Playback order button should cycle through the energy ramp order"
+        );
+        assert!(
+            slint_ui.contains("in-out property <int> settings_energy_ramp_arc_index: 0;")
+                && slint_ui.contains("callback settings_set_energy_ramp_arc(int);")
+                && slint_ui.contains("root.settings_set_energy_ramp_arc(self.current-index);"),
+            "Settings should expose the energy ramp arc selector"
+        );
+        assert!(
+            slint_ui.contains("callback settings_reset_transition_history();")
+                && slint_ui.contains("clicked => { root.settings_reset_transition_history(); }"),
+            "Settings should offer resetting learned track transitions"
+        );
+    }
+
+    #[test]
+    fn test_up_next_panel_saves_queue_as_playlist() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains(
+                "save-queue(shuffle-order) => { root.save_playback_queue_as_playlist(shuffle-order); }"
+            ) && slint_ui.contains(
+                "shuffle-active: root.playback_order_index == 1 || root.playback_order_index == 3 || root.playback_order_index == 4;"
+            ),
+            "Up Next should save the queue, offering shuffle order while shuffle is on"
+        );
+    }
+
+    #[test]
+    fn test_destructive_actions_share_one_confirmation_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("action_confirm_overlay := ConfirmationDialog {")
+                && slint_ui.contains("root.action_confirm_accepted(root.action_confirm_dont_ask);")
+                && !slint_ui.contains("remote_detach_overlay"),
+            "Playlist deletion, sync, and detach prompts should all use the shared dialog"
+        );
+        assert!(
+            slint_ui
+                .contains("root.settings_set_confirmation_enabled(action-index, self.checked);"),
+            "General settings should toggle each confirmation prompt"
+        );
+        let menus = include_str!("components/menus.slint");
+        assert!(
+            menus.contains("text: \"Don't ask again\";"),
+            "The confirmation dialog should offer a don't-ask-again checkbox"
+        );
+    }
+
+    #[test]
+    fn test_import_dialog_offers_recent_locations_and_estimate() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("for recent-location[index] in root.import_dialog_recent_locations")
+                && slint_ui.contains("root.import_dialog_add_recent(index);"),
+            "The import dialog should list recent locations that can be added as roots"
+        );
+        assert!(
+            slint_ui.contains("text: root.import_dialog_estimate_text;")
+                && slint_ui.contains("root.import_dialog_confirm();"),
+            "The import dialog should show the track estimate before importing"
+        );
+    }
+
+    #[test]
+    fn test_general_settings_expose_worker_pool_tuning() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.settings_set_decode_threads(self.current-index);")
+                && slint_ui.contains("root.settings_set_analysis_threads(self.current-index);")
+                && slint_ui.contains("root.settings_set_worker_priority(self.current-index);"),
+            "Performance settings should edit both pool sizes and the worker priority"
+        );
+        assert!(
+            slint_ui.contains("text: root.settings_worker_pool_diagnostics;"),
+            "Performance settings should show live worker pool usage"
+        );
+        assert!(
+            slint_ui.contains("text: root.settings_startup_timing_diagnostics;"),
+            "Performance settings should show startup phase timings"
+        );
+    }
+
+    #[test]
+    fn test_seek_bar_renders_markers_with_snap_and_keyboard_navigation() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let media_ui = include_str!("components/media.slint");
+        assert!(
+            slint_ui.contains("markers: root.seek_markers;")
+                && media_ui.contains("for marker in root.markers : Rectangle {"),
+            "Seek bar should render chapter/silence marker ticks"
+        );
+        assert!(
+            slint_ui.contains(
+                "seek-requested(next-pos, snap-tolerance) => { root.seek-to-snapped(next-pos, snap-tolerance); }"
+            ),
+            "Seek bar releases should snap to nearby markers"
+        );
+        assert!(
+            slint_ui.contains("root.seek-to-adjacent-marker(true);")
+                && slint_ui.contains("root.seek-to-adjacent-marker(false);"),
+            "Keyboard shortcuts should jump to the next/previous marker"
+        );
+        assert!(
+            slint_ui.contains("trim-start-ms: root.playing_trim_start_ms;")
+                && slint_ui.contains("trim-end-ms: root.playing_trim_end_ms;")
+                && media_ui
+                    .contains("if root.total-ms > 0 && root.trim-start-ms > 0 : Rectangle {")
+                && media_ui.contains("if root.total-ms > 0 && root.trim-end-ms > 0 : Rectangle {"),
+            "Seek bar should show the playing track's start and end trims"
+        );
+    }
+
+    #[test]
+    fn test_arrow_keys_seek_by_configurable_steps() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.seek-step(true, event.modifiers.shift, event.repeat);")
+                && slint_ui.contains("root.seek-step(false, event.modifiers.shift, event.repeat);"),
+            "Left/Right should seek by the small step and Shift by the large step"
+        );
+        assert!(
+            slint_ui.contains("root.settings_set_seek_step(false, self.current-index);")
+                && slint_ui.contains("root.settings_set_seek_step(true, self.current-index);"),
+            "Settings should edit both seek step sizes"
+        );
+    }
+
+    #[test]
+    fn test_settings_toggle_zero_based_row_numbers() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("in-out property <bool> settings_zero_based_row_numbers: false;")
+                && slint_ui.contains("root.settings_set_zero_based_row_numbers(self.checked);"),
+            "Settings should switch row numbering between 0- and 1-based"
+        );
+    }
+
+    #[test]
+    fn test_settings_toggle_library_tag_change_watching() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("in-out property <bool> settings_library_watch_tag_changes: true;")
+                && slint_ui.contains("root.settings_set_library_watch_tag_changes("),
+            "Settings should turn refreshing of externally edited tags on and off"
+        );
+    }
+
+    #[test]
+    fn test_settings_toggle_library_slow_storage_mode() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("in-out property <bool> settings_library_slow_storage_mode: false;")
+                && slint_ui.contains("root.settings_set_library_slow_storage_mode("),
+            "Settings should switch slow network storage mode on and off"
+        );
+    }
+
+    #[test]
+    fn test_playlist_menu_and_settings_toggle_skip_silence() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        assert!(
+            menu_ui.contains("root.toggle-skip-silence();")
+                && slint_ui.contains("root.toggle_playlist_skip_silence(row.index);"),
+            "Playlist context menu should toggle skip-silence per playlist"
+        );
+        assert!(
+            slint_ui.contains("root.settings_set_skip_silence_spoken_word(self.checked);")
+                && slint_ui
+                    .contains("root.settings_set_skip_silence_min_pause(self.current-index);"),
+            "Settings should edit spoken-word skipping and the minimum pause"
+        );
+    }
+
+    #[test]
+    fn test_playlist_menu_and_settings_toggle_loudness_normalization() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        assert!(
+            menu_ui.contains("root.toggle-normalize-loudness();")
+                && slint_ui.contains("root.toggle_playlist_normalize_loudness(row.index);"),
+            "Playlist context menu should toggle loudness normalization per playlist"
+        );
+        assert!(
+            slint_ui.contains("root.settings_set_normalize_loudness(self.checked);"),
+            "Settings should turn loudness normalization on for every playlist"
+        );
+    }
+
+    #[test]
+    fn test_settings_import_and_select_eq_presets_per_device() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.settings_set_eq_preset(self.current-index);")
+                && slint_ui.contains("root.settings_import_eq_profile();")
+                && slint_ui.contains("root.settings_remove_eq_preset();"),
+            "Settings should import, select and remove EQ presets"
+        );
+        assert!(
+            slint_ui.contains("root.settings_eq_device_name"),
+            "EQ preset row should name the output device it applies to"
+        );
+    }
+
+    #[test]
+    fn test_match_content_settings_choose_rate_policy_per_device() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.settings_set_rate_policy(self.current-index);")
+                && slint_ui.contains("root.settings_set_pinned_rate(self.current-index);"),
+            "Match Content settings should pick a rate policy and pinned rate"
+        );
+        assert!(
+            slint_ui.contains(
+                "if root.settings_sample_rate_mode_index == 0 && root.settings_rate_policy_index == 1 : Rectangle {"
+            ),
+            "Pinned rate should only show for the pinned policy"
+        );
+    }
+
+    #[test]
+    fn test_output_settings_run_device_tests_before_apply() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.settings_run_output_test(root.settings_output_test_index);")
+                && slint_ui.contains("model: root.settings_output_test_options;"),
+            "Output settings should offer tone, channel and sample-rate tests"
+        );
+        assert!(
+            slint_ui.contains("enabled: !root.settings_output_test_running;"),
+            "Output test controls should lock while a test plays"
+        );
+    }
+
+    #[test]
+    fn test_settings_compare_dsp_chain_with_bypass_and_blind_test() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("root.settings_set_dsp_bypassed(self.checked);")
+                && slint_ui.contains("root.settings_start_dsp_blind_test();"),
+            "Settings should bypass the DSP chain and start a blind test"
+        );
+        assert!(
+            slint_ui.contains("root.settings_select_dsp_blind_choice(1);")
+                && slint_ui.contains("root.settings_reveal_dsp_blind_test();"),
+            "Blind test should switch between choices and reveal the processed one"
+        );
+    }
+
+    #[test]
+    fn test_remote_playlist_menu_downloads_a_local_copy() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        let playlist_ui = include_str!("components/playlist.slint");
+        assert!(
+            menu_ui.contains("if is-playlist && show-make-local-item : Rectangle {")
+                && playlist_ui.contains("show-make-local-item: root.is-remote;")
+                && slint_ui.contains("root.materialize_remote_playlist(row.index);"),
+            "Remote-bound playlists should offer downloading into a local playlist"
+        );
+    }
+
+    #[test]
+    fn test_playlists_export_and_import_xspf() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        let playlist_ui = include_str!("components/playlist.slint");
+        assert!(
+            menu_ui.contains("text: \"Export as XSPF…\";")
+                && playlist_ui.contains("root.context-menu-export-xspf();")
+                && slint_ui.contains("root.export_playlist_xspf(row.index);"),
+            "The playlist context menu should export the playlist as XSPF"
+        );
+        assert!(
+            slint_ui.contains("text: \"Import XSPF playlist...\";")
+                && slint_ui.contains("root.import_xspf_playlist();"),
+            "The import menu should offer importing an XSPF playlist"
+        );
+    }
+
+    #[test]
+    fn test_playlists_export_formatted_documents() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        let playlist_ui = include_str!("components/playlist.slint");
+        assert!(
+            menu_ui.contains("text: \"Export as Document…\";")
+                && playlist_ui.contains("root.context-menu-export-document();")
+                && slint_ui.contains("root.open_playlist_document_export(row.index);"),
+            "The playlist context menu should open the document export dialog"
+        );
+        assert!(
+            slint_ui.contains("model: root.playlist_document_format_options;")
+                && slint_ui.contains("checked <=> root.playlist_document_include_covers;")
+                && slint_ui.contains("root.playlist_document_export("),
+            "The document export dialog should offer formats, columns, and an album art header"
+        );
+    }
+
+    #[test]
+    fn test_playlists_verify_files_and_relocate_missing_ones() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        assert!(
+            menu_ui.contains("text: \"Verify Files\";")
+                && slint_ui.contains("root.verify_playlist_files(row.index);"),
+            "The playlist context menu should check the playlist's files"
+        );
+        assert!(
+            slint_ui.contains("text: root.relocation_summary_text;")
+                && slint_ui.contains("root.relocate_missing_files();"),
+            "The missing files dialog should offer choosing the folder they moved to"
+        );
+    }
+
+    #[test]
+    fn test_column_header_menu_opens_column_style_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menu_ui = include_str!("components/menus.slint");
+        let playlist_ui = include_str!("components/playlist.slint");
+        assert!(
+            menu_ui.contains("root.edit-style();")
+                && slint_ui
+                    .contains("root.open_column_style_dialog(root.column_style_target_index);"),
+            "The column header menu should open the style dialog for the right-clicked column"
+        );
+        assert!(
+            slint_ui.contains("callback apply_column_style(int, int, int, int, int);")
+                && slint_ui.contains("current-index <=> root.column_style_font_weight_index;"),
+            "The column style dialog should apply alignment, elision, numbers, and weight"
+        );
+        assert!(
+            playlist_ui.contains("in property <[int]> column-font-weights: [];")
+                && slint_ui
+                    .contains("column-font-weights: root.playlist_visible_column_font_weights;"),
+            "Track rows should render each column in its font weight"
+        );
+    }
+
+    #[test]
+    fn test_fault_injection_switches_are_gated_by_developer_mode() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("if root.settings_developer_mode : VerticalLayout {")
+                && slint_ui
+                    .contains("root.settings_set_fault_injection(fault-index, self.checked);"),
+            "Fault injection switches should only show in developer mode"
+        );
+    }
+
+    #[test]
+    fn test_notes_panel_is_a_layout_panel_with_save_callbacks() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("property <int> panel_kind_notes: 14;")
+                && slint_ui.contains("root.layout-region-panel-kind(i) == root.panel_kind_notes;"),
+            "Notes panel should be placeable through the layout editor"
+        );
+        assert!(
+            slint_ui.contains("track-note-saved(note) => { root.notes_track_saved(note); }")
+                && slint_ui
+                    .contains("playlist-note-saved(note) => { root.notes_playlist_saved(note); }"),
+            "Notes panel should save track and playlist notes separately"
+        );
+    }
+
+    #[test]
+    fn test_track_lists_report_manual_scrolls_and_cluster_offers_follow_actions() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert_eq!(
+            slint_ui
+                .matches("scrolled => { root.track_list_scrolled_by_user(); }")
+                .count(),
+            2,
+            "Playlist and library track lists should both pause follow playback on manual scroll"
+        );
+        assert!(
+            slint_ui.contains("if (action-id == 13) {\n                                root.toggle_follow_playback();")
+                && slint_ui.contains("if (action-id == 14) {\n                                root.jump_to_playing_track();")
+                && slint_ui.contains("follow-playback: root.settings_auto_scroll_to_playing_track;"),
+            "Button clusters should toggle follow playback and jump to the playing track"
+        );
+    }
+
+    #[test]
+    fn test_lyrics_panel_is_a_layout_panel_following_the_active_line() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("property <int> panel_kind_lyrics: 15;")
+                && slint_ui.contains("root.layout-region-panel-kind(i) == root.panel_kind_lyrics;"),
+            "Lyrics panel should be placeable through the layout editor"
+        );
+        let playlist_components = include_str!("components/playlist.slint");
+        assert!(
+            playlist_components.contains("changed active-line => {")
+                && playlist_components.contains("lyrics-flickable.viewport-y = min(0px, max("),
+            "Lyrics panel should scroll the active synced line into view"
+        );
+    }
+
+    #[test]
+    fn test_transport_bar_reserves_space_and_stays_above_settings_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("padding-top: root.settings_transport_bar_placement_index == 1 ? root.transport-bar-height : 0px;")
+                && slint_ui.contains("padding-bottom: root.settings_transport_bar_placement_index == 2 ? root.transport-bar-height : 0px;"),
+            "Layout workspace should shrink to make room for the transport bar"
+        );
+        let bar_start = slint_ui
+            .find("if root.settings_transport_bar_placement_index != 0 : Rectangle {")
+            .expect("transport bar block should exist");
+        let bar_block = &slint_ui[bar_start..];
+        let bar_block = &bar_block[..bar_block
+            .find("column-header-menu := ColumnHeaderMenu {")
+            .unwrap_or(bar_block.len())];
+        assert!(
+            bar_block.contains("z: 105;")
+                && bar_block.contains("button-actions: [2, 3, 4, 5, 6];")
+                && bar_block.contains("root.seek-to-snapped(next-pos, snap-tolerance);")
+                && bar_block.contains("value <=> root.volume-level;"),
+            "Transport bar should carry playback buttons, seek bar, and volume above the Settings dialog"
+        );
+    }
+
+    #[test]
+    fn test_tasks_panel_is_a_layout_panel_with_pause_and_cancel() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("property <int> panel_kind_tasks: 16;")
+                && slint_ui.contains("root.layout-region-panel-kind(i) == root.panel_kind_tasks;"),
+            "Tasks panel should be placeable through the layout editor"
+        );
+        let task_components = include_str!("components/tasks.slint");
+        assert!(
+            task_components.contains("root.pause-toggled(row.task_id, !row.paused);")
+                && task_components.contains("root.cancel-requested(row.task_id);"),
+            "Tasks panel rows should offer pause/resume and cancel"
+        );
+    }
+
+    #[test]
+    fn test_file_browser_panel_is_a_layout_panel_that_adds_folders() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("property <int> panel_kind_file_browser: 17;")
+                && slint_ui
+                    .contains("root.layout-region-panel-kind(i) == root.panel_kind_file_browser;"),
+            "File Browser panel should be placeable through the layout editor"
+        );
+        let browser_ui = include_str!("components/file_browser.slint");
+        assert!(
+            browser_ui.contains("root.row-activated(i);")
+                && browser_ui.contains("root.add-to-playlist(i);")
+                && browser_ui.contains("root.choose-root();"),
+            "File Browser rows should expand, preview and add to the playlist"
+        );
+    }
+
+    #[test]
+    fn test_playlist_sidebar_renders_folder_tree_with_drag_and_drop() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("for row[r] in root.playlist_tree_rows : Rectangle {")
+                && slint_ui.contains("if row.is_folder : PlaylistFolderRow {")
+                && slint_ui.contains("root.switch_playlist(row.index);"),
+            "Sidebar should render folders and playlists from the tree rows"
+        );
+        assert!(
+            slint_ui.contains(
+                "root.move_playlist_tree_row(root.playlist_tree_drag_row, root.playlist_tree_drop_row);"
+            ) && slint_ui.contains("clicked => { root.create_playlist_folder(-1); }"),
+            "Sidebar rows should drag into folders and the header should create folders"
+        );
+        let playlist_ui = include_str!("components/playlist.slint");
+        assert!(
+            playlist_ui.contains("menu := PlaylistFolderMenu {")
+                && playlist_ui.contains("root.context-menu-new-subfolder();"),
+            "Folder rows should offer rename, subfolder and delete actions"
+        );
+    }
+
+    #[test]
+    fn test_spectrogram_panel_polls_only_while_visible() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("property <int> panel_kind_spectrogram: 18;")
+                && slint_ui
+                    .contains("root.layout-region-panel-kind(i) == root.panel_kind_spectrogram;")
+                && slint_ui.contains("tick => { root.spectrogram_tick(); }"),
+            "Spectrogram panel should be placeable through the layout editor"
+        );
+        let spectrogram_ui = include_str!("components/spectrogram.slint");
+        assert!(
+            spectrogram_ui.contains("running: root.visible;"),
+            "Hidden Spectrogram panels should not keep draining the output tap"
+        );
+        assert!(
+            slint_ui.contains("root.properties_render_spectrogram();")
+                && slint_ui.contains("if root.properties_spectrogram_visible : VerticalLayout {"),
+            "Properties dialog should render a full-track spectrogram on demand"
+        );
+    }
+
     #[test]
     fn test_library_view_shows_add_folder_cta_when_library_has_no_content() {
         let slint_ui = include_str!("../roqtune.slint");
@@ -160,7 +993,7 @@ mod tests {
         );
         assert!(
             slint_ui.contains(
-                "callback apply_settings(int, int, int, int, string, string, string, string, bool, bool, bool, int, int, bool, bool, bool, string, [string]);"
+                "callback apply_settings(int, int, int, int, string, string, string, string, bool, bool, bool, int, int, bool, bool, bool, bool, bool, bool, string, [string]);"
             ),
             "Apply settings callback should include color scheme selection and custom color values"
         );
@@ -171,6 +1004,15 @@ mod tests {
                 && slint_ui.contains("if root.settings_dialog_tab_index == 1 : ScrollView {"),
             "Audio tab should expose Match Content and Manual sample-rate mode selector"
         );
+        assert!(
+            slint_ui.contains("checked <=> root.settings_group_sample_rate_families;")
+                && slint_ui.contains("checked <=> root.settings_resample_rate_outliers;")
+                && slint_ui
+                    .matches("if root.settings_sample_rate_mode_index == 0 : Rectangle {")
+                    .count()
+                    == 2,
+            "Rate grouping toggles should only be shown in Match Content mode"
+        );
     }
 
     #[test]
@@ -323,6 +1165,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_settings_exposes_preset_import_with_conflict_choice() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("callback settings_export_preset();")
+                && slint_ui.contains("callback settings_import_preset();"),
+            "Settings should offer preset export and import"
+        );
+        assert!(
+            slint_ui.contains("callback preset_import_confirmed([bool], int, int);")
+                && slint_ui.contains("current-index <=> root.preset_import_resolution_index;")
+                && slint_ui
+                    .contains("current-index <=> root.preset_import_shortcut_resolution_index;"),
+            "The import dialog should pass the picked sections and both conflict resolutions"
+        );
+    }
+
+    #[test]
+    fn test_playlist_header_supports_multi_key_and_permanent_sort() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("callback add_playlist_sort_column(int);")
+                && slint_ui.contains("root.add_playlist_sort_column(root.column_click_candidate);"),
+            "Shift-clicking a playlist header should add a further sort key"
+        );
+        assert!(
+            slint_ui.contains("in-out property <[int]> playlist_column_sort_ranks: [];"),
+            "Playlist headers should show the rank of each sort key"
+        );
+        assert!(
+            slint_ui.contains("callback sort_playlist_permanently();")
+                && slint_ui.contains("root.sort_playlist_permanently();"),
+            "The read-only view bar should offer to sort the playlist permanently"
+        );
+    }
+
     #[test]
     fn test_layout_panel_menu_excludes_status_bar_and_presets_include_status_bar() {
         let slint_ui = include_str!("../roqtune.slint");
@@ -339,4 +1217,102 @@ mod tests {
             "Layout presets should include the Status Bar text-panel preset"
         );
     }
+
+    #[test]
+    fn test_migration_assistant_window_offers_backup_or_quit() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let assistant = include_str!("components/migration_assistant.slint");
+        assert!(
+            slint_ui.contains("export { MigrationAssistantWindow }"),
+            "The migration assistant should be exported for startup to show"
+        );
+        assert!(
+            assistant.contains("callback migrate();")
+                && assistant.contains("callback quit();")
+                && assistant.contains("text: \"Back up and migrate\""),
+            "The assistant should only convert after the user agrees to a backup"
+        );
+        assert!(
+            assistant.contains("in property <[string]> unmigratable_items: [];")
+                && assistant.contains("in property <[string]> failed_items: [];"),
+            "The assistant should list entries that cannot be migrated"
+        );
+    }
+
+    #[test]
+    fn test_column_header_menu_opens_column_filter_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menus = include_str!("components/menus.slint");
+        assert!(
+            menus.contains("callback edit-filter();")
+                && slint_ui
+                    .contains("root.open_playlist_column_filter(root.column_style_target_index);"),
+            "The column header menu should open the filter dialog for the clicked column"
+        );
+        assert!(
+            slint_ui.contains("root.apply_playlist_column_filter(\n                            root.column_filter_value_checked,")
+                && slint_ui.contains("current-index <=> root.column_filter_operator_index;"),
+            "The filter dialog should apply the picked values and condition together"
+        );
+        assert!(
+            slint_ui.contains("in-out property <[bool]> playlist_column_filter_flags: [];")
+                && slint_ui.contains("source: AppIcons.filter;"),
+            "Filtered columns should be marked in the header"
+        );
+    }
+
+    #[test]
+    fn test_playlist_track_menu_offers_send_to_playlist_flow() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("text: \"Send to Playlist...\";")
+                && slint_ui.contains("root.open_send_to_playlist();")
+                && slint_ui.contains("text: \"New Playlist from Selection...\";")
+                && slint_ui.contains("root.open_new_playlist_from_selection();"),
+            "The playlist track menu should offer sending the selection to playlists"
+        );
+        assert!(
+            slint_ui.contains("root.send_to_playlist_confirm(root.send_to_playlist_checked);")
+                && slint_ui.contains("root.send_to_recent_playlist(index);"),
+            "The picker should send to checked playlists and to recent shortcuts"
+        );
+    }
+
+    #[test]
+    fn test_integration_profiles_offer_share_setup_dialog() {
+        let slint_ui = include_str!("../roqtune.slint");
+        assert!(
+            slint_ui.contains("callback settings_share_backend_profile(bool);")
+                && slint_ui.contains("root.settings_share_backend_profile(false);")
+                && slint_ui.contains("root.settings_share_backend_profile(true);"),
+            "OpenSubsonic and WebDAV settings should both offer sharing their setup"
+        );
+        assert!(
+            slint_ui.contains("source: root.profile_share_qr;")
+                && slint_ui.contains("root.clipboard_text = root.profile_share_link;"),
+            "The share dialog should show the QR code and copy the link"
+        );
+    }
+
+    #[test]
+    fn test_playlist_menu_opens_details_dialog_with_cover_and_description() {
+        let slint_ui = include_str!("../roqtune.slint");
+        let menus_slint = include_str!("components/menus.slint");
+        assert!(
+            menus_slint.contains("text: \"Edit Details…\";")
+                && slint_ui.contains("root.open_playlist_details(row.index);"),
+            "The sidebar playlist menu should open the details dialog"
+        );
+        assert!(
+            slint_ui.contains("root.choose_playlist_details_cover();")
+                && slint_ui.contains("text <=> root.playlist_details_description;")
+                && slint_ui.contains("root.playlist_details_reset_cover = true;"),
+            "The details dialog should edit the cover and description"
+        );
+        assert!(
+            slint_ui.contains("source: root.playlist_covers[root.active_playlist_index];")
+                && slint_ui.contains("root.tooltip_image = root.playlist_covers[row.index];"),
+            "The playlist header and sidebar tooltip should show the playlist cover"
+        );
+    }
 }
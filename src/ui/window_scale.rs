@@ -0,0 +1,114 @@
+//! Window scale factor: automatic per-monitor scaling with a manual override.
+//!
+//! By default the window follows the scale factor of the monitor it is on, re-scaling
+//! when it moves to a display with a different DPI. When detection misbehaves (mixed-DPI
+//! setups under some X11/Wayland compositors or remote desktops), a fixed override
+//! replaces the monitor factor on every display.
+
+use log::info;
+use slint::winit_030::{winit, EventResult as WinitEventResult, WinitWindowAccessor};
+
+/// Scale overrides offered by the settings dialog, in percent; `0` is automatic.
+pub const SCALE_OVERRIDE_CHOICES_PERCENT: &[u32] = &[0, 75, 100, 125, 150, 175, 200, 250, 300];
+const MIN_SCALE_OVERRIDE_PERCENT: u32 = 50;
+const MAX_SCALE_OVERRIDE_PERCENT: u32 = 400;
+
+/// Settings label for an override choice, e.g. `Automatic` or `125%`.
+pub fn scale_override_label(percent: u32) -> String {
+    if percent == 0 {
+        "Automatic".to_string()
+    } else {
+        format!("{percent}%")
+    }
+}
+
+/// Index of the offered choice closest to `percent`; automatic only matches `0`.
+pub fn scale_override_choice_index(percent: u32) -> usize {
+    if percent == 0 {
+        return 0;
+    }
+    SCALE_OVERRIDE_CHOICES_PERCENT
+        .iter()
+        .enumerate()
+        .skip(1)
+        .min_by_key(|(_, choice)| choice.abs_diff(percent))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Keeps `0` (automatic) and clamps any other override to a usable range.
+pub fn sanitize_scale_override_percent(percent: u32) -> u32 {
+    if percent == 0 {
+        0
+    } else {
+        percent.clamp(MIN_SCALE_OVERRIDE_PERCENT, MAX_SCALE_OVERRIDE_PERCENT)
+    }
+}
+
+/// Scale factor the window should use on a monitor reporting `monitor_scale_factor`.
+pub fn effective_scale_factor(monitor_scale_factor: f32, override_percent: u32) -> f32 {
+    match sanitize_scale_override_percent(override_percent) {
+        0 => monitor_scale_factor,
+        percent => percent as f32 / 100.0,
+    }
+}
+
+/// Scale factor reported by the monitor the window is on, once the window exists.
+fn monitor_scale_factor(window: &slint::Window) -> Option<f32> {
+    window.with_winit_window(|winit_window| winit_window.scale_factor() as f32)
+}
+
+/// Applies the override, or the monitor's own factor when set back to automatic.
+pub fn apply_scale_override(window: &slint::Window, override_percent: u32) {
+    let Some(monitor_scale_factor) = monitor_scale_factor(window) else {
+        return;
+    };
+    let scale_factor = effective_scale_factor(monitor_scale_factor, override_percent);
+    if (window.scale_factor() - scale_factor).abs() > f32::EPSILON {
+        info!("Window scale factor set to {scale_factor} (monitor {monitor_scale_factor})");
+        window.dispatch_event(slint::platform::WindowEvent::ScaleFactorChanged { scale_factor });
+    }
+}
+
+/// Handles a winit scale change after the window moved to another monitor. Without an
+/// override Slint re-scales to the new monitor; with one, the override is kept.
+pub fn handle_monitor_scale_changed(
+    window: &slint::Window,
+    event: &winit::event::WindowEvent,
+    override_percent: u32,
+) -> WinitEventResult {
+    let winit::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } = event else {
+        return WinitEventResult::Propagate;
+    };
+    info!("Window moved to a display with scale factor {scale_factor}");
+    if sanitize_scale_override_percent(override_percent) == 0 {
+        return WinitEventResult::Propagate;
+    }
+    let scale_factor = effective_scale_factor(*scale_factor as f32, override_percent);
+    window.dispatch_event(slint::platform::WindowEvent::ScaleFactorChanged { scale_factor });
+    WinitEventResult::PreventDefault
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_override_choices_and_sanitizing() {
+        assert_eq!(scale_override_label(0), "Automatic");
+        assert_eq!(scale_override_label(125), "125%");
+        assert_eq!(scale_override_choice_index(0), 0);
+        assert_eq!(scale_override_choice_index(150), 4);
+        assert_eq!(scale_override_choice_index(10), 1);
+        assert_eq!(sanitize_scale_override_percent(0), 0);
+        assert_eq!(sanitize_scale_override_percent(20), 50);
+        assert_eq!(sanitize_scale_override_percent(900), 400);
+    }
+
+    #[test]
+    fn test_effective_scale_factor_prefers_override() {
+        assert_eq!(effective_scale_factor(1.5, 0), 1.5);
+        assert_eq!(effective_scale_factor(1.5, 100), 1.0);
+        assert_eq!(effective_scale_factor(1.0, 200), 2.0);
+    }
+}
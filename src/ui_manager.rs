@@ -5,7 +5,7 @@
 
 use std::hash::{Hash, Hasher};
 use std::io::Read;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{
     cell::RefCell,
     collections::hash_map::DefaultHasher,
@@ -14,7 +14,7 @@ use std::{
     process::Command,
     rc::Rc,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         mpsc::{self, Receiver as StdReceiver, Sender as StdSender},
     },
     thread,
@@ -26,15 +26,34 @@ use slint::{Image, Model, ModelRc, StandardListViewItem, VecModel};
 use tokio::sync::broadcast::{Receiver, Sender};
 
 use crate::{
+    activity_log, cache_manager,
     config::{self, PlaylistColumnConfig},
+    db_manager::DbManager,
+    file_organizer, format_quality,
     image_pipeline::{self, ManagedImageKind},
     integration_keyring::get_opensubsonic_password,
     integration_uri::{is_remote_track_path, parse_opensubsonic_track_uri},
-    layout::PlaylistColumnWidthOverrideConfig,
-    metadata_tags, protocol, text_template, AppWindow, LayoutAlbumArtViewerPanelModel,
-    LayoutMetadataViewerPanelModel, LibraryRowData, MetadataEditorField as UiMetadataEditorField,
+    integrity_check,
+    layout::{
+        ColumnElision, ColumnNumberFormat, ColumnTextAlignment, PlaylistColumnStyleConfig,
+        PlaylistColumnStyleOverrideConfig, PlaylistColumnWidthOverrideConfig,
+    },
+    library_facets, loudness_analysis, lyrics, maintenance_scheduler, metadata_tags, play_stats,
+    playlist_column_filters::{self, ColumnFilterCondition, PlaylistColumnFilter},
+    playlist_document::{self, DocumentCover, PlaylistDocument, PlaylistDocumentFormat},
+    playlist_folders::{self, PlaylistTreeItem, PlaylistTreeMove},
+    playlist_materialize,
+    playlist_sort::{self, PlaylistSortDirection, PlaylistSortKey},
+    playlist_url::{is_web_stream_path, is_web_track_path, web_track_fallback_title},
+    protocol, release_kind, sandbox_access, spectrogram,
+    task_manager::{self, TaskHandle},
+    text_template, track_energy, track_relocation, track_source,
+    ui::{column_format, external_tagger},
+    user_tags, worker_pool, AppWindow, CacheRowData, LayoutAlbumArtViewerPanelModel,
+    LayoutMetadataViewerPanelModel, LibraryFacetRowData, LibraryRowData,
+    MetadataEditorField as UiMetadataEditorField, PlaylistTreeRowData,
     RichTextBlock as UiRichTextBlock, RichTextLine as UiRichTextLine, RichTextRun as UiRichTextRun,
-    TrackRowData,
+    SeekMarkerData, TaskRowData, TrackRowData, UpNextRowData,
 };
 use governor::{Quota, RateLimiter};
 
@@ -47,6 +66,14 @@ const APP_WINDOW_TITLE_PLAY_ICON: &str = "▶";
 const APP_WINDOW_TITLE_PAUSE_ICON: &str = "⏸";
 const APP_WINDOW_TITLE_UNKNOWN_ARTIST: &str = "Unknown Artist";
 const APP_WINDOW_TITLE_UNKNOWN_TITLE: &str = "Unknown Title";
+/// Pixel size of the full-track spectrogram rendered in the Properties dialog.
+const PROPERTIES_SPECTROGRAM_WIDTH: u32 = 720;
+const PROPERTIES_SPECTROGRAM_HEIGHT: u32 = 200;
+/// Album covers shown side by side in an exported playlist document header.
+const PLAYLIST_DOCUMENT_MAX_COVERS: usize = 4;
+/// Tracks from the top of a playlist searched for covers to tile into its collage.
+const PLAYLIST_COLLAGE_SCAN_TRACKS: usize = 64;
+const PLAYLIST_COLLAGE_EDGE_PX: u32 = 256;
 
 /// Shared UI models that are created in `main` and attached to the Slint window.
 pub struct UiState {
@@ -64,6 +91,7 @@ pub struct UiManager {
     list_image_prepare_tx: StdSender<ListImagePrepareRequest>,
     embedded_cover_art_prepare_tx: StdSender<EmbeddedCoverArtPrepareRequest>,
     metadata_lookup_tx: StdSender<MetadataLookupRequest>,
+    track_format_probe_tx: StdSender<Vec<PathBuf>>,
     last_cover_art_lookup_path: Option<PathBuf>,
     pending_cover_art_lookup_request_id: u64,
     pending_cover_art_lookup_track_path: Option<PathBuf>,
@@ -71,6 +99,11 @@ pub struct UiManager {
     active_playlist_id: String,
     playlist_ids: Vec<String>,
     playlist_names: Vec<String>,
+    /// Latest sidebar snapshot, kept to rebuild the folder tree rows.
+    sidebar_playlists: Vec<protocol::PlaylistInfo>,
+    sidebar_playlist_folders: Vec<protocol::PlaylistFolderInfo>,
+    collapsed_playlist_folder_ids: HashSet<String>,
+    playlist_tree_rows: Vec<playlist_folders::PlaylistTreeRow>,
     opensubsonic_sync_eligible_playlist_ids: HashSet<String>,
     unavailable_track_ids: HashSet<String>,
     track_ids: Vec<String>,
@@ -129,33 +162,89 @@ pub struct UiManager {
     last_elapsed_ms: u64,
     last_total_ms: u64,
     playing_track: PlayingTrackState,
+    /// Lyrics of the playing track shown in Lyrics panels, and the line being sung.
+    playing_lyrics: Option<protocol::TrackLyrics>,
+    lyrics_active_line: Option<usize>,
     favorites_by_key: HashMap<String, protocol::FavoriteEntityRef>,
+    user_tags: Vec<protocol::UserTag>,
+    user_tags_by_path: HashMap<PathBuf, Vec<String>>,
+    /// Recorded import sources of playlist entries, keyed by track id.
+    track_sources_by_id: HashMap<String, protocol::ImportSource>,
+    track_tags_dialog_paths: Vec<PathBuf>,
+    track_trims_by_path: HashMap<PathBuf, protocol::TrackTrims>,
+    track_loudness_by_path: HashMap<PathBuf, protocol::TrackLoudness>,
+    track_energy_by_path: HashMap<PathBuf, protocol::TrackEnergySetting>,
+    track_energy_dialog_paths: Vec<PathBuf>,
+    send_to_playlist_paths: Vec<PathBuf>,
+    /// Playlists tracks were last sent to in this session, most recent first.
+    recent_send_to_playlist_ids: Vec<String>,
+    /// Probed format properties backing the quality column, keyed by track path.
+    track_formats_by_path: HashMap<PathBuf, protocol::TechnicalMetadata>,
+    track_file_sizes_by_path: HashMap<PathBuf, u64>,
+    track_play_stats_by_path: HashMap<PathBuf, protocol::TrackPlayStats>,
+    requested_track_format_paths: HashSet<PathBuf>,
+    hi_res_thresholds: format_quality::HiResThresholds,
+    track_transition_dialog_paths: Vec<PathBuf>,
+    track_notes_by_path: HashMap<PathBuf, String>,
+    playlist_notes_by_id: HashMap<String, String>,
+    playlist_column_filters_by_id: HashMap<String, Vec<PlaylistColumnFilter>>,
+    playlist_descriptions_by_id: HashMap<String, String>,
+    playlist_cover_paths_by_id: HashMap<String, PathBuf>,
+    /// Cover collages built from each playlist's tracks, used when it has no own cover.
+    playlist_collage_paths_by_id: HashMap<String, PathBuf>,
+    playlist_collage_requested_ids: HashSet<String>,
+    playlist_collage_tx: StdSender<String>,
+    column_filter_dialog: Option<ColumnFilterDialog>,
+    /// Visible column whose cells the next rebuild collects for the filter dialog.
+    column_filter_cells_column: Option<usize>,
+    column_filter_cells: Vec<String>,
+    skip_silence_playlist_ids: HashSet<String>,
+    normalize_loudness_playlist_ids: HashSet<String>,
+    remote_playlist_sync_statuses: HashMap<String, protocol::RemotePlaylistSyncStatus>,
+    notes_panel_track_path: Option<PathBuf>,
+    notes_panel_playlist_id: String,
     display_target_priority: DisplayTargetPriority,
     current_technical_metadata: Option<protocol::TechnicalMetadata>,
     current_output_path_info: Option<protocol::OutputPathInfo>,
+    current_output_rate_decision: Option<protocol::OutputRateDecision>,
     cast_connected: bool,
     cast_connecting: bool,
     cast_discovering: bool,
+    battery_saver_active: bool,
+    worker_pool_diagnostics: String,
+    startup_timing_diagnostics: String,
+    upcoming_tracks: Vec<protocol::UpcomingTrack>,
     cast_device_name: String,
+    cast_device_id: String,
     cast_playback_path_kind: Option<protocol::CastPlaybackPathKind>,
     cast_transcode_output_metadata: Option<protocol::TechnicalMetadata>,
     cast_device_ids: Vec<String>,
     cast_device_names: Vec<String>,
+    cast_device_available: Vec<bool>,
     playlist_columns: Vec<PlaylistColumnConfig>,
     playlist_column_content_targets_px: Vec<u32>,
     playlist_column_target_widths_px: HashMap<String, u32>,
     playlist_column_widths_px: Vec<u32>,
     playlist_column_width_overrides_px: HashMap<String, u32>,
+    playlist_column_styles: HashMap<String, PlaylistColumnStyleConfig>,
     playlist_columns_available_width_px: u32,
     playlist_columns_content_width_px: u32,
     playlist_row_height_px: u32,
     album_art_column_min_width_px: u32,
     album_art_column_max_width_px: u32,
-    filter_sort_column_key: Option<String>,
-    filter_sort_direction: Option<PlaylistSortDirection>,
+    filter_sort_keys: Vec<PlaylistSortKey>,
     filter_search_query: String,
     filter_search_visible: bool,
     auto_scroll_to_playing_track: bool,
+    /// Set by a manual track list scroll; follow playback resumes afterwards.
+    follow_playback_paused_until: Option<Instant>,
+    /// Playlist the playing track plays from, for jumping to it from other views.
+    playing_playlist_id: Option<String>,
+    /// Center the playing track once the playlist switched to by a jump has loaded.
+    jump_to_playing_pending: bool,
+    zero_based_row_numbers: bool,
+    /// Configured command for "Open in External Tagger"; empty when none is set.
+    external_tagger_command: String,
     playlist_prefetch_first_row: usize,
     playlist_prefetch_row_count: usize,
     playlist_scroll_center_token: i32,
@@ -167,8 +256,14 @@ pub struct UiManager {
     last_health_log_at: Instant,
     last_image_cache_ttl_sweep_at: Instant,
     collection_mode: i32,
+    /// Library root counts are fetched on first library view open rather than at startup.
+    library_root_counts_requested: bool,
     library_view_stack: Vec<LibraryViewState>,
     library_entries: Vec<LibraryEntry>,
+    /// Remote backend copies of local library files, folded into the local file's row.
+    remote_copies_by_path: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Local library files the user chose to play from their remote copy.
+    remote_source_paths: HashSet<PathBuf>,
     /// Mapping from library view row to source index in `library_entries`.
     /// Semantics mirror `view_indices` for playlists: when a search filter is
     /// active the vector contains the filtered permutation; when empty, view
@@ -219,6 +314,9 @@ pub struct UiManager {
     library_page_entries: Vec<protocol::LibraryEntryPayload>,
     library_page_query: String,
     library_search_visible: bool,
+    library_facet_panel_visible: bool,
+    library_facet_selection: protocol::LibraryFacetSelection,
+    library_facet_counts: Vec<protocol::LibraryFacetCount>,
     library_scan_in_progress: bool,
     library_status_text: String,
     pending_metadata_link_fallback: Option<PendingMetadataLinkFallback>,
@@ -228,21 +326,46 @@ pub struct UiManager {
     library_add_to_dialog_visible: bool,
     library_toast_generation: u64,
     pending_paste_feedback: bool,
+    /// Imported and total track counts of the running import-dialog batch.
+    bulk_import_progress: Option<(usize, usize)>,
+    /// Tasks panel entry of the running import-dialog batch.
+    bulk_import_task: Option<TaskHandle>,
     copied_library_selections: Vec<protocol::LibrarySelectionSpec>,
     pending_library_remove_selections: Vec<protocol::LibrarySelectionSpec>,
     pending_library_remove_from_playlists: bool,
     library_remove_eval_nonce: u64,
     pending_library_remove_eval_request_id: Option<u64>,
+    pending_organize_selections: Vec<protocol::LibrarySelectionSpec>,
+    pending_organize_moves: Vec<protocol::OrganizeFileMove>,
+    organize_undo_available: bool,
+    /// Files found missing by the last playlist check, offered for relocation.
+    relocation_missing_paths: Vec<PathBuf>,
+    pending_album_artist_selections: Vec<protocol::LibrarySelectionSpec>,
+    pending_album_artist_suggestions: Vec<protocol::AlbumArtistSuggestion>,
+    /// Tag-writer requests of the running album-artist fix, with their running totals.
+    pending_album_artist_fix_requests: HashSet<u64>,
+    album_artist_fix_saved_count: usize,
+    album_artist_fix_errors: Vec<String>,
     properties_request_nonce: u64,
     properties_pending_request_id: Option<u64>,
     properties_pending_request_kind: Option<PropertiesRequestKind>,
     properties_target_path: Option<PathBuf>,
+    /// Tracks of a multi-selection edit; empty when editing a single track.
+    properties_batch_paths: Vec<PathBuf>,
+    /// Per-field "values differ" flags of a multi-selection edit.
+    properties_field_mixed: Vec<bool>,
+    /// Per-field "write on save" toggles of a multi-selection edit.
+    properties_field_apply: Vec<bool>,
     properties_target_title: String,
     properties_original_fields: Vec<protocol::MetadataEditorField>,
     properties_fields: Vec<protocol::MetadataEditorField>,
     properties_dialog_visible: bool,
     properties_busy: bool,
     properties_error_text: String,
+    /// Outcome of the last online tag lookup, shown until the dialog closes.
+    properties_status_text: String,
+    /// Full-track spectrogram render in flight; replies with another id are stale.
+    properties_spectrogram_request_id: Option<u64>,
 }
 
 /// Normalized track metadata snapshot used for row rendering and side panel display.
@@ -256,6 +379,10 @@ struct TrackMetadata {
     year: String,
     genre: String,
     track_number: String,
+    composer: String,
+    disc_number: String,
+    total_tracks: String,
+    comment: String,
 }
 
 #[derive(Clone, Default)]
@@ -285,6 +412,7 @@ struct TechnicalInfoTemplateFields {
     technical_channel_from_channels: String,
     technical_channel_to_channels: String,
     technical_dithered: String,
+    technical_quality: String,
 }
 
 impl TechnicalInfoTemplateFields {
@@ -315,6 +443,7 @@ impl TechnicalInfoTemplateFields {
             technical_channel_from_channels: &self.technical_channel_from_channels,
             technical_channel_to_channels: &self.technical_channel_to_channels,
             technical_dithered: &self.technical_dithered,
+            technical_quality: &self.technical_quality,
         }
     }
 }
@@ -401,16 +530,32 @@ struct MetadataLookupRequest {
     track_path: PathBuf,
 }
 
+/// Column the filter dialog is open for, with the values its checklist shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnFilterDialog {
+    column_key: String,
+    values: Vec<String>,
+}
+
+/// How rows are compared on one sorted playlist column. Badges and measurements sort by
+/// value rather than by their label text.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum PlaylistSortDirection {
-    Ascending,
-    Descending,
+enum PlaylistSortValue {
+    Text,
+    Quality,
+    TagNumber,
+    Energy,
+    RowNumber(&'static str),
+    PlayStats(&'static str),
+    Number(&'static str),
+    Loudness(&'static str),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum PropertiesRequestKind {
     Load,
     Save,
+    Lookup,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -469,6 +614,12 @@ struct LibraryRowPresentation {
     secondary: String,
     primary_rich: text_template::RenderedText,
     secondary_rich: text_template::RenderedText,
+    /// Artist and year line under album tiles in the album grid.
+    caption: String,
+    /// Albums/EPs/Singles heading above the first release of each kind on an artist page.
+    section_title: String,
+    /// Shows a play button on album rows of an artist page.
+    show_play_button: bool,
     item_kind: i32,
     cover_art_path: Option<PathBuf>,
     source_badge: String,
@@ -516,6 +667,12 @@ const TEXT_ROW_VERTICAL_PADDING_PX: u32 = 8;
 const DEFAULT_TEXT_LINE_HEIGHT_PX: u32 = 17;
 const COLLECTION_MODE_PLAYLIST: i32 = 0;
 const COLLECTION_MODE_LIBRARY: i32 = 1;
+/// How long a manual scroll of a track list keeps follow playback from scrolling it.
+const FOLLOW_PLAYBACK_MANUAL_SCROLL_PAUSE: Duration = Duration::from_secs(20);
+/// Longest start or end trim the transition dialog accepts.
+const MAX_TRIM_SECONDS: f64 = 600.0;
+/// Recent targets offered as one-click shortcuts in the send-to-playlist picker.
+const RECENT_SEND_TO_PLAYLIST_LIMIT: usize = 3;
 const VIEWER_DISPLAY_PRIORITY_DEFAULT: i32 = 0;
 const VIEWER_DISPLAY_PRIORITY_PREFER_SELECTION: i32 = 1;
 const VIEWER_DISPLAY_PRIORITY_PREFER_NOW_PLAYING: i32 = 2;
@@ -585,6 +742,8 @@ static COVER_ART_MEMORY_CACHE_BUDGET_BYTES: AtomicU64 =
     AtomicU64::new(DEFAULT_IMAGE_MEMORY_CACHE_MAX_BYTES);
 static ARTIST_IMAGE_MEMORY_CACHE_BUDGET_BYTES: AtomicU64 =
     AtomicU64::new(DEFAULT_IMAGE_MEMORY_CACHE_MAX_BYTES);
+/// Mirrors `library.slow_storage_mode` for the cover art and format probe workers.
+static SLOW_STORAGE_MODE: AtomicBool = AtomicBool::new(false);
 
 #[derive(Default)]
 struct PathImageCache {
@@ -1046,13 +1205,26 @@ impl UiManager {
     }
 
     fn source_badge_for_track_path(path: &Path) -> String {
-        if is_remote_track_path(path) {
+        if is_web_stream_path(path) {
+            "stream".to_string()
+        } else if is_web_track_path(path) {
+            "web".to_string()
+        } else if is_remote_track_path(path) {
             "opensubsonic".to_string()
         } else {
             String::new()
         }
     }
 
+    /// Source badge for a library row; local files with remote copies get a combined badge.
+    fn library_source_badge(&self, path: &Path) -> String {
+        if self.remote_copies_by_path.contains_key(path) {
+            "linked".to_string()
+        } else {
+            Self::source_badge_for_track_path(path)
+        }
+    }
+
     fn normalize_favorite_component(value: &str) -> String {
         value.trim().to_ascii_lowercase()
     }
@@ -1202,7 +1374,7 @@ impl UiManager {
         let names = ["cover", "front", "folder", "album", "art"];
         let extensions = ["jpg", "jpeg", "png", "webp"];
 
-        if let Ok(entries) = std::fs::read_dir(parent) {
+        if let Ok(entries) = sandbox_access::read_dir(parent) {
             let mut found_files = Vec::new();
             for entry in entries.flatten() {
                 let path = entry.path();
@@ -1271,7 +1443,7 @@ impl UiManager {
     }
 
     fn is_valid_image_file(path: &Path) -> bool {
-        let mut file = match std::fs::File::open(path) {
+        let mut file = match sandbox_access::open_file(path) {
             Ok(file) => file,
             Err(_) => return false,
         };
@@ -1486,6 +1658,14 @@ impl UiManager {
         None
     }
 
+    fn library_album_caption(album: &protocol::LibraryAlbum) -> String {
+        match Self::parse_library_year(&album.year) {
+            Some(year) if album.album_artist.is_empty() => year.to_string(),
+            Some(year) => format!("{} • {year}", album.album_artist),
+            None => album.album_artist.clone(),
+        }
+    }
+
     fn build_artist_detail_entries(
         albums: Vec<protocol::LibraryAlbum>,
         tracks: Vec<protocol::LibraryTrack>,
@@ -1527,6 +1707,10 @@ impl UiManager {
                 album_artist: key.1.clone(),
                 track_count: album_tracks.len() as u32,
                 representative_track_path: album_tracks.first().map(|track| track.path.clone()),
+                year: album_year_by_key
+                    .get(key)
+                    .map(|year| year.to_string())
+                    .unwrap_or_default(),
             };
             let year = album_year_by_key.get(key).copied();
             ordered_albums.push((key.clone(), synthetic_album, year));
@@ -1534,8 +1718,9 @@ impl UiManager {
 
         ordered_albums.sort_by(
             |(left_key, left_album, left_year), (right_key, right_album, right_year)| {
-                right_year
-                    .cmp(left_year)
+                release_kind::release_kind(left_album)
+                    .cmp(&release_kind::release_kind(right_album))
+                    .then_with(|| right_year.cmp(left_year))
                     .then_with(|| {
                         left_album
                             .album
@@ -1690,6 +1875,7 @@ impl UiManager {
         initial_library_config: config::LibraryConfig,
         library_scan_progress_rx: StdReceiver<protocol::LibraryMessage>,
     ) -> Self {
+        SLOW_STORAGE_MODE.store(initial_library_config.slow_storage_mode, Ordering::Relaxed);
         let (cover_art_lookup_tx, cover_art_lookup_rx) = mpsc::channel::<CoverArtLookupRequest>();
         let cover_art_bus_sender = bus_sender.clone();
         thread::spawn(move || {
@@ -1778,9 +1964,12 @@ impl UiManager {
                     if is_remote_track_path(track_path.as_path()) {
                         continue;
                     }
-                    let Some(cover_art_path) =
+                    let cover_art_path = if SLOW_STORAGE_MODE.load(Ordering::Relaxed) {
+                        UiManager::copy_cover_art_locally(track_path.as_path())
+                    } else {
                         UiManager::extract_embedded_art(track_path.as_path())
-                    else {
+                    };
+                    let Some(cover_art_path) = cover_art_path else {
                         continue;
                     };
                     if image_pipeline::ensure_list_thumbnail(
@@ -1831,6 +2020,89 @@ impl UiManager {
                 ));
             }
         });
+        let (track_format_probe_tx, track_format_probe_rx) = mpsc::channel::<Vec<PathBuf>>();
+        let track_format_bus_sender = bus_sender.clone();
+        thread::spawn(move || {
+            // Probed formats are stored so slow storage mode can skip probing next time.
+            let mut format_cache: Option<DbManager> = None;
+            while let Ok(mut paths) = track_format_probe_rx.recv() {
+                if format_cache.is_none() {
+                    format_cache = DbManager::new()
+                        .map_err(|err| warn!("Failed to open track format cache: {}", err))
+                        .ok();
+                }
+                let mut formats: Vec<(PathBuf, protocol::TechnicalMetadata)> = Vec::new();
+                if SLOW_STORAGE_MODE.load(Ordering::Relaxed) {
+                    if let Some(db) = format_cache.as_ref() {
+                        match db.get_track_formats(&paths) {
+                            Ok(cached) => formats = cached,
+                            Err(err) => warn!("Failed to load cached track formats: {}", err),
+                        }
+                    }
+                    paths.retain(|path| !formats.iter().any(|(cached, _)| cached == path));
+                }
+                let probed: Vec<(PathBuf, protocol::TechnicalMetadata)> = paths
+                    .into_iter()
+                    .filter_map(|path| {
+                        let format = format_quality::probe_track_format(&path)?;
+                        Some((path, format))
+                    })
+                    .collect();
+                if let Some(db) = format_cache.as_ref().filter(|_| !probed.is_empty()) {
+                    if let Err(err) = db.save_track_formats(&probed) {
+                        warn!("Failed to cache track formats: {}", err);
+                    }
+                }
+                formats.extend(probed);
+                if formats.is_empty() {
+                    continue;
+                }
+                let _ = track_format_bus_sender.send(protocol::Message::Playlist(
+                    protocol::PlaylistMessage::TrackFormatsProbed(formats),
+                ));
+            }
+        });
+        let (playlist_collage_tx, playlist_collage_rx) = mpsc::channel::<String>();
+        let playlist_collage_bus_sender = bus_sender.clone();
+        thread::spawn(move || {
+            let mut db_manager: Option<DbManager> = None;
+            while let Ok(playlist_id) = playlist_collage_rx.recv() {
+                let mut playlist_ids = vec![playlist_id];
+                for playlist_id in playlist_collage_rx.try_iter() {
+                    if !playlist_ids.contains(&playlist_id) {
+                        playlist_ids.push(playlist_id);
+                    }
+                }
+                if db_manager.is_none() {
+                    db_manager = DbManager::new_read_only()
+                        .map_err(|err| warn!("Failed to open database for collages: {}", err))
+                        .ok();
+                }
+                let Some(db) = db_manager.as_ref() else {
+                    continue;
+                };
+                for playlist_id in playlist_ids {
+                    let tracks = match db.get_tracks_for_playlist(&playlist_id) {
+                        Ok(tracks) => tracks,
+                        Err(err) => {
+                            warn!("Failed to load tracks for playlist collage: {}", err);
+                            continue;
+                        }
+                    };
+                    let cover_paths = UiManager::playlist_collage_cover_paths(&tracks);
+                    let collage_path = image_pipeline::ensure_playlist_collage(
+                        &cover_paths,
+                        PLAYLIST_COLLAGE_EDGE_PX,
+                    );
+                    let _ = playlist_collage_bus_sender.send(protocol::Message::Playlist(
+                        protocol::PlaylistMessage::PlaylistCollageReady {
+                            playlist_id,
+                            collage_path,
+                        },
+                    ));
+                }
+            }
+        });
 
         let enrichment_prefetch_tick_sender = bus_sender.clone();
         thread::spawn(move || loop {
@@ -1849,6 +2121,7 @@ impl UiManager {
             .layout
             .playlist_column_width_overrides
             .clone();
+        let initial_layout_column_styles = initial_ui_config.layout.playlist_column_styles.clone();
         let initial_image_memory_cache_ttl_secs =
             initial_library_config.image_memory_cache_ttl_secs.max(1);
 
@@ -1861,6 +2134,7 @@ impl UiManager {
             list_image_prepare_tx,
             embedded_cover_art_prepare_tx,
             metadata_lookup_tx,
+            track_format_probe_tx,
             last_cover_art_lookup_path: None,
             pending_cover_art_lookup_request_id: 0,
             pending_cover_art_lookup_track_path: None,
@@ -1868,6 +2142,10 @@ impl UiManager {
             active_playlist_id: String::new(),
             playlist_ids: Vec::new(),
             playlist_names: Vec::new(),
+            sidebar_playlists: Vec::new(),
+            sidebar_playlist_folders: Vec::new(),
+            collapsed_playlist_folder_ids: HashSet::new(),
+            playlist_tree_rows: Vec::new(),
             opensubsonic_sync_eligible_playlist_ids: HashSet::new(),
             unavailable_track_ids: HashSet::new(),
             track_ids: Vec::new(),
@@ -1891,33 +2169,82 @@ impl UiManager {
             last_elapsed_ms: 0,
             last_total_ms: 0,
             playing_track: PlayingTrackState::default(),
+            playing_lyrics: None,
+            lyrics_active_line: None,
             favorites_by_key: HashMap::new(),
+            user_tags: Vec::new(),
+            user_tags_by_path: HashMap::new(),
+            track_sources_by_id: HashMap::new(),
+            track_tags_dialog_paths: Vec::new(),
+            track_trims_by_path: HashMap::new(),
+            track_loudness_by_path: HashMap::new(),
+            track_energy_by_path: HashMap::new(),
+            track_energy_dialog_paths: Vec::new(),
+            send_to_playlist_paths: Vec::new(),
+            recent_send_to_playlist_ids: Vec::new(),
+            track_formats_by_path: HashMap::new(),
+            track_file_sizes_by_path: HashMap::new(),
+            track_play_stats_by_path: HashMap::new(),
+            requested_track_format_paths: HashSet::new(),
+            hi_res_thresholds: format_quality::HiResThresholds {
+                min_sample_rate_hz: initial_ui_config.hi_res_min_sample_rate_hz,
+                min_bit_depth: initial_ui_config.hi_res_min_bit_depth,
+            },
+            track_transition_dialog_paths: Vec::new(),
+            track_notes_by_path: HashMap::new(),
+            playlist_notes_by_id: HashMap::new(),
+            playlist_column_filters_by_id: HashMap::new(),
+            playlist_descriptions_by_id: HashMap::new(),
+            playlist_cover_paths_by_id: HashMap::new(),
+            playlist_collage_paths_by_id: HashMap::new(),
+            playlist_collage_requested_ids: HashSet::new(),
+            playlist_collage_tx,
+            column_filter_dialog: None,
+            column_filter_cells_column: None,
+            column_filter_cells: Vec::new(),
+            skip_silence_playlist_ids: HashSet::new(),
+            normalize_loudness_playlist_ids: HashSet::new(),
+            remote_playlist_sync_statuses: HashMap::new(),
+            notes_panel_track_path: None,
+            notes_panel_playlist_id: String::new(),
             display_target_priority: DisplayTargetPriority::Playing,
             current_technical_metadata: None,
             current_output_path_info: None,
+            current_output_rate_decision: None,
             cast_connected: false,
             cast_connecting: false,
             cast_discovering: false,
+            battery_saver_active: false,
+            worker_pool_diagnostics: String::new(),
+            startup_timing_diagnostics: String::new(),
+            upcoming_tracks: Vec::new(),
             cast_device_name: String::new(),
+            cast_device_id: String::new(),
             cast_playback_path_kind: None,
             cast_transcode_output_metadata: None,
             cast_device_ids: Vec::new(),
             cast_device_names: Vec::new(),
+            cast_device_available: Vec::new(),
             playlist_columns: initial_ui_config.playlist_columns.clone(),
             playlist_column_content_targets_px: Vec::new(),
             playlist_column_target_widths_px: HashMap::new(),
             playlist_column_widths_px: Vec::new(),
             playlist_column_width_overrides_px: HashMap::new(),
+            playlist_column_styles: HashMap::new(),
             playlist_columns_available_width_px: 0,
             playlist_columns_content_width_px: 0,
             playlist_row_height_px: BASE_ROW_HEIGHT_PX,
             album_art_column_min_width_px: initial_ui_config.playlist_album_art_column_min_width_px,
             album_art_column_max_width_px: initial_ui_config.playlist_album_art_column_max_width_px,
-            filter_sort_column_key: None,
-            filter_sort_direction: None,
+            filter_sort_keys: Vec::new(),
             filter_search_query: String::new(),
             filter_search_visible: false,
             auto_scroll_to_playing_track: initial_ui_config.auto_scroll_to_playing_track,
+            follow_playback_paused_until: None,
+            playing_playlist_id: None,
+            jump_to_playing_pending: false,
+            zero_based_row_numbers: initial_ui_config.zero_based_row_numbers,
+            external_tagger_command: initial_ui_config.external_tagger_command.clone(),
             playlist_prefetch_first_row: 0,
             playlist_prefetch_row_count: 0,
             playlist_scroll_center_token: 0,
@@ -1929,8 +2256,11 @@ impl UiManager {
             last_health_log_at: Instant::now(),
             last_image_cache_ttl_sweep_at: Instant::now(),
             collection_mode: COLLECTION_MODE_PLAYLIST,
+            library_root_counts_requested: false,
             library_view_stack: vec![LibraryViewState::TracksRoot],
             library_entries: Vec::new(),
+            remote_copies_by_path: HashMap::new(),
+            remote_source_paths: HashSet::new(),
             library_view_indices: Vec::new(),
             library_rows_with_album_art: HashSet::new(),
             library_selected_indices: Vec::new(),
@@ -1981,6 +2311,9 @@ impl UiManager {
             library_page_entries: Vec::new(),
             library_page_query: String::new(),
             library_search_visible: false,
+            library_facet_panel_visible: false,
+            library_facet_selection: protocol::LibraryFacetSelection::default(),
+            library_facet_counts: Vec::new(),
             library_scan_in_progress: false,
             library_status_text: String::new(),
             pending_metadata_link_fallback: None,
@@ -1990,25 +2323,42 @@ impl UiManager {
             library_add_to_dialog_visible: false,
             library_toast_generation: 0,
             pending_paste_feedback: false,
+            bulk_import_progress: None,
+            bulk_import_task: None,
             copied_library_selections: Vec::new(),
             pending_library_remove_selections: Vec::new(),
             pending_library_remove_from_playlists: false,
             library_remove_eval_nonce: 0,
             pending_library_remove_eval_request_id: None,
+            pending_organize_selections: Vec::new(),
+            pending_organize_moves: Vec::new(),
+            organize_undo_available: false,
+            relocation_missing_paths: Vec::new(),
+            pending_album_artist_selections: Vec::new(),
+            pending_album_artist_suggestions: Vec::new(),
+            pending_album_artist_fix_requests: HashSet::new(),
+            album_artist_fix_saved_count: 0,
+            album_artist_fix_errors: Vec::new(),
             properties_request_nonce: 0,
             properties_pending_request_id: None,
             properties_pending_request_kind: None,
             properties_target_path: None,
+            properties_batch_paths: Vec::new(),
+            properties_field_mixed: Vec::new(),
+            properties_field_apply: Vec::new(),
             properties_target_title: String::new(),
             properties_original_fields: Vec::new(),
             properties_fields: Vec::new(),
             properties_dialog_visible: false,
             properties_busy: false,
             properties_error_text: String::new(),
+            properties_status_text: String::new(),
+            properties_spectrogram_request_id: None,
         };
         // Seed column-width overrides from startup layout so playlist rendering does not depend on
         // racing the asynchronous `ConfigLoaded` bus message.
         manager.apply_layout_column_width_overrides(&initial_layout_width_overrides);
+        manager.apply_layout_column_styles(&initial_layout_column_styles);
         manager.refresh_playlist_column_content_targets();
         manager.apply_playlist_column_layout();
         manager
@@ -2091,18 +2441,28 @@ impl UiManager {
         )
     }
 
+    fn remote_source_name(path: &Path) -> Option<&'static str> {
+        if is_web_stream_path(path) {
+            Some("Web stream")
+        } else if is_web_track_path(path) {
+            Some("Web file")
+        } else {
+            is_remote_track_path(path).then_some("OpenSubsonic")
+        }
+    }
+
     fn current_track_source_label(&self) -> Option<&'static str> {
         self.playing_track
             .path
             .as_ref()
-            .and_then(|path| is_remote_track_path(path.as_path()).then_some("OpenSubsonic"))
+            .and_then(|path| Self::remote_source_name(path.as_path()))
     }
 
     fn current_track_source_provider(&self) -> Option<&'static str> {
         self.playing_track
             .path
             .as_ref()
-            .and_then(|path| is_remote_track_path(path.as_path()).then_some("OpenSubsonic"))
+            .and_then(|path| Self::remote_source_name(path.as_path()))
     }
 
     fn render_local_transform_text(&self) -> String {
@@ -2137,6 +2497,24 @@ impl UiManager {
         }
     }
 
+    fn render_output_rate_decision_text(decision: protocol::OutputRateDecision) -> String {
+        let reason = match decision.reason {
+            protocol::OutputRateReason::FixedRate => "fixed in settings",
+            protocol::OutputRateReason::PinnedForDevice => "pinned for this device",
+            protocol::OutputRateReason::MatchesSource => "matches source",
+            protocol::OutputRateReason::NearestSupported => "nearest supported rate",
+            protocol::OutputRateReason::AlbumRate => "album rate",
+            protocol::OutputRateReason::SameFamily => "same rate family",
+            protocol::OutputRateReason::IsolatedTrack => "isolated track resampled",
+            protocol::OutputRateReason::SwitchPending => "switch pending",
+        };
+        format!(
+            "Output {}: {}",
+            Self::format_rate_hz_text(decision.output_rate_hz),
+            reason
+        )
+    }
+
     fn render_technical_info_fields(&self) -> TechnicalInfoTemplateFields {
         if self.current_technical_metadata.is_none()
             && !self.cast_connected
@@ -2157,6 +2535,9 @@ impl UiManager {
             fields.technical_channels = meta.channel_count.to_string();
             fields.technical_bitrate_kbps = meta.bitrate_kbps.to_string();
             fields.technical_duration_ms = meta.duration_ms.to_string();
+            // Decoder metadata fills in defaults for lossy codecs, so the badge uses the
+            // header probe shared with the quality column.
+            fields.technical_quality = self.playing_track_quality_label();
         }
 
         if self.cast_connected {
@@ -2254,10 +2635,27 @@ impl UiManager {
         } else {
             String::new()
         };
+        let rate_decision = match self.current_output_rate_decision {
+            Some(decision)
+                if !self.cast_connected
+                    && !self.cast_connecting
+                    && self.current_technical_metadata.is_some() =>
+            {
+                Self::render_output_rate_decision_text(decision)
+            }
+            _ => String::new(),
+        };
+        let power_status = if self.battery_saver_active {
+            "Battery saver"
+        } else {
+            ""
+        };
         fields.technical_info = [
             fields.technical_source.as_str(),
             fields.technical_cast_status.as_str(),
             fields.technical_playback_path.as_str(),
+            rate_decision.as_str(),
+            power_status,
         ]
         .into_iter()
         .filter(|section| !section.trim().is_empty())
@@ -2306,19 +2704,41 @@ impl UiManager {
             .cloned()
             .map(slint::SharedString::from)
             .collect::<Vec<_>>();
+        let device_available = self.cast_device_available.clone();
+        let device_id = self.cast_device_id.clone();
         let _ = self.ui.upgrade_in_event_loop(move |ui| {
             ui.set_cast_connected(connected);
             ui.set_cast_connecting(connecting);
+            ui.set_cast_device_id(device_id.into());
+            if !connected {
+                ui.set_volume_muted(false);
+            }
             ui.set_cast_connection_label(label.into());
             ui.set_cast_device_names(ModelRc::from(Rc::new(VecModel::from(device_names))));
             ui.set_cast_device_ids(ModelRc::from(Rc::new(VecModel::from(device_ids))));
+            ui.set_cast_device_available(ModelRc::from(Rc::new(VecModel::from(device_available))));
         });
     }
 
     fn find_local_cover_art(track_path: &Path) -> Option<PathBuf> {
+        if SLOW_STORAGE_MODE.load(Ordering::Relaxed) {
+            return Self::copy_cover_art_locally(track_path);
+        }
         Self::find_external_cover_art(track_path).or_else(|| Self::extract_embedded_art(track_path))
     }
 
+    /// Copies the folder cover image, or else the embedded art, of `track_path` into the
+    /// cover cache, so slow storage is not read for it again.
+    fn copy_cover_art_locally(track_path: &Path) -> Option<PathBuf> {
+        if let Some(cached) = Self::embedded_art_cache_path_if_present(track_path) {
+            return Some(cached);
+        }
+        Self::find_external_cover_art(track_path)
+            .and_then(|cover_path| sandbox_access::read_file(&cover_path).ok())
+            .and_then(|cover_bytes| Self::cache_cover_art_bytes(track_path, &cover_bytes))
+            .or_else(|| Self::extract_embedded_art(track_path))
+    }
+
     fn find_cover_art(
         track_path: &Path,
         password_cache: &mut HashMap<String, Option<String>>,
@@ -2329,6 +2749,34 @@ impl UiManager {
         Self::find_local_cover_art(track_path)
     }
 
+    /// Covers of up to four different albums among the first tracks of a playlist, for
+    /// its collage. Remote tracks only contribute art that is already cached.
+    fn playlist_collage_cover_paths(tracks: &[protocol::RestoredTrack]) -> Vec<PathBuf> {
+        let mut cover_paths: Vec<PathBuf> = Vec::new();
+        let mut seen_folders: HashSet<&Path> = HashSet::new();
+        for track in tracks.iter().take(PLAYLIST_COLLAGE_SCAN_TRACKS) {
+            let cover_path = if is_remote_track_path(&track.path) {
+                Self::embedded_art_cache_path_if_present(&track.path)
+            } else {
+                let folder = track.path.parent().unwrap_or(track.path.as_path());
+                if !seen_folders.insert(folder) {
+                    continue;
+                }
+                Self::find_local_cover_art(&track.path)
+            };
+            let Some(cover_path) = cover_path else {
+                continue;
+            };
+            if !cover_paths.contains(&cover_path) {
+                cover_paths.push(cover_path);
+            }
+            if cover_paths.len() == 4 {
+                break;
+            }
+        }
+        cover_paths
+    }
+
     fn extract_embedded_art(track_path: &Path) -> Option<PathBuf> {
         let cache_dir = Self::covers_cache_dir()?;
         if !cache_dir.exists() {
@@ -2361,6 +2809,120 @@ impl UiManager {
         });
     }
 
+    fn is_source_column_visible(&self) -> bool {
+        self.playlist_columns.iter().any(|column| {
+            column.enabled
+                && !column.custom
+                && Self::normalize_column_format(&column.format)
+                    == track_source::SOURCE_COLUMN_FORMAT
+        })
+    }
+
+    fn is_loudness_column_visible(&self) -> bool {
+        self.playlist_columns
+            .iter()
+            .any(|column| column.enabled && Self::loudness_builtin_format(column).is_some())
+    }
+
+    fn is_energy_column_visible(&self) -> bool {
+        self.playlist_columns
+            .iter()
+            .any(|column| column.enabled && Self::is_energy_builtin_column(column))
+    }
+
+    fn is_quality_column_visible(&self) -> bool {
+        self.playlist_columns.iter().any(|column| {
+            column.enabled
+                && !column.custom
+                && Self::normalize_column_format(&column.format)
+                    == format_quality::QUALITY_COLUMN_FORMAT
+        })
+    }
+
+    fn is_play_stats_column_visible(&self) -> bool {
+        self.playlist_columns
+            .iter()
+            .any(|column| column.enabled && Self::play_stats_builtin_format(column).is_some())
+    }
+
+    fn is_row_number_column_visible(&self) -> bool {
+        self.playlist_columns
+            .iter()
+            .any(|column| column.enabled && Self::row_number_builtin_format(column).is_some())
+    }
+
+    fn is_number_column_visible(&self) -> bool {
+        self.playlist_columns
+            .iter()
+            .any(|column| column.enabled && Self::number_builtin_format(column).is_some())
+    }
+
+    /// Fills the file size cache for local playlist tracks when a size column is shown.
+    fn refresh_track_file_sizes(&mut self) {
+        let size_column_visible = self.playlist_columns.iter().any(|column| {
+            column.enabled
+                && Self::number_builtin_format(column)
+                    == Some(column_format::FILE_SIZE_COLUMN_FORMAT)
+        });
+        if !size_column_visible {
+            return;
+        }
+        for path in &self.track_paths {
+            if is_remote_track_path(path) || self.track_file_sizes_by_path.contains_key(path) {
+                continue;
+            }
+            if let Ok(metadata) = std::fs::metadata(path) {
+                self.track_file_sizes_by_path
+                    .insert(path.clone(), metadata.len());
+            }
+        }
+    }
+
+    fn needs_track_format_probe(&self, path: &Path) -> bool {
+        !is_remote_track_path(path)
+            && !self.track_formats_by_path.contains_key(path)
+            && !self.requested_track_format_paths.contains(path)
+    }
+
+    fn queue_track_format_probes(&mut self, paths: Vec<PathBuf>) {
+        if paths.is_empty() {
+            return;
+        }
+        self.requested_track_format_paths
+            .extend(paths.iter().cloned());
+        let _ = self.track_format_probe_tx.send(paths);
+    }
+
+    /// Queues header probes for local playlist tracks whose format is not known yet.
+    fn request_missing_track_formats(&mut self) {
+        let missing: Vec<PathBuf> = self
+            .track_paths
+            .iter()
+            .filter(|path| self.needs_track_format_probe(path))
+            .cloned()
+            .collect();
+        self.queue_track_format_probes(missing);
+    }
+
+    fn request_playing_track_format(&mut self) {
+        let Some(path) = self.playing_track.path.clone() else {
+            return;
+        };
+        if self.needs_track_format_probe(&path) {
+            self.queue_track_format_probes(vec![path]);
+        }
+    }
+
+    fn playing_track_quality_label(&self) -> String {
+        self.playing_track
+            .path
+            .as_ref()
+            .and_then(|path| self.track_formats_by_path.get(path))
+            .map(|meta| format_quality::classify_format_quality(meta, self.hi_res_thresholds))
+            .map(format_quality::FormatQuality::label)
+            .unwrap_or_default()
+    }
+
     fn is_album_art_column_visible(&self) -> bool {
         self.playlist_columns
             .iter()
@@ -2693,6 +3255,12 @@ impl UiManager {
             &track_metadata.track_number,
             track_path,
         )
+        .with_track_details(
+            &track_metadata.composer,
+            &track_metadata.disc_number,
+            &track_metadata.total_tracks,
+            &track_metadata.comment,
+        )
         .with_indicator_symbols(playing_indicator, favorite_indicator)
     }
 
@@ -2868,6 +3436,223 @@ impl UiManager {
         Self::render_column_rich_value(track_metadata, track_path, format_string).plain_text
     }
 
+    /// Builds the document of the open playlist from the track list: rows in view order,
+    /// `columns` rendered as they are in the list, and the covers of the first albums.
+    fn build_playlist_document(
+        &self,
+        columns: &[PlaylistColumnConfig],
+        include_covers: bool,
+    ) -> PlaylistDocument {
+        let row_count = if self.view_indices.is_empty() {
+            self.track_metadata.len()
+        } else {
+            self.view_indices.len()
+        };
+        let source_indices: Vec<usize> = (0..row_count)
+            .filter_map(|view_index| self.map_view_to_source_index(view_index))
+            .collect();
+        let rows = source_indices
+            .iter()
+            .filter_map(|source_index| {
+                let metadata = self.track_metadata.get(*source_index)?;
+                let path = self.track_paths.get(*source_index).map(PathBuf::as_path);
+                Some(
+                    columns
+                        .iter()
+                        .map(|column| Self::render_column_value(metadata, path, &column.format))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        let mut covers: Vec<DocumentCover> = Vec::new();
+        let mut seen_albums = HashSet::new();
+        for source_index in source_indices.iter().copied() {
+            if !include_covers || covers.len() >= PLAYLIST_DOCUMENT_MAX_COVERS {
+                break;
+            }
+            let (Some(metadata), Some(track_path)) = (
+                self.track_metadata.get(source_index),
+                self.track_paths.get(source_index),
+            ) else {
+                continue;
+            };
+            if !seen_albums.insert((metadata.album.clone(), metadata.album_artist.clone())) {
+                continue;
+            }
+            let cover_path = if is_remote_track_path(track_path) {
+                self.track_cover_art_paths
+                    .get(source_index)
+                    .cloned()
+                    .flatten()
+            } else {
+                Self::find_local_cover_art(track_path)
+            };
+            let Some(cover_path) = cover_path else {
+                continue;
+            };
+            if covers.iter().any(|cover| cover.path == cover_path) {
+                continue;
+            }
+            if let Ok(bytes) = sandbox_access::read_file(&cover_path) {
+                covers.push(DocumentCover {
+                    path: cover_path,
+                    bytes,
+                });
+            }
+        }
+
+        let playlist = self
+            .sidebar_playlists
+            .iter()
+            .find(|playlist| playlist.id == self.active_playlist_id);
+        PlaylistDocument {
+            title: playlist
+                .map(|playlist| playlist.name.clone())
+                .unwrap_or_else(|| "Playlist".to_string()),
+            note: playlist
+                .map(|playlist| playlist.note.clone())
+                .unwrap_or_default(),
+            columns: columns.iter().map(|column| column.name.clone()).collect(),
+            rows,
+            covers,
+        }
+    }
+
+    fn export_playlist_document(
+        &self,
+        index: usize,
+        path: &Path,
+        format: PlaylistDocumentFormat,
+        columns: &[PlaylistColumnConfig],
+        include_covers: bool,
+    ) -> Result<String, String> {
+        if self.playlist_ids.get(index) != Some(&self.active_playlist_id) {
+            return Err("open the playlist before exporting it".to_string());
+        }
+        let document = self.build_playlist_document(columns, include_covers);
+        std::fs::write(path, playlist_document::render_document(&document, format))
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+        Ok(format!(
+            "Exported \"{}\" as {} ({} track(s))",
+            document.title,
+            format.label(),
+            document.rows.len()
+        ))
+    }
+
+    fn sync_relocation_dialog(&self, visible: bool, summary: String, status: String) {
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_relocation_summary_text(summary.into());
+            ui.set_relocation_status_text(status.into());
+            ui.set_show_relocation_dialog(visible);
+        });
+    }
+
+    fn relocation_summary_text(&self, checked: Option<usize>, playlist_name: &str) -> String {
+        let missing = self.relocation_missing_paths.len();
+        let count_text = match checked {
+            Some(checked) => format!("{missing} of {checked} local files in \"{playlist_name}\""),
+            None => format!("{missing} file(s)"),
+        };
+        let folder_text = track_relocation::common_folder(&self.relocation_missing_paths)
+            .map(|folder| format!(" They were under {}.", folder.display()))
+            .unwrap_or_default();
+        format!(
+            "{count_text} could not be found.{folder_text} Choose the folder they moved to and \
+             the same files are looked up there."
+        )
+    }
+
+    /// Marks missing files of the checked playlist and offers to relocate them.
+    fn handle_playlist_files_verified(
+        &mut self,
+        playlist_id: &str,
+        checked_ids: Vec<String>,
+        missing: Vec<protocol::RestoredTrack>,
+    ) {
+        let missing_ids: HashSet<&str> = missing.iter().map(|track| track.id.as_str()).collect();
+        let checked = checked_ids.len();
+        for track_id in checked_ids {
+            if missing_ids.contains(track_id.as_str()) {
+                self.unavailable_track_ids.insert(track_id);
+            } else {
+                self.unavailable_track_ids.remove(&track_id);
+            }
+        }
+        self.rebuild_track_model();
+
+        let playlist_name = self
+            .sidebar_playlists
+            .iter()
+            .find(|playlist| playlist.id == playlist_id)
+            .map(|playlist| playlist.name.clone())
+            .unwrap_or_default();
+        self.relocation_missing_paths = missing.into_iter().map(|track| track.path).collect();
+        if self.relocation_missing_paths.is_empty() {
+            self.sync_relocation_dialog(false, String::new(), String::new());
+            self.show_library_toast(format!(
+                "All {checked} local file(s) in \"{playlist_name}\" were found"
+            ));
+            return;
+        }
+        let summary = self.relocation_summary_text(Some(checked), &playlist_name);
+        self.sync_relocation_dialog(true, summary, String::new());
+    }
+
+    fn request_missing_file_relocation(&mut self, new_base: PathBuf) {
+        if self.relocation_missing_paths.is_empty() {
+            return;
+        }
+        let status = format!("Looking for the files under {}…", new_base.display());
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_relocation_status_text(status.into());
+        });
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::RelocateTrackPaths {
+                missing: self.relocation_missing_paths.clone(),
+                new_base,
+            },
+        ));
+    }
+
+    fn handle_track_paths_relocated(
+        &mut self,
+        moves: &[protocol::OrganizeFileMove],
+        unresolved: Vec<PathBuf>,
+    ) {
+        let relocated: HashSet<&PathBuf> = moves
+            .iter()
+            .flat_map(|file_move| [&file_move.from, &file_move.to])
+            .collect();
+        for (track_id, path) in self.track_ids.iter().zip(&self.track_paths) {
+            if relocated.contains(path) {
+                self.unavailable_track_ids.remove(track_id);
+            }
+        }
+        self.rebuild_track_model();
+        self.library_cover_art_paths.clear();
+        self.folder_cover_art_paths.clear();
+        self.request_library_view_data();
+        self.request_library_root_counts();
+
+        self.relocation_missing_paths = unresolved;
+        self.show_library_toast(format!("Relocated {} file(s)", moves.len()));
+        if self.relocation_missing_paths.is_empty() {
+            self.sync_relocation_dialog(false, String::new(), String::new());
+            return;
+        }
+        let summary = self.relocation_summary_text(None, "");
+        self.sync_relocation_dialog(
+            true,
+            summary,
+            format!(
+                "Relocated {} file(s); pick another folder for the rest.",
+                moves.len()
+            ),
+        );
+    }
+
     #[cfg(test)]
     fn build_playlist_row_values(
         track_metadata: &TrackMetadata,
@@ -2956,33 +3741,429 @@ impl UiManager {
         }
     }
 
-    fn to_ui_rich_text_run(run: &text_template::RichTextRun) -> UiRichTextRun {
-        let horizontal_align = match run.horizontal_align {
-            text_template::HorizontalAlign::Left => 0,
-            text_template::HorizontalAlign::Center => 1,
-            text_template::HorizontalAlign::Right => 2,
-        };
-        let (color_mode, palette_color, color_rgba) = match run.color.as_ref() {
-            Some(text_template::RunColor::Palette(color)) => {
-                (1, color.code(), slint::Color::from_argb_u8(255, 0, 0, 0))
-            }
-            Some(text_template::RunColor::Rgba { r, g, b, a }) => {
-                let alpha = ((*a).clamp(0.0, 1.0) * 255.0).round() as u8;
-                (2, 0, slint::Color::from_argb_u8(alpha, *r, *g, *b))
-            }
-            None => (0, 0, slint::Color::from_argb_u8(255, 0, 0, 0)),
-        };
-        let (link_kind, link_value, link_album, link_album_artist, link_track_path) =
-            if let Some(link) = run.link.as_ref() {
-                let link_kind = match link.kind {
-                    protocol::MetadataLinkKind::Artist => 1,
-                    protocol::MetadataLinkKind::Album => 2,
-                    protocol::MetadataLinkKind::Genre => 3,
-                    protocol::MetadataLinkKind::Decade => 4,
-                    protocol::MetadataLinkKind::Title => 5,
-                };
-                let track_path = link
-                    .track_path
+    fn tag_chip_run(
+        text: String,
+        color: Option<text_template::RunColor>,
+    ) -> text_template::RichTextRun {
+        text_template::RichTextRun {
+            text,
+            bold: false,
+            italic: false,
+            underline: false,
+            horizontal_align: text_template::HorizontalAlign::Left,
+            font_size_px: 13,
+            font_family: String::new(),
+            color,
+            link: None,
+        }
+    }
+
+    /// Renders built-in `{tags}` columns as a colored dot followed by each tag name.
+    fn apply_user_tag_chips(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        track_tags: &[String],
+        defined_tags: &[protocol::UserTag],
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            if column.custom
+                || Self::normalize_column_format(&column.format) != user_tags::TAGS_COLUMN_FORMAT
+            {
+                continue;
+            }
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let mut runs = Vec::with_capacity(track_tags.len() * 2);
+            for (tag_index, tag_name) in track_tags.iter().enumerate() {
+                let key = user_tags::tag_key(tag_name);
+                let color = defined_tags
+                    .iter()
+                    .find(|tag| user_tags::tag_key(&tag.name) == key)
+                    .and_then(|tag| user_tags::tag_color_rgb(&tag.color))
+                    .map(|(r, g, b)| text_template::RunColor::Rgba { r, g, b, a: 1.0 });
+                runs.push(Self::tag_chip_run(
+                    user_tags::TAG_CHIP_SYMBOL.to_string(),
+                    color,
+                ));
+                let separator = if tag_index + 1 < track_tags.len() {
+                    "  "
+                } else {
+                    ""
+                };
+                runs.push(Self::tag_chip_run(
+                    format!(" {}{}", tag_name, separator),
+                    None,
+                ));
+            }
+            let plain_text = track_tags.join(", ");
+            value.plain_text = plain_text.clone();
+            value.rich_text = text_template::RenderedText {
+                plain_text,
+                lines: if runs.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![text_template::RichTextLine { runs }]
+                },
+                vertical_align: text_template::VerticalAlign::Center,
+            };
+        }
+    }
+
+    /// Renders built-in `{source}` columns as the entry's provenance label.
+    fn apply_source_label(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        source_label: &str,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            if column.custom
+                || Self::normalize_column_format(&column.format)
+                    != track_source::SOURCE_COLUMN_FORMAT
+            {
+                continue;
+            }
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            value.plain_text = source_label.to_string();
+            value.rich_text = text_template::RenderedText {
+                plain_text: source_label.to_string(),
+                lines: vec![text_template::RichTextLine {
+                    runs: vec![Self::tag_chip_run(source_label.to_string(), None)],
+                }],
+                vertical_align: text_template::VerticalAlign::Center,
+            };
+        }
+    }
+
+    /// Renders built-in `{loudness}` and `{true_peak}` columns as measurement badges,
+    /// highlighting brickwalled loudness and clipping peaks.
+    fn apply_loudness_badges(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        loudness: Option<&protocol::TrackLoudness>,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            let Some(format) = Self::loudness_builtin_format(column) else {
+                continue;
+            };
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let Some(loudness) = loudness else {
+                value.plain_text.clear();
+                value.rich_text = text_template::RenderedText {
+                    plain_text: String::new(),
+                    lines: Vec::new(),
+                    vertical_align: text_template::VerticalAlign::Center,
+                };
+                continue;
+            };
+            let (label, warn) = if format == loudness_analysis::LOUDNESS_COLUMN_FORMAT {
+                (
+                    loudness_analysis::loudness_label(loudness),
+                    loudness.integrated_lufs > loudness_analysis::BRICKWALL_LOUDNESS_LUFS,
+                )
+            } else {
+                (
+                    loudness_analysis::true_peak_label(loudness),
+                    loudness.true_peak_dbtp > loudness_analysis::CLIPPING_TRUE_PEAK_DBTP,
+                )
+            };
+            let color = warn.then_some(text_template::RunColor::Palette(
+                text_template::PaletteColor::Warning,
+            ));
+            value.plain_text = label.clone();
+            value.rich_text = text_template::RenderedText {
+                plain_text: label.clone(),
+                lines: vec![text_template::RichTextLine {
+                    runs: vec![Self::tag_chip_run(label, color)],
+                }],
+                vertical_align: text_template::VerticalAlign::Center,
+            };
+        }
+    }
+
+    /// Renders built-in `{energy}` columns as the track's energy level; suggested levels
+    /// are shown muted and in italics so they stand apart from levels set by hand.
+    fn apply_energy_label(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        setting: Option<&protocol::TrackEnergySetting>,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            if !Self::is_energy_builtin_column(column) {
+                continue;
+            }
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let Some(setting) = setting else {
+                value.plain_text.clear();
+                value.rich_text = text_template::RenderedText {
+                    plain_text: String::new(),
+                    lines: Vec::new(),
+                    vertical_align: text_template::VerticalAlign::Center,
+                };
+                continue;
+            };
+            let label = setting.energy.label().to_string();
+            let mut run = Self::tag_chip_run(
+                label.clone(),
+                setting
+                    .suggested
+                    .then_some(text_template::RunColor::Palette(
+                        text_template::PaletteColor::TextMuted,
+                    )),
+            );
+            run.italic = setting.suggested;
+            value.plain_text = label.clone();
+            value.rich_text = text_template::RenderedText {
+                plain_text: label,
+                lines: vec![text_template::RichTextLine { runs: vec![run] }],
+                vertical_align: text_template::VerticalAlign::Center,
+            };
+        }
+    }
+
+    /// Raw number shown by a built-in number column; unknown and zero values are `None`.
+    fn number_column_value(
+        builtin_format: &str,
+        format: Option<&protocol::TechnicalMetadata>,
+        file_size: Option<u64>,
+    ) -> Option<u64> {
+        match builtin_format {
+            column_format::DURATION_COLUMN_FORMAT => format.map(|meta| meta.duration_ms),
+            column_format::BITRATE_COLUMN_FORMAT => format.map(|meta| u64::from(meta.bitrate_kbps)),
+            _ => file_size,
+        }
+        .filter(|value| *value > 0)
+    }
+
+    /// Renders built-in `{duration}`, `{bitrate}`, and `{file_size}` columns in each
+    /// column's number format.
+    fn apply_number_column_values(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        styles: &[PlaylistColumnStyleConfig],
+        format: Option<&protocol::TechnicalMetadata>,
+        file_size: Option<u64>,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            let Some(builtin) = Self::number_builtin_format(column) else {
+                continue;
+            };
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let number_format = styles
+                .get(visible_index)
+                .map(|style| style.number_format)
+                .unwrap_or_default();
+            let text = Self::number_column_value(builtin, format, file_size)
+                .map(|number| match builtin {
+                    column_format::DURATION_COLUMN_FORMAT => {
+                        column_format::format_duration(number, number_format)
+                    }
+                    column_format::BITRATE_COLUMN_FORMAT => {
+                        column_format::format_bitrate(number, number_format)
+                    }
+                    _ => column_format::format_file_size(number, number_format),
+                })
+                .unwrap_or_default();
+            value.plain_text = text.clone();
+            value.rich_text = Self::rendered_single_run(text, 13, None);
+        }
+    }
+
+    /// Renders built-in `{playlist_index}` and `{queue_position}` columns. Tracks outside
+    /// the known upcoming sequence have no queue position and stay blank.
+    fn apply_row_number_values(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        playlist_number: u64,
+        queue_number: Option<u64>,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            let Some(builtin) = Self::row_number_builtin_format(column) else {
+                continue;
+            };
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let text = if builtin == column_format::PLAYLIST_INDEX_COLUMN_FORMAT {
+                playlist_number.to_string()
+            } else {
+                queue_number
+                    .map(|number| number.to_string())
+                    .unwrap_or_default()
+            };
+            value.plain_text = text.clone();
+            value.rich_text = Self::rendered_single_run(text, 13, None);
+        }
+    }
+
+    /// Renders built-in `{play_count}`, `{skip_count}`, and `{last_played}` columns.
+    fn apply_play_stats_values(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        stats: Option<&protocol::TrackPlayStats>,
+        now_unix_ms: i64,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            let Some(builtin) = Self::play_stats_builtin_format(column) else {
+                continue;
+            };
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let text = play_stats::column_text(builtin, stats, now_unix_ms);
+            value.plain_text = text.clone();
+            value.rich_text = Self::rendered_single_run(text, 13, None);
+        }
+    }
+
+    /// Applies column alignment and middle elision to rendered playlist cells.
+    fn apply_column_text_styles(
+        values: &mut [RenderedColumnValue],
+        styles: &[PlaylistColumnStyleConfig],
+        column_widths_px: &[u32],
+    ) {
+        for (visible_index, value) in values.iter_mut().enumerate() {
+            let Some(style) = styles
+                .get(visible_index)
+                .filter(|style| !style.is_default())
+            else {
+                continue;
+            };
+            if style.elision == ColumnElision::Middle {
+                let max_chars = column_widths_px
+                    .get(visible_index)
+                    .map(|width_px| column_format::chars_for_width(*width_px));
+                if let Some(elided) = max_chars
+                    .and_then(|max_chars| column_format::elide_middle(&value.plain_text, max_chars))
+                {
+                    value.rich_text = Self::rendered_single_run(elided, 13, None);
+                }
+            }
+            let horizontal_align = match style.alignment {
+                ColumnTextAlignment::Left => text_template::HorizontalAlign::Left,
+                ColumnTextAlignment::Center => text_template::HorizontalAlign::Center,
+                ColumnTextAlignment::Right => text_template::HorizontalAlign::Right,
+            };
+            for run in value
+                .rich_text
+                .lines
+                .iter_mut()
+                .flat_map(|line| line.runs.iter_mut())
+            {
+                run.horizontal_align = horizontal_align;
+            }
+        }
+    }
+
+    /// Renders built-in `{quality}` columns as a Hi-Res, Lossless, or Lossy badge.
+    fn apply_quality_badge(
+        values: &mut [RenderedColumnValue],
+        playlist_columns: &[PlaylistColumnConfig],
+        quality: Option<format_quality::FormatQuality>,
+    ) {
+        for (visible_index, column) in playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .enumerate()
+        {
+            if column.custom
+                || Self::normalize_column_format(&column.format)
+                    != format_quality::QUALITY_COLUMN_FORMAT
+            {
+                continue;
+            }
+            let Some(value) = values.get_mut(visible_index) else {
+                continue;
+            };
+            let Some(quality) = quality else {
+                value.plain_text.clear();
+                value.rich_text = Self::empty_rendered_text();
+                continue;
+            };
+            let color = match quality {
+                format_quality::FormatQuality::HiRes => Some(text_template::RunColor::Palette(
+                    text_template::PaletteColor::Accent,
+                )),
+                format_quality::FormatQuality::Lossless => Some(text_template::RunColor::Palette(
+                    text_template::PaletteColor::Success,
+                )),
+                format_quality::FormatQuality::Lossy { .. } => None,
+            };
+            let label = quality.label();
+            value.plain_text = label.clone();
+            value.rich_text = text_template::RenderedText {
+                plain_text: label.clone(),
+                lines: vec![text_template::RichTextLine {
+                    runs: vec![Self::tag_chip_run(label, color)],
+                }],
+                vertical_align: text_template::VerticalAlign::Center,
+            };
+        }
+    }
+
+    fn to_ui_rich_text_run(run: &text_template::RichTextRun) -> UiRichTextRun {
+        let horizontal_align = match run.horizontal_align {
+            text_template::HorizontalAlign::Left => 0,
+            text_template::HorizontalAlign::Center => 1,
+            text_template::HorizontalAlign::Right => 2,
+        };
+        let (color_mode, palette_color, color_rgba) = match run.color.as_ref() {
+            Some(text_template::RunColor::Palette(color)) => {
+                (1, color.code(), slint::Color::from_argb_u8(255, 0, 0, 0))
+            }
+            Some(text_template::RunColor::Rgba { r, g, b, a }) => {
+                let alpha = ((*a).clamp(0.0, 1.0) * 255.0).round() as u8;
+                (2, 0, slint::Color::from_argb_u8(alpha, *r, *g, *b))
+            }
+            None => (0, 0, slint::Color::from_argb_u8(255, 0, 0, 0)),
+        };
+        let (link_kind, link_value, link_album, link_album_artist, link_track_path) =
+            if let Some(link) = run.link.as_ref() {
+                let link_kind = match link.kind {
+                    protocol::MetadataLinkKind::Artist => 1,
+                    protocol::MetadataLinkKind::Album => 2,
+                    protocol::MetadataLinkKind::Genre => 3,
+                    protocol::MetadataLinkKind::Decade => 4,
+                    protocol::MetadataLinkKind::Title => 5,
+                };
+                let track_path = link
+                    .track_path
                     .as_ref()
                     .map(|path| path.to_string_lossy().to_string())
                     .unwrap_or_default();
@@ -3059,32 +4240,111 @@ impl UiManager {
         !column.custom && Self::normalize_column_format(&column.format) == "{album_art}"
     }
 
-    fn is_favorite_builtin_column(column: &PlaylistColumnConfig) -> bool {
-        !column.custom && Self::normalize_column_format(&column.format) == "{favorite}"
+    /// Returns the format of a built-in "Loudness" or "True Peak" column.
+    fn loudness_builtin_format(column: &PlaylistColumnConfig) -> Option<&'static str> {
+        if column.custom {
+            return None;
+        }
+        [
+            loudness_analysis::LOUDNESS_COLUMN_FORMAT,
+            loudness_analysis::TRUE_PEAK_COLUMN_FORMAT,
+        ]
+        .into_iter()
+        .find(|format| Self::normalize_column_format(&column.format) == *format)
     }
 
-    fn is_playing_builtin_column(column: &PlaylistColumnConfig) -> bool {
-        !column.custom && Self::normalize_column_format(&column.format) == "{playing}"
+    fn play_stats_builtin_format(column: &PlaylistColumnConfig) -> Option<&'static str> {
+        if column.custom {
+            return None;
+        }
+        [
+            play_stats::PLAY_COUNT_COLUMN_FORMAT,
+            play_stats::SKIP_COUNT_COLUMN_FORMAT,
+            play_stats::LAST_PLAYED_COLUMN_FORMAT,
+        ]
+        .into_iter()
+        .find(|format| Self::normalize_column_format(&column.format) == *format)
     }
 
-    fn playlist_column_kind(column: &PlaylistColumnConfig) -> i32 {
-        if Self::is_album_art_builtin_column(column) {
-            PLAYLIST_COLUMN_KIND_ALBUM_ART
-        } else if Self::is_favorite_builtin_column(column) {
-            PLAYLIST_COLUMN_KIND_FAVORITE
-        } else if Self::is_playing_builtin_column(column) {
-            PLAYLIST_COLUMN_KIND_PLAYING
-        } else {
-            PLAYLIST_COLUMN_KIND_TEXT
+    fn row_number_builtin_format(column: &PlaylistColumnConfig) -> Option<&'static str> {
+        if column.custom {
+            return None;
         }
+        [
+            column_format::PLAYLIST_INDEX_COLUMN_FORMAT,
+            column_format::QUEUE_POSITION_COLUMN_FORMAT,
+        ]
+        .into_iter()
+        .find(|format| Self::normalize_column_format(&column.format) == *format)
     }
 
-    fn visible_playlist_column_kinds(columns: &[PlaylistColumnConfig]) -> Vec<i32> {
-        columns
-            .iter()
-            .filter(|column| column.enabled)
-            .map(Self::playlist_column_kind)
-            .collect()
+    fn number_builtin_format(column: &PlaylistColumnConfig) -> Option<&'static str> {
+        if column.custom {
+            return None;
+        }
+        [
+            column_format::DURATION_COLUMN_FORMAT,
+            column_format::BITRATE_COLUMN_FORMAT,
+            column_format::FILE_SIZE_COLUMN_FORMAT,
+        ]
+        .into_iter()
+        .find(|format| Self::normalize_column_format(&column.format) == *format)
+    }
+
+    fn is_energy_builtin_column(column: &PlaylistColumnConfig) -> bool {
+        !column.custom
+            && Self::normalize_column_format(&column.format) == track_energy::ENERGY_COLUMN_FORMAT
+    }
+
+    fn playlist_sort_value(column: &PlaylistColumnConfig) -> PlaylistSortValue {
+        let format = Self::normalize_column_format(&column.format);
+        if !column.custom && format == format_quality::QUALITY_COLUMN_FORMAT {
+            PlaylistSortValue::Quality
+        } else if !column.custom
+            && column_format::TAG_NUMBER_COLUMN_FORMATS.contains(&format.as_str())
+        {
+            PlaylistSortValue::TagNumber
+        } else if Self::is_energy_builtin_column(column) {
+            PlaylistSortValue::Energy
+        } else if let Some(format) = Self::row_number_builtin_format(column) {
+            PlaylistSortValue::RowNumber(format)
+        } else if let Some(format) = Self::play_stats_builtin_format(column) {
+            PlaylistSortValue::PlayStats(format)
+        } else if let Some(format) = Self::number_builtin_format(column) {
+            PlaylistSortValue::Number(format)
+        } else if let Some(format) = Self::loudness_builtin_format(column) {
+            PlaylistSortValue::Loudness(format)
+        } else {
+            PlaylistSortValue::Text
+        }
+    }
+
+    fn is_favorite_builtin_column(column: &PlaylistColumnConfig) -> bool {
+        !column.custom && Self::normalize_column_format(&column.format) == "{favorite}"
+    }
+
+    fn is_playing_builtin_column(column: &PlaylistColumnConfig) -> bool {
+        !column.custom && Self::normalize_column_format(&column.format) == "{playing}"
+    }
+
+    fn playlist_column_kind(column: &PlaylistColumnConfig) -> i32 {
+        if Self::is_album_art_builtin_column(column) {
+            PLAYLIST_COLUMN_KIND_ALBUM_ART
+        } else if Self::is_favorite_builtin_column(column) {
+            PLAYLIST_COLUMN_KIND_FAVORITE
+        } else if Self::is_playing_builtin_column(column) {
+            PLAYLIST_COLUMN_KIND_PLAYING
+        } else {
+            PLAYLIST_COLUMN_KIND_TEXT
+        }
+    }
+
+    fn visible_playlist_column_kinds(columns: &[PlaylistColumnConfig]) -> Vec<i32> {
+        columns
+            .iter()
+            .filter(|column| column.enabled)
+            .map(Self::playlist_column_kind)
+            .collect()
     }
 
     fn playlist_column_key(column: &PlaylistColumnConfig) -> String {
@@ -3131,6 +4391,33 @@ impl UiManager {
         }
     }
 
+    fn apply_layout_column_styles(&mut self, styles: &[PlaylistColumnStyleOverrideConfig]) {
+        self.playlist_column_styles = styles
+            .iter()
+            .map(|item| (item.column_key.clone(), item.style))
+            .collect();
+    }
+
+    /// Styles of the visible playlist columns, in display order.
+    fn visible_playlist_column_styles(&self) -> Vec<PlaylistColumnStyleConfig> {
+        self.playlist_columns
+            .iter()
+            .filter(|column| column.enabled)
+            .map(|column| {
+                self.playlist_column_styles
+                    .get(&Self::playlist_column_key(column))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn has_middle_elided_column(&self) -> bool {
+        self.visible_playlist_column_styles()
+            .iter()
+            .any(|style| style.elision == ColumnElision::Middle)
+    }
+
     fn is_sortable_playlist_column(column: &PlaylistColumnConfig) -> bool {
         !Self::is_album_art_builtin_column(column)
             && !Self::is_favorite_builtin_column(column)
@@ -3154,6 +4441,9 @@ impl UiManager {
         if normalized_format == "{track}"
             || normalized_format == "{track_number}"
             || normalized_format == "{tracknumber}"
+            || normalized_format == "{total_tracks}"
+            || normalized_format == "{playlist_index}"
+            || normalized_format == "{queue_position}"
             || normalized_name == "track #"
             || normalized_name == "track"
         {
@@ -3181,6 +4471,7 @@ impl UiManager {
         if normalized_format == "{artist}"
             || normalized_format == "{album_artist}"
             || normalized_format == "{albumartist}"
+            || normalized_format == "{composer}"
             || normalized_name == "artist"
             || normalized_name == "album artist"
         {
@@ -3655,9 +4946,14 @@ impl UiManager {
             return;
         }
 
+        let widths_changed = widths != self.playlist_column_widths_px;
         self.playlist_column_widths_px = widths.clone();
         self.playlist_columns_content_width_px = content_width;
         self.playlist_row_height_px = row_height_px;
+        if widths_changed && self.has_middle_elided_column() {
+            // Middle-elided cells are shortened to fit their column, so they follow resizes.
+            self.rebuild_track_model();
+        }
 
         let widths_i32: Vec<i32> = widths
             .into_iter()
@@ -4001,6 +5297,35 @@ impl UiManager {
         String::new()
     }
 
+    fn sync_seek_markers_to_ui(&self, markers: Vec<protocol::SeekMarker>) {
+        let rows: Vec<SeekMarkerData> = markers
+            .into_iter()
+            .map(|marker| SeekMarkerData {
+                position_ms: marker.position_ms.min(i32::MAX as u64) as i32,
+                chapter: marker.kind == protocol::SeekMarkerKind::Chapter,
+            })
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_seek_markers(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    fn sync_playing_track_trims_to_ui(&self) {
+        let trims = self
+            .playing_track
+            .path
+            .as_ref()
+            .and_then(|path| self.track_trims_by_path.get(path))
+            .copied()
+            .unwrap_or_default();
+        let trim_start_ms = trims.start_ms.min(i32::MAX as u64) as i32;
+        let trim_end_ms = trims.end_ms.min(i32::MAX as u64) as i32;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_playing_trim_start_ms(trim_start_ms);
+            ui.set_playing_trim_end_ms(trim_end_ms);
+        });
+    }
+
     fn set_playing_track(
         &mut self,
         id: Option<String>,
@@ -5236,6 +6561,10 @@ impl UiManager {
                     year: library_track.year.clone(),
                     genre: library_track.genre.clone(),
                     track_number: library_track.track_number.clone(),
+                    composer: String::new(),
+                    disc_number: String::new(),
+                    total_tracks: String::new(),
+                    comment: String::new(),
                 });
             }
         }
@@ -5259,6 +6588,10 @@ impl UiManager {
                 },
                 genre: metadata.genre.clone(),
                 track_number: String::new(),
+                composer: String::new(),
+                disc_number: String::new(),
+                total_tracks: String::new(),
+                comment: String::new(),
             }
         })
     }
@@ -5448,6 +6781,10 @@ impl UiManager {
                             year: String::new(),
                             genre: String::new(),
                             track_number: String::new(),
+                            composer: String::new(),
+                            disc_number: String::new(),
+                            total_tracks: String::new(),
+                            comment: String::new(),
                         });
 
                     let should_render_template = metadata.is_some() || is_status_source;
@@ -5529,6 +6866,12 @@ impl UiManager {
                             &template_metadata.track_number,
                             display_path,
                         )
+                        .with_track_details(
+                            &template_metadata.composer,
+                            &template_metadata.disc_number,
+                            &template_metadata.total_tracks,
+                            &template_metadata.comment,
+                        )
                         .with_indicator_symbols(Some(playing_indicator), Some(favorite_indicator))
                         .with_status_fields(
                             technical_fields.status_template_fields(&selection_summary_text),
@@ -5911,32 +7254,97 @@ impl UiManager {
         }
     }
 
+    fn playlist_properties_batch_paths(&self) -> Vec<PathBuf> {
+        if self.selected_indices.len() < 2 {
+            return Vec::new();
+        }
+        self.selected_indices
+            .iter()
+            .filter_map(|index| self.track_paths.get(*index).cloned())
+            .collect()
+    }
+
+    fn library_properties_batch_paths(&self) -> Vec<PathBuf> {
+        let paths: Vec<PathBuf> = self
+            .library_selected_indices
+            .iter()
+            .filter_map(|index| match self.library_entries.get(*index)? {
+                LibraryEntry::Track(track) => Some(track.path.clone()),
+                _ => None,
+            })
+            .collect();
+        if paths.len() < 2 {
+            return Vec::new();
+        }
+        paths
+    }
+
+    /// Tracks edited together when more than one track is selected.
+    fn active_properties_batch_paths(&self) -> Vec<PathBuf> {
+        if self.collection_mode == COLLECTION_MODE_LIBRARY {
+            self.library_properties_batch_paths()
+        } else {
+            self.playlist_properties_batch_paths()
+        }
+    }
+
     fn sync_properties_action_state(&self) {
-        let playlist_enabled = self.collection_mode == COLLECTION_MODE_PLAYLIST
+        let playlist_location_enabled = self.collection_mode == COLLECTION_MODE_PLAYLIST
             && self.playlist_properties_target().is_some();
-        let library_enabled = self.collection_mode == COLLECTION_MODE_LIBRARY
+        let library_location_enabled = self.collection_mode == COLLECTION_MODE_LIBRARY
             && self.library_properties_target().is_some();
+        let playlist_enabled = playlist_location_enabled
+            || (self.collection_mode == COLLECTION_MODE_PLAYLIST
+                && !self.playlist_properties_batch_paths().is_empty());
+        let library_enabled = library_location_enabled
+            || (self.collection_mode == COLLECTION_MODE_LIBRARY
+                && !self.library_properties_batch_paths().is_empty());
+        let library_source_toggle_text = self
+            .library_properties_target()
+            .filter(|(path, _)| {
+                self.collection_mode == COLLECTION_MODE_LIBRARY
+                    && self.remote_copies_by_path.contains_key(path)
+            })
+            .map(|(path, _)| {
+                if self.remote_source_paths.contains(&path) {
+                    "Play local file"
+                } else {
+                    "Play remote copy"
+                }
+            })
+            .unwrap_or_default();
         let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_library_source_toggle_text(library_source_toggle_text.into());
             ui.set_playlist_properties_enabled(playlist_enabled);
             ui.set_library_properties_enabled(library_enabled);
+            ui.set_playlist_open_location_enabled(playlist_location_enabled);
+            ui.set_library_open_location_enabled(library_location_enabled);
         });
     }
 
     fn to_ui_metadata_fields(
         fields: &[protocol::MetadataEditorField],
+        mixed: &[bool],
+        apply: &[bool],
     ) -> Vec<UiMetadataEditorField> {
         fields
             .iter()
-            .map(|field| UiMetadataEditorField {
+            .enumerate()
+            .map(|(index, field)| UiMetadataEditorField {
                 id: field.id.as_str().into(),
                 field_name: field.field_name.as_str().into(),
                 value: field.value.as_str().into(),
                 common: field.common,
+                mixed: mixed.get(index).copied().unwrap_or(false),
+                apply: apply.get(index).copied().unwrap_or(false),
             })
             .collect()
     }
 
     fn properties_has_changes(&self) -> bool {
+        if !self.properties_batch_paths.is_empty() {
+            return self.properties_field_apply.iter().any(|apply| *apply);
+        }
         if self.properties_fields.len() != self.properties_original_fields.len() {
             return true;
         }
@@ -5950,7 +7358,7 @@ impl UiManager {
     fn properties_save_enabled(&self) -> bool {
         self.properties_dialog_visible
             && !self.properties_busy
-            && self.properties_target_path.is_some()
+            && (self.properties_target_path.is_some() || !self.properties_batch_paths.is_empty())
             && !self.properties_fields.is_empty()
             && self.properties_has_changes()
     }
@@ -5959,13 +7367,21 @@ impl UiManager {
         let visible = self.properties_dialog_visible;
         let busy = self.properties_busy;
         let error_text = self.properties_error_text.clone();
+        let status_text = self.properties_status_text.clone();
         let target_title = self.properties_target_title.clone();
-        let fields = Self::to_ui_metadata_fields(&self.properties_fields);
+        let fields = Self::to_ui_metadata_fields(
+            &self.properties_fields,
+            &self.properties_field_mixed,
+            &self.properties_field_apply,
+        );
+        let batch_mode = !self.properties_batch_paths.is_empty();
         let save_enabled = self.properties_save_enabled();
         let _ = self.ui.upgrade_in_event_loop(move |ui| {
             ui.set_show_properties_dialog(visible);
+            ui.set_properties_batch_mode(batch_mode);
             ui.set_properties_busy(busy);
             ui.set_properties_error_text(error_text.into());
+            ui.set_properties_status_text(status_text.into());
             ui.set_properties_target_title(target_title.into());
             ui.set_properties_fields(ModelRc::from(Rc::new(VecModel::from(fields))));
             ui.set_properties_save_enabled(save_enabled);
@@ -5985,19 +7401,32 @@ impl UiManager {
         self.properties_pending_request_id = None;
         self.properties_pending_request_kind = None;
         self.properties_target_path = None;
+        self.properties_batch_paths.clear();
+        self.properties_field_mixed.clear();
+        self.properties_field_apply.clear();
         self.properties_target_title.clear();
         self.properties_original_fields.clear();
         self.properties_fields.clear();
         self.properties_dialog_visible = false;
         self.properties_busy = false;
         self.properties_error_text.clear();
+        self.properties_status_text.clear();
+        self.properties_spectrogram_request_id = None;
+        let _ = self.ui.upgrade_in_event_loop(|ui| {
+            ui.set_properties_spectrogram_visible(false);
+            ui.set_properties_spectrogram_rendering(false);
+            ui.set_properties_spectrogram_caption("".into());
+            ui.set_properties_spectrogram(Image::default());
+        });
     }
 
     fn open_properties_for_current_selection(&mut self) {
         let Some((path, _target_title)) = self.active_properties_target() else {
+            self.open_batch_properties_for_current_selection();
             return;
         };
 
+        self.reset_properties_dialog_state();
         self.properties_target_path = Some(path.clone());
         self.properties_target_title = _target_title;
         self.properties_original_fields.clear();
@@ -6016,8 +7445,71 @@ impl UiManager {
         self.sync_properties_dialog_ui();
     }
 
-    fn open_file_location(&self) {
-        let Some((path, _)) = self.active_properties_target() else {
+    fn open_batch_properties_for_current_selection(&mut self) {
+        let paths = self.active_properties_batch_paths();
+        if paths.is_empty() {
+            return;
+        }
+
+        self.reset_properties_dialog_state();
+        self.properties_target_title = format!("{} tracks", paths.len());
+        self.properties_batch_paths = paths.clone();
+        self.properties_dialog_visible = true;
+        self.properties_busy = true;
+
+        let request_id = self.next_properties_request_id();
+        self.properties_pending_request_id = Some(request_id);
+        self.properties_pending_request_kind = Some(PropertiesRequestKind::Load);
+
+        let _ = self.bus_sender.send(protocol::Message::Metadata(
+            protocol::MetadataMessage::RequestTrackPropertiesBatch { request_id, paths },
+        ));
+        self.sync_properties_dialog_ui();
+    }
+
+    /// File on disk for a track: the track itself when local, otherwise its copy in the
+    /// offline cache or the linked local library file.
+    fn local_file_for_track(&self, path: &Path) -> Option<PathBuf> {
+        if !is_remote_track_path(path) {
+            return Some(path.to_path_buf());
+        }
+        playlist_materialize::offline_cache_root()
+            .and_then(|cache_root| playlist_materialize::cached_remote_track(path, &cache_root))
+            .or_else(|| {
+                self.remote_copies_by_path
+                    .iter()
+                    .find(|(_, copies)| copies.iter().any(|copy| copy == path))
+                    .map(|(local_path, _)| local_path.clone())
+            })
+    }
+
+    /// Files on disk for the selected tracks, or a toast explaining why there are none.
+    fn selected_local_files(&mut self) -> Option<Vec<PathBuf>> {
+        let paths = match self.active_properties_target() {
+            Some((path, _)) => vec![path],
+            None => self.active_properties_batch_paths(),
+        };
+        if paths.is_empty() {
+            return None;
+        }
+        let files: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|path| self.local_file_for_track(path))
+            .collect();
+        if files.is_empty() {
+            self.show_library_toast(
+                "No local copy of this remote track yet. Download the playlist for offline use first.",
+            );
+            return None;
+        }
+        Some(files)
+    }
+
+    fn open_file_location(&mut self) {
+        let Some(path) = self
+            .selected_local_files()
+            .and_then(|files| files.into_iter().next())
+        else {
             return;
         };
         if Self::is_running_in_flatpak() {
@@ -6036,6 +7528,54 @@ impl UiManager {
         showfile::show_path_in_file_manager(&path);
     }
 
+    fn copy_file_paths(&mut self) {
+        let Some(files) = self.selected_local_files() else {
+            return;
+        };
+        let text = files
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = match files.len() {
+            1 => "Copied file path".to_string(),
+            count => format!("Copied {count} file paths"),
+        };
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_clipboard_text(text.into());
+            ui.set_clipboard_copy_token(ui.get_clipboard_copy_token().wrapping_add(1));
+        });
+        self.show_library_toast(message);
+    }
+
+    fn open_in_external_tagger(&mut self) {
+        if self.external_tagger_command.trim().is_empty() {
+            self.show_library_toast("Set an external tagger command in Settings > General first.");
+            return;
+        }
+        let Some(files) = self.selected_local_files() else {
+            return;
+        };
+        let Some((program, args)) = external_tagger::tagger_invocation(
+            &self.external_tagger_command,
+            &files,
+            Self::is_running_in_flatpak(),
+        ) else {
+            return;
+        };
+        if let Err(err) = Command::new(&program).args(&args).spawn() {
+            warn!(
+                "UiManager: failed to start external tagger {:?}: {}",
+                program, err
+            );
+            self.show_library_toast(format!(
+                "Could not start {}: {}",
+                program.to_string_lossy(),
+                err
+            ));
+        }
+    }
+
     fn is_running_in_flatpak() -> bool {
         std::env::var_os("FLATPAK_ID").is_some() || Path::new("/.flatpak-info").exists()
     }
@@ -6052,6 +7592,24 @@ impl UiManager {
         }
         field.value = value;
         self.properties_error_text.clear();
+        if let Some(apply) = self.properties_field_apply.get_mut(index) {
+            if !*apply {
+                *apply = true;
+                self.sync_properties_dialog_ui();
+                return;
+            }
+        }
+        self.sync_properties_edit_state_ui();
+    }
+
+    fn set_properties_field_apply(&mut self, index: usize, apply: bool) {
+        if self.properties_busy || !self.properties_dialog_visible {
+            return;
+        }
+        let Some(field_apply) = self.properties_field_apply.get_mut(index) else {
+            return;
+        };
+        *field_apply = apply;
         self.sync_properties_edit_state_ui();
     }
 
@@ -6059,6 +7617,10 @@ impl UiManager {
         if !self.properties_save_enabled() {
             return;
         }
+        if !self.properties_batch_paths.is_empty() {
+            self.save_batch_properties();
+            return;
+        }
         let Some(path) = self.properties_target_path.clone() else {
             return;
         };
@@ -6079,45 +7641,270 @@ impl UiManager {
         self.sync_properties_dialog_ui();
     }
 
-    fn cancel_properties(&mut self) {
-        self.reset_properties_dialog_state();
+    fn save_batch_properties(&mut self) {
+        let edits: Vec<protocol::MetadataEditorField> = self
+            .properties_fields
+            .iter()
+            .zip(self.properties_field_apply.iter())
+            .filter(|(_, apply)| **apply)
+            .map(|(field, _)| field.clone())
+            .collect();
+        if edits.is_empty() {
+            return;
+        }
+
+        let request_id = self.next_properties_request_id();
+        self.properties_pending_request_id = Some(request_id);
+        self.properties_pending_request_kind = Some(PropertiesRequestKind::Save);
+        self.properties_busy = true;
+        self.properties_error_text.clear();
+        let _ = self.bus_sender.send(protocol::Message::Metadata(
+            protocol::MetadataMessage::SaveTrackPropertiesBatch {
+                request_id,
+                paths: self.properties_batch_paths.clone(),
+                edits,
+            },
+        ));
         self.sync_properties_dialog_ui();
     }
 
-    fn expected_properties_response(
-        &self,
-        kind: PropertiesRequestKind,
+    fn lookup_properties_tags(&mut self) {
+        if self.properties_busy
+            || !self.properties_dialog_visible
+            || !self.properties_batch_paths.is_empty()
+        {
+            return;
+        }
+        let Some(path) = self.properties_target_path.clone() else {
+            return;
+        };
+        let field_value = |id: &str| {
+            self.properties_fields
+                .iter()
+                .find(|field| field.id == id)
+                .map(|field| field.value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let title = field_value("common:title");
+        let artist = field_value("common:artist");
+
+        let request_id = self.next_properties_request_id();
+        self.properties_pending_request_id = Some(request_id);
+        self.properties_pending_request_kind = Some(PropertiesRequestKind::Lookup);
+        self.properties_busy = true;
+        self.properties_error_text.clear();
+        self.properties_status_text.clear();
+        let _ = self.bus_sender.send(protocol::Message::Metadata(
+            protocol::MetadataMessage::LookupTrackTags {
+                request_id,
+                path,
+                title,
+                artist,
+            },
+        ));
+        self.sync_properties_dialog_ui();
+    }
+
+    /// Renders the whole track on the analysis pool; the dialog stays editable meanwhile.
+    fn render_properties_spectrogram(&mut self) {
+        if self.properties_busy
+            || !self.properties_dialog_visible
+            || !self.properties_batch_paths.is_empty()
+            || self.properties_spectrogram_request_id.is_some()
+        {
+            return;
+        }
+        let Some(path) = self.properties_target_path.clone() else {
+            return;
+        };
+        let request_id = self.next_properties_request_id();
+        self.properties_spectrogram_request_id = Some(request_id);
+        let _ = self.ui.upgrade_in_event_loop(|ui| {
+            ui.set_properties_spectrogram_rendering(true);
+        });
+        let bus_sender = self.bus_sender.clone();
+        worker_pool::analysis_pool().submit(move || {
+            let result = spectrogram::render_file_spectrogram(
+                &path,
+                PROPERTIES_SPECTROGRAM_WIDTH,
+                PROPERTIES_SPECTROGRAM_HEIGHT,
+            );
+            let _ = bus_sender.send(protocol::Message::Metadata(
+                protocol::MetadataMessage::PropertiesSpectrogramRendered {
+                    request_id,
+                    path,
+                    result,
+                },
+            ));
+        });
+    }
+
+    fn handle_properties_spectrogram_rendered(
+        &mut self,
         request_id: u64,
-        path: &Path,
-    ) -> bool {
+        path: PathBuf,
+        result: Result<(spectrogram::SpectrogramImage, u32), String>,
+    ) {
         let matches_target_path = self
             .properties_target_path
             .as_deref()
-            .is_some_and(|target_path| Self::is_equivalent_track_path(target_path, path));
-        self.properties_dialog_visible
-            && self.properties_pending_request_kind == Some(kind)
-            && self.properties_pending_request_id == Some(request_id)
-            && matches_target_path
+            .is_some_and(|target_path| Self::is_equivalent_track_path(target_path, &path));
+        if self.properties_spectrogram_request_id != Some(request_id) || !matches_target_path {
+            return;
+        }
+        self.properties_spectrogram_request_id = None;
+        match result {
+            Ok((image, sample_rate_hz)) => {
+                let caption = format!(
+                    "Full track, {}. A hard cutoff well below the top edge (often near 16 or \
+                     19-20 kHz) suggests the file was transcoded from a lossy source.",
+                    spectrogram::frequency_range_label(sample_rate_hz)
+                );
+                let _ = self.ui.upgrade_in_event_loop(move |ui| {
+                    ui.set_properties_spectrogram(
+                        crate::app_callbacks::spectrogram::spectrogram_to_image(&image),
+                    );
+                    ui.set_properties_spectrogram_caption(caption.into());
+                    ui.set_properties_spectrogram_visible(true);
+                    ui.set_properties_spectrogram_rendering(false);
+                });
+            }
+            Err(error) => {
+                self.properties_error_text = format!("Spectrogram failed: {error}");
+                self.sync_properties_edit_state_ui();
+                let _ = self.ui.upgrade_in_event_loop(|ui| {
+                    ui.set_properties_spectrogram_rendering(false);
+                });
+            }
+        }
     }
 
-    fn handle_properties_loaded(
+    /// Fills the proposed values into the editor; nothing is written until Save.
+    fn handle_tag_lookup_completed(
         &mut self,
         request_id: u64,
         path: PathBuf,
-        display_name: String,
-        fields: Vec<protocol::MetadataEditorField>,
+        result: Result<protocol::TagLookupProposal, String>,
     ) {
-        if !self.expected_properties_response(PropertiesRequestKind::Load, request_id, &path) {
+        if !self.expected_properties_response(PropertiesRequestKind::Lookup, request_id, &path) {
             return;
         }
 
         self.properties_pending_request_id = None;
         self.properties_pending_request_kind = None;
         self.properties_busy = false;
-        self.properties_error_text.clear();
-        self.properties_target_title = display_name;
-        self.properties_original_fields = fields.clone();
-        self.properties_fields = fields;
+        match result {
+            Ok(proposal) => {
+                let mut changed_count = 0;
+                for (id, value) in proposal.values {
+                    if let Some(field) = self
+                        .properties_fields
+                        .iter_mut()
+                        .find(|field| field.id == id && field.value != value)
+                    {
+                        field.value = value;
+                        changed_count += 1;
+                    }
+                }
+                self.properties_status_text = if changed_count == 0 {
+                    format!("{}: tags already match.", proposal.source)
+                } else {
+                    format!(
+                        "{}: {changed_count} field(s) updated. Review and Save to write them.",
+                        proposal.source
+                    )
+                };
+            }
+            Err(error) => {
+                self.properties_error_text = error;
+            }
+        }
+        self.sync_properties_dialog_ui();
+    }
+
+    fn cancel_properties(&mut self) {
+        self.reset_properties_dialog_state();
+        self.sync_properties_dialog_ui();
+    }
+
+    fn expected_properties_response(
+        &self,
+        kind: PropertiesRequestKind,
+        request_id: u64,
+        path: &Path,
+    ) -> bool {
+        let matches_target_path = self
+            .properties_target_path
+            .as_deref()
+            .is_some_and(|target_path| Self::is_equivalent_track_path(target_path, path));
+        self.properties_dialog_visible
+            && self.properties_pending_request_kind == Some(kind)
+            && self.properties_pending_request_id == Some(request_id)
+            && matches_target_path
+    }
+
+    fn handle_properties_loaded(
+        &mut self,
+        request_id: u64,
+        path: PathBuf,
+        display_name: String,
+        fields: Vec<protocol::MetadataEditorField>,
+    ) {
+        if !self.expected_properties_response(PropertiesRequestKind::Load, request_id, &path) {
+            return;
+        }
+
+        self.properties_pending_request_id = None;
+        self.properties_pending_request_kind = None;
+        self.properties_busy = false;
+        self.properties_error_text.clear();
+        self.properties_target_title = display_name;
+        self.properties_original_fields = fields.clone();
+        self.properties_fields = fields;
+        self.sync_properties_dialog_ui();
+    }
+
+    fn expected_batch_properties_response(
+        &self,
+        kind: PropertiesRequestKind,
+        request_id: u64,
+    ) -> bool {
+        self.properties_dialog_visible
+            && !self.properties_batch_paths.is_empty()
+            && self.properties_pending_request_kind == Some(kind)
+            && self.properties_pending_request_id == Some(request_id)
+    }
+
+    fn handle_batch_properties_loaded(
+        &mut self,
+        request_id: u64,
+        fields: Vec<protocol::MetadataEditorField>,
+        mixed: Vec<bool>,
+    ) {
+        if !self.expected_batch_properties_response(PropertiesRequestKind::Load, request_id) {
+            return;
+        }
+
+        self.properties_pending_request_id = None;
+        self.properties_pending_request_kind = None;
+        self.properties_busy = false;
+        self.properties_error_text.clear();
+        self.properties_field_apply = vec![false; fields.len()];
+        self.properties_field_mixed = mixed;
+        self.properties_original_fields = fields.clone();
+        self.properties_fields = fields;
+        self.sync_properties_dialog_ui();
+    }
+
+    fn handle_batch_properties_load_failed(&mut self, request_id: u64, error: String) {
+        if !self.expected_batch_properties_response(PropertiesRequestKind::Load, request_id) {
+            return;
+        }
+
+        self.properties_pending_request_id = None;
+        self.properties_pending_request_kind = None;
+        self.properties_busy = false;
+        self.properties_error_text = error;
         self.sync_properties_dialog_ui();
     }
 
@@ -6279,8 +8066,52 @@ impl UiManager {
         self.properties_pending_request_kind = None;
         self.properties_busy = false;
 
-        let playlist_changed = self.apply_summary_to_playlist_metadata(&path, &summary);
-        let library_changed = self.apply_summary_to_library_entries(&path, &summary);
+        self.apply_saved_properties(&[(path, summary)], db_sync_warning);
+        self.reset_properties_dialog_state();
+        self.sync_properties_dialog_ui();
+        self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
+    }
+
+    fn handle_batch_properties_saved(
+        &mut self,
+        request_id: u64,
+        saved: Vec<(PathBuf, protocol::TrackMetadataSummary)>,
+        db_sync_warning: Option<String>,
+        error: Option<String>,
+    ) {
+        if !self.expected_batch_properties_response(PropertiesRequestKind::Save, request_id) {
+            return;
+        }
+
+        self.properties_pending_request_id = None;
+        self.properties_pending_request_kind = None;
+        self.properties_busy = false;
+
+        self.apply_saved_properties(&saved, db_sync_warning);
+        if let Some(error) = error {
+            self.properties_error_text = error;
+            self.sync_properties_dialog_ui();
+            return;
+        }
+        self.reset_properties_dialog_state();
+        self.sync_properties_dialog_ui();
+        self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
+    }
+
+    /// Reflects saved tag summaries in playlist rows, library entries, and now-playing.
+    fn apply_saved_properties(
+        &mut self,
+        saved: &[(PathBuf, protocol::TrackMetadataSummary)],
+        db_sync_warning: Option<String>,
+    ) {
+        let mut playlist_changed = false;
+        let mut library_changed = false;
+        for (path, summary) in saved {
+            playlist_changed |= self.apply_summary_to_playlist_metadata(path, summary);
+            library_changed |= self.apply_summary_to_library_entries(path, summary);
+        }
 
         if playlist_changed {
             self.refresh_playlist_column_content_targets();
@@ -6304,10 +8135,6 @@ impl UiManager {
             self.library_status_text = warning.clone();
             self.show_library_toast(warning);
         }
-
-        self.reset_properties_dialog_state();
-        self.sync_properties_dialog_ui();
-        self.sync_properties_action_state();
     }
 
     fn handle_properties_save_failed(&mut self, request_id: u64, path: PathBuf, error: String) {
@@ -6327,21 +8154,18 @@ impl UiManager {
     }
 
     fn reset_filter_state_fields(
-        filter_sort_column_key: &mut Option<String>,
-        filter_sort_direction: &mut Option<PlaylistSortDirection>,
+        filter_sort_keys: &mut Vec<PlaylistSortKey>,
         filter_search_query: &mut String,
         filter_search_visible: &mut bool,
     ) {
-        *filter_sort_column_key = None;
-        *filter_sort_direction = None;
+        filter_sort_keys.clear();
         filter_search_query.clear();
         *filter_search_visible = false;
     }
 
     fn reset_filter_state(&mut self) {
         Self::reset_filter_state_fields(
-            &mut self.filter_sort_column_key,
-            &mut self.filter_sort_direction,
+            &mut self.filter_sort_keys,
             &mut self.filter_search_query,
             &mut self.filter_search_visible,
         );
@@ -6358,7 +8182,39 @@ impl UiManager {
     }
 
     fn is_filter_applied(&self) -> bool {
-        self.filter_sort_direction.is_some() || !self.filter_search_query.trim().is_empty()
+        !self.filter_sort_keys.is_empty()
+            || !self.filter_search_query.trim().is_empty()
+            || !self.active_column_filters().is_empty()
+    }
+
+    /// Filters of the shown playlist on its visible columns, by visible column index.
+    /// Filters on hidden columns stay stored but do not narrow the view.
+    fn active_column_filters(&self) -> Vec<(usize, PlaylistColumnFilter)> {
+        let Some(filters) = self
+            .playlist_column_filters_by_id
+            .get(&self.active_playlist_id)
+        else {
+            return Vec::new();
+        };
+        self.visible_playlist_columns()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, column)| {
+                let column_key = Self::playlist_column_key(column);
+                filters
+                    .iter()
+                    .find(|filter| filter.column_key == column_key)
+                    .map(|filter| (index, filter.clone()))
+            })
+            .collect()
+    }
+
+    fn column_filter_flags(&self) -> Vec<bool> {
+        let mut flags = vec![false; self.visible_playlist_columns().len()];
+        for (index, _) in self.active_column_filters() {
+            flags[index] = true;
+        }
+        flags
     }
 
     fn is_filter_view_active(&self) -> bool {
@@ -6433,7 +8289,11 @@ impl UiManager {
     }
 
     fn auto_scroll_active_collection_to_playing_track(&mut self) {
-        if !self.auto_scroll_to_playing_track {
+        if !self.auto_scroll_to_playing_track
+            || self
+                .follow_playback_paused_until
+                .is_some_and(|paused_until| Instant::now() < paused_until)
+        {
             return;
         }
         if self.collection_mode == COLLECTION_MODE_LIBRARY {
@@ -6443,6 +8303,34 @@ impl UiManager {
         }
     }
 
+    /// Scrolls the playing track into view, switching to the playlist it plays from when
+    /// the current view does not hold it. Ends any manual-scroll pause of follow playback.
+    fn jump_to_playing_track(&mut self) {
+        self.follow_playback_paused_until = None;
+        if self.collection_mode == COLLECTION_MODE_LIBRARY && self.library_playing_index.is_some() {
+            self.center_library_view_on_playing_track();
+            return;
+        }
+        let Some(playing_playlist_id) = self
+            .playing_playlist_id
+            .clone()
+            .filter(|id| self.playlist_ids.contains(id))
+        else {
+            return;
+        };
+        self.set_collection_mode(COLLECTION_MODE_PLAYLIST);
+        if playing_playlist_id == self.active_playlist_id {
+            self.center_playlist_view_on_playing_track();
+        } else {
+            self.jump_to_playing_pending = true;
+            let _ = self.bus_sender.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::SwitchPlaylist {
+                    id: playing_playlist_id,
+                },
+            ));
+        }
+    }
+
     fn map_library_view_to_source_index(&self, view_index: usize) -> Option<usize> {
         if self.library_view_indices.is_empty() {
             if self.library_entries.is_empty() {
@@ -6503,20 +8391,118 @@ impl UiManager {
         }
     }
 
+    /// Track entries also match on their user tags; `tag:<name>` tokens keep only
+    /// tracks carrying every named tag.
+    fn library_entry_matches_tag_query(
+        entry: &LibraryEntry,
+        normalized_query: &str,
+        required_tag_keys: &[String],
+        user_tags_by_path: &HashMap<PathBuf, Vec<String>>,
+        track_notes_by_path: &HashMap<PathBuf, String>,
+    ) -> bool {
+        let LibraryEntry::Track(track) = entry else {
+            return required_tag_keys.is_empty()
+                && Self::library_entry_matches_search(entry, normalized_query);
+        };
+        let track_tags = user_tags_by_path
+            .get(&track.path)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        user_tags::track_has_required_tags(track_tags, required_tag_keys)
+            && (Self::library_entry_matches_search(entry, normalized_query)
+                || user_tags::track_tags_match_text(track_tags, normalized_query)
+                || track_notes_by_path
+                    .get(&track.path)
+                    .is_some_and(|note| Self::track_note_matches_search(note, normalized_query)))
+    }
+
+    fn track_note_matches_search(note: &str, normalized_query: &str) -> bool {
+        !normalized_query.is_empty() && note.to_lowercase().contains(normalized_query)
+    }
+
     fn build_library_view_indices_for_query(
         entries: &[LibraryEntry],
         search_query: &str,
+        user_tags_by_path: &HashMap<PathBuf, Vec<String>>,
+        track_notes_by_path: &HashMap<PathBuf, String>,
     ) -> Vec<usize> {
-        let normalized_query = Self::normalized_search_query(search_query);
+        let tag_query = user_tags::parse_tag_filter_query(search_query);
+        let normalized_query = Self::normalized_search_query(&tag_query.text);
         entries
             .iter()
             .enumerate()
             .filter_map(|(index, entry)| {
-                Self::library_entry_matches_search(entry, &normalized_query).then_some(index)
+                Self::library_entry_matches_tag_query(
+                    entry,
+                    &normalized_query,
+                    &tag_query.required_tag_keys,
+                    user_tags_by_path,
+                    track_notes_by_path,
+                )
+                .then_some(index)
             })
             .collect()
     }
 
+    /// `energy:<level>` tokens keep only tracks stored at one of the named levels.
+    fn retain_library_tracks_with_energy(
+        entries: &[LibraryEntry],
+        indices: &mut Vec<usize>,
+        energies: &[protocol::TrackEnergy],
+        track_energy_by_path: &HashMap<PathBuf, protocol::TrackEnergySetting>,
+    ) {
+        if energies.is_empty() {
+            return;
+        }
+        indices.retain(|&index| {
+            matches!(
+                entries.get(index),
+                Some(LibraryEntry::Track(track)) if track_energy_by_path
+                    .get(&track.path)
+                    .is_some_and(|setting| energies.contains(&setting.energy))
+            )
+        });
+    }
+
+    fn retain_library_tracks_with_play_stats(
+        entries: &[LibraryEntry],
+        indices: &mut Vec<usize>,
+        query: &play_stats::PlayStatsFilterQuery,
+        track_play_stats_by_path: &HashMap<PathBuf, protocol::TrackPlayStats>,
+    ) {
+        if query.conditions.is_empty() {
+            return;
+        }
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        indices.retain(|&index| {
+            matches!(
+                entries.get(index),
+                Some(LibraryEntry::Track(track))
+                    if query.matches(track_play_stats_by_path.get(&track.path), now_unix_ms)
+            )
+        });
+    }
+
+    /// Filter sidebar picks keep only tracks matching every facet dimension.
+    fn retain_library_tracks_with_facets(
+        entries: &[LibraryEntry],
+        indices: &mut Vec<usize>,
+        selection: &protocol::LibraryFacetSelection,
+    ) {
+        if library_facets::selection_is_empty(selection) {
+            return;
+        }
+        indices.retain(|&index| {
+            matches!(
+                entries.get(index),
+                Some(LibraryEntry::Track(track)) if library_facets::track_matches(selection, track)
+            )
+        });
+    }
+
     fn selection_anchor_source_index(&self) -> Option<usize> {
         self.selection_anchor_track_id
             .as_ref()
@@ -6620,49 +8606,73 @@ impl UiManager {
         }
     }
 
-    fn active_sort_column_state(&self) -> Option<(usize, String)> {
-        let sort_key = self.filter_sort_column_key.as_ref()?;
-        self.visible_playlist_columns()
+    /// Visible column index and name of each sort key, in key order. Keys whose column is
+    /// hidden are skipped.
+    fn active_sort_columns(&self) -> Vec<(usize, String, PlaylistSortDirection)> {
+        let visible_columns = self.visible_playlist_columns();
+        self.filter_sort_keys
             .iter()
-            .enumerate()
-            .find_map(|(index, column)| {
-                (Self::playlist_column_key(column) == *sort_key)
-                    .then(|| (index, column.name.clone()))
+            .filter_map(|key| {
+                visible_columns
+                    .iter()
+                    .position(|column| Self::playlist_column_key(column) == key.column_key)
+                    .map(|index| (index, visible_columns[index].name.clone(), key.direction))
             })
+            .collect()
     }
 
+    /// Per visible column: `0` unsorted, `1` ascending, `2` descending.
     fn sort_state_model(&self) -> Vec<i32> {
-        let active_key = self.filter_sort_column_key.as_ref();
-        let active_state = self.filter_sort_direction;
         self.visible_playlist_columns()
             .iter()
             .map(|column| {
                 let key = Self::playlist_column_key(column);
-                if active_key == Some(&key) {
-                    match active_state {
-                        Some(PlaylistSortDirection::Ascending) => 1,
-                        Some(PlaylistSortDirection::Descending) => 2,
-                        None => 0,
-                    }
-                } else {
-                    0
+                match self
+                    .filter_sort_keys
+                    .iter()
+                    .find(|sort_key| sort_key.column_key == key)
+                    .map(|sort_key| sort_key.direction)
+                {
+                    Some(PlaylistSortDirection::Ascending) => 1,
+                    Some(PlaylistSortDirection::Descending) => 2,
+                    None => 0,
                 }
             })
             .collect()
     }
 
+    /// Per visible column: 1-based position among several sort keys, otherwise `0`.
+    fn sort_rank_model(&self) -> Vec<i32> {
+        let multi_key = self.filter_sort_keys.len() > 1;
+        self.visible_playlist_columns()
+            .iter()
+            .map(|column| {
+                let key = Self::playlist_column_key(column);
+                self.filter_sort_keys
+                    .iter()
+                    .position(|sort_key| sort_key.column_key == key)
+                    .filter(|_| multi_key)
+                    .map_or(0, |position| position as i32 + 1)
+            })
+            .collect()
+    }
+
     fn filter_summary_text(&self) -> String {
         let mut parts: Vec<String> = Vec::new();
 
-        if let Some((_, column_name)) = self.active_sort_column_state() {
-            let direction = match self.filter_sort_direction {
-                Some(PlaylistSortDirection::Ascending) => "asc",
-                Some(PlaylistSortDirection::Descending) => "desc",
-                None => "",
-            };
-            if !direction.is_empty() {
-                parts.push(format!("Sort: {} ({})", column_name, direction));
-            }
+        let sort_columns: Vec<String> = self
+            .active_sort_columns()
+            .into_iter()
+            .map(|(_, column_name, direction)| {
+                let direction = match direction {
+                    PlaylistSortDirection::Ascending => "asc",
+                    PlaylistSortDirection::Descending => "desc",
+                };
+                format!("{} ({})", column_name, direction)
+            })
+            .collect();
+        if !sort_columns.is_empty() {
+            parts.push(format!("Sort: {}", sort_columns.join(", ")));
         }
 
         let query = self.filter_search_query.trim();
@@ -6670,6 +8680,20 @@ impl UiManager {
             parts.push(format!("Search: \"{}\"", query));
         }
 
+        let visible_columns = self.visible_playlist_columns();
+        let column_filters: Vec<String> = self
+            .active_column_filters()
+            .into_iter()
+            .filter_map(|(index, filter)| {
+                visible_columns
+                    .get(index)
+                    .map(|column| filter.summary(&column.name))
+            })
+            .collect();
+        if !column_filters.is_empty() {
+            parts.push(format!("Filters: {}", column_filters.join(", ")));
+        }
+
         if parts.is_empty() {
             if self.filter_search_visible {
                 return "Filter view active".to_string();
@@ -6732,6 +8756,9 @@ impl UiManager {
 
     fn sync_filter_state_to_ui(&self) {
         let sort_states = self.sort_state_model();
+        let sort_ranks = self.sort_rank_model();
+        let sort_active = !self.filter_sort_keys.is_empty();
+        let filter_flags = self.column_filter_flags();
         let filter_active = self.is_filter_view_active();
         let search_visible = self.filter_search_visible;
         let search_query = self.filter_search_query.clone();
@@ -6745,38 +8772,100 @@ impl UiManager {
             ui.set_playlist_search_result_text(search_result_text.into());
             ui.set_playlist_filter_summary(summary.into());
             ui.set_playlist_column_sort_states(ModelRc::from(Rc::new(VecModel::from(sort_states))));
+            ui.set_playlist_column_sort_ranks(ModelRc::from(Rc::new(VecModel::from(sort_ranks))));
+            ui.set_playlist_sort_active(sort_active);
+            ui.set_playlist_column_filter_flags(ModelRc::from(Rc::new(VecModel::from(
+                filter_flags,
+            ))));
         });
     }
 
     fn rebuild_track_model(&mut self) {
         self.prune_unavailable_track_ids();
-        let normalized_query = Self::normalized_search_query(&self.filter_search_query);
-        let mut active_sort = self.active_sort_column_state();
-
-        if self.filter_sort_direction.is_some() && active_sort.is_none() {
-            self.filter_sort_column_key = None;
-            self.filter_sort_direction = None;
-            active_sort = None;
+        let tag_query = user_tags::parse_tag_filter_query(&self.filter_search_query);
+        let energy_query = track_energy::parse_energy_filter_query(&tag_query.text);
+        let play_stats_query = play_stats::parse_play_stats_filter_query(&energy_query.text);
+        let normalized_query = Self::normalized_search_query(&play_stats_query.text);
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        let active_sorts = self.active_sort_columns();
+        if active_sorts.len() != self.filter_sort_keys.len() {
+            // Hidden columns no longer take part in the sort.
+            let visible_keys: HashSet<String> = self
+                .visible_playlist_columns()
+                .iter()
+                .map(Self::playlist_column_key)
+                .collect();
+            self.filter_sort_keys
+                .retain(|key| visible_keys.contains(&key.column_key));
         }
-
-        let active_sort_index = active_sort.map(|(index, _)| index);
-        let descending = self.filter_sort_direction == Some(PlaylistSortDirection::Descending);
+        let sort_columns: Vec<(usize, PlaylistSortValue)> = active_sorts
+            .iter()
+            .filter_map(|(index, _, _)| {
+                self.visible_playlist_columns()
+                    .get(*index)
+                    .map(|column| (*index, Self::playlist_sort_value(column)))
+            })
+            .collect();
+        let column_filters = self.active_column_filters();
+        let column_filter_cells_column = self.column_filter_cells_column;
+        let mut column_filter_cells = Vec::new();
         let active_playing_index = self.active_playing_index;
         let playback_active = self.playback_active;
         let album_art_column_visible = self.is_album_art_column_visible();
         if album_art_column_visible {
             self.ensure_track_cover_art_slots();
         }
+        let source_column_visible = self.is_source_column_visible();
+        let loudness_column_visible = self.is_loudness_column_visible();
+        let energy_column_visible = self.is_energy_column_visible();
+        let quality_column_visible = self.is_quality_column_visible();
+        let number_column_visible = self.is_number_column_visible();
+        if quality_column_visible || number_column_visible {
+            self.request_missing_track_formats();
+        }
+        if number_column_visible {
+            self.refresh_track_file_sizes();
+        }
+        let column_styles = self.visible_playlist_column_styles();
+        let play_stats_column_visible = self.is_play_stats_column_visible();
+        let row_number_column_visible = self.is_row_number_column_visible();
+        let queue_positions = column_format::queue_positions(
+            self.playing_track.id.as_deref(),
+            self.upcoming_tracks.iter().map(|track| track.id.as_str()),
+        );
+        let zero_based_row_numbers = self.zero_based_row_numbers;
 
         struct ViewRow {
             source_index: usize,
             rendered_values: Vec<RenderedColumnValue>,
-            sort_key: String,
+            sort_values: Vec<String>,
         }
 
         let mut rows: Vec<ViewRow> = Vec::with_capacity(self.track_metadata.len());
         for (source_index, metadata) in self.track_metadata.iter().enumerate() {
             let track_path = self.track_paths.get(source_index).map(PathBuf::as_path);
+            let track_tags = track_path
+                .and_then(|path| self.user_tags_by_path.get(path))
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            if !user_tags::track_has_required_tags(track_tags, &tag_query.required_tag_keys) {
+                continue;
+            }
+            let track_energy = track_path.and_then(|path| self.track_energy_by_path.get(path));
+            if !energy_query.energies.is_empty()
+                && !track_energy
+                    .is_some_and(|setting| energy_query.energies.contains(&setting.energy))
+            {
+                continue;
+            }
+            let track_play_stats =
+                track_path.and_then(|path| self.track_play_stats_by_path.get(path));
+            if !play_stats_query.matches(track_play_stats, now_unix_ms) {
+                continue;
+            }
             let track_unavailable = self
                 .track_ids
                 .get(source_index)
@@ -6806,6 +8895,31 @@ impl UiManager {
                     &self.playlist_columns,
                 );
             }
+            Self::apply_user_tag_chips(
+                &mut rendered_values,
+                &self.playlist_columns,
+                track_tags,
+                &self.user_tags,
+            );
+            let source_label = if source_column_visible || !normalized_query.is_empty() {
+                let recorded = self
+                    .track_ids
+                    .get(source_index)
+                    .and_then(|track_id| self.track_sources_by_id.get(track_id))
+                    .copied();
+                track_path
+                    .map(|path| track_source::source_label(path, recorded))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+            if source_column_visible {
+                Self::apply_source_label(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    &source_label,
+                );
+            }
             if !normalized_query.is_empty()
                 && !rendered_values.iter().any(|value| {
                     value
@@ -6813,37 +8927,170 @@ impl UiManager {
                         .to_ascii_lowercase()
                         .contains(&normalized_query)
                 })
+                && !user_tags::track_tags_match_text(track_tags, &normalized_query)
+                && !source_label
+                    .to_ascii_lowercase()
+                    .contains(&normalized_query)
+                && !track_path
+                    .and_then(|path| self.track_notes_by_path.get(path))
+                    .is_some_and(|note| Self::track_note_matches_search(note, &normalized_query))
             {
                 continue;
             }
 
-            let sort_key = active_sort_index
-                .and_then(|index| rendered_values.get(index))
-                .map(|value| value.plain_text.to_ascii_lowercase())
-                .unwrap_or_default();
+            let track_loudness = track_path.and_then(|path| self.track_loudness_by_path.get(path));
+            if loudness_column_visible {
+                Self::apply_loudness_badges(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    track_loudness,
+                );
+            }
 
-            rows.push(ViewRow {
-                source_index,
-                rendered_values,
-                sort_key,
-            });
-        }
+            if energy_column_visible {
+                Self::apply_energy_label(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    track_energy,
+                );
+            }
 
-        if active_sort_index.is_some() {
-            rows.sort_by(|lhs, rhs| {
-                let order = lhs
-                    .sort_key
-                    .cmp(&rhs.sort_key)
-                    .then_with(|| lhs.source_index.cmp(&rhs.source_index));
-                if descending {
-                    order.reverse()
-                } else {
-                    order
+            let track_format = track_path.and_then(|path| self.track_formats_by_path.get(path));
+            let quality = track_format
+                .map(|meta| format_quality::classify_format_quality(meta, self.hi_res_thresholds));
+            if quality_column_visible {
+                Self::apply_quality_badge(&mut rendered_values, &self.playlist_columns, quality);
+            }
+            let file_size =
+                track_path.and_then(|path| self.track_file_sizes_by_path.get(path).copied());
+            if number_column_visible {
+                Self::apply_number_column_values(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    &column_styles,
+                    track_format,
+                    file_size,
+                );
+            }
+            let playlist_number = column_format::row_number(source_index, zero_based_row_numbers);
+            let queue_number = self
+                .track_ids
+                .get(source_index)
+                .and_then(|id| queue_positions.get(id.as_str()))
+                .map(|position| column_format::row_number(*position, zero_based_row_numbers));
+            if row_number_column_visible {
+                Self::apply_row_number_values(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    playlist_number,
+                    queue_number,
+                );
+            }
+            if play_stats_column_visible {
+                Self::apply_play_stats_values(
+                    &mut rendered_values,
+                    &self.playlist_columns,
+                    track_play_stats,
+                    now_unix_ms,
+                );
+            }
+
+            let cell_text = |index: usize| {
+                rendered_values
+                    .get(index)
+                    .map_or("", |value| value.plain_text.as_str())
+            };
+            let failed_filter_columns: Vec<usize> = column_filters
+                .iter()
+                .filter(|(index, filter)| !filter.matches(cell_text(*index)))
+                .map(|(index, _)| *index)
+                .collect();
+            // The dialog lists the values left by every filter except its own column's.
+            if let Some(dialog_column) = column_filter_cells_column {
+                if failed_filter_columns
+                    .iter()
+                    .all(|index| *index == dialog_column)
+                {
+                    column_filter_cells.push(cell_text(dialog_column).to_string());
                 }
+            }
+            if !failed_filter_columns.is_empty() {
+                continue;
+            }
+
+            let sort_values = sort_columns
+                .iter()
+                .map(|&(index, sort_value)| match sort_value {
+                    PlaylistSortValue::Quality => quality
+                        .map(format_quality::FormatQuality::sort_key)
+                        .unwrap_or_default(),
+                    PlaylistSortValue::TagNumber => rendered_values
+                        .get(index)
+                        .map(|value| column_format::tag_number_sort_key(&value.plain_text))
+                        .unwrap_or_default(),
+                    PlaylistSortValue::Energy => track_energy
+                        .map(|setting| setting.energy.level().to_string())
+                        .unwrap_or_default(),
+                    PlaylistSortValue::RowNumber(format) => {
+                        if format == column_format::PLAYLIST_INDEX_COLUMN_FORMAT {
+                            column_format::numeric_sort_key(playlist_number)
+                        } else {
+                            queue_number
+                                .map(column_format::numeric_sort_key)
+                                .unwrap_or_default()
+                        }
+                    }
+                    PlaylistSortValue::PlayStats(format) => column_format::numeric_sort_key(
+                        play_stats::sort_value(format, track_play_stats),
+                    ),
+                    PlaylistSortValue::Number(format) => {
+                        Self::number_column_value(format, track_format, file_size)
+                            .map(column_format::numeric_sort_key)
+                            .unwrap_or_default()
+                    }
+                    PlaylistSortValue::Loudness(format) => track_loudness
+                        .map(|loudness| {
+                            loudness_analysis::numeric_sort_key(
+                                if format == loudness_analysis::LOUDNESS_COLUMN_FORMAT {
+                                    loudness.integrated_lufs
+                                } else {
+                                    loudness.true_peak_dbtp
+                                },
+                            )
+                        })
+                        .unwrap_or_default(),
+                    PlaylistSortValue::Text => rendered_values
+                        .get(index)
+                        .map(|value| value.plain_text.to_ascii_lowercase())
+                        .unwrap_or_default(),
+                })
+                .collect();
+            Self::apply_column_text_styles(
+                &mut rendered_values,
+                &column_styles,
+                &self.playlist_column_widths_px,
+            );
+
+            rows.push(ViewRow {
+                source_index,
+                rendered_values,
+                sort_values,
+            });
+        }
+
+        if !sort_columns.is_empty() {
+            let sort_keys = &self.filter_sort_keys;
+            rows.sort_by(|lhs, rhs| {
+                playlist_sort::compare_rows(
+                    sort_keys,
+                    (&lhs.sort_values, lhs.source_index),
+                    (&rhs.sort_values, rhs.source_index),
+                )
             });
         }
 
         self.view_indices = rows.iter().map(|row| row.source_index).collect();
+        self.column_filter_cells = column_filter_cells;
         let selected_set: HashSet<usize> = self.selected_indices.iter().copied().collect();
         let selected_track_count = selected_set.len();
         let selection_summary_text = Self::status_selection_summary_text(selected_track_count);
@@ -6973,6 +9220,7 @@ impl UiManager {
         self.sync_filter_state_to_ui();
         self.sync_now_playing_favorite_state_to_ui();
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
     }
 
     fn sync_playlist_playback_state_to_ui(&self) {
@@ -7228,7 +9476,158 @@ impl UiManager {
 
     fn clear_playlist_filter_view(&mut self) {
         self.reset_filter_state();
+        if self
+            .playlist_column_filters_by_id
+            .contains_key(&self.active_playlist_id)
+        {
+            let _ = self.bus_sender.send(protocol::Message::Playlist(
+                protocol::PlaylistMessage::SetPlaylistColumnFilters {
+                    playlist_id: self.active_playlist_id.clone(),
+                    filters: Vec::new(),
+                },
+            ));
+        }
+        self.rebuild_track_model();
+    }
+
+    fn open_column_filter_dialog(&mut self, view_column_index: usize) {
+        let Some(column) = self
+            .visible_playlist_columns()
+            .get(view_column_index)
+            .map(|column| (*column).clone())
+        else {
+            return;
+        };
+        if self.active_playlist_id.is_empty() {
+            return;
+        }
+        self.column_filter_cells_column = Some(view_column_index);
         self.rebuild_track_model();
+        self.column_filter_cells_column = None;
+        let cells = std::mem::take(&mut self.column_filter_cells);
+        let values = playlist_column_filters::distinct_values(cells.iter().map(String::as_str));
+
+        let column_key = Self::playlist_column_key(&column);
+        let existing = self
+            .playlist_column_filters_by_id
+            .get(&self.active_playlist_id)
+            .and_then(|filters| {
+                filters
+                    .iter()
+                    .find(|filter| filter.column_key == column_key)
+                    .cloned()
+            });
+        let value_labels: Vec<slint::SharedString> = values
+            .iter()
+            .map(|(value, count)| {
+                let label = if value.is_empty() { "(blank)" } else { value };
+                format!("{label} ({count})").into()
+            })
+            .collect();
+        let value_checked: Vec<bool> = values
+            .iter()
+            .map(|(value, _)| {
+                existing
+                    .as_ref()
+                    .is_none_or(|filter| filter.values.is_empty() || filter.values.contains(value))
+            })
+            .collect();
+        let (operator_index, operand) = existing
+            .and_then(|filter| filter.condition)
+            .and_then(|condition| {
+                playlist_column_filters::FILTER_OPERATORS
+                    .iter()
+                    .position(|operator| *operator == condition.operator)
+                    .map(|position| (position as i32 + 1, condition.operand))
+            })
+            .unwrap_or_default();
+        self.column_filter_dialog = Some(ColumnFilterDialog {
+            column_key,
+            values: values.into_iter().map(|(value, _)| value).collect(),
+        });
+        let operator_options: Vec<slint::SharedString> = std::iter::once("No condition")
+            .chain(
+                playlist_column_filters::FILTER_OPERATORS
+                    .iter()
+                    .map(|operator| playlist_column_filters::operator_label(*operator)),
+            )
+            .map(slint::SharedString::from)
+            .collect();
+        let column_name = column.name;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_column_filter_column_name(column_name.into());
+            ui.set_column_filter_value_labels(ModelRc::from(Rc::new(VecModel::from(value_labels))));
+            ui.set_column_filter_value_checked(ModelRc::from(Rc::new(VecModel::from(
+                value_checked,
+            ))));
+            ui.set_column_filter_operator_options(ModelRc::from(Rc::new(VecModel::from(
+                operator_options,
+            ))));
+            ui.set_column_filter_operator_index(operator_index);
+            ui.set_column_filter_operand(operand.into());
+            ui.set_show_column_filter_dialog(true);
+        });
+    }
+
+    /// Turns the filter dialog's choices into the column's filter. Checking every value
+    /// (or none) means the column is not narrowed by value.
+    fn apply_column_filter_dialog(
+        &mut self,
+        checked_values: Vec<bool>,
+        operator_index: usize,
+        operand: String,
+    ) {
+        let Some(dialog) = self.column_filter_dialog.take() else {
+            return;
+        };
+        if self.active_playlist_id.is_empty() {
+            return;
+        }
+        let selected: Vec<String> = dialog
+            .values
+            .iter()
+            .zip(
+                checked_values
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(false)),
+            )
+            .filter(|(_, checked)| *checked)
+            .map(|(value, _)| value.clone())
+            .collect();
+        let values = if selected.len() == dialog.values.len() {
+            Vec::new()
+        } else {
+            selected
+        };
+        let operand = operand.trim().to_string();
+        let condition = operator_index
+            .checked_sub(1)
+            .and_then(|index| playlist_column_filters::FILTER_OPERATORS.get(index))
+            .filter(|_| !operand.is_empty())
+            .map(|operator| ColumnFilterCondition {
+                operator: *operator,
+                operand,
+            });
+        let mut filters = self
+            .playlist_column_filters_by_id
+            .get(&self.active_playlist_id)
+            .cloned()
+            .unwrap_or_default();
+        playlist_column_filters::set_filter(
+            &mut filters,
+            PlaylistColumnFilter {
+                column_key: dialog.column_key,
+                values,
+                condition,
+            },
+        );
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetPlaylistColumnFilters {
+                playlist_id: self.active_playlist_id.clone(),
+                filters,
+            },
+        ));
     }
 
     fn cycle_playlist_sort_by_column(&mut self, view_column_index: usize) {
@@ -7242,29 +9641,55 @@ impl UiManager {
             }
             Self::playlist_column_key(column)
         };
+        playlist_sort::cycle_primary_key(&mut self.filter_sort_keys, &sort_key);
+        self.rebuild_track_model();
+    }
 
-        if self.filter_sort_column_key.as_deref() != Some(sort_key.as_str()) {
-            self.filter_sort_column_key = Some(sort_key);
-            self.filter_sort_direction = Some(PlaylistSortDirection::Ascending);
-        } else {
-            match self.filter_sort_direction {
-                Some(PlaylistSortDirection::Ascending) => {
-                    self.filter_sort_direction = Some(PlaylistSortDirection::Descending);
-                }
-                Some(PlaylistSortDirection::Descending) => {
-                    self.filter_sort_direction = None;
-                    self.filter_sort_column_key = None;
-                }
-                None => {
-                    self.filter_sort_direction = Some(PlaylistSortDirection::Ascending);
-                }
+    fn add_playlist_sort_column(&mut self, view_column_index: usize) {
+        let sort_key = {
+            let visible_columns = self.visible_playlist_columns();
+            let Some(column) = visible_columns.get(view_column_index) else {
+                return;
+            };
+            if !Self::is_sortable_playlist_column(column) {
+                return;
             }
-        }
+            Self::playlist_column_key(column)
+        };
+        playlist_sort::cycle_additional_key(&mut self.filter_sort_keys, &sort_key);
+        self.rebuild_track_model();
+    }
 
+    /// Writes the current sort order into the playlist. The search query only narrows the
+    /// view, so the order is taken from an unfiltered rebuild and the query is kept.
+    fn sort_playlist_permanently(&mut self) {
+        if self.filter_sort_keys.is_empty() {
+            return;
+        }
+        let search_query = std::mem::take(&mut self.filter_search_query);
         self.rebuild_track_model();
+        let order = self.view_indices.clone();
+        self.filter_search_query = search_query;
+        self.rebuild_track_model();
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SortTracks(order),
+        ));
+    }
+
+    fn apply_sorted_tracks_locally(&mut self, order: Vec<usize>) {
+        if !playlist_sort::is_full_reorder(&order, self.track_ids.len()) {
+            return;
+        }
+        self.filter_sort_keys.clear();
+        self.reorder_tracks_locally(order);
     }
 
     fn apply_filter_view_snapshot_locally(&mut self, source_indices: Vec<usize>) {
+        self.reset_filter_state();
+        self.reorder_tracks_locally(source_indices);
+    }
+
+    fn reorder_tracks_locally(&mut self, source_indices: Vec<usize>) {
         let len = self.track_ids.len();
         let mut seen_indices = HashSet::new();
         let normalized: Vec<usize> = source_indices
@@ -7324,8 +9749,6 @@ impl UiManager {
             self.playing_track = PlayingTrackState::default();
         }
 
-        self.reset_filter_state();
-
         self.refresh_playlist_column_content_targets();
         self.apply_playlist_column_layout();
         self.refresh_playing_track_metadata();
@@ -7516,6 +9939,242 @@ impl UiManager {
         self.reset_library_remove_confirmation_state();
     }
 
+    fn open_organize_files_dialog(&mut self) {
+        let selections = self.build_library_selection_specs();
+        if selections.is_empty() {
+            return;
+        }
+        self.pending_organize_selections = selections;
+        self.pending_organize_moves.clear();
+        let summary = format!(
+            "{} library item(s) selected. Preview to see the planned moves.",
+            self.pending_organize_selections.len()
+        );
+        self.set_organize_dialog_state(Vec::new(), summary);
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            if ui.get_organize_template().is_empty() {
+                ui.set_organize_template(file_organizer::DEFAULT_ORGANIZE_TEMPLATE.into());
+            }
+            ui.set_show_organize_files_dialog(true);
+        });
+    }
+
+    fn set_organize_dialog_state(&self, lines: Vec<String>, summary: String) {
+        let apply_enabled = !self.pending_organize_moves.is_empty();
+        let undo_enabled = self.organize_undo_available;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            let lines: Vec<slint::SharedString> = lines.into_iter().map(Into::into).collect();
+            ui.set_organize_preview_lines(ModelRc::from(Rc::new(VecModel::from(lines))));
+            ui.set_organize_summary(summary.into());
+            ui.set_organize_apply_enabled(apply_enabled);
+            ui.set_organize_undo_enabled(undo_enabled);
+        });
+    }
+
+    fn request_organize_preview(&mut self, template: String) {
+        if self.pending_organize_selections.is_empty() {
+            return;
+        }
+        self.pending_organize_moves.clear();
+        self.set_organize_dialog_state(Vec::new(), "Planning moves...".to_string());
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::PlanOrganizeFiles {
+                selections: self.pending_organize_selections.clone(),
+                template,
+            },
+        ));
+    }
+
+    fn show_organize_plan(
+        &mut self,
+        moves: Vec<protocol::OrganizeFileMove>,
+        unchanged: usize,
+        skipped: Vec<String>,
+    ) {
+        if self.pending_organize_selections.is_empty() {
+            return;
+        }
+        let mut lines: Vec<String> = moves
+            .iter()
+            .map(|file_move| {
+                format!(
+                    "{}  \u{2192}  {}",
+                    file_move.from.display(),
+                    file_move.to.display()
+                )
+            })
+            .collect();
+        lines.extend(skipped.iter().map(|reason| format!("Skipped: {}", reason)));
+        let summary = format!(
+            "{} file(s) to move, {} already in place, {} skipped",
+            moves.len(),
+            unchanged,
+            skipped.len()
+        );
+        self.pending_organize_moves = moves;
+        self.set_organize_dialog_state(lines, summary);
+    }
+
+    fn confirm_organize_files(&mut self) {
+        let moves = std::mem::take(&mut self.pending_organize_moves);
+        if moves.is_empty() {
+            return;
+        }
+        self.set_organize_dialog_state(Vec::new(), format!("Moving {} file(s)...", moves.len()));
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::ApplyOrganizeFiles { moves },
+        ));
+    }
+
+    fn close_organize_files_dialog(&mut self) {
+        self.pending_organize_selections.clear();
+        self.pending_organize_moves.clear();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_show_organize_files_dialog(false);
+        });
+    }
+
+    fn open_album_artist_fixes_dialog(&mut self) {
+        let selections = self.build_library_selection_specs();
+        if selections.is_empty() {
+            return;
+        }
+        self.pending_album_artist_selections = selections.clone();
+        self.pending_album_artist_suggestions.clear();
+        self.set_album_artist_dialog_state(
+            Vec::new(),
+            "Looking for albums without an album artist...".to_string(),
+        );
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_show_album_artist_dialog(true);
+        });
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::PlanAlbumArtistFixes { selections },
+        ));
+    }
+
+    fn set_album_artist_dialog_state(&self, lines: Vec<String>, summary: String) {
+        let apply_enabled = !self.pending_album_artist_suggestions.is_empty()
+            && self.pending_album_artist_fix_requests.is_empty();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            let lines: Vec<slint::SharedString> = lines.into_iter().map(Into::into).collect();
+            ui.set_album_artist_preview_lines(ModelRc::from(Rc::new(VecModel::from(lines))));
+            ui.set_album_artist_summary(summary.into());
+            ui.set_album_artist_apply_enabled(apply_enabled);
+        });
+    }
+
+    fn show_album_artist_suggestions(&mut self, suggestions: Vec<protocol::AlbumArtistSuggestion>) {
+        if self.pending_album_artist_selections.is_empty() {
+            return;
+        }
+        let lines: Vec<String> = suggestions
+            .iter()
+            .map(|suggestion| {
+                format!(
+                    "{} ({} tracks, {} artists)  \u{2192}  {}",
+                    suggestion.album,
+                    suggestion.paths.len(),
+                    suggestion.artist_count,
+                    suggestion.suggested_album_artist
+                )
+            })
+            .collect();
+        let summary = if suggestions.is_empty() {
+            "No albums in the selection are missing an album artist.".to_string()
+        } else {
+            format!(
+                "{} album(s) without an album artist. Apply writes the suggested tag to every track.",
+                suggestions.len()
+            )
+        };
+        self.pending_album_artist_suggestions = suggestions;
+        self.set_album_artist_dialog_state(lines, summary);
+    }
+
+    fn confirm_album_artist_fixes(&mut self) {
+        if !self.pending_album_artist_fix_requests.is_empty() {
+            return;
+        }
+        let suggestions = std::mem::take(&mut self.pending_album_artist_suggestions);
+        if suggestions.is_empty() {
+            return;
+        }
+        self.album_artist_fix_saved_count = 0;
+        self.album_artist_fix_errors.clear();
+        let track_count: usize = suggestions
+            .iter()
+            .map(|suggestion| suggestion.paths.len())
+            .sum();
+        for suggestion in suggestions {
+            let request_id = self.next_properties_request_id();
+            self.pending_album_artist_fix_requests.insert(request_id);
+            let _ = self.bus_sender.send(protocol::Message::Metadata(
+                protocol::MetadataMessage::SaveTrackPropertiesBatch {
+                    request_id,
+                    paths: suggestion.paths,
+                    edits: vec![protocol::MetadataEditorField {
+                        id: "common:album_artist".to_string(),
+                        field_name: "Album Artist".to_string(),
+                        value: suggestion.suggested_album_artist,
+                        common: true,
+                    }],
+                },
+            ));
+        }
+        self.set_album_artist_dialog_state(
+            Vec::new(),
+            format!("Writing album artist to {} track(s)...", track_count),
+        );
+    }
+
+    /// Consumes tag-writer results that belong to the album-artist fix; returns `false`
+    /// for results of other requests.
+    fn handle_album_artist_fix_saved(
+        &mut self,
+        request_id: u64,
+        saved: &[(PathBuf, protocol::TrackMetadataSummary)],
+        db_sync_warning: Option<String>,
+        error: Option<String>,
+    ) -> bool {
+        if !self.pending_album_artist_fix_requests.remove(&request_id) {
+            return false;
+        }
+        self.apply_saved_properties(saved, db_sync_warning);
+        self.album_artist_fix_saved_count += saved.len();
+        self.album_artist_fix_errors.extend(error);
+        if !self.pending_album_artist_fix_requests.is_empty() {
+            return true;
+        }
+
+        let mut toast_text = format!(
+            "Set album artist on {} track(s)",
+            self.album_artist_fix_saved_count
+        );
+        if let Some(first_error) = self.album_artist_fix_errors.first() {
+            toast_text = format!(
+                "{}; {} album(s) failed: {}",
+                toast_text,
+                self.album_artist_fix_errors.len(),
+                first_error
+            );
+        }
+        self.set_album_artist_dialog_state(Vec::new(), toast_text.clone());
+        self.library_status_text = toast_text.clone();
+        self.show_library_toast(toast_text);
+        self.request_library_view_data();
+        self.request_library_root_counts();
+        true
+    }
+
+    fn close_album_artist_fixes_dialog(&mut self) {
+        self.pending_album_artist_selections.clear();
+        self.pending_album_artist_suggestions.clear();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_show_album_artist_dialog(false);
+        });
+    }
+
     fn paste_copied_tracks(&mut self) {
         if !self.copied_track_paths.is_empty() {
             self.pending_paste_feedback = true;
@@ -8561,6 +11220,12 @@ impl UiManager {
             if presentation.cover_art_path.is_none() {
                 continue;
             }
+            presentation.section_title = Self::library_release_section_title(
+                &self.library_entries,
+                &self.library_view_indices,
+                &view,
+                row_index,
+            );
             rows_with_art_in_window.insert(row_index);
             updates.push((row_index, presentation));
         }
@@ -8620,6 +11285,14 @@ impl UiManager {
         let resolved = if is_remote_track_path(track_path.as_path()) {
             self.update_cover_art(Some(track_path));
             None
+        } else if SLOW_STORAGE_MODE.load(Ordering::Relaxed) {
+            // The folder cover is copied locally in the background; it is read from the
+            // music folder only until then.
+            let local_copy = Self::embedded_art_cache_path_if_present(track_path);
+            if local_copy.is_none() {
+                self.queue_embedded_cover_art_prepare(track_path.as_path());
+            }
+            local_copy.or_else(|| self.find_external_cover_art_cached(track_path))
         } else {
             self.find_external_cover_art_cached(track_path)
                 .or_else(|| Self::embedded_art_cache_path_if_present(track_path))
@@ -8691,6 +11364,9 @@ impl UiManager {
             secondary: entry.secondary.into(),
             primary_rich: UiManager::to_ui_rich_text_block(&entry.primary_rich),
             secondary_rich: UiManager::to_ui_rich_text_block(&entry.secondary_rich),
+            caption: entry.caption.into(),
+            section_title: entry.section_title.into(),
+            show_play_button: entry.show_play_button,
             item_kind: entry.item_kind,
             album_art: album_art.unwrap_or_default(),
             has_album_art,
@@ -8868,6 +11544,7 @@ impl UiManager {
         self.sync_library_selection_to_ui();
         self.sync_library_add_to_playlist_ui();
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
     }
 
     fn build_library_selection_specs(&self) -> Vec<protocol::LibrarySelectionSpec> {
@@ -9002,13 +11679,83 @@ impl UiManager {
         ));
     }
 
-    fn cancel_library_add_to_playlists(&mut self) {
-        self.library_add_to_dialog_visible = false;
-        self.sync_library_add_to_playlist_ui();
-    }
-
-    fn show_library_toast(&mut self, message: impl Into<String>) {
-        let message = message.into();
+    fn queue_library_selection(&mut self, action: protocol::LibraryQueueAction) {
+        let selections = self.build_library_selection_specs();
+        if selections.is_empty() {
+            self.library_status_text = "Select at least one library item.".to_string();
+            self.show_library_toast("Select at least one library item.");
+            self.sync_library_ui();
+            return;
+        }
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::QueueSelection { selections, action },
+        ));
+    }
+
+    fn apply_remote_copies_snapshot(&mut self, copies: Vec<(PathBuf, Vec<PathBuf>)>) {
+        self.remote_copies_by_path = copies.into_iter().collect();
+        self.remote_source_paths
+            .retain(|path| self.remote_copies_by_path.contains_key(path));
+        self.sync_properties_action_state();
+        self.sync_library_ui();
+    }
+
+    /// Switches the selected library track between its local file and its remote copy.
+    fn toggle_selected_library_track_source(&mut self) {
+        let Some((path, _)) = self.library_properties_target() else {
+            return;
+        };
+        if !self.remote_copies_by_path.contains_key(&path) {
+            return;
+        }
+        let prefer_remote = self.remote_source_paths.insert(path.clone());
+        if !prefer_remote {
+            self.remote_source_paths.remove(&path);
+        }
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetPreferRemoteCopy {
+                path,
+                prefer_remote,
+            },
+        ));
+        self.show_library_toast(if prefer_remote {
+            "This track now plays from its remote copy"
+        } else {
+            "This track now plays from the local file"
+        });
+        self.sync_properties_action_state();
+    }
+
+    fn generate_library_timed_mix(
+        &mut self,
+        target_minutes: u32,
+        destination: protocol::TimedMixDestination,
+        flow: protocol::EnergyFlow,
+    ) {
+        let selections = self.build_library_selection_specs();
+        if selections.is_empty() {
+            self.library_status_text = "Select at least one library item.".to_string();
+            self.show_library_toast("Select at least one library item.");
+            self.sync_library_ui();
+            return;
+        }
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::GenerateTimedMix {
+                selections,
+                target_minutes,
+                destination,
+                flow,
+            },
+        ));
+    }
+
+    fn cancel_library_add_to_playlists(&mut self) {
+        self.library_add_to_dialog_visible = false;
+        self.sync_library_add_to_playlist_ui();
+    }
+
+    fn show_library_toast(&mut self, message: impl Into<String>) {
+        let message = message.into();
         if message.trim().is_empty() {
             return;
         }
@@ -9030,12 +11777,73 @@ impl UiManager {
         });
     }
 
+    fn advance_bulk_import_progress(&mut self, inserted_count: usize) {
+        let Some((imported, total)) = self.bulk_import_progress else {
+            return;
+        };
+        let imported = imported.saturating_add(inserted_count).min(total);
+        if imported >= total {
+            self.bulk_import_progress = None;
+            if let Some(task) = self.bulk_import_task.take() {
+                task.finish(Ok(()));
+            }
+            self.show_library_toast(format!("Imported {} tracks", total));
+        } else {
+            self.bulk_import_progress = Some((imported, total));
+            self.bulk_import_task
+                .get_or_insert_with(|| {
+                    TaskHandle::start(
+                        &self.bus_sender,
+                        protocol::TaskKind::Import,
+                        "Adding tracks to playlist",
+                        false,
+                        false,
+                    )
+                })
+                .progress(
+                    imported as u64,
+                    Some(total as u64),
+                    format!("{imported} of {total} tracks"),
+                );
+            self.show_library_toast(format!("Importing {} of {} tracks...", imported, total));
+        }
+    }
+
     fn hide_library_toast(&self) {
         let _ = self.ui.upgrade_in_event_loop(move |ui| {
             ui.set_library_toast_visible(false);
         });
     }
 
+    /// Albums/EPs/Singles heading for the row at `row_index` when it is the first release
+    /// of its kind on an artist page.
+    fn library_release_section_title(
+        entries: &[LibraryEntry],
+        view_indices: &[usize],
+        view: &LibraryViewState,
+        row_index: usize,
+    ) -> String {
+        if !matches!(view, LibraryViewState::ArtistDetail { .. }) {
+            return String::new();
+        }
+        let release_kind_at = |row: usize| match view_indices
+            .get(row)
+            .and_then(|source_index| entries.get(*source_index))
+        {
+            Some(LibraryEntry::Album(album)) => Some(release_kind::release_kind(album)),
+            _ => None,
+        };
+        let Some(kind) = release_kind_at(row_index) else {
+            return String::new();
+        };
+        let previous_kind = (0..row_index).rev().find_map(release_kind_at);
+        if previous_kind == Some(kind) {
+            String::new()
+        } else {
+            kind.section_title().to_string()
+        }
+    }
+
     fn library_row_presentation_from_entry(
         &mut self,
         entry: &LibraryEntry,
@@ -9108,13 +11916,16 @@ impl UiManager {
                     secondary,
                     primary_rich,
                     secondary_rich,
+                    caption: String::new(),
+                    section_title: String::new(),
+                    show_play_button: false,
                     item_kind: LIBRARY_ITEM_KIND_SONG,
                     cover_art_path: if compact_track_row_view || !resolve_cover_art {
                         None
                     } else {
                         self.resolve_library_cover_art_path(&track.path)
                     },
-                    source_badge: Self::source_badge_for_track_path(track.path.as_path()),
+                    source_badge: self.library_source_badge(track.path.as_path()),
                     is_playing: self.playing_track.path.as_ref() == Some(&track.path),
                     favoritable: true,
                     favorited: self.favorites_by_key.contains_key(&favorite_key),
@@ -9152,6 +11963,9 @@ impl UiManager {
                     secondary: secondary.clone(),
                     primary_rich,
                     secondary_rich: Self::rendered_single_run(secondary, 11, None),
+                    caption: String::new(),
+                    section_title: String::new(),
+                    show_play_button: false,
                     item_kind: LIBRARY_ITEM_KIND_ARTIST,
                     cover_art_path: if resolve_cover_art {
                         self.artist_enrichment_image_path(&artist.artist)
@@ -9209,6 +12023,9 @@ impl UiManager {
                     secondary,
                     primary_rich,
                     secondary_rich,
+                    caption: Self::library_album_caption(album),
+                    section_title: String::new(),
+                    show_play_button: matches!(view, LibraryViewState::ArtistDetail { .. }),
                     item_kind: LIBRARY_ITEM_KIND_ALBUM,
                     cover_art_path: if resolve_cover_art {
                         album
@@ -9245,6 +12062,9 @@ impl UiManager {
                     11,
                     None,
                 ),
+                caption: String::new(),
+                section_title: String::new(),
+                show_play_button: false,
                 item_kind: LIBRARY_ITEM_KIND_GENRE,
                 cover_art_path: None,
                 source_badge: String::new(),
@@ -9273,6 +12093,9 @@ impl UiManager {
                     11,
                     None,
                 ),
+                caption: String::new(),
+                section_title: String::new(),
+                show_play_button: false,
                 item_kind: LIBRARY_ITEM_KIND_DECADE,
                 cover_art_path: None,
                 source_badge: String::new(),
@@ -9291,6 +12114,9 @@ impl UiManager {
                     11,
                     None,
                 ),
+                caption: String::new(),
+                section_title: String::new(),
+                show_play_button: false,
                 item_kind: Self::item_kind_for_favorite_category(category.kind),
                 cover_art_path: None,
                 source_badge: String::new(),
@@ -9361,7 +12187,34 @@ impl UiManager {
         {
             Vec::new()
         } else {
-            Self::build_library_view_indices_for_query(&entries, &self.library_search_query)
+            let energy_query = track_energy::parse_energy_filter_query(&self.library_search_query);
+            let play_stats_query = play_stats::parse_play_stats_filter_query(&energy_query.text);
+            let mut indices = Self::build_library_view_indices_for_query(
+                &entries,
+                &play_stats_query.text,
+                &self.user_tags_by_path,
+                &self.track_notes_by_path,
+            );
+            Self::retain_library_tracks_with_energy(
+                &entries,
+                &mut indices,
+                &energy_query.energies,
+                &self.track_energy_by_path,
+            );
+            Self::retain_library_tracks_with_play_stats(
+                &entries,
+                &mut indices,
+                &play_stats_query,
+                &self.track_play_stats_by_path,
+            );
+            if matches!(view, LibraryViewState::TracksRoot) {
+                Self::retain_library_tracks_with_facets(
+                    &entries,
+                    &mut indices,
+                    &self.library_facet_selection,
+                );
+            }
+            indices
         };
         let library_view_indices = self.library_view_indices.clone();
         let (cover_decode_start, cover_decode_end) =
@@ -9511,6 +12364,12 @@ impl UiManager {
                         selected_set.contains(source_index),
                         resolve_cover_art,
                     );
+                    presentation.section_title = Self::library_release_section_title(
+                        &entries,
+                        &library_view_indices,
+                        &view,
+                        row_index,
+                    );
                     if resolve_cover_art {
                         presentation.cover_art_path =
                             self.list_thumbnail_for_library_presentation(&presentation);
@@ -9612,6 +12471,7 @@ impl UiManager {
         self.library_entries = entries;
         self.sync_library_search_state_to_ui();
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
     }
 
     fn set_collection_mode(&mut self, mode: i32) {
@@ -9628,6 +12488,10 @@ impl UiManager {
         if self.collection_mode == COLLECTION_MODE_LIBRARY {
             self.update_library_playing_index();
             self.request_library_view_data();
+            if !self.library_root_counts_requested {
+                self.library_root_counts_requested = true;
+                self.request_library_root_counts();
+            }
         } else {
             self.release_hidden_library_memory();
         }
@@ -10226,6 +13090,103 @@ impl UiManager {
         ));
     }
 
+    fn request_library_facet_counts(&self) {
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::RequestFacetCounts(self.library_facet_selection.clone()),
+        ));
+    }
+
+    fn set_library_facet_panel_visible(&mut self, visible: bool) {
+        self.library_facet_panel_visible = visible;
+        if visible {
+            self.request_library_facet_counts();
+        } else if !library_facets::selection_is_empty(&self.library_facet_selection) {
+            self.library_facet_selection = protocol::LibraryFacetSelection::default();
+            self.sync_library_ui();
+        }
+        self.sync_library_facets_to_ui();
+    }
+
+    fn toggle_library_facet(&mut self, kind: protocol::LibraryFacetKind, value: &str) {
+        library_facets::toggle(&mut self.library_facet_selection, kind, value);
+        self.request_library_facet_counts();
+        self.sync_library_ui();
+        self.sync_library_facets_to_ui();
+    }
+
+    fn clear_library_facets(&mut self) {
+        if library_facets::selection_is_empty(&self.library_facet_selection) {
+            return;
+        }
+        self.library_facet_selection = protocol::LibraryFacetSelection::default();
+        self.request_library_facet_counts();
+        self.sync_library_ui();
+        self.sync_library_facets_to_ui();
+    }
+
+    /// Sidebar rows: a heading per dimension followed by its values. Picked values the
+    /// latest counts no longer list stay visible with a zero count so they can be unpicked.
+    fn library_facet_rows(
+        selection: &protocol::LibraryFacetSelection,
+        counts: &[protocol::LibraryFacetCount],
+    ) -> Vec<LibraryFacetRowData> {
+        let mut rows = Vec::new();
+        for (kind_index, kind) in [
+            protocol::LibraryFacetKind::Genre,
+            protocol::LibraryFacetKind::Decade,
+            protocol::LibraryFacetKind::Format,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut values: Vec<(String, u32)> = counts
+                .iter()
+                .filter(|count| count.kind == kind)
+                .map(|count| (count.value.clone(), count.track_count))
+                .collect();
+            let selected_missing: Vec<String> = library_facets::selected_values(selection, kind)
+                .iter()
+                .filter(|value| !values.iter().any(|(listed, _)| listed == *value))
+                .cloned()
+                .collect();
+            values.extend(selected_missing.into_iter().map(|value| (value, 0)));
+            if values.is_empty() {
+                continue;
+            }
+            rows.push(LibraryFacetRowData {
+                kind: kind_index as i32,
+                value: library_facets::kind_title(kind).into(),
+                count: 0,
+                selected: false,
+                header: true,
+            });
+            rows.extend(
+                values
+                    .into_iter()
+                    .map(|(value, track_count)| LibraryFacetRowData {
+                        kind: kind_index as i32,
+                        selected: library_facets::is_selected(selection, kind, &value),
+                        value: value.into(),
+                        count: track_count.min(i32::MAX as u32) as i32,
+                        header: false,
+                    }),
+            );
+        }
+        rows
+    }
+
+    fn sync_library_facets_to_ui(&self) {
+        let visible = self.library_facet_panel_visible;
+        let active = !library_facets::selection_is_empty(&self.library_facet_selection);
+        let rows =
+            Self::library_facet_rows(&self.library_facet_selection, &self.library_facet_counts);
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_library_facet_panel_visible(visible);
+            ui.set_library_facets_active(active);
+            ui.set_library_facet_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
     fn set_library_entries(&mut self, entries: Vec<LibraryEntry>) {
         self.library_entries = entries;
         self.update_library_playing_index();
@@ -10309,6 +13270,17 @@ impl UiManager {
                 self.library_status_text = error_text;
                 self.sync_library_scan_status_to_ui();
             }
+            protocol::LibraryMessage::ScanFolderAccessWarnings(warnings) => {
+                let Some(first) = warnings.first() else {
+                    return;
+                };
+                let toast_text = if warnings.len() > 1 {
+                    format!("{} (and {} more folders)", first, warnings.len() - 1)
+                } else {
+                    first.clone()
+                };
+                self.show_library_toast(toast_text);
+            }
             _ => {}
         }
     }
@@ -10477,176 +13449,1351 @@ impl UiManager {
             });
         }
 
-        if tracks.is_empty() {
-            return None;
+        if tracks.is_empty() {
+            return None;
+        }
+
+        let start_index = explicit_start_index.or(selected_start_index).unwrap_or(0);
+        Some(protocol::PlaybackQueueRequest {
+            source: protocol::PlaybackQueueSource::Library,
+            tracks,
+            start_index,
+        })
+    }
+
+    fn start_queue_if_possible(&self, request: Option<protocol::PlaybackQueueRequest>) {
+        let Some(request) = request else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Playback(
+            protocol::PlaybackMessage::StartQueue(request),
+        ));
+    }
+
+    fn play_active_collection(&self) {
+        if self.has_paused_playback_queue() {
+            let _ = self
+                .bus_sender
+                .send(protocol::Message::Playback(protocol::PlaybackMessage::Play));
+            return;
+        }
+        let request = if self.collection_mode == COLLECTION_MODE_LIBRARY {
+            self.build_library_queue_request(None)
+        } else {
+            self.build_playlist_queue_request(None)
+        };
+        self.start_queue_if_possible(request);
+    }
+
+    fn activate_library_item(&mut self, index: usize) {
+        let Some(entry) = self.library_entries.get(index).cloned() else {
+            return;
+        };
+
+        match entry {
+            LibraryEntry::Track(_) => {
+                self.start_queue_if_possible(self.build_library_queue_request(Some(index)));
+            }
+            LibraryEntry::Artist(artist) => {
+                self.clear_search_bars_for_track_list_view_switch();
+                self.remember_current_library_scroll_position();
+                self.library_view_stack
+                    .push(LibraryViewState::ArtistDetail {
+                        artist: artist.artist,
+                    });
+                self.library_artist_prefetch_first_row = 0;
+                self.library_artist_prefetch_row_count = 0;
+                self.prepare_library_view_transition();
+                self.request_library_view_data();
+                self.sync_library_ui();
+            }
+            LibraryEntry::Album(album) => {
+                self.clear_search_bars_for_track_list_view_switch();
+                self.remember_current_library_scroll_position();
+                self.library_view_stack.push(LibraryViewState::AlbumDetail {
+                    album: album.album,
+                    album_artist: album.album_artist,
+                });
+                self.library_artist_prefetch_first_row = 0;
+                self.library_artist_prefetch_row_count = 0;
+                self.prepare_library_view_transition();
+                self.request_library_view_data();
+                self.sync_library_ui();
+            }
+            LibraryEntry::Genre(genre) => {
+                self.clear_search_bars_for_track_list_view_switch();
+                self.remember_current_library_scroll_position();
+                self.library_view_stack
+                    .push(LibraryViewState::GenreDetail { genre: genre.genre });
+                self.library_artist_prefetch_first_row = 0;
+                self.library_artist_prefetch_row_count = 0;
+                self.prepare_library_view_transition();
+                self.request_library_view_data();
+                self.sync_library_ui();
+            }
+            LibraryEntry::Decade(decade) => {
+                self.clear_search_bars_for_track_list_view_switch();
+                self.remember_current_library_scroll_position();
+                self.library_view_stack
+                    .push(LibraryViewState::DecadeDetail {
+                        decade: decade.decade,
+                    });
+                self.library_artist_prefetch_first_row = 0;
+                self.library_artist_prefetch_row_count = 0;
+                self.prepare_library_view_transition();
+                self.request_library_view_data();
+                self.sync_library_ui();
+            }
+            LibraryEntry::FavoriteCategory(category) => {
+                self.clear_search_bars_for_track_list_view_switch();
+                self.remember_current_library_scroll_position();
+                let next_view = match category.kind {
+                    protocol::FavoriteEntityKind::Track => LibraryViewState::FavoriteTracks,
+                    protocol::FavoriteEntityKind::Artist => LibraryViewState::FavoriteArtists,
+                    protocol::FavoriteEntityKind::Album => LibraryViewState::FavoriteAlbums,
+                };
+                self.library_view_stack.push(next_view);
+                self.prepare_library_view_transition();
+                self.request_library_view_data();
+                self.sync_library_ui();
+            }
+        }
+    }
+
+    fn toggle_favorite_for_library_row(&self, view_row: usize) {
+        let Some(source_index) = self.map_library_view_to_source_index(view_row) else {
+            return;
+        };
+        let Some(entry) = self.library_entries.get(source_index) else {
+            return;
+        };
+        let Some(entity) = self.favorite_entity_for_library_entry(entry) else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::ToggleFavorite {
+                entity,
+                desired: None,
+            },
+        ));
+    }
+
+    fn toggle_favorite_for_playlist_row(&self, view_row: usize) {
+        let Some(source_index) = self.map_view_to_source_index(view_row) else {
+            return;
+        };
+        let Some(entity) = self.favorite_entity_for_playlist_source_index(source_index) else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::ToggleFavorite {
+                entity,
+                desired: None,
+            },
+        ));
+    }
+
+    fn toggle_favorite_now_playing(&self) {
+        let Some(entity) = self.current_track_favorite_entity() else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::ToggleFavorite {
+                entity,
+                desired: None,
+            },
+        ));
+    }
+
+    fn apply_favorites_snapshot(&mut self, items: Vec<protocol::FavoriteEntityRef>) {
+        self.favorites_by_key = items
+            .into_iter()
+            .map(|item| (item.entity_key.clone(), item))
+            .collect();
+        self.rebuild_track_model();
+        self.sync_library_ui();
+        self.sync_now_playing_favorite_state_to_ui();
+    }
+
+    fn apply_user_tags_snapshot(
+        &mut self,
+        tags: Vec<protocol::UserTag>,
+        assignments: Vec<(PathBuf, String)>,
+    ) {
+        self.user_tags = tags;
+        self.user_tags_by_path.clear();
+        for (path, tag_name) in assignments {
+            self.user_tags_by_path
+                .entry(path)
+                .or_default()
+                .push(tag_name);
+        }
+        self.rebuild_track_model();
+        self.sync_library_ui();
+        self.sync_track_tags_dialog_ui();
+    }
+
+    /// Publishes live worker pool usage to settings when it changed since the last tick.
+    fn refresh_worker_pool_diagnostics(&mut self) {
+        let diagnostics = worker_pool::diagnostics_summary();
+        if diagnostics == self.worker_pool_diagnostics {
+            return;
+        }
+        self.worker_pool_diagnostics = diagnostics.clone();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_worker_pool_diagnostics(diagnostics.into());
+        });
+    }
+
+    /// Publishes the startup phases recorded so far to settings when new ones were marked.
+    fn refresh_startup_timing_diagnostics(&mut self) {
+        let diagnostics = crate::startup_timing::summary();
+        if diagnostics == self.startup_timing_diagnostics {
+            return;
+        }
+        self.startup_timing_diagnostics = diagnostics.clone();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_startup_timing_diagnostics(diagnostics.into());
+        });
+    }
+
+    /// Publishes the files that failed their latest integrity check to library settings.
+    fn apply_integrity_report_snapshot(&mut self, issues: Vec<protocol::IntegrityIssue>) {
+        let rows: Vec<slint::SharedString> = issues
+            .iter()
+            .map(|issue| {
+                format!(
+                    "{} \u{00b7} {}: {}",
+                    issue.path.display(),
+                    integrity_check::issue_kind_label(issue.kind),
+                    issue.detail
+                )
+                .into()
+            })
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_integrity_issues(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    /// Publishes the activity log, newest first, to library settings.
+    fn apply_activity_log_snapshot(&mut self, entries: Vec<protocol::ActivityLogEntry>) {
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        let rows: Vec<slint::SharedString> = entries
+            .iter()
+            .map(|entry| activity_log::format_entry(now_unix_ms, entry).into())
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_activity_log(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    /// Publishes cache usage to the Caches section of library settings.
+    fn apply_cache_usage_snapshot(&self, usage: &[protocol::CacheUsage]) {
+        let rows: Vec<CacheRowData> = usage.iter().map(Self::cache_row).collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_cache_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    /// Caches settings row for one cache.
+    fn cache_row(usage: &protocol::CacheUsage) -> CacheRowData {
+        let size = column_format::format_file_size(usage.size_bytes, ColumnNumberFormat::Readable);
+        let usage_text = if usage.max_size_mb > 0 {
+            format!(
+                "{size} of {}, {} files",
+                cache_manager::limit_label(usage.max_size_mb),
+                usage.file_count
+            )
+        } else {
+            format!("{size}, {} files", usage.file_count)
+        };
+        let limit_options: Vec<slint::SharedString> = cache_manager::limit_choices_mb(usage.kind)
+            .iter()
+            .map(|value_mb| cache_manager::limit_label(*value_mb).into())
+            .collect();
+        CacheRowData {
+            label: cache_manager::cache_label(usage.kind).into(),
+            usage: usage_text.into(),
+            location: usage.dir.to_string_lossy().to_string().into(),
+            custom_location: usage.dir != cache_manager::default_cache_dir(usage.kind),
+            limit_options: ModelRc::from(Rc::new(VecModel::from(limit_options))),
+            limit_index: cache_manager::limit_choice_index(usage.kind, usage.max_size_mb) as i32,
+        }
+    }
+
+    /// Drops every remembered cover-art path and decoded cover image, so covers are
+    /// looked up again after the cover-art cache was emptied or moved.
+    fn forget_cached_cover_art(&mut self) {
+        TRACK_ROW_COVER_ART_IMAGE_CACHE.with(|cache| {
+            cache.borrow_mut().clear();
+        });
+        TRACK_ROW_COVER_ART_FAILED_PATHS.with(|failed| {
+            failed.borrow_mut().clear();
+        });
+        self.pending_list_image_requests.clear();
+        self.track_cover_art_paths.fill(None);
+        self.track_cover_art_missing_tracks.clear();
+        self.library_cover_art_paths.clear();
+        self.folder_cover_art_paths.clear();
+        self.last_cover_art_lookup_path = None;
+        self.rebuild_track_model();
+        self.request_library_view_data();
+    }
+
+    /// Publishes maintenance run history and each task's last run to library settings.
+    fn apply_maintenance_history_snapshot(&mut self, runs: Vec<protocol::MaintenanceRunRecord>) {
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        let last_runs: Vec<slint::SharedString> = maintenance_scheduler::ALL_TASKS
+            .iter()
+            .map(|task| {
+                runs.iter()
+                    .find(|run| run.task == *task)
+                    .map(|run| {
+                        format!(
+                            "Last run {}",
+                            maintenance_scheduler::format_elapsed(now_unix_ms, run.started_unix_ms)
+                        )
+                    })
+                    .unwrap_or_else(|| "Never run".to_string())
+                    .into()
+            })
+            .collect();
+        let history: Vec<slint::SharedString> = runs
+            .iter()
+            .map(|run| {
+                format!(
+                    "{} \u{00b7} {} \u{00b7} {}{}",
+                    maintenance_scheduler::format_elapsed(now_unix_ms, run.started_unix_ms),
+                    maintenance_scheduler::task_label(run.task),
+                    if run.success { "" } else { "Failed: " },
+                    run.summary
+                )
+                .into()
+            })
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_settings_maintenance_last_runs(ModelRc::from(Rc::new(VecModel::from(
+                last_runs,
+            ))));
+            ui.set_settings_maintenance_history(ModelRc::from(Rc::new(VecModel::from(history))));
+        });
+    }
+
+    fn apply_track_notes_snapshot(&mut self, notes: Vec<(PathBuf, String)>) {
+        self.track_notes_by_path = notes.into_iter().collect();
+        if !self.filter_search_query.trim().is_empty() {
+            self.rebuild_track_model();
+        }
+        if !self.library_search_query.trim().is_empty() {
+            self.sync_library_ui();
+        }
+        self.sync_notes_panel_ui(true);
+    }
+
+    /// Pushes the selected track's and active playlist's notes into the notes panel.
+    /// Text is only replaced when the panel target changes (or `force` is set) so
+    /// unrelated selection syncs do not discard unsaved edits.
+    fn sync_notes_panel_ui(&mut self, force: bool) {
+        let track_target = self.active_properties_target();
+        let track_path = track_target.as_ref().map(|(path, _)| path.clone());
+        let playlist_id = self.active_playlist_id.clone();
+        if !force
+            && track_path == self.notes_panel_track_path
+            && playlist_id == self.notes_panel_playlist_id
+        {
+            return;
+        }
+        let track_changed = force || track_path != self.notes_panel_track_path;
+        let playlist_changed = force || playlist_id != self.notes_panel_playlist_id;
+        self.notes_panel_track_path = track_path;
+        self.notes_panel_playlist_id = playlist_id;
+
+        let track_enabled = track_target.is_some();
+        let track_title = track_target
+            .as_ref()
+            .map(|(_, title)| title.clone())
+            .unwrap_or_default();
+        let track_note = self
+            .notes_panel_track_path
+            .as_ref()
+            .and_then(|path| self.track_notes_by_path.get(path))
+            .cloned()
+            .unwrap_or_default();
+        let playlist_name = self
+            .playlist_ids
+            .iter()
+            .position(|id| id == &self.notes_panel_playlist_id)
+            .and_then(|index| self.playlist_names.get(index))
+            .cloned()
+            .unwrap_or_default();
+        let playlist_enabled = !self.notes_panel_playlist_id.is_empty();
+        let playlist_note = self
+            .playlist_notes_by_id
+            .get(&self.notes_panel_playlist_id)
+            .cloned()
+            .unwrap_or_default();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_notes_track_enabled(track_enabled);
+            ui.set_notes_track_title(track_title.into());
+            if track_changed {
+                ui.set_notes_track_text(track_note.into());
+            }
+            ui.set_notes_playlist_enabled(playlist_enabled);
+            ui.set_notes_playlist_name(playlist_name.into());
+            if playlist_changed {
+                ui.set_notes_playlist_text(playlist_note.into());
+            }
+        });
+    }
+
+    fn save_notes_panel_track_note(&mut self, note: String) {
+        let Some(path) = self.notes_panel_track_path.clone() else {
+            return;
+        };
+        let note = note.trim_end().to_string();
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::SetTrackNote { path, note },
+        ));
+    }
+
+    fn save_notes_panel_playlist_note(&mut self, note: String) {
+        if self.notes_panel_playlist_id.is_empty() {
+            return;
+        }
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetPlaylistNote {
+                playlist_id: self.notes_panel_playlist_id.clone(),
+                note,
+            },
+        ));
+    }
+
+    fn sync_playlist_tree_ui(&mut self) {
+        self.playlist_tree_rows = playlist_folders::build_tree_rows(
+            &self.sidebar_playlists,
+            &self.sidebar_playlist_folders,
+            &self.collapsed_playlist_folder_ids,
+        );
+        let rows = self
+            .playlist_tree_rows
+            .iter()
+            .map(|row| match row.item {
+                PlaylistTreeItem::Folder(index) => PlaylistTreeRowData {
+                    is_folder: true,
+                    index: -1,
+                    depth: row.depth as i32,
+                    expanded: row.expanded,
+                    name: self.sidebar_playlist_folders[index].name.as_str().into(),
+                },
+                PlaylistTreeItem::Playlist(index) => PlaylistTreeRowData {
+                    is_folder: false,
+                    index: index as i32,
+                    depth: row.depth as i32,
+                    expanded: false,
+                    name: self.sidebar_playlists[index].name.as_str().into(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_playlist_tree_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    fn playlist_folder_at_row(&self, row: usize) -> Option<&protocol::PlaylistFolderInfo> {
+        match self.playlist_tree_rows.get(row)?.item {
+            PlaylistTreeItem::Folder(index) => self.sidebar_playlist_folders.get(index),
+            PlaylistTreeItem::Playlist(_) => None,
+        }
+    }
+
+    fn toggle_playlist_folder_row(&mut self, row: usize) {
+        let Some(folder_id) = self
+            .playlist_folder_at_row(row)
+            .map(|folder| folder.id.clone())
+        else {
+            return;
+        };
+        if !self.collapsed_playlist_folder_ids.remove(&folder_id) {
+            self.collapsed_playlist_folder_ids.insert(folder_id);
+        }
+        self.sync_playlist_tree_ui();
+    }
+
+    /// Creates a folder inside the folder at `row`, next to the playlist at `row`, or at
+    /// the top level when no row is given.
+    fn create_playlist_folder_at_row(&mut self, row: Option<usize>) {
+        let parent_folder = row
+            .and_then(|row| self.playlist_tree_rows.get(row))
+            .and_then(|row| match row.item {
+                PlaylistTreeItem::Folder(index) => Some(index),
+                PlaylistTreeItem::Playlist(_) => row.parent_folder,
+            });
+        let parent_id = parent_folder
+            .and_then(|index| self.sidebar_playlist_folders.get(index))
+            .map(|folder| folder.id.clone());
+        if let Some(parent_id) = parent_id.as_ref() {
+            self.collapsed_playlist_folder_ids.remove(parent_id);
+        }
+        let name = playlist_folders::unique_folder_name(
+            &self.sidebar_playlist_folders,
+            parent_id.as_deref(),
+        );
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::CreatePlaylistFolder { name, parent_id },
+        ));
+    }
+
+    fn move_playlist_tree_row(&self, from: usize, to: usize) {
+        let message = match playlist_folders::resolve_drop(
+            &self.playlist_tree_rows,
+            &self.sidebar_playlists,
+            &self.sidebar_playlist_folders,
+            from,
+            to,
+        ) {
+            Some(PlaylistTreeMove::Playlist {
+                playlist_id,
+                folder_id,
+            }) => protocol::PlaylistMessage::MovePlaylistToFolder {
+                playlist_id,
+                folder_id,
+            },
+            Some(PlaylistTreeMove::Folder {
+                folder_id,
+                parent_id,
+            }) => protocol::PlaylistMessage::MovePlaylistFolder {
+                id: folder_id,
+                parent_id,
+            },
+            None => return,
+        };
+        let _ = self.bus_sender.send(protocol::Message::Playlist(message));
+    }
+
+    fn toggle_playlist_skip_silence(&self, index: usize) {
+        let Some(playlist_id) = self.playlist_ids.get(index) else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetPlaylistSkipSilence {
+                playlist_id: playlist_id.clone(),
+                enabled: !self.skip_silence_playlist_ids.contains(playlist_id),
+            },
+        ));
+    }
+
+    fn sync_playlist_skip_silence_ui(&self) {
+        let skip_silence_flags = self
+            .playlist_ids
+            .iter()
+            .map(|playlist_id| self.skip_silence_playlist_ids.contains(playlist_id))
+            .collect::<Vec<_>>();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_playlist_skip_silence(ModelRc::from(Rc::new(VecModel::from(
+                skip_silence_flags,
+            ))));
+        });
+    }
+
+    fn toggle_playlist_normalize_loudness(&self, index: usize) {
+        let Some(playlist_id) = self.playlist_ids.get(index) else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetPlaylistNormalizeLoudness {
+                playlist_id: playlist_id.clone(),
+                enabled: !self.normalize_loudness_playlist_ids.contains(playlist_id),
+            },
+        ));
+    }
+
+    fn sync_playlist_normalize_loudness_ui(&self) {
+        let normalize_loudness_flags = self
+            .playlist_ids
+            .iter()
+            .map(|playlist_id| self.normalize_loudness_playlist_ids.contains(playlist_id))
+            .collect::<Vec<_>>();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_playlist_normalize_loudness(ModelRc::from(Rc::new(VecModel::from(
+                normalize_loudness_flags,
+            ))));
+        });
+    }
+
+    /// Asks the collage worker to (re)build the collage of `playlist_id`.
+    fn request_playlist_collage(&mut self, playlist_id: &str) {
+        self.playlist_collage_requested_ids
+            .insert(playlist_id.to_string());
+        let _ = self.playlist_collage_tx.send(playlist_id.to_string());
+    }
+
+    fn sync_playlist_artwork_ui(&self) {
+        let descriptions = self
+            .playlist_ids
+            .iter()
+            .map(|playlist_id| {
+                slint::SharedString::from(
+                    self.playlist_descriptions_by_id
+                        .get(playlist_id)
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let custom_cover_flags = self
+            .playlist_ids
+            .iter()
+            .map(|playlist_id| self.playlist_cover_paths_by_id.contains_key(playlist_id))
+            .collect::<Vec<_>>();
+        let cover_paths = self
+            .playlist_ids
+            .iter()
+            .map(|playlist_id| {
+                self.playlist_cover_paths_by_id
+                    .get(playlist_id)
+                    .or_else(|| self.playlist_collage_paths_by_id.get(playlist_id))
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            let (covers, cover_flags): (Vec<Image>, Vec<bool>) = cover_paths
+                .iter()
+                .map(|path| Self::load_track_row_cover_art(path.as_ref()))
+                .unzip();
+            ui.set_playlist_descriptions(ModelRc::from(Rc::new(VecModel::from(descriptions))));
+            ui.set_playlist_covers(ModelRc::from(Rc::new(VecModel::from(covers))));
+            ui.set_playlist_has_cover(ModelRc::from(Rc::new(VecModel::from(cover_flags))));
+            ui.set_playlist_has_custom_cover(ModelRc::from(Rc::new(VecModel::from(
+                custom_cover_flags,
+            ))));
+        });
+    }
+
+    /// Describes the sync state of a remote-bound playlist for its sidebar context menu.
+    fn remote_playlist_sync_status_text(
+        status: Option<&protocol::RemotePlaylistSyncStatus>,
+        now_unix_ms: i64,
+    ) -> String {
+        let Some(status) = status else {
+            return "Not synced yet".to_string();
+        };
+        match (status.local_edits_pending, status.remote_changes_pending) {
+            (true, true) => "Local and server changes pending".to_string(),
+            (true, false) => "Local edits not yet saved to server".to_string(),
+            (false, true) => "Server changes not yet pulled".to_string(),
+            (false, false) => status
+                .last_synced_unix_ms
+                .map(|synced| {
+                    format!(
+                        "Synced {}",
+                        maintenance_scheduler::format_elapsed(now_unix_ms, synced)
+                    )
+                })
+                .unwrap_or_else(|| "Not synced yet".to_string()),
+        }
+    }
+
+    fn sync_playlist_remote_sync_ui(&self) {
+        let now_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        let mut pending_flags = Vec::with_capacity(self.playlist_ids.len());
+        let mut status_texts = Vec::with_capacity(self.playlist_ids.len());
+        for playlist_id in &self.playlist_ids {
+            if !Self::is_pure_remote_playlist_id(playlist_id) {
+                pending_flags.push(false);
+                status_texts.push(slint::SharedString::new());
+                continue;
+            }
+            let status = self.remote_playlist_sync_statuses.get(playlist_id);
+            pending_flags.push(
+                status.is_some_and(|status| {
+                    status.local_edits_pending || status.remote_changes_pending
+                }),
+            );
+            status_texts.push(Self::remote_playlist_sync_status_text(status, now_unix_ms).into());
+        }
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_playlist_sync_pending(ModelRc::from(Rc::new(VecModel::from(pending_flags))));
+            ui.set_playlist_sync_status(ModelRc::from(Rc::new(VecModel::from(status_texts))));
+        });
+    }
+
+    /// Tracks the tags dialog applies to: the playlist or library track selection.
+    fn track_tags_targets(&self) -> Vec<PathBuf> {
+        if self.collection_mode == COLLECTION_MODE_LIBRARY {
+            self.library_selected_indices
+                .iter()
+                .filter_map(|&index| match self.library_entries.get(index) {
+                    Some(LibraryEntry::Track(track)) => Some(track.path.clone()),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            self.selected_indices
+                .iter()
+                .filter_map(|&index| self.track_paths.get(index).cloned())
+                .collect()
+        }
+    }
+
+    fn open_track_tags_dialog(&mut self) {
+        let targets = self.track_tags_targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.track_tags_dialog_paths = targets;
+        self.sync_track_tags_dialog_ui();
+    }
+
+    fn close_track_tags_dialog(&mut self) {
+        self.track_tags_dialog_paths.clear();
+        self.sync_track_tags_dialog_ui();
+    }
+
+    fn track_tags_dialog_all_assigned(&self, tag: &protocol::UserTag) -> bool {
+        let key = user_tags::tag_key(&tag.name);
+        self.track_tags_dialog_paths.iter().all(|path| {
+            self.user_tags_by_path.get(path).is_some_and(|tags| {
+                tags.iter()
+                    .any(|track_tag| user_tags::tag_key(track_tag) == key)
+            })
+        })
+    }
+
+    fn sync_track_tags_dialog_ui(&self) {
+        let visible = !self.track_tags_dialog_paths.is_empty();
+        let target_count = self.track_tags_dialog_paths.len();
+        let labels: Vec<slint::SharedString> = self
+            .user_tags
+            .iter()
+            .map(|tag| tag.name.as_str().into())
+            .collect();
+        let colors: Vec<slint::Color> = self
+            .user_tags
+            .iter()
+            .map(|tag| {
+                let (r, g, b) = user_tags::tag_color_rgb(&tag.color).unwrap_or((128, 128, 128));
+                slint::Color::from_rgb_u8(r, g, b)
+            })
+            .collect();
+        let checked: Vec<bool> = self
+            .user_tags
+            .iter()
+            .map(|tag| visible && self.track_tags_dialog_all_assigned(tag))
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_track_tags_labels(ModelRc::from(Rc::new(VecModel::from(labels))));
+            ui.set_track_tags_colors(ModelRc::from(Rc::new(VecModel::from(colors))));
+            ui.set_track_tags_checked(ModelRc::from(Rc::new(VecModel::from(checked))));
+            ui.set_track_tags_target_count(target_count.min(i32::MAX as usize) as i32);
+            ui.set_show_track_tags_dialog(visible);
+        });
+    }
+
+    fn toggle_track_tag_in_dialog(&mut self, index: usize) {
+        let Some(tag) = self.user_tags.get(index) else {
+            return;
+        };
+        if self.track_tags_dialog_paths.is_empty() {
+            return;
+        }
+        let assigned = !self.track_tags_dialog_all_assigned(tag);
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::SetTrackUserTag {
+                paths: self.track_tags_dialog_paths.clone(),
+                tag: tag.name.clone(),
+                assigned,
+            },
+        ));
+    }
+
+    fn create_track_tag_in_dialog(&mut self, name: String) {
+        let Some(name) = user_tags::normalize_tag_name(&name) else {
+            return;
+        };
+        let key = user_tags::tag_key(&name);
+        if !self
+            .user_tags
+            .iter()
+            .any(|tag| user_tags::tag_key(&tag.name) == key)
+        {
+            let _ = self.bus_sender.send(protocol::Message::Library(
+                protocol::LibraryMessage::UpsertUserTag {
+                    name: name.clone(),
+                    color: user_tags::next_palette_color(&self.user_tags),
+                },
+            ));
+        }
+        if !self.track_tags_dialog_paths.is_empty() {
+            let _ = self.bus_sender.send(protocol::Message::Library(
+                protocol::LibraryMessage::SetTrackUserTag {
+                    paths: self.track_tags_dialog_paths.clone(),
+                    tag: name,
+                    assigned: true,
+                },
+            ));
+        }
+    }
+
+    fn cycle_track_tag_color_in_dialog(&mut self, index: usize) {
+        let Some(tag) = self.user_tags.get(index) else {
+            return;
+        };
+        let palette_index = user_tags::TAG_COLOR_PALETTE
+            .iter()
+            .position(|color| color.eq_ignore_ascii_case(&tag.color))
+            .map(|position| (position + 1) % user_tags::TAG_COLOR_PALETTE.len())
+            .unwrap_or(0);
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::UpsertUserTag {
+                name: tag.name.clone(),
+                color: user_tags::TAG_COLOR_PALETTE[palette_index].to_string(),
+            },
+        ));
+    }
+
+    fn delete_track_tag_in_dialog(&mut self, index: usize) {
+        let Some(tag) = self.user_tags.get(index) else {
+            return;
+        };
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::DeleteUserTag {
+                name: tag.name.clone(),
+            },
+        ));
+    }
+
+    /// Parses a transition dialog trim in seconds; blank input clears the trim.
+    fn parse_trim_seconds(text: &str) -> Option<u64> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Some(0);
+        }
+        let seconds = trimmed.parse::<f64>().ok()?;
+        if !seconds.is_finite() || seconds < 0.0 || seconds > MAX_TRIM_SECONDS {
+            return None;
+        }
+        Some((seconds * 1000.0).round() as u64)
+    }
+
+    fn format_trim_seconds(trim_ms: u64) -> String {
+        if trim_ms == 0 {
+            String::new()
+        } else if trim_ms % 1000 == 0 {
+            (trim_ms / 1000).to_string()
+        } else {
+            format!("{:.1}", trim_ms as f64 / 1000.0)
+        }
+    }
+
+    fn open_track_transition_dialog(&mut self) {
+        let targets: Vec<PathBuf> = self
+            .selected_indices
+            .iter()
+            .filter_map(|&index| self.track_paths.get(index).cloned())
+            .collect();
+        let Some(first_path) = targets.first() else {
+            return;
+        };
+        let trims = self
+            .track_trims_by_path
+            .get(first_path)
+            .copied()
+            .unwrap_or_default();
+        let target_count = targets.len().min(i32::MAX as usize) as i32;
+        self.track_transition_dialog_paths = targets;
+        let trim_start_text = Self::format_trim_seconds(trims.start_ms);
+        let trim_end_text = Self::format_trim_seconds(trims.end_ms);
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_track_transition_target_count(target_count);
+            ui.set_track_transition_trim_start_text(trim_start_text.into());
+            ui.set_track_transition_trim_end_text(trim_end_text.into());
+            ui.set_track_transition_error_text("".into());
+            ui.set_show_track_transition_dialog(true);
+        });
+    }
+
+    fn close_track_transition_dialog(&mut self) {
+        self.track_transition_dialog_paths.clear();
+        let _ = self.ui.upgrade_in_event_loop(|ui| {
+            ui.set_show_track_transition_dialog(false);
+        });
+    }
+
+    fn save_track_transition_dialog(&mut self, trim_start_text: String, trim_end_text: String) {
+        if self.track_transition_dialog_paths.is_empty() {
+            return;
+        }
+        let (Some(start_ms), Some(end_ms)) = (
+            Self::parse_trim_seconds(&trim_start_text),
+            Self::parse_trim_seconds(&trim_end_text),
+        ) else {
+            let _ = self.ui.upgrade_in_event_loop(|ui| {
+                ui.set_track_transition_error_text(
+                    format!("Enter a number of seconds between 0 and {MAX_TRIM_SECONDS}.").into(),
+                );
+            });
+            return;
+        };
+        let paths = std::mem::take(&mut self.track_transition_dialog_paths);
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetTrackTrims {
+                paths,
+                trims: protocol::TrackTrims { start_ms, end_ms },
+            },
+        ));
+        self.close_track_transition_dialog();
+    }
+
+    fn analyze_selected_track_loudness(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .selected_indices
+            .iter()
+            .filter_map(|&index| self.track_paths.get(index))
+            .filter(|path| !is_remote_track_path(path))
+            .cloned()
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+        let count = paths.len();
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::AnalyzeTrackLoudness { paths },
+        ));
+        self.show_library_toast(format!(
+            "Measuring loudness of {count} track{}...",
+            if count == 1 { "" } else { "s" }
+        ));
+    }
+
+    /// Playlist tracks the send-to flow applies to, in playlist order.
+    fn selected_playlist_track_paths(&self) -> Vec<PathBuf> {
+        let mut indices: Vec<usize> = self.selected_indices.clone();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+            .into_iter()
+            .filter_map(|index| self.track_paths.get(index).cloned())
+            .collect()
+    }
+
+    /// Recent send-to targets that still exist, as `(id, name)` pairs.
+    fn recent_send_to_playlists(&self) -> Vec<(String, String)> {
+        self.recent_send_to_playlist_ids
+            .iter()
+            .filter_map(|id| {
+                let index = self.playlist_ids.iter().position(|known| known == id)?;
+                let name = self.playlist_names.get(index)?;
+                Some((id.clone(), name.clone()))
+            })
+            .collect()
+    }
+
+    fn open_send_to_playlist_dialog(&mut self) {
+        let paths = self.selected_playlist_track_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let track_count = paths.len().min(i32::MAX as usize) as i32;
+        self.send_to_playlist_paths = paths;
+        let labels: Vec<slint::SharedString> = self
+            .playlist_ids
+            .iter()
+            .zip(self.playlist_names.iter())
+            .map(|(id, name)| {
+                if *id == self.active_playlist_id {
+                    format!("{name} (current)").into()
+                } else {
+                    name.as_str().into()
+                }
+            })
+            .collect();
+        let checked = vec![false; labels.len()];
+        let recent_labels: Vec<slint::SharedString> = self
+            .recent_send_to_playlists()
+            .into_iter()
+            .map(|(_, name)| name.into())
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_send_to_playlist_track_count(track_count);
+            ui.set_send_to_playlist_labels(ModelRc::from(Rc::new(VecModel::from(labels))));
+            ui.set_send_to_playlist_checked(ModelRc::from(Rc::new(VecModel::from(checked))));
+            ui.set_send_to_playlist_recent_labels(ModelRc::from(Rc::new(VecModel::from(
+                recent_labels,
+            ))));
+            ui.set_show_new_playlist_from_selection_dialog(false);
+            ui.set_show_send_to_playlist_dialog(true);
+        });
+    }
+
+    fn open_new_playlist_from_selection_dialog(&mut self) {
+        // Coming from the picker keeps the tracks it was opened for.
+        if !self.send_to_playlist_paths.is_empty() {
+            let _ = self.ui.upgrade_in_event_loop(|ui| {
+                ui.set_show_send_to_playlist_dialog(false);
+                ui.set_new_playlist_from_selection_name("".into());
+                ui.set_show_new_playlist_from_selection_dialog(true);
+            });
+            return;
+        }
+        let paths = self.selected_playlist_track_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let track_count = paths.len().min(i32::MAX as usize) as i32;
+        self.send_to_playlist_paths = paths;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_send_to_playlist_track_count(track_count);
+            ui.set_new_playlist_from_selection_name("".into());
+            ui.set_show_new_playlist_from_selection_dialog(true);
+        });
+    }
+
+    fn close_send_to_playlist_dialog(&mut self) {
+        self.send_to_playlist_paths.clear();
+        let _ = self.ui.upgrade_in_event_loop(|ui| {
+            ui.set_show_send_to_playlist_dialog(false);
+            ui.set_show_new_playlist_from_selection_dialog(false);
+        });
+    }
+
+    fn send_tracks_to_playlists(&mut self, playlist_ids: Vec<String>) {
+        let paths = std::mem::take(&mut self.send_to_playlist_paths);
+        if paths.is_empty() || playlist_ids.is_empty() {
+            return;
+        }
+        let track_count = paths.len();
+        let target_text = match playlist_ids.as_slice() {
+            [id] => self
+                .playlist_ids
+                .iter()
+                .position(|known| known == id)
+                .and_then(|index| self.playlist_names.get(index))
+                .map(|name| format!("\"{name}\""))
+                .unwrap_or_else(|| "1 playlist".to_string()),
+            ids => format!("{} playlists", ids.len()),
+        };
+        for id in playlist_ids.iter().rev() {
+            self.recent_send_to_playlist_ids
+                .retain(|recent| recent != id);
+            self.recent_send_to_playlist_ids.insert(0, id.clone());
+        }
+        self.recent_send_to_playlist_ids
+            .truncate(RECENT_SEND_TO_PLAYLIST_LIMIT);
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::AddTracksToPlaylists {
+                playlist_ids,
+                paths,
+            },
+        ));
+        self.close_send_to_playlist_dialog();
+        self.show_library_toast(format!(
+            "Sent {track_count} track{} to {target_text}",
+            if track_count == 1 { "" } else { "s" }
+        ));
+    }
+
+    fn confirm_send_to_playlists(&mut self, checked: Vec<bool>) {
+        let playlist_ids: Vec<String> = self
+            .playlist_ids
+            .iter()
+            .zip(checked.iter())
+            .filter(|(_, checked)| **checked)
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.send_tracks_to_playlists(playlist_ids);
+    }
+
+    fn send_to_recent_playlist(&mut self, recent_index: usize) {
+        let Some((id, _)) = self
+            .recent_send_to_playlists()
+            .into_iter()
+            .nth(recent_index)
+        else {
+            return;
+        };
+        self.send_tracks_to_playlists(vec![id]);
+    }
+
+    fn confirm_new_playlist_from_selection(&mut self, name: String) {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
         }
-
-        let start_index = explicit_start_index.or(selected_start_index).unwrap_or(0);
-        Some(protocol::PlaybackQueueRequest {
-            source: protocol::PlaybackQueueSource::Library,
-            tracks,
-            start_index,
-        })
+        let paths = std::mem::take(&mut self.send_to_playlist_paths);
+        if paths.is_empty() {
+            return;
+        }
+        let track_count = paths.len();
+        self.show_library_toast(format!(
+            "Created \"{name}\" with {track_count} track{}",
+            if track_count == 1 { "" } else { "s" }
+        ));
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::CreatePlaylistWithTracks { name, paths },
+        ));
+        self.close_send_to_playlist_dialog();
     }
 
-    fn start_queue_if_possible(&self, request: Option<protocol::PlaybackQueueRequest>) {
-        let Some(request) = request else {
+    fn open_track_energy_dialog(&mut self) {
+        let targets: Vec<PathBuf> = self
+            .selected_indices
+            .iter()
+            .filter_map(|&index| self.track_paths.get(index).cloned())
+            .collect();
+        let Some(first_path) = targets.first() else {
             return;
         };
-        let _ = self.bus_sender.send(protocol::Message::Playback(
-            protocol::PlaybackMessage::StartQueue(request),
+        let setting = self.track_energy_by_path.get(first_path).copied();
+        let level_index = setting.map_or(0, |setting| i32::from(setting.energy.level()));
+        let suggested = setting.is_some_and(|setting| setting.suggested);
+        let target_count = targets.len().min(i32::MAX as usize) as i32;
+        self.track_energy_dialog_paths = targets;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_track_energy_target_count(target_count);
+            ui.set_track_energy_level_index(level_index);
+            ui.set_track_energy_suggested(suggested);
+            ui.set_show_track_energy_dialog(true);
+        });
+    }
+
+    fn close_track_energy_dialog(&mut self) {
+        self.track_energy_dialog_paths.clear();
+        let _ = self.ui.upgrade_in_event_loop(|ui| {
+            ui.set_show_track_energy_dialog(false);
+        });
+    }
+
+    fn save_track_energy_dialog(&mut self, level_index: i32) {
+        let paths = std::mem::take(&mut self.track_energy_dialog_paths);
+        if paths.is_empty() {
+            return;
+        }
+        let energy = u8::try_from(level_index)
+            .ok()
+            .and_then(protocol::TrackEnergy::from_level);
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SetTrackEnergy { paths, energy },
         ));
+        self.close_track_energy_dialog();
     }
 
-    fn play_active_collection(&self) {
-        if self.has_paused_playback_queue() {
-            let _ = self
-                .bus_sender
-                .send(protocol::Message::Playback(protocol::PlaybackMessage::Play));
+    fn suggest_track_energy_in_dialog(&mut self) {
+        let paths: Vec<PathBuf> = std::mem::take(&mut self.track_energy_dialog_paths)
+            .into_iter()
+            .filter(|path| !is_remote_track_path(path))
+            .collect();
+        self.close_track_energy_dialog();
+        if paths.is_empty() {
             return;
         }
-        let request = if self.collection_mode == COLLECTION_MODE_LIBRARY {
-            self.build_library_queue_request(None)
-        } else {
-            self.build_playlist_queue_request(None)
-        };
-        self.start_queue_if_possible(request);
+        let count = paths.len();
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::SuggestTrackEnergy { paths },
+        ));
+        self.show_library_toast(format!(
+            "Suggesting energy for {count} track{}...",
+            if count == 1 { "" } else { "s" }
+        ));
     }
 
-    fn activate_library_item(&mut self, index: usize) {
-        let Some(entry) = self.library_entries.get(index).cloned() else {
+    fn detect_track_trims_in_dialog(&mut self) {
+        let paths = std::mem::take(&mut self.track_transition_dialog_paths);
+        if paths.is_empty() {
             return;
+        }
+        let count = paths.len();
+        let _ = self.bus_sender.send(protocol::Message::Playlist(
+            protocol::PlaylistMessage::DetectTrackTrims { paths },
+        ));
+        self.close_track_transition_dialog();
+        self.show_library_toast(format!(
+            "Detecting intros/outros in {count} track{}...",
+            if count == 1 { "" } else { "s" }
+        ));
+    }
+
+    fn up_next_row(&self, track: &protocol::UpcomingTrack) -> UpNextRowData {
+        let (title, artist) = if let Some(metadata) = self
+            .track_ids
+            .iter()
+            .position(|id| id == &track.id)
+            .and_then(|index| self.track_metadata.get(index))
+        {
+            (metadata.title.clone(), metadata.artist.clone())
+        } else if let Some(library_track) =
+            Self::library_track_by_path(&self.library_entries, track.path.as_path())
+        {
+            (library_track.title.clone(), library_track.artist.clone())
+        } else {
+            let metadata = Self::fallback_track_metadata(track.path.as_path());
+            (metadata.title, metadata.artist)
         };
+        UpNextRowData {
+            title: title.into(),
+            artist: artist.into(),
+        }
+    }
 
-        match entry {
-            LibraryEntry::Track(_) => {
-                self.start_queue_if_possible(self.build_library_queue_request(Some(index)));
-            }
-            LibraryEntry::Artist(artist) => {
-                self.clear_search_bars_for_track_list_view_switch();
-                self.remember_current_library_scroll_position();
-                self.library_view_stack
-                    .push(LibraryViewState::ArtistDetail {
-                        artist: artist.artist,
-                    });
-                self.library_artist_prefetch_first_row = 0;
-                self.library_artist_prefetch_row_count = 0;
-                self.prepare_library_view_transition();
-                self.request_library_view_data();
-                self.sync_library_ui();
-            }
-            LibraryEntry::Album(album) => {
-                self.clear_search_bars_for_track_list_view_switch();
-                self.remember_current_library_scroll_position();
-                self.library_view_stack.push(LibraryViewState::AlbumDetail {
-                    album: album.album,
-                    album_artist: album.album_artist,
-                });
-                self.library_artist_prefetch_first_row = 0;
-                self.library_artist_prefetch_row_count = 0;
-                self.prepare_library_view_transition();
-                self.request_library_view_data();
-                self.sync_library_ui();
-            }
-            LibraryEntry::Genre(genre) => {
-                self.clear_search_bars_for_track_list_view_switch();
-                self.remember_current_library_scroll_position();
-                self.library_view_stack
-                    .push(LibraryViewState::GenreDetail { genre: genre.genre });
-                self.library_artist_prefetch_first_row = 0;
-                self.library_artist_prefetch_row_count = 0;
-                self.prepare_library_view_transition();
-                self.request_library_view_data();
-                self.sync_library_ui();
+    fn sync_up_next_ui(&self) {
+        let rows: Vec<UpNextRowData> = self
+            .upcoming_tracks
+            .iter()
+            .map(|track| self.up_next_row(track))
+            .collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_up_next_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
+    }
+
+    /// Tasks panel row for one background task.
+    fn task_row(task: &protocol::TaskSnapshot) -> TaskRowData {
+        let progress = match task.total {
+            Some(total) if total > 0 => task.completed as f32 / total as f32,
+            Some(_) => 1.0,
+            None => -1.0,
+        };
+        let percent = (progress >= 0.0).then(|| format!(" {}%", (progress * 100.0).round()));
+        let (status, detail) = match &task.state {
+            protocol::TaskState::Running => (
+                format!("Running{}", percent.unwrap_or_default()),
+                task.detail.clone(),
+            ),
+            protocol::TaskState::Paused => ("Paused".to_string(), task.detail.clone()),
+            protocol::TaskState::Cancelling => ("Cancelling…".to_string(), task.detail.clone()),
+            protocol::TaskState::Finished(protocol::TaskOutcome::Succeeded) => {
+                ("Done".to_string(), task.detail.clone())
             }
-            LibraryEntry::Decade(decade) => {
-                self.clear_search_bars_for_track_list_view_switch();
-                self.remember_current_library_scroll_position();
-                self.library_view_stack
-                    .push(LibraryViewState::DecadeDetail {
-                        decade: decade.decade,
-                    });
-                self.library_artist_prefetch_first_row = 0;
-                self.library_artist_prefetch_row_count = 0;
-                self.prepare_library_view_transition();
-                self.request_library_view_data();
-                self.sync_library_ui();
+            protocol::TaskState::Finished(protocol::TaskOutcome::Cancelled) => {
+                ("Cancelled".to_string(), task.detail.clone())
             }
-            LibraryEntry::FavoriteCategory(category) => {
-                self.clear_search_bars_for_track_list_view_switch();
-                self.remember_current_library_scroll_position();
-                let next_view = match category.kind {
-                    protocol::FavoriteEntityKind::Track => LibraryViewState::FavoriteTracks,
-                    protocol::FavoriteEntityKind::Artist => LibraryViewState::FavoriteArtists,
-                    protocol::FavoriteEntityKind::Album => LibraryViewState::FavoriteAlbums,
-                };
-                self.library_view_stack.push(next_view);
-                self.prepare_library_view_transition();
-                self.request_library_view_data();
-                self.sync_library_ui();
+            protocol::TaskState::Finished(protocol::TaskOutcome::Failed(error)) => {
+                ("Failed".to_string(), error.clone())
             }
+        };
+        TaskRowData {
+            task_id: task.task_id as i32,
+            kind: task_manager::task_kind_label(task.kind).into(),
+            label: task.label.as_str().into(),
+            detail: detail.into(),
+            status: status.into(),
+            progress,
+            active: !matches!(task.state, protocol::TaskState::Finished(_)),
+            paused: task.state == protocol::TaskState::Paused,
+            failed: matches!(
+                task.state,
+                protocol::TaskState::Finished(protocol::TaskOutcome::Failed(_))
+            ),
+            can_pause: task.can_pause,
+            can_cancel: task.can_cancel,
         }
     }
 
-    fn toggle_favorite_for_library_row(&self, view_row: usize) {
-        let Some(source_index) = self.map_library_view_to_source_index(view_row) else {
-            return;
-        };
-        let Some(entry) = self.library_entries.get(source_index) else {
-            return;
-        };
-        let Some(entity) = self.favorite_entity_for_library_entry(entry) else {
-            return;
-        };
-        let _ = self.bus_sender.send(protocol::Message::Library(
-            protocol::LibraryMessage::ToggleFavorite {
-                entity,
-                desired: None,
-            },
-        ));
+    fn sync_tasks_ui(&self, tasks: &[protocol::TaskSnapshot]) {
+        let rows: Vec<TaskRowData> = tasks.iter().map(Self::task_row).collect();
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_task_rows(ModelRc::from(Rc::new(VecModel::from(rows))));
+        });
     }
 
-    fn toggle_favorite_for_playlist_row(&self, view_row: usize) {
-        let Some(source_index) = self.map_view_to_source_index(view_row) else {
-            return;
-        };
-        let Some(entity) = self.favorite_entity_for_playlist_source_index(source_index) else {
-            return;
+    /// Clears Lyrics panels and asks for the lyrics of the track that started playing.
+    fn request_playing_track_lyrics(&mut self) {
+        self.playing_lyrics = None;
+        self.lyrics_active_line = None;
+        let status = match self.playing_track.path.clone() {
+            Some(path) => {
+                let (artist, title, album) = self.playing_track.metadata.as_ref().map_or_else(
+                    Default::default,
+                    |metadata| {
+                        (
+                            metadata.artist.clone(),
+                            metadata.title.clone(),
+                            metadata.album.clone(),
+                        )
+                    },
+                );
+                let duration_ms = self
+                    .track_formats_by_path
+                    .get(&path)
+                    .map(|format| format.duration_ms)
+                    .filter(|duration_ms| *duration_ms > 0);
+                let _ = self.bus_sender.send(protocol::Message::Lyrics(
+                    protocol::LyricsMessage::RequestLyrics {
+                        path,
+                        artist,
+                        title,
+                        album,
+                        duration_ms,
+                    },
+                ));
+                "Loading lyrics…"
+            }
+            None => "",
         };
-        let _ = self.bus_sender.send(protocol::Message::Library(
-            protocol::LibraryMessage::ToggleFavorite {
-                entity,
-                desired: None,
-            },
-        ));
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_lyrics_lines(ModelRc::from(Rc::new(VecModel::from(Vec::<
+                slint::SharedString,
+            >::new()))));
+            ui.set_lyrics_active_line(-1);
+            ui.set_lyrics_synced(false);
+            ui.set_lyrics_status(status.into());
+        });
     }
 
-    fn toggle_favorite_now_playing(&self) {
-        let Some(entity) = self.current_track_favorite_entity() else {
+    fn apply_loaded_lyrics(&mut self, path: PathBuf, lyrics: Option<protocol::TrackLyrics>) {
+        if self.playing_track.path.as_ref() != Some(&path) {
             return;
-        };
-        let _ = self.bus_sender.send(protocol::Message::Library(
-            protocol::LibraryMessage::ToggleFavorite {
-                entity,
-                desired: None,
-            },
-        ));
+        }
+        let lines: Vec<slint::SharedString> = lyrics
+            .iter()
+            .flat_map(|lyrics| lyrics.lines.iter())
+            .map(|line| line.text.as_str().into())
+            .collect();
+        let synced = lyrics.as_ref().is_some_and(|lyrics| lyrics.synced);
+        self.playing_lyrics = lyrics;
+        self.lyrics_active_line = None;
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_lyrics_lines(ModelRc::from(Rc::new(VecModel::from(lines))));
+            ui.set_lyrics_active_line(-1);
+            ui.set_lyrics_synced(synced);
+            ui.set_lyrics_status("No lyrics found".into());
+        });
     }
 
-    fn apply_favorites_snapshot(&mut self, items: Vec<protocol::FavoriteEntityRef>) {
-        self.favorites_by_key = items
-            .into_iter()
-            .map(|item| (item.entity_key.clone(), item))
-            .collect();
-        self.rebuild_track_model();
-        self.sync_library_ui();
-        self.sync_now_playing_favorite_state_to_ui();
+    /// Highlights the synced lyrics line sung at `elapsed_ms`.
+    fn sync_lyrics_active_line(&mut self, elapsed_ms: u64) {
+        let Some(lyrics) = self.playing_lyrics.as_ref().filter(|lyrics| lyrics.synced) else {
+            return;
+        };
+        let active_line = lyrics::active_line_index(&lyrics.lines, elapsed_ms);
+        if active_line == self.lyrics_active_line {
+            return;
+        }
+        self.lyrics_active_line = active_line;
+        let active_line = active_line.map_or(-1, |index| index as i32);
+        let _ = self.ui.upgrade_in_event_loop(move |ui| {
+            ui.set_lyrics_active_line(active_line);
+        });
     }
 
     fn fallback_track_metadata(path: &Path) -> TrackMetadata {
         if is_remote_track_path(path) {
+            let title = if is_web_track_path(path) {
+                web_track_fallback_title(path)
+            } else {
+                "Remote track".to_string()
+            };
             return TrackMetadata {
-                title: "Remote track".to_string(),
+                title,
                 artist: "".to_string(),
                 album: "".to_string(),
                 album_artist: "".to_string(),
@@ -10654,6 +14801,10 @@ impl UiManager {
                 year: "".to_string(),
                 genre: "".to_string(),
                 track_number: "".to_string(),
+                composer: "".to_string(),
+                disc_number: "".to_string(),
+                total_tracks: "".to_string(),
+                comment: "".to_string(),
             };
         }
         let filename = path
@@ -10671,6 +14822,10 @@ impl UiManager {
             year: "".to_string(),
             genre: "".to_string(),
             track_number: "".to_string(),
+            composer: "".to_string(),
+            disc_number: "".to_string(),
+            total_tracks: "".to_string(),
+            comment: "".to_string(),
         }
     }
 
@@ -10686,6 +14841,10 @@ impl UiManager {
                     genre: parsed.genre,
                     year: parsed.year,
                     track_number: parsed.track_number,
+                    composer: parsed.composer,
+                    disc_number: parsed.disc_number,
+                    total_tracks: parsed.total_tracks,
+                    comment: parsed.comment,
                 };
             }
         }
@@ -10700,6 +14859,10 @@ impl UiManager {
             genre: fallback.genre,
             year: fallback.year,
             track_number: fallback.track_number,
+            composer: fallback.composer,
+            disc_number: fallback.disc_number,
+            total_tracks: fallback.total_tracks,
+            comment: fallback.comment,
         }
     }
 
@@ -10713,6 +14876,10 @@ impl UiManager {
             year: summary.year.clone(),
             genre: summary.genre.clone(),
             track_number: summary.track_number.clone(),
+            composer: summary.composer.clone(),
+            disc_number: summary.disc_number.clone(),
+            total_tracks: summary.total_tracks.clone(),
+            comment: summary.comment.clone(),
         }
     }
 
@@ -10752,7 +14919,12 @@ impl UiManager {
             };
             if update.summary.title == REMOTE_TRACK_UNAVAILABLE_TITLE {
                 self.unavailable_track_ids.insert(update.track_id.clone());
-            } else {
+            } else if self
+                .track_paths
+                .get(index)
+                .is_some_and(|path| is_remote_track_path(path))
+            {
+                // Missing local files stay marked until a file check or relocation clears them.
                 self.unavailable_track_ids.remove(&update.track_id);
             }
             let next = Self::track_metadata_from_summary(&update.summary);
@@ -10765,6 +14937,10 @@ impl UiManager {
                     || current.year != next.year
                     || current.genre != next.genre
                     || current.track_number != next.track_number
+                    || current.composer != next.composer
+                    || current.disc_number != next.disc_number
+                    || current.total_tracks != next.total_tracks
+                    || current.comment != next.comment
                 {
                     *current = next;
                     changed = true;
@@ -10794,6 +14970,7 @@ impl UiManager {
             self.sync_library_selection_to_ui();
             self.sync_library_add_to_playlist_ui();
             self.sync_properties_action_state();
+            self.sync_notes_panel_ui(false);
         } else {
             // Playlist: select all visible tracks (view-order source indices).
             let all_source_indices: Vec<usize> = if self.view_indices.is_empty() {
@@ -10944,6 +15121,7 @@ impl UiManager {
         self.sync_library_selection_to_ui();
         self.sync_library_add_to_playlist_ui();
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
 
         // Scroll to ensure the navigated row is visible.
         self.library_scroll_center_token = self.library_scroll_center_token.wrapping_add(1);
@@ -11104,6 +15282,7 @@ impl UiManager {
         self.sync_library_selection_to_ui();
         self.sync_library_add_to_playlist_ui();
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
 
         self.library_scroll_center_token = self.library_scroll_center_token.wrapping_add(1);
         let token = self.library_scroll_center_token;
@@ -11327,6 +15506,9 @@ impl UiManager {
             if let Some(value) = library.image_memory_cache_ttl_secs {
                 self.image_memory_cache_ttl_secs = value.max(1);
             }
+            if let Some(value) = library.slow_storage_mode {
+                SLOW_STORAGE_MODE.store(value, Ordering::Relaxed);
+            }
 
             list_image_max_edge_changed =
                 previous_list_image_max_edge_px != self.list_image_max_edge_px;
@@ -11424,6 +15606,8 @@ impl UiManager {
         let mut album_art_column_width_limits_changed = false;
         let mut layout_changed = false;
         let mut window_size_patch_received = false;
+        let mut hi_res_thresholds_changed = false;
+        let mut row_numbering_changed = false;
         if let Some(ui_config) = ui_update {
             let has_playlist_columns_patch = ui_config.playlist_columns.is_some();
             let has_layout_patch = ui_config.layout.is_some();
@@ -11437,6 +15621,21 @@ impl UiManager {
             if let Some(auto_scroll_to_playing_track) = ui_config.auto_scroll_to_playing_track {
                 self.auto_scroll_to_playing_track = auto_scroll_to_playing_track;
             }
+            let previous_hi_res_thresholds = self.hi_res_thresholds;
+            if let Some(min_sample_rate_hz) = ui_config.hi_res_min_sample_rate_hz {
+                self.hi_res_thresholds.min_sample_rate_hz = min_sample_rate_hz;
+            }
+            if let Some(min_bit_depth) = ui_config.hi_res_min_bit_depth {
+                self.hi_res_thresholds.min_bit_depth = min_bit_depth;
+            }
+            hi_res_thresholds_changed = previous_hi_res_thresholds != self.hi_res_thresholds;
+            if let Some(zero_based_row_numbers) = ui_config.zero_based_row_numbers {
+                row_numbering_changed = self.zero_based_row_numbers != zero_based_row_numbers;
+                self.zero_based_row_numbers = zero_based_row_numbers;
+            }
+            if let Some(external_tagger_command) = ui_config.external_tagger_command {
+                self.external_tagger_command = external_tagger_command;
+            }
             if let Some(playlist_columns) = ui_config.playlist_columns {
                 self.playlist_columns = playlist_columns;
             }
@@ -11457,6 +15656,7 @@ impl UiManager {
                 .collect();
             if let Some(layout) = ui_config.layout {
                 self.apply_layout_column_width_overrides(&layout.playlist_column_width_overrides);
+                self.apply_layout_column_styles(&layout.playlist_column_styles);
             }
             layout_changed = has_layout_patch;
             self.playlist_column_target_widths_px
@@ -11476,6 +15676,11 @@ impl UiManager {
                     .map(|column| column.name.as_str().into())
                     .collect();
                 let visible_kinds = Self::visible_playlist_column_kinds(&playlist_columns);
+                let visible_font_weights: Vec<i32> = self
+                    .visible_playlist_column_styles()
+                    .into_iter()
+                    .map(|style| column_format::font_weight_value(style.font_weight))
+                    .collect();
                 let menu_labels: Vec<slint::SharedString> = playlist_columns
                     .iter()
                     .map(|column| column.name.as_str().into())
@@ -11495,6 +15700,9 @@ impl UiManager {
                     ui.set_playlist_visible_column_kinds(ModelRc::from(Rc::new(VecModel::from(
                         visible_kinds,
                     ))));
+                    ui.set_playlist_visible_column_font_weights(ModelRc::from(Rc::new(
+                        VecModel::from(visible_font_weights),
+                    )));
                     ui.set_playlist_column_menu_labels(ModelRc::from(Rc::new(VecModel::from(
                         menu_labels,
                     ))));
@@ -11519,9 +15727,13 @@ impl UiManager {
         let refresh_library_ui = list_image_max_edge_changed
             || online_metadata_enabled_changed
             || online_metadata_prompt_changed;
-        let rebuild_playlist_rows = list_image_max_edge_changed || playlist_columns_changed;
+        let rebuild_playlist_rows = list_image_max_edge_changed
+            || playlist_columns_changed
+            || hi_res_thresholds_changed
+            || row_numbering_changed;
         let refresh_display_target = refresh_library_ui
             || playlist_columns_changed
+            || hi_res_thresholds_changed
             || album_art_column_width_limits_changed
             || layout_changed
             || window_size_patch_received;
@@ -11545,14 +15757,21 @@ impl UiManager {
         self.sync_library_add_to_playlist_ui();
         self.sync_library_root_counts_to_ui();
         self.sync_now_playing_favorite_state_to_ui();
-        self.request_library_root_counts();
+        // Library data (including root counts) loads lazily when the library view first opens.
         let _ = self.bus_sender.send(protocol::Message::Library(
             protocol::LibraryMessage::RequestFavoritesSnapshot,
         ));
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::RequestUserTagsSnapshot,
+        ));
+        let _ = self.bus_sender.send(protocol::Message::Library(
+            protocol::LibraryMessage::RequestTrackNotesSnapshot,
+        ));
         let _ = self.bus_sender.send(protocol::Message::Playlist(
             protocol::PlaylistMessage::RequestPlaylistState,
         ));
         self.sync_properties_action_state();
+        self.sync_notes_panel_ui(false);
         self.sync_properties_dialog_ui();
         loop {
             self.drain_scan_progress_queue();
@@ -11618,6 +15837,22 @@ impl UiManager {
                             protocol::LibraryMessage::CancelAddToPlaylists => {
                                 self.cancel_library_add_to_playlists();
                             }
+                            protocol::LibraryMessage::QueueSelected(action) => {
+                                self.queue_library_selection(action);
+                            }
+                            protocol::LibraryMessage::ToggleSelectedTrackSource => {
+                                self.toggle_selected_library_track_source();
+                            }
+                            protocol::LibraryMessage::RemoteCopiesSnapshot(copies) => {
+                                self.apply_remote_copies_snapshot(copies);
+                            }
+                            protocol::LibraryMessage::TimedMixSelected {
+                                target_minutes,
+                                destination,
+                                flow,
+                            } => {
+                                self.generate_library_timed_mix(target_minutes, destination, flow);
+                            }
                             protocol::LibraryMessage::OpenSearch => {
                                 self.open_library_search();
                             }
@@ -11627,6 +15862,19 @@ impl UiManager {
                             protocol::LibraryMessage::SetSearchQuery(query) => {
                                 self.set_library_search_query(query);
                             }
+                            protocol::LibraryMessage::SetFacetPanelVisible(visible) => {
+                                self.set_library_facet_panel_visible(visible);
+                            }
+                            protocol::LibraryMessage::ToggleFacet { kind, value } => {
+                                self.toggle_library_facet(kind, &value);
+                            }
+                            protocol::LibraryMessage::ClearFacets => {
+                                self.clear_library_facets();
+                            }
+                            protocol::LibraryMessage::FacetCountsResult(counts) => {
+                                self.library_facet_counts = counts;
+                                self.sync_library_facets_to_ui();
+                            }
                             protocol::LibraryMessage::CopySelected => {
                                 self.copy_selected_library_items();
                             }
@@ -11639,6 +15887,12 @@ impl UiManager {
                             protocol::LibraryMessage::OpenFileLocation => {
                                 self.open_file_location();
                             }
+                            protocol::LibraryMessage::CopyFilePath => {
+                                self.copy_file_paths();
+                            }
+                            protocol::LibraryMessage::OpenInExternalTagger => {
+                                self.open_in_external_tagger();
+                            }
                             protocol::LibraryMessage::EvaluateRemoveSelection { .. } => {}
                             protocol::LibraryMessage::ConfirmRemoveSelection => {
                                 self.confirm_library_remove_selection();
@@ -11646,6 +15900,48 @@ impl UiManager {
                             protocol::LibraryMessage::CancelRemoveSelection => {
                                 self.cancel_library_remove_selection();
                             }
+                            protocol::LibraryMessage::OpenOrganizeFiles => {
+                                self.open_organize_files_dialog();
+                            }
+                            protocol::LibraryMessage::CloseOrganizeFiles => {
+                                self.close_organize_files_dialog();
+                            }
+                            protocol::LibraryMessage::RequestOrganizePreview { template } => {
+                                self.request_organize_preview(template);
+                            }
+                            protocol::LibraryMessage::OpenAlbumArtistFixes => {
+                                self.open_album_artist_fixes_dialog();
+                            }
+                            protocol::LibraryMessage::CloseAlbumArtistFixes => {
+                                self.close_album_artist_fixes_dialog();
+                            }
+                            protocol::LibraryMessage::ConfirmAlbumArtistFixes => {
+                                self.confirm_album_artist_fixes();
+                            }
+                            protocol::LibraryMessage::ConfirmOrganizeFiles => {
+                                self.confirm_organize_files();
+                            }
+                            protocol::LibraryMessage::SaveNotesPanelTrackNote(note) => {
+                                self.save_notes_panel_track_note(note);
+                            }
+                            protocol::LibraryMessage::OpenTrackTags => {
+                                self.open_track_tags_dialog();
+                            }
+                            protocol::LibraryMessage::CloseTrackTags => {
+                                self.close_track_tags_dialog();
+                            }
+                            protocol::LibraryMessage::ToggleTrackTagInDialog(index) => {
+                                self.toggle_track_tag_in_dialog(index);
+                            }
+                            protocol::LibraryMessage::CreateTrackTagInDialog { name } => {
+                                self.create_track_tag_in_dialog(name);
+                            }
+                            protocol::LibraryMessage::CycleTrackTagColorInDialog(index) => {
+                                self.cycle_track_tag_color_in_dialog(index);
+                            }
+                            protocol::LibraryMessage::DeleteTrackTagInDialog(index) => {
+                                self.delete_track_tag_in_dialog(index);
+                            }
                             protocol::LibraryMessage::LibraryViewportChanged {
                                 first_row,
                                 row_count,
@@ -11676,12 +15972,15 @@ impl UiManager {
                             }
                             protocol::LibraryMessage::EnrichmentPrefetchTick => {
                                 self.on_enrichment_prefetch_tick();
+                                self.refresh_worker_pool_diagnostics();
+                                self.refresh_startup_timing_diagnostics();
                             }
                             protocol::LibraryMessage::ScanStarted
                             | protocol::LibraryMessage::ScanProgress { .. }
                             | protocol::LibraryMessage::ScanCompleted { .. }
                             | protocol::LibraryMessage::MetadataBackfillProgress { .. }
-                            | protocol::LibraryMessage::ScanFailed(_) => {
+                            | protocol::LibraryMessage::ScanFailed(_)
+                            | protocol::LibraryMessage::ScanFolderAccessWarnings(_) => {
                                 self.handle_scan_status_message(library_message);
                             }
                             protocol::LibraryMessage::TracksResult(tracks) => {
@@ -11745,6 +16044,9 @@ impl UiManager {
                                 self.library_root_counts =
                                     [tracks, artists, albums, genres, decades, favorites];
                                 self.sync_library_root_counts_to_ui();
+                                if self.library_facet_panel_visible {
+                                    self.request_library_facet_counts();
+                                }
                             }
                             protocol::LibraryMessage::GlobalSearchDataResult {
                                 tracks,
@@ -11908,8 +16210,39 @@ impl UiManager {
                                 self.show_library_toast(toast_text);
                                 self.sync_library_ui();
                             }
-                            protocol::LibraryMessage::FavoritesSnapshot { items } => {
-                                self.apply_favorites_snapshot(items);
+                            protocol::LibraryMessage::FavoritesSnapshot { items } => {
+                                self.apply_favorites_snapshot(items);
+                            }
+                            protocol::LibraryMessage::UserTagsSnapshot { tags, assignments } => {
+                                self.apply_user_tags_snapshot(tags, assignments);
+                            }
+                            protocol::LibraryMessage::TrackNotesSnapshot { notes } => {
+                                self.apply_track_notes_snapshot(notes);
+                            }
+                            protocol::LibraryMessage::MaintenanceHistorySnapshot(runs) => {
+                                self.apply_maintenance_history_snapshot(runs);
+                            }
+                            protocol::LibraryMessage::IntegrityReportSnapshot(issues) => {
+                                self.apply_integrity_report_snapshot(issues);
+                            }
+                            protocol::LibraryMessage::ActivityLogSnapshot(entries) => {
+                                self.apply_activity_log_snapshot(entries);
+                            }
+                            protocol::LibraryMessage::CacheUsageSnapshot(usage) => {
+                                self.apply_cache_usage_snapshot(&usage);
+                            }
+                            protocol::LibraryMessage::CacheCleared {
+                                kind,
+                                deleted_files,
+                            } => {
+                                if kind == protocol::CacheKind::CoverArt {
+                                    self.forget_cached_cover_art();
+                                }
+                                self.show_library_toast(format!(
+                                    "Cleared {} cache ({} files)",
+                                    cache_manager::cache_label(kind).to_lowercase(),
+                                    deleted_files
+                                ));
                             }
                             protocol::LibraryMessage::FavoriteStateChanged {
                                 entity,
@@ -11943,6 +16276,55 @@ impl UiManager {
                                     self.show_library_toast(toast_text);
                                 }
                             }
+                            protocol::LibraryMessage::SelectionQueued {
+                                action,
+                                track_count,
+                            } => {
+                                let toast_text = match action {
+                                    protocol::LibraryQueueAction::PlayNow => {
+                                        format!("Playing {} track(s)", track_count)
+                                    }
+                                    protocol::LibraryQueueAction::PlayNext => {
+                                        format!("{} track(s) will play next", track_count)
+                                    }
+                                    protocol::LibraryQueueAction::AddToQueue => {
+                                        format!("Added {} track(s) to the queue", track_count)
+                                    }
+                                };
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
+                            protocol::LibraryMessage::TimedMixGenerated {
+                                destination,
+                                track_count,
+                                total_ms,
+                            } => {
+                                let total_seconds = total_ms / 1_000;
+                                let length_text =
+                                    format!("{}:{:02}", total_seconds / 60, total_seconds % 60);
+                                let toast_text = match destination {
+                                    protocol::TimedMixDestination::Queue => format!(
+                                        "Playing a {} mix of {} track(s)",
+                                        length_text, track_count
+                                    ),
+                                    protocol::TimedMixDestination::Playlist => format!(
+                                        "Saved a {} mix of {} track(s) as a new playlist",
+                                        length_text, track_count
+                                    ),
+                                };
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
+                            protocol::LibraryMessage::TimedMixFailed(error_text) => {
+                                let toast_text = format!("Failed to build mix: {}", error_text);
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
+                            protocol::LibraryMessage::QueueSelectionFailed(error_text) => {
+                                let toast_text = format!("Failed to queue tracks: {}", error_text);
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
                             protocol::LibraryMessage::AddToPlaylistsFailed(error_text) => {
                                 let toast_text = if self.pending_paste_feedback {
                                     self.pending_paste_feedback = false;
@@ -11995,6 +16377,53 @@ impl UiManager {
                                 self.library_status_text = toast_text.clone();
                                 self.show_library_toast(toast_text);
                             }
+                            protocol::LibraryMessage::OrganizeFilesPlanned {
+                                moves,
+                                unchanged,
+                                skipped,
+                            } => {
+                                self.show_organize_plan(moves, unchanged, skipped);
+                            }
+                            protocol::LibraryMessage::OrganizeFilesApplied { moves, undone } => {
+                                self.organize_undo_available = !undone;
+                                self.pending_organize_moves.clear();
+                                let toast_text = if undone {
+                                    format!("Moved {} file(s) back", moves.len())
+                                } else {
+                                    format!("Organized {} file(s)", moves.len())
+                                };
+                                self.set_organize_dialog_state(Vec::new(), toast_text.clone());
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                                self.library_cover_art_paths.clear();
+                                self.folder_cover_art_paths.clear();
+                                self.request_library_view_data();
+                                self.request_library_root_counts();
+                            }
+                            protocol::LibraryMessage::TrackPathsRelocated { moves, unresolved } => {
+                                self.handle_track_paths_relocated(&moves, unresolved);
+                            }
+                            protocol::LibraryMessage::TrackRelocationFailed(error_text) => {
+                                let _ = self.ui.upgrade_in_event_loop(move |ui| {
+                                    ui.set_relocation_status_text(error_text.into());
+                                });
+                            }
+                            protocol::LibraryMessage::OrganizeFilesFailed(error_text) => {
+                                let toast_text = format!("Organize failed: {}", error_text);
+                                self.set_organize_dialog_state(Vec::new(), toast_text.clone());
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
+                            protocol::LibraryMessage::AlbumArtistFixesPlanned(suggestions) => {
+                                self.show_album_artist_suggestions(suggestions);
+                            }
+                            protocol::LibraryMessage::AlbumArtistFixesFailed(error_text) => {
+                                let toast_text =
+                                    format!("Album artist check failed: {}", error_text);
+                                self.set_album_artist_dialog_state(Vec::new(), toast_text.clone());
+                                self.library_status_text = toast_text.clone();
+                                self.show_library_toast(toast_text);
+                            }
                             protocol::LibraryMessage::ToastTimeout { generation } => {
                                 if generation == self.library_toast_generation {
                                     self.hide_library_toast();
@@ -12005,6 +16434,7 @@ impl UiManager {
                             }
                             protocol::LibraryMessage::RequestScan
                             | protocol::LibraryMessage::RequestRootCounts
+                            | protocol::LibraryMessage::RequestFacetCounts(_)
                             | protocol::LibraryMessage::RequestTracks
                             | protocol::LibraryMessage::RequestArtists
                             | protocol::LibraryMessage::RequestAlbums
@@ -12024,8 +16454,36 @@ impl UiManager {
                             | protocol::LibraryMessage::ClearEnrichmentCache
                             | protocol::LibraryMessage::AddSelectionToPlaylists { .. }
                             | protocol::LibraryMessage::PasteSelectionToActivePlaylist { .. }
-                            | protocol::LibraryMessage::RemoveSelectionFromLibrary { .. } => {}
+                            | protocol::LibraryMessage::QueueSelection { .. }
+                            | protocol::LibraryMessage::GenerateTimedMix { .. }
+                            | protocol::LibraryMessage::RemoveSelectionFromLibrary { .. }
+                            | protocol::LibraryMessage::PlanOrganizeFiles { .. }
+                            | protocol::LibraryMessage::PlanAlbumArtistFixes { .. }
+                            | protocol::LibraryMessage::ApplyOrganizeFiles { .. }
+                            | protocol::LibraryMessage::RunMaintenanceTask(_)
+                            | protocol::LibraryMessage::RequestMaintenanceHistory
+                            | protocol::LibraryMessage::RequestIntegrityReport
+                            | protocol::LibraryMessage::RecordActivity(_)
+                            | protocol::LibraryMessage::RequestActivityLog
+                            | protocol::LibraryMessage::RequestCacheUsage
+                            | protocol::LibraryMessage::ClearCache(_)
+                            | protocol::LibraryMessage::UndoOrganizeFiles
+                            | protocol::LibraryMessage::RequestUserTagsSnapshot
+                            | protocol::LibraryMessage::UpsertUserTag { .. }
+                            | protocol::LibraryMessage::DeleteUserTag { .. }
+                            | protocol::LibraryMessage::SetTrackUserTag { .. }
+                            | protocol::LibraryMessage::RequestTrackNotesSnapshot
+                            | protocol::LibraryMessage::SetTrackNote { .. } => {}
                         },
+                        protocol::Message::Lyrics(protocol::LyricsMessage::LyricsLoaded {
+                            path,
+                            lyrics,
+                        }) => {
+                            self.apply_loaded_lyrics(path, lyrics);
+                        }
+                        protocol::Message::Task(protocol::TaskMessage::TasksChanged(tasks)) => {
+                            self.sync_tasks_ui(&tasks);
+                        }
                         protocol::Message::Metadata(metadata_message) => match metadata_message {
                             protocol::MetadataMessage::OpenPropertiesForCurrentSelection => {
                                 self.open_properties_for_current_selection();
@@ -12039,6 +16497,28 @@ impl UiManager {
                             protocol::MetadataMessage::CancelProperties => {
                                 self.cancel_properties();
                             }
+                            protocol::MetadataMessage::LookupPropertiesTags => {
+                                self.lookup_properties_tags();
+                            }
+                            protocol::MetadataMessage::RenderPropertiesSpectrogram => {
+                                self.render_properties_spectrogram();
+                            }
+                            protocol::MetadataMessage::PropertiesSpectrogramRendered {
+                                request_id,
+                                path,
+                                result,
+                            } => {
+                                self.handle_properties_spectrogram_rendered(
+                                    request_id, path, result,
+                                );
+                            }
+                            protocol::MetadataMessage::TrackTagLookupCompleted {
+                                request_id,
+                                path,
+                                result,
+                            } => {
+                                self.handle_tag_lookup_completed(request_id, path, result);
+                            }
                             protocol::MetadataMessage::TrackPropertiesLoaded {
                                 request_id,
                                 path,
@@ -12079,8 +16559,51 @@ impl UiManager {
                             } => {
                                 self.handle_properties_save_failed(request_id, path, error);
                             }
+                            protocol::MetadataMessage::SetPropertiesFieldApply { index, apply } => {
+                                self.set_properties_field_apply(index, apply);
+                            }
+                            protocol::MetadataMessage::TrackPropertiesBatchLoaded {
+                                request_id,
+                                fields,
+                                mixed,
+                            } => {
+                                self.handle_batch_properties_loaded(request_id, fields, mixed);
+                            }
+                            protocol::MetadataMessage::TrackPropertiesBatchLoadFailed {
+                                request_id,
+                                error,
+                            } => {
+                                self.handle_batch_properties_load_failed(request_id, error);
+                            }
+                            protocol::MetadataMessage::TrackPropertiesBatchSaved {
+                                request_id,
+                                saved,
+                                db_sync_warning,
+                                error,
+                            } => {
+                                if !self.handle_album_artist_fix_saved(
+                                    request_id,
+                                    &saved,
+                                    db_sync_warning.clone(),
+                                    error.clone(),
+                                ) {
+                                    self.handle_batch_properties_saved(
+                                        request_id,
+                                        saved,
+                                        db_sync_warning,
+                                        error,
+                                    );
+                                }
+                            }
                             protocol::MetadataMessage::RequestTrackProperties { .. }
-                            | protocol::MetadataMessage::SaveTrackProperties { .. } => {}
+                            | protocol::MetadataMessage::SaveTrackProperties { .. }
+                            | protocol::MetadataMessage::RequestTrackPropertiesBatch { .. }
+                            | protocol::MetadataMessage::SaveTrackPropertiesBatch { .. }
+                            | protocol::MetadataMessage::LookupTrackTags { .. }
+                            | protocol::MetadataMessage::RefreshChangedTags(_) => {}
+                            protocol::MetadataMessage::ChangedTagsRefreshed(refreshed) => {
+                                self.apply_saved_properties(&refreshed, None);
+                            }
                         },
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::OpenSubsonicSyncEligiblePlaylists(
@@ -12104,13 +16627,112 @@ impl UiManager {
                             });
                         }
                         protocol::Message::Playlist(
-                            protocol::PlaylistMessage::PlaylistsRestored(playlists),
+                            protocol::PlaylistMessage::PlaylistRestructureFinished(message),
+                        ) => {
+                            self.show_library_toast(message);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::XspfTransferFinished(message),
+                        ) => {
+                            self.show_library_toast(message);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::ExportPlaylistDocument {
+                                index,
+                                path,
+                                format,
+                                columns,
+                                include_covers,
+                            },
+                        ) => {
+                            let message = self
+                                .export_playlist_document(
+                                    index,
+                                    &path,
+                                    format,
+                                    &columns,
+                                    include_covers,
+                                )
+                                .unwrap_or_else(|error| format!("Export failed: {error}"));
+                            self.show_library_toast(message);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistFilesVerified {
+                                playlist_id,
+                                checked_ids,
+                                missing,
+                            },
+                        ) => {
+                            self.handle_playlist_files_verified(&playlist_id, checked_ids, missing);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::RelocateMissingFiles { new_base },
+                        ) => {
+                            self.request_missing_file_relocation(new_base);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::RemotePlaylistSyncStatusChanged(statuses),
                         ) => {
+                            self.remote_playlist_sync_statuses = statuses
+                                .into_iter()
+                                .map(|status| (status.playlist_id.clone(), status))
+                                .collect();
+                            self.sync_playlist_remote_sync_ui();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistsRestored { playlists, folders },
+                        ) => {
+                            self.sidebar_playlists = playlists.clone();
+                            self.sidebar_playlist_folders = folders;
+                            self.sync_playlist_tree_ui();
                             let old_playlist_ids = self.playlist_ids.clone();
                             let old_len = self.playlist_ids.len();
                             self.playlist_ids = playlists.iter().map(|p| p.id.clone()).collect();
                             self.playlist_names =
                                 playlists.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+                            self.playlist_notes_by_id = playlists
+                                .iter()
+                                .filter(|p| !p.note.is_empty())
+                                .map(|p| (p.id.clone(), p.note.clone()))
+                                .collect();
+                            self.playlist_column_filters_by_id = playlists
+                                .iter()
+                                .filter(|p| !p.column_filters.is_empty())
+                                .map(|p| (p.id.clone(), p.column_filters.clone()))
+                                .collect();
+                            self.skip_silence_playlist_ids = playlists
+                                .iter()
+                                .filter(|p| p.skip_silence)
+                                .map(|p| p.id.clone())
+                                .collect();
+                            self.normalize_loudness_playlist_ids = playlists
+                                .iter()
+                                .filter(|p| p.normalize_loudness)
+                                .map(|p| p.id.clone())
+                                .collect();
+                            self.playlist_descriptions_by_id = playlists
+                                .iter()
+                                .filter(|p| !p.description.is_empty())
+                                .map(|p| (p.id.clone(), p.description.clone()))
+                                .collect();
+                            self.playlist_cover_paths_by_id = playlists
+                                .iter()
+                                .filter_map(|p| Some((p.id.clone(), p.cover_path.clone()?)))
+                                .collect();
+                            let unrequested_collage_ids = self
+                                .playlist_ids
+                                .iter()
+                                .filter(|id| !self.playlist_collage_requested_ids.contains(*id))
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            for playlist_id in unrequested_collage_ids {
+                                self.request_playlist_collage(&playlist_id);
+                            }
+                            self.sync_playlist_artwork_ui();
+                            self.sync_notes_panel_ui(true);
+                            self.sync_playlist_skip_silence_ui();
+                            self.sync_playlist_normalize_loudness_ui();
+                            self.sync_playlist_remote_sync_ui();
                             let remote_playlist_flags = self
                                 .playlist_ids
                                 .iter()
@@ -12163,6 +16785,279 @@ impl UiManager {
                                 }
                             });
                         }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistNoteChanged { playlist_id, note },
+                        ) => {
+                            if note.is_empty() {
+                                self.playlist_notes_by_id.remove(&playlist_id);
+                            } else {
+                                self.playlist_notes_by_id.insert(playlist_id, note);
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SaveNotesPanelPlaylistNote(note),
+                        ) => {
+                            self.save_notes_panel_playlist_note(note);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TogglePlaylistFolderByRow(row),
+                        ) => {
+                            self.toggle_playlist_folder_row(row);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::CreatePlaylistFolderAtRow(row),
+                        ) => {
+                            self.create_playlist_folder_at_row(row);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::RenamePlaylistFolderByRow(row, name),
+                        ) => {
+                            if let Some(folder) = self.playlist_folder_at_row(row) {
+                                let _ = self.bus_sender.send(protocol::Message::Playlist(
+                                    protocol::PlaylistMessage::RenamePlaylistFolder {
+                                        id: folder.id.clone(),
+                                        name,
+                                    },
+                                ));
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::DeletePlaylistFolderByRow(row),
+                        ) => {
+                            if let Some(folder) = self.playlist_folder_at_row(row) {
+                                let _ = self.bus_sender.send(protocol::Message::Playlist(
+                                    protocol::PlaylistMessage::DeletePlaylistFolder {
+                                        id: folder.id.clone(),
+                                    },
+                                ));
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::MovePlaylistTreeRow { from, to },
+                        ) => {
+                            self.move_playlist_tree_row(from, to);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TogglePlaylistSkipSilenceByIndex(index),
+                        ) => {
+                            self.toggle_playlist_skip_silence(index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistSkipSilenceChanged {
+                                playlist_id,
+                                enabled,
+                            },
+                        ) => {
+                            if enabled {
+                                self.skip_silence_playlist_ids.insert(playlist_id);
+                            } else {
+                                self.skip_silence_playlist_ids.remove(&playlist_id);
+                            }
+                            self.sync_playlist_skip_silence_ui();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TogglePlaylistNormalizeLoudnessByIndex(
+                                index,
+                            ),
+                        ) => {
+                            self.toggle_playlist_normalize_loudness(index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistNormalizeLoudnessChanged {
+                                playlist_id,
+                                enabled,
+                            },
+                        ) => {
+                            if enabled {
+                                self.normalize_loudness_playlist_ids.insert(playlist_id);
+                            } else {
+                                self.normalize_loudness_playlist_ids.remove(&playlist_id);
+                            }
+                            self.sync_playlist_normalize_loudness_ui();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::OpenTrackTransition,
+                        ) => {
+                            self.open_track_transition_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::CloseTrackTransition,
+                        ) => {
+                            self.close_track_transition_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SaveTrackTransitionDialog {
+                                trim_start_text,
+                                trim_end_text,
+                            },
+                        ) => {
+                            self.save_track_transition_dialog(trim_start_text, trim_end_text);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::DetectTrackTrimsInDialog,
+                        ) => {
+                            self.detect_track_trims_in_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackTrimDetectionFinished {
+                                detected,
+                                total,
+                            },
+                        ) => {
+                            self.show_library_toast(format!(
+                                "Detected intro/outro trims for {detected} of {total} tracks"
+                            ));
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackTrimOverridesSnapshot(overrides),
+                        ) => {
+                            self.track_trims_by_path = overrides.into_iter().collect();
+                            self.sync_playing_track_trims_to_ui();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::BulkImportQueued { total },
+                        ) => {
+                            let (imported, queued) = self.bulk_import_progress.unwrap_or((0, 0));
+                            self.bulk_import_progress = Some((imported, queued + total));
+                            self.advance_bulk_import_progress(0);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::AnalyzeSelectedTrackLoudness,
+                        ) => {
+                            self.analyze_selected_track_loudness();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackLoudnessAnalysisFinished {
+                                analyzed,
+                                total,
+                            },
+                        ) => {
+                            self.show_library_toast(format!(
+                                "Measured loudness of {analyzed} of {total} tracks"
+                            ));
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::OpenSendToPlaylistDialog,
+                        ) => {
+                            self.open_send_to_playlist_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::OpenNewPlaylistFromSelection,
+                        ) => {
+                            self.open_new_playlist_from_selection_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::CloseSendToPlaylistDialog,
+                        ) => {
+                            self.close_send_to_playlist_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::ConfirmSendToPlaylists { checked },
+                        ) => {
+                            self.confirm_send_to_playlists(checked);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SendToRecentPlaylist(recent_index),
+                        ) => {
+                            self.send_to_recent_playlist(recent_index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::ConfirmNewPlaylistFromSelection { name },
+                        ) => {
+                            self.confirm_new_playlist_from_selection(name);
+                        }
+                        protocol::Message::Playlist(protocol::PlaylistMessage::OpenTrackEnergy) => {
+                            self.open_track_energy_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::CloseTrackEnergy,
+                        ) => {
+                            self.close_track_energy_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SaveTrackEnergyDialog { level_index },
+                        ) => {
+                            self.save_track_energy_dialog(level_index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::SuggestTrackEnergyInDialog,
+                        ) => {
+                            self.suggest_track_energy_in_dialog();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackEnergySuggestionFinished {
+                                suggested,
+                                total,
+                            },
+                        ) => {
+                            self.show_library_toast(format!(
+                                "Suggested energy for {suggested} of {total} tracks"
+                            ));
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TransitionHistoryReset { cleared },
+                        ) => {
+                            self.show_library_toast(format!(
+                                "Forgot {cleared} learned track transitions"
+                            ));
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackEnergySnapshot(settings),
+                        ) => {
+                            self.track_energy_by_path = settings.into_iter().collect();
+                            if self.is_energy_column_visible()
+                                || !self.filter_search_query.trim().is_empty()
+                            {
+                                self.rebuild_track_model();
+                            }
+                            if !self.library_search_query.trim().is_empty() {
+                                self.sync_library_ui();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackPlayStatsChanged(stats),
+                        ) => {
+                            self.track_play_stats_by_path.extend(stats);
+                            if self.is_play_stats_column_visible()
+                                || !self.filter_search_query.trim().is_empty()
+                            {
+                                self.rebuild_track_model();
+                            }
+                            if !self.library_search_query.trim().is_empty() {
+                                self.sync_library_ui();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackFormatsProbed(formats),
+                        ) => {
+                            let playing_track_probed =
+                                self.playing_track.path.as_ref().is_some_and(|playing| {
+                                    formats.iter().any(|(path, _)| path == playing)
+                                });
+                            self.track_formats_by_path.extend(formats);
+                            if self.is_quality_column_visible() || self.is_number_column_visible() {
+                                self.rebuild_track_model();
+                            }
+                            if playing_track_probed {
+                                self.update_display_for_active_collection();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackLoudnessChanged(measurements),
+                        ) => {
+                            self.track_loudness_by_path.extend(measurements);
+                            if self.is_loudness_column_visible() {
+                                self.rebuild_track_model();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackSourcesChanged(sources),
+                        ) => {
+                            self.track_sources_by_id.extend(sources);
+                            if self.is_source_column_visible() {
+                                self.rebuild_track_model();
+                            }
+                        }
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::ActivePlaylistChanged(id),
                         ) => {
@@ -12170,6 +17065,8 @@ impl UiManager {
                             self.selection_anchor_track_id = None;
                             self.playlist_column_target_widths_px.clear();
                             self.apply_playlist_column_layout();
+                            // Tracks may have changed since the collage was built.
+                            self.request_playlist_collage(&id);
                             if let Some(index) =
                                 self.playlist_ids.iter().position(|p_id| p_id == &id)
                             {
@@ -12206,6 +17103,25 @@ impl UiManager {
                             self.refresh_playlist_column_content_targets();
                             self.apply_playlist_column_layout();
                             self.rebuild_track_model();
+                            crate::startup_timing::mark("playlist view populated");
+                            self.refresh_startup_timing_diagnostics();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistRestoreContinued(tracks),
+                        ) => {
+                            for track in &tracks {
+                                self.track_ids.push(track.id.clone());
+                                self.track_paths.push(track.path.clone());
+                                self.track_cover_art_paths.push(None);
+                                self.track_metadata
+                                    .push(Self::fallback_track_metadata(track.path.as_path()));
+                            }
+                            self.queue_track_metadata_lookup_batch(&tracks);
+                            self.refresh_playlist_column_content_targets();
+                            self.apply_playlist_column_layout();
+                            self.rebuild_track_model();
+                            crate::startup_timing::mark("playlist view fully populated");
+                            self.refresh_startup_timing_diagnostics();
                         }
                         protocol::Message::Playlist(protocol::PlaylistMessage::TrackAdded {
                             id,
@@ -12326,6 +17242,7 @@ impl UiManager {
                                 self.library_status_text = toast_text.clone();
                                 self.show_library_toast(toast_text);
                             }
+                            self.advance_bulk_import_progress(inserted_count);
                             self.refresh_playlist_column_content_targets();
                             self.apply_playlist_column_layout();
                             self.rebuild_track_model();
@@ -12423,11 +17340,106 @@ impl UiManager {
                         ) => {
                             self.clear_playlist_filter_view();
                         }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::OpenColumnFilterDialog(column_index),
+                        ) => {
+                            self.open_column_filter_dialog(column_index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::ApplyColumnFilterDialog {
+                                checked_values,
+                                operator_index,
+                                operand,
+                            },
+                        ) => {
+                            self.apply_column_filter_dialog(
+                                checked_values,
+                                operator_index,
+                                operand,
+                            );
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistColumnFiltersChanged {
+                                playlist_id,
+                                filters,
+                            },
+                        ) => {
+                            let shown = playlist_id == self.active_playlist_id;
+                            if filters.is_empty() {
+                                self.playlist_column_filters_by_id.remove(&playlist_id);
+                            } else {
+                                self.playlist_column_filters_by_id
+                                    .insert(playlist_id, filters);
+                            }
+                            if shown {
+                                self.rebuild_track_model();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistDetailsChanged {
+                                playlist_id,
+                                description,
+                                cover_path,
+                            },
+                        ) => {
+                            if description.is_empty() {
+                                self.playlist_descriptions_by_id.remove(&playlist_id);
+                            } else {
+                                self.playlist_descriptions_by_id
+                                    .insert(playlist_id.clone(), description);
+                            }
+                            match cover_path {
+                                Some(cover_path) => {
+                                    self.playlist_cover_paths_by_id
+                                        .insert(playlist_id, cover_path);
+                                }
+                                None => {
+                                    self.playlist_cover_paths_by_id.remove(&playlist_id);
+                                }
+                            }
+                            self.sync_playlist_artwork_ui();
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::PlaylistCollageReady {
+                                playlist_id,
+                                collage_path,
+                            },
+                        ) => {
+                            let changed = match collage_path {
+                                Some(collage_path) => {
+                                    self.playlist_collage_paths_by_id
+                                        .insert(playlist_id, collage_path.clone())
+                                        != Some(collage_path)
+                                }
+                                None => self
+                                    .playlist_collage_paths_by_id
+                                    .remove(&playlist_id)
+                                    .is_some(),
+                            };
+                            if changed {
+                                self.sync_playlist_artwork_ui();
+                            }
+                        }
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::CyclePlaylistSortByColumn(column_index),
                         ) => {
                             self.cycle_playlist_sort_by_column(column_index);
                         }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::AddPlaylistSortColumn(column_index),
+                        ) => {
+                            self.add_playlist_sort_column(column_index);
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::RequestSortTracks,
+                        ) => {
+                            self.sort_playlist_permanently();
+                        }
+                        protocol::Message::Playlist(protocol::PlaylistMessage::SortTracks(
+                            order,
+                        )) => {
+                            self.apply_sorted_tracks_locally(order);
+                        }
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::RequestApplyFilterView,
                         ) => {
@@ -12456,6 +17468,7 @@ impl UiManager {
                             },
                         ) => {
                             self.last_progress_at = Some(Instant::now());
+                            self.sync_lyrics_active_line(elapsed_ms);
                             if self.progress_rl.check().is_ok() {
                                 // Check if the displayed second has changed (for text updates)
                                 let elapsed_secs = elapsed_ms / 1000;
@@ -12497,6 +17510,8 @@ impl UiManager {
                         ) => {
                             debug!("UiManager: Technical metadata changed: {:?}", meta);
                             self.current_technical_metadata = Some(meta);
+                            self.current_output_rate_decision = None;
+                            self.request_playing_track_format();
                             self.refresh_technical_info_ui();
                             self.update_display_for_active_collection();
                         }
@@ -12507,19 +17522,105 @@ impl UiManager {
                             self.refresh_technical_info_ui();
                             self.update_display_for_active_collection();
                         }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::OutputRateDecided(decision),
+                        ) => {
+                            self.current_output_rate_decision = Some(decision);
+                            self.refresh_technical_info_ui();
+                        }
+                        protocol::Message::Config(
+                            protocol::ConfigMessage::BatterySaverChanged { active },
+                        ) => {
+                            self.battery_saver_active = active;
+                            self.refresh_technical_info_ui();
+                        }
+                        protocol::Message::Config(
+                            protocol::ConfigMessage::OutputDeviceFailover {
+                                preferred_device,
+                                active_device,
+                                failed_over,
+                            },
+                        ) => {
+                            self.show_library_toast(if failed_over {
+                                format!(
+                                    "{preferred_device} disconnected, playing on {active_device}"
+                                )
+                            } else {
+                                format!("{preferred_device} reconnected, playing on it again")
+                            });
+                        }
+                        protocol::Message::Integration(
+                            protocol::IntegrationMessage::PlaylistUrlFetched {
+                                local_playlist_id: None,
+                                result,
+                                ..
+                            },
+                        ) => match result {
+                            Ok(snapshot) => self.show_library_toast(format!(
+                                "Imported {} ({} entries)",
+                                snapshot.name,
+                                snapshot.tracks.len()
+                            )),
+                            Err(error) => self
+                                .show_library_toast(format!("Playlist URL import failed: {error}")),
+                        },
+                        protocol::Message::Integration(
+                            protocol::IntegrationMessage::MaterializeRemotePlaylist {
+                                playlist_name,
+                                paths,
+                                ..
+                            },
+                        ) => self.show_library_toast(format!(
+                            "Downloading {} track(s) of {}...",
+                            paths.len(),
+                            playlist_name
+                        )),
+                        protocol::Message::Integration(
+                            protocol::IntegrationMessage::RemotePlaylistMaterializeProgress {
+                                playlist_name,
+                                completed,
+                                total,
+                                ..
+                            },
+                        ) => self.show_library_toast(format!(
+                            "Downloading {playlist_name}: {completed} of {total}"
+                        )),
+                        protocol::Message::Integration(
+                            protocol::IntegrationMessage::RemotePlaylistMaterialized {
+                                playlist_name,
+                                result,
+                                ..
+                            },
+                        ) => match result {
+                            Ok(_) => self.show_library_toast(format!(
+                                "{playlist_name} is now a local playlist"
+                            )),
+                            Err(error) => self.show_library_toast(format!(
+                                "Could not download {playlist_name}: {error}"
+                            )),
+                        },
                         protocol::Message::Cast(protocol::CastMessage::DevicesUpdated(devices)) => {
                             self.cast_device_ids =
                                 devices.iter().map(|device| device.id.clone()).collect();
                             self.cast_device_names = devices
                                 .iter()
                                 .map(|device| {
-                                    if device.model.trim().is_empty() {
+                                    let label = if device.is_group() {
+                                        format!("{} (group)", device.name)
+                                    } else if device.model.trim().is_empty() {
                                         device.name.clone()
                                     } else {
                                         format!("{} ({})", device.name, device.model)
+                                    };
+                                    if device.available {
+                                        label
+                                    } else {
+                                        format!("{} - unavailable", label)
                                     }
                                 })
                                 .collect();
+                            self.cast_device_available =
+                                devices.iter().map(|device| device.available).collect();
                             self.sync_cast_state_to_ui();
                         }
                         protocol::Message::Cast(
@@ -12534,6 +17635,10 @@ impl UiManager {
                                 state == protocol::CastConnectionState::Connecting;
                             self.cast_discovering =
                                 state == protocol::CastConnectionState::Discovering;
+                            self.cast_device_id = device
+                                .as_ref()
+                                .map(|device| device.id.clone())
+                                .unwrap_or_default();
                             self.cast_device_name =
                                 device.map(|device| device.name).unwrap_or_default();
                             if !self.cast_connected {
@@ -12551,6 +17656,17 @@ impl UiManager {
                             self.refresh_technical_info_ui();
                             self.update_display_for_active_collection();
                         }
+                        protocol::Message::Cast(protocol::CastMessage::VolumeStatusChanged {
+                            level,
+                            muted,
+                            max_volume,
+                        }) => {
+                            let _ = self.ui.upgrade_in_event_loop(move |ui| {
+                                ui.set_volume_level(level);
+                                ui.set_volume_muted(muted);
+                                ui.set_cast_max_volume(max_volume);
+                            });
+                        }
                         protocol::Message::Cast(protocol::CastMessage::PlaybackPathChanged {
                             kind,
                             description: _description,
@@ -12591,6 +17707,7 @@ impl UiManager {
                             self.playing_track = PlayingTrackState::default();
                             self.current_technical_metadata = None;
                             self.current_output_path_info = None;
+                            self.current_output_rate_decision = None;
                             self.library_playing_index = None;
                             if had_playing_track {
                                 self.display_target_priority = DisplayTargetPriority::Playing;
@@ -12600,6 +17717,8 @@ impl UiManager {
                             // Reset cached progress values
                             self.last_elapsed_ms = 0;
                             self.last_total_ms = 0;
+                            self.sync_seek_markers_to_ui(Vec::new());
+                            self.sync_playing_track_trims_to_ui();
 
                             let _ = self.ui.upgrade_in_event_loop(move |ui| {
                                 ui.set_technical_info("".into());
@@ -12641,6 +17760,7 @@ impl UiManager {
                             if !is_playing {
                                 self.last_progress_at = None;
                             }
+                            self.playing_playlist_id = playing_playlist_id.clone();
                             let previous_active_playing_index = self.active_playing_index;
                             let selected_indices_clone = selected_indices.clone();
                             self.selected_indices = selected_indices_clone.clone();
@@ -12661,6 +17781,9 @@ impl UiManager {
                             );
                             if playing_track_changed {
                                 self.display_target_priority = DisplayTargetPriority::Playing;
+                                self.sync_seek_markers_to_ui(Vec::new());
+                                self.sync_playing_track_trims_to_ui();
+                                self.request_playing_track_lyrics();
                             }
                             self.update_library_playing_index();
                             self.update_display_for_active_collection();
@@ -12689,6 +17812,8 @@ impl UiManager {
                                     protocol::PlaybackOrder::Default => 0,
                                     protocol::PlaybackOrder::Shuffle => 1,
                                     protocol::PlaybackOrder::Random => 2,
+                                    protocol::PlaybackOrder::ShuffleFolders => 3,
+                                    protocol::PlaybackOrder::EnergyRamp => 4,
                                 };
                                 ui.set_playback_order_index(order_int);
                             });
@@ -12699,6 +17824,19 @@ impl UiManager {
                             {
                                 self.auto_scroll_active_collection_to_playing_track();
                             }
+                            if self.jump_to_playing_pending && is_playing_active_playlist {
+                                self.jump_to_playing_pending = false;
+                                self.center_playlist_view_on_playing_track();
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::UpcomingTracksChanged(tracks),
+                        ) => {
+                            self.upcoming_tracks = tracks;
+                            self.sync_up_next_ui();
+                            if self.is_row_number_column_visible() {
+                                self.rebuild_track_model();
+                            }
                         }
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::SelectionChanged(indices),
@@ -12899,6 +18037,13 @@ impl UiManager {
                                 }
                             }
                         }
+                        protocol::Message::Playback(
+                            protocol::PlaybackMessage::SeekMarkersChanged { track_id, markers },
+                        ) => {
+                            if self.playing_track.id.as_deref() == Some(track_id.as_str()) {
+                                self.sync_seek_markers_to_ui(markers);
+                            }
+                        }
                         protocol::Message::Playback(
                             protocol::PlaybackMessage::MetadataDisplayChanged(meta),
                         ) => {
@@ -12934,7 +18079,10 @@ impl UiManager {
                                     protocol::ConfigDeltaEntry::Output(_)
                                     | protocol::ConfigDeltaEntry::Cast(_)
                                     | protocol::ConfigDeltaEntry::Buffering(_)
-                                    | protocol::ConfigDeltaEntry::Integrations(_) => {}
+                                    | protocol::ConfigDeltaEntry::Integrations(_)
+                                    | protocol::ConfigDeltaEntry::Announcements(_)
+                                    | protocol::ConfigDeltaEntry::Power(_)
+                                    | protocol::ConfigDeltaEntry::Dsp(_) => {}
                                 }
                             }
                             self.apply_ui_library_config_updates(
@@ -12942,6 +18090,19 @@ impl UiManager {
                                 (!library_update.is_empty()).then_some(library_update),
                             );
                         }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::TrackListScrolledByUser,
+                        ) => {
+                            if self.auto_scroll_to_playing_track {
+                                self.follow_playback_paused_until =
+                                    Some(Instant::now() + FOLLOW_PLAYBACK_MANUAL_SCROLL_PAUSE);
+                            }
+                        }
+                        protocol::Message::Playlist(
+                            protocol::PlaylistMessage::JumpToPlayingTrack,
+                        ) => {
+                            self.jump_to_playing_track();
+                        }
                         protocol::Message::Playlist(
                             protocol::PlaylistMessage::PlaylistViewportChanged {
                                 first_row,
@@ -13019,11 +18180,14 @@ mod tests {
     use super::{
         fit_column_widths_deterministic, ColumnWidthProfile, CoverArtLookupRequest,
         DeterministicColumnLayoutSpec, LibraryEntry, LibraryViewState, PathImageCache,
-        PlaylistColumnClass, PlaylistSortDirection, TrackMetadata, UiManager,
-        ENRICHMENT_FAILED_ATTEMPT_CAP, TEXT_PANEL_WIDTH_ESTIMATE_GRACE_PX,
-        TEXT_PANEL_WIDTH_OVERFLOW_THRESHOLD_PX,
+        PlaylistColumnClass, TrackMetadata, UiManager, ENRICHMENT_FAILED_ATTEMPT_CAP,
+        TEXT_PANEL_WIDTH_ESTIMATE_GRACE_PX, TEXT_PANEL_WIDTH_OVERFLOW_THRESHOLD_PX,
+    };
+    use crate::{
+        config::PlaylistColumnConfig,
+        playlist_sort::{PlaylistSortDirection, PlaylistSortKey},
+        protocol, text_template,
     };
-    use crate::{config::PlaylistColumnConfig, protocol, text_template};
     use std::collections::{HashMap, HashSet};
     use std::path::{Path, PathBuf};
     use std::sync::mpsc;
@@ -13039,6 +18203,10 @@ mod tests {
             year: "2026".to_string(),
             genre: "test".to_string(),
             track_number: "1".to_string(),
+            composer: String::new(),
+            disc_number: String::new(),
+            total_tracks: String::new(),
+            comment: String::new(),
         }
     }
 
@@ -13053,6 +18221,7 @@ mod tests {
             genre: "test-genre".to_string(),
             year: "2025".to_string(),
             track_number: "1".to_string(),
+            duration_ms: None,
         }
     }
 
@@ -13124,6 +18293,7 @@ mod tests {
             genre: "test-genre".to_string(),
             year: year.to_string(),
             track_number: track_number.to_string(),
+            duration_ms: None,
         }
     }
 
@@ -13133,6 +18303,7 @@ mod tests {
             album_artist: album_artist.to_string(),
             track_count: 3,
             representative_track_path: Some(PathBuf::from(format!("{album}.mp3"))),
+            year: String::new(),
         }
     }
 
@@ -13187,6 +18358,10 @@ mod tests {
             year: "2026",
             genre: "Synthwave",
             track_number: "1",
+            composer: "",
+            disc_number: "",
+            total_tracks: "",
+            comment: "",
             file_name: Some("example.mp3"),
             path: Some("/music/example.mp3"),
             playing: None,
@@ -13213,6 +18388,7 @@ mod tests {
             technical_channel_from_channels: "",
             technical_channel_to_channels: "",
             technical_dithered: "",
+            technical_quality: "",
         }
     }
 
@@ -13255,6 +18431,37 @@ mod tests {
         assert_eq!(UiManager::text_panel_content_inset_px(400, 110), 4);
     }
 
+    #[test]
+    fn test_task_row_shows_progress_and_failure_details() {
+        let mut task = protocol::TaskSnapshot {
+            task_id: 7,
+            kind: protocol::TaskKind::Download,
+            label: "Downloading 'Road trip'".to_string(),
+            state: protocol::TaskState::Running,
+            completed: 1,
+            total: Some(4),
+            detail: "1 of 4 tracks".to_string(),
+            can_pause: true,
+            can_cancel: true,
+        };
+        let row = UiManager::task_row(&task);
+        assert_eq!(row.status.as_str(), "Running 25%");
+        assert_eq!(row.progress, 0.25);
+        assert!(row.active && !row.failed);
+
+        task.total = None;
+        assert_eq!(UiManager::task_row(&task).status.as_str(), "Running");
+        assert_eq!(UiManager::task_row(&task).progress, -1.0);
+
+        task.state = protocol::TaskState::Finished(protocol::TaskOutcome::Failed(
+            "Track 2 of 4: timed out".to_string(),
+        ));
+        let row = UiManager::task_row(&task);
+        assert_eq!(row.status.as_str(), "Failed");
+        assert_eq!(row.detail.as_str(), "Track 2 of 4: timed out");
+        assert!(!row.active && row.failed);
+    }
+
     #[test]
     fn test_text_panel_visible_line_count_uses_full_lines_only() {
         let rendered = rendered_with_line_font_sizes(&[16, 16, 16]);
@@ -13501,20 +18708,20 @@ mod tests {
 
     #[test]
     fn test_reset_filter_state_fields_clears_sort_and_search() {
-        let mut sort_key = Some("title".to_string());
-        let mut sort_direction = Some(PlaylistSortDirection::Descending);
+        let mut sort_keys = vec![PlaylistSortKey {
+            column_key: "title".to_string(),
+            direction: PlaylistSortDirection::Descending,
+        }];
         let mut search_query = "beatles".to_string();
         let mut search_visible = true;
 
         UiManager::reset_filter_state_fields(
-            &mut sort_key,
-            &mut sort_direction,
+            &mut sort_keys,
             &mut search_query,
             &mut search_visible,
         );
 
-        assert!(sort_key.is_none());
-        assert!(sort_direction.is_none());
+        assert!(sort_keys.is_empty());
         assert!(search_query.is_empty());
         assert!(!search_visible);
     }
@@ -14382,6 +19589,85 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_artist_detail_entries_lists_albums_before_eps_and_singles() {
+        let mut full_album = make_library_album("Full", "Artist");
+        full_album.track_count = 10;
+        let mut older_album = make_library_album("Older", "Artist");
+        older_album.track_count = 12;
+        let albums = vec![
+            make_library_album("Hit - Single", "Artist"),
+            make_library_album("Tour (EP)", "Artist"),
+            full_album,
+            older_album,
+        ];
+        let tracks = vec![
+            make_library_track_in_album(
+                "hit",
+                "Hit",
+                "hit.mp3",
+                "Hit - Single",
+                "Artist",
+                "2024",
+                "1",
+            ),
+            make_library_track_in_album(
+                "tour",
+                "Tour",
+                "tour.mp3",
+                "Tour (EP)",
+                "Artist",
+                "2023",
+                "1",
+            ),
+            make_library_track_in_album("full", "Full", "full.mp3", "Full", "Artist", "2020", "1"),
+            make_library_track_in_album("old", "Old", "old.mp3", "Older", "Artist", "2001", "1"),
+        ];
+
+        let entries = UiManager::build_artist_detail_entries(albums, tracks);
+        let view = LibraryViewState::ArtistDetail {
+            artist: "Artist".to_string(),
+        };
+        let view_indices: Vec<usize> = (0..entries.len()).collect();
+        let summary: Vec<String> = entries
+            .iter()
+            .enumerate()
+            .map(|(row, entry)| match entry {
+                LibraryEntry::Album(album) => format!(
+                    "{}|{}",
+                    UiManager::library_release_section_title(&entries, &view_indices, &view, row),
+                    album.album
+                ),
+                LibraryEntry::Track(track) => format!("track:{}", track.title),
+                LibraryEntry::Artist(_)
+                | LibraryEntry::Genre(_)
+                | LibraryEntry::Decade(_)
+                | LibraryEntry::FavoriteCategory(_) => "unexpected".to_string(),
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                "Albums|Full",
+                "track:Full",
+                "|Older",
+                "track:Old",
+                "EPs|Tour (EP)",
+                "track:Tour",
+                "Singles|Hit - Single",
+                "track:Hit",
+            ]
+        );
+        assert!(UiManager::library_release_section_title(
+            &entries,
+            &view_indices,
+            &LibraryViewState::AlbumsRoot,
+            0
+        )
+        .is_empty());
+    }
+
     #[test]
     fn test_build_global_search_entries_orders_by_name_then_kind() {
         let tracks = vec![
@@ -14437,13 +19723,18 @@ mod tests {
             }),
         ];
 
-        let track_match = UiManager::build_library_view_indices_for_query(&entries, "train");
+        let no_tags = HashMap::new();
+        let no_notes = HashMap::new();
+        let track_match =
+            UiManager::build_library_view_indices_for_query(&entries, "train", &no_tags, &no_notes);
         assert_eq!(track_match, vec![0]);
 
-        let artist_match = UiManager::build_library_view_indices_for_query(&entries, "daft");
+        let artist_match =
+            UiManager::build_library_view_indices_for_query(&entries, "daft", &no_tags, &no_notes);
         assert_eq!(artist_match, vec![1, 2]);
 
-        let decade_match = UiManager::build_library_view_indices_for_query(&entries, "2000");
+        let decade_match =
+            UiManager::build_library_view_indices_for_query(&entries, "2000", &no_tags, &no_notes);
         assert_eq!(decade_match, vec![4]);
     }
 
@@ -14459,10 +19750,217 @@ mod tests {
             LibraryEntry::Album(make_library_album("Album", "Artist")),
         ];
 
-        let indices = UiManager::build_library_view_indices_for_query(&entries, "   ");
+        let indices = UiManager::build_library_view_indices_for_query(
+            &entries,
+            "   ",
+            &HashMap::new(),
+            &HashMap::new(),
+        );
         assert_eq!(indices, vec![0, 1, 2]);
     }
 
+    #[test]
+    fn test_apply_user_tag_chips_renders_colored_dots_in_tags_column() {
+        let columns = vec![
+            PlaylistColumnConfig {
+                name: "Title".to_string(),
+                format: "{title}".to_string(),
+                enabled: true,
+                custom: false,
+            },
+            PlaylistColumnConfig {
+                name: "Tags".to_string(),
+                format: "{tags}".to_string(),
+                enabled: true,
+                custom: false,
+            },
+        ];
+        let mut values = UiManager::build_playlist_row_values_with_indicators(
+            &make_meta("Song"),
+            None,
+            &columns,
+            None,
+            None,
+        );
+        let defined_tags = vec![protocol::UserTag {
+            name: "Workout".to_string(),
+            color: "#E5484D".to_string(),
+        }];
+        UiManager::apply_user_tag_chips(
+            &mut values,
+            &columns,
+            &["Workout".to_string(), "Chill".to_string()],
+            &defined_tags,
+        );
+
+        assert_eq!(values[0].plain_text, "Song");
+        assert_eq!(values[1].plain_text, "Workout, Chill");
+        let runs = &values[1].rich_text.lines[0].runs;
+        assert_eq!(runs.len(), 4);
+        assert_eq!(
+            runs[0].color,
+            Some(text_template::RunColor::Rgba {
+                r: 0xE5,
+                g: 0x48,
+                b: 0x4D,
+                a: 1.0
+            })
+        );
+        assert_eq!(runs[2].color, None);
+    }
+
+    #[test]
+    fn test_build_library_view_indices_for_query_filters_by_user_tags() {
+        let entries = vec![
+            LibraryEntry::Track(make_library_track("track-a", "Run", "run.mp3")),
+            LibraryEntry::Track(make_library_track("track-b", "Sleep", "sleep.mp3")),
+            LibraryEntry::Album(make_library_album("Workout Mix", "Various")),
+        ];
+        let tags_by_path = HashMap::from([(PathBuf::from("run.mp3"), vec!["Workout".to_string()])]);
+
+        let no_notes = HashMap::new();
+
+        let text_match = UiManager::build_library_view_indices_for_query(
+            &entries,
+            "workout",
+            &tags_by_path,
+            &no_notes,
+        );
+        assert_eq!(text_match, vec![0, 2]);
+
+        let tag_match = UiManager::build_library_view_indices_for_query(
+            &entries,
+            "tag:workout",
+            &tags_by_path,
+            &no_notes,
+        );
+        assert_eq!(tag_match, vec![0]);
+
+        let tag_and_text_match = UiManager::build_library_view_indices_for_query(
+            &entries,
+            "tag:workout sleep",
+            &tags_by_path,
+            &no_notes,
+        );
+        assert!(tag_and_text_match.is_empty());
+    }
+
+    #[test]
+    fn test_retain_library_tracks_with_energy_keeps_only_named_levels() {
+        let entries = vec![
+            LibraryEntry::Track(make_library_track("track-a", "Run", "run.mp3")),
+            LibraryEntry::Track(make_library_track("track-b", "Sleep", "sleep.mp3")),
+            LibraryEntry::Track(make_library_track("track-c", "Walk", "walk.mp3")),
+            LibraryEntry::Album(make_library_album("Run Mix", "Various")),
+        ];
+        let energy_by_path = HashMap::from([
+            (
+                PathBuf::from("run.mp3"),
+                protocol::TrackEnergySetting {
+                    energy: protocol::TrackEnergy::Intense,
+                    suggested: true,
+                },
+            ),
+            (
+                PathBuf::from("sleep.mp3"),
+                protocol::TrackEnergySetting {
+                    energy: protocol::TrackEnergy::Calm,
+                    suggested: false,
+                },
+            ),
+        ]);
+
+        let mut indices = vec![0, 1, 2, 3];
+        UiManager::retain_library_tracks_with_energy(&entries, &mut indices, &[], &energy_by_path);
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+
+        UiManager::retain_library_tracks_with_energy(
+            &entries,
+            &mut indices,
+            &[
+                protocol::TrackEnergy::Intense,
+                protocol::TrackEnergy::Upbeat,
+            ],
+            &energy_by_path,
+        );
+        assert_eq!(indices, vec![0]);
+    }
+
+    #[test]
+    fn test_library_facet_rows_keep_picked_values_without_matches() {
+        let mut selection = protocol::LibraryFacetSelection::default();
+        selection.formats.insert("OGG".to_string());
+        let counts = vec![
+            protocol::LibraryFacetCount {
+                kind: protocol::LibraryFacetKind::Genre,
+                value: "Jazz".to_string(),
+                track_count: 3,
+            },
+            protocol::LibraryFacetCount {
+                kind: protocol::LibraryFacetKind::Format,
+                value: "FLAC".to_string(),
+                track_count: 2,
+            },
+        ];
+
+        let rows: Vec<(i32, String, i32, bool, bool)> =
+            UiManager::library_facet_rows(&selection, &counts)
+                .into_iter()
+                .map(|row| {
+                    (
+                        row.kind,
+                        row.value.to_string(),
+                        row.count,
+                        row.selected,
+                        row.header,
+                    )
+                })
+                .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "Genre".to_string(), 0, false, true),
+                (0, "Jazz".to_string(), 3, false, false),
+                (2, "Format".to_string(), 0, false, true),
+                (2, "FLAC".to_string(), 2, false, false),
+                (2, "OGG".to_string(), 0, true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_library_view_indices_for_query_matches_track_notes() {
+        let entries = vec![
+            LibraryEntry::Track(make_library_track("track-a", "Run", "run.mp3")),
+            LibraryEntry::Track(make_library_track("track-b", "Sleep", "sleep.mp3")),
+        ];
+        let notes_by_path = HashMap::from([(
+            PathBuf::from("sleep.mp3"),
+            "Skip the long intro".to_string(),
+        )]);
+
+        let indices = UiManager::build_library_view_indices_for_query(
+            &entries,
+            "intro",
+            &HashMap::new(),
+            &notes_by_path,
+        );
+        assert_eq!(indices, vec![1]);
+    }
+
+    #[test]
+    fn test_parse_trim_seconds_accepts_blank_and_fractions_and_rejects_invalid() {
+        assert_eq!(UiManager::parse_trim_seconds("  "), Some(0));
+        assert_eq!(UiManager::parse_trim_seconds("4"), Some(4_000));
+        assert_eq!(UiManager::parse_trim_seconds("2.5"), Some(2_500));
+        assert_eq!(UiManager::parse_trim_seconds("-1"), None);
+        assert_eq!(UiManager::parse_trim_seconds("601"), None);
+        assert_eq!(UiManager::parse_trim_seconds("soon"), None);
+        assert_eq!(UiManager::format_trim_seconds(2_500), "2.5");
+        assert_eq!(UiManager::format_trim_seconds(4_000), "4");
+        assert_eq!(UiManager::format_trim_seconds(0), "");
+    }
+
     #[test]
     fn test_build_library_selection_specs_for_entries_expands_supported_item_types() {
         let entries = vec![
@@ -0,0 +1,390 @@
+//! Resizable worker pools for CPU-heavy background work.
+//!
+//! The decode pool reads tags from files during library scans; the analysis pool runs
+//! whole-file analysis such as intro/outro detection. Sizes and priority come from
+//! `[performance]` and can change at runtime: growing spawns workers immediately, while
+//! surplus workers retire after their current job or once they sit idle. Each pool keeps
+//! live counters that settings shows as diagnostics.
+
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::config::{PerformanceConfig, WorkerPriority};
+
+/// Upper bound for configured pool sizes.
+pub const MAX_WORKER_THREADS: u32 = 32;
+/// Thread-count choices offered in settings; `0` sizes the pool from the CPU count.
+pub const THREAD_COUNT_CHOICES: [u32; 9] = [0, 1, 2, 3, 4, 6, 8, 16, 32];
+/// Priority choices in settings display order.
+pub const PRIORITY_CHOICES: [WorkerPriority; 2] = [WorkerPriority::Normal, WorkerPriority::Low];
+/// How long an idle worker waits before re-checking whether it should retire.
+const IDLE_RETIRE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// Pause after each job on low-priority pools so playback and the UI get CPU time first.
+const LOW_PRIORITY_JOB_PAUSE: Duration = Duration::from_millis(2);
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Point-in-time usage of one pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerPoolStats {
+    pub threads: usize,
+    pub active: usize,
+    pub queued: usize,
+    pub completed: u64,
+}
+
+#[derive(Default)]
+struct PoolCounters {
+    target_threads: AtomicUsize,
+    live_threads: AtomicUsize,
+    active: AtomicUsize,
+    queued: AtomicUsize,
+    completed: AtomicU64,
+    low_priority: AtomicBool,
+}
+
+impl PoolCounters {
+    /// Claims one retirement slot when more workers are alive than configured.
+    fn try_retire_worker(&self) -> bool {
+        let mut live = self.live_threads.load(Ordering::SeqCst);
+        while live > self.target_threads.load(Ordering::SeqCst) {
+            match self.live_threads.compare_exchange(
+                live,
+                live - 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => live = actual,
+            }
+        }
+        false
+    }
+}
+
+/// Shared job queue served by a resizable set of worker threads.
+pub struct WorkerPool {
+    name: &'static str,
+    job_tx: mpsc::Sender<Job>,
+    job_rx: Arc<Mutex<mpsc::Receiver<Job>>>,
+    counters: Arc<PoolCounters>,
+    resize_lock: Mutex<()>,
+}
+
+impl WorkerPool {
+    /// Creates a pool named `name` and starts `threads` workers.
+    pub fn new(name: &'static str, threads: usize) -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<Job>();
+        let pool = Self {
+            name,
+            job_tx,
+            job_rx: Arc::new(Mutex::new(job_rx)),
+            counters: Arc::new(PoolCounters::default()),
+            resize_lock: Mutex::new(()),
+        };
+        pool.set_threads(threads);
+        pool
+    }
+
+    /// Resizes the pool to `threads` workers (at least one).
+    pub fn set_threads(&self, threads: usize) {
+        let threads = threads.clamp(1, MAX_WORKER_THREADS as usize);
+        let _guard = self
+            .resize_lock
+            .lock()
+            .expect("worker pool resize lock poisoned");
+        self.counters
+            .target_threads
+            .store(threads, Ordering::SeqCst);
+        while self.counters.live_threads.load(Ordering::SeqCst) < threads {
+            self.counters.live_threads.fetch_add(1, Ordering::SeqCst);
+            let job_rx = Arc::clone(&self.job_rx);
+            let counters = Arc::clone(&self.counters);
+            let spawned = thread::Builder::new()
+                .name(format!("{}-worker", self.name))
+                .spawn(move || Self::run_worker(job_rx, counters));
+            if let Err(err) = spawned {
+                self.counters.live_threads.fetch_sub(1, Ordering::SeqCst);
+                warn!("WorkerPool[{}]: failed to spawn worker: {}", self.name, err);
+                break;
+            }
+        }
+    }
+
+    /// Sets whether workers pause briefly between jobs to yield CPU time.
+    pub fn set_priority(&self, priority: WorkerPriority) {
+        self.counters
+            .low_priority
+            .store(priority == WorkerPriority::Low, Ordering::SeqCst);
+    }
+
+    fn run_worker(job_rx: Arc<Mutex<mpsc::Receiver<Job>>>, counters: Arc<PoolCounters>) {
+        loop {
+            if counters.try_retire_worker() {
+                return;
+            }
+            let job = {
+                let Ok(job_rx) = job_rx.lock() else {
+                    break;
+                };
+                job_rx.recv_timeout(IDLE_RETIRE_CHECK_INTERVAL)
+            };
+            let job = match job {
+                Ok(job) => job,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+            counters.queued.fetch_sub(1, Ordering::SeqCst);
+            counters.active.fetch_add(1, Ordering::SeqCst);
+            if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                warn!("WorkerPool: job panicked");
+            }
+            counters.active.fetch_sub(1, Ordering::SeqCst);
+            counters.completed.fetch_add(1, Ordering::SeqCst);
+            if counters.low_priority.load(Ordering::SeqCst) {
+                thread::sleep(LOW_PRIORITY_JOB_PAUSE);
+            }
+        }
+        counters.live_threads.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Queues one job on the next idle worker.
+    pub fn submit(&self, job: impl FnOnce() + Send + 'static) {
+        self.counters.queued.fetch_add(1, Ordering::SeqCst);
+        if self.job_tx.send(Box::new(job)).is_err() {
+            self.counters.queued.fetch_sub(1, Ordering::SeqCst);
+            warn!(
+                "WorkerPool[{}]: all workers stopped, dropping job",
+                self.name
+            );
+        }
+    }
+
+    /// Runs `job` over every item in parallel and returns the results in input order.
+    ///
+    /// Blocks the caller until all items finish; results of jobs that panicked are left
+    /// out. Must not be called from a worker of the same pool.
+    pub fn map<T, R, F>(&self, items: Vec<T>, job: F) -> Vec<R>
+    where
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let item_count = items.len();
+        let job = Arc::new(job);
+        let (result_tx, result_rx) = mpsc::channel::<(usize, R)>();
+        for (index, item) in items.into_iter().enumerate() {
+            let job = Arc::clone(&job);
+            let result_tx = result_tx.clone();
+            self.submit(move || {
+                let _ = result_tx.send((index, job(item)));
+            });
+        }
+        drop(result_tx);
+
+        let mut results: Vec<Option<R>> = (0..item_count).map(|_| None).collect();
+        for (index, result) in result_rx {
+            results[index] = Some(result);
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Returns current thread, job, and throughput counters.
+    pub fn stats(&self) -> WorkerPoolStats {
+        WorkerPoolStats {
+            threads: self.counters.live_threads.load(Ordering::SeqCst),
+            active: self.counters.active.load(Ordering::SeqCst),
+            queued: self.counters.queued.load(Ordering::SeqCst),
+            completed: self.counters.completed.load(Ordering::SeqCst),
+        }
+    }
+}
+
+static DECODE_POOL: OnceLock<WorkerPool> = OnceLock::new();
+static ANALYSIS_POOL: OnceLock<WorkerPool> = OnceLock::new();
+
+fn available_cpus() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(2)
+}
+
+/// Default decode pool size: half the CPUs, between 1 and 8.
+pub fn auto_decode_threads(cpus: usize) -> usize {
+    (cpus / 2).clamp(1, 8)
+}
+
+/// Default analysis pool size: a quarter of the CPUs, between 1 and 4.
+pub fn auto_analysis_threads(cpus: usize) -> usize {
+    (cpus / 4).clamp(1, 4)
+}
+
+fn resolve_threads(configured: u32, auto: fn(usize) -> usize) -> usize {
+    if configured == 0 {
+        auto(available_cpus())
+    } else {
+        configured.min(MAX_WORKER_THREADS) as usize
+    }
+}
+
+/// Effective decode pool size for `config`.
+pub fn resolved_decode_threads(config: &PerformanceConfig) -> usize {
+    resolve_threads(config.decode_threads, auto_decode_threads)
+}
+
+/// Effective analysis pool size for `config`.
+pub fn resolved_analysis_threads(config: &PerformanceConfig) -> usize {
+    resolve_threads(config.analysis_threads, auto_analysis_threads)
+}
+
+/// Pool used for per-file tag reads during library scans.
+pub fn decode_pool() -> &'static WorkerPool {
+    DECODE_POOL.get_or_init(|| {
+        WorkerPool::new(
+            "decode",
+            resolved_decode_threads(&PerformanceConfig::default()),
+        )
+    })
+}
+
+/// Pool used for whole-file audio analysis.
+pub fn analysis_pool() -> &'static WorkerPool {
+    ANALYSIS_POOL.get_or_init(|| {
+        WorkerPool::new(
+            "analysis",
+            resolved_analysis_threads(&PerformanceConfig::default()),
+        )
+    })
+}
+
+/// Applies configured sizes and priority to both shared pools.
+pub fn apply_performance_config(config: &PerformanceConfig) {
+    decode_pool().set_threads(resolved_decode_threads(config));
+    decode_pool().set_priority(config.worker_priority);
+    analysis_pool().set_threads(resolved_analysis_threads(config));
+    analysis_pool().set_priority(config.worker_priority);
+}
+
+/// Index into [`THREAD_COUNT_CHOICES`] shown for a configured count; custom values map
+/// to the next larger choice.
+pub fn thread_choice_index(threads: u32) -> usize {
+    THREAD_COUNT_CHOICES
+        .iter()
+        .position(|choice| *choice >= threads)
+        .unwrap_or(THREAD_COUNT_CHOICES.len() - 1)
+}
+
+/// Settings labels for [`THREAD_COUNT_CHOICES`], with the automatic size spelled out.
+pub fn thread_choice_labels(auto_threads: usize) -> Vec<String> {
+    THREAD_COUNT_CHOICES
+        .iter()
+        .map(|choice| match choice {
+            0 => format!("Auto ({auto_threads})"),
+            count => count.to_string(),
+        })
+        .collect()
+}
+
+/// Settings label for one priority choice.
+pub fn priority_label(priority: WorkerPriority) -> &'static str {
+    match priority {
+        WorkerPriority::Normal => "Normal",
+        WorkerPriority::Low => "Low (yield to playback)",
+    }
+}
+
+/// One-line usage summary for a pool.
+pub fn format_pool_stats(label: &str, stats: WorkerPoolStats) -> String {
+    format!(
+        "{label}: {} thread(s), {} busy, {} queued, {} done",
+        stats.threads, stats.active, stats.queued, stats.completed
+    )
+}
+
+/// Live usage of both shared pools for the diagnostics readout.
+pub fn diagnostics_summary() -> String {
+    format!(
+        "{}\n{}",
+        format_pool_stats("Decode", decode_pool().stats()),
+        format_pool_stats("Analysis", analysis_pool().stats())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_for(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition() {
+            assert!(Instant::now() < deadline, "condition not reached in time");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_map_preserves_input_order_and_counts_completed_jobs() {
+        let pool = WorkerPool::new("test-map", 3);
+        let results = pool.map((0..50u64).collect(), |value| {
+            thread::sleep(Duration::from_micros((50 - value) * 20));
+            value * 2
+        });
+        assert_eq!(
+            results,
+            (0..50u64).map(|value| value * 2).collect::<Vec<_>>()
+        );
+        wait_for(|| pool.stats().completed == 50);
+        let stats = pool.stats();
+        assert_eq!(stats.threads, 3);
+        assert_eq!(stats.active, 0);
+        assert_eq!(stats.queued, 0);
+    }
+
+    #[test]
+    fn test_set_threads_grows_and_retires_idle_workers() {
+        let pool = WorkerPool::new("test-resize", 1);
+        pool.set_threads(4);
+        assert_eq!(pool.stats().threads, 4);
+        pool.set_threads(2);
+        wait_for(|| pool.stats().threads == 2);
+        assert_eq!(pool.map(vec![1, 2, 3], |value| value + 1), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_map_skips_panicking_jobs() {
+        let pool = WorkerPool::new("test-panic", 2);
+        let results = pool.map(vec![1, 2, 3], |value| {
+            assert_ne!(value, 2, "simulated job failure");
+            value
+        });
+        assert_eq!(results, vec![1, 3]);
+        assert_eq!(pool.map(vec![4], |value| value), vec![4]);
+    }
+
+    #[test]
+    fn test_auto_thread_counts_scale_with_cpus() {
+        assert_eq!(auto_decode_threads(1), 1);
+        assert_eq!(auto_decode_threads(8), 4);
+        assert_eq!(auto_decode_threads(64), 8);
+        assert_eq!(auto_analysis_threads(2), 1);
+        assert_eq!(auto_analysis_threads(12), 3);
+        assert_eq!(auto_analysis_threads(64), 4);
+        assert_eq!(
+            resolved_decode_threads(&PerformanceConfig {
+                decode_threads: 100,
+                ..PerformanceConfig::default()
+            }),
+            MAX_WORKER_THREADS as usize
+        );
+        assert_eq!(thread_choice_index(0), 0);
+        assert_eq!(thread_choice_index(5), 5);
+        assert_eq!(thread_choice_index(64), THREAD_COUNT_CHOICES.len() - 1);
+    }
+}